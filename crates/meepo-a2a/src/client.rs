@@ -1,6 +1,8 @@
 //! A2A client — sends tasks to peer agents
 
 use anyhow::{Context, Result, anyhow};
+use meepo_core::network;
+use meepo_knowledge::ChangeBatch;
 use reqwest::Client;
 use serde_json::Value;
 use tracing::{debug, info};
@@ -27,6 +29,14 @@ impl Default for A2aClient {
     }
 }
 
+/// Refuse to reach a peer off this machine in offline mode
+fn ensure_reachable(base_url: &str) -> Result<()> {
+    if network::is_local_url(base_url) {
+        return Ok(());
+    }
+    network::ensure_online(&format!("the A2A peer at {}", base_url))
+}
+
 impl A2aClient {
     pub fn new() -> Self {
         Self {
@@ -39,6 +49,8 @@ impl A2aClient {
 
     /// Fetch an agent's capability card
     pub async fn fetch_agent_card(&self, base_url: &str, token: Option<&str>) -> Result<AgentCard> {
+        ensure_reachable(base_url)?;
+
         let url = format!("{}/.well-known/agent.json", base_url.trim_end_matches('/'));
         debug!("Fetching agent card from {}", url);
 
@@ -74,12 +86,15 @@ impl A2aClient {
         prompt: &str,
         context: Value,
     ) -> Result<TaskResponse> {
+        ensure_reachable(base_url)?;
+
         let url = format!("{}/a2a/tasks", base_url.trim_end_matches('/'));
         debug!("Submitting task to {}", url);
 
         let request = TaskRequest {
             prompt: prompt.to_string(),
             context,
+            profile: None,
         };
 
         let mut req = self.http.post(&url).json(&request);
@@ -115,6 +130,8 @@ impl A2aClient {
         token: Option<&str>,
         task_id: &str,
     ) -> Result<TaskResponse> {
+        ensure_reachable(base_url)?;
+
         let url = format!("{}/a2a/tasks/{}", base_url.trim_end_matches('/'), task_id);
 
         let mut req = self.http.get(&url);
@@ -144,6 +161,8 @@ impl A2aClient {
         token: Option<&str>,
         task_id: &str,
     ) -> Result<()> {
+        ensure_reachable(base_url)?;
+
         let url = format!("{}/a2a/tasks/{}", base_url.trim_end_matches('/'), task_id);
 
         let mut req = self.http.delete(&url);
@@ -164,6 +183,86 @@ impl A2aClient {
         Ok(())
     }
 
+    /// Fetch a peer's knowledge changes after `since`
+    pub async fn fetch_changes(
+        &self,
+        base_url: &str,
+        token: Option<&str>,
+        since: i64,
+        limit: usize,
+    ) -> Result<ChangeBatch> {
+        ensure_reachable(base_url)?;
+
+        let url = format!(
+            "{}/a2a/replication/changes?since={}&limit={}",
+            base_url.trim_end_matches('/'),
+            since,
+            limit
+        );
+        debug!("Fetching knowledge changes from {}", url);
+
+        let mut req = self.http.get(&url);
+        if let Some(t) = token {
+            req = req.bearer_auth(t);
+        }
+
+        let resp = req
+            .send()
+            .await
+            .with_context(|| format!("Failed to fetch changes from {}", url))?;
+
+        if !resp.status().is_success() {
+            let status = resp.status();
+            let body = resp.text().await.unwrap_or_default();
+            return Err(anyhow!(
+                "Fetching changes failed: HTTP {} — {}",
+                status,
+                body
+            ));
+        }
+
+        resp.json().await.context("Failed to parse change batch")
+    }
+
+    /// Send knowledge changes to a peer to apply
+    pub async fn push_changes(
+        &self,
+        base_url: &str,
+        token: Option<&str>,
+        batch: &ChangeBatch,
+    ) -> Result<ReplicationAck> {
+        ensure_reachable(base_url)?;
+
+        let url = format!("{}/a2a/replication/changes", base_url.trim_end_matches('/'));
+        debug!(
+            "Pushing {} knowledge changes to {}",
+            batch.changes.len(),
+            url
+        );
+
+        let mut req = self.http.post(&url).json(batch);
+        if let Some(t) = token {
+            req = req.bearer_auth(t);
+        }
+
+        let resp = req
+            .send()
+            .await
+            .with_context(|| format!("Failed to push changes to {}", url))?;
+
+        if !resp.status().is_success() {
+            let status = resp.status();
+            let body = resp.text().await.unwrap_or_default();
+            return Err(anyhow!(
+                "Pushing changes failed: HTTP {} — {}",
+                status,
+                body
+            ));
+        }
+
+        resp.json().await.context("Failed to parse replication ack")
+    }
+
     /// Submit task and poll until completion (blocking)
     pub async fn submit_and_wait(
         &self,
@@ -294,6 +393,19 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[tokio::test]
+    async fn test_replication_connection_refused() {
+        let client = A2aClient::new();
+        let result = client
+            .fetch_changes("http://127.0.0.1:1", Some("token"), 0, 10)
+            .await;
+        assert!(result.is_err());
+        let result = client
+            .push_changes("http://127.0.0.1:1", Some("token"), &ChangeBatch::default())
+            .await;
+        assert!(result.is_err());
+    }
+
     #[tokio::test]
     async fn test_fetch_agent_card_trailing_slash() {
         let client = A2aClient::new();
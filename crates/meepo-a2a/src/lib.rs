@@ -1,14 +1,25 @@
 //! A2A (Agent-to-Agent) protocol support for Meepo
 //!
 //! Implements Google's Agent-to-Agent protocol for multi-agent task delegation.
-//! Provides both server (receive tasks from peers) and client (send tasks to peers).
+//! Provides both server (receive tasks from peers) and client (send tasks to peers),
+//! plus knowledge replication between trusted Meepo instances and trust
+//! scores for peers that results are delegated to.
 
 pub mod client;
+pub mod peers;
 pub mod protocol;
+pub mod replication;
 pub mod server;
 pub mod tool;
+pub mod trust;
 
 pub use client::{A2aClient, PeerAgentConfig};
-pub use protocol::{AgentCard, AuthConfig, TaskRequest, TaskResponse, TaskStatus};
+pub use peers::PeerIdentity;
+pub use protocol::{
+    AgentCard, AuthConfig, ExportedTool, ReplicationAck, TaskRequest, TaskResponse, TaskStatus,
+    ToolListing,
+};
+pub use replication::Replicator;
 pub use server::A2aServer;
 pub use tool::DelegateToAgentTool;
+pub use trust::{PeerTrust, Verifier};
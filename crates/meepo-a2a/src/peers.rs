@@ -0,0 +1,197 @@
+//! Peer identities for the A2A server
+//!
+//! Each peer agent authenticates with its own bearer token, which names it
+//! in the audit log and picks its request quotas and the tools its tasks may
+//! use. The single `auth_token` from older configs becomes a peer called
+//! `default` with no quotas.
+
+use chrono::{DateTime, Duration, Utc};
+use meepo_core::tool_profiles::ToolProfile;
+use std::collections::{HashMap, HashSet, VecDeque};
+
+/// Tool list entry that allows every tool
+pub const ALL_TOOLS: &str = "*";
+
+/// A peer agent allowed to submit tasks
+#[derive(Clone)]
+pub struct PeerIdentity {
+    pub name: String,
+    pub token: String,
+    /// Tools its tasks may use; `"*"` for all, empty for none
+    pub tools: Vec<String>,
+    /// Tasks accepted per rolling hour (0 = no limit)
+    pub requests_per_hour: u32,
+    /// Tasks accepted per rolling day (0 = no limit)
+    pub requests_per_day: u32,
+    /// May exchange knowledge changes with this instance
+    pub replication: bool,
+    /// Tool profile used instead of `tools`
+    pub profile: Option<ToolProfile>,
+}
+
+impl std::fmt::Debug for PeerIdentity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PeerIdentity")
+            .field("name", &self.name)
+            .field("token", &"***")
+            .field("tools", &self.tools)
+            .field("requests_per_hour", &self.requests_per_hour)
+            .field("requests_per_day", &self.requests_per_day)
+            .field("replication", &self.replication)
+            .field("profile", &self.profile.as_ref().map(|p| &p.name))
+            .finish()
+    }
+}
+
+impl PeerIdentity {
+    /// Whether this peer's tasks may use `tool`
+    pub fn allows(&self, tool: &str) -> bool {
+        match &self.profile {
+            Some(profile) => profile.allows(tool),
+            None => self.tools.iter().any(|t| t == ALL_TOOLS || t == tool),
+        }
+    }
+
+    /// The tools this peer's tasks are scoped to, or `None` for all of them.
+    /// A peer with a profile is scoped by the server, which knows the tools.
+    pub fn tool_scope(&self) -> Option<HashSet<String>> {
+        if self.tools.iter().any(|t| t == ALL_TOOLS) {
+            None
+        } else {
+            Some(self.tools.iter().cloned().collect())
+        }
+    }
+}
+
+/// Compare in constant time so the token can't be guessed byte by byte
+fn token_matches(provided: &str, expected: &str) -> bool {
+    let provided = provided.as_bytes();
+    let expected = expected.as_bytes();
+    provided.len() == expected.len()
+        && provided
+            .iter()
+            .zip(expected.iter())
+            .fold(0u8, |acc, (a, b)| acc | (a ^ b))
+            == 0
+}
+
+/// The peer an `Authorization` header belongs to, if any
+pub fn authenticate<'a>(
+    peers: &'a [PeerIdentity],
+    authorization: &str,
+) -> Option<&'a PeerIdentity> {
+    let token = authorization.strip_prefix("Bearer ")?;
+    peers
+        .iter()
+        .filter(|p| !p.token.is_empty())
+        .find(|p| token_matches(token, &p.token))
+}
+
+/// Rolling hourly and daily counts of accepted tasks per peer
+#[derive(Debug, Default)]
+pub struct QuotaTracker {
+    accepted: HashMap<String, VecDeque<DateTime<Utc>>>,
+}
+
+impl QuotaTracker {
+    /// Count a task against the peer's quotas. Fails with the name of the
+    /// exhausted window ("hourly" or "daily") without counting it.
+    pub fn try_acquire(
+        &mut self,
+        peer: &PeerIdentity,
+        now: DateTime<Utc>,
+    ) -> Result<(), &'static str> {
+        let times = self.accepted.entry(peer.name.clone()).or_default();
+        while times.front().is_some_and(|t| now - *t >= Duration::days(1)) {
+            times.pop_front();
+        }
+        if peer.requests_per_day > 0 && times.len() >= peer.requests_per_day as usize {
+            return Err("daily");
+        }
+        let last_hour = times
+            .iter()
+            .filter(|t| now - **t < Duration::hours(1))
+            .count();
+        if peer.requests_per_hour > 0 && last_hour >= peer.requests_per_hour as usize {
+            return Err("hourly");
+        }
+        times.push_back(now);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn peer(name: &str, token: &str, per_hour: u32, per_day: u32) -> PeerIdentity {
+        PeerIdentity {
+            name: name.to_string(),
+            token: token.to_string(),
+            tools: vec!["recall".to_string()],
+            requests_per_hour: per_hour,
+            requests_per_day: per_day,
+            replication: false,
+            profile: None,
+        }
+    }
+
+    #[test]
+    fn test_authenticate_and_scope() {
+        let peers = vec![
+            peer("laptop", "tok-a", 0, 0),
+            peer("openclaw", "tok-b", 0, 0),
+        ];
+        assert_eq!(
+            authenticate(&peers, "Bearer tok-b").map(|p| p.name.as_str()),
+            Some("openclaw")
+        );
+        assert!(authenticate(&peers, "Bearer tok-c").is_none());
+        assert!(authenticate(&peers, "tok-a").is_none());
+
+        assert_eq!(
+            peers[0].tool_scope(),
+            Some(HashSet::from(["recall".to_string()]))
+        );
+        let mut all = peer("default", "t", 0, 0);
+        all.tools = vec![ALL_TOOLS.to_string()];
+        assert!(all.tool_scope().is_none());
+    }
+
+    #[test]
+    fn test_profile_replaces_tools() {
+        let mut researcher = peer("researcher", "t", 0, 0);
+        assert!(researcher.allows("recall"));
+        assert!(!researcher.allows("web_search"));
+
+        researcher.profile = Some(ToolProfile {
+            name: "research".to_string(),
+            description: "Look things up".to_string(),
+            tools: vec![ALL_TOOLS.to_string()],
+            max_risk: meepo_core::autonomy::action_log::ActionRisk::ReadOnly,
+        });
+        assert!(researcher.allows("web_search"));
+        assert!(!researcher.allows("send_email"));
+    }
+
+    #[test]
+    fn test_quotas_roll_over() {
+        let chatty = peer("chatty", "t", 2, 3);
+        let mut quotas = QuotaTracker::default();
+        let start = Utc::now();
+
+        assert!(quotas.try_acquire(&chatty, start).is_ok());
+        assert!(quotas.try_acquire(&chatty, start).is_ok());
+        assert_eq!(quotas.try_acquire(&chatty, start), Err("hourly"));
+
+        let later = start + Duration::hours(2);
+        assert!(quotas.try_acquire(&chatty, later).is_ok());
+        assert_eq!(quotas.try_acquire(&chatty, later), Err("daily"));
+
+        assert!(
+            quotas
+                .try_acquire(&chatty, start + Duration::days(1))
+                .is_ok()
+        );
+    }
+}
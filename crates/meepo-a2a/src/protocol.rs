@@ -3,6 +3,7 @@
 //! Implements Google's Agent-to-Agent protocol for multi-agent task delegation.
 
 use chrono::{DateTime, Utc};
+use meepo_core::tool_profiles::{ProfileSummary, ToolAnnotations};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
@@ -15,6 +16,9 @@ pub struct AgentCard {
     pub capabilities: Vec<String>,
     #[serde(default)]
     pub authentication: AuthConfig,
+    /// Tool profiles a task can ask to run with
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub profiles: Vec<ProfileSummary>,
 }
 
 /// Authentication configuration
@@ -30,6 +34,26 @@ pub struct TaskRequest {
     pub prompt: String,
     #[serde(default)]
     pub context: Value,
+    /// Run with only this tool profile's tools (within the peer's own)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub profile: Option<String>,
+}
+
+/// A tool a peer's tasks can use, as listed by `GET /a2a/tools`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExportedTool {
+    pub name: String,
+    pub description: String,
+    pub input_schema: Value,
+    pub annotations: ToolAnnotations,
+}
+
+/// Response to `GET /a2a/tools`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ToolListing {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub profile: Option<String>,
+    pub tools: Vec<ExportedTool>,
 }
 
 /// Task status response
@@ -67,6 +91,28 @@ impl std::fmt::Display for TaskStatus {
     }
 }
 
+/// Result of applying pushed knowledge changes
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReplicationAck {
+    pub entities: usize,
+    pub relationships: usize,
+    pub removed: usize,
+    pub skipped: usize,
+    pub deferred: usize,
+}
+
+impl From<&meepo_knowledge::ReplicationReport> for ReplicationAck {
+    fn from(report: &meepo_knowledge::ReplicationReport) -> Self {
+        Self {
+            entities: report.entities.len(),
+            relationships: report.relationships,
+            removed: report.removed.len(),
+            skipped: report.skipped,
+            deferred: report.deferred,
+        }
+    }
+}
+
 /// Error response
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ErrorResponse {
@@ -87,6 +133,7 @@ mod tests {
             authentication: AuthConfig {
                 schemes: vec!["bearer".to_string()],
             },
+            profiles: vec![],
         };
         let json = serde_json::to_value(&card).unwrap();
         assert_eq!(json["name"], "meepo");
@@ -161,6 +208,7 @@ mod tests {
             authentication: AuthConfig {
                 schemes: vec!["bearer".to_string()],
             },
+            profiles: vec![],
         };
         let json = serde_json::to_string(&card).unwrap();
         let parsed: AgentCard = serde_json::from_str(&json).unwrap();
@@ -181,6 +229,7 @@ mod tests {
         let req = TaskRequest {
             prompt: "do something".to_string(),
             context: serde_json::json!({"key": "value", "nested": {"a": 1}}),
+            profile: None,
         };
         let json = serde_json::to_value(&req).unwrap();
         assert_eq!(json["prompt"], "do something");
@@ -0,0 +1,162 @@
+//! Knowledge replication between trusted Meepo instances
+//!
+//! Every instance keeps a change log of its entities and relationships
+//! (document chunks are entities too). Syncing with a peer pulls the peer's
+//! changes since the last cursor we saw and pushes ours since the last cursor
+//! the peer took. Both sides resolve conflicts last-write-wins with
+//! tombstones, so it doesn't matter which side starts the sync, and echoed
+//! changes are no-ops.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::Result;
+use meepo_knowledge::KnowledgeGraph;
+use tokio_util::sync::CancellationToken;
+use tracing::{debug, info, warn};
+
+use crate::client::{A2aClient, PeerAgentConfig};
+
+/// Most changes exchanged per request
+pub const MAX_BATCH: usize = 500;
+
+/// Pushed batches must stay under the server's 1MB request limit
+const MAX_PUSH_BYTES: usize = 900_000;
+
+/// Preference category holding replication cursors
+const CATEGORY: &str = "replication";
+
+/// What one sync with a peer exchanged
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SyncStats {
+    pub pulled: usize,
+    pub pushed: usize,
+}
+
+/// Keeps this instance's knowledge in step with trusted peers
+pub struct Replicator {
+    graph: Arc<KnowledgeGraph>,
+    client: A2aClient,
+    peers: Vec<PeerAgentConfig>,
+}
+
+impl Replicator {
+    pub fn new(graph: Arc<KnowledgeGraph>, peers: Vec<PeerAgentConfig>) -> Self {
+        Self {
+            graph,
+            client: A2aClient::new(),
+            peers,
+        }
+    }
+
+    /// Pull a peer's changes and push ours
+    pub async fn sync_peer(&self, peer: &PeerAgentConfig) -> Result<SyncStats> {
+        let token = peer.token.as_deref();
+        let mut stats = SyncStats::default();
+
+        let pull_key = cursor_key(&peer.name, "pulled");
+        let mut cursor = self.cursor(&pull_key).await?;
+        loop {
+            let batch = self
+                .client
+                .fetch_changes(&peer.url, token, cursor, MAX_BATCH)
+                .await?;
+            stats.pulled += batch.changes.len();
+            self.graph.apply_changes(batch.changes).await?;
+            cursor = batch.cursor;
+            self.save_cursor(&pull_key, cursor).await?;
+            if !batch.more {
+                break;
+            }
+        }
+
+        let push_key = cursor_key(&peer.name, "pushed");
+        let mut cursor = self.cursor(&push_key).await?;
+        let mut limit = MAX_BATCH;
+        loop {
+            let batch = self.graph.db().changes_since(cursor, limit).await?;
+            if batch.cursor == cursor {
+                break;
+            }
+            if batch.changes.is_empty() {
+                // Log rows whose item vanished; nothing to send
+            } else if serde_json::to_vec(&batch)?.len() > MAX_PUSH_BYTES {
+                if limit > 1 {
+                    limit /= 2;
+                    continue;
+                }
+                warn!(
+                    "Knowledge change at cursor {} is too large to replicate, skipping",
+                    batch.cursor
+                );
+            } else {
+                self.client.push_changes(&peer.url, token, &batch).await?;
+                stats.pushed += batch.changes.len();
+            }
+            cursor = batch.cursor;
+            self.save_cursor(&push_key, cursor).await?;
+            limit = MAX_BATCH;
+            if !batch.more {
+                break;
+            }
+        }
+
+        debug!(
+            "Replicated with {}: pulled {}, pushed {}",
+            peer.name, stats.pulled, stats.pushed
+        );
+        Ok(stats)
+    }
+
+    /// Sync with every peer; failures are logged and retried next time
+    pub async fn sync_all(&self) {
+        for peer in &self.peers {
+            match self.sync_peer(peer).await {
+                Ok(stats) if stats.pulled + stats.pushed > 0 => info!(
+                    "Replicated knowledge with {}: {} in, {} out",
+                    peer.name, stats.pulled, stats.pushed
+                ),
+                Ok(_) => {}
+                Err(e) => warn!("Knowledge replication with {} failed: {}", peer.name, e),
+            }
+        }
+    }
+
+    /// Sync at startup and then every `interval` until cancelled
+    pub async fn run(self, interval: Duration, cancel: CancellationToken) {
+        info!(
+            "Knowledge replication started ({} peers, every {}s)",
+            self.peers.len(),
+            interval.as_secs()
+        );
+        loop {
+            self.sync_all().await;
+            tokio::select! {
+                _ = cancel.cancelled() => break,
+                _ = tokio::time::sleep(interval) => {}
+            }
+        }
+        info!("Knowledge replication shutting down");
+    }
+
+    async fn cursor(&self, key: &str) -> Result<i64> {
+        let prefs = self.graph.db().get_preferences(Some(CATEGORY)).await?;
+        Ok(prefs
+            .into_iter()
+            .find(|p| p.key == key)
+            .and_then(|p| p.value.as_i64())
+            .unwrap_or(0))
+    }
+
+    async fn save_cursor(&self, key: &str, cursor: i64) -> Result<()> {
+        self.graph
+            .db()
+            .upsert_preference(CATEGORY, key, cursor.into(), 1.0, Some("replication"))
+            .await?;
+        Ok(())
+    }
+}
+
+fn cursor_key(peer: &str, direction: &str) -> String {
+    format!("replication:{}:{}", peer, direction)
+}
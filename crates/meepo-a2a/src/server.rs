@@ -2,14 +2,23 @@
 //!
 //! Exposes endpoints:
 //! - GET  /.well-known/agent.json  — Agent card
+//! - GET  /a2a/tools               — Tools this peer's tasks may use
 //! - POST /a2a/tasks               — Submit a task
 //! - GET  /a2a/tasks/:id           — Poll task status
 //! - DELETE /a2a/tasks/:id         — Cancel a task
+//! - GET  /a2a/replication/changes — Knowledge changes since a cursor
+//! - POST /a2a/replication/changes — Apply a peer's knowledge changes
+//!
+//! Each request is authenticated as a [`PeerIdentity`]. Submitted tasks count
+//! against that peer's quotas, run with only its tools, and are recorded in
+//! the event journal (`meepo journal --kind a2a_delegation`). A task (or a
+//! tool listing, with `?profile=`) can name a tool profile to narrow the
+//! peer's tools further.
 
 use anyhow::Result;
 use chrono::Utc;
 use lru::LruCache;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::num::NonZeroUsize;
 use std::sync::Arc;
 use tokio::sync::Mutex;
@@ -17,10 +26,16 @@ use tracing::{info, warn};
 use uuid::Uuid;
 
 use meepo_core::agent::Agent;
-use meepo_core::tools::ToolRegistry;
+use meepo_core::tool_profiles::{self, ToolProfile};
+use meepo_core::tool_scope;
+use meepo_core::tools::{ToolExecutor, ToolRegistry};
 use meepo_core::types::{ChannelType, IncomingMessage};
+use meepo_knowledge::journal::{A2A_DELEGATION, A2A_DELEGATION_FINISHED};
+use meepo_knowledge::{ChangeBatch, KnowledgeDb, KnowledgeGraph};
 
+use crate::peers::{self, ALL_TOOLS, PeerIdentity, QuotaTracker};
 use crate::protocol::*;
+use crate::replication::MAX_BATCH;
 
 /// Maximum request body size (1MB) to prevent OOM DoS
 const MAX_REQUEST_BODY_SIZE: usize = 1_048_576;
@@ -28,32 +43,105 @@ const MAX_REQUEST_BODY_SIZE: usize = 1_048_576;
 /// Maximum number of tasks to keep in memory (LRU eviction for completed tasks)
 const MAX_TASK_HISTORY: usize = 1000;
 
+/// Prompt characters kept in the audit log
+const AUDIT_PROMPT_CHARS: usize = 200;
+
+/// A submitted task and the peer that owns it
+struct TaskEntry {
+    peer: String,
+    response: TaskResponse,
+}
+
 /// A2A server state
 pub struct A2aServer {
     agent: Arc<Agent>,
+    registry: Arc<ToolRegistry>,
     card: AgentCard,
-    auth_token: Option<String>,
-    tasks: Arc<Mutex<LruCache<String, TaskResponse>>>,
+    peers: Vec<PeerIdentity>,
+    /// Identity for unauthenticated requests, when no token is configured
+    anonymous: Option<PeerIdentity>,
+    quotas: Mutex<QuotaTracker>,
+    tasks: Arc<Mutex<LruCache<String, TaskEntry>>>,
+    replication: Option<Arc<KnowledgeGraph>>,
+    audit: Option<Arc<KnowledgeDb>>,
+    profiles: Vec<ToolProfile>,
 }
 
 impl A2aServer {
+    /// `auth_token` and `allowed_tools` describe the `default` peer (an
+    /// empty tool list means every tool). Without a token, and until
+    /// [`with_peers`](Self::with_peers) adds some, requests aren't
+    /// authenticated.
     pub fn new(
         agent: Arc<Agent>,
-        _registry: Arc<ToolRegistry>,
+        registry: Arc<ToolRegistry>,
         card: AgentCard,
         auth_token: Option<String>,
-        _allowed_tools: Vec<String>,
+        allowed_tools: Vec<String>,
     ) -> Self {
+        let default = PeerIdentity {
+            name: "default".to_string(),
+            token: auth_token.clone().unwrap_or_default(),
+            tools: if allowed_tools.is_empty() {
+                vec![ALL_TOOLS.to_string()]
+            } else {
+                allowed_tools
+            },
+            requests_per_hour: 0,
+            requests_per_day: 0,
+            replication: true,
+            profile: None,
+        };
+        let (peers, anonymous) = match auth_token {
+            Some(_) => (vec![default], None),
+            None => (Vec::new(), Some(default)),
+        };
         Self {
             agent,
+            registry,
             card,
-            auth_token,
+            peers,
+            anonymous,
+            quotas: Mutex::new(QuotaTracker::default()),
             tasks: Arc::new(Mutex::new(LruCache::new(
                 NonZeroUsize::new(MAX_TASK_HISTORY).unwrap(),
             ))),
+            replication: None,
+            audit: None,
+            profiles: Vec::new(),
         }
     }
 
+    /// Tool profiles a task can ask for; they're listed on the agent card
+    pub fn with_profiles(mut self, profiles: Vec<ToolProfile>) -> Self {
+        self.card.profiles = profiles.iter().map(ToolProfile::summary).collect();
+        self.profiles = profiles;
+        self
+    }
+
+    /// Add peers with their own tokens, quotas and tool scopes. Once any
+    /// are set, every request must carry a known token.
+    pub fn with_peers(mut self, peers: Vec<PeerIdentity>) -> Self {
+        if !peers.is_empty() {
+            self.anonymous = None;
+        }
+        self.peers.extend(peers);
+        self
+    }
+
+    /// Record submitted tasks and their outcomes in the event journal
+    pub fn with_audit(mut self, db: Arc<KnowledgeDb>) -> Self {
+        self.audit = Some(db);
+        self
+    }
+
+    /// Serve knowledge changes to, and accept them from, trusted peers.
+    /// Only authenticated peers with replication allowed can use it.
+    pub fn with_replication(mut self, graph: Arc<KnowledgeGraph>) -> Self {
+        self.replication = Some(graph);
+        self
+    }
+
     /// Run the A2A HTTP server
     pub async fn serve(self: Arc<Self>, port: u16) -> Result<()> {
         use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
@@ -117,28 +205,19 @@ impl A2aServer {
                     }
                 }
 
-                // Check auth (constant-time comparison to prevent timing attacks)
-                if let Some(ref expected_token) = server.auth_token {
-                    let auth = headers.get("authorization").cloned().unwrap_or_default();
-                    let is_valid = if let Some(token) = auth.strip_prefix("Bearer ") {
-                        let provided = token.as_bytes();
-                        let expected = expected_token.as_bytes();
-                        // Constant-time comparison: always compare all bytes
-                        provided.len() == expected.len()
-                            && provided
-                                .iter()
-                                .zip(expected.iter())
-                                .fold(0u8, |acc, (a, b)| acc | (a ^ b))
-                                == 0
-                    } else {
-                        false
-                    };
-                    if !is_valid {
-                        let resp = "HTTP/1.1 401 Unauthorized\r\nContent-Type: application/json\r\n\r\n{\"error\":\"unauthorized\"}";
-                        let _ = writer.write_all(resp.as_bytes()).await;
-                        return;
-                    }
-                }
+                // Check auth: which peer is this?
+                let auth = headers.get("authorization").cloned().unwrap_or_default();
+                let peer = match server.anonymous.as_ref() {
+                    Some(anonymous) => anonymous,
+                    None => match peers::authenticate(&server.peers, &auth) {
+                        Some(peer) => peer,
+                        None => {
+                            let resp = "HTTP/1.1 401 Unauthorized\r\nContent-Type: application/json\r\n\r\n{\"error\":\"unauthorized\"}";
+                            let _ = writer.write_all(resp.as_bytes()).await;
+                            return;
+                        }
+                    },
+                };
 
                 // Route
                 let parts: Vec<&str> = request_line.split_whitespace().collect();
@@ -153,14 +232,23 @@ impl A2aServer {
                         let json = serde_json::to_string(&server.card).unwrap();
                         ("200 OK", json)
                     }
-                    ("POST", "/a2a/tasks") => server.handle_submit_task(&body).await,
+                    ("GET", p) if p == "/a2a/tools" || p.starts_with("/a2a/tools?") => {
+                        server.handle_list_tools(peer, p)
+                    }
+                    ("GET", p) if p.starts_with("/a2a/replication/changes") => {
+                        server.handle_fetch_changes(peer, p).await
+                    }
+                    ("POST", "/a2a/replication/changes") => {
+                        server.handle_apply_changes(peer, &body).await
+                    }
+                    ("POST", "/a2a/tasks") => server.handle_submit_task(peer, &body).await,
                     ("GET", p) if p.starts_with("/a2a/tasks/") => {
                         let task_id = &p["/a2a/tasks/".len()..];
-                        server.handle_get_task(task_id).await
+                        server.handle_get_task(peer, task_id).await
                     }
                     ("DELETE", p) if p.starts_with("/a2a/tasks/") => {
                         let task_id = &p["/a2a/tasks/".len()..];
-                        server.handle_cancel_task(task_id).await
+                        server.handle_cancel_task(peer, task_id).await
                     }
                     _ => ("404 Not Found", r#"{"error":"not found"}"#.to_string()),
                 };
@@ -176,7 +264,83 @@ impl A2aServer {
         }
     }
 
-    async fn handle_submit_task(&self, body: &[u8]) -> (&'static str, String) {
+    /// Append an A2A event to the journal, if auditing is on
+    async fn audit(&self, kind: &str, peer: &str, data: serde_json::Value) {
+        if let Some(db) = &self.audit {
+            record(db, kind, peer, data).await;
+        }
+    }
+
+    /// The profile a request named, if any
+    fn requested_profile(
+        &self,
+        name: Option<&str>,
+    ) -> Result<Option<&ToolProfile>, (&'static str, String)> {
+        match name {
+            None => Ok(None),
+            Some(name) => tool_profiles::find(&self.profiles, name)
+                .map(Some)
+                .ok_or_else(|| {
+                    (
+                        "400 Bad Request",
+                        serde_json::json!({ "error": format!("unknown tool profile: {}", name) })
+                            .to_string(),
+                    )
+                }),
+        }
+    }
+
+    /// The tools a task may use: the peer's own, narrowed to the requested
+    /// profile. `None` means every tool.
+    fn task_scope(
+        &self,
+        peer: &PeerIdentity,
+        profile: Option<&ToolProfile>,
+    ) -> Option<HashSet<String>> {
+        if peer.profile.is_none() && profile.is_none() {
+            return peer.tool_scope();
+        }
+        Some(
+            self.registry
+                .list_tools()
+                .into_iter()
+                .map(|t| t.name)
+                .filter(|name| peer.allows(name) && profile.is_none_or(|p| p.allows(name)))
+                .collect(),
+        )
+    }
+
+    fn handle_list_tools(&self, peer: &PeerIdentity, path: &str) -> (&'static str, String) {
+        let requested = path
+            .split_once('?')
+            .into_iter()
+            .flat_map(|(_, query)| query.split('&'))
+            .find_map(|kv| kv.strip_prefix("profile="));
+        let profile = match self.requested_profile(requested) {
+            Ok(profile) => profile,
+            Err(resp) => return resp,
+        };
+        let scope = self.task_scope(peer, profile);
+        let tools = self
+            .registry
+            .list_tools()
+            .into_iter()
+            .filter(|t| scope.as_ref().is_none_or(|s| s.contains(&t.name)))
+            .map(|t| ExportedTool {
+                annotations: tool_profiles::annotations(&t.name),
+                name: t.name,
+                description: t.description,
+                input_schema: t.input_schema,
+            })
+            .collect();
+        let listing = ToolListing {
+            profile: profile.map(|p| p.name.clone()),
+            tools,
+        };
+        ("200 OK", serde_json::to_string(&listing).unwrap())
+    }
+
+    async fn handle_submit_task(&self, peer: &PeerIdentity, body: &[u8]) -> (&'static str, String) {
         let request: TaskRequest = match serde_json::from_slice(body) {
             Ok(r) => r,
             Err(e) => {
@@ -186,9 +350,33 @@ impl A2aServer {
                 );
             }
         };
+        let profile = match self.requested_profile(request.profile.as_deref()) {
+            Ok(profile) => profile,
+            Err(resp) => return resp,
+        };
 
         let task_id = Uuid::new_v4().to_string();
         let now = Utc::now();
+        let prompt_excerpt: String = request.prompt.chars().take(AUDIT_PROMPT_CHARS).collect();
+
+        if let Err(window) = self.quotas.lock().await.try_acquire(peer, now) {
+            warn!("A2A peer '{}' hit its {} quota", peer.name, window);
+            self.audit(
+                A2A_DELEGATION,
+                &peer.name,
+                serde_json::json!({
+                    "task_id": task_id,
+                    "outcome": "rate_limited",
+                    "quota": window,
+                    "prompt": prompt_excerpt,
+                }),
+            )
+            .await;
+            return (
+                "429 Too Many Requests",
+                format!(r#"{{"error":"{} request quota exceeded"}}"#, window),
+            );
+        }
 
         let response = TaskResponse {
             task_id: task_id.clone(),
@@ -205,7 +393,8 @@ impl A2aServer {
             let active_count = tasks
                 .iter()
                 .filter(|(_, t)| {
-                    t.status == TaskStatus::Submitted || t.status == TaskStatus::Working
+                    t.response.status == TaskStatus::Submitted
+                        || t.response.status == TaskStatus::Working
                 })
                 .count();
             if active_count >= 100 {
@@ -215,12 +404,42 @@ impl A2aServer {
                 );
             }
 
-            tasks.put(task_id.clone(), response.clone());
+            tasks.put(
+                task_id.clone(),
+                TaskEntry {
+                    peer: peer.name.clone(),
+                    response: response.clone(),
+                },
+            );
         }
 
+        let scope = self.task_scope(peer, profile);
+        info!(
+            "A2A task {} from peer '{}' ({} tools)",
+            task_id,
+            peer.name,
+            scope
+                .as_ref()
+                .map_or("all".to_string(), |tools| tools.len().to_string())
+        );
+        self.audit(
+            A2A_DELEGATION,
+            &peer.name,
+            serde_json::json!({
+                "task_id": task_id,
+                "outcome": "accepted",
+                "tools": peer.tools,
+                "profile": profile.map(|p| &p.name),
+                "prompt": prompt_excerpt,
+            }),
+        )
+        .await;
+
         // Spawn background task execution
         let tasks = self.tasks.clone();
         let agent = self.agent.clone();
+        let audit = self.audit.clone();
+        let peer_name = peer.name.clone();
         let prompt = request.prompt;
 
         tokio::spawn(async move {
@@ -228,23 +447,26 @@ impl A2aServer {
             {
                 let mut t = tasks.lock().await;
                 if let Some(task) = t.get_mut(&task_id) {
-                    task.status = TaskStatus::Working;
+                    task.response.status = TaskStatus::Working;
                 }
             }
 
-            // Execute via agent
+            // Execute via agent, with only the peer's tools
             let incoming = IncomingMessage {
                 id: task_id.clone(),
-                sender: "a2a".to_string(),
+                sender: format!("a2a:{}", peer_name),
                 content: prompt,
                 channel: ChannelType::Internal,
                 timestamp: Utc::now(),
             };
-            let result = agent.handle_message(incoming).await;
+            let result = match scope {
+                Some(tools) => tool_scope::scope(tools, agent.handle_message(incoming)).await,
+                None => agent.handle_message(incoming).await,
+            };
 
             // Update status
             let mut t = tasks.lock().await;
-            if let Some(task) = t.get_mut(&task_id) {
+            if let Some(TaskEntry { response: task, .. }) = t.get_mut(&task_id) {
                 match result {
                     Ok(outgoing) => {
                         task.status = TaskStatus::Completed;
@@ -257,6 +479,17 @@ impl A2aServer {
                         task.completed_at = Some(Utc::now());
                     }
                 }
+                let status = task.status.to_string();
+                drop(t);
+                if let Some(db) = &audit {
+                    record(
+                        db,
+                        A2A_DELEGATION_FINISHED,
+                        &peer_name,
+                        serde_json::json!({ "task_id": task_id, "status": status }),
+                    )
+                    .await;
+                }
             }
         });
 
@@ -264,10 +497,106 @@ impl A2aServer {
         ("201 Created", json)
     }
 
-    async fn handle_get_task(&self, task_id: &str) -> (&'static str, String) {
+    /// The knowledge graph to replicate, if replication is on for this server
+    fn replication_graph(
+        &self,
+        peer: &PeerIdentity,
+    ) -> Result<&Arc<KnowledgeGraph>, (&'static str, String)> {
+        let graph = self.replication.as_ref().ok_or_else(|| {
+            (
+                "404 Not Found",
+                r#"{"error":"replication not enabled"}"#.to_string(),
+            )
+        })?;
+        if self.anonymous.is_some() {
+            return Err((
+                "403 Forbidden",
+                r#"{"error":"replication requires an auth token"}"#.to_string(),
+            ));
+        }
+        if !peer.replication {
+            return Err((
+                "403 Forbidden",
+                r#"{"error":"replication not allowed for this peer"}"#.to_string(),
+            ));
+        }
+        Ok(graph)
+    }
+
+    async fn handle_fetch_changes(
+        &self,
+        peer: &PeerIdentity,
+        path: &str,
+    ) -> (&'static str, String) {
+        let graph = match self.replication_graph(peer) {
+            Ok(graph) => graph,
+            Err(resp) => return resp,
+        };
+        let mut since = 0i64;
+        let mut limit = MAX_BATCH;
+        if let Some((_, query)) = path.split_once('?') {
+            for (key, value) in query.split('&').filter_map(|kv| kv.split_once('=')) {
+                match key {
+                    "since" => since = value.parse().unwrap_or(0),
+                    "limit" => limit = value.parse().unwrap_or(MAX_BATCH).clamp(1, MAX_BATCH),
+                    _ => {}
+                }
+            }
+        }
+        match graph.db().changes_since(since, limit).await {
+            Ok(batch) => ("200 OK", serde_json::to_string(&batch).unwrap()),
+            Err(e) => {
+                warn!("Failed to read knowledge changes: {}", e);
+                (
+                    "500 Internal Server Error",
+                    r#"{"error":"failed to read changes"}"#.to_string(),
+                )
+            }
+        }
+    }
+
+    async fn handle_apply_changes(
+        &self,
+        peer: &PeerIdentity,
+        body: &[u8],
+    ) -> (&'static str, String) {
+        let graph = match self.replication_graph(peer) {
+            Ok(graph) => graph,
+            Err(resp) => return resp,
+        };
+        let batch: ChangeBatch = match serde_json::from_slice(body) {
+            Ok(b) => b,
+            Err(e) => {
+                return (
+                    "400 Bad Request",
+                    format!(r#"{{"error":"invalid request: {}"}}"#, e),
+                );
+            }
+        };
+        match graph.apply_changes(batch.changes).await {
+            Ok(report) => {
+                let ack = ReplicationAck::from(&report);
+                info!(
+                    "Applied replicated knowledge: {} entities, {} relationships, {} removed",
+                    ack.entities, ack.relationships, ack.removed
+                );
+                ("200 OK", serde_json::to_string(&ack).unwrap())
+            }
+            Err(e) => {
+                warn!("Failed to apply knowledge changes: {}", e);
+                (
+                    "500 Internal Server Error",
+                    r#"{"error":"failed to apply changes"}"#.to_string(),
+                )
+            }
+        }
+    }
+
+    async fn handle_get_task(&self, peer: &PeerIdentity, task_id: &str) -> (&'static str, String) {
         let mut tasks = self.tasks.lock().await;
-        match tasks.get(task_id) {
-            Some(task) => {
+        // Peers only see their own tasks
+        match tasks.get(task_id).filter(|t| t.peer == peer.name) {
+            Some(TaskEntry { response: task, .. }) => {
                 let json = serde_json::to_string(task).unwrap();
                 ("200 OK", json)
             }
@@ -275,10 +604,14 @@ impl A2aServer {
         }
     }
 
-    async fn handle_cancel_task(&self, task_id: &str) -> (&'static str, String) {
+    async fn handle_cancel_task(
+        &self,
+        peer: &PeerIdentity,
+        task_id: &str,
+    ) -> (&'static str, String) {
         let mut tasks = self.tasks.lock().await;
-        match tasks.get_mut(task_id) {
-            Some(task) => {
+        match tasks.get_mut(task_id).filter(|t| t.peer == peer.name) {
+            Some(TaskEntry { response: task, .. }) => {
                 if task.status == TaskStatus::Submitted || task.status == TaskStatus::Working {
                     task.status = TaskStatus::Cancelled;
                     task.completed_at = Some(Utc::now());
@@ -295,6 +628,13 @@ impl A2aServer {
     }
 }
 
+/// Write an audit event; a failed write is logged, not fatal
+async fn record(db: &KnowledgeDb, kind: &str, peer: &str, data: serde_json::Value) {
+    if let Err(e) = db.append_event(kind, peer, data).await {
+        warn!("Failed to record A2A audit event: {}", e);
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -309,6 +649,7 @@ mod tests {
             authentication: AuthConfig {
                 schemes: vec!["bearer".to_string()],
             },
+            profiles: vec![],
         };
         let json = serde_json::to_string_pretty(&card).unwrap();
         assert!(json.contains("meepo"));
@@ -3,16 +3,25 @@
 use anyhow::Result;
 use async_trait::async_trait;
 use serde_json::Value;
-use tracing::{debug, info};
+use std::sync::Arc;
+use tracing::{debug, info, warn};
 
 use meepo_core::tools::ToolHandler;
+use meepo_knowledge::KnowledgeDb;
 
 use crate::client::{A2aClient, PeerAgentConfig};
+use crate::protocol::TaskStatus;
+use crate::trust::{self, Verdict, Verifier};
+
+/// Agent name that picks the most trusted known peer
+const AUTO_AGENT: &str = "auto";
 
 /// Tool that delegates a task to a peer A2A agent
 pub struct DelegateToAgentTool {
     client: A2aClient,
     peers: Vec<PeerAgentConfig>,
+    trust: Option<Arc<KnowledgeDb>>,
+    verifier: Option<Verifier>,
 }
 
 impl DelegateToAgentTool {
@@ -20,6 +29,58 @@ impl DelegateToAgentTool {
         Self {
             client: A2aClient::new(),
             peers,
+            trust: None,
+            verifier: None,
+        }
+    }
+
+    /// Track each known peer's reliability, and route `"auto"` by it
+    pub fn with_trust(mut self, db: Arc<KnowledgeDb>) -> Self {
+        self.trust = Some(db);
+        self
+    }
+
+    /// Critique results that have no schema to check against
+    pub fn with_verifier(mut self, verifier: Verifier) -> Self {
+        self.verifier = Some(verifier);
+        self
+    }
+
+    /// The known peer with the best trust score
+    async fn most_trusted(&self) -> Result<String> {
+        let names: Vec<String> = self.peers.iter().map(|p| p.name.clone()).collect();
+        let best = match &self.trust {
+            Some(db) => trust::ranked(db, &names)
+                .await?
+                .into_iter()
+                .next()
+                .map(|(name, _)| name),
+            None => names.into_iter().next(),
+        };
+        best.ok_or_else(|| anyhow::anyhow!("No peer agents are configured"))
+    }
+
+    /// Check a finished result, if there's a way to
+    async fn verify(&self, task: &str, result: &str, schema: Option<&Value>) -> Option<Verdict> {
+        if let Some(schema) = schema {
+            return Some(trust::check_schema(schema, result));
+        }
+        match self.verifier.as_ref()?.critique(task, result).await {
+            Ok(verdict) => Some(verdict),
+            Err(e) => {
+                warn!("Failed to verify delegated result: {}", e);
+                None
+            }
+        }
+    }
+
+    /// Update a known peer's trust record
+    async fn record(&self, peer: Option<&str>, completed: bool, quality: Option<f64>) {
+        let (Some(db), Some(peer)) = (&self.trust, peer) else {
+            return;
+        };
+        if let Err(e) = trust::record(db, peer, completed, quality).await {
+            warn!("Failed to record trust for agent '{}': {}", peer, e);
         }
     }
 }
@@ -30,9 +91,15 @@ impl ToolHandler for DelegateToAgentTool {
         "delegate_to_agent"
     }
 
+    fn needs_network(&self) -> bool {
+        true
+    }
+
     fn description(&self) -> &str {
         "Delegate a task to a peer AI agent via A2A protocol. \
-         The agent will execute the task and return the result."
+         The agent will execute the task and return the result. Use agent 'auto' to pick \
+         the known agent with the best track record. Pass a JSON schema to have the result \
+         checked against it."
     }
 
     fn input_schema(&self) -> Value {
@@ -41,7 +108,7 @@ impl ToolHandler for DelegateToAgentTool {
             "properties": {
                 "agent": {
                     "type": "string",
-                    "description": "Name of a known agent or full URL of the agent"
+                    "description": "Name of a known agent, 'auto' for the most reliable one, or full URL of the agent"
                 },
                 "task": {
                     "type": "string",
@@ -51,6 +118,10 @@ impl ToolHandler for DelegateToAgentTool {
                     "type": "object",
                     "description": "Optional context (files, URLs, data) to pass to the agent"
                 },
+                "schema": {
+                    "type": "object",
+                    "description": "Optional JSON object schema the result must match (only checked when waiting)"
+                },
                 "wait": {
                     "type": "boolean",
                     "description": "If true, wait for completion. If false, return task ID immediately.",
@@ -77,6 +148,21 @@ impl ToolHandler for DelegateToAgentTool {
             .cloned()
             .unwrap_or(serde_json::json!({}));
         let wait = input.get("wait").and_then(|v| v.as_bool()).unwrap_or(true);
+        let schema = input.get("schema").filter(|s| s.is_object());
+        let agent_name = if agent_name == AUTO_AGENT {
+            let best = self.most_trusted().await?;
+            info!("Routing delegated task to most trusted agent '{}'", best);
+            best
+        } else {
+            agent_name.to_string()
+        };
+        let agent_name = agent_name.as_str();
+        // Only named peers build up a trust record
+        let known = self
+            .peers
+            .iter()
+            .any(|p| p.name == agent_name)
+            .then_some(agent_name);
 
         // Resolve agent: look up by name, or treat as URL
         let (base_url, token) =
@@ -121,7 +207,7 @@ impl ToolHandler for DelegateToAgentTool {
         }
 
         if wait {
-            let result = self
+            let result = match self
                 .client
                 .submit_and_wait(
                     &base_url,
@@ -131,14 +217,45 @@ impl ToolHandler for DelegateToAgentTool {
                     std::time::Duration::from_secs(2),
                     std::time::Duration::from_secs(300),
                 )
-                .await?;
+                .await
+            {
+                Ok(result) => result,
+                Err(e) => {
+                    self.record(known, false, None).await;
+                    return Err(e);
+                }
+            };
 
+            let completed = result.status == TaskStatus::Completed;
+            let verdict = match &result.result {
+                Some(text) if completed => self.verify(task, text, schema).await,
+                _ => None,
+            };
+            self.record(known, completed, verdict.as_ref().map(|v| v.quality))
+                .await;
+
+            let check = verdict
+                .map(|v| {
+                    format!(
+                        "\nVerification: {:.0}/10{}",
+                        v.quality * 10.0,
+                        if v.notes.is_empty() {
+                            String::new()
+                        } else {
+                            format!(" — {}", v.notes)
+                        }
+                    )
+                })
+                .unwrap_or_default();
             match result.result {
                 Some(text) => Ok(format!(
-                    "Agent completed task (status: {}):\n{}",
-                    result.status, text
+                    "Agent '{}' completed task (status: {}):\n{}{}",
+                    agent_name, result.status, text, check
+                )),
+                None => Ok(format!(
+                    "Agent '{}' finished with status: {}",
+                    agent_name, result.status
                 )),
-                None => Ok(format!("Agent finished with status: {}", result.status)),
             }
         } else {
             let response = self
@@ -161,6 +278,7 @@ mod tests {
     fn test_tool_schema() {
         let tool = DelegateToAgentTool::new(vec![]);
         assert_eq!(tool.name(), "delegate_to_agent");
+        assert!(tool.needs_network());
         let schema = tool.input_schema();
         assert_eq!(schema["type"], "object");
         let required = schema["required"].as_array().unwrap();
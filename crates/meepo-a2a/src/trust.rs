@@ -0,0 +1,286 @@
+//! Verification of delegated results and per-peer trust scores
+//!
+//! Every task delegated with `delegate_to_agent` updates the peer's record:
+//! whether it finished, and, when the result is verified, how good it was.
+//! A result is verified against the caller's JSON schema when one is given,
+//! otherwise by a cheap model's critique if verification is on. Records are
+//! stored as `peer_trust` preferences; [`ranked`] orders peers by score so
+//! delegation can prefer the reliable ones.
+
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tracing::debug;
+
+use meepo_core::api::ApiClient;
+use meepo_core::providers::overrides::{self, RequestOverrides};
+use meepo_core::structured;
+use meepo_knowledge::KnowledgeDb;
+
+/// Preference category holding trust records
+pub const CATEGORY: &str = "peer_trust";
+
+/// Characters of a result shown to the critique model
+const MAX_CRITIQUE_CHARS: usize = 8000;
+
+fn trust_key(peer: &str) -> String {
+    format!("peer_trust:{}", peer)
+}
+
+/// What we know about a peer's past work
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct PeerTrust {
+    pub tasks: u32,
+    /// Tasks that came back completed (not failed, cancelled or timed out)
+    pub completed: u32,
+    /// Verified tasks and the sum of their quality (each 0.0–1.0)
+    pub verified: u32,
+    pub quality_sum: f64,
+    pub last_task_at: Option<DateTime<Utc>>,
+}
+
+impl PeerTrust {
+    /// 0.0–1.0: completion rate weighted with verified quality. Both start
+    /// from a neutral prior so one task doesn't swing a new peer's score.
+    pub fn score(&self) -> f64 {
+        let completion = (self.completed as f64 + 1.0) / (self.tasks as f64 + 2.0);
+        let quality = (self.quality_sum + 0.5) / (self.verified as f64 + 1.0);
+        0.5 * completion + 0.5 * quality
+    }
+
+    fn record(&mut self, completed: bool, quality: Option<f64>) {
+        self.tasks += 1;
+        if completed {
+            self.completed += 1;
+        }
+        if let Some(quality) = quality {
+            self.verified += 1;
+            self.quality_sum += quality.clamp(0.0, 1.0);
+        }
+        self.last_task_at = Some(Utc::now());
+    }
+}
+
+/// A peer's trust record (a fresh one if it has none)
+pub async fn load(db: &KnowledgeDb, peer: &str) -> Result<PeerTrust> {
+    let prefs = db.get_preferences(Some(CATEGORY)).await?;
+    Ok(prefs
+        .into_iter()
+        .find(|p| p.key == trust_key(peer))
+        .and_then(|p| serde_json::from_value(p.value).ok())
+        .unwrap_or_default())
+}
+
+/// Record the outcome of a task delegated to `peer`
+pub async fn record(
+    db: &KnowledgeDb,
+    peer: &str,
+    completed: bool,
+    quality: Option<f64>,
+) -> Result<PeerTrust> {
+    let mut trust = load(db, peer).await?;
+    trust.record(completed, quality);
+    db.upsert_preference(
+        CATEGORY,
+        &trust_key(peer),
+        serde_json::to_value(&trust)?,
+        1.0,
+        Some("a2a"),
+    )
+    .await?;
+    Ok(trust)
+}
+
+/// `peers` with their trust records, most trusted first
+pub async fn ranked(db: &KnowledgeDb, peers: &[String]) -> Result<Vec<(String, PeerTrust)>> {
+    let prefs = db.get_preferences(Some(CATEGORY)).await?;
+    let mut ranked: Vec<(String, PeerTrust)> = peers
+        .iter()
+        .map(|peer| {
+            let trust = prefs
+                .iter()
+                .find(|p| p.key == trust_key(peer))
+                .and_then(|p| serde_json::from_value(p.value.clone()).ok())
+                .unwrap_or_default();
+            (peer.clone(), trust)
+        })
+        .collect();
+    ranked.sort_by(|a, b| b.1.score().total_cmp(&a.1.score()));
+    Ok(ranked)
+}
+
+/// Result of checking a delegated result
+#[derive(Debug, Clone, PartialEq)]
+pub struct Verdict {
+    /// 0.0–1.0
+    pub quality: f64,
+    pub notes: String,
+}
+
+/// Check a result against a JSON object schema: it must parse as JSON,
+/// have the required properties, and match the declared primitive types
+pub fn check_schema(schema: &Value, result: &str) -> Verdict {
+    let Some(value) = structured::parse_json_text(result) else {
+        return Verdict {
+            quality: 0.0,
+            notes: "result is not a JSON object".to_string(),
+        };
+    };
+    let obj = value.as_object().expect("parse_json_text returns objects");
+    let mut problems = Vec::new();
+    for field in schema
+        .get("required")
+        .and_then(|r| r.as_array())
+        .into_iter()
+        .flatten()
+        .filter_map(|f| f.as_str())
+    {
+        if !obj.contains_key(field) {
+            problems.push(format!("missing '{}'", field));
+        }
+    }
+    if let Some(props) = schema.get("properties").and_then(|p| p.as_object()) {
+        for (field, spec) in props {
+            let (Some(expected), Some(actual)) =
+                (spec.get("type").and_then(|t| t.as_str()), obj.get(field))
+            else {
+                continue;
+            };
+            let matches = match expected {
+                "string" => actual.is_string(),
+                "number" => actual.is_number(),
+                "integer" => actual.is_i64() || actual.is_u64(),
+                "boolean" => actual.is_boolean(),
+                "array" => actual.is_array(),
+                "object" => actual.is_object(),
+                _ => true,
+            };
+            if !matches {
+                problems.push(format!("'{}' should be {}", field, expected));
+            }
+        }
+    }
+    if problems.is_empty() {
+        Verdict {
+            quality: 1.0,
+            notes: "matches the schema".to_string(),
+        }
+    } else {
+        Verdict {
+            quality: 0.0,
+            notes: problems.join(", "),
+        }
+    }
+}
+
+/// Critiques delegated results with a cheap model
+#[derive(Clone)]
+pub struct Verifier {
+    api: ApiClient,
+    model: String,
+}
+
+impl Verifier {
+    pub fn new(api: ApiClient, model: impl Into<String>) -> Self {
+        Self {
+            api,
+            model: model.into(),
+        }
+    }
+
+    /// Rate how well `result` does `task`
+    pub async fn critique(&self, task: &str, result: &str) -> Result<Verdict> {
+        let result: String = result.chars().take(MAX_CRITIQUE_CHARS).collect();
+        let schema = serde_json::json!({
+            "type": "object",
+            "properties": {
+                "score": {
+                    "type": "integer",
+                    "description": "0 (wrong or useless) to 10 (complete and correct)"
+                },
+                "issues": {
+                    "type": "string",
+                    "description": "One sentence on what's wrong or missing, or empty"
+                }
+            },
+            "required": ["score"]
+        });
+        let answer = overrides::scope(
+            RequestOverrides {
+                model: Some(overrides::resolve_alias(&self.model)),
+                ..Default::default()
+            },
+            structured::ask(
+                &self.api,
+                "You check work another AI agent did. Judge whether the result does the task: \
+                 correct, complete and on topic. Don't redo the task.",
+                &format!("Task:\n{}\n\nResult:\n{}", task, result),
+                &schema,
+            ),
+        )
+        .await?;
+        let score = answer
+            .get("score")
+            .and_then(|s| s.as_f64())
+            .unwrap_or(0.0)
+            .clamp(0.0, 10.0);
+        debug!("Critique scored delegated result {}/10", score);
+        Ok(Verdict {
+            quality: score / 10.0,
+            notes: answer
+                .get("issues")
+                .and_then(|i| i.as_str())
+                .unwrap_or_default()
+                .trim()
+                .to_string(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_schema() {
+        let schema = serde_json::json!({
+            "type": "object",
+            "properties": {
+                "price": {"type": "number"},
+                "currency": {"type": "string"}
+            },
+            "required": ["price", "currency"]
+        });
+        assert_eq!(
+            check_schema(&schema, r#"{"price": 12.5, "currency": "EUR"}"#).quality,
+            1.0
+        );
+        let bad = check_schema(&schema, r#"{"price": "cheap"}"#);
+        assert_eq!(bad.quality, 0.0);
+        assert_eq!(bad.notes, "missing 'currency', 'price' should be number");
+        assert_eq!(check_schema(&schema, "It costs 12.50").quality, 0.0);
+    }
+
+    #[tokio::test]
+    async fn test_trust_ranks_reliable_peers_first() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let db = KnowledgeDb::new(temp.path().join("test.db")).unwrap();
+
+        record(&db, "flaky", false, None).await.unwrap();
+        record(&db, "flaky", true, Some(0.2)).await.unwrap();
+        record(&db, "solid", true, Some(0.9)).await.unwrap();
+        let solid = record(&db, "solid", true, Some(1.0)).await.unwrap();
+        assert_eq!(solid.tasks, 2);
+        assert_eq!(solid.completed, 2);
+
+        let names = vec!["new".to_string(), "flaky".to_string(), "solid".to_string()];
+        let order: Vec<String> = ranked(&db, &names)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|(name, _)| name)
+            .collect();
+        assert_eq!(order, vec!["solid", "new", "flaky"]);
+    }
+}
@@ -2,9 +2,7 @@
 
 use anyhow::{Result, anyhow};
 use async_trait::async_trait;
-#[cfg(test)]
-use meepo_core::types::MessageKind;
-use meepo_core::types::{ChannelType, IncomingMessage, OutgoingMessage};
+use meepo_core::types::{ChannelType, IncomingMessage, MessageKind, OutgoingMessage};
 use std::collections::HashMap;
 use tokio::sync::mpsc;
 use tracing::{debug, error, info};
@@ -20,6 +18,11 @@ pub trait MessageChannel: Send + Sync {
 
     /// Which channel type this adapter handles
     fn channel_type(&self) -> ChannelType;
+
+    /// Whether it can show partial replies; others only get the final one
+    fn supports_streaming(&self) -> bool {
+        false
+    }
 }
 
 /// Central message bus that routes messages between channels and the agent
@@ -93,6 +96,9 @@ impl MessageBus {
             .get(channel_type)
             .ok_or_else(|| anyhow!("No channel registered for type: {}", channel_type))?;
 
+        if msg.kind == MessageKind::Partial && !channel.supports_streaming() {
+            return Ok(());
+        }
         channel.send(msg).await?;
         Ok(())
     }
@@ -135,6 +141,9 @@ impl BusSender {
             .get(channel_type)
             .ok_or_else(|| anyhow!("No channel registered for type: {}", channel_type))?;
 
+        if msg.kind == MessageKind::Partial && !channel.supports_streaming() {
+            return Ok(());
+        }
         channel.send(msg).await?;
         Ok(())
     }
@@ -240,11 +249,31 @@ mod tests {
             channel: ChannelType::Discord,
             reply_to: None,
             kind: MessageKind::Response,
+            interactive: None,
         };
         sender.send(msg).await.unwrap();
         assert!(sent_flag.load(Ordering::SeqCst));
     }
 
+    #[tokio::test]
+    async fn test_partials_skip_channels_without_streaming() {
+        let mut bus = MessageBus::new(32);
+        let mock = MockChannel::new(ChannelType::Slack);
+        let sent_flag = mock.sent.clone();
+        bus.register(Box::new(mock));
+        let (_rx, sender) = bus.split();
+
+        let msg = OutgoingMessage {
+            content: "so far".to_string(),
+            channel: ChannelType::Slack,
+            reply_to: Some("m1".to_string()),
+            kind: MessageKind::Partial,
+            interactive: None,
+        };
+        sender.send(msg).await.unwrap();
+        assert!(!sent_flag.load(Ordering::SeqCst));
+    }
+
     #[tokio::test]
     async fn test_bus_sender_unknown_channel() {
         let mut bus = MessageBus::new(32);
@@ -258,6 +287,7 @@ mod tests {
             channel: ChannelType::Slack,
             reply_to: None,
             kind: MessageKind::Response,
+            interactive: None,
         };
         let result = sender.send(msg).await;
         assert!(result.is_err());
@@ -303,6 +333,7 @@ mod tests {
             channel: ChannelType::Discord,
             reply_to: None,
             kind: MessageKind::Response,
+            interactive: None,
         };
         bus.send(msg).await.unwrap();
         assert!(sent_flag.load(Ordering::SeqCst));
@@ -316,6 +347,7 @@ mod tests {
             channel: ChannelType::Slack,
             reply_to: None,
             kind: MessageKind::Response,
+            interactive: None,
         };
         let result = bus.send(msg).await;
         assert!(result.is_err());
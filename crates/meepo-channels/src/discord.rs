@@ -6,7 +6,11 @@ use anyhow::{Result, anyhow};
 use chrono::Utc;
 use dashmap::DashMap;
 use lru::LruCache;
-use meepo_core::types::{ChannelType, IncomingMessage, MessageKind, OutgoingMessage};
+use meepo_core::types::{ChannelType, IncomingMessage, Interactive, MessageKind, OutgoingMessage};
+use serenity::builder::{
+    CreateActionRow, CreateButton, CreateInteractionResponse, CreateInteractionResponseMessage,
+    CreateMessage, EditMessage,
+};
 use serenity::{
     async_trait, gateway::GatewayError, model::gateway::Ready, model::prelude::*, prelude::*,
 };
@@ -18,9 +22,15 @@ use tokio::sync::{Mutex, RwLock};
 use tracing::{debug, error, info, warn};
 
 const MAX_MESSAGE_CHANNELS: usize = 1000;
+/// Replies being streamed at once, by the message they answer
+const MAX_STREAMED_REPLIES: usize = 100;
 const MAX_MESSAGE_SIZE: usize = 10_240;
 /// Discord's maximum message length in characters
 const DISCORD_MAX_LENGTH: usize = 2000;
+/// Discord's maximum length of a component's custom_id
+const CUSTOM_ID_MAX_LENGTH: usize = 100;
+/// Prefix of the custom_id on Meepo's answer buttons
+const BUTTON_ID_PREFIX: &str = "meepo|";
 
 /// Type key for storing the incoming message sender in Serenity's TypeMap
 struct MessageSender;
@@ -156,6 +166,122 @@ impl EventHandler for DiscordHandler {
         }
     }
 
+    /// 👍/👎 reactions in DMs are forwarded as feedback messages, 💤 as a
+    /// snooze
+    async fn reaction_add(&self, ctx: Context, reaction: Reaction) {
+        if reaction.guild_id.is_some() {
+            return;
+        }
+        let ReactionType::Unicode(emoji) = &reaction.emoji else {
+            return;
+        };
+        if !emoji.starts_with('👍') && !emoji.starts_with('👎') && !emoji.starts_with('💤') {
+            return;
+        }
+        let Some(user_id) = reaction.user_id else {
+            return;
+        };
+
+        let data = ctx.data.read().await;
+        let allowed = data
+            .get::<AllowedUsers>()
+            .is_some_and(|users| users.contains(&user_id));
+        if !allowed {
+            return;
+        }
+        let Some(tx) = data.get::<MessageSender>().cloned() else {
+            return;
+        };
+        drop(data);
+
+        let user = match reaction.user(&ctx).await {
+            Ok(user) if !user.bot => user,
+            Ok(_) => return,
+            Err(e) => {
+                warn!("Failed to look up Discord user {}: {}", user_id, e);
+                return;
+            }
+        };
+
+        let incoming = IncomingMessage {
+            id: format!("discord_reaction_{}", reaction.message_id),
+            sender: match user.discriminator {
+                Some(d) => format!("{}#{:04}", user.name, d),
+                None => user.name.clone(),
+            },
+            content: emoji.clone(),
+            channel: ChannelType::Discord,
+            timestamp: Utc::now(),
+        };
+        debug!(
+            "Forwarding Discord reaction {} from {}",
+            emoji, incoming.sender
+        );
+        if let Err(e) = tx.send(incoming).await {
+            error!("Failed to send Discord reaction to bus: {}", e);
+        }
+    }
+
+    /// Presses on answer buttons are forwarded as button-press messages
+    async fn interaction_create(&self, ctx: Context, interaction: Interaction) {
+        let Interaction::Component(press) = interaction else {
+            return;
+        };
+        if press.guild_id.is_some() || press.user.bot {
+            return;
+        }
+        let Some((prompt_id, value)) = parse_button_id(&press.data.custom_id) else {
+            return;
+        };
+
+        let data = ctx.data.read().await;
+        let allowed = data
+            .get::<AllowedUsers>()
+            .is_some_and(|users| users.contains(&press.user.id));
+        if !allowed {
+            warn!(
+                "Ignoring Discord button press from unauthorized user: {}",
+                press.user.id
+            );
+            return;
+        }
+        let Some(tx) = data.get::<MessageSender>().cloned() else {
+            return;
+        };
+        let message_channel_map = data.get::<MessageChannelMap>().cloned();
+        drop(data);
+
+        // Replace the buttons with the chosen answer
+        let answered = format!("{}\n\n**→ {}**", press.message.content, value);
+        let response = CreateInteractionResponse::UpdateMessage(
+            CreateInteractionResponseMessage::new()
+                .content(answered)
+                .components(Vec::new()),
+        );
+        if let Err(e) = press.create_response(&ctx.http, response).await {
+            warn!("Failed to acknowledge Discord button press: {}", e);
+        }
+
+        let msg_id = format!("discord_interaction_{}", press.id);
+        if let Some(map) = message_channel_map {
+            map.lock().await.put(msg_id.clone(), press.channel_id);
+        }
+        let incoming = IncomingMessage {
+            id: msg_id,
+            sender: match press.user.discriminator {
+                Some(d) => format!("{}#{:04}", press.user.name, d),
+                None => press.user.name.clone(),
+            },
+            content: Interactive::press_content(prompt_id, value),
+            channel: ChannelType::Discord,
+            timestamp: Utc::now(),
+        };
+        debug!("Forwarding Discord button press from {}", incoming.sender);
+        if let Err(e) = tx.send(incoming).await {
+            error!("Failed to send Discord button press to bus: {}", e);
+        }
+    }
+
     async fn ready(&self, _ctx: Context, ready: Ready) {
         info!("Discord bot connected as {}", ready.user.name);
     }
@@ -169,6 +295,8 @@ pub struct DiscordChannel {
     user_channel_map: Arc<DashMap<UserId, ChannelId>>,
     /// Maps message_id -> channel_id for reply-to tracking (LRU-bounded)
     message_channels: Arc<Mutex<LruCache<String, ChannelId>>>,
+    /// Maps reply_to -> the message being edited as the reply streams in
+    streamed_replies: Mutex<LruCache<String, MessageId>>,
 }
 
 impl DiscordChannel {
@@ -186,6 +314,9 @@ impl DiscordChannel {
             message_channels: Arc::new(Mutex::new(LruCache::new(
                 NonZeroUsize::new(MAX_MESSAGE_CHANNELS).unwrap(),
             ))),
+            streamed_replies: Mutex::new(LruCache::new(
+                NonZeroUsize::new(MAX_STREAMED_REPLIES).unwrap(),
+            )),
         }
     }
 
@@ -218,6 +349,52 @@ fn is_fatal_gateway_error(err: &serenity::Error) -> bool {
     }
 }
 
+/// custom_id for an answer button: `meepo|<prompt id>|<value>`
+fn button_id(prompt_id: &str, value: &str) -> String {
+    let id = format!("{}{}|{}", BUTTON_ID_PREFIX, prompt_id, value);
+    match id.char_indices().nth(CUSTOM_ID_MAX_LENGTH) {
+        Some((end, _)) => id[..end].to_string(),
+        None => id,
+    }
+}
+
+/// Prompt ID and value of an answer button's custom_id
+fn parse_button_id(custom_id: &str) -> Option<(&str, &str)> {
+    custom_id.strip_prefix(BUTTON_ID_PREFIX)?.split_once('|')
+}
+
+/// Action row of answer buttons (Discord allows five per row)
+fn button_row(interactive: &Interactive) -> CreateActionRow {
+    use meepo_core::types::ButtonStyle as Style;
+
+    CreateActionRow::Buttons(
+        interactive
+            .buttons
+            .iter()
+            .take(5)
+            .map(|b| {
+                CreateButton::new(button_id(&interactive.prompt_id, &b.value))
+                    .label(b.label.chars().take(80).collect::<String>())
+                    .style(match b.style {
+                        Style::Primary => ButtonStyle::Success,
+                        Style::Danger => ButtonStyle::Danger,
+                        Style::Default => ButtonStyle::Secondary,
+                    })
+            })
+            .collect(),
+    )
+}
+
+/// A partial reply cut to one message; the final reply is split properly
+fn partial_preview(content: &str) -> String {
+    if content.chars().count() <= DISCORD_MAX_LENGTH {
+        return content.to_string();
+    }
+    let mut preview: String = content.chars().take(DISCORD_MAX_LENGTH - 1).collect();
+    preview.push('…');
+    preview
+}
+
 /// Split a message into chunks that fit Discord's 2000 character limit.
 /// Tries to split at newlines to avoid breaking in the middle of sentences.
 fn split_message(content: &str) -> Vec<String> {
@@ -298,7 +475,9 @@ impl MessageChannel for DiscordChannel {
                 info!("Discord client starting (attempt #{})", retry_count);
 
                 // Set up intents
-                let intents = GatewayIntents::DIRECT_MESSAGES | GatewayIntents::MESSAGE_CONTENT;
+                let intents = GatewayIntents::DIRECT_MESSAGES
+                    | GatewayIntents::DIRECT_MESSAGE_REACTIONS
+                    | GatewayIntents::MESSAGE_CONTENT;
 
                 // Build the client
                 let mut client = match Client::builder(&token, intents)
@@ -411,9 +590,42 @@ impl MessageChannel for DiscordChannel {
             return Ok(());
         }
 
-        // Normal response: send text message, splitting if needed
+        // Partial reply: post it, then keep editing that message
+        if msg.kind == MessageKind::Partial {
+            let Some(reply_to) = &msg.reply_to else {
+                return Ok(());
+            };
+            let preview = partial_preview(&msg.content);
+            let existing = self.streamed_replies.lock().await.get(reply_to).copied();
+            match existing {
+                Some(message_id) => {
+                    channel_id
+                        .edit_message(http, message_id, EditMessage::new().content(preview))
+                        .await
+                        .map_err(|e| anyhow!("Failed to update streamed Discord reply: {}", e))?;
+                }
+                None => {
+                    let sent = channel_id
+                        .send_message(http, CreateMessage::new().content(preview))
+                        .await
+                        .map_err(|e| anyhow!("Failed to start streamed Discord reply: {}", e))?;
+                    self.streamed_replies
+                        .lock()
+                        .await
+                        .put(reply_to.clone(), sent.id);
+                }
+            }
+            return Ok(());
+        }
+
+        // Normal response: send text message, splitting if needed. A reply
+        // that was streamed gets its first chunk edited into place.
         debug!("Sending Discord message");
         let chunks = split_message(&msg.content);
+        let mut streamed = match &msg.reply_to {
+            Some(reply_to) => self.streamed_replies.lock().await.pop(reply_to),
+            None => None,
+        };
 
         if chunks.len() > 1 {
             debug!(
@@ -423,8 +635,29 @@ impl MessageChannel for DiscordChannel {
         }
 
         for (i, chunk) in chunks.iter().enumerate() {
+            // Answer buttons go on the last chunk
+            let buttons = msg
+                .interactive
+                .as_ref()
+                .filter(|_| i == chunks.len() - 1)
+                .map(|interactive| vec![button_row(interactive)]);
+            if let Some(message_id) = streamed.take() {
+                let mut edit = EditMessage::new().content(chunk);
+                if let Some(buttons) = buttons {
+                    edit = edit.components(buttons);
+                }
+                channel_id
+                    .edit_message(http, message_id, edit)
+                    .await
+                    .map_err(|e| anyhow!("Failed to finish streamed Discord reply: {}", e))?;
+                continue;
+            }
+            let mut message = CreateMessage::new().content(chunk);
+            if let Some(buttons) = buttons {
+                message = message.components(buttons);
+            }
             channel_id
-                .say(http, chunk)
+                .send_message(http, message)
                 .await
                 .map_err(|e| anyhow!("Failed to send Discord message chunk {}: {}", i + 1, e))?;
 
@@ -445,6 +678,10 @@ impl MessageChannel for DiscordChannel {
     fn channel_type(&self) -> ChannelType {
         ChannelType::Discord
     }
+
+    fn supports_streaming(&self) -> bool {
+        true
+    }
 }
 
 #[cfg(test)]
@@ -481,6 +718,26 @@ mod tests {
         assert_eq!(ids.len(), 0);
     }
 
+    #[test]
+    fn test_button_ids() {
+        let id = button_id("q-1a2b3c4d", "Approve");
+        assert_eq!(id, "meepo|q-1a2b3c4d|Approve");
+        assert_eq!(parse_button_id(&id), Some(("q-1a2b3c4d", "Approve")));
+        assert_eq!(parse_button_id("other|x|y"), None);
+        assert_eq!(
+            button_id("q-1", &"x".repeat(200)).len(),
+            CUSTOM_ID_MAX_LENGTH
+        );
+    }
+
+    #[test]
+    fn test_partial_preview_fits_one_message() {
+        assert_eq!(partial_preview("Checking"), "Checking");
+        let preview = partial_preview(&"a".repeat(DISCORD_MAX_LENGTH + 10));
+        assert_eq!(preview.chars().count(), DISCORD_MAX_LENGTH);
+        assert!(preview.ends_with('…'));
+    }
+
     #[test]
     fn test_split_message_short() {
         let short_msg = "Hello, world!";
@@ -6,8 +6,10 @@ use anyhow::{Result, anyhow};
 use async_trait::async_trait;
 use chrono::Utc;
 use lru::LruCache;
+use meepo_core::audio::voice_notes::{self, VoiceNoteTranscriber};
 use meepo_core::types::{ChannelType, IncomingMessage, MessageKind, OutgoingMessage};
 use std::num::NonZeroUsize;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use std::time::Duration;
 use tokio::process::Command;
@@ -80,6 +82,8 @@ pub struct EmailChannel {
     /// Maps message_id -> (sender, original_subject) for reply routing
     message_senders: Arc<Mutex<LruCache<String, EmailMeta>>>,
     rate_limiter: RateLimiter,
+    /// Transcribes audio attachments (voice notes); `None` ignores them
+    transcriber: Option<Arc<VoiceNoteTranscriber>>,
 }
 
 /// Metadata about an email for reply threading
@@ -97,6 +101,42 @@ impl EmailChannel {
                 NonZeroUsize::new(MAX_EMAIL_SENDERS).unwrap(),
             ))),
             rate_limiter: RateLimiter::new(10, Duration::from_secs(60)),
+            transcriber: None,
+        }
+    }
+
+    /// Transcribe audio attachments and add them to the email's content
+    pub fn with_transcriber(mut self, transcriber: Arc<VoiceNoteTranscriber>) -> Self {
+        self.transcriber = Some(transcriber);
+        self
+    }
+
+    /// Where Mail.app saves audio attachments for transcription
+    fn voice_note_dir() -> PathBuf {
+        std::env::temp_dir().join("meepo-voice-notes")
+    }
+
+    /// Transcribe a saved attachment and delete it. Only files Mail.app
+    /// saved into the voice note directory are read.
+    async fn transcribe_attachment(
+        transcriber: &VoiceNoteTranscriber,
+        path: &Path,
+    ) -> Option<String> {
+        if path.parent() != Some(Self::voice_note_dir().as_path()) {
+            warn!(
+                "Ignoring email audio outside the voice note directory: {}",
+                path.display()
+            );
+            return None;
+        }
+        let result = transcriber.transcribe_path(path).await;
+        let _ = tokio::fs::remove_file(path).await;
+        match result {
+            Ok(transcript) => Some(transcript),
+            Err(e) => {
+                warn!("Failed to transcribe email voice note: {}", e);
+                None
+            }
         }
     }
 
@@ -119,6 +159,29 @@ impl EmailChannel {
 
         let prefix = Self::escape_applescript(&self.subject_prefix);
 
+        // Save audio attachments so they can be transcribed (clearing out
+        // any left by the last poll's skipped messages)
+        let save_audio = if self.transcriber.is_some() {
+            let dir = Self::voice_note_dir();
+            let _ = tokio::fs::remove_dir_all(&dir).await;
+            tokio::fs::create_dir_all(&dir).await?;
+            let dir = Self::escape_applescript(&dir.to_string_lossy());
+            format!(
+                r#"
+            repeat with a in (mail attachments of m)
+                try
+                    if (MIME type of a) starts with "audio/" then
+                        set savePath to "{dir}/" & msgId & "-" & (name of a)
+                        save a in POSIX file savePath
+                        set output to output & "Audio: " & savePath & "\n"
+                    end if
+                end try
+            end repeat"#
+            )
+        } else {
+            String::new()
+        };
+
         let script = format!(
             r#"
 tell application "Mail"
@@ -138,7 +201,7 @@ tell application "Mail"
             set output to output & "ID: " & msgId & "\n"
             set output to output & "From: " & msgSender & "\n"
             set output to output & "Subject: " & msgSubject & "\n"
-            set output to output & "Date: " & msgDate & "\n"
+            set output to output & "Date: " & msgDate & "\n"{save_audio}
             set output to output & "Body: " & msgBody & "\n"
             set output to output & "<<MSG_END>>" & "\n"
             set read status of m to true
@@ -200,6 +263,7 @@ end tell
             let mut sender = String::new();
             let mut subject = String::new();
             let mut body = String::new();
+            let mut audio = Vec::new();
 
             for line in block.lines() {
                 let line = line.trim();
@@ -209,6 +273,8 @@ end tell
                     sender = val.to_string();
                 } else if let Some(val) = line.strip_prefix("Subject: ") {
                     subject = val.to_string();
+                } else if let Some(val) = line.strip_prefix("Audio: ") {
+                    audio.push(PathBuf::from(val));
                 } else if let Some(val) = line.strip_prefix("Body: ") {
                     body = val.to_string();
                 }
@@ -233,7 +299,7 @@ end tell
                 .trim()
                 .to_string();
 
-            let content = if stripped_subject.is_empty() {
+            let mut content = if stripped_subject.is_empty() {
                 body.clone()
             } else if body.is_empty() {
                 stripped_subject.clone()
@@ -241,6 +307,18 @@ end tell
                 format!("{}\n\n{}", stripped_subject, body)
             };
 
+            if let Some(transcriber) = &self.transcriber {
+                let mut transcripts = Vec::new();
+                for path in &audio {
+                    if let Some(transcript) = Self::transcribe_attachment(transcriber, path).await {
+                        transcripts.push(transcript);
+                    }
+                }
+                if !transcripts.is_empty() {
+                    content = voice_notes::message_content(&transcripts.join("\n\n"), &content);
+                }
+            }
+
             let msg_id = format!("email_{}", id);
 
             {
@@ -339,12 +417,14 @@ impl MessageChannel for EmailChannel {
         let subject_prefix = self.subject_prefix.clone();
         let message_senders = self.message_senders.clone();
         let rate_limiter = self.rate_limiter.clone();
+        let transcriber = self.transcriber.clone();
 
         let channel = EmailChannel {
             poll_interval,
             subject_prefix,
             message_senders,
             rate_limiter,
+            transcriber,
         };
 
         tokio::spawn(async move {
@@ -463,6 +543,7 @@ mod tests {
             channel: ChannelType::Email,
             reply_to: None,
             kind: MessageKind::Response,
+            interactive: None,
         };
 
         let result = channel.send(msg).await;
@@ -0,0 +1,1101 @@
+//! Email channel adapter speaking IMAP and SMTP
+//!
+//! The cross-platform counterpart of the Mail.app adapter in `email.rs`.
+//! Unread messages whose subject starts with the prefix are fetched over
+//! IMAP, waiting in IDLE between checks when the server supports it, and
+//! replies go out over SMTP threaded with `In-Reply-To`. Both connections
+//! use TLS: SMTP on port 465 starts with it, other ports upgrade with
+//! STARTTLS.
+
+use crate::bus::MessageChannel;
+use crate::rate_limit::RateLimiter;
+use anyhow::{Context, Result, anyhow, bail};
+use async_trait::async_trait;
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use chrono::Utc;
+use lru::LruCache;
+use meepo_core::types::{ChannelType, IncomingMessage, MessageKind, OutgoingMessage};
+use std::collections::HashSet;
+use std::num::NonZeroUsize;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::io::{AsyncBufReadExt, AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt, BufReader};
+use tokio::net::TcpStream;
+use tokio::sync::{Mutex, mpsc};
+use tokio_rustls::TlsConnector;
+use tokio_rustls::client::TlsStream;
+use tokio_rustls::rustls::pki_types::ServerName;
+use tokio_rustls::rustls::{ClientConfig, RootCertStore};
+use tracing::{debug, error, info, warn};
+
+const MAX_EMAIL_SENDERS: usize = 500;
+
+/// Body text kept per email, as with Mail.app
+const MAX_BODY_CHARS: usize = 2000;
+
+/// Largest message (or other literal) read from the IMAP server
+const MAX_LITERAL_BYTES: usize = 10 * 1024 * 1024;
+
+/// Longest response line read from either server
+const MAX_LINE_BYTES: u64 = 64 * 1024;
+
+/// Messages fetched per check; the rest wait for the next one
+const MAX_FETCH_PER_CHECK: usize = 20;
+
+/// Nested multipart levels searched for the message text
+const MAX_MIME_DEPTH: usize = 5;
+
+const COMMAND_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// RFC 2177 asks clients to re-issue IDLE at least every 29 minutes
+const IDLE_REFRESH: Duration = Duration::from_secs(25 * 60);
+
+const MIN_RECONNECT_DELAY: Duration = Duration::from_secs(5);
+const MAX_RECONNECT_DELAY: Duration = Duration::from_secs(300);
+
+const ACKNOWLEDGMENT: &str = "Your message has been received. Working on a response...";
+
+/// Server and account settings for [`ImapEmailChannel`]
+#[derive(Clone)]
+pub struct ImapEmailConfig {
+    pub imap_host: String,
+    pub imap_port: u16,
+    pub smtp_host: String,
+    pub smtp_port: u16,
+    pub username: String,
+    pub password: String,
+    /// Address replies are sent from
+    pub from: String,
+    pub mailbox: String,
+    pub subject_prefix: String,
+    /// How often to check for mail when the server has no IDLE
+    pub poll_interval: Duration,
+}
+
+impl std::fmt::Debug for ImapEmailConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ImapEmailConfig")
+            .field("imap_host", &self.imap_host)
+            .field("imap_port", &self.imap_port)
+            .field("smtp_host", &self.smtp_host)
+            .field("smtp_port", &self.smtp_port)
+            .field("username", &self.username)
+            .field("password", &"[REDACTED]")
+            .field("from", &self.from)
+            .field("mailbox", &self.mailbox)
+            .field("subject_prefix", &self.subject_prefix)
+            .field("poll_interval", &self.poll_interval)
+            .finish()
+    }
+}
+
+/// Email channel adapter for any IMAP/SMTP account
+#[derive(Clone)]
+pub struct ImapEmailChannel {
+    config: Arc<ImapEmailConfig>,
+    /// Maps message_id -> reply details for threading
+    message_senders: Arc<Mutex<LruCache<String, ReplyMeta>>>,
+    rate_limiter: RateLimiter,
+}
+
+/// What a reply to an email needs
+#[derive(Debug, Clone)]
+struct ReplyMeta {
+    address: String,
+    subject: String,
+    message_id: Option<String>,
+    references: Option<String>,
+}
+
+impl ImapEmailChannel {
+    pub fn new(config: ImapEmailConfig) -> Self {
+        Self {
+            config: Arc::new(config),
+            message_senders: Arc::new(Mutex::new(LruCache::new(
+                NonZeroUsize::new(MAX_EMAIL_SENDERS).unwrap(),
+            ))),
+            rate_limiter: RateLimiter::new(10, Duration::from_secs(60)),
+        }
+    }
+
+    /// One IMAP connection: log in, then check for mail until something fails
+    async fn run_session(&self, tx: &mpsc::Sender<IncomingMessage>) -> Result<()> {
+        let config = &self.config;
+        let mut session = ImapSession::connect(&config.imap_host, config.imap_port).await?;
+        session
+            .command(&format!(
+                "LOGIN {} {}",
+                quote(&config.username)?,
+                quote(&config.password)?
+            ))
+            .await
+            .context("IMAP login failed")?;
+        let idle = session.supports_idle().await?;
+        session
+            .command(&format!("SELECT {}", quote(&config.mailbox)?))
+            .await
+            .with_context(|| format!("Failed to open mailbox '{}'", config.mailbox))?;
+        info!(
+            "Connected to IMAP server {} ({})",
+            config.imap_host,
+            if idle { "IDLE" } else { "polling" }
+        );
+
+        // Unread messages that matched the server-side search but whose
+        // subject doesn't start with the prefix; left unread, not refetched
+        let mut skipped = HashSet::new();
+        loop {
+            self.check(&mut session, &mut skipped, tx).await?;
+            if idle {
+                session.idle(IDLE_REFRESH).await?;
+            } else {
+                tokio::time::sleep(config.poll_interval).await;
+            }
+        }
+    }
+
+    /// Fetch unread messages addressed to Meepo and pass them to the bus
+    async fn check<S: AsyncRead + AsyncWrite + Unpin>(
+        &self,
+        session: &mut ImapSession<S>,
+        skipped: &mut HashSet<u32>,
+        tx: &mpsc::Sender<IncomingMessage>,
+    ) -> Result<()> {
+        let uids: Vec<u32> = session
+            .unseen(&self.config.subject_prefix)
+            .await?
+            .into_iter()
+            .filter(|uid| !skipped.contains(uid))
+            .take(MAX_FETCH_PER_CHECK)
+            .collect();
+        for uid in uids {
+            let Some(raw) = session.fetch(uid).await? else {
+                continue;
+            };
+            let email = ParsedEmail::parse(&raw);
+            if !email.subject.starts_with(&self.config.subject_prefix)
+                || !valid_address(&email.address)
+            {
+                debug!(
+                    "Skipping email {} from '{}': '{}'",
+                    uid, email.address, email.subject
+                );
+                skipped.insert(uid);
+                continue;
+            }
+            session.mark_seen(uid).await?;
+            self.deliver(uid, email, tx).await;
+        }
+        Ok(())
+    }
+
+    async fn deliver(&self, uid: u32, email: ParsedEmail, tx: &mpsc::Sender<IncomingMessage>) {
+        if !self.rate_limiter.check_and_record(&email.address) {
+            return;
+        }
+
+        let stripped_subject = email
+            .subject
+            .strip_prefix(&self.config.subject_prefix)
+            .unwrap_or(&email.subject)
+            .trim()
+            .to_string();
+        let content = if stripped_subject.is_empty() {
+            email.body.clone()
+        } else if email.body.is_empty() {
+            stripped_subject.clone()
+        } else {
+            format!("{}\n\n{}", stripped_subject, email.body)
+        };
+
+        let msg_id = format!("email_{}", uid);
+        self.message_senders.lock().await.put(
+            msg_id.clone(),
+            ReplyMeta {
+                address: email.address.clone(),
+                subject: email.subject,
+                message_id: email.message_id,
+                references: email.references,
+            },
+        );
+
+        info!("New email from {}: {}", email.address, stripped_subject);
+
+        let incoming = IncomingMessage {
+            id: msg_id,
+            sender: email.address,
+            content,
+            channel: ChannelType::Email,
+            timestamp: Utc::now(),
+        };
+        if let Err(e) = tx.send(incoming).await {
+            error!("Failed to send email message to bus: {}", e);
+        }
+    }
+
+    async fn reply(&self, meta: &ReplyMeta, body: &str) -> Result<()> {
+        let subject = if meta.subject.to_lowercase().starts_with("re:") {
+            meta.subject.clone()
+        } else {
+            format!("Re: {}", meta.subject)
+        };
+        let message = compose(
+            &self.config.from,
+            &meta.address,
+            &subject,
+            body,
+            meta.message_id.as_deref(),
+            meta.references.as_deref(),
+        );
+        send_mail(&self.config, &meta.address, &message).await?;
+        info!("Email reply sent to {}", meta.address);
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl MessageChannel for ImapEmailChannel {
+    async fn start(&self, tx: mpsc::Sender<IncomingMessage>) -> Result<()> {
+        info!("Starting Email channel adapter (IMAP)");
+        info!(
+            "IMAP: {}:{}, SMTP: {}:{}",
+            self.config.imap_host,
+            self.config.imap_port,
+            self.config.smtp_host,
+            self.config.smtp_port
+        );
+        info!("Subject prefix: {}", self.config.subject_prefix);
+
+        let channel = self.clone();
+        tokio::spawn(async move {
+            let mut delay = MIN_RECONNECT_DELAY;
+            loop {
+                let started = Instant::now();
+                if let Err(e) = channel.run_session(&tx).await {
+                    warn!("IMAP session ended: {:#}", e);
+                }
+                if tx.is_closed() {
+                    break;
+                }
+                if started.elapsed() > MAX_RECONNECT_DELAY {
+                    delay = MIN_RECONNECT_DELAY;
+                }
+                debug!("Reconnecting to IMAP in {:?}", delay);
+                tokio::time::sleep(delay).await;
+                delay = (delay * 2).min(MAX_RECONNECT_DELAY);
+            }
+        });
+
+        info!("Email channel adapter started");
+        Ok(())
+    }
+
+    async fn send(&self, msg: OutgoingMessage) -> Result<()> {
+        let meta = match &msg.reply_to {
+            Some(reply_to) => self.message_senders.lock().await.peek(reply_to).cloned(),
+            None => None,
+        };
+
+        let Some(meta) = meta else {
+            // Acknowledgments without reply context are silently ignored
+            if msg.kind == MessageKind::Acknowledgment {
+                debug!("Skipping email acknowledgment — no reply context");
+                return Ok(());
+            }
+            warn!("Cannot send email without reply context (no reply_to or sender unknown)");
+            return Err(anyhow!("Cannot send email: no reply context available"));
+        };
+
+        if msg.kind == MessageKind::Acknowledgment {
+            debug!("Sending email acknowledgment to {}", meta.address);
+            if let Err(e) = self.reply(&meta, ACKNOWLEDGMENT).await {
+                warn!("Failed to send email acknowledgment: {}", e);
+            }
+            return Ok(());
+        }
+        self.reply(&meta, &msg.content).await
+    }
+
+    fn channel_type(&self) -> ChannelType {
+        ChannelType::Email
+    }
+}
+
+// ── Connections ─────────────────────────────────────────────────
+
+fn tls_connector() -> TlsConnector {
+    let roots = RootCertStore {
+        roots: webpki_roots::TLS_SERVER_ROOTS.to_vec(),
+    };
+    let config = ClientConfig::builder()
+        .with_root_certificates(roots)
+        .with_no_client_auth();
+    TlsConnector::from(Arc::new(config))
+}
+
+async fn tcp_connect(host: &str, port: u16) -> Result<TcpStream> {
+    tokio::time::timeout(COMMAND_TIMEOUT, TcpStream::connect((host, port)))
+        .await
+        .map_err(|_| anyhow!("Connecting to {}:{} timed out", host, port))?
+        .with_context(|| format!("Failed to connect to {}:{}", host, port))
+}
+
+async fn tls_wrap<S: AsyncRead + AsyncWrite + Unpin>(
+    host: &str,
+    stream: S,
+) -> Result<TlsStream<S>> {
+    let name = ServerName::try_from(host.to_string())
+        .map_err(|_| anyhow!("Invalid server name '{}'", host))?;
+    tokio::time::timeout(COMMAND_TIMEOUT, tls_connector().connect(name, stream))
+        .await
+        .map_err(|_| anyhow!("TLS handshake with {} timed out", host))?
+        .with_context(|| format!("TLS handshake with {} failed", host))
+}
+
+/// Read one line, bounded in length and time
+async fn read_line<S: AsyncRead + Unpin>(
+    stream: &mut BufReader<S>,
+    wait: Duration,
+) -> Result<String> {
+    let mut line = Vec::new();
+    let mut limited = (&mut *stream).take(MAX_LINE_BYTES);
+    let read = tokio::time::timeout(wait, limited.read_until(b'\n', &mut line))
+        .await
+        .map_err(|_| anyhow!("Server stopped responding"))??;
+    if read == 0 {
+        bail!("Connection closed by server");
+    }
+    if !line.ends_with(b"\n") {
+        bail!("Server response line too long");
+    }
+    Ok(String::from_utf8_lossy(&line).into_owned())
+}
+
+async fn write_line<S: AsyncRead + AsyncWrite + Unpin>(
+    stream: &mut BufReader<S>,
+    line: &str,
+) -> Result<()> {
+    let stream = stream.get_mut();
+    stream.write_all(line.as_bytes()).await?;
+    stream.write_all(b"\r\n").await?;
+    stream.flush().await?;
+    Ok(())
+}
+
+// ── IMAP ────────────────────────────────────────────────────────
+
+/// A command's untagged response lines and the literals sent with them
+#[derive(Debug, Default)]
+struct ImapResponse {
+    lines: Vec<String>,
+    literals: Vec<Vec<u8>>,
+}
+
+struct ImapSession<S> {
+    stream: BufReader<S>,
+    tag: u32,
+}
+
+impl ImapSession<TlsStream<TcpStream>> {
+    async fn connect(host: &str, port: u16) -> Result<Self> {
+        let tls = tls_wrap(host, tcp_connect(host, port).await?).await?;
+        let mut session = ImapSession::new(tls);
+        let greeting = read_line(&mut session.stream, COMMAND_TIMEOUT).await?;
+        if !greeting.starts_with("* OK") && !greeting.starts_with("* PREAUTH") {
+            bail!("Unexpected IMAP greeting: {}", greeting.trim());
+        }
+        Ok(session)
+    }
+}
+
+impl<S: AsyncRead + AsyncWrite + Unpin> ImapSession<S> {
+    fn new(stream: S) -> Self {
+        Self {
+            stream: BufReader::new(stream),
+            tag: 0,
+        }
+    }
+
+    fn next_tag(&mut self) -> String {
+        self.tag += 1;
+        format!("m{}", self.tag)
+    }
+
+    async fn command(&mut self, command: &str) -> Result<ImapResponse> {
+        let tag = self.next_tag();
+        write_line(&mut self.stream, &format!("{} {}", tag, command)).await?;
+        self.read_response(&tag).await
+    }
+
+    /// Read until the tagged completion, collecting untagged lines and any
+    /// `{n}` literals
+    async fn read_response(&mut self, tag: &str) -> Result<ImapResponse> {
+        let mut response = ImapResponse::default();
+        loop {
+            let mut line = read_line(&mut self.stream, COMMAND_TIMEOUT).await?;
+            // A line ending in {n} is followed by n bytes, then the rest of the line
+            while let Some(len) = literal_len(&line) {
+                if len > MAX_LITERAL_BYTES {
+                    bail!("IMAP literal of {} bytes is too large", len);
+                }
+                let mut literal = vec![0; len];
+                tokio::time::timeout(COMMAND_TIMEOUT, self.stream.read_exact(&mut literal))
+                    .await
+                    .map_err(|_| anyhow!("Server stopped responding"))??;
+                response.literals.push(literal);
+                line.push_str(&read_line(&mut self.stream, COMMAND_TIMEOUT).await?);
+            }
+            if let Some(status) = line.strip_prefix(tag).and_then(|s| s.strip_prefix(' ')) {
+                if status.starts_with("OK") {
+                    return Ok(response);
+                }
+                bail!("IMAP command failed: {}", status.trim());
+            }
+            response.lines.push(line);
+        }
+    }
+
+    async fn supports_idle(&mut self) -> Result<bool> {
+        let response = self.command("CAPABILITY").await?;
+        Ok(response.lines.iter().any(|line| {
+            line.split_whitespace()
+                .any(|w| w.eq_ignore_ascii_case("IDLE"))
+        }))
+    }
+
+    /// UIDs of unread messages whose subject contains `prefix`
+    async fn unseen(&mut self, prefix: &str) -> Result<Vec<u32>> {
+        // Non-ASCII search strings need a literal; filter those client-side
+        let query = if prefix.is_empty() || !prefix.is_ascii() {
+            "UID SEARCH UNSEEN".to_string()
+        } else {
+            format!("UID SEARCH UNSEEN SUBJECT {}", quote(prefix)?)
+        };
+        let response = self.command(&query).await?;
+        Ok(response
+            .lines
+            .iter()
+            .filter_map(|line| line.strip_prefix("* SEARCH"))
+            .flat_map(|uids| uids.split_whitespace().filter_map(|uid| uid.parse().ok()))
+            .collect())
+    }
+
+    /// The full message, without marking it read
+    async fn fetch(&mut self, uid: u32) -> Result<Option<Vec<u8>>> {
+        let response = self
+            .command(&format!("UID FETCH {} BODY.PEEK[]", uid))
+            .await?;
+        Ok(response.literals.into_iter().next())
+    }
+
+    async fn mark_seen(&mut self, uid: u32) -> Result<()> {
+        self.command(&format!("UID STORE {} +FLAGS.SILENT (\\Seen)", uid))
+            .await?;
+        Ok(())
+    }
+
+    /// Wait in IDLE until the mailbox gets a new message or `wait` passes
+    async fn idle(&mut self, wait: Duration) -> Result<()> {
+        let tag = self.next_tag();
+        write_line(&mut self.stream, &format!("{} IDLE", tag)).await?;
+        let reply = read_line(&mut self.stream, COMMAND_TIMEOUT).await?;
+        if !reply.starts_with('+') {
+            bail!("IMAP server refused IDLE: {}", reply.trim());
+        }
+        let deadline = Instant::now() + wait;
+        loop {
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            if remaining.is_zero() {
+                break;
+            }
+            match read_line(&mut self.stream, remaining).await {
+                Ok(line) if line.contains("EXISTS") || line.contains("RECENT") => break,
+                Ok(_) => {}
+                Err(_) if Instant::now() >= deadline => break,
+                Err(e) => return Err(e),
+            }
+        }
+        write_line(&mut self.stream, "DONE").await?;
+        self.read_response(&tag).await?;
+        Ok(())
+    }
+}
+
+/// The length of the literal announced at the end of an IMAP line
+fn literal_len(line: &str) -> Option<usize> {
+    let line = line.trim_end_matches(['\r', '\n']);
+    let open = line.strip_suffix('}')?.rfind('{')?;
+    line[open + 1..line.len() - 1].parse().ok()
+}
+
+/// An IMAP quoted string
+fn quote(s: &str) -> Result<String> {
+    if s.contains(['\r', '\n', '\0']) {
+        bail!("IMAP strings can't contain line breaks");
+    }
+    Ok(format!(
+        "\"{}\"",
+        s.replace('\\', "\\\\").replace('"', "\\\"")
+    ))
+}
+
+// ── SMTP ────────────────────────────────────────────────────────
+
+struct SmtpConnection<S> {
+    stream: BufReader<S>,
+}
+
+impl<S: AsyncRead + AsyncWrite + Unpin> SmtpConnection<S> {
+    fn new(stream: S) -> Self {
+        Self {
+            stream: BufReader::new(stream),
+        }
+    }
+
+    /// Read a (possibly multi-line) reply, failing unless it is in the same
+    /// class (2xx, 3xx) as `expected`
+    async fn expect(&mut self, expected: u16) -> Result<String> {
+        let mut text = String::new();
+        loop {
+            let line = read_line(&mut self.stream, COMMAND_TIMEOUT).await?;
+            text.push_str(line.trim_end());
+            text.push('\n');
+            // "250-" continues a reply, "250 " ends it
+            if line.as_bytes().get(3) == Some(&b'-') {
+                continue;
+            }
+            let code: u16 = line
+                .get(..3)
+                .and_then(|code| code.parse().ok())
+                .ok_or_else(|| anyhow!("Malformed SMTP reply: {}", line.trim()))?;
+            if code / 100 != expected / 100 {
+                bail!("SMTP server replied: {}", text.trim());
+            }
+            return Ok(text);
+        }
+    }
+
+    async fn command(&mut self, command: &str, expected: u16) -> Result<String> {
+        write_line(&mut self.stream, command).await?;
+        self.expect(expected).await
+    }
+
+    /// Log in and hand over one message
+    async fn deliver(&mut self, config: &ImapEmailConfig, to: &str, message: &str) -> Result<()> {
+        let credentials = BASE64.encode(format!("\0{}\0{}", config.username, config.password));
+        self.command(&format!("AUTH PLAIN {}", credentials), 235)
+            .await
+            .context("SMTP login failed")?;
+        self.command(&format!("MAIL FROM:<{}>", config.from), 250)
+            .await?;
+        self.command(&format!("RCPT TO:<{}>", to), 250).await?;
+        self.command("DATA", 354).await?;
+        self.stream.get_mut().write_all(message.as_bytes()).await?;
+        self.command(".", 250).await?;
+        let _ = self.command("QUIT", 221).await;
+        Ok(())
+    }
+
+    fn into_inner(self) -> S {
+        self.stream.into_inner()
+    }
+}
+
+async fn send_mail(config: &ImapEmailConfig, to: &str, message: &str) -> Result<()> {
+    if !valid_address(to) || !valid_address(&config.from) {
+        bail!("Invalid email address");
+    }
+    let helo = format!("EHLO {}", domain(&config.from));
+    let tcp = tcp_connect(&config.smtp_host, config.smtp_port).await?;
+    if config.smtp_port == 465 {
+        let mut smtp = SmtpConnection::new(tls_wrap(&config.smtp_host, tcp).await?);
+        smtp.expect(220).await?;
+        smtp.command(&helo, 250).await?;
+        smtp.deliver(config, to, message).await
+    } else {
+        let mut smtp = SmtpConnection::new(tcp);
+        smtp.expect(220).await?;
+        smtp.command(&helo, 250).await?;
+        smtp.command("STARTTLS", 220).await?;
+        let tls = tls_wrap(&config.smtp_host, smtp.into_inner()).await?;
+        let mut smtp = SmtpConnection::new(tls);
+        smtp.command(&helo, 250).await?;
+        smtp.deliver(config, to, message).await
+    }
+}
+
+/// A plain-text message with CRLF line endings, dot-stuffed for DATA
+fn compose(
+    from: &str,
+    to: &str,
+    subject: &str,
+    body: &str,
+    in_reply_to: Option<&str>,
+    references: Option<&str>,
+) -> String {
+    let mut headers = vec![
+        format!("From: {}", header_value(from)),
+        format!("To: {}", header_value(to)),
+        format!("Subject: {}", encode_header(subject)),
+        format!("Date: {}", Utc::now().to_rfc2822()),
+        format!("Message-ID: <{}@{}>", uuid::Uuid::new_v4(), domain(from)),
+        "MIME-Version: 1.0".to_string(),
+        "Content-Type: text/plain; charset=utf-8".to_string(),
+        "Content-Transfer-Encoding: 8bit".to_string(),
+    ];
+    if let Some(id) = in_reply_to {
+        let id = header_value(id);
+        headers.push(format!("In-Reply-To: {}", id));
+        headers.push(match references {
+            Some(refs) => format!("References: {} {}", header_value(refs), id),
+            None => format!("References: {}", id),
+        });
+    }
+
+    let mut message = headers.join("\r\n");
+    message.push_str("\r\n\r\n");
+    for line in body.lines() {
+        if line.starts_with('.') {
+            message.push('.');
+        }
+        message.push_str(line);
+        message.push_str("\r\n");
+    }
+    message
+}
+
+/// A header value with line breaks removed, so it can't add headers
+fn header_value(value: &str) -> String {
+    value.replace(['\r', '\n'], " ").trim().to_string()
+}
+
+/// RFC 2047 encoding for non-ASCII header text
+fn encode_header(value: &str) -> String {
+    let value = header_value(value);
+    if value.is_ascii() {
+        value
+    } else {
+        format!("=?UTF-8?B?{}?=", BASE64.encode(value))
+    }
+}
+
+fn valid_address(address: &str) -> bool {
+    address.contains('@')
+        && !address
+            .chars()
+            .any(|c| c.is_whitespace() || c.is_control() || matches!(c, '<' | '>' | ','))
+}
+
+fn domain(address: &str) -> &str {
+    address
+        .rsplit_once('@')
+        .map(|(_, domain)| domain)
+        .filter(|d| !d.is_empty())
+        .unwrap_or("localhost")
+}
+
+// ── Message parsing ─────────────────────────────────────────────
+
+/// The parts of an incoming email the channel uses
+#[derive(Debug, Default)]
+struct ParsedEmail {
+    address: String,
+    subject: String,
+    message_id: Option<String>,
+    references: Option<String>,
+    body: String,
+}
+
+type Headers = Vec<(String, String)>;
+
+impl ParsedEmail {
+    fn parse(raw: &[u8]) -> Self {
+        let (headers, body) = split_message(raw);
+        let from = decode_words(header(&headers, "from").unwrap_or_default());
+        let mut html = None;
+        let text = find_text(&headers, body, 0, &mut html)
+            .or(html)
+            .unwrap_or_default();
+        Self {
+            address: address(&from),
+            subject: decode_words(header(&headers, "subject").unwrap_or_default()),
+            message_id: header(&headers, "message-id").map(str::to_string),
+            references: header(&headers, "references").map(str::to_string),
+            body: text.trim().chars().take(MAX_BODY_CHARS).collect(),
+        }
+    }
+}
+
+fn header<'a>(headers: &'a Headers, name: &str) -> Option<&'a str> {
+    headers
+        .iter()
+        .find(|(n, _)| n.eq_ignore_ascii_case(name))
+        .map(|(_, v)| v.as_str())
+}
+
+/// A `name=value` parameter of a header such as Content-Type
+fn header_param(value: &str, name: &str) -> Option<String> {
+    value.split(';').skip(1).find_map(|param| {
+        let (key, val) = param.split_once('=')?;
+        key.trim()
+            .eq_ignore_ascii_case(name)
+            .then(|| val.trim().trim_matches('"').to_string())
+    })
+}
+
+fn mime_type(content_type: &str) -> String {
+    content_type
+        .split(';')
+        .next()
+        .unwrap_or_default()
+        .trim()
+        .to_ascii_lowercase()
+}
+
+fn find_bytes(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack.windows(needle.len()).position(|w| w == needle)
+}
+
+/// Split headers (unfolded) from the body
+fn split_message(raw: &[u8]) -> (Headers, &[u8]) {
+    let (head, body) = match find_bytes(raw, b"\r\n\r\n") {
+        Some(i) => (&raw[..i], &raw[i + 4..]),
+        None => match find_bytes(raw, b"\n\n") {
+            Some(i) => (&raw[..i], &raw[i + 2..]),
+            None => (raw, &raw[raw.len()..]),
+        },
+    };
+    let mut headers: Headers = Vec::new();
+    for line in String::from_utf8_lossy(head).lines() {
+        if line.starts_with([' ', '\t']) {
+            if let Some((_, value)) = headers.last_mut() {
+                value.push(' ');
+                value.push_str(line.trim());
+            }
+        } else if let Some((name, value)) = line.split_once(':') {
+            headers.push((name.trim().to_string(), value.trim().to_string()));
+        }
+    }
+    (headers, body)
+}
+
+/// The first text/plain part; the first text/html part (tags removed) is
+/// left in `html` as a fallback
+fn find_text(
+    headers: &Headers,
+    body: &[u8],
+    depth: usize,
+    html: &mut Option<String>,
+) -> Option<String> {
+    let content_type = header(headers, "content-type").unwrap_or("text/plain");
+    let mime = mime_type(content_type);
+    if mime.starts_with("multipart/") {
+        if depth >= MAX_MIME_DEPTH {
+            return None;
+        }
+        let boundary = header_param(content_type, "boundary")?;
+        return multipart_parts(body, &boundary)
+            .into_iter()
+            .find_map(|part| {
+                let (part_headers, part_body) = split_message(part);
+                find_text(&part_headers, part_body, depth + 1, html)
+            });
+    }
+    if header(headers, "content-disposition")
+        .is_some_and(|d| d.trim().to_ascii_lowercase().starts_with("attachment"))
+    {
+        return None;
+    }
+    let charset = header_param(content_type, "charset");
+    let text = || {
+        decode_charset(
+            &decode_transfer(header(headers, "content-transfer-encoding"), body),
+            charset.as_deref(),
+        )
+    };
+    match mime.as_str() {
+        "text/plain" => Some(text()),
+        "text/html" => {
+            if html.is_none() {
+                *html = Some(strip_html(&text()));
+            }
+            None
+        }
+        _ => None,
+    }
+}
+
+/// The parts between `--boundary` lines
+fn multipart_parts<'a>(body: &'a [u8], boundary: &str) -> Vec<&'a [u8]> {
+    let delimiter = format!("--{}", boundary);
+    let mut parts = Vec::new();
+    let mut start = None;
+    let mut pos = 0;
+    while pos < body.len() {
+        let end = find_bytes(&body[pos..], b"\n")
+            .map(|i| pos + i + 1)
+            .unwrap_or(body.len());
+        let line = &body[pos..end];
+        if let Some(rest) = line.strip_prefix(delimiter.as_bytes()) {
+            if let Some(s) = start {
+                let part: &[u8] = &body[s..pos];
+                let part = part.strip_suffix(b"\n").unwrap_or(part);
+                parts.push(part.strip_suffix(b"\r").unwrap_or(part));
+            }
+            if rest.starts_with(b"--") {
+                break;
+            }
+            start = Some(end);
+        }
+        pos = end;
+    }
+    parts
+}
+
+fn decode_transfer(encoding: Option<&str>, body: &[u8]) -> Vec<u8> {
+    match encoding.map(|e| e.trim().to_ascii_lowercase()).as_deref() {
+        Some("base64") => {
+            let compact: Vec<u8> = body
+                .iter()
+                .copied()
+                .filter(|b| !b.is_ascii_whitespace())
+                .collect();
+            BASE64.decode(compact).unwrap_or_else(|_| body.to_vec())
+        }
+        Some("quoted-printable") => decode_quoted_printable(body),
+        _ => body.to_vec(),
+    }
+}
+
+fn decode_quoted_printable(input: &[u8]) -> Vec<u8> {
+    let mut out = Vec::with_capacity(input.len());
+    let mut i = 0;
+    while i < input.len() {
+        if input[i] == b'=' {
+            let rest = &input[i + 1..];
+            // Soft line break
+            if rest.starts_with(b"\r\n") {
+                i += 3;
+                continue;
+            }
+            if rest.starts_with(b"\n") {
+                i += 2;
+                continue;
+            }
+            if let Some(byte) = rest
+                .get(..2)
+                .and_then(|hex| std::str::from_utf8(hex).ok())
+                .and_then(|hex| u8::from_str_radix(hex, 16).ok())
+            {
+                out.push(byte);
+                i += 3;
+                continue;
+            }
+        }
+        out.push(input[i]);
+        i += 1;
+    }
+    out
+}
+
+/// UTF-8, or Latin-1 for the single-byte charsets mail commonly uses
+fn decode_charset(bytes: &[u8], charset: Option<&str>) -> String {
+    match charset.map(|c| c.to_ascii_lowercase()).as_deref() {
+        Some("iso-8859-1" | "latin1" | "windows-1252") => {
+            bytes.iter().map(|&b| b as char).collect()
+        }
+        _ => String::from_utf8_lossy(bytes).into_owned(),
+    }
+}
+
+/// Decode RFC 2047 encoded words (`=?utf-8?B?...?=`) in a header
+fn decode_words(value: &str) -> String {
+    let mut out = String::with_capacity(value.len());
+    let mut rest = value;
+    let mut after_word = false;
+    while let Some(start) = rest.find("=?") {
+        let before = &rest[..start];
+        match encoded_word(&rest[start..]) {
+            Some((word, len)) => {
+                // Whitespace between adjacent encoded words is dropped
+                if !(after_word && before.trim().is_empty()) {
+                    out.push_str(before);
+                }
+                out.push_str(&word);
+                rest = &rest[start + len..];
+                after_word = true;
+            }
+            None => {
+                out.push_str(&rest[..start + 2]);
+                rest = &rest[start + 2..];
+                after_word = false;
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+/// Decode the encoded word at the start of `s`, returning it and its length
+fn encoded_word(s: &str) -> Option<(String, usize)> {
+    let inner = s.strip_prefix("=?")?;
+    let (charset, inner) = inner.split_once('?')?;
+    let (encoding, inner) = inner.split_once('?')?;
+    let text = &inner[..inner.find("?=")?];
+    if charset.contains(char::is_whitespace) || text.contains(char::is_whitespace) {
+        return None;
+    }
+    let bytes = match encoding {
+        "B" | "b" => BASE64.decode(text).ok()?,
+        "Q" | "q" => decode_quoted_printable(text.replace('_', " ").as_bytes()),
+        _ => return None,
+    };
+    let len = 2 + charset.len() + 1 + encoding.len() + 1 + text.len() + 2;
+    // RFC 2231 allows a language suffix: utf-8*en
+    let charset = charset.split('*').next().unwrap_or_default();
+    Some((decode_charset(&bytes, Some(charset)), len))
+}
+
+/// The bare address from a From header such as `Ana <ana@example.com>`
+fn address(from: &str) -> String {
+    let address = match (from.rfind('<'), from.rfind('>')) {
+        (Some(open), Some(close)) if open < close => &from[open + 1..close],
+        _ => from,
+    };
+    address.trim().to_lowercase()
+}
+
+/// Rough text from HTML: tags, scripts and styles dropped, common entities
+/// decoded
+fn strip_html(html: &str) -> String {
+    let mut text = String::with_capacity(html.len());
+    let mut rest = html;
+    while let Some(open) = rest.find('<') {
+        text.push_str(&rest[..open]);
+        let tag = &rest[open..];
+        let lower = tag.get(..7).unwrap_or(tag).to_ascii_lowercase();
+        let skip_to = if lower.starts_with("<script") {
+            "</script>"
+        } else if lower.starts_with("<style") {
+            "</style>"
+        } else {
+            ">"
+        };
+        let lower_tag = tag.to_ascii_lowercase();
+        match lower_tag.find(skip_to) {
+            Some(end) => {
+                let name = tag[1..]
+                    .split(|c: char| !c.is_alphanumeric() && c != '/')
+                    .next();
+                if matches!(name, Some("br" | "p" | "/p" | "div" | "/div" | "li" | "tr")) {
+                    text.push('\n');
+                }
+                rest = &tag[end + skip_to.len()..];
+            }
+            None => {
+                rest = "";
+            }
+        }
+    }
+    text.push_str(rest);
+    let text = text
+        .replace("&nbsp;", " ")
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&amp;", "&");
+    text.lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_multipart_email() {
+        let raw = b"From: =?UTF-8?B?QW7DoQ==?= <Ana@Example.com>\r\n\
+Subject: [meepo] =?utf-8?q?caf=C3=A9?=\r\n plans\r\n\
+Message-ID: <abc@example.com>\r\n\
+Content-Type: multipart/alternative; boundary=\"b1\"\r\n\
+\r\n\
+--b1\r\n\
+Content-Type: text/html\r\n\
+\r\n\
+<p>ignored</p>\r\n\
+--b1\r\n\
+Content-Type: text/plain; charset=utf-8\r\n\
+Content-Transfer-Encoding: quoted-printable\r\n\
+\r\n\
+Lunch at caf=C3=A9 =\r\nat noon?\r\n\
+--b1--\r\n";
+        let email = ParsedEmail::parse(raw);
+        assert_eq!(email.address, "ana@example.com");
+        assert_eq!(email.subject, "[meepo] café plans");
+        assert_eq!(email.message_id.as_deref(), Some("<abc@example.com>"));
+        assert_eq!(email.body, "Lunch at café at noon?");
+
+        let html_only = b"Subject: hi\r\nContent-Type: text/html\r\n\r\n<div>Hello&nbsp;<b>there</b></div><style>p{}</style>";
+        assert_eq!(ParsedEmail::parse(html_only).body, "Hello there");
+    }
+
+    #[test]
+    fn test_compose_threads_and_escapes() {
+        let message = compose(
+            "meepo@example.com",
+            "ana@example.com",
+            "Re: [meepo] café\r\nBcc: evil@example.com",
+            "Sure.\n.hidden line",
+            Some("<abc@example.com>"),
+            None,
+        );
+        assert!(message.contains("Subject: =?UTF-8?B?"));
+        assert!(!message.contains("\r\nBcc:"));
+        assert!(message.contains("In-Reply-To: <abc@example.com>\r\n"));
+        assert!(message.contains("References: <abc@example.com>\r\n"));
+        assert!(message.ends_with("\r\n\r\nSure.\r\n..hidden line\r\n"));
+    }
+
+    #[tokio::test]
+    async fn test_imap_response_with_literal() {
+        let (client, server) = tokio::io::duplex(4096);
+        let mut session = ImapSession::new(client);
+        let server = tokio::spawn(async move {
+            let mut server = BufReader::new(server);
+            let command = read_line(&mut server, COMMAND_TIMEOUT).await.unwrap();
+            assert_eq!(command, "m1 UID FETCH 7 BODY.PEEK[]\r\n");
+            let body = "Subject: hi\r\n\r\nhello\r\n";
+            let reply = format!(
+                "* 1 FETCH (UID 7 BODY[] {{{}}}\r\n{})\r\nm1 OK done\r\n",
+                body.len(),
+                body
+            );
+            server.get_mut().write_all(reply.as_bytes()).await.unwrap();
+            let command = read_line(&mut server, COMMAND_TIMEOUT).await.unwrap();
+            assert!(command.starts_with("m2 UID SEARCH UNSEEN SUBJECT \"[meepo]\""));
+            server
+                .get_mut()
+                .write_all(b"* SEARCH 3 9\r\nm2 OK done\r\n")
+                .await
+                .unwrap();
+            read_line(&mut server, COMMAND_TIMEOUT).await.unwrap();
+            server
+                .get_mut()
+                .write_all(b"m3 NO [AUTHENTICATIONFAILED] nope\r\n")
+                .await
+                .unwrap();
+        });
+
+        let raw = session.fetch(7).await.unwrap().unwrap();
+        assert_eq!(ParsedEmail::parse(&raw).body, "hello");
+        assert_eq!(session.unseen("[meepo]").await.unwrap(), vec![3, 9]);
+        let err = session.command("LOGIN \"a\" \"b\"").await.unwrap_err();
+        assert!(err.to_string().contains("AUTHENTICATIONFAILED"));
+        server.await.unwrap();
+    }
+}
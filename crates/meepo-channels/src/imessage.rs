@@ -6,10 +6,11 @@ use anyhow::{Result, anyhow};
 use async_trait::async_trait;
 use chrono::Utc;
 use lru::LruCache;
+use meepo_core::audio::voice_notes::{self, VoiceNoteTranscriber};
 use meepo_core::types::{ChannelType, IncomingMessage, MessageKind, OutgoingMessage};
 use rusqlite::{Connection, params};
 use std::num::NonZeroUsize;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use std::time::Duration;
 use tokio::process::Command;
@@ -32,6 +33,8 @@ pub struct IMessageChannel {
     /// Maps message_id -> sender contact for reply-to tracking (LRU-bounded)
     message_senders: Arc<Mutex<LruCache<String, String>>>,
     rate_limiter: RateLimiter,
+    /// Transcribes audio attachments (voice notes); `None` ignores them
+    transcriber: Option<Arc<VoiceNoteTranscriber>>,
 }
 
 impl IMessageChannel {
@@ -61,6 +64,23 @@ impl IMessageChannel {
                 NonZeroUsize::new(MAX_MESSAGE_SENDERS).unwrap(),
             ))),
             rate_limiter: RateLimiter::new(10, Duration::from_secs(60)),
+            transcriber: None,
+        }
+    }
+
+    /// Transcribe voice notes and forward them as text
+    pub fn with_transcriber(mut self, transcriber: Arc<VoiceNoteTranscriber>) -> Self {
+        self.transcriber = Some(transcriber);
+        self
+    }
+
+    /// Resolve an attachment path from chat.db, which stores them under `~`
+    fn attachment_path(filename: &str) -> PathBuf {
+        match filename.strip_prefix("~/") {
+            Some(rest) => dirs::home_dir()
+                .map(|home| home.join(rest))
+                .unwrap_or_else(|| PathBuf::from(filename)),
+            None => PathBuf::from(filename),
         }
     }
 
@@ -113,12 +133,18 @@ impl IMessageChannel {
                 message.ROWID,
                 message.text,
                 handle.id,
-                datetime(message.date/1000000000 + strftime('%s', '2001-01-01'), 'unixepoch')
+                datetime(message.date/1000000000 + strftime('%s', '2001-01-01'), 'unixepoch'),
+                (SELECT attachment.filename
+                    FROM message_attachment_join
+                    JOIN attachment ON attachment.ROWID = message_attachment_join.attachment_id
+                    WHERE message_attachment_join.message_id = message.ROWID
+                        AND (attachment.mime_type LIKE 'audio/%' OR attachment.filename LIKE '%.caf')
+                    LIMIT 1)
             FROM message
             JOIN handle ON message.handle_id = handle.ROWID
             WHERE message.ROWID > ?
                 AND message.is_from_me = 0
-                AND message.text IS NOT NULL
+                AND (message.text IS NOT NULL OR message.cache_has_attachments = 1)
             ORDER BY message.ROWID ASC
         "#;
 
@@ -131,20 +157,37 @@ impl IMessageChannel {
 
             while let Some(row) = rows.next()? {
                 let rowid: i64 = row.get(0)?;
-                let text: String = row.get(1)?;
+                let text: Option<String> = row.get(1)?;
                 let handle: String = row.get(2)?;
                 let timestamp_str: String = row.get(3)?;
+                let audio: Option<String> = row.get(4)?;
 
                 // Update last_rowid
                 new_last_rowid = new_last_rowid.max(rowid);
 
+                // Voice notes are only picked up when transcription is on
+                let audio = audio
+                    .filter(|_| self.transcriber.is_some())
+                    .map(|f| Self::attachment_path(&f));
+                if text.is_none() && audio.is_none() {
+                    continue;
+                }
+
                 // Check if contact is allowed
                 if !self.is_allowed_contact(&handle) {
                     warn!("Ignoring message from unauthorized contact: {}", handle);
                     continue;
                 }
 
-                let content = text.trim().to_string();
+                // Attachments show up in the text as U+FFFC placeholders
+                let content = text
+                    .unwrap_or_default()
+                    .replace('\u{FFFC}', "")
+                    .trim()
+                    .to_string();
+                if content.is_empty() && audio.is_none() {
+                    continue;
+                }
 
                 // Skip messages that match our own ack text (prevents echo loops
                 // when the recipient has auto-reply or AI assistants enabled)
@@ -179,13 +222,23 @@ impl IMessageChannel {
                         })
                         .unwrap_or_else(Utc::now);
 
-                pending_messages.push((rowid, handle, content, timestamp));
+                pending_messages.push((rowid, handle, content, audio, timestamp));
             }
         } // stmt and rows dropped here — no longer held across await
 
         // Now send messages asynchronously
         let message_count = pending_messages.len();
-        for (rowid, handle, content, timestamp) in pending_messages {
+        for (rowid, handle, content, audio, timestamp) in pending_messages {
+            let content = match (&audio, &self.transcriber) {
+                (Some(path), Some(transcriber)) => {
+                    match Self::transcribe(transcriber, path, &content).await {
+                        Some(transcribed) => transcribed,
+                        None if content.is_empty() => continue,
+                        None => content,
+                    }
+                }
+                _ => content,
+            };
             let msg_id = format!("imessage_{}", rowid);
 
             // Store message_id -> sender mapping for reply-to tracking (LRU auto-evicts oldest)
@@ -226,6 +279,21 @@ impl IMessageChannel {
         Ok(())
     }
 
+    /// Transcribe a voice note, logging (and returning `None`) on failure
+    async fn transcribe(
+        transcriber: &VoiceNoteTranscriber,
+        path: &Path,
+        text: &str,
+    ) -> Option<String> {
+        match transcriber.transcribe_path(path).await {
+            Ok(transcript) => Some(voice_notes::message_content(&transcript, text)),
+            Err(e) => {
+                warn!("Failed to transcribe iMessage voice note: {}", e);
+                None
+            }
+        }
+    }
+
     /// Sanitize a string for safe use in AppleScript.
     /// Escapes special characters and strips control characters to prevent injection.
     fn escape_applescript(s: &str) -> String {
@@ -322,6 +390,7 @@ impl MessageChannel for IMessageChannel {
         let allowed_contacts = self.allowed_contacts.clone();
         let message_senders = self.message_senders.clone();
         let rate_limiter = self.rate_limiter.clone();
+        let transcriber = self.transcriber.clone();
 
         // Create a new channel instance for the task
         let channel = IMessageChannel {
@@ -331,6 +400,7 @@ impl MessageChannel for IMessageChannel {
             last_rowid,
             message_senders,
             rate_limiter,
+            transcriber,
         };
 
         // Spawn polling task
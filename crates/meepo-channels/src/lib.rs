@@ -1,7 +1,7 @@
 //! Channel adapters and message bus for meepo
 //!
 //! This crate provides the message routing infrastructure and channel-specific
-//! adapters for Discord, iMessage, and Slack.
+//! adapters for Discord, iMessage, Slack and email.
 
 pub mod alexa;
 pub mod bus;
@@ -10,6 +10,7 @@ pub mod contacts;
 pub mod discord;
 #[cfg(target_os = "macos")]
 pub mod email;
+pub mod imap_email;
 #[cfg(target_os = "macos")]
 pub mod imessage;
 #[cfg(target_os = "macos")]
@@ -17,6 +18,7 @@ pub mod notes;
 pub mod rate_limit;
 #[cfg(target_os = "macos")]
 pub mod reminders;
+pub mod sim;
 pub mod slack;
 
 // Re-export main types
@@ -27,6 +29,7 @@ pub use contacts::ContactsChannel;
 pub use discord::DiscordChannel;
 #[cfg(target_os = "macos")]
 pub use email::EmailChannel;
+pub use imap_email::{ImapEmailChannel, ImapEmailConfig};
 #[cfg(target_os = "macos")]
 pub use imessage::IMessageChannel;
 #[cfg(target_os = "macos")]
@@ -34,4 +37,5 @@ pub use notes::NotesChannel;
 pub use rate_limit::RateLimiter;
 #[cfg(target_os = "macos")]
 pub use reminders::RemindersChannel;
+pub use sim::{Scenario, SimChannel, SimReport};
 pub use slack::SlackChannel;
@@ -0,0 +1,450 @@
+//! Simulated channel for end-to-end agent testing
+//!
+//! `SimChannel` plays a scripted conversation into the bus as if a user were
+//! typing on a real channel, collects everything the agent sends back, and
+//! checks each turn's replies against the scenario's expectations. It is
+//! what `meepo simulate` plugs into the full daemon wiring in place of the
+//! real channel adapters.
+
+use anyhow::{Result, anyhow};
+use async_trait::async_trait;
+use chrono::Utc;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::Duration;
+use tokio::sync::{mpsc, watch};
+use tokio::time::Instant;
+use tracing::{debug, info};
+
+use crate::bus::MessageChannel;
+use meepo_core::types::{ChannelType, IncomingMessage, MessageKind, OutgoingMessage};
+
+/// A scripted conversation, usually loaded from a YAML file
+#[derive(Debug, Clone, Deserialize)]
+pub struct Scenario {
+    #[serde(default)]
+    pub name: String,
+    /// Channel the simulated user talks on (e.g. "slack", "imessage")
+    #[serde(default = "default_channel")]
+    pub channel: String,
+    #[serde(default = "default_sender")]
+    pub sender: String,
+    /// Directory of recorded LLM fixtures to replay instead of calling providers
+    #[serde(default)]
+    pub fixtures: Option<String>,
+    /// Tools replaced by a stub returning the given output
+    #[serde(default)]
+    pub mock_tools: HashMap<String, String>,
+    pub turns: Vec<ScenarioTurn>,
+}
+
+fn default_channel() -> String {
+    "slack".to_string()
+}
+
+fn default_sender() -> String {
+    "sim-user".to_string()
+}
+
+/// One user message and what the agent's reply must look like
+#[derive(Debug, Clone, Deserialize)]
+pub struct ScenarioTurn {
+    pub user: String,
+    #[serde(default)]
+    pub expect: Expectation,
+    /// Replies to wait for before checking (0 = collect until the timeout)
+    #[serde(default = "default_replies")]
+    pub replies: usize,
+    #[serde(default = "default_turn_timeout")]
+    pub timeout_secs: u64,
+}
+
+fn default_replies() -> usize {
+    1
+}
+
+fn default_turn_timeout() -> u64 {
+    120
+}
+
+/// Assertions on the replies to a turn
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct Expectation {
+    /// Substrings that must appear (case-insensitive)
+    #[serde(default)]
+    pub contains: Vec<String>,
+    /// Substrings that must not appear (case-insensitive)
+    #[serde(default)]
+    pub not_contains: Vec<String>,
+    /// Regex the replies must match
+    #[serde(default)]
+    pub matches: Option<String>,
+}
+
+impl Expectation {
+    /// Check the replies, returning a description of each failed assertion
+    pub fn check(&self, replies: &[String]) -> Vec<String> {
+        let text = replies.join("\n");
+        let lower = text.to_lowercase();
+        let mut failures = Vec::new();
+        for needle in &self.contains {
+            if !lower.contains(&needle.to_lowercase()) {
+                failures.push(format!("expected reply to contain \"{}\"", needle));
+            }
+        }
+        for needle in &self.not_contains {
+            if lower.contains(&needle.to_lowercase()) {
+                failures.push(format!("expected reply not to contain \"{}\"", needle));
+            }
+        }
+        if let Some(pattern) = &self.matches {
+            match Regex::new(pattern) {
+                Ok(re) if re.is_match(&text) => {}
+                Ok(_) => failures.push(format!("expected reply to match /{}/", pattern)),
+                Err(e) => failures.push(format!("invalid regex /{}/: {}", pattern, e)),
+            }
+        }
+        failures
+    }
+}
+
+/// Outcome of a single turn
+#[derive(Debug, Clone, Serialize)]
+pub struct TurnResult {
+    pub input: String,
+    pub replies: Vec<String>,
+    pub failures: Vec<String>,
+}
+
+/// Outcome of a whole scenario
+#[derive(Debug, Clone, Serialize)]
+pub struct SimReport {
+    pub name: String,
+    pub turns: Vec<TurnResult>,
+    /// Messages that arrived after their turn was already checked
+    pub unsolicited: Vec<String>,
+}
+
+impl SimReport {
+    pub fn passed(&self) -> bool {
+        self.turns.iter().all(|t| t.failures.is_empty())
+    }
+
+    /// Number of failed turns
+    pub fn failed_turns(&self) -> usize {
+        self.turns.iter().filter(|t| !t.failures.is_empty()).count()
+    }
+}
+
+/// Waits for a running simulation to finish
+#[derive(Clone)]
+pub struct SimHandle {
+    report_rx: watch::Receiver<Option<SimReport>>,
+}
+
+impl SimHandle {
+    /// Wait until every turn has been played and checked
+    pub async fn finished(mut self) -> Result<SimReport> {
+        let report = self
+            .report_rx
+            .wait_for(|r| r.is_some())
+            .await
+            .map_err(|_| anyhow!("Simulation stopped before finishing"))?;
+        Ok(report.clone().expect("checked above"))
+    }
+}
+
+/// Channel adapter that plays a scenario and records the agent's replies
+pub struct SimChannel {
+    channel_type: ChannelType,
+    sender: String,
+    name: String,
+    turns: Mutex<Option<Vec<ScenarioTurn>>>,
+    outgoing_tx: mpsc::UnboundedSender<OutgoingMessage>,
+    outgoing_rx: Mutex<Option<mpsc::UnboundedReceiver<OutgoingMessage>>>,
+    report_tx: watch::Sender<Option<SimReport>>,
+}
+
+impl SimChannel {
+    pub fn new(scenario: &Scenario) -> Self {
+        let (outgoing_tx, outgoing_rx) = mpsc::unbounded_channel();
+        let (report_tx, _) = watch::channel(None);
+        Self {
+            channel_type: ChannelType::from_string(&scenario.channel),
+            sender: scenario.sender.clone(),
+            name: scenario.name.clone(),
+            turns: Mutex::new(Some(scenario.turns.clone())),
+            outgoing_tx,
+            outgoing_rx: Mutex::new(Some(outgoing_rx)),
+            report_tx,
+        }
+    }
+
+    /// Handle for awaiting the report once the channel has been started
+    pub fn handle(&self) -> SimHandle {
+        SimHandle {
+            report_rx: self.report_tx.subscribe(),
+        }
+    }
+}
+
+#[async_trait]
+impl MessageChannel for SimChannel {
+    async fn start(&self, tx: mpsc::Sender<IncomingMessage>) -> Result<()> {
+        let turns = self
+            .turns
+            .lock()
+            .unwrap()
+            .take()
+            .ok_or_else(|| anyhow!("Simulation already started"))?;
+        let outgoing_rx = self
+            .outgoing_rx
+            .lock()
+            .unwrap()
+            .take()
+            .expect("taken with turns");
+        let runner = Runner {
+            channel_type: self.channel_type.clone(),
+            sender: self.sender.clone(),
+            tx,
+            outgoing_rx,
+        };
+        let name = self.name.clone();
+        let report_tx = self.report_tx.clone();
+        tokio::spawn(async move {
+            let report = runner.run(name, turns).await;
+            let _ = report_tx.send(Some(report));
+        });
+        Ok(())
+    }
+
+    async fn send(&self, msg: OutgoingMessage) -> Result<()> {
+        debug!("Simulated channel received: {}", msg.content);
+        self.outgoing_tx
+            .send(msg)
+            .map_err(|_| anyhow!("Simulation has finished"))
+    }
+
+    fn channel_type(&self) -> ChannelType {
+        self.channel_type.clone()
+    }
+}
+
+struct Runner {
+    channel_type: ChannelType,
+    sender: String,
+    tx: mpsc::Sender<IncomingMessage>,
+    outgoing_rx: mpsc::UnboundedReceiver<OutgoingMessage>,
+}
+
+impl Runner {
+    async fn run(mut self, name: String, turns: Vec<ScenarioTurn>) -> SimReport {
+        let mut results = Vec::with_capacity(turns.len());
+        let mut unsolicited = Vec::new();
+
+        for (i, turn) in turns.into_iter().enumerate() {
+            while let Ok(late) = self.outgoing_rx.try_recv() {
+                unsolicited.push(late.content);
+            }
+
+            info!("Simulation turn {}: {}", i + 1, turn.user);
+            let incoming = IncomingMessage {
+                id: format!("sim-{}", i + 1),
+                sender: self.sender.clone(),
+                content: turn.user.clone(),
+                channel: self.channel_type.clone(),
+                timestamp: Utc::now(),
+            };
+            if self.tx.send(incoming).await.is_err() {
+                results.push(TurnResult {
+                    input: turn.user,
+                    replies: Vec::new(),
+                    failures: vec!["message bus closed".to_string()],
+                });
+                break;
+            }
+
+            let (replies, timed_out) = self.collect(&turn).await;
+            let mut failures = Vec::new();
+            if timed_out {
+                failures.push(format!(
+                    "timed out after {}s waiting for {} repl{} (got {})",
+                    turn.timeout_secs,
+                    turn.replies,
+                    if turn.replies == 1 { "y" } else { "ies" },
+                    replies.len()
+                ));
+            }
+            failures.extend(turn.expect.check(&replies));
+            results.push(TurnResult {
+                input: turn.user,
+                replies,
+                failures,
+            });
+        }
+
+        SimReport {
+            name,
+            turns: results,
+            unsolicited,
+        }
+    }
+
+    /// Gather replies for a turn. Acknowledgments are skipped; returns
+    /// whether the timeout hit before enough replies arrived.
+    async fn collect(&mut self, turn: &ScenarioTurn) -> (Vec<String>, bool) {
+        let deadline = Instant::now() + Duration::from_secs(turn.timeout_secs);
+        let mut replies = Vec::new();
+        loop {
+            if turn.replies > 0 && replies.len() >= turn.replies {
+                return (replies, false);
+            }
+            match tokio::time::timeout_at(deadline, self.outgoing_rx.recv()).await {
+                Ok(Some(msg)) if msg.kind == MessageKind::Acknowledgment => {}
+                Ok(Some(msg)) => replies.push(msg.content),
+                Ok(None) => return (replies, turn.replies > 0),
+                Err(_) => return (replies, turn.replies > 0),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+
+    fn scenario(yaml_turns: Vec<ScenarioTurn>) -> Scenario {
+        Scenario {
+            name: "echo".to_string(),
+            channel: "slack".to_string(),
+            sender: "alice".to_string(),
+            fixtures: None,
+            mock_tools: HashMap::new(),
+            turns: yaml_turns,
+        }
+    }
+
+    fn turn(user: &str, contains: &[&str]) -> ScenarioTurn {
+        ScenarioTurn {
+            user: user.to_string(),
+            expect: Expectation {
+                contains: contains.iter().map(|s| s.to_string()).collect(),
+                ..Default::default()
+            },
+            replies: 1,
+            timeout_secs: 2,
+        }
+    }
+
+    /// Stand-in for the daemon: acknowledge, then echo each message back
+    fn spawn_echo_agent(channel: Arc<SimChannel>, mut rx: mpsc::Receiver<IncomingMessage>) {
+        tokio::spawn(async move {
+            while let Some(msg) = rx.recv().await {
+                let reply = |content: String, kind| OutgoingMessage {
+                    content,
+                    channel: msg.channel.clone(),
+                    reply_to: Some(msg.id.clone()),
+                    kind,
+                    interactive: None,
+                };
+                let _ = channel
+                    .send(reply("...".to_string(), MessageKind::Acknowledgment))
+                    .await;
+                let _ = channel
+                    .send(reply(
+                        format!("You said: {}", msg.content),
+                        MessageKind::Response,
+                    ))
+                    .await;
+            }
+        });
+    }
+
+    #[test]
+    fn test_expectation_check() {
+        let expect = Expectation {
+            contains: vec!["Standup".to_string()],
+            not_contains: vec!["error".to_string()],
+            matches: Some(r"\d{1,2}:\d{2}".to_string()),
+        };
+        assert!(
+            expect
+                .check(&["Your standup is at 9:30".to_string()])
+                .is_empty()
+        );
+        let failures = expect.check(&["An error occurred".to_string()]);
+        assert_eq!(failures.len(), 3);
+
+        let bad = Expectation {
+            matches: Some("(".to_string()),
+            ..Default::default()
+        };
+        assert!(bad.check(&[String::new()])[0].contains("invalid regex"));
+    }
+
+    #[tokio::test]
+    async fn test_sim_channel_plays_scenario() {
+        let channel = Arc::new(SimChannel::new(&scenario(vec![
+            turn("hello", &["hello"]),
+            turn("what time is it", &["noon"]),
+        ])));
+        assert_eq!(channel.channel_type(), ChannelType::Slack);
+        let handle = channel.handle();
+
+        let (tx, rx) = mpsc::channel(8);
+        channel.start(tx.clone()).await.unwrap();
+        assert!(channel.start(tx).await.is_err());
+        spawn_echo_agent(channel.clone(), rx);
+
+        let report = handle.finished().await.unwrap();
+        assert_eq!(report.turns.len(), 2);
+        assert_eq!(report.turns[0].replies, vec!["You said: hello"]);
+        assert!(report.turns[0].failures.is_empty());
+        assert_eq!(report.turns[1].failures.len(), 1);
+        assert!(!report.passed());
+        assert_eq!(report.failed_turns(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_sim_channel_times_out_without_reply() {
+        let mut silent = turn("anyone there?", &[]);
+        silent.timeout_secs = 0;
+        let channel = SimChannel::new(&scenario(vec![silent]));
+        let handle = channel.handle();
+        let (tx, _rx) = mpsc::channel(8);
+        channel.start(tx).await.unwrap();
+
+        let report = handle.finished().await.unwrap();
+        assert!(report.turns[0].failures[0].contains("timed out"));
+    }
+
+    #[test]
+    fn test_scenario_from_yaml() {
+        let yaml = r#"
+name: calendar check
+channel: imessage
+mock_tools:
+  send_email: "Email sent."
+turns:
+  - user: "What's on today?"
+    expect:
+      contains: [standup]
+  - user: "Thanks"
+    replies: 0
+    timeout_secs: 5
+"#;
+        let scenario: Scenario = serde_yml::from_str(yaml).unwrap();
+        assert_eq!(scenario.sender, "sim-user");
+        assert_eq!(scenario.mock_tools["send_email"], "Email sent.");
+        assert_eq!(scenario.turns[0].replies, 1);
+        assert_eq!(scenario.turns[0].timeout_secs, 120);
+        assert_eq!(scenario.turns[1].replies, 0);
+        assert_eq!(
+            SimChannel::new(&scenario).channel_type(),
+            ChannelType::IMessage
+        );
+    }
+}
@@ -1,4 +1,8 @@
 //! Slack channel adapter using Web API polling
+//!
+//! With an app-level token (`xapp-…`), the adapter also opens a Socket Mode
+//! connection so answer buttons (Block Kit) can be pressed; presses arrive
+//! over the socket and are forwarded as button-press messages.
 
 use crate::bus::MessageChannel;
 use crate::rate_limit::RateLimiter;
@@ -6,7 +10,10 @@ use anyhow::{Result, anyhow};
 use async_trait::async_trait;
 use chrono::Utc;
 use dashmap::DashMap;
-use meepo_core::types::{ChannelType, IncomingMessage, MessageKind, OutgoingMessage};
+use futures_util::{SinkExt, StreamExt};
+use meepo_core::types::{
+    ButtonStyle, ChannelType, IncomingMessage, Interactive, MessageKind, OutgoingMessage,
+};
 use std::collections::HashMap;
 use std::sync::Arc;
 use std::time::Duration;
@@ -15,6 +22,8 @@ use tokio::sync::mpsc;
 use tracing::{debug, error, info, warn};
 
 const MAX_MESSAGE_SIZE: usize = 10_240;
+/// Slack's limit on a section block's text
+const SECTION_MAX_LENGTH: usize = 3000;
 
 /// Slack channel adapter using Web API polling
 pub struct SlackChannel {
@@ -29,6 +38,8 @@ pub struct SlackChannel {
     /// Maps original message_id -> (channel_id, message_ts) for pending ack messages
     /// Used to update "Thinking..." placeholders with the real response
     pending_acks: Arc<DashMap<String, (String, String)>>,
+    /// App-level token for Socket Mode; answer buttons are only shown with one
+    app_token: Option<String>,
 }
 
 impl SlackChannel {
@@ -46,9 +57,16 @@ impl SlackChannel {
             allowed_users,
             channel_map: Arc::new(DashMap::new()),
             pending_acks: Arc::new(DashMap::new()),
+            app_token: None,
         }
     }
 
+    /// Enable answer buttons over Socket Mode with an app-level token (xapp-)
+    pub fn with_app_token(mut self, app_token: String) -> Self {
+        self.app_token = Some(app_token).filter(|t| !t.is_empty());
+        self
+    }
+
     /// Call a Slack Web API method
     async fn api_call(
         client: &reqwest::Client,
@@ -87,12 +105,16 @@ impl SlackChannel {
         token: &str,
         channel: &str,
         text: &str,
+        blocks: Option<&serde_json::Value>,
     ) -> Result<String> {
         let url = "https://slack.com/api/chat.postMessage";
-        let body = serde_json::json!({
+        let mut body = serde_json::json!({
             "channel": channel,
             "text": text,
         });
+        if let Some(blocks) = blocks {
+            body["blocks"] = blocks.clone();
+        }
 
         let response = client
             .post(url)
@@ -126,13 +148,17 @@ impl SlackChannel {
         channel: &str,
         ts: &str,
         text: &str,
+        blocks: Option<&serde_json::Value>,
     ) -> Result<()> {
         let url = "https://slack.com/api/chat.update";
-        let body = serde_json::json!({
+        let mut body = serde_json::json!({
             "channel": channel,
             "ts": ts,
             "text": text,
         });
+        if let Some(blocks) = blocks {
+            body["blocks"] = blocks.clone();
+        }
 
         let response = client
             .post(url)
@@ -153,6 +179,184 @@ impl SlackChannel {
 
         Ok(())
     }
+
+    /// Open a Socket Mode connection URL with the app-level token
+    async fn open_socket(client: &reqwest::Client, app_token: &str) -> Result<String> {
+        let result: serde_json::Value = client
+            .post("https://slack.com/api/apps.connections.open")
+            .bearer_auth(app_token)
+            .send()
+            .await?
+            .json()
+            .await?;
+        if result.get("ok").and_then(|v| v.as_bool()) != Some(true) {
+            let err = result
+                .get("error")
+                .and_then(|v| v.as_str())
+                .unwrap_or("unknown");
+            return Err(anyhow!("Slack apps.connections.open error: {}", err));
+        }
+        result
+            .get("url")
+            .and_then(|v| v.as_str())
+            .map(String::from)
+            .ok_or_else(|| anyhow!("Slack apps.connections.open returned no URL"))
+    }
+
+    /// Receive button presses over Socket Mode until the connection drops
+    async fn run_socket(
+        client: &reqwest::Client,
+        app_token: &str,
+        bot_token: &str,
+        allowed_users: &[String],
+        tx: &mpsc::Sender<IncomingMessage>,
+    ) -> Result<()> {
+        let url = Self::open_socket(client, app_token).await?;
+        let (mut ws, _) = tokio_tungstenite::connect_async(url.as_str()).await?;
+        info!("Slack Socket Mode connected");
+
+        while let Some(frame) = ws.next().await {
+            let text = match frame? {
+                tokio_tungstenite::tungstenite::Message::Text(text) => text,
+                tokio_tungstenite::tungstenite::Message::Close(_) => break,
+                _ => continue,
+            };
+            let Ok(envelope) = serde_json::from_str::<serde_json::Value>(&text) else {
+                continue;
+            };
+            // Every envelope must be acknowledged within 3 seconds
+            if let Some(id) = envelope.get("envelope_id").and_then(|v| v.as_str()) {
+                let ack = serde_json::json!({ "envelope_id": id }).to_string();
+                ws.send(tokio_tungstenite::tungstenite::Message::Text(ack.into()))
+                    .await?;
+            }
+            match envelope.get("type").and_then(|v| v.as_str()) {
+                Some("disconnect") => break,
+                Some("interactive") => {}
+                _ => continue,
+            }
+            let Some(press) = envelope.get("payload").and_then(parse_block_action) else {
+                continue;
+            };
+            if !allowed_users.is_empty() && !allowed_users.contains(&press.user) {
+                warn!(
+                    "Ignoring Slack button press from unauthorized user: {}",
+                    press.user
+                );
+                continue;
+            }
+
+            // Replace the buttons with the chosen answer
+            let answered = format!("{}\n\n*→ {}*", press.text, press.value);
+            let blocks = serde_json::json!([section_block(&answered)]);
+            if let Err(e) = Self::update_message(
+                client,
+                bot_token,
+                &press.channel,
+                &press.ts,
+                &answered,
+                Some(&blocks),
+            )
+            .await
+            {
+                warn!("Failed to update Slack message after button press: {}", e);
+            }
+
+            let incoming = IncomingMessage {
+                id: format!("slack_{}_{}", press.channel, press.ts),
+                sender: press.user.clone(),
+                content: Interactive::press_content(&press.prompt_id, &press.value),
+                channel: ChannelType::Slack,
+                timestamp: Utc::now(),
+            };
+            debug!("Forwarding Slack button press from {}", press.user);
+            if let Err(e) = tx.send(incoming).await {
+                error!("Failed to send Slack button press to bus: {}", e);
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A press on one of Meepo's answer buttons
+#[derive(Debug, PartialEq)]
+struct BlockAction {
+    user: String,
+    channel: String,
+    /// Timestamp of the message holding the buttons
+    ts: String,
+    /// Text of that message
+    text: String,
+    prompt_id: String,
+    value: String,
+}
+
+/// Parse a `block_actions` interaction payload
+fn parse_block_action(payload: &serde_json::Value) -> Option<BlockAction> {
+    if payload.get("type").and_then(|v| v.as_str()) != Some("block_actions") {
+        return None;
+    }
+    let str_at = |pointer: &str| {
+        payload
+            .pointer(pointer)
+            .and_then(|v| v.as_str())
+            .map(String::from)
+    };
+    let action = payload.pointer("/actions/0")?;
+    action
+        .get("action_id")
+        .and_then(|v| v.as_str())
+        .filter(|id| id.starts_with("meepo_answer_"))?;
+    Some(BlockAction {
+        user: str_at("/user/id")?,
+        channel: str_at("/channel/id")?,
+        ts: str_at("/message/ts")?,
+        text: str_at("/message/text").unwrap_or_default(),
+        prompt_id: action.get("block_id")?.as_str()?.to_string(),
+        value: action.get("value")?.as_str()?.to_string(),
+    })
+}
+
+fn section_block(text: &str) -> serde_json::Value {
+    let text = match text.char_indices().nth(SECTION_MAX_LENGTH) {
+        Some((end, _)) => &text[..end],
+        None => text,
+    };
+    serde_json::json!({
+        "type": "section",
+        "text": { "type": "mrkdwn", "text": text },
+    })
+}
+
+/// Block Kit blocks for a message with answer buttons
+fn answer_blocks(text: &str, interactive: &Interactive) -> serde_json::Value {
+    let buttons: Vec<serde_json::Value> = interactive
+        .buttons
+        .iter()
+        .enumerate()
+        .map(|(i, b)| {
+            let mut button = serde_json::json!({
+                "type": "button",
+                "action_id": format!("meepo_answer_{}", i),
+                "text": { "type": "plain_text", "text": b.label },
+                "value": b.value,
+            });
+            match b.style {
+                ButtonStyle::Primary => button["style"] = "primary".into(),
+                ButtonStyle::Danger => button["style"] = "danger".into(),
+                ButtonStyle::Default => {}
+            }
+            button
+        })
+        .collect();
+    serde_json::json!([
+        section_block(text),
+        {
+            "type": "actions",
+            "block_id": interactive.prompt_id,
+            "elements": buttons,
+        },
+    ])
 }
 
 #[async_trait]
@@ -212,6 +416,7 @@ impl MessageChannel for SlackChannel {
         let bot_uid = bot_user_id;
         let allowed_users = self.allowed_users.clone();
         let rate_limiter = RateLimiter::new(10, Duration::from_secs(60));
+        let socket_tx = tx.clone();
 
         // Spawn polling task (safe: all initialization is complete)
         tokio::spawn(async move {
@@ -388,6 +593,34 @@ impl MessageChannel for SlackChannel {
             }
         });
 
+        // Button presses arrive over Socket Mode
+        if let Some(app_token) = self.app_token.clone() {
+            let tx = socket_tx;
+            let bot_token = self.bot_token.clone();
+            let allowed_users = self.allowed_users.clone();
+            tokio::spawn(async move {
+                let mut backoff = Duration::from_secs(1);
+                loop {
+                    match Self::run_socket(&client, &app_token, &bot_token, &allowed_users, &tx)
+                        .await
+                    {
+                        Ok(()) => {
+                            debug!("Slack Socket Mode disconnected, reconnecting");
+                            backoff = Duration::from_secs(1);
+                        }
+                        Err(e) => {
+                            warn!("Slack Socket Mode error: {} (retrying in {:?})", e, backoff);
+                            tokio::time::sleep(backoff).await;
+                            backoff = (backoff * 2).min(Duration::from_secs(60));
+                        }
+                    }
+                    if tx.is_closed() {
+                        break;
+                    }
+                }
+            });
+        }
+
         info!("Slack channel adapter started");
         Ok(())
     }
@@ -419,7 +652,9 @@ impl MessageChannel for SlackChannel {
         // Handle acknowledgment: post "Thinking..." placeholder
         if msg.kind == MessageKind::Acknowledgment {
             debug!("Sending Slack acknowledgment to channel {}", channel_id);
-            match Self::post_message(&client, &self.bot_token, &channel_id, "Thinking...").await {
+            match Self::post_message(&client, &self.bot_token, &channel_id, "Thinking...", None)
+                .await
+            {
                 Ok(ts) => {
                     if let Some(reply_to) = &msg.reply_to {
                         self.pending_acks.insert(reply_to.clone(), (channel_id, ts));
@@ -430,6 +665,13 @@ impl MessageChannel for SlackChannel {
             return Ok(());
         }
 
+        let blocks = match &msg.interactive {
+            Some(interactive) if self.app_token.is_some() => {
+                Some(answer_blocks(&msg.content, interactive))
+            }
+            _ => None,
+        };
+
         // Normal response: check if there's a pending ack to update
         if let Some(reply_to) = &msg.reply_to
             && let Some((_, (ack_channel, ack_ts))) = self.pending_acks.remove(reply_to)
@@ -441,6 +683,7 @@ impl MessageChannel for SlackChannel {
                 &ack_channel,
                 &ack_ts,
                 &msg.content,
+                blocks.as_ref(),
             )
             .await
             {
@@ -455,7 +698,14 @@ impl MessageChannel for SlackChannel {
             }
         }
 
-        Self::post_message(&client, &self.bot_token, &channel_id, &msg.content).await?;
+        Self::post_message(
+            &client,
+            &self.bot_token,
+            &channel_id,
+            &msg.content,
+            blocks.as_ref(),
+        )
+        .await?;
         info!("Slack message sent successfully");
         Ok(())
     }
@@ -487,6 +737,42 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[test]
+    fn test_answer_blocks_and_block_action() {
+        let interactive = Interactive {
+            prompt_id: "q-1a2b".to_string(),
+            buttons: vec![
+                meepo_core::types::Button::for_choice("Approve"),
+                meepo_core::types::Button::for_choice("Later"),
+            ],
+        };
+        let blocks = answer_blocks("Delete 3 files?", &interactive);
+        assert_eq!(blocks[1]["block_id"], "q-1a2b");
+        assert_eq!(blocks[1]["elements"][0]["style"], "primary");
+        assert!(blocks[1]["elements"][1].get("style").is_none());
+
+        let payload = serde_json::json!({
+            "type": "block_actions",
+            "user": { "id": "U123" },
+            "channel": { "id": "D456" },
+            "message": { "ts": "1700000000.000100", "text": "Delete 3 files?" },
+            "actions": [{
+                "action_id": "meepo_answer_0",
+                "block_id": "q-1a2b",
+                "value": "Approve",
+            }],
+        });
+        let press = parse_block_action(&payload).unwrap();
+        assert_eq!(press.user, "U123");
+        assert_eq!(press.channel, "D456");
+        assert_eq!(press.prompt_id, "q-1a2b");
+        assert_eq!(press.value, "Approve");
+
+        let mut other = payload.clone();
+        other["actions"][0]["action_id"] = "someone_elses_button".into();
+        assert!(parse_block_action(&other).is_none());
+    }
+
     #[tokio::test]
     async fn test_slack_send_no_channels() {
         let channel =
@@ -496,6 +782,7 @@ mod tests {
             channel: ChannelType::Slack,
             reply_to: None,
             kind: MessageKind::Response,
+            interactive: None,
         };
         let result = channel.send(msg).await;
         assert!(result.is_err()); // No channels mapped yet
@@ -0,0 +1,630 @@
+//! `meepo bench` — profile knowledge and agent performance
+//!
+//! Builds a synthetic knowledge base in a throwaway directory, then times
+//! ingestion, keyword search, hybrid (keyword + vector) retrieval and full
+//! agent turns against a scripted LLM that answers instantly. Each run is
+//! appended to `~/.meepo/bench-history.jsonl` and compared with the last run
+//! of the same size, so regressions in meepo-knowledge show up as numbers.
+
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use meepo_core::providers::types::{ChatBlock, ChatResponseBlock, ChatUsage, StopReason};
+use meepo_core::providers::{ChatMessage, ChatMessageContent, ChatResponse, LlmProvider};
+use meepo_knowledge::{
+    EmbeddingProvider, HashEmbeddingProvider, KnowledgeGraph, NewEntity, NewRelationship,
+    VectorIndex, hybrid_search_rrf,
+};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// A metric this much worse than the previous run is flagged
+const REGRESSION_THRESHOLD: f64 = 0.2;
+
+/// Entities written per transaction while populating
+const BATCH_SIZE: usize = 500;
+
+/// Dimensions of the hashed embeddings used for the vector side of hybrid search
+const EMBEDDING_DIMS: usize = 256;
+
+const FIRST_NAMES: &[&str] = &[
+    "Alice", "Bob", "Carmen", "Deepak", "Elena", "Farid", "Grace", "Hiro", "Ines", "Jonas", "Kemi",
+    "Luca", "Maya", "Nikolai", "Olga", "Priya", "Quinn", "Rosa", "Sven", "Tara",
+];
+const LAST_NAMES: &[&str] = &[
+    "Chen", "Okafor", "Silva", "Novak", "Haddad", "Kim", "Larsen", "Moreau", "Patel", "Reyes",
+    "Schmidt", "Tanaka", "Weber", "Yilmaz", "Zhou", "Brennan",
+];
+const COMPANIES: &[&str] = &[
+    "Acme", "Globex", "Initech", "Umbrella", "Hooli", "Vandelay", "Stark", "Wayne", "Tyrell",
+    "Soylent",
+];
+const TOPICS: &[&str] = &[
+    "budget",
+    "roadmap",
+    "kubernetes",
+    "migration",
+    "hiring",
+    "launch",
+    "pricing",
+    "security",
+    "onboarding",
+    "analytics",
+    "latency",
+    "invoice",
+    "contract",
+    "design",
+    "research",
+    "database",
+    "marketing",
+    "compliance",
+    "travel",
+    "offsite",
+    "quarterly",
+    "forecast",
+    "vendor",
+    "backlog",
+    "incident",
+    "retrospective",
+    "prototype",
+    "dashboard",
+    "partnership",
+    "renewal",
+];
+const RELATIONS: &[&str] = &["works_at", "knows", "related_to", "mentions", "part_of"];
+
+/// Sizes of a benchmark run
+#[derive(Debug, Clone, Copy)]
+pub struct BenchOptions {
+    pub entities: usize,
+    pub queries: usize,
+}
+
+/// One measured number
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Measurement {
+    pub name: String,
+    pub value: f64,
+    pub unit: String,
+    /// Throughputs are better high, latencies low
+    pub higher_is_better: bool,
+}
+
+impl Measurement {
+    fn latency(name: &str, value: Duration) -> Self {
+        Self {
+            name: name.to_string(),
+            value: value.as_secs_f64() * 1000.0,
+            unit: "ms".to_string(),
+            higher_is_better: false,
+        }
+    }
+
+    fn throughput(name: &str, count: usize, elapsed: Duration, unit: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            value: count as f64 / elapsed.as_secs_f64().max(1e-9),
+            unit: unit.to_string(),
+            higher_is_better: true,
+        }
+    }
+
+    /// Relative change from `previous`, positive when this one is worse
+    fn regression_from(&self, previous: &Measurement) -> Option<f64> {
+        if previous.value <= 0.0 {
+            return None;
+        }
+        let change = (self.value - previous.value) / previous.value;
+        Some(if self.higher_is_better {
+            -change
+        } else {
+            change
+        })
+    }
+}
+
+/// A finished benchmark run, as stored in the history file
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BenchRun {
+    pub timestamp: DateTime<Utc>,
+    pub version: String,
+    pub entities: usize,
+    pub queries: usize,
+    pub measurements: Vec<Measurement>,
+}
+
+/// Small deterministic generator so every run sees the same data
+struct Lcg(u64);
+
+impl Lcg {
+    fn next(&mut self) -> u64 {
+        self.0 = self
+            .0
+            .wrapping_mul(6364136223846793005)
+            .wrapping_add(1442695040888963407);
+        self.0 >> 33
+    }
+
+    fn below(&mut self, n: usize) -> usize {
+        (self.next() % n.max(1) as u64) as usize
+    }
+
+    fn pick<'a>(&mut self, items: &[&'a str]) -> &'a str {
+        items[self.below(items.len())]
+    }
+
+    fn sentence(&mut self, words: usize) -> String {
+        (0..words)
+            .map(|_| self.pick(TOPICS))
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+}
+
+/// The `i`th synthetic entity
+fn synthetic_entity(rng: &mut Lcg, i: usize) -> NewEntity {
+    let (name, entity_type) = match i % 4 {
+        0 | 1 => (
+            format!("{} {} {}", rng.pick(FIRST_NAMES), rng.pick(LAST_NAMES), i),
+            "person",
+        ),
+        2 => (
+            format!("{} {} {}", rng.pick(COMPANIES), rng.pick(TOPICS), i),
+            "project",
+        ),
+        _ => (format!("{} {}", rng.pick(TOPICS), i), "concept"),
+    };
+    let words = 6 + rng.below(10);
+    NewEntity {
+        name,
+        entity_type: entity_type.to_string(),
+        metadata: Some(serde_json::json!({
+            "notes": rng.sentence(words),
+            "company": rng.pick(COMPANIES),
+            "source": "bench",
+        })),
+    }
+}
+
+/// A synthetic markdown document of a few thousand characters
+fn synthetic_document(rng: &mut Lcg, i: usize) -> String {
+    let mut doc = format!(
+        "# {} {} notes {}\n\n",
+        rng.pick(COMPANIES),
+        rng.pick(TOPICS),
+        i
+    );
+    for section in 0..6 {
+        doc.push_str(&format!("## {} {}\n\n", rng.pick(TOPICS), section));
+        for _ in 0..3 {
+            let words = 20 + rng.below(30);
+            doc.push_str(&rng.sentence(words));
+            doc.push_str(".\n\n");
+        }
+    }
+    doc
+}
+
+/// Scripted provider: asks for `smart_recall` once, then answers
+struct BenchProvider;
+
+#[async_trait]
+impl LlmProvider for BenchProvider {
+    fn provider_name(&self) -> &str {
+        "bench"
+    }
+
+    fn model(&self) -> &str {
+        "bench-1"
+    }
+
+    async fn chat(
+        &self,
+        messages: &[ChatMessage],
+        _tools: &[meepo_core::api::ToolDefinition],
+        _system: &str,
+    ) -> Result<ChatResponse> {
+        let after_tool = matches!(
+            messages.last().map(|m| &m.content),
+            Some(ChatMessageContent::Blocks(blocks))
+                if blocks.iter().any(|b| matches!(b, ChatBlock::ToolResult { .. }))
+        );
+        let (blocks, stop_reason) = if after_tool {
+            (
+                vec![ChatResponseBlock::Text {
+                    text: "Here is what I found.".to_string(),
+                }],
+                StopReason::EndTurn,
+            )
+        } else {
+            let query = match messages.last().map(|m| &m.content) {
+                Some(ChatMessageContent::Text(text)) => text.clone(),
+                _ => String::new(),
+            };
+            (
+                vec![ChatResponseBlock::ToolCall {
+                    id: "bench-call".to_string(),
+                    name: "smart_recall".to_string(),
+                    input: serde_json::json!({ "query": query }),
+                }],
+                StopReason::ToolUse,
+            )
+        };
+        Ok(ChatResponse {
+            blocks,
+            stop_reason,
+            usage: ChatUsage {
+                input_tokens: 0,
+                output_tokens: 0,
+            },
+        })
+    }
+}
+
+/// The value at fraction `p` (0.0–1.0) of sorted `samples`
+fn percentile(samples: &[Duration], p: f64) -> Duration {
+    if samples.is_empty() {
+        return Duration::ZERO;
+    }
+    let mut sorted = samples.to_vec();
+    sorted.sort();
+    let rank = ((sorted.len() - 1) as f64 * p).round() as usize;
+    sorted[rank.min(sorted.len() - 1)]
+}
+
+fn latencies(name: &str, samples: &[Duration]) -> [Measurement; 2] {
+    [
+        Measurement::latency(&format!("{} p50", name), percentile(samples, 0.5)),
+        Measurement::latency(&format!("{} p95", name), percentile(samples, 0.95)),
+    ]
+}
+
+/// Run the benchmark in `dir`, which should be empty
+pub async fn run(options: BenchOptions, dir: &Path) -> Result<BenchRun> {
+    std::fs::create_dir_all(dir)?;
+    let graph = Arc::new(KnowledgeGraph::new(
+        dir.join("knowledge.db"),
+        dir.join("tantivy_index"),
+    )?);
+    let db = graph.db();
+    let mut rng = Lcg(42);
+    let mut measurements = Vec::new();
+
+    // Entity ingestion, in transactions of BATCH_SIZE
+    println!("  Populating {} entities...", options.entities);
+    let mut ids: Vec<String> = Vec::with_capacity(options.entities);
+    let mut names = Vec::with_capacity(options.entities);
+    let started = Instant::now();
+    let mut i = 0;
+    while i < options.entities {
+        let batch: Vec<NewEntity> = (i..(i + BATCH_SIZE).min(options.entities))
+            .map(|n| synthetic_entity(&mut rng, n))
+            .collect();
+        i += batch.len();
+        names.extend(batch.iter().map(|e| e.name.clone()));
+        ids.extend(graph.add_entities(batch).await?);
+    }
+    let relationships: Vec<NewRelationship> = (1..ids.len())
+        .map(|n| NewRelationship {
+            source: ids[n].clone(),
+            target: ids[rng.below(n)].clone(),
+            relation_type: rng.pick(RELATIONS).to_string(),
+            metadata: None,
+        })
+        .collect();
+    for batch in relationships.chunks(BATCH_SIZE) {
+        graph.add_relationships(batch.to_vec()).await?;
+    }
+    graph.flush()?;
+    measurements.push(Measurement::throughput(
+        "ingest entities",
+        options.entities,
+        started.elapsed(),
+        "entities/s",
+    ));
+
+    // Document ingestion through the same path as ingest_document
+    let documents = (options.entities / 100).max(5);
+    let chunking = meepo_knowledge::ChunkingConfig::default();
+    let started = Instant::now();
+    for n in 0..documents {
+        let content = synthetic_document(&mut rng, n);
+        meepo_core::tools::rag::index_document(
+            &graph,
+            &chunking,
+            &format!("Bench document {}", n),
+            &format!("/bench/doc-{}.md", n),
+            "text/markdown",
+            &content,
+            &[],
+        )
+        .await?;
+    }
+    graph.flush()?;
+    measurements.push(Measurement::throughput(
+        "ingest documents",
+        documents,
+        started.elapsed(),
+        "docs/s",
+    ));
+
+    // Half the queries name an entity, half are topic words
+    let queries: Vec<String> = (0..options.queries.max(1))
+        .map(|n| {
+            if n % 2 == 0 && !names.is_empty() {
+                names[rng.below(names.len())].clone()
+            } else {
+                format!("{} {}", rng.pick(TOPICS), rng.pick(TOPICS))
+            }
+        })
+        .collect();
+
+    println!("  Timing keyword search...");
+    let mut samples = Vec::with_capacity(queries.len());
+    for query in &queries {
+        let started = Instant::now();
+        graph.search(query, 10)?;
+        samples.push(started.elapsed());
+    }
+    measurements.extend(latencies("search", &samples));
+
+    println!("  Timing hybrid retrieval...");
+    let embedder = HashEmbeddingProvider::new(EMBEDDING_DIMS);
+    let vectors = VectorIndex::new(EMBEDDING_DIMS);
+    for entity in graph.get_all_entities().await? {
+        let text = format!(
+            "{} {}",
+            entity.name,
+            entity.metadata.map(|m| m.to_string()).unwrap_or_default()
+        );
+        vectors.insert(&entity.id, embedder.embed(&text).await?)?;
+    }
+    let mut samples = Vec::with_capacity(queries.len());
+    for query in &queries {
+        let started = Instant::now();
+        let keyword: Vec<String> = graph.search(query, 20)?.into_iter().map(|r| r.id).collect();
+        let vector = vectors.search(&embedder.embed(query).await?, 20);
+        hybrid_search_rrf(&keyword, &vector, 60.0, 10);
+        samples.push(started.elapsed());
+    }
+    measurements.extend(latencies("hybrid", &samples));
+
+    println!("  Timing agent turns...");
+    let mut registry = meepo_core::tools::ToolRegistry::new();
+    registry.register(Arc::new(meepo_core::tools::rag::SmartRecallTool::new(
+        graph.clone(),
+        db.clone(),
+    )));
+    let agent = meepo_core::agent::Agent::new(
+        meepo_core::api::ApiClient::from_router(meepo_core::providers::ModelRouter::single(
+            Box::new(BenchProvider),
+        )),
+        Arc::new(registry),
+        "You are a benchmark agent.".to_string(),
+        String::new(),
+        db.clone(),
+    )
+    .with_intent_config(meepo_core::intent::IntentConfig {
+        enabled: false,
+        min_length: 0,
+    });
+    let mut samples = Vec::with_capacity(queries.len());
+    for (n, query) in queries.iter().enumerate() {
+        let msg = meepo_core::types::IncomingMessage {
+            id: format!("bench-{}", n),
+            sender: "bench".to_string(),
+            content: query.clone(),
+            channel: meepo_core::types::ChannelType::Internal,
+            timestamp: Utc::now(),
+        };
+        let started = Instant::now();
+        agent
+            .handle_message(msg)
+            .await
+            .context("Agent turn failed")?;
+        samples.push(started.elapsed());
+    }
+    measurements.extend(latencies("agent turn", &samples));
+
+    Ok(BenchRun {
+        timestamp: Utc::now(),
+        version: env!("CARGO_PKG_VERSION").to_string(),
+        entities: options.entities,
+        queries: options.queries,
+        measurements,
+    })
+}
+
+/// Where runs are recorded
+pub fn history_path() -> PathBuf {
+    crate::config::config_dir().join("bench-history.jsonl")
+}
+
+/// All recorded runs, oldest first; unreadable lines are skipped
+pub fn load_history(path: &Path) -> Vec<BenchRun> {
+    std::fs::read_to_string(path)
+        .map(|raw| {
+            raw.lines()
+                .filter_map(|line| serde_json::from_str(line).ok())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Append a run to the history file
+pub fn save_run(path: &Path, run: &BenchRun) -> Result<()> {
+    use std::io::Write;
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let mut file = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .with_context(|| format!("Failed to open {}", path.display()))?;
+    writeln!(file, "{}", serde_json::to_string(run)?)?;
+    Ok(())
+}
+
+/// The latest run with the same sizes, the only one worth comparing against
+pub fn previous_run<'a>(history: &'a [BenchRun], run: &BenchRun) -> Option<&'a BenchRun> {
+    history
+        .iter()
+        .rev()
+        .find(|r| r.entities == run.entities && r.queries == run.queries)
+}
+
+/// Names of measurements more than `REGRESSION_THRESHOLD` worse than before
+pub fn regressions(run: &BenchRun, previous: &BenchRun) -> Vec<String> {
+    run.measurements
+        .iter()
+        .filter_map(|m| {
+            let before = previous.measurements.iter().find(|p| p.name == m.name)?;
+            (m.regression_from(before)? > REGRESSION_THRESHOLD).then(|| m.name.clone())
+        })
+        .collect()
+}
+
+/// Print the run, side by side with `previous` if there is one
+pub fn print_report(run: &BenchRun, previous: Option<&BenchRun>) {
+    println!();
+    println!(
+        "Benchmark: {} entities, {} queries (meepo {})",
+        run.entities, run.queries, run.version
+    );
+    match previous {
+        Some(previous) => println!(
+            "Compared with {} (meepo {})\n",
+            previous.timestamp.format("%Y-%m-%d %H:%M"),
+            previous.version
+        ),
+        None => println!("No previous run of this size to compare with\n"),
+    }
+    println!(
+        "  {:<18} {:>20} {:>20} {:>9}",
+        "Metric", "This run", "Previous", "Change"
+    );
+    let flagged = previous.map(|p| regressions(run, p)).unwrap_or_default();
+    for m in &run.measurements {
+        let before = previous.and_then(|p| p.measurements.iter().find(|b| b.name == m.name));
+        let (before_text, change_text) = match before {
+            Some(before) if before.value > 0.0 => (
+                format!("{:.2} {}", before.value, before.unit),
+                format!("{:+.1}%", (m.value - before.value) / before.value * 100.0),
+            ),
+            _ => ("-".to_string(), String::new()),
+        };
+        let flag = if flagged.contains(&m.name) {
+            "  ⚠ regression"
+        } else {
+            ""
+        };
+        println!(
+            "  {:<18} {:>20} {:>20} {:>9}{}",
+            m.name,
+            format!("{:.2} {}", m.value, m.unit),
+            before_text,
+            change_text,
+            flag
+        );
+    }
+    if !flagged.is_empty() {
+        println!(
+            "\n{} metric(s) more than {:.0}% worse than the previous run",
+            flagged.len(),
+            REGRESSION_THRESHOLD * 100.0
+        );
+    }
+}
+
+/// A fresh directory for the synthetic knowledge base
+pub fn scratch_dir() -> PathBuf {
+    std::env::temp_dir().join(format!(
+        "meepo-bench-{}",
+        &uuid::Uuid::new_v4().to_string()[..8]
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run_with(search_ms: f64, ingest_rate: f64) -> BenchRun {
+        BenchRun {
+            timestamp: Utc::now(),
+            version: "0.0.0".to_string(),
+            entities: 100,
+            queries: 10,
+            measurements: vec![
+                Measurement {
+                    name: "search p50".to_string(),
+                    value: search_ms,
+                    unit: "ms".to_string(),
+                    higher_is_better: false,
+                },
+                Measurement {
+                    name: "ingest entities".to_string(),
+                    value: ingest_rate,
+                    unit: "entities/s".to_string(),
+                    higher_is_better: true,
+                },
+            ],
+        }
+    }
+
+    #[test]
+    fn test_regressions_and_history() {
+        let before = run_with(1.0, 1000.0);
+        assert!(regressions(&run_with(1.1, 950.0), &before).is_empty());
+        assert_eq!(
+            regressions(&run_with(1.5, 700.0), &before),
+            vec!["search p50", "ingest entities"]
+        );
+        // Faster is never a regression
+        assert!(regressions(&run_with(0.2, 5000.0), &before).is_empty());
+
+        let dir = scratch_dir();
+        let path = dir.join("history.jsonl");
+        save_run(&path, &before).unwrap();
+        let mut other_size = run_with(2.0, 500.0);
+        other_size.entities = 5000;
+        save_run(&path, &other_size).unwrap();
+        let history = load_history(&path);
+        assert_eq!(history.len(), 2);
+        let previous = previous_run(&history, &run_with(1.0, 1000.0)).unwrap();
+        assert_eq!(previous.entities, 100);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_bench_run_small() {
+        let dir = scratch_dir();
+        let run = run(
+            BenchOptions {
+                entities: 40,
+                queries: 4,
+            },
+            &dir,
+        )
+        .await
+        .unwrap();
+        std::fs::remove_dir_all(&dir).unwrap();
+        let names: Vec<&str> = run.measurements.iter().map(|m| m.name.as_str()).collect();
+        assert_eq!(
+            names,
+            vec![
+                "ingest entities",
+                "ingest documents",
+                "search p50",
+                "search p95",
+                "hybrid p50",
+                "hybrid p95",
+                "agent turn p50",
+                "agent turn p95",
+            ]
+        );
+        assert!(run.measurements.iter().all(|m| m.value > 0.0));
+    }
+}
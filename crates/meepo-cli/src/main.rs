@@ -1,5 +1,5 @@
 use anyhow::{Context, Result, bail};
-use clap::{Parser, Subcommand};
+use clap::{Parser, Subcommand, ValueEnum};
 use std::path::PathBuf;
 use std::sync::Arc;
 use tokio::signal;
@@ -7,8 +7,11 @@ use tokio_util::sync::CancellationToken;
 use tracing::{error, info, warn};
 use tracing_subscriber::EnvFilter;
 
+mod bench;
 mod config;
+mod simulate;
 mod template;
+mod warmup;
 
 use config::MeepoConfig;
 
@@ -41,6 +44,26 @@ enum Commands {
     Ask {
         /// The message to send
         message: String,
+
+        /// Output format; json constrains the answer to a JSON object
+        #[arg(long, value_enum, default_value_t = OutputFormat::Text)]
+        format: OutputFormat,
+
+        /// JSON Schema the answer must match (inline JSON or @file); implies --format json
+        #[arg(long)]
+        schema: Option<String>,
+    },
+
+    /// Show whether the daemon is running, with channels, goals and today's usage
+    Status {
+        #[arg(long, value_enum, default_value_t = OutputFormat::Text)]
+        format: OutputFormat,
+    },
+
+    /// List the agent's active goals
+    Goals {
+        #[arg(long, value_enum, default_value_t = OutputFormat::Text)]
+        format: OutputFormat,
     },
 
     /// Initialize config directory and default config
@@ -49,11 +72,18 @@ enum Commands {
     /// Interactive first-time setup wizard
     Setup,
 
-    /// Show current configuration
-    Config,
+    /// Show current configuration, or change a setting
+    Config {
+        #[command(subcommand)]
+        action: Option<ConfigAction>,
+    },
 
     /// Run as an MCP server (STDIO transport)
-    McpServer,
+    McpServer {
+        /// Only expose the tools of this `[[tool_profiles]]` entry
+        #[arg(long)]
+        profile: Option<String>,
+    },
 
     /// Show AI usage statistics and costs
     Usage {
@@ -62,8 +92,91 @@ enum Commands {
         period: String,
 
         /// Export as CSV instead of table
-        #[arg(long)]
+        #[arg(long, conflicts_with = "format")]
         csv: bool,
+
+        #[arg(long, value_enum, default_value_t = OutputFormat::Text)]
+        format: OutputFormat,
+    },
+
+    /// Tool usage analytics
+    Tools {
+        #[command(subcommand)]
+        action: ToolsAction,
+    },
+
+    /// Feedback (👍/👎) on the agent's answers
+    Feedback {
+        #[command(subcommand)]
+        action: FeedbackAction,
+    },
+
+    /// A/B prompt experiments
+    Experiment {
+        #[command(subcommand)]
+        action: ExperimentAction,
+    },
+
+    /// Scheduled reports
+    Report {
+        #[command(subcommand)]
+        action: ReportAction,
+    },
+
+    /// Get to know you: name, timezone, working hours, key people, notifications
+    Onboard {
+        /// Restart the interview on this channel's next message instead of asking here
+        #[arg(long)]
+        channel: Option<String>,
+    },
+
+    /// Declarative automation rules ("when X then Y", no LLM)
+    Rule {
+        #[command(subcommand)]
+        action: RuleCommand,
+    },
+
+    /// Audit trail of goal, watcher, task and memory changes
+    Journal {
+        /// Time period: 'today', 'month', or 'YYYY-MM-DD:YYYY-MM-DD'
+        #[arg(default_value = "today")]
+        period: String,
+
+        /// Only events for this goal, watcher, task, entity or rule ID
+        #[arg(long)]
+        subject: Option<String>,
+
+        /// Only events of this kind (e.g. watcher_fired, goal_status_changed)
+        #[arg(long)]
+        kind: Option<String>,
+
+        /// Show the state replayed up to the end of the period instead
+        #[arg(long)]
+        state: bool,
+    },
+
+    /// Messages held by the spam filter
+    Quarantine {
+        #[command(subcommand)]
+        action: QuarantineAction,
+    },
+
+    /// Review what the agent wants to remember
+    Memory {
+        #[command(subcommand)]
+        action: MemoryAction,
+    },
+
+    /// Read-only guest (demo) mode
+    Guest {
+        #[command(subcommand)]
+        action: GuestAction,
+    },
+
+    /// Switch between named modes ("work", "weekend")
+    Mode {
+        #[command(subcommand)]
+        action: Option<ModeAction>,
     },
 
     /// Manage agent templates
@@ -74,6 +187,216 @@ enum Commands {
 
     /// Run system health checks
     Doctor,
+
+    /// Run the daemon against a scripted scenario and check its replies
+    Simulate {
+        /// Scenario file (YAML)
+        scenario: PathBuf,
+
+        /// Call the real LLM providers and save their responses to the scenario's fixtures
+        #[arg(long)]
+        record: bool,
+    },
+
+    /// Benchmark ingestion, search, hybrid retrieval and agent turns on a
+    /// synthetic knowledge base, compared with the previous run
+    Bench {
+        /// Entities in the synthetic knowledge base
+        #[arg(long, default_value_t = 2000)]
+        entities: usize,
+
+        /// Queries (and agent turns) to time
+        #[arg(long, default_value_t = 50)]
+        queries: usize,
+
+        /// Don't record this run in the history
+        #[arg(long)]
+        no_save: bool,
+    },
+}
+
+/// How a command prints its result
+#[derive(Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+enum OutputFormat {
+    /// Human-readable text
+    #[default]
+    Text,
+    /// Machine-readable JSON on stdout (logs go to stderr)
+    Json,
+}
+
+impl Commands {
+    /// Whether the command prints JSON, so logging must stay off stdout
+    fn prints_json(&self) -> bool {
+        match self {
+            Self::Ask { format, schema, .. } => *format == OutputFormat::Json || schema.is_some(),
+            Self::Status { format } | Self::Goals { format } | Self::Usage { format, .. } => {
+                *format == OutputFormat::Json
+            }
+            _ => false,
+        }
+    }
+}
+
+#[derive(Subcommand)]
+enum ConfigAction {
+    /// Set a config value (e.g. agent.language es) or a response style
+    /// (style.verbosity concise, style.emoji off, style.formality formal)
+    Set {
+        /// Dotted key: section.key, or style.<verbosity|emoji|formality>
+        key: String,
+        /// New value
+        value: String,
+        /// Apply a style setting or reply language to one sender only, as
+        /// channel:sender
+        #[arg(long = "for", value_name = "CHANNEL:SENDER")]
+        scope: Option<String>,
+    },
+}
+
+#[derive(Subcommand)]
+enum FeedbackAction {
+    /// Summarize satisfaction by channel, tool and model
+    Report {
+        /// Time period: 'today', 'month', or 'YYYY-MM-DD:YYYY-MM-DD'
+        #[arg(default_value = "month")]
+        period: String,
+    },
+}
+
+#[derive(Subcommand)]
+enum ToolsAction {
+    /// Calls, success rate, latency and token overhead per tool
+    Stats,
+}
+
+#[derive(Subcommand)]
+enum ExperimentAction {
+    /// Compare feedback and task success between the two prompt variants
+    Report {
+        /// Experiment name (default: the configured experiment)
+        name: Option<String>,
+    },
+}
+
+#[derive(Subcommand)]
+enum ReportAction {
+    /// Generate a report now and print it
+    Run {
+        /// Report name from `[[reports]]`
+        name: String,
+        /// Also deliver it to its email and file destinations
+        #[arg(long)]
+        deliver: bool,
+    },
+}
+
+#[derive(Subcommand)]
+#[allow(clippy::large_enum_variant)]
+enum RuleCommand {
+    /// List rules
+    List,
+    /// Add a rule: give exactly one of --notify, --template, --tool or --prompt
+    Add {
+        /// Rule name
+        name: String,
+        /// Event type (e.g. email_received, file_changed, message_received, or '*')
+        #[arg(long)]
+        event: String,
+        /// field=text the event payload must contain (repeatable)
+        #[arg(long = "filter")]
+        filters: Vec<String>,
+        /// Condition expression, e.g. 'payload.from contains "boss" && hour() < 18'
+        #[arg(long)]
+        when: Option<String>,
+        /// Send this message ({field} placeholders are filled from the event)
+        #[arg(long, conflicts_with_all = ["template", "tool", "prompt"])]
+        notify: Option<String>,
+        /// Send this message template (variables not in --vars come from the event)
+        #[arg(long, conflicts_with_all = ["tool", "prompt"])]
+        template: Option<String>,
+        /// Template variables as a JSON object
+        #[arg(long, requires = "template")]
+        vars: Option<String>,
+        /// Run this tool
+        #[arg(long, conflicts_with = "prompt")]
+        tool: Option<String>,
+        /// Tool arguments as a JSON object
+        #[arg(long, requires = "tool")]
+        args: Option<String>,
+        /// Ask the agent this
+        #[arg(long)]
+        prompt: Option<String>,
+        /// Where results go (default: internal)
+        #[arg(long, default_value = "internal")]
+        channel: String,
+        /// Matching events skip the agent's normal reply
+        #[arg(long)]
+        exclusive: bool,
+    },
+    /// Enable a rule
+    Enable { id: String },
+    /// Disable a rule without deleting it
+    Disable { id: String },
+    /// Delete a rule
+    Remove { id: String },
+}
+
+#[derive(Subcommand)]
+enum QuarantineAction {
+    /// List quarantined messages
+    List,
+    /// Let a message through (it's answered within a minute) and trust its sender
+    Release { id: String },
+    /// Delete a quarantined message
+    Drop {
+        id: String,
+        /// Also drop everything its sender sends from now on
+        #[arg(long)]
+        block: bool,
+    },
+}
+
+#[derive(Subcommand)]
+enum MemoryAction {
+    /// List memories waiting for review
+    Review,
+    /// Keep memories; the daemon stores them within a minute
+    Approve {
+        #[arg(required = true)]
+        ids: Vec<String>,
+    },
+    /// Throw memories away
+    Reject {
+        #[arg(required = true)]
+        ids: Vec<String>,
+    },
+}
+
+#[derive(Subcommand)]
+enum GuestAction {
+    /// Turn guest mode on (all channels, or one with --channel)
+    On {
+        /// Only this channel (e.g. slack)
+        #[arg(long)]
+        channel: Option<String>,
+    },
+    /// Turn guest mode off (all channels, or one with --channel)
+    Off {
+        /// Only this channel (e.g. slack)
+        #[arg(long)]
+        channel: Option<String>,
+    },
+    /// Show where guest mode applies
+    Status,
+}
+
+#[derive(Subcommand)]
+enum ModeAction {
+    /// Show configured modes and which one is on
+    List,
+    /// Switch to a mode, `none` for no mode, or `auto` to follow the schedule
+    Set { name: String },
 }
 
 #[derive(Subcommand)]
@@ -85,6 +408,13 @@ enum TemplateAction {
     Use {
         /// Template name, path, or gh:user/repo/path
         name: String,
+        /// Value for a template parameter (repeatable); others are asked for
+        #[arg(long = "param", value_name = "NAME=VALUE")]
+        params: Vec<String>,
+        /// Apply without asking, even if config keys are overwritten or
+        /// required commands and secrets are missing
+        #[arg(long, short)]
+        yes: bool,
     },
 
     /// Show what a template will change
@@ -115,21 +445,61 @@ async fn main() -> Result<()> {
 
     // Set up logging
     let filter = if cli.debug { "debug" } else { "info" };
-    tracing_subscriber::fmt()
-        .with_env_filter(EnvFilter::new(filter))
-        .init();
+    let subscriber = tracing_subscriber::fmt().with_env_filter(EnvFilter::new(filter));
+    if cli.command.prints_json() {
+        subscriber.with_writer(std::io::stderr).init();
+    } else {
+        subscriber.init();
+    }
 
     match cli.command {
         Commands::Init => cmd_init().await,
         Commands::Setup => cmd_setup().await,
-        Commands::Config => cmd_config(&cli.config).await,
+        Commands::Config { action: None } => cmd_config(&cli.config).await,
+        Commands::Config {
+            action: Some(ConfigAction::Set { key, value, scope }),
+        } => cmd_config_set(&cli.config, &key, &value, scope.as_deref()).await,
         Commands::Start => cmd_start(&cli.config).await,
         Commands::Stop => cmd_stop().await,
-        Commands::Ask { message } => cmd_ask(&cli.config, &message).await,
-        Commands::McpServer => cmd_mcp_server(&cli.config).await,
-        Commands::Usage { period, csv } => cmd_usage(&cli.config, &period, csv).await,
+        Commands::Ask {
+            message,
+            format,
+            schema,
+        } => cmd_ask(&cli.config, &message, format, schema.as_deref()).await,
+        Commands::Status { format } => cmd_status(&cli.config, format).await,
+        Commands::Goals { format } => cmd_goals(&cli.config, format).await,
+        Commands::McpServer { profile } => cmd_mcp_server(&cli.config, profile).await,
+        Commands::Usage {
+            period,
+            csv,
+            format,
+        } => cmd_usage(&cli.config, &period, csv, format).await,
+        Commands::Tools { action } => cmd_tools(&cli.config, action).await,
+        Commands::Feedback { action } => cmd_feedback(&cli.config, action).await,
+        Commands::Experiment { action } => cmd_experiment(&cli.config, action).await,
+        Commands::Report { action } => cmd_report(&cli.config, action).await,
+        Commands::Onboard { channel } => cmd_onboard(&cli.config, channel.as_deref()).await,
+        Commands::Rule { action } => cmd_rule(&cli.config, action).await,
+        Commands::Journal {
+            period,
+            subject,
+            kind,
+            state,
+        } => cmd_journal(&cli.config, &period, subject, kind, state).await,
+        Commands::Quarantine { action } => cmd_quarantine(&cli.config, action).await,
+        Commands::Memory { action } => cmd_memory(&cli.config, action).await,
+        Commands::Guest { action } => cmd_guest(&cli.config, action).await,
+        Commands::Mode { action } => cmd_mode(&cli.config, action).await,
         Commands::Template { action } => cmd_template(action).await,
         Commands::Doctor => cmd_doctor(&cli.config).await,
+        Commands::Simulate { scenario, record } => {
+            cmd_simulate(&cli.config, &scenario, record).await
+        }
+        Commands::Bench {
+            entities,
+            queries,
+            no_save,
+        } => cmd_bench(entities, queries, no_save).await,
     }
 }
 
@@ -178,12 +548,19 @@ async fn cmd_setup() -> Result<()> {
 
     let total_steps = if cfg!(target_os = "macos") { 7 } else { 5 };
 
+    // Speak the user's language if the system locale has a translation
+    let language = meepo_core::i18n::system_language()
+        .filter(|code| meepo_core::i18n::SUPPORTED.contains(&code.as_str()))
+        .unwrap_or_else(|| "en".to_string());
+    meepo_core::i18n::set_language(&language);
+    let t = meepo_core::i18n::strings(&language);
+
     println!();
     println!("  ╔══════════════════════════════════════╗");
-    println!("  ║         Meepo Setup Wizard           ║");
+    println!("  ║{:^38}║", t.setup_title);
     println!("  ╚══════════════════════════════════════╝");
     println!();
-    println!("  This wizard will walk you through everything:");
+    println!("  {}", t.setup_intro);
     println!("    • LLM provider setup (Anthropic, OpenAI, Ollama, etc.)");
     println!("    • macOS permissions (Accessibility, Full Disk Access, etc.)");
     println!("    • Feature selection (iMessage, email, browser, etc.)");
@@ -215,6 +592,14 @@ async fn cmd_setup() -> Result<()> {
     cmd_init().await?;
     let config_dir = config::config_dir();
     let config_path = config_dir.join("config.toml");
+    if language != "en" {
+        update_config_value(
+            &config_path,
+            "agent",
+            "language",
+            &format!("\"{}\"", language),
+        )?;
+    }
     println!("  ✓ Config directory ready\n");
 
     // ── Step 2: LLM Provider ────────────────────────────────────
@@ -647,15 +1032,15 @@ async fn cmd_setup() -> Result<()> {
     println!();
     if api_ok {
         println!("  ╔══════════════════════════════════════╗");
-        println!("  ║       ✓ Setup complete!              ║");
+        println!("  ║{:^38}║", t.setup_complete);
         println!("  ╚══════════════════════════════════════╝");
     } else {
         println!("  ╔══════════════════════════════════════╗");
-        println!("  ║  ⚠ Setup complete (API check failed) ║");
+        println!("  ║{:^38}║", t.setup_complete_api_failed);
         println!("  ╚══════════════════════════════════════╝");
     }
     println!();
-    println!("  Files created:");
+    println!("  {}", t.setup_files_created);
     println!("    Config:  {}", config_path.display());
     println!(
         "    Soul:    {}",
@@ -666,11 +1051,11 @@ async fn cmd_setup() -> Result<()> {
         config_dir.join("workspace/MEMORY.md").display()
     );
     println!();
-    println!("  Quick start:");
+    println!("  {}", t.setup_quick_start);
     println!("    meepo start          # start the daemon");
     println!("    meepo ask \"Hello\"    # one-shot question");
     println!();
-    println!("  Customize further:");
+    println!("  {}", t.setup_customize);
     println!("    nano {}  # edit config", config_path.display());
     println!("    meepo template list  # browse agent templates");
     println!();
@@ -689,7 +1074,8 @@ async fn cmd_setup() -> Result<()> {
 // ── Setup wizard helpers ────────────────────────────────────────
 
 fn setup_step(current: usize, total: usize, title: &str) {
-    println!("  ── Step {}/{}: {} ──", current, total, title);
+    let label = meepo_core::i18n::strings(meepo_core::i18n::language()).setup_step;
+    println!("  ── {} {}/{}: {} ──", label, current, total, title);
     println!();
 }
 
@@ -992,10 +1378,93 @@ async fn cmd_config(config_path: &Option<PathBuf>) -> Result<()> {
     Ok(())
 }
 
+async fn cmd_config_set(
+    config_path: &Option<PathBuf>,
+    key: &str,
+    value: &str,
+    scope: Option<&str>,
+) -> Result<()> {
+    // Response styles live in the knowledge DB as user preferences
+    if let Some(field) = key.strip_prefix("style.") {
+        let cfg = MeepoConfig::load(config_path)?;
+        let db_path = shellexpand(&cfg.knowledge.db_path);
+        let db = meepo_knowledge::KnowledgeDb::new(&db_path)
+            .context("Failed to open knowledge database")?;
+        let scope = match scope {
+            Some(s) if s.contains(':') => s.to_string(),
+            Some(s) => anyhow::bail!("--for must be channel:sender, got '{}'", s),
+            None => meepo_core::style::DEFAULT_SCOPE.to_string(),
+        };
+        let style = meepo_core::style::set_field(&db, &scope, field, value, "config").await?;
+        println!("Response style for {}: {}", scope, style.describe());
+        return Ok(());
+    }
+    // A sender's reply language is a preference too; without --for it's
+    // the configured default
+    if key == "agent.language"
+        && let Some(scope) = scope
+    {
+        if !scope.contains(':') {
+            anyhow::bail!("--for must be channel:sender, got '{}'", scope);
+        }
+        let cfg = MeepoConfig::load(config_path)?;
+        let db_path = shellexpand(&cfg.knowledge.db_path);
+        let db = meepo_knowledge::KnowledgeDb::new(&db_path)
+            .context("Failed to open knowledge database")?;
+        meepo_core::i18n::pin_language(&db, scope, value).await?;
+        if value.trim().eq_ignore_ascii_case("unset") {
+            println!("Reply language for {} will be detected again", scope);
+        } else {
+            println!(
+                "Replying to {} in {}",
+                scope,
+                meepo_core::i18n::language_name(value)
+            );
+        }
+        return Ok(());
+    }
+    if scope.is_some() {
+        anyhow::bail!("--for only applies to style.* settings and agent.language");
+    }
+
+    let (section, field) = key
+        .rsplit_once('.')
+        .ok_or_else(|| anyhow::anyhow!("Key must be section.key, e.g. agent.language"))?;
+    let path = config_path
+        .clone()
+        .unwrap_or_else(|| config::config_dir().join("config.toml"));
+    // Keep numbers and booleans bare; quote everything else as a string
+    let toml_value = match value.parse::<toml::Value>() {
+        Ok(v) if !v.is_str() => value.to_string(),
+        _ => toml::Value::String(value.to_string()).to_string(),
+    };
+    update_config_value(&path, section, field, &toml_value)?;
+    // Make sure the result still loads
+    MeepoConfig::load(config_path)?;
+    println!("Set {} = {} in {}", key, toml_value, path.display());
+    Ok(())
+}
+
 async fn cmd_start(config_path: &Option<PathBuf>) -> Result<()> {
     let cfg = MeepoConfig::load(config_path)?;
+    run_daemon(cfg, None).await?;
+    Ok(())
+}
+
+/// Run the daemon until Ctrl+C, or until the simulation (if any) has played
+/// every turn, returning its report
+async fn run_daemon(
+    cfg: MeepoConfig,
+    mut sim: Option<simulate::Simulation>,
+) -> Result<Option<meepo_channels::SimReport>> {
     info!("Starting Meepo daemon...");
 
+    let timezone = meepo_core::timezone::resolve(&cfg.agent.timezone);
+    meepo_core::timezone::set_user_timezone(timezone);
+    info!("Timezone: {}", timezone);
+    meepo_core::i18n::set_language(&cfg.agent.language);
+    apply_network_mode(&cfg)?;
+
     let cancel = CancellationToken::new();
 
     // Initialize knowledge database and graph
@@ -1007,10 +1476,48 @@ async fn cmd_start(config_path: &Option<PathBuf>) -> Result<()> {
     std::fs::create_dir_all(&tantivy_path)?;
 
     // Create KnowledgeGraph which includes both DB and Tantivy index
-    let knowledge_graph = Arc::new(
-        meepo_knowledge::KnowledgeGraph::new(&db_path, &tantivy_path)
-            .context("Failed to initialize knowledge graph")?,
-    );
+    let mut graph = meepo_knowledge::KnowledgeGraph::with_index_config(
+        &db_path,
+        &tantivy_path,
+        cfg.knowledge.index.to_index_config(),
+    )
+    .context("Failed to initialize knowledge graph")?
+    .with_fuzzy(cfg.knowledge.search.fuzzy)
+    .with_synonyms(cfg.knowledge.search.synonyms.clone());
+    let embeddings = &cfg.rag.embeddings;
+    if embeddings.enabled {
+        let provider = embeddings.to_embedding_config().and_then(|mut config| {
+            if let meepo_knowledge::EmbeddingBackend::Local { model_path, .. } = &mut config.backend
+            {
+                *model_path = shellexpand(&model_path.to_string_lossy());
+            }
+            let provider = meepo_knowledge::embedding_providers::build(&config)?;
+            let vectors = match &config.sqlite_vec {
+                Some(extension) => meepo_knowledge::VectorIndex::open_sqlite_vec(
+                    &db_path,
+                    provider.dimensions(),
+                    &shellexpand(&extension.to_string_lossy()),
+                )?,
+                None => meepo_knowledge::VectorIndex::load_from_db(&db_path, provider.dimensions())
+                    .context("Failed to load entity embeddings")?,
+            };
+            Ok((provider, vectors))
+        });
+        match provider {
+            Ok((provider, vectors)) => {
+                graph = graph.with_vector_index(provider, vectors, &db_path);
+                info!(
+                    "Hybrid search enabled ({:?} embeddings, {})",
+                    embeddings.backend, embeddings.model_name
+                );
+            }
+            Err(e) => warn!("Embeddings disabled: {}", e),
+        }
+    }
+    let knowledge_graph = Arc::new(graph);
+    if let Err(e) = knowledge_graph.refresh_synonyms().await {
+        warn!("Failed to load search synonyms: {}", e);
+    }
 
     // Use the graph's internal DB to avoid duplicate SQLite connections to the same file
     let db = knowledge_graph.db();
@@ -1030,10 +1537,20 @@ async fn cmd_start(config_path: &Option<PathBuf>) -> Result<()> {
 
     // Initialize API client via ModelRouter (multi-provider with failover)
     let use_ollama = cfg.agent.default_model == "ollama";
-    let api = {
+    let replay = llm_replay_settings(&cfg)?;
+    let api = if let Some((meepo_core::providers::ReplayMode::Replay, dir)) = &replay {
+        info!(
+            "Replaying LLM responses from {} (no network)",
+            dir.display()
+        );
+        meepo_core::api::ApiClient::from_router(meepo_core::providers::ModelRouter::single(
+            Box::new(meepo_core::providers::RecordReplayProvider::replay(dir)),
+        ))
+    } else {
         use meepo_core::providers::anthropic::AnthropicProvider;
         use meepo_core::providers::openai_compat::OpenAiCompatProvider;
         use meepo_core::providers::router::ModelRouter;
+        use meepo_core::providers::types::LlmProvider;
 
         let mut providers: Vec<Box<dyn meepo_core::providers::types::LlmProvider>> = Vec::new();
 
@@ -1127,6 +1644,29 @@ async fn cmd_start(config_path: &Option<PathBuf>) -> Result<()> {
                     primary_added = true;
                 }
             }
+            // Azure OpenAI deployment
+            else if model == "azure" {
+                if let Some(azure) = cfg.providers.azure.as_ref().and_then(azure_provider) {
+                    info!("Provider: azure/{}", azure.model());
+                    providers.push(Box::new(azure));
+                    primary_added = true;
+                }
+            }
+            // Claude on Bedrock: "bedrock" or a Bedrock model ID
+            else if (model == "bedrock" || model.contains("anthropic."))
+                && let Some(bedrock_cfg) = &cfg.providers.bedrock
+            {
+                let model = if model == "bedrock" {
+                    &bedrock_cfg.model
+                } else {
+                    model
+                };
+                if let Some(bedrock) = bedrock_provider(bedrock_cfg, model) {
+                    info!("Provider: bedrock/{}", model);
+                    providers.push(Box::new(bedrock));
+                    primary_added = true;
+                }
+            }
 
             // Default: try Anthropic as primary
             if !primary_added && add_anthropic(&mut providers, model, cfg.agent.max_tokens) {
@@ -1140,7 +1680,9 @@ async fn cmd_start(config_path: &Option<PathBuf>) -> Result<()> {
                      Options:\n  \
                      1. Set ANTHROPIC_API_KEY and use a Claude model\n  \
                      2. Set OPENAI_API_KEY and default_model = \"gpt-4o\"\n  \
-                     3. Set default_model = \"ollama\" for local inference\n\n\
+                     3. Set default_model = \"ollama\" for local inference\n  \
+                     4. Configure [providers.azure] and set default_model = \"azure\"\n  \
+                     5. Configure [providers.bedrock] and set default_model = \"bedrock\"\n\n\
                      Run `meepo setup` for guided configuration.",
                     model
                 );
@@ -1198,6 +1740,23 @@ async fn cmd_start(config_path: &Option<PathBuf>) -> Result<()> {
             }
         }
 
+        // Azure OpenAI (if not already primary)
+        if !providers.iter().any(|p| p.provider_name() == "azure")
+            && let Some(azure) = cfg.providers.azure.as_ref().and_then(azure_provider)
+        {
+            info!("Provider: azure/{} (failover)", azure.model());
+            providers.push(Box::new(azure));
+        }
+
+        // AWS Bedrock (if not already primary)
+        if !providers.iter().any(|p| p.provider_name() == "bedrock")
+            && let Some(bedrock_cfg) = &cfg.providers.bedrock
+            && let Some(bedrock) = bedrock_provider(bedrock_cfg, &bedrock_cfg.model)
+        {
+            info!("Provider: bedrock/{} (failover)", bedrock_cfg.model);
+            providers.push(Box::new(bedrock));
+        }
+
         // OpenAI-compatible (generic)
         if let Some(compat_cfg) = &cfg.providers.openai_compat {
             let key = shellexpand_str(&compat_cfg.api_key);
@@ -1233,8 +1792,16 @@ async fn cmd_start(config_path: &Option<PathBuf>) -> Result<()> {
             ModelRouter::with_failover(providers)?
         };
 
-        meepo_core::api::ApiClient::from_router(router)
+        if let Some((_, dir)) = &replay {
+            info!("Recording LLM responses to {}", dir.display());
+            let recorder =
+                meepo_core::providers::RecordReplayProvider::record(Box::new(router), dir);
+            meepo_core::api::ApiClient::from_router(ModelRouter::single(Box::new(recorder)))
+        } else {
+            meepo_core::api::ApiClient::from_router(router)
+        }
     };
+    let api = api.with_loop_guard(cfg.agent.loop_guard.to_loop_guard_config());
     info!("API client initialized (model: {})", api.model());
 
     // Initialize Tavily client (optional — web search works only if API key is set)
@@ -1260,15 +1827,20 @@ async fn cmd_start(config_path: &Option<PathBuf>) -> Result<()> {
     let (bg_task_tx, mut bg_task_rx) =
         tokio::sync::mpsc::channel::<meepo_core::tools::autonomous::BackgroundTaskCommand>(100);
 
+    // Outgoing messages from the loop, notifier, and tools (routed to the bus once it starts)
+    let (loop_resp_tx, mut loop_resp_rx) =
+        tokio::sync::mpsc::channel::<meepo_core::types::OutgoingMessage>(256);
+
+    // Questions background tasks are waiting on the user to answer
+    let pending_questions = Arc::new(meepo_core::questions::PendingQuestions::new());
+
     // Build tool registry
     let mut registry = meepo_core::tools::ToolRegistry::new();
-    // Email, calendar, and UI automation tools require macOS or Windows platform support
+    // Email and UI automation tools require macOS or Windows platform support
     #[cfg(any(target_os = "macos", target_os = "windows"))]
     {
         registry.register(Arc::new(meepo_core::tools::macos::ReadEmailsTool::new()));
-        registry.register(Arc::new(meepo_core::tools::macos::ReadCalendarTool::new()));
         registry.register(Arc::new(meepo_core::tools::macos::SendEmailTool::new()));
-        registry.register(Arc::new(meepo_core::tools::macos::CreateEventTool::new()));
         registry.register(Arc::new(
             meepo_core::tools::accessibility::ReadScreenTool::new(),
         ));
@@ -1279,9 +1851,23 @@ async fn cmd_start(config_path: &Option<PathBuf>) -> Result<()> {
             meepo_core::tools::accessibility::TypeTextTool::new(),
         ));
     }
+    // Calendar tools: Calendar.app, Outlook, or khal on Linux
+    #[cfg(any(target_os = "macos", target_os = "windows", target_os = "linux"))]
+    {
+        registry.register(Arc::new(meepo_core::tools::macos::ReadCalendarTool::new()));
+        registry.register(Arc::new(create_event_tool(&cfg, db.clone())));
+    }
     // Clipboard and app launcher are cross-platform (arboard + open crates)
     registry.register(Arc::new(meepo_core::tools::macos::OpenAppTool::new()));
     registry.register(Arc::new(meepo_core::tools::macos::GetClipboardTool::new()));
+    // Notifications and screen capture through desktop tools on Linux
+    #[cfg(target_os = "linux")]
+    {
+        registry.register(Arc::new(
+            meepo_core::tools::macos::SendNotificationTool::new(),
+        ));
+        registry.register(Arc::new(meepo_core::tools::macos::ScreenCaptureTool::new()));
+    }
     // macOS-only tools: Reminders, Notes, Notifications, Screen Capture, Music, Contacts
     #[cfg(target_os = "macos")]
     {
@@ -1373,6 +1959,9 @@ async fn cmd_start(config_path: &Option<PathBuf>) -> Result<()> {
         registry.register(Arc::new(
             meepo_core::tools::macos_shortcuts::RunShortcutTool::new(),
         ));
+        // Focus modes
+        registry.register(Arc::new(meepo_core::tools::macos_focus::GetFocusTool::new()));
+        registry.register(Arc::new(meepo_core::tools::macos_focus::SetFocusTool::new()));
         // Keychain
         registry.register(Arc::new(
             meepo_core::tools::macos_keychain::KeychainGetPasswordTool::new(),
@@ -1438,9 +2027,17 @@ async fn cmd_start(config_path: &Option<PathBuf>) -> Result<()> {
             meepo_core::tools::macos_productivity::GetFrontmostDocumentTool::new(),
         ));
     }
+    // Browser automation via Chrome DevTools Protocol (all platforms)
+    if cfg.browser.enabled && cfg.browser.backend == "cdp" {
+        let cdp = meepo_core::platform::cdp::CdpBrowser::new(build_cdp_config(&cfg));
+        for tool in meepo_core::tools::browser::browser_tools("browser", || Box::new(cdp.clone())) {
+            registry.register(tool);
+        }
+        info!("Registered browser tools (backend: cdp)");
+    }
     // Browser automation tools (macOS: Safari/Chrome via AppleScript)
     #[cfg(target_os = "macos")]
-    if cfg.browser.enabled {
+    if cfg.browser.enabled && cfg.browser.backend != "cdp" {
         let browser = &cfg.browser.default_browser;
         registry.register(Arc::new(
             meepo_core::tools::browser::BrowserListTabsTool::new(browser),
@@ -1537,6 +2134,13 @@ async fn cmd_start(config_path: &Option<PathBuf>) -> Result<()> {
             browser, other_browser
         );
     }
+    #[cfg(not(target_os = "macos"))]
+    if cfg.browser.enabled && cfg.browser.backend != "cdp" {
+        warn!(
+            "browser.backend = \"{}\" needs macOS; set backend = \"cdp\" for browser tools",
+            cfg.browser.backend
+        );
+    }
     let code_config = meepo_core::tools::code::CodeToolConfig {
         coding_agent_path: shellexpand_str(&cfg.code.coding_agent_path),
         gh_path: shellexpand_str(&cfg.code.gh_path),
@@ -1558,57 +2162,150 @@ async fn cmd_start(config_path: &Option<PathBuf>) -> Result<()> {
             bg_task_tx.clone(),
         ),
     ));
-    registry.register(Arc::new(meepo_core::tools::memory::RememberTool::new(
+    registry.register(Arc::new(
+        meepo_core::tools::memory::RememberTool::new(db.clone())
+            .with_review(cfg.knowledge.review.enabled),
+    ));
+    registry.register(Arc::new(meepo_core::tools::memory::RecallTool::new(
         db.clone(),
     )));
-    registry.register(Arc::new(meepo_core::tools::memory::RecallTool::new(
+    registry.register(Arc::new(meepo_core::tools::memory::ListTagsTool::new(
         db.clone(),
     )));
+    registry.register(Arc::new(
+        meepo_core::tools::memory::ListKnowledgeGapsTool::new(db.clone()),
+    ));
+    registry.register(Arc::new(
+        meepo_core::tools::dossier::GenerateDossierTool::new(db.clone(), workspace.clone())
+            .with_watcher_commands(watcher_command_tx.clone()),
+    ));
     // Use KnowledgeGraph for SearchKnowledgeTool to enable Tantivy full-text search
     registry.register(Arc::new(
         meepo_core::tools::memory::SearchKnowledgeTool::with_graph(knowledge_graph.clone()),
     ));
-    registry.register(Arc::new(meepo_core::tools::memory::LinkEntitiesTool::new(
-        db.clone(),
-    )));
-    // RAG-enhanced tools: GraphRAG-powered recall and document ingestion
-    registry.register(Arc::new(meepo_core::tools::rag::SmartRecallTool::new(
+    registry.register(Arc::new(
+        meepo_core::tools::memory::LinkEntitiesTool::new(db.clone())
+            .with_review(cfg.knowledge.review.enabled),
+    ));
+    registry.register(Arc::new(
+        meepo_core::tools::memory::RememberManyTool::new(knowledge_graph.clone())
+            .with_review(cfg.knowledge.review.enabled),
+    ));
+    registry.register(Arc::new(meepo_core::tools::memory::ManageAliasesTool::new(
         knowledge_graph.clone(),
-        db.clone(),
     )));
-    registry.register(Arc::new(meepo_core::tools::rag::IngestDocumentTool::new(
+    registry.register(Arc::new(meepo_core::tools::memory::ForgetTool::new(
         knowledge_graph.clone(),
     )));
-    registry.register(Arc::new(meepo_core::tools::system::RunCommandTool));
-    registry.register(Arc::new(meepo_core::tools::system::ReadFileTool));
-    registry.register(Arc::new(meepo_core::tools::system::WriteFileTool));
-    // Filesystem access tools — validate configured directories exist
-    for dir in &cfg.filesystem.allowed_directories {
-        let expanded = shellexpand(dir);
-        if !expanded.exists() {
-            warn!(
-                "Configured allowed directory does not exist: {} (expanded: {})",
-                dir,
-                expanded.display()
-            );
-        }
+    // RAG-enhanced tools: GraphRAG-powered recall and document ingestion
+    let mut smart_recall =
+        meepo_core::tools::rag::SmartRecallTool::new(knowledge_graph.clone(), db.clone())
+            .with_config(cfg.rag.graph_rag.to_graph_rag_config());
+    if cfg.rag.rerank.enabled {
+        smart_recall = smart_recall.with_reranker(Arc::new(
+            meepo_core::rerank::Reranker::new(cfg.rag.rerank.to_rerank_config())
+                .with_api(api.clone()),
+        ));
+        info!(
+            "smart_recall re-ranking enabled ({:?})",
+            cfg.rag.rerank.backend
+        );
     }
+    registry.register(Arc::new(smart_recall));
     registry.register(Arc::new(
-        meepo_core::tools::filesystem::ListDirectoryTool::new(
-            cfg.filesystem.allowed_directories.clone(),
-        ),
+        meepo_core::tools::provenance::ExplainRecallTool::new(knowledge_graph.clone(), db.clone()),
     ));
+    let mut ingest_tool = meepo_core::tools::rag::IngestDocumentTool::new(knowledge_graph.clone())
+        .with_chunking_config(cfg.rag.chunking.to_chunking_config());
+    if cfg.action_items.enabled {
+        ingest_tool = ingest_tool.with_action_items(action_item_config(&cfg));
+    }
+    let ingest_tool = Arc::new(ingest_tool);
+    registry.register(ingest_tool.clone());
     registry.register(Arc::new(
-        meepo_core::tools::filesystem::SearchFilesTool::new(
-            cfg.filesystem.allowed_directories.clone(),
-        ),
+        meepo_core::tools::pdf::ReadPdfTool::new(knowledge_graph.clone())
+            .with_chunking_config(cfg.rag.chunking.to_chunking_config()),
     ));
-    // BrowseUrlTool with optional Tavily extract
-    if let Some(ref tavily) = tavily_client {
-        registry.register(Arc::new(
-            meepo_core::tools::system::BrowseUrlTool::with_tavily(tavily.clone()),
-        ));
-    } else {
+    registry.register(Arc::new(meepo_core::tools::system::RunCommandTool));
+    registry.register(Arc::new(
+        meepo_core::tools::datetime::CurrentDateTimeTool::new(cfg.agent.locale.clone()),
+    ));
+    registry.register(Arc::new(
+        meepo_core::tools::network::NetworkKillSwitchTool::new(),
+    ));
+    registry.register(Arc::new(meepo_core::tools::style::SetStyleTool::new(
+        db.clone(),
+    )));
+    registry.register(Arc::new(meepo_core::tools::pins::PinContextTool::new(
+        db.clone(),
+    )));
+    registry.register(Arc::new(meepo_core::tools::pins::UnpinContextTool::new(
+        db.clone(),
+    )));
+    let modes = Arc::new(modes_config(&cfg));
+    if !modes.modes.is_empty() {
+        registry.register(Arc::new(meepo_core::tools::modes::SetModeTool::new(
+            db.clone(),
+            modes.clone(),
+        )));
+    }
+    registry.register(Arc::new(meepo_core::tools::rules::CreateRuleTool::new(
+        db.clone(),
+    )));
+    registry.register(Arc::new(meepo_core::tools::rules::ListRulesTool::new(
+        db.clone(),
+    )));
+    let message_templates = Arc::new(
+        meepo_core::message_templates::TemplateStore::new(db.clone()).with_workspace(&workspace),
+    );
+    registry.register(Arc::new(
+        meepo_core::tools::message_templates::SendTemplateTool::new(
+            message_templates.clone(),
+            loop_resp_tx.clone(),
+            meepo_core::types::ChannelType::from_string(&cfg.notifications.channel),
+        ),
+    ));
+    registry.register(Arc::new(
+        meepo_core::tools::message_templates::SaveTemplateTool::new(message_templates.clone()),
+    ));
+    registry.register(Arc::new(
+        meepo_core::tools::message_templates::ListTemplatesTool::new(message_templates.clone()),
+    ));
+    registry.register(Arc::new(
+        meepo_core::tools::onboarding::CompleteOnboardingTool::new(
+            db.clone(),
+            workspace.join(&cfg.agent.memory_file),
+        ),
+    ));
+    registry.register(Arc::new(meepo_core::tools::system::ReadFileTool));
+    registry.register(Arc::new(meepo_core::tools::system::WriteFileTool));
+    // Filesystem access tools — validate configured directories exist
+    for dir in &cfg.filesystem.allowed_directories {
+        let expanded = shellexpand(dir);
+        if !expanded.exists() {
+            warn!(
+                "Configured allowed directory does not exist: {} (expanded: {})",
+                dir,
+                expanded.display()
+            );
+        }
+    }
+    registry.register(Arc::new(
+        meepo_core::tools::filesystem::ListDirectoryTool::new(
+            cfg.filesystem.allowed_directories.clone(),
+        ),
+    ));
+    registry.register(Arc::new(
+        meepo_core::tools::filesystem::SearchFilesTool::new(
+            cfg.filesystem.allowed_directories.clone(),
+        ),
+    ));
+    // BrowseUrlTool with optional Tavily extract
+    if let Some(ref tavily) = tavily_client {
+        registry.register(Arc::new(
+            meepo_core::tools::system::BrowseUrlTool::with_tavily(tavily.clone()),
+        ));
+    } else {
         registry.register(Arc::new(meepo_core::tools::system::BrowseUrlTool::new()));
     }
     // Register web_search tool if Tavily is available
@@ -1638,6 +2335,12 @@ async fn cmd_start(config_path: &Option<PathBuf>) -> Result<()> {
         watcher_command_tx.clone(),
         bg_task_tx.clone(),
     )));
+    registry.register(Arc::new(meepo_core::tools::autonomous::AskUserTool::new(
+        db.clone(),
+        pending_questions.clone(),
+        loop_resp_tx.clone(),
+        meepo_core::types::ChannelType::from_string(&cfg.notifications.channel),
+    )));
     // ── Lifestyle Integration Tools ──────────────────────────────
     // Phase 1: Email Intelligence (macOS/Windows only — needs email provider)
     #[cfg(any(target_os = "macos", target_os = "windows"))]
@@ -1654,9 +2357,16 @@ async fn cmd_start(config_path: &Option<PathBuf>) -> Result<()> {
         registry.register(Arc::new(
             meepo_core::tools::lifestyle::email_intelligence::EmailUnsubscribeTool::new(),
         ));
+        registry.register(Arc::new(
+            meepo_core::tools::lifestyle::email_intelligence::EmailRuleTool::new(db.clone()),
+        ));
+        // The briefing reads the inbox as well as the calendar
+        registry.register(Arc::new(
+            meepo_core::tools::lifestyle::calendar::DailyBriefingTool::new(db.clone()),
+        ));
     }
-    // Phase 1: Smart Calendar (macOS/Windows only — needs calendar provider)
-    #[cfg(any(target_os = "macos", target_os = "windows"))]
+    // Phase 1: Smart Calendar (needs calendar provider)
+    #[cfg(any(target_os = "macos", target_os = "windows", target_os = "linux"))]
     {
         registry.register(Arc::new(
             meepo_core::tools::lifestyle::calendar::FindFreeTimeTool::new(),
@@ -1668,10 +2378,13 @@ async fn cmd_start(config_path: &Option<PathBuf>) -> Result<()> {
             meepo_core::tools::lifestyle::calendar::RescheduleEventTool::new(),
         ));
         registry.register(Arc::new(
-            meepo_core::tools::lifestyle::calendar::DailyBriefingTool::new(db.clone()),
+            meepo_core::tools::lifestyle::calendar::WeeklyReviewTool::new(db.clone()),
         ));
         registry.register(Arc::new(
-            meepo_core::tools::lifestyle::calendar::WeeklyReviewTool::new(db.clone()),
+            meepo_core::tools::lifestyle::calendar::MeetingPrepTool::new(
+                meepo_core::meeting_prep::MeetingPrep::for_platform(db.clone())
+                    .expect("Calendar provider not available on this platform"),
+            ),
         ));
     }
     // Phase 1: Deep Research (cross-platform — uses Tavily + knowledge graph)
@@ -1793,7 +2506,16 @@ async fn cmd_start(config_path: &Option<PathBuf>) -> Result<()> {
         meepo_core::tools::canvas::CanvasSnapshotTool::new(),
     ));
     // ── Agent-to-Agent Session Tools ──────────────────────────────
-    let shared_sessions = Arc::new(meepo_gateway::session::SessionManager::new());
+    let push_registry = if cfg.gateway.enabled && cfg.gateway.push.enabled {
+        Some(meepo_core::push::PushRegistry::load(db.clone()).await?)
+    } else {
+        None
+    };
+    let mut shared_sessions = meepo_gateway::session::SessionManager::new();
+    if let Some(registry) = &push_registry {
+        shared_sessions = shared_sessions.with_push(registry.clone());
+    }
+    let shared_sessions = Arc::new(shared_sessions);
     let a2a_tool_config = meepo_gateway::AgentToAgentConfig {
         enabled: cfg.agent_to_agent.enabled,
         allow: cfg.agent_to_agent.allow.clone(),
@@ -1827,21 +2549,33 @@ async fn cmd_start(config_path: &Option<PathBuf>) -> Result<()> {
         a2a_tool_config.clone(),
     )));
     // ── Docker Sandbox Tool ───────────────────────────────────────
-    registry.register(Arc::new(
-        meepo_core::tools::sandbox_exec::SandboxExecTool::new(meepo_core::sandbox::SandboxConfig {
-            enabled: cfg.sandbox.enabled,
-            docker_socket: cfg.sandbox.docker_socket.clone(),
-            policy: meepo_core::sandbox::policy::ExecutionPolicy {
-                resource_limits: meepo_core::sandbox::policy::ResourceLimits {
-                    memory_mb: cfg.sandbox.memory_mb,
-                    timeout_secs: cfg.sandbox.timeout_secs,
-                    network_enabled: cfg.sandbox.network_enabled,
-                    ..Default::default()
-                },
+    let sandbox_config = meepo_core::sandbox::SandboxConfig {
+        enabled: cfg.sandbox.enabled,
+        docker_socket: cfg.sandbox.docker_socket.clone(),
+        policy: meepo_core::sandbox::policy::ExecutionPolicy {
+            resource_limits: meepo_core::sandbox::policy::ResourceLimits {
+                memory_mb: cfg.sandbox.memory_mb,
+                timeout_secs: cfg.sandbox.timeout_secs,
+                network_enabled: cfg.sandbox.network_enabled,
                 ..Default::default()
             },
-        }),
+            ..Default::default()
+        },
+    };
+    registry.register(Arc::new(
+        meepo_core::tools::sandbox_exec::SandboxExecTool::new(sandbox_config.clone()),
     ));
+    registry.register(Arc::new(meepo_core::tools::script::ExecuteScriptTool::new(
+        sandbox_config,
+        shellexpand(&cfg.sandbox.artifacts_dir),
+    )));
+    if cfg.http.enabled {
+        registry.register(Arc::new(build_http_tool(&cfg)));
+    }
+    if let Some(manager) = build_download_manager(&cfg) {
+        let manager = manager.with_follow_ups(db.clone(), bg_task_tx.clone());
+        register_download_tools(&mut registry, &cfg, manager);
+    }
     info!(
         "Registered {} tools (including lifestyle integrations)",
         registry.len()
@@ -1858,6 +2592,8 @@ async fn cmd_start(config_path: &Option<PathBuf>) -> Result<()> {
         parallel_timeout_secs: cfg.orchestrator.parallel_timeout_secs,
         background_timeout_secs: cfg.orchestrator.background_timeout_secs,
         max_background_groups: cfg.orchestrator.max_background_groups,
+        scratchpad_max_notes: cfg.orchestrator.scratchpad_max_notes,
+        scratchpad_max_note_chars: cfg.orchestrator.scratchpad_max_note_chars,
     };
     let orchestrator_api = api.clone();
     let orchestrator = Arc::new(meepo_core::orchestrator::TaskOrchestrator::new(
@@ -1880,36 +2616,60 @@ async fn cmd_start(config_path: &Option<PathBuf>) -> Result<()> {
     );
 
     // ── Phase 2: MCP Clients — connect to external MCP servers ──
-    for client_cfg in &cfg.mcp.clients {
-        let mcp_config = meepo_mcp::McpClientConfig {
-            name: client_cfg.name.clone(),
-            command: shellexpand_str(&client_cfg.command),
-            args: client_cfg.args.iter().map(|a| shellexpand_str(a)).collect(),
-            env: client_cfg
-                .env
-                .iter()
-                .map(|(k, v)| (k.clone(), shellexpand_str(v)))
-                .collect(),
-        };
-
-        match meepo_mcp::McpClient::connect(mcp_config).await {
-            Ok(client) => match client.discover_tools().await {
-                Ok(tools) => {
-                    let count = tools.len();
-                    for tool in tools {
-                        registry.register(tool);
-                    }
-                    info!(
-                        "MCP client '{}': registered {} tools",
-                        client_cfg.name, count
-                    );
+    // Handshakes run in parallel; tools are registered in config order
+    let handshakes: Vec<_> = cfg
+        .mcp
+        .clients
+        .iter()
+        .map(|client_cfg| {
+            let mcp_config = meepo_mcp::McpClientConfig {
+                name: client_cfg.name.clone(),
+                command: shellexpand_str(&client_cfg.command),
+                args: client_cfg.args.iter().map(|a| shellexpand_str(a)).collect(),
+                env: client_cfg
+                    .env
+                    .iter()
+                    .map(|(k, v)| (k.clone(), shellexpand_str(v)))
+                    .collect(),
+            };
+            tokio::spawn(warmup::timed(
+                format!("mcp:{}", client_cfg.name),
+                cfg.warmup.timeout(),
+                async move {
+                    let client = meepo_mcp::McpClient::connect(mcp_config)
+                        .await
+                        .context("failed to connect")?;
+                    client
+                        .discover_tools()
+                        .await
+                        .context("failed to discover tools")
+                },
+            ))
+        })
+        .collect();
+    let mut mcp_steps = Vec::with_capacity(handshakes.len());
+    for (client_cfg, handshake) in cfg.mcp.clients.iter().zip(handshakes) {
+        match handshake.await {
+            Ok((step, Some(tools))) => {
+                let count = tools.len();
+                for tool in tools {
+                    registry.register(tool);
                 }
-                Err(e) => warn!(
-                    "MCP client '{}': failed to discover tools: {}",
-                    client_cfg.name, e
-                ),
-            },
-            Err(e) => warn!("MCP client '{}': failed to connect: {}", client_cfg.name, e),
+                info!(
+                    "MCP client '{}': registered {} tools in {}ms",
+                    client_cfg.name, count, step.duration_ms
+                );
+                mcp_steps.push(step);
+            }
+            Ok((step, None)) => {
+                warn!(
+                    "MCP client '{}': {}",
+                    client_cfg.name,
+                    step.error.as_deref().unwrap_or("failed")
+                );
+                mcp_steps.push(step);
+            }
+            Err(e) => warn!("MCP client '{}': {}", client_cfg.name, e),
         }
     }
 
@@ -1930,7 +2690,14 @@ async fn cmd_start(config_path: &Option<PathBuf>) -> Result<()> {
             })
             .collect();
 
-        registry.register(Arc::new(meepo_a2a::DelegateToAgentTool::new(peers)));
+        let mut delegate = meepo_a2a::DelegateToAgentTool::new(peers).with_trust(db.clone());
+        if cfg.a2a.verification.enabled {
+            delegate = delegate.with_verifier(meepo_a2a::Verifier::new(
+                api.clone(),
+                cfg.a2a.verification.model.clone(),
+            ));
+        }
+        registry.register(Arc::new(delegate));
         info!(
             "A2A: registered delegate_to_agent tool ({} peer agents)",
             cfg.a2a.agents.len()
@@ -1960,6 +2727,11 @@ async fn cmd_start(config_path: &Option<PathBuf>) -> Result<()> {
         }
     }
 
+    // ── Plugins — native and wasm tool plugins ─────────────────
+    if cfg.plugins.enabled {
+        register_plugins(&cfg, &mut registry);
+    }
+
     // ── Usage Tracker ──────────────────────────────────────────────
     let usage_tracker = if cfg.usage.enabled {
         let mut model_prices = std::collections::HashMap::new();
@@ -1995,6 +2767,87 @@ async fn cmd_start(config_path: &Option<PathBuf>) -> Result<()> {
         None
     };
 
+    // Workspace artifact sync (scheduled runs start below)
+    let artifact_sync = if cfg.artifact_sync.enabled {
+        match artifact_sync(&cfg, db.clone()) {
+            Ok(sync) => {
+                let sync = Arc::new(sync.with_ingest(ingest_tool.clone()));
+                registry.register(Arc::new(
+                    meepo_core::tools::artifact_sync::SyncNowTool::new(sync.clone()),
+                ));
+                info!("Artifact sync enabled ({})", sync.target_name());
+                Some(sync)
+            }
+            Err(e) => {
+                warn!("Artifact sync disabled: {:#}", e);
+                None
+            }
+        }
+    } else {
+        None
+    };
+
+    // App-usage report (samples are taken by the tracker started below)
+    if cfg.app_usage.enabled {
+        registry.register(Arc::new(meepo_core::tools::app_usage::AppUsageTool::new(
+            db.clone(),
+        )));
+    }
+
+    if let Some(sim) = &sim {
+        sim.mock_tools(&mut registry);
+    }
+
+    // Email and message tools go through the send guard
+    let send_guard = if cfg.send_guard.enabled {
+        let guard = &cfg.send_guard;
+        let guard = Arc::new(
+            meepo_core::send_guard::SendGuard::new(
+                meepo_core::send_guard::SendGuardConfig {
+                    allow: guard.allow.clone(),
+                    deny: guard.deny.clone(),
+                    daily_cap: guard.daily_cap,
+                    confirm_new_recipients: guard.confirm_new_recipients,
+                    cooling_off: std::time::Duration::from_secs(guard.cooling_off_secs),
+                },
+                db.clone(),
+                loop_resp_tx.clone(),
+                meepo_core::types::ChannelType::from_string(&cfg.notifications.channel),
+            )
+            .with_questions(pending_questions.clone()),
+        );
+        guard.protect(&mut registry);
+        registry.register(Arc::new(
+            meepo_core::tools::send_guard::CancelSendTool::new(guard.clone()),
+        ));
+        Some(guard)
+    } else {
+        None
+    };
+
+    // Tools offered by devices connected to the gateway
+    let gateway_nodes = (cfg.gateway.enabled && !cfg.gateway.nodes.is_empty()).then(|| {
+        let grants = cfg
+            .gateway
+            .nodes
+            .iter()
+            .map(|n| meepo_gateway::NodeGrant {
+                name: n.name.clone(),
+                token: shellexpand_str(&n.token),
+                tools: n.tools.clone(),
+            })
+            .collect();
+        let nodes = Arc::new(meepo_gateway::NodeRegistry::new(
+            grants,
+            std::time::Duration::from_secs(cfg.gateway.node_timeout_secs),
+        ));
+        match &send_guard {
+            Some(guard) => registry.add_source(guard.protect_source(nodes.clone())),
+            None => registry.add_source(nodes.clone()),
+        }
+        nodes
+    });
+
     info!("Total tools registered: {}", registry.len());
 
     // Initialize agent
@@ -2004,16 +2857,130 @@ async fn cmd_start(config_path: &Option<PathBuf>) -> Result<()> {
         "registry slot already set"
     );
 
-    let mut agent = meepo_core::agent::Agent::new(api, registry.clone(), soul, memory, db.clone());
+    let mut agent =
+        meepo_core::agent::Agent::new(api.clone(), registry.clone(), soul, memory, db.clone())
+            .with_trace_storage(cfg.agent.store_traces)
+            .with_soul_dir(workspace.clone())
+            .with_voice_note_summaries(cfg.voice.voice_note_summaries)
+            .with_context_compression(cfg.rag.context_compression.to_compression_config())
+            .with_router_config(cfg.rag.query_router.to_router_config())
+            .with_tool_summaries(cfg.agent.tool_summaries.to_summary_config());
+    if cfg.knowledge.gaps.enabled {
+        let gaps = &cfg.knowledge.gaps;
+        agent = agent.with_knowledge_gaps(meepo_core::knowledge_gaps::KnowledgeGapConfig {
+            enabled: true,
+            min_score: gaps.min_score,
+            min_occurrences: gaps.min_occurrences,
+            research_goals: gaps.research_goals,
+            max_research_goals_per_day: gaps.max_research_goals_per_day,
+            research_budget_usd: gaps.research_budget_usd,
+        });
+    }
+    if cfg.action_items.enabled {
+        agent = agent.with_action_items(action_item_config(&cfg));
+    }
+    agent = agent.with_guest_mode(meepo_core::guest::GuestModeConfig {
+        channels: cfg.guest_mode.channels.clone(),
+        senders: cfg.guest_mode.senders.clone(),
+        allow_tools: cfg.guest_mode.allow_tools.clone(),
+    });
+    if !cfg.restricted_profiles.is_empty() {
+        agent = agent.with_profiles(restricted_profiles(&cfg));
+    }
+    if !cfg.contact_policies.is_empty() {
+        let mut middleware = meepo_core::middleware::MiddlewareChain::new();
+        middleware.add(Arc::new(contact_policies(&cfg)));
+        agent = agent.with_middleware(middleware);
+        info!("{} contact policies active", cfg.contact_policies.len());
+    }
+    agent = agent.with_onboarding(cfg.onboarding.enabled);
+    let redacted = cfg.channels.redacted_channels();
+    if !redacted.is_empty() {
+        agent = agent.with_redaction(
+            meepo_core::redaction::RedactionConfig {
+                channels: redacted.iter().map(|c| c.to_string()).collect(),
+                numbers: cfg.redaction.numbers,
+                ..Default::default()
+            }
+            .with_patterns(&cfg.redaction.patterns),
+        );
+        info!("Redacting cloud model calls on {}", redacted.join(", "));
+    }
+    if cfg.tool_costs.enabled {
+        let costs = &cfg.tool_costs;
+        agent = agent.with_tool_costs(Arc::new(meepo_core::tool_cost::ToolCostModel::new(
+            &registry,
+            meepo_core::tool_cost::ToolCostConfig {
+                budget: costs.budget,
+                idle_reset: std::time::Duration::from_secs(costs.idle_reset_mins * 60),
+                overrides: costs.overrides.clone(),
+            },
+        )));
+        info!("Tool budget: {} units per conversation", costs.budget);
+    }
+    if let Some(live) = live_context(&cfg) {
+        agent = agent.with_live_context(live);
+    }
+    let channel_models = cfg.channels.models();
+    for (channel, model) in &channel_models {
+        info!("Channel {} answers with {}", channel, model);
+    }
+    agent = agent.with_channel_models(channel_models);
+    if cfg.experiments.enabled {
+        let path = workspace.join(&cfg.experiments.variant_b_file);
+        match meepo_knowledge::load_soul(&path) {
+            Ok(variant_b) => {
+                info!(
+                    "Prompt experiment '{}' running ({:.0}% get {})",
+                    cfg.experiments.name,
+                    cfg.experiments.split * 100.0,
+                    path.display()
+                );
+                agent = agent.with_experiment(meepo_core::experiments::PromptExperiment {
+                    name: cfg.experiments.name.clone(),
+                    variant_b,
+                    split: cfg.experiments.split.clamp(0.0, 1.0),
+                });
+            }
+            Err(e) => warn!(
+                "Prompt experiment disabled: can't read {}: {}",
+                path.display(),
+                e
+            ),
+        }
+    }
     if let Some(ref tracker) = usage_tracker {
         agent = agent.with_usage_tracker(tracker.clone());
     }
+    let catalog = meepo_core::tools::ToolExecutor::list_tools(registry.as_ref())
+        .into_iter()
+        .map(|t| {
+            let chars = serde_json::to_string(&t).map_or(0, |json| json.len());
+            (t.name, chars)
+        })
+        .collect();
+    if let Err(e) = db.sync_tool_catalog(catalog).await {
+        warn!("Failed to record tool catalog: {}", e);
+    }
+    if cfg.tool_usage.prune_unused {
+        agent = agent.with_tool_pruning(cfg.tool_usage.unused_days.max(1));
+        info!(
+            "Tools unused for {} days are left out of prompts",
+            cfg.tool_usage.unused_days.max(1)
+        );
+    }
     let agent = Arc::new(agent);
 
     // Initialize watcher scheduler
     let (watcher_event_tx, mut watcher_event_rx) = tokio::sync::mpsc::unbounded_channel();
     let watcher_runner = Arc::new(tokio::sync::Mutex::new(
-        meepo_scheduler::runner::WatcherRunner::new(watcher_event_tx),
+        meepo_scheduler::runner::WatcherRunner::with_config(
+            watcher_event_tx,
+            meepo_scheduler::runner::WatcherConfig {
+                timezone,
+                ..Default::default()
+            },
+        ),
     ));
 
     // Initialize scheduler database (kept alive for runtime persistence)
@@ -2033,6 +3000,30 @@ async fn cmd_start(config_path: &Option<PathBuf>) -> Result<()> {
     }
     info!("Watcher scheduler initialized");
 
+    // Battery-aware throttling: pause polling watchers in low-power mode
+    let power_monitor = meepo_core::power::PowerMonitor::new(meepo_core::power::PowerConfig {
+        enabled: cfg.power.enabled,
+        low_battery_percent: cfg.power.low_battery_percent,
+        tick_multiplier: cfg.power.tick_multiplier,
+        pause_polling_watchers: cfg.power.pause_polling_watchers,
+        defer_background_tasks: cfg.power.defer_background_tasks,
+        poll_secs: cfg.power.poll_secs,
+    });
+    tokio::spawn(power_monitor.clone().run(cancel.clone()));
+    {
+        let power = power_monitor.clone();
+        let runner = watcher_runner.clone();
+        let mut power_rx = power.subscribe();
+        tokio::spawn(async move {
+            while power_rx.changed().await.is_ok() {
+                runner
+                    .lock()
+                    .await
+                    .set_polling_paused(power.pause_watchers());
+            }
+        });
+    }
+
     // Initialize message bus
     let mut bus = meepo_channels::bus::MessageBus::new(256);
 
@@ -2046,14 +3037,21 @@ async fn cmd_start(config_path: &Option<PathBuf>) -> Result<()> {
         info!("Discord channel registered");
     }
 
+    // Voice notes arriving on iMessage or email (macOS only)
+    #[cfg(target_os = "macos")]
+    let transcriber = voice_note_transcriber(&cfg);
+
     // Register iMessage channel if enabled (macOS only)
     #[cfg(target_os = "macos")]
     if cfg.channels.imessage.enabled {
-        let imessage = meepo_channels::imessage::IMessageChannel::new(
+        let mut imessage = meepo_channels::imessage::IMessageChannel::new(
             std::time::Duration::from_secs(cfg.channels.imessage.poll_interval_secs),
             cfg.channels.imessage.allowed_contacts.clone(),
             None,
         );
+        if let Some(transcriber) = &transcriber {
+            imessage = imessage.with_transcriber(transcriber.clone());
+        }
         bus.register(Box::new(imessage));
         info!("iMessage channel registered");
     }
@@ -2068,25 +3066,39 @@ async fn cmd_start(config_path: &Option<PathBuf>) -> Result<()> {
             shellexpand_str(&cfg.channels.slack.bot_token),
             std::time::Duration::from_secs(cfg.channels.slack.poll_interval_secs),
             cfg.channels.slack.allowed_users.clone(),
-        );
+        )
+        .with_app_token(shellexpand_str(&cfg.channels.slack.app_token));
         bus.register(Box::new(slack));
         info!("Slack channel registered");
     }
 
-    // Register Email channel if enabled (macOS only — uses Mail.app)
+    // Register Email channel if enabled: IMAP/SMTP anywhere, Mail.app on macOS
+    let imap_email = cfg.channels.email.backend == config::EmailBackendKind::Imap;
+    if cfg.channels.email.enabled && imap_email {
+        match cfg.channels.email.to_imap_config() {
+            Ok(imap) => {
+                bus.register(Box::new(meepo_channels::ImapEmailChannel::new(imap)));
+                info!("Email channel registered (IMAP)");
+            }
+            Err(e) => warn!("Email channel not started: {}", e),
+        }
+    }
     #[cfg(target_os = "macos")]
-    if cfg.channels.email.enabled {
-        let email = meepo_channels::email::EmailChannel::new(
+    if cfg.channels.email.enabled && !imap_email {
+        let mut email = meepo_channels::email::EmailChannel::new(
             std::time::Duration::from_secs(cfg.channels.email.poll_interval_secs),
             cfg.channels.email.subject_prefix.clone(),
         );
+        if let Some(transcriber) = &transcriber {
+            email = email.with_transcriber(transcriber.clone());
+        }
         bus.register(Box::new(email));
         info!("Email channel registered");
     }
     #[cfg(not(target_os = "macos"))]
-    if cfg.channels.email.enabled {
+    if cfg.channels.email.enabled && !imap_email {
         warn!(
-            "Email channel (Mail.app) is only available on macOS — use the read_emails/send_email tools for Outlook on Windows"
+            "Email channel (Mail.app) is only available on macOS — set channels.email.backend = \"imap\" to use an IMAP/SMTP account"
         );
     }
 
@@ -2146,6 +3158,12 @@ async fn cmd_start(config_path: &Option<PathBuf>) -> Result<()> {
         warn!("Contacts channel is only available on macOS — ignoring");
     }
 
+    // Register the scripted channel when simulating
+    if let Some(channel) = sim.as_mut().and_then(|s| s.take_channel()) {
+        bus.register(Box::new(channel));
+        info!("Simulated channel registered");
+    }
+
     // Start all channels
     bus.start_all().await?;
     info!("All message channels started");
@@ -2161,13 +3179,114 @@ async fn cmd_start(config_path: &Option<PathBuf>) -> Result<()> {
 
     let (loop_msg_tx, loop_msg_rx) =
         tokio::sync::mpsc::channel::<meepo_core::types::IncomingMessage>(256);
-    let (loop_resp_tx, mut loop_resp_rx) =
-        tokio::sync::mpsc::channel::<meepo_core::types::OutgoingMessage>(256);
     let wake = meepo_core::autonomy::AutonomousLoop::create_wake_handle();
 
-    // Forward incoming bus messages to the autonomous loop
+    // Notifications the user can snooze, and their follow-ups
+    let snoozes = if cfg.notifications.enabled && cfg.notifications.snooze {
+        let escalate_after = (cfg.notifications.escalate_after_mins > 0)
+            .then(|| std::time::Duration::from_secs(cfg.notifications.escalate_after_mins * 60));
+        let store = meepo_core::snooze::SnoozeStore::new(meepo_core::snooze::SnoozeConfig {
+            default_snooze: std::time::Duration::from_secs(
+                cfg.notifications.snooze_mins.max(1) * 60,
+            ),
+            escalate_after,
+        })
+        .with_db(db.clone());
+        if let Err(e) = store.load().await {
+            warn!("Failed to restore snoozed notifications: {}", e);
+        }
+        Some(Arc::new(store))
+    } else {
+        None
+    };
+
+    // Screen inbound messages on public-ish channels before they cost an
+    // agent run; released ones are picked up from the quarantine queue
+    let spam_filter = if cfg.spam_filter.enabled {
+        let filter = meepo_core::spam_filter::SpamFilter::new(
+            cfg.spam_filter.to_filter_config(),
+            db.clone(),
+        )
+        .with_api(api.clone());
+        let filter = match &usage_tracker {
+            Some(tracker) => filter.with_usage_tracker(tracker.clone()),
+            None => filter,
+        };
+        let released_db = db.clone();
+        let released_tx = loop_msg_tx.clone();
+        let released_wake = wake.clone();
+        let released_cancel = cancel.clone();
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(std::time::Duration::from_secs(30));
+            loop {
+                tokio::select! {
+                    _ = released_cancel.cancelled() => break,
+                    _ = interval.tick() => {}
+                }
+                match meepo_core::spam_filter::take_released(&released_db).await {
+                    Ok(messages) => {
+                        for msg in messages {
+                            info!(
+                                "Released from quarantine: message from {} via {}",
+                                msg.sender, msg.channel
+                            );
+                            if released_tx.send(msg).await.is_err() {
+                                return;
+                            }
+                            released_wake.notify_one();
+                        }
+                    }
+                    Err(e) => warn!("Failed to read released messages: {}", e),
+                }
+            }
+        });
+        info!(
+            "Spam filter enabled for {}",
+            cfg.spam_filter.channels.join(", ")
+        );
+        Some(Arc::new(filter))
+    } else {
+        None
+    };
+
+    // Store memories the user approved with `meepo memory approve`. Runs
+    // with review off too, so approvals made before turning it off land.
+    {
+        let graph = knowledge_graph.clone();
+        let memory_path = workspace.join(&cfg.agent.memory_file);
+        let memory_types = cfg.knowledge.review.memory_types.clone();
+        let review_cancel = cancel.clone();
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(std::time::Duration::from_secs(60));
+            loop {
+                tokio::select! {
+                    _ = review_cancel.cancelled() => break,
+                    _ = interval.tick() => {}
+                }
+                match meepo_core::memory_review::promote_approved(
+                    &graph,
+                    &memory_path,
+                    &memory_types,
+                )
+                .await
+                {
+                    Ok(0) => {}
+                    Ok(n) => info!("Stored {} approved memories", n),
+                    Err(e) => warn!("Failed to store approved memories: {}", e),
+                }
+            }
+        });
+    }
+
+    // Forward incoming bus messages to the autonomous loop, unless they're
+    // quarantined as spam, answer a question a background task is waiting
+    // on or snooze a notification
     let wake_clone = wake.clone();
     let cancel_clone = cancel.clone();
+    let questions_for_bus = pending_questions.clone();
+    let snoozes_for_bus = snoozes.clone();
+    let spam_filter_for_bus = spam_filter.clone();
+    let ack_tx = loop_resp_tx.clone();
     let bus_to_loop = tokio::spawn(async move {
         loop {
             tokio::select! {
@@ -2179,6 +3298,47 @@ async fn cmd_start(config_path: &Option<PathBuf>) -> Result<()> {
                                 incoming.sender,
                                 incoming.channel,
                                 &incoming.content[..incoming.content.len().min(100)]);
+                            if let Some(filter) = &spam_filter_for_bus
+                                && filter.check(&incoming).await != meepo_core::spam_filter::Verdict::Allow
+                            {
+                                continue;
+                            }
+                            if let Some(question) = questions_for_bus.try_answer(&incoming).await {
+                                let _ = ack_tx.send(meepo_core::types::OutgoingMessage {
+                                    content: match &question.task_id {
+                                        Some(task_id) => format!("Got it — resuming task [{}].", task_id),
+                                        None => "Got it.".to_string(),
+                                    },
+                                    channel: incoming.channel.clone(),
+                                    reply_to: Some(incoming.id.clone()),
+                                    kind: meepo_core::types::MessageKind::Response,
+                                    interactive: None,
+                                }).await;
+                                continue;
+                            }
+                            // A button on a question that's been answered or timed out
+                            if meepo_core::types::Interactive::parse_press(&incoming.content).is_some() {
+                                let _ = ack_tx.send(meepo_core::types::OutgoingMessage {
+                                    content: "That question was already answered or has expired.".to_string(),
+                                    channel: incoming.channel.clone(),
+                                    reply_to: Some(incoming.id.clone()),
+                                    kind: meepo_core::types::MessageKind::Response,
+                                    interactive: None,
+                                }).await;
+                                continue;
+                            }
+                            if let Some(snoozes) = &snoozes_for_bus
+                                && let Some(reply) = snoozes.handle(&incoming).await
+                            {
+                                let _ = ack_tx.send(meepo_core::types::OutgoingMessage {
+                                    content: reply,
+                                    channel: incoming.channel.clone(),
+                                    reply_to: Some(incoming.id.clone()),
+                                    kind: meepo_core::types::MessageKind::Response,
+                                    interactive: None,
+                                }).await;
+                                continue;
+                            }
                             if loop_msg_tx.send(incoming).await.is_err() {
                                 break;
                             }
@@ -2221,6 +3381,18 @@ async fn cmd_start(config_path: &Option<PathBuf>) -> Result<()> {
             let end = chrono::NaiveTime::parse_from_str(&qh.end, "%H:%M").ok()?;
             Some((start, end))
         });
+        let routes = nc
+            .routes
+            .iter()
+            .enumerate()
+            .filter_map(|(i, route)| match notify_route(route) {
+                Ok(route) => Some(route),
+                Err(e) => {
+                    warn!("Ignoring notification route {}: {}", i + 1, e);
+                    None
+                }
+            })
+            .collect();
         let notify_config = meepo_core::notifications::NotifyConfig {
             enabled: nc.enabled,
             channel: meepo_core::types::ChannelType::from_string(&nc.channel),
@@ -2231,13 +3403,45 @@ async fn cmd_start(config_path: &Option<PathBuf>) -> Result<()> {
             on_autonomous_action: nc.on_autonomous_action,
             on_error: nc.on_error,
             quiet_hours,
+            timezone,
+            language: cfg.agent.language.clone(),
+            routes,
+            ntfy: nc
+                .ntfy
+                .as_ref()
+                .map(|ntfy| meepo_core::notifications::NtfyConfig {
+                    server: ntfy.server.clone(),
+                    topic: ntfy.topic.clone(),
+                }),
         };
-        meepo_core::notifications::NotificationService::new(notify_config, loop_resp_tx.clone())
-    };
+        let notifier = meepo_core::notifications::NotificationService::new(
+            notify_config,
+            loop_resp_tx.clone(),
+        );
+        let notifier = match &snoozes {
+            Some(snoozes) => notifier.with_snoozes(snoozes.clone()),
+            None => notifier,
+        };
+        match &push_registry {
+            Some(registry) => notifier.with_push(Arc::new(push_bridge(&cfg, registry.clone()))),
+            None => notifier,
+        }
+    };
+    if snoozes.is_some() {
+        tokio::spawn(notifier.clone().run_follow_ups(cancel.clone()));
+    }
 
     // Clone bus_sender for background task handler before it moves into resp_to_bus
     let bus_sender_for_bg = bus_sender.clone();
 
+    // Replies also spoken on this Mac, per channel
+    let read_aloud = read_aloud(&cfg, timezone);
+
+    // Gateway clients also see replies as they're written, once it's up
+    let gateway_events: Arc<std::sync::OnceLock<meepo_gateway::events::EventBus>> =
+        Arc::new(std::sync::OnceLock::new());
+    let reply_mirror = gateway_events.clone();
+
     // Forward loop responses to the bus sender
     let cancel_clone3 = cancel.clone();
     let resp_to_bus = tokio::spawn(async move {
@@ -2247,6 +3451,20 @@ async fn cmd_start(config_path: &Option<PathBuf>) -> Result<()> {
                 resp = loop_resp_rx.recv() => {
                     match resp {
                         Some(msg) => {
+                            if let Some(speaker) = &read_aloud
+                                && speaker.should_speak(&msg)
+                            {
+                                let speaker = speaker.clone();
+                                let content = msg.content.clone();
+                                tokio::spawn(async move {
+                                    if let Err(e) = speaker.speak(&content).await {
+                                        warn!("Failed to read reply aloud: {}", e);
+                                    }
+                                });
+                            }
+                            if let Some(events) = reply_mirror.get() {
+                                events.mirror_reply(&msg);
+                            }
                             let channel = msg.channel.clone();
                             if let Err(e) = bus_sender.send(msg).await {
                                 // Internal channel has no handler — this is expected
@@ -2277,35 +3495,10 @@ async fn cmd_start(config_path: &Option<PathBuf>) -> Result<()> {
                             use meepo_core::tools::watchers::WatcherCommand;
                             match command {
                                 WatcherCommand::Create { id, kind, config, action, reply_channel } => {
-                                    // Map the tool's kind string to WatcherKind's serde tag variant name
-                                    let type_tag = match kind.as_str() {
-                                        "email" => "EmailWatch",
-                                        "calendar" => "CalendarWatch",
-                                        "github" => "GitHubWatch",
-                                        "file" => "FileWatch",
-                                        "message" => "MessageWatch",
-                                        "scheduled" | "time" => "Scheduled",
-                                        "oneshot" => "OneShot",
-                                        other => {
-                                            error!("Unknown watcher kind: {}", other);
-                                            return;
-                                        }
-                                    };
-                                    // Inject the "type" tag into config for serde deserialization
-                                    let config_with_type = match config {
-                                        serde_json::Value::Object(mut map) => {
-                                            map.insert("type".to_string(), serde_json::Value::String(type_tag.to_string()));
-                                            serde_json::Value::Object(map)
-                                        }
-                                        _ => {
-                                            error!("Watcher config is not a JSON object");
-                                            return;
-                                        }
-                                    };
-                                    let watcher_kind: meepo_scheduler::watcher::WatcherKind = match serde_json::from_value(config_with_type) {
+                                    let watcher_kind = match watcher_kind_from_tool(&kind, config) {
                                         Ok(k) => k,
                                         Err(e) => {
-                                            error!("Failed to deserialize watcher kind: {}", e);
+                                            error!("{}", e);
                                             return;
                                         }
                                     };
@@ -2356,6 +3549,25 @@ async fn cmd_start(config_path: &Option<PathBuf>) -> Result<()> {
         gh_path: shellexpand_str(&cfg.code.gh_path),
         default_workspace: shellexpand_str(&cfg.code.default_workspace),
     };
+    // Shared with the autonomous loop so background tasks yield to user conversations
+    let activity_gate = meepo_core::autonomy::priority::ActivityGate::new(
+        cfg.autonomy.max_actions_during_chat,
+        std::time::Duration::from_secs(cfg.autonomy.chat_active_secs),
+    );
+    let activity_gate_bg = activity_gate.clone();
+    let power_monitor_bg = power_monitor.clone();
+    // Heavy tasks wait for the host to be idle
+    let idle_monitor = Arc::new(meepo_core::autonomy::idle::IdleMonitor::new(
+        meepo_core::autonomy::idle::IdleConfig {
+            enabled: cfg.autonomy.idle.enabled,
+            min_idle_secs: cfg.autonomy.idle.min_idle_secs,
+            require_ac_power: cfg.autonomy.idle.require_ac_power,
+            locked_counts_as_idle: cfg.autonomy.idle.locked_counts_as_idle,
+            max_defer_secs: cfg.autonomy.idle.max_defer_secs,
+            poll_secs: cfg.autonomy.idle.poll_secs,
+        },
+    ));
+    let idle_monitor_tag = idle_monitor.clone();
     let bg_task_handler = tokio::spawn(async move {
         // Track cancellation tokens for background tasks
         let task_cancels = Arc::new(tokio::sync::Mutex::new(std::collections::HashMap::<
@@ -2368,7 +3580,7 @@ async fn cmd_start(config_path: &Option<PathBuf>) -> Result<()> {
                 _ = cancel_clone_bg.cancelled() => break,
                 cmd = bg_task_rx.recv() => {
                     match cmd {
-                        Some(meepo_core::tools::autonomous::BackgroundTaskCommand::Spawn { id, description, reply_channel }) => {
+                        Some(meepo_core::tools::autonomous::BackgroundTaskCommand::Spawn { id, description, reply_channel, heavy }) => {
                             info!("Spawning background task [{}]: {}", id, description);
                             let task_cancel = tokio_util::sync::CancellationToken::new();
                             task_cancels.lock().await.insert(id.clone(), task_cancel.clone());
@@ -2380,8 +3592,27 @@ async fn cmd_start(config_path: &Option<PathBuf>) -> Result<()> {
                             let task_cancels = task_cancels.clone();
                             let id_clone = id.clone();
                             let reply_channel_clone = reply_channel.clone();
+                            let activity = activity_gate_bg.clone();
+                            let idle = idle_monitor.clone();
+                            let power = power_monitor_bg.clone();
 
                             tokio::spawn(async move {
+                                // Wait out low-power mode; heavy tasks also wait for an idle window
+                                let what = format!("background task [{}]", id_clone);
+                                tokio::select! {
+                                    _ = task_cancel.cancelled() => {
+                                        let _ = db.update_background_task(&id_clone, "cancelled", Some("Task cancelled")).await;
+                                        task_cancels.lock().await.remove(&id_clone);
+                                        return;
+                                    }
+                                    _ = async {
+                                        power.wait_for_power(&what).await;
+                                        if heavy {
+                                            idle.wait_for_idle(&what).await;
+                                        }
+                                    } => {}
+                                }
+
                                 // Notify user that task is starting
                                 notifier.notify(meepo_core::notifications::NotifyEvent::TaskStarted {
                                     task_id: id_clone.clone(),
@@ -2406,7 +3637,17 @@ async fn cmd_start(config_path: &Option<PathBuf>) -> Result<()> {
                                     _ = task_cancel.cancelled() => {
                                         Err(anyhow::anyhow!("Task cancelled"))
                                     }
-                                    result = agent.handle_message(msg) => result
+                                    result = async {
+                                        // Wait while the user is chatting and the cap is reached
+                                        let _permit = activity
+                                            .acquire(meepo_core::autonomy::priority::Priority::Background)
+                                            .await;
+                                        meepo_core::questions::scope_task(
+                                            id_clone.clone(),
+                                            agent.handle_message(msg),
+                                        )
+                                        .await
+                                    } => result
                                 };
 
                                 match result {
@@ -2426,6 +3667,7 @@ async fn cmd_start(config_path: &Option<PathBuf>) -> Result<()> {
                                             channel: meepo_core::types::ChannelType::from_string(&reply_channel_clone),
                                             reply_to: None,
                                             kind: meepo_core::types::MessageKind::Response,
+                                            interactive: None,
                                         };
                                         let _ = bus.send(notify_msg).await;
                                     }
@@ -2446,6 +3688,7 @@ async fn cmd_start(config_path: &Option<PathBuf>) -> Result<()> {
                                                 channel: meepo_core::types::ChannelType::from_string(&reply_channel_clone),
                                                 reply_to: None,
                                                 kind: meepo_core::types::MessageKind::Response,
+                                                interactive: None,
                                             };
                                             let _ = bus.send(notify_msg).await;
                                         }
@@ -2466,8 +3709,24 @@ async fn cmd_start(config_path: &Option<PathBuf>) -> Result<()> {
                             let notifier = notifier_bg.clone();
                             let task_cancels = task_cancels.clone();
                             let agent_path = code_config_bg.coding_agent_path.clone();
+                            let idle = idle_monitor.clone();
+                            let power = power_monitor_bg.clone();
 
                             tokio::spawn(async move {
+                                // Coding agents are heavy — wait for power and an idle window first
+                                let what = format!("coding agent [{}]", id);
+                                tokio::select! {
+                                    _ = task_cancel.cancelled() => {
+                                        let _ = db.update_background_task(&id, "cancelled", Some("Task cancelled")).await;
+                                        task_cancels.lock().await.remove(&id);
+                                        return;
+                                    }
+                                    _ = async {
+                                        power.wait_for_power(&what).await;
+                                        idle.wait_for_idle(&what).await;
+                                    } => {}
+                                }
+
                                 // Notify user that task is starting
                                 notifier.notify(meepo_core::notifications::NotifyEvent::TaskStarted {
                                     task_id: id.clone(),
@@ -2504,6 +3763,7 @@ async fn cmd_start(config_path: &Option<PathBuf>) -> Result<()> {
                                             channel: meepo_core::types::ChannelType::from_string(&reply_channel),
                                             reply_to: None,
                                             kind: meepo_core::types::MessageKind::Response,
+                                            interactive: None,
                                         };
                                         let _ = bus.send(notify).await;
                                         task_cancels.lock().await.remove(&id);
@@ -2564,6 +3824,7 @@ async fn cmd_start(config_path: &Option<PathBuf>) -> Result<()> {
                                             channel: meepo_core::types::ChannelType::from_string(&reply_channel),
                                             reply_to: None,
                                             kind: meepo_core::types::MessageKind::Response,
+                                            interactive: None,
                                         };
                                         let _ = bus.send(notify).await;
                                     }
@@ -2584,6 +3845,7 @@ async fn cmd_start(config_path: &Option<PathBuf>) -> Result<()> {
                                                 channel: meepo_core::types::ChannelType::from_string(&reply_channel),
                                                 reply_to: None,
                                                 kind: meepo_core::types::MessageKind::Response,
+                                                interactive: None,
                                             };
                                             let _ = bus.send(notify).await;
                                         }
@@ -2629,11 +3891,17 @@ async fn cmd_start(config_path: &Option<PathBuf>) -> Result<()> {
         tick_interval_secs: cfg.autonomy.tick_interval_secs,
         max_goals: cfg.autonomy.max_goals,
         send_acknowledgments: cfg.autonomy.send_acknowledgments,
+        stream_responses: cfg.autonomy.stream_responses,
         daily_plan_hour: cfg.autonomy.daily_plan_hour,
+        timezone,
         max_calls_per_minute: cfg.autonomy.max_calls_per_minute,
+        max_actions_during_chat: cfg.autonomy.max_actions_during_chat,
+        chat_active_secs: cfg.autonomy.chat_active_secs,
     };
 
-    let auto_loop = meepo_core::autonomy::AutonomousLoop::new(
+    let report_tx = loop_resp_tx.clone();
+    let meeting_tx = loop_resp_tx.clone();
+    let mut auto_loop = meepo_core::autonomy::AutonomousLoop::new(
         agent.clone(),
         db.clone(),
         autonomy_config,
@@ -2642,7 +3910,44 @@ async fn cmd_start(config_path: &Option<PathBuf>) -> Result<()> {
         loop_resp_tx,
         notifier.clone(),
         wake,
-    );
+    )
+    .with_activity_gate(activity_gate)
+    .with_power_monitor(power_monitor)
+    .with_research_budget(cfg.knowledge.gaps.research_budget_usd)
+    .with_rules(
+        meepo_core::rules::RuleEngine::new(db.clone(), registry.clone())
+            .with_templates(message_templates.clone()),
+    )
+    .with_document_refresh(ingest_tool.clone());
+
+    if cfg.email_triage.enabled {
+        match meepo_core::platform::create_email_provider() {
+            Ok(provider) => {
+                let triage = &cfg.email_triage;
+                let triage_config = meepo_core::email_triage::EmailTriageConfig {
+                    enabled: true,
+                    model: triage.model.clone(),
+                    flag_urgent: triage.flag_urgent,
+                    flag_action: triage.flag_action,
+                    newsletter_mailbox: (!triage.newsletter_mailbox.is_empty())
+                        .then(|| triage.newsletter_mailbox.clone()),
+                    draft_replies: triage.draft_replies,
+                    learn_after: triage.learn_after,
+                    summarize: triage.summarize,
+                    long_email_chars: triage.long_email_chars,
+                    long_thread_messages: triage.long_thread_messages,
+                };
+                auto_loop =
+                    auto_loop.with_email_triage(meepo_core::email_triage::EmailTriager::new(
+                        triage_config,
+                        db.clone(),
+                        Arc::from(provider),
+                    ));
+                info!("Email triage enabled (model: {})", triage.model);
+            }
+            Err(e) => warn!("Email triage disabled: {}", e),
+        }
+    }
 
     let cancel_clone6 = cancel.clone();
     let loop_task = tokio::spawn(async move {
@@ -2687,8 +3992,8 @@ async fn cmd_start(config_path: &Option<PathBuf>) -> Result<()> {
             );
 
             loop {
-                // Find the next digest time
-                let now = chrono::Utc::now();
+                // Find the next digest time (crons are in the user's timezone)
+                let now = chrono::Utc::now().with_timezone(&timezone);
                 let next_morning = morning_schedule.as_ref().and_then(|s| s.after(&now).next());
                 let next_evening = evening_schedule.as_ref().and_then(|s| s.after(&now).next());
 
@@ -2746,6 +4051,174 @@ async fn cmd_start(config_path: &Option<PathBuf>) -> Result<()> {
         None
     };
 
+    // ── Auto-Tagging ────────────────────────────────────────────
+    let tag_task = if cfg.knowledge.auto_tag.enabled {
+        let cancel_tag = cancel.clone();
+        let db_tag = db.clone();
+        let tag_cfg = cfg.knowledge.auto_tag.clone();
+        let interval = std::time::Duration::from_secs(tag_cfg.interval_hours.max(1) * 3600);
+        let tagger = meepo_knowledge::AutoTagger::new(
+            Box::new(meepo_knowledge::HashEmbeddingProvider::new(256)),
+            meepo_knowledge::AutoTagConfig {
+                similarity_threshold: tag_cfg.similarity_threshold,
+                min_cluster_size: tag_cfg.min_cluster_size,
+                max_conversations: tag_cfg.max_conversations,
+            },
+        );
+
+        Some(tokio::spawn(async move {
+            info!(
+                "Auto-tagger started (every {}h)",
+                tag_cfg.interval_hours.max(1)
+            );
+            loop {
+                // Clustering touches every entity — wait for an idle window
+                tokio::select! {
+                    _ = cancel_tag.cancelled() => break,
+                    _ = idle_monitor_tag.wait_for_idle("auto-tagging") => {}
+                }
+                if let Err(e) = tagger.run(&db_tag).await {
+                    warn!("Auto-tagging failed: {}", e);
+                }
+                tokio::select! {
+                    _ = cancel_tag.cancelled() => break,
+                    _ = tokio::time::sleep(interval) => {}
+                }
+            }
+            info!("Auto-tagger shutting down");
+        }))
+    } else {
+        None
+    };
+
+    // ── Scheduled Reports ───────────────────────────────────────
+    let report_task = if cfg.reports.is_empty() {
+        None
+    } else {
+        let cancel_reports = cancel.clone();
+        let definitions = report_definitions(&cfg.reports, &workspace)?;
+        let scheduler =
+            meepo_core::reports::ReportScheduler::new(definitions, db.clone(), workspace.clone())
+                .with_outgoing(report_tx);
+        Some(tokio::spawn(scheduler.run(cancel_reports)))
+    };
+
+    // ── Meeting Prep ────────────────────────────────────────────
+    let meeting_task = if cfg.meeting_prep.enabled {
+        match meepo_core::meeting_prep::MeetingPrep::for_platform(db.clone()) {
+            Ok(prep) => {
+                let channel = if cfg.meeting_prep.channel.is_empty() {
+                    &cfg.notifications.channel
+                } else {
+                    &cfg.meeting_prep.channel
+                };
+                let prep_config = meepo_core::meeting_prep::MeetingPrepConfig {
+                    enabled: true,
+                    lead_minutes: cfg.meeting_prep.lead_minutes,
+                    poll_secs: cfg.meeting_prep.poll_secs,
+                    channel: meepo_core::types::ChannelType::from_string(channel),
+                };
+                Some(tokio::spawn(prep.run(
+                    prep_config,
+                    meeting_tx,
+                    cancel.clone(),
+                )))
+            }
+            Err(e) => {
+                warn!("Meeting prep disabled: {}", e);
+                None
+            }
+        }
+    } else {
+        None
+    };
+
+    // ── Task Sync ───────────────────────────────────────────────
+    let task_sync_task = if cfg.task_sync.enabled {
+        match task_sync_backend(&cfg.task_sync) {
+            Ok(backend) => {
+                let sync = meepo_core::task_sync::TaskSync::new(db.clone(), backend)
+                    .with_goals(cfg.task_sync.include_goals);
+                let interval = std::time::Duration::from_secs(cfg.task_sync.interval_secs.max(60));
+                Some(tokio::spawn(sync.run(interval, cancel.clone())))
+            }
+            Err(e) => {
+                warn!("Task sync disabled: {}", e);
+                None
+            }
+        }
+    } else {
+        None
+    };
+
+    // ── Focus Modes ─────────────────────────────────────────────
+    let focus_task = if cfg.focus.enabled {
+        match meepo_core::platform::create_focus_provider() {
+            Ok(provider) => {
+                let monitor = meepo_core::focus::FocusMonitor::new(
+                    meepo_core::focus::FocusConfig {
+                        enabled: true,
+                        poll_secs: cfg.focus.poll_secs,
+                        quiet_modes: cfg.focus.quiet_modes.clone(),
+                    },
+                    provider,
+                );
+                Some(tokio::spawn(monitor.run(cancel.clone())))
+            }
+            Err(e) => {
+                warn!("Focus awareness disabled: {}", e);
+                None
+            }
+        }
+    } else {
+        None
+    };
+
+    // ── Modes ───────────────────────────────────────────────────
+    let modes_task = if modes.modes.is_empty() {
+        None
+    } else {
+        let monitor = meepo_core::modes::ModeMonitor::new(db.clone(), modes.clone());
+        Some(tokio::spawn(monitor.run(cancel.clone())))
+    };
+
+    // ── App Usage ───────────────────────────────────────────────
+    let app_usage_task = if cfg.app_usage.enabled {
+        match meepo_core::platform::create_active_app_provider() {
+            Ok(provider) => {
+                let tracker = meepo_core::app_usage::AppUsageTracker::new(
+                    db.clone(),
+                    provider,
+                    meepo_core::app_usage::AppUsageConfig {
+                        enabled: true,
+                        sample_secs: cfg.app_usage.sample_secs,
+                        idle_secs: cfg.app_usage.idle_secs,
+                        exclude: cfg.app_usage.exclude.clone(),
+                    },
+                );
+                Some(tokio::spawn(tracker.run(cancel.clone())))
+            }
+            Err(e) => {
+                warn!("App-usage tracking disabled: {}", e);
+                None
+            }
+        }
+    } else {
+        None
+    };
+
+    // ── Artifact Sync ───────────────────────────────────────────
+    let artifact_sync_task = match artifact_sync {
+        Some(sync) => match meepo_core::reports::parse_schedule(&cfg.artifact_sync.schedule) {
+            Ok(schedule) => Some(tokio::spawn(sync.run(schedule, cancel.clone()))),
+            Err(e) => {
+                warn!("Scheduled artifact sync disabled: {}", e);
+                None
+            }
+        },
+        None => None,
+    };
+
     // ── Phase 3: A2A Server ─────────────────────────────────────
     if cfg.a2a.enabled {
         let a2a_card = meepo_a2a::AgentCard {
@@ -2762,12 +4235,13 @@ async fn cmd_start(config_path: &Option<PathBuf>) -> Result<()> {
                 "code_review".to_string(),
             ],
             authentication: meepo_a2a::AuthConfig {
-                schemes: if cfg.a2a.auth_token.is_empty() {
+                schemes: if cfg.a2a.auth_token.is_empty() && cfg.a2a.peers.is_empty() {
                     vec![]
                 } else {
                     vec!["bearer".to_string()]
                 },
             },
+            profiles: vec![],
         };
 
         let auth_token = {
@@ -2775,13 +4249,54 @@ async fn cmd_start(config_path: &Option<PathBuf>) -> Result<()> {
             if t.is_empty() { None } else { Some(t) }
         };
 
-        let a2a_server = Arc::new(meepo_a2a::A2aServer::new(
+        if cfg.a2a.replication.enabled && auth_token.is_none() && cfg.a2a.peers.is_empty() {
+            warn!("Knowledge replication needs a2a.auth_token; peers won't be able to sync");
+        }
+        let peers = cfg
+            .a2a
+            .peers
+            .iter()
+            .filter_map(|p| {
+                let token = shellexpand_str(&p.token);
+                if token.is_empty() {
+                    warn!("A2A peer '{}' has no token; ignoring it", p.name);
+                    return None;
+                }
+                let profile = match &p.profile {
+                    Some(name) => match tool_profile(&cfg, name) {
+                        Ok(profile) => Some(profile),
+                        Err(e) => {
+                            warn!("A2A peer '{}': {}; ignoring it", p.name, e);
+                            return None;
+                        }
+                    },
+                    None => None,
+                };
+                Some(meepo_a2a::PeerIdentity {
+                    name: p.name.clone(),
+                    token,
+                    tools: p.tools.clone(),
+                    requests_per_hour: p.requests_per_hour,
+                    requests_per_day: p.requests_per_day,
+                    replication: p.replication,
+                    profile,
+                })
+            })
+            .collect();
+        let mut a2a_server = meepo_a2a::A2aServer::new(
             agent.clone(),
             registry.clone(),
             a2a_card,
             auth_token,
             cfg.a2a.allowed_tools.clone(),
-        ));
+        )
+        .with_peers(peers)
+        .with_profiles(cfg.tool_profiles.iter().map(|p| p.to_profile()).collect())
+        .with_audit(db.clone());
+        if cfg.a2a.replication.enabled {
+            a2a_server = a2a_server.with_replication(knowledge_graph.clone());
+        }
+        let a2a_server = Arc::new(a2a_server);
 
         let a2a_port = cfg.a2a.port;
         tokio::spawn(async move {
@@ -2792,6 +4307,62 @@ async fn cmd_start(config_path: &Option<PathBuf>) -> Result<()> {
         info!("A2A server started on port {}", cfg.a2a.port);
     }
 
+    // ── Knowledge Replication ───────────────────────────────────
+    let replication_task = if cfg.a2a.enabled && cfg.a2a.replication.enabled {
+        let mut peers = Vec::new();
+        for name in &cfg.a2a.replication.peers {
+            match cfg.a2a.agents.iter().find(|a| &a.name == name) {
+                Some(a) => peers.push(meepo_a2a::PeerAgentConfig {
+                    name: a.name.clone(),
+                    url: shellexpand_str(&a.url),
+                    token: if a.token.is_empty() {
+                        None
+                    } else {
+                        Some(shellexpand_str(&a.token))
+                    },
+                }),
+                None => warn!("Replication peer '{}' is not in [[a2a.agents]]", name),
+            }
+        }
+        if peers.is_empty() {
+            None
+        } else {
+            let replicator = meepo_a2a::Replicator::new(knowledge_graph.clone(), peers);
+            let interval =
+                std::time::Duration::from_secs(cfg.a2a.replication.interval_secs.max(30));
+            Some(tokio::spawn(replicator.run(interval, cancel.clone())))
+        }
+    } else {
+        None
+    };
+
+    // Embed entities that don't have a vector yet
+    if knowledge_graph.has_embeddings() {
+        let graph = knowledge_graph.clone();
+        let batch = cfg.rag.embeddings.batch_size.max(1) * 4;
+        let interval = std::time::Duration::from_secs(cfg.rag.embeddings.interval_secs.max(30));
+        let embed_cancel = cancel.clone();
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            loop {
+                tokio::select! {
+                    _ = embed_cancel.cancelled() => break,
+                    _ = ticker.tick() => {
+                        // Keep going while each pass fills a whole batch
+                        loop {
+                            match graph.embed_missing(batch).await {
+                                Ok(n) if n >= batch => continue,
+                                Ok(_) => {}
+                                Err(e) => warn!("Embedding backfill failed: {}", e),
+                            }
+                            break;
+                        }
+                    }
+                }
+            }
+        });
+    }
+
     // Start Gateway (WebSocket control plane) if enabled
     if cfg.gateway.enabled {
         let bind_addr: std::net::SocketAddr = format!("{}:{}", cfg.gateway.bind, cfg.gateway.port)
@@ -2799,11 +4370,27 @@ async fn cmd_start(config_path: &Option<PathBuf>) -> Result<()> {
             .context("Invalid gateway bind address")?;
 
         let gateway_token = shellexpand_str(&cfg.gateway.auth_token);
-        let gateway = meepo_gateway::GatewayServer::with_sessions(
+        let mut gateway = meepo_gateway::GatewayServer::with_sessions(
             bind_addr,
             gateway_token,
             shared_sessions.clone(),
         );
+        if cfg.gateway.calendar_feed {
+            gateway = gateway.with_calendar_feed(db.clone());
+        }
+        gateway = gateway.with_timeline(db.clone());
+        if let Some(nodes) = &gateway_nodes {
+            gateway = gateway.with_nodes(nodes.clone());
+        }
+        if cfg.gateway.ingest {
+            gateway = gateway.with_ingest(
+                knowledge_graph.clone(),
+                cfg.rag.chunking.to_chunking_config(),
+                cfg.gateway.ingest_queue,
+            );
+        }
+
+        let _ = gateway_events.set(gateway.event_bus().clone());
 
         tokio::spawn(async move {
             if let Err(e) = gateway.run().await {
@@ -2816,9 +4403,58 @@ async fn cmd_start(config_path: &Option<PathBuf>) -> Result<()> {
         );
     }
 
-    // Wait for shutdown signal
-    signal::ctrl_c().await?;
-    info!("Received Ctrl+C, shutting down...");
+    // Warm up indexes, embeddings and the model connection, then tell
+    // gateway clients the daemon is ready
+    {
+        let graph = knowledge_graph.clone();
+        let api = api.clone();
+        let events = gateway_events.clone();
+        let warmup = cfg.warmup.clone();
+        tokio::spawn(async move {
+            let mut report = if warmup.enabled {
+                warmup::run(&warmup.to_warmup_config(), graph, api).await
+            } else {
+                warmup::WarmupReport {
+                    steps: Vec::new(),
+                    duration_ms: 0,
+                }
+            };
+            report.steps.splice(0..0, mcp_steps);
+            for step in report.failed() {
+                warn!(
+                    "Warm-up step '{}' failed: {}",
+                    step.name,
+                    step.error.as_deref().unwrap_or("unknown error")
+                );
+            }
+            info!(
+                "Meepo ready ({} warm-up steps in {}ms)",
+                report.steps.len(),
+                report.duration_ms
+            );
+            if let Some(events) = events.get() {
+                events.mark_ready(serde_json::to_value(&report).unwrap_or_default());
+            }
+        });
+    }
+
+    // Wait for shutdown signal, or for the simulation to finish
+    let report = match sim {
+        Some(sim) => tokio::select! {
+            report = sim.handle.finished() => Some(report?),
+            result = signal::ctrl_c() => {
+                result?;
+                None
+            }
+        },
+        None => {
+            signal::ctrl_c().await?;
+            None
+        }
+    };
+    if report.is_none() {
+        info!("Received Ctrl+C, shutting down...");
+    }
     cancel.cancel();
 
     // Wait for all tasks
@@ -2834,27 +4470,128 @@ async fn cmd_start(config_path: &Option<PathBuf>) -> Result<()> {
     if let Some(dt) = digest_task {
         let _ = dt.await;
     }
+    if let Some(tt) = tag_task {
+        let _ = tt.await;
+    }
+    if let Some(rt) = report_task {
+        let _ = rt.await;
+    }
+    if let Some(mt) = meeting_task {
+        let _ = mt.await;
+    }
+    if let Some(st) = task_sync_task {
+        let _ = st.await;
+    }
+    if let Some(ft) = focus_task {
+        let _ = ft.await;
+    }
+    if let Some(mt) = modes_task {
+        let _ = mt.await;
+    }
+    if let Some(at) = app_usage_task {
+        let _ = at.await;
+    }
+    if let Some(st) = artifact_sync_task {
+        let _ = st.await;
+    }
+    if let Some(rt) = replication_task {
+        let _ = rt.await;
+    }
 
     // Stop all watchers
     watcher_runner.lock().await.stop_all().await;
 
+    if let Err(e) = knowledge_graph.flush() {
+        warn!("Failed to commit search index: {}", e);
+    }
+
     println!("Meepo stopped.");
+    Ok(report)
+}
+
+async fn cmd_simulate(
+    config_path: &Option<PathBuf>,
+    scenario_path: &std::path::Path,
+    record: bool,
+) -> Result<()> {
+    let mut cfg = MeepoConfig::load(config_path)?;
+    let scenario = simulate::load_scenario(scenario_path)?;
+    if record && scenario.fixtures.is_none() {
+        bail!("--record needs a `fixtures:` directory in the scenario");
+    }
+    let dir = simulate::scratch_dir();
+    simulate::sandbox_config(&mut cfg, &scenario, &dir, record)?;
+    info!(
+        "Simulating '{}' ({} turns) in {}",
+        scenario.name,
+        scenario.turns.len(),
+        dir.display()
+    );
+
+    let result = run_daemon(cfg, Some(simulate::Simulation::new(scenario))).await;
+    let _ = std::fs::remove_dir_all(&dir);
+    let Some(report) = result? else {
+        bail!("Simulation interrupted");
+    };
+    simulate::print_report(&report);
+    if !report.passed() {
+        std::process::exit(1);
+    }
+    Ok(())
+}
+
+async fn cmd_bench(entities: usize, queries: usize, no_save: bool) -> Result<()> {
+    if entities == 0 {
+        bail!("--entities must be at least 1");
+    }
+    let dir = bench::scratch_dir();
+    println!("\n  Benchmarking in {}\n", dir.display());
+    let result = bench::run(bench::BenchOptions { entities, queries }, &dir).await;
+    let _ = std::fs::remove_dir_all(&dir);
+    let run = result?;
+
+    let history_path = bench::history_path();
+    let history = bench::load_history(&history_path);
+    bench::print_report(&run, bench::previous_run(&history, &run));
+    if !no_save {
+        bench::save_run(&history_path, &run)?;
+        println!("\nSaved to {}", history_path.display());
+    }
     Ok(())
 }
 
 /// Build a digest summary from the knowledge database
 async fn build_digest_summary(db: &meepo_knowledge::KnowledgeDb, is_morning: bool) -> String {
+    use meepo_core::i18n::fill;
+
+    let t = meepo_core::i18n::strings(meepo_core::i18n::language());
     let mut summary = String::new();
 
+    // Read from one snapshot so counts in the digest agree with each other.
+    // Email briefs are marked as digested, so they come from the live database.
+    let snapshot = match db.snapshot().await {
+        Ok(snapshot) => Some(snapshot),
+        Err(e) => {
+            warn!(
+                "Failed to snapshot database for digest, reading live: {}",
+                e
+            );
+            None
+        }
+    };
+    let view = snapshot.as_ref().unwrap_or(db);
+
     // Active watchers
-    match db.get_active_watchers().await {
+    match view.get_active_watchers().await {
         Ok(watchers) if !watchers.is_empty() => {
-            summary.push_str(&format!("📡 {} active watchers\n", watchers.len()));
+            let n = watchers.len().to_string();
+            summary.push_str(&format!("{}\n", fill(t.digest_watchers, &[("n", &n)])));
             for w in watchers.iter().take(5) {
                 summary.push_str(&format!("  • {} → {}\n", w.kind, w.action));
             }
             if watchers.len() > 5 {
-                summary.push_str(&format!("  ... and {} more\n", watchers.len() - 5));
+                let more = (watchers.len() - 5).to_string();
+                summary.push_str(&format!("  {}\n", fill(t.digest_more, &[("n", &more)])));
             }
             summary.push('\n');
         }
@@ -2862,11 +4599,12 @@ async fn build_digest_summary(db: &meepo_knowledge::KnowledgeDb, is_morning: boo
     }
 
     // Running tasks
-    match db.get_active_background_tasks().await {
+    match view.get_active_background_tasks().await {
         Ok(tasks) if !tasks.is_empty() => {
-            summary.push_str(&format!("⚙️ {} running tasks\n", tasks.len()));
-            for t in tasks.iter().take(5) {
-                summary.push_str(&format!("  • [{}] {}\n", t.id, t.description));
+            let n = tasks.len().to_string();
+            summary.push_str(&format!("{}\n", fill(t.digest_running, &[("n", &n)])));
+            for task in tasks.iter().take(5) {
+                summary.push_str(&format!("  • [{}] {}\n", task.id, task.description));
             }
             summary.push('\n');
         }
@@ -2875,22 +4613,24 @@ async fn build_digest_summary(db: &meepo_knowledge::KnowledgeDb, is_morning: boo
 
     // Recently completed tasks (for evening recap)
     if !is_morning {
-        match db.get_recent_background_tasks(10).await {
+        match view.get_recent_background_tasks(10).await {
             Ok(tasks) if !tasks.is_empty() => {
                 let completed: Vec<_> = tasks.iter().filter(|t| t.status == "completed").collect();
                 let failed: Vec<_> = tasks.iter().filter(|t| t.status == "failed").collect();
 
                 if !completed.is_empty() {
-                    summary.push_str(&format!("✅ {} tasks completed today\n", completed.len()));
-                    for t in completed.iter().take(5) {
-                        summary.push_str(&format!("  • {}\n", t.description));
+                    let n = completed.len().to_string();
+                    summary.push_str(&format!("{}\n", fill(t.digest_completed, &[("n", &n)])));
+                    for task in completed.iter().take(5) {
+                        summary.push_str(&format!("  • {}\n", task.description));
                     }
                     summary.push('\n');
                 }
                 if !failed.is_empty() {
-                    summary.push_str(&format!("❌ {} tasks failed\n", failed.len()));
-                    for t in failed.iter().take(3) {
-                        summary.push_str(&format!("  • {}\n", t.description));
+                    let n = failed.len().to_string();
+                    summary.push_str(&format!("{}\n", fill(t.digest_failed, &[("n", &n)])));
+                    for task in failed.iter().take(3) {
+                        summary.push_str(&format!("  • {}\n", task.description));
                     }
                     summary.push('\n');
                 }
@@ -2899,10 +4639,150 @@ async fn build_digest_summary(db: &meepo_knowledge::KnowledgeDb, is_morning: boo
         }
     }
 
+    // What Meepo did today (evening recap)
+    if !is_morning {
+        let midnight = meepo_core::timezone::now()
+            .date_naive()
+            .and_hms_opt(0, 0, 0)
+            .and_then(|t| {
+                t.and_local_timezone(meepo_core::timezone::user_timezone())
+                    .earliest()
+            })
+            .map(|t| t.with_timezone(&chrono::Utc));
+        let query = meepo_knowledge::TimelineQuery {
+            since: midnight,
+            ..Default::default()
+        };
+        match view.timeline(query).await {
+            Ok(entries) if !entries.is_empty() => {
+                use meepo_knowledge::TimelineSource;
+                let count = |source: TimelineSource| {
+                    entries
+                        .iter()
+                        .filter(|e| e.source == source)
+                        .count()
+                        .to_string()
+                };
+                summary.push_str(&format!(
+                    "{}\n\n",
+                    fill(
+                        t.digest_activity,
+                        &[
+                            ("messages", &count(TimelineSource::Conversation)),
+                            ("actions", &count(TimelineSource::Action)),
+                            ("tasks", &count(TimelineSource::Task)),
+                            ("watchers", &count(TimelineSource::Watcher)),
+                        ]
+                    )
+                ));
+            }
+            Ok(_) => {}
+            Err(e) => warn!("Failed to read activity timeline for digest: {}", e),
+        }
+    }
+
+    // Email briefs collected since the last digest (evening recap)
+    if !is_morning {
+        match meepo_core::email_triage::take_undigested_briefs(db).await {
+            Ok(briefs) if !briefs.is_empty() => {
+                let n = briefs.len().to_string();
+                summary.push_str(&format!("{}\n", fill(t.digest_email_briefs, &[("n", &n)])));
+                for brief in &briefs {
+                    summary.push_str(&format!("  • {} ({})\n", brief.subject, brief.from));
+                    for bullet in &brief.bullets {
+                        summary.push_str(&format!("    - {}\n", bullet));
+                    }
+                }
+                summary.push('\n');
+            }
+            Ok(_) => {}
+            Err(e) => warn!("Failed to collect email briefs for digest: {}", e),
+        }
+    }
+
+    // Messages waiting in quarantine
+    match meepo_core::spam_filter::list(db).await {
+        Ok(messages) => {
+            let pending = messages.iter().filter(|m| !m.released).count();
+            if pending > 0 {
+                let n = pending.to_string();
+                summary.push_str(&format!("{}\n\n", fill(t.digest_quarantine, &[("n", &n)])));
+            }
+        }
+        Err(e) => warn!("Failed to read quarantine for digest: {}", e),
+    }
+
+    // Memories waiting for the user's approval
+    match meepo_core::memory_review::pending(db).await {
+        Ok(staged) if !staged.is_empty() => {
+            let n = staged.len().to_string();
+            summary.push_str(&format!(
+                "{}\n\n",
+                fill(t.digest_memory_review, &[("n", &n)])
+            ));
+        }
+        Ok(_) => {}
+        Err(e) => warn!("Failed to read memory review queue for digest: {}", e),
+    }
+
+    // Screen time today (evening recap)
+    if !is_morning {
+        let today = meepo_core::timezone::now().date_naive();
+        match meepo_core::app_usage::usage_between(view, today, today, None).await {
+            Ok(usage) if !usage.is_empty() => {
+                use meepo_core::app_usage::{format_duration, total_seconds};
+                let time = format_duration(total_seconds(&usage));
+                summary.push_str(&format!(
+                    "{}\n",
+                    fill(t.digest_screen_time, &[("time", &time)])
+                ));
+                for entry in usage.iter().take(3) {
+                    summary.push_str(&format!(
+                        "  • {} {}\n",
+                        entry.app,
+                        format_duration(entry.seconds)
+                    ));
+                }
+                summary.push('\n');
+            }
+            Ok(_) => {}
+            Err(e) => warn!("Failed to collect app usage for digest: {}", e),
+        }
+    }
+
+    // Open action items extracted from conversations and notes
+    match meepo_core::action_items::open_items(view).await {
+        Ok(items) if !items.is_empty() => {
+            let n = items.len().to_string();
+            summary.push_str(&format!("{}\n", fill(t.digest_action_items, &[("n", &n)])));
+            for item in items.iter().take(5) {
+                match item.due {
+                    Some(due) => {
+                        let date = due.to_string();
+                        summary.push_str(&format!(
+                            "  • {} ({})\n",
+                            item.title,
+                            fill(t.digest_due, &[("date", &date)])
+                        ));
+                    }
+                    None => summary.push_str(&format!("  • {}\n", item.title)),
+                }
+            }
+            if items.len() > 5 {
+                let more = (items.len() - 5).to_string();
+                summary.push_str(&format!("  {}\n", fill(t.digest_more, &[("n", &more)])));
+            }
+            summary.push('\n');
+        }
+        Ok(_) => {}
+        Err(e) => warn!("Failed to collect action items for digest: {}", e),
+    }
+
     // Active goals
-    match db.get_due_goals().await {
+    match view.get_due_goals().await {
         Ok(goals) if !goals.is_empty() => {
-            summary.push_str(&format!("🎯 {} goals due\n", goals.len()));
+            let n = goals.len().to_string();
+            summary.push_str(&format!("{}\n", fill(t.digest_goals, &[("n", &n)])));
             for g in goals.iter().take(5) {
                 summary.push_str(&format!("  • {}\n", g.description));
             }
@@ -2913,44 +4793,696 @@ async fn build_digest_summary(db: &meepo_knowledge::KnowledgeDb, is_morning: boo
 
     if summary.is_empty() {
         if is_morning {
-            summary = "Nothing scheduled. Quiet day ahead!".to_string();
+            summary = t.digest_quiet_morning.to_string();
         } else {
-            summary = "Quiet day — no tasks or events to report.".to_string();
+            summary = t.digest_quiet_evening.to_string();
         }
     }
 
     summary
 }
 
-async fn cmd_stop() -> Result<()> {
-    #[cfg(target_os = "macos")]
-    let output = tokio::process::Command::new("pkill")
-        .args(["-f", "meepo start"])
-        .output()
-        .await?;
-
-    #[cfg(target_os = "windows")]
-    let output = tokio::process::Command::new("taskkill")
-        .args(["/IM", "meepo.exe", "/F"])
-        .output()
-        .await?;
+/// Backend for `[task_sync]`
+fn task_sync_backend(
+    cfg: &crate::config::TaskSyncCliConfig,
+) -> Result<Box<dyn meepo_core::task_sync::TaskBackend>> {
+    match cfg.target.as_str() {
+        "reminders" => Ok(Box::new(meepo_core::task_sync::RemindersBackend::new(
+            meepo_core::platform::create_reminders_provider()?,
+            cfg.list.clone(),
+        ))),
+        "todoist" => {
+            if cfg.todoist_token.is_empty() {
+                anyhow::bail!("task_sync.todoist_token is not set");
+            }
+            Ok(Box::new(meepo_core::task_sync::TodoistBackend::new(
+                cfg.todoist_token.clone(),
+                cfg.list.clone(),
+            )))
+        }
+        other => anyhow::bail!(
+            "Unknown task_sync.target '{}' (use reminders or todoist)",
+            other
+        ),
+    }
+}
 
-    #[cfg(not(any(target_os = "macos", target_os = "windows")))]
-    let output = tokio::process::Command::new("pkill")
-        .args(["-f", "meepo start"])
-        .output()
-        .await?;
+/// Artifact sync from `[artifact_sync]`
+fn artifact_sync(
+    cfg: &MeepoConfig,
+    db: Arc<meepo_knowledge::KnowledgeDb>,
+) -> Result<meepo_core::artifact_sync::ArtifactSync> {
+    use meepo_core::artifact_sync::{
+        ArtifactSync, ArtifactSyncConfig, DOCUMENTS_ROOT, S3Target, SyncRoot, SyncTarget,
+        WebDavTarget, default_device,
+    };
 
-    if output.status.success() {
-        println!("Meepo daemon stopped.");
-    } else {
-        println!("No running Meepo daemon found.");
+    let sync = &cfg.artifact_sync;
+    let target: Box<dyn SyncTarget> = match sync.target.as_str() {
+        "s3" => Box::new(S3Target::new(
+            Some(&sync.s3.endpoint),
+            &sync.s3.bucket,
+            &sync.s3.region,
+            &sync.s3.access_key_id,
+            &sync.s3.secret_access_key,
+            &sync.s3.prefix,
+        )?),
+        "webdav" => Box::new(WebDavTarget::new(
+            &sync.webdav.url,
+            Some(&sync.webdav.username),
+            Some(&sync.webdav.password),
+        )?),
+        other => anyhow::bail!(
+            "Unknown artifact_sync.target '{}' (use s3 or webdav)",
+            other
+        ),
+    };
+    let mut roots = vec![SyncRoot {
+        name: "workspace".to_string(),
+        path: shellexpand(&cfg.memory.workspace),
+    }];
+    if sync.share_documents {
+        roots.push(SyncRoot {
+            name: DOCUMENTS_ROOT.to_string(),
+            path: shellexpand(&sync.documents_dir),
+        });
     }
-    Ok(())
+    ArtifactSync::new(
+        db,
+        target,
+        ArtifactSyncConfig {
+            roots,
+            include: sync.include.clone(),
+            exclude: sync.exclude.clone(),
+            max_file_bytes: sync.max_file_mb * 1024 * 1024,
+            share_documents: sync.share_documents,
+            backups: sync.backups,
+            keep_backups: sync.keep_backups,
+            device: if sync.device.is_empty() {
+                default_device()
+            } else {
+                sync.device.clone()
+            },
+        },
+    )
 }
 
-async fn cmd_ask(config_path: &Option<PathBuf>, message: &str) -> Result<()> {
+/// Notification route from a `[[notifications.routes]]` entry
+fn notify_route(
+    route: &crate::config::NotifyRouteConfig,
+) -> Result<meepo_core::notifications::NotifyRoute> {
+    use meepo_core::notifications::{NotifyRoute, NotifyTarget, Severity};
+
+    let target = NotifyTarget::from_name(&route.target)
+        .ok_or_else(|| anyhow::anyhow!("unknown target '{}'", route.target))?;
+    let min_severity = Severity::from_name(&route.min_severity)
+        .ok_or_else(|| anyhow::anyhow!("unknown severity '{}'", route.min_severity))?;
+    let hours = match &route.hours {
+        Some(hours) => {
+            let parse = |t: &str| chrono::NaiveTime::parse_from_str(t.trim(), "%H:%M");
+            let (start, end) = hours
+                .split_once('-')
+                .ok_or_else(|| anyhow::anyhow!("hours must look like \"22:00-07:00\""))?;
+            Some((parse(start)?, parse(end)?))
+        }
+        None => None,
+    };
+    let when = route
+        .when
+        .as_deref()
+        .map(meepo_core::expr::Expression::parse)
+        .transpose()?;
+    Ok(NotifyRoute {
+        events: route.events.clone(),
+        min_severity,
+        hours,
+        when,
+        target,
+        dedup: route.dedup_secs.map(std::time::Duration::from_secs),
+    })
+}
+
+/// Modes from `[[modes]]`, skipping settings that don't parse
+fn modes_config(cfg: &MeepoConfig) -> meepo_core::modes::ModesConfig {
+    use meepo_core::modes::{Mode, ModeAutonomy, ModeWindow, ModesConfig};
+    use meepo_core::notifications::Severity;
+
+    let mut modes = Vec::new();
+    for entry in &cfg.modes {
+        let min_severity = entry.min_severity.as_deref().and_then(|s| {
+            let severity = Severity::from_name(s);
+            if severity.is_none() {
+                warn!("Mode '{}': unknown severity '{}', ignoring", entry.name, s);
+            }
+            severity
+        });
+        let autonomy = ModeAutonomy::from_name(&entry.autonomy).unwrap_or_else(|| {
+            warn!(
+                "Mode '{}': unknown autonomy '{}', using full",
+                entry.name, entry.autonomy
+            );
+            ModeAutonomy::Full
+        });
+        let schedule = entry
+            .schedule
+            .iter()
+            .filter_map(|w| match ModeWindow::parse(&w.days, &w.hours) {
+                Ok(window) => Some(window),
+                Err(e) => {
+                    warn!("Mode '{}': bad schedule, ignoring: {}", entry.name, e);
+                    None
+                }
+            })
+            .collect();
+        modes.push(Mode {
+            name: entry.name.clone(),
+            soul: entry.soul.trim().to_string(),
+            min_severity,
+            autonomy,
+            tools: entry.tools.clone(),
+            schedule,
+        });
+    }
+    ModesConfig {
+        modes,
+        ..Default::default()
+    }
+}
+
+/// Live context for the system prompt from `[live_context]`; calendar and
+/// unread counts are read only where the platform has providers for them
+fn live_context(cfg: &MeepoConfig) -> Option<Arc<meepo_core::live_context::LiveContext>> {
+    use meepo_core::live_context::{LiveContext, LiveContextConfig};
+    use meepo_core::platform;
+
+    let live = &cfg.live_context;
+    if !live.enabled {
+        return None;
+    }
+    let mut context = LiveContext::new(LiveContextConfig {
+        time: live.time,
+        battery: live.battery,
+        focus: live.focus,
+        next_event: live.next_event,
+        unread: live.unread,
+        event_hours: live.event_hours,
+        cache_secs: live.cache_secs,
+        locale: cfg.agent.locale.clone(),
+    });
+    if live.next_event
+        && let Ok(calendar) = platform::create_calendar_provider()
+    {
+        context = context.with_calendar(calendar);
+    }
+    if live.unread {
+        if let Ok(email) = platform::create_email_provider() {
+            context = context.with_email(email);
+        }
+        if let Ok(messages) = platform::create_messages_provider() {
+            context = context.with_messages(messages);
+        }
+    }
+    Some(Arc::new(context))
+}
+
+/// Voice note transcriber for the iMessage and email channels, when
+/// `[voice] voice_notes` is on and the STT provider can be set up
+#[cfg(target_os = "macos")]
+fn voice_note_transcriber(
+    cfg: &MeepoConfig,
+) -> Option<Arc<meepo_core::audio::voice_notes::VoiceNoteTranscriber>> {
+    use meepo_core::audio::{AudioConfig, SttProvider, stt, voice_notes::VoiceNoteTranscriber};
+
+    if !cfg.voice.voice_notes {
+        return None;
+    }
+    let audio = AudioConfig {
+        stt_provider: match cfg.voice.stt_provider.as_str() {
+            "whisper_local" => SttProvider::WhisperLocal,
+            _ => SttProvider::WhisperApi,
+        },
+        openai_api_key: cfg
+            .providers
+            .openai
+            .as_ref()
+            .map(|o| shellexpand_str(&o.api_key))
+            .unwrap_or_default(),
+        // Contacts may speak any language, so only hint one when configured
+        language: Some(cfg.voice.language.clone()).filter(|l| !l.is_empty()),
+        ..Default::default()
+    };
+    match stt::create_stt(&audio) {
+        Ok(stt) => {
+            info!("Voice note transcription enabled ({})", stt.name());
+            Some(Arc::new(VoiceNoteTranscriber::new(stt)))
+        }
+        Err(e) => {
+            warn!("Voice notes disabled: {}", e);
+            None
+        }
+    }
+}
+
+/// Read-aloud speaker for channels with `read_aloud = true` (macOS only)
+fn read_aloud(
+    cfg: &MeepoConfig,
+    timezone: chrono_tz::Tz,
+) -> Option<Arc<meepo_core::audio::read_aloud::ReadAloud>> {
+    use meepo_core::audio::read_aloud::{ReadAloud, ReadAloudConfig};
+    use meepo_core::audio::{AudioConfig, TtsProvider, tts};
+    use meepo_core::types::ChannelType;
+
+    let channels = cfg.channels.read_aloud_channels();
+    if channels.is_empty() {
+        return None;
+    }
+    if !cfg!(target_os = "macos") {
+        warn!(
+            "Read-aloud needs macOS; ignoring read_aloud on {}",
+            channels.join(", ")
+        );
+        return None;
+    }
+    let hours = match cfg.voice.read_aloud_hours.trim() {
+        "" => None,
+        hours => {
+            let parse = |t: &str| chrono::NaiveTime::parse_from_str(t.trim(), "%H:%M").ok();
+            let window = hours
+                .split_once('-')
+                .and_then(|(start, end)| Some((parse(start)?, parse(end)?)));
+            if window.is_none() {
+                warn!(
+                    "Ignoring voice.read_aloud_hours '{}' (expected e.g. \"18:00-23:00\")",
+                    hours
+                );
+            }
+            window
+        }
+    };
+    let audio = AudioConfig {
+        tts_provider: match cfg.voice.tts_provider.as_str() {
+            "elevenlabs" => TtsProvider::Elevenlabs,
+            "openai_tts" => TtsProvider::OpenaiTts,
+            _ => TtsProvider::MacosSay,
+        },
+        elevenlabs_api_key: shellexpand_str(&cfg.voice.elevenlabs_api_key),
+        elevenlabs_voice_id: cfg.voice.elevenlabs_voice_id.clone(),
+        openai_api_key: cfg
+            .providers
+            .openai
+            .as_ref()
+            .map(|o| shellexpand_str(&o.api_key))
+            .unwrap_or_default(),
+        language: Some(if cfg.voice.language.is_empty() {
+            cfg.agent.language.clone()
+        } else {
+            cfg.voice.language.clone()
+        })
+        .filter(|l| !l.is_empty()),
+        ..Default::default()
+    };
+    let tts = match tts::create_tts(&audio) {
+        Ok(tts) => tts,
+        Err(e) => {
+            warn!("Read-aloud disabled: {}", e);
+            return None;
+        }
+    };
+    info!(
+        "Reading replies aloud on {} ({})",
+        channels.join(", "),
+        tts.name()
+    );
+    let config = ReadAloudConfig {
+        channels: channels
+            .iter()
+            .map(|c| ChannelType::from_string(c))
+            .collect(),
+        hours,
+        timezone,
+        urgent: cfg.voice.read_aloud_urgent,
+        volume: Some(cfg.voice.read_aloud_volume.min(100)).filter(|v| *v > 0),
+        ..Default::default()
+    };
+    Some(Arc::new(ReadAloud::new(config, tts)))
+}
+
+/// `create_calendar_event`, with the `[calendar]` conflict checks
+#[cfg(any(target_os = "macos", target_os = "windows", target_os = "linux"))]
+fn create_event_tool(
+    cfg: &MeepoConfig,
+    db: Arc<meepo_knowledge::KnowledgeDb>,
+) -> meepo_core::tools::macos::CreateEventTool {
+    use meepo_core::calendar_conflicts::{ConflictCheck, TravelRoute, TravelTimes};
+
+    let tool = meepo_core::tools::macos::CreateEventTool::new().with_db(db);
+    if !cfg.calendar.conflict_check {
+        return tool;
+    }
+    tool.with_conflict_check(ConflictCheck {
+        travel: TravelTimes {
+            default_minutes: cfg.calendar.travel_minutes,
+            routes: cfg
+                .calendar
+                .routes
+                .iter()
+                .map(|r| TravelRoute {
+                    from: r.from.clone(),
+                    to: r.to.clone(),
+                    minutes: r.minutes,
+                })
+                .collect(),
+        },
+        suggestions: cfg.calendar.suggestions,
+    })
+}
+
+/// Push bridge from `[gateway.push]`, skipping pushers that can't be set up
+fn push_bridge(
+    cfg: &MeepoConfig,
+    registry: meepo_core::push::PushRegistry,
+) -> meepo_core::push::PushBridge {
+    use meepo_core::notifications::{NtfyConfig, Severity};
+    use meepo_core::push::{ApnsPusher, FcmPusher, PushBridge};
+
+    let pc = &cfg.gateway.push;
+    let min_severity = Severity::from_name(&pc.min_severity).unwrap_or_else(|| {
+        warn!("Unknown push severity '{}', using warning", pc.min_severity);
+        Severity::Warning
+    });
+    let mut bridge = PushBridge::new(registry).with_min_severity(min_severity);
+    if let Some(path) = &pc.apns_key_path {
+        let pusher = std::fs::read_to_string(shellexpand(path))
+            .map_err(anyhow::Error::from)
+            .and_then(|pem| {
+                ApnsPusher::new(
+                    &pem,
+                    pc.apns_key_id.clone(),
+                    pc.apns_team_id.clone(),
+                    pc.apns_topic.clone(),
+                    pc.apns_sandbox,
+                )
+            });
+        match pusher {
+            Ok(pusher) => bridge = bridge.with_pusher(Box::new(pusher)),
+            Err(e) => warn!("APNs push disabled: {}", e),
+        }
+    }
+    if let Some(path) = &pc.fcm_service_account_path {
+        let pusher = std::fs::read_to_string(shellexpand(path))
+            .map_err(anyhow::Error::from)
+            .and_then(|json| FcmPusher::new(&json));
+        match pusher {
+            Ok(pusher) => bridge = bridge.with_pusher(Box::new(pusher)),
+            Err(e) => warn!("FCM push disabled: {}", e),
+        }
+    }
+    if pc.ntfy_fallback
+        && let Some(ntfy) = &cfg.notifications.ntfy
+    {
+        bridge = bridge.with_ntfy_fallback(NtfyConfig {
+            server: ntfy.server.clone(),
+            topic: ntfy.topic.clone(),
+        });
+    }
+    bridge
+}
+
+/// Register tools from `[plugins] dir`. Plugin tools never replace a tool
+/// that's already registered.
+fn register_plugins(cfg: &MeepoConfig, registry: &mut meepo_core::tools::ToolRegistry) {
+    let dir = shellexpand(&cfg.plugins.dir);
+    let config = meepo_core::plugins::PluginConfig {
+        allow_native: cfg.plugins.allow_native.clone(),
+        wasm_fuel: cfg.plugins.wasm_fuel,
+        wasm_memory_bytes: (cfg.plugins.wasm_memory_mb.max(1) * 1024 * 1024) as usize,
+        timeout: std::time::Duration::from_secs(cfg.plugins.timeout_secs.max(1)),
+    };
+    match meepo_core::plugins::load_plugins(&dir, &config) {
+        Ok(tools) => {
+            let mut count = 0;
+            for tool in tools {
+                if registry.get(tool.name()).is_some() {
+                    warn!(
+                        "Plugins: skipping tool '{}', a tool with that name already exists",
+                        tool.name()
+                    );
+                    continue;
+                }
+                registry.register(tool);
+                count += 1;
+            }
+            info!("Plugins: loaded {} tools from {}", count, dir.display());
+        }
+        Err(e) => warn!("Plugins: failed to load from {}: {}", dir.display(), e),
+    }
+}
+
+/// Middleware enforcing `[[contact_policies]]`
+fn contact_policies(cfg: &MeepoConfig) -> meepo_core::contact_policy::ContactPolicyMiddleware {
+    use meepo_core::contact_policy::{ContactPolicy, ContactPolicyMiddleware};
+
+    let policies = cfg
+        .contact_policies
+        .iter()
+        .map(|entry| {
+            if entry.senders.is_empty() {
+                warn!(
+                    "Contact policy '{}' has no senders and will never be used",
+                    entry.name
+                );
+            }
+            ContactPolicy {
+                name: entry.name.clone(),
+                senders: entry.senders.clone(),
+                withhold: entry.withhold.clone(),
+                blocked_terms: entry.blocked_terms.clone(),
+                tone: entry.tone.clone(),
+                auto_reply: entry.auto_reply.clone(),
+                auto_reply_when_busy: entry.auto_reply_when_busy,
+                refusal: entry.refusal.clone(),
+            }
+        })
+        .collect();
+    ContactPolicyMiddleware::new(policies)
+}
+
+/// Agent manager routing `[[restricted_profiles]]` senders to their profiles
+fn restricted_profiles(cfg: &MeepoConfig) -> meepo_core::agents::AgentManager {
+    use meepo_core::agents::{
+        AgentManager, AgentProfile, ChannelRoute, ProfileRestrictions, RouteFilter,
+    };
+    use meepo_core::types::ChannelType;
+
+    let mut manager = AgentManager::new(AgentProfile::new("default", "Meepo"));
+    for entry in &cfg.restricted_profiles {
+        let mut profile = AgentProfile::new(&entry.id, &entry.name);
+        profile.model = entry.model.clone();
+        profile.tools = entry.tools.clone();
+        profile.restrictions = Some(ProfileRestrictions {
+            blocked_terms: entry.blocked_terms.clone(),
+            personal_knowledge: entry.personal_knowledge,
+            daily_message_limit: entry.daily_message_limit,
+        });
+        for sender in &entry.senders {
+            let (channel, who) = match sender.split_once(':') {
+                Some((channel, who)) => (channel, Some(who.to_string())),
+                None => (sender.as_str(), None),
+            };
+            let channel_type = ChannelType::from_string(channel);
+            if channel_type == ChannelType::Internal {
+                warn!(
+                    "Restricted profile '{}': unknown channel in '{}', ignoring",
+                    entry.id, sender
+                );
+                continue;
+            }
+            profile.channels.push(ChannelRoute {
+                channel_type,
+                filter: RouteFilter {
+                    sender_allowlist: who.into_iter().collect(),
+                    ..Default::default()
+                },
+            });
+        }
+        if profile.channels.is_empty() {
+            warn!(
+                "Restricted profile '{}' has no senders and will never be used",
+                entry.id
+            );
+        }
+        manager.add_profile(profile);
+    }
+    manager
+}
+
+/// Action-item extraction settings from `[action_items]`
+fn action_item_config(cfg: &MeepoConfig) -> meepo_core::action_items::ActionItemConfig {
+    meepo_core::action_items::ActionItemConfig {
+        enabled: cfg.action_items.enabled,
+        from_documents: cfg.action_items.from_documents,
+        close_on_evidence: cfg.action_items.close_on_evidence,
+    }
+}
+
+/// Azure OpenAI provider, if its API key is set
+fn azure_provider(
+    azure_cfg: &config::AzureProviderConfig,
+) -> Option<meepo_core::providers::azure::AzureOpenAiProvider> {
+    let key = shellexpand_str(&azure_cfg.api_key);
+    if key.is_empty() || key.contains("${") {
+        return None;
+    }
+    Some(meepo_core::providers::azure::AzureOpenAiProvider::new(
+        key,
+        shellexpand_str(&azure_cfg.endpoint),
+        azure_cfg.deployment.clone(),
+        azure_cfg.api_version.clone(),
+        azure_cfg.max_tokens,
+    ))
+}
+
+/// Bedrock provider for `model`, if AWS credentials are set
+fn bedrock_provider(
+    bedrock_cfg: &config::BedrockProviderConfig,
+    model: &str,
+) -> Option<meepo_core::providers::bedrock::BedrockProvider> {
+    use meepo_core::providers::bedrock::{AwsCredentials, BedrockProvider};
+
+    let is_set = |value: &str| !value.is_empty() && !value.contains("${");
+    let access_key_id = shellexpand_str(&bedrock_cfg.access_key_id);
+    let secret_access_key = shellexpand_str(&bedrock_cfg.secret_access_key);
+    if !is_set(&access_key_id) || !is_set(&secret_access_key) {
+        return None;
+    }
+    let session_token = Some(shellexpand_str(&bedrock_cfg.session_token)).filter(|t| is_set(t));
+    Some(BedrockProvider::new(
+        AwsCredentials {
+            access_key_id,
+            secret_access_key,
+            session_token,
+        },
+        bedrock_cfg.region.clone(),
+        model.to_string(),
+        bedrock_cfg.max_tokens,
+    ))
+}
+
+/// Lock offline mode on when `[network] offline` is set (or the binary was
+/// built with the `offline` feature), and refuse configs that would need a
+/// cloud provider to work. Embedding providers follow the kill switch too.
+fn apply_network_mode(cfg: &MeepoConfig) -> Result<()> {
+    meepo_knowledge::embedding_providers::set_offline_check(meepo_core::network::is_offline);
+    if !cfg.network.offline && !meepo_core::network::BUILT_OFFLINE {
+        return Ok(());
+    }
+    meepo_core::network::lock_offline();
+    if cfg.agent.default_model != "ollama" {
+        anyhow::bail!(
+            "Offline mode needs a local model, but default_model is \"{}\".\n\n\
+             Set default_model = \"ollama\" and configure [providers.ollama].",
+            cfg.agent.default_model
+        );
+    }
+    if cfg.voice.voice_notes && cfg.voice.stt_provider != "whisper_local" {
+        anyhow::bail!(
+            "Offline mode needs local transcription for voice notes.\n\n\
+             Set [voice] stt_provider = \"whisper_local\" or voice_notes = false."
+        );
+    }
+    if !cfg.channels.read_aloud_channels().is_empty() && cfg.voice.tts_provider != "macos_say" {
+        anyhow::bail!(
+            "Offline mode needs local speech for read-aloud.\n\n\
+             Set [voice] tts_provider = \"macos_say\"."
+        );
+    }
+    let embeddings = &cfg.rag.embeddings;
+    let remote_embeddings = match embeddings.backend {
+        config::EmbeddingBackendKind::Openai => true,
+        config::EmbeddingBackendKind::Ollama => embeddings
+            .url
+            .as_deref()
+            .is_some_and(|url| !meepo_core::network::is_local_url(url)),
+        _ => false,
+    };
+    if embeddings.enabled && remote_embeddings {
+        anyhow::bail!(
+            "Offline mode needs local embeddings.\n\n\
+             Set [rag.embeddings] backend = \"ollama\" (on localhost) or \"local\"."
+        );
+    }
+    info!("Offline mode: only local models are used and web tools are disabled");
+    Ok(())
+}
+
+/// LLM record/replay settings from `[providers.replay]`, if enabled
+fn llm_replay_settings(
+    cfg: &MeepoConfig,
+) -> Result<Option<(meepo_core::providers::ReplayMode, PathBuf)>> {
+    let Some(replay) = &cfg.providers.replay else {
+        return Ok(None);
+    };
+    if replay.mode.trim().is_empty() {
+        return Ok(None);
+    }
+    let mode = replay.mode.parse()?;
+    Ok(Some((mode, shellexpand(&replay.dir))))
+}
+
+async fn cmd_stop() -> Result<()> {
+    #[cfg(target_os = "macos")]
+    let output = tokio::process::Command::new("pkill")
+        .args(["-f", "meepo start"])
+        .output()
+        .await?;
+
+    #[cfg(target_os = "windows")]
+    let output = tokio::process::Command::new("taskkill")
+        .args(["/IM", "meepo.exe", "/F"])
+        .output()
+        .await?;
+
+    #[cfg(not(any(target_os = "macos", target_os = "windows")))]
+    let output = tokio::process::Command::new("pkill")
+        .args(["-f", "meepo start"])
+        .output()
+        .await?;
+
+    if output.status.success() {
+        println!("Meepo daemon stopped.");
+    } else {
+        println!("No running Meepo daemon found.");
+    }
+    Ok(())
+}
+
+async fn cmd_ask(
+    config_path: &Option<PathBuf>,
+    message: &str,
+    format: OutputFormat,
+    schema: Option<&str>,
+) -> Result<()> {
+    // Check the schema before spending an API call
+    let schema = match schema {
+        Some(schema) => {
+            let text = match schema.strip_prefix('@') {
+                Some(path) => std::fs::read_to_string(shellexpand(path))
+                    .with_context(|| format!("Failed to read schema file {}", path))?,
+                None => schema.to_string(),
+            };
+            let schema: serde_json::Value =
+                serde_json::from_str(&text).context("--schema is not valid JSON")?;
+            meepo_core::structured::check_schema(&schema)?;
+            Some(schema)
+        }
+        None if format == OutputFormat::Json => Some(meepo_core::structured::default_schema()),
+        None => None,
+    };
+
     let cfg = MeepoConfig::load(config_path)?;
+    meepo_core::timezone::set_user_timezone(meepo_core::timezone::resolve(&cfg.agent.timezone));
+    meepo_core::i18n::set_language(&cfg.agent.language);
+    apply_network_mode(&cfg)?;
 
     let use_ollama = cfg.agent.default_model == "ollama";
     let api = {
@@ -3014,6 +5546,11 @@ async fn cmd_ask(config_path: &Option<PathBuf>, message: &str) -> Result<()> {
 
     let system = format!("{}\n\n## Current Memory\n{}", soul, memory);
 
+    if let Some(schema) = schema {
+        let answer = meepo_core::structured::ask(&api, &system, message, &schema).await?;
+        return print_json(&answer);
+    }
+
     let response = api
         .chat(
             &[meepo_core::api::ApiMessage {
@@ -3034,7 +5571,12 @@ async fn cmd_ask(config_path: &Option<PathBuf>, message: &str) -> Result<()> {
     Ok(())
 }
 
-async fn cmd_usage(config_path: &Option<PathBuf>, period: &str, csv: bool) -> Result<()> {
+async fn cmd_usage(
+    config_path: &Option<PathBuf>,
+    period: &str,
+    csv: bool,
+    format: OutputFormat,
+) -> Result<()> {
     let cfg = MeepoConfig::load(config_path)?;
 
     let db_path = shellexpand(&cfg.knowledge.db_path);
@@ -3049,28 +5591,7 @@ async fn cmd_usage(config_path: &Option<PathBuf>, period: &str, csv: bool) -> Re
         meepo_knowledge::KnowledgeDb::new(&db_path).context("Failed to open knowledge database")?,
     );
 
-    let today = chrono::Utc::now().format("%Y-%m-%d").to_string();
-
-    let (start, end) = match period {
-        "today" => (today.clone(), today),
-        "month" => {
-            let now = chrono::Utc::now();
-            let first_of_month = format!("{}-{:02}-01", now.format("%Y"), now.format("%m"));
-            let today = now.format("%Y-%m-%d").to_string();
-            (first_of_month, today)
-        }
-        other if other.contains(':') => {
-            let parts: Vec<&str> = other.splitn(2, ':').collect();
-            if parts.len() != 2 {
-                bail!("Invalid date range format. Use 'YYYY-MM-DD:YYYY-MM-DD'");
-            }
-            (parts[0].to_string(), parts[1].to_string())
-        }
-        _ => bail!(
-            "Invalid period '{}'. Use 'today', 'month', or 'YYYY-MM-DD:YYYY-MM-DD'",
-            period
-        ),
-    };
+    let (start, end) = parse_period(period)?;
 
     if csv {
         let csv_data = db.export_usage_csv(&start, &end).await?;
@@ -3079,6 +5600,28 @@ async fn cmd_usage(config_path: &Option<PathBuf>, period: &str, csv: bool) -> Re
     }
 
     let summary = db.get_usage_summary(&start, &end).await?;
+    let budget = if cfg.usage.enabled {
+        Some(usage_tracker(&cfg, db.clone()).check_budget().await)
+    } else {
+        None
+    };
+
+    if format == OutputFormat::Json {
+        let mut value = serde_json::to_value(&summary)?;
+        value["start"] = start.into();
+        value["end"] = end.into();
+        if let Some(budget) = &budget {
+            value["budget"] = match budget {
+                Ok(status) => serde_json::json!({
+                    "status": status.to_string(),
+                    "warning": status.is_warning(),
+                    "exceeded": status.is_exceeded(),
+                }),
+                Err(e) => serde_json::json!({ "error": e.to_string() }),
+            };
+        }
+        return print_json(&value);
+    }
 
     println!();
     println!("  Meepo Usage Report");
@@ -3092,57 +5635,965 @@ async fn cmd_usage(config_path: &Option<PathBuf>, period: &str, csv: bool) -> Re
     println!("  Est. Cost:     ${:.4}", summary.estimated_cost_usd);
 
     // Budget status
-    if cfg.usage.enabled {
-        let mut model_prices = std::collections::HashMap::new();
-        for (name, price) in &cfg.usage.model_prices {
-            model_prices.insert(
-                name.clone(),
-                meepo_core::usage::ModelPricing {
-                    input_per_mtok: price.input_per_mtok,
-                    output_per_mtok: price.output_per_mtok,
-                    cache_read_per_mtok: price.cache_read_per_mtok,
-                    cache_write_per_mtok: price.cache_write_per_mtok,
-                },
+    match &budget {
+        Some(Ok(status)) => println!("  Budget:        {}", status),
+        Some(Err(e)) => println!("  Budget:        (check failed: {})", e),
+        None => {}
+    }
+
+    if !summary.by_source.is_empty() {
+        println!();
+        println!("  By Source:");
+        for (source, usage) in &summary.by_source {
+            println!(
+                "    {:<15} {:>5} calls  {:>8} tokens  ${:.4}",
+                source,
+                usage.api_calls,
+                usage.input_tokens + usage.output_tokens,
+                usage.estimated_cost_usd
             );
         }
-        let usage_config = meepo_core::usage::UsageConfig {
-            enabled: true,
-            daily_budget_usd: cfg.usage.daily_budget_usd,
-            monthly_budget_usd: cfg.usage.monthly_budget_usd,
-            warn_at_percent: cfg.usage.warn_at_percent as u32,
-            model_prices,
-        };
-        let tracker = meepo_core::usage::UsageTracker::new(db.clone(), usage_config);
-        match tracker.check_budget().await {
-            Ok(status) => println!("  Budget:        {}", status),
-            Err(e) => println!("  Budget:        (check failed: {})", e),
+    }
+
+    if !summary.by_model.is_empty() {
+        println!();
+        println!("  By Model:");
+        for (model, usage) in &summary.by_model {
+            println!(
+                "    {:<30} {:>5} calls  {:>8} tokens  ${:.4}",
+                model,
+                usage.api_calls,
+                usage.input_tokens + usage.output_tokens,
+                usage.estimated_cost_usd
+            );
+        }
+    }
+
+    if !summary.by_channel_model.is_empty() {
+        println!();
+        println!("  By Channel:");
+        let mut pairs: Vec<_> = summary.by_channel_model.iter().collect();
+        pairs.sort_by(|a, b| a.0.cmp(b.0));
+        for (pair, usage) in pairs {
+            println!(
+                "    {:<40} {:>5} calls  {:>8} tokens  ${:.4}",
+                pair,
+                usage.api_calls,
+                usage.input_tokens + usage.output_tokens,
+                usage.estimated_cost_usd
+            );
         }
     }
 
-    if !summary.by_source.is_empty() {
+    println!();
+    Ok(())
+}
+
+/// Usage tracker with the configured budgets and prices
+fn usage_tracker(
+    cfg: &MeepoConfig,
+    db: Arc<meepo_knowledge::KnowledgeDb>,
+) -> meepo_core::usage::UsageTracker {
+    let mut model_prices = std::collections::HashMap::new();
+    for (name, price) in &cfg.usage.model_prices {
+        model_prices.insert(
+            name.clone(),
+            meepo_core::usage::ModelPricing {
+                input_per_mtok: price.input_per_mtok,
+                output_per_mtok: price.output_per_mtok,
+                cache_read_per_mtok: price.cache_read_per_mtok,
+                cache_write_per_mtok: price.cache_write_per_mtok,
+            },
+        );
+    }
+    let usage_config = meepo_core::usage::UsageConfig {
+        enabled: true,
+        daily_budget_usd: cfg.usage.daily_budget_usd,
+        monthly_budget_usd: cfg.usage.monthly_budget_usd,
+        warn_at_percent: cfg.usage.warn_at_percent as u32,
+        model_prices,
+    };
+    meepo_core::usage::UsageTracker::new(db, usage_config)
+}
+
+fn print_json(value: &impl serde::Serialize) -> Result<()> {
+    println!("{}", serde_json::to_string_pretty(value)?);
+    Ok(())
+}
+
+/// PIDs of running `meepo start` daemons, or None where that can't be checked
+async fn daemon_pids() -> Option<Vec<u32>> {
+    if cfg!(target_os = "windows") {
+        return None;
+    }
+    let output = tokio::process::Command::new("pgrep")
+        .args(["-f", "meepo start"])
+        .output()
+        .await
+        .ok()?;
+    let own = std::process::id();
+    Some(
+        String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .filter_map(|l| l.trim().parse().ok())
+            .filter(|pid| *pid != own)
+            .collect(),
+    )
+}
+
+async fn cmd_status(config_path: &Option<PathBuf>, format: OutputFormat) -> Result<()> {
+    let cfg = MeepoConfig::load(config_path)?;
+    let pids = daemon_pids().await;
+
+    let channels: Vec<&str> = [
+        ("discord", cfg.channels.discord.enabled),
+        ("slack", cfg.channels.slack.enabled),
+        ("imessage", cfg.channels.imessage.enabled),
+        ("email", cfg.channels.email.enabled),
+        ("alexa", cfg.channels.alexa.enabled),
+    ]
+    .into_iter()
+    .filter_map(|(name, enabled)| enabled.then_some(name))
+    .collect();
+
+    // Goals and usage need the knowledge database, which exists after the first start
+    let db_path = shellexpand(&cfg.knowledge.db_path);
+    let (active_goals, today) = if db_path.exists() {
+        let db = meepo_knowledge::KnowledgeDb::new(&db_path)
+            .context("Failed to open knowledge database")?;
+        let (start, end) = parse_period("today")?;
+        (
+            Some(db.get_active_goals().await?.len()),
+            Some(db.get_usage_summary(&start, &end).await?),
+        )
+    } else {
+        (None, None)
+    };
+    // Without the full-text index recall still works, from SQLite, but slower
+    let search = db_path
+        .exists()
+        .then(|| meepo_knowledge::TantivyIndex::probe(shellexpand(&cfg.knowledge.tantivy_path)));
+
+    let offline = cfg.network.offline || meepo_core::network::BUILT_OFFLINE;
+
+    if format == OutputFormat::Json {
+        return print_json(&serde_json::json!({
+            "running": pids.as_ref().map(|p| !p.is_empty()),
+            "pids": pids,
+            "version": env!("CARGO_PKG_VERSION"),
+            "default_model": cfg.agent.default_model,
+            "offline": offline,
+            "channels": channels,
+            "gateway": cfg.gateway.enabled.then(|| format!("{}:{}", cfg.gateway.bind, cfg.gateway.port)),
+            "active_goals": active_goals,
+            "search": search.as_ref().map(|s| match s {
+                Ok(entries) => serde_json::json!({"full_text": true, "entries": entries}),
+                Err(e) => serde_json::json!({"full_text": false, "error": format!("{:#}", e)}),
+            }),
+            "today": today.as_ref().map(|u| serde_json::json!({
+                "api_calls": u.total_api_calls,
+                "tool_calls": u.total_tool_calls,
+                "input_tokens": u.total_input_tokens,
+                "output_tokens": u.total_output_tokens,
+                "estimated_cost_usd": u.estimated_cost_usd,
+            })),
+        }));
+    }
+
+    println!();
+    match &pids {
+        Some(p) if !p.is_empty() => println!(
+            "  Daemon:    running (pid {})",
+            p.iter()
+                .map(|p| p.to_string())
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+        Some(_) => println!("  Daemon:    not running"),
+        None => println!("  Daemon:    unknown"),
+    }
+    println!("  Version:   {}", env!("CARGO_PKG_VERSION"));
+    println!("  Model:     {}", cfg.agent.default_model);
+    if offline {
+        println!("  Network:   offline (local models only, web tools disabled)");
+    }
+    println!(
+        "  Channels:  {}",
+        if channels.is_empty() {
+            "none".to_string()
+        } else {
+            channels.join(", ")
+        }
+    );
+    if cfg.gateway.enabled {
+        println!("  Gateway:   {}:{}", cfg.gateway.bind, cfg.gateway.port);
+    }
+    if let Some(n) = active_goals {
+        println!("  Goals:     {} active", n);
+    }
+    match &search {
+        Some(Ok(entries)) => println!("  Search:    full-text index ({} entries)", entries),
+        Some(Err(e)) => println!(
+            "  Search:    ⚠ full-text index unavailable ({:#}); recall falls back to SQLite search",
+            e
+        ),
+        None => {}
+    }
+    if let Some(u) = &today {
+        println!(
+            "  Today:     {} API calls, {} tool calls, ${:.4}",
+            u.total_api_calls, u.total_tool_calls, u.estimated_cost_usd
+        );
+    }
+    println!();
+    Ok(())
+}
+
+async fn cmd_goals(config_path: &Option<PathBuf>, format: OutputFormat) -> Result<()> {
+    let cfg = MeepoConfig::load(config_path)?;
+    let db_path = shellexpand(&cfg.knowledge.db_path);
+    if !db_path.exists() {
+        bail!(
+            "Knowledge database not found at {}. Run `meepo start` first.",
+            db_path.display()
+        );
+    }
+    let db =
+        meepo_knowledge::KnowledgeDb::new(&db_path).context("Failed to open knowledge database")?;
+    let goals = db.get_active_goals().await?;
+
+    if format == OutputFormat::Json {
+        return print_json(&goals);
+    }
+    if goals.is_empty() {
+        println!("No active goals.");
+        return Ok(());
+    }
+    println!();
+    for goal in &goals {
+        let due = goal
+            .deadline
+            .map(|d| format!("  due {}", d.format("%Y-%m-%d %H:%M")))
+            .unwrap_or_default();
+        println!(
+            "  {}  P{}  {}{}",
+            goal.id, goal.priority, goal.description, due
+        );
+    }
+    println!();
+    Ok(())
+}
+
+/// Resolve 'today', 'month' or 'YYYY-MM-DD:YYYY-MM-DD' to a (start, end) date range
+fn parse_period(period: &str) -> Result<(String, String)> {
+    let today = chrono::Utc::now().format("%Y-%m-%d").to_string();
+
+    Ok(match period {
+        "today" => (today.clone(), today),
+        "month" => {
+            let now = chrono::Utc::now();
+            let first_of_month = format!("{}-{:02}-01", now.format("%Y"), now.format("%m"));
+            (first_of_month, today)
+        }
+        other if other.contains(':') => {
+            let parts: Vec<&str> = other.splitn(2, ':').collect();
+            if parts.len() != 2 {
+                bail!("Invalid date range format. Use 'YYYY-MM-DD:YYYY-MM-DD'");
+            }
+            (parts[0].to_string(), parts[1].to_string())
+        }
+        _ => bail!(
+            "Invalid period '{}'. Use 'today', 'month', or 'YYYY-MM-DD:YYYY-MM-DD'",
+            period
+        ),
+    })
+}
+
+async fn cmd_onboard(config_path: &Option<PathBuf>, channel: Option<&str>) -> Result<()> {
+    use meepo_core::onboarding::{self, KeyPerson, OnboardingAnswers};
+
+    let cfg = MeepoConfig::load(config_path)?;
+    let db_path = shellexpand(&cfg.knowledge.db_path);
+    if let Some(parent) = db_path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let db =
+        meepo_knowledge::KnowledgeDb::new(&db_path).context("Failed to open knowledge database")?;
+
+    if let Some(channel) = channel {
+        let channel = channel.trim().to_lowercase();
+        if meepo_core::types::ChannelType::from_string(&channel)
+            == meepo_core::types::ChannelType::Internal
+        {
+            bail!("Unknown channel '{}'", channel);
+        }
+        onboarding::start(&db, &channel).await?;
+        println!(
+            "Meepo will interview you on your next {} message{}",
+            channel,
+            if cfg.onboarding.enabled {
+                ""
+            } else {
+                " (enable [onboarding] in config.toml first)"
+            }
+        );
+        return Ok(());
+    }
+
+    println!();
+    println!("  Let's get to know each other. Press Enter to skip any question.");
+    println!();
+    let name = prompt_line("What should I call you?")?;
+    let timezone = loop {
+        let detected = meepo_core::timezone::detect().map(|tz| tz.name().to_string());
+        let question = match &detected {
+            Some(tz) => format!("Your timezone? [{}]", tz),
+            None => "Your timezone (e.g. Europe/Berlin)?".to_string(),
+        };
+        match prompt_line(&question)?.or(detected) {
+            Some(tz) if tz.parse::<chrono_tz::Tz>().is_err() => {
+                println!("  Unknown timezone '{}', try an IANA name", tz)
+            }
+            tz => break tz,
+        }
+    };
+    let working_hours = prompt_line("Your working hours (e.g. Mon-Fri 9-17:30)?")?;
+    let people = prompt_line("Key people, comma-separated, as name (relation)?")?
+        .map(|line| {
+            split_list(&line)
+                .into_iter()
+                .map(|entry| match entry.split_once('(') {
+                    Some((name, relation)) => KeyPerson {
+                        name: name.trim().to_string(),
+                        relation: Some(relation.trim_end_matches(')').trim().to_string())
+                            .filter(|r| !r.is_empty()),
+                    },
+                    None => KeyPerson {
+                        name: entry,
+                        relation: None,
+                    },
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    let projects = prompt_line("Projects you're working on, comma-separated?")?
+        .map(|line| split_list(&line))
+        .unwrap_or_default();
+    let notifications = prompt_line("How and when should I notify you?")?;
+
+    let answers = OnboardingAnswers {
+        name,
+        timezone,
+        working_hours,
+        people,
+        projects,
+        notifications,
+    };
+    let memory_path = shellexpand(&cfg.memory.workspace).join(&cfg.agent.memory_file);
+    let summary = onboarding::complete(&db, &memory_path, &answers).await?;
+    println!();
+    println!("  {}", summary);
+
+    if let Some(tz) = &answers.timezone
+        && *tz != cfg.agent.timezone
+    {
+        let path = config_path
+            .clone()
+            .unwrap_or_else(|| config::config_dir().join("config.toml"));
+        update_config_value(
+            &path,
+            "agent",
+            "timezone",
+            &toml::Value::String(tz.clone()).to_string(),
+        )?;
+        println!("  Set agent.timezone = \"{}\" in {}", tz, path.display());
+    }
+    Ok(())
+}
+
+/// Ask a question on the terminal; empty answers are `None`
+fn prompt_line(question: &str) -> Result<Option<String>> {
+    use std::io::{self, BufRead, Write};
+    print!("  {} ", question);
+    io::stdout().flush()?;
+    let mut answer = String::new();
+    io::stdin().lock().read_line(&mut answer)?;
+    let answer = answer.trim();
+    Ok((!answer.is_empty()).then(|| answer.to_string()))
+}
+
+fn split_list(line: &str) -> Vec<String> {
+    line.split(',')
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .map(String::from)
+        .collect()
+}
+
+async fn cmd_journal(
+    config_path: &Option<PathBuf>,
+    period: &str,
+    subject: Option<String>,
+    kind: Option<String>,
+    state: bool,
+) -> Result<()> {
+    use meepo_knowledge::journal;
+
+    let cfg = MeepoConfig::load(config_path)?;
+    meepo_core::timezone::set_user_timezone(meepo_core::timezone::resolve(&cfg.agent.timezone));
+    let tz = meepo_core::timezone::user_timezone();
+    let db_path = shellexpand(&cfg.knowledge.db_path);
+    if !db_path.exists() {
+        println!("No journal yet");
+        return Ok(());
+    }
+    let db =
+        meepo_knowledge::KnowledgeDb::new(&db_path).context("Failed to open knowledge database")?;
+
+    // Whole days in the user's timezone
+    let (start, end) = parse_period(period)?;
+    let midnight = |date: &str, days: i64| -> Result<chrono::DateTime<chrono::Utc>> {
+        let date = chrono::NaiveDate::parse_from_str(date, "%Y-%m-%d")
+            .with_context(|| format!("Invalid date '{}'", date))?
+            + chrono::Duration::days(days);
+        date.and_hms_opt(0, 0, 0)
+            .and_then(|t| t.and_local_timezone(tz).earliest())
+            .map(|t| t.with_timezone(&chrono::Utc))
+            .ok_or_else(|| anyhow::anyhow!("Invalid date '{}'", date))
+    };
+    let since = midnight(&start, 0)?;
+    let until = midnight(&end, 1)?;
+
+    if state {
+        let projection = journal::project(&db, Some(until)).await?;
+        println!(
+            "State at {} (event #{})",
+            until.with_timezone(&tz),
+            projection.seq
+        );
+        println!("\nGoals ({}):", projection.goals.len());
+        for (id, goal) in &projection.goals {
+            println!("  {} [{}] {}", id, goal.status, goal.description);
+        }
+        println!("\nWatchers ({}):", projection.watchers.len());
+        for (id, watcher) in &projection.watchers {
+            println!(
+                "  {} {} {} fired {}×{}",
+                id,
+                watcher.kind,
+                if watcher.active { "active" } else { "inactive" },
+                watcher.fired,
+                watcher
+                    .last_fired_at
+                    .map(|t| format!(
+                        ", last at {}",
+                        t.with_timezone(&tz).format("%Y-%m-%d %H:%M")
+                    ))
+                    .unwrap_or_default()
+            );
+        }
+        println!("\nTasks ({}):", projection.tasks.len());
+        for (id, task) in &projection.tasks {
+            println!("  {} [{}] {}", id, task.status, task.description);
+        }
+        println!(
+            "\nMemories: {} ({} writes), rules fired: {}",
+            projection.memories.len(),
+            projection.memory_writes,
+            projection.rules_fired
+        );
+        return Ok(());
+    }
+
+    let events = db
+        .get_events(meepo_knowledge::JournalQuery {
+            since: Some(since),
+            until: Some(until),
+            subject_id: subject,
+            kind,
+            ..Default::default()
+        })
+        .await?;
+    if events.is_empty() {
+        println!("No journal events for {}", period);
+    }
+    for event in &events {
+        println!(
+            "{}  {}",
+            event
+                .created_at
+                .with_timezone(&tz)
+                .format("%Y-%m-%d %H:%M:%S"),
+            journal::describe(event)
+        );
+    }
+    Ok(())
+}
+
+async fn cmd_rule(config_path: &Option<PathBuf>, action: RuleCommand) -> Result<()> {
+    use meepo_core::rules::{self, RuleAction};
+
+    let cfg = MeepoConfig::load(config_path)?;
+    let db_path = shellexpand(&cfg.knowledge.db_path);
+    if let Some(parent) = db_path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let db =
+        meepo_knowledge::KnowledgeDb::new(&db_path).context("Failed to open knowledge database")?;
+
+    match action {
+        RuleCommand::List => {
+            let stored = db.get_rules(false).await?;
+            if stored.is_empty() {
+                println!("No automation rules");
+            }
+            for rule in &stored {
+                println!("{}", rules::describe(rule));
+            }
+        }
+        RuleCommand::Add {
+            name,
+            event,
+            filters,
+            when,
+            notify,
+            template,
+            vars,
+            tool,
+            args,
+            prompt,
+            channel,
+            exclusive,
+        } => {
+            let action = match (notify, template, tool, prompt) {
+                (Some(message), None, None, None) => RuleAction::Notify { message },
+                (None, Some(template), None, None) => RuleAction::Template {
+                    template,
+                    vars: match vars {
+                        Some(vars) => serde_json::from_str(&vars).context("--vars must be JSON")?,
+                        None => serde_json::Value::Null,
+                    },
+                },
+                (None, None, Some(tool), None) => RuleAction::RunTool {
+                    tool,
+                    args: match args {
+                        Some(args) => serde_json::from_str(&args).context("--args must be JSON")?,
+                        None => serde_json::Value::Null,
+                    },
+                },
+                (None, None, None, Some(prompt)) => RuleAction::Prompt { prompt },
+                _ => bail!("Give exactly one of --notify, --template, --tool or --prompt"),
+            };
+            let mut filter = std::collections::BTreeMap::new();
+            for entry in &filters {
+                let (field, text) = entry.split_once('=').ok_or_else(|| {
+                    anyhow::anyhow!("--filter must be field=text, got '{}'", entry)
+                })?;
+                filter.insert(field.trim().to_string(), text.trim().to_string());
+            }
+            let id = rules::create_rule(
+                &db,
+                &name,
+                &event,
+                &filter,
+                when.as_deref(),
+                &action,
+                &channel,
+                exclusive,
+            )
+            .await?;
+            println!("Created rule {}", id);
+        }
+        RuleCommand::Enable { id } => {
+            if !db.set_rule_enabled(&id, true).await? {
+                bail!("No rule {}", id);
+            }
+            println!("Enabled rule {}", id);
+        }
+        RuleCommand::Disable { id } => {
+            if !db.set_rule_enabled(&id, false).await? {
+                bail!("No rule {}", id);
+            }
+            println!("Disabled rule {}", id);
+        }
+        RuleCommand::Remove { id } => {
+            if !db.delete_rule(&id).await? {
+                bail!("No rule {}", id);
+            }
+            println!("Deleted rule {}", id);
+        }
+    }
+    Ok(())
+}
+
+async fn cmd_quarantine(config_path: &Option<PathBuf>, action: QuarantineAction) -> Result<()> {
+    use meepo_core::spam_filter;
+
+    let cfg = MeepoConfig::load(config_path)?;
+    let db_path = shellexpand(&cfg.knowledge.db_path);
+    if let Some(parent) = db_path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let db =
+        meepo_knowledge::KnowledgeDb::new(&db_path).context("Failed to open knowledge database")?;
+
+    match action {
+        QuarantineAction::List => {
+            let messages = spam_filter::list(&db).await?;
+            if messages.is_empty() {
+                println!("No quarantined messages");
+            }
+            for entry in &messages {
+                let msg = &entry.message;
+                let preview: String = msg.content.chars().take(120).collect();
+                println!(
+                    "{}  {}  {} via {}{}",
+                    entry.id,
+                    entry
+                        .quarantined_at
+                        .with_timezone(&meepo_core::timezone::user_timezone())
+                        .format("%Y-%m-%d %H:%M"),
+                    msg.sender,
+                    msg.channel,
+                    if entry.released { "  (released)" } else { "" }
+                );
+                println!("    {}", preview.replace('\n', " "));
+                println!("    score {:.2}: {}", entry.score, entry.reasons.join("; "));
+            }
+        }
+        QuarantineAction::Release { id } => {
+            let entry = spam_filter::release(&db, &id).await?;
+            println!(
+                "Released {}; {} is now trusted",
+                entry.id, entry.message.sender
+            );
+        }
+        QuarantineAction::Drop { id, block } => {
+            let entry = spam_filter::discard(&db, &id, block).await?;
+            if block {
+                println!("Dropped {} and blocked {}", entry.id, entry.message.sender);
+            } else {
+                println!("Dropped {}", entry.id);
+            }
+        }
+    }
+    Ok(())
+}
+
+async fn cmd_memory(config_path: &Option<PathBuf>, action: MemoryAction) -> Result<()> {
+    use meepo_core::memory_review;
+
+    let cfg = MeepoConfig::load(config_path)?;
+    let db_path = shellexpand(&cfg.knowledge.db_path);
+    if let Some(parent) = db_path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let db =
+        meepo_knowledge::KnowledgeDb::new(&db_path).context("Failed to open knowledge database")?;
+
+    match action {
+        MemoryAction::Review => {
+            let staged = memory_review::list(&db).await?;
+            if staged.is_empty() {
+                println!("No memories waiting for review");
+                if !cfg.knowledge.review.enabled {
+                    println!("(review is off; enable [knowledge.review] to hold new memories)");
+                }
+            }
+            for entry in &staged {
+                println!(
+                    "{}  {}  from {}{}",
+                    entry.id,
+                    entry
+                        .staged_at
+                        .with_timezone(&meepo_core::timezone::user_timezone())
+                        .format("%Y-%m-%d %H:%M"),
+                    entry.source,
+                    if entry.approved { "  (approved)" } else { "" }
+                );
+                for line in entry.lines() {
+                    println!("    {}", line);
+                }
+                if let Some(error) = &entry.error {
+                    println!("    couldn't be stored: {}", error);
+                }
+            }
+        }
+        MemoryAction::Approve { ids } => {
+            for id in ids {
+                let entry = memory_review::approve(&db, &id).await?;
+                println!("Approved {} ({} item(s))", entry.id, entry.lines().len());
+            }
+        }
+        MemoryAction::Reject { ids } => {
+            for id in ids {
+                let entry = memory_review::reject(&db, &id).await?;
+                println!("Rejected {}", entry.id);
+            }
+        }
+    }
+    Ok(())
+}
+
+async fn cmd_guest(config_path: &Option<PathBuf>, action: GuestAction) -> Result<()> {
+    let cfg = MeepoConfig::load(config_path)?;
+    let db_path = shellexpand(&cfg.knowledge.db_path);
+    if let Some(parent) = db_path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let db =
+        meepo_knowledge::KnowledgeDb::new(&db_path).context("Failed to open knowledge database")?;
+
+    let (on, channel) = match action {
+        GuestAction::On { channel } => (true, channel),
+        GuestAction::Off { channel } => (false, channel),
+        GuestAction::Status => {
+            let mut scopes = meepo_core::guest::toggled_scopes(&db).await?;
+            scopes.sort();
+            let configured: Vec<&str> = cfg
+                .guest_mode
+                .channels
+                .iter()
+                .chain(&cfg.guest_mode.senders)
+                .map(String::as_str)
+                .collect();
+            if scopes.is_empty() && configured.is_empty() {
+                println!("Guest mode is off");
+            }
+            if !scopes.is_empty() {
+                println!("Guest mode on for: {}", scopes.join(", "));
+            }
+            if !configured.is_empty() {
+                println!("Always guests (config): {}", configured.join(", "));
+            }
+            return Ok(());
+        }
+    };
+    let scope = channel
+        .as_deref()
+        .unwrap_or(meepo_core::guest::ALL_CHANNELS);
+    meepo_core::guest::set_toggle(&db, scope, on).await?;
+    println!(
+        "Guest mode {} for {}",
+        if on { "on" } else { "off" },
+        if channel.is_some() {
+            scope
+        } else {
+            "all channels"
+        }
+    );
+    if !on && channel.is_none() {
+        // Per-channel toggles stay on until turned off individually
+        let remaining = meepo_core::guest::toggled_scopes(&db).await?;
+        if !remaining.is_empty() {
+            println!("Still on for: {}", remaining.join(", "));
+        }
+    }
+    Ok(())
+}
+
+async fn cmd_mode(config_path: &Option<PathBuf>, action: Option<ModeAction>) -> Result<()> {
+    let cfg = MeepoConfig::load(config_path)?;
+    meepo_core::timezone::set_user_timezone(meepo_core::timezone::resolve(&cfg.agent.timezone));
+    let modes = modes_config(&cfg);
+    if modes.modes.is_empty() {
+        println!("No modes configured. Add [[modes]] to your config.");
+        return Ok(());
+    }
+    let db_path = shellexpand(&cfg.knowledge.db_path);
+    if let Some(parent) = db_path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let db =
+        meepo_knowledge::KnowledgeDb::new(&db_path).context("Failed to open knowledge database")?;
+
+    let active = match action.unwrap_or(ModeAction::List) {
+        ModeAction::Set { name } => {
+            meepo_core::modes::set_manual(&db, &modes, &name, "cli").await?
+        }
+        ModeAction::List => {
+            for mode in &modes.modes {
+                let schedule: Vec<String> = cfg
+                    .modes
+                    .iter()
+                    .find(|m| m.name == mode.name)
+                    .map(|m| {
+                        m.schedule
+                            .iter()
+                            .map(|w| match w.days.is_empty() {
+                                true => format!("daily {}", w.hours),
+                                false => format!("{} {}", w.days.join(","), w.hours),
+                            })
+                            .collect()
+                    })
+                    .unwrap_or_default();
+                println!(
+                    "{:<12} {}{}",
+                    mode.name,
+                    mode.describe(),
+                    if schedule.is_empty() {
+                        String::new()
+                    } else {
+                        format!(" — on {}", schedule.join("; "))
+                    }
+                );
+            }
+            meepo_core::modes::resolve(&db, &modes).await?
+        }
+    };
+    match active {
+        Some(active) => println!(
+            "Mode: {} ({})",
+            active.mode.name,
+            match active.source {
+                meepo_core::modes::ModeSource::Manual => "picked by hand",
+                meepo_core::modes::ModeSource::Scheduled => "scheduled",
+            }
+        ),
+        None => println!("Mode: none"),
+    }
+    Ok(())
+}
+
+async fn cmd_tools(config_path: &Option<PathBuf>, action: ToolsAction) -> Result<()> {
+    let ToolsAction::Stats = action;
+    let cfg = MeepoConfig::load(config_path)?;
+    meepo_core::timezone::set_user_timezone(meepo_core::timezone::resolve(&cfg.agent.timezone));
+
+    let db_path = shellexpand(&cfg.knowledge.db_path);
+    if !db_path.exists() {
+        bail!(
+            "Knowledge database not found at {}. Run `meepo start` first.",
+            db_path.display()
+        );
+    }
+    let db =
+        meepo_knowledge::KnowledgeDb::new(&db_path).context("Failed to open knowledge database")?;
+
+    let stats = db.get_tool_stats().await?;
+    let unused: std::collections::HashSet<String> = db
+        .unused_tools(cfg.tool_usage.unused_days.max(1))
+        .await?
+        .into_iter()
+        .collect();
+
+    println!();
+    println!("  Meepo Tool Usage");
+    println!("  ════════════════");
+    println!();
+    if stats.is_empty() {
+        println!("  No tools recorded yet. Run `meepo start` first.");
+        println!();
+        return Ok(());
+    }
+    // Tokens are estimated at 4 characters each
+    let prompt_tokens: u64 = stats
+        .iter()
+        .filter(|s| !cfg.tool_usage.prune_unused || !unused.contains(&s.tool))
+        .map(|s| s.definition_chars / 4)
+        .sum();
+    println!(
+        "  Tools:         {} ({} called, {} unused for {}+ days)",
+        stats.len(),
+        stats.iter().filter(|s| s.calls > 0).count(),
+        unused.len(),
+        cfg.tool_usage.unused_days.max(1)
+    );
+    println!("  Definitions:   ~{} tokens per request", prompt_tokens);
+    println!();
+    println!(
+        "    {:<30} {:>6} {:>8} {:>8} {:>8} {:>10}  Last used",
+        "Tool", "Calls", "Success", "Avg ms", "Def tok", "Out tok"
+    );
+    for s in &stats {
+        let last_used = match s.last_used_at {
+            Some(at) => at
+                .with_timezone(&meepo_core::timezone::user_timezone())
+                .format("%Y-%m-%d")
+                .to_string(),
+            None => "never".to_string(),
+        };
+        let avg_ms = s.total_duration_ms.checked_div(s.calls).unwrap_or(0);
+        let success = if s.calls == 0 {
+            "-".to_string()
+        } else {
+            format!("{:.0}%", s.success_rate() * 100.0)
+        };
+        println!(
+            "    {:<30} {:>6} {:>8} {:>8} {:>8} {:>10}  {}{}",
+            s.tool,
+            s.calls,
+            success,
+            avg_ms,
+            s.definition_chars / 4,
+            s.output_chars / 4,
+            last_used,
+            if unused.contains(&s.tool) {
+                " (unused)"
+            } else {
+                ""
+            }
+        );
+    }
+    println!();
+    Ok(())
+}
+
+async fn cmd_feedback(config_path: &Option<PathBuf>, action: FeedbackAction) -> Result<()> {
+    let FeedbackAction::Report { period } = action;
+    let cfg = MeepoConfig::load(config_path)?;
+
+    let db_path = shellexpand(&cfg.knowledge.db_path);
+    if !db_path.exists() {
+        bail!(
+            "Knowledge database not found at {}. Run `meepo start` first.",
+            db_path.display()
+        );
+    }
+    let db =
+        meepo_knowledge::KnowledgeDb::new(&db_path).context("Failed to open knowledge database")?;
+
+    let (start, end) = parse_period(&period)?;
+    let summary = db.get_feedback_summary(&start, &end).await?;
+
+    println!();
+    println!("  Meepo Feedback Report");
+    println!("  ═════════════════════");
+    println!();
+    println!("  Period:        {}", summary.period);
+    if summary.overall.total() == 0 {
+        println!("  No feedback yet. Reply 👍 or 👎 to an answer to rate it.");
         println!();
-        println!("  By Source:");
-        for (source, usage) in &summary.by_source {
-            println!(
-                "    {:<15} {:>5} calls  {:>8} tokens  ${:.4}",
-                source,
-                usage.api_calls,
-                usage.input_tokens + usage.output_tokens,
-                usage.estimated_cost_usd
-            );
-        }
+        return Ok(());
     }
+    println!(
+        "  Ratings:       {} (👍 {}  👎 {})",
+        summary.overall.total(),
+        summary.overall.positive,
+        summary.overall.negative
+    );
+    println!(
+        "  Satisfaction:  {:.0}%",
+        summary.overall.satisfaction() * 100.0
+    );
 
-    if !summary.by_model.is_empty() {
+    for (title, width, breakdown) in [
+        ("By Channel", 15, &summary.by_channel),
+        ("By Tool", 25, &summary.by_tool),
+        ("By Model", 30, &summary.by_model),
+    ] {
+        let mut rows: Vec<_> = breakdown.iter().collect();
+        rows.sort_by(|a, b| b.1.total().cmp(&a.1.total()).then(a.0.cmp(b.0)));
         println!();
-        println!("  By Model:");
-        for (model, usage) in &summary.by_model {
+        println!("  {}:", title);
+        for (name, counts) in rows {
             println!(
-                "    {:<30} {:>5} calls  {:>8} tokens  ${:.4}",
-                model,
-                usage.api_calls,
-                usage.input_tokens + usage.output_tokens,
-                usage.estimated_cost_usd
+                "    {:<width$} {:>4}%  👍 {:<4} 👎 {:<4}",
+                name,
+                (counts.satisfaction() * 100.0).round() as u32,
+                counts.positive,
+                counts.negative,
+                width = width
             );
         }
     }
@@ -3151,8 +6602,147 @@ async fn cmd_usage(config_path: &Option<PathBuf>, period: &str, csv: bool) -> Re
     Ok(())
 }
 
-async fn cmd_mcp_server(config_path: &Option<PathBuf>) -> Result<()> {
+async fn cmd_experiment(config_path: &Option<PathBuf>, action: ExperimentAction) -> Result<()> {
+    let ExperimentAction::Report { name } = action;
+    let cfg = MeepoConfig::load(config_path)?;
+
+    let db_path = shellexpand(&cfg.knowledge.db_path);
+    if !db_path.exists() {
+        bail!(
+            "Knowledge database not found at {}. Run `meepo start` first.",
+            db_path.display()
+        );
+    }
+    let db =
+        meepo_knowledge::KnowledgeDb::new(&db_path).context("Failed to open knowledge database")?;
+
+    let name = name.unwrap_or_else(|| cfg.experiments.name.clone());
+    let report = meepo_core::experiments::ExperimentReport::load(&db, &name).await?;
+    println!();
+    if report.a.responses + report.b.responses == 0 {
+        println!("  No responses recorded for experiment '{}' yet.", name);
+    } else {
+        for line in report.format().lines() {
+            println!("  {}", line);
+        }
+    }
+    println!();
+    Ok(())
+}
+
+async fn cmd_report(config_path: &Option<PathBuf>, action: ReportAction) -> Result<()> {
+    let ReportAction::Run { name, deliver } = action;
+    let cfg = MeepoConfig::load(config_path)?;
+    meepo_core::timezone::set_user_timezone(meepo_core::timezone::resolve(&cfg.agent.timezone));
+
+    let db_path = shellexpand(&cfg.knowledge.db_path);
+    if !db_path.exists() {
+        bail!(
+            "Knowledge database not found at {}. Run `meepo start` first.",
+            db_path.display()
+        );
+    }
+    let db = Arc::new(
+        meepo_knowledge::KnowledgeDb::new(&db_path).context("Failed to open knowledge database")?,
+    );
+
+    let workspace = shellexpand(&cfg.memory.workspace);
+    let mut def = report_definitions(&cfg.reports, &workspace)?
+        .into_iter()
+        .find(|r| r.name == name)
+        .ok_or_else(|| anyhow::anyhow!("No report named '{}' in [[reports]]", name))?;
+    if !deliver {
+        def.destinations.clear();
+    } else if def
+        .destinations
+        .iter()
+        .any(|d| matches!(d, meepo_core::reports::ReportDestination::Channel(_)))
+    {
+        println!("  Channel delivery needs the daemon; skipping it.");
+        def.destinations
+            .retain(|d| !matches!(d, meepo_core::reports::ReportDestination::Channel(_)));
+    }
+
+    let scheduler = meepo_core::reports::ReportScheduler::new(vec![], db, workspace);
+    let report = scheduler
+        .run_report(&def, meepo_core::timezone::now().date_naive())
+        .await?;
+    println!("{}", report.body);
+    Ok(())
+}
+
+/// Build report definitions from `[[reports]]`, loading template files from
+/// the workspace
+fn report_definitions(
+    reports: &[crate::config::ReportCliConfig],
+    workspace: &std::path::Path,
+) -> Result<Vec<meepo_core::reports::ReportDefinition>> {
+    use meepo_core::reports::{ReportDefinition, ReportDestination, ReportSource};
+
+    reports
+        .iter()
+        .map(|r| {
+            let sources = r
+                .sources
+                .iter()
+                .map(|s| {
+                    serde_json::from_value::<ReportSource>(serde_json::json!(s)).map_err(|_| {
+                        anyhow::anyhow!("Unknown source '{}' in report '{}'", s, r.name)
+                    })
+                })
+                .collect::<Result<Vec<_>>>()?;
+            let template = r
+                .template_file
+                .as_ref()
+                .map(|file| {
+                    let path = workspace.join(file);
+                    std::fs::read_to_string(&path)
+                        .with_context(|| format!("Failed to read template {}", path.display()))
+                })
+                .transpose()?;
+            let mut destinations = Vec::new();
+            if let Some(channel) = &r.channel {
+                destinations.push(ReportDestination::Channel(
+                    meepo_core::types::ChannelType::from_string(channel),
+                ));
+            }
+            if let Some(email) = &r.email {
+                destinations.push(ReportDestination::Email(email.clone()));
+            }
+            if let Some(file) = &r.file {
+                destinations.push(ReportDestination::File(file.clone()));
+            }
+            let def = ReportDefinition {
+                name: r.name.clone(),
+                schedule: r.schedule.clone(),
+                period_days: r.period_days,
+                sources,
+                template,
+                destinations,
+            };
+            def.cron()?;
+            Ok(def)
+        })
+        .collect()
+}
+
+/// A `[[tool_profiles]]` entry by name
+fn tool_profile(cfg: &MeepoConfig, name: &str) -> Result<meepo_core::tool_profiles::ToolProfile> {
+    cfg.tool_profiles
+        .iter()
+        .find(|p| p.name == name)
+        .map(|p| p.to_profile())
+        .ok_or_else(|| anyhow::anyhow!("No tool profile named '{}' in [[tool_profiles]]", name))
+}
+
+async fn cmd_mcp_server(config_path: &Option<PathBuf>, profile: Option<String>) -> Result<()> {
     let cfg = MeepoConfig::load(config_path)?;
+    let profile = match profile.or_else(|| cfg.mcp.server.profile.clone()) {
+        Some(name) => Some(tool_profile(&cfg, &name)?),
+        None => None,
+    };
+    meepo_core::timezone::set_user_timezone(meepo_core::timezone::resolve(&cfg.agent.timezone));
+    meepo_core::i18n::set_language(&cfg.agent.language);
 
     // Build tool registry (same tools as cmd_start, minus channels/bus/orchestrator)
     let db_path = shellexpand(&cfg.knowledge.db_path);
@@ -3163,9 +6753,18 @@ async fn cmd_mcp_server(config_path: &Option<PathBuf>) -> Result<()> {
     std::fs::create_dir_all(&tantivy_path)?;
 
     let knowledge_graph = Arc::new(
-        meepo_knowledge::KnowledgeGraph::new(&db_path, &tantivy_path)
-            .context("Failed to initialize knowledge graph")?,
+        meepo_knowledge::KnowledgeGraph::with_index_config(
+            &db_path,
+            &tantivy_path,
+            cfg.knowledge.index.to_index_config(),
+        )
+        .context("Failed to initialize knowledge graph")?
+        .with_fuzzy(cfg.knowledge.search.fuzzy)
+        .with_synonyms(cfg.knowledge.search.synonyms.clone()),
     );
+    if let Err(e) = knowledge_graph.refresh_synonyms().await {
+        warn!("Failed to load search synonyms: {}", e);
+    }
     let db = knowledge_graph.db();
 
     // Tavily client (optional)
@@ -3186,9 +6785,7 @@ async fn cmd_mcp_server(config_path: &Option<PathBuf>) -> Result<()> {
     #[cfg(any(target_os = "macos", target_os = "windows"))]
     {
         registry.register(Arc::new(meepo_core::tools::macos::ReadEmailsTool::new()));
-        registry.register(Arc::new(meepo_core::tools::macos::ReadCalendarTool::new()));
         registry.register(Arc::new(meepo_core::tools::macos::SendEmailTool::new()));
-        registry.register(Arc::new(meepo_core::tools::macos::CreateEventTool::new()));
         registry.register(Arc::new(
             meepo_core::tools::accessibility::ReadScreenTool::new(),
         ));
@@ -3199,8 +6796,20 @@ async fn cmd_mcp_server(config_path: &Option<PathBuf>) -> Result<()> {
             meepo_core::tools::accessibility::TypeTextTool::new(),
         ));
     }
+    #[cfg(any(target_os = "macos", target_os = "windows", target_os = "linux"))]
+    {
+        registry.register(Arc::new(meepo_core::tools::macos::ReadCalendarTool::new()));
+        registry.register(Arc::new(create_event_tool(&cfg, db.clone())));
+    }
     registry.register(Arc::new(meepo_core::tools::macos::OpenAppTool::new()));
     registry.register(Arc::new(meepo_core::tools::macos::GetClipboardTool::new()));
+    #[cfg(target_os = "linux")]
+    {
+        registry.register(Arc::new(
+            meepo_core::tools::macos::SendNotificationTool::new(),
+        ));
+        registry.register(Arc::new(meepo_core::tools::macos::ScreenCaptureTool::new()));
+    }
     #[cfg(target_os = "macos")]
     {
         registry.register(Arc::new(meepo_core::tools::macos::ListRemindersTool::new()));
@@ -3291,6 +6900,9 @@ async fn cmd_mcp_server(config_path: &Option<PathBuf>) -> Result<()> {
         registry.register(Arc::new(
             meepo_core::tools::macos_shortcuts::RunShortcutTool::new(),
         ));
+        // Focus modes
+        registry.register(Arc::new(meepo_core::tools::macos_focus::GetFocusTool::new()));
+        registry.register(Arc::new(meepo_core::tools::macos_focus::SetFocusTool::new()));
         // Keychain
         registry.register(Arc::new(
             meepo_core::tools::macos_keychain::KeychainGetPasswordTool::new(),
@@ -3357,8 +6969,14 @@ async fn cmd_mcp_server(config_path: &Option<PathBuf>) -> Result<()> {
         ));
     }
     // Browser automation tools for MCP mode
+    if cfg.browser.enabled && cfg.browser.backend == "cdp" {
+        let cdp = meepo_core::platform::cdp::CdpBrowser::new(build_cdp_config(&cfg));
+        for tool in meepo_core::tools::browser::browser_tools("browser", || Box::new(cdp.clone())) {
+            registry.register(tool);
+        }
+    }
     #[cfg(target_os = "macos")]
-    if cfg.browser.enabled {
+    if cfg.browser.enabled && cfg.browser.backend != "cdp" {
         for b in &["safari", "chrome"] {
             registry.register(Arc::new(
                 meepo_core::tools::browser::BrowserListTabsTool::new(b),
@@ -3418,19 +7036,80 @@ async fn cmd_mcp_server(config_path: &Option<PathBuf>) -> Result<()> {
     registry.register(Arc::new(meepo_core::tools::code::ReviewPrTool::new(
         code_config,
     )));
-    registry.register(Arc::new(meepo_core::tools::memory::RememberTool::new(
+    registry.register(Arc::new(
+        meepo_core::tools::memory::RememberTool::new(db.clone())
+            .with_review(cfg.knowledge.review.enabled),
+    ));
+    registry.register(Arc::new(meepo_core::tools::memory::RecallTool::new(
         db.clone(),
     )));
-    registry.register(Arc::new(meepo_core::tools::memory::RecallTool::new(
+    registry.register(Arc::new(meepo_core::tools::memory::ListTagsTool::new(
         db.clone(),
     )));
+    registry.register(Arc::new(
+        meepo_core::tools::memory::ListKnowledgeGapsTool::new(db.clone()),
+    ));
+    registry.register(Arc::new(
+        meepo_core::tools::dossier::GenerateDossierTool::new(
+            db.clone(),
+            shellexpand(&cfg.memory.workspace),
+        ),
+    ));
     registry.register(Arc::new(
         meepo_core::tools::memory::SearchKnowledgeTool::with_graph(knowledge_graph.clone()),
     ));
-    registry.register(Arc::new(meepo_core::tools::memory::LinkEntitiesTool::new(
-        db.clone(),
+    registry.register(Arc::new(
+        meepo_core::tools::memory::LinkEntitiesTool::new(db.clone())
+            .with_review(cfg.knowledge.review.enabled),
+    ));
+    registry.register(Arc::new(
+        meepo_core::tools::memory::RememberManyTool::new(knowledge_graph.clone())
+            .with_review(cfg.knowledge.review.enabled),
+    ));
+    registry.register(Arc::new(meepo_core::tools::memory::ManageAliasesTool::new(
+        knowledge_graph.clone(),
+    )));
+    registry.register(Arc::new(meepo_core::tools::memory::ForgetTool::new(
+        knowledge_graph.clone(),
     )));
     registry.register(Arc::new(meepo_core::tools::system::RunCommandTool));
+    registry.register(Arc::new(
+        meepo_core::tools::datetime::CurrentDateTimeTool::new(cfg.agent.locale.clone()),
+    ));
+    registry.register(Arc::new(
+        meepo_core::tools::network::NetworkKillSwitchTool::new(),
+    ));
+    registry.register(Arc::new(meepo_core::tools::style::SetStyleTool::new(
+        db.clone(),
+    )));
+    registry.register(Arc::new(meepo_core::tools::pins::PinContextTool::new(
+        db.clone(),
+    )));
+    registry.register(Arc::new(meepo_core::tools::pins::UnpinContextTool::new(
+        db.clone(),
+    )));
+    registry.register(Arc::new(meepo_core::tools::rules::CreateRuleTool::new(
+        db.clone(),
+    )));
+    registry.register(Arc::new(meepo_core::tools::rules::ListRulesTool::new(
+        db.clone(),
+    )));
+    let message_templates = Arc::new(
+        meepo_core::message_templates::TemplateStore::new(db.clone())
+            .with_workspace(shellexpand(&cfg.memory.workspace)),
+    );
+    registry.register(Arc::new(
+        meepo_core::tools::message_templates::SaveTemplateTool::new(message_templates.clone()),
+    ));
+    registry.register(Arc::new(
+        meepo_core::tools::message_templates::ListTemplatesTool::new(message_templates),
+    ));
+    registry.register(Arc::new(
+        meepo_core::tools::onboarding::CompleteOnboardingTool::new(
+            db.clone(),
+            shellexpand(&cfg.memory.workspace).join(&cfg.agent.memory_file),
+        ),
+    ));
     registry.register(Arc::new(meepo_core::tools::system::ReadFileTool));
     registry.register(Arc::new(meepo_core::tools::system::WriteFileTool));
     registry.register(Arc::new(
@@ -3482,6 +7161,16 @@ async fn cmd_mcp_server(config_path: &Option<PathBuf>) -> Result<()> {
         registry.register(Arc::new(
             meepo_core::tools::lifestyle::email_intelligence::EmailUnsubscribeTool::new(),
         ));
+        registry.register(Arc::new(
+            meepo_core::tools::lifestyle::email_intelligence::EmailRuleTool::new(db.clone()),
+        ));
+        // The briefing reads the inbox as well as the calendar
+        registry.register(Arc::new(
+            meepo_core::tools::lifestyle::calendar::DailyBriefingTool::new(db.clone()),
+        ));
+    }
+    #[cfg(any(target_os = "macos", target_os = "windows", target_os = "linux"))]
+    {
         registry.register(Arc::new(
             meepo_core::tools::lifestyle::calendar::FindFreeTimeTool::new(),
         ));
@@ -3492,10 +7181,13 @@ async fn cmd_mcp_server(config_path: &Option<PathBuf>) -> Result<()> {
             meepo_core::tools::lifestyle::calendar::RescheduleEventTool::new(),
         ));
         registry.register(Arc::new(
-            meepo_core::tools::lifestyle::calendar::DailyBriefingTool::new(db.clone()),
+            meepo_core::tools::lifestyle::calendar::WeeklyReviewTool::new(db.clone()),
         ));
         registry.register(Arc::new(
-            meepo_core::tools::lifestyle::calendar::WeeklyReviewTool::new(db.clone()),
+            meepo_core::tools::lifestyle::calendar::MeetingPrepTool::new(
+                meepo_core::meeting_prep::MeetingPrep::for_platform(db.clone())
+                    .expect("Calendar provider not available on this platform"),
+            ),
         ));
     }
     registry.register(Arc::new(
@@ -3609,20 +7301,35 @@ async fn cmd_mcp_server(config_path: &Option<PathBuf>) -> Result<()> {
         meepo_core::tools::canvas::CanvasSnapshotTool::new(),
     ));
     // ── Docker Sandbox Tool (MCP mode) ──────────────────────────────
-    registry.register(Arc::new(
-        meepo_core::tools::sandbox_exec::SandboxExecTool::new(meepo_core::sandbox::SandboxConfig {
-            enabled: cfg.sandbox.enabled,
-            docker_socket: cfg.sandbox.docker_socket.clone(),
-            policy: meepo_core::sandbox::policy::ExecutionPolicy {
-                resource_limits: meepo_core::sandbox::policy::ResourceLimits {
-                    memory_mb: cfg.sandbox.memory_mb,
-                    timeout_secs: cfg.sandbox.timeout_secs,
-                    network_enabled: cfg.sandbox.network_enabled,
-                    ..Default::default()
-                },
+    let sandbox_config = meepo_core::sandbox::SandboxConfig {
+        enabled: cfg.sandbox.enabled,
+        docker_socket: cfg.sandbox.docker_socket.clone(),
+        policy: meepo_core::sandbox::policy::ExecutionPolicy {
+            resource_limits: meepo_core::sandbox::policy::ResourceLimits {
+                memory_mb: cfg.sandbox.memory_mb,
+                timeout_secs: cfg.sandbox.timeout_secs,
+                network_enabled: cfg.sandbox.network_enabled,
                 ..Default::default()
             },
-        }),
+            ..Default::default()
+        },
+    };
+    registry.register(Arc::new(
+        meepo_core::tools::sandbox_exec::SandboxExecTool::new(sandbox_config.clone()),
+    ));
+    registry.register(Arc::new(meepo_core::tools::script::ExecuteScriptTool::new(
+        sandbox_config,
+        shellexpand(&cfg.sandbox.artifacts_dir),
+    )));
+    if cfg.http.enabled {
+        registry.register(Arc::new(build_http_tool(&cfg)));
+    }
+    if let Some(manager) = build_download_manager(&cfg) {
+        register_download_tools(&mut registry, &cfg, manager);
+    }
+    registry.register(Arc::new(
+        meepo_core::tools::pdf::ReadPdfTool::new(knowledge_graph.clone())
+            .with_chunking_config(cfg.rag.chunking.to_chunking_config()),
     ));
 
     // ── Agent-to-Agent Session Tools (MCP mode) ──────────────────────
@@ -3692,6 +7399,21 @@ async fn cmd_mcp_server(config_path: &Option<PathBuf>) -> Result<()> {
         ));
     }
 
+    if cfg.app_usage.enabled {
+        registry.register(Arc::new(meepo_core::tools::app_usage::AppUsageTool::new(
+            db.clone(),
+        )));
+    }
+
+    if cfg.artifact_sync.enabled {
+        match artifact_sync(&cfg, db.clone()) {
+            Ok(sync) => registry.register(Arc::new(
+                meepo_core::tools::artifact_sync::SyncNowTool::new(Arc::new(sync)),
+            )),
+            Err(e) => warn!("Artifact sync disabled: {:#}", e),
+        }
+    }
+
     // Load skills if enabled
     if cfg.skills.enabled {
         let skills_dir = shellexpand(&cfg.skills.dir);
@@ -3701,6 +7423,9 @@ async fn cmd_mcp_server(config_path: &Option<PathBuf>) -> Result<()> {
             }
         }
     }
+    if cfg.plugins.enabled {
+        register_plugins(&cfg, &mut registry);
+    }
 
     // ── MCP Clients — connect to external MCP servers ──────────────
     for client_cfg in &cfg.mcp.clients {
@@ -3740,7 +7465,11 @@ async fn cmd_mcp_server(config_path: &Option<PathBuf>) -> Result<()> {
     info!("MCP server: {} tools available", registry.len());
 
     // Create MCP adapter and server
-    let adapter = meepo_mcp::McpToolAdapter::new(registry);
+    let mut adapter = meepo_mcp::McpToolAdapter::new(registry);
+    if let Some(profile) = profile {
+        info!("MCP server: limited to tool profile '{}'", profile.name);
+        adapter = adapter.with_profile(profile);
+    }
     let server = meepo_mcp::McpServer::new(adapter);
 
     // Serve over STDIO
@@ -3769,15 +7498,48 @@ async fn cmd_template(action: TemplateAction) -> Result<()> {
             println!();
             Ok(())
         }
-        TemplateAction::Use { name } => {
+        TemplateAction::Use { name, params, yes } => {
             let t = template::resolve_template(&name)?;
             println!("\n  Activating template: {}", t.metadata.name);
             println!("  {}\n", t.metadata.description);
 
+            // Fill in the template's parameters before changing anything
+            let mut given = std::collections::HashMap::new();
+            for param in &params {
+                let (key, value) = param
+                    .split_once('=')
+                    .with_context(|| format!("Expected NAME=VALUE, got '{}'", param))?;
+                given.insert(key.trim().to_string(), value.to_string());
+            }
+            let values = t.resolve_params(given, prompt_template_param)?;
+            let (goals, watchers) = t.instantiate(&values);
+            let mut watcher_kinds = Vec::with_capacity(watchers.len());
+            for watcher in &watchers {
+                let config = serde_json::to_value(&watcher.config)?;
+                let kind = watcher_kind_from_tool(&watcher.kind, config.clone())
+                    .with_context(|| format!("Invalid template watcher '{}'", watcher.action))?;
+                watcher_kinds.push((config, kind));
+            }
+            if !values.is_empty() {
+                println!();
+            }
+
             let config_dir = config::config_dir();
             let config_path = config_dir.join("config.toml");
             let workspace = config_dir.join("workspace");
 
+            // Show what the template would change and what it needs, so
+            // the user can back out before anything is touched
+            if !preview_template(&t, &config_path)? && !yes {
+                print!("  Apply the template anyway? [y/N]: ");
+                std::io::Write::flush(&mut std::io::stdout())?;
+                if !prompt_yes_no()? {
+                    println!("  Aborted — nothing was changed.");
+                    return Ok(());
+                }
+                println!();
+            }
+
             // 1. Backup current config
             if config_path.exists() {
                 std::fs::copy(&config_path, config_dir.join("config.toml.bak"))?;
@@ -3816,27 +7578,64 @@ async fn cmd_template(action: TemplateAction) -> Result<()> {
                 println!("  Merged config overlay");
             }
 
-            // 5. Insert goals into database
-            if !t.goals.is_empty() {
+            // 5. Insert goals and watchers into database
+            let mut watcher_ids = Vec::new();
+            if !goals.is_empty() || !watchers.is_empty() {
                 let db_path = config_dir.join("knowledge.db");
                 if db_path.exists() {
                     let db = meepo_knowledge::KnowledgeDb::new(&db_path)?;
                     let source = format!("template:{}", t.metadata.name);
-                    for goal in &t.goals {
-                        db.insert_goal(
-                            &goal.description,
-                            goal.priority,
-                            goal.check_interval_secs,
-                            goal.success_criteria.as_deref(),
-                            None,
-                            &source,
-                        )
-                        .await?;
+                    for goal in &goals {
+                        let id = db
+                            .insert_goal(
+                                &goal.description,
+                                goal.priority,
+                                goal.check_interval_secs,
+                                goal.success_criteria.as_deref(),
+                                None,
+                                &source,
+                            )
+                            .await?;
+                        if let Some(deadline) = &goal.deadline {
+                            let due = meepo_core::calendar_feed::parse_time(deadline)
+                                .with_context(|| format!("Invalid goal deadline '{}'", deadline))?;
+                            db.set_goal_deadline(&id, Some(due)).await?;
+                        }
+                    }
+                    if !goals.is_empty() {
+                        println!("  Injected {} goals", goals.len());
+                    }
+
+                    let sched_db = rusqlite::Connection::open(&db_path)?;
+                    meepo_scheduler::persistence::init_watcher_tables(&sched_db)?;
+                    for (watcher, (config, kind)) in watchers.iter().zip(watcher_kinds) {
+                        let id = db
+                            .insert_watcher(
+                                &watcher.kind,
+                                config,
+                                &watcher.action,
+                                &watcher.reply_channel,
+                            )
+                            .await?;
+                        meepo_scheduler::persistence::save_watcher(
+                            &sched_db,
+                            &meepo_scheduler::Watcher {
+                                id: id.clone(),
+                                kind,
+                                action: watcher.action.clone(),
+                                reply_channel: watcher.reply_channel.clone(),
+                                active: true,
+                                created_at: chrono::Utc::now(),
+                            },
+                        )?;
+                        watcher_ids.push(id);
+                    }
+                    if !watcher_ids.is_empty() {
+                        println!("  Created {} watchers", watcher_ids.len());
                     }
-                    println!("  Injected {} goals", t.goals.len());
                 } else {
                     println!(
-                        "  Note: knowledge.db not found — goals will be injected on first `meepo start`"
+                        "  Note: knowledge.db not found — run `meepo start` once, then activate the template again to add its goals and watchers"
                     );
                 }
             }
@@ -3860,7 +7659,7 @@ async fn cmd_template(action: TemplateAction) -> Result<()> {
             }
 
             // 7. Record active template
-            template::set_active_template(&t.metadata.name, "local")?;
+            template::set_active_template(&t.metadata.name, "local", watcher_ids)?;
 
             println!("\n  Template '{}' activated!", t.metadata.name);
             println!(
@@ -3879,6 +7678,17 @@ async fn cmd_template(action: TemplateAction) -> Result<()> {
             if !t.metadata.tags.is_empty() {
                 println!("  Tags: {}", t.metadata.tags.join(", "));
             }
+            if !t.params.is_empty() {
+                println!("\n  Parameters ({}):", t.params.len());
+                for param in &t.params {
+                    let default = param
+                        .default
+                        .as_ref()
+                        .map(|d| format!(" (default: {})", d))
+                        .unwrap_or_default();
+                    println!("    - {}{} {}", param.name, default, param.prompt);
+                }
+            }
             println!("\n  Goals ({}):", t.goals.len());
             for goal in &t.goals {
                 println!(
@@ -3886,6 +7696,12 @@ async fn cmd_template(action: TemplateAction) -> Result<()> {
                     goal.priority, goal.description, goal.check_interval_secs
                 );
             }
+            if !t.watchers.is_empty() {
+                println!("\n  Watchers ({}):", t.watchers.len());
+                for watcher in &t.watchers {
+                    println!("    - [{}] {}", watcher.kind, watcher.action);
+                }
+            }
             if let Some(overlay) = t.config_overlay.as_table()
                 && !overlay.is_empty()
             {
@@ -3943,6 +7759,15 @@ async fn cmd_template(action: TemplateAction) -> Result<()> {
                 let source = format!("template:{}", active.name);
                 let deleted = db.delete_goals_by_source(&source).await?;
                 println!("  Removed {} template goals", deleted);
+                if !active.watchers.is_empty() {
+                    let sched_db = rusqlite::Connection::open(&db_path)?;
+                    meepo_scheduler::persistence::init_watcher_tables(&sched_db)?;
+                    for id in &active.watchers {
+                        db.delete_watcher(id).await?;
+                        meepo_scheduler::persistence::delete_watcher(&sched_db, id)?;
+                    }
+                    println!("  Removed {} template watchers", active.watchers.len());
+                }
             }
 
             // 5. Clear active template
@@ -4070,6 +7895,261 @@ async fn cmd_doctor(config_path: &Option<PathBuf>) -> Result<()> {
     Ok(())
 }
 
+/// Map `[browser.cdp]` config onto the core CDP backend config
+fn build_cdp_config(cfg: &MeepoConfig) -> meepo_core::platform::cdp::CdpConfig {
+    let cdp = &cfg.browser.cdp;
+    meepo_core::platform::cdp::CdpConfig {
+        endpoint: cdp.endpoint.clone(),
+        launch: cdp.launch,
+        headless: cdp.headless,
+        chrome_path: cdp.chrome_path.as_deref().map(shellexpand_str),
+        user_data_dir: cdp.user_data_dir.as_deref().map(shellexpand),
+        timeout_ms: cdp.timeout_ms,
+    }
+}
+
+/// Build the http_request tool from config, resolving profile secrets via the secrets store
+fn build_http_tool(cfg: &MeepoConfig) -> meepo_core::tools::http::HttpRequestTool {
+    use meepo_core::tools::http::{HttpAuthProfile, HttpAuthType, HttpToolConfig};
+
+    let profiles = cfg
+        .http
+        .profiles
+        .iter()
+        .map(|(name, p)| {
+            let auth_type = match p.auth_type.as_str() {
+                "header" => HttpAuthType::Header,
+                "query" => HttpAuthType::Query,
+                "basic" => HttpAuthType::Basic,
+                "bearer" => HttpAuthType::Bearer,
+                other => {
+                    warn!(
+                        "Unknown auth_type '{}' for http profile '{}', using bearer",
+                        other, name
+                    );
+                    HttpAuthType::Bearer
+                }
+            };
+            (
+                name.clone(),
+                HttpAuthProfile {
+                    auth_type,
+                    secret: p.secret.clone(),
+                    name: p.name.clone(),
+                    domains: p.domains.clone(),
+                },
+            )
+        })
+        .collect();
+
+    let secrets =
+        meepo_core::secrets::SecretsManager::from_config(&meepo_core::secrets::SecretsConfig {
+            provider: match cfg.secrets.provider.as_str() {
+                "file" => meepo_core::secrets::SecretsProviderType::File,
+                _ => meepo_core::secrets::SecretsProviderType::Env,
+            },
+            secrets_dir: cfg.secrets.secrets_dir.as_deref().map(shellexpand_str),
+        });
+
+    meepo_core::tools::http::HttpRequestTool::new(
+        HttpToolConfig {
+            allowed_domains: cfg.http.allowed_domains.clone(),
+            blocked_domains: cfg.http.blocked_domains.clone(),
+            max_response_bytes: cfg.http.max_response_bytes,
+            timeout_secs: cfg.http.timeout_secs,
+            profiles,
+        },
+        Arc::new(secrets),
+    )
+}
+
+/// Create the shared download manager, or `None` if downloads are disabled or misconfigured
+fn build_download_manager(
+    cfg: &MeepoConfig,
+) -> Option<meepo_core::tools::download::DownloadManager> {
+    if !cfg.downloads.enabled {
+        return None;
+    }
+    let dir = shellexpand_str(&cfg.downloads.dir);
+    match meepo_core::tools::download::DownloadManager::new(&dir, cfg.downloads.max_bytes) {
+        Ok(manager) => Some(manager),
+        Err(e) => {
+            warn!("Downloads disabled: {}", e);
+            None
+        }
+    }
+}
+
+/// Register download_file, download_status, and (with the CDP backend) browser_download
+fn register_download_tools(
+    registry: &mut meepo_core::tools::ToolRegistry,
+    cfg: &MeepoConfig,
+    manager: meepo_core::tools::download::DownloadManager,
+) {
+    use meepo_core::tools::download::{BrowserDownloadTool, DownloadFileTool, DownloadStatusTool};
+
+    let manager = Arc::new(manager);
+    registry.register(Arc::new(DownloadFileTool::new(manager.clone())));
+    registry.register(Arc::new(DownloadStatusTool::new(manager.clone())));
+    if cfg.browser.enabled && cfg.browser.backend == "cdp" {
+        let cdp = meepo_core::platform::cdp::CdpBrowser::new(build_cdp_config(cfg));
+        registry.register(Arc::new(BrowserDownloadTool::new(
+            "browser",
+            manager,
+            Box::new(cdp),
+        )));
+    } else if cfg.browser.enabled
+        && let Ok(provider) =
+            meepo_core::platform::create_browser_provider_for(&cfg.browser.default_browser)
+    {
+        registry.register(Arc::new(BrowserDownloadTool::new(
+            "browser", manager, provider,
+        )));
+    }
+}
+
+/// Build a scheduler watcher kind from the create_watcher tool's kind
+/// string and config object
+fn watcher_kind_from_tool(
+    kind: &str,
+    config: serde_json::Value,
+) -> Result<meepo_scheduler::watcher::WatcherKind> {
+    // Map the tool's kind string to WatcherKind's serde tag variant name
+    let type_tag = match kind {
+        "email" => "EmailWatch",
+        "calendar" => "CalendarWatch",
+        "github" => "GitHubWatch",
+        "file" => "FileWatch",
+        "message" => "MessageWatch",
+        "scheduled" | "time" => "Scheduled",
+        "oneshot" => "OneShot",
+        other => bail!("Unknown watcher kind: {}", other),
+    };
+    // Inject the "type" tag into config for serde deserialization
+    let serde_json::Value::Object(mut map) = config else {
+        bail!("Watcher config is not a JSON object");
+    };
+    map.insert(
+        "type".to_string(),
+        serde_json::Value::String(type_tag.to_string()),
+    );
+    serde_json::from_value(serde_json::Value::Object(map))
+        .context("Failed to deserialize watcher kind")
+}
+
+/// Wrap `text` in an ANSI color when stdout is a terminal
+fn paint(text: &str, color: u8) -> String {
+    use std::io::IsTerminal;
+    if std::io::stdout().is_terminal() {
+        format!("\x1b[{}m{}\x1b[0m", color, text)
+    } else {
+        text.to_string()
+    }
+}
+
+/// Print the config diff, unknown keys and missing requirements of a
+/// template. Fails if the merged config no longer parses; returns `false`
+/// when something deserves a confirmation.
+fn preview_template(t: &template::Template, config_path: &std::path::Path) -> Result<bool> {
+    const RED: u8 = 31;
+    const GREEN: u8 = 32;
+    const YELLOW: u8 = 33;
+    let mut clean = true;
+
+    let has_overlay = t.config_overlay.as_table().is_some_and(|o| !o.is_empty());
+    if has_overlay && config_path.exists() {
+        let current: toml::Value = toml::from_str(&std::fs::read_to_string(config_path)?)
+            .context("Failed to parse current config.toml")?;
+        let mut merged = current.clone();
+        template::deep_merge(&mut merged, &t.config_overlay);
+        let checked = toml::from_str::<config::MeepoConfig>(&toml::to_string(&merged)?)
+            .with_context(|| {
+                format!(
+                    "Template '{}' isn't compatible with this version's config",
+                    t.metadata.name
+                )
+            })?;
+        let schema = toml::Value::try_from(&checked)?;
+
+        let changes = template::overlay_changes(&current, &t.config_overlay);
+        if !changes.is_empty() {
+            println!("  Config changes:");
+            let mut overwritten = 0;
+            for change in &changes {
+                match change {
+                    template::ConfigChange::Added { path, value } => {
+                        println!("{}", paint(&format!("    + {} = {}", path, value), GREEN));
+                    }
+                    template::ConfigChange::Overwritten { path, old, new } => {
+                        overwritten += 1;
+                        println!("{}", paint(&format!("    - {} = {}", path, old), RED));
+                        println!("{}", paint(&format!("    + {} = {}", path, new), GREEN));
+                    }
+                    template::ConfigChange::Appended { path, values } => {
+                        for value in values {
+                            println!("{}", paint(&format!("    + {} += {}", path, value), GREEN));
+                        }
+                    }
+                }
+            }
+            if overwritten > 0 {
+                println!(
+                    "{}",
+                    paint(
+                        &format!("  {} existing key(s) would be overwritten", overwritten),
+                        YELLOW
+                    )
+                );
+                clean = false;
+            }
+            println!();
+        }
+
+        let unknown = template::unrecognized_keys(&t.config_overlay, &schema);
+        if !unknown.is_empty() {
+            println!(
+                "{}",
+                paint("  Unknown config keys (they'd be ignored):", YELLOW)
+            );
+            for key in &unknown {
+                println!("    - {}", key);
+            }
+            println!();
+            clean = false;
+        }
+    }
+
+    let missing = t.missing_requirements();
+    if !missing.is_empty() {
+        println!("{}", paint("  Missing requirements:", YELLOW));
+        for requirement in &missing {
+            println!("    - {}", requirement);
+        }
+        println!();
+        clean = false;
+    }
+    Ok(clean)
+}
+
+/// Ask for a template parameter on stdin; `None` takes the default
+fn prompt_template_param(param: &template::TemplateParam) -> Result<Option<String>> {
+    use std::io::{self, BufRead, Write};
+    let question = if param.prompt.is_empty() {
+        &param.name
+    } else {
+        &param.prompt
+    };
+    match &param.default {
+        Some(default) => print!("  {} [{}]: ", question, default),
+        None => print!("  {}: ", question),
+    }
+    io::stdout().flush()?;
+    let mut answer = String::new();
+    io::stdin().lock().read_line(&mut answer)?;
+    let answer = answer.trim();
+    Ok((!answer.is_empty()).then(|| answer.to_string()))
+}
+
 /// Recursively copy a directory
 fn copy_dir_recursive(src: &std::path::Path, dst: &std::path::Path) -> Result<()> {
     std::fs::create_dir_all(dst)?;
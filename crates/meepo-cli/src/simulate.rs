@@ -0,0 +1,263 @@
+//! `meepo simulate` — run the daemon against a scripted scenario
+//!
+//! The scenario's conversation is played through a `SimChannel` registered
+//! on the bus in place of the real channels. Knowledge, memory and the
+//! scheduler database live in a throwaway directory, network-facing servers
+//! stay off, and tools listed under `mock_tools` are replaced by stubs so
+//! nothing reaches real accounts.
+
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use meepo_channels::sim::{Scenario, SimChannel, SimHandle, SimReport};
+use meepo_core::tools::{ToolHandler, ToolRegistry};
+use serde_json::Value;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tracing::warn;
+
+use crate::config::{MeepoConfig, ReplayCliConfig};
+
+/// A scenario being run by the daemon
+pub struct Simulation {
+    pub scenario: Scenario,
+    pub handle: SimHandle,
+    channel: Option<SimChannel>,
+}
+
+impl Simulation {
+    pub fn new(scenario: Scenario) -> Self {
+        let channel = SimChannel::new(&scenario);
+        Self {
+            handle: channel.handle(),
+            channel: Some(channel),
+            scenario,
+        }
+    }
+
+    /// The channel adapter to register on the bus (once)
+    pub fn take_channel(&mut self) -> Option<SimChannel> {
+        self.channel.take()
+    }
+
+    /// Swap mocked tools in the registry for stubs
+    pub fn mock_tools(&self, registry: &mut ToolRegistry) {
+        for (name, output) in &self.scenario.mock_tools {
+            match registry.get(name) {
+                Some(real) => registry.register(Arc::new(MockTool {
+                    real,
+                    output: output.clone(),
+                })),
+                None => warn!("Scenario mocks unknown tool '{}' — ignoring", name),
+            }
+        }
+    }
+}
+
+/// Load a scenario from a YAML file
+pub fn load_scenario(path: &Path) -> Result<Scenario> {
+    let raw = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read scenario {}", path.display()))?;
+    let mut scenario: Scenario = serde_yml::from_str(&raw)
+        .with_context(|| format!("Failed to parse scenario {}", path.display()))?;
+    if scenario.name.is_empty() {
+        scenario.name = path
+            .file_stem()
+            .map(|s| s.to_string_lossy().to_string())
+            .unwrap_or_default();
+    }
+    // Relative fixture paths are relative to the scenario file
+    if let Some(fixtures) = &scenario.fixtures {
+        let fixtures = crate::shellexpand(fixtures);
+        if fixtures.is_relative() {
+            let base = path.parent().unwrap_or(Path::new("."));
+            scenario.fixtures = Some(base.join(fixtures).to_string_lossy().to_string());
+        }
+    }
+    Ok(scenario)
+}
+
+/// Point the config at a scratch directory and turn off everything that
+/// talks to the outside world. With `record`, live LLM responses are saved
+/// to the scenario's fixtures instead of replayed from them.
+pub fn sandbox_config(
+    cfg: &mut MeepoConfig,
+    scenario: &Scenario,
+    dir: &Path,
+    record: bool,
+) -> Result<()> {
+    let workspace = dir.join("workspace");
+    std::fs::create_dir_all(&workspace)?;
+    // Start from a copy of the real SOUL and MEMORY so behaviour matches the daemon
+    let real_workspace = crate::shellexpand(&cfg.memory.workspace);
+    for file in [&cfg.agent.system_prompt_file, &cfg.agent.memory_file] {
+        let src = real_workspace.join(file);
+        if src.exists() {
+            std::fs::copy(&src, workspace.join(file))?;
+        }
+    }
+    cfg.memory.workspace = workspace.to_string_lossy().to_string();
+    cfg.knowledge.db_path = dir.join("knowledge.db").to_string_lossy().to_string();
+    cfg.knowledge.tantivy_path = dir.join("tantivy").to_string_lossy().to_string();
+    cfg.knowledge.auto_tag.enabled = false;
+
+    cfg.channels.discord.enabled = false;
+    cfg.channels.slack.enabled = false;
+    cfg.channels.imessage.enabled = false;
+    cfg.channels.email.enabled = false;
+    cfg.channels.alexa.enabled = false;
+    cfg.channels.reminders.enabled = false;
+    cfg.channels.notes.enabled = false;
+    cfg.channels.contacts.enabled = false;
+    cfg.gateway.enabled = false;
+    cfg.a2a.enabled = false;
+    cfg.notifications.digest.enabled = false;
+    // Host state shouldn't make runs flaky
+    cfg.power.enabled = false;
+    cfg.autonomy.idle.enabled = false;
+
+    if let Some(fixtures) = &scenario.fixtures {
+        cfg.providers.replay = Some(ReplayCliConfig {
+            mode: if record { "record" } else { "replay" }.to_string(),
+            dir: fixtures.clone(),
+        });
+    }
+    Ok(())
+}
+
+/// Scratch directory for one simulation run
+pub fn scratch_dir() -> PathBuf {
+    std::env::temp_dir().join(format!(
+        "meepo-sim-{}",
+        &uuid::Uuid::new_v4().to_string()[..8]
+    ))
+}
+
+/// Print a human-readable report
+pub fn print_report(report: &SimReport) {
+    println!();
+    println!("Scenario: {}", report.name);
+    for (i, turn) in report.turns.iter().enumerate() {
+        let status = if turn.failures.is_empty() {
+            "PASS"
+        } else {
+            "FAIL"
+        };
+        println!("  [{}] {}. > {}", status, i + 1, turn.input);
+        for reply in &turn.replies {
+            println!("         < {}", reply.replace('\n', "\n           "));
+        }
+        for failure in &turn.failures {
+            println!("         ✗ {}", failure);
+        }
+    }
+    if !report.unsolicited.is_empty() {
+        println!("  {} unsolicited message(s):", report.unsolicited.len());
+        for msg in &report.unsolicited {
+            println!("         < {}", msg);
+        }
+    }
+    println!();
+    if report.passed() {
+        println!("{} turn(s) passed", report.turns.len());
+    } else {
+        println!(
+            "{} of {} turn(s) failed",
+            report.failed_turns(),
+            report.turns.len()
+        );
+    }
+}
+
+/// Stands in for a real tool, keeping its name and schema
+struct MockTool {
+    real: Arc<dyn ToolHandler>,
+    output: String,
+}
+
+#[async_trait]
+impl ToolHandler for MockTool {
+    fn name(&self) -> &str {
+        self.real.name()
+    }
+
+    fn description(&self) -> &str {
+        self.real.description()
+    }
+
+    fn input_schema(&self) -> Value {
+        self.real.input_schema()
+    }
+
+    async fn execute(&self, input: Value) -> Result<String> {
+        tracing::info!("Mocked tool {} called with {}", self.real.name(), input);
+        Ok(self.output.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sandbox_config() {
+        let mut cfg: MeepoConfig = toml::from_str(
+            r#"
+            [agent]
+            default_model = "claude-sonnet-4-20250514"
+            max_tokens = 8192
+            [providers]
+            [channels.discord]
+            enabled = true
+            [channels.slack]
+            [channels.imessage]
+            [knowledge]
+            db_path = "~/.meepo/knowledge.db"
+            tantivy_path = "~/.meepo/tantivy_index"
+            [watchers.active_hours]
+            start = "08:00"
+            end = "23:00"
+            [code]
+            [memory]
+            workspace = "/nonexistent/meepo-workspace"
+            [gateway]
+            enabled = true
+            "#,
+        )
+        .unwrap();
+        let scenario: Scenario =
+            serde_yml::from_str("fixtures: /tmp/fixtures\nturns: []\n").unwrap();
+        let dir = scratch_dir();
+
+        sandbox_config(&mut cfg, &scenario, &dir, false).unwrap();
+        assert!(!cfg.channels.discord.enabled);
+        assert!(!cfg.gateway.enabled);
+        assert!(cfg.knowledge.db_path.starts_with(dir.to_str().unwrap()));
+        let replay = cfg.providers.replay.as_ref().unwrap();
+        assert_eq!(replay.mode, "replay");
+        assert_eq!(replay.dir, "/tmp/fixtures");
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_load_scenario_resolves_fixtures() {
+        let dir = scratch_dir();
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("inbox.yaml");
+        std::fs::write(
+            &path,
+            "fixtures: fixtures\nturns:\n  - user: hi\n    expect:\n      contains: [hello]\n",
+        )
+        .unwrap();
+
+        let scenario = load_scenario(&path).unwrap();
+        assert_eq!(scenario.name, "inbox");
+        assert_eq!(
+            scenario.fixtures.as_deref(),
+            Some(dir.join("fixtures").to_str().unwrap())
+        );
+        assert_eq!(scenario.turns.len(), 1);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
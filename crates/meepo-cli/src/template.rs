@@ -2,6 +2,7 @@
 
 use anyhow::{Context, Result, bail};
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::path::PathBuf;
 
 /// Metadata section from template.toml
@@ -30,6 +31,9 @@ pub struct TemplateGoal {
     #[serde(default = "default_check_interval")]
     pub check_interval_secs: i64,
     pub success_criteria: Option<String>,
+    /// Due date, as `YYYY-MM-DD` or an RFC 3339 time
+    #[serde(default)]
+    pub deadline: Option<String>,
 }
 
 fn default_priority() -> i32 {
@@ -39,12 +43,59 @@ fn default_check_interval() -> i64 {
     1800
 }
 
-/// Parsed template.toml — metadata + goals + raw TOML overlay
+/// A value the user supplies when activating a template, used as
+/// `{{name}}` in goals and watchers
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TemplateParam {
+    pub name: String,
+    /// Question shown when asking for the value
+    #[serde(default)]
+    pub prompt: String,
+    /// Used when the user gives no value; without one the value is required
+    #[serde(default)]
+    pub default: Option<String>,
+}
+
+/// A watcher defined in template.toml
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TemplateWatcher {
+    /// Watcher kind, as for the create_watcher tool ("github", "email", ...)
+    pub kind: String,
+    #[serde(default = "default_watcher_config")]
+    pub config: toml::Value,
+    pub action: String,
+    #[serde(default = "default_reply_channel")]
+    pub reply_channel: String,
+}
+
+fn default_watcher_config() -> toml::Value {
+    toml::Value::Table(toml::map::Map::new())
+}
+fn default_reply_channel() -> String {
+    "internal".to_string()
+}
+
+/// Commands and secrets a template needs, from its `[requires]` section
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TemplateRequires {
+    /// Commands that must be on PATH, e.g. "gh"
+    #[serde(default)]
+    pub commands: Vec<String>,
+    /// Environment variables that must be set, e.g. "GITHUB_TOKEN"
+    #[serde(default)]
+    pub env: Vec<String>,
+}
+
+/// Parsed template.toml — metadata + params + goals + watchers + raw TOML overlay
 #[derive(Debug, Clone)]
 pub struct Template {
     pub metadata: TemplateMetadata,
+    pub params: Vec<TemplateParam>,
     pub goals: Vec<TemplateGoal>,
-    /// The raw TOML table for config overlay (everything except [template] and [[goals]])
+    pub watchers: Vec<TemplateWatcher>,
+    pub requires: TemplateRequires,
+    /// The raw TOML table for config overlay (everything except [template],
+    /// [requires], [[params]], [[goals]] and [[watchers]])
     pub config_overlay: toml::Value,
     /// Directory the template was loaded from (synthetic for built-in)
     pub dir: PathBuf,
@@ -56,6 +107,9 @@ pub struct ActiveTemplate {
     pub name: String,
     pub source: String,
     pub activated_at: String,
+    /// IDs of the watchers the template created, removed on reset
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub watchers: Vec<String>,
 }
 
 // ── Built-in templates ──────────────────────────────────────────
@@ -109,7 +163,11 @@ impl Template {
             .try_into()
             .context("Invalid [template] section")?;
 
-        // Extract [[goals]] array
+        // Extract [[params]], [[goals]] and [[watchers]] arrays
+        let params: Vec<TemplateParam> = match table.get("params") {
+            Some(val) => val.clone().try_into().context("Invalid [[params]] array")?,
+            None => vec![],
+        };
         let goals: Vec<TemplateGoal> = if let Some(goals_val) = table.get("goals") {
             goals_val
                 .clone()
@@ -118,21 +176,188 @@ impl Template {
         } else {
             vec![]
         };
+        let watchers: Vec<TemplateWatcher> = match table.get("watchers") {
+            Some(val) => val
+                .clone()
+                .try_into()
+                .context("Invalid [[watchers]] array")?,
+            None => vec![],
+        };
+        let requires: TemplateRequires = match table.get("requires") {
+            Some(val) => val
+                .clone()
+                .try_into()
+                .context("Invalid [requires] section")?,
+            None => TemplateRequires::default(),
+        };
 
         // Everything else is config overlay
         let mut overlay = toml::map::Map::new();
         for (key, value) in table {
-            if key != "template" && key != "goals" {
+            if !SECTIONS.contains(&key.as_str()) {
                 overlay.insert(key.clone(), value.clone());
             }
         }
 
-        Ok(Template {
+        let template = Template {
             metadata,
+            params,
             goals,
+            watchers,
+            requires,
             config_overlay: toml::Value::Table(overlay),
             dir,
-        })
+        };
+        for name in template.placeholders() {
+            if !template.params.iter().any(|p| p.name == name) {
+                bail!(
+                    "Template uses {{{{{}}}}} but doesn't declare it under [[params]]",
+                    name
+                );
+            }
+        }
+        Ok(template)
+    }
+
+    /// Placeholder names used in goals and watchers, without duplicates
+    fn placeholders(&self) -> Vec<String> {
+        let mut texts: Vec<&str> = Vec::new();
+        for goal in &self.goals {
+            texts.push(&goal.description);
+            texts.extend(goal.success_criteria.as_deref());
+            texts.extend(goal.deadline.as_deref());
+        }
+        let configs: Vec<String> = self.watchers.iter().map(|w| w.config.to_string()).collect();
+        for (watcher, config) in self.watchers.iter().zip(&configs) {
+            texts.push(&watcher.action);
+            texts.push(&watcher.reply_channel);
+            texts.push(config);
+        }
+        let mut names = Vec::new();
+        for text in texts {
+            for name in placeholders(text) {
+                if !names.contains(&name) {
+                    names.push(name);
+                }
+            }
+        }
+        names
+    }
+
+    /// Values for every param: given ones first, then `ask` (which may
+    /// return `None` to take the default)
+    pub fn resolve_params(
+        &self,
+        mut given: HashMap<String, String>,
+        mut ask: impl FnMut(&TemplateParam) -> Result<Option<String>>,
+    ) -> Result<HashMap<String, String>> {
+        if let Some(unknown) = given
+            .keys()
+            .find(|k| !self.params.iter().any(|p| &p.name == *k))
+        {
+            bail!(
+                "Template '{}' has no parameter '{}'",
+                self.metadata.name,
+                unknown
+            );
+        }
+        let mut values = HashMap::new();
+        for param in &self.params {
+            let value = match given.remove(&param.name) {
+                Some(value) => Some(value),
+                None => ask(param)?.filter(|v| !v.trim().is_empty()),
+            };
+            match value.or_else(|| param.default.clone()) {
+                Some(value) => values.insert(param.name.clone(), value.trim().to_string()),
+                None => bail!("A value for '{}' is required", param.name),
+            };
+        }
+        Ok(values)
+    }
+
+    /// Goals and watchers with `{{name}}` placeholders filled in
+    pub fn instantiate(
+        &self,
+        values: &HashMap<String, String>,
+    ) -> (Vec<TemplateGoal>, Vec<TemplateWatcher>) {
+        let goals = self
+            .goals
+            .iter()
+            .map(|goal| TemplateGoal {
+                description: fill(&goal.description, values),
+                success_criteria: goal.success_criteria.as_ref().map(|c| fill(c, values)),
+                deadline: goal.deadline.as_ref().map(|d| fill(d, values)),
+                ..goal.clone()
+            })
+            .collect();
+        let watchers = self
+            .watchers
+            .iter()
+            .map(|watcher| TemplateWatcher {
+                kind: watcher.kind.clone(),
+                config: fill_value(&watcher.config, values),
+                action: fill(&watcher.action, values),
+                reply_channel: fill(&watcher.reply_channel, values),
+            })
+            .collect();
+        (goals, watchers)
+    }
+}
+
+/// Top-level template.toml sections that aren't config overlay
+const SECTIONS: &[&str] = &["template", "requires", "params", "goals", "watchers"];
+
+/// Names of the `{{name}}` placeholders in a string
+fn placeholders(text: &str) -> Vec<String> {
+    let mut names = Vec::new();
+    let mut rest = text;
+    while let Some(start) = rest.find("{{") {
+        let Some(len) = rest[start + 2..].find("}}") else {
+            break;
+        };
+        let name = rest[start + 2..start + 2 + len].trim();
+        if !name.is_empty() {
+            names.push(name.to_string());
+        }
+        rest = &rest[start + 2 + len + 2..];
+    }
+    names
+}
+
+/// Replace `{{name}}` placeholders with their values
+fn fill(text: &str, values: &HashMap<String, String>) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find("{{") {
+        let Some(len) = rest[start + 2..].find("}}") else {
+            break;
+        };
+        out.push_str(&rest[..start]);
+        let name = rest[start + 2..start + 2 + len].trim();
+        match values.get(name) {
+            Some(value) => out.push_str(value),
+            None => out.push_str(&rest[start..start + 2 + len + 2]),
+        }
+        rest = &rest[start + 2 + len + 2..];
+    }
+    out.push_str(rest);
+    out
+}
+
+/// [`fill`] applied to every string in a TOML value
+fn fill_value(value: &toml::Value, values: &HashMap<String, String>) -> toml::Value {
+    match value {
+        toml::Value::String(s) => toml::Value::String(fill(s, values)),
+        toml::Value::Array(items) => {
+            toml::Value::Array(items.iter().map(|v| fill_value(v, values)).collect())
+        }
+        toml::Value::Table(table) => toml::Value::Table(
+            table
+                .iter()
+                .map(|(k, v)| (k.clone(), fill_value(v, values)))
+                .collect(),
+        ),
+        other => other.clone(),
     }
 }
 
@@ -242,6 +467,154 @@ pub fn deep_merge(base: &mut toml::Value, overlay: &toml::Value) {
     }
 }
 
+// ── Overlay Preview ─────────────────────────────────────────────
+
+/// A config key the template overlay would change
+#[derive(Debug, Clone, PartialEq)]
+pub enum ConfigChange {
+    /// A key the config doesn't have yet
+    Added { path: String, value: toml::Value },
+    /// An existing value the overlay replaces
+    Overwritten {
+        path: String,
+        old: toml::Value,
+        new: toml::Value,
+    },
+    /// Entries added to an existing array
+    Appended {
+        path: String,
+        values: Vec<toml::Value>,
+    },
+}
+
+/// What `deep_merge(base, overlay)` would change, one entry per leaf key
+pub fn overlay_changes(base: &toml::Value, overlay: &toml::Value) -> Vec<ConfigChange> {
+    let mut changes = Vec::new();
+    collect_changes("", Some(base), overlay, &mut changes);
+    changes
+}
+
+fn collect_changes(
+    path: &str,
+    base: Option<&toml::Value>,
+    overlay: &toml::Value,
+    changes: &mut Vec<ConfigChange>,
+) {
+    match (base, overlay) {
+        (Some(toml::Value::Table(_)) | None, toml::Value::Table(overlay_table)) => {
+            let base_table = base.and_then(|b| b.as_table());
+            for (key, value) in overlay_table {
+                let path = if path.is_empty() {
+                    key.clone()
+                } else {
+                    format!("{}.{}", path, key)
+                };
+                collect_changes(&path, base_table.and_then(|t| t.get(key)), value, changes);
+            }
+        }
+        (None, value) => changes.push(ConfigChange::Added {
+            path: path.to_string(),
+            value: value.clone(),
+        }),
+        (Some(toml::Value::Array(_)), toml::Value::Array(values)) => {
+            if !values.is_empty() {
+                changes.push(ConfigChange::Appended {
+                    path: path.to_string(),
+                    values: values.clone(),
+                });
+            }
+        }
+        (Some(old), new) => {
+            if old != new {
+                changes.push(ConfigChange::Overwritten {
+                    path: path.to_string(),
+                    old: old.clone(),
+                    new: new.clone(),
+                });
+            }
+        }
+    }
+}
+
+/// Overlay keys that don't appear in `schema`, the merged config as
+/// serialized back from `MeepoConfig` (so keys serde ignored are missing)
+pub fn unrecognized_keys(overlay: &toml::Value, schema: &toml::Value) -> Vec<String> {
+    let mut keys = Vec::new();
+    collect_unrecognized("", overlay, schema, &mut keys);
+    keys
+}
+
+fn collect_unrecognized(
+    path: &str,
+    overlay: &toml::Value,
+    schema: &toml::Value,
+    keys: &mut Vec<String>,
+) {
+    let (Some(overlay_table), Some(schema_table)) = (overlay.as_table(), schema.as_table()) else {
+        return;
+    };
+    for (key, value) in overlay_table {
+        let path = if path.is_empty() {
+            key.clone()
+        } else {
+            format!("{}.{}", path, key)
+        };
+        match schema_table.get(key) {
+            Some(known) => collect_unrecognized(&path, value, known, keys),
+            None => keys.push(path),
+        }
+    }
+}
+
+impl Template {
+    /// Required commands that aren't on PATH and secrets that aren't set.
+    /// Secrets include `${VAR}` references in the config overlay.
+    pub fn missing_requirements(&self) -> Vec<String> {
+        let mut missing = Vec::new();
+        for command in &self.requires.commands {
+            if !on_path(command) {
+                missing.push(format!("command `{}` (not found on PATH)", command));
+            }
+        }
+        let mut env = self.requires.env.clone();
+        for var in env_references(&self.config_overlay.to_string()) {
+            if !env.contains(&var) {
+                env.push(var);
+            }
+        }
+        for var in env {
+            if std::env::var(&var).map_or(true, |v| v.is_empty()) {
+                missing.push(format!("secret {} (environment variable not set)", var));
+            }
+        }
+        missing
+    }
+}
+
+fn on_path(command: &str) -> bool {
+    std::env::var_os("PATH")
+        .map(|paths| std::env::split_paths(&paths).any(|dir| dir.join(command).is_file()))
+        .unwrap_or(false)
+}
+
+/// Variable names of `${VAR}` references in `text`
+fn env_references(text: &str) -> Vec<String> {
+    let mut names = Vec::new();
+    let mut rest = text;
+    while let Some(start) = rest.find("${") {
+        rest = &rest[start + 2..];
+        let Some(end) = rest.find('}') else {
+            break;
+        };
+        let name = &rest[..end];
+        if !name.is_empty() && !names.iter().any(|n| n == name) {
+            names.push(name.to_string());
+        }
+        rest = &rest[end + 1..];
+    }
+    names
+}
+
 // ── Template Content Access ─────────────────────────────────────
 
 /// Get the SOUL.md content for a template.
@@ -286,11 +659,12 @@ pub fn get_active_template() -> Option<ActiveTemplate> {
 }
 
 /// Write the active template state
-pub fn set_active_template(name: &str, source: &str) -> Result<()> {
+pub fn set_active_template(name: &str, source: &str, watchers: Vec<String>) -> Result<()> {
     let state = ActiveTemplate {
         name: name.to_string(),
         source: source.to_string(),
         activated_at: chrono::Utc::now().to_rfc3339(),
+        watchers,
     };
     let path = crate::config::config_dir().join(".active-template");
     let content = toml::to_string_pretty(&state)?;
@@ -322,6 +696,7 @@ description = "A test template"
 description = "Do something"
 priority = 4
 check_interval_secs = 900
+deadline = "2026-06-30"
 
 [autonomy]
 tick_interval_secs = 10
@@ -330,6 +705,7 @@ tick_interval_secs = 10
         assert_eq!(t.metadata.name, "test-agent");
         assert_eq!(t.goals.len(), 1);
         assert_eq!(t.goals[0].priority, 4);
+        assert_eq!(t.goals[0].deadline.as_deref(), Some("2026-06-30"));
 
         let overlay = t.config_overlay.as_table().unwrap();
         assert!(overlay.contains_key("autonomy"));
@@ -337,6 +713,63 @@ tick_interval_secs = 10
         assert!(!overlay.contains_key("goals"));
     }
 
+    #[test]
+    fn test_template_params() {
+        let toml_str = r#"
+[template]
+name = "devops"
+description = "Repo watcher"
+
+[[params]]
+name = "github_repo"
+prompt = "GitHub repository (owner/name)"
+
+[[params]]
+name = "standup_time"
+default = "09:30"
+
+[[goals]]
+description = "Post a standup for {{github_repo}} at {{ standup_time }}"
+
+[[watchers]]
+kind = "github"
+action = "Summarize new PRs in {{github_repo}}"
+config = { repo = "{{github_repo}}", events = ["pull_request"], interval_secs = 300 }
+"#;
+        let t = Template::parse(toml_str, PathBuf::from("/tmp")).unwrap();
+        assert_eq!(t.params.len(), 2);
+        assert!(!t.config_overlay.as_table().unwrap().contains_key("params"));
+        assert_eq!(t.watchers[0].reply_channel, "internal");
+
+        // Given values win, asked-for values come next, then defaults
+        let given = HashMap::from([("github_repo".to_string(), "acme/api".to_string())]);
+        let mut asked = Vec::new();
+        let values = t
+            .resolve_params(given, |p| {
+                asked.push(p.name.clone());
+                Ok(None)
+            })
+            .unwrap();
+        assert_eq!(asked, vec!["standup_time"]);
+        assert_eq!(values["standup_time"], "09:30");
+
+        let (goals, watchers) = t.instantiate(&values);
+        assert_eq!(goals[0].description, "Post a standup for acme/api at 09:30");
+        assert_eq!(watchers[0].action, "Summarize new PRs in acme/api");
+        assert_eq!(watchers[0].config["repo"].as_str(), Some("acme/api"));
+        assert_eq!(watchers[0].config["interval_secs"].as_integer(), Some(300));
+
+        // A required param needs a value, and unknown ones are rejected
+        assert!(t.resolve_params(HashMap::new(), |_| Ok(None)).is_err());
+        let unknown = HashMap::from([("repo".to_string(), "x".to_string())]);
+        assert!(t.resolve_params(unknown, |_| Ok(None)).is_err());
+
+        // Placeholders must be declared
+        let undeclared = toml_str.replace("name = \"standup_time\"", "name = \"other\"");
+        let err = Template::parse(&undeclared, PathBuf::from("/tmp")).unwrap_err();
+        assert!(err.to_string().contains("{{standup_time}}"));
+    }
+
     #[test]
     fn test_deep_merge_scalars() {
         let mut base: toml::Value = toml::from_str(
@@ -490,6 +923,71 @@ y = 2
         assert_eq!(base.as_str(), Some("new"));
     }
 
+    #[test]
+    fn test_overlay_changes_and_requirements() {
+        let base: toml::Value = toml::from_str(
+            r#"
+[agent]
+default_model = "claude-sonnet"
+max_tokens = 4096
+tags = ["a"]
+"#,
+        )
+        .unwrap();
+        let t = Template::parse(
+            r#"
+[template]
+name = "devops"
+description = "Ops"
+
+[requires]
+commands = ["definitely-not-a-real-command"]
+env = ["MEEPO_TEST_UNSET_TOKEN"]
+
+[agent]
+default_model = "claude-opus"
+max_tokens = 4096
+tags = ["b"]
+
+[tools]
+api_key = "${MEEPO_TEST_UNSET_KEY}"
+"#,
+            PathBuf::from("/tmp"),
+        )
+        .unwrap();
+        let changes = overlay_changes(&base, &t.config_overlay);
+        assert_eq!(
+            changes,
+            vec![
+                ConfigChange::Overwritten {
+                    path: "agent.default_model".to_string(),
+                    old: toml::Value::String("claude-sonnet".to_string()),
+                    new: toml::Value::String("claude-opus".to_string()),
+                },
+                ConfigChange::Appended {
+                    path: "agent.tags".to_string(),
+                    values: vec![toml::Value::String("b".to_string())],
+                },
+                ConfigChange::Added {
+                    path: "tools.api_key".to_string(),
+                    value: toml::Value::String("${MEEPO_TEST_UNSET_KEY}".to_string()),
+                },
+            ]
+        );
+
+        let schema: toml::Value = toml::from_str("[agent]\ndefault_model = \"x\"").unwrap();
+        assert_eq!(
+            unrecognized_keys(&t.config_overlay, &schema),
+            vec!["agent.max_tokens", "agent.tags", "tools"]
+        );
+
+        let missing = t.missing_requirements();
+        assert_eq!(missing.len(), 3);
+        assert!(missing[0].contains("definitely-not-a-real-command"));
+        assert!(missing[1].contains("MEEPO_TEST_UNSET_TOKEN"));
+        assert!(missing[2].contains("MEEPO_TEST_UNSET_KEY"));
+    }
+
     #[test]
     fn test_resolve_github_not_implemented() {
         let result = resolve_template("gh:user/repo");
@@ -527,6 +1025,7 @@ y = 2
             name: "test".to_string(),
             source: "built-in".to_string(),
             activated_at: "2024-01-01T00:00:00Z".to_string(),
+            watchers: vec![],
         };
         let toml_str = toml::to_string_pretty(&at).unwrap();
         let parsed: ActiveTemplate = toml::from_str(&toml_str).unwrap();
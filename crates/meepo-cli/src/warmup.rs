@@ -0,0 +1,201 @@
+//! Startup warm-up
+//!
+//! The first message after the daemon starts used to pay for opening the
+//! search index reader, loading the embedding model and the first TLS
+//! handshake with the LLM provider. Warm-up does that work up front, with
+//! every step running in parallel under its own timeout, and reports what
+//! was warmed so the gateway can tell clients the daemon is ready.
+
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use meepo_core::api::{ApiClient, ApiMessage, MessageContent};
+use meepo_knowledge::KnowledgeGraph;
+use serde::Serialize;
+
+/// Which steps run, and how long each may take
+#[derive(Debug, Clone)]
+pub struct WarmupConfig {
+    pub index: bool,
+    pub embeddings: bool,
+    pub llm: bool,
+    pub timeout: Duration,
+}
+
+/// How one warm-up step went
+#[derive(Debug, Clone, Serialize)]
+pub struct WarmupStep {
+    pub name: String,
+    pub ok: bool,
+    pub duration_ms: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Every step that ran, and the wall time of the whole warm-up
+#[derive(Debug, Clone, Serialize)]
+pub struct WarmupReport {
+    pub steps: Vec<WarmupStep>,
+    pub duration_ms: u64,
+}
+
+impl WarmupReport {
+    pub fn failed(&self) -> impl Iterator<Item = &WarmupStep> {
+        self.steps.iter().filter(|s| !s.ok)
+    }
+}
+
+type StepFuture = Pin<Box<dyn Future<Output = anyhow::Result<()>> + Send>>;
+
+/// Run one step under `timeout`, returning how it went and its output
+pub async fn timed<T>(
+    name: impl Into<String>,
+    timeout: Duration,
+    step: impl Future<Output = anyhow::Result<T>>,
+) -> (WarmupStep, Option<T>) {
+    let started = Instant::now();
+    let result = match tokio::time::timeout(timeout, step).await {
+        Ok(result) => result,
+        Err(_) => Err(anyhow::anyhow!("timed out after {:?}", timeout)),
+    };
+    let step = WarmupStep {
+        name: name.into(),
+        ok: result.is_ok(),
+        duration_ms: started.elapsed().as_millis() as u64,
+        error: result.as_ref().err().map(|e| format!("{:#}", e)),
+    };
+    (step, result.ok())
+}
+
+/// Run the configured steps in parallel. Failures and timeouts are
+/// reported, never returned: warm-up only makes the first turn faster.
+pub async fn run(
+    config: &WarmupConfig,
+    graph: Arc<KnowledgeGraph>,
+    api: ApiClient,
+) -> WarmupReport {
+    let started = Instant::now();
+    let mut steps: Vec<(&'static str, StepFuture)> = Vec::new();
+
+    if config.index {
+        let graph = graph.clone();
+        steps.push((
+            "index",
+            Box::pin(async move {
+                tokio::task::spawn_blocking(move || graph.search("warm up", 1)).await??;
+                Ok(())
+            }),
+        ));
+    }
+    if config.embeddings && graph.has_embeddings() {
+        steps.push((
+            "embeddings",
+            Box::pin(async move {
+                graph.hybrid_search("warm up", 1).await?;
+                Ok(())
+            }),
+        ));
+    }
+    if config.llm {
+        steps.push((
+            "llm",
+            Box::pin(async move {
+                let messages = vec![ApiMessage {
+                    role: "user".to_string(),
+                    content: MessageContent::Text("ping".to_string()),
+                }];
+                api.chat(&messages, &[], "Reply with the single word: pong")
+                    .await?;
+                Ok(())
+            }),
+        ));
+    }
+
+    let handles: Vec<_> = steps
+        .into_iter()
+        .map(|(name, step)| (name, tokio::spawn(timed(name, config.timeout, step))))
+        .collect();
+
+    let mut report = WarmupReport {
+        steps: Vec::with_capacity(handles.len()),
+        duration_ms: 0,
+    };
+    for (name, handle) in handles {
+        report.steps.push(match handle.await {
+            Ok((step, _)) => step,
+            Err(e) => WarmupStep {
+                name: name.to_string(),
+                ok: false,
+                duration_ms: 0,
+                error: Some(e.to_string()),
+            },
+        });
+    }
+    report.duration_ms = started.elapsed().as_millis() as u64;
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use async_trait::async_trait;
+    use meepo_core::providers::types::{ChatResponseBlock, ChatUsage, StopReason};
+    use meepo_core::providers::{ChatMessage, ChatResponse, LlmProvider, ModelRouter};
+
+    struct SlowProvider;
+
+    #[async_trait]
+    impl LlmProvider for SlowProvider {
+        fn provider_name(&self) -> &str {
+            "slow"
+        }
+        fn model(&self) -> &str {
+            "slow-1"
+        }
+        async fn chat(
+            &self,
+            _messages: &[ChatMessage],
+            _tools: &[meepo_core::api::ToolDefinition],
+            _system: &str,
+        ) -> anyhow::Result<ChatResponse> {
+            tokio::time::sleep(Duration::from_secs(5)).await;
+            Ok(ChatResponse {
+                blocks: vec![ChatResponseBlock::Text {
+                    text: "pong".to_string(),
+                }],
+                stop_reason: StopReason::EndTurn,
+                usage: ChatUsage {
+                    input_tokens: 1,
+                    output_tokens: 1,
+                },
+            })
+        }
+    }
+
+    #[tokio::test]
+    async fn test_warmup_reports_timeouts() {
+        let dir = std::env::temp_dir().join(format!("meepo-warmup-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let graph = Arc::new(KnowledgeGraph::new(dir.join("k.db"), dir.join("index")).unwrap());
+        let api = ApiClient::from_router(ModelRouter::single(Box::new(SlowProvider)));
+        let config = WarmupConfig {
+            index: true,
+            embeddings: true,
+            llm: true,
+            timeout: Duration::from_millis(200),
+        };
+
+        let report = run(&config, graph, api).await;
+        // No embedding provider is attached, so that step is skipped
+        let names: Vec<&str> = report.steps.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(names, vec!["index", "llm"]);
+        assert!(report.steps[0].ok);
+        let failed: Vec<&WarmupStep> = report.failed().collect();
+        assert_eq!(failed.len(), 1);
+        assert!(failed[0].error.as_deref().unwrap().contains("timed out"));
+        assert!(report.duration_ms < 5000);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,714 @@
+//! Action-item extraction
+//!
+//! Commitments the user makes in conversation ("I'll send the doc Friday")
+//! and action items in ingested meeting notes are stored as `task` entities,
+//! with due dates resolved from phrases like "tomorrow" or "by Friday".
+//! Extracted items stay open until a later message reports them done ("sent
+//! the doc"), at which point they're closed with that message as evidence.
+
+use anyhow::Result;
+use chrono::{Datelike, Duration, NaiveDate, Weekday};
+use meepo_knowledge::KnowledgeDb;
+use meepo_knowledge::embeddings::content_words;
+use serde_json::Value;
+use tracing::{debug, info};
+
+/// `source` of tasks extracted from conversation
+pub const SOURCE_CONVERSATION: &str = "conversation";
+
+/// `source` of tasks extracted from ingested documents
+pub const SOURCE_DOCUMENT: &str = "document";
+
+/// Phrases that introduce a commitment
+const COMMITMENT_CUES: &[&str] = &[
+    "i'll ",
+    "i will ",
+    "i'm going to ",
+    "i am going to ",
+    "i need to ",
+    "i have to ",
+    "i must ",
+    "i promised to ",
+    "remind me to ",
+];
+
+/// Words after a cue that make it something other than a commitment
+/// ("I'll be late", "I'll see")
+const NON_ACTIONS: &[&str] = &["be", "see", "think", "let", "try", "probably", "maybe"];
+
+/// Fillers skipped between the cue and the action
+const FILLERS: &[&str] = &["just", "also", "then", "definitely", "still", "first"];
+
+/// Words that say a task is done regardless of its verb
+const DONE_CUES: &[&str] = &["done", "finished", "completed", "handled", "sorted"];
+
+/// Headings whose bullets are action items in meeting notes
+const ACTION_HEADINGS: &[&str] = &[
+    "action items",
+    "action item",
+    "action points",
+    "next steps",
+    "todo",
+    "todos",
+    "to do",
+    "to-do",
+    "follow-ups",
+    "follow ups",
+    "follow-up",
+];
+
+/// Past tenses the regular rules get wrong
+const IRREGULAR_PAST: &[(&str, &str)] = &[
+    ("send", "sent"),
+    ("make", "made"),
+    ("write", "wrote"),
+    ("buy", "bought"),
+    ("bring", "brought"),
+    ("pay", "paid"),
+    ("get", "got"),
+    ("give", "gave"),
+    ("do", "did"),
+    ("tell", "told"),
+    ("find", "found"),
+    ("read", "read"),
+    ("set", "set"),
+    ("put", "put"),
+    ("take", "took"),
+    ("meet", "met"),
+    ("leave", "left"),
+    ("speak", "spoke"),
+    ("ship", "shipped"),
+    ("submit", "submitted"),
+    ("plan", "planned"),
+    ("drop", "dropped"),
+    ("forward", "forwarded"),
+];
+
+/// Configuration for action-item extraction
+#[derive(Debug, Clone)]
+pub struct ActionItemConfig {
+    pub enabled: bool,
+    /// Also extract from documents ingested with `ingest_document`
+    pub from_documents: bool,
+    /// Close extracted items when a message reports them done
+    pub close_on_evidence: bool,
+}
+
+impl Default for ActionItemConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            from_documents: true,
+            close_on_evidence: true,
+        }
+    }
+}
+
+/// A commitment found in text
+#[derive(Debug, Clone, PartialEq)]
+pub struct ActionItem {
+    pub title: String,
+    pub due: Option<NaiveDate>,
+    /// The sentence or line it was found in
+    pub evidence: String,
+}
+
+/// An extracted task that hasn't been done yet
+#[derive(Debug, Clone, PartialEq)]
+pub struct OpenItem {
+    pub id: String,
+    pub title: String,
+    pub due: Option<NaiveDate>,
+}
+
+/// Tasks created and closed by one message
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ItemChanges {
+    pub created: Vec<String>,
+    pub closed: Vec<String>,
+}
+
+impl ItemChanges {
+    pub fn is_empty(&self) -> bool {
+        self.created.is_empty() && self.closed.is_empty()
+    }
+}
+
+/// Find commitments in free text
+pub fn extract_commitments(text: &str, today: NaiveDate) -> Vec<ActionItem> {
+    sentences(text)
+        .into_iter()
+        .filter_map(|sentence| commitment(sentence, today))
+        .collect()
+}
+
+/// Find action items in meeting notes: unchecked `- [ ]` boxes, bullets
+/// under an "Action items" / "Next steps" / "TODO" heading, and commitments
+/// anywhere else
+pub fn extract_from_notes(text: &str, today: NaiveDate) -> Vec<ActionItem> {
+    let mut items = Vec::new();
+    let mut in_section = false;
+
+    for line in text.lines() {
+        let trimmed = line.trim();
+        if trimmed.is_empty() {
+            continue;
+        }
+        if let Some(rest) = checkbox(trimmed) {
+            items.extend(item_from_line(rest, trimmed, today));
+            continue;
+        }
+
+        let heading = trimmed
+            .trim_start_matches('#')
+            .trim_matches(|c: char| c == '*' || c == '_' || c == ':' || c.is_whitespace())
+            .to_lowercase();
+        if ACTION_HEADINGS.contains(&heading.as_str()) {
+            in_section = true;
+            continue;
+        }
+
+        match bullet(trimmed) {
+            Some(rest) if in_section => items.extend(item_from_line(rest, trimmed, today)),
+            _ => {
+                if trimmed.starts_with('#') || trimmed.ends_with(':') || bullet(trimmed).is_none() {
+                    in_section = false;
+                }
+                items.extend(extract_commitments(trimmed, today));
+            }
+        }
+    }
+    items
+}
+
+/// Split a trailing due-date phrase out of an action, returning the action
+/// without it and the resolved date
+pub fn parse_due(action: &str, today: NaiveDate) -> (String, Option<NaiveDate>) {
+    let words: Vec<&str> = action.split_whitespace().collect();
+    let lower: Vec<String> = words
+        .iter()
+        .map(|w| {
+            w.trim_matches(|c: char| !c.is_alphanumeric() && c != '-')
+                .to_lowercase()
+        })
+        .collect();
+
+    for start in 0..lower.len() {
+        let Some((len, due)) = date_phrase(&lower[start..], today) else {
+            continue;
+        };
+        let mut from = start;
+        while from > 0
+            && matches!(
+                lower[from - 1].as_str(),
+                "by" | "on" | "before" | "until" | "due" | "this"
+            )
+        {
+            from -= 1;
+        }
+        let rest: Vec<&str> = words[..from]
+            .iter()
+            .chain(&words[start + len..])
+            .copied()
+            .collect();
+        return (tidy(&rest.join(" ")), Some(due));
+    }
+    (tidy(action), None)
+}
+
+/// Store extracted items as pending tasks, skipping ones already open.
+/// Returns the titles that were added.
+pub async fn record(db: &KnowledgeDb, items: &[ActionItem], source: &str) -> Result<Vec<String>> {
+    let mut open: Vec<String> = open_items(db)
+        .await?
+        .into_iter()
+        .map(|i| i.title.to_lowercase())
+        .collect();
+    let mut created = Vec::new();
+
+    for item in items {
+        if open.contains(&item.title.to_lowercase()) {
+            continue;
+        }
+        // The user asked to forget it; don't pick it up again from old messages
+        if db.is_forgotten(&item.title).await? {
+            debug!("Skipping forgotten action item: {}", item.title);
+            continue;
+        }
+        let metadata = serde_json::json!({
+            "status": "pending",
+            "priority": "medium",
+            "priority_num": 3,
+            "description": item.evidence,
+            "due_date": item.due.map(|d| d.to_string()),
+            "project": null,
+            "context": null,
+            "source": source,
+            "extracted": true,
+            "created_at": chrono::Utc::now().to_rfc3339(),
+        });
+        db.insert_entity(&item.title, "task", Some(metadata))
+            .await?;
+        info!("Extracted action item: {}", item.title);
+        open.push(item.title.to_lowercase());
+        created.push(item.title.clone());
+    }
+    Ok(created)
+}
+
+/// Extracted tasks still pending, soonest due first
+pub async fn open_items(db: &KnowledgeDb) -> Result<Vec<OpenItem>> {
+    let mut items: Vec<OpenItem> = db
+        .search_entities("", Some("task"))
+        .await?
+        .into_iter()
+        .filter(|e| {
+            e.metadata.as_ref().is_some_and(|m| {
+                m.get("extracted").and_then(Value::as_bool) == Some(true)
+                    && m.get("status").and_then(Value::as_str) == Some("pending")
+            })
+        })
+        .map(|e| OpenItem {
+            due: e
+                .metadata
+                .as_ref()
+                .and_then(|m| m.get("due_date"))
+                .and_then(Value::as_str)
+                .and_then(|d| d.parse().ok()),
+            id: e.id,
+            title: e.name,
+        })
+        .collect();
+    items.sort_by_key(|i| (i.due.is_none(), i.due));
+    Ok(items)
+}
+
+/// Close open extracted items that a message reports as done. Returns the
+/// titles that were closed.
+pub async fn close_completed(db: &KnowledgeDb, text: &str) -> Result<Vec<String>> {
+    let evidence: Vec<&str> = sentences(text)
+        .into_iter()
+        .filter(|s| !s.ends_with('?') && find_cue(&normalize(s)).is_none())
+        .collect();
+    if evidence.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let mut closed = Vec::new();
+    for item in open_items(db).await? {
+        let Some(sentence) = evidence.iter().find(|s| reports_done(&item.title, s)) else {
+            continue;
+        };
+        let Some(entity) = db.get_entity(&item.id).await? else {
+            continue;
+        };
+        let mut metadata = entity.metadata.unwrap_or_else(|| serde_json::json!({}));
+        metadata["status"] = serde_json::json!("completed");
+        metadata["completed_at"] = serde_json::json!(chrono::Utc::now().to_rfc3339());
+        metadata["completion_note"] = serde_json::json!(sentence);
+        db.update_entity_metadata(&item.id, metadata).await?;
+        info!("Closed action item: {}", item.title);
+        closed.push(item.title);
+    }
+    Ok(closed)
+}
+
+/// Close items the message reports done, then record its new commitments
+pub async fn process_message(
+    db: &KnowledgeDb,
+    text: &str,
+    config: &ActionItemConfig,
+) -> Result<ItemChanges> {
+    let mut changes = ItemChanges::default();
+    if !config.enabled {
+        return Ok(changes);
+    }
+    if config.close_on_evidence {
+        changes.closed = close_completed(db, text).await?;
+    }
+    let items = extract_commitments(text, crate::timezone::now().date_naive());
+    changes.created = record(db, &items, SOURCE_CONVERSATION).await?;
+    if !changes.is_empty() {
+        debug!(
+            "Action items: {} created, {} closed",
+            changes.created.len(),
+            changes.closed.len()
+        );
+    }
+    Ok(changes)
+}
+
+/// Record the action items in an ingested document
+pub async fn process_document(
+    db: &KnowledgeDb,
+    content: &str,
+    config: &ActionItemConfig,
+) -> Result<Vec<String>> {
+    if !config.enabled || !config.from_documents {
+        return Ok(Vec::new());
+    }
+    let items = extract_from_notes(content, crate::timezone::now().date_naive());
+    record(db, &items, SOURCE_DOCUMENT).await
+}
+
+/// Sentences with their terminator, so questions can be told apart
+fn sentences(text: &str) -> Vec<&str> {
+    let mut out = Vec::new();
+    let mut start = 0;
+    for (i, c) in text.char_indices() {
+        if matches!(c, '.' | '!' | '?' | ';' | '\n') {
+            // Keep dates and decimals ("v1.2") together
+            let next = text[i + c.len_utf8()..].chars().next();
+            if c == '.' && next.is_some_and(|n| !n.is_whitespace()) {
+                continue;
+            }
+            let sentence = text[start..i + c.len_utf8()].trim();
+            if !sentence.is_empty() {
+                out.push(sentence);
+            }
+            start = i + c.len_utf8();
+        }
+    }
+    let tail = text[start..].trim();
+    if !tail.is_empty() {
+        out.push(tail);
+    }
+    out
+}
+
+fn normalize(text: &str) -> String {
+    text.replace('\u{2019}', "'").to_lowercase()
+}
+
+/// Byte offset just past the first commitment cue starting at a word boundary
+fn find_cue(lower: &str) -> Option<usize> {
+    COMMITMENT_CUES
+        .iter()
+        .filter_map(|cue| {
+            lower.match_indices(cue).find_map(|(i, _)| {
+                let boundary = lower[..i]
+                    .chars()
+                    .next_back()
+                    .is_none_or(|c| !c.is_alphanumeric());
+                boundary.then_some(i + cue.len())
+            })
+        })
+        .min()
+}
+
+fn commitment(sentence: &str, today: NaiveDate) -> Option<ActionItem> {
+    if sentence.ends_with('?') {
+        return None;
+    }
+    let normalized = sentence.replace('\u{2019}', "'");
+    let end = find_cue(&normalized.to_lowercase())?;
+    let mut action = normalized.get(end..)?.trim();
+    loop {
+        let word = action.split_whitespace().next()?;
+        let first = word.to_lowercase();
+        if NON_ACTIONS.contains(&first.as_str()) {
+            return None;
+        }
+        if !FILLERS.contains(&first.as_str()) {
+            break;
+        }
+        action = action[word.len()..].trim_start();
+    }
+
+    let (title, due) = parse_due(action, today);
+    if title.split_whitespace().count() < 2 || content_words(&title).is_empty() {
+        return None;
+    }
+    Some(ActionItem {
+        title,
+        due,
+        evidence: sentence.to_string(),
+    })
+}
+
+fn item_from_line(text: &str, line: &str, today: NaiveDate) -> Option<ActionItem> {
+    let (title, due) = parse_due(text, today);
+    (!content_words(&title).is_empty()).then(|| ActionItem {
+        title,
+        due,
+        evidence: line.to_string(),
+    })
+}
+
+/// Text of an unchecked checkbox line
+fn checkbox(line: &str) -> Option<&str> {
+    let rest = bullet(line).unwrap_or(line);
+    rest.strip_prefix("[ ]").map(str::trim)
+}
+
+/// Text of a bullet or numbered list line
+fn bullet(line: &str) -> Option<&str> {
+    for marker in ["- ", "* ", "• "] {
+        if let Some(rest) = line.strip_prefix(marker) {
+            return Some(rest.trim());
+        }
+    }
+    let digits = line.chars().take_while(char::is_ascii_digit).count();
+    if digits > 0 {
+        return line[digits..]
+            .strip_prefix(". ")
+            .or_else(|| line[digits..].strip_prefix(") "))
+            .map(str::trim);
+    }
+    None
+}
+
+/// Capitalize and strip trailing punctuation
+fn tidy(text: &str) -> String {
+    let text = text
+        .trim()
+        .trim_end_matches(|c: char| c.is_ascii_punctuation() || c.is_whitespace());
+    let mut chars = text.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+/// A date phrase at the start of `words`: (words used, date)
+fn date_phrase(words: &[String], today: NaiveDate) -> Option<(usize, NaiveDate)> {
+    let word = |i: usize| words.get(i).map(String::as_str).unwrap_or("");
+    let end_of_week = || {
+        let ahead = (Weekday::Fri.num_days_from_monday() as i64
+            - today.weekday().num_days_from_monday() as i64)
+            .max(0);
+        today + Duration::days(ahead)
+    };
+
+    match (word(0), word(1), word(2), word(3)) {
+        ("end", "of", "the", "day") => Some((4, today)),
+        ("end", "of", "the", "week") => Some((4, end_of_week())),
+        ("end", "of", "the", "month") => Some((4, end_of_month(today))),
+        ("end", "of", "day", _) => Some((3, today)),
+        ("end", "of", "week", _) => Some((3, end_of_week())),
+        ("end", "of", "month", _) => Some((3, end_of_month(today))),
+        ("next", "week", _, _) => {
+            let ahead = 7 - today.weekday().num_days_from_monday() as i64;
+            Some((2, today + Duration::days(ahead)))
+        }
+        ("next", day, _, _) if weekday(day).is_some() => {
+            Some((2, next_weekday(today, weekday(day)?)))
+        }
+        ("in", n, "days" | "day", _) => {
+            let n: i64 = n.parse().ok()?;
+            Some((3, today + Duration::days(n)))
+        }
+        ("in", n, "weeks" | "week", _) => {
+            let n: i64 = n.parse().ok()?;
+            Some((3, today + Duration::weeks(n)))
+        }
+        ("today" | "tonight" | "eod", _, _, _) => Some((1, today)),
+        ("tomorrow", _, _, _) => Some((1, today + Duration::days(1))),
+        ("eow", _, _, _) => Some((1, end_of_week())),
+        (day, _, _, _) if weekday(day).is_some() => Some((1, next_weekday(today, weekday(day)?))),
+        (date, _, _, _) => date.parse::<NaiveDate>().ok().map(|d| (1, d)),
+    }
+}
+
+fn weekday(word: &str) -> Option<Weekday> {
+    match word {
+        "monday" => Some(Weekday::Mon),
+        "tuesday" => Some(Weekday::Tue),
+        "wednesday" => Some(Weekday::Wed),
+        "thursday" => Some(Weekday::Thu),
+        "friday" => Some(Weekday::Fri),
+        "saturday" => Some(Weekday::Sat),
+        "sunday" => Some(Weekday::Sun),
+        _ => None,
+    }
+}
+
+/// The next `day` after today
+fn next_weekday(today: NaiveDate, day: Weekday) -> NaiveDate {
+    let ahead = (day.num_days_from_monday() as i64 - today.weekday().num_days_from_monday() as i64)
+        .rem_euclid(7);
+    today + Duration::days(if ahead == 0 { 7 } else { ahead })
+}
+
+fn end_of_month(today: NaiveDate) -> NaiveDate {
+    let (year, month) = if today.month() == 12 {
+        (today.year() + 1, 1)
+    } else {
+        (today.year(), today.month() + 1)
+    };
+    NaiveDate::from_ymd_opt(year, month, 1)
+        .map(|d| d - Duration::days(1))
+        .unwrap_or(today)
+}
+
+fn past_tense(verb: &str) -> String {
+    if let Some((_, past)) = IRREGULAR_PAST.iter().find(|(v, _)| *v == verb) {
+        return past.to_string();
+    }
+    if verb.ends_with('e') {
+        format!("{}d", verb)
+    } else if let Some(stem) = verb.strip_suffix('y')
+        && !stem.ends_with(['a', 'e', 'o', 'u'])
+    {
+        format!("{}ied", stem)
+    } else {
+        format!("{}ed", verb)
+    }
+}
+
+/// Whether a sentence says the task was done: it uses the task's verb in the
+/// past tense (or a generic "done") and mentions at least half of the rest
+/// of the task
+fn reports_done(title: &str, sentence: &str) -> bool {
+    let lower = normalize(sentence);
+    let said: Vec<&str> = lower
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .collect();
+    let title_lower = title.to_lowercase();
+    let Some(verb) = title_lower.split_whitespace().next() else {
+        return false;
+    };
+    let past = past_tense(verb);
+    if !said.contains(&past.as_str()) && !DONE_CUES.iter().any(|cue| said.contains(cue)) {
+        return false;
+    }
+
+    let object: Vec<String> = content_words(&title_lower)
+        .into_iter()
+        .filter(|w| w != verb)
+        .collect();
+    if object.is_empty() {
+        return false;
+    }
+    let said_words = content_words(&lower);
+    let overlap = object.iter().filter(|w| said_words.contains(w)).count();
+    overlap * 2 >= object.len()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn date(s: &str) -> NaiveDate {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn test_extract_commitments_and_due_dates() {
+        // A Wednesday
+        let today = date("2026-03-04");
+        let items = extract_commitments(
+            "Thanks! I'll send the doc Friday. Can you check the numbers? \
+             I\u{2019}ll be late tomorrow. Remind me to renew the passport by end of month; \
+             I need to call the landlord tomorrow.",
+            today,
+        );
+        let got: Vec<(&str, Option<NaiveDate>)> =
+            items.iter().map(|i| (i.title.as_str(), i.due)).collect();
+        assert_eq!(
+            got,
+            vec![
+                ("Send the doc", Some(date("2026-03-06"))),
+                ("Renew the passport", Some(date("2026-03-31"))),
+                ("Call the landlord", Some(date("2026-03-05"))),
+            ]
+        );
+        assert_eq!(items[0].evidence, "I'll send the doc Friday.");
+
+        assert_eq!(
+            parse_due("review the deck next week", today),
+            ("Review the deck".to_string(), Some(date("2026-03-09")))
+        );
+        assert_eq!(
+            parse_due("file the report on 2026-04-01", today),
+            ("File the report".to_string(), Some(date("2026-04-01")))
+        );
+        assert_eq!(
+            parse_due("book flights", today),
+            ("Book flights".to_string(), None)
+        );
+        assert!(extract_commitments("Will you send it?", today).is_empty());
+
+        let notes = "# Sync 3/4\n\
+                     Discussed launch.\n\
+                     ## Action items\n\
+                     - Alice: draft the press release by Thursday\n\
+                     - Bob: update pricing page\n\
+                     Next meeting in two weeks.\n\
+                     - not an action\n\
+                     - [ ] Order new badges\n\
+                     - [x] Book the room";
+        let titles: Vec<String> = extract_from_notes(notes, today)
+            .into_iter()
+            .map(|i| i.title)
+            .collect();
+        assert_eq!(
+            titles,
+            vec![
+                "Alice: draft the press release",
+                "Bob: update pricing page",
+                "Order new badges",
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_record_and_close_on_evidence() {
+        let temp = TempDir::new().unwrap();
+        let db = KnowledgeDb::new(temp.path().join("test.db")).unwrap();
+        let config = ActionItemConfig::default();
+
+        let changes = process_message(
+            &db,
+            "I'll send the quarterly report to Dana. I need to book the venue.",
+            &config,
+        )
+        .await
+        .unwrap();
+        assert_eq!(
+            changes.created,
+            vec!["Send the quarterly report to Dana", "Book the venue"]
+        );
+
+        // Repeating a commitment doesn't duplicate it
+        let changes = process_message(&db, "I'll send the quarterly report to Dana", &config)
+            .await
+            .unwrap();
+        assert!(changes.is_empty());
+
+        // Unrelated or still-future mentions don't close anything
+        let changes = process_message(&db, "Sent the invoice to Dana.", &config)
+            .await
+            .unwrap();
+        assert!(changes.closed.is_empty());
+
+        let changes = process_message(&db, "Just sent the quarterly report over.", &config)
+            .await
+            .unwrap();
+        assert_eq!(changes.closed, vec!["Send the quarterly report to Dana"]);
+
+        let open = open_items(&db).await.unwrap();
+        assert_eq!(open.len(), 1);
+        assert_eq!(open[0].title, "Book the venue");
+
+        let tasks = db.search_entities("", Some("task")).await.unwrap();
+        let closed = tasks
+            .iter()
+            .find(|t| t.name == "Send the quarterly report to Dana")
+            .unwrap();
+        let metadata = closed.metadata.as_ref().unwrap();
+        assert_eq!(metadata["status"], "completed");
+        assert_eq!(metadata["source"], SOURCE_CONVERSATION);
+        assert_eq!(
+            metadata["completion_note"],
+            "Just sent the quarterly report over."
+        );
+
+        let created = process_document(&db, "TODO:\n- Confirm catering count", &config)
+            .await
+            .unwrap();
+        assert_eq!(created, vec!["Confirm catering count"]);
+    }
+}
@@ -1,22 +1,52 @@
 //! Main agent loop - the brain of meepo
 
 use anyhow::{Context, Result};
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::path::PathBuf;
 use std::sync::Arc;
-use tracing::{debug, info};
+use std::time::Instant;
+use tracing::{debug, info, warn};
 
+use crate::action_items::{self, ActionItemConfig};
+use crate::agents::restricted::{self, RestrictedToolExecutor};
+use crate::agents::{AgentManager, AgentProfile};
 use crate::api::ApiClient;
+use crate::audio::voice_notes;
 use crate::context::build_system_prompt;
+use crate::context_compression::{self, ContextCompressionConfig};
+use crate::experiments::{PromptExperiment, Variant};
+use crate::feedback;
+use crate::focus;
 use crate::guardrails::{GuardrailContext, GuardrailPipeline};
+use crate::guest::{self, GuestModeConfig, GuestToolExecutor};
+use crate::i18n;
+use crate::inline_commands::{self, ConversationSettings};
 use crate::intent::{self, IntentConfig, UserIntent};
+use crate::knowledge_gaps::{GapTracker, KnowledgeGapConfig};
+use crate::live_context::LiveContext;
 use crate::middleware::{MiddlewareChain, MiddlewareContext};
+use crate::modes::{self, ModeToolExecutor};
+use crate::onboarding;
+use crate::pins;
+use crate::power;
 use crate::query_router::{self, QueryRouterConfig, RetrievalStrategy};
+use crate::redaction::{self, RedactionConfig};
+use crate::send_guard;
+use crate::soul_template::{self, SoulRenderer, SoulVars};
+use crate::streaming::{self, StreamSink};
+use crate::style;
 use crate::summarization::{self, SummarizationConfig};
+use crate::tool_cost::{CostedToolExecutor, ToolCostModel};
+use crate::tool_scope::{self, ScopedToolExecutor};
 use crate::tool_selector::{self, ToolSelectorConfig};
+use crate::tool_summaries::{self, ArtifactStore, SummarizingToolExecutor, ToolSummaryConfig};
 use crate::tools::{GuardedToolExecutor, ToolExecutor, ToolRegistry};
-use crate::types::{IncomingMessage, MessageKind, OutgoingMessage};
+use crate::trace::TurnTrace;
+use crate::types::{ChannelType, IncomingMessage, MessageKind, OutgoingMessage};
 use crate::usage::{UsageSource, UsageTracker};
 
-use meepo_knowledge::KnowledgeDb;
+use meepo_knowledge::{KnowledgeDb, ToolCallRecord};
 
 /// Maximum context size in bytes to prevent multi-MB context strings.
 const MAX_CONTEXT_SIZE: usize = 100_000;
@@ -36,12 +66,44 @@ pub struct Agent {
     summarization_config: SummarizationConfig,
     /// Tool selection configuration
     tool_selector_config: ToolSelectorConfig,
+    /// Context deduplication and compression configuration
+    context_compression: ContextCompressionConfig,
+    /// Leave tools unused for this many days out of the default tool list
+    prune_unused_tools_after: Option<u32>,
     /// Usage tracker for cost monitoring
     usage_tracker: Option<Arc<UsageTracker>>,
     /// Guardrails pipeline for input safety checks
     guardrails: Option<GuardrailPipeline>,
     /// Intent understanding configuration
     intent_config: IntentConfig,
+    /// Store each turn's trace in the response's conversation metadata
+    store_traces: bool,
+    /// Record questions that stored knowledge couldn't answer
+    knowledge_gaps: Option<KnowledgeGapConfig>,
+    /// A/B test of an alternative system prompt
+    experiment: Option<PromptExperiment>,
+    /// Turn commitments in user messages into tasks
+    action_items: Option<ActionItemConfig>,
+    /// Who gets read-only demo mode
+    guest_mode: GuestModeConfig,
+    /// Profiles that route senders to a restricted agent
+    profiles: Option<Arc<AgentManager>>,
+    /// Interview new users on their first message in a channel
+    onboarding: bool,
+    /// Default model per channel name ("slack" → "claude-haiku-4-5")
+    channel_models: HashMap<String, String>,
+    /// Renders SOUL.md when it uses template tags
+    soul_renderer: SoulRenderer,
+    /// Open replies to voice notes with a one-line summary of the note
+    voice_note_summaries: bool,
+    /// Time, battery, Focus, next event and unread counts for the prompt
+    live_context: Option<Arc<LiveContext>>,
+    /// Tool cost hints and per-conversation tool budgets
+    tool_costs: Option<Arc<ToolCostModel>>,
+    /// Channels whose turns are redacted before cloud model calls
+    redaction: Option<RedactionConfig>,
+    /// Summarize long tool outputs, keeping the full text for fetching
+    tool_summaries: Option<(ToolSummaryConfig, Arc<ArtifactStore>)>,
 }
 
 impl Agent {
@@ -63,9 +125,25 @@ impl Agent {
             router_config: QueryRouterConfig::default(),
             summarization_config: SummarizationConfig::default(),
             tool_selector_config: ToolSelectorConfig::default(),
+            context_compression: ContextCompressionConfig::default(),
+            prune_unused_tools_after: None,
             usage_tracker: None,
             guardrails: None,
             intent_config: IntentConfig::default(),
+            store_traces: false,
+            knowledge_gaps: None,
+            experiment: None,
+            action_items: None,
+            guest_mode: GuestModeConfig::default(),
+            profiles: None,
+            onboarding: false,
+            channel_models: HashMap::new(),
+            soul_renderer: SoulRenderer::default(),
+            voice_note_summaries: false,
+            live_context: None,
+            tool_costs: None,
+            redaction: None,
+            tool_summaries: None,
         }
     }
 
@@ -93,6 +171,20 @@ impl Agent {
         self
     }
 
+    /// Set the context deduplication and compression configuration
+    pub fn with_context_compression(mut self, config: ContextCompressionConfig) -> Self {
+        self.context_compression = config;
+        self
+    }
+
+    /// Leave tools nobody has called for `days` days out of the tool list
+    /// sent to the model. They stay callable and are offered again when a
+    /// message names them.
+    pub fn with_tool_pruning(mut self, days: u32) -> Self {
+        self.prune_unused_tools_after = Some(days);
+        self
+    }
+
     /// Set the usage tracker
     pub fn with_usage_tracker(mut self, tracker: Arc<UsageTracker>) -> Self {
         self.usage_tracker = Some(tracker);
@@ -111,8 +203,131 @@ impl Agent {
         self
     }
 
+    /// Store each turn's trace (tools, token usage, timings) with its response
+    pub fn with_trace_storage(mut self, enabled: bool) -> Self {
+        self.store_traces = enabled;
+        self
+    }
+
+    /// Record knowledge gaps (and optionally research goals) for questions
+    /// that stored knowledge couldn't answer
+    pub fn with_knowledge_gaps(mut self, config: KnowledgeGapConfig) -> Self {
+        self.knowledge_gaps = Some(config);
+        self
+    }
+
+    /// Run an A/B experiment of an alternative system prompt
+    pub fn with_experiment(mut self, experiment: PromptExperiment) -> Self {
+        self.experiment = Some(experiment);
+        self
+    }
+
+    /// Extract commitments from user messages as tasks, closing them when
+    /// later messages report them done
+    pub fn with_action_items(mut self, config: ActionItemConfig) -> Self {
+        self.action_items = Some(config);
+        self
+    }
+
+    /// Set which channels and senders are handled in guest (demo) mode
+    pub fn with_guest_mode(mut self, config: GuestModeConfig) -> Self {
+        self.guest_mode = config;
+        self
+    }
+
+    /// Route senders matched by restricted profiles to a constrained agent
+    pub fn with_profiles(mut self, profiles: AgentManager) -> Self {
+        self.profiles = Some(Arc::new(profiles));
+        self
+    }
+
+    /// Run the onboarding interview on the first message in each channel
+    /// (or after `meepo onboard`) until it's completed
+    pub fn with_onboarding(mut self, enabled: bool) -> Self {
+        self.onboarding = enabled;
+        self
+    }
+
+    /// Answer on these channels with their own model unless the
+    /// conversation picked one with `!model`
+    pub fn with_channel_models(mut self, channel_models: HashMap<String, String>) -> Self {
+        self.channel_models = channel_models;
+        self
+    }
+
+    /// Resolve `{% include %}`s in a templated SOUL.md against this directory
+    pub fn with_soul_dir(mut self, dir: PathBuf) -> Self {
+        self.soul_renderer = SoulRenderer::new(Some(dir));
+        self
+    }
+
+    /// Start replies to transcribed voice notes with a summary of what was said
+    pub fn with_voice_note_summaries(mut self, enabled: bool) -> Self {
+        self.voice_note_summaries = enabled;
+        self
+    }
+
+    /// Add a live context section (time, battery, next event, ...) to the prompt
+    pub fn with_live_context(mut self, live_context: Arc<LiveContext>) -> Self {
+        self.live_context = Some(live_context);
+        self
+    }
+
+    /// Show tool costs to the model and budget expensive tools per conversation
+    pub fn with_tool_costs(mut self, tool_costs: Arc<ToolCostModel>) -> Self {
+        self.tool_costs = Some(tool_costs);
+        self
+    }
+
+    /// Redact account numbers and amounts on these channels before model calls
+    pub fn with_redaction(mut self, config: RedactionConfig) -> Self {
+        self.redaction = Some(config).filter(|c| !c.channels.is_empty());
+        self
+    }
+
+    /// Condense tool outputs over the configured length with a cheap model
+    pub fn with_tool_summaries(mut self, config: ToolSummaryConfig) -> Self {
+        self.tool_summaries = config.enabled.then(|| {
+            let store = Arc::new(ArtifactStore::new(config.max_artifacts));
+            (config, store)
+        });
+        self
+    }
+
     /// Handle an incoming message and generate a response
     pub async fn handle_message(&self, msg: IncomingMessage) -> Result<OutgoingMessage> {
+        self.handle_message_traced(msg)
+            .await
+            .map(|(response, _)| response)
+    }
+
+    /// Handle an incoming message, sending the reply's text to `sink` as
+    /// the model writes it
+    pub async fn handle_message_streamed(
+        &self,
+        msg: IncomingMessage,
+        sink: StreamSink,
+    ) -> Result<OutgoingMessage> {
+        streaming::scope(sink, self.handle_message(msg)).await
+    }
+
+    /// Handle an incoming message, also returning a trace of the turn
+    pub async fn handle_message_traced(
+        &self,
+        msg: IncomingMessage,
+    ) -> Result<(OutgoingMessage, TurnTrace)> {
+        match &self.redaction {
+            Some(config) if config.applies_to(&msg.channel.to_string()) => {
+                debug!("Redacting model calls for this turn on {}", msg.channel);
+                redaction::scope(config.redactor(), self.handle_turn(msg)).await
+            }
+            _ => self.handle_turn(msg).await,
+        }
+    }
+
+    async fn handle_turn(&self, msg: IncomingMessage) -> Result<(OutgoingMessage, TurnTrace)> {
+        let turn_started = Instant::now();
+        let mut trace = TurnTrace::new();
         info!(
             "Handling message from {} on channel {}",
             msg.sender, msg.channel
@@ -137,16 +352,108 @@ impl Agent {
                     msg.sender,
                     violations
                 );
-                return Ok(OutgoingMessage {
+                return Ok((
+                    OutgoingMessage {
+                        channel: msg.channel,
+                        content:
+                            "I'm unable to process that request as it was flagged by safety checks."
+                                .to_string(),
+                        reply_to: Some(msg.id.clone()),
+                        kind: MessageKind::Response,
+                        interactive: None,
+                    },
+                    trace,
+                ));
+            }
+        }
+
+        // Restricted profiles (e.g. a kid's contact) get a constrained agent
+        let restricted = self.restricted_profile(&msg);
+        if let Some(profile) = &restricted
+            && let Some(reply) = self.check_restricted(profile, &msg).await
+        {
+            return Ok((
+                OutgoingMessage {
                     channel: msg.channel,
-                    content:
-                        "I'm unable to process that request as it was flagged by safety checks."
-                            .to_string(),
-                    reply_to: Some(msg.id.clone()),
+                    content: reply,
+                    reply_to: Some(msg.id),
                     kind: MessageKind::Response,
-                });
+                    interactive: None,
+                },
+                trace,
+            ));
+        }
+        let private = restricted
+            .as_ref()
+            .and_then(|p| p.restrictions.as_ref())
+            .is_some_and(|r| !r.personal_knowledge);
+
+        // A bare 👍/👎 (or a reaction/tapback) rates the previous response
+        if msg.channel != ChannelType::Internal
+            && let Some(signal) = feedback::parse(&msg.content)
+        {
+            let reply = self.record_feedback(&msg, &signal).await;
+            return Ok((
+                OutgoingMessage {
+                    channel: msg.channel,
+                    content: reply,
+                    reply_to: Some(msg.id),
+                    kind: MessageKind::Response,
+                    interactive: None,
+                },
+                trace,
+            ));
+        }
+
+        // Inline commands ("!model haiku", "!tools off") adjust this conversation's settings
+        // (restricted profiles can't change them, and use the profile's model)
+        let (msg, settings, command_reply) = match &restricted {
+            Some(profile) => {
+                let settings = ConversationSettings {
+                    model: profile.model.clone(),
+                    ..Default::default()
+                };
+                (msg, settings, None)
             }
+            None => self.apply_inline_commands(msg).await,
+        };
+        if let Some(reply) = command_reply {
+            return Ok((
+                OutgoingMessage {
+                    channel: msg.channel,
+                    content: reply,
+                    reply_to: Some(msg.id),
+                    kind: MessageKind::Response,
+                    interactive: None,
+                },
+                trace,
+            ));
+        }
+        let mut settings = settings;
+        if settings.model.is_none() {
+            settings.model = self.channel_models.get(&msg.channel.to_string()).cloned();
         }
+        let model = settings
+            .model
+            .clone()
+            .unwrap_or_else(|| self.api.model().to_string());
+
+        let guest = guest::is_guest(&self.db, &self.guest_mode, &msg).await;
+        if guest {
+            debug!("Handling message from {} in guest mode", msg.sender);
+        }
+        let mode = modes::current();
+
+        // Only the owner is onboarded; first contact is checked before storing
+        let onboarding = self.onboarding
+            && !guest
+            && restricted.is_none()
+            && onboarding::interviewing(&self.db, &msg)
+                .await
+                .unwrap_or_else(|e| {
+                    debug!("Failed to check onboarding state: {}", e);
+                    false
+                });
 
         // Store the incoming message in conversation history
         self.db
@@ -154,7 +461,37 @@ impl Agent {
             .await
             .context("Failed to store conversation")?;
 
+        // Middleware may answer on its own (e.g. a contact's auto-reply)
+        let mw_ctx = MiddlewareContext {
+            query: msg.content.clone(),
+            channel: msg.channel.to_string(),
+            sender: msg.sender.clone(),
+            metadata: serde_json::Value::Null,
+        };
+        if let Some(reply) = self.middleware.run_before_agent(&mw_ctx).await? {
+            return Ok((
+                OutgoingMessage {
+                    channel: msg.channel,
+                    content: reply,
+                    reply_to: Some(msg.id),
+                    kind: MessageKind::Response,
+                    interactive: None,
+                },
+                trace,
+            ));
+        }
+
+        if msg.channel != ChannelType::Internal
+            && !guest
+            && restricted.is_none()
+            && let Some(config) = &self.action_items
+            && let Err(e) = action_items::process_message(&self.db, &msg.content, config).await
+        {
+            debug!("Failed to process action items: {}", e);
+        }
+
         // Understand the user's intent via LLM (with usage tracking)
+        let started = Instant::now();
         let (intent, intent_usage) =
             intent::understand_intent(&self.api, &msg.content, &self.intent_config)
                 .await
@@ -168,6 +505,15 @@ impl Agent {
             intent.action, intent.entities, intent.clarification_needed
         );
 
+        if let Some(usage) = &intent_usage {
+            trace.push_stage(
+                "intent",
+                usage.input_tokens,
+                usage.output_tokens,
+                started.elapsed(),
+            );
+        }
+
         // Record intent LLM usage if any
         if let (Some(tracker), Some(usage)) = (&self.usage_tracker, &intent_usage) {
             let precall_usage = crate::usage::AccumulatedUsage::from_tokens(
@@ -201,6 +547,7 @@ impl Agent {
         }
 
         // Route the query to determine retrieval strategy (with usage tracking)
+        let started = Instant::now();
         let (strategy, router_usage) =
             query_router::route_query_tracked(&msg.content, Some(&self.api), &self.router_config)
                 .await
@@ -220,6 +567,15 @@ impl Agent {
                     )
                 });
 
+        if let Some(usage) = &router_usage {
+            trace.push_stage(
+                "router",
+                usage.input_tokens,
+                usage.output_tokens,
+                started.elapsed(),
+            );
+        }
+
         // Record router LLM usage if any
         if let (Some(tracker), Some(usage)) = (&self.usage_tracker, &router_usage) {
             let precall_usage = crate::usage::AccumulatedUsage::from_tokens(
@@ -242,21 +598,165 @@ impl Agent {
         debug!("Query routed as {:?}", strategy.complexity);
 
         // Load relevant context from knowledge graph (guided by strategy and intent)
-        let context = self.load_context(&msg, &strategy, &intent).await?;
+        let mut context = if private {
+            self.load_restricted_context(&msg).await?
+        } else {
+            self.load_context(&msg, &strategy, &intent).await?
+        };
+
+        // Drop facts repeated across history, knowledge and sender details
+        let started = Instant::now();
+        let (compressed, stats, compression_usage) =
+            context_compression::compress_context(&self.api, &context, &self.context_compression)
+                .await;
+        context = compressed;
+        if let Some(usage) = &compression_usage {
+            trace.push_stage(
+                "context_compression",
+                usage.input_tokens,
+                usage.output_tokens,
+                started.elapsed(),
+            );
+            if let Some(tracker) = &self.usage_tracker
+                && let Err(e) = tracker
+                    .record(
+                        self.api.model(),
+                        &crate::usage::AccumulatedUsage::from_tokens(
+                            usage.input_tokens,
+                            usage.output_tokens,
+                        ),
+                        &UsageSource::User,
+                        Some(&msg.channel.to_string()),
+                    )
+                    .await
+            {
+                debug!("Failed to record context compression usage: {}", e);
+            }
+        }
+        if stats.tokens_saved > 0 {
+            trace.context = Some(stats);
+        }
+        if guest {
+            context.push_str(&guest::context_section());
+        }
+        if let Some(profile) = &restricted {
+            context.push_str(&restricted::context_section(profile));
+        }
+        if onboarding {
+            context.push_str(&onboarding::context_section());
+        }
+        if let Some(active) = &mode {
+            context.push_str(&modes::context_section_for(active));
+        }
+        if voice_notes::is_transcribed(&msg.content) {
+            context.push_str(&voice_notes::context_section(self.voice_note_summaries));
+        }
+        // Pins go in after compression so they're never trimmed
+        match pins::load(&self.db, &msg.channel.to_string(), &msg.sender).await {
+            Ok(pinned) => context.push_str(&pins::context_section(&pinned)),
+            Err(e) => debug!("Failed to load pinned context: {}", e),
+        }
 
         // Build system prompt
-        let system_prompt = build_system_prompt(&self.soul, &self.memory, &context);
+        let variant = self.experiment_variant(&msg).await;
+        let soul = match (&self.experiment, variant) {
+            (Some(experiment), Some(variant)) => experiment.soul(variant, &self.soul),
+            _ => &self.soul,
+        };
+        let soul = match mode.as_ref().filter(|a| !a.mode.soul.is_empty()) {
+            Some(active) => Cow::Owned(format!("{}\n\n{}", soul, active.mode.soul)),
+            None => Cow::Borrowed(soul),
+        };
+        let soul = if soul_template::is_template(&soul) {
+            let vars = self.soul_vars(&msg).await;
+            Cow::Owned(self.soul_renderer.render(&soul, &vars))
+        } else {
+            soul
+        };
+        let memory = if private { "" } else { self.memory.as_str() };
+        // Guests and restricted profiles don't get the user's schedule
+        let live = match &self.live_context {
+            Some(live) if !guest && restricted.is_none() => live.section().await,
+            _ => String::new(),
+        };
+        let system_prompt = build_system_prompt(&soul, memory, &context, &live);
 
         // Get tool definitions (with optional LLM selection + usage tracking)
-        let all_tools = self.tools.list_tools();
-        let (tool_definitions, selector_usage) = tool_selector::select_tools_tracked(
-            &self.api,
-            &msg.content,
-            &all_tools,
-            &self.tool_selector_config,
-        )
-        .await
-        .unwrap_or((all_tools, None));
+        let mut all_tools = self.tools.list_tools();
+        if guest {
+            all_tools.retain(|t| self.guest_mode.tool_allowed(&t.name));
+        }
+        if let Some(profile) = &restricted {
+            all_tools.retain(|t| restricted::tool_allowed(profile, self.tools.as_ref(), &t.name));
+        }
+        if let Some(active) = &mode {
+            all_tools.retain(|t| active.mode.tool_allowed(&t.name));
+        }
+        let scoped = tool_scope::current();
+        if let Some(allowed) = &scoped {
+            all_tools.retain(|t| allowed.contains(&t.name));
+        }
+        if let Some(days) = self.prune_unused_tools_after {
+            match self.db.unused_tools(days).await {
+                Ok(unused) if !unused.is_empty() => {
+                    let dropped = tool_selector::drop_unused(
+                        &msg.content,
+                        &mut all_tools,
+                        &unused,
+                        &self.tool_selector_config,
+                    );
+                    debug!("Left out {} tool(s) unused for {} days", dropped, days);
+                }
+                Ok(_) => {}
+                Err(e) => debug!("Failed to load unused tools: {}", e),
+            }
+        }
+        let started = Instant::now();
+        let onboarding_tool = all_tools
+            .iter()
+            .find(|t| onboarding && t.name == "complete_onboarding")
+            .cloned();
+        let (mut tool_definitions, selector_usage) = if settings.tools_enabled() {
+            tool_selector::select_tools_tracked(
+                &self.api,
+                &msg.content,
+                &all_tools,
+                &self.tool_selector_config,
+            )
+            .await
+            .unwrap_or((all_tools, None))
+        } else {
+            (Vec::new(), None)
+        };
+        // The interview's answers rarely mention onboarding, so keep its tool
+        if let Some(tool) = onboarding_tool
+            && settings.tools_enabled()
+            && !tool_definitions.iter().any(|t| t.name == tool.name)
+        {
+            tool_definitions.push(tool);
+        }
+
+        // Middleware may filter the prompt and tools (e.g. a contact's policy)
+        let (system_prompt, mut tool_definitions) = self
+            .middleware
+            .run_before_prompt(system_prompt, tool_definitions, &mw_ctx)
+            .await?;
+        let conversation = format!("{}:{}", msg.channel, msg.sender);
+        if let Some(costs) = &self.tool_costs {
+            costs.annotate(&conversation, &mut tool_definitions);
+        }
+        if self.tool_summaries.is_some() && !tool_definitions.is_empty() {
+            tool_definitions.push(tool_summaries::fetch_tool_definition());
+        }
+
+        if let Some(usage) = &selector_usage {
+            trace.push_stage(
+                "tool_selector",
+                usage.input_tokens,
+                usage.output_tokens,
+                started.elapsed(),
+            );
+        }
 
         // Record selector LLM usage if any
         if let (Some(tracker), Some(usage)) = (&self.usage_tracker, &selector_usage) {
@@ -290,16 +790,20 @@ impl Agent {
                     spent,
                     budget,
                 }) => {
-                    return Ok(OutgoingMessage {
-                        content: format!(
-                            "I've reached my {} budget limit (${:.2} of ${:.2}). \
-                             Please increase the budget in config.toml or wait for the next period.",
-                            period, spent, budget
-                        ),
-                        channel: msg.channel,
-                        reply_to: Some(msg.id),
-                        kind: MessageKind::Response,
-                    });
+                    return Ok((
+                        OutgoingMessage {
+                            content: format!(
+                                "I've reached my {} budget limit (${:.2} of ${:.2}). \
+                                 Please increase the budget in config.toml or wait for the next period.",
+                                period, spent, budget
+                            ),
+                            channel: msg.channel,
+                            reply_to: Some(msg.id),
+                            kind: MessageKind::Response,
+                            interactive: None,
+                        },
+                        trace,
+                    ));
                 }
                 Ok(crate::usage::BudgetStatus::Warning {
                     period,
@@ -329,26 +833,98 @@ impl Agent {
         } else {
             self.tools.clone()
         };
+        let tool_executor: Arc<dyn ToolExecutor> = if guest {
+            Arc::new(GuestToolExecutor::new(
+                tool_executor,
+                self.guest_mode.clone(),
+            ))
+        } else {
+            tool_executor
+        };
+        let tool_executor: Arc<dyn ToolExecutor> = match &mode {
+            Some(active) if !active.mode.tools.is_empty() => {
+                Arc::new(ModeToolExecutor::new(tool_executor, active.mode.clone()))
+            }
+            _ => tool_executor,
+        };
+        let tool_executor: Arc<dyn ToolExecutor> = match &restricted {
+            Some(profile) => Arc::new(RestrictedToolExecutor::new(tool_executor, profile.clone())),
+            None => tool_executor,
+        };
+        let tool_executor: Arc<dyn ToolExecutor> = match scoped {
+            Some(allowed) => Arc::new(ScopedToolExecutor::new(tool_executor, allowed)),
+            None => tool_executor,
+        };
+        let tool_executor: Arc<dyn ToolExecutor> = match &self.tool_costs {
+            Some(costs) => Arc::new(CostedToolExecutor::new(
+                tool_executor,
+                costs.clone(),
+                conversation,
+            )),
+            None => tool_executor,
+        };
+        // Outermost, so the fetch tool isn't filtered out by the wrappers above
+        let summarizer = self.tool_summaries.as_ref().map(|(config, store)| {
+            Arc::new(SummarizingToolExecutor::new(
+                tool_executor.clone(),
+                self.api.clone(),
+                store.clone(),
+                config.clone(),
+            ))
+        });
+        let tool_executor: Arc<dyn ToolExecutor> = match &summarizer {
+            Some(summarizer) => summarizer.clone(),
+            None => tool_executor,
+        };
 
-        // Run the tool loop to get final response
-        let (response_text, usage) = self
-            .api
-            .run_tool_loop(
-                &msg.content,
-                &system_prompt,
-                &tool_definitions,
-                tool_executor.as_ref(),
-            )
-            .await
-            .context("Failed to run agent tool loop")?;
+        // Run the tool loop to get final response, thinking harder on
+        // complex queries when budgets are configured
+        let mut overrides = settings.request_overrides();
+        overrides.thinking_budget = self.router_config.thinking.budget_for(strategy.complexity);
+        let (response_text, usage, hops) = crate::providers::overrides::scope(
+            overrides,
+            send_guard::scope_owner(
+                !guest && restricted.is_none(),
+                pins::scope_conversation(
+                    msg.channel.to_string(),
+                    msg.sender.clone(),
+                    self.api.run_tool_loop_traced(
+                        &msg.content,
+                        &system_prompt,
+                        &tool_definitions,
+                        tool_executor.as_ref(),
+                    ),
+                ),
+            ),
+        )
+        .await
+        .context("Failed to run agent tool loop")?;
+
+        if let Some((usage, elapsed)) = summarizer.and_then(|s| s.take_usage()) {
+            trace.push_stage(
+                "tool_summaries",
+                usage.input_tokens,
+                usage.output_tokens,
+                elapsed,
+            );
+            if let Some(tracker) = &self.usage_tracker
+                && let Err(e) = tracker
+                    .record(
+                        self.api.model(),
+                        &crate::usage::AccumulatedUsage::from_tokens(
+                            usage.input_tokens,
+                            usage.output_tokens,
+                        ),
+                        &UsageSource::User,
+                        Some(&msg.channel.to_string()),
+                    )
+                    .await
+            {
+                debug!("Failed to record tool summary usage: {}", e);
+            }
+        }
 
         // Run middleware after_agent hooks on the final response
-        let mw_ctx = MiddlewareContext {
-            query: msg.content.clone(),
-            channel: msg.channel.to_string(),
-            sender: msg.sender.clone(),
-            metadata: serde_json::Value::Null,
-        };
         let response_text = self
             .middleware
             .run_after_agent(response_text, &mw_ctx)
@@ -358,11 +934,24 @@ impl Agent {
                 String::from("[Response processing error]")
             });
 
+        // Restricted profiles' content filter applies to replies too
+        let response_text = match restricted
+            .as_ref()
+            .and_then(|p| p.restrictions.as_ref())
+            .and_then(|r| r.blocked_term(&response_text))
+        {
+            Some(term) => {
+                warn!("Withheld a reply to {} containing '{}'", msg.sender, term);
+                String::from("Sorry, I can't help with that one.")
+            }
+            None => response_text,
+        };
+
         // Record usage
         if let Some(tracker) = &self.usage_tracker
             && let Err(e) = tracker
                 .record(
-                    self.api.model(),
+                    &model,
                     &usage,
                     &UsageSource::User,
                     Some(&msg.channel.to_string()),
@@ -372,9 +961,56 @@ impl Agent {
             debug!("Failed to record usage: {}", e);
         }
 
-        // Store the response in conversation history
+        trace.hops.extend(hops);
+        trace.duration_ms = turn_started.elapsed().as_millis() as u64;
+
+        let calls: Vec<ToolCallRecord> = trace
+            .tool_calls()
+            .map(|t| ToolCallRecord {
+                tool: t.name.clone(),
+                ok: t.ok,
+                duration_ms: t.duration_ms,
+                output_chars: t.output_chars,
+            })
+            .collect();
+        if let Err(e) = self.db.record_tool_calls(calls).await {
+            debug!("Failed to record tool stats: {}", e);
+        }
+
+        // Store the response in conversation history with who it answered, the
+        // model and tools behind it (for feedback) and the trace for debugging
+        let mut tools_used: Vec<&str> = trace.tool_calls().map(|t| t.name.as_str()).collect();
+        tools_used.sort();
+        tools_used.dedup();
+        let mut metadata = serde_json::json!({
+            "to": msg.sender,
+            "model": model,
+            "tools": tools_used,
+            "tool_errors": trace.tool_calls().filter(|t| !t.ok).count(),
+        });
+        if let (Some(experiment), Some(variant)) = (&self.experiment, variant) {
+            metadata["experiment"] = serde_json::json!({
+                "name": experiment.name,
+                "variant": variant,
+            });
+        }
+        if self.store_traces {
+            // Thinking stays in the returned trace but not in stored history
+            let mut stored = trace.clone();
+            if !self.router_config.thinking.store_in_history {
+                stored.redact_thinking();
+            }
+            if let Ok(t) = serde_json::to_value(&stored) {
+                metadata["trace"] = t;
+            }
+        }
         self.db
-            .insert_conversation(&msg.channel.to_string(), "meepo", &response_text, None)
+            .insert_conversation(
+                &msg.channel.to_string(),
+                "meepo",
+                &response_text,
+                Some(metadata),
+            )
             .await
             .context("Failed to store response")?;
 
@@ -384,12 +1020,253 @@ impl Agent {
             usage.total_tokens()
         );
 
-        Ok(OutgoingMessage {
-            content: response_text,
-            channel: msg.channel,
-            reply_to: Some(msg.id),
-            kind: MessageKind::Response,
-        })
+        Ok((
+            OutgoingMessage {
+                content: response_text,
+                channel: msg.channel,
+                reply_to: Some(msg.id),
+                kind: MessageKind::Response,
+                interactive: None,
+            },
+            trace,
+        ))
+    }
+
+    /// The sender's variant in the running prompt experiment, if any.
+    /// Internal messages always get the configured SOUL.
+    async fn experiment_variant(&self, msg: &IncomingMessage) -> Option<Variant> {
+        let experiment = self.experiment.as_ref()?;
+        if msg.channel == ChannelType::Internal {
+            return None;
+        }
+        experiment
+            .assign(&self.db, &msg.channel.to_string(), &msg.sender)
+            .await
+            .map_err(|e| debug!("Failed to assign experiment variant: {}", e))
+            .ok()
+    }
+
+    /// Store a 👍/👎 against the sender's previous response and return the reply
+    async fn record_feedback(
+        &self,
+        msg: &IncomingMessage,
+        signal: &feedback::FeedbackSignal,
+    ) -> String {
+        match feedback::record(&self.db, &msg.channel.to_string(), &msg.sender, signal).await {
+            Ok(Some(_)) if signal.rating > 0 => "Thanks for the feedback!".to_string(),
+            Ok(Some(_)) => {
+                "Thanks for letting me know. Tell me what was wrong and I'll try again.".to_string()
+            }
+            Ok(None) => "There's no recent reply of mine to rate.".to_string(),
+            Err(e) => {
+                debug!("Failed to record feedback: {}", e);
+                "Sorry, I couldn't save that feedback.".to_string()
+            }
+        }
+    }
+
+    /// Apply leading inline commands and load the sender's conversation settings.
+    ///
+    /// Returns the message without its command lines, the settings in effect,
+    /// and a reply to send instead of running the agent when the message held
+    /// nothing but commands (or a command was invalid).
+    async fn apply_inline_commands(
+        &self,
+        mut msg: IncomingMessage,
+    ) -> (IncomingMessage, ConversationSettings, Option<String>) {
+        let channel = msg.channel.to_string();
+        let settings = inline_commands::load(&self.db, &channel, &msg.sender)
+            .await
+            .unwrap_or_else(|e| {
+                debug!("Failed to load conversation settings: {}", e);
+                ConversationSettings::default()
+            });
+
+        let parsed = inline_commands::parse(&msg.content);
+        if parsed.commands.is_empty() {
+            return (msg, settings, None);
+        }
+
+        let mut updated = settings.clone();
+        let mut notes = Vec::new();
+        for (command, arg) in &parsed.commands {
+            let result = match command.as_str() {
+                "pin" => pins::pin(&self.db, &channel, &msg.sender, arg)
+                    .await
+                    .map(Some),
+                "unpin" => pins::unpin(&self.db, &channel, &msg.sender, arg)
+                    .await
+                    .map(Some),
+                "pins" => pins::load(&self.db, &channel, &msg.sender)
+                    .await
+                    .map(|p| Some(pins::describe(&p))),
+                _ => updated.apply(command, arg).map(|_| None),
+            };
+            match result {
+                Ok(Some(note)) => notes.push(note),
+                Ok(None) => {}
+                Err(e) => return (msg, settings, Some(e.to_string())),
+            }
+        }
+        if updated != settings {
+            if let Err(e) = inline_commands::save(&self.db, &channel, &msg.sender, &updated).await {
+                return (
+                    msg,
+                    settings,
+                    Some(format!("Couldn't save conversation settings: {}", e)),
+                );
+            }
+            info!(
+                "Conversation settings for {} on {}: {}",
+                msg.sender,
+                channel,
+                updated.describe()
+            );
+        }
+
+        // Settings are only echoed when a settings command was sent
+        if parsed
+            .commands
+            .iter()
+            .any(|(command, _)| !pins::COMMANDS.contains(&command.as_str()))
+        {
+            notes.push(format!("Conversation settings: {}", updated.describe()));
+        }
+        let reply = parsed.rest.is_empty().then(|| notes.join("\n"));
+        msg.content = parsed.rest;
+        (msg, updated, reply)
+    }
+
+    /// Reply-language override for a sender, learned from the language they write in.
+    ///
+    /// Per-sender overrides are stored as `language` preferences keyed by
+    /// `language:channel:sender`; internal messages never update them, and a pinned
+    /// language is used whatever the message is written in.
+    async fn reply_language_section(&self, msg: &IncomingMessage) -> String {
+        let default = i18n::language();
+        let key = style::preference_key(
+            i18n::PREFERENCE_CATEGORY,
+            &style::sender_scope(&msg.channel.to_string(), &msg.sender),
+        );
+        let stored = self
+            .db
+            .get_preferences(Some(i18n::PREFERENCE_CATEGORY))
+            .await
+            .ok()
+            .and_then(|prefs| prefs.into_iter().find(|p| p.key == key));
+        let pinned = stored
+            .as_ref()
+            .is_some_and(|p| p.learned_from.as_deref() == Some(i18n::PINNED));
+        let learned = stored.and_then(|p| p.value.as_str().map(String::from));
+
+        let detected = if msg.channel == ChannelType::Internal || pinned {
+            None
+        } else {
+            let content = msg
+                .content
+                .strip_prefix(voice_notes::TRANSCRIBED_PREFIX)
+                .unwrap_or(&msg.content);
+            i18n::detect_language(content)
+        };
+
+        if let Some(lang) = detected
+            && learned.as_deref().unwrap_or(default) != lang
+        {
+            debug!("Learned reply language '{}' for {}", lang, key);
+            if let Err(e) = self
+                .db
+                .upsert_preference(
+                    i18n::PREFERENCE_CATEGORY,
+                    &key,
+                    serde_json::json!(lang),
+                    0.7,
+                    Some("conversation"),
+                )
+                .await
+            {
+                debug!("Failed to store language preference: {}", e);
+            }
+        }
+
+        // The default language is already in the system prompt
+        match detected.map(String::from).or(learned) {
+            Some(lang) if lang != default => format!(
+                "## Reply Language\n\n{}\n\n",
+                i18n::reply_instruction(&lang, true)
+            ),
+            _ => String::new(),
+        }
+    }
+
+    /// The restricted profile this message routes to, if any. Internal
+    /// messages (watchers, the autonomous loop) are never restricted.
+    fn restricted_profile(&self, msg: &IncomingMessage) -> Option<AgentProfile> {
+        if msg.channel == ChannelType::Internal {
+            return None;
+        }
+        let profile = self.profiles.as_ref()?.route(&msg.channel, &msg.sender);
+        profile.is_restricted().then(|| profile.clone())
+    }
+
+    /// Apply a restricted profile's content filter and daily cap, returning
+    /// the reply to send instead of answering
+    async fn check_restricted(
+        &self,
+        profile: &AgentProfile,
+        msg: &IncomingMessage,
+    ) -> Option<String> {
+        if let Some(term) = profile
+            .restrictions
+            .as_ref()
+            .and_then(|r| r.blocked_term(&msg.content))
+        {
+            warn!(
+                "Content filter for profile '{}' blocked a message containing '{}'",
+                profile.id, term
+            );
+            return Some("Sorry, I can't help with that one.".to_string());
+        }
+        match restricted::take_daily_message(&self.db, profile).await {
+            Ok(true) => None,
+            Ok(false) => {
+                info!("Profile '{}' reached its daily message limit", profile.id);
+                Some("That's all for today — you've reached your daily message limit.".to_string())
+            }
+            Err(e) => {
+                // Fail closed: a cap we can't check shouldn't become no cap
+                warn!(
+                    "Failed to check daily usage for profile '{}': {}",
+                    profile.id, e
+                );
+                Some("I can't answer right now, please try again later.".to_string())
+            }
+        }
+    }
+
+    /// Context for restricted profiles without personal knowledge: only this
+    /// sender's own recent messages, nothing from the owner's knowledge base
+    async fn load_restricted_context(&self, msg: &IncomingMessage) -> Result<String> {
+        let mut context = String::new();
+        let recent = self
+            .db
+            .get_recent_conversations(Some(&msg.channel.to_string()), 30)
+            .await
+            .context("Failed to load recent conversations")?;
+        let own: Vec<_> = recent
+            .iter()
+            .filter(|c| c.sender == msg.sender)
+            .take(10)
+            .collect();
+        if !own.is_empty() {
+            context.push_str("## Their Recent Messages\n\n");
+            for c in own.iter().rev() {
+                let content: String = c.content.chars().take(500).collect();
+                context.push_str(&format!("- {}\n", content));
+            }
+            context.push('\n');
+        }
+        context.push_str(&self.reply_language_section(msg).await);
+        Ok(context)
     }
 
     /// Load relevant context for the message.
@@ -468,8 +1345,9 @@ impl Agent {
 
             if !keywords.is_empty() {
                 context.push_str("## Relevant Knowledge\n\n");
+                let mut matched = 0usize;
 
-                for keyword in keywords {
+                for &keyword in &keywords {
                     // Early termination: skip remaining keywords if context is already large
                     if context.len() > MAX_CONTEXT_SIZE {
                         truncated = true;
@@ -477,6 +1355,9 @@ impl Agent {
                     }
 
                     if let Ok(entities) = self.db.search_entities(keyword, None).await {
+                        if !entities.is_empty() {
+                            matched += 1;
+                        }
                         for entity in entities.iter().take(strategy.knowledge_limit.min(3)) {
                             context
                                 .push_str(&format!("- {} ({})", entity.name, entity.entity_type));
@@ -488,6 +1369,19 @@ impl Agent {
                     }
                 }
                 context.push('\n');
+
+                if !truncated
+                    && msg.channel != ChannelType::Internal
+                    && let Some(config) = &self.knowledge_gaps
+                {
+                    let score = matched as f64 / keywords.len() as f64;
+                    if let Err(e) = GapTracker::new(&self.db, config)
+                        .observe(&msg.content, &keywords, score)
+                        .await
+                    {
+                        debug!("Failed to record knowledge gap: {}", e);
+                    }
+                }
             }
         }
 
@@ -504,6 +1398,30 @@ impl Agent {
             context.push('\n');
         }
 
+        // Reply language (configured default, or what this sender writes in)
+        context.push_str(&self.reply_language_section(msg).await);
+
+        // Response style (default plus this sender's overrides)
+        let channel = msg.channel.to_string();
+        match style::effective(&self.db, &channel, &msg.sender).await {
+            Ok(style) if !style.is_empty() => {
+                context.push_str("## Response Style\n\n");
+                context.push_str(&style.instructions());
+                context.push_str(&format!(
+                    "\n(Adjust with set_style using channel \"{}\" and sender \"{}\".)\n\n",
+                    channel, msg.sender
+                ));
+            }
+            Ok(_) => {}
+            Err(e) => debug!("Failed to load response style: {}", e),
+        }
+
+        // Power state (only when running on battery)
+        context.push_str(&power::context_section());
+
+        // Active Focus mode
+        context.push_str(&focus::context_section());
+
         // Final truncation guard: hard-cap the string if it still exceeds the limit
         if context.len() > MAX_CONTEXT_SIZE {
             context.truncate(MAX_CONTEXT_SIZE);
@@ -520,6 +1438,35 @@ impl Agent {
         Ok(context)
     }
 
+    /// Template variables for a templated SOUL.md
+    async fn soul_vars(&self, msg: &IncomingMessage) -> SoulVars {
+        let user_name = match self.db.get_preferences(Some("communication")).await {
+            Ok(prefs) => prefs
+                .into_iter()
+                .find(|p| p.key == "user_name")
+                .and_then(|p| p.value.as_str().map(str::to_string)),
+            Err(e) => {
+                debug!("Failed to load user name for SOUL template: {}", e);
+                None
+            }
+        };
+        let active_goals = self
+            .db
+            .get_active_goals()
+            .await
+            .map(|goals| goals.len())
+            .unwrap_or_else(|e| {
+                debug!("Failed to count goals for SOUL template: {}", e);
+                0
+            });
+        SoulVars {
+            user_name,
+            active_goals,
+            channel: msg.channel.to_string(),
+            ..SoulVars::at(&crate::timezone::now())
+        }
+    }
+
     /// Update the agent's memory
     pub fn update_memory(&mut self, new_memory: String) {
         self.memory = new_memory;
@@ -613,6 +1560,44 @@ mod tests {
         assert!(context.len() <= 100_000, "Context unexpectedly large");
     }
 
+    #[tokio::test]
+    async fn test_reply_language_learned_per_sender() {
+        let (agent, _temp) = create_test_agent();
+        let msg = IncomingMessage {
+            id: "test-2".to_string(),
+            sender: "maria".to_string(),
+            content: "Hola, ¿me puedes decir qué tiempo hace hoy en la ciudad?".to_string(),
+            channel: ChannelType::Discord,
+            timestamp: Utc::now(),
+        };
+        let section = agent.reply_language_section(&msg).await;
+        assert!(section.contains("Spanish"));
+
+        // A later short message still gets the learned language
+        let short = IncomingMessage {
+            content: "ok".to_string(),
+            ..msg.clone()
+        };
+        let section = agent.reply_language_section(&short).await;
+        assert!(section.contains("Spanish"));
+
+        let prefs = agent.db().get_preferences(Some("language")).await.unwrap();
+        assert_eq!(prefs.len(), 1);
+        assert_eq!(prefs[0].key, "language:discord:maria");
+
+        // A pinned language wins over what the message is written in
+        i18n::pin_language(agent.db(), "discord:maria", "de")
+            .await
+            .unwrap();
+        let section = agent.reply_language_section(&msg).await;
+        assert!(section.contains("German"));
+        i18n::pin_language(agent.db(), "discord:maria", "unset")
+            .await
+            .unwrap();
+        let section = agent.reply_language_section(&msg).await;
+        assert!(section.contains("Spanish"));
+    }
+
     #[test]
     fn test_update_soul() {
         let (mut agent, _temp) = create_test_agent();
@@ -744,4 +1729,541 @@ mod tests {
             .unwrap();
         assert!(context.contains("Rust Language"));
     }
+
+    #[tokio::test]
+    async fn test_unanswered_question_records_knowledge_gap() {
+        let (agent, _temp) = create_test_agent();
+        let agent = agent.with_knowledge_gaps(KnowledgeGapConfig::default());
+        agent
+            .db
+            .insert_entity("Rust Language", "concept", None)
+            .await
+            .unwrap();
+
+        let strategy = RetrievalStrategy {
+            complexity: query_router::QueryComplexity::SingleStep,
+            search_knowledge: true,
+            search_web: false,
+            load_history: false,
+            graph_expand: false,
+            corrective_rag: false,
+            knowledge_limit: 5,
+        };
+        for content in [
+            "What does Kubernetes autoscaling cost?",
+            "Is Rust Language fast?",
+        ] {
+            let msg = IncomingMessage {
+                id: "test-gap".to_string(),
+                sender: "user".to_string(),
+                content: content.to_string(),
+                channel: ChannelType::Slack,
+                timestamp: Utc::now(),
+            };
+            agent
+                .load_context(&msg, &strategy, &UserIntent::default())
+                .await
+                .unwrap();
+        }
+
+        let gaps = crate::knowledge_gaps::open_gaps(&agent.db).await.unwrap();
+        assert_eq!(gaps.len(), 1);
+        assert_eq!(gaps[0].topic, "kubernetes autoscaling cost");
+    }
+
+    /// Asks for the `lookup` tool once, then answers
+    struct ToolCallingProvider;
+
+    #[async_trait::async_trait]
+    impl crate::providers::LlmProvider for ToolCallingProvider {
+        fn provider_name(&self) -> &str {
+            "scripted"
+        }
+        fn model(&self) -> &str {
+            "scripted-1"
+        }
+        async fn chat(
+            &self,
+            messages: &[crate::providers::ChatMessage],
+            _tools: &[crate::api::ToolDefinition],
+            _system: &str,
+        ) -> Result<crate::providers::ChatResponse> {
+            use crate::providers::types::{ChatResponseBlock, ChatUsage, StopReason};
+            let (mut blocks, stop_reason) = if messages.len() == 1 {
+                (
+                    vec![ChatResponseBlock::ToolCall {
+                        id: "call-1".to_string(),
+                        name: "lookup".to_string(),
+                        input: serde_json::json!({"query": "weather", "api_key": "sk-secret"}),
+                    }],
+                    StopReason::ToolUse,
+                )
+            } else {
+                (
+                    vec![ChatResponseBlock::Text {
+                        text: "Sunny".to_string(),
+                    }],
+                    StopReason::EndTurn,
+                )
+            };
+            // With a thinking budget, think first; earlier thinking must
+            // come back in the assistant turn
+            if let Some(budget) = crate::providers::overrides::current().thinking_budget {
+                let echoed = messages.iter().any(|m| {
+                    matches!(&m.content, crate::providers::ChatMessageContent::Blocks(b)
+                        if b.iter().any(|b| matches!(b, crate::providers::types::ChatBlock::Thinking { .. })))
+                });
+                if messages.len() > 1 && !echoed {
+                    anyhow::bail!("thinking block was not sent back");
+                }
+                blocks.insert(
+                    0,
+                    ChatResponseBlock::Thinking {
+                        thinking: format!("Budget {}: the user wants the weather.", budget),
+                        signature: "sig".to_string(),
+                    },
+                );
+            }
+            Ok(crate::providers::ChatResponse {
+                blocks,
+                stop_reason,
+                usage: ChatUsage {
+                    input_tokens: 50,
+                    output_tokens: 5,
+                },
+            })
+        }
+    }
+
+    struct LookupTool;
+
+    #[async_trait::async_trait]
+    impl crate::tools::ToolHandler for LookupTool {
+        fn name(&self) -> &str {
+            "lookup"
+        }
+        fn description(&self) -> &str {
+            "Look something up"
+        }
+        fn input_schema(&self) -> serde_json::Value {
+            serde_json::json!({"type": "object"})
+        }
+        async fn execute(&self, _input: serde_json::Value) -> Result<String> {
+            Ok("72F and clear".to_string())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_handle_message_traced_records_tools_and_stores_trace() {
+        let temp_dir = TempDir::new().unwrap();
+        let db = Arc::new(KnowledgeDb::new(temp_dir.path().join("test.db")).unwrap());
+        let mut registry = ToolRegistry::new();
+        registry.register(Arc::new(LookupTool));
+        let agent = Agent::new(
+            ApiClient::from_router(crate::providers::ModelRouter::single(Box::new(
+                ToolCallingProvider,
+            ))),
+            Arc::new(registry),
+            "soul".to_string(),
+            String::new(),
+            db.clone(),
+        )
+        .with_intent_config(IntentConfig {
+            enabled: false,
+            min_length: 10,
+        })
+        .with_trace_storage(true);
+
+        let msg = IncomingMessage {
+            id: "test-4".to_string(),
+            sender: "user".to_string(),
+            content: "What's the weather?".to_string(),
+            channel: ChannelType::Internal,
+            timestamp: Utc::now(),
+        };
+        let (response, trace) = agent.handle_message_traced(msg).await.unwrap();
+        assert_eq!(response.content, "Sunny");
+
+        let loop_hops: Vec<_> = trace
+            .hops
+            .iter()
+            .filter(|h| h.stage == "tool_loop")
+            .collect();
+        assert_eq!(loop_hops.len(), 2);
+        assert_eq!(loop_hops[0].stop_reason.as_deref(), Some("tool_use"));
+        let call = trace.tool_calls().next().unwrap();
+        assert_eq!(call.name, "lookup");
+        assert!(call.ok);
+        assert_eq!(call.output_chars, "72F and clear".len());
+        assert_eq!(call.input["api_key"], "[redacted]");
+
+        let rows = db.get_recent_conversations(None, 10).await.unwrap();
+        let reply = rows.iter().find(|c| c.sender == "meepo").unwrap();
+        let stored = &reply.metadata.as_ref().unwrap()["trace"];
+        assert_eq!(stored["hops"].as_array().unwrap().len(), trace.hops.len());
+
+        let stats = db.get_tool_stats().await.unwrap();
+        assert_eq!(stats[0].tool, "lookup");
+        assert_eq!(stats[0].calls, 1);
+        assert_eq!(stats[0].success_rate(), 1.0);
+    }
+
+    #[tokio::test]
+    async fn test_thinking_budget_by_complexity_and_redacted_from_history() {
+        let temp_dir = TempDir::new().unwrap();
+        let db = Arc::new(KnowledgeDb::new(temp_dir.path().join("test.db")).unwrap());
+        let mut registry = ToolRegistry::new();
+        registry.register(Arc::new(LookupTool));
+        let agent = Agent::new(
+            ApiClient::from_router(crate::providers::ModelRouter::single(Box::new(
+                ToolCallingProvider,
+            ))),
+            Arc::new(registry),
+            "soul".to_string(),
+            String::new(),
+            db.clone(),
+        )
+        .with_intent_config(IntentConfig {
+            enabled: false,
+            min_length: 10,
+        })
+        .with_router_config(QueryRouterConfig {
+            thinking: query_router::ThinkingBudgets {
+                enabled: true,
+                single_step: 2048,
+                ..Default::default()
+            },
+            ..Default::default()
+        })
+        .with_trace_storage(true);
+
+        let msg = IncomingMessage {
+            id: "test-5".to_string(),
+            sender: "user".to_string(),
+            content: "What's the weather?".to_string(),
+            channel: ChannelType::Internal,
+            timestamp: Utc::now(),
+        };
+        let (response, trace) = agent.handle_message_traced(msg).await.unwrap();
+        assert_eq!(response.content, "Sunny");
+        let thinking = trace
+            .hops
+            .iter()
+            .find_map(|h| h.thinking.as_deref())
+            .unwrap();
+        assert_eq!(thinking, "Budget 2048: the user wants the weather.");
+
+        let rows = db.get_recent_conversations(None, 10).await.unwrap();
+        let reply = rows.iter().find(|c| c.sender == "meepo").unwrap();
+        let stored = &reply.metadata.as_ref().unwrap()["trace"];
+        assert!(
+            stored["hops"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .all(|h| h.get("thinking").is_none())
+        );
+    }
+
+    #[tokio::test]
+    async fn test_thumbs_down_rates_previous_response() {
+        let temp_dir = TempDir::new().unwrap();
+        let db = Arc::new(KnowledgeDb::new(temp_dir.path().join("test.db")).unwrap());
+        let mut registry = ToolRegistry::new();
+        registry.register(Arc::new(LookupTool));
+        let agent = Agent::new(
+            ApiClient::from_router(crate::providers::ModelRouter::single(Box::new(
+                ToolCallingProvider,
+            ))),
+            Arc::new(registry),
+            "soul".to_string(),
+            String::new(),
+            db.clone(),
+        )
+        .with_intent_config(IntentConfig {
+            enabled: false,
+            min_length: 10,
+        });
+
+        let message = |content: &str| IncomingMessage {
+            id: "test-feedback".to_string(),
+            sender: "alice".to_string(),
+            content: content.to_string(),
+            channel: ChannelType::Slack,
+            timestamp: Utc::now(),
+        };
+        let response = agent
+            .handle_message(message("What's the weather?"))
+            .await
+            .unwrap();
+        assert_eq!(response.content, "Sunny");
+
+        let response = agent.handle_message(message("👎 too vague")).await.unwrap();
+        assert!(response.content.starts_with("Thanks for letting me know"));
+
+        let today = Utc::now().format("%Y-%m-%d").to_string();
+        let feedback = db.get_feedback(&today, &today).await.unwrap();
+        assert_eq!(feedback.len(), 1);
+        assert_eq!(feedback[0].rating, -1);
+        assert_eq!(feedback[0].model.as_deref(), Some("scripted-1"));
+        assert_eq!(feedback[0].tools, vec!["lookup".to_string()]);
+        assert_eq!(feedback[0].comment.as_deref(), Some("too vague"));
+    }
+
+    /// Replies with the identity section of the system prompt
+    struct IdentityEchoProvider;
+
+    #[async_trait::async_trait]
+    impl crate::providers::LlmProvider for IdentityEchoProvider {
+        fn provider_name(&self) -> &str {
+            "scripted"
+        }
+        fn model(&self) -> &str {
+            "scripted-1"
+        }
+        async fn chat(
+            &self,
+            _messages: &[crate::providers::ChatMessage],
+            _tools: &[crate::api::ToolDefinition],
+            system: &str,
+        ) -> Result<crate::providers::ChatResponse> {
+            use crate::providers::types::{ChatResponseBlock, ChatUsage, StopReason};
+            let identity = system
+                .strip_prefix("# IDENTITY\n\n")
+                .and_then(|rest| rest.lines().next())
+                .unwrap_or_default();
+            Ok(crate::providers::ChatResponse {
+                blocks: vec![ChatResponseBlock::Text {
+                    text: identity.to_string(),
+                }],
+                stop_reason: StopReason::EndTurn,
+                usage: ChatUsage {
+                    input_tokens: 10,
+                    output_tokens: 5,
+                },
+            })
+        }
+    }
+
+    #[tokio::test]
+    async fn test_experiment_variant_replaces_soul() {
+        let temp_dir = TempDir::new().unwrap();
+        let db = Arc::new(KnowledgeDb::new(temp_dir.path().join("test.db")).unwrap());
+        let agent = Agent::new(
+            ApiClient::from_router(crate::providers::ModelRouter::single(Box::new(
+                IdentityEchoProvider,
+            ))),
+            Arc::new(ToolRegistry::new()),
+            "Variant A soul".to_string(),
+            String::new(),
+            db.clone(),
+        )
+        .with_intent_config(IntentConfig {
+            enabled: false,
+            min_length: 10,
+        })
+        .with_experiment(PromptExperiment {
+            name: "soul-test".to_string(),
+            variant_b: "Variant B soul".to_string(),
+            split: 1.0,
+        });
+
+        let message = |channel: ChannelType| IncomingMessage {
+            id: "test-experiment".to_string(),
+            sender: "alice".to_string(),
+            content: "Hello there".to_string(),
+            channel,
+            timestamp: Utc::now(),
+        };
+        let response = agent
+            .handle_message(message(ChannelType::Slack))
+            .await
+            .unwrap();
+        assert_eq!(response.content, "Variant B soul");
+        // Internal work isn't part of the experiment
+        let response = agent
+            .handle_message(message(ChannelType::Internal))
+            .await
+            .unwrap();
+        assert_eq!(response.content, "Variant A soul");
+
+        let report = crate::experiments::ExperimentReport::load(&db, "soul-test")
+            .await
+            .unwrap();
+        assert_eq!(report.a.responses, 0);
+        assert_eq!(report.b.responses, 1);
+        assert_eq!(report.b.successes, 1);
+    }
+
+    /// Replies with the model, temperature and tool count it was called with
+    struct OverrideEchoProvider;
+
+    #[async_trait::async_trait]
+    impl crate::providers::LlmProvider for OverrideEchoProvider {
+        fn provider_name(&self) -> &str {
+            "anthropic"
+        }
+        fn model(&self) -> &str {
+            "claude-sonnet-4-5"
+        }
+        async fn chat(
+            &self,
+            _messages: &[crate::providers::ChatMessage],
+            tools: &[crate::api::ToolDefinition],
+            _system: &str,
+        ) -> Result<crate::providers::ChatResponse> {
+            use crate::providers::types::{ChatResponseBlock, ChatUsage, StopReason};
+            let overrides = crate::providers::overrides::current();
+            let text = format!(
+                "model={} temp={:?} tools={}",
+                overrides.model_for("anthropic", self.model()),
+                overrides.temperature,
+                tools.len()
+            );
+            Ok(crate::providers::ChatResponse {
+                blocks: vec![ChatResponseBlock::Text { text }],
+                stop_reason: StopReason::EndTurn,
+                usage: ChatUsage {
+                    input_tokens: 10,
+                    output_tokens: 5,
+                },
+            })
+        }
+    }
+
+    #[tokio::test]
+    async fn test_inline_commands_persist_for_conversation() {
+        let temp_dir = TempDir::new().unwrap();
+        let db = Arc::new(KnowledgeDb::new(temp_dir.path().join("test.db")).unwrap());
+        let mut registry = ToolRegistry::new();
+        registry.register(Arc::new(LookupTool));
+        let agent = Agent::new(
+            ApiClient::from_router(crate::providers::ModelRouter::single(Box::new(
+                OverrideEchoProvider,
+            ))),
+            Arc::new(registry),
+            "soul".to_string(),
+            String::new(),
+            db.clone(),
+        )
+        .with_intent_config(IntentConfig {
+            enabled: false,
+            min_length: 10,
+        });
+        let send = |content: &str, sender: &str| IncomingMessage {
+            id: uuid::Uuid::new_v4().to_string(),
+            sender: sender.to_string(),
+            content: content.to_string(),
+            channel: ChannelType::Slack,
+            timestamp: Utc::now(),
+        };
+
+        // Commands alone are acknowledged without calling the model
+        let reply = agent
+            .handle_message(send("!model haiku\n!tools off", "alice"))
+            .await
+            .unwrap();
+        assert_eq!(
+            reply.content,
+            "Conversation settings: model=claude-haiku-4-5, tools=off"
+        );
+
+        // Settings stick for later messages, and a command can lead a question
+        let reply = agent
+            .handle_message(send("!temp 0.3\nhow are you?", "alice"))
+            .await
+            .unwrap();
+        assert_eq!(
+            reply.content,
+            "model=claude-haiku-4-5 temp=Some(0.3) tools=0"
+        );
+
+        // Other senders are unaffected
+        let reply = agent.handle_message(send("hi", "bob")).await.unwrap();
+        assert_eq!(reply.content, "model=claude-sonnet-4-5 temp=None tools=1");
+
+        let reply = agent.handle_message(send("!reset", "alice")).await.unwrap();
+        assert_eq!(reply.content, "Conversation settings: defaults");
+        let reply = agent.handle_message(send("hi", "alice")).await.unwrap();
+        assert_eq!(reply.content, "model=claude-sonnet-4-5 temp=None tools=1");
+
+        let reply = agent
+            .handle_message(send("!temp hot", "alice"))
+            .await
+            .unwrap();
+        assert!(reply.content.starts_with("Usage: !temp"));
+    }
+
+    #[tokio::test]
+    async fn test_channel_models_and_usage_by_channel() {
+        let temp_dir = TempDir::new().unwrap();
+        let db = Arc::new(KnowledgeDb::new(temp_dir.path().join("test.db")).unwrap());
+        let tracker = Arc::new(UsageTracker::new(
+            db.clone(),
+            crate::usage::UsageConfig::default(),
+        ));
+        let agent = Agent::new(
+            ApiClient::from_router(crate::providers::ModelRouter::single(Box::new(
+                OverrideEchoProvider,
+            ))),
+            Arc::new(ToolRegistry::new()),
+            "soul".to_string(),
+            String::new(),
+            db.clone(),
+        )
+        .with_intent_config(IntentConfig {
+            enabled: false,
+            min_length: 10,
+        })
+        .with_usage_tracker(tracker.clone())
+        .with_channel_models(HashMap::from([(
+            "slack".to_string(),
+            "claude-haiku-4-5".to_string(),
+        )]));
+        let send = |content: &str, channel: ChannelType| IncomingMessage {
+            id: uuid::Uuid::new_v4().to_string(),
+            sender: "alice".to_string(),
+            content: content.to_string(),
+            channel,
+            timestamp: Utc::now(),
+        };
+
+        let reply = agent
+            .handle_message(send("hi", ChannelType::Slack))
+            .await
+            .unwrap();
+        assert!(reply.content.starts_with("model=claude-haiku-4-5 "));
+        let reply = agent
+            .handle_message(send("hi", ChannelType::Discord))
+            .await
+            .unwrap();
+        assert!(reply.content.starts_with("model=claude-sonnet-4-5 "));
+
+        // A conversation's !model still wins over the channel's
+        agent
+            .handle_message(send("!model opus", ChannelType::Slack))
+            .await
+            .unwrap();
+        let reply = agent
+            .handle_message(send("hi", ChannelType::Slack))
+            .await
+            .unwrap();
+        assert!(reply.content.starts_with("model=claude-opus-4-1 "));
+
+        let summary = tracker.get_daily_summary().await.unwrap();
+        let mut pairs: Vec<&str> = summary
+            .by_channel_model
+            .keys()
+            .map(String::as_str)
+            .collect();
+        pairs.sort();
+        assert_eq!(
+            pairs,
+            [
+                "discord/claude-sonnet-4-5",
+                "slack/claude-haiku-4-5",
+                "slack/claude-opus-4-1"
+            ]
+        );
+    }
 }
@@ -2,10 +2,13 @@
 //!
 //! Routes incoming messages to isolated agents based on channel type,
 //! sender, and routing rules. Each agent has its own model, system prompt,
-//! workspace, and tool set.
+//! workspace, and tool set. Restricted profiles constrain who they route
+//! (e.g. a kid's contact) with tool, content and daily usage limits.
 
 pub mod manager;
 pub mod profile;
+pub mod restricted;
 
 pub use manager::AgentManager;
 pub use profile::{AgentProfile, ChannelRoute, RouteFilter};
+pub use restricted::{ProfileRestrictions, RestrictedToolExecutor};
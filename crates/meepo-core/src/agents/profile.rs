@@ -3,6 +3,7 @@
 use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
 
+use super::restricted::{PERSONAL_KNOWLEDGE_TOOLS, ProfileRestrictions};
 use crate::types::ChannelType;
 
 /// An agent profile defines a distinct agent persona with its own
@@ -27,6 +28,9 @@ pub struct AgentProfile {
     pub channels: Vec<ChannelRoute>,
     #[serde(default)]
     pub max_tokens: Option<u32>,
+    /// Makes this a restricted profile (content filter, daily cap, no personal knowledge)
+    #[serde(default)]
+    pub restrictions: Option<ProfileRestrictions>,
 }
 
 impl AgentProfile {
@@ -42,16 +46,33 @@ impl AgentProfile {
             denied_tools: Vec::new(),
             channels: Vec::new(),
             max_tokens: None,
+            restrictions: None,
         }
     }
 
+    /// Whether this is a restricted profile
+    pub fn is_restricted(&self) -> bool {
+        self.restrictions.is_some()
+    }
+
+    /// Whether this profile is kept away from the owner's knowledge base
+    pub fn blocks_personal_knowledge(&self) -> bool {
+        self.restrictions
+            .as_ref()
+            .is_some_and(|r| !r.personal_knowledge)
+    }
+
     /// Check if a tool is allowed for this agent
     pub fn is_tool_allowed(&self, tool_name: &str) -> bool {
         if self.denied_tools.contains(&tool_name.to_string()) {
             return false;
         }
+        if self.blocks_personal_knowledge() && PERSONAL_KNOWLEDGE_TOOLS.contains(&tool_name) {
+            return false;
+        }
         if self.tools.is_empty() {
-            return true; // empty allowlist = all tools allowed
+            // empty allowlist = all tools allowed, or none for restricted profiles
+            return !self.is_restricted();
         }
         self.tools.contains(&tool_name.to_string())
     }
@@ -160,6 +181,14 @@ mod tests {
         assert!(!profile.is_tool_allowed("run_command"));
     }
 
+    #[test]
+    fn test_restricted_empty_allowlist_allows_nothing() {
+        let mut profile = AgentProfile::new("kid", "Kid");
+        profile.restrictions = Some(ProfileRestrictions::default());
+        assert!(profile.is_restricted());
+        assert!(!profile.is_tool_allowed("web_search"));
+    }
+
     #[test]
     fn test_matches_route_no_channels() {
         let profile = AgentProfile::new("test", "Test");
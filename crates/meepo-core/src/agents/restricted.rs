@@ -0,0 +1,265 @@
+//! Restricted profiles — constrained agents for shared households
+//!
+//! A restricted profile (e.g. routed from a kid's contact) only gets the
+//! tools it allowlists, has incoming and outgoing messages checked against a
+//! content filter, can't see the owner's personal knowledge unless allowed,
+//! and stops answering once its daily message cap is used up.
+
+use std::sync::Arc;
+
+use anyhow::{Result, anyhow};
+use async_trait::async_trait;
+use meepo_knowledge::KnowledgeDb;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tracing::{debug, warn};
+
+use super::profile::AgentProfile;
+use crate::api::ToolDefinition;
+use crate::tools::ToolExecutor;
+
+/// Preference category holding daily usage counters
+pub const CATEGORY: &str = "profile_usage";
+
+/// Tools that read or change the owner's knowledge base, for checks that
+/// only have a tool name; registered tools also flag themselves through
+/// `ToolHandler::reads_personal_knowledge`
+pub const PERSONAL_KNOWLEDGE_TOOLS: &[&str] = &[
+    "remember",
+    "remember_many",
+    "manage_aliases",
+    "recall",
+    "smart_recall",
+    "search_knowledge",
+    "link_entities",
+    "ingest_document",
+    "list_knowledge_gaps",
+    "list_tags",
+    "forget",
+    "explain_recall",
+    "generate_dossier",
+];
+
+/// Limits applied to a restricted profile
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ProfileRestrictions {
+    /// Words and phrases that block a message or reply (case-insensitive)
+    #[serde(default)]
+    pub blocked_terms: Vec<String>,
+    /// Allow the owner's knowledge base, memory and conversation history
+    #[serde(default)]
+    pub personal_knowledge: bool,
+    /// Messages answered per day; further messages get a polite refusal
+    #[serde(default)]
+    pub daily_message_limit: Option<u32>,
+}
+
+impl ProfileRestrictions {
+    /// The first blocked term `text` contains, if any
+    pub fn blocked_term(&self, text: &str) -> Option<&str> {
+        let text = text.to_lowercase();
+        self.blocked_terms
+            .iter()
+            .map(|t| t.trim())
+            .find(|t| !t.is_empty() && text.contains(&t.to_lowercase()))
+    }
+}
+
+/// Count one message against the profile's daily cap. Returns false (and
+/// doesn't count it) once the cap is reached.
+pub async fn take_daily_message(db: &KnowledgeDb, profile: &AgentProfile) -> Result<bool> {
+    let Some(limit) = profile
+        .restrictions
+        .as_ref()
+        .and_then(|r| r.daily_message_limit)
+    else {
+        return Ok(true);
+    };
+    let key = format!("profile_usage:{}", profile.id);
+    let today = crate::timezone::now().date_naive().to_string();
+    let used = db
+        .get_preferences(Some(CATEGORY))
+        .await?
+        .into_iter()
+        .find(|p| p.key == key)
+        .filter(|p| p.value.get("date").and_then(|d| d.as_str()) == Some(today.as_str()))
+        .and_then(|p| p.value.get("count").and_then(|c| c.as_u64()))
+        .unwrap_or(0);
+    if used >= limit as u64 {
+        return Ok(false);
+    }
+    db.upsert_preference(
+        CATEGORY,
+        &key,
+        serde_json::json!({ "date": today, "count": used + 1 }),
+        1.0,
+        Some("profile"),
+    )
+    .await?;
+    Ok(true)
+}
+
+/// Context section telling the agent who it's talking to and its limits
+pub fn context_section(profile: &AgentProfile) -> String {
+    let mut section = format!(
+        "\n\n## Restricted Profile\n\nYou are talking with {} through a restricted profile, \
+         not with your owner. Keep replies friendly and age-appropriate, use only the tools \
+         you're given, and don't share anything about your owner.",
+        profile.name
+    );
+    if profile
+        .restrictions
+        .as_ref()
+        .is_some_and(|r| !r.blocked_terms.is_empty())
+    {
+        section.push_str(" Some topics are off limits; if asked about them, gently decline.");
+    }
+    section.push('\n');
+    section
+}
+
+/// Whether the profile may use a tool, also hiding any tool `tools` flags
+/// as reading personal knowledge when the profile isn't allowed it
+pub fn tool_allowed(profile: &AgentProfile, tools: &dyn ToolExecutor, tool_name: &str) -> bool {
+    profile.is_tool_allowed(tool_name)
+        && !(profile.blocks_personal_knowledge() && tools.reads_personal_knowledge(tool_name))
+}
+
+/// Tool executor that only runs tools the profile allows
+pub struct RestrictedToolExecutor {
+    inner: Arc<dyn ToolExecutor>,
+    profile: AgentProfile,
+}
+
+impl RestrictedToolExecutor {
+    pub fn new(inner: Arc<dyn ToolExecutor>, profile: AgentProfile) -> Self {
+        Self { inner, profile }
+    }
+}
+
+#[async_trait]
+impl ToolExecutor for RestrictedToolExecutor {
+    async fn execute(&self, tool_name: &str, input: Value) -> Result<String> {
+        if !tool_allowed(&self.profile, self.inner.as_ref(), tool_name) {
+            warn!(
+                "Blocked '{}' for restricted profile '{}'",
+                tool_name, self.profile.id
+            );
+            return Err(anyhow!(
+                "Tool '{}' is not available to this profile",
+                tool_name
+            ));
+        }
+        debug!(
+            "Restricted profile '{}' executing tool: {}",
+            self.profile.id, tool_name
+        );
+        self.inner.execute(tool_name, input).await
+    }
+
+    fn list_tools(&self) -> Vec<ToolDefinition> {
+        self.inner
+            .list_tools()
+            .into_iter()
+            .filter(|t| tool_allowed(&self.profile, self.inner.as_ref(), &t.name))
+            .collect()
+    }
+
+    fn reads_personal_knowledge(&self, tool_name: &str) -> bool {
+        self.inner.reads_personal_knowledge(tool_name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tools::ToolRegistry;
+
+    fn kid_profile(limit: Option<u32>) -> AgentProfile {
+        let mut profile = AgentProfile::new("kid", "Sam");
+        profile.tools = vec!["web_search".to_string(), "search_knowledge".to_string()];
+        profile.restrictions = Some(ProfileRestrictions {
+            blocked_terms: vec!["Casino".to_string()],
+            personal_knowledge: false,
+            daily_message_limit: limit,
+        });
+        profile
+    }
+
+    #[tokio::test]
+    async fn test_restricted_profile_limits() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let db = KnowledgeDb::new(temp.path().join("test.db")).unwrap();
+        let profile = kid_profile(Some(2));
+
+        assert!(take_daily_message(&db, &profile).await.unwrap());
+        assert!(take_daily_message(&db, &profile).await.unwrap());
+        assert!(!take_daily_message(&db, &profile).await.unwrap());
+        assert!(take_daily_message(&db, &kid_profile(None)).await.unwrap());
+
+        let restrictions = profile.restrictions.as_ref().unwrap();
+        assert_eq!(
+            restrictions.blocked_term("best online casinos?"),
+            Some("Casino")
+        );
+        assert!(restrictions.blocked_term("help with homework").is_none());
+
+        assert!(profile.is_tool_allowed("web_search"));
+        assert!(!profile.is_tool_allowed("search_knowledge"));
+        assert!(!profile.is_tool_allowed("send_email"));
+
+        let executor = RestrictedToolExecutor::new(Arc::new(ToolRegistry::new()), profile);
+        let err = executor
+            .execute("send_email", serde_json::json!({}))
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("not available"));
+    }
+
+    struct Notes;
+
+    #[async_trait]
+    impl crate::tools::ToolHandler for Notes {
+        fn name(&self) -> &str {
+            "household_notes"
+        }
+
+        fn description(&self) -> &str {
+            "Notes"
+        }
+
+        fn input_schema(&self) -> Value {
+            serde_json::json!({"type": "object"})
+        }
+
+        fn reads_personal_knowledge(&self) -> bool {
+            true
+        }
+
+        async fn execute(&self, _input: Value) -> Result<String> {
+            Ok("secret".to_string())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_flagged_tools_hidden_without_personal_knowledge() {
+        let mut registry = ToolRegistry::new();
+        registry.register(Arc::new(Notes));
+        let registry: Arc<dyn ToolExecutor> = Arc::new(registry);
+        let mut profile = kid_profile(None);
+        profile.tools.push("household_notes".to_string());
+
+        let executor = RestrictedToolExecutor::new(registry.clone(), profile.clone());
+        assert!(executor.list_tools().is_empty());
+        assert!(
+            executor
+                .execute("household_notes", serde_json::json!({}))
+                .await
+                .is_err()
+        );
+
+        profile.restrictions.as_mut().unwrap().personal_knowledge = true;
+        let executor = RestrictedToolExecutor::new(registry, profile);
+        assert_eq!(executor.list_tools().len(), 1);
+    }
+}
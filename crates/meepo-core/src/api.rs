@@ -8,21 +8,24 @@ use anyhow::{Result, anyhow};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use std::sync::Arc;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 use tracing::{debug, info, warn};
 
+use crate::loop_guard::{self, CallVerdict, LoopBreak, LoopGuard, LoopGuardConfig};
 use crate::providers::anthropic::AnthropicProvider;
 use crate::providers::router::ModelRouter;
 use crate::providers::types::{
     ChatBlock, ChatMessage, ChatMessageContent, ChatResponseBlock, ChatRole, StopReason,
 };
 use crate::tools::ToolExecutor;
+use crate::trace::{ToolTrace, TraceHop, thinking_summary};
 use crate::usage::AccumulatedUsage;
 
 /// LLM API client — delegates to [`ModelRouter`] for multi-provider support
 #[derive(Clone)]
 pub struct ApiClient {
     router: Arc<ModelRouter>,
+    loop_guard: LoopGuardConfig,
 }
 
 impl std::fmt::Debug for ApiClient {
@@ -48,6 +51,7 @@ impl ApiClient {
         );
         Self {
             router: Arc::new(ModelRouter::single(Box::new(provider))),
+            loop_guard: LoopGuardConfig::default(),
         }
     }
 
@@ -55,9 +59,16 @@ impl ApiClient {
     pub fn from_router(router: ModelRouter) -> Self {
         Self {
             router: Arc::new(router),
+            loop_guard: LoopGuardConfig::default(),
         }
     }
 
+    /// Set the limits that stop runaway tool loops
+    pub fn with_loop_guard(mut self, loop_guard: LoopGuardConfig) -> Self {
+        self.loop_guard = loop_guard;
+        self
+    }
+
     /// Set max tokens for responses (only works with single-provider backward-compat constructor)
     pub fn with_max_tokens(self, max_tokens: u32) -> Self {
         // For backward compatibility: rebuild the Anthropic provider with new max_tokens.
@@ -98,12 +109,28 @@ impl ApiClient {
         tools: &[ToolDefinition],
         tool_executor: &dyn ToolExecutor,
     ) -> Result<(String, AccumulatedUsage)> {
-        tokio::time::timeout(
+        let (text, usage, _) = self
+            .run_tool_loop_traced(initial_message, system, tools, tool_executor)
+            .await?;
+        Ok((text, usage))
+    }
+
+    /// Run the tool use loop, also returning a trace hop for every LLM call
+    pub async fn run_tool_loop_traced(
+        &self,
+        initial_message: &str,
+        system: &str,
+        tools: &[ToolDefinition],
+        tool_executor: &dyn ToolExecutor,
+    ) -> Result<(String, AccumulatedUsage, Vec<TraceHop>)> {
+        let mut hops = Vec::new();
+        let (text, usage) = tokio::time::timeout(
             Duration::from_secs(300),
-            self.run_tool_loop_inner(initial_message, system, tools, tool_executor),
+            self.run_tool_loop_inner(initial_message, system, tools, tool_executor, &mut hops),
         )
         .await
-        .map_err(|_| anyhow!("Tool loop timed out after 5 minutes"))?
+        .map_err(|_| anyhow!("Tool loop timed out after 5 minutes"))??;
+        Ok((text, usage, hops))
     }
 
     async fn run_tool_loop_inner(
@@ -112,6 +139,7 @@ impl ApiClient {
         system: &str,
         tools: &[ToolDefinition],
         tool_executor: &dyn ToolExecutor,
+        hops: &mut Vec<TraceHop>,
     ) -> Result<(String, AccumulatedUsage)> {
         const MAX_TOOL_OUTPUT: usize = 100_000;
 
@@ -122,35 +150,53 @@ impl ApiClient {
             content: ChatMessageContent::Text(initial_message.to_string()),
         }];
 
-        let mut iterations = 0;
-        const MAX_ITERATIONS: usize = 10;
+        let mut guard = LoopGuard::new(self.loop_guard.clone());
 
         loop {
-            iterations += 1;
-            if iterations > MAX_ITERATIONS {
-                warn!("Tool loop exceeded maximum iterations ({})", MAX_ITERATIONS);
-                return Err(anyhow!("Tool loop exceeded maximum iterations"));
-            }
+            let iterations = match guard.next_iteration() {
+                Ok(iterations) => iterations,
+                Err(reason) => {
+                    return self
+                        .stop_tool_loop(reason, conversation, system, tools, accumulated, hops)
+                        .await;
+                }
+            };
 
             info!("Tool loop iteration {}", iterations);
 
-            let response = self.router.chat(&conversation, tools, system).await?;
+            let started = Instant::now();
+            let response =
+                crate::streaming::live(self.router.chat(&conversation, tools, system)).await?;
+            let thinking: Vec<&str> = response
+                .blocks
+                .iter()
+                .filter_map(|b| match b {
+                    ChatResponseBlock::Thinking { thinking, .. } => Some(thinking.as_str()),
+                    ChatResponseBlock::RedactedThinking { .. } => Some("[redacted thinking]"),
+                    _ => None,
+                })
+                .collect();
+            hops.push(TraceHop {
+                stage: "tool_loop".to_string(),
+                input_tokens: response.usage.input_tokens,
+                output_tokens: response.usage.output_tokens,
+                duration_ms: started.elapsed().as_millis() as u64,
+                stop_reason: serde_json::to_value(response.stop_reason)
+                    .ok()
+                    .and_then(|v| v.as_str().map(String::from)),
+                tools: Vec::new(),
+                thinking: thinking_summary(&thinking.join("\n")),
+            });
 
             // Accumulate token usage from this API call
             accumulated.add(response.usage.input_tokens, response.usage.output_tokens);
 
-            // Build assistant message from response blocks
+            // Build assistant message from response blocks; thinking blocks
+            // must go back with their signatures while tools are in play
             let assistant_blocks: Vec<ChatBlock> = response
                 .blocks
                 .iter()
-                .map(|b| match b {
-                    ChatResponseBlock::Text { text } => ChatBlock::Text { text: text.clone() },
-                    ChatResponseBlock::ToolCall { id, name, input } => ChatBlock::ToolCall {
-                        id: id.clone(),
-                        name: name.clone(),
-                        input: input.clone(),
-                    },
-                })
+                .map(ChatResponseBlock::to_chat_block)
                 .collect();
 
             conversation.push(ChatMessage {
@@ -162,21 +208,52 @@ impl ApiClient {
                 debug!("Processing tool calls from response");
 
                 let mut tool_results = Vec::new();
+                let mut stopped: Option<LoopBreak> = None;
 
                 for block in &response.blocks {
                     if let ChatResponseBlock::ToolCall { id, name, input } = block {
+                        // Every call needs a result, even once the loop is stopped
+                        let skipped = match &stopped {
+                            Some(_) => Some("Not run: the tool loop was stopped".to_string()),
+                            None => match guard.check_call(name, input) {
+                                CallVerdict::Run => None,
+                                CallVerdict::Repeat(note) => {
+                                    debug!("Skipping repeated call to {}", name);
+                                    Some(note)
+                                }
+                                CallVerdict::Stop(reason) => {
+                                    stopped = Some(reason);
+                                    Some("Not run: the tool loop was stopped".to_string())
+                                }
+                            },
+                        };
+                        if let Some(note) = skipped {
+                            tool_results.push(ChatBlock::ToolResult {
+                                tool_call_id: id.clone(),
+                                content: note,
+                            });
+                            continue;
+                        }
+
                         info!("Executing tool: {}", name);
 
                         accumulated.record_tool_call(name);
 
-                        let result = tool_executor.execute(name, input.clone()).await;
+                        let started = Instant::now();
+                        let result =
+                            crate::streaming::muted(tool_executor.execute(name, input.clone()))
+                                .await
+                                .map_err(|e| {
+                                    warn!("Tool {} failed: {}", name, e);
+                                    format!("Error: {}", e)
+                                });
+                        if let Some(hop) = hops.last_mut() {
+                            hop.tools
+                                .push(ToolTrace::new(name, input, started.elapsed(), &result));
+                        }
 
                         let mut result_content = match result {
-                            Ok(output) => output,
-                            Err(e) => {
-                                warn!("Tool {} failed: {}", name, e);
-                                format!("Error: {}", e)
-                            }
+                            Ok(output) | Err(output) => output,
                         };
 
                         if result_content.len() > MAX_TOOL_OUTPUT {
@@ -200,6 +277,12 @@ impl ApiClient {
                     role: ChatRole::User,
                     content: ChatMessageContent::Blocks(tool_results),
                 });
+
+                if let Some(reason) = stopped {
+                    return self
+                        .stop_tool_loop(reason, conversation, system, tools, accumulated, hops)
+                        .await;
+                }
             } else if response.stop_reason.is_end_turn()
                 || response.stop_reason == StopReason::Unknown
                 || response.stop_reason == StopReason::MaxTokens
@@ -249,6 +332,70 @@ impl ApiClient {
         }
     }
 
+    /// End a tool loop the guard stopped, asking the model to summarize
+    /// its partial progress instead of failing the turn
+    async fn stop_tool_loop(
+        &self,
+        reason: LoopBreak,
+        mut conversation: Vec<ChatMessage>,
+        system: &str,
+        tools: &[ToolDefinition],
+        mut accumulated: AccumulatedUsage,
+        hops: &mut Vec<TraceHop>,
+    ) -> Result<(String, AccumulatedUsage)> {
+        warn!("Stopped tool loop: the model {}", reason);
+        let note = ChatBlock::Text {
+            text: loop_guard::summary_request(&reason),
+        };
+        // The last message holds the tool results; the note goes with them
+        match conversation.last_mut() {
+            Some(ChatMessage {
+                role: ChatRole::User,
+                content: ChatMessageContent::Blocks(blocks),
+            }) => blocks.push(note),
+            _ => conversation.push(ChatMessage {
+                role: ChatRole::User,
+                content: ChatMessageContent::Blocks(vec![note]),
+            }),
+        }
+
+        // Tools stay defined because the history has tool calls in it
+        let started = Instant::now();
+        let response = match self.router.chat(&conversation, tools, system).await {
+            Ok(response) => response,
+            Err(e) => {
+                warn!("Failed to summarize stopped tool loop: {}", e);
+                return Ok((loop_guard::fallback_reply(&reason), accumulated));
+            }
+        };
+        hops.push(TraceHop {
+            stage: "loop_summary".to_string(),
+            input_tokens: response.usage.input_tokens,
+            output_tokens: response.usage.output_tokens,
+            duration_ms: started.elapsed().as_millis() as u64,
+            stop_reason: serde_json::to_value(response.stop_reason)
+                .ok()
+                .and_then(|v| v.as_str().map(String::from)),
+            tools: Vec::new(),
+            thinking: None,
+        });
+        accumulated.add(response.usage.input_tokens, response.usage.output_tokens);
+
+        let text: Vec<&str> = response
+            .blocks
+            .iter()
+            .filter_map(|b| match b {
+                ChatResponseBlock::Text { text } => Some(text.as_str()),
+                _ => None,
+            })
+            .collect();
+        let text = text.join("\n");
+        if text.trim().is_empty() {
+            return Ok((loop_guard::fallback_reply(&reason), accumulated));
+        }
+        Ok((text, accumulated))
+    }
+
     /// Get the model name (for usage tracking)
     pub fn model(&self) -> &str {
         self.router.model()
@@ -297,16 +444,19 @@ impl ApiClient {
     }
 
     fn from_chat_response(resp: crate::providers::types::ChatResponse) -> ApiResponse {
-        let content: Vec<ContentBlock> = resp
-            .blocks
-            .into_iter()
-            .map(|b| match b {
-                ChatResponseBlock::Text { text } => ContentBlock::Text { text },
-                ChatResponseBlock::ToolCall { id, name, input } => {
-                    ContentBlock::ToolUse { id, name, input }
-                }
-            })
-            .collect();
+        let content: Vec<ContentBlock> =
+            resp.blocks
+                .into_iter()
+                .filter_map(|b| match b {
+                    ChatResponseBlock::Text { text } => Some(ContentBlock::Text { text }),
+                    ChatResponseBlock::ToolCall { id, name, input } => {
+                        Some(ContentBlock::ToolUse { id, name, input })
+                    }
+                    // Single calls don't continue the turn, so thinking isn't needed
+                    ChatResponseBlock::Thinking { .. }
+                    | ChatResponseBlock::RedactedThinking { .. } => None,
+                })
+                .collect();
 
         let stop_reason = match resp.stop_reason {
             StopReason::EndTurn => Some("end_turn".to_string()),
@@ -520,4 +670,93 @@ mod tests {
             matches!(&result.content[0], ContentBlock::ToolUse { name, .. } if name == "search")
         );
     }
+
+    #[tokio::test]
+    async fn test_tool_loop_stops_repeated_calls() {
+        use crate::providers::router::ModelRouter;
+        use crate::providers::types::{ChatResponse, ChatUsage, LlmProvider};
+        use crate::tools::{ToolHandler, ToolRegistry};
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        /// Calls `recall` with the same query until asked to wrap up
+        struct Stuck;
+
+        #[async_trait::async_trait]
+        impl LlmProvider for Stuck {
+            fn provider_name(&self) -> &str {
+                "stuck"
+            }
+            fn model(&self) -> &str {
+                "stuck"
+            }
+            async fn chat(
+                &self,
+                messages: &[ChatMessage],
+                _tools: &[ToolDefinition],
+                _system: &str,
+            ) -> Result<ChatResponse> {
+                let wrap_up = matches!(
+                    &messages.last().unwrap().content,
+                    ChatMessageContent::Blocks(blocks)
+                        if blocks.iter().any(|b| matches!(b, ChatBlock::Text { .. }))
+                );
+                let usage = ChatUsage {
+                    input_tokens: 1,
+                    output_tokens: 1,
+                };
+                Ok(if wrap_up {
+                    ChatResponse {
+                        blocks: vec![ChatResponseBlock::Text {
+                            text: "Here's what I found so far".to_string(),
+                        }],
+                        stop_reason: StopReason::EndTurn,
+                        usage,
+                    }
+                } else {
+                    ChatResponse {
+                        blocks: vec![ChatResponseBlock::ToolCall {
+                            id: format!("tc_{}", messages.len()),
+                            name: "recall".to_string(),
+                            input: serde_json::json!({"query": "dentist"}),
+                        }],
+                        stop_reason: StopReason::ToolUse,
+                        usage,
+                    }
+                })
+            }
+        }
+
+        struct Recall(Arc<AtomicUsize>);
+
+        #[async_trait::async_trait]
+        impl ToolHandler for Recall {
+            fn name(&self) -> &str {
+                "recall"
+            }
+            fn description(&self) -> &str {
+                "Recall"
+            }
+            fn input_schema(&self) -> Value {
+                serde_json::json!({"type": "object"})
+            }
+            async fn execute(&self, _input: Value) -> Result<String> {
+                self.0.fetch_add(1, Ordering::SeqCst);
+                Ok("nothing".to_string())
+            }
+        }
+
+        let runs = Arc::new(AtomicUsize::new(0));
+        let mut registry = ToolRegistry::new();
+        registry.register(Arc::new(Recall(runs.clone())));
+        let client = ApiClient::from_router(ModelRouter::single(Box::new(Stuck)));
+        let (text, _, hops) = client
+            .run_tool_loop_traced("when is my dentist?", "", &[], &registry)
+            .await
+            .unwrap();
+        assert_eq!(text, "Here's what I found so far");
+        // The repeat isn't run again, the third attempt stops the loop
+        assert_eq!(runs.load(Ordering::SeqCst), 1);
+        assert_eq!(hops.len(), 4);
+        assert_eq!(hops[3].stage, "loop_summary");
+    }
 }
@@ -0,0 +1,229 @@
+//! App-usage tracking
+//!
+//! Opt-in self-quantification: the frontmost app is sampled periodically and
+//! the time is added to a per-day, per-app total in the knowledge DB, so the
+//! agent can answer "how much time did I spend in Xcode this week?" and the
+//! evening digest can include screen time. Samples are skipped while the
+//! screen is locked or the user has been away from the keyboard.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::{Result, anyhow};
+use chrono::{Datelike, NaiveDate};
+use meepo_knowledge::{AppUsage, KnowledgeDb};
+use tokio_util::sync::CancellationToken;
+use tracing::{debug, info, warn};
+
+use crate::platform::ActiveAppProvider;
+
+/// Apps that mean nobody is really using the machine
+const ALWAYS_EXCLUDED: &[&str] = &["loginwindow", "ScreenSaverEngine", "LockApp"];
+
+/// Configuration for app-usage tracking
+#[derive(Debug, Clone)]
+pub struct AppUsageConfig {
+    pub enabled: bool,
+    /// How often to sample the frontmost app; each sample counts this long
+    pub sample_secs: u64,
+    /// Samples taken after this long without keyboard/mouse input are dropped
+    pub idle_secs: u64,
+    /// Apps never recorded (case-insensitive)
+    pub exclude: Vec<String>,
+}
+
+impl Default for AppUsageConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            sample_secs: 60,
+            idle_secs: 300,
+            exclude: Vec::new(),
+        }
+    }
+}
+
+impl AppUsageConfig {
+    fn is_excluded(&self, app: &str) -> bool {
+        ALWAYS_EXCLUDED
+            .iter()
+            .copied()
+            .chain(self.exclude.iter().map(String::as_str))
+            .any(|e| e.eq_ignore_ascii_case(app))
+    }
+}
+
+/// Samples the frontmost app into the knowledge DB
+pub struct AppUsageTracker {
+    db: Arc<KnowledgeDb>,
+    provider: Box<dyn ActiveAppProvider>,
+    config: AppUsageConfig,
+}
+
+impl AppUsageTracker {
+    pub fn new(
+        db: Arc<KnowledgeDb>,
+        provider: Box<dyn ActiveAppProvider>,
+        config: AppUsageConfig,
+    ) -> Self {
+        Self {
+            db,
+            provider,
+            config,
+        }
+    }
+
+    /// Take one sample, returning the app credited (if any)
+    pub async fn sample(&self) -> Result<Option<String>> {
+        let activity = crate::autonomy::idle::probe().await;
+        if activity.screen_locked == Some(true)
+            || activity
+                .idle_secs
+                .is_some_and(|secs| secs >= self.config.idle_secs)
+        {
+            debug!("Skipping app-usage sample ({})", activity.describe());
+            return Ok(None);
+        }
+        let Some(app) = self.provider.frontmost_app().await? else {
+            return Ok(None);
+        };
+        if self.config.is_excluded(&app) {
+            return Ok(None);
+        }
+        let today = crate::timezone::now().date_naive().to_string();
+        self.db
+            .add_app_usage(&today, &app, self.config.sample_secs)
+            .await?;
+        Ok(Some(app))
+    }
+
+    /// Sample until cancelled
+    pub async fn run(self, cancel: CancellationToken) {
+        if !self.config.enabled {
+            return;
+        }
+        let interval = Duration::from_secs(self.config.sample_secs.max(10));
+        info!("App-usage tracking started (every {}s)", interval.as_secs());
+        let mut warned = false;
+        loop {
+            match self.sample().await {
+                Ok(_) => warned = false,
+                Err(e) if !warned => {
+                    warn!("Failed to sample frontmost app: {}", e);
+                    warned = true;
+                }
+                Err(e) => debug!("Failed to sample frontmost app: {}", e),
+            }
+            tokio::select! {
+                _ = cancel.cancelled() => break,
+                _ = tokio::time::sleep(interval) => {}
+            }
+        }
+        info!("App-usage tracking shutting down");
+    }
+}
+
+/// Resolve a period ("today", "yesterday", "week", "last_week", "month" or
+/// "YYYY-MM-DD:YYYY-MM-DD") to an inclusive date range. Weeks start Monday.
+pub fn period_range(period: &str, today: NaiveDate) -> Result<(NaiveDate, NaiveDate)> {
+    let week_start = today - chrono::Duration::days(today.weekday().num_days_from_monday() as i64);
+    match period.trim().to_lowercase().as_str() {
+        "today" => Ok((today, today)),
+        "yesterday" => {
+            let day = today - chrono::Duration::days(1);
+            Ok((day, day))
+        }
+        "week" | "this_week" => Ok((week_start, today)),
+        "last_week" => Ok((
+            week_start - chrono::Duration::days(7),
+            week_start - chrono::Duration::days(1),
+        )),
+        "month" | "this_month" => Ok((today.with_day(1).unwrap_or(today), today)),
+        other => {
+            let (start, end) = other.split_once(':').ok_or_else(|| {
+                anyhow!(
+                    "Invalid period '{}'. Use today, yesterday, week, last_week, month or YYYY-MM-DD:YYYY-MM-DD",
+                    period
+                )
+            })?;
+            let start = NaiveDate::parse_from_str(start.trim(), "%Y-%m-%d")?;
+            let end = NaiveDate::parse_from_str(end.trim(), "%Y-%m-%d")?;
+            if end < start {
+                return Err(anyhow!("Period ends before it starts"));
+            }
+            Ok((start, end))
+        }
+    }
+}
+
+/// "2h 05m", "45m" or "<1m"
+pub fn format_duration(seconds: u64) -> String {
+    let minutes = seconds / 60;
+    match (minutes / 60, minutes % 60) {
+        (0, 0) => "<1m".to_string(),
+        (0, m) => format!("{}m", m),
+        (h, m) => format!("{}h {:02}m", h, m),
+    }
+}
+
+/// Total time across apps
+pub fn total_seconds(usage: &[AppUsage]) -> u64 {
+    usage.iter().map(|u| u.seconds).sum()
+}
+
+/// Usage between two dates, optionally for apps whose name contains `app`
+pub async fn usage_between(
+    db: &KnowledgeDb,
+    start: NaiveDate,
+    end: NaiveDate,
+    app: Option<&str>,
+) -> Result<Vec<AppUsage>> {
+    let usage = db
+        .get_app_usage(&start.to_string(), &end.to_string())
+        .await?;
+    Ok(match app.map(str::to_lowercase) {
+        Some(filter) => usage
+            .into_iter()
+            .filter(|u| u.app.to_lowercase().contains(&filter))
+            .collect(),
+        None => usage,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_period_range_and_format() {
+        // Thursday
+        let today = NaiveDate::from_ymd_opt(2026, 3, 5).unwrap();
+        let d = |day| NaiveDate::from_ymd_opt(2026, 3, day).unwrap();
+        assert_eq!(period_range("today", today).unwrap(), (d(5), d(5)));
+        assert_eq!(period_range("yesterday", today).unwrap(), (d(4), d(4)));
+        assert_eq!(period_range("week", today).unwrap(), (d(2), d(5)));
+        assert_eq!(
+            period_range("last_week", today).unwrap(),
+            (NaiveDate::from_ymd_opt(2026, 2, 23).unwrap(), d(1))
+        );
+        assert_eq!(period_range("month", today).unwrap(), (d(1), d(5)));
+        assert_eq!(
+            period_range("2026-03-01:2026-03-03", today).unwrap(),
+            (d(1), d(3))
+        );
+        assert!(period_range("2026-03-03:2026-03-01", today).is_err());
+        assert!(period_range("fortnight", today).is_err());
+
+        assert_eq!(format_duration(30), "<1m");
+        assert_eq!(format_duration(45 * 60), "45m");
+        assert_eq!(format_duration(2 * 3600 + 5 * 60), "2h 05m");
+
+        let config = AppUsageConfig {
+            exclude: vec!["Slack".to_string()],
+            ..Default::default()
+        };
+        assert!(config.is_excluded("slack"));
+        assert!(config.is_excluded("loginwindow"));
+        assert!(!config.is_excluded("Xcode"));
+    }
+}
@@ -3,9 +3,11 @@
 //! Provides speech-to-text, text-to-speech, and voice activity detection
 //! for hands-free interaction with Meepo.
 
+pub mod read_aloud;
 pub mod stt;
 pub mod tts;
 pub mod vad;
+pub mod voice_notes;
 
 use serde::{Deserialize, Serialize};
 
@@ -23,6 +25,9 @@ pub struct AudioConfig {
     pub sample_rate: u32,
     pub silence_threshold: f32,
     pub silence_duration_ms: u64,
+    /// Spoken language hint (ISO 639-1) for STT and TTS; `None` = auto-detect
+    #[serde(default)]
+    pub language: Option<String>,
 }
 
 impl std::fmt::Debug for AudioConfig {
@@ -42,6 +47,7 @@ impl std::fmt::Debug for AudioConfig {
             .field("sample_rate", &self.sample_rate)
             .field("silence_threshold", &self.silence_threshold)
             .field("silence_duration_ms", &self.silence_duration_ms)
+            .field("language", &self.language)
             .finish()
     }
 }
@@ -60,6 +66,7 @@ impl Default for AudioConfig {
             sample_rate: 16000,
             silence_threshold: 0.01,
             silence_duration_ms: 1500,
+            language: None,
         }
     }
 }
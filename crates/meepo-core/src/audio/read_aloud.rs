@@ -0,0 +1,264 @@
+//! Read replies aloud on the Mac
+//!
+//! When the user is away from the keyboard, replies sent to a channel can
+//! also be spoken through the TTS pipeline on the local machine: during
+//! configured hours, or whenever a message is urgent. Nothing is spoken
+//! while a quiet Focus mode (Do Not Disturb) is on or the output is muted.
+
+use chrono::{DateTime, NaiveTime, Utc};
+use tokio::process::Command;
+use tokio::sync::Mutex;
+use tracing::{debug, info, warn};
+
+use anyhow::{Context, Result, anyhow};
+
+use super::tts::TextToSpeech;
+use crate::notifications::in_window;
+use crate::types::{ChannelType, MessageKind, OutgoingMessage};
+
+/// When and how replies are read aloud
+#[derive(Debug, Clone)]
+pub struct ReadAloudConfig {
+    /// Channels whose replies may be spoken
+    pub channels: Vec<ChannelType>,
+    /// Speak every reply in this window (user's timezone); may wrap midnight
+    pub hours: Option<(NaiveTime, NaiveTime)>,
+    pub timezone: chrono_tz::Tz,
+    /// Speak urgent messages at any time
+    pub urgent: bool,
+    /// Output volume (1-100) while speaking; `None` leaves it alone
+    pub volume: Option<u8>,
+    /// Longer replies are cut off here
+    pub max_chars: usize,
+}
+
+impl Default for ReadAloudConfig {
+    fn default() -> Self {
+        Self {
+            channels: Vec::new(),
+            hours: None,
+            timezone: chrono_tz::Tz::UTC,
+            urgent: true,
+            volume: None,
+            max_chars: 600,
+        }
+    }
+}
+
+impl ReadAloudConfig {
+    /// Whether `msg` should be spoken at `at` (ignoring Focus and volume)
+    pub fn wants(&self, msg: &OutgoingMessage, at: DateTime<Utc>) -> bool {
+        if matches!(msg.kind, MessageKind::Acknowledgment | MessageKind::Partial)
+            || msg.content.trim().is_empty()
+            || !self.channels.contains(&msg.channel)
+        {
+            return false;
+        }
+        if self.urgent && msg.kind == MessageKind::Urgent {
+            return true;
+        }
+        self.hours.is_some_and(|(start, end)| {
+            in_window(at.with_timezone(&self.timezone).time(), start, end)
+        })
+    }
+}
+
+/// Text worth speaking: markdown and links stripped, cut at a sentence
+fn spoken_text(content: &str, max_chars: usize) -> String {
+    let mut text = String::new();
+    for word in content.split_whitespace() {
+        if word.starts_with("http://") || word.starts_with("https://") {
+            continue;
+        }
+        let word = word.trim_matches(|c| matches!(c, '*' | '_' | '`' | '#' | '>' | '|'));
+        if word.is_empty() || word == "-" {
+            continue;
+        }
+        if !text.is_empty() {
+            text.push(' ');
+        }
+        text.push_str(word);
+    }
+    if text.chars().count() <= max_chars {
+        return text;
+    }
+    let cut: String = text.chars().take(max_chars).collect();
+    match cut.rfind(['.', '!', '?']) {
+        Some(end) if end > max_chars / 2 => cut[..=end].to_string(),
+        _ => format!("{}…", cut.trim_end()),
+    }
+}
+
+/// Output volume (0-100) and whether it's muted
+async fn volume_settings() -> Result<(u8, bool)> {
+    let output = Command::new("osascript")
+        .args([
+            "-e",
+            "set s to get volume settings",
+            "-e",
+            "return (output volume of s as text) & \",\" & (output muted of s as text)",
+        ])
+        .output()
+        .await
+        .context("Failed to read volume settings")?;
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let (volume, muted) = stdout
+        .trim()
+        .split_once(',')
+        .ok_or_else(|| anyhow!("Unexpected volume settings: {}", stdout.trim()))?;
+    Ok((volume.trim().parse().unwrap_or(0), muted.trim() == "true"))
+}
+
+async fn set_volume(volume: u8) -> Result<()> {
+    Command::new("osascript")
+        .args([
+            "-e",
+            &format!("set volume output volume {}", volume.min(100)),
+        ])
+        .status()
+        .await
+        .context("Failed to set volume")?;
+    Ok(())
+}
+
+/// Speaks replies through a TTS provider
+pub struct ReadAloud {
+    config: ReadAloudConfig,
+    tts: Box<dyn TextToSpeech>,
+    /// One reply at a time
+    speaking: Mutex<()>,
+}
+
+impl ReadAloud {
+    pub fn new(config: ReadAloudConfig, tts: Box<dyn TextToSpeech>) -> Self {
+        Self {
+            config,
+            tts,
+            speaking: Mutex::new(()),
+        }
+    }
+
+    /// Whether `msg` should be spoken now
+    pub fn should_speak(&self, msg: &OutgoingMessage) -> bool {
+        if !self.config.wants(msg, Utc::now()) {
+            return false;
+        }
+        if crate::focus::is_quiet() {
+            debug!("Not reading reply aloud (focus mode)");
+            return false;
+        }
+        true
+    }
+
+    /// Speak a reply, unless the output is muted
+    pub async fn speak(&self, content: &str) -> Result<()> {
+        let text = spoken_text(content, self.config.max_chars);
+        if text.is_empty() {
+            return Ok(());
+        }
+        let _speaking = self.speaking.lock().await;
+
+        let (volume, muted) = volume_settings().await?;
+        if muted || (volume == 0 && self.config.volume.is_none()) {
+            debug!("Not reading reply aloud (output muted)");
+            return Ok(());
+        }
+
+        let audio = self.tts.synthesize(&text).await?;
+        let extension = if self.tts.name() == "macos_say" {
+            "aiff"
+        } else {
+            "mp3"
+        };
+        let path = std::env::temp_dir().join(format!(
+            "meepo_read_aloud_{}.{}",
+            uuid::Uuid::new_v4(),
+            extension
+        ));
+        tokio::fs::write(&path, &audio).await?;
+
+        let restore = match self.config.volume {
+            Some(target) if target != volume => {
+                set_volume(target).await?;
+                Some(volume)
+            }
+            _ => None,
+        };
+        info!("Reading reply aloud ({} chars)", text.len());
+        let played = Command::new("afplay").arg(&path).status().await;
+        if let Some(volume) = restore
+            && let Err(e) = set_volume(volume).await
+        {
+            warn!("Failed to restore volume: {}", e);
+        }
+        let _ = tokio::fs::remove_file(&path).await;
+
+        let status = played.context("Failed to run afplay")?;
+        if !status.success() {
+            return Err(anyhow!("afplay exited with {}", status));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn reply(channel: ChannelType, kind: MessageKind) -> OutgoingMessage {
+        OutgoingMessage {
+            content: "Your flight is delayed.".to_string(),
+            channel,
+            reply_to: None,
+            kind,
+            interactive: None,
+        }
+    }
+
+    #[test]
+    fn test_wants_hours_and_urgent() {
+        let config = ReadAloudConfig {
+            channels: vec![ChannelType::IMessage],
+            hours: Some((
+                NaiveTime::from_hms_opt(18, 0, 0).unwrap(),
+                NaiveTime::from_hms_opt(23, 0, 0).unwrap(),
+            )),
+            ..Default::default()
+        };
+        let evening = Utc.with_ymd_and_hms(2026, 3, 2, 19, 30, 0).unwrap();
+        let morning = Utc.with_ymd_and_hms(2026, 3, 2, 9, 0, 0).unwrap();
+
+        assert!(config.wants(
+            &reply(ChannelType::IMessage, MessageKind::Response),
+            evening
+        ));
+        assert!(!config.wants(
+            &reply(ChannelType::IMessage, MessageKind::Response),
+            morning
+        ));
+        assert!(config.wants(&reply(ChannelType::IMessage, MessageKind::Urgent), morning));
+        assert!(!config.wants(&reply(ChannelType::Slack, MessageKind::Urgent), evening));
+        assert!(!config.wants(
+            &reply(ChannelType::IMessage, MessageKind::Acknowledgment),
+            evening
+        ));
+
+        let no_urgent = ReadAloudConfig {
+            urgent: false,
+            ..config
+        };
+        assert!(!no_urgent.wants(&reply(ChannelType::IMessage, MessageKind::Urgent), morning));
+    }
+
+    #[test]
+    fn test_spoken_text() {
+        assert_eq!(
+            spoken_text("**Done.** See https://example.com for `details`", 100),
+            "Done. See for details"
+        );
+        let long = "First sentence here. Second sentence that goes on and on.";
+        assert_eq!(spoken_text(long, 30), "First sentence here.");
+        assert_eq!(spoken_text("abcdefghij klm", 8), "abcdefgh…");
+    }
+}
@@ -10,6 +10,14 @@ use super::{AudioChunk, AudioConfig, SttProvider, Transcription, encode_wav};
 #[async_trait]
 pub trait SpeechToText: Send + Sync {
     async fn transcribe(&self, audio: &AudioChunk) -> Result<Transcription>;
+
+    /// Transcribe an encoded audio file (m4a, mp3, ogg, wav, ...), e.g. a
+    /// voice note; the extension of `file_name` gives the format
+    async fn transcribe_file(&self, data: Vec<u8>, file_name: &str) -> Result<Transcription> {
+        let _ = (data, file_name);
+        Err(anyhow!("{} can't transcribe audio files", self.name()))
+    }
+
     fn name(&self) -> &str;
 }
 
@@ -22,7 +30,9 @@ pub fn create_stt(config: &AudioConfig) -> Result<Box<dyn SpeechToText>> {
             } else {
                 return Err(anyhow!("OpenAI API key required for Whisper API STT"));
             };
-            Ok(Box::new(WhisperApiStt::new(api_key)))
+            Ok(Box::new(
+                WhisperApiStt::new(api_key).with_language(config.language.clone()),
+            ))
         }
         SttProvider::WhisperLocal => Ok(Box::new(WhisperLocalStt::new())),
     }
@@ -33,6 +43,7 @@ pub struct WhisperApiStt {
     client: reqwest::Client,
     api_key: String,
     base_url: String,
+    language: Option<String>,
 }
 
 impl WhisperApiStt {
@@ -41,6 +52,7 @@ impl WhisperApiStt {
             client: reqwest::Client::new(),
             api_key,
             base_url: "https://api.openai.com".to_string(),
+            language: None,
         }
     }
 
@@ -48,28 +60,33 @@ impl WhisperApiStt {
         self.base_url = url;
         self
     }
-}
-
-#[async_trait]
-impl SpeechToText for WhisperApiStt {
-    async fn transcribe(&self, audio: &AudioChunk) -> Result<Transcription> {
-        let wav_bytes = encode_wav(&audio.samples, audio.sample_rate);
-        let duration_ms = audio.duration_ms();
 
-        debug!(
-            "Whisper API: transcribing {} ms of audio ({} bytes WAV)",
-            duration_ms,
-            wav_bytes.len()
-        );
-
-        let part = reqwest::multipart::Part::bytes(wav_bytes)
-            .file_name("audio.wav")
-            .mime_str("audio/wav")?;
+    /// Hint the spoken language (ISO 639-1), improving accuracy and latency
+    pub fn with_language(mut self, language: Option<String>) -> Self {
+        self.language = language
+            .map(|l| crate::i18n::normalize(&l))
+            .filter(|l| !l.is_empty());
+        self
+    }
+}
 
-        let form = reqwest::multipart::Form::new()
+impl WhisperApiStt {
+    /// Send one file to the transcription endpoint
+    async fn request(&self, data: Vec<u8>, file_name: &str, mime: &str) -> Result<Transcription> {
+        crate::network::ensure_online(
+            "Whisper API transcription (use stt_provider = \"whisper_local\")",
+        )?;
+        let part = reqwest::multipart::Part::bytes(data)
+            .file_name(file_name.to_string())
+            .mime_str(mime)?;
+
+        let mut form = reqwest::multipart::Form::new()
             .part("file", part)
             .text("model", "whisper-1")
             .text("response_format", "json");
+        if let Some(language) = &self.language {
+            form = form.text("language", language.clone());
+        }
 
         let url = format!("{}/v1/audio/transcriptions", self.base_url);
 
@@ -102,15 +119,64 @@ impl SpeechToText for WhisperApiStt {
         Ok(Transcription {
             text,
             language,
+            duration_ms: 0,
+        })
+    }
+}
+
+#[async_trait]
+impl SpeechToText for WhisperApiStt {
+    async fn transcribe(&self, audio: &AudioChunk) -> Result<Transcription> {
+        let wav_bytes = encode_wav(&audio.samples, audio.sample_rate);
+        let duration_ms = audio.duration_ms();
+
+        debug!(
+            "Whisper API: transcribing {} ms of audio ({} bytes WAV)",
             duration_ms,
+            wav_bytes.len()
+        );
+
+        let transcription = self.request(wav_bytes, "audio.wav", "audio/wav").await?;
+        Ok(Transcription {
+            duration_ms,
+            ..transcription
         })
     }
 
+    async fn transcribe_file(&self, data: Vec<u8>, file_name: &str) -> Result<Transcription> {
+        debug!(
+            "Whisper API: transcribing {} ({} bytes)",
+            file_name,
+            data.len()
+        );
+        self.request(data, file_name, audio_mime_type(file_name))
+            .await
+    }
+
     fn name(&self) -> &str {
         "whisper_api"
     }
 }
 
+/// MIME type for an audio file name, by extension
+pub fn audio_mime_type(file_name: &str) -> &'static str {
+    let extension = std::path::Path::new(file_name)
+        .extension()
+        .and_then(|e| e.to_str())
+        .unwrap_or("")
+        .to_lowercase();
+    match extension.as_str() {
+        "mp3" | "mpga" | "mpeg" => "audio/mpeg",
+        "m4a" | "mp4" | "aac" => "audio/mp4",
+        "ogg" | "oga" | "opus" => "audio/ogg",
+        "webm" => "audio/webm",
+        "flac" => "audio/flac",
+        "caf" => "audio/x-caf",
+        "amr" => "audio/amr",
+        _ => "audio/wav",
+    }
+}
+
 /// Local whisper.cpp speech-to-text (stub — requires whisper-rs crate)
 pub struct WhisperLocalStt;
 
@@ -20,12 +20,23 @@ pub fn create_tts(config: &AudioConfig) -> Result<Box<dyn TextToSpeech>> {
             if config.elevenlabs_api_key.is_empty() {
                 return Err(anyhow!("ElevenLabs API key required for ElevenLabs TTS"));
             }
-            Ok(Box::new(ElevenLabsTts::new(
-                config.elevenlabs_api_key.clone(),
-                config.elevenlabs_voice_id.clone(),
-            )))
+            Ok(Box::new(
+                ElevenLabsTts::new(
+                    config.elevenlabs_api_key.clone(),
+                    config.elevenlabs_voice_id.clone(),
+                )
+                .with_language(config.language.as_deref()),
+            ))
+        }
+        TtsProvider::MacosSay => {
+            let tts = MacosSayTts::new();
+            Ok(Box::new(
+                match config.language.as_deref().and_then(say_voice_for) {
+                    Some(voice) => tts.with_voice(voice.to_string()),
+                    None => tts,
+                },
+            ))
         }
-        TtsProvider::MacosSay => Ok(Box::new(MacosSayTts::new())),
         TtsProvider::OpenaiTts => {
             let api_key = if !config.openai_api_key.is_empty() {
                 config.openai_api_key.clone()
@@ -59,6 +70,16 @@ impl ElevenLabsTts {
             model_id: "eleven_monolingual_v1".to_string(),
         }
     }
+
+    /// Switch to the multilingual model for non-English speech
+    pub fn with_language(mut self, language: Option<&str>) -> Self {
+        if let Some(lang) = language
+            && crate::i18n::normalize(lang) != "en"
+        {
+            self.model_id = "eleven_multilingual_v2".to_string();
+        }
+        self
+    }
 }
 
 #[async_trait]
@@ -67,6 +88,7 @@ impl TextToSpeech for ElevenLabsTts {
         if text.is_empty() {
             return Ok(Vec::new());
         }
+        crate::network::ensure_online("ElevenLabs speech")?;
 
         let truncated = if text.len() > 5000 {
             &text[..5000]
@@ -121,6 +143,24 @@ impl TextToSpeech for ElevenLabsTts {
     }
 }
 
+/// Built-in macOS voice for a language (ISO 639-1), if we know a good default
+pub fn say_voice_for(language: &str) -> Option<&'static str> {
+    match crate::i18n::normalize(language).as_str() {
+        "en" => Some("Samantha"),
+        "es" => Some("Monica"),
+        "fr" => Some("Thomas"),
+        "de" => Some("Anna"),
+        "pt" => Some("Luciana"),
+        "it" => Some("Alice"),
+        "nl" => Some("Xander"),
+        "ja" => Some("Kyoko"),
+        "zh" => Some("Ting-Ting"),
+        "ko" => Some("Yuna"),
+        "ru" => Some("Milena"),
+        _ => None,
+    }
+}
+
 /// macOS `say` command text-to-speech
 pub struct MacosSayTts {
     voice: String,
@@ -228,6 +268,7 @@ impl TextToSpeech for OpenAiTts {
         if text.is_empty() {
             return Ok(Vec::new());
         }
+        crate::network::ensure_online("OpenAI speech")?;
 
         let truncated = if text.len() > 4096 {
             &text[..4096]
@@ -284,6 +325,21 @@ mod tests {
         assert_eq!(tts.name(), "macos_say");
     }
 
+    #[test]
+    fn test_say_voice_for_language() {
+        assert_eq!(say_voice_for("es-MX"), Some("Monica"));
+        assert_eq!(say_voice_for("de"), Some("Anna"));
+        assert_eq!(say_voice_for("tlh"), None);
+    }
+
+    #[test]
+    fn test_elevenlabs_multilingual_model() {
+        let tts = ElevenLabsTts::new("k".into(), "default".into()).with_language(Some("fr"));
+        assert_eq!(tts.model_id, "eleven_multilingual_v2");
+        let tts = ElevenLabsTts::new("k".into(), "default".into()).with_language(Some("en-US"));
+        assert_eq!(tts.model_id, "eleven_monolingual_v1");
+    }
+
     #[test]
     fn test_create_tts_elevenlabs_no_key() {
         let mut config = AudioConfig::default();
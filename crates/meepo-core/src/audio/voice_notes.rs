@@ -0,0 +1,227 @@
+//! Voice note transcription
+//!
+//! Audio messages arriving on iMessage or email attachments are run
+//! through the STT provider and handed to the agent as text. Transcribed
+//! messages start with [`TRANSCRIBED_PREFIX`] so the agent knows the words
+//! came from speech recognition. Formats Whisper doesn't read (iMessage's
+//! `.caf`, `.amr`) are converted with `afconvert` first.
+
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result, anyhow};
+use tokio::process::Command;
+use tracing::{debug, info};
+
+use super::stt::SpeechToText;
+
+/// Marks a message whose content is a voice note transcription
+pub const TRANSCRIBED_PREFIX: &str = "[Voice note, transcribed]";
+
+/// Whisper's upload limit
+const DEFAULT_MAX_BYTES: u64 = 25 * 1024 * 1024;
+
+/// Extensions treated as audio attachments
+const AUDIO_EXTENSIONS: &[&str] = &[
+    "caf", "m4a", "mp3", "wav", "ogg", "oga", "opus", "amr", "aac", "webm", "flac",
+];
+
+/// Extensions converted to m4a before transcription
+const CONVERT_EXTENSIONS: &[&str] = &["caf", "amr"];
+
+/// Whether an attachment looks like a voice note, by MIME type or name
+pub fn is_audio_attachment(file_name: &str, mime_type: Option<&str>) -> bool {
+    if mime_type.is_some_and(|m| m.to_lowercase().starts_with("audio/")) {
+        return true;
+    }
+    extension(file_name).is_some_and(|e| AUDIO_EXTENSIONS.contains(&e.as_str()))
+}
+
+/// Whether a message's content is a voice note transcription
+pub fn is_transcribed(content: &str) -> bool {
+    content.starts_with(TRANSCRIBED_PREFIX)
+}
+
+/// Message content for a transcription, with any text sent alongside it
+pub fn message_content(transcript: &str, text: &str) -> String {
+    let text = text.trim();
+    if text.is_empty() {
+        format!("{} {}", TRANSCRIBED_PREFIX, transcript.trim())
+    } else {
+        format!("{} {}\n\n{}", TRANSCRIBED_PREFIX, transcript.trim(), text)
+    }
+}
+
+/// Context section for a transcribed message
+pub fn context_section(summarize: bool) -> String {
+    let mut section = String::from(
+        "## Voice Note\n\nThe user's message is an automatic transcription of a voice note; \
+         words may be misheard. Ask if something important is unclear.",
+    );
+    if summarize {
+        section.push_str(
+            " Start your reply with a one-line summary of what the voice note said, so they \
+             can check you understood it.",
+        );
+    }
+    section.push_str("\n\n");
+    section
+}
+
+fn extension(file_name: &str) -> Option<String> {
+    Path::new(file_name)
+        .extension()
+        .and_then(|e| e.to_str())
+        .map(str::to_lowercase)
+}
+
+/// Transcribes voice note files with an STT provider
+pub struct VoiceNoteTranscriber {
+    stt: Box<dyn SpeechToText>,
+    max_bytes: u64,
+}
+
+impl VoiceNoteTranscriber {
+    pub fn new(stt: Box<dyn SpeechToText>) -> Self {
+        Self {
+            stt,
+            max_bytes: DEFAULT_MAX_BYTES,
+        }
+    }
+
+    /// Skip voice notes larger than this
+    pub fn with_max_bytes(mut self, max_bytes: u64) -> Self {
+        self.max_bytes = max_bytes;
+        self
+    }
+
+    /// Transcribe the audio file at `path`, returning the spoken text
+    pub async fn transcribe_path(&self, path: &Path) -> Result<String> {
+        let size = tokio::fs::metadata(path)
+            .await
+            .with_context(|| format!("Voice note {} not found", path.display()))?
+            .len();
+        if size > self.max_bytes {
+            return Err(anyhow!(
+                "Voice note is {} MB, over the {} MB limit",
+                size / (1024 * 1024),
+                self.max_bytes / (1024 * 1024)
+            ));
+        }
+
+        let needs_conversion = extension(&path.to_string_lossy())
+            .is_some_and(|e| CONVERT_EXTENSIONS.contains(&e.as_str()));
+        let converted = if needs_conversion {
+            Some(convert_to_m4a(path).await?)
+        } else {
+            None
+        };
+        let source = converted.as_deref().unwrap_or(path);
+        let file_name = source
+            .file_name()
+            .map(|n| n.to_string_lossy().into_owned())
+            .unwrap_or_else(|| "voice-note.m4a".to_string());
+        let data = tokio::fs::read(source)
+            .await
+            .with_context(|| format!("Failed to read voice note {}", source.display()));
+        if let Some(converted) = &converted {
+            let _ = tokio::fs::remove_file(converted).await;
+        }
+        let data = data?;
+
+        let transcription = self.stt.transcribe_file(data, &file_name).await?;
+        if transcription.text.is_empty() {
+            return Err(anyhow!("No speech recognized in voice note"));
+        }
+        info!(
+            "Transcribed voice note {} ({} chars)",
+            file_name,
+            transcription.text.len()
+        );
+        Ok(transcription.text)
+    }
+}
+
+/// Convert a file Whisper can't read to AAC in a temporary `.m4a`
+async fn convert_to_m4a(path: &Path) -> Result<PathBuf> {
+    let output =
+        std::env::temp_dir().join(format!("meepo-voice-note-{}.m4a", uuid::Uuid::new_v4()));
+    debug!("Converting voice note {} to m4a", path.display());
+    let status = Command::new("afconvert")
+        .args(["-f", "m4af", "-d", "aac"])
+        .arg(path)
+        .arg(&output)
+        .status()
+        .await
+        .context("Failed to run afconvert (voice notes in this format need macOS)")?;
+    if !status.success() {
+        let _ = tokio::fs::remove_file(&output).await;
+        return Err(anyhow!("afconvert failed to convert {}", path.display()));
+    }
+    Ok(output)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::audio::{AudioChunk, Transcription};
+    use async_trait::async_trait;
+
+    struct FakeStt;
+
+    #[async_trait]
+    impl SpeechToText for FakeStt {
+        async fn transcribe(&self, _audio: &AudioChunk) -> Result<Transcription> {
+            unreachable!()
+        }
+
+        async fn transcribe_file(&self, data: Vec<u8>, file_name: &str) -> Result<Transcription> {
+            Ok(Transcription {
+                text: format!("{} bytes of {}", data.len(), file_name),
+                language: None,
+                duration_ms: 0,
+            })
+        }
+
+        fn name(&self) -> &str {
+            "fake"
+        }
+    }
+
+    #[test]
+    fn test_audio_attachments_and_content() {
+        assert!(is_audio_attachment("Audio Message.caf", None));
+        assert!(is_audio_attachment("note.M4A", None));
+        assert!(is_audio_attachment("blob", Some("audio/ogg")));
+        assert!(!is_audio_attachment("photo.jpg", Some("image/jpeg")));
+
+        let content = message_content(" Call me back ", "");
+        assert_eq!(content, "[Voice note, transcribed] Call me back");
+        assert!(is_transcribed(&content));
+        assert!(message_content("Hi", "Re: lunch").ends_with("Hi\n\nRe: lunch"));
+        assert!(!is_transcribed("Call me back"));
+        assert!(context_section(true).contains("one-line summary"));
+        assert!(!context_section(false).contains("summary"));
+    }
+
+    #[tokio::test]
+    async fn test_transcribe_path() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("note.m4a");
+        std::fs::write(&path, vec![0u8; 64]).unwrap();
+
+        let transcriber = VoiceNoteTranscriber::new(Box::new(FakeStt));
+        assert_eq!(
+            transcriber.transcribe_path(&path).await.unwrap(),
+            "64 bytes of note.m4a"
+        );
+
+        let small = VoiceNoteTranscriber::new(Box::new(FakeStt)).with_max_bytes(10);
+        assert!(small.transcribe_path(&path).await.is_err());
+        assert!(
+            transcriber
+                .transcribe_path(&dir.path().join("missing.m4a"))
+                .await
+                .is_err()
+        );
+    }
+}
@@ -46,6 +46,8 @@ pub fn classify_tool(tool_name: &str) -> ActionRisk {
         | "recall"
         | "search_knowledge"
         | "smart_recall"
+        | "list_tags"
+        | "list_knowledge_gaps"
         | "browse_url"
         | "web_search"
         | "get_clipboard"
@@ -76,13 +78,21 @@ pub fn classify_tool(tool_name: &str) -> ActionRisk {
         | "flight_status"
         | "message_summary"
         | "daily_briefing"
-        | "weekly_review" => ActionRisk::ReadOnly,
+        | "weekly_review"
+        | "meeting_prep"
+        | "get_focus"
+        | "list_rules"
+        | "list_message_templates"
+        | "app_usage" => ActionRisk::ReadOnly,
 
         // Write tools (reversible, local data)
         "write_file"
         | "remember"
+        | "remember_many"
+        | "manage_aliases"
         | "link_entities"
         | "ingest_document"
+        | "generate_dossier"
         | "create_watcher"
         | "cancel_watcher"
         | "create_task"
@@ -97,17 +107,21 @@ pub fn classify_tool(tool_name: &str) -> ActionRisk {
         | "create_note"
         | "create_reminder"
         | "set_auto_reply"
+        | "email_rule"
         | "packing_list"
+        | "set_focus"
         | "spawn_background_task"
         | "stop_task"
+        | "complete_onboarding"
+        | "create_rule"
+        | "save_message_template"
         | "write_code" => ActionRisk::Write,
 
         // External tools (send data outside the system)
         "send_email" | "send_sms" | "send_notification" | "make_pr" | "review_pr"
         | "create_event" | "reschedule_event" | "schedule_meeting" | "delegate_tasks"
-        | "delegate_to_agent" | "email_draft_reply" | "email_unsubscribe" | "suggest_followups" => {
-            ActionRisk::External
-        }
+        | "delegate_to_agent" | "email_draft_reply" | "email_unsubscribe" | "suggest_followups"
+        | "sync_now" | "send_template" => ActionRisk::External,
 
         // Destructive tools (irreversible or high-impact)
         "run_command"
@@ -195,6 +209,7 @@ mod tests {
         assert_eq!(classify_tool("send_email"), ActionRisk::External);
         assert_eq!(classify_tool("make_pr"), ActionRisk::External);
         assert_eq!(classify_tool("delegate_tasks"), ActionRisk::External);
+        assert_eq!(classify_tool("sync_now"), ActionRisk::External);
     }
 
     #[test]
@@ -244,6 +259,8 @@ mod tests {
             "list_directory",
             "search_files",
             "smart_recall",
+            "list_tags",
+            "list_knowledge_gaps",
             "browse_url",
             "get_clipboard",
             "read_emails",
@@ -273,6 +290,10 @@ mod tests {
             "message_summary",
             "daily_briefing",
             "weekly_review",
+            "meeting_prep",
+            "get_focus",
+            "list_rules",
+            "app_usage",
         ];
         for tool in &read_only {
             assert_eq!(
@@ -288,8 +309,11 @@ mod tests {
     fn test_classify_all_write_tools() {
         let write = [
             "remember",
+            "remember_many",
+            "manage_aliases",
             "link_entities",
             "ingest_document",
+            "generate_dossier",
             "create_watcher",
             "cancel_watcher",
             "create_task",
@@ -304,9 +328,13 @@ mod tests {
             "create_note",
             "create_reminder",
             "set_auto_reply",
+            "email_rule",
             "packing_list",
+            "set_focus",
             "spawn_background_task",
             "stop_task",
+            "complete_onboarding",
+            "create_rule",
             "write_code",
         ];
         for tool in &write {
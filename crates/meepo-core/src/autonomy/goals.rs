@@ -89,6 +89,9 @@ impl GoalEvaluator {
             if let Some(ref strategy) = goal.strategy {
                 prompt.push_str(&format!("  Current strategy: {}\n", strategy));
             }
+            if let Some(deadline) = goal.deadline {
+                prompt.push_str(&format!("  Deadline: {}\n", deadline.to_rfc3339()));
+            }
             prompt.push('\n');
         }
 
@@ -333,6 +336,7 @@ mod tests {
             source: "user".to_string(),
             created_at: chrono::Utc::now(),
             updated_at: chrono::Utc::now(),
+            deadline: None,
         }];
         let prompt = evaluator.build_evaluation_prompt(&goals);
         assert!(prompt.is_some());
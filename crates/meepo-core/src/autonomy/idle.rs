@@ -0,0 +1,331 @@
+//! Host idle detection — run heavy autonomous work when the machine is free
+//!
+//! Heavy tasks (bulk ingestion, consolidation, coding agents) are deferred
+//! while the user is actively using the machine and started once it has
+//! been idle for a while: no keyboard/mouse input, or the screen is locked,
+//! and (optionally) on AC power. Probes that aren't available on the current
+//! platform report `None` and never hold work back.
+
+use std::time::{Duration, Instant};
+use tokio::process::Command;
+use tokio::sync::Mutex;
+use tracing::{debug, info};
+
+/// Configuration for idle-window scheduling
+#[derive(Debug, Clone)]
+pub struct IdleConfig {
+    /// Defer heavy work until the host is idle
+    pub enabled: bool,
+    /// Seconds without keyboard/mouse input before the host counts as idle
+    pub min_idle_secs: u64,
+    /// Only count as idle when on AC power
+    pub require_ac_power: bool,
+    /// A locked screen counts as idle regardless of input time
+    pub locked_counts_as_idle: bool,
+    /// Run deferred work anyway after waiting this long (0 = wait indefinitely)
+    pub max_defer_secs: u64,
+    /// How often to re-check host state while waiting
+    pub poll_secs: u64,
+}
+
+impl Default for IdleConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            min_idle_secs: 300,
+            require_ac_power: true,
+            locked_counts_as_idle: true,
+            max_defer_secs: 14_400,
+            poll_secs: 60,
+        }
+    }
+}
+
+/// A snapshot of host activity. `None` means the probe isn't available.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct HostActivity {
+    /// Seconds since the last keyboard/mouse input
+    pub idle_secs: Option<u64>,
+    pub screen_locked: Option<bool>,
+    pub on_ac_power: Option<bool>,
+}
+
+impl HostActivity {
+    /// Whether heavy work may run now under the given config
+    pub fn is_idle(&self, config: &IdleConfig) -> bool {
+        if !config.enabled {
+            return true;
+        }
+        if config.require_ac_power && self.on_ac_power == Some(false) {
+            return false;
+        }
+        if config.locked_counts_as_idle && self.screen_locked == Some(true) {
+            return true;
+        }
+        match self.idle_secs {
+            Some(secs) => secs >= config.min_idle_secs,
+            // Can't tell whether the user is at the machine — don't hold work back
+            None => true,
+        }
+    }
+
+    /// Short description for logs, e.g. "idle 42s, unlocked, on battery"
+    pub fn describe(&self) -> String {
+        let idle = match self.idle_secs {
+            Some(secs) => format!("idle {}s", secs),
+            None => "idle time unknown".to_string(),
+        };
+        let lock = match self.screen_locked {
+            Some(true) => "locked",
+            Some(false) => "unlocked",
+            None => "lock state unknown",
+        };
+        let power = match self.on_ac_power {
+            Some(true) => "on AC power",
+            Some(false) => "on battery",
+            None => "power unknown",
+        };
+        format!("{}, {}, {}", idle, lock, power)
+    }
+}
+
+/// Probes host activity and lets heavy work wait for an idle window
+pub struct IdleMonitor {
+    config: IdleConfig,
+    /// Last probe result and when it expires
+    cache: Mutex<Option<(Instant, HostActivity)>>,
+}
+
+impl IdleMonitor {
+    pub fn new(config: IdleConfig) -> Self {
+        Self {
+            config,
+            cache: Mutex::new(None),
+        }
+    }
+
+    /// Current host activity (cached for `poll_secs`)
+    pub async fn current(&self) -> HostActivity {
+        let mut cache = self.cache.lock().await;
+        if let Some((expires, activity)) = cache.as_ref()
+            && Instant::now() < *expires
+        {
+            return activity.clone();
+        }
+        let activity = probe().await;
+        let expires = Instant::now() + Duration::from_secs(self.config.poll_secs);
+        *cache = Some((expires, activity.clone()));
+        activity
+    }
+
+    /// Whether heavy work may run now
+    pub async fn is_idle(&self) -> bool {
+        !self.config.enabled || self.current().await.is_idle(&self.config)
+    }
+
+    /// Wait until the host is idle, or until `max_defer_secs` has passed.
+    /// Returns true if an idle window was reached.
+    pub async fn wait_for_idle(&self, what: &str) -> bool {
+        if !self.config.enabled {
+            return true;
+        }
+        let started = Instant::now();
+        let max_defer = Duration::from_secs(self.config.max_defer_secs);
+        let mut logged = false;
+        loop {
+            let activity = self.current().await;
+            if activity.is_idle(&self.config) {
+                if logged {
+                    info!("Host idle ({}) — starting {}", activity.describe(), what);
+                }
+                return true;
+            }
+            if self.config.max_defer_secs > 0 && started.elapsed() >= max_defer {
+                info!(
+                    "Deferred {} for {}s without an idle window — running it anyway",
+                    what, self.config.max_defer_secs
+                );
+                return false;
+            }
+            if !logged {
+                info!(
+                    "Host in use ({}) — deferring {} until idle",
+                    activity.describe(),
+                    what
+                );
+                logged = true;
+            }
+            tokio::time::sleep(Duration::from_secs(self.config.poll_secs.max(1))).await;
+        }
+    }
+}
+
+/// Probe the host for input idle time, screen lock and power source
+pub async fn probe() -> HostActivity {
+    let activity = probe_platform().await;
+    debug!("Host activity: {}", activity.describe());
+    activity
+}
+
+#[cfg_attr(not(any(target_os = "macos", target_os = "linux")), allow(dead_code))]
+async fn command_output(program: &str, args: &[&str]) -> Option<String> {
+    let output = tokio::time::timeout(
+        Duration::from_secs(5),
+        Command::new(program).args(args).output(),
+    )
+    .await
+    .ok()?
+    .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    Some(String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+#[cfg(target_os = "macos")]
+async fn probe_platform() -> HostActivity {
+    let idle_secs = command_output("ioreg", &["-c", "IOHIDSystem", "-d", "4"])
+        .await
+        .and_then(|out| parse_hid_idle_time(&out));
+    let screen_locked = command_output("ioreg", &["-n", "Root", "-d", "1"])
+        .await
+        .map(|out| parse_screen_locked(&out));
+    HostActivity {
+        idle_secs,
+        screen_locked,
+        on_ac_power: crate::power::probe().await.on_ac_power,
+    }
+}
+
+#[cfg(target_os = "linux")]
+async fn probe_platform() -> HostActivity {
+    // xprintidle reports milliseconds since the last X11 input event
+    let idle_secs = command_output("xprintidle", &[])
+        .await
+        .and_then(|out| out.trim().parse::<u64>().ok())
+        .map(|ms| ms / 1000);
+    let screen_locked = match std::env::var("XDG_SESSION_ID") {
+        Ok(session) => command_output("loginctl", &["show-session", &session, "-p", "LockedHint"])
+            .await
+            .and_then(|out| parse_locked_hint(&out)),
+        Err(_) => None,
+    };
+    HostActivity {
+        idle_secs,
+        screen_locked,
+        on_ac_power: crate::power::probe().await.on_ac_power,
+    }
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "linux")))]
+async fn probe_platform() -> HostActivity {
+    HostActivity::default()
+}
+
+/// Parse `HIDIdleTime` (nanoseconds) from `ioreg -c IOHIDSystem` output
+#[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+fn parse_hid_idle_time(output: &str) -> Option<u64> {
+    output
+        .lines()
+        .find(|line| line.contains("\"HIDIdleTime\""))
+        .and_then(|line| line.split('=').nth(1))
+        .and_then(|value| value.trim().parse::<u64>().ok())
+        .map(|ns| ns / 1_000_000_000)
+}
+
+/// Whether `ioreg -n Root -d 1` reports the console session as locked
+#[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+fn parse_screen_locked(output: &str) -> bool {
+    output.contains("\"CGSSessionScreenIsLocked\"=Yes")
+}
+
+/// Parse `LockedHint=yes|no` from `loginctl show-session`
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn parse_locked_hint(output: &str) -> Option<bool> {
+    match output.trim().strip_prefix("LockedHint=")? {
+        "yes" => Some(true),
+        "no" => Some(false),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn activity(idle: Option<u64>, locked: Option<bool>, ac: Option<bool>) -> HostActivity {
+        HostActivity {
+            idle_secs: idle,
+            screen_locked: locked,
+            on_ac_power: ac,
+        }
+    }
+
+    #[test]
+    fn test_is_idle_rules() {
+        let config = IdleConfig::default();
+        assert!(!activity(Some(10), Some(false), Some(true)).is_idle(&config));
+        assert!(activity(Some(600), Some(false), Some(true)).is_idle(&config));
+        // Locked screen counts as idle even with recent input
+        assert!(activity(Some(10), Some(true), Some(true)).is_idle(&config));
+        // On battery never counts as idle when AC is required
+        assert!(!activity(Some(600), Some(true), Some(false)).is_idle(&config));
+        // Unknown probes don't hold work back
+        assert!(HostActivity::default().is_idle(&config));
+
+        let relaxed = IdleConfig {
+            require_ac_power: false,
+            ..IdleConfig::default()
+        };
+        assert!(activity(Some(600), None, Some(false)).is_idle(&relaxed));
+
+        let disabled = IdleConfig {
+            enabled: false,
+            ..IdleConfig::default()
+        };
+        assert!(activity(Some(0), Some(false), Some(false)).is_idle(&disabled));
+    }
+
+    #[test]
+    fn test_parse_probe_output() {
+        let ioreg = "    | |   \"HIDIdleTime\" = 125000000000\n    | |   \"HIDKeyboardModifierMappingPairs\" = ()";
+        assert_eq!(parse_hid_idle_time(ioreg), Some(125));
+        assert_eq!(parse_hid_idle_time("nothing here"), None);
+
+        assert!(parse_screen_locked(
+            "\"IOConsoleUsers\" = ({\"CGSSessionScreenIsLocked\"=Yes,\"kCGSSessionOnConsoleKey\"=Yes})"
+        ));
+        assert!(!parse_screen_locked(
+            "\"IOConsoleUsers\" = ({\"kCGSSessionOnConsoleKey\"=Yes})"
+        ));
+
+        assert_eq!(parse_locked_hint("LockedHint=yes\n"), Some(true));
+        assert_eq!(parse_locked_hint("LockedHint=no"), Some(false));
+        assert_eq!(parse_locked_hint(""), None);
+    }
+
+    #[tokio::test]
+    async fn test_wait_for_idle_gives_up_after_max_defer() {
+        let monitor = IdleMonitor::new(IdleConfig {
+            max_defer_secs: 1,
+            poll_secs: 1,
+            ..IdleConfig::default()
+        });
+        // Seed the cache with a busy host so the probe isn't consulted
+        *monitor.cache.lock().await = Some((
+            Instant::now() + Duration::from_secs(60),
+            activity(Some(0), Some(false), Some(true)),
+        ));
+        assert!(!monitor.is_idle().await);
+        let reached = tokio::time::timeout(Duration::from_secs(5), monitor.wait_for_idle("test"))
+            .await
+            .unwrap();
+        assert!(!reached);
+
+        let disabled = IdleMonitor::new(IdleConfig {
+            enabled: false,
+            ..IdleConfig::default()
+        });
+        assert!(disabled.wait_for_idle("test").await);
+    }
+}
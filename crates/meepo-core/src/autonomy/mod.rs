@@ -7,24 +7,36 @@
 
 pub mod action_log;
 pub mod goals;
+pub mod idle;
 pub mod planner;
+pub mod priority;
 pub mod user_model;
 
 use chrono::{Datelike, NaiveDate, Timelike, Utc};
+use std::collections::VecDeque;
 use std::sync::Arc;
 use std::time::{Duration, Instant};
 use tokio::sync::{Notify, mpsc};
 use tracing::{debug, error, info, warn};
 
 use crate::agent::Agent;
+use crate::email_triage::{EmailTriager, IncomingEmail, TriageCategory};
+use crate::expr::Expression;
+use crate::modes::ModeAutonomy;
 use crate::notifications::{NotificationService, NotifyEvent};
+use crate::power::PowerMonitor;
+use crate::rules::{RuleEngine, RuleEvent, RuleOutcome};
+use crate::streaming;
+use crate::tools::ToolHandler;
 use crate::types::{ChannelType, IncomingMessage, MessageKind, OutgoingMessage};
-use meepo_knowledge::KnowledgeDb;
+use crate::usage::UsageSource;
+use meepo_knowledge::{KnowledgeDb, journal};
 use meepo_scheduler::WatcherEvent;
 
 use self::action_log::ActionLogger;
 use self::goals::GoalEvaluator;
 use self::planner::ConfidenceGate;
+use self::priority::{ActivityGate, Priority};
 use self::user_model::UserModel;
 
 /// Configuration for the autonomous loop
@@ -35,10 +47,18 @@ pub struct AutonomyConfig {
     pub max_goals: usize,
     /// Send acknowledgment/typing indicators before processing messages
     pub send_acknowledgments: bool,
+    /// Send the reply as it's written to channels that can show it
+    pub stream_responses: bool,
     /// Hour (0-23) at which to generate the daily plan (default: 7)
     pub daily_plan_hour: u32,
+    /// User's timezone, used to interpret daily_plan_hour
+    pub timezone: chrono_tz::Tz,
     /// Max autonomous API calls per minute (0 = unlimited)
     pub max_calls_per_minute: u32,
+    /// Max concurrent autonomous actions while the user is chatting (0 = pause them)
+    pub max_actions_during_chat: usize,
+    /// Seconds after the last user message that the conversation counts as active
+    pub chat_active_secs: u64,
 }
 
 /// Simple sliding-window rate limiter for autonomous API calls
@@ -96,6 +116,15 @@ enum LoopInput {
     WatcherEvent(WatcherEvent),
 }
 
+impl LoopInput {
+    fn priority(&self) -> Priority {
+        match self {
+            LoopInput::UserMessage(_) => Priority::User,
+            LoopInput::WatcherEvent(_) => Priority::Watcher,
+        }
+    }
+}
+
 /// The autonomous loop that drives the agent
 pub struct AutonomousLoop {
     agent: Arc<Agent>,
@@ -117,6 +146,24 @@ pub struct AutonomousLoop {
     /// Rate limiter for autonomous API calls
     rate_limiter: RateLimiter,
 
+    /// Tracks user conversations so autonomous work yields to them
+    activity: Arc<ActivityGate>,
+
+    /// Power source monitor; slows the tick rate in low-power mode
+    power: Option<Arc<PowerMonitor>>,
+
+    /// Daily spend (USD) above which knowledge-gap research goals are held
+    research_budget_usd: Option<f64>,
+
+    /// Triages new mail instead of handing it to the agent
+    email_triage: Option<EmailTriager>,
+
+    /// Declarative rules fired on messages and watcher events
+    rules: Option<RuleEngine>,
+
+    /// Re-ingests previously ingested files when a watcher sees them change
+    document_refresh: Option<Arc<dyn ToolHandler>>,
+
     /// Date of the last daily plan (to avoid re-planning same day)
     daily_plan_date: Option<NaiveDate>,
 
@@ -153,6 +200,10 @@ impl AutonomousLoop {
         let confidence_gate = ConfidenceGate::default();
         let user_model = UserModel::new(db.clone());
         let rate_limiter = RateLimiter::new(config.max_calls_per_minute, Duration::from_secs(60));
+        let activity = ActivityGate::new(
+            config.max_actions_during_chat,
+            Duration::from_secs(config.chat_active_secs),
+        );
         Self {
             agent,
             db,
@@ -162,6 +213,12 @@ impl AutonomousLoop {
             confidence_gate,
             user_model,
             rate_limiter,
+            activity,
+            power: None,
+            research_budget_usd: None,
+            email_triage: None,
+            rules: None,
+            document_refresh: None,
             daily_plan_date: None,
             message_rx,
             watcher_rx,
@@ -171,6 +228,44 @@ impl AutonomousLoop {
         }
     }
 
+    /// Share an activity gate with other producers of autonomous work
+    /// (e.g. the background task runner) so they all yield to the user
+    pub fn with_activity_gate(mut self, gate: Arc<ActivityGate>) -> Self {
+        self.activity = gate;
+        self
+    }
+
+    /// Slow the tick rate while the machine is in low-power mode
+    pub fn with_power_monitor(mut self, power: Arc<PowerMonitor>) -> Self {
+        self.power = Some(power);
+        self
+    }
+
+    /// Hold knowledge-gap research goals once today's spend reaches a budget
+    pub fn with_research_budget(mut self, budget_usd: f64) -> Self {
+        self.research_budget_usd = Some(budget_usd);
+        self
+    }
+
+    /// Triage `email_received` watcher events with the given triager
+    pub fn with_email_triage(mut self, triager: EmailTriager) -> Self {
+        self.email_triage = Some(triager);
+        self
+    }
+
+    /// Evaluate automation rules on every message and watcher event
+    pub fn with_rules(mut self, rules: RuleEngine) -> Self {
+        self.rules = Some(rules);
+        self
+    }
+
+    /// Re-ingest previously ingested documents with this tool (normally
+    /// `ingest_document`) when a `file_changed` event reports them modified
+    pub fn with_document_refresh(mut self, tool: Arc<dyn ToolHandler>) -> Self {
+        self.document_refresh = Some(tool);
+        self
+    }
+
     /// Create a Notify handle that can be shared with message producers
     /// to wake the loop immediately when new inputs arrive.
     pub fn create_wake_handle() -> Arc<Notify> {
@@ -184,9 +279,14 @@ impl AutonomousLoop {
             self.config.tick_interval_secs
         );
 
-        let tick_duration = Duration::from_secs(self.config.tick_interval_secs);
+        let base_tick = Duration::from_secs(self.config.tick_interval_secs);
 
         loop {
+            let tick_duration = match &self.power {
+                Some(power) => power.tick_interval(base_tick),
+                None => base_tick,
+            };
+
             // Wait for: cancellation, tick timer, or wake signal
             tokio::select! {
                 _ = cancel.cancelled() => {
@@ -213,6 +313,25 @@ impl AutonomousLoop {
                     vec![]
                 }
             };
+            let due_goals = match self.research_budget_usd {
+                Some(budget) => {
+                    crate::knowledge_gaps::hold_research_over_budget(&self.db, due_goals, budget)
+                        .await
+                }
+                None => due_goals,
+            };
+
+            // The active mode may leave goals alone entirely
+            let due_goals =
+                if crate::modes::autonomy() == ModeAutonomy::Off && !due_goals.is_empty() {
+                    debug!(
+                        "Holding {} due goals (autonomy off in this mode)",
+                        due_goals.len()
+                    );
+                    Vec::new()
+                } else {
+                    due_goals
+                };
 
             // Skip tick if nothing to do
             if inputs.is_empty() && due_goals.is_empty() {
@@ -225,48 +344,84 @@ impl AutonomousLoop {
                 due_goals.len()
             );
 
-            // THINK + ACT: process user messages and watcher events first
-            for input in inputs {
+            // THINK + ACT: highest priority first; user messages that arrive
+            // while we work jump ahead of the remaining watcher events
+            let mut queue: VecDeque<LoopInput> = VecDeque::new();
+            self.enqueue(&mut queue, inputs);
+            while let Some(input) = queue.pop_front() {
                 match input {
                     LoopInput::UserMessage(msg) => {
+                        let _turn = self.activity.user_turn();
                         self.handle_user_message(msg).await;
                     }
                     LoopInput::WatcherEvent(event) => {
                         self.handle_watcher_event(event).await;
                     }
                 }
+                let fresh = self.drain_user_messages();
+                self.enqueue(&mut queue, fresh);
             }
 
             // Check budget after processing inputs and send notifications
             self.check_and_notify_budget().await;
 
-            // EVALUATE: process due goals through the GoalEvaluator (rate-limited)
+            // EVALUATE: process due goals through the GoalEvaluator (rate-limited,
+            // and held back while the user is chatting)
             if !due_goals.is_empty() {
-                if self.rate_limiter.try_acquire() {
-                    self.evaluate_goals(due_goals).await;
-                } else {
-                    debug!(
-                        "Rate limit hit — deferring {} goal evaluations to next tick",
-                        due_goals.len()
-                    );
+                match self.activity.try_acquire(Priority::Goal) {
+                    None => {
+                        debug!(
+                            "User conversation active — deferring {} goal evaluations",
+                            due_goals.len()
+                        );
+                    }
+                    Some(_permit) if self.rate_limiter.try_acquire() => {
+                        self.evaluate_goals(due_goals).await;
+                    }
+                    Some(_) => {
+                        debug!(
+                            "Rate limit hit — deferring {} goal evaluations to next tick",
+                            due_goals.len()
+                        );
+                    }
                 }
             }
 
             // PLAN: generate daily plan once per day at the configured hour (rate-limited)
-            if self.rate_limiter.remaining() > 0 {
+            if self.rate_limiter.remaining() > 0
+                && let Some(_permit) = self.activity.try_acquire(Priority::Goal)
+            {
                 self.maybe_daily_plan().await;
             }
         }
     }
 
-    /// Drain all pending inputs from channels without blocking
-    fn drain_inputs(&mut self) -> Vec<LoopInput> {
-        let mut inputs = Vec::new();
+    /// Insert inputs into the work queue, keeping it ordered by priority.
+    /// Inputs of equal priority stay in arrival order.
+    fn enqueue(&self, queue: &mut VecDeque<LoopInput>, inputs: Vec<LoopInput>) {
+        for input in inputs {
+            let priority = input.priority();
+            let idx = queue.partition_point(|queued| queued.priority() >= priority);
+            queue.insert(idx, input);
+        }
+    }
 
-        // Drain user messages
+    /// Pull any user messages that arrived since the last drain
+    fn drain_user_messages(&mut self) -> Vec<LoopInput> {
+        let mut inputs = Vec::new();
         while let Ok(msg) = self.message_rx.try_recv() {
             inputs.push(LoopInput::UserMessage(msg));
         }
+        if !inputs.is_empty() {
+            self.activity.note_user_message();
+        }
+        inputs
+    }
+
+    /// Drain all pending inputs from channels without blocking
+    fn drain_inputs(&mut self) -> Vec<LoopInput> {
+        // Drain user messages
+        let mut inputs = self.drain_user_messages();
 
         // Drain watcher events
         while let Ok(event) = self.watcher_rx.try_recv() {
@@ -278,7 +433,7 @@ impl AutonomousLoop {
 
     /// Generate a daily plan if it's past the configured hour and we haven't planned today
     async fn maybe_daily_plan(&mut self) {
-        let now = Utc::now();
+        let now = Utc::now().with_timezone(&self.config.timezone);
         let today = now.date_naive();
         let current_hour = now.hour();
 
@@ -346,7 +501,7 @@ impl AutonomousLoop {
             sender: "daily_planner".to_string(),
             content: prompt,
             channel: ChannelType::Internal,
-            timestamp: now,
+            timestamp: now.with_timezone(&Utc),
         };
 
         match self.agent.handle_message(msg).await {
@@ -470,6 +625,17 @@ impl AutonomousLoop {
                                     );
                                     continue;
                                 }
+                                let autonomy = crate::modes::autonomy();
+                                if action.confidence < autonomy.min_confidence() {
+                                    info!(
+                                        "Goal action for {} held by mode (autonomy {}, \
+                                         confidence {:.2})",
+                                        action.goal_id,
+                                        autonomy.as_str(),
+                                        action.confidence
+                                    );
+                                    continue;
+                                }
 
                                 info!(
                                     "Executing goal action for {}: {}",
@@ -556,6 +722,16 @@ impl AutonomousLoop {
         let sender = msg.sender.clone();
         info!("Clone on {} handling message from {}", channel, sender);
 
+        if let Some(rules) = &self.rules {
+            let outcome = rules.evaluate(&RuleEvent::from_message(&msg)).await;
+            let handled = outcome.handled;
+            self.dispatch_rule_outcome(outcome).await;
+            if handled {
+                debug!("Message from {} handled by a rule", sender);
+                return;
+            }
+        }
+
         // Send acknowledgment so the user knows we're working on it
         if self.config.send_acknowledgments {
             let ack = OutgoingMessage {
@@ -563,11 +739,29 @@ impl AutonomousLoop {
                 channel: msg.channel.clone(),
                 reply_to: Some(msg.id.clone()),
                 kind: MessageKind::Acknowledgment,
+                interactive: None,
             };
             let _ = self.response_tx.send(ack).await;
         }
 
-        match self.agent.handle_message(msg).await {
+        let result = if self.config.stream_responses {
+            let (sink, deltas) = mpsc::unbounded_channel();
+            let relay = tokio::spawn(streaming::relay(
+                deltas,
+                msg.channel.clone(),
+                Some(msg.id.clone()),
+                self.response_tx.clone(),
+                streaming::DEFAULT_INTERVAL,
+            ));
+            let result = self.agent.handle_message_streamed(msg, sink).await;
+            // The last partial must arrive before the response replaces it
+            let _ = relay.await;
+            result
+        } else {
+            self.agent.handle_message(msg).await
+        };
+
+        match result {
             Ok(response) => {
                 if let Err(e) = self.response_tx.send(response).await {
                     error!("Failed to send response: {}", e);
@@ -585,6 +779,63 @@ impl AutonomousLoop {
         }
     }
 
+    /// Send what fired rules produced; prompts go through the agent
+    async fn dispatch_rule_outcome(&self, outcome: RuleOutcome) {
+        for message in outcome.messages {
+            if let Err(e) = self.response_tx.send(message).await {
+                error!("Failed to send rule message: {}", e);
+            }
+        }
+        for prompt in outcome.prompts {
+            let channel = prompt.channel.clone();
+            match self.agent.handle_message(prompt).await {
+                Ok(mut response) => {
+                    response.channel = channel;
+                    if let Err(e) = self.response_tx.send(response).await {
+                        error!("Failed to send rule response: {}", e);
+                    }
+                }
+                Err(e) => error!("Agent error handling rule prompt: {}", e),
+            }
+        }
+    }
+
+    /// Re-ingest a changed file that was ingested before, so retrieval stops
+    /// serving its stale chunks. Files never ingested are left alone.
+    async fn refresh_document(&self, event: &WatcherEvent) {
+        let Some(ingest) = &self.document_refresh else {
+            return;
+        };
+        let Some(path) = event.payload.get("path").and_then(|p| p.as_str()) else {
+            return;
+        };
+        let doc = match self.db.find_document_by_source(path).await {
+            Ok(Some(doc)) if std::path::Path::new(path).is_file() => doc,
+            Ok(_) => return,
+            Err(e) => {
+                warn!("Failed to look up ingested document {}: {}", path, e);
+                return;
+            }
+        };
+        let tags = doc
+            .metadata
+            .as_ref()
+            .and_then(|m| m.get("tags"))
+            .cloned()
+            .unwrap_or_else(|| serde_json::json!([]));
+        match ingest
+            .execute(serde_json::json!({"path": path, "title": doc.name, "tags": tags}))
+            .await
+        {
+            Ok(summary) => info!(
+                "Refreshed ingested document {}: {}",
+                path,
+                summary.lines().next().unwrap_or_default()
+            ),
+            Err(e) => warn!("Failed to refresh ingested document {}: {}", path, e),
+        }
+    }
+
     /// Handle a watcher event — look up the watcher's reply_channel and action,
     /// then route the agent's response to the correct channel.
     async fn handle_watcher_event(&self, event: WatcherEvent) {
@@ -593,6 +844,60 @@ impl AutonomousLoop {
             event.kind, event.watcher_id
         );
 
+        if event.kind == "file_changed" {
+            self.refresh_document(&event).await;
+        }
+
+        // Look up the watcher to get reply_channel, action and condition
+        let watcher = match self.db.get_watcher(&event.watcher_id).await {
+            Ok(Some(w)) => Some(w),
+            Ok(None) => {
+                error!("Watcher {} not found in database", event.watcher_id);
+                None
+            }
+            Err(e) => {
+                error!("Failed to look up watcher {}: {}", event.watcher_id, e);
+                None
+            }
+        };
+
+        if let Some(condition) = watcher
+            .as_ref()
+            .and_then(|w| w.config.get("condition"))
+            .and_then(|c| c.as_str())
+        {
+            let vars = RuleEvent::from_watcher(&event).vars();
+            match Expression::parse(condition).and_then(|expr| expr.matches(&vars)) {
+                Ok(true) => {}
+                Ok(false) => {
+                    debug!(
+                        "Watcher {} event skipped, condition not met: {}",
+                        event.watcher_id, condition
+                    );
+                    return;
+                }
+                Err(e) => {
+                    warn!(
+                        "Watcher {} condition failed, skipping event: {}",
+                        event.watcher_id, e
+                    );
+                    return;
+                }
+            }
+        }
+
+        if let Err(e) = self
+            .db
+            .append_event(
+                journal::WATCHER_FIRED,
+                &event.watcher_id,
+                serde_json::json!({ "kind": event.kind, "payload": event.payload }),
+            )
+            .await
+        {
+            warn!("Failed to journal watcher event: {}", e);
+        }
+
         // Notify user that a watcher triggered
         self.notifier
             .notify(NotifyEvent::WatcherTriggered {
@@ -602,19 +907,29 @@ impl AutonomousLoop {
             })
             .await;
 
-        // Look up the watcher to get reply_channel and action
-        let (reply_channel, action) = match self.db.get_watcher(&event.watcher_id).await {
-            Ok(Some(w)) => (ChannelType::from_string(&w.reply_channel), w.action),
-            Ok(None) => {
-                error!("Watcher {} not found in database", event.watcher_id);
-                (ChannelType::Internal, String::new())
-            }
-            Err(e) => {
-                error!("Failed to look up watcher {}: {}", event.watcher_id, e);
-                (ChannelType::Internal, String::new())
+        if let Some(rules) = &self.rules {
+            let outcome = rules.evaluate(&RuleEvent::from_watcher(&event)).await;
+            let handled = outcome.handled;
+            self.dispatch_rule_outcome(outcome).await;
+            if handled {
+                debug!("Watcher event {} handled by a rule", event.watcher_id);
+                return;
             }
+        }
+
+        let (reply_channel, action) = match watcher {
+            Some(w) => (ChannelType::from_string(&w.reply_channel), w.action),
+            None => (ChannelType::Internal, String::new()),
         };
 
+        if event.kind == "email_received"
+            && let Some(triager) = &self.email_triage
+            && let Some(email) = IncomingEmail::from_payload(&event.payload)
+        {
+            self.triage_email(triager, &email, reply_channel).await;
+            return;
+        }
+
         // Build prompt with the watcher's action context
         let content = if action.is_empty() {
             format!("Watcher {} triggered: {}", event.watcher_id, event.payload)
@@ -655,6 +970,60 @@ impl AutonomousLoop {
             }
         }
     }
+
+    /// Triage a new email, telling the user only about mail that can't wait
+    /// for the digest
+    async fn triage_email(
+        &self,
+        triager: &EmailTriager,
+        email: &IncomingEmail,
+        reply_channel: ChannelType,
+    ) {
+        match triager.triage(self.agent.api(), email).await {
+            Ok(outcome) => {
+                if let Some(tracker) = self.agent.usage_tracker()
+                    && outcome.usage.api_calls > 0
+                    && let Err(e) = tracker
+                        .record(
+                            &triager.model(),
+                            &outcome.usage,
+                            &UsageSource::Watcher,
+                            None,
+                        )
+                        .await
+                {
+                    warn!("Failed to record triage usage: {}", e);
+                }
+                if !outcome.should_notify() {
+                    return;
+                }
+                // Only urgent mail breaks through a quiet Focus mode
+                if outcome.category != TriageCategory::Urgent && crate::focus::is_quiet() {
+                    debug!("Triage notice held back (focus mode)");
+                    return;
+                }
+                let notice = OutgoingMessage {
+                    content: outcome.summary(email),
+                    channel: reply_channel,
+                    reply_to: None,
+                    kind: MessageKind::Response,
+                    interactive: None,
+                };
+                if let Err(e) = self.response_tx.send(notice).await {
+                    error!("Failed to send triage notice: {}", e);
+                }
+            }
+            Err(e) => {
+                error!("Failed to triage email '{}': {}", email.subject, e);
+                self.notifier
+                    .notify(NotifyEvent::Error {
+                        context: format!("Triaging email from {}", email.from),
+                        error: e.to_string(),
+                    })
+                    .await;
+            }
+        }
+    }
 }
 
 #[cfg(test)]
@@ -697,8 +1066,12 @@ mod tests {
                 tick_interval_secs: 30,
                 max_goals: 50,
                 send_acknowledgments: true,
+                stream_responses: false,
                 daily_plan_hour: 7,
+                timezone: chrono_tz::Tz::UTC,
                 max_calls_per_minute: 10,
+                max_actions_during_chat: 1,
+                chat_active_secs: 120,
             },
             msg_rx,
             watcher_rx,
@@ -740,8 +1113,12 @@ mod tests {
                 tick_interval_secs: 30,
                 max_goals: 50,
                 send_acknowledgments: true,
+                stream_responses: false,
                 daily_plan_hour: 7,
+                timezone: chrono_tz::Tz::UTC,
                 max_calls_per_minute: 10,
+                max_actions_during_chat: 1,
+                chat_active_secs: 120,
             },
             msg_rx,
             watcher_rx,
@@ -754,6 +1131,75 @@ mod tests {
         assert_eq!(inputs.len(), 1);
     }
 
+    #[tokio::test]
+    async fn test_user_messages_jump_ahead_of_watcher_events() {
+        let (agent, db, _tmp) = setup();
+        let (msg_tx, msg_rx) = mpsc::channel(16);
+        let (watcher_tx, watcher_rx) = mpsc::unbounded_channel();
+        let (resp_tx, _) = mpsc::channel(16);
+        let notifier = NotificationService::disabled(resp_tx.clone());
+        let user_msg = |id: &str| IncomingMessage {
+            id: id.into(),
+            sender: "user".into(),
+            content: "hi".into(),
+            channel: ChannelType::Discord,
+            timestamp: chrono::Utc::now(),
+        };
+
+        for id in ["w-1", "w-2"] {
+            watcher_tx
+                .send(WatcherEvent::new(
+                    id.into(),
+                    "file_changed".into(),
+                    serde_json::json!({}),
+                ))
+                .unwrap();
+        }
+        msg_tx.send(user_msg("u-1")).await.unwrap();
+
+        let mut loop_ = AutonomousLoop::new(
+            agent,
+            db,
+            AutonomyConfig {
+                enabled: true,
+                tick_interval_secs: 30,
+                max_goals: 50,
+                send_acknowledgments: true,
+                stream_responses: false,
+                daily_plan_hour: 7,
+                timezone: chrono_tz::Tz::UTC,
+                max_calls_per_minute: 10,
+                max_actions_during_chat: 1,
+                chat_active_secs: 120,
+            },
+            msg_rx,
+            watcher_rx,
+            resp_tx,
+            notifier,
+            AutonomousLoop::create_wake_handle(),
+        );
+
+        let mut queue = VecDeque::new();
+        let inputs = loop_.drain_inputs();
+        loop_.enqueue(&mut queue, inputs);
+        assert!(loop_.activity.user_active());
+
+        // Handle the first user message, then a new one arrives mid-tick
+        assert!(matches!(queue.pop_front(), Some(LoopInput::UserMessage(_))));
+        msg_tx.send(user_msg("u-2")).await.unwrap();
+        let fresh = loop_.drain_user_messages();
+        loop_.enqueue(&mut queue, fresh);
+
+        let order: Vec<String> = queue
+            .iter()
+            .map(|input| match input {
+                LoopInput::UserMessage(m) => m.id.clone(),
+                LoopInput::WatcherEvent(e) => e.watcher_id.clone(),
+            })
+            .collect();
+        assert_eq!(order, vec!["u-2", "w-1", "w-2"]);
+    }
+
     #[test]
     fn test_rate_limiter_allows_within_limit() {
         let mut limiter = RateLimiter::new(3, Duration::from_secs(60));
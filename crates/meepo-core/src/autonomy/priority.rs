@@ -0,0 +1,214 @@
+//! Work priorities and the user-activity gate
+//!
+//! Direct user messages always go first. While the user is chatting
+//! (a turn is in flight, or they sent something recently), lower-priority
+//! autonomous work — goal evaluations, daily plans, background tasks —
+//! yields: at most `max_during_chat` such actions may run at once, and the
+//! rest wait until the conversation goes quiet.
+
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::sync::Notify;
+use tracing::debug;
+
+/// Priority of a unit of work; higher runs first
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Priority {
+    /// Spawned background tasks
+    Background,
+    /// Goal evaluations and daily planning
+    Goal,
+    /// Watcher events (time-sensitive, but not the user)
+    Watcher,
+    /// Direct user messages
+    User,
+}
+
+#[derive(Default)]
+struct GateState {
+    /// When the last user message arrived
+    last_user: Option<Instant>,
+    /// User turns currently being processed
+    user_turns: usize,
+    /// Autonomous actions currently holding a permit
+    running: usize,
+}
+
+/// Tracks user conversation activity and caps autonomous work during it
+pub struct ActivityGate {
+    state: Mutex<GateState>,
+    changed: Notify,
+    /// Max concurrent autonomous actions while the user is chatting (0 = pause all)
+    max_during_chat: usize,
+    /// How long after the last user message the conversation counts as active
+    active_window: Duration,
+}
+
+impl ActivityGate {
+    pub fn new(max_during_chat: usize, active_window: Duration) -> Arc<Self> {
+        Arc::new(Self {
+            state: Mutex::new(GateState::default()),
+            changed: Notify::new(),
+            max_during_chat,
+            active_window,
+        })
+    }
+
+    /// Record that a user message arrived
+    pub fn note_user_message(&self) {
+        self.state.lock().unwrap().last_user = Some(Instant::now());
+    }
+
+    /// Mark a user turn as in flight until the guard is dropped
+    pub fn user_turn(self: &Arc<Self>) -> UserTurn {
+        {
+            let mut state = self.state.lock().unwrap();
+            state.user_turns += 1;
+            state.last_user = Some(Instant::now());
+        }
+        UserTurn { gate: self.clone() }
+    }
+
+    /// Whether a user conversation is in flight
+    pub fn user_active(&self) -> bool {
+        let state = self.state.lock().unwrap();
+        self.is_active(&state)
+    }
+
+    fn is_active(&self, state: &GateState) -> bool {
+        state.user_turns > 0
+            || state
+                .last_user
+                .is_some_and(|t| t.elapsed() < self.active_window)
+    }
+
+    /// Autonomous actions currently running
+    pub fn running(&self) -> usize {
+        self.state.lock().unwrap().running
+    }
+
+    /// Take a permit for autonomous work if the gate allows it right now
+    pub fn try_acquire(self: &Arc<Self>, priority: Priority) -> Option<ActionPermit> {
+        let mut state = self.state.lock().unwrap();
+        if priority < Priority::User
+            && self.is_active(&state)
+            && state.running >= self.max_during_chat
+        {
+            return None;
+        }
+        state.running += 1;
+        Some(ActionPermit { gate: self.clone() })
+    }
+
+    /// Wait until autonomous work of this priority may run, then take a permit
+    pub async fn acquire(self: &Arc<Self>, priority: Priority) -> ActionPermit {
+        let mut logged = false;
+        loop {
+            let notified = self.changed.notified();
+            if let Some(permit) = self.try_acquire(priority) {
+                return permit;
+            }
+            if !logged {
+                debug!("{:?} work yielding to active user conversation", priority);
+                logged = true;
+            }
+            // The active window expires without a notification, so re-check periodically
+            tokio::select! {
+                _ = notified => {}
+                _ = tokio::time::sleep(Duration::from_secs(1)) => {}
+            }
+        }
+    }
+
+    fn release(&self, f: impl FnOnce(&mut GateState)) {
+        f(&mut self.state.lock().unwrap());
+        self.changed.notify_waiters();
+    }
+}
+
+/// Held while a user turn is being processed
+pub struct UserTurn {
+    gate: Arc<ActivityGate>,
+}
+
+impl Drop for UserTurn {
+    fn drop(&mut self) {
+        self.gate
+            .release(|s| s.user_turns = s.user_turns.saturating_sub(1));
+    }
+}
+
+/// Held while an autonomous action runs
+pub struct ActionPermit {
+    gate: Arc<ActivityGate>,
+}
+
+impl Drop for ActionPermit {
+    fn drop(&mut self) {
+        self.gate
+            .release(|s| s.running = s.running.saturating_sub(1));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_priority_order() {
+        assert!(Priority::User > Priority::Watcher);
+        assert!(Priority::Watcher > Priority::Goal);
+        assert!(Priority::Goal > Priority::Background);
+    }
+
+    #[test]
+    fn test_gate_caps_work_during_chat() {
+        let gate = ActivityGate::new(1, Duration::from_secs(60));
+
+        // Idle: no cap
+        let a = gate.try_acquire(Priority::Goal).unwrap();
+        let b = gate.try_acquire(Priority::Background).unwrap();
+        assert_eq!(gate.running(), 2);
+        drop((a, b));
+
+        let turn = gate.user_turn();
+        assert!(gate.user_active());
+        let first = gate.try_acquire(Priority::Goal).unwrap();
+        assert!(gate.try_acquire(Priority::Background).is_none());
+        // User work is never held back
+        assert!(gate.try_acquire(Priority::User).is_some());
+        drop(first);
+        assert!(gate.try_acquire(Priority::Background).is_some());
+        drop(turn);
+    }
+
+    #[test]
+    fn test_gate_zero_pauses_until_quiet() {
+        let gate = ActivityGate::new(0, Duration::from_millis(20));
+        gate.note_user_message();
+        assert!(gate.try_acquire(Priority::Goal).is_none());
+        std::thread::sleep(Duration::from_millis(30));
+        assert!(!gate.user_active());
+        assert!(gate.try_acquire(Priority::Goal).is_some());
+    }
+
+    #[tokio::test]
+    async fn test_acquire_waits_for_turn_to_finish() {
+        let gate = ActivityGate::new(0, Duration::ZERO);
+        let turn = gate.user_turn();
+        let waiter = {
+            let gate = gate.clone();
+            tokio::spawn(async move { gate.acquire(Priority::Background).await })
+        };
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        assert!(!waiter.is_finished());
+        drop(turn);
+        let permit = tokio::time::timeout(Duration::from_secs(2), waiter)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(gate.running(), 1);
+        drop(permit);
+        assert_eq!(gate.running(), 0);
+    }
+}
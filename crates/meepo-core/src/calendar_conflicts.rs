@@ -0,0 +1,326 @@
+//! Conflict and travel-time checks for new calendar events
+//!
+//! Before `create_calendar_event` books anything it compares the new event
+//! with what's already on the calendar: an overlap is a conflict, and so is
+//! a gap too short to get between two events held in different places.
+//! Travel time comes from configured routes, falling back to a flat
+//! estimate whenever the locations differ. When there's a clash the tool
+//! offers the nearest free slots instead of double-booking.
+
+use anyhow::Result;
+use chrono::{DateTime, Duration, Timelike, Utc};
+use chrono_tz::Tz;
+use tracing::debug;
+
+use crate::platform::{CalendarEvent, CalendarProvider};
+
+/// Step between candidate start times when looking for alternatives
+const SLOT_STEP_MINUTES: i64 = 30;
+
+/// How far past the requested time alternatives are looked for
+const SEARCH_DAYS: i64 = 3;
+
+/// Alternatives start within these local hours
+const DAY_START_HOUR: u32 = 8;
+const DAY_END_HOUR: u32 = 20;
+
+/// A known travel time between two places
+#[derive(Debug, Clone)]
+pub struct TravelRoute {
+    pub from: String,
+    pub to: String,
+    pub minutes: u64,
+}
+
+/// Travel-time estimates between event locations
+#[derive(Debug, Clone, Default)]
+pub struct TravelTimes {
+    /// Assumed when two events are in different places with no route
+    pub default_minutes: u64,
+    /// Known routes; each also counts in the other direction
+    pub routes: Vec<TravelRoute>,
+}
+
+fn same_place(a: &str, b: &str) -> bool {
+    a.trim().eq_ignore_ascii_case(b.trim())
+}
+
+impl TravelTimes {
+    /// Minutes needed to get from one event's location to the next. Events
+    /// without a location, or in the same place, need none.
+    pub fn minutes(&self, from: Option<&str>, to: Option<&str>) -> u64 {
+        let (Some(from), Some(to)) = (from, to) else {
+            return 0;
+        };
+        if from.trim().is_empty() || to.trim().is_empty() || same_place(from, to) {
+            return 0;
+        }
+        self.routes
+            .iter()
+            .find(|r| {
+                (same_place(&r.from, from) && same_place(&r.to, to))
+                    || (same_place(&r.from, to) && same_place(&r.to, from))
+            })
+            .map(|r| r.minutes)
+            .unwrap_or(self.default_minutes)
+    }
+}
+
+/// How new events are checked before they're booked
+#[derive(Debug, Clone, Default)]
+pub struct ConflictCheck {
+    pub travel: TravelTimes,
+    /// Free slots offered when there's a clash
+    pub suggestions: usize,
+}
+
+/// Why an existing event clashes with the new one
+#[derive(Debug, Clone, PartialEq)]
+pub enum ConflictKind {
+    Overlap,
+    /// Not enough time to travel between the two
+    TravelTime {
+        needed_minutes: u64,
+        gap_minutes: i64,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Conflict {
+    pub event: CalendarEvent,
+    pub kind: ConflictKind,
+}
+
+impl Conflict {
+    pub fn describe(&self) -> String {
+        let when = crate::timezone::format_local(
+            &self
+                .event
+                .start
+                .with_timezone(&crate::timezone::user_timezone()),
+            "",
+        );
+        match &self.kind {
+            ConflictKind::Overlap => format!("overlaps '{}' ({})", self.event.title, when),
+            ConflictKind::TravelTime {
+                needed_minutes,
+                gap_minutes,
+            } => format!(
+                "leaves {} min to travel to or from '{}' ({}{}), which needs about {} min",
+                (*gap_minutes).max(0),
+                self.event.title,
+                when,
+                self.event
+                    .location
+                    .as_deref()
+                    .map(|l| format!(" at {}", l))
+                    .unwrap_or_default(),
+                needed_minutes
+            ),
+        }
+    }
+}
+
+/// Existing events that clash with a new one from `start` to `end`
+pub fn find_conflicts(
+    existing: &[CalendarEvent],
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+    location: Option<&str>,
+    travel: &TravelTimes,
+) -> Vec<Conflict> {
+    let mut conflicts = Vec::new();
+    for event in existing {
+        let kind = if event.start < end && start < event.end {
+            Some(ConflictKind::Overlap)
+        } else {
+            // Travel from the earlier event's place to the later one's
+            let (gap, needed) = if event.end <= start {
+                (
+                    start - event.end,
+                    travel.minutes(event.location.as_deref(), location),
+                )
+            } else {
+                (
+                    event.start - end,
+                    travel.minutes(location, event.location.as_deref()),
+                )
+            };
+            (gap < Duration::minutes(needed as i64)).then(|| ConflictKind::TravelTime {
+                needed_minutes: needed,
+                gap_minutes: gap.num_minutes(),
+            })
+        };
+        if let Some(kind) = kind {
+            conflicts.push(Conflict {
+                event: event.clone(),
+                kind,
+            });
+        }
+    }
+    conflicts
+}
+
+/// Up to `count` clash-free start times from `start` on, in daytime hours
+/// of `tz`
+pub fn suggest_alternatives(
+    existing: &[CalendarEvent],
+    start: DateTime<Utc>,
+    duration: Duration,
+    location: Option<&str>,
+    travel: &TravelTimes,
+    tz: Tz,
+    count: usize,
+) -> Vec<DateTime<Utc>> {
+    let mut found = Vec::new();
+    let mut candidate = start + Duration::minutes(SLOT_STEP_MINUTES);
+    let last = start + Duration::days(SEARCH_DAYS);
+    while found.len() < count && candidate <= last {
+        let local = candidate.with_timezone(&tz);
+        let end_local = (candidate + duration).with_timezone(&tz);
+        let daytime = local.hour() >= DAY_START_HOUR
+            && local.date_naive() == end_local.date_naive()
+            && (end_local.hour() < DAY_END_HOUR
+                || (end_local.hour() == DAY_END_HOUR && end_local.minute() == 0));
+        if daytime
+            && find_conflicts(existing, candidate, candidate + duration, location, travel)
+                .is_empty()
+        {
+            found.push(candidate);
+        }
+        candidate += Duration::minutes(SLOT_STEP_MINUTES);
+    }
+    found
+}
+
+/// Check a new event against the calendar. Returns a message explaining
+/// the clashes and offering alternatives, or `None` when it can be booked.
+/// Start times that aren't absolute (e.g. "next Tuesday") can't be checked.
+pub async fn review(
+    provider: &dyn CalendarProvider,
+    check: &ConflictCheck,
+    summary: &str,
+    start_time: &str,
+    duration_minutes: u64,
+    location: Option<&str>,
+) -> Result<Option<String>> {
+    let Some(start) = crate::calendar_feed::parse_time(start_time) else {
+        debug!(
+            "Not checking '{}' for conflicts: start '{}'",
+            summary, start_time
+        );
+        return Ok(None);
+    };
+    let duration = Duration::minutes(duration_minutes as i64);
+    let end = start + duration;
+    let now = Utc::now();
+    if end <= now {
+        return Ok(None);
+    }
+    // Cover the event, travel around it and the alternatives after it
+    let hours_ahead = (end - now).num_hours() as u64 + 24 * (SEARCH_DAYS as u64 + 1);
+    let existing = provider.upcoming_events(hours_ahead).await?;
+
+    let conflicts = find_conflicts(&existing, start, end, location, &check.travel);
+    if conflicts.is_empty() {
+        return Ok(None);
+    }
+    let tz = crate::timezone::user_timezone();
+    let mut message = format!(
+        "Not created: '{}' at {}",
+        summary,
+        crate::timezone::format_local(&start.with_timezone(&tz), "")
+    );
+    for conflict in &conflicts {
+        message.push_str(&format!("\n- {}", conflict.describe()));
+    }
+    let alternatives = suggest_alternatives(
+        &existing,
+        start,
+        duration,
+        location,
+        &check.travel,
+        tz,
+        check.suggestions,
+    );
+    if !alternatives.is_empty() {
+        message.push_str("\n\nFree alternatives:");
+        for time in alternatives {
+            message.push_str(&format!(
+                "\n- {}",
+                crate::timezone::format_local(&time.with_timezone(&tz), "")
+            ));
+        }
+    }
+    message.push_str(
+        "\n\nAsk the user which to use. To book the original time anyway, call again \
+         with allow_conflicts: true.",
+    );
+    Ok(Some(message))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn event(title: &str, start: DateTime<Utc>, minutes: i64, location: &str) -> CalendarEvent {
+        CalendarEvent {
+            id: title.to_string(),
+            title: title.to_string(),
+            start,
+            end: start + Duration::minutes(minutes),
+            location: (!location.is_empty()).then(|| location.to_string()),
+            attendees: vec![],
+        }
+    }
+
+    #[test]
+    fn test_overlap_and_travel_conflicts() {
+        let travel = TravelTimes {
+            default_minutes: 30,
+            routes: vec![TravelRoute {
+                from: "Office".to_string(),
+                to: "Gym".to_string(),
+                minutes: 15,
+            }],
+        };
+        assert_eq!(travel.minutes(Some("gym"), Some("office")), 15);
+        assert_eq!(travel.minutes(Some("Home"), Some("Office")), 30);
+        assert_eq!(travel.minutes(None, Some("Office")), 0);
+
+        let at = |h: u32, m: u32| Utc.with_ymd_and_hms(2026, 3, 2, h, m, 0).unwrap();
+        let existing = vec![
+            event("Standup", at(9, 0), 30, "Office"),
+            event("Lunch", at(12, 0), 60, "Cafe Roma"),
+        ];
+
+        // 9:15 overlaps standup
+        let clash = find_conflicts(&existing, at(9, 15), at(10, 0), None, &travel);
+        assert_eq!(clash.len(), 1);
+        assert_eq!(clash[0].kind, ConflictKind::Overlap);
+
+        // Gym at 9:40 is 10 minutes after standup at the office; 15 are needed
+        let clash = find_conflicts(&existing, at(9, 40), at(10, 40), Some("Gym"), &travel);
+        assert_eq!(
+            clash[0].kind,
+            ConflictKind::TravelTime {
+                needed_minutes: 15,
+                gap_minutes: 10
+            }
+        );
+        assert!(find_conflicts(&existing, at(9, 45), at(10, 45), Some("Gym"), &travel).is_empty());
+
+        // Next free hour-long slots at the office after 11:00, clear of lunch
+        let slots = suggest_alternatives(
+            &existing,
+            at(11, 0),
+            Duration::hours(1),
+            Some("Office"),
+            &travel,
+            Tz::UTC,
+            2,
+        );
+        assert_eq!(slots, vec![at(13, 30), at(14, 0)]);
+    }
+}
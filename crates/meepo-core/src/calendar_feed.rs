@@ -0,0 +1,349 @@
+//! ICS calendar feed
+//!
+//! Publishes what the agent has put on the calendar — events it created, goal
+//! deadlines and upcoming runs of scheduled/one-shot watchers — as an
+//! iCalendar (RFC 5545) document. The gateway serves it at `/calendar.ics` so
+//! any calendar app can subscribe, independent of the platform calendar
+//! provider.
+
+use anyhow::Result;
+use chrono::{DateTime, Duration, NaiveDate, NaiveDateTime, TimeZone, Utc};
+use meepo_knowledge::KnowledgeDb;
+use serde_json::Value;
+use tracing::debug;
+
+/// Entity type agent-created events are stored under
+pub const EVENT_ENTITY_TYPE: &str = "calendar_event";
+
+/// How far ahead recurring watchers are expanded
+const HORIZON_DAYS: i64 = 30;
+
+/// Most occurrences listed per recurring watcher
+const MAX_OCCURRENCES: usize = 50;
+
+/// Length given to watcher runs, which have no duration of their own
+const TASK_MINUTES: i64 = 15;
+
+/// One event in the feed
+#[derive(Debug, Clone, PartialEq)]
+pub struct FeedEvent {
+    /// Stable identifier, so calendar apps update rather than duplicate
+    pub uid: String,
+    pub summary: String,
+    pub description: Option<String>,
+    pub start: DateTime<Utc>,
+    pub end: DateTime<Utc>,
+}
+
+/// Parse an event or deadline time: RFC 3339, a local `YYYY-MM-DD HH:MM[:SS]`
+/// (with `T` or a space) or a bare `YYYY-MM-DD` (start of day). Local times
+/// are in the user's timezone.
+pub fn parse_time(input: &str) -> Option<DateTime<Utc>> {
+    let input = input.trim();
+    if let Ok(time) = DateTime::parse_from_rfc3339(input) {
+        return Some(time.with_timezone(&Utc));
+    }
+    let naive = [
+        "%Y-%m-%dT%H:%M:%S",
+        "%Y-%m-%dT%H:%M",
+        "%Y-%m-%d %H:%M:%S",
+        "%Y-%m-%d %H:%M",
+    ]
+    .iter()
+    .find_map(|fmt| NaiveDateTime::parse_from_str(input, fmt).ok())
+    .or_else(|| {
+        NaiveDate::parse_from_str(input, "%Y-%m-%d")
+            .ok()
+            .and_then(|d| d.and_hms_opt(0, 0, 0))
+    })?;
+    crate::timezone::user_timezone()
+        .from_local_datetime(&naive)
+        .earliest()
+        .map(|t| t.with_timezone(&Utc))
+}
+
+/// Remember an event the agent created so it appears in the feed. Events
+/// whose start can't be parsed (e.g. "next Tuesday") are kept out of the feed.
+pub async fn record_event(
+    db: &KnowledgeDb,
+    summary: &str,
+    start_time: &str,
+    duration_minutes: u64,
+) -> Result<Option<String>> {
+    let Some(start) = parse_time(start_time) else {
+        debug!(
+            "Not publishing event '{}': unparseable start '{}'",
+            summary, start_time
+        );
+        return Ok(None);
+    };
+    let id = db
+        .insert_entity(
+            summary,
+            EVENT_ENTITY_TYPE,
+            Some(serde_json::json!({
+                "start": start.to_rfc3339(),
+                "duration_minutes": duration_minutes,
+                "source": "agent",
+            })),
+        )
+        .await?;
+    Ok(Some(id))
+}
+
+/// Gather every event in the feed as of `now`
+pub async fn collect(db: &KnowledgeDb, now: DateTime<Utc>) -> Result<Vec<FeedEvent>> {
+    let mut events = Vec::new();
+
+    for entity in db.search_entities("", Some(EVENT_ENTITY_TYPE)).await? {
+        let metadata = entity.metadata.as_ref();
+        let Some(start) = metadata
+            .and_then(|m| m.get("start"))
+            .and_then(Value::as_str)
+            .and_then(|s| s.parse::<DateTime<Utc>>().ok())
+        else {
+            continue;
+        };
+        let minutes = metadata
+            .and_then(|m| m.get("duration_minutes"))
+            .and_then(Value::as_i64)
+            .unwrap_or(60);
+        events.push(FeedEvent {
+            uid: format!("event-{}@meepo", entity.id),
+            summary: entity.name.clone(),
+            description: None,
+            start,
+            end: start + Duration::minutes(minutes),
+        });
+    }
+
+    for goal in db.get_active_goals().await? {
+        let Some(deadline) = goal.deadline else {
+            continue;
+        };
+        events.push(FeedEvent {
+            uid: format!("goal-{}@meepo", goal.id),
+            summary: format!("Deadline: {}", goal.description),
+            description: goal.success_criteria.clone(),
+            start: deadline,
+            end: deadline + Duration::minutes(TASK_MINUTES),
+        });
+    }
+
+    let horizon = now + Duration::days(HORIZON_DAYS);
+    for watcher in db.get_active_watchers().await? {
+        let task = watcher
+            .config
+            .get("task")
+            .and_then(Value::as_str)
+            .unwrap_or(&watcher.action)
+            .to_string();
+        let starts: Vec<DateTime<Utc>> = match watcher.kind.as_str() {
+            "scheduled" | "time" => {
+                let Some(schedule) = watcher
+                    .config
+                    .get("cron_expr")
+                    .and_then(Value::as_str)
+                    .and_then(|expr| crate::reports::parse_schedule(expr).ok())
+                else {
+                    continue;
+                };
+                schedule
+                    .after(&now)
+                    .take_while(|t| *t <= horizon)
+                    .take(MAX_OCCURRENCES)
+                    .collect()
+            }
+            "oneshot" => watcher
+                .config
+                .get("at")
+                .and_then(Value::as_str)
+                .and_then(parse_time)
+                .into_iter()
+                .collect(),
+            _ => continue,
+        };
+        for start in starts {
+            events.push(FeedEvent {
+                uid: format!("task-{}-{}@meepo", watcher.id, start.timestamp()),
+                summary: task.clone(),
+                description: Some(watcher.action.clone()),
+                start,
+                end: start + Duration::minutes(TASK_MINUTES),
+            });
+        }
+    }
+
+    events.sort_by_key(|e| e.start);
+    Ok(events)
+}
+
+/// Render events as an iCalendar document
+pub fn render_ics(events: &[FeedEvent], now: DateTime<Utc>) -> String {
+    let stamp = ics_time(now);
+    let mut lines = vec![
+        "BEGIN:VCALENDAR".to_string(),
+        "VERSION:2.0".to_string(),
+        "PRODID:-//meepo//calendar feed//EN".to_string(),
+        "CALSCALE:GREGORIAN".to_string(),
+        "METHOD:PUBLISH".to_string(),
+        "X-WR-CALNAME:Meepo".to_string(),
+    ];
+    for event in events {
+        lines.push("BEGIN:VEVENT".to_string());
+        lines.push(format!("UID:{}", event.uid));
+        lines.push(format!("DTSTAMP:{}", stamp));
+        lines.push(format!("DTSTART:{}", ics_time(event.start)));
+        lines.push(format!("DTEND:{}", ics_time(event.end)));
+        lines.push(format!("SUMMARY:{}", escape(&event.summary)));
+        if let Some(description) = &event.description {
+            lines.push(format!("DESCRIPTION:{}", escape(description)));
+        }
+        lines.push("END:VEVENT".to_string());
+    }
+    lines.push("END:VCALENDAR".to_string());
+
+    lines
+        .iter()
+        .map(|line| fold(line))
+        .collect::<Vec<_>>()
+        .join("\r\n")
+        + "\r\n"
+}
+
+/// Build the feed from the database
+pub async fn build(db: &KnowledgeDb) -> Result<String> {
+    let now = Utc::now();
+    Ok(render_ics(&collect(db, now).await?, now))
+}
+
+fn ics_time(time: DateTime<Utc>) -> String {
+    time.format("%Y%m%dT%H%M%SZ").to_string()
+}
+
+/// Escape a TEXT value (RFC 5545 §3.3.11)
+fn escape(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace("\r\n", "\\n")
+        .replace('\n', "\\n")
+}
+
+/// Fold a content line at 75 octets (RFC 5545 §3.1)
+fn fold(line: &str) -> String {
+    let mut out = String::with_capacity(line.len());
+    let mut width = 0;
+    for c in line.chars() {
+        if width + c.len_utf8() > 75 {
+            out.push_str("\r\n ");
+            width = 1;
+        }
+        out.push(c);
+        width += c.len_utf8();
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_render_ics_escapes_and_folds() {
+        let start = "2026-03-06T17:00:00Z".parse().unwrap();
+        let event = FeedEvent {
+            uid: "event-1@meepo".to_string(),
+            summary: "Review: Q1, Q2; misc".to_string(),
+            description: Some(format!("line one\n{}", "x".repeat(100))),
+            start,
+            end: start + Duration::minutes(30),
+        };
+        let ics = render_ics(&[event], start);
+        assert!(ics.starts_with("BEGIN:VCALENDAR\r\n"));
+        assert!(ics.ends_with("END:VCALENDAR\r\n"));
+        assert!(ics.contains("DTSTART:20260306T170000Z\r\n"));
+        assert!(ics.contains("DTEND:20260306T173000Z\r\n"));
+        assert!(ics.contains("SUMMARY:Review: Q1\\, Q2\\; misc\r\n"));
+        assert!(ics.contains("DESCRIPTION:line one\\nxxx"));
+        assert!(ics.split("\r\n").all(|l| l.len() <= 75));
+    }
+
+    #[tokio::test]
+    async fn test_collect_events_deadlines_and_tasks() {
+        let temp = TempDir::new().unwrap();
+        let db = KnowledgeDb::new(temp.path().join("test.db")).unwrap();
+        let now: DateTime<Utc> = "2026-03-02T09:00:00Z".parse().unwrap();
+
+        assert!(
+            record_event(&db, "Dentist", "2026-03-04T15:00:00Z", 45)
+                .await
+                .unwrap()
+                .is_some()
+        );
+        assert!(
+            record_event(&db, "Lunch", "next Tuesday", 60)
+                .await
+                .unwrap()
+                .is_none()
+        );
+
+        let goal = db
+            .insert_goal("File taxes", 4, 3600, None, None, "user")
+            .await
+            .unwrap();
+        db.set_goal_deadline(&goal, parse_time("2026-04-15T12:00:00Z"))
+            .await
+            .unwrap();
+        db.insert_goal("No deadline", 2, 3600, None, None, "user")
+            .await
+            .unwrap();
+
+        db.insert_watcher(
+            "scheduled",
+            serde_json::json!({"cron_expr": "0 0 8 * * MON", "task": "Weekly planning"}),
+            "Plan the week",
+            "slack",
+        )
+        .await
+        .unwrap();
+        db.insert_watcher(
+            "oneshot",
+            serde_json::json!({"at": "2026-03-03T10:00:00Z", "task": "Call the bank"}),
+            "Remind me to call the bank",
+            "slack",
+        )
+        .await
+        .unwrap();
+        db.insert_watcher(
+            "file",
+            serde_json::json!({"path": "/tmp"}),
+            "Watch tmp",
+            "slack",
+        )
+        .await
+        .unwrap();
+
+        let events = collect(&db, now).await.unwrap();
+        let summaries: Vec<&str> = events.iter().map(|e| e.summary.as_str()).collect();
+        assert_eq!(
+            summaries,
+            vec![
+                "Call the bank",
+                "Dentist",
+                "Weekly planning",
+                "Weekly planning",
+                "Weekly planning",
+                "Weekly planning",
+                "Deadline: File taxes",
+            ]
+        );
+        let dentist = &events[1];
+        assert_eq!(dentist.end - dentist.start, Duration::minutes(45));
+        assert!(events[2].uid != events[3].uid);
+
+        let ics = build(&db).await.unwrap();
+        assert!(ics.contains("SUMMARY:Deadline: File taxes"));
+    }
+}
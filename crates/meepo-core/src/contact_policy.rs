@@ -0,0 +1,427 @@
+//! Contact-level conversation policies
+//!
+//! A policy covers a set of senders and says how to talk to them: what the
+//! agent may not disclose (e.g. calendar details for anyone outside an
+//! allowlist), the tone to use, and an optional auto-reply sent instead of
+//! answering. The first policy whose senders match a message applies.
+//!
+//! Policies are enforced by [`ContactPolicyMiddleware`]: before the model
+//! runs it strips withheld material from the system prompt and drops the
+//! tools that read it; afterwards it checks the reply and withholds it if
+//! it still looks like it discloses something it shouldn't.
+
+use std::sync::LazyLock;
+
+use anyhow::Result;
+use async_trait::async_trait;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use tracing::warn;
+
+use crate::agents::restricted::PERSONAL_KNOWLEDGE_TOOLS;
+use crate::api::ToolDefinition;
+use crate::middleware::{AgentMiddleware, MiddlewareContext};
+
+/// Reply sent instead of one that breaks a policy
+pub const DEFAULT_REFUSAL: &str = "Sorry, that's not something I can share.";
+
+/// What a policy can keep from a contact
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Disclosure {
+    /// Events, meetings and free/busy times
+    Calendar,
+    /// Mail contents, senders and unread counts
+    Email,
+    /// Other people's phone numbers and addresses
+    Contacts,
+    /// The owner's memory and knowledge base
+    Memory,
+}
+
+impl Disclosure {
+    fn label(self) -> &'static str {
+        match self {
+            Disclosure::Calendar => "calendar details (events, meetings, when the user is free)",
+            Disclosure::Email => "the user's email (messages, senders, unread counts)",
+            Disclosure::Contacts => "other people's contact details (phone numbers, addresses)",
+            Disclosure::Memory => "personal facts from the user's memory and knowledge base",
+        }
+    }
+
+    /// Tools that read this kind of information
+    fn tools(self) -> &'static [&'static str] {
+        match self {
+            Disclosure::Calendar => &[
+                "read_calendar",
+                "create_calendar_event",
+                "find_free_time",
+                "schedule_meeting",
+                "reschedule_event",
+                "daily_briefing",
+                "weekly_review",
+                "meeting_prep",
+            ],
+            Disclosure::Email => &[
+                "read_emails",
+                "email_triage",
+                "email_summarize_thread",
+                "email_draft_reply",
+                "daily_briefing",
+            ],
+            Disclosure::Contacts => &[
+                "search_contacts",
+                "relationship_summary",
+                "generate_dossier",
+            ],
+            Disclosure::Memory => PERSONAL_KNOWLEDGE_TOOLS,
+        }
+    }
+
+    /// Live context lines that carry this kind of information
+    fn prompt_lines(self) -> &'static [&'static str] {
+        match self {
+            Disclosure::Calendar => &["- Next event:"],
+            Disclosure::Email => &["- Unread:"],
+            Disclosure::Contacts | Disclosure::Memory => &[],
+        }
+    }
+
+    /// Whether a reply looks like it discloses this kind of information
+    fn leaks(self, text: &str) -> bool {
+        static TIME: LazyLock<Regex> = LazyLock::new(|| {
+            Regex::new(r"(?i)\b(\d{1,2}:\d{2}|\d{1,2}\s?(am|pm))\b").expect("valid regex")
+        });
+        static CALENDAR_WORDS: LazyLock<Regex> = LazyLock::new(|| {
+            Regex::new(r"(?i)\b(meeting|appointment|calendar|schedule[ds]?|event|busy)\b")
+                .expect("valid regex")
+        });
+        static EMAIL_HEADERS: LazyLock<Regex> = LazyLock::new(|| {
+            Regex::new(r"(?im)^\s*(from|subject):\s|\bunread (emails?|messages)\b")
+                .expect("valid regex")
+        });
+        static CONTACT_DETAILS: LazyLock<Regex> = LazyLock::new(|| {
+            Regex::new(r"\+?\d[\d ().-]{7,}\d|[\w.+-]+@[\w-]+\.[\w.]+").expect("valid regex")
+        });
+        match self {
+            Disclosure::Calendar => TIME.is_match(text) && CALENDAR_WORDS.is_match(text),
+            Disclosure::Email => EMAIL_HEADERS.is_match(text),
+            Disclosure::Contacts => CONTACT_DETAILS.is_match(text),
+            // Not checkable in text; enforced by the prompt and tool filter
+            Disclosure::Memory => false,
+        }
+    }
+}
+
+/// How to talk to a set of senders
+#[derive(Debug, Clone, Default)]
+pub struct ContactPolicy {
+    pub name: String,
+    /// `channel:sender` pairs, a bare channel, or `*` for everyone. A
+    /// sender starting with `*` matches by suffix (`email:*@acme.com`).
+    pub senders: Vec<String>,
+    /// What the agent may not tell these senders
+    pub withhold: Vec<Disclosure>,
+    /// Words and phrases a reply may not contain (case-insensitive)
+    pub blocked_terms: Vec<String>,
+    /// Tone to use, e.g. "formal and brief"
+    pub tone: Option<String>,
+    /// Send this instead of answering
+    pub auto_reply: Option<String>,
+    /// Only auto-reply while a quiet Focus mode is on
+    pub auto_reply_when_busy: bool,
+    /// Sent instead of a reply that breaks the policy
+    pub refusal: Option<String>,
+}
+
+impl ContactPolicy {
+    /// Whether the policy covers `sender` on `channel`
+    pub fn matches(&self, channel: &str, sender: &str) -> bool {
+        self.senders.iter().any(|pattern| {
+            let pattern = pattern.trim();
+            if pattern == "*" {
+                return true;
+            }
+            let Some((c, who)) = pattern.split_once(':') else {
+                return pattern.eq_ignore_ascii_case(channel);
+            };
+            if !c.eq_ignore_ascii_case(channel) {
+                return false;
+            }
+            match who.strip_prefix('*') {
+                Some(suffix) => sender.to_lowercase().ends_with(&suffix.to_lowercase()),
+                None => who.eq_ignore_ascii_case(sender),
+            }
+        })
+    }
+
+    fn withholds(&self, what: Disclosure) -> bool {
+        self.withhold.contains(&what)
+    }
+
+    /// Remove withheld material from a system prompt
+    pub fn filter_prompt(&self, prompt: &str) -> String {
+        let mut prompt = if self.withholds(Disclosure::Memory) {
+            strip_section(prompt, "# MEMORY")
+        } else {
+            prompt.to_string()
+        };
+        let markers: Vec<&str> = self
+            .withhold
+            .iter()
+            .flat_map(|w| w.prompt_lines().iter().copied())
+            .collect();
+        if !markers.is_empty() {
+            prompt = prompt
+                .lines()
+                .filter(|line| !markers.iter().any(|m| line.starts_with(m)))
+                .map(|line| format!("{}\n", line))
+                .collect();
+        }
+        prompt
+    }
+
+    /// Whether the policy lets a tool be offered
+    pub fn tool_allowed(&self, tool: &str) -> bool {
+        !self.withhold.iter().any(|w| w.tools().contains(&tool))
+    }
+
+    /// Instructions for the agent
+    pub fn context_section(&self, sender: &str) -> String {
+        let mut section = format!("## Conversation Policy\n\nYou're talking with {}.", sender);
+        if let Some(tone) = self.tone.as_deref().filter(|t| !t.trim().is_empty()) {
+            section.push_str(&format!(" Use this tone: {}.", tone.trim()));
+        }
+        if !self.withhold.is_empty() {
+            let labels: Vec<&str> = self.withhold.iter().map(|w| w.label()).collect();
+            section.push_str(&format!(
+                " Don't share {}. If asked, decline politely without explaining what you know.",
+                labels.join("; ")
+            ));
+        }
+        section.push_str("\n\n");
+        section
+    }
+
+    /// The first withheld category or blocked term a reply discloses
+    pub fn violation(&self, reply: &str) -> Option<String> {
+        if let Some(what) = self.withhold.iter().find(|w| w.leaks(reply)) {
+            return Some(format!("{:?}", what).to_lowercase());
+        }
+        let lower = reply.to_lowercase();
+        self.blocked_terms
+            .iter()
+            .map(|t| t.trim())
+            .find(|t| !t.is_empty() && lower.contains(&t.to_lowercase()))
+            .map(|t| format!("term '{}'", t))
+    }
+
+    fn refusal(&self) -> String {
+        self.refusal
+            .clone()
+            .unwrap_or_else(|| DEFAULT_REFUSAL.to_string())
+    }
+}
+
+/// Drop a `# HEADER` section (up to the next top-level header)
+fn strip_section(prompt: &str, header: &str) -> String {
+    let Some(start) = prompt.find(&format!("{}\n", header)) else {
+        return prompt.to_string();
+    };
+    let rest = &prompt[start + header.len()..];
+    match rest.find("\n# ") {
+        Some(end) => format!("{}{}", &prompt[..start], &rest[end + 1..]),
+        None => prompt[..start].to_string(),
+    }
+}
+
+/// Enforces contact policies around each turn
+pub struct ContactPolicyMiddleware {
+    policies: Vec<ContactPolicy>,
+}
+
+impl ContactPolicyMiddleware {
+    pub fn new(policies: Vec<ContactPolicy>) -> Self {
+        Self { policies }
+    }
+
+    /// The policy for a message, if any. Internal messages have none.
+    pub fn policy_for(&self, channel: &str, sender: &str) -> Option<&ContactPolicy> {
+        if channel == "internal" {
+            return None;
+        }
+        self.policies.iter().find(|p| p.matches(channel, sender))
+    }
+}
+
+#[async_trait]
+impl AgentMiddleware for ContactPolicyMiddleware {
+    fn name(&self) -> &str {
+        "contact_policy"
+    }
+
+    async fn before_agent(&self, ctx: &MiddlewareContext) -> Result<Option<String>> {
+        let Some(policy) = self.policy_for(&ctx.channel, &ctx.sender) else {
+            return Ok(None);
+        };
+        let reply = policy
+            .auto_reply
+            .as_ref()
+            .filter(|r| !r.trim().is_empty())
+            .filter(|_| !policy.auto_reply_when_busy || crate::focus::is_quiet());
+        Ok(reply.cloned())
+    }
+
+    async fn before_prompt(
+        &self,
+        system_prompt: String,
+        mut tools: Vec<ToolDefinition>,
+        ctx: &MiddlewareContext,
+    ) -> Result<(String, Vec<ToolDefinition>)> {
+        let Some(policy) = self.policy_for(&ctx.channel, &ctx.sender) else {
+            return Ok((system_prompt, tools));
+        };
+        tools.retain(|t| policy.tool_allowed(&t.name));
+        let mut prompt = policy.filter_prompt(&system_prompt);
+        prompt.push('\n');
+        prompt.push_str(&policy.context_section(&ctx.sender));
+        Ok((prompt, tools))
+    }
+
+    async fn after_agent(&self, response: String, ctx: &MiddlewareContext) -> Result<String> {
+        let Some(policy) = self.policy_for(&ctx.channel, &ctx.sender) else {
+            return Ok(response);
+        };
+        match policy.violation(&response) {
+            Some(what) => {
+                warn!(
+                    "Withheld a reply to {} under policy '{}' ({})",
+                    ctx.sender, policy.name, what
+                );
+                Ok(policy.refusal())
+            }
+            None => Ok(response),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::Value;
+
+    fn ctx(channel: &str, sender: &str) -> MiddlewareContext {
+        MiddlewareContext {
+            query: "when are you free?".to_string(),
+            channel: channel.to_string(),
+            sender: sender.to_string(),
+            metadata: Value::Null,
+        }
+    }
+
+    fn middleware() -> ContactPolicyMiddleware {
+        ContactPolicyMiddleware::new(vec![
+            ContactPolicy {
+                name: "family".to_string(),
+                senders: vec!["imessage:+15551234567".to_string()],
+                ..Default::default()
+            },
+            ContactPolicy {
+                name: "vendors".to_string(),
+                senders: vec!["email:*@vendor.com".to_string()],
+                auto_reply: Some("Thanks, I'll get back to you.".to_string()),
+                ..Default::default()
+            },
+            ContactPolicy {
+                name: "everyone else".to_string(),
+                senders: vec!["*".to_string()],
+                withhold: vec![Disclosure::Calendar, Disclosure::Memory],
+                tone: Some("polite and brief".to_string()),
+                ..Default::default()
+            },
+        ])
+    }
+
+    fn tool(name: &str) -> ToolDefinition {
+        ToolDefinition {
+            name: name.to_string(),
+            description: String::new(),
+            input_schema: Value::Null,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_policy_routing_and_auto_reply() {
+        let mw = middleware();
+        assert_eq!(
+            mw.policy_for("imessage", "+15551234567").unwrap().name,
+            "family"
+        );
+        assert_eq!(
+            mw.policy_for("email", "Sales@Vendor.com").unwrap().name,
+            "vendors"
+        );
+        assert_eq!(
+            mw.policy_for("slack", "U123").unwrap().name,
+            "everyone else"
+        );
+        assert!(mw.policy_for("internal", "autonomous").is_none());
+
+        let reply = mw
+            .before_agent(&ctx("email", "a@vendor.com"))
+            .await
+            .unwrap();
+        assert_eq!(reply.as_deref(), Some("Thanks, I'll get back to you."));
+        assert!(
+            mw.before_agent(&ctx("slack", "U123"))
+                .await
+                .unwrap()
+                .is_none()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_prompt_filter_and_reply_check() {
+        let mw = middleware();
+        let prompt = "# IDENTITY\n\nMeepo\n\n# MEMORY\n\nLikes sushi\n\n# LIVE CONTEXT\n\n\
+                      - Power: on AC power\n- Next event: \"Dentist\" at 10:00\n\n# INSTRUCTIONS\n\n";
+        let (filtered, tools) = mw
+            .before_prompt(
+                prompt.to_string(),
+                vec![tool("read_calendar"), tool("web_search"), tool("recall")],
+                &ctx("slack", "U123"),
+            )
+            .await
+            .unwrap();
+        assert!(!filtered.contains("sushi"));
+        assert!(!filtered.contains("Dentist"));
+        assert!(filtered.contains("- Power: on AC power"));
+        assert!(filtered.contains("# INSTRUCTIONS"));
+        assert!(filtered.contains("Use this tone: polite and brief."));
+        assert!(filtered.contains("Don't share calendar details"));
+        let names: Vec<&str> = tools.iter().map(|t| t.name.as_str()).collect();
+        assert_eq!(names, vec!["web_search"]);
+
+        let leaked = mw
+            .after_agent(
+                "They have a meeting at 3pm.".to_string(),
+                &ctx("slack", "U123"),
+            )
+            .await
+            .unwrap();
+        assert_eq!(leaked, DEFAULT_REFUSAL);
+        let fine = mw
+            .after_agent("Happy to help!".to_string(), &ctx("slack", "U123"))
+            .await
+            .unwrap();
+        assert_eq!(fine, "Happy to help!");
+        let family = mw
+            .after_agent(
+                "Meeting at 3pm.".to_string(),
+                &ctx("imessage", "+15551234567"),
+            )
+            .await
+            .unwrap();
+        assert_eq!(family, "Meeting at 3pm.");
+    }
+}
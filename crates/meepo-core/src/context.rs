@@ -3,7 +3,15 @@
 use tracing::debug;
 
 /// Build complete system prompt from components
-pub fn build_system_prompt(soul: &str, memory: &str, extra_context: &str) -> String {
+///
+/// `live_context` is the user's current situation (battery, Focus, next
+/// event, ...) from [`crate::live_context`]; empty leaves the section out.
+pub fn build_system_prompt(
+    soul: &str,
+    memory: &str,
+    extra_context: &str,
+    live_context: &str,
+) -> String {
     let mut prompt = String::new();
 
     // Add SOUL first - this is the core identity
@@ -27,18 +35,33 @@ pub fn build_system_prompt(soul: &str, memory: &str, extra_context: &str) -> Str
         prompt.push_str("\n\n");
     }
 
-    // Add current timestamp
+    // Add current timestamp in the user's timezone
+    let now = crate::timezone::now();
     prompt.push_str("# CURRENT TIME\n\n");
-    prompt.push_str(&chrono::Utc::now().to_rfc3339());
+    prompt.push_str(&format!("{} ({})", now.to_rfc3339(), now.timezone().name()));
     prompt.push_str("\n\n");
 
+    // Add live context - what's going on for the user right now
+    if !live_context.is_empty() {
+        prompt.push_str("# LIVE CONTEXT\n\n");
+        prompt.push_str(live_context);
+        prompt.push_str(
+            "\nThis is current; use it instead of asking the user for the time or their schedule.\n\n",
+        );
+    }
+
     // Add instructions
     prompt.push_str("# INSTRUCTIONS\n\n");
     prompt.push_str("You are an autonomous agent with access to powerful tools. ");
     prompt.push_str("Use your tools proactively to help the user. ");
     prompt.push_str("When you learn something important, use the Remember tool to store it. ");
     prompt.push_str("Be concise but thorough. ");
-    prompt.push_str("Always think step-by-step about complex tasks.\n");
+    prompt.push_str("Always think step-by-step about complex tasks. ");
+    prompt.push_str(&crate::i18n::reply_instruction(
+        crate::i18n::language(),
+        false,
+    ));
+    prompt.push('\n');
 
     debug!("Built system prompt ({} chars)", prompt.len());
 
@@ -55,7 +78,7 @@ mod tests {
         let memory = "The user likes Rust";
         let context = "Recent conversation about async programming";
 
-        let prompt = build_system_prompt(soul, memory, context);
+        let prompt = build_system_prompt(soul, memory, context, "");
 
         assert!(prompt.contains("IDENTITY"));
         assert!(prompt.contains("MEMORY"));
@@ -66,7 +89,7 @@ mod tests {
 
     #[test]
     fn test_build_system_prompt_empty() {
-        let prompt = build_system_prompt("", "", "");
+        let prompt = build_system_prompt("", "", "", "");
         assert!(prompt.contains("INSTRUCTIONS"));
         assert!(prompt.contains("CURRENT TIME"));
         // Should NOT contain IDENTITY, MEMORY, or CONTEXT sections
@@ -78,21 +101,21 @@ mod tests {
     #[test]
     fn test_build_system_prompt_partial() {
         // Only soul, no memory or context
-        let prompt = build_system_prompt("I am meepo", "", "");
+        let prompt = build_system_prompt("I am meepo", "", "", "");
         assert!(prompt.contains("IDENTITY"));
         assert!(prompt.contains("meepo"));
         assert!(!prompt.contains("MEMORY"));
         assert!(!prompt.contains("CONTEXT"));
 
         // Only memory
-        let prompt = build_system_prompt("", "User likes Rust", "");
+        let prompt = build_system_prompt("", "User likes Rust", "", "");
         assert!(!prompt.contains("IDENTITY"));
         assert!(prompt.contains("MEMORY"));
         assert!(prompt.contains("Rust"));
         assert!(!prompt.contains("CONTEXT"));
 
         // Only context
-        let prompt = build_system_prompt("", "", "Recent chat");
+        let prompt = build_system_prompt("", "", "Recent chat", "");
         assert!(!prompt.contains("IDENTITY"));
         assert!(!prompt.contains("MEMORY"));
         assert!(prompt.contains("CONTEXT"));
@@ -101,16 +124,27 @@ mod tests {
 
     #[test]
     fn test_build_system_prompt_always_has_time_and_instructions() {
-        let prompt = build_system_prompt("soul", "mem", "ctx");
+        let prompt = build_system_prompt("soul", "mem", "ctx", "");
         assert!(prompt.contains("CURRENT TIME"));
         assert!(prompt.contains("INSTRUCTIONS"));
         assert!(prompt.contains("autonomous agent"));
         assert!(prompt.contains("Remember tool"));
+        assert!(prompt.contains("by default"));
+    }
+
+    #[test]
+    fn test_build_system_prompt_live_context() {
+        let prompt = build_system_prompt("", "", "", "- Next event: \"Standup\" at 10:00\n");
+        assert!(prompt.contains("# LIVE CONTEXT"));
+        assert!(prompt.contains("Standup"));
+        assert!(prompt.find("CURRENT TIME").unwrap() < prompt.find("LIVE CONTEXT").unwrap());
+        assert!(prompt.find("LIVE CONTEXT").unwrap() < prompt.find("INSTRUCTIONS").unwrap());
+        assert!(!build_system_prompt("", "", "", "").contains("LIVE CONTEXT"));
     }
 
     #[test]
     fn test_build_system_prompt_section_order() {
-        let prompt = build_system_prompt("soul", "mem", "ctx");
+        let prompt = build_system_prompt("soul", "mem", "ctx", "");
         let identity_pos = prompt.find("IDENTITY").unwrap();
         let memory_pos = prompt.find("MEMORY").unwrap();
         let context_pos = prompt.find("CONTEXT").unwrap();
@@ -0,0 +1,447 @@
+//! Context deduplication and compression
+//!
+//! Conversation history, knowledge search hits and sender details often
+//! state the same facts, which would then reach the prompt two or three
+//! times. Before the system prompt is built, sentences mostly covered by an
+//! earlier one are dropped. Optionally, sections still longer than a limit
+//! are condensed with map-reduce summarization: each piece is summarized by
+//! a cheap model, then the summaries are merged.
+
+use std::collections::HashSet;
+
+use anyhow::Result;
+use futures_util::future::join_all;
+use serde::{Deserialize, Serialize};
+use tracing::{debug, warn};
+
+use crate::api::{ApiClient, ApiMessage, ContentBlock, MessageContent, Usage};
+use crate::providers::overrides::{self, RequestOverrides};
+
+/// Rough characters per token, for reporting savings
+const CHARS_PER_TOKEN: usize = 4;
+
+/// Configuration for context compression
+#[derive(Debug, Clone)]
+pub struct ContextCompressionConfig {
+    /// Drop sentences that repeat earlier context
+    pub enabled: bool,
+    /// Share of a sentence's words found in one earlier sentence at which it
+    /// counts as a duplicate (0.0 to 1.0)
+    pub similarity_threshold: f32,
+    /// Sentences with fewer significant words are always kept
+    pub min_sentence_words: usize,
+    /// Summarize sections still longer than `section_chars`
+    pub summarize: bool,
+    pub section_chars: usize,
+    /// Model (or alias) for summaries
+    pub model: String,
+}
+
+impl Default for ContextCompressionConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            similarity_threshold: 0.8,
+            min_sentence_words: 4,
+            summarize: false, // opt-in, adds latency
+            section_chars: 6000,
+            model: "haiku".to_string(),
+        }
+    }
+}
+
+/// What compression did to one turn's context
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct CompressionStats {
+    pub original_chars: usize,
+    pub final_chars: usize,
+    pub duplicate_sentences: usize,
+    pub summarized_sections: usize,
+    /// Estimated from the characters removed
+    pub tokens_saved: usize,
+}
+
+/// Deduplicate `context` and, if configured, summarize oversized sections.
+///
+/// Returns the compressed context, what was saved, and the token usage of
+/// any summarization calls. A failed summary leaves its section as it was.
+pub async fn compress_context(
+    api: &ApiClient,
+    context: &str,
+    config: &ContextCompressionConfig,
+) -> (String, CompressionStats, Option<Usage>) {
+    let mut stats = CompressionStats {
+        original_chars: context.len(),
+        ..Default::default()
+    };
+    if !config.enabled {
+        stats.final_chars = context.len();
+        return (context.to_string(), stats, None);
+    }
+
+    let (mut compressed, duplicates) = dedup_sentences(context, config);
+    stats.duplicate_sentences = duplicates;
+
+    let mut usage: Option<Usage> = None;
+    if config.summarize {
+        let mut sections = split_sections(&compressed);
+        let oversized: Vec<usize> = (0..sections.len())
+            .filter(|&i| sections[i].1.len() > config.section_chars)
+            .collect();
+        let summaries = join_all(
+            oversized
+                .iter()
+                .map(|&i| summarize_section(api, &sections[i].1, config)),
+        )
+        .await;
+        for (&i, summary) in oversized.iter().zip(summaries) {
+            match summary {
+                Ok((text, section_usage)) => {
+                    sections[i].1 = format!("\n{}\n\n", text.trim());
+                    stats.summarized_sections += 1;
+                    let total = usage.get_or_insert(Usage {
+                        input_tokens: 0,
+                        output_tokens: 0,
+                    });
+                    total.input_tokens += section_usage.input_tokens;
+                    total.output_tokens += section_usage.output_tokens;
+                }
+                Err(e) => warn!("Context summarization failed, keeping section: {:#}", e),
+            }
+        }
+        compressed = sections
+            .into_iter()
+            .map(|(heading, body)| heading + &body)
+            .collect();
+    }
+
+    stats.final_chars = compressed.len();
+    stats.tokens_saved = stats.original_chars.saturating_sub(stats.final_chars) / CHARS_PER_TOKEN;
+    if stats.tokens_saved > 0 {
+        debug!(
+            "Compressed context {} → {} chars ({} duplicate sentences, {} summarized sections)",
+            stats.original_chars,
+            stats.final_chars,
+            stats.duplicate_sentences,
+            stats.summarized_sections
+        );
+    }
+    (compressed, stats, usage)
+}
+
+/// Drop sentences whose significant words mostly appear in one earlier
+/// sentence. Headings are kept unless their section ends up empty; lines
+/// keep their bullet prefix. Returns the new text and sentences dropped.
+pub fn dedup_sentences(context: &str, config: &ContextCompressionConfig) -> (String, usize) {
+    let mut seen: Vec<HashSet<String>> = Vec::new();
+    let mut removed = 0;
+    let mut lines: Vec<String> = Vec::new();
+
+    for line in context.lines() {
+        let trimmed = line.trim_start();
+        if trimmed.is_empty() || trimmed.starts_with('#') {
+            lines.push(line.to_string());
+            continue;
+        }
+        let body_start = line.len() - trimmed.len()
+            + ["- ", "* "]
+                .iter()
+                .find(|p| trimmed.starts_with(**p))
+                .map_or(0, |p| p.len());
+        let (prefix, body) = line.split_at(body_start);
+
+        let sentences = split_sentences(body);
+        let mut kept = Vec::with_capacity(sentences.len());
+        for sentence in &sentences {
+            let words = significant_words(sentence);
+            if words.len() >= config.min_sentence_words {
+                if seen
+                    .iter()
+                    .any(|s| covered(&words, s, config.similarity_threshold))
+                {
+                    removed += 1;
+                    continue;
+                }
+                seen.push(words);
+            }
+            kept.push(*sentence);
+        }
+        if kept.len() == sentences.len() {
+            lines.push(line.to_string());
+        } else if !kept.is_empty() {
+            lines.push(format!("{}{}", prefix, kept.join(" ")));
+        }
+    }
+
+    if removed == 0 {
+        return (context.to_string(), 0);
+    }
+    let mut text = drop_empty_sections(&lines).join("\n");
+    if context.ends_with('\n') {
+        text.push('\n');
+    }
+    (text, removed)
+}
+
+/// Whether at least `threshold` of `words` appear in `earlier`
+fn covered(words: &HashSet<String>, earlier: &HashSet<String>, threshold: f32) -> bool {
+    let shared = words.intersection(earlier).count();
+    shared as f32 / words.len() as f32 >= threshold
+}
+
+/// Lowercased words of three or more characters, and numbers
+fn significant_words(text: &str) -> HashSet<String> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|w| w.chars().count() > 2 || (!w.is_empty() && w.chars().all(|c| c.is_numeric())))
+        .map(str::to_lowercase)
+        .collect()
+}
+
+/// Split after `.`, `!` or `?` followed by whitespace
+fn split_sentences(text: &str) -> Vec<&str> {
+    let mut sentences = Vec::new();
+    let mut start = 0;
+    let mut chars = text.char_indices().peekable();
+    while let Some((i, c)) = chars.next() {
+        if matches!(c, '.' | '!' | '?')
+            && chars.peek().is_some_and(|(_, next)| next.is_whitespace())
+        {
+            let sentence = text[start..=i].trim();
+            if !sentence.is_empty() {
+                sentences.push(sentence);
+            }
+            start = i + 1;
+        }
+    }
+    let rest = text[start..].trim();
+    if !rest.is_empty() {
+        sentences.push(rest);
+    }
+    sentences
+}
+
+/// Remove headings with nothing but blank lines before the next heading of
+/// the same or higher level
+fn drop_empty_sections(lines: &[String]) -> Vec<String> {
+    let level = |line: &str| {
+        let trimmed = line.trim_start();
+        trimmed
+            .starts_with('#')
+            .then(|| trimmed.chars().take_while(|c| *c == '#').count())
+    };
+    let mut kept: Vec<String> = Vec::with_capacity(lines.len());
+    for (i, line) in lines.iter().enumerate() {
+        // Collapse the blank runs that removed lines leave behind
+        if line.trim().is_empty() && kept.last().is_some_and(|l| l.trim().is_empty()) {
+            continue;
+        }
+        if let Some(depth) = level(line) {
+            let has_content = lines[i + 1..]
+                .iter()
+                .take_while(|l| level(l).is_none_or(|d| d > depth))
+                .any(|l| !l.trim().is_empty() && level(l).is_none());
+            if !has_content {
+                continue;
+            }
+        }
+        kept.push(line.clone());
+    }
+    kept
+}
+
+/// Split into (heading line, body) pairs at `## ` headings; text before the
+/// first heading has an empty heading
+fn split_sections(text: &str) -> Vec<(String, String)> {
+    let mut sections = vec![(String::new(), String::new())];
+    for line in text.split_inclusive('\n') {
+        if line.starts_with("## ") {
+            sections.push((line.to_string(), String::new()));
+        } else if let Some(last) = sections.last_mut() {
+            last.1.push_str(line);
+        }
+    }
+    sections
+}
+
+/// Map: summarize pieces of the section in parallel. Reduce: if the joined
+/// summaries are still too long, summarize them once more.
+async fn summarize_section(
+    api: &ApiClient,
+    section: &str,
+    config: &ContextCompressionConfig,
+) -> Result<(String, Usage)> {
+    let mut usage = Usage {
+        input_tokens: 0,
+        output_tokens: 0,
+    };
+    let pieces = split_pieces(section, config.section_chars.max(500));
+    let summaries = join_all(pieces.iter().map(|p| summarize(api, p, config))).await;
+    let mut combined = Vec::with_capacity(summaries.len());
+    for summary in summaries {
+        let (text, piece_usage) = summary?;
+        usage.input_tokens += piece_usage.input_tokens;
+        usage.output_tokens += piece_usage.output_tokens;
+        combined.push(text);
+    }
+    let mut combined = combined.join("\n");
+    if pieces.len() > 1 && combined.len() > config.section_chars {
+        let (text, reduce_usage) = summarize(api, &combined, config).await?;
+        usage.input_tokens += reduce_usage.input_tokens;
+        usage.output_tokens += reduce_usage.output_tokens;
+        combined = text;
+    }
+    Ok((combined, usage))
+}
+
+/// Pieces of at most about `max_chars`, cut at line boundaries
+fn split_pieces(text: &str, max_chars: usize) -> Vec<String> {
+    let mut pieces = vec![String::new()];
+    for line in text.split_inclusive('\n') {
+        let current = pieces.last_mut().expect("pieces is never empty");
+        if !current.is_empty() && current.len() + line.len() > max_chars {
+            pieces.push(String::new());
+        }
+        pieces
+            .last_mut()
+            .expect("pieces is never empty")
+            .push_str(line);
+    }
+    pieces
+}
+
+async fn summarize(
+    api: &ApiClient,
+    text: &str,
+    config: &ContextCompressionConfig,
+) -> Result<(String, Usage)> {
+    let messages = vec![ApiMessage {
+        role: "user".to_string(),
+        content: MessageContent::Text(text.to_string()),
+    }];
+    let system = "Condense this context for an assistant into short bullet points. Keep every \
+                  distinct fact, name, date, number and open question; drop repetition and \
+                  filler. Reply with the bullet points only.";
+    let response = overrides::scope(
+        RequestOverrides {
+            model: Some(overrides::resolve_alias(&config.model)),
+            ..Default::default()
+        },
+        api.chat(&messages, &[], system),
+    )
+    .await?;
+    let summary: String = response
+        .content
+        .iter()
+        .filter_map(|b| match b {
+            ContentBlock::Text { text } => Some(text.as_str()),
+            _ => None,
+        })
+        .collect();
+    if summary.trim().is_empty() {
+        anyhow::bail!("empty summary");
+    }
+    Ok((summary, response.usage))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::providers::types::{ChatResponseBlock, ChatUsage, StopReason};
+    use crate::providers::{ChatMessage, ChatResponse, LlmProvider, ModelRouter};
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    #[test]
+    fn test_dedup_drops_repeated_facts() {
+        let context = "## Recent Conversation\n\n\
+                       user: Alice moved the launch to March 3rd. Can you update the plan?\n\n\
+                       ## Relevant Knowledge\n\n\
+                       - The launch was moved to March 3rd by Alice.\n\
+                       - Acme Corp is the launch partner for the spring release.\n\n\
+                       ## About the Sender\n\n\
+                       - Alice moved the launch to March 3rd\n";
+        let (deduped, removed) = dedup_sentences(context, &ContextCompressionConfig::default());
+
+        assert_eq!(removed, 2);
+        assert!(deduped.contains("user: Alice moved the launch to March 3rd. Can you"));
+        assert!(deduped.contains("- Acme Corp is the launch partner"));
+        assert!(!deduped.contains("moved to March 3rd by Alice"));
+        // The sender section had nothing left, so its heading goes too
+        assert!(!deduped.contains("About the Sender"));
+        assert!(deduped.contains("## Relevant Knowledge"));
+    }
+
+    #[test]
+    fn test_dedup_keeps_short_and_extended_sentences() {
+        let context = "Name: Bob\nName: Bob\n\
+                       Bob works at Acme on billing.\n\
+                       Bob works at Acme on billing, and leads the payments migration to Stripe.\n";
+        let (deduped, removed) = dedup_sentences(context, &ContextCompressionConfig::default());
+        assert_eq!(removed, 0);
+        assert_eq!(deduped, context);
+    }
+
+    struct ScriptedProvider {
+        calls: Arc<AtomicUsize>,
+    }
+
+    #[async_trait::async_trait]
+    impl LlmProvider for ScriptedProvider {
+        fn provider_name(&self) -> &str {
+            "scripted"
+        }
+        fn model(&self) -> &str {
+            "scripted-1"
+        }
+        async fn chat(
+            &self,
+            _messages: &[ChatMessage],
+            _tools: &[crate::api::ToolDefinition],
+            _system: &str,
+        ) -> Result<ChatResponse> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            Ok(ChatResponse {
+                blocks: vec![ChatResponseBlock::Text {
+                    text: "- condensed".to_string(),
+                }],
+                stop_reason: StopReason::EndTurn,
+                usage: ChatUsage {
+                    input_tokens: 300,
+                    output_tokens: 10,
+                },
+            })
+        }
+    }
+
+    #[tokio::test]
+    async fn test_map_reduce_summarizes_long_sections() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let api = ApiClient::from_router(ModelRouter::single(Box::new(ScriptedProvider {
+            calls: calls.clone(),
+        })));
+        let history: String = (0..40)
+            .map(|i| format!("user: note number {} about topic {}\n", i, i * 7))
+            .collect();
+        let context = format!(
+            "## Recent Conversation\n\n{}\n## Response Style\n\nBrief.\n",
+            history
+        );
+        let config = ContextCompressionConfig {
+            summarize: true,
+            section_chars: 500,
+            ..Default::default()
+        };
+
+        let (compressed, stats, usage) = compress_context(&api, &context, &config).await;
+        assert_eq!(
+            compressed,
+            "## Recent Conversation\n\n- condensed\n- condensed\n- condensed\n\n\
+             ## Response Style\n\nBrief.\n"
+        );
+        assert_eq!(stats.summarized_sections, 1);
+        assert!(stats.tokens_saved > 200);
+        // Three map calls; the joined summaries were short enough to skip reduce
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+        assert_eq!(usage.unwrap().input_tokens, 900);
+    }
+}
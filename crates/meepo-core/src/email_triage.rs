@@ -0,0 +1,902 @@
+//! Email triage
+//!
+//! New-mail watcher events are sorted into urgent, action, FYI and newsletter
+//! by a cheap model. Urgent and action mail is flagged, newsletters are moved
+//! to their own mailbox, and replies to mail that needs one are saved to
+//! Drafts — never sent — so they wait for the user's approval.
+//!
+//! Each sender's category is remembered as an `email_rules` preference. Once
+//! a sender has been classified the same way a few times in a row, or the
+//! user sets a rule for them, the model is skipped for their mail.
+//!
+//! Newsletters and long emails or threads are summarized into three-bullet
+//! briefs, stored as `email_summary` entities, and collected into the evening
+//! digest instead of a notice per email.
+
+use anyhow::{Context, Result};
+use meepo_knowledge::{Entity, KnowledgeDb};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::sync::Arc;
+use tracing::{debug, info, warn};
+
+use crate::api::{ApiClient, ApiMessage, ContentBlock, MessageContent};
+use crate::platform::EmailProvider;
+use crate::providers::overrides::{self, RequestOverrides};
+use crate::usage::AccumulatedUsage;
+
+/// Preference category used for sender rules
+pub const CATEGORY: &str = "email_rules";
+
+/// Prefix of sender rule preference keys
+const RULE_PREFIX: &str = "email_rule:";
+
+/// Entity type email briefs are stored under
+pub const SUMMARY_ENTITY_TYPE: &str = "email_summary";
+
+/// Characters of the body shown to the model
+const MAX_BODY_CHARS: usize = 4000;
+
+/// Characters of the body shown to the model when summarizing
+const MAX_SUMMARY_CHARS: usize = 12000;
+
+/// Bullets per brief
+const BRIEF_BULLETS: usize = 3;
+
+/// What the drafting model answers when no reply is needed
+const NO_REPLY: &str = "NO_REPLY";
+
+/// Triage category of an email
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TriageCategory {
+    /// Needs attention now
+    Urgent,
+    /// Needs a reply or a task done, but not right away
+    Action,
+    /// Worth knowing, nothing to do
+    Fyi,
+    /// Bulk mail: newsletters, promotions, notifications
+    Newsletter,
+}
+
+impl TriageCategory {
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.trim().to_lowercase().as_str() {
+            "urgent" => Some(Self::Urgent),
+            "action" => Some(Self::Action),
+            "fyi" => Some(Self::Fyi),
+            "newsletter" => Some(Self::Newsletter),
+            _ => None,
+        }
+    }
+
+    /// Whether the user should hear about this mail
+    pub fn needs_attention(self) -> bool {
+        matches!(self, Self::Urgent | Self::Action)
+    }
+}
+
+impl std::fmt::Display for TriageCategory {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Urgent => write!(f, "urgent"),
+            Self::Action => write!(f, "action"),
+            Self::Fyi => write!(f, "fyi"),
+            Self::Newsletter => write!(f, "newsletter"),
+        }
+    }
+}
+
+/// Configuration for email triage
+#[derive(Debug, Clone)]
+pub struct EmailTriageConfig {
+    pub enabled: bool,
+    /// Model (or alias such as "haiku") used to classify and draft
+    pub model: String,
+    /// Flag urgent mail
+    pub flag_urgent: bool,
+    /// Flag action mail
+    pub flag_action: bool,
+    /// Mailbox newsletters are moved to (`None` leaves them in the inbox)
+    pub newsletter_mailbox: Option<String>,
+    /// Save draft replies for urgent and action mail
+    pub draft_replies: bool,
+    /// Consecutive matching classifications after which a sender's category
+    /// is trusted without asking the model
+    pub learn_after: u32,
+    /// Summarize newsletters and long emails into briefs for the digest
+    pub summarize: bool,
+    /// Body length (characters) from which an email counts as long
+    pub long_email_chars: usize,
+    /// Messages quoted in a thread from which it counts as long
+    pub long_thread_messages: usize,
+}
+
+impl Default for EmailTriageConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            model: "haiku".to_string(),
+            flag_urgent: true,
+            flag_action: true,
+            newsletter_mailbox: Some("Newsletters".to_string()),
+            draft_replies: true,
+            learn_after: 3,
+            summarize: true,
+            long_email_chars: 3000,
+            long_thread_messages: 4,
+        }
+    }
+}
+
+/// A remembered category for a sender
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SenderRule {
+    pub category: TriageCategory,
+    /// Consecutive classifications that agreed with `category`
+    pub count: u32,
+    /// Set by the user rather than learned
+    #[serde(default)]
+    pub manual: bool,
+}
+
+impl SenderRule {
+    fn applies(&self, learn_after: u32) -> bool {
+        self.manual || self.count >= learn_after
+    }
+}
+
+/// Normalized sender address: the part in angle brackets if present, lowercased
+pub fn sender_address(from: &str) -> String {
+    let from = from.trim();
+    from.rfind('<')
+        .and_then(|start| {
+            from[start + 1..]
+                .find('>')
+                .map(|end| &from[start + 1..start + 1 + end])
+        })
+        .unwrap_or(from)
+        .trim()
+        .to_lowercase()
+}
+
+/// The rule for a sender, if any
+pub async fn get_rule(db: &KnowledgeDb, sender: &str) -> Result<Option<SenderRule>> {
+    let key = format!("{}{}", RULE_PREFIX, sender_address(sender));
+    Ok(db
+        .get_preferences(Some(CATEGORY))
+        .await?
+        .into_iter()
+        .find(|p| p.key == key)
+        .and_then(|p| serde_json::from_value(p.value).ok()))
+}
+
+async fn save_rule(db: &KnowledgeDb, sender: &str, rule: &SenderRule, source: &str) -> Result<()> {
+    db.upsert_preference(
+        CATEGORY,
+        &format!("{}{}", RULE_PREFIX, sender_address(sender)),
+        serde_json::to_value(rule)?,
+        if rule.manual {
+            1.0
+        } else {
+            (rule.count as f64 / 5.0).min(1.0)
+        },
+        Some(source),
+    )
+    .await?;
+    Ok(())
+}
+
+/// Set a rule for a sender on the user's behalf
+pub async fn set_rule(db: &KnowledgeDb, sender: &str, category: TriageCategory) -> Result<()> {
+    let rule = SenderRule {
+        category,
+        count: 0,
+        manual: true,
+    };
+    save_rule(db, sender, &rule, "set by user").await
+}
+
+/// Remove a sender's rule. Returns whether there was one.
+pub async fn forget_rule(db: &KnowledgeDb, sender: &str) -> Result<bool> {
+    db.delete_preference(&format!("{}{}", RULE_PREFIX, sender_address(sender)))
+        .await
+}
+
+/// All sender rules, as (address, rule) pairs
+pub async fn list_rules(db: &KnowledgeDb) -> Result<Vec<(String, SenderRule)>> {
+    let mut rules: Vec<(String, SenderRule)> = db
+        .get_preferences(Some(CATEGORY))
+        .await?
+        .into_iter()
+        .filter_map(|p| {
+            let sender = p.key.strip_prefix(RULE_PREFIX)?.to_string();
+            Some((sender, serde_json::from_value(p.value).ok()?))
+        })
+        .collect();
+    rules.sort_by(|a, b| a.0.cmp(&b.0));
+    Ok(rules)
+}
+
+/// An email from a new-mail watcher event
+#[derive(Debug, Clone, PartialEq)]
+pub struct IncomingEmail {
+    pub from: String,
+    pub subject: String,
+    pub body: String,
+}
+
+impl IncomingEmail {
+    /// Read an `email_received` watcher payload
+    pub fn from_payload(payload: &Value) -> Option<Self> {
+        let field = |name: &str| payload.get(name).and_then(Value::as_str).map(String::from);
+        Some(Self {
+            from: field("from")?,
+            subject: field("subject").unwrap_or_default(),
+            body: field("body").unwrap_or_default(),
+        })
+    }
+}
+
+/// Number of messages in an email, counting quoted replies
+/// ("On ... wrote:" and "-----Original Message-----" separators)
+pub fn thread_length(body: &str) -> usize {
+    1 + body
+        .lines()
+        .map(str::trim)
+        .filter(|line| {
+            (line.starts_with("On ") && line.ends_with("wrote:"))
+                || line.eq_ignore_ascii_case("-----Original Message-----")
+        })
+        .count()
+}
+
+/// A stored email brief
+#[derive(Debug, Clone, PartialEq)]
+pub struct EmailBrief {
+    pub id: String,
+    pub from: String,
+    pub subject: String,
+    pub bullets: Vec<String>,
+}
+
+impl EmailBrief {
+    fn from_entity(entity: &Entity) -> Option<Self> {
+        let metadata = entity.metadata.as_ref()?;
+        Some(Self {
+            id: entity.id.clone(),
+            from: metadata.get("from")?.as_str()?.to_string(),
+            subject: entity.name.clone(),
+            bullets: metadata
+                .get("bullets")
+                .and_then(|b| serde_json::from_value(b.clone()).ok())
+                .unwrap_or_default(),
+        })
+    }
+}
+
+/// Store a brief in the knowledge base, pending the next digest
+pub async fn store_brief(
+    db: &KnowledgeDb,
+    email: &IncomingEmail,
+    category: TriageCategory,
+    bullets: &[String],
+) -> Result<String> {
+    let name = if email.subject.trim().is_empty() {
+        "(no subject)"
+    } else {
+        email.subject.trim()
+    };
+    db.insert_entity(
+        name,
+        SUMMARY_ENTITY_TYPE,
+        Some(serde_json::json!({
+            "from": email.from,
+            "category": category,
+            "bullets": bullets,
+            "digested": false,
+        })),
+    )
+    .await
+}
+
+/// Briefs not yet included in a digest, oldest first. They are marked as
+/// digested, so each brief is handed out once.
+pub async fn take_undigested_briefs(db: &KnowledgeDb) -> Result<Vec<EmailBrief>> {
+    let mut briefs = Vec::new();
+    for entity in db.search_entities("", Some(SUMMARY_ENTITY_TYPE)).await? {
+        let Some(mut metadata) = entity.metadata.clone() else {
+            continue;
+        };
+        if metadata.get("digested").and_then(Value::as_bool) != Some(false) {
+            continue;
+        }
+        if let Some(brief) = EmailBrief::from_entity(&entity) {
+            briefs.push(brief);
+        }
+        metadata["digested"] = Value::Bool(true);
+        db.update_entity_metadata(&entity.id, metadata).await?;
+    }
+    briefs.reverse();
+    Ok(briefs)
+}
+
+/// What triage did with an email
+#[derive(Debug, Clone)]
+pub struct TriageOutcome {
+    pub category: TriageCategory,
+    pub reason: String,
+    /// Category came from a sender rule rather than the model
+    pub from_rule: bool,
+    pub flagged: bool,
+    pub moved_to: Option<String>,
+    pub draft_saved: bool,
+    /// Bullets of the brief saved for the digest, if the email was summarized
+    pub brief: Option<Vec<String>>,
+    pub usage: AccumulatedUsage,
+}
+
+impl TriageOutcome {
+    /// Whether to tell the user now rather than in the digest. Summarized
+    /// mail waits for the digest unless it's urgent.
+    pub fn should_notify(&self) -> bool {
+        match self.category {
+            TriageCategory::Urgent => true,
+            TriageCategory::Action => self.brief.is_none(),
+            _ => false,
+        }
+    }
+
+    /// One-paragraph notice for the user
+    pub fn summary(&self, email: &IncomingEmail) -> String {
+        let mut out = format!(
+            "📬 [{}] {} — {}",
+            self.category.to_string().to_uppercase(),
+            email.from,
+            email.subject
+        );
+        if !self.reason.is_empty() {
+            out.push_str(&format!("\n{}", self.reason));
+        }
+        if self.draft_saved {
+            out.push_str("\nA draft reply is waiting in Drafts for your approval.");
+        }
+        out
+    }
+}
+
+/// Classifies new mail and files it through the platform email provider
+pub struct EmailTriager {
+    config: EmailTriageConfig,
+    db: Arc<KnowledgeDb>,
+    provider: Arc<dyn EmailProvider>,
+}
+
+impl EmailTriager {
+    pub fn new(
+        config: EmailTriageConfig,
+        db: Arc<KnowledgeDb>,
+        provider: Arc<dyn EmailProvider>,
+    ) -> Self {
+        Self {
+            config,
+            db,
+            provider,
+        }
+    }
+
+    /// Model ID triage requests are sent to
+    pub fn model(&self) -> String {
+        overrides::resolve_alias(&self.config.model)
+    }
+
+    /// Triage one email
+    pub async fn triage(&self, api: &ApiClient, email: &IncomingEmail) -> Result<TriageOutcome> {
+        let mut usage = AccumulatedUsage::new();
+        let rule = get_rule(&self.db, &email.from).await?;
+
+        let (category, reason, from_rule) = match &rule {
+            Some(rule) if rule.applies(self.config.learn_after) => {
+                debug!("Triaging mail from {} by rule", email.from);
+                (rule.category, String::new(), true)
+            }
+            _ => {
+                let (category, reason) = self.classify(api, email, &mut usage).await?;
+                self.learn(&email.from, rule, category).await?;
+                (category, reason, false)
+            }
+        };
+
+        let sender = sender_address(&email.from);
+        let mut flagged = false;
+        let flag = match category {
+            TriageCategory::Urgent => self.config.flag_urgent,
+            TriageCategory::Action => self.config.flag_action,
+            _ => false,
+        };
+        if flag {
+            match self
+                .provider
+                .flag_email(&sender, &email.subject, true)
+                .await
+            {
+                Ok(_) => flagged = true,
+                Err(e) => warn!("Failed to flag '{}': {}", email.subject, e),
+            }
+        }
+
+        let mut moved_to = None;
+        if category == TriageCategory::Newsletter
+            && let Some(mailbox) = &self.config.newsletter_mailbox
+        {
+            match self
+                .provider
+                .move_email(&sender, &email.subject, mailbox)
+                .await
+            {
+                Ok(_) => moved_to = Some(mailbox.clone()),
+                Err(e) => warn!("Failed to move '{}' to {}: {}", email.subject, mailbox, e),
+            }
+        }
+
+        let mut draft_saved = false;
+        if self.config.draft_replies
+            && category.needs_attention()
+            && let Some(draft) = self.draft_reply(api, email, &mut usage).await?
+        {
+            let subject = if email.subject.to_lowercase().starts_with("re:") {
+                email.subject.clone()
+            } else {
+                format!("Re: {}", email.subject)
+            };
+            match self
+                .provider
+                .save_draft(&sender, &subject, &draft, Some(&email.subject))
+                .await
+            {
+                Ok(_) => draft_saved = true,
+                Err(e) => warn!("Failed to save draft reply to '{}': {}", email.subject, e),
+            }
+        }
+
+        let mut brief = None;
+        if self.needs_summary(category, email) {
+            let bullets = self.summarize(api, email, &mut usage).await?;
+            if !bullets.is_empty() {
+                store_brief(&self.db, email, category, &bullets).await?;
+                brief = Some(bullets);
+            }
+        }
+
+        info!(
+            "Triaged '{}' from {} as {}{}",
+            email.subject,
+            email.from,
+            category,
+            if from_rule { " (sender rule)" } else { "" }
+        );
+        Ok(TriageOutcome {
+            category,
+            reason,
+            from_rule,
+            flagged,
+            moved_to,
+            draft_saved,
+            brief,
+            usage,
+        })
+    }
+
+    /// Newsletters and long emails or threads get a brief
+    fn needs_summary(&self, category: TriageCategory, email: &IncomingEmail) -> bool {
+        self.config.summarize
+            && (category == TriageCategory::Newsletter
+                || email.body.chars().count() >= self.config.long_email_chars
+                || thread_length(&email.body) >= self.config.long_thread_messages)
+    }
+
+    async fn summarize(
+        &self,
+        api: &ApiClient,
+        email: &IncomingEmail,
+        usage: &mut AccumulatedUsage,
+    ) -> Result<Vec<String>> {
+        let prompt = format!(
+            "Summarize this email in exactly {} short bullet points covering what matters to \
+             the user: key news, decisions, asks and dates. For a thread, summarize where it \
+             ended up, not each message. Output one bullet per line starting with \"- \" and \
+             nothing else.\n\nFrom: {}\nSubject: {}\n\n{}",
+            BRIEF_BULLETS,
+            email.from,
+            email.subject,
+            email
+                .body
+                .chars()
+                .take(MAX_SUMMARY_CHARS)
+                .collect::<String>()
+        );
+        let text = self
+            .ask(api, prompt, "You write concise email briefs.", usage)
+            .await
+            .context("Failed to summarize email")?;
+        Ok(parse_bullets(&text))
+    }
+
+    /// Update the sender's learned rule with a new classification
+    async fn learn(
+        &self,
+        sender: &str,
+        rule: Option<SenderRule>,
+        category: TriageCategory,
+    ) -> Result<()> {
+        let rule = match rule {
+            Some(rule) if rule.manual => return Ok(()),
+            Some(rule) if rule.category == category => SenderRule {
+                count: rule.count + 1,
+                ..rule
+            },
+            _ => SenderRule {
+                category,
+                count: 1,
+                manual: false,
+            },
+        };
+        save_rule(&self.db, sender, &rule, "email triage").await
+    }
+
+    async fn classify(
+        &self,
+        api: &ApiClient,
+        email: &IncomingEmail,
+        usage: &mut AccumulatedUsage,
+    ) -> Result<(TriageCategory, String)> {
+        let prompt = format!(
+            r#"Classify this email for the user's inbox.
+
+From: {}
+Subject: {}
+
+{}
+
+Categories:
+- urgent: needs the user's attention within hours (deadlines today, outages, time-sensitive requests from real people)
+- action: needs a reply or a task done, but not right away
+- fyi: worth knowing, nothing to do
+- newsletter: bulk mail such as newsletters, promotions and automated notifications
+
+Respond with JSON only: {{"category": "<urgent|action|fyi|newsletter>", "reason": "<one short sentence>"}}"#,
+            email.from,
+            email.subject,
+            truncate(&email.body)
+        );
+        let text = self
+            .ask(
+                api,
+                prompt,
+                "You are an email triage system. Output only valid JSON.",
+                usage,
+            )
+            .await
+            .context("Failed to classify email")?;
+
+        Ok(parse_classification(&text).unwrap_or_else(|| {
+            debug!("Unparseable triage response, treating as FYI: {:?}", text);
+            (TriageCategory::Fyi, String::new())
+        }))
+    }
+
+    async fn draft_reply(
+        &self,
+        api: &ApiClient,
+        email: &IncomingEmail,
+        usage: &mut AccumulatedUsage,
+    ) -> Result<Option<String>> {
+        let prompt = format!(
+            "Draft a reply to this email on the user's behalf. Be brief and match the sender's \
+             tone. Leave placeholders like [time] for facts you don't know rather than inventing \
+             them. Output only the reply body, without a subject line. If the email doesn't \
+             need a reply, output exactly {}.\n\nFrom: {}\nSubject: {}\n\n{}",
+            NO_REPLY,
+            email.from,
+            email.subject,
+            truncate(&email.body)
+        );
+        let text = self
+            .ask(
+                api,
+                prompt,
+                "You draft email replies for the user to review before sending.",
+                usage,
+            )
+            .await
+            .context("Failed to draft email reply")?;
+        let text = text.trim();
+        Ok((!text.is_empty() && !text.contains(NO_REPLY)).then(|| text.to_string()))
+    }
+
+    /// Send one prompt to the triage model and return the text of the reply
+    async fn ask(
+        &self,
+        api: &ApiClient,
+        prompt: String,
+        system: &str,
+        usage: &mut AccumulatedUsage,
+    ) -> Result<String> {
+        let messages = vec![ApiMessage {
+            role: "user".to_string(),
+            content: MessageContent::Text(prompt),
+        }];
+        let response = overrides::scope(
+            RequestOverrides {
+                model: Some(self.model()),
+                ..Default::default()
+            },
+            api.chat(&messages, &[], system),
+        )
+        .await?;
+        usage.add(response.usage.input_tokens, response.usage.output_tokens);
+
+        Ok(response
+            .content
+            .iter()
+            .filter_map(|b| match b {
+                ContentBlock::Text { text } => Some(text.as_str()),
+                _ => None,
+            })
+            .collect())
+    }
+}
+
+fn truncate(body: &str) -> String {
+    body.chars().take(MAX_BODY_CHARS).collect()
+}
+
+/// Up to three bullets from a model response, markers stripped
+fn parse_bullets(text: &str) -> Vec<String> {
+    text.lines()
+        .map(str::trim)
+        .filter_map(|line| {
+            let rest = line
+                .strip_prefix(['-', '*', '•'])
+                .or_else(|| {
+                    let digits = line.trim_start_matches(|c: char| c.is_ascii_digit());
+                    (digits.len() < line.len())
+                        .then(|| digits.strip_prefix(['.', ')']))
+                        .flatten()
+                })?
+                .trim();
+            (!rest.is_empty()).then(|| rest.to_string())
+        })
+        .take(BRIEF_BULLETS)
+        .collect()
+}
+
+/// Parse `{"category": ..., "reason": ...}` out of a model response
+fn parse_classification(text: &str) -> Option<(TriageCategory, String)> {
+    let start = text.find('{')?;
+    let end = text.rfind('}')?;
+    let value: Value = serde_json::from_str(text.get(start..=end)?).ok()?;
+    let category = TriageCategory::parse(value.get("category")?.as_str()?)?;
+    let reason = value
+        .get("reason")
+        .and_then(Value::as_str)
+        .unwrap_or_default()
+        .to_string();
+    Some((category, reason))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::providers::types::{ChatResponseBlock, ChatUsage, StopReason};
+    use crate::providers::{ChatMessage, ChatResponse, LlmProvider, ModelRouter};
+    use std::sync::Mutex;
+    use tempfile::TempDir;
+
+    /// Classifies everything as action, drafts a fixed reply and writes a
+    /// fixed brief
+    struct ScriptedProvider {
+        calls: Arc<Mutex<Vec<String>>>,
+    }
+
+    #[async_trait::async_trait]
+    impl LlmProvider for ScriptedProvider {
+        fn provider_name(&self) -> &str {
+            "scripted"
+        }
+        fn model(&self) -> &str {
+            "scripted-1"
+        }
+        async fn chat(
+            &self,
+            _messages: &[ChatMessage],
+            _tools: &[crate::api::ToolDefinition],
+            system: &str,
+        ) -> Result<ChatResponse> {
+            self.calls.lock().unwrap().push(system.to_string());
+            let text = if system.contains("triage") {
+                r#"Sure: {"category": "action", "reason": "Asks for a review by Friday"}"#
+            } else if system.contains("briefs") {
+                "- Rust 2.0 announced\n- Async closures stable\n- Conference in May\n- Extra"
+            } else {
+                "Thanks, I'll take a look before Friday."
+            };
+            Ok(ChatResponse {
+                blocks: vec![ChatResponseBlock::Text {
+                    text: text.to_string(),
+                }],
+                stop_reason: StopReason::EndTurn,
+                usage: ChatUsage {
+                    input_tokens: 100,
+                    output_tokens: 20,
+                },
+            })
+        }
+    }
+
+    #[derive(Default)]
+    struct RecordingMail {
+        calls: Mutex<Vec<String>>,
+    }
+
+    #[async_trait::async_trait]
+    impl EmailProvider for RecordingMail {
+        async fn read_emails(
+            &self,
+            _limit: u64,
+            _mailbox: &str,
+            _search: Option<&str>,
+        ) -> Result<String> {
+            Ok(String::new())
+        }
+        async fn send_email(
+            &self,
+            to: &str,
+            _subject: &str,
+            _body: &str,
+            _cc: Option<&str>,
+            _in_reply_to: Option<&str>,
+        ) -> Result<String> {
+            self.calls.lock().unwrap().push(format!("send {}", to));
+            Ok("sent".to_string())
+        }
+        async fn flag_email(&self, sender: &str, subject: &str, flagged: bool) -> Result<String> {
+            self.calls
+                .lock()
+                .unwrap()
+                .push(format!("flag {} {} {}", sender, subject, flagged));
+            Ok("flagged".to_string())
+        }
+        async fn move_email(&self, sender: &str, subject: &str, mailbox: &str) -> Result<String> {
+            self.calls
+                .lock()
+                .unwrap()
+                .push(format!("move {} {} {}", sender, subject, mailbox));
+            Ok("moved".to_string())
+        }
+        async fn save_draft(
+            &self,
+            to: &str,
+            subject: &str,
+            body: &str,
+            _in_reply_to: Option<&str>,
+        ) -> Result<String> {
+            self.calls
+                .lock()
+                .unwrap()
+                .push(format!("draft {} {} {}", to, subject, body));
+            Ok("saved".to_string())
+        }
+    }
+
+    #[test]
+    fn test_parse_helpers() {
+        assert_eq!(
+            sender_address("Alice <Alice@Example.com>"),
+            "alice@example.com"
+        );
+        assert_eq!(sender_address(" bob@example.com "), "bob@example.com");
+        assert_eq!(
+            parse_classification(r#"{"category": "Newsletter", "reason": "Weekly digest"}"#),
+            Some((TriageCategory::Newsletter, "Weekly digest".to_string()))
+        );
+        assert!(parse_classification(r#"{"category": "spam"}"#).is_none());
+        assert!(parse_classification("no json").is_none());
+        let email = IncomingEmail::from_payload(&serde_json::json!({
+            "from": "a@b.c", "subject": "Hi"
+        }))
+        .unwrap();
+        assert_eq!(email.body, "");
+        assert!(IncomingEmail::from_payload(&serde_json::json!({"subject": "Hi"})).is_none());
+
+        assert_eq!(thread_length("Hi"), 1);
+        assert_eq!(
+            thread_length("Sounds good\n\nOn Mon, Bob wrote:\n> ok\n-----Original Message-----"),
+            3
+        );
+        assert_eq!(
+            parse_bullets("Here you go:\n- one\n* two\n3. three\n- four"),
+            vec!["one", "two", "three"]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_triage_flags_drafts_and_learns_sender() {
+        let temp = TempDir::new().unwrap();
+        let db = Arc::new(KnowledgeDb::new(temp.path().join("test.db")).unwrap());
+        let llm_calls = Arc::new(Mutex::new(Vec::new()));
+        let api = ApiClient::from_router(ModelRouter::single(Box::new(ScriptedProvider {
+            calls: llm_calls.clone(),
+        })));
+        let mail = Arc::new(RecordingMail::default());
+        let triager = EmailTriager::new(
+            EmailTriageConfig {
+                enabled: true,
+                learn_after: 2,
+                ..Default::default()
+            },
+            db.clone(),
+            mail.clone(),
+        );
+        let email = IncomingEmail {
+            from: "Carol <carol@example.com>".to_string(),
+            subject: "Design review".to_string(),
+            body: "Can you review the doc by Friday?".to_string(),
+        };
+
+        let outcome = triager.triage(&api, &email).await.unwrap();
+        assert_eq!(outcome.category, TriageCategory::Action);
+        assert!(outcome.flagged && outcome.draft_saved && !outcome.from_rule);
+        assert_eq!(outcome.usage.api_calls, 2);
+        assert!(outcome.summary(&email).contains("[ACTION] Carol"));
+        assert!(outcome.should_notify() && outcome.brief.is_none());
+        assert_eq!(
+            *mail.calls.lock().unwrap(),
+            vec![
+                "flag carol@example.com Design review true".to_string(),
+                "draft carol@example.com Re: Design review Thanks, I'll take a look before Friday."
+                    .to_string(),
+            ]
+        );
+
+        // The second agreeing classification makes the rule stick...
+        triager.triage(&api, &email).await.unwrap();
+        let rule = get_rule(&db, "carol@example.com").await.unwrap().unwrap();
+        assert_eq!(rule.count, 2);
+
+        // ...so the third email skips the classifier
+        llm_calls.lock().unwrap().clear();
+        let outcome = triager.triage(&api, &email).await.unwrap();
+        assert!(outcome.from_rule);
+        assert_eq!(llm_calls.lock().unwrap().len(), 1);
+
+        // A user rule overrides what was learned; newsletters get moved, not drafted
+        set_rule(&db, "CAROL@example.com", TriageCategory::Newsletter)
+            .await
+            .unwrap();
+        mail.calls.lock().unwrap().clear();
+        let outcome = triager.triage(&api, &email).await.unwrap();
+        assert_eq!(outcome.category, TriageCategory::Newsletter);
+        assert_eq!(outcome.moved_to.as_deref(), Some("Newsletters"));
+        assert_eq!(outcome.brief.as_ref().map(Vec::len), Some(3));
+        assert!(!outcome.should_notify());
+        assert_eq!(
+            *mail.calls.lock().unwrap(),
+            vec!["move carol@example.com Design review Newsletters".to_string()]
+        );
+
+        // Briefs go to one digest only
+        let briefs = take_undigested_briefs(&db).await.unwrap();
+        assert_eq!(briefs.len(), 1);
+        assert_eq!(briefs[0].subject, "Design review");
+        assert_eq!(briefs[0].bullets[0], "Rust 2.0 announced");
+        assert!(take_undigested_briefs(&db).await.unwrap().is_empty());
+
+        let rules = list_rules(&db).await.unwrap();
+        assert_eq!(rules.len(), 1);
+        assert!(rules[0].1.manual);
+        assert!(forget_rule(&db, "carol@example.com").await.unwrap());
+        assert!(list_rules(&db).await.unwrap().is_empty());
+    }
+}
@@ -0,0 +1,358 @@
+//! A/B experiments on the system prompt (SOUL)
+//!
+//! An experiment pits the configured SOUL (variant A) against an alternative
+//! prompt (variant B). Each conversation (`channel:sender`) is randomly
+//! assigned a variant the first time it talks to the agent, and keeps it for
+//! the rest of the experiment (stored as an `experiments` user preference).
+//! Responses record the experiment and variant in their conversation
+//! metadata, so 👍/👎 feedback and tool failures can be compared per variant
+//! with a two-proportion z-test.
+
+use anyhow::Result;
+use meepo_knowledge::{Conversation, KnowledgeDb};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tracing::info;
+
+use crate::style::sender_scope;
+
+/// Preference category used for variant assignments
+pub const CATEGORY: &str = "experiments";
+
+/// Minimum samples per variant before a p-value is reported
+const MIN_SAMPLES: u64 = 10;
+
+/// Significance level used in reports
+const ALPHA: f64 = 0.05;
+
+/// Prompt variant in an experiment
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Variant {
+    /// The configured SOUL
+    A,
+    /// The alternative prompt
+    B,
+}
+
+impl std::fmt::Display for Variant {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::A => write!(f, "a"),
+            Self::B => write!(f, "b"),
+        }
+    }
+}
+
+/// A running prompt experiment
+#[derive(Debug, Clone)]
+pub struct PromptExperiment {
+    /// Name used to tag responses and assignments
+    pub name: String,
+    /// Prompt used in place of SOUL for variant B
+    pub variant_b: String,
+    /// Share of conversations assigned to variant B (0.0-1.0)
+    pub split: f64,
+}
+
+impl PromptExperiment {
+    /// The conversation's variant, assigning one at random on first contact
+    pub async fn assign(&self, db: &KnowledgeDb, channel: &str, sender: &str) -> Result<Variant> {
+        let key = format!("{}:{}", self.name, sender_scope(channel, sender));
+        let prefs = db.get_preferences(Some(CATEGORY)).await?;
+        if let Some(variant) = prefs
+            .into_iter()
+            .find(|p| p.key == key)
+            .and_then(|p| serde_json::from_value(p.value).ok())
+        {
+            return Ok(variant);
+        }
+
+        let roll = (uuid::Uuid::new_v4().as_u128() % 10_000) as f64 / 10_000.0;
+        let variant = if roll < self.split {
+            Variant::B
+        } else {
+            Variant::A
+        };
+        db.upsert_preference(
+            CATEGORY,
+            &key,
+            serde_json::to_value(variant)?,
+            1.0,
+            Some("random assignment"),
+        )
+        .await?;
+        info!(
+            "Assigned {} on {} to variant {} of experiment '{}'",
+            sender, channel, variant, self.name
+        );
+        Ok(variant)
+    }
+
+    /// System prompt for a variant
+    pub fn soul<'a>(&'a self, variant: Variant, soul: &'a str) -> &'a str {
+        match variant {
+            Variant::A => soul,
+            Variant::B => &self.variant_b,
+        }
+    }
+}
+
+/// Outcomes for one variant
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct VariantStats {
+    /// Responses given under this variant
+    pub responses: u64,
+    pub positive: u64,
+    pub negative: u64,
+    /// Responses where every tool call succeeded and that weren't rated 👎
+    pub successes: u64,
+}
+
+impl VariantStats {
+    pub fn rated(&self) -> u64 {
+        self.positive + self.negative
+    }
+
+    pub fn satisfaction(&self) -> Option<f64> {
+        (self.rated() > 0).then(|| self.positive as f64 / self.rated() as f64)
+    }
+
+    pub fn success_rate(&self) -> Option<f64> {
+        (self.responses > 0).then(|| self.successes as f64 / self.responses as f64)
+    }
+
+    fn add(&mut self, response: &Conversation) {
+        let metadata = response.metadata.as_ref();
+        let rating = metadata
+            .and_then(|m| m.pointer("/feedback/rating"))
+            .and_then(Value::as_i64)
+            .unwrap_or(0);
+        let tool_errors = metadata
+            .and_then(|m| m.get("tool_errors"))
+            .and_then(Value::as_u64)
+            .unwrap_or(0);
+
+        self.responses += 1;
+        match rating {
+            r if r > 0 => self.positive += 1,
+            r if r < 0 => self.negative += 1,
+            _ => {}
+        }
+        if tool_errors == 0 && rating >= 0 {
+            self.successes += 1;
+        }
+    }
+}
+
+/// Comparison of the two variants of an experiment
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ExperimentReport {
+    pub name: String,
+    pub a: VariantStats,
+    pub b: VariantStats,
+    /// Two-sided p-value for the difference in satisfaction
+    pub satisfaction_p: Option<f64>,
+    /// Two-sided p-value for the difference in task success
+    pub success_p: Option<f64>,
+}
+
+impl ExperimentReport {
+    /// Build a report from the responses stored for an experiment
+    pub async fn load(db: &KnowledgeDb, name: &str) -> Result<Self> {
+        let responses = db
+            .get_conversations_by_metadata("$.experiment.name", name)
+            .await?;
+        let mut report = Self {
+            name: name.to_string(),
+            ..Default::default()
+        };
+        for response in &responses {
+            let variant = response
+                .metadata
+                .as_ref()
+                .and_then(|m| m.pointer("/experiment/variant"))
+                .and_then(|v| serde_json::from_value(v.clone()).ok());
+            match variant {
+                Some(Variant::A) => report.a.add(response),
+                Some(Variant::B) => report.b.add(response),
+                None => {}
+            }
+        }
+
+        report.satisfaction_p = two_proportion_p_value(
+            report.a.positive,
+            report.a.rated(),
+            report.b.positive,
+            report.b.rated(),
+        );
+        report.success_p = two_proportion_p_value(
+            report.a.successes,
+            report.a.responses,
+            report.b.successes,
+            report.b.responses,
+        );
+        Ok(report)
+    }
+
+    /// Human-readable report
+    pub fn format(&self) -> String {
+        fn pct(value: Option<f64>) -> String {
+            value
+                .map(|v| format!("{:.0}%", v * 100.0))
+                .unwrap_or_else(|| "-".to_string())
+        }
+        fn verdict(p: Option<f64>, a: Option<f64>, b: Option<f64>) -> String {
+            match (p, a, b) {
+                (Some(p), Some(a), Some(b)) if p < ALPHA => format!(
+                    "p = {:.3} — variant {} is better",
+                    p,
+                    if b > a { "B" } else { "A" }
+                ),
+                (Some(p), _, _) => format!("p = {:.3} — no significant difference", p),
+                _ => format!("not enough data (need {} per variant)", MIN_SAMPLES),
+            }
+        }
+
+        let mut out = format!("Experiment '{}'\n\n", self.name);
+        out.push_str("  Variant  Responses  Rated  👍    👎    Satisfaction  Success\n");
+        for (label, stats) in [("A", &self.a), ("B", &self.b)] {
+            out.push_str(&format!(
+                "  {:<8} {:>9}  {:>5}  {:<4}  {:<4}  {:>12}  {:>7}\n",
+                label,
+                stats.responses,
+                stats.rated(),
+                stats.positive,
+                stats.negative,
+                pct(stats.satisfaction()),
+                pct(stats.success_rate()),
+            ));
+        }
+        out.push_str(&format!(
+            "\n  Satisfaction: {}\n  Task success: {}\n",
+            verdict(
+                self.satisfaction_p,
+                self.a.satisfaction(),
+                self.b.satisfaction()
+            ),
+            verdict(self.success_p, self.a.success_rate(), self.b.success_rate()),
+        ));
+        out
+    }
+}
+
+/// Two-sided p-value of a two-proportion z-test, or `None` with too few samples
+pub fn two_proportion_p_value(x1: u64, n1: u64, x2: u64, n2: u64) -> Option<f64> {
+    if n1 < MIN_SAMPLES || n2 < MIN_SAMPLES {
+        return None;
+    }
+    let (n1, n2) = (n1 as f64, n2 as f64);
+    let pooled = (x1 + x2) as f64 / (n1 + n2);
+    let se = (pooled * (1.0 - pooled) * (1.0 / n1 + 1.0 / n2)).sqrt();
+    if se == 0.0 {
+        return Some(1.0);
+    }
+    let z = (x1 as f64 / n1 - x2 as f64 / n2) / se;
+    Some((2.0 * (1.0 - normal_cdf(z.abs()))).clamp(0.0, 1.0))
+}
+
+/// Standard normal CDF (Abramowitz & Stegun 7.1.26 approximation of erf)
+fn normal_cdf(z: f64) -> f64 {
+    let x = z / std::f64::consts::SQRT_2;
+    let t = 1.0 / (1.0 + 0.327_591_1 * x.abs());
+    let poly = t
+        * (0.254_829_592
+            + t * (-0.284_496_736
+                + t * (1.421_413_741 + t * (-1.453_152_027 + t * 1.061_405_429))));
+    let erf = 1.0 - poly * (-x * x).exp();
+    let erf = if x >= 0.0 { erf } else { -erf };
+    0.5 * (1.0 + erf)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_two_proportion_p_value() {
+        assert!(two_proportion_p_value(5, 9, 1, 9).is_none());
+        // 80% vs 40% over 50 samples each is clearly significant
+        let p = two_proportion_p_value(40, 50, 20, 50).unwrap();
+        assert!(p < 0.001, "p = {}", p);
+        // Identical rates are not
+        let p = two_proportion_p_value(25, 50, 25, 50).unwrap();
+        assert!((p - 1.0).abs() < 1e-6);
+        assert!((normal_cdf(1.96) - 0.975).abs() < 1e-3);
+    }
+
+    #[tokio::test]
+    async fn test_assignment_is_sticky_and_report_counts_variants() {
+        let temp = TempDir::new().unwrap();
+        let db = KnowledgeDb::new(temp.path().join("test.db")).unwrap();
+        let experiment = PromptExperiment {
+            name: "terse-soul".to_string(),
+            variant_b: "Be terse.".to_string(),
+            split: 1.0,
+        };
+        assert_eq!(
+            experiment.assign(&db, "slack", "alice").await.unwrap(),
+            Variant::B
+        );
+        assert_eq!(experiment.soul(Variant::B, "Be kind."), "Be terse.");
+        assert_eq!(experiment.soul(Variant::A, "Be kind."), "Be kind.");
+
+        // Changing the split doesn't move existing conversations
+        let everyone_a = PromptExperiment {
+            split: 0.0,
+            ..experiment.clone()
+        };
+        assert_eq!(
+            everyone_a.assign(&db, "slack", "alice").await.unwrap(),
+            Variant::B
+        );
+        assert_eq!(
+            everyone_a.assign(&db, "slack", "bob").await.unwrap(),
+            Variant::A
+        );
+
+        for (variant, rating, tool_errors) in [
+            ("a", 1, 0),
+            ("a", -1, 0),
+            ("a", 0, 1),
+            ("b", 1, 0),
+            ("b", 0, 0),
+        ] {
+            db.insert_conversation(
+                "slack",
+                "meepo",
+                "answer",
+                Some(serde_json::json!({
+                    "experiment": { "name": "terse-soul", "variant": variant },
+                    "tool_errors": tool_errors,
+                    "feedback": { "rating": rating },
+                })),
+            )
+            .await
+            .unwrap();
+        }
+        db.insert_conversation(
+            "slack",
+            "meepo",
+            "other",
+            Some(serde_json::json!({ "experiment": { "name": "other", "variant": "b" } })),
+        )
+        .await
+        .unwrap();
+
+        let report = ExperimentReport::load(&db, "terse-soul").await.unwrap();
+        assert_eq!(report.a.responses, 3);
+        assert_eq!(report.a.rated(), 2);
+        assert_eq!(report.a.successes, 1);
+        assert_eq!(report.b.responses, 2);
+        assert_eq!(report.b.satisfaction(), Some(1.0));
+        assert_eq!(report.b.success_rate(), Some(1.0));
+        assert!(report.satisfaction_p.is_none());
+        assert!(report.format().contains("not enough data"));
+    }
+}
@@ -0,0 +1,662 @@
+//! Condition expressions for watchers, rules and notification routing
+//!
+//! A small, CEL-like language evaluated against an event's JSON fields:
+//!
+//! ```text
+//! payload.from contains "boss" && hour() < 18
+//! kind in ["email_received", "calendar_event"] && !(weekday() >= 6)
+//! payload.subject matches "^(urgent|asap)"
+//! ```
+//!
+//! - Fields are dotted paths into the event (`kind`, `source`, `payload.x.y`);
+//!   missing fields are `null`, and comparisons involving `null` are false.
+//! - Literals: `"text"` / `'text'`, numbers, `true`, `false`, `null`, `[a, b]`.
+//! - Operators: `||`, `&&`, `!`, `==`, `!=`, `<`, `<=`, `>`, `>=`, and
+//!   `contains`, `startsWith`, `endsWith`, `in` (case-insensitive for text),
+//!   `matches` (regex literal).
+//! - Functions: `hour()`, `minute()`, `weekday()` (1 = Monday … 7 = Sunday)
+//!   in the user's timezone, `lower(x)`, `upper(x)`, `len(x)`.
+//!
+//! Expressions are sandboxed: there are no loops, assignments or I/O, only the
+//! functions above exist, and length, nesting and regex size are capped.
+
+use std::fmt;
+use std::str::FromStr;
+
+use anyhow::{Result, anyhow, bail};
+use chrono::{DateTime, Datelike, Timelike};
+use chrono_tz::Tz;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// Longest accepted expression, in characters
+const MAX_LENGTH: usize = 1000;
+
+/// Deepest accepted nesting of parentheses, lists, calls and `!`
+const MAX_DEPTH: usize = 32;
+
+/// Compiled size limit for `matches` patterns
+const MAX_REGEX_SIZE: usize = 64 * 1024;
+
+/// A parsed condition expression
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct Expression {
+    source: String,
+    root: Node,
+}
+
+impl Expression {
+    pub fn parse(source: &str) -> Result<Self> {
+        let source = source.trim();
+        if source.is_empty() {
+            bail!("Empty expression");
+        }
+        if source.chars().count() > MAX_LENGTH {
+            bail!("Expression is longer than {} characters", MAX_LENGTH);
+        }
+        let tokens = tokenize(source)?;
+        let mut parser = Parser {
+            tokens,
+            pos: 0,
+            depth: 0,
+        };
+        let root = parser.or()?;
+        if let Some((token, at)) = parser.tokens.get(parser.pos) {
+            bail!("Unexpected {} at position {}", token, at);
+        }
+        Ok(Self {
+            source: source.to_string(),
+            root,
+        })
+    }
+
+    pub fn source(&self) -> &str {
+        &self.source
+    }
+
+    /// Evaluate against `vars`, with time functions in the user's timezone
+    pub fn evaluate(&self, vars: &Value) -> Result<Value> {
+        self.evaluate_at(vars, crate::timezone::now())
+    }
+
+    pub fn evaluate_at(&self, vars: &Value, now: DateTime<Tz>) -> Result<Value> {
+        Env { vars, now }.eval(&self.root)
+    }
+
+    /// Evaluate as a condition: `null` counts as false, other non-booleans
+    /// are an error
+    pub fn matches(&self, vars: &Value) -> Result<bool> {
+        self.matches_at(vars, crate::timezone::now())
+    }
+
+    pub fn matches_at(&self, vars: &Value, now: DateTime<Tz>) -> Result<bool> {
+        truth(&self.evaluate_at(vars, now)?)
+    }
+}
+
+impl FromStr for Expression {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Self::parse(s)
+    }
+}
+
+impl TryFrom<String> for Expression {
+    type Error = anyhow::Error;
+
+    fn try_from(s: String) -> Result<Self> {
+        Self::parse(&s)
+    }
+}
+
+impl From<Expression> for String {
+    fn from(expr: Expression) -> Self {
+        expr.source
+    }
+}
+
+impl fmt::Display for Expression {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.source)
+    }
+}
+
+#[derive(Debug, Clone)]
+enum Node {
+    Literal(Value),
+    Field(Vec<String>),
+    List(Vec<Node>),
+    Call(Function, Vec<Node>),
+    Not(Box<Node>),
+    And(Box<Node>, Box<Node>),
+    Or(Box<Node>, Box<Node>),
+    Compare(Op, Box<Node>, Box<Node>),
+    Matches(Box<Node>, Regex),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Op {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    Contains,
+    StartsWith,
+    EndsWith,
+    In,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Function {
+    Hour,
+    Minute,
+    Weekday,
+    Lower,
+    Upper,
+    Len,
+}
+
+impl Function {
+    fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "hour" => Some(Self::Hour),
+            "minute" => Some(Self::Minute),
+            "weekday" => Some(Self::Weekday),
+            "lower" => Some(Self::Lower),
+            "upper" => Some(Self::Upper),
+            "len" => Some(Self::Len),
+            _ => None,
+        }
+    }
+
+    fn arity(self) -> usize {
+        match self {
+            Self::Hour | Self::Minute | Self::Weekday => 0,
+            Self::Lower | Self::Upper | Self::Len => 1,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Ident(String),
+    Str(String),
+    Num(f64),
+    Symbol(&'static str),
+}
+
+impl fmt::Display for Token {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Ident(s) => write!(f, "'{}'", s),
+            Self::Str(s) => write!(f, "\"{}\"", s),
+            Self::Num(n) => write!(f, "{}", n),
+            Self::Symbol(s) => write!(f, "'{}'", s),
+        }
+    }
+}
+
+const SYMBOLS: &[&str] = &[
+    "&&", "||", "==", "!=", "<=", ">=", "<", ">", "!", "(", ")", "[", "]", ",", ".",
+];
+
+fn tokenize(source: &str) -> Result<Vec<(Token, usize)>> {
+    let chars: Vec<char> = source.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        let start = i;
+        if c.is_whitespace() {
+            i += 1;
+        } else if c == '"' || c == '\'' {
+            let mut text = String::new();
+            i += 1;
+            loop {
+                match chars.get(i) {
+                    None => bail!("Unterminated string at position {}", start),
+                    Some(&q) if q == c => break,
+                    Some('\\') => {
+                        i += 1;
+                        match chars.get(i) {
+                            Some('n') => text.push('\n'),
+                            Some('t') => text.push('\t'),
+                            Some(&other) => text.push(other),
+                            None => bail!("Unterminated string at position {}", start),
+                        }
+                    }
+                    Some(&other) => text.push(other),
+                }
+                i += 1;
+            }
+            i += 1;
+            tokens.push((Token::Str(text), start));
+        } else if c.is_ascii_digit() {
+            while i < chars.len()
+                && (chars[i].is_ascii_digit()
+                    || (chars[i] == '.' && chars.get(i + 1).is_some_and(|c| c.is_ascii_digit())))
+            {
+                i += 1;
+            }
+            let text: String = chars[start..i].iter().collect();
+            let n = text
+                .parse()
+                .map_err(|_| anyhow!("Invalid number '{}' at position {}", text, start))?;
+            tokens.push((Token::Num(n), start));
+        } else if c.is_alphabetic() || c == '_' {
+            while i < chars.len() && (chars[i].is_alphanumeric() || chars[i] == '_') {
+                i += 1;
+            }
+            tokens.push((Token::Ident(chars[start..i].iter().collect()), start));
+        } else {
+            let rest: String = chars[i..(i + 2).min(chars.len())].iter().collect();
+            let symbol = SYMBOLS
+                .iter()
+                .find(|s| rest.starts_with(**s))
+                .ok_or_else(|| anyhow!("Unexpected '{}' at position {}", c, start))?;
+            i += symbol.len();
+            tokens.push((Token::Symbol(symbol), start));
+        }
+    }
+    Ok(tokens)
+}
+
+struct Parser {
+    tokens: Vec<(Token, usize)>,
+    pos: usize,
+    depth: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos).map(|(t, _)| t)
+    }
+
+    fn next(&mut self) -> Result<Token> {
+        let (token, _) = self
+            .tokens
+            .get(self.pos)
+            .cloned()
+            .ok_or_else(|| anyhow!("Unexpected end of expression"))?;
+        self.pos += 1;
+        Ok(token)
+    }
+
+    fn eat(&mut self, symbol: &str) -> bool {
+        if matches!(self.peek(), Some(Token::Symbol(s)) if *s == symbol) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect(&mut self, symbol: &str) -> Result<()> {
+        if self.eat(symbol) {
+            return Ok(());
+        }
+        match self.tokens.get(self.pos) {
+            Some((token, at)) => bail!("Expected '{}' at position {}, found {}", symbol, at, token),
+            None => bail!("Expected '{}' at end of expression", symbol),
+        }
+    }
+
+    fn nest(&mut self) -> Result<()> {
+        self.depth += 1;
+        if self.depth > MAX_DEPTH {
+            bail!("Expression is nested more than {} levels deep", MAX_DEPTH);
+        }
+        Ok(())
+    }
+
+    fn or(&mut self) -> Result<Node> {
+        let mut node = self.and()?;
+        while self.eat("||") {
+            node = Node::Or(Box::new(node), Box::new(self.and()?));
+        }
+        Ok(node)
+    }
+
+    fn and(&mut self) -> Result<Node> {
+        let mut node = self.unary()?;
+        while self.eat("&&") {
+            node = Node::And(Box::new(node), Box::new(self.unary()?));
+        }
+        Ok(node)
+    }
+
+    fn unary(&mut self) -> Result<Node> {
+        if self.eat("!") {
+            self.nest()?;
+            let node = Node::Not(Box::new(self.unary()?));
+            self.depth -= 1;
+            return Ok(node);
+        }
+        self.comparison()
+    }
+
+    fn comparison(&mut self) -> Result<Node> {
+        let left = self.primary()?;
+        let op = match self.peek() {
+            Some(Token::Symbol(s)) => match *s {
+                "==" => Op::Eq,
+                "!=" => Op::Ne,
+                "<" => Op::Lt,
+                "<=" => Op::Le,
+                ">" => Op::Gt,
+                ">=" => Op::Ge,
+                _ => return Ok(left),
+            },
+            Some(Token::Ident(word)) => match word.as_str() {
+                "contains" => Op::Contains,
+                "startsWith" => Op::StartsWith,
+                "endsWith" => Op::EndsWith,
+                "in" => Op::In,
+                "matches" => {
+                    self.pos += 1;
+                    let pattern = match self.next()? {
+                        Token::Str(pattern) => pattern,
+                        other => bail!("'matches' needs a quoted pattern, found {}", other),
+                    };
+                    let regex = regex::RegexBuilder::new(&pattern)
+                        .case_insensitive(true)
+                        .size_limit(MAX_REGEX_SIZE)
+                        .build()
+                        .map_err(|e| anyhow!("Invalid pattern \"{}\": {}", pattern, e))?;
+                    return Ok(Node::Matches(Box::new(left), regex));
+                }
+                _ => return Ok(left),
+            },
+            _ => return Ok(left),
+        };
+        self.pos += 1;
+        let right = self.primary()?;
+        Ok(Node::Compare(op, Box::new(left), Box::new(right)))
+    }
+
+    fn primary(&mut self) -> Result<Node> {
+        let at = self.tokens.get(self.pos).map(|(_, at)| *at).unwrap_or(0);
+        match self.next()? {
+            Token::Str(s) => Ok(Node::Literal(Value::String(s))),
+            Token::Num(n) => Ok(Node::Literal(serde_json::json!(n))),
+            Token::Symbol("(") => {
+                self.nest()?;
+                let node = self.or()?;
+                self.expect(")")?;
+                self.depth -= 1;
+                Ok(node)
+            }
+            Token::Symbol("[") => {
+                self.nest()?;
+                let items = self.items("]")?;
+                self.depth -= 1;
+                Ok(Node::List(items))
+            }
+            Token::Ident(name) => match name.as_str() {
+                "true" => Ok(Node::Literal(Value::Bool(true))),
+                "false" => Ok(Node::Literal(Value::Bool(false))),
+                "null" => Ok(Node::Literal(Value::Null)),
+                _ if self.eat("(") => {
+                    let function = Function::from_name(&name)
+                        .ok_or_else(|| anyhow!("Unknown function '{}' at position {}", name, at))?;
+                    self.nest()?;
+                    let args = self.items(")")?;
+                    self.depth -= 1;
+                    if args.len() != function.arity() {
+                        bail!(
+                            "{}() takes {} argument(s), got {}",
+                            name,
+                            function.arity(),
+                            args.len()
+                        );
+                    }
+                    Ok(Node::Call(function, args))
+                }
+                _ => {
+                    let mut path = vec![name];
+                    while self.eat(".") {
+                        match self.next()? {
+                            Token::Ident(key) => path.push(key),
+                            Token::Num(n) if n.fract() == 0.0 => path.push(n.to_string()),
+                            other => bail!("Expected a field name after '.', found {}", other),
+                        }
+                    }
+                    Ok(Node::Field(path))
+                }
+            },
+            other => bail!("Unexpected {} at position {}", other, at),
+        }
+    }
+
+    /// Comma-separated expressions up to the closing symbol
+    fn items(&mut self, close: &str) -> Result<Vec<Node>> {
+        let mut items = Vec::new();
+        if self.eat(close) {
+            return Ok(items);
+        }
+        loop {
+            items.push(self.or()?);
+            if self.eat(close) {
+                return Ok(items);
+            }
+            self.expect(",")?;
+        }
+    }
+}
+
+struct Env<'a> {
+    vars: &'a Value,
+    now: DateTime<Tz>,
+}
+
+impl Env<'_> {
+    fn eval(&self, node: &Node) -> Result<Value> {
+        Ok(match node {
+            Node::Literal(value) => value.clone(),
+            Node::Field(path) => path
+                .iter()
+                .try_fold(self.vars, |value, key| match value {
+                    Value::Array(items) => key.parse::<usize>().ok().and_then(|i| items.get(i)),
+                    other => other.get(key),
+                })
+                .cloned()
+                .unwrap_or(Value::Null),
+            Node::List(items) => Value::Array(
+                items
+                    .iter()
+                    .map(|item| self.eval(item))
+                    .collect::<Result<_>>()?,
+            ),
+            Node::Call(function, args) => self.call(*function, args)?,
+            Node::Not(inner) => Value::Bool(!truth(&self.eval(inner)?)?),
+            Node::And(left, right) => {
+                Value::Bool(truth(&self.eval(left)?)? && truth(&self.eval(right)?)?)
+            }
+            Node::Or(left, right) => {
+                Value::Bool(truth(&self.eval(left)?)? || truth(&self.eval(right)?)?)
+            }
+            Node::Compare(op, left, right) => {
+                Value::Bool(compare(*op, &self.eval(left)?, &self.eval(right)?))
+            }
+            Node::Matches(left, regex) => Value::Bool(match self.eval(left)? {
+                Value::String(s) => regex.is_match(&s),
+                Value::Null => false,
+                other => regex.is_match(&other.to_string()),
+            }),
+        })
+    }
+
+    fn call(&self, function: Function, args: &[Node]) -> Result<Value> {
+        let arg = match args.first() {
+            Some(node) => self.eval(node)?,
+            None => Value::Null,
+        };
+        Ok(match function {
+            Function::Hour => self.now.hour().into(),
+            Function::Minute => self.now.minute().into(),
+            Function::Weekday => self.now.weekday().number_from_monday().into(),
+            Function::Lower => text(&arg).map_or(Value::Null, |s| s.to_lowercase().into()),
+            Function::Upper => text(&arg).map_or(Value::Null, |s| s.to_uppercase().into()),
+            Function::Len => match &arg {
+                Value::String(s) => s.chars().count().into(),
+                Value::Array(items) => items.len().into(),
+                Value::Object(map) => map.len().into(),
+                _ => 0.into(),
+            },
+        })
+    }
+}
+
+fn truth(value: &Value) -> Result<bool> {
+    match value {
+        Value::Bool(b) => Ok(*b),
+        Value::Null => Ok(false),
+        other => Err(anyhow!("Expected true or false, got {}", other)),
+    }
+}
+
+fn text(value: &Value) -> Option<String> {
+    match value {
+        Value::String(s) => Some(s.clone()),
+        Value::Null => None,
+        other => Some(other.to_string()),
+    }
+}
+
+fn equal(left: &Value, right: &Value) -> bool {
+    match (left.as_f64(), right.as_f64()) {
+        (Some(a), Some(b)) => a == b,
+        _ => left == right,
+    }
+}
+
+fn contains(haystack: &Value, needle: &Value) -> bool {
+    match (haystack, needle) {
+        (Value::String(h), Value::String(n)) => h.to_lowercase().contains(&n.to_lowercase()),
+        (Value::Array(items), needle) => items.iter().any(|item| match (item, needle) {
+            (Value::String(a), Value::String(b)) => a.eq_ignore_ascii_case(b),
+            (item, needle) => equal(item, needle),
+        }),
+        (Value::Object(map), Value::String(key)) => map.contains_key(key),
+        _ => false,
+    }
+}
+
+fn compare(op: Op, left: &Value, right: &Value) -> bool {
+    let ordering = match (left, right) {
+        (Value::String(a), Value::String(b)) => Some(a.cmp(b)),
+        _ => left
+            .as_f64()
+            .zip(right.as_f64())
+            .and_then(|(a, b)| a.partial_cmp(&b)),
+    };
+    let lower = |v: &Value| v.as_str().map(str::to_lowercase);
+    match op {
+        Op::Eq => equal(left, right),
+        Op::Ne => !equal(left, right),
+        Op::Lt => ordering.is_some_and(|o| o.is_lt()),
+        Op::Le => ordering.is_some_and(|o| o.is_le()),
+        Op::Gt => ordering.is_some_and(|o| o.is_gt()),
+        Op::Ge => ordering.is_some_and(|o| o.is_ge()),
+        Op::Contains => contains(left, right),
+        Op::In => contains(right, left),
+        Op::StartsWith => lower(left)
+            .zip(lower(right))
+            .is_some_and(|(a, b)| a.starts_with(&b)),
+        Op::EndsWith => lower(left)
+            .zip(lower(right))
+            .is_some_and(|(a, b)| a.ends_with(&b)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn at(hour: u32) -> DateTime<Tz> {
+        // 2026-03-04 is a Wednesday
+        chrono_tz::Europe::Berlin
+            .with_ymd_and_hms(2026, 3, 4, hour, 30, 0)
+            .unwrap()
+    }
+
+    fn check(source: &str, vars: &Value, hour: u32) -> bool {
+        Expression::parse(source)
+            .unwrap()
+            .matches_at(vars, at(hour))
+            .unwrap()
+    }
+
+    #[test]
+    fn test_evaluates_conditions() {
+        let vars = serde_json::json!({
+            "kind": "email_received",
+            "payload": {
+                "sender": "The Boss <Boss@Company.com>",
+                "subject": "URGENT: numbers",
+                "labels": ["work", "finance"],
+                "size": 42
+            }
+        });
+        assert!(check(
+            r#"payload.sender contains "boss" && hour() < 18"#,
+            &vars,
+            9
+        ));
+        assert!(!check(
+            r#"payload.sender contains "boss" && hour() < 18"#,
+            &vars,
+            19
+        ));
+        assert!(check(
+            r#"kind in ["calendar_event", 'email_received'] && weekday() == 3"#,
+            &vars,
+            9
+        ));
+        assert!(check(
+            r#"payload.subject matches "^(urgent|asap)" && !(payload.size > 100)"#,
+            &vars,
+            9
+        ));
+        assert!(check(
+            r#"payload.labels contains "Finance" && payload.labels.0 == "work""#,
+            &vars,
+            9
+        ));
+        assert!(check(r#"len(payload.labels) >= 2 || false"#, &vars, 9));
+        // Missing fields are null and never compare true
+        assert!(!check(r#"payload.missing.deep > 1"#, &vars, 9));
+        assert!(check(r#"payload.missing == null"#, &vars, 9));
+        assert!(!check(r#"payload.missing"#, &vars, 9));
+    }
+
+    #[test]
+    fn test_rejects_unsafe_or_invalid_expressions() {
+        for bad in [
+            "",
+            "payload.x ==",
+            "system(\"rm -rf /\")",
+            "(a == 1",
+            "a == 1 b",
+            "a matches \"(\"",
+            "lower()",
+            "'unterminated",
+        ] {
+            assert!(Expression::parse(bad).is_err(), "accepted {:?}", bad);
+        }
+        let deep = format!("{}true{}", "(".repeat(40), ")".repeat(40));
+        assert!(Expression::parse(&deep).is_err());
+        let long = format!("a == \"{}\"", "x".repeat(MAX_LENGTH));
+        assert!(Expression::parse(&long).is_err());
+
+        let not_bool = Expression::parse("payload.size").unwrap();
+        let vars = serde_json::json!({ "payload": { "size": 3 } });
+        assert!(not_bool.matches_at(&vars, at(9)).is_err());
+    }
+}
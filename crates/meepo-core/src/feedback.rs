@@ -0,0 +1,250 @@
+//! Thumbs up/down feedback on agent responses
+//!
+//! A message that is just a 👍 or 👎 (optionally followed by a comment), a
+//! Slack `:+1:`/`:-1:`, an iMessage "Liked"/"Disliked" tapback or a Discord
+//! reaction rates the agent's latest response to that sender. Ratings go to
+//! the `feedback` table with the model and tools behind the response, and
+//! are also noted in the response's conversation metadata.
+
+use anyhow::Result;
+use meepo_knowledge::{Conversation, KnowledgeDb, NewFeedback};
+use serde_json::Value;
+use tracing::info;
+
+/// Sender name the agent's own responses are stored under
+const AGENT_SENDER: &str = "meepo";
+
+/// How many recent conversation rows to search for the rated response
+const SEARCH_WINDOW: usize = 50;
+
+const POSITIVE: &[&str] = &["👍", ":+1:", ":thumbsup:"];
+const NEGATIVE: &[&str] = &["👎", ":-1:", ":thumbsdown:"];
+
+/// A rating parsed from a message
+#[derive(Debug, Clone, PartialEq)]
+pub struct FeedbackSignal {
+    /// 1 for thumbs up, -1 for thumbs down
+    pub rating: i32,
+    pub comment: Option<String>,
+    /// Start of the rated text, when the channel quotes it (iMessage tapbacks)
+    pub quoted: Option<String>,
+}
+
+/// Parse a feedback message. Returns `None` for anything else.
+pub fn parse(content: &str) -> Option<FeedbackSignal> {
+    let content = content.trim();
+
+    // iMessage tapbacks arrive as `Liked “original text”`
+    for (prefix, rating) in [("Liked ", 1), ("Loved ", 1), ("Disliked ", -1)] {
+        if let Some(rest) = content.strip_prefix(prefix) {
+            let quoted = rest
+                .trim_start_matches(['“', '"'])
+                .trim_end_matches(['”', '"'])
+                .trim_end_matches('…');
+            if quoted.len() < rest.len() {
+                return Some(FeedbackSignal {
+                    rating,
+                    comment: None,
+                    quoted: Some(quoted.trim().to_string()),
+                });
+            }
+        }
+    }
+
+    // Bare "+1" / "-1"
+    match content {
+        "+1" => return Some(signal(1, "")),
+        "-1" => return Some(signal(-1, "")),
+        _ => {}
+    }
+
+    for (tokens, rating) in [(POSITIVE, 1), (NEGATIVE, -1)] {
+        for token in tokens {
+            if let Some(rest) = content.strip_prefix(token) {
+                // Skin-tone modifiers and variation selectors follow the emoji
+                let rest = rest.trim_start_matches(|c: char| {
+                    ('\u{1F3FB}'..='\u{1F3FF}').contains(&c) || c == '\u{FE0F}'
+                });
+                if rest.is_empty() || rest.starts_with(char::is_whitespace) {
+                    return Some(signal(rating, rest));
+                }
+            }
+        }
+    }
+    None
+}
+
+fn signal(rating: i32, rest: &str) -> FeedbackSignal {
+    let comment = rest.trim().trim_start_matches(['-', ':', ',', '—']).trim();
+    FeedbackSignal {
+        rating,
+        comment: (!comment.is_empty()).then(|| comment.to_string()),
+        quoted: None,
+    }
+}
+
+/// Find the response a signal rates: the latest agent reply to `sender` on
+/// `channel`, or the one starting with the quoted text
+pub async fn find_response(
+    db: &KnowledgeDb,
+    channel: &str,
+    sender: &str,
+    signal: &FeedbackSignal,
+) -> Result<Option<Conversation>> {
+    let recent = db
+        .get_recent_conversations(Some(channel), SEARCH_WINDOW)
+        .await?;
+    let mut replies = recent.into_iter().filter(|c| {
+        c.sender == AGENT_SENDER
+            && c.metadata
+                .as_ref()
+                .and_then(|m| m.get("to"))
+                .and_then(Value::as_str)
+                .is_none_or(|to| to == sender)
+    });
+    Ok(match signal.quoted.as_deref().filter(|q| !q.is_empty()) {
+        Some(quoted) => replies.find(|c| c.content.trim_start().starts_with(quoted)),
+        None => replies.next(),
+    })
+}
+
+/// Record a rating against the response it refers to. Returns the rated
+/// conversation row, or `None` if there was no response to rate.
+pub async fn record(
+    db: &KnowledgeDb,
+    channel: &str,
+    sender: &str,
+    signal: &FeedbackSignal,
+) -> Result<Option<String>> {
+    let Some(response) = find_response(db, channel, sender, signal).await? else {
+        return Ok(None);
+    };
+    let mut metadata = response
+        .metadata
+        .clone()
+        .unwrap_or_else(|| serde_json::json!({}));
+    let model = metadata
+        .get("model")
+        .and_then(Value::as_str)
+        .map(String::from);
+    let tools: Vec<String> = metadata
+        .get("tools")
+        .and_then(|t| serde_json::from_value(t.clone()).ok())
+        .unwrap_or_default();
+
+    db.insert_feedback(NewFeedback {
+        conversation_id: Some(&response.id),
+        channel,
+        sender,
+        rating: signal.rating,
+        comment: signal.comment.as_deref(),
+        model: model.as_deref(),
+        tools: &tools,
+    })
+    .await?;
+
+    if let Some(map) = metadata.as_object_mut() {
+        map.insert(
+            "feedback".to_string(),
+            serde_json::json!({
+                "rating": signal.rating,
+                "comment": signal.comment,
+            }),
+        );
+        db.update_conversation_metadata(&response.id, metadata)
+            .await?;
+    }
+    info!(
+        "Recorded {} feedback from {} on {}",
+        if signal.rating > 0 {
+            "positive"
+        } else {
+            "negative"
+        },
+        sender,
+        channel
+    );
+    Ok(Some(response.id))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_parse_feedback() {
+        assert_eq!(parse("👍").unwrap().rating, 1);
+        assert_eq!(parse("👍🏽").unwrap().rating, 1);
+        assert_eq!(parse(":+1:").unwrap().rating, 1);
+        assert_eq!(parse("+1").unwrap().rating, 1);
+        let signal = parse("👎 — the dates were wrong").unwrap();
+        assert_eq!(signal.rating, -1);
+        assert_eq!(signal.comment.as_deref(), Some("the dates were wrong"));
+
+        let tapback = parse("Disliked “Your flight leaves at 9…”").unwrap();
+        assert_eq!(tapback.rating, -1);
+        assert_eq!(tapback.quoted.as_deref(), Some("Your flight leaves at 9"));
+
+        assert!(parse("-1 degrees outside, should I go running?").is_none());
+        assert!(parse("👍is this a word").is_none());
+        assert!(parse("Liked the movie").is_none());
+        assert!(parse("thanks").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_record_rates_latest_reply_to_sender() {
+        let temp = TempDir::new().unwrap();
+        let db = KnowledgeDb::new(temp.path().join("test.db")).unwrap();
+
+        assert!(
+            record(&db, "slack", "alice", &parse("👍").unwrap())
+                .await
+                .unwrap()
+                .is_none()
+        );
+
+        let to_alice = db
+            .insert_conversation(
+                "slack",
+                AGENT_SENDER,
+                "Your flight leaves at 9am",
+                Some(serde_json::json!({
+                    "to": "alice",
+                    "model": "claude-sonnet-4-5",
+                    "tools": ["read_calendar"],
+                })),
+            )
+            .await
+            .unwrap();
+        tokio::time::sleep(std::time::Duration::from_millis(5)).await;
+        db.insert_conversation(
+            "slack",
+            AGENT_SENDER,
+            "Lunch is booked",
+            Some(serde_json::json!({ "to": "bob" })),
+        )
+        .await
+        .unwrap();
+
+        let rated = record(&db, "slack", "alice", &parse("👎 wrong day").unwrap())
+            .await
+            .unwrap();
+        assert_eq!(rated.as_deref(), Some(to_alice.as_str()));
+
+        let today = chrono::Utc::now().format("%Y-%m-%d").to_string();
+        let feedback = db.get_feedback(&today, &today).await.unwrap();
+        assert_eq!(feedback.len(), 1);
+        assert_eq!(feedback[0].rating, -1);
+        assert_eq!(feedback[0].model.as_deref(), Some("claude-sonnet-4-5"));
+        assert_eq!(feedback[0].tools, vec!["read_calendar".to_string()]);
+        assert_eq!(feedback[0].comment.as_deref(), Some("wrong day"));
+
+        let rows = db
+            .get_recent_conversations(Some("slack"), 10)
+            .await
+            .unwrap();
+        let row = rows.iter().find(|c| c.id == to_alice).unwrap();
+        assert_eq!(row.metadata.as_ref().unwrap()["feedback"]["rating"], -1);
+    }
+}
@@ -0,0 +1,177 @@
+//! Focus mode awareness
+//!
+//! Polls the active macOS Focus mode and keeps it process-wide, like the
+//! power state, so the agent's context can mention it and proactive
+//! notifications are held back while the user is in a quiet mode such as
+//! "Deep Work". Errors and urgent mail still get through.
+
+use std::sync::RwLock;
+use std::time::Duration;
+use tokio_util::sync::CancellationToken;
+use tracing::{debug, info, warn};
+
+use crate::platform::FocusProvider;
+
+/// Configuration for Focus mode awareness
+#[derive(Debug, Clone)]
+pub struct FocusConfig {
+    pub enabled: bool,
+    /// How often to re-read the active Focus mode
+    pub poll_secs: u64,
+    /// Focus modes (case-insensitive) during which notifications are held
+    /// back; `"*"` matches any mode
+    pub quiet_modes: Vec<String>,
+}
+
+impl Default for FocusConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            poll_secs: 60,
+            quiet_modes: vec![
+                "Do Not Disturb".to_string(),
+                "Deep Work".to_string(),
+                "Sleep".to_string(),
+            ],
+        }
+    }
+}
+
+impl FocusConfig {
+    /// Whether `mode` holds back notifications
+    pub fn is_quiet(&self, mode: &str) -> bool {
+        self.quiet_modes
+            .iter()
+            .any(|q| q == "*" || q.eq_ignore_ascii_case(mode))
+    }
+}
+
+/// The active Focus mode and whether it holds back notifications
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FocusStatus {
+    pub mode: Option<String>,
+    pub quiet: bool,
+}
+
+/// Latest Focus status
+static CURRENT: RwLock<Option<FocusStatus>> = RwLock::new(None);
+
+/// Publish the current Focus status
+pub fn set_current(status: FocusStatus) {
+    *CURRENT.write().unwrap() = Some(status);
+}
+
+/// The last published Focus status, if a monitor is running
+pub fn current() -> Option<FocusStatus> {
+    CURRENT.read().unwrap().clone()
+}
+
+/// Whether notifications should be held back right now
+pub fn is_quiet() -> bool {
+    current().is_some_and(|s| s.quiet)
+}
+
+/// Context section for the agent; empty unless a Focus mode is on
+pub fn context_section() -> String {
+    current().map(|s| section_for(&s)).unwrap_or_default()
+}
+
+fn section_for(status: &FocusStatus) -> String {
+    let Some(mode) = &status.mode else {
+        return String::new();
+    };
+    let mut section = format!("## Focus\n\nThe user's \"{}\" Focus mode is on.", mode);
+    if status.quiet {
+        section.push_str(
+            " Proactive notifications are held back. Don't interrupt them with \
+             anything that can wait; keep replies short.",
+        );
+    }
+    section.push_str("\n\n");
+    section
+}
+
+/// Keeps the Focus status current
+pub struct FocusMonitor {
+    config: FocusConfig,
+    provider: Box<dyn FocusProvider>,
+}
+
+impl FocusMonitor {
+    pub fn new(config: FocusConfig, provider: Box<dyn FocusProvider>) -> Self {
+        Self { config, provider }
+    }
+
+    /// Read the active mode and publish it, returning the new status
+    pub async fn refresh(&self) -> anyhow::Result<FocusStatus> {
+        let mode = self.provider.current_focus().await?;
+        let status = FocusStatus {
+            quiet: mode.as_deref().is_some_and(|m| self.config.is_quiet(m)),
+            mode,
+        };
+        if current().as_ref() != Some(&status) {
+            match &status.mode {
+                Some(mode) if status.quiet => {
+                    info!("Focus mode '{}' on — holding back notifications", mode)
+                }
+                Some(mode) => info!("Focus mode '{}' on", mode),
+                None => info!("Focus off"),
+            }
+        }
+        set_current(status.clone());
+        Ok(status)
+    }
+
+    /// Poll until cancelled
+    pub async fn run(self, cancel: CancellationToken) {
+        if !self.config.enabled {
+            return;
+        }
+        let interval = Duration::from_secs(self.config.poll_secs.max(10));
+        let mut warned = false;
+        loop {
+            match self.refresh().await {
+                Ok(_) => warned = false,
+                Err(e) if !warned => {
+                    warn!("Failed to read Focus mode: {}", e);
+                    warned = true;
+                }
+                Err(e) => debug!("Failed to read Focus mode: {}", e),
+            }
+            tokio::select! {
+                _ = cancel.cancelled() => break,
+                _ = tokio::time::sleep(interval) => {}
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_quiet_modes_and_context() {
+        let config = FocusConfig::default();
+        assert!(config.is_quiet("deep work"));
+        assert!(!config.is_quiet("Personal"));
+        let any = FocusConfig {
+            quiet_modes: vec!["*".to_string()],
+            ..config
+        };
+        assert!(any.is_quiet("Personal"));
+
+        assert!(section_for(&FocusStatus::default()).is_empty());
+        let personal = section_for(&FocusStatus {
+            mode: Some("Personal".to_string()),
+            quiet: false,
+        });
+        assert!(personal.contains("\"Personal\" Focus mode is on"));
+        assert!(!personal.contains("held back"));
+        let deep = section_for(&FocusStatus {
+            mode: Some("Deep Work".to_string()),
+            quiet: true,
+        });
+        assert!(deep.contains("held back"));
+    }
+}
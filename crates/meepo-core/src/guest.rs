@@ -0,0 +1,220 @@
+//! Guest (demo) mode
+//!
+//! When showing Meepo to someone else, every tool that changes something —
+//! sending email, writing files, running commands, purchases — is switched
+//! off and the agent says it's in demo mode, while search, recall and other
+//! read-only tools keep working. Guest mode always applies to configured
+//! channels and senders, and can be toggled at runtime for every channel or
+//! one channel (`meepo guest on|off`), stored as `guest_mode` preferences.
+
+use std::sync::Arc;
+
+use anyhow::{Result, anyhow};
+use async_trait::async_trait;
+use meepo_knowledge::KnowledgeDb;
+use serde_json::Value;
+use tracing::{debug, warn};
+
+use crate::api::ToolDefinition;
+use crate::autonomy::action_log::{ActionRisk, classify_tool};
+use crate::tools::ToolExecutor;
+use crate::types::{ChannelType, IncomingMessage};
+
+/// Preference category holding runtime toggles
+pub const CATEGORY: &str = "guest_mode";
+
+/// Toggle scope that covers every channel
+pub const ALL_CHANNELS: &str = "all";
+
+/// Which conversations are guests and what they may use
+#[derive(Debug, Clone, Default)]
+pub struct GuestModeConfig {
+    /// Channels always in guest mode (e.g. "discord")
+    pub channels: Vec<String>,
+    /// `channel:sender` pairs always treated as guests
+    pub senders: Vec<String>,
+    /// Tools guests may use on top of the read-only ones
+    pub allow_tools: Vec<String>,
+}
+
+impl GuestModeConfig {
+    /// Read-only tools, plus any explicitly allowed
+    pub fn tool_allowed(&self, tool: &str) -> bool {
+        classify_tool(tool) == ActionRisk::ReadOnly || self.allow_tools.iter().any(|t| t == tool)
+    }
+
+    fn configured_for(&self, channel: &str, sender: &str) -> bool {
+        self.channels
+            .iter()
+            .any(|c| c.eq_ignore_ascii_case(channel))
+            || self.senders.iter().any(|s| {
+                s.split_once(':').is_some_and(|(c, who)| {
+                    c.eq_ignore_ascii_case(channel) && who.eq_ignore_ascii_case(sender)
+                })
+            })
+    }
+}
+
+fn toggle_key(scope: &str) -> String {
+    format!("guest_mode:{}", scope.trim().to_lowercase())
+}
+
+/// Channels (or `all`) with guest mode switched on at runtime
+pub async fn toggled_scopes(db: &KnowledgeDb) -> Result<Vec<String>> {
+    let prefs = db.get_preferences(Some(CATEGORY)).await?;
+    Ok(prefs
+        .into_iter()
+        .filter(|p| p.value.as_bool() == Some(true))
+        .filter_map(|p| p.key.strip_prefix("guest_mode:").map(String::from))
+        .collect())
+}
+
+/// Switch guest mode on or off for one channel, or `all`
+pub async fn set_toggle(db: &KnowledgeDb, scope: &str, on: bool) -> Result<()> {
+    let scope = scope.trim().to_lowercase();
+    if scope != ALL_CHANNELS && ChannelType::from_string(&scope) == ChannelType::Internal {
+        return Err(anyhow!(
+            "Unknown channel '{}'. Use a channel name (discord, slack, imessage, email, ...) or 'all'",
+            scope
+        ));
+    }
+    if on {
+        db.upsert_preference(
+            CATEGORY,
+            &toggle_key(&scope),
+            Value::Bool(true),
+            1.0,
+            Some("cli"),
+        )
+        .await?;
+    } else {
+        db.delete_preference(&toggle_key(&scope)).await?;
+    }
+    Ok(())
+}
+
+/// Whether a message should be handled in guest mode. Internal messages
+/// (watchers, the autonomous loop) never are.
+pub async fn is_guest(db: &KnowledgeDb, config: &GuestModeConfig, msg: &IncomingMessage) -> bool {
+    if msg.channel == ChannelType::Internal {
+        return false;
+    }
+    let channel = msg.channel.to_string();
+    if config.configured_for(&channel, &msg.sender) {
+        return true;
+    }
+    match toggled_scopes(db).await {
+        Ok(scopes) => scopes
+            .iter()
+            .any(|s| s == ALL_CHANNELS || s.eq_ignore_ascii_case(&channel)),
+        Err(e) => {
+            // Fail closed: a demo shouldn't turn into full access
+            warn!("Failed to read guest mode toggles, assuming guest: {}", e);
+            true
+        }
+    }
+}
+
+/// Context section telling the agent it's in demo mode
+pub fn context_section() -> String {
+    "\n\n## Guest Mode\n\nYou are in guest (demo) mode: someone other than your owner may be \
+     watching. Tools that send messages, change files, run commands, make purchases or \
+     otherwise change anything are disabled; search, recall and read-only tools still work. \
+     Say you're in demo mode when asked to do something that would change anything, and \
+     never claim to have done it.\n"
+        .to_string()
+}
+
+/// Tool executor that refuses anything but guest-safe tools
+pub struct GuestToolExecutor {
+    inner: Arc<dyn ToolExecutor>,
+    config: GuestModeConfig,
+}
+
+impl GuestToolExecutor {
+    pub fn new(inner: Arc<dyn ToolExecutor>, config: GuestModeConfig) -> Self {
+        Self { inner, config }
+    }
+}
+
+#[async_trait]
+impl ToolExecutor for GuestToolExecutor {
+    async fn execute(&self, tool_name: &str, input: Value) -> Result<String> {
+        if !self.config.tool_allowed(tool_name) {
+            warn!("Blocked '{}' in guest mode", tool_name);
+            return Err(anyhow!(
+                "Tool '{}' is disabled in guest (demo) mode",
+                tool_name
+            ));
+        }
+        debug!("Guest executing tool: {}", tool_name);
+        self.inner.execute(tool_name, input).await
+    }
+
+    fn list_tools(&self) -> Vec<ToolDefinition> {
+        self.inner
+            .list_tools()
+            .into_iter()
+            .filter(|t| self.config.tool_allowed(&t.name))
+            .collect()
+    }
+
+    fn reads_personal_knowledge(&self, tool_name: &str) -> bool {
+        self.inner.reads_personal_knowledge(tool_name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tools::ToolRegistry;
+
+    fn message(channel: ChannelType, sender: &str) -> IncomingMessage {
+        IncomingMessage {
+            id: "1".to_string(),
+            sender: sender.to_string(),
+            content: "hi".to_string(),
+            channel,
+            timestamp: chrono::Utc::now(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_guest_mode_scopes_and_tools() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let db = KnowledgeDb::new(temp.path().join("test.db")).unwrap();
+        let config = GuestModeConfig {
+            channels: vec!["discord".to_string()],
+            senders: vec!["slack:U42".to_string()],
+            allow_tools: vec!["remember".to_string()],
+        };
+
+        assert!(is_guest(&db, &config, &message(ChannelType::Discord, "anyone")).await);
+        assert!(is_guest(&db, &config, &message(ChannelType::Slack, "u42")).await);
+        assert!(!is_guest(&db, &config, &message(ChannelType::Slack, "owner")).await);
+
+        set_toggle(&db, "slack", true).await.unwrap();
+        assert!(is_guest(&db, &config, &message(ChannelType::Slack, "owner")).await);
+        assert!(!is_guest(&db, &config, &message(ChannelType::Email, "owner")).await);
+        set_toggle(&db, "all", true).await.unwrap();
+        assert!(is_guest(&db, &config, &message(ChannelType::Email, "owner")).await);
+        assert!(!is_guest(&db, &config, &message(ChannelType::Internal, "watcher")).await);
+        set_toggle(&db, "all", false).await.unwrap();
+        set_toggle(&db, "slack", false).await.unwrap();
+        assert!(toggled_scopes(&db).await.unwrap().is_empty());
+        assert!(set_toggle(&db, "carrier-pigeon", true).await.is_err());
+
+        assert!(config.tool_allowed("search_knowledge"));
+        assert!(config.tool_allowed("remember"));
+        assert!(!config.tool_allowed("send_email"));
+        assert!(!config.tool_allowed("write_file"));
+        assert!(!config.tool_allowed("run_command"));
+
+        let executor = GuestToolExecutor::new(Arc::new(ToolRegistry::new()), config);
+        let err = executor
+            .execute("send_email", serde_json::json!({}))
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("guest"));
+    }
+}
@@ -0,0 +1,618 @@
+//! Localization of user-facing strings and reply language selection
+//!
+//! The configured language (ISO 639-1 code, e.g. "es") selects the string
+//! table for notifications, digests, and the setup wizard, and tells the
+//! model which language to reply in. Per-sender overrides are learned from
+//! the language people actually write in, unless one has been pinned with
+//! `meepo config set agent.language <code> --for channel:sender`.
+
+use std::sync::OnceLock;
+
+use anyhow::{Result, anyhow};
+use meepo_knowledge::KnowledgeDb;
+use tracing::debug;
+
+/// Preference category holding per-sender reply languages, keyed by
+/// `language:channel:sender`
+pub const PREFERENCE_CATEGORY: &str = "language";
+
+/// `learned_from` of a per-sender language set by hand; detection never
+/// overrides it
+pub const PINNED: &str = "config";
+
+static LANGUAGE: OnceLock<String> = OnceLock::new();
+
+/// Languages with a translated string table
+pub const SUPPORTED: &[&str] = &["en", "es", "fr", "de", "pt"];
+
+/// User-facing string templates. `{name}` placeholders are filled with [`fill`].
+#[derive(Debug)]
+pub struct Strings {
+    pub task_started: &'static str,
+    pub task_completed: &'static str,
+    pub task_failed: &'static str,
+    pub result_label: &'static str,
+    pub error_label: &'static str,
+    pub watcher_triggered: &'static str,
+    pub autonomous_action: &'static str,
+    pub budget_warning: &'static str,
+    pub budget_exceeded: &'static str,
+    pub notify_batched: &'static str,
+    pub notify_snoozed: &'static str,
+    pub notify_escalated: &'static str,
+    pub digest_morning: &'static str,
+    pub digest_evening: &'static str,
+    pub digest_watchers: &'static str,
+    pub digest_running: &'static str,
+    pub digest_completed: &'static str,
+    pub digest_failed: &'static str,
+    pub digest_goals: &'static str,
+    pub digest_email_briefs: &'static str,
+    pub digest_quarantine: &'static str,
+    pub digest_memory_review: &'static str,
+    pub digest_activity: &'static str,
+    pub digest_action_items: &'static str,
+    pub digest_due: &'static str,
+    pub digest_screen_time: &'static str,
+    pub digest_more: &'static str,
+    pub digest_quiet_morning: &'static str,
+    pub digest_quiet_evening: &'static str,
+    pub setup_title: &'static str,
+    pub setup_intro: &'static str,
+    pub setup_step: &'static str,
+    pub setup_complete: &'static str,
+    pub setup_complete_api_failed: &'static str,
+    pub setup_files_created: &'static str,
+    pub setup_quick_start: &'static str,
+    pub setup_customize: &'static str,
+}
+
+const EN: Strings = Strings {
+    task_started: "🤖 Starting background task",
+    task_completed: "✅ Task completed",
+    task_failed: "❌ Task failed",
+    result_label: "Result",
+    error_label: "Error",
+    watcher_triggered: "👁 Watcher triggered",
+    autonomous_action: "🧠 Taking autonomous action",
+    budget_warning: "💰 Budget warning: {period} spending at {percent}% (${spent} of ${budget})",
+    budget_exceeded: "🚨 Budget EXCEEDED: {period} spending ${spent} of ${budget} limit. API calls paused.",
+    notify_batched: "🔔 {n} notifications in the last {secs}s:",
+    notify_snoozed: "⏰ Snoozed notification:",
+    notify_escalated: "⚠️ Still waiting on your reply:",
+    digest_morning: "☀️ Good morning! Here's your briefing:",
+    digest_evening: "🌙 End of day recap:",
+    digest_watchers: "📡 {n} active watchers",
+    digest_running: "⚙️ {n} running tasks",
+    digest_completed: "✅ {n} tasks completed today",
+    digest_failed: "❌ {n} tasks failed",
+    digest_goals: "🎯 {n} goals due",
+    digest_email_briefs: "📰 {n} email briefs",
+    digest_quarantine: "🚫 {n} messages in quarantine (review with `meepo quarantine list`)",
+    digest_memory_review: "🧠 {n} memories waiting for review (`meepo memory review`)",
+    digest_activity: "📋 Today: {messages} messages, {actions} actions, {tasks} task updates, {watchers} watcher fires",
+    digest_action_items: "📝 {n} open action items",
+    digest_due: "due {date}",
+    digest_screen_time: "⏱ {time} screen time today",
+    digest_more: "... and {n} more",
+    digest_quiet_morning: "Nothing scheduled. Quiet day ahead!",
+    digest_quiet_evening: "Quiet day — no tasks or events to report.",
+    setup_title: "Meepo Setup Wizard",
+    setup_intro: "This wizard will walk you through everything:",
+    setup_step: "Step",
+    setup_complete: "✓ Setup complete!",
+    setup_complete_api_failed: "⚠ Setup complete (API check failed)",
+    setup_files_created: "Files created:",
+    setup_quick_start: "Quick start:",
+    setup_customize: "Customize further:",
+};
+
+const ES: Strings = Strings {
+    task_started: "🤖 Iniciando tarea en segundo plano",
+    task_completed: "✅ Tarea completada",
+    task_failed: "❌ La tarea falló",
+    result_label: "Resultado",
+    error_label: "Error",
+    watcher_triggered: "👁 Vigilante activado",
+    autonomous_action: "🧠 Realizando una acción autónoma",
+    budget_warning: "💰 Aviso de presupuesto: gasto {period} al {percent}% (${spent} de ${budget})",
+    budget_exceeded: "🚨 Presupuesto SUPERADO: gasto {period} de ${spent} sobre un límite de ${budget}. Llamadas a la API en pausa.",
+    notify_batched: "🔔 {n} notificaciones en los últimos {secs}s:",
+    notify_snoozed: "⏰ Notificación pospuesta:",
+    notify_escalated: "⚠️ Sigue esperando tu respuesta:",
+    digest_morning: "☀️ ¡Buenos días! Este es tu resumen:",
+    digest_evening: "🌙 Resumen del día:",
+    digest_watchers: "📡 {n} vigilantes activos",
+    digest_running: "⚙️ {n} tareas en curso",
+    digest_completed: "✅ {n} tareas completadas hoy",
+    digest_failed: "❌ {n} tareas fallidas",
+    digest_goals: "🎯 {n} objetivos pendientes",
+    digest_email_briefs: "📰 {n} resúmenes de correo",
+    digest_quarantine: "🚫 {n} mensajes en cuarentena (revísalos con `meepo quarantine list`)",
+    digest_memory_review: "🧠 {n} recuerdos pendientes de revisión (`meepo memory review`)",
+    digest_activity: "📋 Hoy: {messages} mensajes, {actions} acciones, {tasks} cambios de tareas, {watchers} alertas de vigilantes",
+    digest_action_items: "📝 {n} compromisos pendientes",
+    digest_due: "vence {date}",
+    digest_screen_time: "⏱ {time} de tiempo de pantalla hoy",
+    digest_more: "... y {n} más",
+    digest_quiet_morning: "Nada programado. ¡Día tranquilo!",
+    digest_quiet_evening: "Día tranquilo: no hay tareas ni eventos que reportar.",
+    setup_title: "Asistente de configuración de Meepo",
+    setup_intro: "Este asistente te guiará en todo el proceso:",
+    setup_step: "Paso",
+    setup_complete: "✓ ¡Configuración completa!",
+    setup_complete_api_failed: "⚠ Configuración completa (falló la prueba de API)",
+    setup_files_created: "Archivos creados:",
+    setup_quick_start: "Inicio rápido:",
+    setup_customize: "Más personalización:",
+};
+
+const FR: Strings = Strings {
+    task_started: "🤖 Démarrage d'une tâche en arrière-plan",
+    task_completed: "✅ Tâche terminée",
+    task_failed: "❌ Échec de la tâche",
+    result_label: "Résultat",
+    error_label: "Erreur",
+    watcher_triggered: "👁 Surveillance déclenchée",
+    autonomous_action: "🧠 Action autonome en cours",
+    budget_warning: "💰 Alerte budget : dépenses ({period}) à {percent}% (${spent} sur ${budget})",
+    budget_exceeded: "🚨 Budget DÉPASSÉ : dépenses ({period}) de ${spent} pour une limite de ${budget}. Appels API suspendus.",
+    notify_batched: "🔔 {n} notifications ces {secs} dernières secondes :",
+    notify_snoozed: "⏰ Notification reportée :",
+    notify_escalated: "⚠️ Toujours en attente de votre réponse :",
+    digest_morning: "☀️ Bonjour ! Voici votre briefing :",
+    digest_evening: "🌙 Récapitulatif de la journée :",
+    digest_watchers: "📡 {n} surveillances actives",
+    digest_running: "⚙️ {n} tâches en cours",
+    digest_completed: "✅ {n} tâches terminées aujourd'hui",
+    digest_failed: "❌ {n} tâches en échec",
+    digest_goals: "🎯 {n} objectifs à échéance",
+    digest_email_briefs: "📰 {n} résumés d'e-mails",
+    digest_quarantine: "🚫 {n} messages en quarantaine (à vérifier avec `meepo quarantine list`)",
+    digest_memory_review: "🧠 {n} souvenirs en attente de validation (`meepo memory review`)",
+    digest_activity: "📋 Aujourd'hui : {messages} messages, {actions} actions, {tasks} mises à jour de tâches, {watchers} déclenchements de surveillances",
+    digest_action_items: "📝 {n} actions en attente",
+    digest_due: "échéance {date}",
+    digest_screen_time: "⏱ {time} de temps d'écran aujourd'hui",
+    digest_more: "... et {n} de plus",
+    digest_quiet_morning: "Rien de prévu. Journée calme en perspective !",
+    digest_quiet_evening: "Journée calme — aucune tâche ni aucun événement à signaler.",
+    setup_title: "Assistant de configuration Meepo",
+    setup_intro: "Cet assistant vous guide à travers toutes les étapes :",
+    setup_step: "Étape",
+    setup_complete: "✓ Configuration terminée !",
+    setup_complete_api_failed: "⚠ Configuration terminée (test API échoué)",
+    setup_files_created: "Fichiers créés :",
+    setup_quick_start: "Démarrage rapide :",
+    setup_customize: "Personnalisation :",
+};
+
+const DE: Strings = Strings {
+    task_started: "🤖 Hintergrundaufgabe wird gestartet",
+    task_completed: "✅ Aufgabe abgeschlossen",
+    task_failed: "❌ Aufgabe fehlgeschlagen",
+    result_label: "Ergebnis",
+    error_label: "Fehler",
+    watcher_triggered: "👁 Watcher ausgelöst",
+    autonomous_action: "🧠 Autonome Aktion wird ausgeführt",
+    budget_warning: "💰 Budgetwarnung: Ausgaben ({period}) bei {percent}% (${spent} von ${budget})",
+    budget_exceeded: "🚨 Budget ÜBERSCHRITTEN: Ausgaben ({period}) ${spent} bei ${budget} Limit. API-Aufrufe pausiert.",
+    notify_batched: "🔔 {n} Benachrichtigungen in den letzten {secs}s:",
+    notify_snoozed: "⏰ Zurückgestellte Benachrichtigung:",
+    notify_escalated: "⚠️ Wartet noch auf deine Antwort:",
+    digest_morning: "☀️ Guten Morgen! Hier ist dein Briefing:",
+    digest_evening: "🌙 Tagesrückblick:",
+    digest_watchers: "📡 {n} aktive Watcher",
+    digest_running: "⚙️ {n} laufende Aufgaben",
+    digest_completed: "✅ {n} Aufgaben heute erledigt",
+    digest_failed: "❌ {n} Aufgaben fehlgeschlagen",
+    digest_goals: "🎯 {n} fällige Ziele",
+    digest_email_briefs: "📰 {n} E-Mail-Zusammenfassungen",
+    digest_quarantine: "🚫 {n} Nachrichten in Quarantäne (prüfen mit `meepo quarantine list`)",
+    digest_memory_review: "🧠 {n} Erinnerungen warten auf Prüfung (`meepo memory review`)",
+    digest_activity: "📋 Heute: {messages} Nachrichten, {actions} Aktionen, {tasks} Aufgaben-Updates, {watchers} ausgelöste Watcher",
+    digest_action_items: "📝 {n} offene Aufgaben",
+    digest_due: "fällig {date}",
+    digest_screen_time: "⏱ {time} Bildschirmzeit heute",
+    digest_more: "... und {n} weitere",
+    digest_quiet_morning: "Nichts geplant. Ein ruhiger Tag steht bevor!",
+    digest_quiet_evening: "Ruhiger Tag — keine Aufgaben oder Ereignisse.",
+    setup_title: "Meepo-Einrichtungsassistent",
+    setup_intro: "Dieser Assistent führt dich durch alle Schritte:",
+    setup_step: "Schritt",
+    setup_complete: "✓ Einrichtung abgeschlossen!",
+    setup_complete_api_failed: "⚠ Einrichtung abgeschlossen (API-Test fehlgeschlagen)",
+    setup_files_created: "Erstellte Dateien:",
+    setup_quick_start: "Schnellstart:",
+    setup_customize: "Weitere Anpassungen:",
+};
+
+const PT: Strings = Strings {
+    task_started: "🤖 Iniciando tarefa em segundo plano",
+    task_completed: "✅ Tarefa concluída",
+    task_failed: "❌ A tarefa falhou",
+    result_label: "Resultado",
+    error_label: "Erro",
+    watcher_triggered: "👁 Monitor acionado",
+    autonomous_action: "🧠 Executando uma ação autônoma",
+    budget_warning: "💰 Aviso de orçamento: gasto {period} em {percent}% (${spent} de ${budget})",
+    budget_exceeded: "🚨 Orçamento EXCEDIDO: gasto {period} de ${spent} para um limite de ${budget}. Chamadas de API pausadas.",
+    notify_batched: "🔔 {n} notificações nos últimos {secs}s:",
+    notify_snoozed: "⏰ Notificação adiada:",
+    notify_escalated: "⚠️ Ainda aguardando sua resposta:",
+    digest_morning: "☀️ Bom dia! Aqui está o seu resumo:",
+    digest_evening: "🌙 Resumo do dia:",
+    digest_watchers: "📡 {n} monitores ativos",
+    digest_running: "⚙️ {n} tarefas em andamento",
+    digest_completed: "✅ {n} tarefas concluídas hoje",
+    digest_failed: "❌ {n} tarefas com falha",
+    digest_goals: "🎯 {n} metas pendentes",
+    digest_email_briefs: "📰 {n} resumos de e-mail",
+    digest_quarantine: "🚫 {n} mensagens em quarentena (revise com `meepo quarantine list`)",
+    digest_memory_review: "🧠 {n} memórias aguardando revisão (`meepo memory review`)",
+    digest_activity: "📋 Hoje: {messages} mensagens, {actions} ações, {tasks} atualizações de tarefas, {watchers} disparos de vigilantes",
+    digest_action_items: "📝 {n} ações pendentes",
+    digest_due: "prazo {date}",
+    digest_screen_time: "⏱ {time} de tempo de tela hoje",
+    digest_more: "... e mais {n}",
+    digest_quiet_morning: "Nada agendado. Dia tranquilo pela frente!",
+    digest_quiet_evening: "Dia tranquilo — nenhuma tarefa ou evento para relatar.",
+    setup_title: "Assistente de configuração do Meepo",
+    setup_intro: "Este assistente vai guiá-lo por todo o processo:",
+    setup_step: "Passo",
+    setup_complete: "✓ Configuração concluída!",
+    setup_complete_api_failed: "⚠ Configuração concluída (teste de API falhou)",
+    setup_files_created: "Arquivos criados:",
+    setup_quick_start: "Início rápido:",
+    setup_customize: "Mais personalização:",
+};
+
+/// Reduce a language tag ("es-MX", "pt_BR.UTF-8") to its ISO 639-1 code
+pub fn normalize(code: &str) -> String {
+    code.trim()
+        .split(['-', '_', '.'])
+        .next()
+        .unwrap_or("")
+        .to_lowercase()
+}
+
+/// String table for a language (English if untranslated)
+pub fn strings(code: &str) -> &'static Strings {
+    match normalize(code).as_str() {
+        "es" => &ES,
+        "fr" => &FR,
+        "de" => &DE,
+        "pt" => &PT,
+        _ => &EN,
+    }
+}
+
+/// Fill `{name}` placeholders in a template
+pub fn fill(template: &str, args: &[(&str, &str)]) -> String {
+    let mut out = template.to_string();
+    for (name, value) in args {
+        out = out.replace(&format!("{{{}}}", name), value);
+    }
+    out
+}
+
+/// English name of a language, for model instructions
+pub fn language_name(code: &str) -> &'static str {
+    match normalize(code).as_str() {
+        "en" => "English",
+        "es" => "Spanish",
+        "fr" => "French",
+        "de" => "German",
+        "pt" => "Portuguese",
+        "it" => "Italian",
+        "nl" => "Dutch",
+        "ja" => "Japanese",
+        "zh" => "Chinese",
+        "ko" => "Korean",
+        "ru" => "Russian",
+        _ => "the user's language",
+    }
+}
+
+/// Set the process-wide default language. Only the first call takes effect.
+pub fn set_language(code: &str) {
+    let code = normalize(code);
+    let code = if code.is_empty() {
+        "en".to_string()
+    } else {
+        code
+    };
+    if LANGUAGE.set(code.clone()).is_ok() {
+        debug!("Default language set to {}", code);
+    }
+}
+
+/// The configured default language (English if never set)
+pub fn language() -> &'static str {
+    LANGUAGE.get().map(|s| s.as_str()).unwrap_or("en")
+}
+
+/// Language from the environment (`LC_ALL`, `LANG`), e.g. for the setup wizard
+pub fn system_language() -> Option<String> {
+    ["LC_ALL", "LC_MESSAGES", "LANG"]
+        .iter()
+        .filter_map(|var| std::env::var(var).ok())
+        .map(|v| normalize(&v))
+        .find(|code| code.len() == 2)
+}
+
+/// System prompt instruction for the reply language
+pub fn reply_instruction(code: &str, learned: bool) -> String {
+    let name = language_name(code);
+    if learned {
+        format!(
+            "Reply in {} — this person usually writes in it — unless they write to you in \
+             another language.",
+            name
+        )
+    } else {
+        format!(
+            "Reply in {} by default. If the user writes in another language, reply in that \
+             language instead.",
+            name
+        )
+    }
+}
+
+/// Common function words used to guess the language of a message
+const STOPWORDS: &[(&str, &[&str])] = &[
+    (
+        "en",
+        &[
+            "the", "and", "is", "are", "you", "to", "of", "what", "can", "please", "with", "for",
+            "this", "that", "my",
+        ],
+    ),
+    (
+        "es",
+        &[
+            "el", "la", "los", "las", "que", "y", "es", "por", "para", "una", "con", "qué", "cómo",
+            "mi", "puedes", "gracias",
+        ],
+    ),
+    (
+        "fr",
+        &[
+            "le", "la", "les", "et", "est", "vous", "une", "des", "pour", "avec", "je", "pas",
+            "merci", "mon", "peux",
+        ],
+    ),
+    (
+        "de",
+        &[
+            "der", "die", "das", "und", "ist", "ich", "nicht", "mit", "ein", "eine", "bitte", "du",
+            "kannst", "mein", "danke",
+        ],
+    ),
+    (
+        "pt",
+        &[
+            "o", "os", "as", "que", "e", "é", "não", "uma", "com", "para", "você", "meu",
+            "obrigado", "pode", "isso",
+        ],
+    ),
+    (
+        "it",
+        &[
+            "il", "lo", "gli", "che", "è", "per", "non", "una", "sono", "mi", "puoi", "grazie",
+            "della", "questo", "ciao",
+        ],
+    ),
+    (
+        "nl",
+        &[
+            "de",
+            "het",
+            "een",
+            "en",
+            "is",
+            "niet",
+            "ik",
+            "je",
+            "van",
+            "met",
+            "voor",
+            "kun",
+            "alsjeblieft",
+            "dank",
+            "mijn",
+        ],
+    ),
+];
+
+/// Guess the language of text written in a non-Latin script. A few
+/// characters are enough, since the script alone gives the language away.
+fn detect_script(words: &[String]) -> Option<&'static str> {
+    let (mut letters, mut hangul, mut kana, mut han, mut cyrillic) = (0, 0, 0, 0, 0);
+    for c in words.iter().flat_map(|w| w.chars()) {
+        letters += 1;
+        match c {
+            '\u{AC00}'..='\u{D7AF}' | '\u{1100}'..='\u{11FF}' | '\u{3130}'..='\u{318F}' => {
+                hangul += 1
+            }
+            '\u{3040}'..='\u{30FF}' => kana += 1,
+            '\u{4E00}'..='\u{9FFF}' | '\u{3400}'..='\u{4DBF}' => han += 1,
+            '\u{0400}'..='\u{04FF}' => cyrillic += 1,
+            _ => {}
+        }
+    }
+    let cjk = kana + han;
+    let (lang, count) = [
+        ("ko", hangul),
+        (if kana > 0 { "ja" } else { "zh" }, cjk),
+        ("ru", cyrillic),
+    ]
+    .into_iter()
+    .max_by_key(|(_, count)| *count)?;
+    (count >= 2 && count * 2 >= letters).then_some(lang)
+}
+
+/// Guess the language of a message from common function words.
+///
+/// Returns `None` for short or ambiguous text so a single "ok" or a link
+/// never flips someone's language.
+pub fn detect_language(text: &str) -> Option<&'static str> {
+    // Ignore URLs and addresses, whose fragments look like short words
+    let words: Vec<String> = text
+        .split_whitespace()
+        .filter(|t| !t.contains("://") && !t.contains('@') && !t.starts_with("www."))
+        .flat_map(|t| t.split(|c: char| !c.is_alphabetic()))
+        .filter(|w| !w.is_empty())
+        .map(|w| w.to_lowercase())
+        .collect();
+    if let Some(lang) = detect_script(&words) {
+        return Some(lang);
+    }
+    if words.len() < 5 {
+        return None;
+    }
+
+    let mut scores: Vec<(&'static str, usize)> = STOPWORDS
+        .iter()
+        .map(|(lang, stops)| {
+            let hits = words.iter().filter(|w| stops.contains(&w.as_str())).count();
+            (*lang, hits)
+        })
+        .collect();
+    scores.sort_by_key(|(_, hits)| std::cmp::Reverse(*hits));
+
+    let (best, best_score) = scores[0];
+    let runner_up = scores[1].1;
+    if best_score >= 2 && best_score >= runner_up * 2 {
+        Some(best)
+    } else {
+        None
+    }
+}
+
+/// Pin the reply language for a `channel:sender`, or `unset` to go back
+/// to detecting it
+pub async fn pin_language(db: &KnowledgeDb, scope: &str, code: &str) -> Result<()> {
+    if code.trim().eq_ignore_ascii_case("unset") {
+        db.delete_category_preference(
+            PREFERENCE_CATEGORY,
+            &crate::style::preference_key(PREFERENCE_CATEGORY, scope),
+        )
+        .await?;
+        return Ok(());
+    }
+    let code = normalize(code);
+    if code.len() != 2 || !code.chars().all(|c| c.is_ascii_lowercase()) {
+        return Err(anyhow!(
+            "Language must be an ISO 639-1 code like 'de', got '{}'",
+            code
+        ));
+    }
+    db.upsert_preference(
+        PREFERENCE_CATEGORY,
+        &crate::style::preference_key(PREFERENCE_CATEGORY, scope),
+        serde_json::json!(code),
+        1.0,
+        Some(PINNED),
+    )
+    .await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize() {
+        assert_eq!(normalize("es-MX"), "es");
+        assert_eq!(normalize("pt_BR.UTF-8"), "pt");
+        assert_eq!(normalize(" EN "), "en");
+    }
+
+    #[test]
+    fn test_strings_fallback_to_english() {
+        assert_eq!(strings("de-AT").task_completed, "✅ Aufgabe abgeschlossen");
+        assert_eq!(strings("xx").task_completed, "✅ Task completed");
+    }
+
+    #[test]
+    fn test_fill() {
+        assert_eq!(
+            fill(strings("en").digest_watchers, &[("n", "3")]),
+            "📡 3 active watchers"
+        );
+        assert_eq!(fill("{a}-{b}-{a}", &[("a", "1"), ("b", "2")]), "1-2-1");
+    }
+
+    #[test]
+    fn test_all_tables_have_placeholders() {
+        for code in SUPPORTED {
+            let s = strings(code);
+            for template in [
+                s.digest_watchers,
+                s.digest_running,
+                s.digest_completed,
+                s.digest_failed,
+                s.digest_goals,
+                s.digest_email_briefs,
+                s.digest_quarantine,
+                s.digest_memory_review,
+                s.digest_action_items,
+                s.digest_more,
+            ] {
+                assert!(template.contains("{n}"), "{}: {}", code, template);
+            }
+            assert!(s.digest_due.contains("{date}"), "{}", code);
+            assert!(s.digest_screen_time.contains("{time}"), "{}", code);
+            assert!(s.budget_warning.contains("{percent}"), "{}", code);
+            assert!(s.budget_exceeded.contains("{budget}"), "{}", code);
+            assert!(s.notify_batched.contains("{n}"), "{}", code);
+        }
+    }
+
+    #[test]
+    fn test_detect_language() {
+        assert_eq!(
+            detect_language("Can you please check what the weather is for my trip"),
+            Some("en")
+        );
+        assert_eq!(
+            detect_language("¿Puedes revisar el calendario para la reunión con el equipo?"),
+            Some("es")
+        );
+        assert_eq!(
+            detect_language("Kannst du bitte die Datei mit dem Bericht senden, danke"),
+            Some("de")
+        );
+        assert_eq!(
+            detect_language("Est-ce que vous pouvez envoyer le rapport pour la réunion"),
+            Some("fr")
+        );
+        assert_eq!(
+            detect_language("Puoi controllare il calendario per la riunione di domani? Grazie"),
+            Some("it")
+        );
+        assert_eq!(
+            detect_language("Kun je het rapport voor de vergadering sturen?"),
+            Some("nl")
+        );
+        // Non-Latin scripts are recognised even in short messages
+        assert_eq!(detect_language("Привет, как дела?"), Some("ru"));
+        assert_eq!(detect_language("안녕하세요"), Some("ko"));
+        assert_eq!(detect_language("ありがとう、明日会いましょう"), Some("ja"));
+        assert_eq!(detect_language("明天见"), Some("zh"));
+        assert_eq!(detect_language("ok 👍 Ж"), None);
+        assert_eq!(detect_language("ok thanks"), None);
+        assert_eq!(
+            detect_language("see https://example.com/a/e/com and https://x.com/o/e"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_reply_instruction() {
+        assert!(reply_instruction("es", false).starts_with("Reply in Spanish by default"));
+        assert!(reply_instruction("de", true).contains("usually writes"));
+    }
+}
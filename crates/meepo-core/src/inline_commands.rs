@@ -0,0 +1,256 @@
+//! Inline conversation commands (`!model`, `!temp`, `!tools`, `!reset`,
+//! `!pin`, `!unpin`, `!pins`)
+//!
+//! Lines at the start of a message that look like `!model haiku` change how
+//! the agent answers that sender on that channel. Settings are stored as
+//! `overrides` user preferences keyed by `channel:sender`, so they stick
+//! until the user sends `!reset` (or `default` for a single setting).
+//! Anything after the command lines is handled as a normal message.
+//! The pin commands are handled by [`crate::pins`].
+
+use anyhow::{Result, anyhow};
+use meepo_knowledge::KnowledgeDb;
+use serde::{Deserialize, Serialize};
+
+use crate::providers::overrides::{self, RequestOverrides};
+use crate::style::{preference_key, sender_scope};
+
+/// Preference category used for conversation settings
+pub const CATEGORY: &str = "overrides";
+
+/// Per-conversation settings set with inline commands
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ConversationSettings {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub model: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub temperature: Option<f32>,
+    /// `Some(false)` turns tool use off for the conversation
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tools: Option<bool>,
+}
+
+impl ConversationSettings {
+    pub fn is_empty(&self) -> bool {
+        self.model.is_none() && self.temperature.is_none() && self.tools.is_none()
+    }
+
+    pub fn tools_enabled(&self) -> bool {
+        self.tools.unwrap_or(true)
+    }
+
+    /// Request overrides to apply to LLM calls for this conversation
+    pub fn request_overrides(&self) -> RequestOverrides {
+        RequestOverrides {
+            model: self.model.clone(),
+            temperature: self.temperature,
+            ..Default::default()
+        }
+    }
+
+    /// Apply one command, e.g. `("temp", "0.2")`
+    pub fn apply(&mut self, command: &str, arg: &str) -> Result<()> {
+        let arg = arg.trim();
+        let clear = arg.eq_ignore_ascii_case("default") || arg.eq_ignore_ascii_case("reset");
+        match command {
+            "model" => {
+                if arg.is_empty() {
+                    return Err(anyhow!("Usage: !model <name|default>"));
+                }
+                self.model = (!clear).then(|| overrides::resolve_alias(arg));
+            }
+            "temp" | "temperature" => {
+                self.temperature = if clear {
+                    None
+                } else {
+                    let value: f32 = arg
+                        .parse()
+                        .map_err(|_| anyhow!("Usage: !temp <0.0-2.0|default>"))?;
+                    if !(0.0..=2.0).contains(&value) {
+                        return Err(anyhow!("Temperature must be between 0.0 and 2.0"));
+                    }
+                    Some(value)
+                };
+            }
+            "tools" => {
+                self.tools = match arg.to_lowercase().as_str() {
+                    "on" | "true" | "yes" => Some(true),
+                    "off" | "false" | "no" | "none" => Some(false),
+                    "default" | "reset" => None,
+                    _ => return Err(anyhow!("Usage: !tools on|off")),
+                };
+            }
+            "reset" => *self = Self::default(),
+            other => return Err(anyhow!("Unknown command !{}", other)),
+        }
+        Ok(())
+    }
+
+    /// Short human-readable summary, e.g. "model=claude-haiku-4-5, tools=off"
+    pub fn describe(&self) -> String {
+        let mut parts = Vec::new();
+        if let Some(model) = &self.model {
+            parts.push(format!("model={}", model));
+        }
+        if let Some(temperature) = self.temperature {
+            parts.push(format!("temperature={}", temperature));
+        }
+        if let Some(tools) = self.tools {
+            parts.push(format!("tools={}", if tools { "on" } else { "off" }));
+        }
+        if parts.is_empty() {
+            "defaults".to_string()
+        } else {
+            parts.join(", ")
+        }
+    }
+}
+
+/// A message split into its leading commands and the remaining text
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ParsedMessage {
+    /// `(command, argument)` pairs in the order they appeared
+    pub commands: Vec<(String, String)>,
+    pub rest: String,
+}
+
+/// Commands recognized at the start of a message
+const COMMANDS: &[&str] = &[
+    "model",
+    "temp",
+    "temperature",
+    "tools",
+    "reset",
+    "pin",
+    "unpin",
+    "pins",
+];
+
+/// Split leading command lines off a message. Lines starting with `!` that
+/// aren't known commands end the command block and stay in the text.
+pub fn parse(content: &str) -> ParsedMessage {
+    let mut commands = Vec::new();
+    let mut lines = content.lines().peekable();
+    while let Some(line) = lines.peek() {
+        let line = line.trim();
+        if line.is_empty() && !commands.is_empty() {
+            lines.next();
+            continue;
+        }
+        let Some(body) = line.strip_prefix('!') else {
+            break;
+        };
+        let (command, arg) = body.split_once(char::is_whitespace).unwrap_or((body, ""));
+        let command = command.to_lowercase();
+        if !COMMANDS.contains(&command.as_str()) {
+            break;
+        }
+        commands.push((command, arg.trim().to_string()));
+        lines.next();
+    }
+    ParsedMessage {
+        commands,
+        rest: lines.collect::<Vec<_>>().join("\n").trim().to_string(),
+    }
+}
+
+/// Load the settings for a sender on a channel (empty if none)
+pub async fn load(db: &KnowledgeDb, channel: &str, sender: &str) -> Result<ConversationSettings> {
+    let key = preference_key(CATEGORY, &sender_scope(channel, sender));
+    let prefs = db.get_preferences(Some(CATEGORY)).await?;
+    Ok(prefs
+        .into_iter()
+        .find(|p| p.key == key)
+        .and_then(|p| serde_json::from_value(p.value).ok())
+        .unwrap_or_default())
+}
+
+/// Store the settings for a sender on a channel
+pub async fn save(
+    db: &KnowledgeDb,
+    channel: &str,
+    sender: &str,
+    settings: &ConversationSettings,
+) -> Result<()> {
+    db.upsert_preference(
+        CATEGORY,
+        &preference_key(CATEGORY, &sender_scope(channel, sender)),
+        serde_json::to_value(settings)?,
+        1.0,
+        Some("inline command"),
+    )
+    .await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_parse_leading_commands() {
+        let parsed = parse("!model haiku\n!temp 0.2\n\nSummarize my inbox");
+        assert_eq!(
+            parsed.commands,
+            vec![
+                ("model".to_string(), "haiku".to_string()),
+                ("temp".to_string(), "0.2".to_string()),
+            ]
+        );
+        assert_eq!(parsed.rest, "Summarize my inbox");
+
+        // Only leading lines count, and unknown commands are left alone
+        let parsed = parse("hello\n!tools off");
+        assert!(parsed.commands.is_empty());
+        assert_eq!(parsed.rest, "hello\n!tools off");
+        let parsed = parse("!important: call mom");
+        assert!(parsed.commands.is_empty());
+        assert_eq!(parsed.rest, "!important: call mom");
+
+        assert_eq!(parse("!reset").rest, "");
+    }
+
+    #[test]
+    fn test_apply_commands() {
+        let mut settings = ConversationSettings::default();
+        settings.apply("model", "haiku").unwrap();
+        settings.apply("temp", "0.2").unwrap();
+        settings.apply("tools", "off").unwrap();
+        assert_eq!(settings.model.as_deref(), Some("claude-haiku-4-5"));
+        assert!(!settings.tools_enabled());
+        assert_eq!(
+            settings.describe(),
+            "model=claude-haiku-4-5, temperature=0.2, tools=off"
+        );
+
+        assert!(settings.apply("temp", "5").is_err());
+        assert!(settings.apply("temp", "warm").is_err());
+        assert!(settings.apply("tools", "maybe").is_err());
+
+        settings.apply("model", "default").unwrap();
+        assert_eq!(settings.model, None);
+        settings.apply("reset", "").unwrap();
+        assert!(settings.is_empty());
+        assert_eq!(settings.describe(), "defaults");
+    }
+
+    #[tokio::test]
+    async fn test_settings_persist_per_sender() {
+        let temp = TempDir::new().unwrap();
+        let db = KnowledgeDb::new(temp.path().join("test.db")).unwrap();
+
+        let settings = ConversationSettings {
+            tools: Some(false),
+            ..Default::default()
+        };
+        save(&db, "slack", "alice", &settings).await.unwrap();
+        assert_eq!(load(&db, "slack", "alice").await.unwrap(), settings);
+        assert!(load(&db, "slack", "bob").await.unwrap().is_empty());
+
+        save(&db, "slack", "alice", &ConversationSettings::default())
+            .await
+            .unwrap();
+        assert!(load(&db, "slack", "alice").await.unwrap().is_empty());
+    }
+}
@@ -0,0 +1,447 @@
+//! Knowledge-gap detection and research goals
+//!
+//! When a question pulls little or nothing out of the knowledge base, the
+//! topic is recorded as a knowledge gap (a `knowledge_gaps` user preference
+//! keyed by the topic's content words). Once the same gap has come up a few
+//! times, a research goal can be created so the autonomous loop fills it with
+//! `web_search` and `remember`/`ingest_document`. Research is capped per day
+//! and only runs while today's estimated spend is under a budget.
+
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use meepo_knowledge::embeddings::content_words;
+use meepo_knowledge::{Goal, KnowledgeDb};
+use serde::{Deserialize, Serialize};
+use tracing::{debug, info};
+
+/// Preference category used for knowledge gaps
+pub const CATEGORY: &str = "knowledge_gaps";
+
+/// `source` of goals created to fill a knowledge gap
+pub const GOAL_SOURCE: &str = "knowledge_gap";
+
+/// Questions kept as examples per gap
+const MAX_EXAMPLES: usize = 5;
+
+/// Content words used to identify a topic
+const TOPIC_WORDS: usize = 3;
+
+/// Question words that mark a message as a question without a `?`
+const QUESTION_WORDS: &[&str] = &[
+    "who", "what", "when", "where", "why", "how", "which", "is", "are", "does",
+];
+
+/// Configuration for knowledge-gap detection
+#[derive(Debug, Clone)]
+pub struct KnowledgeGapConfig {
+    pub enabled: bool,
+    /// Retrieval score (fraction of search terms with a match) below which a
+    /// question counts as poorly answered
+    pub min_score: f64,
+    /// Times a gap must come up before research is started
+    pub min_occurrences: u32,
+    /// Create research goals for recurring gaps
+    pub research_goals: bool,
+    /// Maximum research goals created per day
+    pub max_research_goals_per_day: u32,
+    /// Research only starts or continues while today's estimated spend is
+    /// below this amount (USD)
+    pub research_budget_usd: f64,
+}
+
+impl Default for KnowledgeGapConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            min_score: 0.34,
+            min_occurrences: 3,
+            research_goals: false,
+            max_research_goals_per_day: 2,
+            research_budget_usd: 0.50,
+        }
+    }
+}
+
+/// A topic the knowledge base couldn't answer questions about
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct KnowledgeGap {
+    pub topic: String,
+    /// Recent questions that hit this gap, newest last
+    pub questions: Vec<String>,
+    pub occurrences: u32,
+    /// Best retrieval score seen for the topic
+    pub best_score: f64,
+    pub first_seen: DateTime<Utc>,
+    pub last_seen: DateTime<Utc>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub research_goal_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub research_started_at: Option<DateTime<Utc>>,
+    /// Set once a question on the topic is answered well from stored knowledge
+    #[serde(default)]
+    pub resolved: bool,
+}
+
+/// Whether a message reads like a question
+pub fn is_question(text: &str) -> bool {
+    let text = text.trim();
+    if text.ends_with('?') {
+        return true;
+    }
+    text.split_whitespace()
+        .next()
+        .map(|w| QUESTION_WORDS.contains(&w.to_lowercase().as_str()))
+        .unwrap_or(false)
+}
+
+/// Topic for a set of search terms: the first few distinct content words.
+/// Returns `(key, topic)`, where the key is order-independent.
+pub fn topic_for(terms: &[&str]) -> Option<(String, String)> {
+    let mut words: Vec<String> = Vec::new();
+    for word in content_words(&terms.join(" ")) {
+        if !words.contains(&word) {
+            words.push(word);
+        }
+        if words.len() == TOPIC_WORDS {
+            break;
+        }
+    }
+    if words.is_empty() {
+        return None;
+    }
+    let topic = words.join(" ");
+    words.sort();
+    Some((words.join(" "), topic))
+}
+
+/// Records knowledge gaps and turns recurring ones into research goals
+pub struct GapTracker<'a> {
+    db: &'a KnowledgeDb,
+    config: &'a KnowledgeGapConfig,
+}
+
+impl<'a> GapTracker<'a> {
+    pub fn new(db: &'a KnowledgeDb, config: &'a KnowledgeGapConfig) -> Self {
+        Self { db, config }
+    }
+
+    /// Note how well a question was answered from stored knowledge. Low
+    /// scores record (or bump) a gap; a good score resolves an open one.
+    /// Returns the gap if one was recorded.
+    pub async fn observe(
+        &self,
+        question: &str,
+        terms: &[&str],
+        score: f64,
+    ) -> Result<Option<KnowledgeGap>> {
+        if !self.config.enabled || !is_question(question) {
+            return Ok(None);
+        }
+        let Some((key, topic)) = topic_for(terms) else {
+            return Ok(None);
+        };
+        let existing = self.get(&key).await?;
+
+        if score >= self.config.min_score {
+            if let Some(mut gap) = existing
+                && !gap.resolved
+            {
+                debug!(
+                    "Knowledge gap '{}' answered (score {:.2})",
+                    gap.topic, score
+                );
+                gap.resolved = true;
+                gap.best_score = gap.best_score.max(score);
+                self.save(&key, &gap).await?;
+            }
+            return Ok(None);
+        }
+
+        let now = Utc::now();
+        let mut gap = match existing {
+            Some(gap) if !gap.resolved => gap,
+            _ => KnowledgeGap {
+                topic,
+                questions: Vec::new(),
+                occurrences: 0,
+                best_score: score,
+                first_seen: now,
+                last_seen: now,
+                research_goal_id: None,
+                research_started_at: None,
+                resolved: false,
+            },
+        };
+        gap.occurrences += 1;
+        gap.best_score = gap.best_score.max(score);
+        gap.last_seen = now;
+        let question = question.trim().to_string();
+        if !gap.questions.contains(&question) {
+            gap.questions.push(question);
+            if gap.questions.len() > MAX_EXAMPLES {
+                gap.questions.remove(0);
+            }
+        }
+
+        if let Some(goal_id) = self.maybe_start_research(&gap).await? {
+            gap.research_goal_id = Some(goal_id);
+            gap.research_started_at = Some(now);
+        }
+        self.save(&key, &gap).await?;
+        Ok(Some(gap))
+    }
+
+    async fn get(&self, key: &str) -> Result<Option<KnowledgeGap>> {
+        let prefs = self.db.get_preferences(Some(CATEGORY)).await?;
+        Ok(prefs
+            .into_iter()
+            .find(|p| p.key == key)
+            .and_then(|p| serde_json::from_value(p.value).ok()))
+    }
+
+    async fn save(&self, key: &str, gap: &KnowledgeGap) -> Result<()> {
+        self.db
+            .upsert_preference(
+                CATEGORY,
+                key,
+                serde_json::to_value(gap)?,
+                1.0,
+                Some("knowledge retrieval"),
+            )
+            .await?;
+        Ok(())
+    }
+
+    /// Create a research goal for a recurring gap, within the daily limits
+    async fn maybe_start_research(&self, gap: &KnowledgeGap) -> Result<Option<String>> {
+        if !self.config.research_goals
+            || gap.research_goal_id.is_some()
+            || gap.occurrences < self.config.min_occurrences
+        {
+            return Ok(None);
+        }
+
+        let today = Utc::now().date_naive();
+        let started_today = load_all(self.db)
+            .await?
+            .iter()
+            .filter(|g| g.research_started_at.map(|t| t.date_naive()) == Some(today))
+            .count() as u32;
+        if started_today >= self.config.max_research_goals_per_day {
+            debug!(
+                "Research goal limit reached for today, not researching '{}'",
+                gap.topic
+            );
+            return Ok(None);
+        }
+        if !within_budget(self.db, self.config.research_budget_usd).await? {
+            debug!(
+                "Research budget spent for today, not researching '{}'",
+                gap.topic
+            );
+            return Ok(None);
+        }
+
+        let example = gap.questions.last().map(String::as_str).unwrap_or_default();
+        let description = format!(
+            "Research \"{}\": the user has asked about this {} times (e.g. \"{}\") and \
+             stored knowledge had no good answer. Use web_search to find reliable sources, \
+             then save the key facts with remember or ingest_document so future questions \
+             can be answered.",
+            gap.topic, gap.occurrences, example
+        );
+        let criteria = format!("Stored knowledge answers questions about {}", gap.topic);
+        let goal_id = self
+            .db
+            .insert_goal(&description, 2, 3600, Some(&criteria), None, GOAL_SOURCE)
+            .await?;
+        info!(
+            "Created research goal {} for knowledge gap '{}'",
+            goal_id, gap.topic
+        );
+        Ok(Some(goal_id))
+    }
+}
+
+async fn load_all(db: &KnowledgeDb) -> Result<Vec<KnowledgeGap>> {
+    let prefs = db.get_preferences(Some(CATEGORY)).await?;
+    Ok(prefs
+        .into_iter()
+        .filter_map(|p| serde_json::from_value(p.value).ok())
+        .collect())
+}
+
+/// Open (unresolved) gaps, most frequent first
+pub async fn open_gaps(db: &KnowledgeDb) -> Result<Vec<KnowledgeGap>> {
+    let mut gaps: Vec<KnowledgeGap> = load_all(db)
+        .await?
+        .into_iter()
+        .filter(|g| !g.resolved)
+        .collect();
+    gaps.sort_by(|a, b| {
+        b.occurrences
+            .cmp(&a.occurrences)
+            .then(b.last_seen.cmp(&a.last_seen))
+    });
+    Ok(gaps)
+}
+
+/// Whether today's estimated spend is below the research budget
+pub async fn within_budget(db: &KnowledgeDb, budget_usd: f64) -> Result<bool> {
+    let today = Utc::now().format("%Y-%m-%d").to_string();
+    Ok(db.get_usage_cost_for_date(&today).await? < budget_usd)
+}
+
+/// Drop research goals from a batch of due goals once the budget is spent
+pub async fn hold_research_over_budget(
+    db: &KnowledgeDb,
+    goals: Vec<Goal>,
+    budget_usd: f64,
+) -> Vec<Goal> {
+    if !goals.iter().any(|g| g.source == GOAL_SOURCE) {
+        return goals;
+    }
+    match within_budget(db, budget_usd).await {
+        Ok(true) => goals,
+        Ok(false) => {
+            debug!("Research budget spent for today — holding research goals");
+            goals
+                .into_iter()
+                .filter(|g| g.source != GOAL_SOURCE)
+                .collect()
+        }
+        Err(e) => {
+            debug!("Failed to check research budget: {}", e);
+            goals
+        }
+    }
+}
+
+/// Render open gaps as a short list
+pub fn format_gaps(gaps: &[KnowledgeGap]) -> String {
+    if gaps.is_empty() {
+        return "No open knowledge gaps.".to_string();
+    }
+    let mut output = format!("{} open knowledge gap(s):\n", gaps.len());
+    for gap in gaps {
+        output.push_str(&format!(
+            "- {} (asked {}x, last {})",
+            gap.topic,
+            gap.occurrences,
+            gap.last_seen.format("%Y-%m-%d")
+        ));
+        if gap.research_goal_id.is_some() {
+            output.push_str(" [researching]");
+        }
+        if let Some(q) = gap.questions.last() {
+            output.push_str(&format!("\n  e.g. \"{}\"", q));
+        }
+        output.push('\n');
+    }
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_question_and_topic() {
+        assert!(is_question("What is the wifi password at the cabin?"));
+        assert!(is_question("how do I reset the router"));
+        assert!(!is_question("Remind me to call mom"));
+
+        let (key, topic) = topic_for(&["cabin", "wifi", "password", "cabin"]).unwrap();
+        assert_eq!(topic, "cabin wifi password");
+        assert_eq!(key, "cabin password wifi");
+        let (same_key, _) = topic_for(&["wifi", "password", "cabin"]).unwrap();
+        assert_eq!(key, same_key);
+        assert!(topic_for(&["the", "a"]).is_none());
+    }
+
+    #[tokio::test]
+    async fn test_recurring_gap_creates_research_goal() {
+        let temp = TempDir::new().unwrap();
+        let db = KnowledgeDb::new(temp.path().join("test.db")).unwrap();
+        let config = KnowledgeGapConfig {
+            research_goals: true,
+            min_occurrences: 2,
+            max_research_goals_per_day: 1,
+            ..Default::default()
+        };
+        let tracker = GapTracker::new(&db, &config);
+        let terms = ["zigbee", "thermostat"];
+
+        let gap = tracker
+            .observe("How do I pair the zigbee thermostat?", &terms, 0.0)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(gap.occurrences, 1);
+        assert!(gap.research_goal_id.is_none());
+
+        let gap = tracker
+            .observe("What zigbee thermostat do I have?", &terms, 0.0)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(gap.occurrences, 2);
+        let goal_id = gap.research_goal_id.clone().unwrap();
+        let goals = db.get_active_goals().await.unwrap();
+        assert_eq!(goals.len(), 1);
+        assert_eq!(goals[0].id, goal_id);
+        assert_eq!(goals[0].source, GOAL_SOURCE);
+        assert!(goals[0].description.contains("zigbee thermostat"));
+
+        // The daily limit stops a second topic from starting research
+        for _ in 0..2 {
+            tracker
+                .observe("Who fixes the boiler valve?", &["boiler", "valve"], 0.0)
+                .await
+                .unwrap();
+        }
+        assert_eq!(db.get_active_goals().await.unwrap().len(), 1);
+        assert_eq!(open_gaps(&db).await.unwrap().len(), 2);
+
+        // A good answer resolves the gap; statements are ignored
+        assert!(
+            tracker
+                .observe("Where is the zigbee thermostat?", &terms, 1.0)
+                .await
+                .unwrap()
+                .is_none()
+        );
+        assert!(
+            tracker
+                .observe("Buy a boiler valve", &["boiler", "valve"], 0.0)
+                .await
+                .unwrap()
+                .is_none()
+        );
+        let open = open_gaps(&db).await.unwrap();
+        assert_eq!(open.len(), 1);
+        assert_eq!(open[0].topic, "boiler valve");
+        assert!(format_gaps(&open).contains("boiler valve (asked 2x"));
+    }
+
+    #[tokio::test]
+    async fn test_research_held_over_budget() {
+        let temp = TempDir::new().unwrap();
+        let db = KnowledgeDb::new(temp.path().join("test.db")).unwrap();
+        let id = db
+            .insert_goal("Research x", 2, 3600, None, None, GOAL_SOURCE)
+            .await
+            .unwrap();
+        db.insert_goal("Water plants", 2, 3600, None, None, "user")
+            .await
+            .unwrap();
+        let goals = db.get_active_goals().await.unwrap();
+
+        let kept = hold_research_over_budget(&db, goals.clone(), 1.0).await;
+        assert_eq!(kept.len(), 2);
+        let kept = hold_research_over_budget(&db, goals, 0.0).await;
+        assert_eq!(kept.len(), 1);
+        assert_ne!(kept[0].id, id);
+    }
+}
@@ -7,31 +7,80 @@
 //! - Context loading from SOUL and MEMORY files
 //! - Integration with knowledge graph and watcher scheduler
 
+pub mod action_items;
 pub mod agent;
 pub mod agents;
 pub mod api;
+pub mod app_usage;
+pub mod artifact_sync;
 pub mod audio;
 pub mod autonomy;
+pub mod calendar_conflicts;
+pub mod calendar_feed;
+pub mod contact_policy;
 pub mod context;
+pub mod context_compression;
 pub mod corrective_rag;
 pub mod doctor;
+pub mod email_triage;
+pub mod experiments;
+pub mod expr;
+pub mod feedback;
+pub mod focus;
 pub mod guardrails;
+pub mod guest;
+pub mod i18n;
+pub mod inline_commands;
 pub mod intent;
+pub mod knowledge_gaps;
+pub mod live_context;
+pub mod loop_guard;
+pub mod meeting_prep;
+pub mod memory_review;
+pub mod message_templates;
 pub mod middleware;
+pub mod modes;
+pub mod network;
 pub mod notifications;
+pub mod onboarding;
 pub mod orchestrator;
+pub mod pins;
 pub mod platform;
+pub mod plugins;
+pub mod power;
 pub mod providers;
+pub mod push;
 pub mod query_router;
+pub mod questions;
+pub mod redaction;
 pub mod registry;
+pub mod reports;
+pub mod rerank;
+pub mod rules;
 pub mod sandbox;
+pub mod scratchpad;
 pub mod secrets;
+pub mod send_guard;
 pub mod skills;
+pub mod snooze;
+pub mod soul_template;
+pub mod spam_filter;
+pub mod streaming;
+pub mod structured;
+pub mod style;
 pub mod summarization;
+pub mod task_sync;
 pub mod tavily;
+pub mod timezone;
+pub mod tool_cost;
+pub mod tool_profiles;
+pub mod tool_scope;
 pub mod tool_selector;
+pub mod tool_summaries;
 pub mod tools;
+pub mod trace;
 pub mod types;
+pub mod uri;
 pub mod usage;
 
 // Re-export main types for convenience
@@ -48,10 +97,11 @@ pub use orchestrator::{
     TaskGroup, TaskOrchestrator,
 };
 pub use providers::{ChatMessage, ChatResponse, LlmProvider, ModelRouter};
-pub use query_router::{QueryComplexity, QueryRouterConfig, RetrievalStrategy};
+pub use query_router::{QueryComplexity, QueryRouterConfig, RetrievalStrategy, ThinkingBudgets};
 pub use summarization::SummarizationConfig;
 pub use tool_selector::ToolSelectorConfig;
 pub use tools::{ToolExecutor, ToolHandler, ToolRegistry};
+pub use trace::TurnTrace;
 pub use types::{ChannelType, IncomingMessage, MessageKind, OutgoingMessage};
 pub use usage::{AccumulatedUsage, BudgetStatus, UsageConfig, UsageSource, UsageTracker};
 
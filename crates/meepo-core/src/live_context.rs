@@ -0,0 +1,375 @@
+//! Live context for the system prompt
+//!
+//! A short list of facts about the user's situation right now — local
+//! time, battery, Focus mode, the next calendar event and unread counts —
+//! so the agent doesn't ask what time it is or claim it can't see the
+//! schedule. Each item can be turned off. Time, battery and Focus come
+//! from process-wide state; calendar and unread counts call out to the
+//! platform, so they're cached for `cache_secs` and fetched with a short
+//! timeout. Items that can't be read are left out.
+
+use std::time::{Duration, Instant};
+
+use chrono::{DateTime, Utc};
+use tokio::sync::Mutex;
+use tracing::debug;
+
+use crate::platform::{CalendarEvent, CalendarProvider, EmailProvider, MessagesProvider};
+use crate::{focus, power, timezone};
+
+/// Longest a platform read may hold up a reply
+const FETCH_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// Which items go into the live context
+#[derive(Debug, Clone)]
+pub struct LiveContextConfig {
+    /// Local date, time and timezone
+    pub time: bool,
+    /// Battery level and power source
+    pub battery: bool,
+    /// Active Focus mode
+    pub focus: bool,
+    /// Next calendar event within `event_hours`
+    pub next_event: bool,
+    /// Unread email and iMessage counts
+    pub unread: bool,
+    /// Look this far ahead for the next event
+    pub event_hours: u64,
+    /// Re-read calendar and unread counts after this many seconds
+    pub cache_secs: u64,
+    /// Locale for the date and time
+    pub locale: String,
+}
+
+impl Default for LiveContextConfig {
+    fn default() -> Self {
+        Self {
+            time: true,
+            battery: true,
+            focus: true,
+            next_event: true,
+            unread: true,
+            event_hours: 24,
+            cache_secs: 300,
+            locale: "en-US".to_string(),
+        }
+    }
+}
+
+/// The items read at one point in time; `None` means unknown or off
+#[derive(Debug, Clone, Default)]
+pub struct Snapshot {
+    pub time: Option<String>,
+    pub battery: Option<String>,
+    pub focus: Option<String>,
+    pub next_event: Option<Option<CalendarEvent>>,
+    pub unread_email: Option<u64>,
+    pub unread_messages: Option<u64>,
+}
+
+impl Snapshot {
+    /// Render as a bullet list, relative to `now`
+    pub fn render(&self, now: DateTime<Utc>) -> String {
+        let mut lines = Vec::new();
+        if let Some(time) = &self.time {
+            lines.push(format!("- Local time: {}", time));
+        }
+        if let Some(battery) = &self.battery {
+            lines.push(format!("- Power: {}", battery));
+        }
+        if let Some(mode) = &self.focus {
+            lines.push(format!("- Focus: {}", mode));
+        }
+        match &self.next_event {
+            Some(Some(event)) => {
+                lines.push(format!("- Next event: {}", describe_event(event, now)))
+            }
+            Some(None) => lines.push("- Next event: nothing scheduled soon".to_string()),
+            None => {}
+        }
+        let unread: Vec<String> = [
+            (self.unread_email, "email"),
+            (self.unread_messages, "iMessage"),
+        ]
+        .into_iter()
+        .filter_map(|(count, what)| count.map(|n| format!("{} {}", n, what)))
+        .collect();
+        if !unread.is_empty() {
+            lines.push(format!("- Unread: {}", unread.join(", ")));
+        }
+        if lines.is_empty() {
+            return String::new();
+        }
+        lines.push(String::new());
+        lines.join("\n")
+    }
+}
+
+fn describe_event(event: &CalendarEvent, now: DateTime<Utc>) -> String {
+    let local = event.start.with_timezone(&timezone::user_timezone());
+    let minutes = (event.start - now).num_minutes();
+    let when = if minutes <= 0 {
+        "now".to_string()
+    } else if minutes < 60 {
+        format!("in {} min", minutes)
+    } else {
+        format!("in {}h {}m", minutes / 60, minutes % 60)
+    };
+    let mut text = format!(
+        "\"{}\" at {} ({})",
+        event.title,
+        local.format("%H:%M"),
+        when
+    );
+    if let Some(location) = &event.location {
+        text.push_str(&format!(", {}", location));
+    }
+    text
+}
+
+/// A value and when it was read
+struct Cached<T> {
+    value: T,
+    at: Instant,
+}
+
+#[derive(Default)]
+struct Cache {
+    next_event: Option<Cached<Option<Option<CalendarEvent>>>>,
+    unread_email: Option<Cached<Option<u64>>>,
+    unread_messages: Option<Cached<Option<u64>>>,
+}
+
+/// Builds the live context section, caching the slow items
+pub struct LiveContext {
+    config: LiveContextConfig,
+    calendar: Option<Box<dyn CalendarProvider>>,
+    email: Option<Box<dyn EmailProvider>>,
+    messages: Option<Box<dyn MessagesProvider>>,
+    cache: Mutex<Cache>,
+}
+
+impl LiveContext {
+    pub fn new(config: LiveContextConfig) -> Self {
+        Self {
+            config,
+            calendar: None,
+            email: None,
+            messages: None,
+            cache: Mutex::new(Cache::default()),
+        }
+    }
+
+    /// Read the next event from this calendar
+    pub fn with_calendar(mut self, calendar: Box<dyn CalendarProvider>) -> Self {
+        self.calendar = Some(calendar);
+        self
+    }
+
+    /// Read the unread email count from this provider
+    pub fn with_email(mut self, email: Box<dyn EmailProvider>) -> Self {
+        self.email = Some(email);
+        self
+    }
+
+    /// Read the unread iMessage count from this provider
+    pub fn with_messages(mut self, messages: Box<dyn MessagesProvider>) -> Self {
+        self.messages = Some(messages);
+        self
+    }
+
+    fn fresh<T>(&self, cached: &Option<Cached<T>>) -> bool {
+        cached
+            .as_ref()
+            .is_some_and(|c| c.at.elapsed() < Duration::from_secs(self.config.cache_secs))
+    }
+
+    /// Read the enabled items, using cached values that aren't stale
+    pub async fn snapshot(&self) -> Snapshot {
+        let config = &self.config;
+        let mut snapshot = Snapshot::default();
+
+        if config.time {
+            let now = timezone::now();
+            snapshot.time = Some(format!(
+                "{} ({})",
+                timezone::format_local(&now, &config.locale),
+                now.timezone().name()
+            ));
+        }
+        if config.battery {
+            snapshot.battery = power::current()
+                .map(|(state, _)| state)
+                .filter(|s| s.battery_percent.is_some())
+                .map(|s| s.describe());
+        }
+        if config.focus {
+            snapshot.focus = focus::current().map(|s| s.mode.unwrap_or_else(|| "off".to_string()));
+        }
+
+        let mut cache = self.cache.lock().await;
+        if config.next_event
+            && let Some(calendar) = &self.calendar
+        {
+            if !self.fresh(&cache.next_event) {
+                let hours = config.event_hours.max(1);
+                let value = fetch("next event", calendar.upcoming_events(hours))
+                    .await
+                    .map(|events| {
+                        let now = Utc::now();
+                        events.into_iter().find(|e| e.end > now)
+                    });
+                cache.next_event = Some(Cached {
+                    value,
+                    at: Instant::now(),
+                });
+            }
+            let now = Utc::now();
+            snapshot.next_event = cache
+                .next_event
+                .as_ref()
+                .and_then(|c| c.value.clone())
+                .map(|event| event.filter(|e| e.end > now));
+        }
+        if config.unread {
+            if let Some(email) = &self.email {
+                if !self.fresh(&cache.unread_email) {
+                    cache.unread_email = Some(Cached {
+                        value: fetch("unread email", email.unread_count()).await,
+                        at: Instant::now(),
+                    });
+                }
+                snapshot.unread_email = cache.unread_email.as_ref().and_then(|c| c.value);
+            }
+            if let Some(messages) = &self.messages {
+                if !self.fresh(&cache.unread_messages) {
+                    cache.unread_messages = Some(Cached {
+                        value: fetch("unread iMessages", messages.unread_count()).await,
+                        at: Instant::now(),
+                    });
+                }
+                snapshot.unread_messages = cache.unread_messages.as_ref().and_then(|c| c.value);
+            }
+        }
+        snapshot
+    }
+
+    /// The live context section body; empty if nothing is known
+    pub async fn section(&self) -> String {
+        self.snapshot().await.render(Utc::now())
+    }
+}
+
+/// Run a platform read with a timeout; failures are logged and give `None`
+async fn fetch<T>(what: &str, read: impl Future<Output = anyhow::Result<T>>) -> Option<T> {
+    match tokio::time::timeout(FETCH_TIMEOUT, read).await {
+        Ok(Ok(value)) => Some(value),
+        Ok(Err(e)) => {
+            debug!("Live context: couldn't read {}: {}", what, e);
+            None
+        }
+        Err(_) => {
+            debug!("Live context: reading {} timed out", what);
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::Result;
+    use async_trait::async_trait;
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    struct CountingCalendar(Arc<AtomicUsize>);
+
+    #[async_trait]
+    impl CalendarProvider for CountingCalendar {
+        async fn read_events(&self, _days_ahead: u64) -> Result<String> {
+            unreachable!()
+        }
+
+        async fn create_event(&self, _: &str, _: &str, _: u64, _: Option<&str>) -> Result<String> {
+            unreachable!()
+        }
+
+        async fn upcoming_events(&self, _hours_ahead: u64) -> Result<Vec<CalendarEvent>> {
+            self.0.fetch_add(1, Ordering::SeqCst);
+            let now = Utc::now();
+            Ok(vec![CalendarEvent {
+                id: "e1".to_string(),
+                title: "Standup".to_string(),
+                start: now + chrono::Duration::minutes(30),
+                end: now + chrono::Duration::minutes(45),
+                location: None,
+                attendees: Vec::new(),
+            }])
+        }
+    }
+
+    #[test]
+    fn test_render() {
+        let now = Utc::now();
+        let snapshot = Snapshot {
+            time: Some("Saturday, October 17, 2026 9:14 AM EDT (America/New_York)".to_string()),
+            battery: Some("on battery (54%)".to_string()),
+            focus: Some("Deep Work".to_string()),
+            next_event: Some(Some(CalendarEvent {
+                id: "e1".to_string(),
+                title: "Dentist".to_string(),
+                start: now + chrono::Duration::minutes(90),
+                end: now + chrono::Duration::minutes(120),
+                location: Some("Main St".to_string()),
+                attendees: Vec::new(),
+            })),
+            unread_email: Some(3),
+            unread_messages: None,
+        };
+        let text = snapshot.render(now);
+        assert!(text.contains("- Local time: Saturday"));
+        assert!(text.contains("- Power: on battery (54%)"));
+        assert!(text.contains("- Focus: Deep Work"));
+        assert!(text.contains("\"Dentist\""));
+        assert!(text.contains("(in 1h 30m), Main St"));
+        assert!(text.contains("- Unread: 3 email\n"));
+
+        let empty = Snapshot {
+            next_event: Some(None),
+            ..Default::default()
+        };
+        assert_eq!(empty.render(now), "- Next event: nothing scheduled soon\n");
+        assert!(Snapshot::default().render(now).is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_toggles_and_cache() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let live = LiveContext::new(LiveContextConfig {
+            battery: false,
+            focus: false,
+            unread: false,
+            ..Default::default()
+        })
+        .with_calendar(Box::new(CountingCalendar(calls.clone())));
+
+        let first = live.section().await;
+        assert!(first.contains("Local time:"));
+        assert!(first.contains("\"Standup\""));
+        assert!(!first.contains("Power:"));
+        live.section().await;
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+
+        let uncached = LiveContext::new(LiveContextConfig {
+            time: false,
+            cache_secs: 0,
+            ..Default::default()
+        })
+        .with_calendar(Box::new(CountingCalendar(calls.clone())));
+        uncached.section().await;
+        let text = uncached.section().await;
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+        assert!(!text.contains("Local time:"));
+    }
+}
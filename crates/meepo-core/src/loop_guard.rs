@@ -0,0 +1,248 @@
+//! Loop detection for the tool-use cycle
+//!
+//! Models sometimes call the same tool with the same arguments over and
+//! over, or bounce between two tools without getting anywhere. The guard
+//! watches the calls in one tool loop and:
+//!
+//! - answers a repeated identical call with a reminder instead of running
+//!   it again, and stops the loop once a call has been repeated too often
+//! - stops the loop when the recent calls strictly alternate between two
+//!   tools
+//! - stops the loop after a maximum number of model calls
+//!
+//! When the loop is stopped the model is asked to summarize its partial
+//! progress, so the user gets an answer instead of an error.
+
+use std::collections::HashMap;
+use std::fmt;
+
+use serde_json::Value;
+
+/// Limits for one tool loop
+#[derive(Debug, Clone)]
+pub struct LoopGuardConfig {
+    /// Model calls before the loop is stopped
+    pub max_iterations: usize,
+    /// Times one exact call (tool and arguments) may be made before the
+    /// loop is stopped; repeats below the limit aren't run again
+    pub max_identical_calls: usize,
+    /// Consecutive calls alternating between two tools that stop the loop;
+    /// 0 turns ping-pong detection off
+    pub ping_pong_window: usize,
+}
+
+impl Default for LoopGuardConfig {
+    fn default() -> Self {
+        Self {
+            max_iterations: 10,
+            max_identical_calls: 3,
+            ping_pong_window: 8,
+        }
+    }
+}
+
+/// Why a tool loop was stopped
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LoopBreak {
+    /// Reached the model-call budget
+    MaxIterations(usize),
+    /// The same call was made too many times
+    RepeatedCall { tool: String, count: usize },
+    /// Calls kept alternating between two tools
+    PingPong { first: String, second: String },
+}
+
+impl fmt::Display for LoopBreak {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::MaxIterations(n) => write!(f, "reached the limit of {} tool rounds", n),
+            Self::RepeatedCall { tool, count } => {
+                write!(f, "called {} {} times with the same arguments", tool, count)
+            }
+            Self::PingPong { first, second } => write!(
+                f,
+                "kept alternating between {} and {} without finishing",
+                first, second
+            ),
+        }
+    }
+}
+
+/// What to do with one tool call
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CallVerdict {
+    /// Run it
+    Run,
+    /// Don't run it again; tell the model it already has the result
+    Repeat(String),
+    /// Stop the loop
+    Stop(LoopBreak),
+}
+
+/// Tracks the calls made in one tool loop
+#[derive(Debug)]
+pub struct LoopGuard {
+    config: LoopGuardConfig,
+    iterations: usize,
+    counts: HashMap<(String, String), usize>,
+    recent: Vec<String>,
+}
+
+impl LoopGuard {
+    pub fn new(config: LoopGuardConfig) -> Self {
+        Self {
+            config,
+            iterations: 0,
+            counts: HashMap::new(),
+            recent: Vec::new(),
+        }
+    }
+
+    /// Count a model call, stopping the loop once the budget is used
+    pub fn next_iteration(&mut self) -> Result<usize, LoopBreak> {
+        if self.iterations >= self.config.max_iterations {
+            return Err(LoopBreak::MaxIterations(self.config.max_iterations));
+        }
+        self.iterations += 1;
+        Ok(self.iterations)
+    }
+
+    /// Check a tool call before it runs
+    pub fn check_call(&mut self, tool: &str, input: &Value) -> CallVerdict {
+        // serde_json objects are sorted maps, so equal arguments serialize
+        // the same way regardless of key order
+        let key = (tool.to_string(), input.to_string());
+        let count = self.counts.entry(key).or_insert(0);
+        *count += 1;
+        let count = *count;
+        if count > 1 && count >= self.config.max_identical_calls {
+            return CallVerdict::Stop(LoopBreak::RepeatedCall {
+                tool: tool.to_string(),
+                count,
+            });
+        }
+
+        self.recent.push(tool.to_string());
+        if let Some((first, second)) = self.ping_pong() {
+            return CallVerdict::Stop(LoopBreak::PingPong { first, second });
+        }
+        if count > 1 {
+            return CallVerdict::Repeat(format!(
+                "Not run: you already called {} with these exact arguments in this \
+                 turn. Use the earlier result instead of calling it again.",
+                tool
+            ));
+        }
+        CallVerdict::Run
+    }
+
+    /// The two tools the recent calls strictly alternate between, if any
+    fn ping_pong(&self) -> Option<(String, String)> {
+        let window = self.config.ping_pong_window;
+        if window < 4 || self.recent.len() < window {
+            return None;
+        }
+        let tail = &self.recent[self.recent.len() - window..];
+        let (first, second) = (&tail[0], &tail[1]);
+        let alternating = first != second
+            && tail
+                .iter()
+                .enumerate()
+                .all(|(i, tool)| tool == if i % 2 == 0 { first } else { second });
+        alternating.then(|| (first.clone(), second.clone()))
+    }
+}
+
+/// Note added to the conversation asking the model to wrap up
+pub fn summary_request(reason: &LoopBreak) -> String {
+    format!(
+        "[The tool loop was stopped because it {}. Don't call any more tools. \
+         Reply to the user with what you've done and found so far, and what's \
+         still left to do.]",
+        reason
+    )
+}
+
+/// Reply used when the model doesn't produce a summary
+pub fn fallback_reply(reason: &LoopBreak) -> String {
+    format!(
+        "I stopped working on this because I {}. Could you narrow down the \
+         request or tell me how you'd like me to continue?",
+        reason
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_identical_calls_and_iterations() {
+        let mut guard = LoopGuard::new(LoopGuardConfig {
+            max_iterations: 2,
+            ..Default::default()
+        });
+        assert_eq!(guard.next_iteration(), Ok(1));
+        assert_eq!(
+            guard.check_call("recall", &json!({"query": "a", "limit": 5})),
+            CallVerdict::Run
+        );
+        assert_eq!(
+            guard.check_call("recall", &json!({"query": "b"})),
+            CallVerdict::Run
+        );
+        // Same arguments in a different key order
+        assert!(matches!(
+            guard.check_call("recall", &json!({"limit": 5, "query": "a"})),
+            CallVerdict::Repeat(_)
+        ));
+        assert_eq!(
+            guard.check_call("recall", &json!({"query": "a", "limit": 5})),
+            CallVerdict::Stop(LoopBreak::RepeatedCall {
+                tool: "recall".to_string(),
+                count: 3
+            })
+        );
+
+        assert_eq!(guard.next_iteration(), Ok(2));
+        let reason = guard.next_iteration().unwrap_err();
+        assert_eq!(reason, LoopBreak::MaxIterations(2));
+        assert!(summary_request(&reason).contains("limit of 2 tool rounds"));
+    }
+
+    #[test]
+    fn test_ping_pong() {
+        let mut guard = LoopGuard::new(LoopGuardConfig {
+            ping_pong_window: 4,
+            ..Default::default()
+        });
+        assert_eq!(
+            guard.check_call("read_file", &json!({"n": 1})),
+            CallVerdict::Run
+        );
+        assert_eq!(
+            guard.check_call("write_file", &json!({"n": 1})),
+            CallVerdict::Run
+        );
+        assert_eq!(
+            guard.check_call("read_file", &json!({"n": 2})),
+            CallVerdict::Run
+        );
+        assert_eq!(
+            guard.check_call("write_file", &json!({"n": 2})),
+            CallVerdict::Stop(LoopBreak::PingPong {
+                first: "read_file".to_string(),
+                second: "write_file".to_string()
+            })
+        );
+
+        let mut varied = LoopGuard::new(LoopGuardConfig {
+            ping_pong_window: 4,
+            ..Default::default()
+        });
+        for (i, tool) in ["a", "b", "a", "c", "a", "b"].iter().enumerate() {
+            assert_eq!(varied.check_call(tool, &json!({"i": i})), CallVerdict::Run);
+        }
+    }
+}
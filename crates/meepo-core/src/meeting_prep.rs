@@ -0,0 +1,664 @@
+//! Meeting prep briefings
+//!
+//! Shortly before each calendar event with attendees, a brief is assembled
+//! from the calendar and the knowledge graph: who is attending (contacts and
+//! known entities), the last interactions with them, open action items that
+//! mention them or the meeting, and relevant documents. Briefs are stored as
+//! `meeting_brief` entities, which also records which events were briefed.
+
+use anyhow::Result;
+use chrono::{DateTime, Duration, Utc};
+use meepo_knowledge::embeddings::content_words;
+use meepo_knowledge::{Entity, KnowledgeDb};
+use serde_json::Value;
+use std::sync::Arc;
+use tokio::sync::mpsc;
+use tokio_util::sync::CancellationToken;
+use tracing::{debug, error, info, warn};
+
+use crate::email_triage::SUMMARY_ENTITY_TYPE;
+use crate::platform::{Attendee, CalendarEvent, CalendarProvider, ContactsProvider};
+use crate::types::{ChannelType, MessageKind, OutgoingMessage};
+
+/// Entity type briefs are stored under
+pub const BRIEF_ENTITY_TYPE: &str = "meeting_brief";
+
+/// Items listed per section
+const MAX_ITEMS: usize = 5;
+
+/// Conversation excerpts longer than this are cut short
+const MAX_EXCERPT_CHARS: usize = 160;
+
+/// Entity types that never describe an attendee
+const SKIP_TYPES: &[&str] = &[
+    "document",
+    "document_chunk",
+    "task",
+    BRIEF_ENTITY_TYPE,
+    SUMMARY_ENTITY_TYPE,
+];
+
+/// Configuration for automatic meeting briefs
+#[derive(Debug, Clone)]
+pub struct MeetingPrepConfig {
+    pub enabled: bool,
+    /// How long before an event the brief is sent
+    pub lead_minutes: u64,
+    /// How often the calendar is checked
+    pub poll_secs: u64,
+    /// Channel briefs are delivered to
+    pub channel: ChannelType,
+}
+
+impl Default for MeetingPrepConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            lead_minutes: 30,
+            poll_secs: 300,
+            channel: ChannelType::Internal,
+        }
+    }
+}
+
+/// Assembles meeting briefs
+pub struct MeetingPrep {
+    db: Arc<KnowledgeDb>,
+    calendar: Arc<dyn CalendarProvider>,
+    contacts: Option<Arc<dyn ContactsProvider>>,
+}
+
+impl MeetingPrep {
+    pub fn new(db: Arc<KnowledgeDb>, calendar: Arc<dyn CalendarProvider>) -> Self {
+        Self {
+            db,
+            calendar,
+            contacts: None,
+        }
+    }
+
+    /// Use this platform's calendar, and its contacts when available
+    pub fn for_platform(db: Arc<KnowledgeDb>) -> Result<Self> {
+        let calendar = crate::platform::create_calendar_provider()?;
+        let prep = Self::new(db, Arc::from(calendar));
+        Ok(match crate::platform::create_contacts_provider() {
+            Ok(contacts) => prep.with_contacts(Arc::from(contacts)),
+            Err(_) => prep,
+        })
+    }
+
+    /// Look attendees up in the address book too
+    pub fn with_contacts(mut self, contacts: Arc<dyn ContactsProvider>) -> Self {
+        self.contacts = Some(contacts);
+        self
+    }
+
+    /// Events with attendees starting within `hours_ahead` hours
+    pub async fn upcoming_meetings(&self, hours_ahead: u64) -> Result<Vec<CalendarEvent>> {
+        let mut events = self.calendar.upcoming_events(hours_ahead).await?;
+        events.retain(|e| !e.attendees.is_empty());
+        Ok(events)
+    }
+
+    /// Assemble the brief for one event
+    pub async fn build_brief(&self, event: &CalendarEvent, now: DateTime<Utc>) -> Result<String> {
+        let tz = crate::timezone::user_timezone();
+        let minutes = (event.start - now).num_minutes().max(0);
+        let mut out = format!(
+            "📋 Meeting prep: {} — {} (in {} min)\n",
+            event.title,
+            event.start.with_timezone(&tz).format("%a %H:%M"),
+            minutes
+        );
+        if let Some(location) = &event.location {
+            out.push_str(&format!("Location: {}\n", location));
+        }
+
+        // Who: contacts, graph entities and their relationships
+        let mut people = Vec::new();
+        out.push_str("\n👥 Who\n");
+        for attendee in &event.attendees {
+            let entity = self.find_entity(attendee).await?;
+            out.push_str(&format!("• {}", attendee.label()));
+            if let (Some(_), Some(email)) = (&attendee.name, &attendee.email) {
+                out.push_str(&format!(" <{}>", email));
+            }
+            if let Some(entity) = &entity {
+                out.push_str(&format!(" — {}", entity.entity_type));
+                let details = entity_details(entity);
+                if !details.is_empty() {
+                    out.push_str(&format!("; {}", details));
+                }
+            }
+            out.push('\n');
+            if let Some(contact) = self.lookup_contact(attendee).await {
+                out.push_str(&format!("  Contact: {}\n", contact));
+            }
+            if let Some(entity) = &entity {
+                let related = self.related_names(entity).await?;
+                if !related.is_empty() {
+                    out.push_str(&format!("  Related: {}\n", related.join(", ")));
+                }
+            }
+            people.push((attendee, entity));
+        }
+
+        // Terms that tie conversations, tasks and goals to this meeting
+        let mut terms: Vec<String> = Vec::new();
+        for (attendee, entity) in &people {
+            terms.extend(attendee.name.iter().map(|n| n.to_lowercase()));
+            terms.extend(attendee.email.iter().map(|e| e.to_lowercase()));
+            if let Some(entity) = entity {
+                terms.push(entity.name.to_lowercase());
+            }
+        }
+        terms.sort();
+        terms.dedup();
+
+        let interactions = self.last_interactions(&terms).await?;
+        if !interactions.is_empty() {
+            out.push_str("\n💬 Last interactions\n");
+            for line in interactions {
+                out.push_str(&format!("• {}\n", line));
+            }
+        }
+
+        let mut mentions = terms.clone();
+        mentions.push(event.title.to_lowercase());
+        let items = self.open_action_items(&mentions).await?;
+        if !items.is_empty() {
+            out.push_str("\n✅ Open action items\n");
+            for item in items {
+                out.push_str(&format!("• {}\n", item));
+            }
+        }
+
+        let entities: Vec<&Entity> = people.iter().filter_map(|(_, e)| e.as_ref()).collect();
+        let documents = self.relevant_documents(&event.title, &entities).await?;
+        if !documents.is_empty() {
+            out.push_str("\n📄 Relevant documents\n");
+            for doc in documents {
+                out.push_str(&format!("• {}\n", doc));
+            }
+        }
+
+        Ok(out)
+    }
+
+    /// Build and store briefs for meetings starting within `lead_minutes`
+    /// that haven't been briefed yet
+    pub async fn prepare_due(
+        &self,
+        lead_minutes: u64,
+        now: DateTime<Utc>,
+    ) -> Result<Vec<(CalendarEvent, String)>> {
+        let hours = lead_minutes.div_ceil(60).max(1);
+        let cutoff = now + Duration::minutes(lead_minutes as i64);
+        let briefed = self.briefed_keys().await?;
+
+        let mut prepared = Vec::new();
+        for event in self.upcoming_meetings(hours).await? {
+            if event.start > cutoff || event.start < now || briefed.contains(&event_key(&event)) {
+                continue;
+            }
+            let brief = self.build_brief(&event, now).await?;
+            self.db
+                .insert_entity(
+                    &event.title,
+                    BRIEF_ENTITY_TYPE,
+                    Some(serde_json::json!({
+                        "event_key": event_key(&event),
+                        "start": event.start.to_rfc3339(),
+                        "attendees": event
+                            .attendees
+                            .iter()
+                            .map(|a| a.label().to_string())
+                            .collect::<Vec<_>>(),
+                        "brief": brief,
+                    })),
+                )
+                .await?;
+            info!("Prepared meeting brief for '{}'", event.title);
+            prepared.push((event, brief));
+        }
+        Ok(prepared)
+    }
+
+    /// Check the calendar every `poll_secs` and deliver briefs until cancelled
+    pub async fn run(
+        self,
+        config: MeetingPrepConfig,
+        outgoing: mpsc::Sender<OutgoingMessage>,
+        cancel: CancellationToken,
+    ) {
+        info!(
+            "Meeting prep started ({} min ahead, to {})",
+            config.lead_minutes, config.channel
+        );
+        let interval = std::time::Duration::from_secs(config.poll_secs.max(60));
+        loop {
+            match self.prepare_due(config.lead_minutes, Utc::now()).await {
+                Ok(prepared) => {
+                    for (_, brief) in prepared {
+                        let msg = OutgoingMessage {
+                            content: brief,
+                            channel: config.channel.clone(),
+                            reply_to: None,
+                            kind: MessageKind::Response,
+                            interactive: None,
+                        };
+                        if let Err(e) = outgoing.send(msg).await {
+                            error!("Failed to deliver meeting brief: {}", e);
+                        }
+                    }
+                }
+                Err(e) => warn!("Meeting prep failed: {}", e),
+            }
+            tokio::select! {
+                _ = cancel.cancelled() => break,
+                _ = tokio::time::sleep(interval) => {}
+            }
+        }
+        info!("Meeting prep shutting down");
+    }
+
+    async fn briefed_keys(&self) -> Result<Vec<String>> {
+        Ok(self
+            .db
+            .search_entities("", Some(BRIEF_ENTITY_TYPE))
+            .await?
+            .iter()
+            .filter_map(|e| e.metadata.as_ref()?.get("event_key")?.as_str())
+            .map(String::from)
+            .collect())
+    }
+
+    /// Best knowledge graph entity for an attendee: an exact name match,
+    /// else a person, else the first match by name or email
+    async fn find_entity(&self, attendee: &Attendee) -> Result<Option<Entity>> {
+        for query in attendee.name.iter().chain(attendee.email.iter()) {
+            let mut matches = self.db.search_entities(query, None).await?;
+            matches.retain(|e| !SKIP_TYPES.contains(&e.entity_type.as_str()));
+            let best = matches
+                .iter()
+                .position(|e| e.name.eq_ignore_ascii_case(query))
+                .or_else(|| matches.iter().position(|e| e.entity_type == "person"))
+                .or((!matches.is_empty()).then_some(0));
+            if let Some(i) = best {
+                return Ok(Some(matches.swap_remove(i)));
+            }
+        }
+        Ok(None)
+    }
+
+    async fn lookup_contact(&self, attendee: &Attendee) -> Option<String> {
+        let contacts = self.contacts.as_ref()?;
+        let query = attendee.email.as_deref().or(attendee.name.as_deref())?;
+        match contacts.search_contacts(query).await {
+            Ok(result) => {
+                let line = result
+                    .lines()
+                    .map(str::trim)
+                    .filter(|l| !l.is_empty() && !l.starts_with("---"))
+                    .take(3)
+                    .collect::<Vec<_>>()
+                    .join("; ");
+                (!line.is_empty() && !line.starts_with("No ")).then_some(line)
+            }
+            Err(e) => {
+                debug!("Contact lookup for {} failed: {}", query, e);
+                None
+            }
+        }
+    }
+
+    async fn related_names(&self, entity: &Entity) -> Result<Vec<String>> {
+        let mut names = Vec::new();
+        for rel in self
+            .db
+            .get_relationships_for(&entity.id)
+            .await?
+            .iter()
+            .take(MAX_ITEMS)
+        {
+            let other = if rel.source_id == entity.id {
+                &rel.target_id
+            } else {
+                &rel.source_id
+            };
+            if let Some(other) = self.db.get_entity(other).await? {
+                names.push(format!("{} {}", rel.relation_type, other.name));
+            }
+        }
+        Ok(names)
+    }
+
+    /// Recent messages and email briefs involving the attendees
+    async fn last_interactions(&self, terms: &[String]) -> Result<Vec<String>> {
+        let mut conversations = Vec::new();
+        for term in terms {
+            for convo in self.db.search_conversations(term, MAX_ITEMS).await? {
+                if convo.sender != "meepo"
+                    && !conversations
+                        .iter()
+                        .any(|c: &meepo_knowledge::Conversation| c.id == convo.id)
+                {
+                    conversations.push(convo);
+                }
+            }
+        }
+        conversations.sort_by_key(|c| std::cmp::Reverse(c.created_at));
+
+        let mut lines: Vec<(DateTime<Utc>, String)> = conversations
+            .into_iter()
+            .take(MAX_ITEMS)
+            .map(|c| {
+                (
+                    c.created_at,
+                    format!(
+                        "{} — {} on {}: {}",
+                        c.created_at.format("%Y-%m-%d"),
+                        c.sender,
+                        c.channel,
+                        excerpt(&c.content)
+                    ),
+                )
+            })
+            .collect();
+
+        for brief in self
+            .db
+            .search_entities("", Some(SUMMARY_ENTITY_TYPE))
+            .await?
+        {
+            let from = brief
+                .metadata
+                .as_ref()
+                .and_then(|m| m.get("from"))
+                .and_then(Value::as_str)
+                .unwrap_or_default()
+                .to_lowercase();
+            if terms.iter().any(|t| from.contains(t.as_str())) {
+                lines.push((
+                    brief.created_at,
+                    format!(
+                        "{} — email: {}",
+                        brief.created_at.format("%Y-%m-%d"),
+                        brief.name
+                    ),
+                ));
+            }
+        }
+
+        lines.sort_by_key(|l| std::cmp::Reverse(l.0));
+        Ok(lines
+            .into_iter()
+            .take(MAX_ITEMS)
+            .map(|(_, line)| line)
+            .collect())
+    }
+
+    /// Pending tasks and active goals that mention an attendee or the meeting
+    async fn open_action_items(&self, mentions: &[String]) -> Result<Vec<String>> {
+        let mentions_any = |text: &str| {
+            let text = text.to_lowercase();
+            mentions
+                .iter()
+                .any(|m| !m.is_empty() && text.contains(m.as_str()))
+        };
+        let mut items = Vec::new();
+
+        for task in self.db.search_entities("", Some("task")).await? {
+            let meta = task.metadata.as_ref();
+            let status = meta
+                .and_then(|m| m.get("status"))
+                .and_then(Value::as_str)
+                .unwrap_or("pending");
+            if matches!(status, "completed" | "done" | "cancelled") {
+                continue;
+            }
+            let description = meta
+                .and_then(|m| m.get("description"))
+                .and_then(Value::as_str)
+                .unwrap_or_default();
+            if mentions_any(&task.name) || mentions_any(description) {
+                let due = meta
+                    .and_then(|m| m.get("due_date"))
+                    .and_then(Value::as_str)
+                    .map(|d| format!(" (due {})", d))
+                    .unwrap_or_default();
+                items.push(format!("[task] {}{}", task.name, due));
+            }
+        }
+
+        for goal in self.db.get_active_goals().await? {
+            if mentions_any(&goal.description) {
+                items.push(format!("[goal] {}", goal.description));
+            }
+        }
+
+        items.truncate(MAX_ITEMS);
+        Ok(items)
+    }
+
+    /// Documents linked to an attendee's entity or named like the meeting
+    async fn relevant_documents(&self, title: &str, entities: &[&Entity]) -> Result<Vec<String>> {
+        let mut docs: Vec<Entity> = Vec::new();
+        for entity in entities {
+            for rel in self.db.get_relationships_for(&entity.id).await? {
+                let other = if rel.source_id == entity.id {
+                    &rel.target_id
+                } else {
+                    &rel.source_id
+                };
+                if let Some(doc) = self.db.get_entity(other).await?
+                    && doc.entity_type == "document"
+                    && !docs.iter().any(|d| d.id == doc.id)
+                {
+                    docs.push(doc);
+                }
+            }
+        }
+        for word in content_words(title) {
+            for doc in self.db.search_entities(&word, Some("document")).await? {
+                if !docs.iter().any(|d| d.id == doc.id) {
+                    docs.push(doc);
+                }
+            }
+        }
+
+        Ok(docs
+            .iter()
+            .take(MAX_ITEMS)
+            .map(|doc| {
+                match doc
+                    .metadata
+                    .as_ref()
+                    .and_then(|m| m.get("source_path"))
+                    .and_then(Value::as_str)
+                {
+                    Some(path) => format!("{} — {}", doc.name, path),
+                    None => doc.name.clone(),
+                }
+            })
+            .collect())
+    }
+}
+
+/// Identifies one occurrence of an event
+fn event_key(event: &CalendarEvent) -> String {
+    format!("{}@{}", event.id, event.start.timestamp())
+}
+
+/// Short "key: value" summary of an entity's metadata
+fn entity_details(entity: &Entity) -> String {
+    let Some(Value::Object(map)) = &entity.metadata else {
+        return String::new();
+    };
+    map.iter()
+        .filter(|(k, _)| !matches!(k.as_str(), "tags" | "auto_tags" | "trace"))
+        .filter_map(|(k, v)| match v {
+            Value::String(s) if !s.is_empty() => Some(format!("{}: {}", k, excerpt(s))),
+            Value::Number(n) => Some(format!("{}: {}", k, n)),
+            _ => None,
+        })
+        .take(3)
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Single-line excerpt of a message
+fn excerpt(content: &str) -> String {
+    let line = content.split_whitespace().collect::<Vec<_>>().join(" ");
+    if line.chars().count() > MAX_EXCERPT_CHARS {
+        let cut: String = line.chars().take(MAX_EXCERPT_CHARS).collect();
+        format!("{}…", cut)
+    } else {
+        line
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    struct FixedCalendar(Vec<CalendarEvent>);
+
+    #[async_trait::async_trait]
+    impl CalendarProvider for FixedCalendar {
+        async fn read_events(&self, _days_ahead: u64) -> Result<String> {
+            Ok(String::new())
+        }
+        async fn create_event(
+            &self,
+            _summary: &str,
+            _start_time: &str,
+            _duration_minutes: u64,
+            _location: Option<&str>,
+        ) -> Result<String> {
+            Ok(String::new())
+        }
+        async fn upcoming_events(&self, _hours_ahead: u64) -> Result<Vec<CalendarEvent>> {
+            Ok(self.0.clone())
+        }
+    }
+
+    fn event(
+        id: &str,
+        title: &str,
+        start: DateTime<Utc>,
+        attendees: &[(&str, &str)],
+    ) -> CalendarEvent {
+        CalendarEvent {
+            id: id.to_string(),
+            title: title.to_string(),
+            start,
+            end: start + Duration::minutes(30),
+            location: None,
+            attendees: attendees
+                .iter()
+                .map(|(name, email)| Attendee {
+                    name: Some(name.to_string()),
+                    email: Some(email.to_string()),
+                })
+                .collect(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_brief_gathers_people_interactions_tasks_and_documents() {
+        let temp = TempDir::new().unwrap();
+        let db = Arc::new(KnowledgeDb::new(temp.path().join("test.db")).unwrap());
+        let now = Utc::now();
+
+        let carol = db
+            .insert_entity(
+                "Carol Diaz",
+                "person",
+                Some(serde_json::json!({"role": "PM at Acme"})),
+            )
+            .await
+            .unwrap();
+        let roadmap = db
+            .insert_entity(
+                "Acme roadmap",
+                "document",
+                Some(serde_json::json!({"source_path": "/docs/roadmap.pdf"})),
+            )
+            .await
+            .unwrap();
+        db.insert_relationship(&carol, &roadmap, "owns", None)
+            .await
+            .unwrap();
+        db.insert_conversation("slack", "me", "Need to ask Carol Diaz about pricing", None)
+            .await
+            .unwrap();
+        db.insert_entity(
+            "Send Carol Diaz the deck",
+            "task",
+            Some(serde_json::json!({"status": "pending", "due_date": "2026-03-03"})),
+        )
+        .await
+        .unwrap();
+        db.insert_entity(
+            "Old Carol Diaz task",
+            "task",
+            Some(serde_json::json!({"status": "completed"})),
+        )
+        .await
+        .unwrap();
+
+        let sync = event(
+            "e1",
+            "Pricing sync",
+            now + Duration::minutes(20),
+            &[("Carol Diaz", "carol@acme.com")],
+        );
+        let prep = MeetingPrep::new(db.clone(), Arc::new(FixedCalendar(vec![sync.clone()])));
+        let brief = prep.build_brief(&sync, now).await.unwrap();
+
+        assert!(brief.contains("Meeting prep: Pricing sync"));
+        assert!(brief.contains("Carol Diaz <carol@acme.com> — person; role: PM at Acme"));
+        assert!(brief.contains("Related: owns Acme roadmap"));
+        assert!(brief.contains("Need to ask Carol Diaz about pricing"));
+        assert!(brief.contains("[task] Send Carol Diaz the deck (due 2026-03-03)"));
+        assert!(!brief.contains("Old Carol Diaz task"));
+        assert!(brief.contains("Acme roadmap — /docs/roadmap.pdf"));
+    }
+
+    #[tokio::test]
+    async fn test_prepare_due_briefs_each_meeting_once() {
+        let temp = TempDir::new().unwrap();
+        let db = Arc::new(KnowledgeDb::new(temp.path().join("test.db")).unwrap());
+        let now = Utc::now();
+        let calendar = FixedCalendar(vec![
+            event(
+                "soon",
+                "1:1",
+                now + Duration::minutes(25),
+                &[("Dan", "dan@x.com")],
+            ),
+            event(
+                "later",
+                "Planning",
+                now + Duration::minutes(90),
+                &[("Eve", "eve@x.com")],
+            ),
+            event("solo", "Focus", now + Duration::minutes(10), &[]),
+        ]);
+        let prep = MeetingPrep::new(db.clone(), Arc::new(calendar));
+
+        let prepared = prep.prepare_due(30, now).await.unwrap();
+        assert_eq!(prepared.len(), 1);
+        assert_eq!(prepared[0].0.id, "soon");
+        assert!(prep.prepare_due(30, now).await.unwrap().is_empty());
+
+        let stored = db
+            .search_entities("", Some(BRIEF_ENTITY_TYPE))
+            .await
+            .unwrap();
+        assert_eq!(stored.len(), 1);
+        assert_eq!(stored[0].name, "1:1");
+    }
+}
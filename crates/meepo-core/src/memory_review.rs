@@ -0,0 +1,385 @@
+//! Review queue for memories learned in conversation
+//!
+//! With review on, `remember`, `remember_many` and `link_entities` don't
+//! write to the knowledge graph directly: what the agent wants to keep is
+//! staged here, counted in the digest and listed by `meepo memory review`,
+//! and only stored once the user approves it. Approved facts and
+//! preferences are also noted in MEMORY.md. Approving from the CLI only
+//! marks the item; the daemon, which owns the search index, promotes it on
+//! its next check.
+//!
+//! Staged items are kept in the preferences table.
+
+use std::path::Path;
+
+use anyhow::{Context, Result, anyhow};
+use chrono::{DateTime, Utc};
+use meepo_knowledge::{KnowledgeDb, KnowledgeGraph, NewEntity, NewRelationship, RememberedBatch};
+use serde::{Deserialize, Serialize};
+use tracing::{info, warn};
+
+/// Preference category holding staged memories, keyed by
+/// `memory_review:<id>`
+pub const REVIEW_CATEGORY: &str = "memory_review";
+
+/// MEMORY.md section approved notes are added to
+pub const LEARNED_HEADING: &str = "## Learned";
+
+/// Entity types noted in MEMORY.md when approved, by default
+pub const DEFAULT_MEMORY_TYPES: &[&str] = &["fact", "preference"];
+
+/// Memories proposed by one tool call, waiting for the user
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StagedMemory {
+    pub id: String,
+    #[serde(default)]
+    pub entities: Vec<NewEntity>,
+    #[serde(default)]
+    pub relationships: Vec<NewRelationship>,
+    /// Tool that proposed it
+    pub source: String,
+    pub staged_at: DateTime<Utc>,
+    /// Approved by the user, waiting for the daemon to store it
+    #[serde(default)]
+    pub approved: bool,
+    /// Why the last attempt to store it failed
+    #[serde(default)]
+    pub error: Option<String>,
+}
+
+impl StagedMemory {
+    /// One line per entity and relationship, for review
+    pub fn lines(&self) -> Vec<String> {
+        let entities = self.entities.iter().map(|e| match &e.metadata {
+            Some(metadata) => format!("{} ({}) {}", e.name, e.entity_type, metadata),
+            None => format!("{} ({})", e.name, e.entity_type),
+        });
+        let relationships = self
+            .relationships
+            .iter()
+            .map(|r| format!("{} -[{}]-> {}", r.source, r.relation_type, r.target));
+        entities.chain(relationships).collect()
+    }
+}
+
+fn review_key(id: &str) -> String {
+    format!("{}:{}", REVIEW_CATEGORY, id)
+}
+
+async fn save(db: &KnowledgeDb, staged: &StagedMemory) -> Result<()> {
+    db.upsert_preference(
+        REVIEW_CATEGORY,
+        &review_key(&staged.id),
+        serde_json::to_value(staged)?,
+        1.0,
+        Some("memory_review"),
+    )
+    .await?;
+    Ok(())
+}
+
+/// Hold entities and relationships for review instead of storing them
+pub async fn stage(
+    db: &KnowledgeDb,
+    source: &str,
+    entities: Vec<NewEntity>,
+    relationships: Vec<NewRelationship>,
+) -> Result<StagedMemory> {
+    if entities.is_empty() && relationships.is_empty() {
+        return Err(anyhow!("Nothing to remember"));
+    }
+    let staged = StagedMemory {
+        id: format!("m{}", &uuid::Uuid::new_v4().simple().to_string()[..7]),
+        entities,
+        relationships,
+        source: source.to_string(),
+        staged_at: Utc::now(),
+        approved: false,
+        error: None,
+    };
+    save(db, &staged).await?;
+    info!(
+        "Staged memory {} from {} for review",
+        staged.id, staged.source
+    );
+    Ok(staged)
+}
+
+/// Staged memories, oldest first
+pub async fn list(db: &KnowledgeDb) -> Result<Vec<StagedMemory>> {
+    let mut staged: Vec<StagedMemory> = db
+        .get_preferences(Some(REVIEW_CATEGORY))
+        .await?
+        .into_iter()
+        .filter_map(|p| serde_json::from_value(p.value).ok())
+        .collect();
+    staged.sort_by_key(|m| m.staged_at);
+    Ok(staged)
+}
+
+/// Staged memories still waiting for a decision
+pub async fn pending(db: &KnowledgeDb) -> Result<Vec<StagedMemory>> {
+    Ok(list(db)
+        .await?
+        .into_iter()
+        .filter(|m| !m.approved)
+        .collect())
+}
+
+async fn find(db: &KnowledgeDb, id: &str) -> Result<StagedMemory> {
+    list(db)
+        .await?
+        .into_iter()
+        .find(|m| m.id == id)
+        .ok_or_else(|| anyhow!("No staged memory '{}'", id))
+}
+
+/// Approve a staged memory; the daemon stores it on its next check
+pub async fn approve(db: &KnowledgeDb, id: &str) -> Result<StagedMemory> {
+    let mut staged = find(db, id).await?;
+    staged.approved = true;
+    staged.error = None;
+    save(db, &staged).await?;
+    Ok(staged)
+}
+
+/// Throw a staged memory away
+pub async fn reject(db: &KnowledgeDb, id: &str) -> Result<StagedMemory> {
+    let staged = find(db, id).await?;
+    db.delete_preference(&review_key(id)).await?;
+    Ok(staged)
+}
+
+/// Store a memory in the graph, and note its facts in MEMORY.md. Entities
+/// the user has asked to forget since it was staged are left out, along
+/// with relationships naming them.
+pub async fn promote(
+    graph: &KnowledgeGraph,
+    memory_path: &Path,
+    memory_types: &[String],
+    staged: &StagedMemory,
+) -> Result<RememberedBatch> {
+    let db = graph.db();
+    let mut entities = Vec::new();
+    let mut forgotten = Vec::new();
+    for entity in &staged.entities {
+        if db.is_forgotten(&entity.name).await? {
+            forgotten.push(entity.name.trim().to_lowercase());
+        } else {
+            entities.push(entity.clone());
+        }
+    }
+    if !forgotten.is_empty() {
+        warn!(
+            "Leaving {} forgotten entities out of memory {}",
+            forgotten.len(),
+            staged.id
+        );
+    }
+    let relationships: Vec<NewRelationship> = staged
+        .relationships
+        .iter()
+        .filter(|r| {
+            !forgotten.contains(&r.source.trim().to_lowercase())
+                && !forgotten.contains(&r.target.trim().to_lowercase())
+        })
+        .cloned()
+        .collect();
+    if entities.is_empty() && relationships.is_empty() {
+        return Ok(RememberedBatch::default());
+    }
+
+    let batch = graph.remember_many(entities.clone(), relationships).await?;
+    let notes: Vec<String> = entities
+        .iter()
+        .filter(|e| {
+            memory_types
+                .iter()
+                .any(|t| t.eq_ignore_ascii_case(&e.entity_type))
+        })
+        .map(|e| format!("- {}", e.name.trim()))
+        .collect();
+    if !notes.is_empty() {
+        let memory = meepo_knowledge::load_memory(memory_path).unwrap_or_default();
+        meepo_knowledge::save_memory(memory_path, &add_notes(&memory, &notes))
+            .context("Failed to update MEMORY.md")?;
+    }
+    Ok(batch)
+}
+
+/// Store every approved memory and remove it from the queue. One that
+/// can't be stored goes back to pending with the error, so it isn't
+/// retried forever. Returns how many were stored.
+pub async fn promote_approved(
+    graph: &KnowledgeGraph,
+    memory_path: &Path,
+    memory_types: &[String],
+) -> Result<usize> {
+    let db = graph.db();
+    let mut promoted = 0;
+    for mut staged in list(&db).await?.into_iter().filter(|m| m.approved) {
+        match promote(graph, memory_path, memory_types, &staged).await {
+            Ok(_) => {
+                db.delete_preference(&review_key(&staged.id)).await?;
+                promoted += 1;
+            }
+            Err(e) => {
+                warn!("Failed to store approved memory {}: {:#}", staged.id, e);
+                staged.approved = false;
+                staged.error = Some(format!("{:#}", e));
+                save(&db, &staged).await?;
+            }
+        }
+    }
+    Ok(promoted)
+}
+
+/// Add notes to the "Learned" section of MEMORY.md (creating it at the
+/// end), skipping ones already there
+fn add_notes(memory: &str, notes: &[String]) -> String {
+    let notes: Vec<&String> = notes
+        .iter()
+        .filter(|n| !memory.lines().any(|line| line.trim() == n.trim()))
+        .collect();
+    if notes.is_empty() {
+        return memory.to_string();
+    }
+    let block: String = notes.iter().map(|n| format!("{}\n", n)).collect();
+    let Some(start) = memory.find(LEARNED_HEADING) else {
+        let trimmed = memory.trim_end();
+        return if trimmed.is_empty() {
+            format!("{}\n\n{}", LEARNED_HEADING, block)
+        } else {
+            format!("{}\n\n{}\n\n{}", trimmed, LEARNED_HEADING, block)
+        };
+    };
+    let body_start = start + LEARNED_HEADING.len();
+    let end = memory[body_start..]
+        .find("\n## ")
+        .map(|i| body_start + i + 1)
+        .unwrap_or(memory.len());
+    let section = memory[..end].trim_end();
+    let rest = &memory[end..];
+    if rest.is_empty() {
+        format!("{}\n{}", section, block)
+    } else {
+        format!("{}\n{}\n{}", section, block, rest)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entity(name: &str, entity_type: &str) -> NewEntity {
+        NewEntity {
+            name: name.to_string(),
+            entity_type: entity_type.to_string(),
+            metadata: None,
+        }
+    }
+
+    #[test]
+    fn test_add_notes_to_learned_section() {
+        let memory = "# Memory\n\n## Learned\n\n- Likes tea\n\n## Notes\n\nx\n";
+        let notes = vec!["- Likes tea".to_string(), "- Allergic to nuts".to_string()];
+        assert_eq!(
+            add_notes(memory, &notes),
+            "# Memory\n\n## Learned\n\n- Likes tea\n- Allergic to nuts\n\n## Notes\n\nx\n"
+        );
+        assert_eq!(
+            add_notes("# Memory\n", &notes),
+            "# Memory\n\n## Learned\n\n- Likes tea\n- Allergic to nuts\n"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_stage_approve_and_promote() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let graph =
+            KnowledgeGraph::new(temp.path().join("test.db"), temp.path().join("index")).unwrap();
+        let db = graph.db();
+        let memory_path = temp.path().join("MEMORY.md");
+        let types = vec!["fact".to_string()];
+
+        let kept = stage(
+            &db,
+            "remember_many",
+            vec![
+                entity("Prefers morning meetings", "fact"),
+                entity("Ana", "person"),
+            ],
+            vec![],
+        )
+        .await
+        .unwrap();
+        let dropped = stage(&db, "remember", vec![entity("Wrong guess", "fact")], vec![])
+            .await
+            .unwrap();
+        assert_eq!(pending(&db).await.unwrap().len(), 2);
+
+        // Nothing is stored until approved and promoted
+        assert_eq!(
+            promote_approved(&graph, &memory_path, &types)
+                .await
+                .unwrap(),
+            0
+        );
+        assert!(db.search_entities("Ana", None).await.unwrap().is_empty());
+
+        approve(&db, &kept.id).await.unwrap();
+        reject(&db, &dropped.id).await.unwrap();
+        assert!(pending(&db).await.unwrap().is_empty());
+        assert_eq!(
+            promote_approved(&graph, &memory_path, &types)
+                .await
+                .unwrap(),
+            1
+        );
+
+        assert_eq!(db.search_entities("Ana", None).await.unwrap().len(), 1);
+        assert!(list(&db).await.unwrap().is_empty());
+        let memory = std::fs::read_to_string(&memory_path).unwrap();
+        assert!(memory.contains("- Prefers morning meetings"));
+        assert!(!memory.contains("Ana"));
+        assert!(!memory.contains("Wrong guess"));
+    }
+
+    #[tokio::test]
+    async fn test_forgotten_entities_not_promoted() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let graph =
+            KnowledgeGraph::new(temp.path().join("test.db"), temp.path().join("index")).unwrap();
+        let db = graph.db();
+        let memory_path = temp.path().join("MEMORY.md");
+
+        let staged = stage(
+            &db,
+            "remember_many",
+            vec![entity("Old flat", "place"), entity("Ana", "person")],
+            vec![NewRelationship {
+                source: "Ana".to_string(),
+                target: "Old flat".to_string(),
+                relation_type: "lives_at".to_string(),
+                metadata: None,
+            }],
+        )
+        .await
+        .unwrap();
+        let id = db.insert_entity("Old flat", "place", None).await.unwrap();
+        graph.forget_entities(&[id], "moved").await.unwrap();
+
+        approve(&db, &staged.id).await.unwrap();
+        assert_eq!(
+            promote_approved(&graph, &memory_path, &[]).await.unwrap(),
+            1
+        );
+        assert!(
+            db.search_entities("Old flat", None)
+                .await
+                .unwrap()
+                .is_empty()
+        );
+        assert_eq!(db.search_entities("Ana", None).await.unwrap().len(), 1);
+    }
+}
@@ -0,0 +1,306 @@
+//! Outbound message templates
+//!
+//! Recurring sends (a standup reminder, a weekly report header) use a stored
+//! template with `{variable}` placeholders instead of asking the model to
+//! write the same message every time. Templates are kept as
+//! `message_template` preferences, or as `<name>.md` / `<name>.txt` files in
+//! the workspace's `message_templates/` directory; a stored template wins
+//! over a file of the same name. `{date}`, `{time}` and `{weekday}` are
+//! always available, in the user's timezone.
+
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use anyhow::{Result, anyhow, bail};
+use meepo_knowledge::KnowledgeDb;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// Preference category holding stored templates
+pub const CATEGORY: &str = "message_template";
+
+/// Workspace directory holding template files
+pub const DIR: &str = "message_templates";
+
+/// Longest template body accepted
+const MAX_TEMPLATE_CHARS: usize = 4000;
+
+/// A reusable message
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MessageTemplate {
+    pub name: String,
+    pub body: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+}
+
+impl MessageTemplate {
+    /// Names of the `{variables}` the body uses, in order of appearance
+    pub fn variables(&self) -> Vec<String> {
+        let mut names = Vec::new();
+        fill(&self.body, |name| {
+            if !names.iter().any(|n| n == name) {
+                names.push(name.to_string());
+            }
+            None
+        });
+        names
+    }
+}
+
+/// Fill `{name}` placeholders from `lookup`, leaving unknown ones as they
+/// are. Names may contain letters, digits, `_` and `.`. Returns the text
+/// and the names `lookup` had no value for.
+pub fn fill(
+    template: &str,
+    mut lookup: impl FnMut(&str) -> Option<String>,
+) -> (String, Vec<String>) {
+    let mut out = String::with_capacity(template.len());
+    let mut missing = Vec::new();
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        out.push_str(&rest[..start]);
+        let after = &rest[start + 1..];
+        match after.find('}') {
+            Some(end)
+                if !after[..end].is_empty()
+                    && after[..end]
+                        .chars()
+                        .all(|c| c.is_alphanumeric() || c == '_' || c == '.') =>
+            {
+                let name = &after[..end];
+                match lookup(name) {
+                    Some(value) => out.push_str(&value),
+                    None => {
+                        out.push_str(&rest[start..start + end + 2]);
+                        missing.push(name.to_string());
+                    }
+                }
+                rest = &after[end + 1..];
+            }
+            _ => {
+                out.push('{');
+                rest = after;
+            }
+        }
+    }
+    out.push_str(rest);
+    (out, missing)
+}
+
+/// Template names are lowercase words joined by `_` or `-`
+fn normalize_name(name: &str) -> Result<String> {
+    let name = name.trim().to_lowercase();
+    if name.is_empty()
+        || name.len() > 64
+        || !name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+    {
+        bail!(
+            "Template names use letters, digits, '_' and '-' (got '{}')",
+            name
+        );
+    }
+    Ok(name)
+}
+
+fn preference_key(name: &str) -> String {
+    format!("{}:{}", CATEGORY, name)
+}
+
+/// A variable's value as message text
+fn value_text(value: &Value) -> Option<String> {
+    match value {
+        Value::String(s) => Some(s.clone()),
+        Value::Null => None,
+        other => Some(other.to_string()),
+    }
+}
+
+/// Stored and workspace templates
+pub struct TemplateStore {
+    db: Arc<KnowledgeDb>,
+    dir: Option<PathBuf>,
+}
+
+impl TemplateStore {
+    pub fn new(db: Arc<KnowledgeDb>) -> Self {
+        Self { db, dir: None }
+    }
+
+    /// Also read `<workspace>/message_templates/*.md` and `*.txt`
+    pub fn with_workspace(mut self, workspace: impl Into<PathBuf>) -> Self {
+        self.dir = Some(workspace.into().join(DIR));
+        self
+    }
+
+    /// Store a template, replacing one with the same name
+    pub async fn save(
+        &self,
+        name: &str,
+        body: &str,
+        description: Option<&str>,
+    ) -> Result<MessageTemplate> {
+        let name = normalize_name(name)?;
+        let body = body.trim();
+        if body.is_empty() {
+            bail!("Templates need a body");
+        }
+        if body.chars().count() > MAX_TEMPLATE_CHARS {
+            bail!("Templates are limited to {} characters", MAX_TEMPLATE_CHARS);
+        }
+        let template = MessageTemplate {
+            name: name.clone(),
+            body: body.to_string(),
+            description: description
+                .map(str::trim)
+                .filter(|d| !d.is_empty())
+                .map(String::from),
+        };
+        self.db
+            .upsert_preference(
+                CATEGORY,
+                &preference_key(&name),
+                serde_json::to_value(&template)?,
+                1.0,
+                Some("message_template"),
+            )
+            .await?;
+        Ok(template)
+    }
+
+    /// Remove a stored template; workspace files are left alone
+    pub async fn delete(&self, name: &str) -> Result<bool> {
+        self.db
+            .delete_preference(&preference_key(&normalize_name(name)?))
+            .await
+    }
+
+    /// Stored templates and workspace files, by name
+    pub async fn list(&self) -> Result<Vec<MessageTemplate>> {
+        let mut templates: BTreeMap<String, MessageTemplate> = BTreeMap::new();
+        if let Some(dir) = &self.dir
+            && let Ok(entries) = std::fs::read_dir(dir)
+        {
+            for path in entries.filter_map(|e| e.ok()).map(|e| e.path()) {
+                let is_template = path
+                    .extension()
+                    .is_some_and(|ext| ext == "md" || ext == "txt");
+                let Some(stem) = path.file_stem().and_then(|s| s.to_str()) else {
+                    continue;
+                };
+                let (Ok(name), true) = (normalize_name(stem), is_template) else {
+                    continue;
+                };
+                if let Ok(body) = std::fs::read_to_string(&path) {
+                    templates.insert(
+                        name.clone(),
+                        MessageTemplate {
+                            name,
+                            body: body.trim().to_string(),
+                            description: None,
+                        },
+                    );
+                }
+            }
+        }
+        for pref in self.db.get_preferences(Some(CATEGORY)).await? {
+            if let Ok(template) = serde_json::from_value::<MessageTemplate>(pref.value) {
+                templates.insert(template.name.clone(), template);
+            }
+        }
+        Ok(templates.into_values().collect())
+    }
+
+    pub async fn get(&self, name: &str) -> Result<Option<MessageTemplate>> {
+        let name = normalize_name(name)?;
+        Ok(self.list().await?.into_iter().find(|t| t.name == name))
+    }
+
+    /// Render a template with `vars` (an object of name → value, looked up
+    /// by dotted path). Fails if a variable has no value, so a recurring
+    /// send never goes out with `{placeholders}` in it.
+    pub async fn render(&self, name: &str, vars: &Value) -> Result<String> {
+        let template = self
+            .get(name)
+            .await?
+            .ok_or_else(|| anyhow!("No message template named '{}'", name.trim()))?;
+        let now = crate::timezone::now();
+        let (text, missing) = fill(&template.body, |key| {
+            key.split('.')
+                .try_fold(vars, |value, part| value.get(part))
+                .and_then(value_text)
+                .or_else(|| match key {
+                    "date" => Some(now.format("%Y-%m-%d").to_string()),
+                    "time" => Some(now.format("%H:%M").to_string()),
+                    "weekday" => Some(now.format("%A").to_string()),
+                    _ => None,
+                })
+        });
+        if !missing.is_empty() {
+            bail!(
+                "Template '{}' needs a value for: {}",
+                template.name,
+                missing.join(", ")
+            );
+        }
+        Ok(text)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fill_reports_missing() {
+        let (text, missing) = fill("Hi {name}, {json {x} {a.b}", |key| {
+            (key == "name").then(|| "Ana".to_string())
+        });
+        assert_eq!(text, "Hi Ana, {json {x} {a.b}");
+        assert_eq!(missing, vec!["x", "a.b"]);
+    }
+
+    #[tokio::test]
+    async fn test_render_stored_and_workspace_templates() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let db = Arc::new(KnowledgeDb::new(temp.path().join("test.db")).unwrap());
+        std::fs::create_dir(temp.path().join(DIR)).unwrap();
+        std::fs::write(
+            temp.path().join(DIR).join("standup_reminder.md"),
+            "Standup on {weekday} at {room}\n",
+        )
+        .unwrap();
+        let store = TemplateStore::new(db).with_workspace(temp.path());
+
+        let text = store
+            .render("standup_reminder", &serde_json::json!({"room": "B2"}))
+            .await
+            .unwrap();
+        assert!(text.starts_with("Standup on ") && text.ends_with(" at B2"));
+        assert!(!text.contains('{'));
+        let err = store
+            .render("standup_reminder", &serde_json::json!({}))
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("room"));
+
+        // A stored template overrides the file
+        let saved = store
+            .save("Standup_Reminder", "Standup in {room} ({date})", None)
+            .await
+            .unwrap();
+        assert_eq!(saved.variables(), vec!["room", "date"]);
+        let text = store
+            .render("standup_reminder", &serde_json::json!({"room": 4}))
+            .await
+            .unwrap();
+        assert!(text.starts_with("Standup in 4 ("));
+        assert_eq!(store.list().await.unwrap().len(), 1);
+
+        assert!(store.delete("standup_reminder").await.unwrap());
+        assert!(store.save("bad name", "x", None).await.is_err());
+    }
+}
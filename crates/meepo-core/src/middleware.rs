@@ -8,6 +8,8 @@
 //! - Modify messages before they reach the model (summarization, PII redaction)
 //! - Filter/select tools per query (tool selection)
 //! - Intercept tool results (validation, caching)
+//! - Answer a message without running the model (auto-replies)
+//! - Rewrite the system prompt and tool list for a conversation (policies)
 //! - Transform the final response (formatting, guardrails)
 
 use anyhow::Result;
@@ -40,6 +42,25 @@ pub trait AgentMiddleware: Send + Sync {
     /// Human-readable name for logging
     fn name(&self) -> &str;
 
+    /// Called before the agent handles a message.
+    ///
+    /// Return `Some(reply)` to answer with it instead of running the model.
+    async fn before_agent(&self, _ctx: &MiddlewareContext) -> Result<Option<String>> {
+        Ok(None)
+    }
+
+    /// Called once the system prompt and tool list for a turn are built.
+    ///
+    /// Return the (possibly modified) prompt and tools.
+    async fn before_prompt(
+        &self,
+        system_prompt: String,
+        tools: Vec<ToolDefinition>,
+        _ctx: &MiddlewareContext,
+    ) -> Result<(String, Vec<ToolDefinition>)> {
+        Ok((system_prompt, tools))
+    }
+
     /// Called before the model is invoked. Can modify messages and tools.
     ///
     /// Return the (possibly modified) messages and tools.
@@ -122,6 +143,30 @@ impl MiddlewareChain {
         self.middlewares.is_empty()
     }
 
+    /// Run before_agent hooks in order; the first reply wins
+    pub async fn run_before_agent(&self, ctx: &MiddlewareContext) -> Result<Option<String>> {
+        for mw in &self.middlewares {
+            if let Some(reply) = mw.before_agent(ctx).await? {
+                debug!("Middleware {} answered the message", mw.name());
+                return Ok(Some(reply));
+            }
+        }
+        Ok(None)
+    }
+
+    /// Run all before_prompt hooks in order
+    pub async fn run_before_prompt(
+        &self,
+        mut system_prompt: String,
+        mut tools: Vec<ToolDefinition>,
+        ctx: &MiddlewareContext,
+    ) -> Result<(String, Vec<ToolDefinition>)> {
+        for mw in &self.middlewares {
+            (system_prompt, tools) = mw.before_prompt(system_prompt, tools, ctx).await?;
+        }
+        Ok((system_prompt, tools))
+    }
+
     /// Run all before_model hooks in order
     pub async fn run_before_model(
         &self,
@@ -361,6 +406,12 @@ mod tests {
         let (msgs, tools) = chain.run_before_model(vec![], vec![], &ctx).await.unwrap();
         assert!(msgs.is_empty());
         assert!(tools.is_empty());
+        assert!(chain.run_before_agent(&ctx).await.unwrap().is_none());
+        let (prompt, _) = chain
+            .run_before_prompt("prompt".to_string(), vec![], &ctx)
+            .await
+            .unwrap();
+        assert_eq!(prompt, "prompt");
 
         let result = chain
             .run_after_agent("hello".to_string(), &ctx)
@@ -0,0 +1,543 @@
+//! Named modes ("work", "weekend")
+//!
+//! A mode bundles a SOUL overlay, a notification threshold, an autonomy
+//! level and a tool allowlist. One mode is active at a time: either picked
+//! by hand (the `set_mode` tool or `meepo mode set`) or by the schedule,
+//! e.g. work mode 09:00–17:00 on weekdays. A hand-picked mode lasts until
+//! the schedule moves on to a different mode, and is stored as a `mode`
+//! preference so it survives restarts. Like the Focus status, the active
+//! mode is kept process-wide so notifications and the autonomous loop can
+//! check it without a database round trip.
+
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+
+use anyhow::{Result, anyhow};
+use async_trait::async_trait;
+use chrono::{Datelike, NaiveTime, Weekday};
+use meepo_knowledge::KnowledgeDb;
+use serde_json::{Value, json};
+use tokio_util::sync::CancellationToken;
+use tracing::{debug, info, warn};
+
+use crate::api::ToolDefinition;
+use crate::notifications::{Severity, in_window};
+use crate::tools::ToolExecutor;
+
+/// Preference category holding the hand-picked mode
+pub const CATEGORY: &str = "mode";
+
+const MANUAL_KEY: &str = "mode:manual";
+
+/// Name that switches modes off until the schedule changes
+pub const NONE: &str = "none";
+
+/// Name that drops a hand-picked mode and follows the schedule again
+pub const AUTO: &str = "auto";
+
+/// The tool that changes modes; always allowed so a mode can be left
+pub const SET_MODE_TOOL: &str = "set_mode";
+
+/// How much the autonomous loop may do on its own
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ModeAutonomy {
+    /// Evaluate goals and act on them as usual
+    #[default]
+    Full,
+    /// Evaluate goals, but only act when very confident
+    Cautious,
+    /// Leave goals alone
+    Off,
+}
+
+impl ModeAutonomy {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.trim().to_lowercase().as_str() {
+            "full" | "normal" => Some(Self::Full),
+            "cautious" => Some(Self::Cautious),
+            "off" | "none" => Some(Self::Off),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Full => "full",
+            Self::Cautious => "cautious",
+            Self::Off => "off",
+        }
+    }
+
+    /// Lowest confidence a goal action needs, on top of the usual gate
+    pub fn min_confidence(&self) -> f64 {
+        match self {
+            Self::Full => 0.0,
+            Self::Cautious => 0.9,
+            Self::Off => f64::INFINITY,
+        }
+    }
+}
+
+/// When a mode turns on by itself
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ModeWindow {
+    /// Days the window applies on; empty means every day
+    pub days: Vec<Weekday>,
+    /// Time-of-day window in the user's timezone; may wrap midnight
+    pub start: NaiveTime,
+    pub end: NaiveTime,
+}
+
+impl ModeWindow {
+    /// Parse days ("mon", "weekdays", "weekends") and hours ("09:00-17:00")
+    pub fn parse(days: &[String], hours: &str) -> Result<Self> {
+        let mut parsed = Vec::new();
+        for day in days {
+            match day.trim().to_lowercase().as_str() {
+                "weekdays" => parsed.extend([
+                    Weekday::Mon,
+                    Weekday::Tue,
+                    Weekday::Wed,
+                    Weekday::Thu,
+                    Weekday::Fri,
+                ]),
+                "weekends" => parsed.extend([Weekday::Sat, Weekday::Sun]),
+                other => parsed.push(
+                    other
+                        .parse::<Weekday>()
+                        .map_err(|_| anyhow!("unknown day '{}'", day))?,
+                ),
+            }
+        }
+        let parse = |t: &str| NaiveTime::parse_from_str(t.trim(), "%H:%M");
+        let (start, end) = hours
+            .split_once('-')
+            .ok_or_else(|| anyhow!("hours must look like \"09:00-17:00\""))?;
+        Ok(Self {
+            days: parsed,
+            start: parse(start)?,
+            end: parse(end)?,
+        })
+    }
+
+    /// Whether the window covers `day` at `time`. A window wrapping
+    /// midnight belongs to the day it starts on.
+    pub fn contains(&self, day: Weekday, time: NaiveTime) -> bool {
+        let day = if self.start >= self.end && time < self.end {
+            day.pred()
+        } else {
+            day
+        };
+        (self.days.is_empty() || self.days.contains(&day)) && in_window(time, self.start, self.end)
+    }
+}
+
+/// A named bundle of behaviour
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Mode {
+    pub name: String,
+    /// Appended to SOUL.md while the mode is on
+    pub soul: String,
+    /// Proactive notifications below this are held back
+    pub min_severity: Option<Severity>,
+    pub autonomy: ModeAutonomy,
+    /// Tools the agent may use; empty means all
+    pub tools: Vec<String>,
+    pub schedule: Vec<ModeWindow>,
+}
+
+impl Mode {
+    /// Whether a notification of this severity is held back
+    pub fn holds_back(&self, severity: Severity) -> bool {
+        self.min_severity.is_some_and(|min| severity < min)
+    }
+
+    pub fn tool_allowed(&self, tool: &str) -> bool {
+        self.tools.is_empty() || tool == SET_MODE_TOOL || self.tools.iter().any(|t| t == tool)
+    }
+
+    /// What the mode changes, for `meepo mode` and the set_mode tool
+    pub fn describe(&self) -> String {
+        let mut parts = Vec::new();
+        if let Some(severity) = self.min_severity {
+            parts.push(format!("notifications from {}", severity.as_str()));
+        }
+        if self.autonomy != ModeAutonomy::Full {
+            parts.push(format!("autonomy {}", self.autonomy.as_str()));
+        }
+        if !self.tools.is_empty() {
+            parts.push(format!("{} tools", self.tools.len()));
+        }
+        if !self.soul.is_empty() {
+            parts.push("SOUL overlay".to_string());
+        }
+        if parts.is_empty() {
+            "no changes".to_string()
+        } else {
+            parts.join(", ")
+        }
+    }
+}
+
+/// Configured modes
+#[derive(Debug, Clone)]
+pub struct ModesConfig {
+    pub modes: Vec<Mode>,
+    /// How often to check the schedule
+    pub poll_secs: u64,
+}
+
+impl Default for ModesConfig {
+    fn default() -> Self {
+        Self {
+            modes: Vec::new(),
+            poll_secs: 60,
+        }
+    }
+}
+
+impl ModesConfig {
+    pub fn get(&self, name: &str) -> Option<&Mode> {
+        self.modes
+            .iter()
+            .find(|m| m.name.eq_ignore_ascii_case(name.trim()))
+    }
+
+    /// The first mode whose schedule covers `day` at `time`
+    pub fn scheduled_at(&self, day: Weekday, time: NaiveTime) -> Option<&Mode> {
+        self.modes
+            .iter()
+            .find(|m| m.schedule.iter().any(|w| w.contains(day, time)))
+    }
+
+    fn scheduled_now(&self) -> Option<&Mode> {
+        let now = crate::timezone::now();
+        self.scheduled_at(now.weekday(), now.time())
+    }
+}
+
+/// How the active mode was chosen
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ModeSource {
+    Manual,
+    Scheduled,
+}
+
+/// The mode in effect
+#[derive(Debug, Clone, PartialEq)]
+pub struct ActiveMode {
+    pub mode: Mode,
+    pub source: ModeSource,
+}
+
+/// Latest active mode
+static CURRENT: RwLock<Option<ActiveMode>> = RwLock::new(None);
+
+/// Publish the active mode
+pub fn set_current(active: Option<ActiveMode>) {
+    let mut current = CURRENT.write().unwrap_or_else(|p| p.into_inner());
+    let name = |a: &Option<ActiveMode>| a.as_ref().map(|a| a.mode.name.clone());
+    if name(&current) != name(&active) {
+        match &active {
+            Some(a) => info!("Mode '{}' on", a.mode.name),
+            None => info!("Mode off"),
+        }
+    }
+    *current = active;
+}
+
+/// The last published mode, if any is on
+pub fn current() -> Option<ActiveMode> {
+    CURRENT.read().unwrap_or_else(|p| p.into_inner()).clone()
+}
+
+/// Whether the active mode holds back a notification of this severity
+pub fn holds_back(severity: Severity) -> bool {
+    current().is_some_and(|a| a.mode.holds_back(severity))
+}
+
+/// Autonomy level of the active mode
+pub fn autonomy() -> ModeAutonomy {
+    current().map(|a| a.mode.autonomy).unwrap_or_default()
+}
+
+/// Context section telling the agent which mode is on
+pub fn context_section_for(active: &ActiveMode) -> String {
+    let how = match active.source {
+        ModeSource::Manual => "picked by the user",
+        ModeSource::Scheduled => "on its schedule",
+    };
+    let mut section = format!("## Mode\n\n\"{}\" mode is on ({}).", active.mode.name, how);
+    if active.mode.min_severity.is_some() {
+        section.push_str(
+            " Proactive notifications are limited; don't interrupt with anything that can wait.",
+        );
+    }
+    if !active.mode.tools.is_empty() {
+        section.push_str(
+            " Only some tools are available; if asked for something outside them, say \
+             which mode would allow it (set_mode switches modes).",
+        );
+    }
+    section.push_str("\n\n");
+    section
+}
+
+/// A hand-picked mode, with the scheduled mode it overrode
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct ManualChoice {
+    /// `None` switches modes off
+    mode: Option<String>,
+    scheduled: Option<String>,
+}
+
+async fn load_manual(db: &KnowledgeDb) -> Result<Option<ManualChoice>> {
+    let prefs = db.get_preferences(Some(CATEGORY)).await?;
+    Ok(prefs
+        .into_iter()
+        .find(|p| p.key == MANUAL_KEY)
+        .map(|p| ManualChoice {
+            mode: p.value["mode"].as_str().map(String::from),
+            scheduled: p.value["scheduled"].as_str().map(String::from),
+        }))
+}
+
+/// Switch to a mode by name, `none` for no mode, or `auto` to follow the
+/// schedule again. Returns the mode now in effect; a running daemon picks
+/// it up on its next [`refresh`].
+pub async fn set_manual(
+    db: &KnowledgeDb,
+    config: &ModesConfig,
+    name: &str,
+    source: &str,
+) -> Result<Option<ActiveMode>> {
+    let name = name.trim();
+    if name.eq_ignore_ascii_case(AUTO) {
+        db.delete_preference(MANUAL_KEY).await?;
+    } else {
+        let mode = if name.eq_ignore_ascii_case(NONE) {
+            None
+        } else {
+            let mode = config.get(name).ok_or_else(|| {
+                let known: Vec<&str> = config.modes.iter().map(|m| m.name.as_str()).collect();
+                anyhow!(
+                    "Unknown mode '{}'. Modes: {}, or '{}' / '{}'",
+                    name,
+                    known.join(", "),
+                    NONE,
+                    AUTO
+                )
+            })?;
+            Some(mode.name.clone())
+        };
+        let scheduled = config.scheduled_now().map(|m| m.name.clone());
+        db.upsert_preference(
+            CATEGORY,
+            MANUAL_KEY,
+            json!({ "mode": mode, "scheduled": scheduled }),
+            1.0,
+            Some(source),
+        )
+        .await?;
+    }
+    resolve(db, config).await
+}
+
+/// Work out the mode in effect now. A hand-picked mode is dropped once
+/// the schedule has moved on from the mode it overrode.
+pub async fn resolve(db: &KnowledgeDb, config: &ModesConfig) -> Result<Option<ActiveMode>> {
+    let scheduled = config.scheduled_now();
+    let manual = match load_manual(db).await? {
+        Some(choice) if choice.scheduled.as_deref() != scheduled.map(|m| m.name.as_str()) => {
+            debug!("Schedule moved on; dropping hand-picked mode");
+            db.delete_preference(MANUAL_KEY).await?;
+            None
+        }
+        choice => choice,
+    };
+    let active = match manual {
+        Some(choice) => choice
+            .mode
+            .and_then(|name| config.get(&name).cloned())
+            .map(|mode| ActiveMode {
+                mode,
+                source: ModeSource::Manual,
+            }),
+        None => scheduled.cloned().map(|mode| ActiveMode {
+            mode,
+            source: ModeSource::Scheduled,
+        }),
+    };
+    Ok(active)
+}
+
+/// Resolve the mode in effect and publish it
+pub async fn refresh(db: &KnowledgeDb, config: &ModesConfig) -> Result<Option<ActiveMode>> {
+    let active = resolve(db, config).await?;
+    set_current(active.clone());
+    Ok(active)
+}
+
+/// Keeps the active mode in step with the schedule
+pub struct ModeMonitor {
+    db: Arc<KnowledgeDb>,
+    config: Arc<ModesConfig>,
+}
+
+impl ModeMonitor {
+    pub fn new(db: Arc<KnowledgeDb>, config: Arc<ModesConfig>) -> Self {
+        Self { db, config }
+    }
+
+    /// Poll until cancelled
+    pub async fn run(self, cancel: CancellationToken) {
+        if self.config.modes.is_empty() {
+            return;
+        }
+        let interval = Duration::from_secs(self.config.poll_secs.max(10));
+        loop {
+            if let Err(e) = refresh(&self.db, &self.config).await {
+                warn!("Failed to refresh mode: {}", e);
+            }
+            tokio::select! {
+                _ = cancel.cancelled() => break,
+                _ = tokio::time::sleep(interval) => {}
+            }
+        }
+    }
+}
+
+/// Tool executor that refuses tools outside a mode's allowlist
+pub struct ModeToolExecutor {
+    inner: Arc<dyn ToolExecutor>,
+    mode: Mode,
+}
+
+impl ModeToolExecutor {
+    pub fn new(inner: Arc<dyn ToolExecutor>, mode: Mode) -> Self {
+        Self { inner, mode }
+    }
+}
+
+#[async_trait]
+impl ToolExecutor for ModeToolExecutor {
+    async fn execute(&self, tool_name: &str, input: Value) -> Result<String> {
+        if !self.mode.tool_allowed(tool_name) {
+            warn!("Blocked '{}' in {} mode", tool_name, self.mode.name);
+            return Err(anyhow!(
+                "Tool '{}' is not available in {} mode",
+                tool_name,
+                self.mode.name
+            ));
+        }
+        self.inner.execute(tool_name, input).await
+    }
+
+    fn list_tools(&self) -> Vec<ToolDefinition> {
+        self.inner
+            .list_tools()
+            .into_iter()
+            .filter(|t| self.mode.tool_allowed(&t.name))
+            .collect()
+    }
+
+    fn reads_personal_knowledge(&self, tool_name: &str) -> bool {
+        self.inner.reads_personal_knowledge(tool_name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tools::ToolRegistry;
+
+    fn t(s: &str) -> NaiveTime {
+        NaiveTime::parse_from_str(s, "%H:%M").unwrap()
+    }
+
+    fn config() -> ModesConfig {
+        ModesConfig {
+            modes: vec![
+                Mode {
+                    name: "work".to_string(),
+                    min_severity: Some(Severity::Warning),
+                    tools: vec!["search_knowledge".to_string()],
+                    schedule: vec![
+                        ModeWindow::parse(&["weekdays".to_string()], "09:00-17:00").unwrap(),
+                    ],
+                    ..Default::default()
+                },
+                Mode {
+                    name: "night".to_string(),
+                    autonomy: ModeAutonomy::Off,
+                    schedule: vec![ModeWindow::parse(&["fri".to_string()], "22:00-06:00").unwrap()],
+                    ..Default::default()
+                },
+            ],
+            poll_secs: 60,
+        }
+    }
+
+    #[test]
+    fn test_schedule_windows() {
+        let config = config();
+        let at = |day, time| config.scheduled_at(day, t(time)).map(|m| m.name.as_str());
+        assert_eq!(at(Weekday::Mon, "09:00"), Some("work"));
+        assert_eq!(at(Weekday::Fri, "16:59"), Some("work"));
+        assert_eq!(at(Weekday::Fri, "17:00"), None);
+        assert_eq!(at(Weekday::Sat, "10:00"), None);
+        // Friday night runs into Saturday morning, but not Friday morning
+        assert_eq!(at(Weekday::Fri, "23:00"), Some("night"));
+        assert_eq!(at(Weekday::Sat, "05:00"), Some("night"));
+        assert_eq!(at(Weekday::Fri, "05:00"), None);
+
+        assert!(ModeWindow::parse(&["someday".to_string()], "09:00-17:00").is_err());
+        assert!(ModeWindow::parse(&[], "9 to 5").is_err());
+        assert_eq!(
+            ModeAutonomy::from_name("Cautious"),
+            Some(ModeAutonomy::Cautious)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_manual_modes_and_policy() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let db = KnowledgeDb::new(temp.path().join("test.db")).unwrap();
+        let config = config();
+
+        let active = set_manual(&db, &config, "Work", "test")
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(active.mode.name, "work");
+        assert_eq!(active.source, ModeSource::Manual);
+        assert!(active.mode.holds_back(Severity::Info));
+        assert!(!active.mode.holds_back(Severity::Critical));
+        assert!(context_section_for(&active).contains("\"work\" mode is on (picked by the user)"));
+
+        // The choice is stored
+        assert_eq!(resolve(&db, &config).await.unwrap(), Some(active.clone()));
+        assert!(set_manual(&db, &config, "holiday", "test").await.is_err());
+
+        assert!(
+            set_manual(&db, &config, NONE, "test")
+                .await
+                .unwrap()
+                .is_none()
+        );
+        assert!(load_manual(&db).await.unwrap().is_some());
+        set_manual(&db, &config, AUTO, "test").await.unwrap();
+        assert!(load_manual(&db).await.unwrap().is_none());
+
+        let mode = active.mode;
+        assert!(mode.tool_allowed("search_knowledge"));
+        assert!(mode.tool_allowed(SET_MODE_TOOL));
+        assert!(!mode.tool_allowed("send_email"));
+        let executor = ModeToolExecutor::new(Arc::new(ToolRegistry::new()), mode);
+        let err = executor
+            .execute("send_email", serde_json::json!({}))
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("work mode"));
+    }
+}
@@ -0,0 +1,99 @@
+//! Offline mode and the network kill switch
+//!
+//! In offline mode meepo makes no outbound requests: the model router only
+//! uses local providers (e.g. Ollama on localhost), and tools that reach the
+//! internet refuse to run with an "offline mode" error. Push notifications
+//! are held, and A2A peers, cross-encoder re-rankers and embedding servers
+//! are only reached on localhost. Offline mode is set from `[network]
+//! offline` at startup, toggled at runtime by the `network_kill_switch`
+//! tool, and always on in builds with the `offline` cargo feature. When the
+//! config or the build asks for it, it is locked and can't be switched back
+//! off at runtime.
+
+use anyhow::{Result, bail};
+use std::sync::atomic::{AtomicBool, Ordering};
+use tracing::{info, warn};
+
+static OFFLINE: AtomicBool = AtomicBool::new(false);
+static LOCKED: AtomicBool = AtomicBool::new(false);
+
+/// Whether this binary was built with the `offline` feature
+pub const BUILT_OFFLINE: bool = cfg!(feature = "offline");
+
+/// Whether outbound network access is currently cut off
+pub fn is_offline() -> bool {
+    BUILT_OFFLINE || OFFLINE.load(Ordering::Relaxed)
+}
+
+/// Whether offline mode can't be switched off at runtime
+pub fn is_locked() -> bool {
+    BUILT_OFFLINE || LOCKED.load(Ordering::Relaxed)
+}
+
+/// Turn offline mode on or off. Going back online fails when offline mode
+/// is locked by the config or the build.
+pub fn set_offline(offline: bool) -> Result<()> {
+    if !offline && is_locked() {
+        bail!(
+            "offline mode is locked by {}",
+            if BUILT_OFFLINE {
+                "this build (compiled with the `offline` feature)"
+            } else {
+                "the config ([network] offline = true)"
+            }
+        );
+    }
+    if OFFLINE.swap(offline, Ordering::Relaxed) != offline {
+        if offline {
+            warn!("Network kill switch engaged: offline mode on");
+        } else {
+            info!("Network kill switch released: back online");
+        }
+    }
+    Ok(())
+}
+
+/// Turn offline mode on and keep it on for the life of the process
+pub fn lock_offline() {
+    OFFLINE.store(true, Ordering::Relaxed);
+    LOCKED.store(true, Ordering::Relaxed);
+}
+
+/// Fail with a clear error if `what` would need the network while offline
+pub fn ensure_online(what: &str) -> Result<()> {
+    if is_offline() {
+        bail!(
+            "offline mode: {} needs network access, which is disabled \
+             (kill switch on or [network] offline = true)",
+            what
+        );
+    }
+    Ok(())
+}
+
+/// Whether a URL points at this machine (localhost or a loopback address)
+pub fn is_local_url(url: &str) -> bool {
+    let Ok(parsed) = url::Url::parse(url) else {
+        return false;
+    };
+    match parsed.host() {
+        Some(url::Host::Domain(host)) => host.eq_ignore_ascii_case("localhost"),
+        Some(url::Host::Ipv4(ip)) => ip.is_loopback(),
+        Some(url::Host::Ipv6(ip)) => ip.is_loopback(),
+        None => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_local_url() {
+        assert!(is_local_url("http://localhost:11434/v1"));
+        assert!(is_local_url("http://127.0.0.1:8080"));
+        assert!(is_local_url("http://[::1]:11434"));
+        assert!(!is_local_url("https://api.openai.com/v1"));
+        assert!(!is_local_url("not a url"));
+    }
+}
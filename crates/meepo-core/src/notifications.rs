@@ -3,11 +3,33 @@
 //! Sends iMessages (or other channel messages) to the user throughout the day
 //! when Meepo takes autonomous actions, watchers trigger, tasks complete, etc.
 //! Also supports daily digest summaries (morning briefing, evening recap).
+//!
+//! Routes map events — by type, severity, time of day, or a condition
+//! expression (see [`crate::expr`]) — to a target: a channel, an ntfy topic,
+//! or the log only. A route's dedup window collapses a burst of the same
+//! event type into one message.
+//!
+//! With a push bridge (see [`crate::push`]), warnings and critical events
+//! are also pushed to mobile gateway clients whose connection is closed.
+//!
+//! With a snooze store (see [`crate::snooze`]), channel notifications carry
+//! an ID the user can snooze, and [`NotificationService::run_follow_ups`]
+//! sends snoozed and unanswered ones again.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
 
-use chrono::{NaiveTime, Utc};
+use chrono::{DateTime, NaiveTime, Utc};
+use serde_json::Value;
 use tokio::sync::mpsc;
+use tokio_util::sync::CancellationToken;
 use tracing::{debug, info, warn};
 
+use crate::expr::Expression;
+use crate::i18n;
+use crate::push::{PushBridge, PushMessage};
+use crate::snooze::{FollowUpKind, SnoozeStore};
 use crate::types::{ChannelType, MessageKind, OutgoingMessage};
 
 /// Which kind of event triggered this notification
@@ -58,6 +80,250 @@ pub enum NotifyEvent {
     },
 }
 
+/// How urgent a notification is
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Severity {
+    Info,
+    Warning,
+    Critical,
+}
+
+impl Severity {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Info => "info",
+            Self::Warning => "warning",
+            Self::Critical => "critical",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.trim().to_lowercase().as_str() {
+            "info" => Some(Self::Info),
+            "warning" | "warn" => Some(Self::Warning),
+            "critical" | "error" => Some(Self::Critical),
+            _ => None,
+        }
+    }
+}
+
+impl NotifyEvent {
+    /// Failures and budget warnings are warnings; errors and an exceeded
+    /// budget are critical
+    pub fn severity(&self) -> Severity {
+        match self {
+            Self::TaskFailed { .. } | Self::BudgetWarning { .. } => Severity::Warning,
+            Self::Error { .. } | Self::BudgetExceeded { .. } => Severity::Critical,
+            _ => Severity::Info,
+        }
+    }
+
+    /// Event type name, as routing conditions see it in `kind`
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Self::TaskStarted { .. } => "task_started",
+            Self::TaskCompleted { .. } => "task_completed",
+            Self::TaskFailed { .. } => "task_failed",
+            Self::WatcherTriggered { .. } => "watcher_triggered",
+            Self::AutonomousAction { .. } => "autonomous_action",
+            Self::Error { .. } => "error",
+            Self::BudgetWarning { .. } => "budget_warning",
+            Self::BudgetExceeded { .. } => "budget_exceeded",
+            Self::DigestMorning { .. } => "digest_morning",
+            Self::DigestEvening { .. } => "digest_evening",
+        }
+    }
+
+    /// What routing conditions see: `kind`, `severity` and the event's
+    /// fields as `payload`
+    fn vars(&self) -> Value {
+        let payload = match self {
+            Self::TaskStarted {
+                task_id,
+                description,
+            } => serde_json::json!({ "task_id": task_id, "description": description }),
+            Self::TaskCompleted {
+                task_id,
+                description,
+                result_preview,
+            } => serde_json::json!({
+                "task_id": task_id,
+                "description": description,
+                "result": result_preview,
+            }),
+            Self::TaskFailed {
+                task_id,
+                description,
+                error,
+            } => serde_json::json!({
+                "task_id": task_id,
+                "description": description,
+                "error": error,
+            }),
+            Self::WatcherTriggered {
+                watcher_id,
+                kind,
+                payload,
+            } => serde_json::json!({
+                "watcher_id": watcher_id,
+                "kind": kind,
+                // Watcher payloads arrive as JSON text; expose their fields
+                "payload": serde_json::from_str::<Value>(payload)
+                    .unwrap_or_else(|_| Value::String(payload.clone())),
+            }),
+            Self::AutonomousAction { description } => {
+                serde_json::json!({ "description": description })
+            }
+            Self::Error { context, error } => {
+                serde_json::json!({ "context": context, "error": error })
+            }
+            Self::BudgetWarning {
+                period,
+                spent,
+                budget,
+                percent,
+            } => serde_json::json!({
+                "period": period,
+                "spent": spent,
+                "budget": budget,
+                "percent": percent,
+            }),
+            Self::BudgetExceeded {
+                period,
+                spent,
+                budget,
+            } => serde_json::json!({ "period": period, "spent": spent, "budget": budget }),
+            Self::DigestMorning { summary } | Self::DigestEvening { summary } => {
+                serde_json::json!({ "summary": summary })
+            }
+        };
+        serde_json::json!({
+            "kind": self.kind(),
+            "severity": self.severity().as_str(),
+            "payload": payload,
+        })
+    }
+}
+
+/// Where a routed notification goes
+#[derive(Debug, Clone, PartialEq)]
+pub enum NotifyTarget {
+    Channel(ChannelType),
+    /// Push to the configured ntfy topic
+    Ntfy,
+    /// Write to the log only
+    Log,
+}
+
+impl NotifyTarget {
+    /// "ntfy", "log" (or "silent"), or a channel name
+    pub fn from_name(name: &str) -> Option<Self> {
+        let name = name.trim().to_lowercase();
+        match name.as_str() {
+            "ntfy" => Some(Self::Ntfy),
+            "log" | "silent" => Some(Self::Log),
+            "internal" => Some(Self::Channel(ChannelType::Internal)),
+            other => match ChannelType::from_string(other) {
+                ChannelType::Internal => None,
+                channel => Some(Self::Channel(channel)),
+            },
+        }
+    }
+}
+
+/// Sends matching notifications to a target. Empty or unset criteria
+/// match everything.
+#[derive(Debug, Clone)]
+pub struct NotifyRoute {
+    /// Event types (see [`NotifyEvent::kind`])
+    pub events: Vec<String>,
+    pub min_severity: Severity,
+    /// Time-of-day window in the user's timezone; may wrap midnight
+    pub hours: Option<(NaiveTime, NaiveTime)>,
+    pub when: Option<Expression>,
+    pub target: NotifyTarget,
+    /// Collapse events of the same type within this window into one message
+    pub dedup: Option<Duration>,
+}
+
+impl NotifyRoute {
+    /// A route sending everything to `target`
+    pub fn to(target: NotifyTarget) -> Self {
+        Self {
+            events: Vec::new(),
+            min_severity: Severity::Info,
+            hours: None,
+            when: None,
+            target,
+            dedup: None,
+        }
+    }
+
+    fn matches(&self, event: &NotifyEvent, vars: &Value, at: NaiveTime) -> bool {
+        if !self.events.is_empty() && !self.events.iter().any(|e| e == event.kind()) {
+            return false;
+        }
+        if event.severity() < self.min_severity {
+            return false;
+        }
+        if let Some((start, end)) = self.hours
+            && !in_window(at, start, end)
+        {
+            return false;
+        }
+        match &self.when {
+            Some(when) => when.matches(vars).unwrap_or_else(|e| {
+                warn!("Notification route '{}' failed: {}", when, e);
+                false
+            }),
+            None => true,
+        }
+    }
+}
+
+/// Whether `now` falls in `start..end`, wrapping midnight when `end <= start`
+pub(crate) fn in_window(now: NaiveTime, start: NaiveTime, end: NaiveTime) -> bool {
+    if start < end {
+        // e.g. 08:00 - 17:00
+        now >= start && now < end
+    } else {
+        // Wraps midnight, e.g. 23:00 - 08:00
+        now >= start || now < end
+    }
+}
+
+/// ntfy server and topic for the `ntfy` target
+#[derive(Debug, Clone)]
+pub struct NtfyConfig {
+    /// e.g. "https://ntfy.sh"
+    pub server: String,
+    pub topic: String,
+}
+
+/// Post a message to an ntfy topic
+pub(crate) async fn send_ntfy(
+    http: &reqwest::Client,
+    ntfy: &NtfyConfig,
+    severity: Severity,
+    content: String,
+) -> reqwest::Result<()> {
+    let priority = match severity {
+        Severity::Info => "default",
+        Severity::Warning => "high",
+        Severity::Critical => "urgent",
+    };
+    let url = format!("{}/{}", ntfy.server.trim_end_matches('/'), ntfy.topic);
+    http.post(&url)
+        .header("Title", "Meepo")
+        .header("Priority", priority)
+        .body(content)
+        .timeout(Duration::from_secs(10))
+        .send()
+        .await
+        .and_then(|r| r.error_for_status())
+        .map(|_| ())
+}
+
 /// Configuration for the notification service (mirrors config.toml)
 #[derive(Debug, Clone)]
 pub struct NotifyConfig {
@@ -70,6 +336,14 @@ pub struct NotifyConfig {
     pub on_autonomous_action: bool,
     pub on_error: bool,
     pub quiet_hours: Option<(NaiveTime, NaiveTime)>,
+    /// Timezone quiet hours are expressed in
+    pub timezone: chrono_tz::Tz,
+    /// Language for notification text (ISO 639-1)
+    pub language: String,
+    /// Checked in order; the first match picks the target, otherwise
+    /// notifications go to `channel`
+    pub routes: Vec<NotifyRoute>,
+    pub ntfy: Option<NtfyConfig>,
 }
 
 impl Default for NotifyConfig {
@@ -84,17 +358,31 @@ impl Default for NotifyConfig {
             on_autonomous_action: true,
             on_error: true,
             quiet_hours: None,
+            timezone: chrono_tz::Tz::UTC,
+            language: "en".to_string(),
+            routes: Vec::new(),
+            ntfy: None,
         }
     }
 }
 
+/// Messages held back by a route's dedup window, keyed by route and event type
+type PendingBatches = HashMap<(usize, &'static str), Vec<String>>;
+
 /// The notification service — holds config and a sender to the message bus
 #[derive(Clone)]
 pub struct NotificationService {
     config: NotifyConfig,
     response_tx: mpsc::Sender<OutgoingMessage>,
+    pending: Arc<Mutex<PendingBatches>>,
+    http: reqwest::Client,
+    push: Option<Arc<PushBridge>>,
+    snoozes: Option<Arc<SnoozeStore>>,
 }
 
+/// How often snoozes and escalations are checked
+const FOLLOW_UP_INTERVAL: Duration = Duration::from_secs(30);
+
 impl NotificationService {
     pub fn new(config: NotifyConfig, response_tx: mpsc::Sender<OutgoingMessage>) -> Self {
         if config.enabled {
@@ -110,6 +398,52 @@ impl NotificationService {
         Self {
             config,
             response_tx,
+            pending: Arc::new(Mutex::new(HashMap::new())),
+            http: reqwest::Client::new(),
+            push: None,
+            snoozes: None,
+        }
+    }
+
+    /// Also push severe notifications to mobile clients that aren't connected
+    pub fn with_push(mut self, push: Arc<PushBridge>) -> Self {
+        self.push = Some(push);
+        self
+    }
+
+    /// Tag channel notifications with IDs the user can snooze
+    pub fn with_snoozes(mut self, snoozes: Arc<SnoozeStore>) -> Self {
+        self.snoozes = Some(snoozes);
+        self
+    }
+
+    /// Send snoozed notifications when their snooze ends and escalate
+    /// unanswered important ones, until `cancel` fires
+    pub async fn run_follow_ups(self, cancel: CancellationToken) {
+        let Some(snoozes) = self.snoozes.clone() else {
+            return;
+        };
+        let mut ticker = tokio::time::interval(FOLLOW_UP_INTERVAL);
+        loop {
+            tokio::select! {
+                _ = cancel.cancelled() => break,
+                _ = ticker.tick() => {}
+            }
+            // Follow-ups wait out quiet hours and Focus like anything else
+            if self.is_quiet_hours() || crate::focus::is_quiet() {
+                continue;
+            }
+            let t = i18n::strings(&self.config.language);
+            for follow_up in snoozes.due(Utc::now()).await {
+                let notification = follow_up.notification;
+                let (header, severity) = match follow_up.kind {
+                    FollowUpKind::Snoozed => (t.notify_snoozed, Severity::Info),
+                    FollowUpKind::Escalated => (t.notify_escalated, Severity::Warning),
+                };
+                let content = format!("{}\n{}", header, notification.content);
+                self.send_to_channel(notification.channel, severity, content, false)
+                    .await;
+            }
         }
     }
 
@@ -137,15 +471,142 @@ impl NotificationService {
             return;
         }
 
+        // Hold back while a quiet Focus mode is on
+        if !is_error && crate::focus::is_quiet() {
+            debug!("Notification suppressed (focus mode): {:?}", event);
+            return;
+        }
+
+        let severity = event.severity();
+        if crate::modes::holds_back(severity) {
+            debug!("Notification suppressed (mode): {:?}", event);
+            return;
+        }
+
         let content = self.format_message(&event);
 
+        if let Some(push) = &self.push
+            && push.wants(severity)
+        {
+            let message = PushMessage {
+                title: "Meepo".to_string(),
+                body: content.clone(),
+                severity,
+            };
+            let push = push.clone();
+            tokio::spawn(async move {
+                push.push(&message).await;
+            });
+        }
+
+        let Some((index, route)) = self.route_for(&event) else {
+            let target = NotifyTarget::Channel(self.config.channel.clone());
+            self.deliver(&target, severity, content).await;
+            return;
+        };
+        let Some(window) = route.dedup else {
+            self.deliver(&route.target, severity, content).await;
+            return;
+        };
+
+        // The first event in a window starts a batch that's flushed when the
+        // window closes; later ones just join it
+        let key = (index, event.kind());
+        let starts_batch = {
+            let mut pending = self.pending.lock().unwrap_or_else(|p| p.into_inner());
+            let batch = pending.entry(key).or_default();
+            batch.push(content);
+            batch.len() == 1
+        };
+        if starts_batch {
+            let service = self.clone();
+            let target = route.target.clone();
+            tokio::spawn(async move {
+                tokio::time::sleep(window).await;
+                service.flush(key, &target, severity, window).await;
+            });
+        }
+    }
+
+    /// Send a route's held-back messages as one
+    async fn flush(
+        &self,
+        key: (usize, &'static str),
+        target: &NotifyTarget,
+        severity: Severity,
+        window: Duration,
+    ) {
+        let batch = self
+            .pending
+            .lock()
+            .unwrap_or_else(|p| p.into_inner())
+            .remove(&key)
+            .unwrap_or_default();
+        let content = match batch.len() {
+            0 => return,
+            1 => batch.into_iter().next().unwrap_or_default(),
+            n => {
+                let t = i18n::strings(&self.config.language);
+                let header = i18n::fill(
+                    t.notify_batched,
+                    &[
+                        ("n", &n.to_string()),
+                        ("secs", &window.as_secs().to_string()),
+                    ],
+                );
+                format!("{}\n\n{}", header, batch.join("\n\n"))
+            }
+        };
+        self.deliver(target, severity, content).await;
+    }
+
+    async fn deliver(&self, target: &NotifyTarget, severity: Severity, content: String) {
+        match target {
+            NotifyTarget::Channel(channel) => {
+                let escalates = severity >= Severity::Warning;
+                self.send_to_channel(channel.clone(), severity, content, escalates)
+                    .await;
+            }
+            NotifyTarget::Ntfy => {
+                let Some(ntfy) = &self.config.ntfy else {
+                    warn!("Notification routed to ntfy but no topic is configured");
+                    return;
+                };
+                if let Err(e) = send_ntfy(&self.http, ntfy, severity, content).await {
+                    warn!("Failed to send ntfy notification: {}", e);
+                }
+            }
+            NotifyTarget::Log => info!("Notification ({}): {}", severity.as_str(), content),
+        }
+    }
+
+    /// Send to a channel, tagged with a snooze ID when snoozing is on.
+    /// Unanswered notifications escalate only when `escalates` is set.
+    async fn send_to_channel(
+        &self,
+        channel: ChannelType,
+        severity: Severity,
+        content: String,
+        escalates: bool,
+    ) {
+        let content = match &self.snoozes {
+            Some(snoozes) => {
+                let id = snoozes.track(channel.clone(), escalates, &content);
+                format!("{}\n[{}]", content, id)
+            }
+            None => content,
+        };
         let msg = OutgoingMessage {
             content,
-            channel: self.config.channel.clone(),
+            channel,
             reply_to: None,
-            kind: MessageKind::Response,
+            kind: if severity == Severity::Critical {
+                MessageKind::Urgent
+            } else {
+                MessageKind::Response
+            },
+            interactive: None,
         };
-
         if let Err(e) = self.response_tx.send(msg).await {
             warn!("Failed to send notification: {}", e);
         }
@@ -165,32 +626,45 @@ impl NotificationService {
         }
     }
 
+    /// The first matching route and its index
+    fn route_for(&self, event: &NotifyEvent) -> Option<(usize, &NotifyRoute)> {
+        if self.config.routes.is_empty() {
+            return None;
+        }
+        let vars = event.vars();
+        let now = Utc::now().with_timezone(&self.config.timezone).time();
+        self.config
+            .routes
+            .iter()
+            .enumerate()
+            .find(|(_, route)| route.matches(event, &vars, now))
+    }
+
     /// Check if we're currently in quiet hours
     fn is_quiet_hours(&self) -> bool {
+        self.is_quiet_at(Utc::now())
+    }
+
+    /// Check if the given instant falls in quiet hours (in the user's timezone)
+    fn is_quiet_at(&self, at: DateTime<Utc>) -> bool {
         let Some((start, end)) = self.config.quiet_hours else {
             return false;
         };
 
-        let now = Utc::now().time();
-        if start < end {
-            // e.g., 23:00 - 08:00 doesn't wrap, but 22:00 - 06:00 does
-            // Actually start < end means e.g. 08:00 - 17:00 (no wrap)
-            now >= start && now < end
-        } else {
-            // Wraps midnight, e.g., 23:00 - 08:00
-            now >= start || now < end
-        }
+        in_window(at.with_timezone(&self.config.timezone).time(), start, end)
     }
 
     /// Format a notification event into a user-friendly iMessage
     fn format_message(&self, event: &NotifyEvent) -> String {
+        let t = i18n::strings(&self.config.language);
         match event {
             NotifyEvent::TaskStarted {
                 task_id,
                 description,
             } => {
                 format!(
-                    "🤖 Starting background task\n[{}] {}",
+                    "{}\n[{}] {}",
+                    t.task_started,
                     task_id,
                     truncate(description, 200)
                 )
@@ -201,9 +675,11 @@ impl NotificationService {
                 result_preview,
             } => {
                 format!(
-                    "✅ Task completed\n[{}] {}\n\nResult: {}",
+                    "{}\n[{}] {}\n\n{}: {}",
+                    t.task_completed,
                     task_id,
                     truncate(description, 150),
+                    t.result_label,
                     truncate(result_preview, 300)
                 )
             }
@@ -213,9 +689,11 @@ impl NotificationService {
                 error,
             } => {
                 format!(
-                    "❌ Task failed\n[{}] {}\n\nError: {}",
+                    "{}\n[{}] {}\n\n{}: {}",
+                    t.task_failed,
                     task_id,
                     truncate(description, 150),
+                    t.error_label,
                     truncate(error, 200)
                 )
             }
@@ -225,21 +703,20 @@ impl NotificationService {
                 payload,
             } => {
                 format!(
-                    "👁 Watcher triggered\n[{}] {}\n{}",
+                    "{}\n[{}] {}\n{}",
+                    t.watcher_triggered,
                     watcher_id,
                     kind,
                     truncate(payload, 300)
                 )
             }
             NotifyEvent::AutonomousAction { description } => {
-                format!(
-                    "🧠 Taking autonomous action\n{}",
-                    truncate(description, 400)
-                )
+                format!("{}\n{}", t.autonomous_action, truncate(description, 400))
             }
             NotifyEvent::Error { context, error } => {
                 format!(
-                    "⚠️ Error: {}\n{}",
+                    "⚠️ {}: {}\n{}",
+                    t.error_label,
                     truncate(context, 100),
                     truncate(error, 300)
                 )
@@ -249,27 +726,32 @@ impl NotificationService {
                 spent,
                 budget,
                 percent,
-            } => {
-                format!(
-                    "💰 Budget warning: {} spending at {:.0}% (${:.2} of ${:.2})",
-                    period, percent, spent, budget
-                )
-            }
+            } => i18n::fill(
+                t.budget_warning,
+                &[
+                    ("period", period),
+                    ("percent", &format!("{:.0}", percent)),
+                    ("spent", &format!("{:.2}", spent)),
+                    ("budget", &format!("{:.2}", budget)),
+                ],
+            ),
             NotifyEvent::BudgetExceeded {
                 period,
                 spent,
                 budget,
-            } => {
-                format!(
-                    "🚨 Budget EXCEEDED: {} spending ${:.2} of ${:.2} limit. API calls paused.",
-                    period, spent, budget
-                )
-            }
+            } => i18n::fill(
+                t.budget_exceeded,
+                &[
+                    ("period", period),
+                    ("spent", &format!("{:.2}", spent)),
+                    ("budget", &format!("{:.2}", budget)),
+                ],
+            ),
             NotifyEvent::DigestMorning { summary } => {
-                format!("☀️ Good morning! Here's your briefing:\n\n{}", summary)
+                format!("{}\n\n{}", t.digest_morning, summary)
             }
             NotifyEvent::DigestEvening { summary } => {
-                format!("🌙 End of day recap:\n\n{}", summary)
+                format!("{}\n\n{}", t.digest_evening, summary)
             }
         }
     }
@@ -371,6 +853,125 @@ mod tests {
         assert!(msg.content.contains("Report generated"));
     }
 
+    #[tokio::test]
+    async fn test_notify_tags_snoozable_id() {
+        let (tx, mut rx) = mpsc::channel(16);
+        let config = NotifyConfig {
+            enabled: true,
+            ..Default::default()
+        };
+        let snoozes = Arc::new(SnoozeStore::new(Default::default()));
+        let svc = NotificationService::new(config, tx).with_snoozes(snoozes.clone());
+
+        svc.notify(NotifyEvent::TaskFailed {
+            task_id: "t-7".into(),
+            description: "Sync".into(),
+            error: "timeout".into(),
+        })
+        .await;
+
+        let msg = rx.try_recv().unwrap();
+        let id = msg
+            .content
+            .rsplit('[')
+            .next()
+            .unwrap()
+            .trim_end_matches(']');
+        let snooze = crate::types::IncomingMessage {
+            id: "m1".into(),
+            sender: "user".into(),
+            content: format!("snooze {} 2h", id),
+            channel: ChannelType::IMessage,
+            timestamp: Utc::now(),
+        };
+        let reply = snoozes.handle(&snooze).await.unwrap();
+        assert!(reply.starts_with("💤 Snoozed"), "{}", reply);
+    }
+
+    #[tokio::test]
+    async fn test_notify_routes_by_condition() {
+        let (tx, mut rx) = mpsc::channel(16);
+        let config = NotifyConfig {
+            enabled: true,
+            routes: vec![
+                NotifyRoute {
+                    when: Some(
+                        Expression::parse(r#"payload.payload.from contains "boss""#).unwrap(),
+                    ),
+                    ..NotifyRoute::to(NotifyTarget::Channel(ChannelType::Slack))
+                },
+                NotifyRoute {
+                    min_severity: Severity::Warning,
+                    ..NotifyRoute::to(NotifyTarget::Channel(ChannelType::Discord))
+                },
+                NotifyRoute {
+                    events: vec!["autonomous_action".into()],
+                    ..NotifyRoute::to(NotifyTarget::Log)
+                },
+            ],
+            ..Default::default()
+        };
+        let svc = NotificationService::new(config, tx);
+
+        svc.notify(NotifyEvent::WatcherTriggered {
+            watcher_id: "w-1".into(),
+            kind: "email_received".into(),
+            payload: r#"{"from": "Boss@company.com"}"#.into(),
+        })
+        .await;
+        svc.notify(NotifyEvent::Error {
+            context: "agent".into(),
+            error: "boom".into(),
+        })
+        .await;
+        // Logged only
+        svc.notify(NotifyEvent::AutonomousAction {
+            description: "tidied up".into(),
+        })
+        .await;
+        svc.notify(NotifyEvent::TaskStarted {
+            task_id: "t-1".into(),
+            description: "test".into(),
+        })
+        .await;
+
+        assert_eq!(rx.try_recv().unwrap().channel, ChannelType::Slack);
+        assert_eq!(rx.try_recv().unwrap().channel, ChannelType::Discord);
+        assert_eq!(rx.try_recv().unwrap().channel, ChannelType::IMessage);
+        assert!(rx.try_recv().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_notify_dedup_window_collapses_burst() {
+        let (tx, mut rx) = mpsc::channel(16);
+        let config = NotifyConfig {
+            enabled: true,
+            routes: vec![NotifyRoute {
+                events: vec!["task_completed".into()],
+                dedup: Some(Duration::from_millis(50)),
+                ..NotifyRoute::to(NotifyTarget::Channel(ChannelType::Discord))
+            }],
+            ..Default::default()
+        };
+        let svc = NotificationService::new(config, tx);
+
+        for i in 0..5 {
+            svc.notify(NotifyEvent::TaskCompleted {
+                task_id: format!("t-{}", i),
+                description: "build".into(),
+                result_preview: "ok".into(),
+            })
+            .await;
+        }
+        assert!(rx.try_recv().is_err());
+
+        let msg = rx.recv().await.unwrap();
+        assert_eq!(msg.channel, ChannelType::Discord);
+        assert!(msg.content.starts_with("🔔 5 notifications"));
+        assert!(msg.content.contains("t-0") && msg.content.contains("t-4"));
+        assert!(rx.try_recv().is_err());
+    }
+
     #[tokio::test]
     async fn test_notify_suppressed_event_type() {
         let (tx, mut rx) = mpsc::channel(16);
@@ -494,4 +1095,57 @@ mod tests {
         assert!(config.on_error);
         assert!(config.quiet_hours.is_none());
     }
+
+    #[test]
+    fn test_quiet_hours_use_local_timezone() {
+        use chrono::TimeZone;
+
+        let (tx, _rx) = mpsc::channel(1);
+        let service = NotificationService::new(
+            NotifyConfig {
+                quiet_hours: Some((
+                    NaiveTime::from_hms_opt(22, 0, 0).unwrap(),
+                    NaiveTime::from_hms_opt(7, 0, 0).unwrap(),
+                )),
+                timezone: chrono_tz::Tz::America__New_York,
+                ..Default::default()
+            },
+            tx,
+        );
+
+        // 03:00 UTC is 23:00 EDT — quiet
+        assert!(service.is_quiet_at(Utc.with_ymd_and_hms(2025, 6, 10, 3, 0, 0).unwrap()));
+        // 12:00 UTC is 08:00 EDT — not quiet
+        assert!(!service.is_quiet_at(Utc.with_ymd_and_hms(2025, 6, 10, 12, 0, 0).unwrap()));
+        // 23:30 UTC is 19:30 EDT — not quiet, although it would be in UTC
+        assert!(!service.is_quiet_at(Utc.with_ymd_and_hms(2025, 6, 10, 23, 30, 0).unwrap()));
+    }
+
+    #[test]
+    fn test_format_message_localized() {
+        let (tx, _rx) = mpsc::channel(1);
+        let service = NotificationService::new(
+            NotifyConfig {
+                language: "es".to_string(),
+                ..Default::default()
+            },
+            tx,
+        );
+        let msg = service.format_message(&NotifyEvent::TaskCompleted {
+            task_id: "t-1".into(),
+            description: "informe".into(),
+            result_preview: "listo".into(),
+        });
+        assert!(msg.starts_with("✅ Tarea completada"));
+        assert!(msg.contains("Resultado: listo"));
+
+        let msg = service.format_message(&NotifyEvent::BudgetWarning {
+            period: "diario".into(),
+            spent: 8.0,
+            budget: 10.0,
+            percent: 80.0,
+        });
+        assert!(msg.contains("80%"));
+        assert!(msg.contains("$8.00 de $10.00"));
+    }
 }
@@ -0,0 +1,380 @@
+//! Conversation-driven onboarding
+//!
+//! On the first message in a channel (or after `meepo onboard`), the agent
+//! interviews the user — name, timezone, working hours, key people and
+//! projects, notification preferences — and saves the answers with the
+//! `complete_onboarding` tool: an "About Me" section in MEMORY.md, user
+//! preferences, and person/project entities. Onboarding state is stored as
+//! `onboarding` preferences so it runs once, not once per restart.
+
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use meepo_knowledge::KnowledgeDb;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tracing::{debug, info, warn};
+
+use crate::types::{ChannelType, IncomingMessage};
+
+/// Preference category holding onboarding state
+pub const CATEGORY: &str = "onboarding";
+
+/// Key set once onboarding has been completed (or skipped)
+const DONE_KEY: &str = "onboarding:done";
+
+/// Heading of the MEMORY.md section onboarding writes
+const MEMORY_HEADING: &str = "## About Me";
+
+/// A key person named during onboarding
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct KeyPerson {
+    pub name: String,
+    /// How they relate to the user (e.g. "manager", "partner")
+    #[serde(default)]
+    pub relation: Option<String>,
+}
+
+/// What the user told us during onboarding
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct OnboardingAnswers {
+    #[serde(default)]
+    pub name: Option<String>,
+    /// IANA timezone (e.g. "Europe/Berlin")
+    #[serde(default)]
+    pub timezone: Option<String>,
+    /// Free text, e.g. "Mon–Fri 9:00–17:30"
+    #[serde(default)]
+    pub working_hours: Option<String>,
+    #[serde(default)]
+    pub people: Vec<KeyPerson>,
+    #[serde(default)]
+    pub projects: Vec<String>,
+    /// Free text, e.g. "only urgent things after 7pm, digest in the morning"
+    #[serde(default)]
+    pub notifications: Option<String>,
+}
+
+impl OnboardingAnswers {
+    pub fn is_empty(&self) -> bool {
+        self.name.is_none()
+            && self.timezone.is_none()
+            && self.working_hours.is_none()
+            && self.people.is_empty()
+            && self.projects.is_empty()
+            && self.notifications.is_none()
+    }
+
+    /// The MEMORY.md section for these answers
+    pub fn memory_section(&self) -> String {
+        let mut section = format!("{}\n\n", MEMORY_HEADING);
+        let fields = [
+            ("Name", &self.name),
+            ("Timezone", &self.timezone),
+            ("Working hours", &self.working_hours),
+            ("Notifications", &self.notifications),
+        ];
+        for (label, value) in fields {
+            if let Some(value) = value {
+                section.push_str(&format!("- **{}:** {}\n", label, value));
+            }
+        }
+        if !self.people.is_empty() {
+            let people: Vec<String> = self
+                .people
+                .iter()
+                .map(|p| match &p.relation {
+                    Some(relation) => format!("{} ({})", p.name, relation),
+                    None => p.name.clone(),
+                })
+                .collect();
+            section.push_str(&format!("- **Key people:** {}\n", people.join(", ")));
+        }
+        if !self.projects.is_empty() {
+            section.push_str(&format!("- **Projects:** {}\n", self.projects.join(", ")));
+        }
+        section
+    }
+}
+
+/// Whether onboarding has been completed or skipped
+pub async fn is_done(db: &KnowledgeDb) -> Result<bool> {
+    let prefs = db.get_preferences(Some(CATEGORY)).await?;
+    Ok(prefs.iter().any(|p| p.key == DONE_KEY))
+}
+
+/// Start (or restart) the interview on a channel's next message
+pub async fn start(db: &KnowledgeDb, channel: &str) -> Result<()> {
+    db.delete_preference(DONE_KEY).await?;
+    db.upsert_preference(
+        CATEGORY,
+        &channel_key(channel),
+        Value::Bool(true),
+        1.0,
+        Some("onboarding"),
+    )
+    .await?;
+    Ok(())
+}
+
+/// Whether this message is part of an onboarding interview. The first
+/// message on a channel starts one, unless onboarding is already done.
+/// Call before the message is stored in conversation history.
+pub async fn interviewing(db: &KnowledgeDb, msg: &IncomingMessage) -> Result<bool> {
+    if msg.channel == ChannelType::Internal {
+        return Ok(false);
+    }
+    let prefs = db.get_preferences(Some(CATEGORY)).await?;
+    if prefs.iter().any(|p| p.key == DONE_KEY) {
+        return Ok(false);
+    }
+    let channel = msg.channel.to_string();
+    let key = channel_key(&channel);
+    if prefs.iter().any(|p| p.key == key) {
+        return Ok(true);
+    }
+    let first_contact = db
+        .get_recent_conversations(Some(&channel), 1)
+        .await?
+        .is_empty();
+    if first_contact {
+        info!("First message on {}, starting onboarding", channel);
+        start(db, &channel).await?;
+    }
+    Ok(first_contact)
+}
+
+/// Save onboarding answers — MEMORY.md, preferences and entities — and
+/// mark onboarding done. Returns a short summary of what was saved.
+pub async fn complete(
+    db: &KnowledgeDb,
+    memory_path: &Path,
+    answers: &OnboardingAnswers,
+) -> Result<String> {
+    let mut saved = Vec::new();
+    if !answers.is_empty() {
+        let memory = meepo_knowledge::load_memory(memory_path).unwrap_or_default();
+        meepo_knowledge::save_memory(
+            memory_path,
+            &replace_section(&memory, &answers.memory_section()),
+        )
+        .context("Failed to update MEMORY.md")?;
+        saved.push("MEMORY.md".to_string());
+    }
+
+    let prefs = [
+        ("communication", "user_name", &answers.name),
+        ("schedule", "user_timezone", &answers.timezone),
+        ("schedule", "working_hours", &answers.working_hours),
+        (
+            "communication",
+            "notification_preferences",
+            &answers.notifications,
+        ),
+    ];
+    let mut pref_count = 0;
+    for (category, key, value) in prefs {
+        if let Some(value) = value {
+            db.upsert_preference(
+                category,
+                key,
+                Value::String(value.clone()),
+                1.0,
+                Some("onboarding"),
+            )
+            .await?;
+            pref_count += 1;
+        }
+    }
+    if pref_count > 0 {
+        saved.push(format!("{} preferences", pref_count));
+    }
+
+    let mut entity_count = 0;
+    for person in &answers.people {
+        let metadata = person
+            .relation
+            .as_ref()
+            .map(|r| serde_json::json!({ "relation": r, "source": "onboarding" }));
+        if add_entity(db, &person.name, "person", metadata).await? {
+            entity_count += 1;
+        }
+    }
+    for project in &answers.projects {
+        let metadata = Some(serde_json::json!({ "source": "onboarding" }));
+        if add_entity(db, project, "project", metadata).await? {
+            entity_count += 1;
+        }
+    }
+    if entity_count > 0 {
+        saved.push(format!("{} people/projects", entity_count));
+    }
+
+    finish(db).await?;
+    debug!("Onboarding complete: {:?}", saved);
+    Ok(if saved.is_empty() {
+        "Onboarding skipped; nothing saved.".to_string()
+    } else {
+        format!("Onboarding complete. Saved {}.", saved.join(", "))
+    })
+}
+
+/// Context section that asks the agent to run the interview
+pub fn context_section() -> String {
+    "\n\n## Onboarding\n\nThis is a new user. Before anything else (but after helping with \
+     whatever they asked), get to know them with a short, friendly interview, one or two \
+     questions per message: their name, timezone, working hours, the key people and projects \
+     in their life, and how and when they want to be notified. When you have the answers — or \
+     they'd rather skip — call complete_onboarding with what you learned.\n"
+        .to_string()
+}
+
+async fn add_entity(
+    db: &KnowledgeDb,
+    name: &str,
+    entity_type: &str,
+    metadata: Option<Value>,
+) -> Result<bool> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Ok(false);
+    }
+    let existing = db.search_entities(name, Some(entity_type)).await?;
+    if existing.iter().any(|e| e.name.eq_ignore_ascii_case(name)) {
+        return Ok(false);
+    }
+    db.insert_entity(name, entity_type, metadata).await?;
+    Ok(true)
+}
+
+async fn finish(db: &KnowledgeDb) -> Result<()> {
+    for pref in db.get_preferences(Some(CATEGORY)).await? {
+        if let Err(e) = db.delete_preference(&pref.key).await {
+            warn!("Failed to clear onboarding state {}: {}", pref.key, e);
+        }
+    }
+    db.upsert_preference(
+        CATEGORY,
+        DONE_KEY,
+        Value::String(chrono::Utc::now().to_rfc3339()),
+        1.0,
+        Some("onboarding"),
+    )
+    .await?;
+    Ok(())
+}
+
+fn channel_key(channel: &str) -> String {
+    format!("onboarding:{}", channel.trim().to_lowercase())
+}
+
+/// Replace the "About Me" section of MEMORY.md (up to the next `## `
+/// heading), or append it
+fn replace_section(memory: &str, section: &str) -> String {
+    let Some(start) = memory.find(MEMORY_HEADING) else {
+        let trimmed = memory.trim_end();
+        return if trimmed.is_empty() {
+            section.to_string()
+        } else {
+            format!("{}\n\n{}", trimmed, section)
+        };
+    };
+    let rest = &memory[start + MEMORY_HEADING.len()..];
+    let end = rest
+        .find("\n## ")
+        .map(|i| start + MEMORY_HEADING.len() + i + 1);
+    match end {
+        Some(end) => format!("{}{}\n{}", &memory[..start], section, &memory[end..]),
+        None => format!("{}{}", &memory[..start], section),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn message(channel: ChannelType) -> IncomingMessage {
+        IncomingMessage {
+            id: "1".to_string(),
+            sender: "owner".to_string(),
+            content: "hi".to_string(),
+            channel,
+            timestamp: chrono::Utc::now(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_onboarding_flow() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let db = KnowledgeDb::new(temp.path().join("test.db")).unwrap();
+        let memory_path = temp.path().join("MEMORY.md");
+        std::fs::write(&memory_path, "# Meepo - Memory\n\n## Notes\n\nLikes tea\n").unwrap();
+
+        assert!(
+            interviewing(&db, &message(ChannelType::Slack))
+                .await
+                .unwrap()
+        );
+        db.insert_conversation("slack", "owner", "hi", None)
+            .await
+            .unwrap();
+        // Still interviewing on the same channel until it's completed
+        assert!(
+            interviewing(&db, &message(ChannelType::Slack))
+                .await
+                .unwrap()
+        );
+        assert!(
+            !interviewing(&db, &message(ChannelType::Internal))
+                .await
+                .unwrap()
+        );
+
+        let answers = OnboardingAnswers {
+            name: Some("Alex".to_string()),
+            timezone: Some("Europe/Berlin".to_string()),
+            people: vec![KeyPerson {
+                name: "Jordan".to_string(),
+                relation: Some("manager".to_string()),
+            }],
+            projects: vec!["Apollo".to_string()],
+            ..Default::default()
+        };
+        let summary = complete(&db, &memory_path, &answers).await.unwrap();
+        assert!(summary.contains("2 preferences"));
+        assert!(summary.contains("2 people/projects"));
+        assert!(is_done(&db).await.unwrap());
+        assert!(
+            !interviewing(&db, &message(ChannelType::Discord))
+                .await
+                .unwrap()
+        );
+
+        let memory = std::fs::read_to_string(&memory_path).unwrap();
+        assert!(memory.contains("## Notes\n\nLikes tea"));
+        assert!(memory.contains("- **Key people:** Jordan (manager)"));
+
+        // Re-running replaces the section instead of appending another
+        start(&db, "slack").await.unwrap();
+        complete(&db, &memory_path, &answers).await.unwrap();
+        let memory = std::fs::read_to_string(&memory_path).unwrap();
+        assert_eq!(memory.matches(MEMORY_HEADING).count(), 1);
+        assert_eq!(
+            db.search_entities("Jordan", Some("person"))
+                .await
+                .unwrap()
+                .len(),
+            1
+        );
+    }
+
+    #[test]
+    fn test_replace_section_before_next_heading() {
+        let memory = "# Memory\n\n## About Me\n\n- old\n\n## Notes\n\nx\n";
+        let updated = replace_section(memory, "## About Me\n\n- new\n");
+        assert_eq!(
+            updated,
+            "# Memory\n\n## About Me\n\n- new\n\n## Notes\n\nx\n"
+        );
+    }
+}
@@ -3,6 +3,8 @@
 //! Spawns focused Meepo clones for parallel and background task execution.
 //! Each clone gets a scoped toolset and works independently, reporting back
 //! to the prime Meepo when done. If one clone fails, the others keep digging.
+//! Clones in a group share a scratchpad; in sequential mode each one picks
+//! up where the last left off (researcher → writer → reviewer).
 
 use std::collections::HashSet;
 use std::sync::Arc;
@@ -17,6 +19,7 @@ use tokio::sync::{Semaphore, mpsc};
 use tracing::{debug, warn};
 
 use crate::api::{ApiClient, ToolDefinition};
+use crate::scratchpad::{self, Scratchpad, ScratchpadToolExecutor};
 use crate::tools::{ToolExecutor, ToolRegistry};
 use crate::types::{ChannelType, MessageKind, OutgoingMessage};
 use crate::usage::{AccumulatedUsage, UsageSource, UsageTracker};
@@ -27,6 +30,8 @@ use crate::usage::{AccumulatedUsage, UsageSource, UsageTracker};
 pub enum ExecutionMode {
     Parallel,
     Background,
+    /// One clone after another, in the order given
+    Sequential,
 }
 
 /// Status of a completed sub-task
@@ -83,6 +88,10 @@ pub struct OrchestratorConfig {
     pub parallel_timeout_secs: u64,
     pub background_timeout_secs: u64,
     pub max_background_groups: usize,
+    /// Notes a group's scratchpad holds before refusing more
+    pub scratchpad_max_notes: usize,
+    /// Characters kept per scratchpad note
+    pub scratchpad_max_note_chars: usize,
 }
 
 impl Default for OrchestratorConfig {
@@ -93,6 +102,8 @@ impl Default for OrchestratorConfig {
             parallel_timeout_secs: 120,
             background_timeout_secs: 600,
             max_background_groups: 3,
+            scratchpad_max_notes: 50,
+            scratchpad_max_note_chars: 2000,
         }
     }
 }
@@ -135,6 +146,10 @@ impl ToolExecutor for FilteredToolExecutor {
             .filter(|t| self.allowed.contains(&t.name))
             .collect()
     }
+
+    fn reads_personal_knowledge(&self, tool_name: &str) -> bool {
+        self.inner.reads_personal_knowledge(tool_name)
+    }
 }
 
 /// The clone orchestrator — spawns and manages Meepo clones for delegated work.
@@ -167,23 +182,41 @@ impl TaskOrchestrator {
         self
     }
 
+    /// A fresh scratchpad for one task group
+    fn scratchpad(&self) -> Arc<Scratchpad> {
+        Arc::new(Scratchpad::new(
+            self.config.scratchpad_max_notes,
+            self.config.scratchpad_max_note_chars,
+        ))
+    }
+
     /// Spawn a single clone to execute a focused task. Returns the result.
     async fn run_subtask(
         api: ApiClient,
         registry: Arc<ToolRegistry>,
         task: SubTask,
         timeout_secs: u64,
+        pad: Arc<Scratchpad>,
     ) -> SubTaskResult {
         let system_prompt = format!(
             "You are a Meepo clone — a focused copy of the prime agent, spawned to handle a specific task. \
              Dig in, get it done, and report back concisely.\n\n\
              ## Context\n{}\n\n\
              ## Your Task\n{}\n\n\
+             ## Scratchpad\nOther clones in your group share a scratchpad. Check it with `{}` \
+             before you start, and leave anything they'd need with `{}`.\n\n\
              Respond with your findings/results directly. Be concise.",
-            task.context_summary, task.prompt
+            task.context_summary,
+            task.prompt,
+            scratchpad::READ_TOOL,
+            scratchpad::WRITE_TOOL
         );
 
-        let filtered = FilteredToolExecutor::new(registry, &task.allowed_tools);
+        let filtered = ScratchpadToolExecutor::new(
+            FilteredToolExecutor::new(registry, &task.allowed_tools),
+            pad.clone(),
+            task.task_id.clone(),
+        );
         let tool_defs = filtered.list_tools();
 
         let result = tokio::time::timeout(
@@ -192,6 +225,16 @@ impl TaskOrchestrator {
         )
         .await;
 
+        // Later clones in the group see each finished clone's result
+        if let Ok(Ok((output, _))) = &result
+            && let Err(e) = pad.append(&task.task_id, "result", output)
+        {
+            debug!(
+                "Couldn't post {}'s result to the scratchpad: {}",
+                task.task_id, e
+            );
+        }
+
         match result {
             Ok(Ok((output, usage))) => SubTaskResult {
                 task_id: task.task_id,
@@ -248,6 +291,7 @@ impl TaskOrchestrator {
             channel: channel.clone(),
             reply_to: reply_to.clone(),
             kind: MessageKind::Response,
+            interactive: None,
         };
         if let Err(e) = self.progress_tx.send(msg).await {
             warn!("Failed to send progress message: {}", e);
@@ -279,15 +323,17 @@ impl TaskOrchestrator {
         .await;
 
         let semaphore = Arc::new(Semaphore::new(self.config.max_concurrent_subtasks));
+        let pad = self.scratchpad();
         let mut handles = Vec::new();
         for task in group.tasks {
             let api = self.api.clone();
             let reg = registry.clone();
             let sem = semaphore.clone();
+            let pad = pad.clone();
             let timeout_secs = self.config.parallel_timeout_secs;
             handles.push(tokio::spawn(async move {
                 let _permit = sem.acquire().await.expect("semaphore closed");
-                Self::run_subtask(api, reg, task, timeout_secs).await
+                Self::run_subtask(api, reg, task, timeout_secs, pad).await
             }));
         }
 
@@ -312,6 +358,58 @@ impl TaskOrchestrator {
         Ok(Self::format_results(&results))
     }
 
+    /// Execute a task group in sequential mode — clones take turns, in order,
+    /// each reading what the earlier ones left on the scratchpad. Blocks
+    /// until the last one reports back.
+    pub async fn run_sequential(
+        &self,
+        group: TaskGroup,
+        registry: Arc<ToolRegistry>,
+    ) -> Result<String> {
+        let task_count = group.tasks.len();
+
+        if task_count > self.config.max_subtasks_per_request {
+            return Err(anyhow!(
+                "Too many sub-tasks: {} (max {})",
+                task_count,
+                self.config.max_subtasks_per_request,
+            ));
+        }
+
+        self.send_progress(
+            &group.channel,
+            &group.reply_to,
+            &format!("Running {} clones in turn...", task_count),
+        )
+        .await;
+
+        let pad = self.scratchpad();
+        let mut results = Vec::new();
+        for task in group.tasks {
+            let result = Self::run_subtask(
+                self.api.clone(),
+                registry.clone(),
+                task,
+                self.config.parallel_timeout_secs,
+                pad.clone(),
+            )
+            .await;
+            if result.status != SubTaskStatus::Completed {
+                warn!(
+                    "Clone '{}' {}; the next one continues without its result",
+                    result.task_id, result.status
+                );
+            }
+            results.push(result);
+        }
+
+        if let Some(tracker) = &self.usage_tracker {
+            Self::record_subtask_usage(tracker, self.api.model(), &results).await;
+        }
+
+        Ok(Self::format_results(&results))
+    }
+
     /// Execute a task group in background mode — fire-and-forget clones.
     /// Returns immediately. Clones report progress as they surface.
     pub async fn run_background(
@@ -358,6 +456,7 @@ impl TaskOrchestrator {
         let max_concurrent = self.config.max_concurrent_subtasks;
         let usage_tracker = self.usage_tracker.clone();
         let model_name = self.api.model().to_string();
+        let pad = self.scratchpad();
 
         tokio::spawn(async move {
             let _ = progress_tx
@@ -366,6 +465,7 @@ impl TaskOrchestrator {
                     channel: channel.clone(),
                     reply_to: reply_to.clone(),
                     kind: MessageKind::Response,
+                    interactive: None,
                 })
                 .await;
 
@@ -375,9 +475,10 @@ impl TaskOrchestrator {
                 let api = api.clone();
                 let reg = registry.clone();
                 let sem = semaphore.clone();
+                let pad = pad.clone();
                 handles.push(tokio::spawn(async move {
                     let _permit = sem.acquire().await.expect("semaphore closed");
-                    Self::run_subtask(api, reg, task, timeout_secs).await
+                    Self::run_subtask(api, reg, task, timeout_secs, pad).await
                 }));
             }
 
@@ -398,6 +499,7 @@ impl TaskOrchestrator {
                                 channel: channel.clone(),
                                 reply_to: reply_to.clone(),
                                 kind: MessageKind::Response,
+                                interactive: None,
                             })
                             .await;
                         results.push(result);
@@ -409,6 +511,7 @@ impl TaskOrchestrator {
                                 channel: channel.clone(),
                                 reply_to: reply_to.clone(),
                                 kind: MessageKind::Response,
+                                interactive: None,
                             })
                             .await;
                         results.push(SubTaskResult {
@@ -433,6 +536,7 @@ impl TaskOrchestrator {
                     channel: channel.clone(),
                     reply_to: reply_to.clone(),
                     kind: MessageKind::Response,
+                    interactive: None,
                 })
                 .await;
 
@@ -493,6 +597,8 @@ mod tests {
         assert_eq!(json, "\"parallel\"");
         let mode: ExecutionMode = serde_json::from_str("\"background\"").unwrap();
         assert_eq!(mode, ExecutionMode::Background);
+        let mode: ExecutionMode = serde_json::from_str("\"sequential\"").unwrap();
+        assert_eq!(mode, ExecutionMode::Sequential);
     }
 
     #[test]
@@ -510,6 +616,7 @@ mod tests {
         assert_eq!(config.parallel_timeout_secs, 120);
         assert_eq!(config.background_timeout_secs, 600);
         assert_eq!(config.max_background_groups, 3);
+        assert_eq!(config.scratchpad_max_notes, 50);
     }
 
     #[tokio::test]
@@ -0,0 +1,227 @@
+//! Pinned context — facts kept in one conversation's context
+//!
+//! The user (with `!pin`/`!unpin`) or the agent (with the `pin_context` and
+//! `unpin_context` tools) can pin facts to a conversation. Pins are added to
+//! every prompt for that channel and sender, after history windowing and
+//! context compression, so they're never dropped or summarized away. They're
+//! stored as `pinned_context` preferences keyed by
+//! `pinned_context:channel:sender`.
+
+use std::future::Future;
+
+use anyhow::{Result, anyhow};
+use chrono::{DateTime, Utc};
+use meepo_knowledge::KnowledgeDb;
+use serde::{Deserialize, Serialize};
+
+use crate::style::{preference_key, sender_scope};
+
+/// Preference category holding each conversation's pins
+pub const CATEGORY: &str = "pinned_context";
+
+/// Most pins a conversation can hold
+pub const MAX_PINS: usize = 20;
+
+/// Inline commands handled here rather than as conversation settings
+pub const COMMANDS: &[&str] = &["pin", "unpin", "pins"];
+
+/// Longest text one pin can hold
+const MAX_PIN_CHARS: usize = 500;
+
+tokio::task_local! {
+    /// `(channel, sender)` of the conversation the current agent turn is for
+    static CURRENT_CONVERSATION: (String, String);
+}
+
+/// Run a future as part of a conversation's turn, so pin tools know where
+/// to pin
+pub async fn scope_conversation<F: Future>(channel: String, sender: String, fut: F) -> F::Output {
+    CURRENT_CONVERSATION.scope((channel, sender), fut).await
+}
+
+/// The conversation the calling tool is running for, if any
+pub fn current_conversation() -> Option<(String, String)> {
+    CURRENT_CONVERSATION.try_with(|c| c.clone()).ok()
+}
+
+/// One pinned fact
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Pin {
+    pub text: String,
+    pub pinned_at: DateTime<Utc>,
+}
+
+/// Pins for a sender on a channel, oldest first
+pub async fn load(db: &KnowledgeDb, channel: &str, sender: &str) -> Result<Vec<Pin>> {
+    let key = preference_key(CATEGORY, &sender_scope(channel, sender));
+    let prefs = db.get_preferences(Some(CATEGORY)).await?;
+    Ok(prefs
+        .into_iter()
+        .find(|p| p.key == key)
+        .and_then(|p| serde_json::from_value(p.value).ok())
+        .unwrap_or_default())
+}
+
+async fn save(db: &KnowledgeDb, channel: &str, sender: &str, pins: &[Pin]) -> Result<()> {
+    db.upsert_preference(
+        CATEGORY,
+        &preference_key(CATEGORY, &sender_scope(channel, sender)),
+        serde_json::to_value(pins)?,
+        1.0,
+        Some("pin"),
+    )
+    .await?;
+    Ok(())
+}
+
+/// Pin a fact to a conversation; returns a confirmation
+pub async fn pin(db: &KnowledgeDb, channel: &str, sender: &str, text: &str) -> Result<String> {
+    let text = text.trim();
+    if text.is_empty() {
+        return Err(anyhow!("Usage: !pin <fact to keep in this conversation>"));
+    }
+    if text.chars().count() > MAX_PIN_CHARS {
+        return Err(anyhow!(
+            "Pins are limited to {} characters; pin a shorter summary",
+            MAX_PIN_CHARS
+        ));
+    }
+    let mut pins = load(db, channel, sender).await?;
+    if let Some(i) = pins.iter().position(|p| p.text.eq_ignore_ascii_case(text)) {
+        return Ok(format!("Already pinned as #{}", i + 1));
+    }
+    if pins.len() >= MAX_PINS {
+        return Err(anyhow!(
+            "This conversation already has {} pins; unpin one first",
+            MAX_PINS
+        ));
+    }
+    pins.push(Pin {
+        text: text.to_string(),
+        pinned_at: Utc::now(),
+    });
+    save(db, channel, sender, &pins).await?;
+    Ok(format!("Pinned #{}: {}", pins.len(), text))
+}
+
+/// Unpin by number, by text, or `all`; returns a confirmation
+pub async fn unpin(db: &KnowledgeDb, channel: &str, sender: &str, target: &str) -> Result<String> {
+    let target = target.trim().trim_start_matches('#');
+    if target.is_empty() {
+        return Err(anyhow!("Usage: !unpin <number|text|all>"));
+    }
+    let mut pins = load(db, channel, sender).await?;
+    if target.eq_ignore_ascii_case("all") {
+        let count = pins.len();
+        save(db, channel, sender, &[]).await?;
+        return Ok(format!("Removed {} pin(s)", count));
+    }
+    let index = match target.parse::<usize>() {
+        Ok(n) if (1..=pins.len()).contains(&n) => n - 1,
+        Ok(n) => return Err(anyhow!("There's no pin #{}", n)),
+        Err(_) => {
+            let target = target.to_lowercase();
+            let matches: Vec<usize> = pins
+                .iter()
+                .enumerate()
+                .filter(|(_, p)| p.text.to_lowercase().contains(&target))
+                .map(|(i, _)| i)
+                .collect();
+            match matches.as_slice() {
+                [i] => *i,
+                [] => return Err(anyhow!("No pin matches '{}'", target)),
+                _ => {
+                    return Err(anyhow!(
+                        "'{}' matches {} pins; unpin by number instead",
+                        target,
+                        matches.len()
+                    ));
+                }
+            }
+        }
+    };
+    let removed = pins.remove(index);
+    save(db, channel, sender, &pins).await?;
+    Ok(format!("Unpinned: {}", removed.text))
+}
+
+/// Numbered list for `!pins`
+pub fn describe(pins: &[Pin]) -> String {
+    if pins.is_empty() {
+        return "Nothing is pinned in this conversation.".to_string();
+    }
+    let lines: Vec<String> = pins
+        .iter()
+        .enumerate()
+        .map(|(i, p)| format!("{}. {}", i + 1, p.text))
+        .collect();
+    format!("Pinned:\n{}", lines.join("\n"))
+}
+
+/// Context section with the conversation's pins (empty if none)
+pub fn context_section(pins: &[Pin]) -> String {
+    if pins.is_empty() {
+        return String::new();
+    }
+    let mut section = String::from(
+        "\n\n## Pinned for This Conversation\n\
+         These were pinned to this thread; keep them in mind in every reply:\n",
+    );
+    for (i, pin) in pins.iter().enumerate() {
+        section.push_str(&format!("{}. {}\n", i + 1, pin.text));
+    }
+    section
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[tokio::test]
+    async fn test_pin_and_unpin() {
+        let temp = TempDir::new().unwrap();
+        let db = KnowledgeDb::new(temp.path().join("test.db")).unwrap();
+
+        assert_eq!(
+            pin(&db, "slack", "alice", "Budget is $2k").await.unwrap(),
+            "Pinned #1: Budget is $2k"
+        );
+        pin(&db, "slack", "alice", "Flight UA 123 on Friday")
+            .await
+            .unwrap();
+        assert_eq!(
+            pin(&db, "slack", "alice", "budget is $2K").await.unwrap(),
+            "Already pinned as #1"
+        );
+        assert!(pin(&db, "slack", "alice", " ").await.is_err());
+        assert!(load(&db, "slack", "bob").await.unwrap().is_empty());
+
+        let pins = load(&db, "slack", "alice").await.unwrap();
+        assert_eq!(pins.len(), 2);
+        let section = context_section(&pins);
+        assert!(section.contains("## Pinned for This Conversation"));
+        assert!(section.contains("2. Flight UA 123 on Friday"));
+
+        assert_eq!(
+            unpin(&db, "slack", "alice", "flight").await.unwrap(),
+            "Unpinned: Flight UA 123 on Friday"
+        );
+        assert!(unpin(&db, "slack", "alice", "#3").await.is_err());
+        assert_eq!(
+            unpin(&db, "slack", "alice", "all").await.unwrap(),
+            "Removed 1 pin(s)"
+        );
+        assert!(context_section(&load(&db, "slack", "alice").await.unwrap()).is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_current_conversation() {
+        assert_eq!(current_conversation(), None);
+        let inside = scope_conversation("slack".to_string(), "alice".to_string(), async {
+            current_conversation()
+        })
+        .await;
+        assert_eq!(inside, Some(("slack".to_string(), "alice".to_string())));
+    }
+}
@@ -0,0 +1,750 @@
+//! Chrome DevTools Protocol browser backend
+//!
+//! Drives Chrome/Chromium over its remote debugging websocket instead of
+//! AppleScript. Works on every platform, waits on real DOM state (so SPAs
+//! behave), and can launch its own headless instance for background tasks.
+
+use anyhow::{Context, Result, anyhow};
+use async_trait::async_trait;
+use base64::Engine;
+use futures_util::{SinkExt, StreamExt};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+use tokio::process::{Child, Command};
+use tokio_tungstenite::tungstenite::Message;
+use tracing::{debug, info, warn};
+
+use super::{BrowserCookie, BrowserProvider, BrowserTab, PageContent};
+
+/// Interval between DOM polls while waiting for load or elements
+const POLL_INTERVAL_MS: u64 = 100;
+
+/// Upper bound on any single wait, regardless of what the caller asks for
+const MAX_WAIT_MS: u64 = 30_000;
+
+/// Configuration for the CDP backend
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CdpConfig {
+    /// DevTools HTTP endpoint, e.g. `http://127.0.0.1:9222`
+    #[serde(default = "default_endpoint")]
+    pub endpoint: String,
+    /// Launch Chrome automatically if nothing is listening on the endpoint
+    #[serde(default = "default_true")]
+    pub launch: bool,
+    /// Run the launched instance headless
+    #[serde(default = "default_true")]
+    pub headless: bool,
+    /// Chrome/Chromium binary (default: platform-specific search)
+    #[serde(default)]
+    pub chrome_path: Option<String>,
+    /// Profile directory for the launched instance
+    #[serde(default)]
+    pub user_data_dir: Option<PathBuf>,
+    /// Timeout for a single protocol command and for page loads
+    #[serde(default = "default_timeout_ms")]
+    pub timeout_ms: u64,
+}
+
+fn default_endpoint() -> String {
+    "http://127.0.0.1:9222".to_string()
+}
+
+fn default_true() -> bool {
+    true
+}
+
+fn default_timeout_ms() -> u64 {
+    15_000
+}
+
+impl Default for CdpConfig {
+    fn default() -> Self {
+        Self {
+            endpoint: default_endpoint(),
+            launch: true,
+            headless: true,
+            chrome_path: None,
+            user_data_dir: None,
+            timeout_ms: default_timeout_ms(),
+        }
+    }
+}
+
+/// A debuggable target as reported by `/json/list`
+#[derive(Debug, Clone, Deserialize)]
+struct CdpTarget {
+    id: String,
+    #[serde(rename = "type")]
+    kind: String,
+    #[serde(default)]
+    title: String,
+    #[serde(default)]
+    url: String,
+    #[serde(rename = "webSocketDebuggerUrl", default)]
+    ws_url: Option<String>,
+}
+
+struct CdpInner {
+    config: CdpConfig,
+    http: reqwest::Client,
+    next_id: AtomicU64,
+    child: tokio::sync::Mutex<Option<Child>>,
+}
+
+/// Browser provider backed by the Chrome DevTools Protocol.
+///
+/// Cheap to clone; all clones share one launched browser process.
+#[derive(Clone)]
+pub struct CdpBrowser {
+    inner: Arc<CdpInner>,
+}
+
+impl CdpBrowser {
+    pub fn new(config: CdpConfig) -> Self {
+        Self {
+            inner: Arc::new(CdpInner {
+                config,
+                http: reqwest::Client::builder()
+                    .timeout(Duration::from_secs(10))
+                    .build()
+                    .unwrap_or_default(),
+                next_id: AtomicU64::new(1),
+                child: tokio::sync::Mutex::new(None),
+            }),
+        }
+    }
+
+    fn endpoint(&self) -> &str {
+        self.inner.config.endpoint.trim_end_matches('/')
+    }
+
+    fn timeout(&self) -> Duration {
+        Duration::from_millis(self.inner.config.timeout_ms)
+    }
+
+    /// Fetch page targets, launching Chrome on first failure if configured
+    async fn targets(&self) -> Result<Vec<CdpTarget>> {
+        match self.fetch_targets().await {
+            Ok(t) => Ok(t),
+            Err(e) if self.inner.config.launch => {
+                debug!("CDP endpoint unreachable ({}), launching browser", e);
+                self.launch().await?;
+                self.fetch_targets().await
+            }
+            Err(e) => Err(e.context(format!(
+                "Chrome DevTools endpoint {} is not reachable. Start Chrome with \
+                 --remote-debugging-port or set browser.cdp.launch = true",
+                self.endpoint()
+            ))),
+        }
+    }
+
+    async fn fetch_targets(&self) -> Result<Vec<CdpTarget>> {
+        let targets: Vec<CdpTarget> = self
+            .inner
+            .http
+            .get(format!("{}/json/list", self.endpoint()))
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await
+            .context("Invalid /json/list response")?;
+        Ok(targets.into_iter().filter(|t| t.kind == "page").collect())
+    }
+
+    /// Start a Chrome instance listening on the configured endpoint
+    async fn launch(&self) -> Result<()> {
+        let mut child_slot = self.inner.child.lock().await;
+        if let Some(child) = child_slot.as_mut()
+            && child.try_wait()?.is_none()
+        {
+            // Already running, just not ready yet
+            return self.wait_until_ready().await;
+        }
+
+        let port = url::Url::parse(&self.inner.config.endpoint)
+            .ok()
+            .and_then(|u| u.port())
+            .unwrap_or(9222);
+        let user_data_dir = self
+            .inner
+            .config
+            .user_data_dir
+            .clone()
+            .unwrap_or_else(|| std::env::temp_dir().join("meepo-cdp-profile"));
+
+        let args = launch_args(port, &user_data_dir, self.inner.config.headless);
+        let candidates = match &self.inner.config.chrome_path {
+            Some(path) => vec![path.clone()],
+            None => default_chrome_paths(),
+        };
+
+        let mut last_err = None;
+        for bin in &candidates {
+            match Command::new(bin)
+                .args(&args)
+                .stdout(std::process::Stdio::null())
+                .stderr(std::process::Stdio::null())
+                .kill_on_drop(true)
+                .spawn()
+            {
+                Ok(child) => {
+                    info!(
+                        "Launched {} for CDP on port {} (headless: {})",
+                        bin, port, self.inner.config.headless
+                    );
+                    *child_slot = Some(child);
+                    return self.wait_until_ready().await;
+                }
+                Err(e) => last_err = Some(e),
+            }
+        }
+        Err(anyhow!(
+            "Failed to launch Chrome (tried {}): {}",
+            candidates.join(", "),
+            last_err.map(|e| e.to_string()).unwrap_or_default()
+        ))
+    }
+
+    async fn wait_until_ready(&self) -> Result<()> {
+        let deadline = tokio::time::Instant::now() + self.timeout();
+        while tokio::time::Instant::now() < deadline {
+            if self.fetch_targets().await.is_ok() {
+                return Ok(());
+            }
+            tokio::time::sleep(Duration::from_millis(200)).await;
+        }
+        Err(anyhow!(
+            "Chrome did not become ready on {}",
+            self.endpoint()
+        ))
+    }
+
+    /// Resolve a tab by id, or the most recently focused page
+    async fn target(&self, tab_id: Option<&str>) -> Result<CdpTarget> {
+        let targets = self.targets().await?;
+        match tab_id {
+            Some(id) => targets
+                .into_iter()
+                .find(|t| t.id == id)
+                .ok_or_else(|| anyhow!("Tab not found: {}", id)),
+            None => targets
+                .into_iter()
+                .next()
+                .ok_or_else(|| anyhow!("No open tabs")),
+        }
+    }
+
+    /// Send a single protocol command to a target and return its result
+    async fn call(&self, target: &CdpTarget, method: &str, params: Value) -> Result<Value> {
+        let ws_url = target
+            .ws_url
+            .as_deref()
+            .ok_or_else(|| anyhow!("Tab {} is already being debugged elsewhere", target.id))?;
+        let id = self.inner.next_id.fetch_add(1, Ordering::Relaxed);
+
+        let exchange = async {
+            let (mut ws, _) = tokio_tungstenite::connect_async(ws_url)
+                .await
+                .context("Failed to connect to tab debugger")?;
+            let request = serde_json::json!({"id": id, "method": method, "params": params});
+            ws.send(Message::Text(request.to_string().into())).await?;
+
+            while let Some(msg) = ws.next().await {
+                let text = match msg? {
+                    Message::Text(t) => t.to_string(),
+                    Message::Close(_) => break,
+                    _ => continue,
+                };
+                let reply: Value = serde_json::from_str(&text)?;
+                if reply.get("id").and_then(|v| v.as_u64()) != Some(id) {
+                    continue; // protocol event, not our response
+                }
+                let _ = ws.close(None).await;
+                if let Some(err) = reply.get("error") {
+                    let msg = err.get("message").and_then(|m| m.as_str()).unwrap_or("");
+                    return Err(anyhow!("CDP {} failed: {}", method, msg));
+                }
+                return Ok(reply.get("result").cloned().unwrap_or(Value::Null));
+            }
+            Err(anyhow!("CDP connection closed before {} returned", method))
+        };
+
+        tokio::time::timeout(self.timeout(), exchange)
+            .await
+            .map_err(|_| anyhow!("CDP {} timed out", method))?
+    }
+
+    /// Evaluate an expression in the page and return its JSON value
+    async fn evaluate(&self, target: &CdpTarget, expression: &str) -> Result<Value> {
+        let result = self
+            .call(
+                target,
+                "Runtime.evaluate",
+                serde_json::json!({
+                    "expression": expression,
+                    "returnByValue": true,
+                    "awaitPromise": true,
+                }),
+            )
+            .await?;
+        if let Some(details) = result.get("exceptionDetails") {
+            let msg = details
+                .pointer("/exception/description")
+                .or_else(|| details.get("text"))
+                .and_then(|v| v.as_str())
+                .unwrap_or("unknown error");
+            return Err(anyhow!("JavaScript error: {}", msg));
+        }
+        Ok(result
+            .pointer("/result/value")
+            .cloned()
+            .unwrap_or(Value::Null))
+    }
+
+    /// Poll an expression until it is truthy or the timeout elapses
+    async fn poll_until(&self, target: &CdpTarget, expression: &str, timeout_ms: u64) -> bool {
+        let deadline =
+            tokio::time::Instant::now() + Duration::from_millis(timeout_ms.min(MAX_WAIT_MS));
+        loop {
+            if let Ok(v) = self.evaluate(target, expression).await
+                && v.as_bool() == Some(true)
+            {
+                return true;
+            }
+            if tokio::time::Instant::now() >= deadline {
+                return false;
+            }
+            tokio::time::sleep(Duration::from_millis(POLL_INTERVAL_MS)).await;
+        }
+    }
+
+    /// Wait for the document to finish loading after a navigation
+    async fn wait_for_load(&self, target: &CdpTarget) {
+        // Give the navigation a moment to replace the old document
+        tokio::time::sleep(Duration::from_millis(POLL_INTERVAL_MS)).await;
+        if !self
+            .poll_until(
+                target,
+                "document.readyState === 'complete'",
+                self.inner.config.timeout_ms,
+            )
+            .await
+        {
+            warn!("Page {} did not finish loading within timeout", target.id);
+        }
+    }
+}
+
+/// Command-line flags for a CDP-controlled Chrome instance
+fn launch_args(port: u16, user_data_dir: &std::path::Path, headless: bool) -> Vec<String> {
+    let mut args = vec![
+        format!("--remote-debugging-port={}", port),
+        format!("--user-data-dir={}", user_data_dir.display()),
+        "--no-first-run".to_string(),
+        "--no-default-browser-check".to_string(),
+    ];
+    if headless {
+        args.push("--headless=new".to_string());
+    }
+    args.push("about:blank".to_string());
+    args
+}
+
+/// Likely Chrome/Chromium binaries for the current platform
+fn default_chrome_paths() -> Vec<String> {
+    #[cfg(target_os = "macos")]
+    {
+        vec![
+            "/Applications/Google Chrome.app/Contents/MacOS/Google Chrome".to_string(),
+            "/Applications/Chromium.app/Contents/MacOS/Chromium".to_string(),
+        ]
+    }
+    #[cfg(target_os = "windows")]
+    {
+        vec![
+            r"C:\Program Files\Google\Chrome\Application\chrome.exe".to_string(),
+            r"C:\Program Files (x86)\Google\Chrome\Application\chrome.exe".to_string(),
+            "chrome.exe".to_string(),
+        ]
+    }
+    #[cfg(not(any(target_os = "macos", target_os = "windows")))]
+    {
+        vec![
+            "google-chrome".to_string(),
+            "google-chrome-stable".to_string(),
+            "chromium".to_string(),
+            "chromium-browser".to_string(),
+        ]
+    }
+}
+
+/// `/json/new` URL opening `url`, encoded so its own `&`, `#` and spaces
+/// reach Chrome intact
+fn new_tab_url(endpoint: &str, url: &str) -> String {
+    format!("{}/json/new?{}", endpoint, crate::uri::encode(url, true))
+}
+
+/// JSON-quote a string for safe embedding in a JavaScript expression
+fn js_string(s: &str) -> String {
+    Value::String(s.to_string()).to_string()
+}
+
+fn click_script(selector: &str) -> String {
+    format!(
+        "(() => {{ const el = document.querySelector({}); if (!el) return false; \
+         el.scrollIntoView({{block: 'center'}}); el.click(); return true; }})()",
+        js_string(selector)
+    )
+}
+
+/// Uses the native value setter so framework-controlled inputs (React, Vue)
+/// see the change, then fires input/change events.
+fn fill_script(selector: &str, value: &str) -> String {
+    format!(
+        "(() => {{ const el = document.querySelector({sel}); if (!el) return false; el.focus(); \
+         const proto = Object.getPrototypeOf(el); \
+         const setter = Object.getOwnPropertyDescriptor(proto, 'value')?.set; \
+         if (setter) setter.call(el, {val}); else el.value = {val}; \
+         el.dispatchEvent(new Event('input', {{bubbles: true}})); \
+         el.dispatchEvent(new Event('change', {{bubbles: true}})); return true; }})()",
+        sel = js_string(selector),
+        val = js_string(value)
+    )
+}
+
+fn scroll_script(direction: &str, amount: u32) -> Result<String> {
+    Ok(match direction.to_lowercase().as_str() {
+        "up" => format!("window.scrollBy(0, -{})", amount),
+        "down" => format!("window.scrollBy(0, {})", amount),
+        "left" => format!("window.scrollBy(-{}, 0)", amount),
+        "right" => format!("window.scrollBy({}, 0)", amount),
+        "top" => "window.scrollTo(0, 0)".to_string(),
+        "bottom" => "window.scrollTo(0, document.body.scrollHeight)".to_string(),
+        _ => {
+            return Err(anyhow!(
+                "Invalid direction: {}. Use: up, down, left, right, top, bottom",
+                direction
+            ));
+        }
+    })
+}
+
+#[async_trait]
+impl BrowserProvider for CdpBrowser {
+    async fn list_tabs(&self) -> Result<Vec<BrowserTab>> {
+        Ok(self
+            .targets()
+            .await?
+            .into_iter()
+            .enumerate()
+            .map(|(i, t)| BrowserTab {
+                id: t.id,
+                title: t.title,
+                url: t.url,
+                is_active: i == 0,
+                window_index: 0,
+            })
+            .collect())
+    }
+
+    async fn open_tab(&self, url: &str) -> Result<BrowserTab> {
+        // Make sure a browser is available before creating the target
+        self.targets().await?;
+        let target: CdpTarget = self
+            .inner
+            .http
+            .put(new_tab_url(self.endpoint(), url))
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await
+            .context("Invalid /json/new response")?;
+        self.wait_for_load(&target).await;
+        let title = self
+            .evaluate(&target, "document.title")
+            .await
+            .ok()
+            .and_then(|v| v.as_str().map(String::from))
+            .unwrap_or(target.title.clone());
+        Ok(BrowserTab {
+            id: target.id,
+            title,
+            url: url.to_string(),
+            is_active: true,
+            window_index: 0,
+        })
+    }
+
+    async fn close_tab(&self, tab_id: &str) -> Result<()> {
+        self.inner
+            .http
+            .get(format!("{}/json/close/{}", self.endpoint(), tab_id))
+            .send()
+            .await?
+            .error_for_status()
+            .with_context(|| format!("Failed to close tab {}", tab_id))?;
+        Ok(())
+    }
+
+    async fn switch_tab(&self, tab_id: &str) -> Result<()> {
+        self.inner
+            .http
+            .get(format!("{}/json/activate/{}", self.endpoint(), tab_id))
+            .send()
+            .await?
+            .error_for_status()
+            .with_context(|| format!("Failed to activate tab {}", tab_id))?;
+        Ok(())
+    }
+
+    async fn get_page_content(&self, tab_id: Option<&str>) -> Result<PageContent> {
+        let target = self.target(tab_id).await?;
+        let v = self
+            .evaluate(
+                &target,
+                "({text: document.body ? document.body.innerText : '', \
+                  html: document.documentElement.outerHTML, \
+                  url: location.href, title: document.title})",
+            )
+            .await?;
+        let field = |k: &str| v.get(k).and_then(|x| x.as_str()).unwrap_or("").to_string();
+        Ok(PageContent {
+            text: field("text"),
+            html: field("html"),
+            url: field("url"),
+            title: field("title"),
+        })
+    }
+
+    async fn execute_javascript(&self, tab_id: Option<&str>, script: &str) -> Result<String> {
+        let target = self.target(tab_id).await?;
+        Ok(match self.evaluate(&target, script).await? {
+            Value::String(s) => s,
+            Value::Null => String::new(),
+            other => other.to_string(),
+        })
+    }
+
+    async fn click_element(&self, tab_id: Option<&str>, selector: &str) -> Result<()> {
+        let target = self.target(tab_id).await?;
+        match self.evaluate(&target, &click_script(selector)).await? {
+            Value::Bool(true) => Ok(()),
+            _ => Err(anyhow!("Element not found: {}", selector)),
+        }
+    }
+
+    async fn fill_form(&self, tab_id: Option<&str>, selector: &str, value: &str) -> Result<()> {
+        let target = self.target(tab_id).await?;
+        match self
+            .evaluate(&target, &fill_script(selector, value))
+            .await?
+        {
+            Value::Bool(true) => Ok(()),
+            _ => Err(anyhow!("Element not found: {}", selector)),
+        }
+    }
+
+    async fn screenshot_page(&self, tab_id: Option<&str>, path: Option<&str>) -> Result<String> {
+        let target = self.target(tab_id).await?;
+        let output = path.map(PathBuf::from).unwrap_or_else(|| {
+            std::env::temp_dir().join(format!(
+                "meepo-browser-screenshot-{}.png",
+                chrono::Utc::now().timestamp()
+            ))
+        });
+        let ext = output
+            .extension()
+            .and_then(|e| e.to_str())
+            .unwrap_or("png")
+            .to_lowercase();
+
+        let result = if ext == "pdf" {
+            self.call(&target, "Page.printToPDF", serde_json::json!({}))
+                .await?
+        } else {
+            let format = if ext == "jpg" { "jpeg" } else { "png" };
+            self.call(
+                &target,
+                "Page.captureScreenshot",
+                serde_json::json!({"format": format}),
+            )
+            .await?
+        };
+        let data = result
+            .get("data")
+            .and_then(|d| d.as_str())
+            .ok_or_else(|| anyhow!("Screenshot returned no data"))?;
+        let bytes = base64::engine::general_purpose::STANDARD
+            .decode(data)
+            .context("Invalid screenshot data")?;
+        tokio::fs::write(&output, bytes)
+            .await
+            .with_context(|| format!("Failed to write {}", output.display()))?;
+        Ok(output.display().to_string())
+    }
+
+    async fn go_back(&self, tab_id: Option<&str>) -> Result<()> {
+        let target = self.target(tab_id).await?;
+        self.evaluate(&target, "history.back()").await?;
+        self.wait_for_load(&target).await;
+        Ok(())
+    }
+
+    async fn go_forward(&self, tab_id: Option<&str>) -> Result<()> {
+        let target = self.target(tab_id).await?;
+        self.evaluate(&target, "history.forward()").await?;
+        self.wait_for_load(&target).await;
+        Ok(())
+    }
+
+    async fn reload(&self, tab_id: Option<&str>) -> Result<()> {
+        let target = self.target(tab_id).await?;
+        self.call(&target, "Page.reload", serde_json::json!({}))
+            .await?;
+        self.wait_for_load(&target).await;
+        Ok(())
+    }
+
+    async fn get_cookies(&self, tab_id: Option<&str>) -> Result<Vec<BrowserCookie>> {
+        let target = self.target(tab_id).await?;
+        let result = self
+            .call(
+                &target,
+                "Network.getCookies",
+                serde_json::json!({"urls": [target.url]}),
+            )
+            .await?;
+        let cookies = result
+            .get("cookies")
+            .and_then(|c| c.as_array())
+            .cloned()
+            .unwrap_or_default();
+        Ok(cookies
+            .iter()
+            .map(|c| {
+                let field = |k: &str| c.get(k).and_then(|x| x.as_str()).unwrap_or("").to_string();
+                BrowserCookie {
+                    name: field("name"),
+                    value: field("value"),
+                    domain: field("domain"),
+                    path: field("path"),
+                }
+            })
+            .collect())
+    }
+
+    async fn get_page_url(&self, tab_id: Option<&str>) -> Result<String> {
+        let target = self.target(tab_id).await?;
+        Ok(self
+            .evaluate(&target, "location.href")
+            .await?
+            .as_str()
+            .map(String::from)
+            .unwrap_or(target.url))
+    }
+
+    async fn scroll(&self, tab_id: Option<&str>, direction: &str, amount: u32) -> Result<()> {
+        let script = scroll_script(direction, amount)?;
+        let target = self.target(tab_id).await?;
+        self.evaluate(&target, &script).await?;
+        Ok(())
+    }
+
+    async fn wait_for_element(
+        &self,
+        tab_id: Option<&str>,
+        selector: &str,
+        timeout_ms: u64,
+    ) -> Result<bool> {
+        let target = self.target(tab_id).await?;
+        let expr = format!("document.querySelector({}) !== null", js_string(selector));
+        Ok(self.poll_until(&target, &expr, timeout_ms).await)
+    }
+
+    async fn screenshot_tab(&self, tab_id: Option<&str>, path: Option<&str>) -> Result<String> {
+        if let Some(id) = tab_id {
+            self.switch_tab(id).await?;
+        }
+        self.screenshot_page(tab_id, path).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cdp_config_default() {
+        let config = CdpConfig::default();
+        assert_eq!(config.endpoint, "http://127.0.0.1:9222");
+        assert!(config.launch);
+        assert!(config.headless);
+        assert!(config.chrome_path.is_none());
+    }
+
+    #[test]
+    fn test_launch_args() {
+        let args = launch_args(9333, std::path::Path::new("/tmp/profile"), true);
+        assert!(args.contains(&"--remote-debugging-port=9333".to_string()));
+        assert!(args.contains(&"--headless=new".to_string()));
+        let args = launch_args(9222, std::path::Path::new("/tmp/profile"), false);
+        assert!(!args.iter().any(|a| a.starts_with("--headless")));
+    }
+
+    #[test]
+    fn test_new_tab_url_encoded() {
+        assert_eq!(
+            new_tab_url("http://127.0.0.1:9222", "https://x.com/a b?q=1&r=2#top"),
+            "http://127.0.0.1:9222/json/new?https%3A%2F%2Fx.com%2Fa%20b%3Fq%3D1%26r%3D2%23top"
+        );
+    }
+
+    #[test]
+    fn test_js_string_escapes_selector() {
+        let script = click_script("a[href=\"x\"]');alert(1)//");
+        assert!(script.contains(r#"document.querySelector("a[href=\"x\"]');alert(1)//")"#));
+        let script = fill_script("#q", "line\nbreak");
+        assert!(script.contains(r#""line\nbreak""#));
+    }
+
+    #[test]
+    fn test_scroll_script() {
+        assert_eq!(
+            scroll_script("down", 300).unwrap(),
+            "window.scrollBy(0, 300)"
+        );
+        assert_eq!(scroll_script("TOP", 0).unwrap(), "window.scrollTo(0, 0)");
+        assert!(scroll_script("sideways", 10).is_err());
+    }
+
+    #[test]
+    fn test_parse_targets() {
+        let targets: Vec<CdpTarget> = serde_json::from_value(serde_json::json!([
+            {"id": "A", "type": "page", "title": "Home", "url": "https://a.test",
+             "webSocketDebuggerUrl": "ws://127.0.0.1:9222/devtools/page/A"},
+            {"id": "B", "type": "service_worker", "url": "https://a.test/sw.js"}
+        ]))
+        .unwrap();
+        assert_eq!(targets.len(), 2);
+        assert_eq!(targets[0].kind, "page");
+        assert!(targets[1].ws_url.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_unreachable_endpoint_without_launch() {
+        let browser = CdpBrowser::new(CdpConfig {
+            endpoint: "http://127.0.0.1:1".to_string(),
+            launch: false,
+            ..Default::default()
+        });
+        let err = browser.list_tabs().await.unwrap_err();
+        assert!(format!("{:#}", err).contains("not reachable"));
+    }
+}
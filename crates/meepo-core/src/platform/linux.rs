@@ -0,0 +1,410 @@
+//! Linux platform implementations using desktop command-line tools
+//!
+//! Notifications go over D-Bus to `org.freedesktop.Notifications` (through
+//! `gdbus`, falling back to `notify-send`). The clipboard is read with
+//! `wl-paste` on Wayland or `xclip` on X11, the calendar comes from `khal`,
+//! and screenshots are taken with whichever of `grim`, `spectacle`,
+//! `gnome-screenshot` or ImageMagick's `import` is installed.
+
+use std::collections::HashSet;
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use chrono::{DateTime, NaiveDate, NaiveDateTime, TimeZone, Utc};
+use tokio::process::Command;
+use tracing::{debug, warn};
+
+use super::{
+    CalendarEvent, CalendarProvider, ClipboardProvider, CrossPlatformClipboard,
+    NotificationProvider, ScreenCaptureProvider, validate_screenshot_path,
+};
+
+/// Whether `program` is an executable on `PATH`
+fn on_path(program: &str) -> bool {
+    std::env::var_os("PATH")
+        .is_some_and(|paths| std::env::split_paths(&paths).any(|dir| dir.join(program).is_file()))
+}
+
+/// Run a program with a timeout, returning its stdout
+async fn run(program: &str, args: &[&str], timeout: Duration) -> Result<String> {
+    let output = tokio::time::timeout(timeout, Command::new(program).args(args).output())
+        .await
+        .map_err(|_| anyhow::anyhow!("{} timed out after {:?}", program, timeout))?
+        .with_context(|| format!("Failed to run {}", program))?;
+    if output.status.success() {
+        Ok(String::from_utf8_lossy(&output.stdout).to_string())
+    } else {
+        let error = String::from_utf8_lossy(&output.stderr).trim().to_string();
+        warn!("{} failed: {}", program, error);
+        Err(anyhow::anyhow!("{} failed: {}", program, error))
+    }
+}
+
+/// Quote a string as a GVariant text-format literal, for `gdbus call`
+fn gvariant_string(input: &str) -> String {
+    let mut quoted = String::with_capacity(input.len() + 2);
+    quoted.push('\'');
+    for c in input.chars() {
+        match c {
+            '\\' => quoted.push_str("\\\\"),
+            '\'' => quoted.push_str("\\'"),
+            '\n' => quoted.push_str("\\n"),
+            '\t' => quoted.push_str("\\t"),
+            c if c < ' ' => {}
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('\'');
+    quoted
+}
+
+// ── Notifications ──────────────────────────────────────────────────────────
+
+pub struct LinuxNotificationProvider;
+
+#[async_trait]
+impl NotificationProvider for LinuxNotificationProvider {
+    async fn send_notification(
+        &self,
+        title: &str,
+        message: &str,
+        sound: Option<&str>,
+    ) -> Result<String> {
+        if title.len() > 200 {
+            return Err(anyhow::anyhow!("Title too long"));
+        }
+        if message.len() > 2000 {
+            return Err(anyhow::anyhow!("Message too long"));
+        }
+        debug!("Sending notification: {}", title);
+        let timeout = Duration::from_secs(10);
+        if on_path("gdbus") {
+            // Notify(app_name, replaces_id, app_icon, summary, body, actions, hints, expire_timeout)
+            let hints = match sound.filter(|s| !s.eq_ignore_ascii_case("default")) {
+                Some(sound) => format!("{{'sound-name': <{}>}}", gvariant_string(sound)),
+                None => "{}".to_string(),
+            };
+            let summary = gvariant_string(title);
+            let body = gvariant_string(message);
+            run(
+                "gdbus",
+                &[
+                    "call",
+                    "--session",
+                    "--dest",
+                    "org.freedesktop.Notifications",
+                    "--object-path",
+                    "/org/freedesktop/Notifications",
+                    "--method",
+                    "org.freedesktop.Notifications.Notify",
+                    "'Meepo'",
+                    "0",
+                    "''",
+                    &summary,
+                    &body,
+                    "[]",
+                    &hints,
+                    "5000",
+                ],
+                timeout,
+            )
+            .await?;
+        } else {
+            run(
+                "notify-send",
+                &["--app-name=Meepo", "--", title, message],
+                timeout,
+            )
+            .await
+            .context("Install gdbus (glib) or notify-send (libnotify) for notifications")?;
+        }
+        Ok(format!("Notification sent: {}", title))
+    }
+}
+
+// ── Clipboard ──────────────────────────────────────────────────────────────
+
+/// Reads the clipboard with `wl-paste` (Wayland) or `xclip` (X11), and
+/// through `arboard` when neither is installed
+pub struct LinuxClipboard;
+
+#[async_trait]
+impl ClipboardProvider for LinuxClipboard {
+    async fn get_clipboard(&self) -> Result<String> {
+        let timeout = Duration::from_secs(5);
+        if std::env::var_os("WAYLAND_DISPLAY").is_some() && on_path("wl-paste") {
+            return run("wl-paste", &["--no-newline"], timeout).await;
+        }
+        if std::env::var_os("DISPLAY").is_some() && on_path("xclip") {
+            return run("xclip", &["-selection", "clipboard", "-o"], timeout).await;
+        }
+        CrossPlatformClipboard.get_clipboard().await
+    }
+}
+
+// ── Calendar ───────────────────────────────────────────────────────────────
+
+/// Calendar backed by `khal`, which reads local vdir calendars (synced
+/// with CalDAV servers, Evolution or Thunderbird through vdirsyncer)
+pub struct LinuxCalendarProvider;
+
+/// Date-time layouts tried when reading khal's `{start-long}` output, which
+/// follows the user's `longdatetimeformat`
+const KHAL_DATETIME_FORMATS: &[&str] = &[
+    "%Y-%m-%d %H:%M",
+    "%Y-%m-%d %H:%M:%S",
+    "%Y-%m-%dT%H:%M:%S",
+    "%d.%m.%Y %H:%M",
+    "%d/%m/%Y %H:%M",
+    "%m/%d/%Y %H:%M",
+    "%m/%d/%Y %I:%M %p",
+    "%a %d %b %Y %H:%M:%S",
+    "%a %b %e %H:%M:%S %Y",
+];
+
+/// Date layouts for all-day events, from `longdateformat`
+const KHAL_DATE_FORMATS: &[&str] = &["%Y-%m-%d", "%d.%m.%Y", "%d/%m/%Y", "%m/%d/%Y"];
+
+/// A khal time in the user's timezone
+fn parse_khal_time(text: &str, tz: &chrono_tz::Tz) -> Option<DateTime<Utc>> {
+    let text = text.trim();
+    let naive = KHAL_DATETIME_FORMATS
+        .iter()
+        .find_map(|f| NaiveDateTime::parse_from_str(text, f).ok())
+        .or_else(|| {
+            KHAL_DATE_FORMATS
+                .iter()
+                .find_map(|f| NaiveDate::parse_from_str(text, f).ok())
+                .and_then(|d| d.and_hms_opt(0, 0, 0))
+        })?;
+    tz.from_local_datetime(&naive)
+        .earliest()
+        .map(|t| t.with_timezone(&Utc))
+}
+
+/// Parse `khal list --format "{uid}\t{start-long}\t{end-long}\t{title}\t{location}"`.
+/// Events spanning several days are listed once per day, so they're kept
+/// once; lines whose times can't be read are skipped.
+pub fn parse_khal_listing(output: &str, tz: &chrono_tz::Tz) -> Vec<CalendarEvent> {
+    let mut seen = HashSet::new();
+    let mut events: Vec<CalendarEvent> = output
+        .lines()
+        .filter_map(|line| {
+            let fields: Vec<&str> = line.splitn(5, '\t').collect();
+            if fields.len() < 4 {
+                return None;
+            }
+            let start = parse_khal_time(fields[1], tz)?;
+            let end = parse_khal_time(fields[2], tz).unwrap_or(start);
+            if !seen.insert((fields[0].to_string(), start)) {
+                return None;
+            }
+            Some(CalendarEvent {
+                id: fields[0].trim().to_string(),
+                title: fields[3].trim().to_string(),
+                start,
+                end,
+                location: fields
+                    .get(4)
+                    .map(|l| l.trim())
+                    .filter(|l| !l.is_empty())
+                    .map(String::from),
+                attendees: Vec::new(),
+            })
+        })
+        .collect();
+    events.sort_by_key(|e| e.start);
+    events
+}
+
+/// khal's `new` arguments for a start time: ISO 8601 is rewritten as
+/// `YYYY-MM-DD HH:MM` in the user's timezone, anything else ("tomorrow
+/// 14:00") is passed through for khal to interpret
+fn khal_start_args(start_time: &str, tz: &chrono_tz::Tz) -> Vec<String> {
+    let start_time = start_time.trim();
+    let local = DateTime::parse_from_rfc3339(start_time)
+        .map(|t| t.with_timezone(tz).naive_local())
+        .ok()
+        .or_else(|| {
+            ["%Y-%m-%dT%H:%M:%S", "%Y-%m-%dT%H:%M", "%Y-%m-%d %H:%M"]
+                .iter()
+                .find_map(|f| NaiveDateTime::parse_from_str(start_time, f).ok())
+        });
+    match local {
+        Some(t) => vec![
+            t.format("%Y-%m-%d").to_string(),
+            t.format("%H:%M").to_string(),
+        ],
+        None => start_time.split_whitespace().map(String::from).collect(),
+    }
+}
+
+#[async_trait]
+impl CalendarProvider for LinuxCalendarProvider {
+    async fn read_events(&self, days_ahead: u64) -> Result<String> {
+        debug!(
+            "Reading calendar events for next {} days from khal",
+            days_ahead
+        );
+        let delta = format!("{}d", days_ahead.max(1));
+        let output = run("khal", &["list", "now", &delta], Duration::from_secs(30)).await?;
+        if output.trim().is_empty() {
+            Ok(format!("No events in the next {} days", days_ahead))
+        } else {
+            Ok(output)
+        }
+    }
+
+    async fn create_event(
+        &self,
+        summary: &str,
+        start_time: &str,
+        duration_minutes: u64,
+        location: Option<&str>,
+    ) -> Result<String> {
+        debug!("Creating calendar event: {}", summary);
+        let tz = crate::timezone::user_timezone();
+        let mut args: Vec<String> = vec!["new".to_string()];
+        if let Some(location) = location.filter(|l| !l.trim().is_empty()) {
+            args.push("--location".to_string());
+            args.push(location.to_string());
+        }
+        args.push("--".to_string());
+        args.extend(khal_start_args(start_time, &tz));
+        args.push(format!("{}m", duration_minutes.max(1)));
+        args.push(summary.to_string());
+        let args: Vec<&str> = args.iter().map(String::as_str).collect();
+        run("khal", &args, Duration::from_secs(30)).await?;
+        Ok(format!("Event created: {}", summary))
+    }
+
+    async fn upcoming_events(&self, hours_ahead: u64) -> Result<Vec<CalendarEvent>> {
+        debug!(
+            "Reading structured calendar events for next {} hours from khal",
+            hours_ahead
+        );
+        let delta = format!("{}h", hours_ahead.max(1));
+        let output = run(
+            "khal",
+            &[
+                "list",
+                "--day-format",
+                "",
+                "--format",
+                "{uid}\t{start-long}\t{end-long}\t{title}\t{location}",
+                "now",
+                &delta,
+            ],
+            Duration::from_secs(30),
+        )
+        .await?;
+        Ok(parse_khal_listing(
+            &output,
+            &crate::timezone::user_timezone(),
+        ))
+    }
+}
+
+// ── Screen Capture ─────────────────────────────────────────────────────────
+
+pub struct LinuxScreenCaptureProvider;
+
+/// The installed screenshot tool and its arguments for writing to `path`
+fn screenshot_command(path: &str) -> Option<(&'static str, Vec<&str>)> {
+    let wayland = std::env::var_os("WAYLAND_DISPLAY").is_some();
+    if wayland && on_path("grim") {
+        return Some(("grim", vec![path]));
+    }
+    if on_path("spectacle") {
+        return Some((
+            "spectacle",
+            vec![
+                "--background",
+                "--nonotify",
+                "--fullscreen",
+                "--output",
+                path,
+            ],
+        ));
+    }
+    if on_path("gnome-screenshot") {
+        return Some(("gnome-screenshot", vec!["--file", path]));
+    }
+    if std::env::var_os("DISPLAY").is_some() && on_path("import") {
+        return Some(("import", vec!["-window", "root", path]));
+    }
+    None
+}
+
+#[async_trait]
+impl ScreenCaptureProvider for LinuxScreenCaptureProvider {
+    async fn capture_screen(&self, path: Option<&str>) -> Result<String> {
+        let timestamp = chrono::Utc::now().format("%Y%m%d_%H%M%S");
+        let output_path = path
+            .map(|p| p.to_string())
+            .unwrap_or_else(|| format!("/tmp/meepo-screenshot-{}.png", timestamp));
+        validate_screenshot_path(&output_path)?;
+        let (program, args) = screenshot_command(&output_path).ok_or_else(|| {
+            anyhow::anyhow!(
+                "No screenshot tool found; install grim (Wayland), spectacle, gnome-screenshot or ImageMagick"
+            )
+        })?;
+        debug!("Capturing screen to {} with {}", output_path, program);
+        run(program, &args, Duration::from_secs(15)).await?;
+        Ok(format!("Screenshot saved to {}", output_path))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_gvariant_string_escapes() {
+        assert_eq!(gvariant_string("plain"), "'plain'");
+        assert_eq!(
+            gvariant_string("it's a \\ test\nnext\u{7}"),
+            r"'it\'s a \\ test\nnext'"
+        );
+    }
+
+    #[test]
+    fn test_parse_khal_listing() {
+        let tz: chrono_tz::Tz = "Europe/Berlin".parse().unwrap();
+        let output = "\n\
+            abc\t2026-03-02 14:00\t2026-03-02 15:30\tDesign sync\tRoom 4\n\
+            trip\t2026-03-03\t2026-03-05\tConference\t\n\
+            trip\t2026-03-03\t2026-03-05\tConference\t\n\
+            bad\tsometime\tlater\tUnreadable\t\n\
+            xyz\t02.03.2026 09:00\t02.03.2026 09:30\tStandup\n";
+        let events = parse_khal_listing(output, &tz);
+        assert_eq!(events.len(), 3);
+        assert_eq!(events[0].title, "Standup");
+        assert_eq!(events[0].start.to_rfc3339(), "2026-03-02T08:00:00+00:00");
+        assert!(events[0].location.is_none());
+        assert_eq!(events[1].location.as_deref(), Some("Room 4"));
+        assert_eq!(
+            events[1].end - events[1].start,
+            chrono::Duration::minutes(90)
+        );
+        assert_eq!(events[2].id, "trip");
+    }
+
+    #[test]
+    fn test_khal_start_args() {
+        let tz: chrono_tz::Tz = "America/New_York".parse().unwrap();
+        assert_eq!(
+            khal_start_args("2026-03-02T19:00:00Z", &tz),
+            vec!["2026-03-02", "14:00"]
+        );
+        assert_eq!(
+            khal_start_args("2026-03-02T14:00", &tz),
+            vec!["2026-03-02", "14:00"]
+        );
+        assert_eq!(
+            khal_start_args("tomorrow 10:00", &tz),
+            vec!["tomorrow", "10:00"]
+        );
+    }
+}
@@ -6,11 +6,12 @@ use tokio::process::Command;
 use tracing::{debug, info, warn};
 
 use super::{
-    BrowserCookie, BrowserProvider, BrowserTab, CalendarProvider, ContactsProvider, EmailProvider,
-    FinderProvider, KeychainProvider, MediaProvider, MessagesProvider, MusicProvider,
-    NotesProvider, NotificationProvider, PageContent, PhotosProvider, ProductivityProvider,
-    RemindersProvider, ScreenCaptureProvider, ShortcutsProvider, SpotlightProvider,
-    SystemControlProvider, TerminalProvider, UiAutomation, WindowManagerProvider,
+    ActiveAppProvider, BrowserCookie, BrowserProvider, BrowserTab, CalendarEvent, CalendarProvider,
+    ContactsProvider, EmailProvider, FinderProvider, FocusProvider, KeychainProvider,
+    MediaProvider, MessagesProvider, MusicProvider, NotesProvider, NotificationProvider,
+    PageContent, PhotosProvider, ProductivityProvider, ReminderItem, RemindersProvider,
+    ScreenCaptureProvider, ShortcutsProvider, SpotlightProvider, SystemControlProvider,
+    TerminalProvider, UiAutomation, WindowManagerProvider, validate_screenshot_path,
 };
 
 /// Sanitize a string for safe use in AppleScript
@@ -24,68 +25,6 @@ fn sanitize_applescript_string(input: &str) -> String {
         .collect()
 }
 
-/// Validate screenshot output path to prevent writing to sensitive locations
-fn validate_screenshot_path(path: &str) -> Result<()> {
-    if path.contains("..") {
-        return Err(anyhow::anyhow!(
-            "Screenshot path contains '..' which is not allowed"
-        ));
-    }
-
-    let path_buf = std::path::PathBuf::from(path);
-
-    // Resolve parent directory to check location
-    let check_path = if let Some(parent) = path_buf.parent() {
-        if parent.as_os_str().is_empty() || !parent.exists() {
-            path_buf.clone()
-        } else {
-            parent
-                .canonicalize()
-                .unwrap_or_else(|_| parent.to_path_buf())
-                .join(path_buf.file_name().unwrap_or_default())
-        }
-    } else {
-        path_buf.clone()
-    };
-
-    let home_dir =
-        dirs::home_dir().ok_or_else(|| anyhow::anyhow!("Could not determine home directory"))?;
-    let temp_dir = std::env::temp_dir()
-        .canonicalize()
-        .unwrap_or_else(|_| std::env::temp_dir());
-
-    let is_in_home = check_path.starts_with(&home_dir);
-    let is_in_temp = check_path.starts_with(&temp_dir);
-
-    if !is_in_home && !is_in_temp {
-        return Err(anyhow::anyhow!(
-            "Screenshot path '{}' must be within home or temp directory",
-            path
-        ));
-    }
-
-    // Block system directories even if under home
-    let system_dirs = [
-        "/etc",
-        "/bin",
-        "/sbin",
-        "/usr/bin",
-        "/usr/sbin",
-        "/System",
-        "/Library",
-    ];
-    for sys_dir in &system_dirs {
-        if check_path.starts_with(sys_dir) {
-            return Err(anyhow::anyhow!(
-                "Screenshot path cannot target system directory '{}'",
-                sys_dir
-            ));
-        }
-    }
-
-    Ok(())
-}
-
 /// Check if an application is currently running
 async fn is_app_running(app_name: &str) -> bool {
     let safe_name = sanitize_applescript_string(app_name);
@@ -351,6 +290,165 @@ end tell
         };
         run_applescript(&script).await
     }
+
+    async fn flag_email(&self, sender: &str, subject: &str, flagged: bool) -> Result<String> {
+        let safe_sender = sanitize_applescript_string(sender);
+        let safe_subject = sanitize_applescript_string(subject);
+        debug!("Setting flag={} on email: {}", flagged, subject);
+        ensure_mail_app_running().await?;
+
+        let script = format!(
+            r#"
+tell application "Mail"
+    try
+        set targetMsgs to (every message of inbox whose subject is "{}" and sender contains "{}")
+        if (count of targetMsgs) = 0 then return "Error: message not found"
+        set flagged status of (item 1 of targetMsgs) to {}
+        return "OK"
+    on error errMsg
+        return "Error: " & errMsg
+    end try
+end tell
+"#,
+            safe_subject, safe_sender, flagged
+        );
+        mail_result(run_applescript(&script).await?)
+    }
+
+    async fn move_email(&self, sender: &str, subject: &str, mailbox: &str) -> Result<String> {
+        let safe_sender = sanitize_applescript_string(sender);
+        let safe_subject = sanitize_applescript_string(subject);
+        let safe_mailbox = sanitize_applescript_string(mailbox);
+        debug!("Moving email '{}' to {}", subject, mailbox);
+        ensure_mail_app_running().await?;
+
+        let script = format!(
+            r#"
+tell application "Mail"
+    try
+        set targetMsgs to (every message of inbox whose subject is "{}" and sender contains "{}")
+        if (count of targetMsgs) = 0 then return "Error: message not found"
+        set theMsg to item 1 of targetMsgs
+        set theAccount to account of mailbox of theMsg
+        if not (exists mailbox "{}" of theAccount) then
+            make new mailbox at theAccount with properties {{name:"{}"}}
+        end if
+        move theMsg to mailbox "{}" of theAccount
+        return "OK"
+    on error errMsg
+        return "Error: " & errMsg
+    end try
+end tell
+"#,
+            safe_subject, safe_sender, safe_mailbox, safe_mailbox, safe_mailbox
+        );
+        mail_result(run_applescript(&script).await?)
+    }
+
+    async fn save_draft(
+        &self,
+        to: &str,
+        subject: &str,
+        body: &str,
+        in_reply_to: Option<&str>,
+    ) -> Result<String> {
+        let safe_to = sanitize_applescript_string(to);
+        let safe_subject = sanitize_applescript_string(subject);
+        // Keep line breaks: join sanitized lines with AppleScript returns
+        let body_expr = body
+            .lines()
+            .map(|line| format!("\"{}\"", sanitize_applescript_string(line)))
+            .collect::<Vec<_>>()
+            .join(" & return & ");
+        let body_expr = if body_expr.is_empty() {
+            "\"\"".to_string()
+        } else {
+            body_expr
+        };
+        ensure_mail_app_running().await?;
+
+        let new_draft = format!(
+            r#"set draftMsg to make new outgoing message with properties {{subject:"{}", content:{}, visible:false}}
+            tell draftMsg
+                make new to recipient at end of to recipients with properties {{address:"{}"}}
+            end tell
+            save draftMsg"#,
+            safe_subject, body_expr, safe_to
+        );
+        let script = if let Some(reply_subject) = in_reply_to {
+            debug!("Saving draft reply to: {}", reply_subject);
+            format!(
+                r#"
+tell application "Mail"
+    try
+        set targetMsgs to (every message of inbox whose subject contains "{}")
+        if (count of targetMsgs) > 0 then
+            set draftMsg to reply (item 1 of targetMsgs) without opening window
+            set content of draftMsg to {}
+            save draftMsg
+        else
+            {}
+        end if
+        return "OK"
+    on error errMsg
+        return "Error: " & errMsg
+    end try
+end tell
+"#,
+                sanitize_applescript_string(reply_subject),
+                body_expr,
+                new_draft
+            )
+        } else {
+            debug!("Saving new draft to: {}", to);
+            format!(
+                r#"
+tell application "Mail"
+    try
+        {}
+        return "OK"
+    on error errMsg
+        return "Error: " & errMsg
+    end try
+end tell
+"#,
+                new_draft
+            )
+        };
+        mail_result(run_applescript(&script).await?)
+    }
+
+    async fn unread_count(&self) -> Result<u64> {
+        // Cheap check only: don't launch Mail.app just to count
+        if !is_app_running("Mail").await {
+            return Err(anyhow::anyhow!("Mail.app isn't running"));
+        }
+        let output = mail_result(
+            run_applescript(
+                r#"
+tell application "Mail"
+    try
+        return unread count of inbox
+    on error errMsg
+        return "Error: " & errMsg
+    end try
+end tell
+"#,
+            )
+            .await?,
+        )?;
+        output
+            .parse()
+            .with_context(|| format!("Unexpected unread count: {}", output))
+    }
+}
+
+/// Turn Mail.app's "Error: ..." script output into an error
+fn mail_result(output: String) -> Result<String> {
+    match output.trim().strip_prefix("Error: ") {
+        Some(error) => Err(anyhow::anyhow!("Mail.app: {}", error)),
+        None => Ok(output.trim().to_string()),
+    }
 }
 
 pub struct MacOsCalendarProvider;
@@ -394,10 +492,12 @@ end tell
         summary: &str,
         start_time: &str,
         duration_minutes: u64,
+        location: Option<&str>,
     ) -> Result<String> {
         debug!("Creating calendar event: {}", summary);
         let safe_summary = sanitize_applescript_string(summary);
         let safe_start_time = sanitize_applescript_string(start_time);
+        let safe_location = sanitize_applescript_string(location.unwrap_or_default());
         let script = format!(
             r#"
 tell application "Calendar"
@@ -406,7 +506,7 @@ tell application "Calendar"
         set endDate to startDate + ({} * minutes)
         set targetCal to first calendar
         tell targetCal
-            make new event with properties {{summary:"{}", start date:startDate, end date:endDate}}
+            make new event with properties {{summary:"{}", start date:startDate, end date:endDate, location:"{}"}}
         end tell
         return "Event created successfully in calendar: " & (name of targetCal)
     on error errMsg
@@ -414,10 +514,58 @@ tell application "Calendar"
     end try
 end tell
 "#,
-            safe_start_time, duration_minutes, safe_summary
+            safe_start_time, duration_minutes, safe_summary, safe_location
         );
         run_applescript(&script).await
     }
+
+    async fn upcoming_events(&self, hours_ahead: u64) -> Result<Vec<CalendarEvent>> {
+        debug!(
+            "Reading structured calendar events for next {} hours",
+            hours_ahead
+        );
+        let script = format!(
+            r#"
+tell application "Calendar"
+    try
+        set nowDate to current date
+        set endDate to nowDate + ({} * hours)
+        set output to ""
+        repeat with cal in calendars
+            set theEvents to (every event of cal whose start date is greater than or equal to nowDate and start date is less than or equal to endDate)
+            repeat with evt in theEvents
+                set startOffset to ((start date of evt) - nowDate)
+                set endOffset to ((end date of evt) - nowDate)
+                set evtLocation to ""
+                try
+                    set evtLocation to (location of evt) as string
+                end try
+                set output to output & "EVENT" & tab & (uid of evt) & tab & startOffset & tab & endOffset & tab & (summary of evt) & tab & evtLocation & linefeed
+                repeat with att in attendees of evt
+                    set attName to ""
+                    set attEmail to ""
+                    try
+                        set attName to (display name of att) as string
+                    end try
+                    try
+                        set attEmail to (email of att) as string
+                    end try
+                    set output to output & "ATTENDEE" & tab & attName & tab & attEmail & linefeed
+                end repeat
+            end repeat
+        end repeat
+        return output
+    on error errMsg
+        return "Error: " & errMsg
+    end try
+end tell
+"#,
+            hours_ahead
+        );
+        let now = chrono::Utc::now();
+        let output = run_applescript_with_retry(&script, 60, 2).await?;
+        super::parse_event_listing(&output, now)
+    }
 }
 
 /// Allowlist of valid UI element types for macOS accessibility
@@ -619,6 +767,118 @@ end tell
         );
         run_applescript(&script).await
     }
+
+    async fn list_reminder_items(&self, list_name: &str) -> Result<Vec<ReminderItem>> {
+        let safe_list = sanitize_applescript_string(list_name);
+        debug!("Reading reminders for sync from list {}", list_name);
+        let script = format!(
+            r#"
+tell application "Reminders"
+    try
+        if not (exists list "{list}") then
+            make new list with properties {{name:"{list}"}}
+        end if
+        set nowDate to current date
+        set output to ""
+        repeat with r in reminders of list "{list}"
+            set dueOffset to ""
+            try
+                set d to due date of r
+                if d is not missing value then set dueOffset to (d - nowDate)
+            end try
+            set modOffset to ((modification date of r) - nowDate)
+            set output to output & "REMINDER" & tab & (id of r) & tab & (completed of r) & tab & dueOffset & tab & modOffset & tab & (name of r) & linefeed
+        end repeat
+        return output
+    on error errMsg
+        return "Error: " & errMsg
+    end try
+end tell
+"#,
+            list = safe_list
+        );
+        let now = chrono::Utc::now();
+        let output = run_applescript_with_retry(&script, 60, 2).await?;
+        super::parse_reminder_listing(&output, now)
+    }
+
+    async fn add_reminder_item(&self, list_name: &str, item: &ReminderItem) -> Result<String> {
+        let safe_list = sanitize_applescript_string(list_name);
+        let mut props = format!(r#"name:"{}""#, sanitize_applescript_string(&item.title));
+        if let Some(notes) = &item.notes {
+            props.push_str(&format!(
+                r#", body:"{}""#,
+                sanitize_applescript_string(notes)
+            ));
+        }
+        debug!("Adding reminder for sync: {}", item.title);
+        let script = format!(
+            r#"
+tell application "Reminders"
+    try
+        if not (exists list "{list}") then
+            make new list with properties {{name:"{list}"}}
+        end if
+        set r to make new reminder at end of list "{list}" with properties {{{props}}}
+        {due}
+        set completed of r to {completed}
+        return id of r
+    on error errMsg
+        return "Error: " & errMsg
+    end try
+end tell
+"#,
+            list = safe_list,
+            props = props,
+            due = reminder_due_clause(item.due),
+            completed = item.completed,
+        );
+        let output = run_applescript(&script).await?;
+        if let Some(err) = output.trim().strip_prefix("Error:") {
+            anyhow::bail!("Failed to add reminder: {}", err.trim());
+        }
+        Ok(output.trim().to_string())
+    }
+
+    async fn update_reminder_item(&self, item: &ReminderItem) -> Result<()> {
+        debug!("Updating reminder for sync: {}", item.title);
+        let script = format!(
+            r#"
+tell application "Reminders"
+    try
+        set r to reminder id "{id}"
+        set name of r to "{title}"
+        {due}
+        set completed of r to {completed}
+        return "ok"
+    on error errMsg
+        return "Error: " & errMsg
+    end try
+end tell
+"#,
+            id = sanitize_applescript_string(&item.id),
+            title = sanitize_applescript_string(&item.title),
+            due = reminder_due_clause(item.due),
+            completed = item.completed,
+        );
+        let output = run_applescript(&script).await?;
+        if let Some(err) = output.trim().strip_prefix("Error:") {
+            anyhow::bail!("Failed to update reminder: {}", err.trim());
+        }
+        Ok(())
+    }
+}
+
+/// AppleScript setting the due date of reminder `r`, as an offset from now
+/// so it doesn't depend on the locale's date format
+fn reminder_due_clause(due: Option<chrono::DateTime<chrono::Utc>>) -> String {
+    match due {
+        Some(due) => format!(
+            "set due date of r to (current date) + {}",
+            (due - chrono::Utc::now()).num_seconds()
+        ),
+        None => "set due date of r to missing value".to_string(),
+    }
 }
 
 pub struct MacOsNotesProvider;
@@ -2063,6 +2323,91 @@ impl ShortcutsProvider for MacOsShortcutsProvider {
     }
 }
 
+// ── Focus ──────────────────────────────────────────────────────────────────
+
+pub struct MacOsFocusProvider;
+
+impl MacOsFocusProvider {
+    /// Read a file from the Focus database (needs Full Disk Access)
+    async fn read_db_file(name: &str) -> Result<String> {
+        let path = dirs::home_dir()
+            .ok_or_else(|| anyhow::anyhow!("Could not determine home directory"))?
+            .join("Library/DoNotDisturb/DB")
+            .join(name);
+        tokio::fs::read_to_string(&path).await.with_context(|| {
+            format!(
+                "Failed to read {} (grant Meepo Full Disk Access to read Focus state)",
+                path.display()
+            )
+        })
+    }
+
+    async fn modes() -> Result<Vec<(String, String)>> {
+        Ok(super::parse_focus_modes(
+            &Self::read_db_file("ModeConfigurations.json").await?,
+        ))
+    }
+}
+
+#[async_trait]
+impl FocusProvider for MacOsFocusProvider {
+    async fn current_focus(&self) -> Result<Option<String>> {
+        let modes = Self::modes().await.unwrap_or_default();
+        let assertions = Self::read_db_file("Assertions.json").await?;
+        Ok(super::parse_active_focus(&assertions, &modes))
+    }
+
+    async fn list_focus_modes(&self) -> Result<Vec<String>> {
+        Ok(Self::modes()
+            .await?
+            .into_iter()
+            .map(|(_, name)| name)
+            .collect())
+    }
+
+    async fn set_focus(&self, mode: Option<&str>) -> Result<String> {
+        let suffix = mode.unwrap_or("Off");
+        if suffix.len() > 100 {
+            return Err(anyhow::anyhow!(
+                "Focus mode name too long (max 100 characters)"
+            ));
+        }
+        let shortcut = format!("{} {}", super::FOCUS_SHORTCUT_PREFIX, suffix);
+        debug!("Setting Focus via shortcut: {}", shortcut);
+        MacOsShortcutsProvider
+            .run_shortcut(&shortcut, None)
+            .await
+            .with_context(|| {
+                format!(
+                    "Create a shortcut named '{}' with a Set Focus action to let Meepo do this",
+                    shortcut
+                )
+            })?;
+        Ok(match mode {
+            Some(mode) => format!("Focus mode '{}' turned on", mode),
+            None => "Focus turned off".to_string(),
+        })
+    }
+}
+
+// ── Active App ─────────────────────────────────────────────────────────────
+
+pub struct MacOsActiveAppProvider;
+
+#[async_trait]
+impl ActiveAppProvider for MacOsActiveAppProvider {
+    async fn frontmost_app(&self) -> Result<Option<String>> {
+        let script = r#"
+tell application "System Events"
+    set frontApp to first application process whose frontmost is true
+    return name of frontApp
+end tell"#;
+        let name = run_applescript(script).await?;
+        let name = name.trim();
+        Ok((!name.is_empty()).then(|| name.to_string()))
+    }
+}
+
 // ── Keychain ───────────────────────────────────────────────────────────────
 
 pub struct MacOsKeychainProvider;
@@ -2257,6 +2602,39 @@ end tell"#,
             ))
         }
     }
+
+    async fn unread_count(&self) -> Result<u64> {
+        let db_path = dirs::home_dir()
+            .ok_or_else(|| anyhow::anyhow!("Cannot determine home directory"))?
+            .join("Library/Messages/chat.db");
+        if !db_path.exists() {
+            return Err(anyhow::anyhow!("Messages database not found"));
+        }
+        // Unread incoming messages from the last week; older ones were
+        // most likely read on another device
+        let output = tokio::time::timeout(
+            std::time::Duration::from_secs(5),
+            Command::new("sqlite3")
+                .arg(db_path.to_string_lossy().to_string())
+                .arg(
+                    "SELECT COUNT(*) FROM message \
+                     WHERE is_read = 0 AND is_from_me = 0 AND item_type = 0 \
+                     AND date > (strftime('%s', 'now') - 978307200 - 604800) * 1000000000",
+                )
+                .output(),
+        )
+        .await
+        .map_err(|_| anyhow::anyhow!("Unread count timed out"))?
+        .context("Failed to read messages database")?;
+        if !output.status.success() {
+            return Err(anyhow::anyhow!(
+                "Could not read messages database (access denied)"
+            ));
+        }
+        let text = String::from_utf8_lossy(&output.stdout).trim().to_string();
+        text.parse()
+            .with_context(|| format!("Unexpected unread count: {}", text))
+    }
 }
 
 // ── Photos ─────────────────────────────────────────────────────────────────
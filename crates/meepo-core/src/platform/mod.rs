@@ -3,7 +3,12 @@
 //! Provides trait definitions and platform-specific implementations.
 //! On macOS: AppleScript-based implementations.
 //! On Windows: PowerShell/COM-based implementations.
+//! On Linux: D-Bus and desktop command-line tools (`linux`).
+//! On any platform: Chrome DevTools Protocol browser backend (`cdp`).
 
+pub mod cdp;
+#[cfg(target_os = "linux")]
+pub mod linux;
 #[cfg(target_os = "macos")]
 pub mod macos;
 #[cfg(target_os = "windows")]
@@ -11,6 +16,7 @@ pub mod windows;
 
 use anyhow::Result;
 use async_trait::async_trait;
+use chrono::{DateTime, Utc};
 
 /// Email provider for reading and sending emails
 #[async_trait]
@@ -24,6 +30,24 @@ pub trait EmailProvider: Send + Sync {
         cc: Option<&str>,
         in_reply_to: Option<&str>,
     ) -> Result<String>;
+    /// Flag or unflag the latest inbox message from `sender` with `subject`
+    async fn flag_email(&self, sender: &str, subject: &str, flagged: bool) -> Result<String>;
+    /// Move the latest inbox message from `sender` with `subject` to
+    /// `mailbox`, creating the mailbox if needed
+    async fn move_email(&self, sender: &str, subject: &str, mailbox: &str) -> Result<String>;
+    /// Save an email (a reply when `in_reply_to` names the original subject)
+    /// to Drafts without sending it
+    async fn save_draft(
+        &self,
+        to: &str,
+        subject: &str,
+        body: &str,
+        in_reply_to: Option<&str>,
+    ) -> Result<String>;
+    /// Unread messages in the inbox
+    async fn unread_count(&self) -> Result<u64> {
+        anyhow::bail!("Unread counts aren't supported by this email provider")
+    }
 }
 
 /// Calendar provider for reading and creating events
@@ -35,7 +59,96 @@ pub trait CalendarProvider: Send + Sync {
         summary: &str,
         start_time: &str,
         duration_minutes: u64,
+        location: Option<&str>,
     ) -> Result<String>;
+    /// Events starting within the next `hours_ahead` hours, with attendees
+    async fn upcoming_events(&self, hours_ahead: u64) -> Result<Vec<CalendarEvent>>;
+}
+
+/// A calendar event with structured times and attendees
+#[derive(Debug, Clone, PartialEq)]
+pub struct CalendarEvent {
+    /// Provider identifier, stable across reads
+    pub id: String,
+    pub title: String,
+    pub start: DateTime<Utc>,
+    pub end: DateTime<Utc>,
+    pub location: Option<String>,
+    pub attendees: Vec<Attendee>,
+}
+
+/// Someone invited to an event
+#[derive(Debug, Clone, PartialEq)]
+pub struct Attendee {
+    pub name: Option<String>,
+    pub email: Option<String>,
+}
+
+impl Attendee {
+    /// Name if known, otherwise email
+    pub fn label(&self) -> &str {
+        self.name
+            .as_deref()
+            .or(self.email.as_deref())
+            .unwrap_or("unknown")
+    }
+}
+
+/// Parse the tab-separated event listing the platform scripts print:
+///
+/// ```text
+/// EVENT\t<id>\t<start offset secs>\t<end offset secs>\t<title>\t<location>
+/// ATTENDEE\t<name>\t<email>
+/// ```
+///
+/// Offsets are seconds from `now`, which keeps the scripts free of
+/// locale-dependent date formatting.
+pub fn parse_event_listing(output: &str, now: DateTime<Utc>) -> Result<Vec<CalendarEvent>> {
+    if let Some(err) = output.trim().strip_prefix("Error:") {
+        anyhow::bail!("Calendar error: {}", err.trim());
+    }
+    let non_empty = |s: Option<&str>| {
+        s.map(str::trim)
+            .filter(|s| !s.is_empty() && *s != "missing value")
+            .map(String::from)
+    };
+
+    let mut events: Vec<CalendarEvent> = Vec::new();
+    for line in output.lines() {
+        let fields: Vec<&str> = line.trim_end_matches('\r').split('\t').collect();
+        match fields.first().copied() {
+            Some("EVENT") if fields.len() >= 5 => {
+                let (Ok(start), Ok(end)) = (
+                    fields[2].trim().parse::<f64>(),
+                    fields[3].trim().parse::<f64>(),
+                ) else {
+                    continue;
+                };
+                events.push(CalendarEvent {
+                    id: fields[1].trim().to_string(),
+                    title: fields[4].trim().to_string(),
+                    start: now + chrono::Duration::seconds(start as i64),
+                    end: now + chrono::Duration::seconds(end as i64),
+                    location: non_empty(fields.get(5).copied()),
+                    attendees: Vec::new(),
+                });
+            }
+            Some("ATTENDEE") => {
+                let attendee = Attendee {
+                    name: non_empty(fields.get(1).copied()),
+                    email: non_empty(fields.get(2).copied()),
+                };
+                if let Some(event) = events.last_mut()
+                    && (attendee.name.is_some() || attendee.email.is_some())
+                {
+                    event.attendees.push(attendee);
+                }
+            }
+            _ => {}
+        }
+    }
+    events.sort_by_key(|e| e.start);
+    Ok(events)
 }
 
 /// Clipboard provider for reading clipboard contents
@@ -69,6 +182,64 @@ pub trait RemindersProvider: Send + Sync {
         due_date: Option<&str>,
         notes: Option<&str>,
     ) -> Result<String>;
+    /// Every reminder in a list, including completed ones, creating the list
+    /// if it doesn't exist
+    async fn list_reminder_items(&self, list_name: &str) -> Result<Vec<ReminderItem>>;
+    /// Add a reminder to a list, returning its id
+    async fn add_reminder_item(&self, list_name: &str, item: &ReminderItem) -> Result<String>;
+    /// Update a reminder's title, due date and completion
+    async fn update_reminder_item(&self, item: &ReminderItem) -> Result<()>;
+}
+
+/// A reminder with the fields needed to sync it
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReminderItem {
+    /// Provider identifier (empty for reminders not created yet)
+    pub id: String,
+    pub title: String,
+    pub completed: bool,
+    pub due: Option<DateTime<Utc>>,
+    pub notes: Option<String>,
+    /// Last modification, used to resolve sync conflicts
+    pub modified: Option<DateTime<Utc>>,
+}
+
+/// Parse the tab-separated reminder listing the platform scripts print:
+///
+/// ```text
+/// REMINDER\t<id>\t<completed>\t<due offset secs>\t<modified offset secs>\t<title>
+/// ```
+///
+/// Offsets are seconds from `now` (the due offset is empty without a due
+/// date), as in [`parse_event_listing`].
+pub fn parse_reminder_listing(output: &str, now: DateTime<Utc>) -> Result<Vec<ReminderItem>> {
+    if let Some(err) = output.trim().strip_prefix("Error:") {
+        anyhow::bail!("Reminders error: {}", err.trim());
+    }
+    let offset = |s: &str| {
+        s.trim()
+            .parse::<f64>()
+            .ok()
+            .map(|secs| now + chrono::Duration::seconds(secs as i64))
+    };
+
+    Ok(output
+        .lines()
+        .filter_map(|line| {
+            let fields: Vec<&str> = line.trim_end_matches('\r').splitn(6, '\t').collect();
+            if fields.len() < 6 || fields[0] != "REMINDER" {
+                return None;
+            }
+            Some(ReminderItem {
+                id: fields[1].trim().to_string(),
+                title: fields[5].trim().to_string(),
+                completed: fields[2].trim() == "true",
+                due: offset(fields[3]),
+                notes: None,
+                modified: offset(fields[4]),
+            })
+        })
+        .collect())
 }
 
 /// Notes provider for reading and creating notes
@@ -181,6 +352,82 @@ pub trait ShortcutsProvider: Send + Sync {
     async fn run_shortcut(&self, name: &str, input: Option<&str>) -> Result<String>;
 }
 
+/// Focus mode provider (Do Not Disturb and its variants)
+#[async_trait]
+pub trait FocusProvider: Send + Sync {
+    /// Name of the active Focus mode, if any
+    async fn current_focus(&self) -> Result<Option<String>>;
+    /// Names of the Focus modes set up on this machine
+    async fn list_focus_modes(&self) -> Result<Vec<String>>;
+    /// Turn a Focus mode on, or the active one off with `None`
+    async fn set_focus(&self, mode: Option<&str>) -> Result<String>;
+}
+
+/// Shortcuts named `"<prefix> <Mode>"` turn a Focus mode on and
+/// `"<prefix> Off"` turns it off, since macOS has no scriptable Focus API
+pub const FOCUS_SHORTCUT_PREFIX: &str = "Meepo Focus";
+
+/// Focus mode identifiers and names from `ModeConfigurations.json`
+/// (`~/Library/DoNotDisturb/DB`)
+pub fn parse_focus_modes(json: &str) -> Vec<(String, String)> {
+    let Ok(config) = serde_json::from_str::<serde_json::Value>(json) else {
+        return Vec::new();
+    };
+    let mut modes: Vec<(String, String)> = config
+        .get("data")
+        .and_then(|d| d.as_array())
+        .into_iter()
+        .flatten()
+        .filter_map(|entry| entry.get("modeConfigurations")?.as_object())
+        .flat_map(|configs| configs.values())
+        .filter_map(|config| {
+            let mode = config.get("mode")?;
+            Some((
+                mode.get("modeIdentifier")?.as_str()?.to_string(),
+                mode.get("name")?.as_str()?.to_string(),
+            ))
+        })
+        .collect();
+    modes.sort_by(|a, b| a.1.cmp(&b.1));
+    modes
+}
+
+/// Name of the active Focus mode from `Assertions.json`, looked up in
+/// `modes` (see [`parse_focus_modes`])
+pub fn parse_active_focus(json: &str, modes: &[(String, String)]) -> Option<String> {
+    let assertions = serde_json::from_str::<serde_json::Value>(json).ok()?;
+    let identifier = assertions
+        .get("data")?
+        .as_array()?
+        .iter()
+        .filter_map(|entry| entry.get("storeAssertionRecords")?.as_array())
+        .flatten()
+        .find_map(|record| {
+            record
+                .pointer("/assertionDetails/assertionDetailsModeIdentifier")?
+                .as_str()
+        })?;
+    let name = modes
+        .iter()
+        .find(|(id, _)| id == identifier)
+        .map(|(_, name)| name.clone())
+        .unwrap_or_else(|| {
+            if identifier.contains("donotdisturb") {
+                "Do Not Disturb".to_string()
+            } else {
+                identifier.to_string()
+            }
+        });
+    Some(name)
+}
+
+/// Frontmost application provider, for app-usage tracking
+#[async_trait]
+pub trait ActiveAppProvider: Send + Sync {
+    /// Name of the app in front, if any
+    async fn frontmost_app(&self) -> Result<Option<String>>;
+}
+
 /// Keychain provider for password management
 #[async_trait]
 pub trait KeychainProvider: Send + Sync {
@@ -194,6 +441,10 @@ pub trait MessagesProvider: Send + Sync {
     async fn read_messages(&self, contact: &str, limit: u64) -> Result<String>;
     async fn send_message(&self, contact: &str, message: &str) -> Result<String>;
     async fn start_facetime(&self, contact: &str, audio_only: bool) -> Result<String>;
+    /// Unread incoming messages across all conversations
+    async fn unread_count(&self) -> Result<u64> {
+        anyhow::bail!("Unread counts aren't supported by this messages provider")
+    }
 }
 
 /// Photos provider for Apple Photos
@@ -298,7 +549,11 @@ pub fn create_calendar_provider() -> Result<Box<dyn CalendarProvider>> {
     {
         Ok(Box::new(windows::WindowsCalendarProvider))
     }
-    #[cfg(not(any(target_os = "macos", target_os = "windows")))]
+    #[cfg(target_os = "linux")]
+    {
+        Ok(Box::new(linux::LinuxCalendarProvider))
+    }
+    #[cfg(not(any(target_os = "macos", target_os = "windows", target_os = "linux")))]
     {
         Err(anyhow::anyhow!(
             "Calendar provider not available on this platform"
@@ -308,7 +563,14 @@ pub fn create_calendar_provider() -> Result<Box<dyn CalendarProvider>> {
 
 /// Create cross-platform clipboard provider
 pub fn create_clipboard_provider() -> Box<dyn ClipboardProvider> {
-    Box::new(CrossPlatformClipboard)
+    #[cfg(target_os = "linux")]
+    {
+        Box::new(linux::LinuxClipboard)
+    }
+    #[cfg(not(target_os = "linux"))]
+    {
+        Box::new(CrossPlatformClipboard)
+    }
 }
 
 /// Create cross-platform app launcher
@@ -360,30 +622,38 @@ pub fn create_notes_provider() -> Result<Box<dyn NotesProvider>> {
     }
 }
 
-/// Create platform notification provider (macOS only)
+/// Create platform notification provider (macOS and Linux)
 pub fn create_notification_provider() -> Result<Box<dyn NotificationProvider>> {
     #[cfg(target_os = "macos")]
     {
         Ok(Box::new(macos::MacOsNotificationProvider))
     }
-    #[cfg(not(target_os = "macos"))]
+    #[cfg(target_os = "linux")]
+    {
+        Ok(Box::new(linux::LinuxNotificationProvider))
+    }
+    #[cfg(not(any(target_os = "macos", target_os = "linux")))]
     {
         Err(anyhow::anyhow!(
-            "Notification provider is only available on macOS"
+            "Notification provider is only available on macOS and Linux"
         ))
     }
 }
 
-/// Create platform screen capture provider (macOS only)
+/// Create platform screen capture provider (macOS and Linux)
 pub fn create_screen_capture_provider() -> Result<Box<dyn ScreenCaptureProvider>> {
     #[cfg(target_os = "macos")]
     {
         Ok(Box::new(macos::MacOsScreenCaptureProvider))
     }
-    #[cfg(not(target_os = "macos"))]
+    #[cfg(target_os = "linux")]
+    {
+        Ok(Box::new(linux::LinuxScreenCaptureProvider))
+    }
+    #[cfg(not(any(target_os = "macos", target_os = "linux")))]
     {
         Err(anyhow::anyhow!(
-            "Screen capture provider is only available on macOS"
+            "Screen capture provider is only available on macOS and Linux"
         ))
     }
 }
@@ -470,6 +740,36 @@ pub fn create_shortcuts_provider() -> Result<Box<dyn ShortcutsProvider>> {
     }
 }
 
+/// Create platform Focus mode provider (macOS only)
+pub fn create_focus_provider() -> Result<Box<dyn FocusProvider>> {
+    #[cfg(target_os = "macos")]
+    {
+        Ok(Box::new(macos::MacOsFocusProvider))
+    }
+    #[cfg(not(target_os = "macos"))]
+    {
+        Err(anyhow::anyhow!("Focus provider is only available on macOS"))
+    }
+}
+
+/// Create platform frontmost-app provider
+pub fn create_active_app_provider() -> Result<Box<dyn ActiveAppProvider>> {
+    #[cfg(target_os = "macos")]
+    {
+        Ok(Box::new(macos::MacOsActiveAppProvider))
+    }
+    #[cfg(target_os = "windows")]
+    {
+        Ok(Box::new(windows::WindowsActiveAppProvider))
+    }
+    #[cfg(not(any(target_os = "macos", target_os = "windows")))]
+    {
+        Err(anyhow::anyhow!(
+            "Active app provider not available on this platform"
+        ))
+    }
+}
+
 /// Create platform Keychain provider (macOS only)
 pub fn create_keychain_provider() -> Result<Box<dyn KeychainProvider>> {
     #[cfg(target_os = "macos")]
@@ -598,13 +898,77 @@ pub fn create_browser_provider_for(browser: &str) -> Result<Box<dyn BrowserProvi
                 ))
             }
         }
+        "cdp" => Ok(Box::new(cdp::CdpBrowser::new(cdp::CdpConfig::default()))),
         _ => Err(anyhow::anyhow!(
-            "Unsupported browser: {}. Supported: safari, chrome",
+            "Unsupported browser: {}. Supported: safari, chrome, cdp",
             browser
         )),
     }
 }
 
+/// Validate screenshot output path to prevent writing to sensitive locations
+#[cfg(any(target_os = "macos", target_os = "linux"))]
+pub(crate) fn validate_screenshot_path(path: &str) -> Result<()> {
+    if path.contains("..") {
+        return Err(anyhow::anyhow!(
+            "Screenshot path contains '..' which is not allowed"
+        ));
+    }
+
+    let path_buf = std::path::PathBuf::from(path);
+
+    // Resolve parent directory to check location
+    let check_path = if let Some(parent) = path_buf.parent() {
+        if parent.as_os_str().is_empty() || !parent.exists() {
+            path_buf.clone()
+        } else {
+            parent
+                .canonicalize()
+                .unwrap_or_else(|_| parent.to_path_buf())
+                .join(path_buf.file_name().unwrap_or_default())
+        }
+    } else {
+        path_buf.clone()
+    };
+
+    let home_dir =
+        dirs::home_dir().ok_or_else(|| anyhow::anyhow!("Could not determine home directory"))?;
+    let temp_dir = std::env::temp_dir()
+        .canonicalize()
+        .unwrap_or_else(|_| std::env::temp_dir());
+
+    let is_in_home = check_path.starts_with(&home_dir);
+    let is_in_temp = check_path.starts_with(&temp_dir);
+
+    if !is_in_home && !is_in_temp {
+        return Err(anyhow::anyhow!(
+            "Screenshot path '{}' must be within home or temp directory",
+            path
+        ));
+    }
+
+    // Block system directories even if under home
+    let system_dirs = [
+        "/etc",
+        "/bin",
+        "/sbin",
+        "/usr/bin",
+        "/usr/sbin",
+        "/System",
+        "/Library",
+    ];
+    for sys_dir in &system_dirs {
+        if check_path.starts_with(sys_dir) {
+            return Err(anyhow::anyhow!(
+                "Screenshot path cannot target system directory '{}'",
+                sys_dir
+            ));
+        }
+    }
+
+    Ok(())
+}
+
 /// Cross-platform clipboard using `arboard` crate
 pub struct CrossPlatformClipboard;
 
@@ -665,6 +1029,71 @@ impl AppLauncher for CrossPlatformAppLauncher {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_parse_event_listing() {
+        let now: DateTime<Utc> = "2026-03-02T09:00:00Z".parse().unwrap();
+        let output = "EVENT\tabc\t3600\t5400\tDesign sync\tRoom 4\n\
+                      ATTENDEE\tCarol Diaz\tcarol@example.com\n\
+                      ATTENDEE\tmissing value\tdan@example.com\n\
+                      EVENT\txyz\t600\t1200\tFocus time\tmissing value\n";
+        let events = parse_event_listing(output, now).unwrap();
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[0].title, "Focus time");
+        assert!(events[0].attendees.is_empty() && events[0].location.is_none());
+        let sync = &events[1];
+        assert_eq!(sync.start, now + chrono::Duration::hours(1));
+        assert_eq!(sync.location.as_deref(), Some("Room 4"));
+        assert_eq!(sync.attendees.len(), 2);
+        assert_eq!(sync.attendees[1].label(), "dan@example.com");
+        assert!(parse_event_listing("Error: not allowed", now).is_err());
+    }
+
+    #[test]
+    fn test_parse_focus_state() {
+        let modes = parse_focus_modes(
+            r#"{"data":[{"modeConfigurations":{
+                "com.apple.focus.work":{"mode":{"name":"Work","modeIdentifier":"com.apple.focus.work"}},
+                "com.apple.focus.deep":{"mode":{"name":"Deep Work","modeIdentifier":"com.apple.focus.deep"}}
+            }}]}"#,
+        );
+        assert_eq!(
+            modes.iter().map(|(_, n)| n.as_str()).collect::<Vec<_>>(),
+            vec!["Deep Work", "Work"]
+        );
+        let active = |id: &str| {
+            format!(
+                r#"{{"data":[{{"storeAssertionRecords":[{{"assertionDetails":{{"assertionDetailsModeIdentifier":"{}"}}}}]}}]}}"#,
+                id
+            )
+        };
+        assert_eq!(
+            parse_active_focus(&active("com.apple.focus.deep"), &modes).as_deref(),
+            Some("Deep Work")
+        );
+        assert_eq!(
+            parse_active_focus(&active("com.apple.donotdisturb.mode.default"), &modes).as_deref(),
+            Some("Do Not Disturb")
+        );
+        assert!(parse_active_focus(r#"{"data":[{}]}"#, &modes).is_none());
+        assert!(parse_focus_modes("not json").is_empty());
+    }
+
+    #[test]
+    fn test_parse_reminder_listing() {
+        let now: DateTime<Utc> = "2026-03-02T09:00:00Z".parse().unwrap();
+        let output = "REMINDER\tx-1\tfalse\t86400\t-60\tBuy milk\tand eggs\n\
+                      REMINDER\tx-2\ttrue\t\t-3600\tCall mom\n\
+                      garbage line\n";
+        let items = parse_reminder_listing(output, now).unwrap();
+        assert_eq!(items.len(), 2);
+        assert_eq!(items[0].title, "Buy milk\tand eggs");
+        assert_eq!(items[0].due, Some(now + chrono::Duration::days(1)));
+        assert!(!items[0].completed);
+        assert!(items[1].completed && items[1].due.is_none());
+        assert_eq!(items[1].modified, Some(now - chrono::Duration::hours(1)));
+        assert!(parse_reminder_listing("Error: no access", now).is_err());
+    }
+
     #[test]
     fn test_clipboard_provider_creates() {
         let _provider = create_clipboard_provider();
@@ -694,6 +1123,14 @@ mod tests {
         let _browser = create_browser_provider().unwrap();
     }
 
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_linux_providers_create() {
+        let _calendar = create_calendar_provider().unwrap();
+        let _notification = create_notification_provider().unwrap();
+        let _screen = create_screen_capture_provider().unwrap();
+    }
+
     #[cfg(target_os = "macos")]
     #[test]
     fn test_macos_system_providers_create() {
@@ -701,6 +1138,7 @@ mod tests {
         let _finder = create_finder_provider().unwrap();
         let _spotlight = create_spotlight_provider().unwrap();
         let _shortcuts = create_shortcuts_provider().unwrap();
+        let _focus = create_focus_provider().unwrap();
         let _keychain = create_keychain_provider().unwrap();
         let _messages = create_messages_provider().unwrap();
         let _photos = create_photos_provider().unwrap();
@@ -726,6 +1164,12 @@ mod tests {
         assert!(provider2.is_ok());
     }
 
+    #[test]
+    fn test_browser_provider_for_cdp() {
+        // CDP backend is available on every platform
+        assert!(create_browser_provider_for("cdp").is_ok());
+    }
+
     #[test]
     fn test_browser_provider_unsupported() {
         let result = create_browser_provider_for("firefox");
@@ -5,7 +5,7 @@ use async_trait::async_trait;
 use tokio::process::Command;
 use tracing::{debug, warn};
 
-use super::{CalendarProvider, EmailProvider, UiAutomation};
+use super::{ActiveAppProvider, CalendarEvent, CalendarProvider, EmailProvider, UiAutomation};
 
 /// Sanitize a string for safe use in PowerShell
 /// Escapes backticks, dollar signs, double/single quotes, and control characters
@@ -182,6 +182,108 @@ try {{
         };
         run_powershell(&script).await
     }
+
+    async fn flag_email(&self, sender: &str, subject: &str, flagged: bool) -> Result<String> {
+        debug!("Setting flag={} on Outlook email: {}", flagged, subject);
+        let find = find_inbox_message(sender, subject);
+        let status = if flagged { 2 } else { 0 }; // olFlagMarked / olNoFlag
+        let script = format!(
+            r#"
+try {{
+{find}
+    if ($msg -eq $null) {{ throw "message not found" }}
+    $msg.FlagStatus = {status}
+    $msg.Save()
+    Write-Output "OK"
+}} catch {{
+    Write-Error "Error flagging email: $_"
+}}
+"#
+        );
+        run_powershell(&script).await
+    }
+
+    async fn move_email(&self, sender: &str, subject: &str, mailbox: &str) -> Result<String> {
+        debug!("Moving Outlook email '{}' to {}", subject, mailbox);
+        let find = find_inbox_message(sender, subject);
+        let safe_mailbox = sanitize_powershell_string(mailbox);
+        let script = format!(
+            r#"
+try {{
+{find}
+    if ($msg -eq $null) {{ throw "message not found" }}
+    $parent = $inbox.Parent
+    $dest = $parent.Folders | Where-Object {{ $_.Name -eq "{safe_mailbox}" }} | Select-Object -First 1
+    if ($dest -eq $null) {{ $dest = $parent.Folders.Add("{safe_mailbox}") }}
+    $msg.Move($dest) | Out-Null
+    Write-Output "OK"
+}} catch {{
+    Write-Error "Error moving email: $_"
+}}
+"#
+        );
+        run_powershell(&script).await
+    }
+
+    async fn save_draft(
+        &self,
+        to: &str,
+        subject: &str,
+        body: &str,
+        in_reply_to: Option<&str>,
+    ) -> Result<String> {
+        let safe_to = sanitize_powershell_string(to);
+        let safe_subject = sanitize_powershell_string(subject);
+        let safe_body = sanitize_powershell_string(body);
+        let reply_lookup = match in_reply_to {
+            Some(reply_subject) => {
+                debug!("Saving Outlook draft reply to: {}", reply_subject);
+                let safe_reply = sanitize_powershell_string(reply_subject);
+                format!(
+                    r#"$inbox = $namespace.GetDefaultFolder(6)
+    $found = $inbox.Items.Find("[Subject] = '{safe_reply}'")"#
+                )
+            }
+            None => "$found = $null".to_string(),
+        };
+        let script = format!(
+            r#"
+try {{
+    $outlook = New-Object -ComObject Outlook.Application
+    $namespace = $outlook.GetNamespace("MAPI")
+    {reply_lookup}
+    if ($found -ne $null) {{
+        $draft = $found.Reply()
+        $draft.Body = "{safe_body}" + "`n`n" + $draft.Body
+    }} else {{
+        $draft = $outlook.CreateItem(0)
+        $draft.To = "{safe_to}"
+        $draft.Subject = "{safe_subject}"
+        $draft.Body = "{safe_body}"
+    }}
+    $draft.Save()
+    Write-Output "OK"
+}} catch {{
+    Write-Error "Error saving draft: $_"
+}}
+"#
+        );
+        run_powershell(&script).await
+    }
+}
+
+/// PowerShell that sets `$inbox` and `$msg` to the newest inbox message from
+/// `sender` with `subject` (`$msg` is `$null` when there is none)
+fn find_inbox_message(sender: &str, subject: &str) -> String {
+    let safe_sender = sanitize_powershell_string(sender);
+    let safe_subject = sanitize_powershell_string(subject);
+    format!(
+        r#"    $outlook = New-Object -ComObject Outlook.Application
+    $inbox = $outlook.GetNamespace("MAPI").GetDefaultFolder(6)
+    $items = $inbox.Items
+    $items.Sort("[ReceivedTime]", $true)
+    $msg = $items | Where-Object {{ $_.Subject -eq "{safe_subject}" -and ($_.SenderName -like "*{safe_sender}*" -or $_.SenderEmailAddress -like "*{safe_sender}*") }} | Select-Object -First 1"#
+    )
 }
 
 pub struct WindowsCalendarProvider;
@@ -227,10 +329,12 @@ try {{
         summary: &str,
         start_time: &str,
         duration_minutes: u64,
+        location: Option<&str>,
     ) -> Result<String> {
         debug!("Creating calendar event: {}", summary);
         let safe_summary = sanitize_powershell_string(summary);
         let safe_start = sanitize_powershell_string(start_time);
+        let safe_location = sanitize_powershell_string(location.unwrap_or_default());
         let script = format!(
             r#"
 try {{
@@ -239,6 +343,7 @@ try {{
     $appt.Subject = "{safe_summary}"
     $appt.Start = [DateTime]::Parse("{safe_start}")
     $appt.Duration = {duration_minutes}
+    $appt.Location = "{safe_location}"
     $appt.Save()
     Write-Output "Event created successfully"
 }} catch {{
@@ -248,6 +353,48 @@ try {{
         );
         run_powershell(&script).await
     }
+
+    async fn upcoming_events(&self, hours_ahead: u64) -> Result<Vec<CalendarEvent>> {
+        debug!(
+            "Reading structured calendar events for next {} hours from Outlook",
+            hours_ahead
+        );
+        let script = format!(
+            r#"
+try {{
+    $outlook = New-Object -ComObject Outlook.Application
+    $namespace = $outlook.GetNamespace("MAPI")
+    $calendar = $namespace.GetDefaultFolder(9)
+    $items = $calendar.Items
+    $items.IncludeRecurrences = $true
+    $items.Sort("[Start]")
+    $now = Get-Date
+    $start = $now.ToString("g")
+    $end = $now.AddHours({hours_ahead}).ToString("g")
+    $filtered = $items.Restrict("[Start] >= '$start' AND [Start] <= '$end'")
+    $output = ""
+    foreach ($evt in $filtered) {{
+        $startOffset = [int]($evt.Start - $now).TotalSeconds
+        $endOffset = [int]($evt.End - $now).TotalSeconds
+        $output += "EVENT`t$($evt.GlobalAppointmentID)-$($evt.Start.Ticks)`t$startOffset`t$endOffset`t$($evt.Subject)`t$($evt.Location)`n"
+        foreach ($recipient in $evt.Recipients) {{
+            $address = $recipient.Address
+            $exchangeUser = $null
+            try {{ $exchangeUser = $recipient.AddressEntry.GetExchangeUser() }} catch {{}}
+            if ($exchangeUser) {{ $address = $exchangeUser.PrimarySmtpAddress }}
+            $output += "ATTENDEE`t$($recipient.Name)`t$address`n"
+        }}
+    }}
+    Write-Output $output
+}} catch {{
+    Write-Output "Error: $_"
+}}
+"#
+        );
+        let now = chrono::Utc::now();
+        let output = run_powershell(&script).await?;
+        super::parse_event_listing(&output, now)
+    }
 }
 
 pub struct WindowsUiAutomation;
@@ -320,6 +467,33 @@ try {{
     }
 }
 
+pub struct WindowsActiveAppProvider;
+
+#[async_trait]
+impl ActiveAppProvider for WindowsActiveAppProvider {
+    async fn frontmost_app(&self) -> Result<Option<String>> {
+        let script = r#"
+Add-Type @"
+using System;
+using System.Runtime.InteropServices;
+public class MeepoForeground {
+    [DllImport("user32.dll")] public static extern IntPtr GetForegroundWindow();
+    [DllImport("user32.dll")] public static extern uint GetWindowThreadProcessId(IntPtr hWnd, out uint pid);
+}
+"@
+$procId = 0
+[void][MeepoForeground]::GetWindowThreadProcessId([MeepoForeground]::GetForegroundWindow(), [ref]$procId)
+$process = Get-Process -Id $procId -ErrorAction SilentlyContinue
+if ($process) {
+    if ($process.Description) { Write-Output $process.Description } else { Write-Output $process.ProcessName }
+}
+"#;
+        let name = run_powershell(script).await?;
+        let name = name.trim();
+        Ok((!name.is_empty()).then(|| name.to_string()))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -0,0 +1,434 @@
+//! Component plugins — WebAssembly components with granted WASI access
+//!
+//! A middle ground between the bare [`wasm`](super::wasm) sandbox and
+//! native plugins: the component implements the `tool` world in
+//! `wit/plugin.wit` and may import WASI, but only gets what its manifest
+//! declares:
+//!
+//! - `fs` — directories preopened at the same path in the guest. Relative
+//!   paths are inside the plugin directory, `~/` is expanded, and a `:ro`
+//!   suffix makes the directory read-only.
+//! - `network` — hosts (optionally `host:port`) it may open TCP
+//!   connections to. Name lookups are allowed; UDP, listening sockets and
+//!   every other address are refused.
+//!
+//! There's no environment, no arguments and no stdio, and `exec` can't be
+//! granted because WASI has no way to run programs. Each call gets a fresh
+//! instance with the same fuel, memory and time limits as wasm plugins.
+
+use std::net::{SocketAddr, ToSocketAddrs};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::{Context, Result, anyhow};
+use async_trait::async_trait;
+use serde_json::Value;
+use tracing::debug;
+use wasmtime::component::{Component, Linker, ResourceTable};
+use wasmtime::{Engine, Store, StoreLimits, StoreLimitsBuilder};
+use wasmtime_wasi::{
+    DirPerms, FilePerms, IoView, SocketAddrUse, WasiCtx, WasiCtxBuilder, WasiView,
+};
+
+use super::{PluginConfig, PluginManifest, PluginToolSpec};
+use crate::tools::ToolHandler;
+
+mod bindings {
+    wasmtime::component::bindgen!({ path: "wit/plugin.wit", world: "tool" });
+}
+
+/// A directory the component may use
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct DirGrant {
+    path: PathBuf,
+    read_only: bool,
+}
+
+impl DirGrant {
+    fn parse(entry: &str, plugin_dir: &Path) -> Self {
+        let (path, read_only) = match entry.strip_suffix(":ro") {
+            Some(path) => (path, true),
+            None => (entry, false),
+        };
+        let path = match path.strip_prefix("~/") {
+            Some(rest) => dirs::home_dir()
+                .map(|h| h.join(rest))
+                .unwrap_or_else(|| PathBuf::from(path)),
+            None => plugin_dir.join(path),
+        };
+        Self { path, read_only }
+    }
+}
+
+/// A host the component may connect to
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct HostGrant {
+    host: String,
+    port: Option<u16>,
+}
+
+impl HostGrant {
+    fn parse(entry: &str) -> Self {
+        if let Some((host, port)) = entry.rsplit_once(':')
+            && let Ok(port) = port.parse()
+            && (!host.contains(':') || host.starts_with('['))
+        {
+            return Self {
+                host: host
+                    .trim_start_matches('[')
+                    .trim_end_matches(']')
+                    .to_string(),
+                port: Some(port),
+            };
+        }
+        Self {
+            host: entry
+                .trim_start_matches('[')
+                .trim_end_matches(']')
+                .to_string(),
+            port: None,
+        }
+    }
+
+    /// Whether the host is this machine
+    fn is_local(&self) -> bool {
+        self.host.eq_ignore_ascii_case("localhost")
+            || self
+                .host
+                .parse::<std::net::IpAddr>()
+                .is_ok_and(|ip| ip.is_loopback())
+    }
+}
+
+/// Whether `addr` belongs to one of the granted hosts
+///
+/// Hosts are resolved at connect time, so a grant follows DNS changes.
+fn host_allowed(hosts: &[HostGrant], addr: SocketAddr) -> bool {
+    hosts.iter().any(|grant| {
+        grant.port.is_none_or(|p| p == addr.port())
+            && (grant.host.as_str(), addr.port())
+                .to_socket_addrs()
+                .is_ok_and(|mut resolved| resolved.any(|a| a.ip() == addr.ip()))
+    })
+}
+
+/// Store state for one call
+struct Host {
+    wasi: WasiCtx,
+    table: ResourceTable,
+    limits: StoreLimits,
+}
+
+impl IoView for Host {
+    fn table(&mut self) -> &mut ResourceTable {
+        &mut self.table
+    }
+}
+
+impl WasiView for Host {
+    fn ctx(&mut self) -> &mut WasiCtx {
+        &mut self.wasi
+    }
+}
+
+/// A compiled component, what it's granted and the limits each call runs
+/// under
+struct ComponentPlugin {
+    name: String,
+    engine: Engine,
+    component: Component,
+    linker: Linker<Host>,
+    dirs: Vec<DirGrant>,
+    hosts: Arc<Vec<HostGrant>>,
+    fuel: u64,
+    memory_bytes: usize,
+}
+
+impl ComponentPlugin {
+    fn wasi(&self) -> Result<WasiCtx> {
+        let mut builder = WasiCtxBuilder::new();
+        for dir in &self.dirs {
+            let guest = dir.path.to_string_lossy();
+            let (dir_perms, file_perms) = if dir.read_only {
+                (DirPerms::READ, FilePerms::READ)
+            } else {
+                (DirPerms::all(), FilePerms::all())
+            };
+            builder
+                .preopened_dir(&dir.path, guest, dir_perms, file_perms)
+                .with_context(|| format!("Failed to open {}", dir.path.display()))?;
+        }
+        builder.allow_udp(false);
+        if !self.hosts.is_empty() {
+            let hosts = self.hosts.clone();
+            builder.allow_ip_name_lookup(true);
+            builder.socket_addr_check(move |addr, usage| {
+                let allowed =
+                    matches!(usage, SocketAddrUse::TcpConnect) && host_allowed(&hosts, addr);
+                if !allowed {
+                    debug!("Component plugin refused {:?} to {}", usage, addr);
+                }
+                Box::pin(async move { allowed })
+            });
+        }
+        Ok(builder.build())
+    }
+
+    /// A fresh instance with its own store
+    fn instantiate(&self) -> Result<(Store<Host>, bindings::Tool)> {
+        let host = Host {
+            wasi: self.wasi()?,
+            table: ResourceTable::new(),
+            limits: StoreLimitsBuilder::new()
+                .memory_size(self.memory_bytes)
+                .build(),
+        };
+        let mut store = Store::new(&self.engine, host);
+        store.limiter(|host| &mut host.limits);
+        store.set_fuel(self.fuel)?;
+        let tool = bindings::Tool::instantiate(&mut store, &self.component, &self.linker)?;
+        Ok((store, tool))
+    }
+
+    fn failed(&self, e: anyhow::Error) -> anyhow::Error {
+        match e.downcast_ref::<wasmtime::Trap>() {
+            Some(wasmtime::Trap::OutOfFuel) => anyhow!("Plugin {} ran out of fuel", self.name),
+            _ => anyhow!("Plugin {} failed: {}", self.name, e),
+        }
+    }
+
+    fn tools(&self) -> Result<String> {
+        let (mut store, tool) = self.instantiate()?;
+        tool.call_list_tools(&mut store).map_err(|e| self.failed(e))
+    }
+
+    fn call(&self, tool: &str, input: &str) -> Result<String> {
+        let (mut store, instance) = self.instantiate()?;
+        instance
+            .call_call_tool(&mut store, tool, input)
+            .map_err(|e| self.failed(e))?
+            .map_err(|e| anyhow!("{}", e))
+    }
+}
+
+/// Compile a component plugin and wrap each of its tools
+pub fn load(
+    manifest: &PluginManifest,
+    path: &Path,
+    config: &PluginConfig,
+) -> Result<Vec<Arc<dyn ToolHandler>>> {
+    if manifest.capabilities.exec {
+        return Err(anyhow!(
+            "Component plugin '{}' asks for exec, which components can't be granted",
+            manifest.name
+        ));
+    }
+    let plugin_dir = path.parent().unwrap_or(Path::new("."));
+    let dirs: Vec<DirGrant> = manifest
+        .capabilities
+        .fs
+        .iter()
+        .map(|entry| DirGrant::parse(entry, plugin_dir))
+        .collect();
+    if let Some(missing) = dirs.iter().find(|d| !d.path.is_dir()) {
+        return Err(anyhow!(
+            "Component plugin '{}' needs {}, which isn't a directory",
+            manifest.name,
+            missing.path.display()
+        ));
+    }
+    let hosts = manifest
+        .capabilities
+        .network
+        .iter()
+        .map(|entry| HostGrant::parse(entry))
+        .collect();
+
+    let mut engine_config = wasmtime::Config::new();
+    engine_config.consume_fuel(true);
+    let engine = Engine::new(&engine_config)?;
+    let component = Component::from_file(&engine, path)
+        .with_context(|| format!("Failed to compile {}", path.display()))?;
+    let mut linker = Linker::new(&engine);
+    wasmtime_wasi::add_to_linker_sync(&mut linker)?;
+
+    let plugin = Arc::new(ComponentPlugin {
+        name: manifest.name.clone(),
+        engine,
+        component,
+        linker,
+        dirs,
+        hosts: Arc::new(hosts),
+        fuel: config.wasm_fuel,
+        memory_bytes: config.wasm_memory_bytes,
+    });
+    // WASI's sync bindings block on a Tokio runtime, which can't happen on
+    // an async worker thread; list the tools from a plain thread instead
+    let tools = std::thread::scope(|s| s.spawn(|| plugin.tools()).join())
+        .map_err(|_| anyhow!("Plugin {} panicked listing its tools", manifest.name))??;
+    let specs: Vec<PluginToolSpec> =
+        serde_json::from_str(&tools).context("Plugin returned an invalid tool list")?;
+    Ok(specs
+        .into_iter()
+        .map(|spec| {
+            Arc::new(ComponentTool {
+                plugin: plugin.clone(),
+                spec,
+                timeout: config.timeout,
+            }) as Arc<dyn ToolHandler>
+        })
+        .collect())
+}
+
+/// One tool from a component plugin
+struct ComponentTool {
+    plugin: Arc<ComponentPlugin>,
+    spec: PluginToolSpec,
+    timeout: Duration,
+}
+
+#[async_trait]
+impl ToolHandler for ComponentTool {
+    fn name(&self) -> &str {
+        &self.spec.name
+    }
+
+    fn description(&self) -> &str {
+        &self.spec.description
+    }
+
+    fn input_schema(&self) -> Value {
+        self.spec.input_schema.clone()
+    }
+
+    /// Granted a host off this machine, so it's refused in offline mode
+    fn needs_network(&self) -> bool {
+        self.plugin.hosts.iter().any(|host| !host.is_local())
+    }
+
+    async fn execute(&self, input: Value) -> Result<String> {
+        debug!(
+            "Calling component plugin tool {}/{}",
+            self.plugin.name, self.spec.name
+        );
+        let plugin = self.plugin.clone();
+        let tool = self.spec.name.clone();
+        let call = tokio::task::spawn_blocking(move || plugin.call(&tool, &input.to_string()));
+        tokio::time::timeout(self.timeout, call)
+            .await
+            .map_err(|_| {
+                anyhow!(
+                    "Plugin tool {} timed out after {}s",
+                    self.spec.name,
+                    self.timeout.as_secs()
+                )
+            })?
+            .context("Plugin call panicked")?
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::plugins::{Capabilities, PluginKind};
+
+    /// Lists one tool and echoes its input back
+    const ECHO: &str = r#"
+(component
+  (core module $m
+    (memory (export "memory") 1)
+    (global $next (mut i32) (i32.const 4096))
+    (func (export "realloc") (param i32 i32 i32 i32) (result i32)
+      (local $p i32)
+      (local.set $p (global.get $next))
+      (global.set $next (i32.add (global.get $next) (local.get 3)))
+      (local.get $p))
+    (data (i32.const 0) "[{\"name\":\"echo\",\"description\":\"Echo input\"}]")
+    (func (export "list-tools") (result i32)
+      (i32.store (i32.const 2048) (i32.const 0))
+      (i32.store (i32.const 2052) (i32.const 44))
+      (i32.const 2048))
+    (func (export "call-tool") (param i32 i32 i32 i32) (result i32)
+      (i32.store8 (i32.const 2064) (i32.const 0))
+      (i32.store (i32.const 2068) (local.get 2))
+      (i32.store (i32.const 2072) (local.get 3))
+      (i32.const 2064)))
+  (core instance $i (instantiate $m))
+  (func (export "list-tools") (result string)
+    (canon lift (core func $i "list-tools") (memory $i "memory") (realloc (func $i "realloc"))))
+  (func (export "call-tool") (param "name" string) (param "input" string) (result (result string (error string)))
+    (canon lift (core func $i "call-tool") (memory $i "memory") (realloc (func $i "realloc")))))
+"#;
+
+    fn manifest(capabilities: Capabilities) -> PluginManifest {
+        PluginManifest {
+            name: "echo".to_string(),
+            version: "0.1.0".to_string(),
+            description: String::new(),
+            kind: PluginKind::Component,
+            path: "echo.wat".to_string(),
+            capabilities,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_component_plugin_tool() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("echo.wat");
+        std::fs::write(&path, ECHO).unwrap();
+        std::fs::create_dir(dir.path().join("data")).unwrap();
+
+        let granted = manifest(Capabilities {
+            fs: vec!["data:ro".to_string()],
+            network: vec!["api.example.com:443".to_string()],
+            exec: false,
+        });
+        let tools = load(&granted, &path, &PluginConfig::default()).unwrap();
+        assert_eq!(tools.len(), 1);
+        assert_eq!(tools[0].name(), "echo");
+        assert!(tools[0].needs_network());
+        assert_eq!(
+            tools[0].execute(serde_json::json!({"x": 1})).await.unwrap(),
+            r#"{"x":1}"#
+        );
+
+        let exec = manifest(Capabilities {
+            exec: true,
+            ..Default::default()
+        });
+        assert!(load(&exec, &path, &PluginConfig::default()).is_err());
+        let local = manifest(Capabilities {
+            network: vec!["localhost:8080".to_string()],
+            ..Default::default()
+        });
+        assert!(!load(&local, &path, &PluginConfig::default()).unwrap()[0].needs_network());
+        let missing = manifest(Capabilities {
+            fs: vec!["nope".to_string()],
+            ..Default::default()
+        });
+        assert!(load(&missing, &path, &PluginConfig::default()).is_err());
+    }
+
+    #[test]
+    fn test_grants() {
+        let plugin_dir = Path::new("/plugins/echo");
+        assert_eq!(
+            DirGrant::parse("data:ro", plugin_dir),
+            DirGrant {
+                path: PathBuf::from("/plugins/echo/data"),
+                read_only: true,
+            }
+        );
+        assert!(!DirGrant::parse("/tmp/out", plugin_dir).read_only);
+        assert_eq!(HostGrant::parse("[::1]:8080").host, "::1");
+        assert_eq!(HostGrant::parse("::1").port, None);
+
+        let hosts = vec![HostGrant::parse("127.0.0.1:8080")];
+        assert!(host_allowed(&hosts, "127.0.0.1:8080".parse().unwrap()));
+        assert!(!host_allowed(&hosts, "127.0.0.1:22".parse().unwrap()));
+        assert!(!host_allowed(&hosts, "10.0.0.1:8080".parse().unwrap()));
+        let any_port = vec![HostGrant::parse("localhost")];
+        assert!(host_allowed(&any_port, "127.0.0.1:443".parse().unwrap()));
+    }
+}
@@ -0,0 +1,311 @@
+//! Plugins — third-party tools compiled to native libraries or WebAssembly
+//!
+//! Beyond MCP servers and SKILL.md skills, tools can ship as compiled code
+//! dropped into the plugins directory:
+//!
+//! ```text
+//! plugins/
+//!   weather/
+//!     plugin.toml
+//!     libweather.dylib     (kind = "native")
+//!   slugify/
+//!     plugin.toml
+//!     slugify.wasm         (kind = "wasm")
+//!   notes/
+//!     plugin.toml
+//!     notes.wasm           (kind = "component")
+//! ```
+//!
+//! Every plugin declares the capabilities it needs in its manifest. Native
+//! plugins run in-process with full access, so their declarations can't be
+//! enforced and each one has to be allowlisted by name. WebAssembly plugins
+//! are sandboxed: no host access at all, with fuel, memory and time limits.
+//! Component plugins are sandboxed the same way but get WASI, limited to
+//! the directories and hosts they declare. See [`native`], [`wasm`] and
+//! [`component`] for the three ABIs.
+
+pub mod component;
+pub mod native;
+pub mod wasm;
+
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::{Context, Result, anyhow};
+use serde::{Deserialize, Serialize};
+use tracing::{info, warn};
+
+use crate::tools::ToolHandler;
+
+/// Manifest file in each plugin directory
+pub const MANIFEST_FILE: &str = "plugin.toml";
+
+/// How a plugin is built
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PluginKind {
+    /// A `cdylib` exporting the C ABI in [`native`]
+    Native,
+    /// A WebAssembly module exporting the ABI in [`wasm`]
+    Wasm,
+    /// A WebAssembly component implementing `wit/plugin.wit`, see
+    /// [`component`]
+    Component,
+}
+
+/// Host access a plugin says it needs
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Capabilities {
+    /// Hosts it talks to
+    #[serde(default)]
+    pub network: Vec<String>,
+    /// Paths it reads or writes
+    #[serde(default)]
+    pub fs: Vec<String>,
+    /// Whether it runs other programs
+    #[serde(default)]
+    pub exec: bool,
+}
+
+impl Capabilities {
+    pub fn is_empty(&self) -> bool {
+        self.network.is_empty() && self.fs.is_empty() && !self.exec
+    }
+
+    /// Short description for logs, e.g. "network: api.example.com; exec"
+    pub fn describe(&self) -> String {
+        let mut parts = Vec::new();
+        if !self.network.is_empty() {
+            parts.push(format!("network: {}", self.network.join(", ")));
+        }
+        if !self.fs.is_empty() {
+            parts.push(format!("fs: {}", self.fs.join(", ")));
+        }
+        if self.exec {
+            parts.push("exec".to_string());
+        }
+        if parts.is_empty() {
+            "none".to_string()
+        } else {
+            parts.join("; ")
+        }
+    }
+}
+
+/// `plugin.toml`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PluginManifest {
+    pub name: String,
+    pub version: String,
+    #[serde(default)]
+    pub description: String,
+    pub kind: PluginKind,
+    /// Library or module file, relative to the plugin directory
+    pub path: String,
+    #[serde(default)]
+    pub capabilities: Capabilities,
+}
+
+impl PluginManifest {
+    pub fn parse(content: &str) -> Result<Self> {
+        let manifest: Self = toml::from_str(content)?;
+        if manifest.name.trim().is_empty() {
+            return Err(anyhow!("Plugin name is empty"));
+        }
+        if Path::new(&manifest.path).is_absolute() || manifest.path.contains("..") {
+            return Err(anyhow!(
+                "Plugin path '{}' must be inside the plugin directory",
+                manifest.path
+            ));
+        }
+        Ok(manifest)
+    }
+}
+
+/// What plugins may load and how wasm and component plugins are limited
+#[derive(Debug, Clone)]
+pub struct PluginConfig {
+    /// Native plugins allowed to load, by name (they run unsandboxed)
+    pub allow_native: Vec<String>,
+    /// Fuel (roughly, instructions) a wasm or component tool call may use
+    pub wasm_fuel: u64,
+    /// Memory a wasm or component tool call may grow to
+    pub wasm_memory_bytes: usize,
+    /// Longest a tool call may run
+    pub timeout: Duration,
+}
+
+impl Default for PluginConfig {
+    fn default() -> Self {
+        Self {
+            allow_native: Vec::new(),
+            wasm_fuel: 1_000_000_000,
+            wasm_memory_bytes: 64 * 1024 * 1024,
+            timeout: Duration::from_secs(30),
+        }
+    }
+}
+
+/// Tool description a plugin reports
+#[derive(Debug, Clone, Deserialize)]
+pub struct PluginToolSpec {
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    #[serde(default = "empty_schema")]
+    pub input_schema: serde_json::Value,
+}
+
+fn empty_schema() -> serde_json::Value {
+    serde_json::json!({"type": "object", "properties": {}})
+}
+
+/// Result of a plugin tool call: `{"ok": "..."}` or `{"error": "..."}`
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum CallResult {
+    Ok(String),
+    Error(String),
+}
+
+/// Turn a plugin's JSON reply into a tool result
+fn parse_call_result(plugin: &str, json: &str) -> Result<String> {
+    match serde_json::from_str::<CallResult>(json) {
+        Ok(CallResult::Ok(output)) => Ok(output),
+        Ok(CallResult::Error(e)) => Err(anyhow!("{}", e)),
+        Err(e) => Err(anyhow!(
+            "Plugin {} returned an invalid result: {}",
+            plugin,
+            e
+        )),
+    }
+}
+
+/// Load all plugins from a directory
+///
+/// Expects structure: `dir/plugin_name/plugin.toml`. Plugins that fail to
+/// load, or that the config doesn't allow, are skipped with a warning.
+pub fn load_plugins(dir: &Path, config: &PluginConfig) -> Result<Vec<Arc<dyn ToolHandler>>> {
+    let mut tools: Vec<Arc<dyn ToolHandler>> = Vec::new();
+    if !dir.exists() {
+        info!(
+            "Plugins directory does not exist: {} — skipping",
+            dir.display()
+        );
+        return Ok(tools);
+    }
+
+    for entry in std::fs::read_dir(dir)? {
+        let path = entry?.path();
+        if !path.join(MANIFEST_FILE).exists() {
+            continue;
+        }
+        match load_plugin(&path, config) {
+            Ok(plugin_tools) => tools.extend(plugin_tools),
+            Err(e) => warn!("Failed to load plugin from {}: {:#}", path.display(), e),
+        }
+    }
+    info!("Loaded {} plugin tools from {}", tools.len(), dir.display());
+    Ok(tools)
+}
+
+/// Load one plugin directory
+pub fn load_plugin(dir: &Path, config: &PluginConfig) -> Result<Vec<Arc<dyn ToolHandler>>> {
+    let manifest_path = dir.join(MANIFEST_FILE);
+    let manifest = PluginManifest::parse(
+        &std::fs::read_to_string(&manifest_path)
+            .with_context(|| format!("Failed to read {}", manifest_path.display()))?,
+    )
+    .with_context(|| format!("Invalid {}", manifest_path.display()))?;
+    let file: PathBuf = dir.join(&manifest.path);
+
+    let tools = match manifest.kind {
+        PluginKind::Native => {
+            if !config.allow_native.iter().any(|n| n == &manifest.name) {
+                return Err(anyhow!(
+                    "Native plugin '{}' runs unsandboxed (declares {}); add it to \
+                     [plugins] allow_native to load it",
+                    manifest.name,
+                    manifest.capabilities.describe()
+                ));
+            }
+            native::load(&manifest, &file, config.timeout)?
+        }
+        PluginKind::Wasm => wasm::load(&manifest, &file, config)?,
+        PluginKind::Component => component::load(&manifest, &file, config)?,
+    };
+    info!(
+        "Loaded {} plugin {} v{} ({} tools, capabilities: {})",
+        match manifest.kind {
+            PluginKind::Native => "native",
+            PluginKind::Wasm => "wasm",
+            PluginKind::Component => "component",
+        },
+        manifest.name,
+        manifest.version,
+        tools.len(),
+        manifest.capabilities.describe()
+    );
+    Ok(tools)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_manifest_and_native_allowlist() {
+        let manifest = PluginManifest::parse(
+            r#"
+name = "weather"
+version = "0.1.0"
+kind = "native"
+path = "libweather.dylib"
+
+[capabilities]
+network = ["api.weather.com"]
+"#,
+        )
+        .unwrap();
+        assert_eq!(manifest.kind, PluginKind::Native);
+        assert_eq!(manifest.capabilities.describe(), "network: api.weather.com");
+        assert!(
+            PluginManifest::parse(
+                "name = \"x\"\nversion = \"1\"\nkind = \"wasm\"\npath = \"../x.wasm\""
+            )
+            .is_err()
+        );
+
+        let dir = tempfile::TempDir::new().unwrap();
+        let plugin_dir = dir.path().join("weather");
+        std::fs::create_dir(&plugin_dir).unwrap();
+        std::fs::write(
+            plugin_dir.join(MANIFEST_FILE),
+            "name = \"weather\"\nversion = \"0.1.0\"\nkind = \"native\"\npath = \"libweather.dylib\"",
+        )
+        .unwrap();
+        let err = load_plugin(&plugin_dir, &PluginConfig::default())
+            .err()
+            .unwrap();
+        assert!(err.to_string().contains("allow_native"));
+        // Skipped with a warning rather than failing the whole directory
+        assert!(
+            load_plugins(dir.path(), &PluginConfig::default())
+                .unwrap()
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn test_parse_call_result() {
+        assert_eq!(parse_call_result("p", r#"{"ok": "done"}"#).unwrap(), "done");
+        assert_eq!(
+            parse_call_result("p", r#"{"error": "bad input"}"#)
+                .unwrap_err()
+                .to_string(),
+            "bad input"
+        );
+        assert!(parse_call_result("p", "nope").is_err());
+    }
+}
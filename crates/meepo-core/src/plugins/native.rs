@@ -0,0 +1,183 @@
+//! Native plugins — `cdylib` crates loaded with a C ABI
+//!
+//! A native plugin exports one symbol, `meepo_plugin_v1`, returning a
+//! pointer to a static [`PluginVTable`]. Everything crossing the boundary
+//! is a NUL-terminated UTF-8 C string holding JSON, so the plugin can be
+//! built with any Rust version (or any language with a C ABI):
+//!
+//! ```ignore
+//! #[unsafe(no_mangle)]
+//! pub extern "C" fn meepo_plugin_v1() -> *const PluginVTable {
+//!     static VTABLE: PluginVTable = PluginVTable {
+//!         abi_version: 1,
+//!         tools,  // JSON array of {name, description, input_schema}
+//!         call,   // (tool, input JSON) -> {"ok": "..."} or {"error": "..."}
+//!         free,   // frees strings returned by `call`
+//!     };
+//!     &VTABLE
+//! }
+//! ```
+//!
+//! `call` may be invoked from several threads at once. Native code runs
+//! with the daemon's full permissions, so it can't be sandboxed; a call
+//! that overruns the timeout is abandoned but keeps running.
+
+use std::ffi::{CStr, CString, c_char};
+use std::path::Path;
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::{Context, Result, anyhow};
+use async_trait::async_trait;
+use libloading::Library;
+use serde_json::Value;
+use tracing::debug;
+
+use super::{PluginManifest, PluginToolSpec, parse_call_result};
+use crate::tools::ToolHandler;
+
+/// ABI version this build understands
+pub const ABI_VERSION: u32 = 1;
+
+/// Symbol every native plugin exports
+pub const ENTRY_SYMBOL: &[u8] = b"meepo_plugin_v1\0";
+
+/// Function table a native plugin exposes
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct PluginVTable {
+    /// Must be [`ABI_VERSION`]
+    pub abi_version: u32,
+    /// JSON array of tool specs; owned by the plugin and never freed
+    pub tools: unsafe extern "C" fn() -> *const c_char,
+    /// Run a tool. Returns `{"ok": "..."}` or `{"error": "..."}`, released
+    /// with `free`
+    pub call: unsafe extern "C" fn(tool: *const c_char, input: *const c_char) -> *mut c_char,
+    /// Release a string returned by `call`
+    pub free: unsafe extern "C" fn(value: *mut c_char),
+}
+
+/// A loaded library and its function table
+struct NativePlugin {
+    name: String,
+    vtable: PluginVTable,
+    /// Keeps the code behind `vtable` mapped
+    _library: Library,
+}
+
+impl NativePlugin {
+    fn call(&self, tool: &str, input: &str) -> Result<String> {
+        let tool = CString::new(tool)?;
+        let input = CString::new(input).context("Tool input contains a NUL byte")?;
+        // SAFETY: the plugin promised the ABI by exporting `meepo_plugin_v1`
+        // with a matching version; arguments are valid C strings for the call
+        unsafe {
+            let result = (self.vtable.call)(tool.as_ptr(), input.as_ptr());
+            if result.is_null() {
+                return Err(anyhow!("Plugin {} returned no result", self.name));
+            }
+            let json = CStr::from_ptr(result).to_string_lossy().into_owned();
+            (self.vtable.free)(result);
+            Ok(json)
+        }
+    }
+}
+
+/// Load a native plugin library and wrap each of its tools
+pub fn load(
+    manifest: &PluginManifest,
+    path: &Path,
+    timeout: Duration,
+) -> Result<Vec<Arc<dyn ToolHandler>>> {
+    // SAFETY: loading runs the library's initializers; native plugins must
+    // be allowlisted by name before we get here
+    let library = unsafe { Library::new(path) }
+        .with_context(|| format!("Failed to load {}", path.display()))?;
+    // SAFETY: the entry point's signature is fixed by the ABI
+    let vtable = unsafe {
+        let entry = library
+            .get::<unsafe extern "C" fn() -> *const PluginVTable>(ENTRY_SYMBOL)
+            .context("Library doesn't export meepo_plugin_v1")?;
+        let vtable = entry();
+        if vtable.is_null() {
+            return Err(anyhow!("meepo_plugin_v1 returned null"));
+        }
+        *vtable
+    };
+    if vtable.abi_version != ABI_VERSION {
+        return Err(anyhow!(
+            "Plugin ABI version {} isn't supported (expected {})",
+            vtable.abi_version,
+            ABI_VERSION
+        ));
+    }
+    // SAFETY: `tools` returns a static C string per the ABI
+    let specs = unsafe {
+        let tools = (vtable.tools)();
+        if tools.is_null() {
+            return Err(anyhow!("Plugin returned no tool list"));
+        }
+        CStr::from_ptr(tools).to_string_lossy().into_owned()
+    };
+    let specs: Vec<PluginToolSpec> =
+        serde_json::from_str(&specs).context("Plugin returned an invalid tool list")?;
+
+    let plugin = Arc::new(NativePlugin {
+        name: manifest.name.clone(),
+        vtable,
+        _library: library,
+    });
+    Ok(specs
+        .into_iter()
+        .map(|spec| {
+            Arc::new(NativeTool {
+                plugin: plugin.clone(),
+                spec,
+                timeout,
+            }) as Arc<dyn ToolHandler>
+        })
+        .collect())
+}
+
+/// One tool from a native plugin
+struct NativeTool {
+    plugin: Arc<NativePlugin>,
+    spec: PluginToolSpec,
+    timeout: Duration,
+}
+
+#[async_trait]
+impl ToolHandler for NativeTool {
+    fn name(&self) -> &str {
+        &self.spec.name
+    }
+
+    fn description(&self) -> &str {
+        &self.spec.description
+    }
+
+    fn input_schema(&self) -> Value {
+        self.spec.input_schema.clone()
+    }
+
+    async fn execute(&self, input: Value) -> Result<String> {
+        debug!(
+            "Calling native plugin tool {}/{}",
+            self.plugin.name, self.spec.name
+        );
+        let plugin = self.plugin.clone();
+        let tool = self.spec.name.clone();
+        let call = tokio::task::spawn_blocking(move || plugin.call(&tool, &input.to_string()));
+        let json = tokio::time::timeout(self.timeout, call)
+            .await
+            .map_err(|_| {
+                anyhow!(
+                    "Plugin tool {} timed out after {}s",
+                    self.spec.name,
+                    self.timeout.as_secs()
+                )
+            })?
+            .context("Plugin call panicked")??;
+        parse_call_result(&self.plugin.name, &json)
+    }
+}
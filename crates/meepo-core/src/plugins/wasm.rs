@@ -0,0 +1,292 @@
+//! WebAssembly plugins — sandboxed tools run with wasmtime
+//!
+//! A wasm plugin is a core module with no imports, so it has no way to
+//! reach the host: no files, network, clock or environment. Every call
+//! gets a fresh instance, bounded by fuel and a memory cap. The module
+//! exports:
+//!
+//! - `memory`
+//! - `meepo_alloc(len: i32) -> i32` — space for the host to write into
+//! - `meepo_tools() -> i64` — JSON array of tool specs
+//! - `meepo_call(tool_ptr, tool_len, input_ptr, input_len: i32) -> i64` —
+//!   `{"ok": "..."}` or `{"error": "..."}`
+//!
+//! Strings are UTF-8 in linear memory; an `i64` result packs a pointer in
+//! the high 32 bits and a length in the low 32 bits.
+
+use std::path::Path;
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::{Context, Result, anyhow};
+use async_trait::async_trait;
+use serde_json::Value;
+use tracing::debug;
+use wasmtime::{Engine, Instance, Linker, Module, Store, StoreLimits, StoreLimitsBuilder};
+
+use super::{PluginConfig, PluginManifest, PluginToolSpec, parse_call_result};
+use crate::tools::ToolHandler;
+
+/// A compiled module and the limits each call runs under
+struct WasmPlugin {
+    name: String,
+    engine: Engine,
+    module: Module,
+    fuel: u64,
+    memory_bytes: usize,
+}
+
+impl WasmPlugin {
+    /// A fresh instance with its own store
+    fn instantiate(&self) -> Result<(Store<StoreLimits>, Instance)> {
+        let limits = StoreLimitsBuilder::new()
+            .memory_size(self.memory_bytes)
+            .instances(1)
+            .build();
+        let mut store = Store::new(&self.engine, limits);
+        store.limiter(|limits| limits);
+        store.set_fuel(self.fuel)?;
+        let instance = Linker::new(&self.engine).instantiate(&mut store, &self.module)?;
+        Ok((store, instance))
+    }
+
+    fn read_string(
+        store: &mut Store<StoreLimits>,
+        instance: &Instance,
+        packed: i64,
+    ) -> Result<String> {
+        let memory = instance
+            .get_memory(&mut *store, "memory")
+            .ok_or_else(|| anyhow!("Module doesn't export memory"))?;
+        let ptr = (packed as u64 >> 32) as usize;
+        let len = (packed as u64 & 0xffff_ffff) as usize;
+        let mut bytes = vec![0u8; len];
+        memory
+            .read(&*store, ptr, &mut bytes)
+            .context("Module returned an out-of-bounds string")?;
+        String::from_utf8(bytes).context("Module returned invalid UTF-8")
+    }
+
+    fn write_string(
+        store: &mut Store<StoreLimits>,
+        instance: &Instance,
+        value: &str,
+    ) -> Result<(i32, i32)> {
+        let alloc = instance.get_typed_func::<i32, i32>(&mut *store, "meepo_alloc")?;
+        let len = i32::try_from(value.len()).context("Input too large")?;
+        let ptr = alloc.call(&mut *store, len)?;
+        let memory = instance
+            .get_memory(&mut *store, "memory")
+            .ok_or_else(|| anyhow!("Module doesn't export memory"))?;
+        memory
+            .write(&mut *store, ptr as u32 as usize, value.as_bytes())
+            .context("meepo_alloc returned an out-of-bounds pointer")?;
+        Ok((ptr, len))
+    }
+
+    fn tools(&self) -> Result<String> {
+        let (mut store, instance) = self.instantiate()?;
+        let tools = instance.get_typed_func::<(), i64>(&mut store, "meepo_tools")?;
+        let packed = tools.call(&mut store, ())?;
+        Self::read_string(&mut store, &instance, packed)
+    }
+
+    fn call(&self, tool: &str, input: &str) -> Result<String> {
+        let (mut store, instance) = self.instantiate()?;
+        let (tool_ptr, tool_len) = Self::write_string(&mut store, &instance, tool)?;
+        let (input_ptr, input_len) = Self::write_string(&mut store, &instance, input)?;
+        let call =
+            instance.get_typed_func::<(i32, i32, i32, i32), i64>(&mut store, "meepo_call")?;
+        let packed = call
+            .call(&mut store, (tool_ptr, tool_len, input_ptr, input_len))
+            .map_err(|e| match e.downcast_ref::<wasmtime::Trap>() {
+                Some(wasmtime::Trap::OutOfFuel) => {
+                    anyhow!("Plugin {} ran out of fuel", self.name)
+                }
+                _ => anyhow!("Plugin {} failed: {}", self.name, e),
+            })?;
+        Self::read_string(&mut store, &instance, packed)
+    }
+}
+
+/// Compile a wasm plugin and wrap each of its tools
+pub fn load(
+    manifest: &PluginManifest,
+    path: &Path,
+    config: &PluginConfig,
+) -> Result<Vec<Arc<dyn ToolHandler>>> {
+    if !manifest.capabilities.is_empty() {
+        return Err(anyhow!(
+            "Wasm plugin '{}' declares capabilities ({}), but wasm plugins get no host access; \
+             package it as a component to be granted them",
+            manifest.name,
+            manifest.capabilities.describe()
+        ));
+    }
+    let mut engine_config = wasmtime::Config::new();
+    engine_config.consume_fuel(true);
+    let engine = Engine::new(&engine_config)?;
+    let module = Module::from_file(&engine, path)
+        .with_context(|| format!("Failed to compile {}", path.display()))?;
+    if let Some(import) = module.imports().next() {
+        return Err(anyhow!(
+            "Module imports {}::{}; wasm plugins get no host access",
+            import.module(),
+            import.name()
+        ));
+    }
+
+    let plugin = Arc::new(WasmPlugin {
+        name: manifest.name.clone(),
+        engine,
+        module,
+        fuel: config.wasm_fuel,
+        memory_bytes: config.wasm_memory_bytes,
+    });
+    let specs: Vec<PluginToolSpec> =
+        serde_json::from_str(&plugin.tools()?).context("Plugin returned an invalid tool list")?;
+    Ok(specs
+        .into_iter()
+        .map(|spec| {
+            Arc::new(WasmTool {
+                plugin: plugin.clone(),
+                spec,
+                timeout: config.timeout,
+            }) as Arc<dyn ToolHandler>
+        })
+        .collect())
+}
+
+/// One tool from a wasm plugin
+struct WasmTool {
+    plugin: Arc<WasmPlugin>,
+    spec: PluginToolSpec,
+    timeout: Duration,
+}
+
+#[async_trait]
+impl ToolHandler for WasmTool {
+    fn name(&self) -> &str {
+        &self.spec.name
+    }
+
+    fn description(&self) -> &str {
+        &self.spec.description
+    }
+
+    fn input_schema(&self) -> Value {
+        self.spec.input_schema.clone()
+    }
+
+    async fn execute(&self, input: Value) -> Result<String> {
+        debug!(
+            "Calling wasm plugin tool {}/{}",
+            self.plugin.name, self.spec.name
+        );
+        let plugin = self.plugin.clone();
+        let tool = self.spec.name.clone();
+        let call = tokio::task::spawn_blocking(move || plugin.call(&tool, &input.to_string()));
+        let json = tokio::time::timeout(self.timeout, call)
+            .await
+            .map_err(|_| {
+                anyhow!(
+                    "Plugin tool {} timed out after {}s",
+                    self.spec.name,
+                    self.timeout.as_secs()
+                )
+            })?
+            .context("Plugin call panicked")??;
+        parse_call_result(&self.plugin.name, &json)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::plugins::{Capabilities, PluginKind};
+
+    const PING: &str = r#"
+(module
+  (memory (export "memory") 1)
+  (global $next (mut i32) (i32.const 1024))
+  (func (export "meepo_alloc") (param $len i32) (result i32)
+    (local $p i32)
+    (local.set $p (global.get $next))
+    (global.set $next (i32.add (global.get $next) (local.get $len)))
+    (local.get $p))
+  (data (i32.const 0) "[{\"name\":\"ping\",\"description\":\"Reply pong\"}]")
+  (data (i32.const 100) "{\"ok\":\"pong\"}")
+  (func (export "meepo_tools") (result i64) (i64.const 44))
+  (func (export "meepo_call") (param i32 i32 i32 i32) (result i64)
+    (i64.or (i64.shl (i64.const 100) (i64.const 32)) (i64.const 13))))
+"#;
+
+    fn manifest() -> PluginManifest {
+        PluginManifest {
+            name: "ping".to_string(),
+            version: "0.1.0".to_string(),
+            description: String::new(),
+            kind: PluginKind::Wasm,
+            path: "ping.wat".to_string(),
+            capabilities: Capabilities::default(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_wasm_plugin_tool() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("ping.wat");
+        std::fs::write(&path, PING).unwrap();
+
+        let tools = load(&manifest(), &path, &PluginConfig::default()).unwrap();
+        assert_eq!(tools.len(), 1);
+        assert_eq!(tools[0].name(), "ping");
+        assert_eq!(tools[0].description(), "Reply pong");
+        assert_eq!(
+            tools[0].execute(serde_json::json!({"x": 1})).await.unwrap(),
+            "pong"
+        );
+
+        let networked = PluginManifest {
+            capabilities: Capabilities {
+                network: vec!["example.com".to_string()],
+                ..Default::default()
+            },
+            ..manifest()
+        };
+        assert!(load(&networked, &path, &PluginConfig::default()).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_wasm_plugin_sandbox() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let looping = dir.path().join("loop.wat");
+        std::fs::write(
+            &looping,
+            PING.replace(
+                "(i64.or (i64.shl (i64.const 100) (i64.const 32)) (i64.const 13))",
+                "(loop $l (br $l)) (i64.const 0)",
+            ),
+        )
+        .unwrap();
+        let config = PluginConfig {
+            wasm_fuel: 100_000,
+            ..Default::default()
+        };
+        let tools = load(&manifest(), &looping, &config).unwrap();
+        let err = tools[0].execute(Value::Null).await.unwrap_err();
+        assert!(err.to_string().contains("ran out of fuel"));
+
+        let importing = dir.path().join("import.wat");
+        std::fs::write(
+            &importing,
+            PING.replace(
+                "(memory (export \"memory\") 1)",
+                "(import \"wasi_snapshot_preview1\" \"fd_write\" (func (param i32 i32 i32 i32) (result i32)))\n  (memory (export \"memory\") 1)",
+            ),
+        )
+        .unwrap();
+        let err = load(&manifest(), &importing, &config).err().unwrap();
+        assert!(err.to_string().contains("no host access"));
+    }
+}
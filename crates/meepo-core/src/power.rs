@@ -0,0 +1,388 @@
+//! Power source and battery awareness
+//!
+//! On a laptop running on battery below `low_battery_percent`, Meepo goes
+//! into low-power mode: the autonomous loop ticks less often, polling
+//! watchers pause, and background tasks wait until the machine is plugged
+//! in again. The latest state is kept process-wide so the agent's context
+//! can mention it.
+
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+use tokio::sync::watch;
+use tokio_util::sync::CancellationToken;
+use tracing::{debug, info};
+
+/// Configuration for battery-aware throttling
+#[derive(Debug, Clone)]
+pub struct PowerConfig {
+    pub enabled: bool,
+    /// Low-power mode kicks in on battery below this percentage
+    pub low_battery_percent: u8,
+    /// Multiply the autonomous loop's tick interval by this in low-power mode
+    pub tick_multiplier: u32,
+    /// Pause polling watchers (email, calendar, GitHub) in low-power mode
+    pub pause_polling_watchers: bool,
+    /// Hold background tasks until back on AC power
+    pub defer_background_tasks: bool,
+    /// How often to re-check the power source
+    pub poll_secs: u64,
+}
+
+impl Default for PowerConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            low_battery_percent: 20,
+            tick_multiplier: 4,
+            pause_polling_watchers: true,
+            defer_background_tasks: true,
+            poll_secs: 60,
+        }
+    }
+}
+
+/// Power source and charge. `None` means it couldn't be detected.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PowerState {
+    pub on_ac_power: Option<bool>,
+    pub battery_percent: Option<u8>,
+}
+
+impl PowerState {
+    /// On battery and below the threshold
+    pub fn is_low(&self, threshold: u8) -> bool {
+        self.on_ac_power == Some(false) && self.battery_percent.is_some_and(|p| p < threshold)
+    }
+
+    /// Short description, e.g. "on battery (18%)"
+    pub fn describe(&self) -> String {
+        let source = match self.on_ac_power {
+            Some(true) => "on AC power",
+            Some(false) => "on battery",
+            None => "power source unknown",
+        };
+        match self.battery_percent {
+            Some(p) => format!("{} ({}%)", source, p),
+            None => source.to_string(),
+        }
+    }
+}
+
+/// Latest power state and whether low-power mode is on
+static CURRENT: RwLock<Option<(PowerState, bool)>> = RwLock::new(None);
+
+/// Publish the current power state for the agent's context
+pub fn set_current(state: PowerState, low_power: bool) {
+    *CURRENT.write().unwrap() = Some((state, low_power));
+}
+
+/// The last published power state, if a monitor is running
+pub fn current() -> Option<(PowerState, bool)> {
+    *CURRENT.read().unwrap()
+}
+
+/// Context section for the agent; empty unless running on battery
+pub fn context_section() -> String {
+    current()
+        .map(|(state, low_power)| section_for(&state, low_power))
+        .unwrap_or_default()
+}
+
+fn section_for(state: &PowerState, low_power: bool) -> String {
+    if state.on_ac_power != Some(false) {
+        return String::new();
+    }
+    let mut section = format!("## Power\n\nThe machine is running {}.", state.describe());
+    if low_power {
+        section.push_str(
+            " Low-power mode is on: polling watchers are paused and background tasks \
+             wait until it's plugged in. Avoid starting heavy or long-running work \
+             unless the user asks for it.",
+        );
+    }
+    section.push_str("\n\n");
+    section
+}
+
+/// Watches the power source and drives low-power mode
+pub struct PowerMonitor {
+    config: PowerConfig,
+    state_tx: watch::Sender<PowerState>,
+}
+
+impl PowerMonitor {
+    pub fn new(config: PowerConfig) -> Arc<Self> {
+        let (state_tx, _) = watch::channel(PowerState::default());
+        Arc::new(Self { config, state_tx })
+    }
+
+    pub fn state(&self) -> PowerState {
+        *self.state_tx.borrow()
+    }
+
+    /// Whether low-power mode is on
+    pub fn low_power(&self) -> bool {
+        self.config.enabled && self.state().is_low(self.config.low_battery_percent)
+    }
+
+    /// Whether polling watchers should be paused right now
+    pub fn pause_watchers(&self) -> bool {
+        self.config.pause_polling_watchers && self.low_power()
+    }
+
+    /// Subscribe to power state changes
+    pub fn subscribe(&self) -> watch::Receiver<PowerState> {
+        self.state_tx.subscribe()
+    }
+
+    /// Tick interval for the autonomous loop under the current power state
+    pub fn tick_interval(&self, base: Duration) -> Duration {
+        if self.low_power() {
+            base * self.config.tick_multiplier.max(1)
+        } else {
+            base
+        }
+    }
+
+    /// Record a new power state, logging low-power transitions
+    pub fn update(&self, state: PowerState) {
+        let was_low = self.low_power();
+        self.state_tx.send_if_modified(|current| {
+            let changed = *current != state;
+            *current = state;
+            changed
+        });
+        let low = self.low_power();
+        if low != was_low {
+            if low {
+                info!("Low-power mode on ({})", state.describe());
+            } else {
+                info!("Low-power mode off ({})", state.describe());
+            }
+        }
+    }
+
+    /// Probe the host, record the result and publish it for the agent's context
+    pub async fn refresh(&self) {
+        self.update(probe().await);
+        set_current(self.state(), self.low_power());
+    }
+
+    /// Keep the power state current until cancelled
+    pub async fn run(self: Arc<Self>, cancel: CancellationToken) {
+        if !self.config.enabled {
+            return;
+        }
+        let interval = Duration::from_secs(self.config.poll_secs.max(1));
+        loop {
+            self.refresh().await;
+            tokio::select! {
+                _ = cancel.cancelled() => break,
+                _ = tokio::time::sleep(interval) => {}
+            }
+        }
+    }
+
+    /// Wait until background tasks may run (back on AC, or charged above the threshold)
+    pub async fn wait_for_power(&self, what: &str) {
+        if !self.config.defer_background_tasks || !self.low_power() {
+            return;
+        }
+        info!(
+            "Low-power mode ({}) — deferring {} until plugged in",
+            self.state().describe(),
+            what
+        );
+        let threshold = self.config.low_battery_percent;
+        let mut rx = self.subscribe();
+        let _ = rx.wait_for(|state| !state.is_low(threshold)).await;
+        info!("Power restored — starting {}", what);
+    }
+}
+
+/// Probe the power source and battery level
+pub async fn probe() -> PowerState {
+    let state = probe_platform().await;
+    debug!("Power state: {}", state.describe());
+    state
+}
+
+#[cfg(target_os = "macos")]
+async fn probe_platform() -> PowerState {
+    let output = tokio::time::timeout(
+        Duration::from_secs(5),
+        tokio::process::Command::new("pmset")
+            .args(["-g", "batt"])
+            .output(),
+    )
+    .await;
+    match output {
+        Ok(Ok(out)) if out.status.success() => {
+            parse_pmset_batt(&String::from_utf8_lossy(&out.stdout))
+        }
+        _ => PowerState::default(),
+    }
+}
+
+#[cfg(target_os = "linux")]
+async fn probe_platform() -> PowerState {
+    let Ok(entries) = std::fs::read_dir("/sys/class/power_supply") else {
+        return PowerState::default();
+    };
+    let mut on_ac_power = None;
+    let mut battery_percent = None;
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let read = |name: &str| {
+            std::fs::read_to_string(path.join(name))
+                .map(|s| s.trim().to_string())
+                .unwrap_or_default()
+        };
+        match read("type").as_str() {
+            "Mains" => on_ac_power = Some(read("online") == "1"),
+            "Battery" => {
+                battery_percent = read("capacity").parse().ok();
+                if on_ac_power.is_none() && read("status") == "Discharging" {
+                    on_ac_power = Some(false);
+                }
+            }
+            _ => {}
+        }
+    }
+    // No battery at all: a desktop, always on mains
+    if on_ac_power.is_none() && battery_percent.is_none() {
+        on_ac_power = Some(true);
+    }
+    PowerState {
+        on_ac_power,
+        battery_percent,
+    }
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "linux")))]
+async fn probe_platform() -> PowerState {
+    PowerState::default()
+}
+
+/// Parse `pmset -g batt`:
+/// "Now drawing from 'Battery Power'\n -InternalBattery-0 (id=..)\t80%; discharging; ..."
+#[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+fn parse_pmset_batt(output: &str) -> PowerState {
+    let mut lines = output.lines();
+    let on_ac_power = lines.next().and_then(|first| {
+        if first.contains("'AC Power'") {
+            Some(true)
+        } else if first.contains("'Battery Power'") {
+            Some(false)
+        } else {
+            None
+        }
+    });
+    let battery_percent = lines.find_map(|line| {
+        let end = line.find('%')?;
+        let digits: String = line[..end]
+            .chars()
+            .rev()
+            .take_while(|c| c.is_ascii_digit())
+            .collect::<Vec<_>>()
+            .into_iter()
+            .rev()
+            .collect();
+        digits.parse().ok()
+    });
+    PowerState {
+        on_ac_power,
+        battery_percent,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn battery(percent: u8) -> PowerState {
+        PowerState {
+            on_ac_power: Some(false),
+            battery_percent: Some(percent),
+        }
+    }
+
+    #[test]
+    fn test_parse_pmset_batt() {
+        let out = "Now drawing from 'Battery Power'\n -InternalBattery-0 (id=4653155)\t18%; discharging; 1:02 remaining present: true";
+        assert_eq!(parse_pmset_batt(out), battery(18));
+
+        let out = "Now drawing from 'AC Power'\n -InternalBattery-0 (id=4653155)\t100%; charged; 0:00 remaining present: true";
+        let state = parse_pmset_batt(out);
+        assert_eq!(state.on_ac_power, Some(true));
+        assert_eq!(state.battery_percent, Some(100));
+
+        // Desktop Mac: no battery line
+        let state = parse_pmset_batt("Now drawing from 'AC Power'\n");
+        assert_eq!(state.on_ac_power, Some(true));
+        assert_eq!(state.battery_percent, None);
+    }
+
+    #[test]
+    fn test_low_power_mode() {
+        let monitor = PowerMonitor::new(PowerConfig::default());
+        let base = Duration::from_secs(30);
+
+        monitor.update(battery(50));
+        assert!(!monitor.low_power());
+        assert_eq!(monitor.tick_interval(base), base);
+
+        monitor.update(battery(12));
+        assert!(monitor.low_power());
+        assert!(monitor.pause_watchers());
+        assert_eq!(monitor.tick_interval(base), Duration::from_secs(120));
+
+        // Plugged in at low charge is fine
+        monitor.update(PowerState {
+            on_ac_power: Some(true),
+            battery_percent: Some(12),
+        });
+        assert!(!monitor.low_power());
+
+        let disabled = PowerMonitor::new(PowerConfig {
+            enabled: false,
+            ..PowerConfig::default()
+        });
+        disabled.update(battery(5));
+        assert!(!disabled.low_power());
+    }
+
+    #[tokio::test]
+    async fn test_wait_for_power_resumes_on_ac() {
+        let monitor = PowerMonitor::new(PowerConfig::default());
+        monitor.update(battery(10));
+        let waiter = {
+            let monitor = monitor.clone();
+            tokio::spawn(async move { monitor.wait_for_power("test task").await })
+        };
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        assert!(!waiter.is_finished());
+
+        monitor.update(PowerState {
+            on_ac_power: Some(true),
+            battery_percent: Some(10),
+        });
+        tokio::time::timeout(Duration::from_secs(2), waiter)
+            .await
+            .unwrap()
+            .unwrap();
+    }
+
+    #[test]
+    fn test_context_section() {
+        let section = section_for(&battery(15), true);
+        assert!(section.contains("on battery (15%)"));
+        assert!(section.contains("Low-power mode is on"));
+        assert!(!section_for(&battery(60), false).contains("Low-power"));
+        let plugged_in = PowerState {
+            on_ac_power: Some(true),
+            battery_percent: Some(90),
+        };
+        assert!(section_for(&plugged_in, false).is_empty());
+    }
+}
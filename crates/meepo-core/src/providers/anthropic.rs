@@ -10,10 +10,12 @@ use tracing::debug;
 
 use crate::api::ToolDefinition;
 
+use super::overrides::RequestOverrides;
 use super::types::{
     ChatBlock, ChatMessage, ChatMessageContent, ChatResponse, ChatResponseBlock, ChatRole,
     ChatUsage, LlmProvider, StopReason,
 };
+use crate::streaming::StreamDelta;
 
 /// Anthropic Claude provider
 pub struct AnthropicProvider {
@@ -84,6 +86,16 @@ impl AnthropicProvider {
                                     tool_use_id: tool_call_id.clone(),
                                     content: content.clone(),
                                 },
+                                ChatBlock::Thinking {
+                                    thinking,
+                                    signature,
+                                } => AnthropicBlock::Thinking {
+                                    thinking: thinking.clone(),
+                                    signature: signature.clone(),
+                                },
+                                ChatBlock::RedactedThinking { data } => {
+                                    AnthropicBlock::RedactedThinking { data: data.clone() }
+                                }
                             })
                             .collect();
                         AnthropicContent::Blocks(ab)
@@ -110,6 +122,16 @@ impl AnthropicProvider {
                 AnthropicBlock::ToolResult { .. } => ChatResponseBlock::Text {
                     text: "[tool_result in response]".to_string(),
                 },
+                AnthropicBlock::Thinking {
+                    thinking,
+                    signature,
+                } => ChatResponseBlock::Thinking {
+                    thinking,
+                    signature,
+                },
+                AnthropicBlock::RedactedThinking { data } => {
+                    ChatResponseBlock::RedactedThinking { data }
+                }
             })
             .collect();
 
@@ -148,26 +170,15 @@ impl LlmProvider for AnthropicProvider {
         system: &str,
     ) -> Result<ChatResponse> {
         let url = format!("{}/v1/messages", self.base_url);
-        let anthropic_messages = Self::to_anthropic_messages(messages);
-
-        let mut body = serde_json::json!({
-            "model": self.model,
-            "max_tokens": self.max_tokens,
-            "system": system,
-            "messages": anthropic_messages,
-        });
-
-        if !tools.is_empty() {
-            body["tools"] = serde_json::to_value(tools)?;
+        let overrides = super::overrides::current();
+        let model = overrides.model_for("anthropic", &self.model);
+        let mut body = request_body(model, self.max_tokens, messages, tools, system, &overrides)?;
+        let sink = crate::streaming::live_sink();
+        if sink.is_some() {
+            body["stream"] = serde_json::json!(true);
         }
 
-        debug!(
-            "Anthropic request: model={}, messages={}",
-            self.model,
-            anthropic_messages.len()
-        );
-
-        let response = self
+        let mut response = self
             .client
             .post(&url)
             .header("x-api-key", &self.api_key)
@@ -191,19 +202,203 @@ impl LlmProvider for AnthropicProvider {
             ));
         }
 
-        let api_response: AnthropicApiResponse = response
-            .json()
+        let Some(sink) = sink else {
+            let body: Value = response
+                .json()
+                .await
+                .context("Failed to parse Anthropic API response")?;
+            return parse_response(body);
+        };
+
+        let mut stream = StreamAccumulator::default();
+        while let Some(chunk) = response
+            .chunk()
             .await
-            .context("Failed to parse Anthropic API response")?;
+            .context("Anthropic API stream was interrupted")?
+        {
+            for text in stream.push(&chunk)? {
+                let _ = sink.send(StreamDelta::Text(text));
+            }
+        }
+        parse_response(stream.finish()?)
+    }
+}
 
-        debug!(
-            "Anthropic response: blocks={}, stop_reason={:?}",
-            api_response.content.len(),
-            api_response.stop_reason
-        );
+/// Rebuilds a Messages API response body from its server-sent events,
+/// handing back text deltas as they arrive
+#[derive(Debug, Default)]
+struct StreamAccumulator {
+    /// Bytes after the last complete line
+    pending: Vec<u8>,
+    message: Option<Value>,
+    blocks: Vec<Value>,
+    /// Tool input JSON as it arrives, by block index
+    tool_input: Vec<String>,
+}
+
+impl StreamAccumulator {
+    /// Take more of the stream; returns the text it added
+    fn push(&mut self, bytes: &[u8]) -> Result<Vec<String>> {
+        self.pending.extend_from_slice(bytes);
+        let mut texts = Vec::new();
+        while let Some(end) = self.pending.iter().position(|b| *b == b'\n') {
+            let line: Vec<u8> = self.pending.drain(..=end).collect();
+            let line = String::from_utf8_lossy(&line);
+            let Some(data) = line.trim_end().strip_prefix("data:") else {
+                continue;
+            };
+            let event: Value = serde_json::from_str(data.trim())
+                .context("Failed to parse Anthropic stream event")?;
+            if let Some(text) = self.apply(&event)? {
+                texts.push(text);
+            }
+        }
+        Ok(texts)
+    }
+
+    fn apply(&mut self, event: &Value) -> Result<Option<String>> {
+        let index = event["index"].as_u64().unwrap_or(0) as usize;
+        match event["type"].as_str().unwrap_or_default() {
+            "message_start" => self.message = Some(event["message"].clone()),
+            "content_block_start" => {
+                if self.blocks.len() <= index {
+                    self.blocks.resize(index + 1, Value::Null);
+                    self.tool_input.resize(index + 1, String::new());
+                }
+                let mut block = event["content_block"].clone();
+                if block["type"] == "thinking" && block.get("signature").is_none() {
+                    block["signature"] = Value::String(String::new());
+                }
+                self.blocks[index] = block;
+            }
+            "content_block_delta" => {
+                let Some(block) = self.blocks.get_mut(index) else {
+                    return Ok(None);
+                };
+                let delta = &event["delta"];
+                let append = |block: &mut Value, field: &str, key: &str| {
+                    let more = delta[key].as_str().unwrap_or_default();
+                    let so_far = block[field].as_str().unwrap_or_default();
+                    block[field] = Value::String(format!("{}{}", so_far, more));
+                };
+                match delta["type"].as_str().unwrap_or_default() {
+                    "text_delta" => {
+                        append(block, "text", "text");
+                        return Ok(delta["text"].as_str().map(String::from));
+                    }
+                    "thinking_delta" => append(block, "thinking", "thinking"),
+                    "signature_delta" => append(block, "signature", "signature"),
+                    "input_json_delta" => self.tool_input[index]
+                        .push_str(delta["partial_json"].as_str().unwrap_or_default()),
+                    _ => {}
+                }
+            }
+            "content_block_stop" => {
+                if let Some(json) = self.tool_input.get(index).filter(|j| !j.is_empty()) {
+                    self.blocks[index]["input"] = serde_json::from_str(json)
+                        .context("Failed to parse streamed tool input")?;
+                }
+            }
+            "message_delta" => {
+                if let Some(message) = &mut self.message {
+                    message["stop_reason"] = event["delta"]["stop_reason"].clone();
+                    if let Some(output) = event["usage"].get("output_tokens") {
+                        message["usage"]["output_tokens"] = output.clone();
+                    }
+                }
+            }
+            "error" => {
+                return Err(anyhow!(
+                    "Anthropic API stream failed: {}",
+                    event["error"]["message"]
+                        .as_str()
+                        .unwrap_or("unknown error")
+                ));
+            }
+            _ => {}
+        }
+        Ok(None)
+    }
 
-        Ok(Self::from_anthropic_response(api_response))
+    /// The complete response body
+    fn finish(self) -> Result<Value> {
+        let mut message = self
+            .message
+            .ok_or_else(|| anyhow!("Anthropic API stream ended before the message started"))?;
+        message["content"] = Value::Array(self.blocks);
+        Ok(message)
+    }
+}
+
+/// Build a Messages API request body. Also used by the Bedrock provider,
+/// which takes the same body without `model`.
+pub(super) fn request_body(
+    model: &str,
+    max_tokens: u32,
+    messages: &[ChatMessage],
+    tools: &[ToolDefinition],
+    system: &str,
+    overrides: &RequestOverrides,
+) -> Result<Value> {
+    let anthropic_messages = AnthropicProvider::to_anthropic_messages(messages);
+    let mut body = serde_json::json!({
+        "model": model,
+        "max_tokens": max_tokens,
+        "system": system,
+        "messages": anthropic_messages,
+    });
+
+    if !tools.is_empty() {
+        body["tools"] = serde_json::to_value(tools)?;
     }
+    let thinking_budget = overrides
+        .thinking_budget
+        .filter(|_| supports_thinking(model));
+    if let Some(budget) = thinking_budget {
+        let budget = budget.max(MIN_THINKING_BUDGET);
+        // max_tokens covers the thinking as well as the answer
+        body["max_tokens"] = serde_json::json!(max_tokens + budget);
+        body["thinking"] = serde_json::json!({
+            "type": "enabled",
+            "budget_tokens": budget,
+        });
+    } else if let Some(temperature) = overrides.temperature {
+        // Anthropic accepts 0.0–1.0; thinking requires the default
+        body["temperature"] = serde_json::json!(temperature.min(1.0));
+    }
+
+    debug!(
+        "Anthropic request: model={}, messages={}, thinking={:?}",
+        model,
+        anthropic_messages.len(),
+        thinking_budget
+    );
+    Ok(body)
+}
+
+/// Parse a Messages API response body
+pub(super) fn parse_response(body: Value) -> Result<ChatResponse> {
+    let api_response: AnthropicApiResponse =
+        serde_json::from_value(body).context("Failed to parse Anthropic API response")?;
+
+    debug!(
+        "Anthropic response: blocks={}, stop_reason={:?}",
+        api_response.content.len(),
+        api_response.stop_reason
+    );
+
+    Ok(AnthropicProvider::from_anthropic_response(api_response))
+}
+
+/// Smallest thinking budget the API accepts
+const MIN_THINKING_BUDGET: u32 = 1024;
+
+/// Whether a model supports extended thinking (Claude 3.7 and later)
+fn supports_thinking(model: &str) -> bool {
+    // Bedrock IDs look like "us.anthropic.claude-sonnet-4-20250514-v1:0"
+    let model = model.rsplit_once("anthropic.").map_or(model, |(_, m)| m);
+    model.starts_with("claude")
+        && (!model.starts_with("claude-3-") || model.starts_with("claude-3-7"))
 }
 
 // ── Anthropic wire types ──
@@ -236,6 +431,13 @@ enum AnthropicBlock {
         tool_use_id: String,
         content: String,
     },
+    Thinking {
+        thinking: String,
+        signature: String,
+    },
+    RedactedThinking {
+        data: String,
+    },
 }
 
 #[derive(Debug, Clone, Deserialize)]
@@ -341,6 +543,53 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_thinking_blocks_round_trip() {
+        let json = serde_json::json!({
+            "id": "msg_3",
+            "content": [
+                {"type": "thinking", "thinking": "Check the calendar first.", "signature": "sig"},
+                {"type": "redacted_thinking", "data": "opaque"},
+                {"type": "tool_use", "id": "tu_1", "name": "calendar", "input": {}},
+            ],
+            "stop_reason": "tool_use",
+            "usage": {"input_tokens": 30, "output_tokens": 40},
+        });
+        let resp: AnthropicApiResponse = serde_json::from_value(json).unwrap();
+        let result = AnthropicProvider::from_anthropic_response(resp);
+        assert!(matches!(
+            &result.blocks[0],
+            ChatResponseBlock::Thinking { thinking, signature }
+                if thinking == "Check the calendar first." && signature == "sig"
+        ));
+
+        // The assistant turn sent back keeps thinking blocks with signatures
+        let msgs = vec![ChatMessage {
+            role: ChatRole::Assistant,
+            content: ChatMessageContent::Blocks(
+                result.blocks.iter().map(|b| b.to_chat_block()).collect(),
+            ),
+        }];
+        let wire = serde_json::to_value(AnthropicProvider::to_anthropic_messages(&msgs)).unwrap();
+        assert_eq!(wire[0]["content"][0]["type"], "thinking");
+        assert_eq!(wire[0]["content"][0]["signature"], "sig");
+        assert_eq!(wire[0]["content"][1]["type"], "redacted_thinking");
+    }
+
+    #[test]
+    fn test_supports_thinking() {
+        assert!(supports_thinking("claude-sonnet-4-5"));
+        assert!(supports_thinking("claude-3-7-sonnet-latest"));
+        assert!(!supports_thinking("claude-3-5-haiku-latest"));
+        assert!(!supports_thinking("gpt-4o"));
+        assert!(supports_thinking(
+            "us.anthropic.claude-sonnet-4-20250514-v1:0"
+        ));
+        assert!(!supports_thinking(
+            "anthropic.claude-3-5-haiku-20241022-v1:0"
+        ));
+    }
+
     #[test]
     fn test_anthropic_block_serialization() {
         let block = AnthropicBlock::Text {
@@ -351,6 +600,48 @@ mod tests {
         assert!(json.contains("\"text\":\"hello\""));
     }
 
+    #[test]
+    fn test_stream_accumulator_rebuilds_response() {
+        let events = [
+            r#"{"type":"message_start","message":{"id":"msg_1","type":"message","role":"assistant","content":[],"stop_reason":null,"usage":{"input_tokens":12,"output_tokens":1}}}"#,
+            r#"{"type":"content_block_start","index":0,"content_block":{"type":"text","text":""}}"#,
+            r#"{"type":"content_block_delta","index":0,"delta":{"type":"text_delta","text":"Checking "}}"#,
+            r#"{"type":"content_block_delta","index":0,"delta":{"type":"text_delta","text":"the weather"}}"#,
+            r#"{"type":"content_block_start","index":1,"content_block":{"type":"tool_use","id":"tu_1","name":"weather","input":{}}}"#,
+            r#"{"type":"content_block_delta","index":1,"delta":{"type":"input_json_delta","partial_json":"{\"city\": \"Par"}}"#,
+            r#"{"type":"content_block_delta","index":1,"delta":{"type":"input_json_delta","partial_json":"is\"}"}}"#,
+            r#"{"type":"content_block_stop","index":1}"#,
+            r#"{"type":"message_delta","delta":{"stop_reason":"tool_use"},"usage":{"output_tokens":30}}"#,
+        ];
+        let raw: String = events
+            .iter()
+            .map(|e| format!("event: x\ndata: {}\n\n", e))
+            .collect();
+
+        // Chunks can split lines anywhere
+        let mut stream = StreamAccumulator::default();
+        let mut texts = Vec::new();
+        for chunk in raw.as_bytes().chunks(7) {
+            texts.extend(stream.push(chunk).unwrap());
+        }
+        assert_eq!(texts.concat(), "Checking the weather");
+
+        let response = parse_response(stream.finish().unwrap()).unwrap();
+        assert!(response.stop_reason.is_tool_use());
+        assert_eq!(response.usage.output_tokens, 30);
+        assert!(matches!(
+            &response.blocks[1],
+            ChatResponseBlock::ToolCall { input, .. } if input["city"] == "Paris"
+        ));
+
+        let mut failed = StreamAccumulator::default();
+        assert!(
+            failed
+                .push(b"data: {\"type\":\"error\",\"error\":{\"message\":\"Overloaded\"}}\n")
+                .is_err()
+        );
+    }
+
     #[test]
     fn test_anthropic_provider_debug_hides_key() {
         let provider = AnthropicProvider::new(
@@ -0,0 +1,151 @@
+//! Azure OpenAI provider
+//!
+//! Same wire format as OpenAI, but requests go to a named deployment under
+//! the resource endpoint, carry an `api-version` query parameter, and
+//! authenticate with an `api-key` header.
+
+use anyhow::{Context, Result, anyhow};
+use async_trait::async_trait;
+use reqwest::Client;
+use serde_json::Value;
+use std::time::Duration;
+
+use crate::api::ToolDefinition;
+
+use super::openai;
+use super::types::{ChatMessage, ChatResponse, LlmProvider};
+
+/// Azure OpenAI provider for a single deployment
+pub struct AzureOpenAiProvider {
+    client: Client,
+    api_key: String,
+    endpoint: String,
+    deployment: String,
+    api_version: String,
+    max_tokens: u32,
+}
+
+impl std::fmt::Debug for AzureOpenAiProvider {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("AzureOpenAiProvider")
+            .field("endpoint", &self.endpoint)
+            .field("deployment", &self.deployment)
+            .field("api_version", &self.api_version)
+            .field("max_tokens", &self.max_tokens)
+            .finish()
+    }
+}
+
+impl AzureOpenAiProvider {
+    /// - `endpoint`: the resource URL (e.g. `https://my-resource.openai.azure.com`)
+    /// - `deployment`: the deployment name, which picks the model
+    pub fn new(
+        api_key: String,
+        endpoint: String,
+        deployment: String,
+        api_version: String,
+        max_tokens: u32,
+    ) -> Self {
+        let client = Client::builder()
+            .timeout(Duration::from_secs(120))
+            .build()
+            .expect("Failed to build HTTP client");
+
+        Self {
+            client,
+            api_key,
+            endpoint: endpoint.trim_end_matches('/').to_string(),
+            deployment,
+            api_version,
+            max_tokens,
+        }
+    }
+
+    fn chat_url(&self) -> String {
+        format!(
+            "{}/openai/deployments/{}/chat/completions?api-version={}",
+            self.endpoint, self.deployment, self.api_version
+        )
+    }
+}
+
+#[async_trait]
+impl LlmProvider for AzureOpenAiProvider {
+    fn provider_name(&self) -> &str {
+        "azure"
+    }
+
+    fn model(&self) -> &str {
+        &self.deployment
+    }
+
+    async fn chat(
+        &self,
+        messages: &[ChatMessage],
+        tools: &[ToolDefinition],
+        system: &str,
+    ) -> Result<ChatResponse> {
+        // The deployment fixes the model, so model overrides don't apply
+        let overrides = super::overrides::current();
+        let body = openai::request_body(
+            &self.deployment,
+            self.max_tokens,
+            messages,
+            tools,
+            system,
+            &overrides,
+        )?;
+
+        let response = self
+            .client
+            .post(self.chat_url())
+            .header("api-key", &self.api_key)
+            .header("Content-Type", "application/json")
+            .json(&body)
+            .send()
+            .await
+            .context("Failed to send request to Azure OpenAI")?;
+
+        let status = response.status();
+        if !status.is_success() {
+            let error_text = response
+                .text()
+                .await
+                .unwrap_or_else(|_| "Unknown error".to_string());
+            return Err(anyhow!(
+                "Azure OpenAI request failed with status {}: {}",
+                status,
+                error_text
+            ));
+        }
+
+        let body: Value = response
+            .json()
+            .await
+            .context("Failed to parse Azure OpenAI response")?;
+        openai::parse_response(body)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_chat_url_and_debug() {
+        let p = AzureOpenAiProvider::new(
+            "azure_secret".to_string(),
+            "https://contoso.openai.azure.com/".to_string(),
+            "gpt-4o-prod".to_string(),
+            "2024-10-21".to_string(),
+            4096,
+        );
+        assert_eq!(
+            p.chat_url(),
+            "https://contoso.openai.azure.com/openai/deployments/gpt-4o-prod/chat/completions?api-version=2024-10-21"
+        );
+        assert_eq!(p.provider_name(), "azure");
+        assert_eq!(p.model(), "gpt-4o-prod");
+        assert!(!format!("{:?}", p).contains("azure_secret"));
+    }
+}
@@ -0,0 +1,350 @@
+//! AWS Bedrock provider (Claude models)
+//!
+//! Sends Anthropic Messages API bodies to the Bedrock runtime `invoke`
+//! endpoint, signed with AWS Signature Version 4.
+
+use anyhow::{Context, Result, anyhow};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use reqwest::Client;
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use std::time::Duration;
+
+use crate::api::ToolDefinition;
+use crate::uri;
+
+use super::anthropic;
+use super::types::{ChatMessage, ChatResponse, LlmProvider};
+
+/// Messages API version Bedrock expects in the body
+const BEDROCK_ANTHROPIC_VERSION: &str = "bedrock-2023-05-31";
+
+/// AWS credentials used to sign requests
+#[derive(Clone)]
+pub struct AwsCredentials {
+    pub access_key_id: String,
+    pub secret_access_key: String,
+    /// Set for temporary (STS / SSO) credentials
+    pub session_token: Option<String>,
+}
+
+impl std::fmt::Debug for AwsCredentials {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("AwsCredentials")
+            .field("access_key_id", &self.access_key_id)
+            .field("session_token", &self.session_token.is_some())
+            .finish()
+    }
+}
+
+/// AWS Bedrock provider
+pub struct BedrockProvider {
+    client: Client,
+    credentials: AwsCredentials,
+    region: String,
+    model: String,
+    max_tokens: u32,
+}
+
+impl std::fmt::Debug for BedrockProvider {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("BedrockProvider")
+            .field("region", &self.region)
+            .field("model", &self.model)
+            .field("max_tokens", &self.max_tokens)
+            .finish()
+    }
+}
+
+impl BedrockProvider {
+    /// `model` is a Bedrock model or inference profile ID, e.g.
+    /// `us.anthropic.claude-sonnet-4-20250514-v1:0`
+    pub fn new(
+        credentials: AwsCredentials,
+        region: String,
+        model: String,
+        max_tokens: u32,
+    ) -> Self {
+        let client = Client::builder()
+            .timeout(Duration::from_secs(120))
+            .build()
+            .expect("Failed to build HTTP client");
+
+        Self {
+            client,
+            credentials,
+            region,
+            model,
+            max_tokens,
+        }
+    }
+
+    fn host(&self) -> String {
+        format!("bedrock-runtime.{}.amazonaws.com", self.region)
+    }
+
+    /// Signed headers for a POST of `payload` to `path` (already encoded)
+    fn signed_headers(
+        &self,
+        path: &str,
+        payload: &[u8],
+        now: DateTime<Utc>,
+    ) -> Vec<(String, String)> {
+        let amz_date = now.format("%Y%m%dT%H%M%SZ").to_string();
+        let mut headers = vec![
+            ("content-type".to_string(), "application/json".to_string()),
+            ("host".to_string(), self.host()),
+            ("x-amz-date".to_string(), amz_date),
+        ];
+        if let Some(token) = &self.credentials.session_token {
+            headers.push(("x-amz-security-token".to_string(), token.clone()));
+        }
+        // Non-S3 services sign the path with each segment encoded again
+        let canonical_uri = path
+            .split('/')
+            .map(|segment| uri::encode(segment, true))
+            .collect::<Vec<_>>()
+            .join("/");
+        let authorization = sign_v4(
+            &self.credentials,
+            &self.region,
+            "bedrock",
+            "POST",
+            &canonical_uri,
+            &headers,
+            payload,
+            now,
+        );
+        headers.push(("authorization".to_string(), authorization));
+        headers
+    }
+}
+
+#[async_trait]
+impl LlmProvider for BedrockProvider {
+    fn provider_name(&self) -> &str {
+        "bedrock"
+    }
+
+    fn model(&self) -> &str {
+        &self.model
+    }
+
+    async fn chat(
+        &self,
+        messages: &[ChatMessage],
+        tools: &[ToolDefinition],
+        system: &str,
+    ) -> Result<ChatResponse> {
+        let overrides = super::overrides::current();
+        let model = overrides.model_for("bedrock", &self.model);
+        let mut body =
+            anthropic::request_body(model, self.max_tokens, messages, tools, system, &overrides)?;
+        // The model goes in the URL instead
+        if let Some(obj) = body.as_object_mut() {
+            obj.remove("model");
+            obj.insert(
+                "anthropic_version".to_string(),
+                Value::String(BEDROCK_ANTHROPIC_VERSION.to_string()),
+            );
+        }
+        let payload = serde_json::to_vec(&body)?;
+
+        let path = format!("/model/{}/invoke", uri::encode(model, true));
+        let url = format!("https://{}{}", self.host(), path);
+        let mut request = self.client.post(&url);
+        for (name, value) in self.signed_headers(&path, &payload, Utc::now()) {
+            if name != "host" {
+                request = request.header(name, value);
+            }
+        }
+
+        let response = request
+            .body(payload)
+            .send()
+            .await
+            .context("Failed to send request to AWS Bedrock")?;
+
+        let status = response.status();
+        if !status.is_success() {
+            let error_text = response
+                .text()
+                .await
+                .unwrap_or_else(|_| "Unknown error".to_string());
+            return Err(anyhow!(
+                "Bedrock request failed with status {}: {}",
+                status,
+                error_text
+            ));
+        }
+
+        let body: Value = response
+            .json()
+            .await
+            .context("Failed to parse Bedrock response")?;
+        anthropic::parse_response(body)
+    }
+}
+
+/// The `Authorization` header value for a SigV4-signed request. `headers`
+/// must hold every header to sign, with lowercase names; the query string
+/// is assumed empty.
+#[allow(clippy::too_many_arguments)]
+fn sign_v4(
+    credentials: &AwsCredentials,
+    region: &str,
+    service: &str,
+    method: &str,
+    canonical_uri: &str,
+    headers: &[(String, String)],
+    payload: &[u8],
+    now: DateTime<Utc>,
+) -> String {
+    let amz_date = now.format("%Y%m%dT%H%M%SZ").to_string();
+    let date = now.format("%Y%m%d").to_string();
+
+    let mut headers: Vec<&(String, String)> = headers.iter().collect();
+    headers.sort_by(|a, b| a.0.cmp(&b.0));
+    let canonical_headers: String = headers
+        .iter()
+        .map(|(name, value)| format!("{}:{}\n", name, value.trim()))
+        .collect();
+    let signed_headers = headers
+        .iter()
+        .map(|(name, _)| name.as_str())
+        .collect::<Vec<_>>()
+        .join(";");
+
+    let canonical_request = format!(
+        "{}\n{}\n\n{}\n{}\n{}",
+        method,
+        canonical_uri,
+        canonical_headers,
+        signed_headers,
+        hex(&Sha256::digest(payload))
+    );
+    let scope = format!("{}/{}/{}/aws4_request", date, region, service);
+    let string_to_sign = format!(
+        "AWS4-HMAC-SHA256\n{}\n{}\n{}",
+        amz_date,
+        scope,
+        hex(&Sha256::digest(canonical_request.as_bytes()))
+    );
+
+    let secret = format!("AWS4{}", credentials.secret_access_key);
+    let key = hmac_sha256(secret.as_bytes(), date.as_bytes());
+    let key = hmac_sha256(&key, region.as_bytes());
+    let key = hmac_sha256(&key, service.as_bytes());
+    let key = hmac_sha256(&key, b"aws4_request");
+    let signature = hex(&hmac_sha256(&key, string_to_sign.as_bytes()));
+
+    format!(
+        "AWS4-HMAC-SHA256 Credential={}/{}, SignedHeaders={}, Signature={}",
+        credentials.access_key_id, scope, signed_headers, signature
+    )
+}
+
+/// HMAC-SHA256 (RFC 2104)
+fn hmac_sha256(key: &[u8], message: &[u8]) -> [u8; 32] {
+    const BLOCK_SIZE: usize = 64;
+    let mut block = [0u8; BLOCK_SIZE];
+    if key.len() > BLOCK_SIZE {
+        block[..32].copy_from_slice(&Sha256::digest(key));
+    } else {
+        block[..key.len()].copy_from_slice(key);
+    }
+
+    let mut inner = Sha256::new();
+    inner.update(block.map(|b| b ^ 0x36));
+    inner.update(message);
+    let mut outer = Sha256::new();
+    outer.update(block.map(|b| b ^ 0x5c));
+    outer.update(inner.finalize());
+    outer.finalize().into()
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn example_credentials() -> AwsCredentials {
+        AwsCredentials {
+            access_key_id: "AKIDEXAMPLE".to_string(),
+            secret_access_key: "wJalrXUtnFEMI/K7MDENG+bPxRfiCYEXAMPLEKEY".to_string(),
+            session_token: None,
+        }
+    }
+
+    #[test]
+    fn test_hmac_sha256_rfc4231() {
+        let mac = hmac_sha256(b"Jefe", b"what do ya want for nothing?");
+        assert_eq!(
+            hex(&mac),
+            "5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+        );
+    }
+
+    #[test]
+    fn test_sign_v4_matches_aws_test_suite() {
+        // "get-vanilla" from the AWS SigV4 test suite
+        let now = Utc.with_ymd_and_hms(2015, 8, 30, 12, 36, 0).unwrap();
+        let headers = vec![
+            ("host".to_string(), "example.amazonaws.com".to_string()),
+            ("x-amz-date".to_string(), "20150830T123600Z".to_string()),
+        ];
+        let auth = sign_v4(
+            &example_credentials(),
+            "us-east-1",
+            "service",
+            "GET",
+            "/",
+            &headers,
+            b"",
+            now,
+        );
+        assert_eq!(
+            auth,
+            "AWS4-HMAC-SHA256 Credential=AKIDEXAMPLE/20150830/us-east-1/service/aws4_request, \
+             SignedHeaders=host;x-amz-date, \
+             Signature=5fa00fa31553b73ebf1942676e86291e8372ff2a2260956d9b8aae1d763fbf31"
+        );
+    }
+
+    #[test]
+    fn test_signed_headers_for_model_path() {
+        let mut credentials = example_credentials();
+        credentials.session_token = Some("session".to_string());
+        let p = BedrockProvider::new(
+            credentials,
+            "us-west-2".to_string(),
+            "us.anthropic.claude-sonnet-4-20250514-v1:0".to_string(),
+            4096,
+        );
+        let path = format!("/model/{}/invoke", uri::encode(p.model(), true));
+        assert_eq!(
+            path,
+            "/model/us.anthropic.claude-sonnet-4-20250514-v1%3A0/invoke"
+        );
+
+        let now = Utc.with_ymd_and_hms(2025, 1, 2, 3, 4, 5).unwrap();
+        let headers = p.signed_headers(&path, b"{}", now);
+        let get = |name: &str| {
+            headers
+                .iter()
+                .find(|(n, _)| n == name)
+                .map(|(_, v)| v.as_str())
+        };
+        assert_eq!(get("host"), Some("bedrock-runtime.us-west-2.amazonaws.com"));
+        assert_eq!(get("x-amz-security-token"), Some("session"));
+        let auth = get("authorization").unwrap();
+        assert!(auth.contains("Credential=AKIDEXAMPLE/20250102/us-west-2/bedrock/aws4_request"));
+        assert!(auth.contains("SignedHeaders=content-type;host;x-amz-date;x-amz-security-token"));
+        assert!(!format!("{:?}", p.credentials).contains("EXAMPLEKEY"));
+    }
+}
@@ -5,6 +5,7 @@ use async_trait::async_trait;
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
+use std::collections::HashMap;
 use std::time::Duration;
 use tracing::debug;
 
@@ -49,6 +50,22 @@ impl GoogleProvider {
 
     /// Convert provider-agnostic messages to Gemini wire format
     fn to_gemini_contents(messages: &[ChatMessage]) -> Vec<GeminiContent> {
+        // Gemini matches function responses by function name, not call ID,
+        // so look up the name behind each ID (calls may come from another
+        // provider after failover)
+        let call_names: HashMap<&str, &str> = messages
+            .iter()
+            .filter_map(|m| match &m.content {
+                ChatMessageContent::Blocks(blocks) => Some(blocks),
+                ChatMessageContent::Text(_) => None,
+            })
+            .flatten()
+            .filter_map(|b| match b {
+                ChatBlock::ToolCall { id, name, .. } => Some((id.as_str(), name.as_str())),
+                _ => None,
+            })
+            .collect();
+
         messages
             .iter()
             .filter(|m| m.role != ChatRole::System)
@@ -64,23 +81,31 @@ impl GoogleProvider {
                     }
                     ChatMessageContent::Blocks(blocks) => blocks
                         .iter()
-                        .map(|b| match b {
-                            ChatBlock::Text { text } => GeminiPart::Text { text: text.clone() },
-                            ChatBlock::ToolCall { name, input, .. } => GeminiPart::FunctionCall {
-                                function_call: GeminiFunctionCall {
-                                    name: name.clone(),
-                                    args: input.clone(),
-                                },
-                            },
+                        .filter_map(|b| match b {
+                            ChatBlock::Text { text } => {
+                                Some(GeminiPart::Text { text: text.clone() })
+                            }
+                            ChatBlock::ToolCall { name, input, .. } => {
+                                Some(GeminiPart::FunctionCall {
+                                    function_call: GeminiFunctionCall {
+                                        name: name.clone(),
+                                        args: input.clone(),
+                                    },
+                                })
+                            }
                             ChatBlock::ToolResult {
                                 content,
                                 tool_call_id,
-                            } => GeminiPart::FunctionResponse {
+                            } => Some(GeminiPart::FunctionResponse {
                                 function_response: GeminiFunctionResponse {
-                                    name: tool_call_id.clone(),
+                                    name: call_names
+                                        .get(tool_call_id.as_str())
+                                        .map_or_else(|| tool_call_id.clone(), |n| n.to_string()),
                                     response: serde_json::json!({"result": content}),
                                 },
-                            },
+                            }),
+                            // Anthropic-specific; Gemini can't take it back
+                            ChatBlock::Thinking { .. } | ChatBlock::RedactedThinking { .. } => None,
                         })
                         .collect(),
                 };
@@ -89,6 +114,9 @@ impl GoogleProvider {
                     parts,
                 }
             })
+            // Gemini rejects contents without parts (e.g. a turn that was
+            // only Anthropic thinking)
+            .filter(|c| !c.parts.is_empty())
             .collect()
     }
 
@@ -103,7 +131,7 @@ impl GoogleProvider {
                 .map(|t| GeminiFunctionDeclaration {
                     name: t.name.clone(),
                     description: t.description.clone(),
-                    parameters: t.input_schema.clone(),
+                    parameters: gemini_schema(&t.input_schema),
                 })
                 .collect(),
         }]
@@ -120,15 +148,17 @@ impl GoogleProvider {
         let mut blocks = Vec::new();
         let mut has_tool_calls = false;
 
-        for part in candidate.content.parts {
+        for (i, part) in candidate.content.parts.into_iter().enumerate() {
             match part {
                 GeminiPart::Text { text } => {
                     blocks.push(ChatResponseBlock::Text { text });
                 }
                 GeminiPart::FunctionCall { function_call } => {
                     has_tool_calls = true;
+                    // Gemini doesn't ID calls; the index keeps parallel
+                    // calls to the same function apart
                     blocks.push(ChatResponseBlock::ToolCall {
-                        id: format!("gemini_{}", function_call.name),
+                        id: format!("gemini_{}_{}", i, function_call.name),
                         name: function_call.name,
                         input: function_call.args,
                     });
@@ -178,9 +208,11 @@ impl LlmProvider for GoogleProvider {
         tools: &[ToolDefinition],
         system: &str,
     ) -> Result<ChatResponse> {
+        let overrides = super::overrides::current();
+        let model = overrides.model_for("google", &self.model);
         let url = format!(
             "https://generativelanguage.googleapis.com/v1beta/models/{}:generateContent?key={}",
-            self.model, self.api_key
+            model, self.api_key
         );
 
         let contents = Self::to_gemini_contents(messages);
@@ -199,10 +231,13 @@ impl LlmProvider for GoogleProvider {
         if !gemini_tools.is_empty() {
             body["tools"] = serde_json::to_value(&gemini_tools)?;
         }
+        if let Some(temperature) = overrides.temperature {
+            body["generationConfig"]["temperature"] = serde_json::json!(temperature);
+        }
 
         debug!(
             "Gemini request: model={}, contents={}",
-            self.model,
+            model,
             contents.len()
         );
 
@@ -242,6 +277,70 @@ impl LlmProvider for GoogleProvider {
     }
 }
 
+/// JSON Schema keywords Gemini function declarations accept
+const GEMINI_SCHEMA_KEYS: &[&str] = &[
+    "type",
+    "format",
+    "title",
+    "description",
+    "nullable",
+    "enum",
+    "items",
+    "minItems",
+    "maxItems",
+    "properties",
+    "required",
+    "minProperties",
+    "maxProperties",
+    "minLength",
+    "maxLength",
+    "pattern",
+    "minimum",
+    "maximum",
+    "anyOf",
+    "default",
+];
+
+/// Reduce a tool's JSON Schema to the subset Gemini accepts: unsupported
+/// keywords (`additionalProperties`, `$schema`, ...) are dropped and
+/// `"type": ["string", "null"]` becomes `"type": "string", "nullable": true`
+fn gemini_schema(schema: &Value) -> Value {
+    let Value::Object(map) = schema else {
+        return schema.clone();
+    };
+    let mut out = serde_json::Map::new();
+    for (key, value) in map {
+        if !GEMINI_SCHEMA_KEYS.contains(&key.as_str()) {
+            continue;
+        }
+        let value = match (key.as_str(), value) {
+            ("type", Value::Array(types)) => {
+                if types.iter().any(|t| t == "null") {
+                    out.insert("nullable".to_string(), Value::Bool(true));
+                }
+                types
+                    .iter()
+                    .find(|t| *t != "null")
+                    .cloned()
+                    .unwrap_or_else(|| Value::String("string".to_string()))
+            }
+            ("properties", Value::Object(props)) => Value::Object(
+                props
+                    .iter()
+                    .map(|(name, prop)| (name.clone(), gemini_schema(prop)))
+                    .collect(),
+            ),
+            ("items", items) => gemini_schema(items),
+            ("anyOf", Value::Array(options)) => {
+                Value::Array(options.iter().map(gemini_schema).collect())
+            }
+            _ => value.clone(),
+        };
+        out.insert(key.clone(), value);
+    }
+    Value::Object(out)
+}
+
 // ── Gemini wire types ──
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -418,6 +517,102 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_parallel_calls_and_results_round_trip() {
+        let call = |q: &str| GeminiPart::FunctionCall {
+            function_call: GeminiFunctionCall {
+                name: "search".to_string(),
+                args: serde_json::json!({"q": q}),
+            },
+        };
+        let resp = GeminiApiResponse {
+            candidates: vec![GeminiCandidate {
+                content: GeminiContent {
+                    role: "model".to_string(),
+                    parts: vec![call("rust"), call("go")],
+                },
+                finish_reason: Some("STOP".to_string()),
+            }],
+            usage_metadata: None,
+        };
+        let result = GoogleProvider::from_gemini_response(resp).unwrap();
+        let ids: Vec<String> = result
+            .blocks
+            .iter()
+            .filter_map(|b| match b {
+                ChatResponseBlock::ToolCall { id, .. } => Some(id.clone()),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(ids.len(), 2);
+        assert_ne!(ids[0], ids[1]);
+
+        // Results go back named after the function; calls made by another
+        // provider before failover resolve the same way
+        let mut assistant: Vec<ChatBlock> =
+            result.blocks.iter().map(|b| b.to_chat_block()).collect();
+        assistant.push(ChatBlock::ToolCall {
+            id: "toolu_01".to_string(),
+            name: "read_file".to_string(),
+            input: serde_json::json!({}),
+        });
+        let results = ids
+            .iter()
+            .map(String::as_str)
+            .chain(["toolu_01"])
+            .map(|id| ChatBlock::ToolResult {
+                tool_call_id: id.to_string(),
+                content: "ok".to_string(),
+            })
+            .collect();
+        let msgs = vec![
+            ChatMessage {
+                role: ChatRole::Assistant,
+                content: ChatMessageContent::Blocks(assistant),
+            },
+            ChatMessage {
+                role: ChatRole::User,
+                content: ChatMessageContent::Blocks(results),
+            },
+        ];
+        let contents = serde_json::to_value(GoogleProvider::to_gemini_contents(&msgs)).unwrap();
+        assert_eq!(contents[0]["parts"].as_array().unwrap().len(), 3);
+        let names: Vec<&str> = contents[1]["parts"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|p| p["functionResponse"]["name"].as_str().unwrap())
+            .collect();
+        assert_eq!(names, ["search", "search", "read_file"]);
+    }
+
+    #[test]
+    fn test_gemini_schema_drops_unsupported_keywords() {
+        let schema = serde_json::json!({
+            "$schema": "http://json-schema.org/draft-07/schema#",
+            "type": "object",
+            "additionalProperties": false,
+            "properties": {
+                "path": {"type": "string", "description": "File path"},
+                "limit": {"type": ["integer", "null"], "minimum": 1},
+                "tags": {"type": "array", "items": {"type": "string", "const": "x"}},
+            },
+            "required": ["path"],
+        });
+        assert_eq!(
+            gemini_schema(&schema),
+            serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "path": {"type": "string", "description": "File path"},
+                    "limit": {"type": "integer", "nullable": true, "minimum": 1},
+                    "tags": {"type": "array", "items": {"type": "string"}},
+                },
+                "required": ["path"],
+            })
+        );
+    }
+
     #[test]
     fn test_from_gemini_response_no_candidates() {
         let resp = GeminiApiResponse {
@@ -1,15 +1,22 @@
 //! Multi-provider LLM abstraction layer
 //!
 //! Supports multiple LLM providers: Anthropic, OpenAI, Google Gemini, Ollama,
-//! and any OpenAI-compatible endpoint. Providers implement the [`LlmProvider`]
+//! Azure OpenAI, AWS Bedrock, and any OpenAI-compatible endpoint. Providers implement the [`LlmProvider`]
 //! trait and are composed via [`ModelRouter`] for automatic failover.
+//! [`RecordReplayProvider`] records responses to fixtures and replays them
+//! for offline, deterministic runs.
 
 pub mod anthropic;
+pub mod azure;
+pub mod bedrock;
 pub mod google;
 pub mod openai;
 pub mod openai_compat;
+pub mod overrides;
+pub mod replay;
 pub mod router;
 pub mod types;
 
+pub use replay::{RecordReplayProvider, ReplayMode};
 pub use router::ModelRouter;
 pub use types::{ChatMessage, ChatMessageContent, ChatResponse, ChatResponseBlock, LlmProvider};
@@ -6,10 +6,11 @@ use reqwest::Client;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use std::time::Duration;
-use tracing::debug;
+use tracing::{debug, warn};
 
 use crate::api::ToolDefinition;
 
+use super::overrides::RequestOverrides;
 use super::types::{
     ChatBlock, ChatMessage, ChatMessageContent, ChatResponse, ChatResponseBlock, ChatRole,
     ChatUsage, LlmProvider, StopReason,
@@ -89,10 +90,16 @@ impl OpenAiProvider {
                                     },
                                 });
                             }
-                            ChatBlock::ToolResult { .. } => {}
+                            ChatBlock::ToolResult { .. }
+                            | ChatBlock::Thinking { .. }
+                            | ChatBlock::RedactedThinking { .. } => {}
                         }
                     }
 
+                    if text_parts.is_empty() && tool_calls.is_empty() {
+                        // Nothing OpenAI can take (e.g. only Anthropic thinking)
+                        continue;
+                    }
                     let content = if text_parts.is_empty() {
                         None
                     } else {
@@ -128,7 +135,9 @@ impl OpenAiProvider {
                                     tool_call_id: Some(tool_call_id.clone()),
                                 });
                             }
-                            ChatBlock::ToolCall { .. } => {}
+                            ChatBlock::ToolCall { .. }
+                            | ChatBlock::Thinking { .. }
+                            | ChatBlock::RedactedThinking { .. } => {}
                         }
                     }
 
@@ -156,7 +165,7 @@ impl OpenAiProvider {
                 function: OpenAiToolFunction {
                     name: t.name.clone(),
                     description: t.description.clone(),
-                    parameters: t.input_schema.clone(),
+                    parameters: function_parameters(&t.input_schema),
                 },
             })
             .collect()
@@ -178,19 +187,37 @@ impl OpenAiProvider {
             blocks.push(ChatResponseBlock::Text { text: content });
         }
 
-        if let Some(tool_calls) = choice.message.tool_calls {
-            for tc in tool_calls {
-                let input: Value = serde_json::from_str(&tc.function.arguments)
-                    .unwrap_or(Value::Object(serde_json::Map::new()));
-                blocks.push(ChatResponseBlock::ToolCall {
-                    id: tc.id,
-                    name: tc.function.name,
-                    input,
-                });
-            }
+        let tool_calls = choice.message.tool_calls.unwrap_or_default();
+        let has_tool_calls = !tool_calls.is_empty();
+        for (i, tc) in tool_calls.into_iter().enumerate() {
+            let arguments = tc.function.arguments.trim();
+            let input: Value = if arguments.is_empty() {
+                Value::Object(serde_json::Map::new())
+            } else {
+                serde_json::from_str(arguments).unwrap_or_else(|e| {
+                    warn!(
+                        "Invalid arguments for tool call {}: {}",
+                        tc.function.name, e
+                    );
+                    Value::Object(serde_json::Map::new())
+                })
+            };
+            // Some compatible servers leave out call IDs
+            let id = if tc.id.is_empty() {
+                format!("call_{}_{}", i, tc.function.name)
+            } else {
+                tc.id
+            };
+            blocks.push(ChatResponseBlock::ToolCall {
+                id,
+                name: tc.function.name,
+                input,
+            });
         }
 
+        // Ollama and others report "stop" alongside tool calls
         let stop_reason = match choice.finish_reason.as_deref() {
+            _ if has_tool_calls => StopReason::ToolUse,
             Some("tool_calls") => StopReason::ToolUse,
             Some("stop") => StopReason::EndTurn,
             Some("length") => StopReason::MaxTokens,
@@ -216,6 +243,10 @@ impl LlmProvider for OpenAiProvider {
         "openai"
     }
 
+    fn is_local(&self) -> bool {
+        crate::network::is_local_url(&self.base_url)
+    }
+
     fn model(&self) -> &str {
         &self.model
     }
@@ -227,23 +258,9 @@ impl LlmProvider for OpenAiProvider {
         system: &str,
     ) -> Result<ChatResponse> {
         let url = format!("{}/v1/chat/completions", self.base_url);
-        let openai_messages = Self::to_openai_messages(messages, system);
-
-        let mut body = serde_json::json!({
-            "model": self.model,
-            "max_tokens": self.max_tokens,
-            "messages": openai_messages,
-        });
-
-        if !tools.is_empty() {
-            body["tools"] = serde_json::to_value(Self::to_openai_tools(tools))?;
-        }
-
-        debug!(
-            "OpenAI request: model={}, messages={}",
-            self.model,
-            openai_messages.len()
-        );
+        let overrides = super::overrides::current();
+        let model = overrides.model_for("openai", &self.model);
+        let body = request_body(model, self.max_tokens, messages, tools, system, &overrides)?;
 
         let response = self
             .client
@@ -268,18 +285,70 @@ impl LlmProvider for OpenAiProvider {
             ));
         }
 
-        let api_response: OpenAiApiResponse = response
+        let body: Value = response
             .json()
             .await
             .context("Failed to parse OpenAI API response")?;
+        parse_response(body)
+    }
+}
 
-        debug!(
-            "OpenAI response: choices={}, finish_reason={:?}",
-            api_response.choices.len(),
-            api_response.choices.first().map(|c| &c.finish_reason)
-        );
+/// Build a chat completions request body. Also used by the Azure provider.
+pub(super) fn request_body(
+    model: &str,
+    max_tokens: u32,
+    messages: &[ChatMessage],
+    tools: &[ToolDefinition],
+    system: &str,
+    overrides: &RequestOverrides,
+) -> Result<Value> {
+    let openai_messages = OpenAiProvider::to_openai_messages(messages, system);
+    let mut body = serde_json::json!({
+        "model": model,
+        "max_tokens": max_tokens,
+        "messages": openai_messages,
+    });
+
+    if !tools.is_empty() {
+        body["tools"] = serde_json::to_value(OpenAiProvider::to_openai_tools(tools))?;
+    }
+    if let Some(temperature) = overrides.temperature {
+        body["temperature"] = serde_json::json!(temperature);
+    }
+
+    debug!(
+        "OpenAI request: model={}, messages={}",
+        model,
+        openai_messages.len()
+    );
+    Ok(body)
+}
+
+/// Parse a chat completions response body
+pub(super) fn parse_response(body: Value) -> Result<ChatResponse> {
+    let api_response: OpenAiApiResponse =
+        serde_json::from_value(body).context("Failed to parse OpenAI API response")?;
+
+    debug!(
+        "OpenAI response: choices={}, finish_reason={:?}",
+        api_response.choices.len(),
+        api_response.choices.first().map(|c| &c.finish_reason)
+    );
 
-        Self::from_openai_response(api_response)
+    OpenAiProvider::from_openai_response(api_response)
+}
+
+/// Function parameters must be an object schema; tools without one get an
+/// empty object
+fn function_parameters(schema: &Value) -> Value {
+    match schema {
+        Value::Object(map) if map.get("type").is_some_and(|t| t == "object") => schema.clone(),
+        Value::Object(map) if map.contains_key("properties") => {
+            let mut map = map.clone();
+            map.insert("type".to_string(), Value::String("object".to_string()));
+            Value::Object(map)
+        }
+        _ => serde_json::json!({"type": "object", "properties": {}}),
     }
 }
 
@@ -298,11 +367,17 @@ struct OpenAiMessage {
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 struct OpenAiToolCall {
+    #[serde(default)]
     id: String,
+    #[serde(default = "default_tool_call_type")]
     r#type: String,
     function: OpenAiFunction,
 }
 
+fn default_tool_call_type() -> String {
+    "function".to_string()
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 struct OpenAiFunction {
     name: String,
@@ -460,6 +535,81 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_parallel_tool_calls_from_compatible_server() {
+        // Ollama-style: no IDs, empty arguments, finish_reason "stop"
+        let json = serde_json::json!({
+            "choices": [{
+                "message": {
+                    "content": "",
+                    "tool_calls": [
+                        {"function": {"name": "search", "arguments": "{\"q\":\"rust\"}"}},
+                        {"function": {"name": "list_goals", "arguments": ""}},
+                    ],
+                },
+                "finish_reason": "stop",
+            }],
+        });
+        let resp: OpenAiApiResponse = serde_json::from_value(json).unwrap();
+        let result = OpenAiProvider::from_openai_response(resp).unwrap();
+        assert_eq!(result.stop_reason, StopReason::ToolUse);
+        let calls: Vec<(&str, &Value)> = result
+            .blocks
+            .iter()
+            .filter_map(|b| match b {
+                ChatResponseBlock::ToolCall { id, input, .. } => Some((id.as_str(), input)),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(calls.len(), 2);
+        assert_ne!(calls[0].0, calls[1].0);
+        assert_eq!(calls[0].1["q"], "rust");
+        assert_eq!(*calls[1].1, serde_json::json!({}));
+
+        // Both results follow the assistant turn as tool messages
+        let mut assistant: Vec<ChatBlock> =
+            result.blocks.iter().map(|b| b.to_chat_block()).collect();
+        assistant.insert(
+            0,
+            ChatBlock::Thinking {
+                thinking: "from a failed-over provider".to_string(),
+                signature: "sig".to_string(),
+            },
+        );
+        let results = calls
+            .iter()
+            .map(|(id, _)| ChatBlock::ToolResult {
+                tool_call_id: id.to_string(),
+                content: "ok".to_string(),
+            })
+            .collect();
+        let msgs = vec![
+            ChatMessage {
+                role: ChatRole::Assistant,
+                content: ChatMessageContent::Blocks(assistant),
+            },
+            ChatMessage {
+                role: ChatRole::User,
+                content: ChatMessageContent::Blocks(results),
+            },
+        ];
+        let wire = OpenAiProvider::to_openai_messages(&msgs, "sys");
+        let roles: Vec<&str> = wire.iter().map(|m| m.role.as_str()).collect();
+        assert_eq!(roles, ["system", "assistant", "tool", "tool"]);
+        assert_eq!(wire[1].tool_calls.as_ref().unwrap().len(), 2);
+        assert_eq!(wire[3].tool_call_id.as_deref(), Some(calls[1].0));
+    }
+
+    #[test]
+    fn test_function_parameters_are_object_schemas() {
+        let props = serde_json::json!({"properties": {"q": {"type": "string"}}});
+        assert_eq!(function_parameters(&props)["type"], "object");
+        assert_eq!(
+            function_parameters(&serde_json::json!({})),
+            serde_json::json!({"type": "object", "properties": {}})
+        );
+    }
+
     #[test]
     fn test_from_openai_response_no_choices() {
         let resp = OpenAiApiResponse {
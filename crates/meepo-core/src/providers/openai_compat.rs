@@ -54,6 +54,10 @@ impl LlmProvider for OpenAiCompatProvider {
         self.inner.model()
     }
 
+    fn is_local(&self) -> bool {
+        self.inner.is_local()
+    }
+
     async fn chat(
         &self,
         messages: &[ChatMessage],
@@ -0,0 +1,121 @@
+//! Per-request generation overrides
+//!
+//! The agent runs a turn inside [`scope`] to change the model, temperature
+//! or thinking budget for just that turn. Providers read [`current`] when
+//! building a request, and [`ModelRouter`](super::ModelRouter) tries the
+//! provider that serves the requested model first.
+
+use serde::{Deserialize, Serialize};
+use std::future::Future;
+
+tokio::task_local! {
+    static OVERRIDES: RequestOverrides;
+}
+
+/// Model and sampling settings that replace the provider defaults
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct RequestOverrides {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub model: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub temperature: Option<f32>,
+    /// Extended thinking budget in tokens, for providers that support it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub thinking_budget: Option<u32>,
+}
+
+impl RequestOverrides {
+    pub fn is_empty(&self) -> bool {
+        self.model.is_none() && self.temperature.is_none() && self.thinking_budget.is_none()
+    }
+
+    /// The model a provider should use: the override if it belongs to this
+    /// provider (or to no known provider), otherwise the provider's own
+    pub fn model_for<'a>(&'a self, provider: &str, default: &'a str) -> &'a str {
+        match &self.model {
+            Some(model) if provider_for_model(model).is_none_or(|p| p == provider) => model,
+            _ => default,
+        }
+    }
+}
+
+/// Run a future with request overrides in effect
+pub async fn scope<F: Future>(overrides: RequestOverrides, fut: F) -> F::Output {
+    OVERRIDES.scope(overrides, fut).await
+}
+
+/// Overrides for the current request (empty outside [`scope`])
+pub fn current() -> RequestOverrides {
+    OVERRIDES.try_with(|o| o.clone()).unwrap_or_default()
+}
+
+/// Expand a short model alias ("haiku", "sonnet", "mini") to a model ID
+pub fn resolve_alias(name: &str) -> String {
+    match name.trim().to_lowercase().as_str() {
+        "haiku" => "claude-haiku-4-5".to_string(),
+        "sonnet" => "claude-sonnet-4-5".to_string(),
+        "opus" => "claude-opus-4-1".to_string(),
+        "gpt4o" | "4o" => "gpt-4o".to_string(),
+        "mini" | "4o-mini" => "gpt-4o-mini".to_string(),
+        "flash" | "gemini" => "gemini-2.0-flash".to_string(),
+        _ => name.trim().to_string(),
+    }
+}
+
+/// The provider that serves a model, if it follows a known naming scheme
+pub fn provider_for_model(model: &str) -> Option<&'static str> {
+    let model = model.to_lowercase();
+    if model.starts_with("claude") {
+        Some("anthropic")
+    } else if model.starts_with("gpt-")
+        || model.starts_with("o1")
+        || model.starts_with("o3")
+        || model.starts_with("o4")
+    {
+        Some("openai")
+    } else if model.starts_with("gemini") {
+        Some("google")
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_model_for_provider() {
+        let overrides = RequestOverrides {
+            model: Some(resolve_alias("haiku")),
+            ..Default::default()
+        };
+        assert_eq!(
+            overrides.model_for("anthropic", "claude-sonnet-4-5"),
+            "claude-haiku-4-5"
+        );
+        // A Claude model isn't sent to OpenAI
+        assert_eq!(overrides.model_for("openai", "gpt-4o"), "gpt-4o");
+
+        // Unknown families (e.g. local models) go to whoever serves the request
+        let local = RequestOverrides {
+            model: Some("llama3.2".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(local.model_for("openai", "gpt-4o"), "llama3.2");
+        assert_eq!(provider_for_model("o3-mini"), Some("openai"));
+        assert_eq!(resolve_alias("Flash"), "gemini-2.0-flash");
+    }
+
+    #[tokio::test]
+    async fn test_scope() {
+        assert!(current().is_empty());
+        let overrides = RequestOverrides {
+            temperature: Some(0.2),
+            ..Default::default()
+        };
+        let seen = scope(overrides.clone(), async { current() }).await;
+        assert_eq!(seen, overrides);
+        assert!(current().is_empty());
+    }
+}
@@ -0,0 +1,433 @@
+//! Record/replay layer for LLM providers
+//!
+//! [`RecordReplayProvider`] wraps another provider. In record mode every
+//! request/response pair is written to a JSON fixture named by a hash of the
+//! request; in replay mode responses are served from those fixtures without
+//! touching the network. This makes agent, orchestrator and goal-evaluation
+//! logic testable without API keys, and lets odd agent behavior be replayed
+//! offline.
+//!
+//! The hash covers the system prompt, messages and tool names. The
+//! `# CURRENT TIME` section of the system prompt is masked so fixtures stay
+//! valid across runs. When the same request is made several times, the
+//! recorded responses are served in order and the last one repeats.
+
+use anyhow::{Context, Result, anyhow};
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use tokio::sync::Mutex;
+use tracing::debug;
+
+use crate::api::ToolDefinition;
+
+use super::types::{ChatMessage, ChatResponse, LlmProvider};
+
+/// System prompt section whose contents change every run
+const TIME_HEADER: &str = "# CURRENT TIME\n\n";
+
+/// Whether to capture live responses or serve recorded ones
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReplayMode {
+    /// Call the wrapped provider and save each request/response pair
+    Record,
+    /// Serve saved responses; never call a provider
+    Replay,
+}
+
+impl FromStr for ReplayMode {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.trim().to_lowercase().as_str() {
+            "record" => Ok(Self::Record),
+            "replay" => Ok(Self::Replay),
+            other => Err(anyhow!(
+                "Invalid replay mode '{}'. Use record or replay",
+                other
+            )),
+        }
+    }
+}
+
+/// One fixture file: a request and the responses recorded for it
+#[derive(Debug, Serialize, Deserialize)]
+struct Fixture {
+    hash: String,
+    provider: String,
+    model: String,
+    system: String,
+    messages: Vec<ChatMessage>,
+    tools: Vec<String>,
+    responses: Vec<ChatResponse>,
+}
+
+/// Provider wrapper that records responses to fixtures or replays them
+pub struct RecordReplayProvider {
+    mode: ReplayMode,
+    dir: PathBuf,
+    inner: Option<Box<dyn LlmProvider>>,
+    provider_name: String,
+    model: String,
+    /// Replay: next response index per request hash
+    cursors: Mutex<HashMap<String, usize>>,
+    /// Record: request hashes already written this session
+    recorded: Mutex<HashSet<String>>,
+}
+
+impl RecordReplayProvider {
+    /// Wrap a live provider and record its responses into `dir`
+    pub fn record(inner: Box<dyn LlmProvider>, dir: impl Into<PathBuf>) -> Self {
+        let provider_name = inner.provider_name().to_string();
+        let model = inner.model().to_string();
+        Self {
+            mode: ReplayMode::Record,
+            dir: dir.into(),
+            inner: Some(inner),
+            provider_name,
+            model,
+            cursors: Mutex::new(HashMap::new()),
+            recorded: Mutex::new(HashSet::new()),
+        }
+    }
+
+    /// Serve responses recorded in `dir` without any network access
+    pub fn replay(dir: impl Into<PathBuf>) -> Self {
+        Self {
+            mode: ReplayMode::Replay,
+            dir: dir.into(),
+            inner: None,
+            provider_name: "replay".to_string(),
+            model: "replay".to_string(),
+            cursors: Mutex::new(HashMap::new()),
+            recorded: Mutex::new(HashSet::new()),
+        }
+    }
+
+    pub fn mode(&self) -> ReplayMode {
+        self.mode
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Stable hash identifying a request
+    pub fn request_hash(
+        messages: &[ChatMessage],
+        tools: &[ToolDefinition],
+        system: &str,
+    ) -> String {
+        let key = serde_json::json!({
+            "system": normalize_system(system),
+            "messages": messages,
+            "tools": tool_names(tools),
+        });
+        let digest = Sha256::digest(key.to_string().as_bytes());
+        digest.iter().map(|b| format!("{:02x}", b)).collect()
+    }
+
+    fn fixture_path(&self, hash: &str) -> PathBuf {
+        self.dir.join(format!("{}.json", hash))
+    }
+
+    async fn load(&self, hash: &str) -> Result<Option<Fixture>> {
+        let path = self.fixture_path(hash);
+        match tokio::fs::read_to_string(&path).await {
+            Ok(raw) => {
+                Ok(Some(serde_json::from_str(&raw).with_context(|| {
+                    format!("Invalid LLM fixture {}", path.display())
+                })?))
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e).with_context(|| format!("Failed to read {}", path.display())),
+        }
+    }
+
+    async fn replay_response(&self, hash: &str) -> Result<ChatResponse> {
+        let fixture = self.load(hash).await?.ok_or_else(|| {
+            anyhow!(
+                "No recorded LLM response for this request in {} (fixture {}.json). \
+                 Run in record mode to capture it.",
+                self.dir.display(),
+                hash
+            )
+        })?;
+        let mut cursors = self.cursors.lock().await;
+        let cursor = cursors.entry(hash.to_string()).or_insert(0);
+        let idx = (*cursor).min(fixture.responses.len().saturating_sub(1));
+        *cursor += 1;
+        debug!("Replaying LLM fixture {} (response {})", hash, idx + 1);
+        fixture
+            .responses
+            .get(idx)
+            .cloned()
+            .ok_or_else(|| anyhow!("LLM fixture {} has no responses", hash))
+    }
+
+    async fn record_response(
+        &self,
+        hash: &str,
+        messages: &[ChatMessage],
+        tools: &[ToolDefinition],
+        system: &str,
+        response: &ChatResponse,
+    ) -> Result<()> {
+        // The first recording of a request this session replaces any old fixture
+        let first = self.recorded.lock().await.insert(hash.to_string());
+        let mut fixture = match (first, self.load(hash).await?) {
+            (false, Some(existing)) => existing,
+            _ => Fixture {
+                hash: hash.to_string(),
+                provider: self.provider_name.clone(),
+                model: self.model.clone(),
+                system: system.to_string(),
+                messages: messages.to_vec(),
+                tools: tool_names(tools),
+                responses: Vec::new(),
+            },
+        };
+        fixture.responses.push(response.clone());
+
+        tokio::fs::create_dir_all(&self.dir)
+            .await
+            .with_context(|| format!("Failed to create {}", self.dir.display()))?;
+        let path = self.fixture_path(hash);
+        tokio::fs::write(&path, serde_json::to_string_pretty(&fixture)?)
+            .await
+            .with_context(|| format!("Failed to write {}", path.display()))?;
+        debug!(
+            "Recorded LLM fixture {} ({} responses)",
+            hash,
+            fixture.responses.len()
+        );
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl LlmProvider for RecordReplayProvider {
+    fn provider_name(&self) -> &str {
+        &self.provider_name
+    }
+
+    fn model(&self) -> &str {
+        &self.model
+    }
+
+    fn is_local(&self) -> bool {
+        match (self.mode, &self.inner) {
+            (ReplayMode::Replay, _) => true,
+            (ReplayMode::Record, Some(inner)) => inner.is_local(),
+            (ReplayMode::Record, None) => false,
+        }
+    }
+
+    async fn chat(
+        &self,
+        messages: &[ChatMessage],
+        tools: &[ToolDefinition],
+        system: &str,
+    ) -> Result<ChatResponse> {
+        let hash = Self::request_hash(messages, tools, system);
+        match (self.mode, &self.inner) {
+            (ReplayMode::Replay, _) => self.replay_response(&hash).await,
+            (ReplayMode::Record, Some(inner)) => {
+                let response = inner.chat(messages, tools, system).await?;
+                self.record_response(&hash, messages, tools, system, &response)
+                    .await?;
+                Ok(response)
+            }
+            (ReplayMode::Record, None) => Err(anyhow!("Record mode requires a provider")),
+        }
+    }
+}
+
+fn tool_names(tools: &[ToolDefinition]) -> Vec<String> {
+    tools.iter().map(|t| t.name.clone()).collect()
+}
+
+/// Mask the current-time section so the hash is stable across runs
+fn normalize_system(system: &str) -> String {
+    let Some(start) = system.find(TIME_HEADER) else {
+        return system.to_string();
+    };
+    let body = start + TIME_HEADER.len();
+    let end = system[body..]
+        .find("\n\n")
+        .map(|i| body + i)
+        .unwrap_or(system.len());
+    format!("{}{}", &system[..body], &system[end..])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::providers::types::{
+        ChatMessageContent, ChatResponseBlock, ChatRole, ChatUsage, StopReason,
+    };
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use tempfile::TempDir;
+
+    /// Counts calls and answers with the call number
+    struct CountingProvider {
+        calls: Arc<AtomicUsize>,
+    }
+
+    #[async_trait]
+    impl LlmProvider for CountingProvider {
+        fn provider_name(&self) -> &str {
+            "counting"
+        }
+        fn model(&self) -> &str {
+            "counting-1"
+        }
+        async fn chat(
+            &self,
+            _messages: &[ChatMessage],
+            _tools: &[ToolDefinition],
+            _system: &str,
+        ) -> Result<ChatResponse> {
+            let n = self.calls.fetch_add(1, Ordering::SeqCst) + 1;
+            Ok(ChatResponse {
+                blocks: vec![ChatResponseBlock::Text {
+                    text: format!("answer {}", n),
+                }],
+                stop_reason: StopReason::EndTurn,
+                usage: ChatUsage {
+                    input_tokens: 3,
+                    output_tokens: 2,
+                },
+            })
+        }
+    }
+
+    fn user(text: &str) -> Vec<ChatMessage> {
+        vec![ChatMessage {
+            role: ChatRole::User,
+            content: ChatMessageContent::Text(text.to_string()),
+        }]
+    }
+
+    fn text(response: &ChatResponse) -> String {
+        match &response.blocks[0] {
+            ChatResponseBlock::Text { text } => text.clone(),
+            other => panic!("expected text, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_hash_ignores_current_time() {
+        let a = "soul\n\n# CURRENT TIME\n\n2026-01-01T09:00:00+00:00 (UTC)\n\n# INSTRUCTIONS\n\nbe nice";
+        let b = "soul\n\n# CURRENT TIME\n\n2026-03-04T17:30:00+00:00 (UTC)\n\n# INSTRUCTIONS\n\nbe nice";
+        let msgs = user("hi");
+        assert_eq!(
+            RecordReplayProvider::request_hash(&msgs, &[], a),
+            RecordReplayProvider::request_hash(&msgs, &[], b)
+        );
+        assert_ne!(
+            RecordReplayProvider::request_hash(&msgs, &[], a),
+            RecordReplayProvider::request_hash(&user("bye"), &[], a)
+        );
+        assert_eq!("record".parse::<ReplayMode>().unwrap(), ReplayMode::Record);
+        assert!("rewind".parse::<ReplayMode>().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_record_then_replay_in_order() {
+        let temp = TempDir::new().unwrap();
+        let calls = Arc::new(AtomicUsize::new(0));
+        let recorder = RecordReplayProvider::record(
+            Box::new(CountingProvider {
+                calls: calls.clone(),
+            }),
+            temp.path(),
+        );
+        assert_eq!(recorder.model(), "counting-1");
+
+        let msgs = user("what's up?");
+        assert_eq!(
+            text(&recorder.chat(&msgs, &[], "sys").await.unwrap()),
+            "answer 1"
+        );
+        assert_eq!(
+            text(&recorder.chat(&msgs, &[], "sys").await.unwrap()),
+            "answer 2"
+        );
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+
+        let replayer = RecordReplayProvider::replay(temp.path());
+        let first = replayer.chat(&msgs, &[], "sys").await.unwrap();
+        assert_eq!(text(&first), "answer 1");
+        assert_eq!(first.usage.output_tokens, 2);
+        assert_eq!(
+            text(&replayer.chat(&msgs, &[], "sys").await.unwrap()),
+            "answer 2"
+        );
+        // Past the end, the last response repeats
+        assert_eq!(
+            text(&replayer.chat(&msgs, &[], "sys").await.unwrap()),
+            "answer 2"
+        );
+
+        let missing = replayer.chat(&user("unrecorded"), &[], "sys").await;
+        assert!(missing.unwrap_err().to_string().contains("record mode"));
+    }
+
+    #[tokio::test]
+    async fn test_agent_runs_offline_from_fixtures() {
+        use crate::agent::Agent;
+        use crate::api::ApiClient;
+        use crate::providers::ModelRouter;
+        use crate::tools::ToolRegistry;
+        use crate::types::{ChannelType, IncomingMessage};
+        use meepo_knowledge::KnowledgeDb;
+
+        let fixtures = TempDir::new().unwrap();
+        let message = || IncomingMessage {
+            id: "m-1".to_string(),
+            sender: "alice".to_string(),
+            content: "Summarize my day".to_string(),
+            channel: ChannelType::Internal,
+            timestamp: chrono::Utc::now(),
+        };
+        let agent_with = |provider: RecordReplayProvider, db_dir: &TempDir| {
+            let db = Arc::new(KnowledgeDb::new(db_dir.path().join("test.db")).unwrap());
+            Agent::new(
+                ApiClient::from_router(ModelRouter::single(Box::new(provider))),
+                Arc::new(ToolRegistry::new()),
+                "test soul".into(),
+                "test memory".into(),
+                db,
+            )
+        };
+
+        let calls = Arc::new(AtomicUsize::new(0));
+        let db_dir = TempDir::new().unwrap();
+        let recorded = agent_with(
+            RecordReplayProvider::record(
+                Box::new(CountingProvider {
+                    calls: calls.clone(),
+                }),
+                fixtures.path(),
+            ),
+            &db_dir,
+        )
+        .handle_message(message())
+        .await
+        .unwrap();
+        assert!(calls.load(Ordering::SeqCst) > 0);
+
+        // A fresh agent with no provider behind it gives the same answer
+        let db_dir = TempDir::new().unwrap();
+        let replayed = agent_with(RecordReplayProvider::replay(fixtures.path()), &db_dir)
+            .handle_message(message())
+            .await
+            .unwrap();
+        assert_eq!(replayed.content, recorded.content);
+    }
+}
@@ -1,6 +1,7 @@
 //! Model router with automatic failover across providers
 
 use anyhow::{Result, anyhow};
+use async_trait::async_trait;
 use std::time::Duration;
 use tracing::{debug, info, warn};
 
@@ -60,8 +61,16 @@ impl ModelRouter {
         system: &str,
     ) -> Result<ChatResponse> {
         let mut last_error = None;
+        let order = self.provider_order();
+        if order.is_empty() {
+            crate::network::ensure_online(&format!(
+                "the {} provider (configure a local provider such as Ollama)",
+                self.provider_name()
+            ))?;
+        }
 
-        for (idx, provider) in self.providers.iter().enumerate() {
+        for (pos, &idx) in order.iter().enumerate() {
+            let provider = &self.providers[idx];
             for attempt in 0..self.max_retries_per_provider {
                 debug!(
                     "Trying provider {} ({}/{}) attempt {}/{}",
@@ -72,9 +81,9 @@ impl ModelRouter {
                     self.max_retries_per_provider,
                 );
 
-                match provider.chat(messages, tools, system).await {
+                match Self::send(provider.as_ref(), messages, tools, system).await {
                     Ok(response) => {
-                        if idx > 0 {
+                        if pos > 0 {
                             info!(
                                 "Request succeeded on failover provider {} ({})",
                                 provider.provider_name(),
@@ -112,11 +121,11 @@ impl ModelRouter {
                 }
             }
 
-            if idx + 1 < self.providers.len() {
+            if let Some(&next) = order.get(pos + 1) {
                 info!(
                     "Failing over from {} to {}",
                     provider.provider_name(),
-                    self.providers[idx + 1].provider_name()
+                    self.providers[next].provider_name()
                 );
             }
         }
@@ -124,6 +133,46 @@ impl ModelRouter {
         Err(last_error.unwrap_or_else(|| anyhow!("All providers failed")))
     }
 
+    /// Send one request, redacting it first when the turn is redacted and
+    /// the provider isn't local
+    async fn send(
+        provider: &dyn LlmProvider,
+        messages: &[ChatMessage],
+        tools: &[ToolDefinition],
+        system: &str,
+    ) -> Result<ChatResponse> {
+        let Some(redactor) = crate::redaction::active().filter(|_| !provider.is_local()) else {
+            return provider.chat(messages, tools, system).await;
+        };
+        let (messages, system) = redactor
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .redact_request(messages, system);
+        let mut response = provider.chat(&messages, tools, &system).await?;
+        redactor
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .restore_response(&mut response);
+        Ok(response)
+    }
+
+    /// Provider indices in the order to try them. A model override moves the
+    /// provider that serves that model to the front; offline mode drops
+    /// providers that aren't local.
+    fn provider_order(&self) -> Vec<usize> {
+        let offline = crate::network::is_offline();
+        let mut order: Vec<usize> = (0..self.providers.len())
+            .filter(|&i| !offline || self.providers[i].is_local())
+            .collect();
+        if let Some(model) = super::overrides::current().model
+            && let Some(wanted) = super::overrides::provider_for_model(&model)
+        {
+            // Stable sort keeps the configured failover order otherwise
+            order.sort_by_key(|&i| self.providers[i].provider_name() != wanted);
+        }
+        order
+    }
+
     /// Get the primary provider's model name
     pub fn model(&self) -> &str {
         self.providers
@@ -146,6 +195,31 @@ impl ModelRouter {
     }
 }
 
+/// A router is itself a provider, so it can be wrapped (e.g. for recording)
+#[async_trait]
+impl LlmProvider for ModelRouter {
+    fn provider_name(&self) -> &str {
+        ModelRouter::provider_name(self)
+    }
+
+    fn model(&self) -> &str {
+        ModelRouter::model(self)
+    }
+
+    fn is_local(&self) -> bool {
+        self.providers.iter().any(|p| p.is_local())
+    }
+
+    async fn chat(
+        &self,
+        messages: &[ChatMessage],
+        tools: &[ToolDefinition],
+        system: &str,
+    ) -> Result<ChatResponse> {
+        ModelRouter::chat(self, messages, tools, system).await
+    }
+}
+
 /// Determine if an error is retryable (rate limit, server error, timeout)
 fn is_retryable_error(err: &str) -> bool {
     let retryable_patterns = [
@@ -170,7 +244,6 @@ fn is_retryable_error(err: &str) -> bool {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use async_trait::async_trait;
 
     use super::super::types::{ChatResponseBlock, ChatUsage, StopReason};
 
@@ -333,4 +406,85 @@ mod tests {
             assert_eq!(text, "from fallback");
         }
     }
+
+    #[tokio::test]
+    async fn test_model_override_prefers_matching_provider() {
+        let router = ModelRouter::with_failover(vec![
+            Box::new(SuccessProvider {
+                name: "anthropic".to_string(),
+                model_name: "claude-sonnet-4-5".to_string(),
+            }),
+            Box::new(SuccessProvider {
+                name: "openai".to_string(),
+                model_name: "gpt-4o".to_string(),
+            }),
+        ])
+        .unwrap();
+
+        let overrides = super::super::overrides::RequestOverrides {
+            model: Some("gpt-4o-mini".to_string()),
+            ..Default::default()
+        };
+        let result = super::super::overrides::scope(overrides, router.chat(&[], &[], "system"))
+            .await
+            .unwrap();
+        if let ChatResponseBlock::Text { text } = &result.blocks[0] {
+            assert_eq!(text, "from openai");
+        } else {
+            panic!("expected text block");
+        }
+    }
+
+    /// Mock provider that answers with the system prompt it was sent
+    struct EchoProvider {
+        seen: std::sync::Arc<std::sync::Mutex<String>>,
+    }
+
+    #[async_trait]
+    impl LlmProvider for EchoProvider {
+        fn provider_name(&self) -> &str {
+            "echo"
+        }
+        fn model(&self) -> &str {
+            "echo-model"
+        }
+        async fn chat(
+            &self,
+            _messages: &[ChatMessage],
+            _tools: &[ToolDefinition],
+            system: &str,
+        ) -> Result<ChatResponse> {
+            *self.seen.lock().unwrap() = system.to_string();
+            Ok(ChatResponse {
+                blocks: vec![ChatResponseBlock::Text {
+                    text: system.to_string(),
+                }],
+                stop_reason: StopReason::EndTurn,
+                usage: ChatUsage::default(),
+            })
+        }
+    }
+
+    #[tokio::test]
+    async fn test_redacted_turn_hides_values_from_provider() {
+        let seen = std::sync::Arc::new(std::sync::Mutex::new(String::new()));
+        let router = ModelRouter::single(Box::new(EchoProvider { seen: seen.clone() }));
+
+        let redactor = crate::redaction::RedactionConfig::default().redactor();
+        let result = crate::redaction::scope(
+            redactor,
+            router.chat(&[], &[], "Pay $40.00 to account 12345678"),
+        )
+        .await
+        .unwrap();
+        assert_eq!(
+            *seen.lock().unwrap(),
+            "Pay [AMOUNT_1] to account [ACCOUNT_1]"
+        );
+        if let ChatResponseBlock::Text { text } = &result.blocks[0] {
+            assert_eq!(text, "Pay $40.00 to account 12345678");
+        } else {
+            panic!("expected text block");
+        }
+    }
 }
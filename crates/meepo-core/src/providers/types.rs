@@ -46,10 +46,20 @@ pub enum ChatBlock {
         tool_call_id: String,
         content: String,
     },
+    /// Extended thinking from an earlier response, sent back unchanged
+    /// (with its signature) while a tool loop continues
+    Thinking {
+        thinking: String,
+        signature: String,
+    },
+    /// Thinking the provider returned encrypted
+    RedactedThinking {
+        data: String,
+    },
 }
 
 /// Provider-agnostic response from an LLM
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ChatResponse {
     pub blocks: Vec<ChatResponseBlock>,
     pub stop_reason: StopReason,
@@ -57,7 +67,7 @@ pub struct ChatResponse {
 }
 
 /// A block in the response
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum ChatResponseBlock {
     Text {
         text: String,
@@ -67,10 +77,41 @@ pub enum ChatResponseBlock {
         name: String,
         input: Value,
     },
+    /// Extended thinking that preceded the answer
+    Thinking {
+        thinking: String,
+        signature: String,
+    },
+    RedactedThinking {
+        data: String,
+    },
+}
+
+impl ChatResponseBlock {
+    /// The block to send back in the assistant turn of the conversation
+    pub fn to_chat_block(&self) -> ChatBlock {
+        match self {
+            Self::Text { text } => ChatBlock::Text { text: text.clone() },
+            Self::ToolCall { id, name, input } => ChatBlock::ToolCall {
+                id: id.clone(),
+                name: name.clone(),
+                input: input.clone(),
+            },
+            Self::Thinking {
+                thinking,
+                signature,
+            } => ChatBlock::Thinking {
+                thinking: thinking.clone(),
+                signature: signature.clone(),
+            },
+            Self::RedactedThinking { data } => ChatBlock::RedactedThinking { data: data.clone() },
+        }
+    }
 }
 
 /// Why the model stopped generating
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum StopReason {
     EndTurn,
     ToolUse,
@@ -79,7 +120,7 @@ pub enum StopReason {
 }
 
 /// Token usage from a single API call
-#[derive(Debug, Clone, Copy, Default)]
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
 pub struct ChatUsage {
     pub input_tokens: u32,
     pub output_tokens: u32,
@@ -94,6 +135,12 @@ pub trait LlmProvider: Send + Sync {
     /// Model identifier (e.g. "claude-opus-4-6", "gpt-4o")
     fn model(&self) -> &str;
 
+    /// Whether requests stay on this machine (e.g. Ollama on localhost).
+    /// Only local providers are used in offline mode.
+    fn is_local(&self) -> bool {
+        false
+    }
+
     /// Send a chat request with optional tools and system prompt
     async fn chat(
         &self,
@@ -102,6 +102,8 @@ pub struct QueryRouterConfig {
     pub use_llm_classification: bool,
     /// Whether the router is enabled at all
     pub enabled: bool,
+    /// Extended thinking budgets by complexity
+    pub thinking: ThinkingBudgets,
 }
 
 impl Default for QueryRouterConfig {
@@ -109,10 +111,53 @@ impl Default for QueryRouterConfig {
         Self {
             use_llm_classification: false, // start with heuristics, cheaper
             enabled: true,
+            thinking: ThinkingBudgets::default(),
         }
     }
 }
 
+/// Extended thinking budget (in tokens) for each complexity tier; 0 turns
+/// thinking off for that tier. Only models that support it use a budget.
+#[derive(Debug, Clone)]
+pub struct ThinkingBudgets {
+    pub enabled: bool,
+    pub no_retrieval: u32,
+    pub single_step: u32,
+    pub multi_source: u32,
+    pub multi_hop: u32,
+    /// Keep thinking summaries in traces stored with conversation history
+    pub store_in_history: bool,
+}
+
+impl Default for ThinkingBudgets {
+    fn default() -> Self {
+        Self {
+            enabled: false, // opt-in, thinking tokens are billed as output
+            no_retrieval: 0,
+            single_step: 0,
+            multi_source: 4096,
+            multi_hop: 16000,
+            store_in_history: false,
+        }
+    }
+}
+
+impl ThinkingBudgets {
+    /// The budget for a query of this complexity, if thinking applies
+    pub fn budget_for(&self, complexity: QueryComplexity) -> Option<u32> {
+        if !self.enabled {
+            return None;
+        }
+        let budget = match complexity {
+            QueryComplexity::NoRetrieval => self.no_retrieval,
+            QueryComplexity::SingleStep => self.single_step,
+            QueryComplexity::MultiSource => self.multi_source,
+            QueryComplexity::MultiHop => self.multi_hop,
+        };
+        (budget > 0).then_some(budget)
+    }
+}
+
 /// Route a query to the appropriate retrieval strategy.
 ///
 /// Uses heuristics first (fast, free), with optional LLM classification
@@ -326,6 +371,17 @@ fn strategy_for(complexity: QueryComplexity) -> RetrievalStrategy {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_thinking_budget_by_complexity() {
+        let mut budgets = ThinkingBudgets::default();
+        assert_eq!(budgets.budget_for(QueryComplexity::MultiHop), None);
+
+        budgets.enabled = true;
+        assert_eq!(budgets.budget_for(QueryComplexity::NoRetrieval), None);
+        assert_eq!(budgets.budget_for(QueryComplexity::MultiSource), Some(4096));
+        assert_eq!(budgets.budget_for(QueryComplexity::MultiHop), Some(16000));
+    }
+
     #[test]
     fn test_heuristic_greetings() {
         assert_eq!(classify_heuristic("hello"), QueryComplexity::NoRetrieval);
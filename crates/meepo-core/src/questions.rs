@@ -0,0 +1,234 @@
+//! Pending questions — let the agent ask the user for clarification mid-task
+//!
+//! When a background task needs input, the `ask_user` tool posts the
+//! question to the user's channel and parks the task on a oneshot receiver.
+//! Incoming messages are checked here before they reach the agent: the next
+//! reply on a channel with a pending question is delivered as the answer and
+//! the task resumes with it as the tool result. Button presses (see
+//! [`Interactive`]) answer the exact question they were attached to. A
+//! question asked of one sender is only answered by that sender.
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::future::Future;
+use tokio::sync::{Mutex, oneshot};
+use tracing::{debug, info};
+
+use crate::types::{ChannelType, IncomingMessage, Interactive};
+
+tokio::task_local! {
+    /// ID of the background task the current agent turn belongs to
+    static CURRENT_TASK: String;
+}
+
+/// Run a future as part of a background task, so tools it calls can find the task ID
+pub async fn scope_task<F: Future>(task_id: String, fut: F) -> F::Output {
+    CURRENT_TASK.scope(task_id, fut).await
+}
+
+/// The background task the calling tool is running under, if any
+pub fn current_task() -> Option<String> {
+    CURRENT_TASK.try_with(|id| id.clone()).ok()
+}
+
+/// A question waiting for the user's answer
+#[derive(Debug, Clone, Serialize)]
+pub struct PendingQuestion {
+    pub id: String,
+    pub task_id: Option<String>,
+    pub question: String,
+    pub channel: ChannelType,
+    /// The only sender whose reply answers it, if limited to one
+    pub sender: Option<String>,
+    pub asked_at: DateTime<Utc>,
+}
+
+impl PendingQuestion {
+    fn answered_by(&self, msg: &IncomingMessage) -> bool {
+        self.sender.as_ref().is_none_or(|s| *s == msg.sender)
+    }
+}
+
+struct Waiting {
+    question: PendingQuestion,
+    answer_tx: oneshot::Sender<String>,
+}
+
+/// Registry of questions the agent is waiting on
+#[derive(Default)]
+pub struct PendingQuestions {
+    waiting: Mutex<Vec<Waiting>>,
+}
+
+impl PendingQuestions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register a question; the receiver resolves with the user's answer
+    pub async fn ask(
+        &self,
+        task_id: Option<String>,
+        question: &str,
+        channel: ChannelType,
+    ) -> (PendingQuestion, oneshot::Receiver<String>) {
+        self.ask_sender(task_id, question, channel, None).await
+    }
+
+    /// Register a question only `sender` (on `channel`) can answer
+    pub async fn ask_sender(
+        &self,
+        task_id: Option<String>,
+        question: &str,
+        channel: ChannelType,
+        sender: Option<String>,
+    ) -> (PendingQuestion, oneshot::Receiver<String>) {
+        let (answer_tx, answer_rx) = oneshot::channel();
+        let question = PendingQuestion {
+            id: format!("q-{}", &uuid::Uuid::new_v4().to_string()[..8]),
+            task_id,
+            question: question.to_string(),
+            channel,
+            sender,
+            asked_at: Utc::now(),
+        };
+        debug!("Pending question {} on {}", question.id, question.channel);
+        self.waiting.lock().await.push(Waiting {
+            question: question.clone(),
+            answer_tx,
+        });
+        (question, answer_rx)
+    }
+
+    /// Deliver a user message as the answer to the oldest question pending on
+    /// its channel. Returns the answered question, or None if the message
+    /// should go to the agent as usual.
+    pub async fn try_answer(&self, msg: &IncomingMessage) -> Option<PendingQuestion> {
+        if msg.channel == ChannelType::Internal || msg.content.trim().is_empty() {
+            return None;
+        }
+        let mut waiting = self.waiting.lock().await;
+        // Drop questions whose task has gone away (cancelled or timed out)
+        waiting.retain(|w| !w.answer_tx.is_closed());
+        let (idx, answer) = match Interactive::parse_press(&msg.content) {
+            Some((id, value)) => (
+                waiting
+                    .iter()
+                    .position(|w| w.question.id == id && w.question.answered_by(msg))?,
+                value.to_string(),
+            ),
+            None => (
+                waiting.iter().position(|w| {
+                    w.question.channel == msg.channel && w.question.answered_by(msg)
+                })?,
+                msg.content.clone(),
+            ),
+        };
+        let w = waiting.remove(idx);
+        if w.answer_tx.send(answer).is_err() {
+            return None;
+        }
+        info!(
+            "Answer from {} resolved pending question {}",
+            msg.sender, w.question.id
+        );
+        Some(w.question)
+    }
+
+    /// Withdraw a question (e.g. after a timeout)
+    pub async fn withdraw(&self, id: &str) {
+        self.waiting.lock().await.retain(|w| w.question.id != id);
+    }
+
+    /// Questions currently waiting for an answer, oldest first
+    pub async fn list(&self) -> Vec<PendingQuestion> {
+        self.waiting
+            .lock()
+            .await
+            .iter()
+            .filter(|w| !w.answer_tx.is_closed())
+            .map(|w| w.question.clone())
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn reply(channel: ChannelType, content: &str) -> IncomingMessage {
+        IncomingMessage {
+            id: "m1".to_string(),
+            sender: "user".to_string(),
+            content: content.to_string(),
+            channel,
+            timestamp: Utc::now(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_answer_resolves_oldest_on_channel() {
+        let pending = PendingQuestions::new();
+        let (q1, rx1) = pending
+            .ask(Some("t-1".to_string()), "Which folder?", ChannelType::Slack)
+            .await;
+        let (_q2, rx2) = pending.ask(None, "Proceed?", ChannelType::Slack).await;
+
+        // Other channels don't answer it
+        assert!(
+            pending
+                .try_answer(&reply(ChannelType::Discord, "hi"))
+                .await
+                .is_none()
+        );
+
+        let answered = pending
+            .try_answer(&reply(ChannelType::Slack, "~/Documents"))
+            .await
+            .unwrap();
+        assert_eq!(answered.id, q1.id);
+        assert_eq!(rx1.await.unwrap(), "~/Documents");
+        assert_eq!(pending.list().await.len(), 1);
+
+        drop(rx2);
+        // A question whose task stopped waiting is skipped
+        assert!(
+            pending
+                .try_answer(&reply(ChannelType::Slack, "yes"))
+                .await
+                .is_none()
+        );
+        assert!(pending.list().await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_button_press_answers_its_question() {
+        let pending = PendingQuestions::new();
+        let (_q1, _rx1) = pending.ask(None, "Which folder?", ChannelType::Slack).await;
+        let (q2, rx2) = pending.ask(None, "Delete them?", ChannelType::Slack).await;
+
+        let press = Interactive::press_content(&q2.id, "Approve");
+        let answered = pending
+            .try_answer(&reply(ChannelType::Slack, &press))
+            .await
+            .unwrap();
+        assert_eq!(answered.id, q2.id);
+        assert_eq!(rx2.await.unwrap(), "Approve");
+
+        // A press on a question that's gone doesn't answer another one
+        assert!(
+            pending
+                .try_answer(&reply(ChannelType::Slack, &press))
+                .await
+                .is_none()
+        );
+        assert_eq!(pending.list().await.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_current_task_scope() {
+        assert!(current_task().is_none());
+        let id = scope_task("t-42".to_string(), async { current_task() }).await;
+        assert_eq!(id.as_deref(), Some("t-42"));
+    }
+}
@@ -0,0 +1,287 @@
+//! Redaction of sensitive numbers before model calls
+//!
+//! On channels marked for redaction (e.g. a Slack workspace used for
+//! finance), account numbers, IBANs, card numbers and amounts are swapped
+//! for placeholders like `[ACCOUNT_1]` before a request leaves for a cloud
+//! model. The mapping stays in memory for the turn: placeholders in the
+//! model's reply and in its tool calls are swapped back, so tools act on the
+//! real values and the user sees them, but the provider never does.
+//!
+//! The agent runs a redacted turn inside [`scope`]; the model router reads
+//! [`active`] and skips redaction for local providers. Sub-agents spawned
+//! onto other tasks (background tasks, delegation) don't inherit the scope.
+
+use std::collections::{HashMap, HashSet};
+use std::future::Future;
+use std::sync::{Arc, LazyLock, Mutex};
+
+use regex::Regex;
+use serde_json::Value;
+use tracing::warn;
+
+use crate::providers::types::{
+    ChatBlock, ChatMessage, ChatMessageContent, ChatResponse, ChatResponseBlock,
+};
+
+tokio::task_local! {
+    static REDACTOR: Arc<Mutex<Redactor>>;
+}
+
+/// Which channels are redacted, and what gets redacted on them
+#[derive(Debug, Clone, Default)]
+pub struct RedactionConfig {
+    /// Channel names ("slack", "email") whose turns are redacted
+    pub channels: HashSet<String>,
+    /// Also redact other numbers of three or more digits, and decimals
+    pub numbers: bool,
+    /// Extra patterns to redact, e.g. internal client codes
+    pub patterns: Vec<Regex>,
+}
+
+impl RedactionConfig {
+    /// Add extra patterns, skipping (with a warning) any that don't compile
+    pub fn with_patterns(mut self, patterns: &[String]) -> Self {
+        for pattern in patterns {
+            match Regex::new(pattern) {
+                Ok(regex) => self.patterns.push(regex),
+                Err(e) => warn!("Ignoring redaction pattern '{}': {}", pattern, e),
+            }
+        }
+        self
+    }
+
+    pub fn applies_to(&self, channel: &str) -> bool {
+        self.channels.contains(channel)
+    }
+
+    /// A fresh redactor for one turn
+    pub fn redactor(&self) -> Redactor {
+        Redactor {
+            numbers: self.numbers,
+            patterns: self.patterns.clone(),
+            placeholders: HashMap::new(),
+            values: Vec::new(),
+        }
+    }
+}
+
+/// Swaps sensitive values for placeholders and back
+#[derive(Debug)]
+pub struct Redactor {
+    numbers: bool,
+    patterns: Vec<Regex>,
+    /// Value → placeholder
+    placeholders: HashMap<String, String>,
+    /// Placeholder → value, in the order they were handed out
+    values: Vec<(String, String)>,
+}
+
+impl Redactor {
+    /// Number of distinct values redacted so far
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    fn placeholder(&mut self, kind: &str, value: &str) -> String {
+        if let Some(placeholder) = self.placeholders.get(value) {
+            return placeholder.clone();
+        }
+        let n = self
+            .values
+            .iter()
+            .filter(|(p, _)| p.starts_with(&format!("[{}_", kind)))
+            .count()
+            + 1;
+        let placeholder = format!("[{}_{}]", kind, n);
+        self.placeholders
+            .insert(value.to_string(), placeholder.clone());
+        self.values.push((placeholder.clone(), value.to_string()));
+        placeholder
+    }
+
+    fn replace(&mut self, text: String, regex: &Regex, kind: &str) -> String {
+        if !regex.is_match(&text) {
+            return text;
+        }
+        regex
+            .replace_all(&text, |caps: &regex::Captures| {
+                self.placeholder(kind, &caps[0])
+            })
+            .into_owned()
+    }
+
+    /// Replace sensitive values in `text` with placeholders
+    pub fn redact(&mut self, text: &str) -> String {
+        static IBAN: LazyLock<Regex> = LazyLock::new(|| {
+            Regex::new(r"\b[A-Z]{2}\d{2}(?: ?[A-Z0-9]{4}){2,7}(?: ?[A-Z0-9]{1,4})?\b")
+                .expect("valid regex")
+        });
+        static ACCOUNT: LazyLock<Regex> = LazyLock::new(|| {
+            Regex::new(r"\b\d{4}(?:[ -]\d{4}){2,4}\b|\b\d{6,}\b").expect("valid regex")
+        });
+        static AMOUNT: LazyLock<Regex> = LazyLock::new(|| {
+            Regex::new(
+                r"[$€£¥]\s?\d[\d,]*(?:\.\d+)?|\b\d[\d,]*(?:\.\d+)?\s?(?:USD|EUR|GBP|JPY|CHF|CAD|AUD)\b",
+            )
+            .expect("valid regex")
+        });
+        static NUMBER: LazyLock<Regex> = LazyLock::new(|| {
+            Regex::new(r"\b\d{3,}(?:[.,]\d+)*\b|\b\d+[.,]\d+\b").expect("valid regex")
+        });
+
+        let mut text = text.to_string();
+        for pattern in self.patterns.clone() {
+            text = self.replace(text, &pattern, "REDACTED");
+        }
+        text = self.replace(text, &IBAN, "ACCOUNT");
+        text = self.replace(text, &ACCOUNT, "ACCOUNT");
+        text = self.replace(text, &AMOUNT, "AMOUNT");
+        if self.numbers {
+            text = self.replace(text, &NUMBER, "NUMBER");
+        }
+        text
+    }
+
+    /// Put the original values back in place of placeholders
+    pub fn restore(&self, text: &str) -> String {
+        if !text.contains('[') {
+            return text.to_string();
+        }
+        let mut text = text.to_string();
+        for (placeholder, value) in &self.values {
+            if text.contains(placeholder.as_str()) {
+                text = text.replace(placeholder.as_str(), value);
+            }
+        }
+        text
+    }
+
+    /// Restore placeholders in every string of a JSON value (tool inputs)
+    pub fn restore_value(&self, value: &Value) -> Value {
+        match value {
+            Value::String(s) => Value::String(self.restore(s)),
+            Value::Array(items) => {
+                Value::Array(items.iter().map(|v| self.restore_value(v)).collect())
+            }
+            Value::Object(map) => Value::Object(
+                map.iter()
+                    .map(|(k, v)| (k.clone(), self.restore_value(v)))
+                    .collect(),
+            ),
+            other => other.clone(),
+        }
+    }
+
+    fn redact_value(&mut self, value: &Value) -> Value {
+        match value {
+            Value::String(s) => Value::String(self.redact(s)),
+            Value::Array(items) => {
+                Value::Array(items.iter().map(|v| self.redact_value(v)).collect())
+            }
+            Value::Object(map) => Value::Object(
+                map.iter()
+                    .map(|(k, v)| (k.clone(), self.redact_value(v)))
+                    .collect(),
+            ),
+            other => other.clone(),
+        }
+    }
+
+    /// Redact the messages and system prompt of a request. Thinking blocks
+    /// are signed by the provider and go back unchanged.
+    pub fn redact_request(
+        &mut self,
+        messages: &[ChatMessage],
+        system: &str,
+    ) -> (Vec<ChatMessage>, String) {
+        let messages = messages
+            .iter()
+            .map(|m| ChatMessage {
+                role: m.role,
+                content: match &m.content {
+                    ChatMessageContent::Text(text) => ChatMessageContent::Text(self.redact(text)),
+                    ChatMessageContent::Blocks(blocks) => ChatMessageContent::Blocks(
+                        blocks
+                            .iter()
+                            .map(|block| match block {
+                                ChatBlock::Text { text } => ChatBlock::Text {
+                                    text: self.redact(text),
+                                },
+                                ChatBlock::ToolCall { id, name, input } => ChatBlock::ToolCall {
+                                    id: id.clone(),
+                                    name: name.clone(),
+                                    input: self.redact_value(input),
+                                },
+                                ChatBlock::ToolResult {
+                                    tool_call_id,
+                                    content,
+                                } => ChatBlock::ToolResult {
+                                    tool_call_id: tool_call_id.clone(),
+                                    content: self.redact(content),
+                                },
+                                other => other.clone(),
+                            })
+                            .collect(),
+                    ),
+                },
+            })
+            .collect();
+        (messages, self.redact(system))
+    }
+
+    /// Swap placeholders back in a response's text and tool calls
+    pub fn restore_response(&self, response: &mut ChatResponse) {
+        for block in &mut response.blocks {
+            match block {
+                ChatResponseBlock::Text { text } => *text = self.restore(text),
+                ChatResponseBlock::ToolCall { input, .. } => *input = self.restore_value(input),
+                ChatResponseBlock::Thinking { .. } | ChatResponseBlock::RedactedThinking { .. } => {
+                }
+            }
+        }
+    }
+}
+
+/// Run a future with redaction in effect for its model calls
+pub async fn scope<F: Future>(redactor: Redactor, fut: F) -> F::Output {
+    REDACTOR.scope(Arc::new(Mutex::new(redactor)), fut).await
+}
+
+/// The redactor for the current turn, if it is redacted
+pub fn active() -> Option<Arc<Mutex<Redactor>>> {
+    REDACTOR.try_with(Arc::clone).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_redact_and_restore() {
+        let config = RedactionConfig {
+            numbers: true,
+            ..Default::default()
+        };
+        let mut redactor = config.redactor();
+        let text = "Move $1,250.00 from DE89 3704 0044 0532 0130 00 to account 12345678, \
+                    then pay 12345678 again with 2 of the 350 shares.";
+        let redacted = redactor.redact(text);
+        assert_eq!(
+            redacted,
+            "Move [AMOUNT_1] from [ACCOUNT_1] to account [ACCOUNT_2], \
+             then pay [ACCOUNT_2] again with 2 of the [NUMBER_1] shares."
+        );
+        assert_eq!(redactor.len(), 4);
+        assert_eq!(redactor.restore(&redacted), text);
+
+        let input = serde_json::json!({"to": "[ACCOUNT_2]", "amount": ["[AMOUNT_1]"]});
+        assert_eq!(
+            redactor.restore_value(&input),
+            serde_json::json!({"to": "12345678", "amount": ["$1,250.00"]})
+        );
+    }
+}
@@ -0,0 +1,514 @@
+//! Scheduled reports
+//!
+//! A report definition names the data it draws on (usage, goals, finished
+//! background tasks, ingested documents, feedback), a Markdown template and
+//! where to deliver the result — a channel, an email address and/or a file in
+//! the workspace. The [`ReportScheduler`] generates each report on its cron
+//! schedule (in the user's timezone), e.g. a weekly review every Friday.
+//!
+//! Templates use Tera-style `{{ variable }}` placeholders. Every source renders
+//! a Markdown section under its own name (`{{ usage }}`, `{{ goals }}`, ...),
+//! alongside `{{ name }}`, `{{ date }}`, `{{ period_start }}` and
+//! `{{ period_end }}`.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::Arc;
+
+use anyhow::{Context, Result, anyhow};
+use chrono::{Duration, NaiveDate};
+use meepo_knowledge::KnowledgeDb;
+use serde::{Deserialize, Serialize};
+use tokio::sync::mpsc;
+use tokio_util::sync::CancellationToken;
+use tracing::{debug, info, warn};
+
+use crate::types::{ChannelType, MessageKind, OutgoingMessage};
+
+/// Template used when a report doesn't define one
+pub const DEFAULT_TEMPLATE: &str = "# {{ name }}\n\n_{{ period_start }} to {{ period_end }}_\n\n\
+{{ usage }}{{ goals }}{{ tasks }}{{ documents }}{{ feedback }}";
+
+/// Most items listed per section before summarising the rest
+const MAX_ITEMS: usize = 20;
+
+/// Data a report can draw on
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ReportSource {
+    /// API usage and cost
+    Usage,
+    /// Goals completed, failed or still open
+    Goals,
+    /// Finished background tasks
+    Tasks,
+    /// Documents ingested into the knowledge base
+    Documents,
+    /// 👍/👎 ratings on answers
+    Feedback,
+}
+
+impl ReportSource {
+    /// Template variable the section renders into
+    pub fn variable(&self) -> &'static str {
+        match self {
+            Self::Usage => "usage",
+            Self::Goals => "goals",
+            Self::Tasks => "tasks",
+            Self::Documents => "documents",
+            Self::Feedback => "feedback",
+        }
+    }
+}
+
+/// Where a generated report goes
+#[derive(Debug, Clone, PartialEq)]
+pub enum ReportDestination {
+    Channel(ChannelType),
+    Email(String),
+    /// Path relative to the workspace; may contain template placeholders
+    File(String),
+}
+
+/// A configured report
+#[derive(Debug, Clone)]
+pub struct ReportDefinition {
+    pub name: String,
+    /// Cron expression (5 or 6 fields) in the user's timezone
+    pub schedule: String,
+    /// Days covered by the report, ending on the day it runs
+    pub period_days: u32,
+    pub sources: Vec<ReportSource>,
+    /// Markdown template; [`DEFAULT_TEMPLATE`] when `None`
+    pub template: Option<String>,
+    pub destinations: Vec<ReportDestination>,
+}
+
+impl ReportDefinition {
+    /// Parsed cron schedule
+    pub fn cron(&self) -> Result<cron::Schedule> {
+        parse_schedule(&self.schedule)
+            .with_context(|| format!("Invalid schedule for report '{}'", self.name))
+    }
+}
+
+/// Parse a cron expression, accepting standard 5-field cron by adding a
+/// seconds field
+pub fn parse_schedule(expr: &str) -> Result<cron::Schedule> {
+    let expr = expr.trim();
+    let expr = if expr.split_whitespace().count() == 5 {
+        format!("0 {}", expr)
+    } else {
+        expr.to_string()
+    };
+    cron::Schedule::from_str(&expr).map_err(|e| anyhow!("Invalid cron '{}': {}", expr, e))
+}
+
+/// A generated report
+#[derive(Debug, Clone)]
+pub struct Report {
+    pub name: String,
+    /// Used as the email subject
+    pub title: String,
+    /// Rendered Markdown
+    pub body: String,
+    pub period_start: NaiveDate,
+    pub period_end: NaiveDate,
+    /// Template variables the body was rendered from
+    pub variables: HashMap<String, String>,
+}
+
+/// Generate a report covering the `period_days` days ending on `today`.
+/// All sections are read from one snapshot of the database, or from the
+/// live database if the snapshot can't be taken.
+pub async fn generate(
+    db: &KnowledgeDb,
+    def: &ReportDefinition,
+    today: NaiveDate,
+) -> Result<Report> {
+    let period_start = today - Duration::days(i64::from(def.period_days.max(1)) - 1);
+    let start = period_start.format("%Y-%m-%d").to_string();
+    let end = today.format("%Y-%m-%d").to_string();
+
+    let mut variables = HashMap::from([
+        ("name".to_string(), def.name.clone()),
+        ("date".to_string(), end.clone()),
+        ("period_start".to_string(), start.clone()),
+        ("period_end".to_string(), end.clone()),
+    ]);
+    // Read every section from one snapshot so their numbers agree
+    let snapshot = match db.snapshot().await {
+        Ok(snapshot) => Some(snapshot),
+        Err(e) => {
+            warn!(
+                "Failed to snapshot database for report '{}', reading live: {}",
+                def.name, e
+            );
+            None
+        }
+    };
+    let db = snapshot.as_ref().unwrap_or(db);
+    for source in &def.sources {
+        let section = match source {
+            ReportSource::Usage => usage_section(db, &start, &end).await?,
+            ReportSource::Goals => goals_section(db, &start, &end).await?,
+            ReportSource::Tasks => tasks_section(db, &start, &end).await?,
+            ReportSource::Documents => documents_section(db, &start, &end).await?,
+            ReportSource::Feedback => feedback_section(db, &start, &end).await?,
+        };
+        variables.insert(source.variable().to_string(), section);
+    }
+
+    let template = def.template.as_deref().unwrap_or(DEFAULT_TEMPLATE);
+    let body = format!("{}\n", render(template, &variables).trim_end());
+    Ok(Report {
+        name: def.name.clone(),
+        title: format!("{} ({} to {})", def.name, start, end),
+        body,
+        period_start,
+        period_end: today,
+        variables,
+    })
+}
+
+/// Substitute `{{ variable }}` placeholders. Unknown variables render empty.
+pub fn render(template: &str, variables: &HashMap<String, String>) -> String {
+    let mut out = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(open) = rest.find("{{") {
+        let Some(close) = rest[open + 2..].find("}}") else {
+            break;
+        };
+        out.push_str(&rest[..open]);
+        let key = rest[open + 2..open + 2 + close].trim();
+        match variables.get(key) {
+            Some(value) => out.push_str(value),
+            None => debug!("Report template references unknown variable '{}'", key),
+        }
+        rest = &rest[open + 2 + close + 2..];
+    }
+    out.push_str(rest);
+    out
+}
+
+async fn usage_section(db: &KnowledgeDb, start: &str, end: &str) -> Result<String> {
+    let usage = db.get_usage_summary(start, end).await?;
+    let mut out = format!(
+        "## Usage\n\n- Cost: ${:.2}\n- API calls: {}\n- Tool calls: {}\n- Tokens: {} in / {} out\n",
+        usage.estimated_cost_usd,
+        usage.total_api_calls,
+        usage.total_tool_calls,
+        usage.total_input_tokens,
+        usage.total_output_tokens,
+    );
+    let mut models: Vec<_> = usage.by_model.iter().collect();
+    models.sort_by(|a, b| b.1.estimated_cost_usd.total_cmp(&a.1.estimated_cost_usd));
+    for (model, m) in models {
+        out.push_str(&format!(
+            "  - {}: ${:.2} ({} calls)\n",
+            model, m.estimated_cost_usd, m.api_calls
+        ));
+    }
+    out.push('\n');
+    Ok(out)
+}
+
+async fn goals_section(db: &KnowledgeDb, start: &str, end: &str) -> Result<String> {
+    let updated = db.get_goals_updated(start, end).await?;
+    let active = db.get_active_goals().await?;
+    let mut out = "## Goals\n\n".to_string();
+    for (status, heading) in [("completed", "Completed"), ("failed", "Failed")] {
+        let goals: Vec<String> = updated
+            .iter()
+            .filter(|g| g.status == status)
+            .map(|g| g.description.clone())
+            .collect();
+        if !goals.is_empty() {
+            out.push_str(&format!("**{}**\n\n{}\n", heading, bullets(&goals)));
+        }
+    }
+    let open: Vec<String> = active
+        .iter()
+        .map(|g| format!("{} (priority {})", g.description, g.priority))
+        .collect();
+    if open.is_empty() {
+        out.push_str("No open goals.\n\n");
+    } else {
+        out.push_str(&format!("**Open**\n\n{}\n", bullets(&open)));
+    }
+    Ok(out)
+}
+
+async fn tasks_section(db: &KnowledgeDb, start: &str, end: &str) -> Result<String> {
+    let tasks = db.get_background_tasks_finished(start, end).await?;
+    if tasks.is_empty() {
+        return Ok("## Tasks\n\nNo background tasks finished.\n\n".to_string());
+    }
+    let items: Vec<String> = tasks
+        .iter()
+        .map(|t| {
+            let mark = if t.status == "completed" {
+                "✓"
+            } else {
+                "✗"
+            };
+            format!("{} {}", mark, t.description)
+        })
+        .collect();
+    let done = tasks.iter().filter(|t| t.status == "completed").count();
+    Ok(format!(
+        "## Tasks\n\n{} completed, {} failed\n\n{}\n",
+        done,
+        tasks.len() - done,
+        bullets(&items)
+    ))
+}
+
+async fn documents_section(db: &KnowledgeDb, start: &str, end: &str) -> Result<String> {
+    let docs = db
+        .get_entities_created(Some("document"), start, end)
+        .await?;
+    if docs.is_empty() {
+        return Ok("## Documents\n\nNo documents ingested.\n\n".to_string());
+    }
+    let names: Vec<String> = docs.iter().map(|d| d.name.clone()).collect();
+    Ok(format!(
+        "## Documents\n\n{} ingested\n\n{}\n",
+        docs.len(),
+        bullets(&names)
+    ))
+}
+
+async fn feedback_section(db: &KnowledgeDb, start: &str, end: &str) -> Result<String> {
+    let summary = db.get_feedback_summary(start, end).await?;
+    let overall = &summary.overall;
+    if overall.total() == 0 {
+        return Ok("## Feedback\n\nNo ratings.\n\n".to_string());
+    }
+    Ok(format!(
+        "## Feedback\n\n- 👍 {} / 👎 {} ({:.0}% satisfied)\n\n",
+        overall.positive,
+        overall.negative,
+        overall.satisfaction() * 100.0
+    ))
+}
+
+/// Markdown bullet list, capped at [`MAX_ITEMS`]
+fn bullets(items: &[String]) -> String {
+    let mut out: String = items
+        .iter()
+        .take(MAX_ITEMS)
+        .map(|item| format!("- {}\n", item))
+        .collect();
+    if items.len() > MAX_ITEMS {
+        out.push_str(&format!("- … and {} more\n", items.len() - MAX_ITEMS));
+    }
+    out
+}
+
+/// Generates reports on schedule and delivers them
+pub struct ReportScheduler {
+    reports: Vec<ReportDefinition>,
+    db: Arc<KnowledgeDb>,
+    workspace: PathBuf,
+    outgoing: Option<mpsc::Sender<OutgoingMessage>>,
+}
+
+impl ReportScheduler {
+    pub fn new(reports: Vec<ReportDefinition>, db: Arc<KnowledgeDb>, workspace: PathBuf) -> Self {
+        Self {
+            reports,
+            db,
+            workspace,
+            outgoing: None,
+        }
+    }
+
+    /// Channel for delivering reports to messaging channels
+    pub fn with_outgoing(mut self, tx: mpsc::Sender<OutgoingMessage>) -> Self {
+        self.outgoing = Some(tx);
+        self
+    }
+
+    /// Generate and deliver reports until cancelled
+    pub async fn run(self, cancel: CancellationToken) {
+        let mut scheduled = Vec::new();
+        for def in &self.reports {
+            match def.cron() {
+                Ok(schedule) => scheduled.push((def, schedule)),
+                Err(e) => warn!("Skipping report: {:#}", e),
+            }
+        }
+        if scheduled.is_empty() {
+            return;
+        }
+        info!("Report scheduler started ({} reports)", scheduled.len());
+
+        loop {
+            let tz = crate::timezone::user_timezone();
+            let now = chrono::Utc::now().with_timezone(&tz);
+            let Some((next_time, def)) = scheduled
+                .iter()
+                .filter_map(|(def, s)| s.after(&now).next().map(|t| (t, *def)))
+                .min_by_key(|(t, _)| *t)
+            else {
+                warn!("No upcoming report runs — report scheduler exiting");
+                break;
+            };
+            let wait = (next_time - now)
+                .to_std()
+                .unwrap_or(std::time::Duration::from_secs(60));
+            debug!("Next report '{}' at {}", def.name, next_time);
+
+            tokio::select! {
+                _ = cancel.cancelled() => break,
+                _ = tokio::time::sleep(wait) => {
+                    if let Err(e) = self.run_report(def, next_time.date_naive()).await {
+                        warn!("Report '{}' failed: {:#}", def.name, e);
+                    }
+                }
+            }
+        }
+        info!("Report scheduler shutting down");
+    }
+
+    /// Generate one report and deliver it to all its destinations
+    pub async fn run_report(&self, def: &ReportDefinition, today: NaiveDate) -> Result<Report> {
+        let report = generate(&self.db, def, today).await?;
+        for destination in &def.destinations {
+            if let Err(e) = self.deliver(&report, destination).await {
+                warn!(
+                    "Failed to deliver report '{}' to {:?}: {:#}",
+                    def.name, destination, e
+                );
+            }
+        }
+        info!("Generated report '{}'", def.name);
+        Ok(report)
+    }
+
+    async fn deliver(&self, report: &Report, destination: &ReportDestination) -> Result<()> {
+        match destination {
+            ReportDestination::Channel(channel) => {
+                let tx = self
+                    .outgoing
+                    .as_ref()
+                    .ok_or_else(|| anyhow!("channel delivery needs the daemon"))?;
+                tx.send(OutgoingMessage {
+                    content: report.body.clone(),
+                    channel: channel.clone(),
+                    reply_to: None,
+                    kind: MessageKind::Response,
+                    interactive: None,
+                })
+                .await
+                .map_err(|_| anyhow!("outgoing channel closed"))?;
+            }
+            ReportDestination::Email(to) => {
+                crate::platform::create_email_provider()?
+                    .send_email(to, &report.title, &report.body, None, None)
+                    .await?;
+            }
+            ReportDestination::File(path) => {
+                let path = report_path(&self.workspace, path, report)?;
+                if let Some(parent) = path.parent() {
+                    tokio::fs::create_dir_all(parent).await?;
+                }
+                tokio::fs::write(&path, &report.body)
+                    .await
+                    .with_context(|| format!("Failed to write {}", path.display()))?;
+                debug!("Wrote report to {}", path.display());
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Resolve a file destination inside the workspace
+fn report_path(workspace: &Path, template: &str, report: &Report) -> Result<PathBuf> {
+    let relative = PathBuf::from(render(template, &report.variables));
+    if relative.is_absolute()
+        || relative
+            .components()
+            .any(|c| matches!(c, std::path::Component::ParentDir))
+    {
+        return Err(anyhow!(
+            "Report path must stay inside the workspace: {}",
+            relative.display()
+        ));
+    }
+    Ok(workspace.join(relative))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_render_and_schedule() {
+        let vars = HashMap::from([("name".to_string(), "Weekly review".to_string())]);
+        assert_eq!(
+            render("# {{ name }}\n{{missing}}{{name}} {{ unclosed", &vars),
+            "# Weekly review\nWeekly review {{ unclosed"
+        );
+        assert!(parse_schedule("0 17 * * Fri").is_ok());
+        assert!(parse_schedule("0 0 17 * * Fri").is_ok());
+        assert!(parse_schedule("every friday").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_weekly_review_written_to_workspace() {
+        let temp = TempDir::new().unwrap();
+        let db = Arc::new(KnowledgeDb::new(temp.path().join("test.db")).unwrap());
+        let goal = db
+            .insert_goal("Ship the release", 3, 3600, None, None, "user")
+            .await
+            .unwrap();
+        db.update_goal_status(&goal, "completed").await.unwrap();
+        db.insert_goal("Learn Rust macros", 2, 3600, None, None, "user")
+            .await
+            .unwrap();
+        db.insert_entity("Q3 plan.pdf", "document", None)
+            .await
+            .unwrap();
+        db.insert_background_task("t1", "Summarise inbox", "slack", "agent")
+            .await
+            .unwrap();
+        db.update_background_task("t1", "completed", Some("done"))
+            .await
+            .unwrap();
+
+        let def = ReportDefinition {
+            name: "Weekly review".to_string(),
+            schedule: "0 17 * * Fri".to_string(),
+            period_days: 7,
+            sources: vec![
+                ReportSource::Goals,
+                ReportSource::Tasks,
+                ReportSource::Documents,
+            ],
+            template: None,
+            destinations: vec![ReportDestination::File(
+                "reports/weekly-{{ date }}.md".to_string(),
+            )],
+        };
+        let today = chrono::Utc::now().date_naive();
+        let scheduler = ReportScheduler::new(vec![], db, temp.path().to_path_buf());
+        let report = scheduler.run_report(&def, today).await.unwrap();
+
+        assert!(report.body.starts_with("# Weekly review"));
+        assert!(report.body.contains("**Completed**\n\n- Ship the release"));
+        assert!(report.body.contains("Learn Rust macros (priority 2)"));
+        assert!(report.body.contains("✓ Summarise inbox"));
+        assert!(report.body.contains("- Q3 plan.pdf"));
+        assert!(!report.body.contains("## Usage"));
+
+        let path = temp
+            .path()
+            .join(format!("reports/weekly-{}.md", today.format("%Y-%m-%d")));
+        assert_eq!(std::fs::read_to_string(path).unwrap(), report.body);
+        assert!(report_path(temp.path(), "../escape.md", &report).is_err());
+    }
+}
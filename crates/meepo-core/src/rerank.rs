@@ -0,0 +1,413 @@
+//! Re-ranking of retrieved knowledge
+//!
+//! Keyword search and rank fusion are good at finding candidates but poor at
+//! judging how well each one answers the query, so marginally relevant chunks
+//! reach the context. This optional stage scores the top-N candidates against
+//! the query — with a cross-encoder served over HTTP or a cheap LLM call — and
+//! reorders them before context assembly. Scores are cached per (query, text),
+//! and a latency budget bounds the stage: if scoring overruns it or fails, the
+//! original order is kept.
+
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::num::NonZeroUsize;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+use lru::LruCache;
+use serde_json::Value;
+use tracing::{debug, warn};
+
+use crate::api::{ApiClient, ApiMessage, ContentBlock, MessageContent};
+use crate::providers::overrides::{self, RequestOverrides};
+
+/// Characters of each candidate sent for scoring
+const MAX_TEXT_CHARS: usize = 1500;
+
+/// How candidates are scored
+#[derive(Debug, Clone, PartialEq)]
+pub enum RerankBackend {
+    /// Ask an LLM (a model name or alias such as "haiku") to rate each candidate
+    Llm { model: String },
+    /// POST to a cross-encoder endpoint. Without a model name the request uses
+    /// text-embeddings-inference's `/rerank` format; with one, the
+    /// Cohere/Jina `{"model", "query", "documents"}` format.
+    CrossEncoder { url: String, model: Option<String> },
+}
+
+/// Configuration for re-ranking
+#[derive(Debug, Clone)]
+pub struct RerankConfig {
+    pub backend: RerankBackend,
+    /// Candidates scored per query; the rest keep their order after them
+    pub top_n: usize,
+    /// Time allowed for scoring before falling back to the original order
+    pub budget: Duration,
+    /// (query, text) scores kept in memory
+    pub cache_size: usize,
+    /// Scored candidates below this relevance (0.0 to 1.0) are dropped
+    pub min_score: f32,
+}
+
+impl Default for RerankConfig {
+    fn default() -> Self {
+        Self {
+            backend: RerankBackend::Llm {
+                model: "haiku".to_string(),
+            },
+            top_n: 20,
+            budget: Duration::from_millis(2000),
+            cache_size: 2000,
+            min_score: 0.0,
+        }
+    }
+}
+
+pub struct Reranker {
+    config: RerankConfig,
+    api: Option<ApiClient>,
+    http: reqwest::Client,
+    cache: Mutex<LruCache<u64, f32>>,
+}
+
+impl Reranker {
+    pub fn new(config: RerankConfig) -> Self {
+        let capacity = NonZeroUsize::new(config.cache_size).unwrap_or(NonZeroUsize::MIN);
+        Self {
+            config,
+            api: None,
+            http: reqwest::Client::new(),
+            cache: Mutex::new(LruCache::new(capacity)),
+        }
+    }
+
+    /// API client for the LLM backend
+    pub fn with_api(mut self, api: ApiClient) -> Self {
+        self.api = Some(api);
+        self
+    }
+
+    pub fn top_n(&self) -> usize {
+        self.config.top_n
+    }
+
+    /// Reorder `texts` by relevance to `query`.
+    ///
+    /// Returns `(index, score)` pairs: the first `top_n` texts sorted by
+    /// score (0.0 to 1.0) without those under `min_score`, then the rest in
+    /// their original order at the lowest score given. Returns `None` when
+    /// scoring failed or overran the budget, so the caller keeps its order.
+    pub async fn rerank(&self, query: &str, texts: &[&str]) -> Option<Vec<(usize, f32)>> {
+        let n = texts.len().min(self.config.top_n);
+        if n == 0 {
+            return None;
+        }
+
+        let keys: Vec<u64> = texts[..n].iter().map(|t| cache_key(query, t)).collect();
+        let mut scores: Vec<Option<f32>> = {
+            let mut cache = self.cache.lock().unwrap_or_else(|poisoned| {
+                warn!("Rerank cache mutex was poisoned, recovering");
+                poisoned.into_inner()
+            });
+            keys.iter().map(|k| cache.get(k).copied()).collect()
+        };
+
+        let missing: Vec<usize> = (0..n).filter(|&i| scores[i].is_none()).collect();
+        if !missing.is_empty() {
+            let batch: Vec<&str> = missing.iter().map(|&i| texts[i]).collect();
+            let fresh =
+                match tokio::time::timeout(self.config.budget, self.score(query, &batch)).await {
+                    Ok(Ok(fresh)) => fresh,
+                    Ok(Err(e)) => {
+                        warn!("Re-ranking failed, keeping search order: {:#}", e);
+                        return None;
+                    }
+                    Err(_) => {
+                        warn!(
+                            "Re-ranking exceeded its {}ms budget, keeping search order",
+                            self.config.budget.as_millis()
+                        );
+                        return None;
+                    }
+                };
+            let mut cache = self.cache.lock().unwrap_or_else(|poisoned| {
+                warn!("Rerank cache mutex was poisoned, recovering");
+                poisoned.into_inner()
+            });
+            for (&i, score) in missing.iter().zip(fresh) {
+                cache.put(keys[i], score);
+                scores[i] = Some(score);
+            }
+        }
+        debug!(
+            "Re-ranked {} candidate(s), {} from cache",
+            n,
+            n - missing.len()
+        );
+
+        let mut ranked: Vec<(usize, f32)> = scores
+            .into_iter()
+            .enumerate()
+            .filter_map(|(i, s)| s.map(|s| (i, s)))
+            .filter(|(_, s)| *s >= self.config.min_score)
+            .collect();
+        ranked.sort_by(|a, b| b.1.total_cmp(&a.1));
+        let floor = ranked.last().map_or(0.0, |(_, s)| *s);
+        ranked.extend((n..texts.len()).map(|i| (i, floor)));
+        Some(ranked)
+    }
+
+    /// Relevance of each text to the query, 0.0 to 1.0
+    async fn score(&self, query: &str, texts: &[&str]) -> Result<Vec<f32>> {
+        let texts: Vec<String> = texts.iter().map(|t| truncate(t)).collect();
+        match &self.config.backend {
+            RerankBackend::Llm { model } => self.score_llm(model, query, &texts).await,
+            RerankBackend::CrossEncoder { url, model } => {
+                self.score_cross_encoder(url, model.as_deref(), query, &texts)
+                    .await
+            }
+        }
+    }
+
+    async fn score_llm(&self, model: &str, query: &str, texts: &[String]) -> Result<Vec<f32>> {
+        let api = self
+            .api
+            .as_ref()
+            .context("LLM re-ranking needs an API client")?;
+        let mut prompt = format!("Query: {}\n\nDocuments:\n", query);
+        for (i, text) in texts.iter().enumerate() {
+            prompt.push_str(&format!("[{}] {}\n\n", i + 1, text.replace('\n', " ")));
+        }
+        let messages = vec![ApiMessage {
+            role: "user".to_string(),
+            content: MessageContent::Text(prompt),
+        }];
+        let system = "You rate how well documents answer a search query. For each document, \
+                      reply with one line `N: score`, where N is the document number and \
+                      score is 0 (unrelated) to 10 (directly answers the query). Reply with \
+                      nothing else.";
+        let response = overrides::scope(
+            RequestOverrides {
+                model: Some(overrides::resolve_alias(model)),
+                ..Default::default()
+            },
+            api.chat(&messages, &[], system),
+        )
+        .await?;
+        let reply: String = response
+            .content
+            .iter()
+            .filter_map(|b| match b {
+                ContentBlock::Text { text } => Some(text.as_str()),
+                _ => None,
+            })
+            .collect();
+        parse_llm_scores(&reply, texts.len())
+    }
+
+    async fn score_cross_encoder(
+        &self,
+        url: &str,
+        model: Option<&str>,
+        query: &str,
+        texts: &[String],
+    ) -> Result<Vec<f32>> {
+        if !crate::network::is_local_url(url) {
+            crate::network::ensure_online("the cross-encoder re-ranker")?;
+        }
+        let body = match model {
+            Some(model) => serde_json::json!({
+                "model": model,
+                "query": query,
+                "documents": texts,
+            }),
+            None => serde_json::json!({"query": query, "texts": texts}),
+        };
+        let response: Value = self
+            .http
+            .post(url)
+            .json(&body)
+            .send()
+            .await
+            .context("Failed to reach cross-encoder")?
+            .error_for_status()
+            .context("Cross-encoder returned an error")?
+            .json()
+            .await
+            .context("Failed to parse cross-encoder response")?;
+        parse_cross_encoder_scores(&response, texts.len())
+    }
+}
+
+/// Parse `N: score` lines (0 to 10) into scores for `count` documents.
+/// Documents the model skipped score 0.
+fn parse_llm_scores(reply: &str, count: usize) -> Result<Vec<f32>> {
+    let mut scores = vec![0.0; count];
+    let mut parsed = 0;
+    for line in reply.lines() {
+        let Some((index, rest)) = line.split_once(':') else {
+            continue;
+        };
+        let index = index.trim().trim_start_matches('[').trim_end_matches(']');
+        let score = rest
+            .split_whitespace()
+            .next()
+            .and_then(|s| s.split('/').next())
+            .and_then(|s| s.trim_end_matches([',', '.']).parse::<f32>().ok());
+        if let (Ok(index), Some(score)) = (index.parse::<usize>(), score)
+            && (1..=count).contains(&index)
+        {
+            scores[index - 1] = (score / 10.0).clamp(0.0, 1.0);
+            parsed += 1;
+        }
+    }
+    if parsed == 0 {
+        anyhow::bail!("no scores in re-ranking reply: {}", reply.trim());
+    }
+    Ok(scores)
+}
+
+/// Scores from `[{"index", "score"}]` (text-embeddings-inference) or
+/// `{"results": [{"index", "relevance_score"}]}` (Cohere, Jina)
+fn parse_cross_encoder_scores(response: &Value, count: usize) -> Result<Vec<f32>> {
+    let results = response
+        .as_array()
+        .or_else(|| response.get("results").and_then(|r| r.as_array()))
+        .context("Unexpected cross-encoder response shape")?;
+    let mut scores = vec![0.0; count];
+    for result in results {
+        let index = result.get("index").and_then(|i| i.as_u64());
+        let score = result
+            .get("relevance_score")
+            .or_else(|| result.get("score"))
+            .and_then(|s| s.as_f64());
+        if let (Some(index), Some(score)) = (index, score)
+            && (index as usize) < count
+        {
+            scores[index as usize] = score as f32;
+        }
+    }
+    Ok(scores)
+}
+
+fn cache_key(query: &str, text: &str) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    query.trim().to_lowercase().hash(&mut hasher);
+    text.hash(&mut hasher);
+    hasher.finish()
+}
+
+fn truncate(text: &str) -> String {
+    text.chars().take(MAX_TEXT_CHARS).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::providers::types::{ChatResponseBlock, ChatUsage, StopReason};
+    use crate::providers::{ChatMessage, ChatResponse, LlmProvider, ModelRouter};
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    struct ScriptedProvider {
+        calls: Arc<AtomicUsize>,
+        delay: Duration,
+    }
+
+    #[async_trait::async_trait]
+    impl LlmProvider for ScriptedProvider {
+        fn provider_name(&self) -> &str {
+            "scripted"
+        }
+        fn model(&self) -> &str {
+            "scripted-1"
+        }
+        async fn chat(
+            &self,
+            _messages: &[ChatMessage],
+            _tools: &[crate::api::ToolDefinition],
+            _system: &str,
+        ) -> Result<ChatResponse> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            tokio::time::sleep(self.delay).await;
+            Ok(ChatResponse {
+                blocks: vec![ChatResponseBlock::Text {
+                    text: "1: 2\n[2]: 9/10\n3: 0".to_string(),
+                }],
+                stop_reason: StopReason::EndTurn,
+                usage: ChatUsage {
+                    input_tokens: 100,
+                    output_tokens: 10,
+                },
+            })
+        }
+    }
+
+    fn reranker(calls: Arc<AtomicUsize>, delay: Duration, config: RerankConfig) -> Reranker {
+        let api = ApiClient::from_router(ModelRouter::single(Box::new(ScriptedProvider {
+            calls,
+            delay,
+        })));
+        Reranker::new(config).with_api(api)
+    }
+
+    #[tokio::test]
+    async fn test_llm_rerank_orders_filters_and_caches() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let reranker = reranker(
+            calls.clone(),
+            Duration::ZERO,
+            RerankConfig {
+                top_n: 3,
+                min_score: 0.1,
+                ..Default::default()
+            },
+        );
+        let texts = [
+            "lunch menu",
+            "deploy steps for prod",
+            "weather",
+            "tail item",
+        ];
+
+        let ranked = reranker.rerank("how to deploy", &texts).await.unwrap();
+        // "weather" scored 0 and is dropped; the unscored tail follows
+        assert_eq!(ranked, vec![(1, 0.9), (0, 0.2), (3, 0.2)]);
+
+        // Same query and texts are served from the cache
+        let again = reranker.rerank("How to deploy ", &texts).await.unwrap();
+        assert_eq!(again, ranked);
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_rerank_over_budget_keeps_order() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let reranker = reranker(
+            calls,
+            Duration::from_millis(500),
+            RerankConfig {
+                budget: Duration::from_millis(20),
+                ..Default::default()
+            },
+        );
+        assert!(reranker.rerank("q", &["a", "b"]).await.is_none());
+    }
+
+    #[test]
+    fn test_parse_cross_encoder_formats() {
+        let tei = serde_json::json!([{"index": 1, "score": 0.8}, {"index": 0, "score": 0.1}]);
+        assert_eq!(parse_cross_encoder_scores(&tei, 2).unwrap(), vec![0.1, 0.8]);
+
+        let cohere = serde_json::json!({"results": [
+            {"index": 0, "relevance_score": 0.7},
+            {"index": 5, "relevance_score": 0.9}
+        ]});
+        assert_eq!(
+            parse_cross_encoder_scores(&cohere, 2).unwrap(),
+            vec![0.7, 0.0]
+        );
+        assert!(parse_cross_encoder_scores(&serde_json::json!({}), 2).is_err());
+    }
+}
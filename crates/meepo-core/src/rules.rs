@@ -0,0 +1,577 @@
+//! Declarative automation rules — "when X then Y" without an LLM call
+//!
+//! A rule pairs an event type (a watcher event kind like `email_received`,
+//! `message_received` for incoming messages, or `*`) and a filter of
+//! `field → text` pairs (case-insensitive substring match on the event
+//! payload) and an optional condition expression (see [`crate::expr`]) with a
+//! direct action: send a notification, send a message template (see
+//! [`crate::message_templates`]), run a tool with fixed arguments, or queue a
+//! prompt for the agent. `{field}` placeholders in the action are filled
+//! from the event. Rules are evaluated in order for every event and
+//! stored in the `rules` table.
+
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+use anyhow::{Result, anyhow, bail};
+use meepo_knowledge::{KnowledgeDb, Rule};
+use meepo_scheduler::WatcherEvent;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tracing::{debug, info, warn};
+
+use crate::autonomy::action_log::{ActionRisk, classify_tool};
+use crate::expr::Expression;
+use crate::message_templates::{self, TemplateStore};
+use crate::tools::ToolExecutor;
+use crate::types::{ChannelType, IncomingMessage, MessageKind, OutgoingMessage};
+
+/// Event type for incoming user messages
+pub const MESSAGE_RECEIVED: &str = "message_received";
+
+/// Event type matching every event
+pub const ANY_EVENT: &str = "*";
+
+/// Tool output longer than this is cut before it's sent
+const MAX_TOOL_OUTPUT: usize = 2000;
+
+/// What a rule does when it fires
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum RuleAction {
+    /// Send a message to the rule's channel
+    Notify { message: String },
+    /// Send a message template; variables not given come from the event
+    Template {
+        template: String,
+        #[serde(default)]
+        vars: Value,
+    },
+    /// Run a tool with fixed arguments and send its output
+    RunTool {
+        tool: String,
+        #[serde(default)]
+        args: Value,
+    },
+    /// Hand a prompt to the agent and send its reply
+    Prompt { prompt: String },
+}
+
+impl RuleAction {
+    /// Rules run unattended, so destructive (or unknown) tools are refused
+    pub fn validate(&self) -> Result<()> {
+        match self {
+            Self::Notify { message } if message.trim().is_empty() => {
+                bail!("Notify rules need a message")
+            }
+            Self::Prompt { prompt } if prompt.trim().is_empty() => {
+                bail!("Prompt rules need a prompt")
+            }
+            Self::Template { template, .. } if template.trim().is_empty() => {
+                bail!("Template rules need a template name")
+            }
+            Self::Template { vars, .. } if !(vars.is_object() || vars.is_null()) => {
+                bail!("Template variables must be a JSON object")
+            }
+            Self::RunTool { tool, args } => {
+                if classify_tool(tool) == ActionRisk::Destructive {
+                    bail!(
+                        "Tool '{}' can't run from a rule (destructive or unknown); use a prompt rule instead",
+                        tool
+                    );
+                }
+                if !(args.is_object() || args.is_null()) {
+                    bail!("Tool arguments must be a JSON object");
+                }
+                Ok(())
+            }
+            _ => Ok(()),
+        }
+    }
+
+    pub fn describe(&self) -> String {
+        match self {
+            Self::Notify { message } => format!("notify \"{}\"", message),
+            Self::Template { template, vars } if vars.is_null() => {
+                format!("send template {}", template)
+            }
+            Self::Template { template, vars } => format!("send template {} {}", template, vars),
+            Self::RunTool { tool, args } if args.is_null() => format!("run {}", tool),
+            Self::RunTool { tool, args } => format!("run {} {}", tool, args),
+            Self::Prompt { prompt } => format!("ask agent \"{}\"", prompt),
+        }
+    }
+}
+
+/// Something that happened, as rules see it
+#[derive(Debug, Clone)]
+pub struct RuleEvent {
+    pub kind: String,
+    /// Watcher ID or `channel:sender`, matched by the `source` filter key
+    pub source: String,
+    pub payload: Value,
+}
+
+impl RuleEvent {
+    pub fn from_watcher(event: &WatcherEvent) -> Self {
+        Self {
+            kind: event.kind.clone(),
+            source: event.watcher_id.clone(),
+            payload: event.payload.clone(),
+        }
+    }
+
+    pub fn from_message(msg: &IncomingMessage) -> Self {
+        Self {
+            kind: MESSAGE_RECEIVED.to_string(),
+            source: format!("{}:{}", msg.channel, msg.sender),
+            payload: serde_json::json!({
+                "channel": msg.channel.to_string(),
+                "sender": msg.sender,
+                "content": msg.content,
+            }),
+        }
+    }
+
+    /// What condition expressions see: `kind`, `source` and `payload`
+    pub fn vars(&self) -> Value {
+        serde_json::json!({
+            "kind": self.kind,
+            "source": self.source,
+            "payload": self.payload,
+        })
+    }
+
+    /// A payload field by dotted path (`source` is the event source)
+    fn field(&self, path: &str) -> Option<String> {
+        if path == "source" {
+            return Some(self.source.clone());
+        }
+        let value = path
+            .split('.')
+            .try_fold(&self.payload, |value, key| value.get(key))?;
+        match value {
+            Value::String(s) => Some(s.clone()),
+            Value::Null => None,
+            other => Some(other.to_string()),
+        }
+    }
+
+    /// Fill `{field}` placeholders; unknown fields are left as they are
+    fn render(&self, template: &str) -> String {
+        message_templates::fill(template, |field| self.field(field)).0
+    }
+
+    /// Variables for a message template: the payload and `source`, then
+    /// `vars` (with placeholders filled) on top
+    fn template_vars(&self, vars: &Value) -> Value {
+        let mut merged = match &self.payload {
+            Value::Object(payload) => payload.clone(),
+            _ => serde_json::Map::new(),
+        };
+        merged.insert("source".to_string(), Value::String(self.source.clone()));
+        if let Value::Object(vars) = self.render_value(vars) {
+            merged.extend(vars);
+        }
+        Value::Object(merged)
+    }
+
+    fn render_value(&self, value: &Value) -> Value {
+        match value {
+            Value::String(s) => Value::String(self.render(s)),
+            Value::Array(items) => {
+                Value::Array(items.iter().map(|v| self.render_value(v)).collect())
+            }
+            Value::Object(map) => Value::Object(
+                map.iter()
+                    .map(|(k, v)| (k.clone(), self.render_value(v)))
+                    .collect(),
+            ),
+            other => other.clone(),
+        }
+    }
+}
+
+/// Whether a rule's event type, filter and condition match an event
+pub fn matches(rule: &Rule, event: &RuleEvent) -> bool {
+    if rule.event != ANY_EVENT && !rule.event.eq_ignore_ascii_case(&event.kind) {
+        return false;
+    }
+    let filtered = rule.filter.as_object().is_none_or(|filter| {
+        filter.iter().all(|(field, expected)| {
+            let expected = match expected {
+                Value::String(s) => s.to_lowercase(),
+                other => other.to_string(),
+            };
+            event
+                .field(field)
+                .is_some_and(|actual| actual.to_lowercase().contains(&expected))
+        })
+    });
+    let Some(condition) = rule.condition.as_deref().filter(|_| filtered) else {
+        return filtered;
+    };
+    match Expression::parse(condition).and_then(|expr| expr.matches(&event.vars())) {
+        Ok(matched) => matched,
+        Err(e) => {
+            warn!("Rule '{}' condition failed: {}", rule.name, e);
+            false
+        }
+    }
+}
+
+/// " where field ~ \"text\", ..." for a rule filter (empty if none)
+pub fn describe_filter(filter: &Value) -> String {
+    let Some(filter) = filter.as_object().filter(|f| !f.is_empty()) else {
+        return String::new();
+    };
+    let parts: Vec<String> = filter
+        .iter()
+        .map(|(k, v)| match v {
+            Value::String(s) => format!("{} ~ \"{}\"", k, s),
+            other => format!("{} ~ {}", k, other),
+        })
+        .collect();
+    format!(" where {}", parts.join(", "))
+}
+
+/// One-line summary of a rule
+pub fn describe(rule: &Rule) -> String {
+    let action = serde_json::from_value::<RuleAction>(rule.action.clone())
+        .map(|a| a.describe())
+        .unwrap_or_else(|_| "invalid action".to_string());
+    let condition = rule
+        .condition
+        .as_ref()
+        .map(|c| format!(" if {}", c))
+        .unwrap_or_default();
+    format!(
+        "{} '{}'{}: when {}{}{} → {} (to {}, fired {}×){}",
+        rule.id,
+        rule.name,
+        if rule.enabled { "" } else { " [disabled]" },
+        rule.event,
+        describe_filter(&rule.filter),
+        condition,
+        action,
+        rule.reply_channel,
+        rule.fire_count,
+        if rule.exclusive { ", exclusive" } else { "" }
+    )
+}
+
+/// Validate and store a new rule, returning its ID
+#[allow(clippy::too_many_arguments)]
+pub async fn create_rule(
+    db: &KnowledgeDb,
+    name: &str,
+    event: &str,
+    filter: &BTreeMap<String, String>,
+    condition: Option<&str>,
+    action: &RuleAction,
+    reply_channel: &str,
+    exclusive: bool,
+) -> Result<String> {
+    let event = event.trim();
+    if name.trim().is_empty() || event.is_empty() {
+        bail!("Rules need a name and an event type");
+    }
+    let condition = condition.map(str::trim).filter(|c| !c.is_empty());
+    if let Some(condition) = condition {
+        Expression::parse(condition).map_err(|e| anyhow!("Invalid condition: {}", e))?;
+    }
+    action.validate()?;
+    let channel = reply_channel.trim().to_lowercase();
+    if channel != "internal" && ChannelType::from_string(&channel) == ChannelType::Internal {
+        bail!("Unknown channel '{}'", reply_channel);
+    }
+    db.insert_rule(
+        name.trim(),
+        event,
+        serde_json::to_value(filter)?,
+        condition,
+        serde_json::to_value(action)?,
+        &channel,
+        exclusive,
+    )
+    .await
+}
+
+/// What firing rules produced for one event
+#[derive(Debug, Default)]
+pub struct RuleOutcome {
+    /// Messages to send right away
+    pub messages: Vec<OutgoingMessage>,
+    /// Prompts for the agent; reply to the message's channel
+    pub prompts: Vec<IncomingMessage>,
+    /// An exclusive rule fired, so the event shouldn't also go to the agent
+    pub handled: bool,
+}
+
+/// Evaluates stored rules against events
+pub struct RuleEngine {
+    db: Arc<KnowledgeDb>,
+    tools: Arc<dyn ToolExecutor>,
+    templates: Option<Arc<TemplateStore>>,
+}
+
+impl RuleEngine {
+    pub fn new(db: Arc<KnowledgeDb>, tools: Arc<dyn ToolExecutor>) -> Self {
+        Self {
+            db,
+            tools,
+            templates: None,
+        }
+    }
+
+    /// Let template rules render from `templates`
+    pub fn with_templates(mut self, templates: Arc<TemplateStore>) -> Self {
+        self.templates = Some(templates);
+        self
+    }
+
+    /// Fire every enabled rule matching the event
+    pub async fn evaluate(&self, event: &RuleEvent) -> RuleOutcome {
+        let mut outcome = RuleOutcome::default();
+        let rules = match self.db.get_rules(true).await {
+            Ok(rules) => rules,
+            Err(e) => {
+                warn!("Failed to load rules: {}", e);
+                return outcome;
+            }
+        };
+        for rule in rules.iter().filter(|r| matches(r, event)) {
+            let action: RuleAction = match serde_json::from_value(rule.action.clone()) {
+                Ok(action) => action,
+                Err(e) => {
+                    warn!("Rule '{}' has an invalid action: {}", rule.name, e);
+                    continue;
+                }
+            };
+            info!("Rule '{}' fired on {}", rule.name, event.kind);
+            let channel = ChannelType::from_string(&rule.reply_channel);
+            match self.fire(rule, &action, event).await {
+                Ok(Fired::Message(content)) => outcome.messages.push(OutgoingMessage {
+                    content,
+                    channel,
+                    reply_to: None,
+                    kind: MessageKind::Response,
+                    interactive: None,
+                }),
+                Ok(Fired::Prompt(content)) => outcome.prompts.push(IncomingMessage {
+                    id: uuid::Uuid::new_v4().to_string(),
+                    sender: "rule".to_string(),
+                    content,
+                    channel,
+                    timestamp: chrono::Utc::now(),
+                }),
+                Err(e) => {
+                    warn!("Rule '{}' failed: {}", rule.name, e);
+                    continue;
+                }
+            }
+            outcome.handled |= rule.exclusive;
+            if let Err(e) = self.db.record_rule_fired(&rule.id).await {
+                debug!("Failed to record rule firing: {}", e);
+            }
+        }
+        outcome
+    }
+
+    async fn fire(&self, rule: &Rule, action: &RuleAction, event: &RuleEvent) -> Result<Fired> {
+        match action {
+            RuleAction::Notify { message } => Ok(Fired::Message(event.render(message))),
+            RuleAction::Template { template, vars } => {
+                let templates = self
+                    .templates
+                    .as_ref()
+                    .ok_or_else(|| anyhow!("message templates aren't available"))?;
+                let text = templates
+                    .render(template, &event.template_vars(vars))
+                    .await?;
+                Ok(Fired::Message(text))
+            }
+            RuleAction::RunTool { tool, args } => {
+                // Re-checked here in case the rule was stored by an older version
+                action.validate()?;
+                let args = match args {
+                    Value::Null => serde_json::json!({}),
+                    args => event.render_value(args),
+                };
+                let output = self
+                    .tools
+                    .execute(tool, args)
+                    .await
+                    .map_err(|e| anyhow!("{} failed: {}", tool, e))?;
+                let output = if output.chars().count() > MAX_TOOL_OUTPUT {
+                    let cut: String = output.chars().take(MAX_TOOL_OUTPUT).collect();
+                    format!("{}…", cut)
+                } else {
+                    output
+                };
+                Ok(Fired::Message(format!("[{}] {}", rule.name, output)))
+            }
+            RuleAction::Prompt { prompt } => Ok(Fired::Prompt(format!(
+                "Rule '{}' fired on {}: {}\nYour requested action: {}",
+                rule.name,
+                event.kind,
+                event.payload,
+                event.render(prompt)
+            ))),
+        }
+    }
+}
+
+enum Fired {
+    Message(String),
+    Prompt(String),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tools::ToolRegistry;
+
+    fn email_event() -> RuleEvent {
+        RuleEvent::from_watcher(&WatcherEvent::email(
+            "w-1".to_string(),
+            "Boss@Company.com".to_string(),
+            "Quarterly numbers".to_string(),
+            "See attached".to_string(),
+        ))
+    }
+
+    #[tokio::test]
+    async fn test_rules_fire_without_llm() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let db = Arc::new(KnowledgeDb::new(temp.path().join("test.db")).unwrap());
+        let filter = BTreeMap::from([("from".to_string(), "boss@".to_string())]);
+        create_rule(
+            &db,
+            "boss mail",
+            "email_received",
+            &filter,
+            Some("payload.subject contains \"numbers\" && hour() >= 0"),
+            &RuleAction::Notify {
+                message: "Mail from {from}: {subject} {missing}".to_string(),
+            },
+            "imessage",
+            true,
+        )
+        .await
+        .unwrap();
+        create_rule(
+            &db,
+            "summarize",
+            ANY_EVENT,
+            &BTreeMap::from([("source".to_string(), "w-2".to_string())]),
+            None,
+            &RuleAction::Prompt {
+                prompt: "Summarize it".to_string(),
+            },
+            "slack",
+            false,
+        )
+        .await
+        .unwrap();
+        assert!(
+            create_rule(
+                &db,
+                "nope",
+                "file_changed",
+                &BTreeMap::new(),
+                None,
+                &RuleAction::RunTool {
+                    tool: "run_command".to_string(),
+                    args: serde_json::json!({"command": "rm -rf /"}),
+                },
+                "internal",
+                false,
+            )
+            .await
+            .is_err()
+        );
+        create_rule(
+            &db,
+            "after hours",
+            "email_received",
+            &BTreeMap::new(),
+            Some("payload.from endsWith \"@company.com\" && hour() > 24"),
+            &RuleAction::Notify {
+                message: "never".to_string(),
+            },
+            "internal",
+            false,
+        )
+        .await
+        .unwrap();
+        assert!(
+            create_rule(
+                &db,
+                "bad",
+                ANY_EVENT,
+                &BTreeMap::new(),
+                Some("payload.from contains"),
+                &RuleAction::Notify {
+                    message: "x".to_string(),
+                },
+                "internal",
+                false,
+            )
+            .await
+            .is_err()
+        );
+
+        create_rule(
+            &db,
+            "boss template",
+            "email_received",
+            &filter,
+            None,
+            &RuleAction::Template {
+                template: "boss_mail".to_string(),
+                vars: serde_json::json!({"who": "{from}"}),
+            },
+            "internal",
+            false,
+        )
+        .await
+        .unwrap();
+        let templates = Arc::new(TemplateStore::new(db.clone()));
+        templates
+            .save("boss_mail", "{who} wrote about {subject}", None)
+            .await
+            .unwrap();
+
+        let engine =
+            RuleEngine::new(db.clone(), Arc::new(ToolRegistry::new())).with_templates(templates);
+        let outcome = engine.evaluate(&email_event()).await;
+        assert!(outcome.handled);
+        assert!(outcome.prompts.is_empty());
+        assert_eq!(outcome.messages.len(), 2);
+        assert_eq!(
+            outcome.messages[0].content,
+            "Mail from Boss@Company.com: Quarterly numbers {missing}"
+        );
+        assert_eq!(outcome.messages[0].channel, ChannelType::IMessage);
+        assert_eq!(
+            outcome.messages[1].content,
+            "Boss@Company.com wrote about Quarterly numbers"
+        );
+
+        let msg = IncomingMessage {
+            id: "1".to_string(),
+            sender: "alice".to_string(),
+            content: "hello".to_string(),
+            channel: ChannelType::Slack,
+            timestamp: chrono::Utc::now(),
+        };
+        let outcome = engine.evaluate(&RuleEvent::from_message(&msg)).await;
+        assert!(!outcome.handled);
+        assert!(outcome.messages.is_empty() && outcome.prompts.is_empty());
+
+        let rules = db.get_rules(false).await.unwrap();
+        assert_eq!(rules[0].fire_count, 1);
+        assert_eq!(rules[1].fire_count, 0);
+    }
+}
@@ -2,11 +2,17 @@
 
 use anyhow::{Context, Result, anyhow};
 use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::process::{Output, Stdio};
+use tokio::io::AsyncWriteExt;
 use tokio::process::Command;
 use tracing::{debug, info, warn};
 
 use super::policy::{ExecutionPolicy, ResourceLimits};
 
+/// Maximum stdin payload passed into a container
+const MAX_STDIN_BYTES: usize = 1024 * 1024;
+
 /// Configuration for the Docker sandbox
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SandboxConfig {
@@ -70,6 +76,22 @@ impl DockerSandbox {
         language: &str,
         code: &str,
         limits: Option<ResourceLimits>,
+    ) -> Result<SandboxResult> {
+        self.execute_with_io(language, code, None, None, limits)
+            .await
+    }
+
+    /// Execute code with optional stdin and a writable output directory.
+    ///
+    /// When `output_dir` is set it is mounted read-write at `/out` inside the
+    /// container (also exposed as `$OUTPUT_DIR`) so scripts can produce files.
+    pub async fn execute_with_io(
+        &self,
+        language: &str,
+        code: &str,
+        stdin: Option<&str>,
+        output_dir: Option<&Path>,
+        limits: Option<ResourceLimits>,
     ) -> Result<SandboxResult> {
         if !self.config.enabled {
             return Err(anyhow!("Docker sandbox is not enabled in configuration"));
@@ -109,6 +131,12 @@ impl DockerSandbox {
             return Err(anyhow!("Code too large (max 100KB)"));
         }
 
+        if let Some(input) = stdin
+            && input.len() > MAX_STDIN_BYTES
+        {
+            return Err(anyhow!("Stdin too large (max 1MB)"));
+        }
+
         debug!(
             "Sandbox: executing {} code ({} bytes) in {}",
             language,
@@ -130,6 +158,7 @@ impl DockerSandbox {
         let mut args = vec![
             "run".to_string(),
             "--rm".to_string(),
+            "-i".to_string(),
             "--name".to_string(),
             container_name.clone(),
             // Resource limits
@@ -164,6 +193,16 @@ impl DockerSandbox {
         args.push("-v".to_string());
         args.push(format!("{}:/tmp/code.{}:ro", code_file.display(), ext));
 
+        // Mount writable output directory for generated files
+        if let Some(dir) = output_dir {
+            args.push("-v".to_string());
+            args.push(format!("{}:/out:rw", dir.display()));
+            args.push("-e".to_string());
+            args.push("OUTPUT_DIR=/out".to_string());
+            args.push("-w".to_string());
+            args.push("/out".to_string());
+        }
+
         // Image
         args.push(image.to_string());
 
@@ -171,9 +210,10 @@ impl DockerSandbox {
         args.extend(run_cmd);
 
         // Execute with timeout
+        let stdin_data = stdin.unwrap_or_default().as_bytes().to_vec();
         let result = tokio::time::timeout(
             std::time::Duration::from_secs(limits.timeout_secs),
-            Command::new("docker").args(&args).output(),
+            run_with_stdin(&args, stdin_data),
         )
         .await;
 
@@ -239,6 +279,38 @@ impl DockerSandbox {
     }
 }
 
+/// Spawn `docker` with the given args, feed stdin, and collect output
+async fn run_with_stdin(args: &[String], stdin_data: Vec<u8>) -> std::io::Result<Output> {
+    let mut command = Command::new("docker");
+    command.args(args);
+    pipe_through(command, stdin_data).await
+}
+
+/// Run `command` with `stdin_data` on stdin, collecting its output. Stdin is
+/// written from its own task while the output is drained, so a child that
+/// fills its stdout pipe before reading all of stdin can't deadlock us.
+async fn pipe_through(mut command: Command, stdin_data: Vec<u8>) -> std::io::Result<Output> {
+    let mut child = command
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true)
+        .spawn()?;
+
+    let writer = child.stdin.take().map(|mut child_stdin| {
+        tokio::spawn(async move {
+            // A script that never reads stdin closes the pipe early; that's fine
+            let _ = child_stdin.write_all(&stdin_data).await;
+        })
+    });
+
+    let output = child.wait_with_output().await;
+    if let Some(writer) = writer {
+        let _ = writer.await;
+    }
+    output
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -263,6 +335,21 @@ mod tests {
         assert!(json.contains("\"exit_code\":0"));
     }
 
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_pipe_through_large_input() {
+        // Far more than a pipe buffer, echoed back before stdin is done
+        let input = vec![b'x'; 1 << 20];
+        let output = tokio::time::timeout(
+            std::time::Duration::from_secs(10),
+            pipe_through(Command::new("cat"), input.clone()),
+        )
+        .await
+        .expect("deadlocked")
+        .unwrap();
+        assert_eq!(output.stdout, input);
+    }
+
     #[tokio::test]
     async fn test_sandbox_disabled() {
         let sandbox = DockerSandbox::new(SandboxConfig::default());
@@ -293,4 +380,18 @@ mod tests {
         assert!(result.is_err());
         assert!(result.unwrap_err().to_string().contains("too large"));
     }
+
+    #[tokio::test]
+    async fn test_sandbox_stdin_too_large() {
+        let sandbox = DockerSandbox::new(SandboxConfig {
+            enabled: true,
+            ..Default::default()
+        });
+        let large_stdin = "x".repeat(MAX_STDIN_BYTES + 1);
+        let result = sandbox
+            .execute_with_io("python", "print(1)", Some(&large_stdin), None, None)
+            .await;
+        assert!(result.is_err());
+        assert!(result.unwrap_err().to_string().contains("Stdin too large"));
+    }
 }
@@ -0,0 +1,217 @@
+//! Shared scratchpad for a clone group
+//!
+//! Clones in one task group share a bounded notes area: each can read what
+//! the others wrote and add its own notes, tagged with its task ID and a
+//! topic. That lets a researcher leave findings for a writer, and a
+//! reviewer comment on the draft, without routing everything through the
+//! prime Meepo's prompt. The pad lives only as long as the group.
+
+use std::sync::{Arc, Mutex};
+
+use anyhow::{Result, anyhow};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde_json::Value;
+
+use crate::api::ToolDefinition;
+use crate::tools::{ToolExecutor, json_schema};
+
+pub const READ_TOOL: &str = "scratchpad_read";
+pub const WRITE_TOOL: &str = "scratchpad_write";
+
+/// One note on the pad
+#[derive(Debug, Clone)]
+pub struct ScratchNote {
+    /// Task ID of the clone that wrote it
+    pub author: String,
+    pub topic: String,
+    pub text: String,
+    pub written_at: DateTime<Utc>,
+}
+
+/// Notes shared by the clones of one task group
+#[derive(Debug)]
+pub struct Scratchpad {
+    notes: Mutex<Vec<ScratchNote>>,
+    max_notes: usize,
+    max_note_chars: usize,
+}
+
+impl Scratchpad {
+    pub fn new(max_notes: usize, max_note_chars: usize) -> Self {
+        Self {
+            notes: Mutex::new(Vec::new()),
+            max_notes,
+            max_note_chars,
+        }
+    }
+
+    /// Add a note, cut to the length limit. Fails once the pad is full.
+    pub fn append(&self, author: &str, topic: &str, text: &str) -> Result<usize> {
+        let mut notes = self.notes.lock().unwrap_or_else(|p| p.into_inner());
+        if notes.len() >= self.max_notes {
+            return Err(anyhow!(
+                "The scratchpad is full ({} notes); read it and work with what's there",
+                self.max_notes
+            ));
+        }
+        let text = text.trim();
+        let mut note: String = text.chars().take(self.max_note_chars).collect();
+        if text.chars().count() > self.max_note_chars {
+            note.push('…');
+        }
+        notes.push(ScratchNote {
+            author: author.to_string(),
+            topic: topic.trim().to_lowercase(),
+            text: note,
+            written_at: Utc::now(),
+        });
+        Ok(notes.len())
+    }
+
+    /// Notes in the order they were written, optionally on one topic
+    pub fn read(&self, topic: Option<&str>) -> Vec<ScratchNote> {
+        let notes = self.notes.lock().unwrap_or_else(|p| p.into_inner());
+        let topic = topic.map(|t| t.trim().to_lowercase());
+        notes
+            .iter()
+            .filter(|n| topic.as_ref().is_none_or(|t| &n.topic == t))
+            .cloned()
+            .collect()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.notes
+            .lock()
+            .unwrap_or_else(|p| p.into_inner())
+            .is_empty()
+    }
+
+    /// Notes as markdown, one section per note
+    pub fn render(&self, topic: Option<&str>) -> String {
+        self.read(topic)
+            .iter()
+            .enumerate()
+            .map(|(i, n)| format!("#{} [{}] {}:\n{}", i + 1, n.topic, n.author, n.text))
+            .collect::<Vec<_>>()
+            .join("\n\n")
+    }
+}
+
+/// Gives one clone the scratchpad tools on top of its own toolset
+pub struct ScratchpadToolExecutor<E> {
+    inner: E,
+    pad: Arc<Scratchpad>,
+    author: String,
+}
+
+impl<E: ToolExecutor> ScratchpadToolExecutor<E> {
+    pub fn new(inner: E, pad: Arc<Scratchpad>, author: impl Into<String>) -> Self {
+        Self {
+            inner,
+            pad,
+            author: author.into(),
+        }
+    }
+}
+
+#[async_trait]
+impl<E: ToolExecutor> ToolExecutor for ScratchpadToolExecutor<E> {
+    async fn execute(&self, tool_name: &str, input: Value) -> Result<String> {
+        let topic = input.get("topic").and_then(|v| v.as_str());
+        match tool_name {
+            READ_TOOL => {
+                let notes = self.pad.render(topic);
+                Ok(if notes.is_empty() {
+                    "The scratchpad has no notes yet.".to_string()
+                } else {
+                    notes
+                })
+            }
+            WRITE_TOOL => {
+                let text = input
+                    .get("text")
+                    .and_then(|v| v.as_str())
+                    .ok_or_else(|| anyhow!("Missing 'text' parameter"))?;
+                let n = self
+                    .pad
+                    .append(&self.author, topic.unwrap_or("notes"), text)?;
+                Ok(format!("Added note #{} to the scratchpad.", n))
+            }
+            _ => self.inner.execute(tool_name, input).await,
+        }
+    }
+
+    fn list_tools(&self) -> Vec<ToolDefinition> {
+        let mut tools = self.inner.list_tools();
+        tools.push(ToolDefinition {
+            name: READ_TOOL.to_string(),
+            description: "Read the notes your fellow clones left on the shared scratchpad, \
+                          optionally only those on one topic."
+                .to_string(),
+            input_schema: json_schema(
+                serde_json::json!({
+                    "topic": {"type": "string", "description": "Only notes on this topic"}
+                }),
+                vec![],
+            ),
+        });
+        tools.push(ToolDefinition {
+            name: WRITE_TOOL.to_string(),
+            description: "Leave a note on the shared scratchpad for the other clones in this \
+                          group: findings, a draft, review comments. Keep it short."
+                .to_string(),
+            input_schema: json_schema(
+                serde_json::json!({
+                    "topic": {
+                        "type": "string",
+                        "description": "Short topic, e.g. 'sources', 'draft', 'review'"
+                    },
+                    "text": {"type": "string"}
+                }),
+                vec!["text"],
+            ),
+        });
+        tools
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tools::ToolRegistry;
+
+    #[tokio::test]
+    async fn test_clones_share_notes() {
+        let pad = Arc::new(Scratchpad::new(2, 10));
+        let researcher =
+            ScratchpadToolExecutor::new(ToolRegistry::new(), pad.clone(), "researcher");
+        let writer = ScratchpadToolExecutor::new(ToolRegistry::new(), pad.clone(), "writer");
+        assert!(writer.list_tools().iter().any(|t| t.name == WRITE_TOOL));
+
+        researcher
+            .execute(
+                WRITE_TOOL,
+                serde_json::json!({"topic": "Sources", "text": "three papers on RAG"}),
+            )
+            .await
+            .unwrap();
+        let notes = writer
+            .execute(READ_TOOL, serde_json::json!({"topic": "sources"}))
+            .await
+            .unwrap();
+        assert_eq!(notes, "#1 [sources] researcher:\nthree pape…");
+
+        writer
+            .execute(WRITE_TOOL, serde_json::json!({"text": "draft"}))
+            .await
+            .unwrap();
+        assert!(
+            writer
+                .execute(WRITE_TOOL, serde_json::json!({"text": "more"}))
+                .await
+                .is_err()
+        );
+        assert_eq!(pad.read(Some("notes"))[0].author, "writer");
+    }
+}
@@ -0,0 +1,912 @@
+//! Outbound send guard
+//!
+//! Keeps a confused or hallucinating agent from mass-mailing people. Every
+//! tool that sends something to someone (email, iMessage, SMS), including
+//! the ones connected devices offer, is wrapped in [`GuardedSendTool`],
+//! which:
+//!
+//! - refuses recipients on the denylist, or off the allowlist when one is set
+//! - asks the owner, in their own conversation, before the first message to
+//!   a new recipient, and sends it once they approve
+//! - caps how many sends autonomous work (background tasks, watchers, goals,
+//!   rules) may make per day
+//! - holds autonomous sends for a cooling-off delay, telling the user and
+//!   letting them cancel with `cancel_send`
+//!
+//! Recipients that have been sent to and the day's send count are stored as
+//! `send_guard` preferences.
+
+use std::collections::HashSet;
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use anyhow::{Result, bail};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use meepo_knowledge::KnowledgeDb;
+use serde_json::Value;
+use tokio::sync::mpsc;
+use tokio_util::sync::CancellationToken;
+use tracing::{debug, info, warn};
+
+use crate::api::ToolDefinition;
+use crate::pins;
+use crate::questions::{self, PendingQuestions};
+use crate::tool_cost::ToolCost;
+use crate::tools::{ToolExecutor, ToolHandler, ToolRegistry};
+use crate::types::{Button, ChannelType, Interactive, MessageKind, OutgoingMessage};
+
+/// Preference category holding known recipients and the day's send count
+pub const CATEGORY: &str = "send_guard";
+
+/// Tools that send something to someone, and the inputs naming who
+pub const SEND_TOOLS: &[(&str, &[&str])] = &[
+    ("send_email", &["to", "cc"]),
+    ("send_imessage", &["contact"]),
+    ("send_sms", &["to"]),
+];
+
+/// Senders of the agent turns the autonomous loop starts
+const AUTONOMOUS_SENDERS: &[&str] = &[
+    "daily_planner",
+    "goal_evaluator",
+    "goal_action",
+    "watcher",
+    "rule",
+];
+
+const SENT_TODAY_KEY: &str = "send_guard:sent_today";
+
+tokio::task_local! {
+    /// Whether the current agent turn answers the owner rather than a guest
+    /// or a restricted profile
+    static OWNER_TURN: bool;
+}
+
+/// Run an agent turn, noting whether it answers the owner, so only the
+/// owner can approve sends
+pub async fn scope_owner<F: Future>(owner: bool, fut: F) -> F::Output {
+    OWNER_TURN.scope(owner, fut).await
+}
+
+/// Whether the calling tool runs in a turn answering the owner
+fn owner_turn() -> bool {
+    OWNER_TURN.try_with(|owner| *owner).unwrap_or(false)
+}
+
+/// How long a first message waits for the user to approve it
+const CONFIRM_TIMEOUT: Duration = Duration::from_secs(60 * 60);
+
+/// Longest input value shown when asking the user to approve a send
+const PREVIEW_CHARS: usize = 300;
+
+/// Who may be sent to, and how much autonomous work may send
+#[derive(Debug, Clone)]
+pub struct SendGuardConfig {
+    /// If set, only these recipients (exact, `*@domain.com` or `*suffix`)
+    pub allow: Vec<String>,
+    /// Never send to these
+    pub deny: Vec<String>,
+    /// Autonomous sends per day (0 = no cap)
+    pub daily_cap: u32,
+    /// Ask before the first message to someone not on the allowlist
+    pub confirm_new_recipients: bool,
+    /// How long autonomous sends wait, cancellable, before going out
+    pub cooling_off: Duration,
+}
+
+impl Default for SendGuardConfig {
+    fn default() -> Self {
+        Self {
+            allow: Vec::new(),
+            deny: Vec::new(),
+            daily_cap: 20,
+            confirm_new_recipients: true,
+            cooling_off: Duration::from_secs(120),
+        }
+    }
+}
+
+/// The recipient inputs of a send tool, also matching device tools by the
+/// name after their `device__` prefix
+fn send_fields(tool_name: &str) -> Option<&'static [&'static str]> {
+    let base = tool_name
+        .rsplit_once("__")
+        .map_or(tool_name, |(_, tool)| tool);
+    SEND_TOOLS
+        .iter()
+        .find(|(name, _)| *name == base)
+        .map(|(_, fields)| *fields)
+}
+
+fn matches(pattern: &str, recipient: &str) -> bool {
+    let pattern = pattern.trim().to_lowercase();
+    match pattern.strip_prefix('*') {
+        Some(suffix) => recipient.ends_with(suffix),
+        None => pattern == recipient,
+    }
+}
+
+fn known_key(recipient: &str) -> String {
+    format!("send_guard:known:{}", recipient)
+}
+
+/// Recipients named in a send tool's input, lowercased
+fn recipients(input: &Value, fields: &[&str]) -> Vec<String> {
+    let mut seen = HashSet::new();
+    fields
+        .iter()
+        .filter_map(|field| input.get(*field).and_then(Value::as_str))
+        .flat_map(|value| value.split([',', ';']))
+        .map(|r| r.trim().to_lowercase())
+        .filter(|r| !r.is_empty() && seen.insert(r.clone()))
+        .collect()
+}
+
+/// The text inputs of a send, for the user to check before approving it
+fn preview(input: &Value) -> String {
+    let Some(fields) = input.as_object() else {
+        return String::new();
+    };
+    fields
+        .iter()
+        .filter_map(|(key, value)| {
+            let value = value.as_str()?;
+            let mut shown: String = value.chars().take(PREVIEW_CHARS).collect();
+            if shown.len() < value.len() {
+                shown.push('…');
+            }
+            Some(format!("{}: {}", key, shown))
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Whether the user's answer approves a send
+fn approves(answer: &str) -> bool {
+    matches!(
+        answer.trim().to_lowercase().as_str(),
+        "approve" | "yes" | "y" | "ok" | "send" | "confirm" | "go ahead"
+    )
+}
+
+/// Whether the calling tool runs without the user in the loop: in a
+/// background task, a turn the autonomous loop started, or outside any
+/// conversation (e.g. a delegated sub-task)
+pub fn is_autonomous() -> bool {
+    if questions::current_task().is_some() {
+        return true;
+    }
+    match pins::current_conversation() {
+        Some((channel, sender)) => {
+            channel == ChannelType::Internal.to_string()
+                || AUTONOMOUS_SENDERS.contains(&sender.as_str())
+        }
+        None => true,
+    }
+}
+
+/// A send waiting out its cooling-off delay
+#[derive(Debug, Clone)]
+pub struct PendingSend {
+    pub id: String,
+    pub tool: String,
+    pub recipients: Vec<String>,
+    pub due: DateTime<Utc>,
+    cancel: CancellationToken,
+}
+
+/// Enforces the send policy and holds autonomous sends until they're due
+pub struct SendGuard {
+    config: SendGuardConfig,
+    db: Arc<KnowledgeDb>,
+    outgoing: mpsc::Sender<OutgoingMessage>,
+    notify_channel: ChannelType,
+    pending: Mutex<Vec<PendingSend>>,
+    questions: Option<Arc<PendingQuestions>>,
+    /// Recipients whose first message is waiting for the user's approval
+    confirming: Mutex<HashSet<String>>,
+}
+
+impl SendGuard {
+    pub fn new(
+        config: SendGuardConfig,
+        db: Arc<KnowledgeDb>,
+        outgoing: mpsc::Sender<OutgoingMessage>,
+        notify_channel: ChannelType,
+    ) -> Self {
+        Self {
+            config,
+            db,
+            outgoing,
+            notify_channel,
+            pending: Mutex::new(Vec::new()),
+            questions: None,
+            confirming: Mutex::new(HashSet::new()),
+        }
+    }
+
+    /// Ask the user through pending questions before the first message to
+    /// a new recipient. Without it, such sends are refused.
+    pub fn with_questions(mut self, questions: Arc<PendingQuestions>) -> Self {
+        self.questions = Some(questions);
+        self
+    }
+
+    /// Wrap the registry's send tools in the guard
+    pub fn protect(self: &Arc<Self>, registry: &mut ToolRegistry) {
+        for (name, fields) in SEND_TOOLS {
+            if let Some(inner) = registry.get(name) {
+                registry.register(Arc::new(GuardedSendTool {
+                    inner,
+                    guard: self.clone(),
+                    fields,
+                }));
+            }
+        }
+    }
+
+    /// Wrap the send tools of an executor whose tools come and go (e.g.
+    /// connected devices) in the guard
+    pub fn protect_source(
+        self: &Arc<Self>,
+        source: Arc<dyn ToolExecutor>,
+    ) -> Arc<dyn ToolExecutor> {
+        Arc::new(GuardedSource {
+            inner: source,
+            guard: self.clone(),
+        })
+    }
+
+    /// Refuse denied recipients, and ones off the allowlist when it's set
+    pub fn check_lists(&self, recipients: &[String]) -> Result<()> {
+        for recipient in recipients {
+            if self.config.deny.iter().any(|p| matches(p, recipient)) {
+                bail!("send guard: {} is on the denylist", recipient);
+            }
+            if !self.config.allow.is_empty() && !self.allowed(recipient) {
+                bail!(
+                    "send guard: {} is not on the allowlist ([send_guard] allow)",
+                    recipient
+                );
+            }
+        }
+        Ok(())
+    }
+
+    fn allowed(&self, recipient: &str) -> bool {
+        self.config.allow.iter().any(|p| matches(p, recipient))
+    }
+
+    /// Recipients that have never been sent to and aren't allowlisted
+    async fn new_recipients(&self, recipients: &[String]) -> Vec<String> {
+        let known: HashSet<String> = match self.db.get_preferences(Some(CATEGORY)).await {
+            Ok(prefs) => prefs.into_iter().map(|p| p.key).collect(),
+            Err(e) => {
+                // Fail closed: unknown state means asking again
+                warn!("Failed to read known recipients: {}", e);
+                HashSet::new()
+            }
+        };
+        recipients
+            .iter()
+            .filter(|r| !self.allowed(r) && !known.contains(&known_key(r)))
+            .cloned()
+            .collect()
+    }
+
+    async fn remember(&self, recipients: &[String]) {
+        for recipient in recipients {
+            if let Err(e) = self
+                .db
+                .upsert_preference(
+                    CATEGORY,
+                    &known_key(recipient),
+                    Value::Bool(true),
+                    1.0,
+                    Some("send"),
+                )
+                .await
+            {
+                debug!("Failed to remember recipient {}: {}", recipient, e);
+            }
+        }
+    }
+
+    /// Autonomous sends so far today (in the user's timezone)
+    pub async fn sent_today(&self) -> u32 {
+        let today = crate::timezone::now().date_naive().to_string();
+        self.db
+            .get_preferences(Some(CATEGORY))
+            .await
+            .ok()
+            .and_then(|prefs| prefs.into_iter().find(|p| p.key == SENT_TODAY_KEY))
+            .filter(|p| p.value.get("date").and_then(Value::as_str) == Some(today.as_str()))
+            .and_then(|p| p.value.get("count").and_then(Value::as_u64))
+            .unwrap_or(0) as u32
+    }
+
+    async fn count_send(&self) -> Result<()> {
+        let today = crate::timezone::now().date_naive().to_string();
+        let count = self.sent_today().await + 1;
+        self.db
+            .upsert_preference(
+                CATEGORY,
+                SENT_TODAY_KEY,
+                serde_json::json!({"date": today, "count": count}),
+                1.0,
+                Some("send"),
+            )
+            .await?;
+        Ok(())
+    }
+
+    /// Sends waiting out their cooling-off delay
+    pub fn pending(&self) -> Vec<PendingSend> {
+        self.pending
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone()
+    }
+
+    /// Cancel a pending send. Returns it if it was still waiting.
+    pub fn cancel(&self, id: &str) -> Option<PendingSend> {
+        let mut pending = self.pending.lock().unwrap_or_else(|e| e.into_inner());
+        let pos = pending.iter().position(|p| p.id == id)?;
+        let send = pending.remove(pos);
+        send.cancel.cancel();
+        info!(
+            "Cancelled pending {} to {}",
+            send.tool,
+            send.recipients.join(", ")
+        );
+        Some(send)
+    }
+
+    async fn notify(&self, content: String) {
+        let msg = OutgoingMessage {
+            content,
+            channel: self.notify_channel.clone(),
+            reply_to: None,
+            kind: MessageKind::Response,
+            interactive: None,
+        };
+        if let Err(e) = self.outgoing.send(msg).await {
+            warn!("Failed to send send-guard notice: {}", e);
+        }
+    }
+
+    /// Ask the owner, in the conversation the send came from, to approve
+    /// the first message to `new`, and send it if they do. Returns what to
+    /// tell the agent meanwhile.
+    async fn confirm(
+        self: &Arc<Self>,
+        inner: Arc<dyn ToolHandler>,
+        input: Value,
+        recipients: Vec<String>,
+        new: Vec<String>,
+    ) -> Result<String> {
+        // Only the owner can approve, and only the one asking
+        let (Some(questions), Some((channel, sender))) =
+            (self.questions.clone(), pins::current_conversation())
+        else {
+            bail!(
+                "send guard: {} hasn't been sent anything before; add them to \
+                 [send_guard] allow to send",
+                new.join(", ")
+            );
+        };
+        if is_autonomous() || !owner_turn() {
+            bail!(
+                "send guard: {} hasn't been sent anything before; only the owner can approve \
+                 a first message, from their own conversation",
+                new.join(", ")
+            );
+        }
+        {
+            let mut confirming = self.confirming.lock().unwrap_or_else(|e| e.into_inner());
+            if let Some(waiting) = new.iter().find(|r| confirming.contains(*r)) {
+                return Ok(format!(
+                    "Not sent: still waiting for the user to approve the first message to {}.",
+                    waiting
+                ));
+            }
+            confirming.extend(new.iter().cloned());
+        }
+
+        let channel = ChannelType::from_string(&channel);
+        let tool = inner.name().to_string();
+        let prompt = format!(
+            "✉️ First message to {} ({}):\n{}\n\nSend it?",
+            new.join(", "),
+            tool,
+            preview(&input)
+        );
+        let (question, answer_rx) = questions
+            .ask_sender(
+                questions::current_task(),
+                &prompt,
+                channel.clone(),
+                Some(sender),
+            )
+            .await;
+        let msg = OutgoingMessage {
+            content: format!("{}\n(Reply Approve / Reject.)", prompt),
+            channel,
+            reply_to: None,
+            kind: MessageKind::Response,
+            interactive: Some(Interactive {
+                prompt_id: question.id.clone(),
+                buttons: vec![Button::for_choice("Approve"), Button::for_choice("Reject")],
+            }),
+        };
+        if let Err(e) = self.outgoing.send(msg).await {
+            questions.withdraw(&question.id).await;
+            self.confirmed(&new);
+            bail!(
+                "send guard: failed to ask the user to approve the send: {}",
+                e
+            );
+        }
+
+        let guard = self.clone();
+        let asked = new.clone();
+        tokio::spawn(async move {
+            let answer = tokio::time::timeout(CONFIRM_TIMEOUT, answer_rx).await;
+            guard.confirmed(&asked);
+            let to = recipients.join(", ");
+            match answer {
+                Ok(Ok(answer)) if approves(&answer) => match inner.execute(input).await {
+                    Ok(_) => {
+                        info!("Sent approved {} to {}", tool, to);
+                        guard.remember(&recipients).await;
+                        guard.notify(format!("✉️ Sent ({}) to {}.", tool, to)).await;
+                    }
+                    Err(e) => {
+                        warn!("Approved {} to {} failed: {}", tool, to, e);
+                        guard
+                            .notify(format!("⚠️ Couldn't send ({}) to {}: {}", tool, to, e))
+                            .await;
+                    }
+                },
+                Ok(Ok(_)) => {
+                    info!("User rejected {} to {}", tool, to);
+                    guard
+                        .notify(format!("Not sent ({}) to {}.", tool, to))
+                        .await;
+                }
+                _ => {
+                    questions.withdraw(&question.id).await;
+                    guard
+                        .notify(format!(
+                            "Not sent ({}) to {}: no approval within {} minutes.",
+                            tool,
+                            to,
+                            CONFIRM_TIMEOUT.as_secs() / 60
+                        ))
+                        .await;
+                }
+            }
+        });
+
+        Ok(format!(
+            "Not sent yet: this is the first message to {}, so the user was asked to approve \
+             it. It goes out if they do; don't send it again.",
+            new.join(", ")
+        ))
+    }
+
+    /// Stop waiting on the user's approval for these recipients
+    fn confirmed(&self, recipients: &[String]) {
+        self.confirming
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .retain(|r| !recipients.contains(r));
+    }
+
+    /// Hold a send for the cooling-off delay, then run it unless cancelled
+    async fn hold(
+        self: &Arc<Self>,
+        inner: Arc<dyn ToolHandler>,
+        input: Value,
+        recipients: Vec<String>,
+    ) -> String {
+        let delay = self.config.cooling_off;
+        let send = PendingSend {
+            id: format!("send-{}", &uuid::Uuid::new_v4().to_string()[..8]),
+            tool: inner.name().to_string(),
+            recipients,
+            due: Utc::now() + chrono::Duration::from_std(delay).unwrap_or_default(),
+            cancel: CancellationToken::new(),
+        };
+        self.pending
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .push(send.clone());
+        let to = send.recipients.join(", ");
+        self.notify(format!(
+            "✉️ Sending ({}) to {} in {}s. Say \"cancel send {}\" to stop it.",
+            send.tool,
+            to,
+            delay.as_secs(),
+            send.id
+        ))
+        .await;
+
+        let guard = self.clone();
+        let queued = send.clone();
+        tokio::spawn(async move {
+            tokio::select! {
+                _ = send.cancel.cancelled() => return,
+                _ = tokio::time::sleep(delay) => {}
+            }
+            guard
+                .pending
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .retain(|p| p.id != send.id);
+            match inner.execute(input).await {
+                Ok(_) => {
+                    info!("Sent held {} to {}", send.tool, to);
+                    guard.remember(&send.recipients).await;
+                }
+                Err(e) => {
+                    warn!("Held {} to {} failed: {}", send.tool, to, e);
+                    guard
+                        .notify(format!("⚠️ Couldn't send ({}) to {}: {}", send.tool, to, e))
+                        .await;
+                }
+            }
+        });
+
+        format!(
+            "Queued as {}: goes out to {} in {}s unless the user cancels it (cancel_send).",
+            queued.id,
+            queued.recipients.join(", "),
+            delay.as_secs()
+        )
+    }
+}
+
+/// A send tool behind the guard
+pub struct GuardedSendTool {
+    inner: Arc<dyn ToolHandler>,
+    guard: Arc<SendGuard>,
+    fields: &'static [&'static str],
+}
+
+#[async_trait]
+impl ToolHandler for GuardedSendTool {
+    fn name(&self) -> &str {
+        self.inner.name()
+    }
+
+    fn description(&self) -> &str {
+        self.inner.description()
+    }
+
+    fn input_schema(&self) -> Value {
+        self.inner.input_schema()
+    }
+
+    fn cost(&self) -> ToolCost {
+        self.inner.cost()
+    }
+
+    fn needs_network(&self) -> bool {
+        self.inner.needs_network()
+    }
+
+    fn reads_personal_knowledge(&self) -> bool {
+        self.inner.reads_personal_knowledge()
+    }
+
+    async fn execute(&self, input: Value) -> Result<String> {
+        let recipients = recipients(&input, self.fields);
+        if recipients.is_empty() {
+            return self.inner.execute(input).await;
+        }
+        self.guard.check_lists(&recipients)?;
+
+        if self.guard.config.confirm_new_recipients {
+            let new = self.guard.new_recipients(&recipients).await;
+            if !new.is_empty() {
+                return self
+                    .guard
+                    .confirm(self.inner.clone(), input, recipients, new)
+                    .await;
+            }
+        }
+
+        if is_autonomous() {
+            let cap = self.guard.config.daily_cap;
+            if cap > 0 && self.guard.sent_today().await >= cap {
+                bail!(
+                    "send guard: the daily cap of {} autonomous sends is reached. \
+                     Tell the user instead of sending.",
+                    cap
+                );
+            }
+            self.guard.count_send().await?;
+            if !self.guard.config.cooling_off.is_zero() {
+                return Ok(self.guard.hold(self.inner.clone(), input, recipients).await);
+            }
+        }
+
+        let result = self.inner.execute(input).await?;
+        self.guard.remember(&recipients).await;
+        Ok(result)
+    }
+}
+
+/// One tool of a source executor, so it can go behind the guard
+struct SourceTool {
+    source: Arc<dyn ToolExecutor>,
+    name: String,
+}
+
+#[async_trait]
+impl ToolHandler for SourceTool {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn description(&self) -> &str {
+        ""
+    }
+
+    fn input_schema(&self) -> Value {
+        Value::Null
+    }
+
+    async fn execute(&self, input: Value) -> Result<String> {
+        self.source.execute(&self.name, input).await
+    }
+}
+
+/// A source executor whose send tools go through the guard
+struct GuardedSource {
+    inner: Arc<dyn ToolExecutor>,
+    guard: Arc<SendGuard>,
+}
+
+#[async_trait]
+impl ToolExecutor for GuardedSource {
+    async fn execute(&self, tool_name: &str, input: Value) -> Result<String> {
+        let Some(fields) = send_fields(tool_name) else {
+            return self.inner.execute(tool_name, input).await;
+        };
+        GuardedSendTool {
+            inner: Arc::new(SourceTool {
+                source: self.inner.clone(),
+                name: tool_name.to_string(),
+            }),
+            guard: self.guard.clone(),
+            fields,
+        }
+        .execute(input)
+        .await
+    }
+
+    fn list_tools(&self) -> Vec<ToolDefinition> {
+        self.inner.list_tools()
+    }
+
+    fn reads_personal_knowledge(&self, tool_name: &str) -> bool {
+        self.inner.reads_personal_knowledge(tool_name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tools::ToolExecutor;
+
+    struct FakeSend;
+
+    #[async_trait]
+    impl ToolHandler for FakeSend {
+        fn name(&self) -> &str {
+            "send_email"
+        }
+        fn description(&self) -> &str {
+            "Send an email"
+        }
+        fn input_schema(&self) -> Value {
+            crate::tools::json_schema(serde_json::json!({"to": {"type": "string"}}), vec!["to"])
+        }
+        async fn execute(&self, input: Value) -> Result<String> {
+            Ok(format!(
+                "sent to {}",
+                input["to"].as_str().unwrap_or_default()
+            ))
+        }
+    }
+
+    /// A connected device offering `phone__send_sms`
+    struct Phone;
+
+    #[async_trait]
+    impl ToolExecutor for Phone {
+        async fn execute(&self, tool_name: &str, input: Value) -> Result<String> {
+            Ok(format!(
+                "{} to {}",
+                tool_name,
+                input["to"].as_str().unwrap_or_default()
+            ))
+        }
+
+        fn list_tools(&self) -> Vec<ToolDefinition> {
+            ["phone__send_sms", "phone__battery"]
+                .iter()
+                .map(|name| ToolDefinition {
+                    name: name.to_string(),
+                    description: String::new(),
+                    input_schema: serde_json::json!({"type": "object"}),
+                })
+                .collect()
+        }
+    }
+
+    #[test]
+    fn test_recipients_and_patterns() {
+        let input = serde_json::json!({"to": "Bob@Acme.com, eve@evil.com", "cc": "bob@acme.com"});
+        assert_eq!(
+            recipients(&input, &["to", "cc"]),
+            vec!["bob@acme.com", "eve@evil.com"]
+        );
+        assert!(matches("*@acme.com", "bob@acme.com"));
+        assert!(matches("Bob@Acme.com", "bob@acme.com"));
+        assert!(!matches("*@acme.com", "eve@evil.com"));
+        assert_eq!(send_fields("phone__send_sms"), Some(&["to"][..]));
+        assert!(send_fields("phone__battery").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_device_send_tools_guarded() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let db = Arc::new(KnowledgeDb::new(temp.path().join("test.db")).unwrap());
+        let (tx, mut rx) = mpsc::channel(8);
+        let guard = Arc::new(SendGuard::new(
+            SendGuardConfig {
+                deny: vec!["+15551234".to_string()],
+                ..Default::default()
+            },
+            db,
+            tx,
+            ChannelType::Slack,
+        ));
+        let mut registry = ToolRegistry::new();
+        registry.add_source(guard.protect_source(Arc::new(Phone)));
+
+        assert_eq!(
+            registry
+                .execute("phone__battery", serde_json::json!({}))
+                .await
+                .unwrap(),
+            "phone__battery to "
+        );
+        // Denied numbers are refused, known ones held for cooling-off
+        let out = registry
+            .execute("phone__send_sms", serde_json::json!({"to": "+15551234"}))
+            .await;
+        assert!(out.is_err());
+        guard.remember(&["+4420123".to_string()]).await;
+        let out = registry
+            .execute("phone__send_sms", serde_json::json!({"to": "+4420123"}))
+            .await
+            .unwrap();
+        assert!(out.starts_with("Queued as send-"));
+        assert!(rx.recv().await.unwrap().content.contains("phone__send_sms"));
+    }
+
+    #[tokio::test]
+    async fn test_guarded_send() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let db = Arc::new(KnowledgeDb::new(temp.path().join("test.db")).unwrap());
+        let (tx, mut rx) = mpsc::channel(8);
+        let questions = Arc::new(PendingQuestions::new());
+        let guard = Arc::new(
+            SendGuard::new(
+                SendGuardConfig {
+                    deny: vec!["*@evil.com".to_string()],
+                    daily_cap: 1,
+                    ..Default::default()
+                },
+                db,
+                tx,
+                ChannelType::Slack,
+            )
+            .with_questions(questions.clone()),
+        );
+        let mut registry = ToolRegistry::new();
+        registry.register(Arc::new(FakeSend));
+        guard.protect(&mut registry);
+
+        // From a chat: denylisted, then a new recipient needs the user's
+        // approval, which the model can't claim for itself
+        let chat = |input: Value| {
+            scope_owner(
+                true,
+                pins::scope_conversation(
+                    "discord".to_string(),
+                    "alice".to_string(),
+                    registry.execute("send_email", input),
+                ),
+            )
+        };
+        assert!(
+            chat(serde_json::json!({"to": "eve@evil.com"}))
+                .await
+                .is_err()
+        );
+        let out = chat(serde_json::json!({"to": "bob@acme.com", "confirm_new_recipient": true}))
+            .await
+            .unwrap();
+        assert!(out.starts_with("Not sent yet"));
+        let prompt = rx.recv().await.unwrap();
+        assert_eq!(prompt.channel, ChannelType::Discord);
+        assert!(prompt.content.contains("First message to bob@acme.com"));
+        let out = chat(serde_json::json!({"to": "bob@acme.com"}))
+            .await
+            .unwrap();
+        assert!(out.contains("still waiting"));
+        // A guest or restricted profile can't ask for approval at all
+        let kid = scope_owner(
+            false,
+            pins::scope_conversation(
+                "discord".to_string(),
+                "kid".to_string(),
+                registry.execute("send_email", serde_json::json!({"to": "carol@acme.com"})),
+            ),
+        );
+        assert!(kid.await.is_err());
+
+        // Someone else on the channel can't approve it, by reply or button
+        let reply = |sender: &str, content: String| crate::types::IncomingMessage {
+            id: "m1".to_string(),
+            sender: sender.to_string(),
+            content,
+            channel: ChannelType::Discord,
+            timestamp: Utc::now(),
+        };
+        let prompt_id = prompt.interactive.unwrap().prompt_id;
+        assert!(
+            questions
+                .try_answer(&reply("mallory", "yes".to_string()))
+                .await
+                .is_none()
+        );
+        let press = Interactive::press_content(&prompt_id, "Approve");
+        assert!(
+            questions
+                .try_answer(&reply("mallory", press.clone()))
+                .await
+                .is_none()
+        );
+        assert!(questions.try_answer(&reply("alice", press)).await.is_some());
+        assert!(rx.recv().await.unwrap().content.starts_with("✉️ Sent"));
+        let out = chat(serde_json::json!({"to": "bob@acme.com"}))
+            .await
+            .unwrap();
+        assert_eq!(out, "sent to bob@acme.com");
+
+        // Autonomous: held for cooling-off and cancellable, then capped
+        let out = registry
+            .execute("send_email", serde_json::json!({"to": "bob@acme.com"}))
+            .await
+            .unwrap();
+        assert!(out.starts_with("Queued as send-"));
+        assert!(rx.recv().await.unwrap().content.contains("cancel send"));
+        let id = guard.pending()[0].id.clone();
+        assert!(guard.cancel(&id).is_some());
+        assert!(guard.pending().is_empty());
+        assert!(
+            registry
+                .execute("send_email", serde_json::json!({"to": "bob@acme.com"}))
+                .await
+                .is_err()
+        );
+    }
+}
@@ -0,0 +1,447 @@
+//! Snoozing notifications and escalating unanswered ones
+//!
+//! With a [`SnoozeStore`] attached, every notification sent to a channel
+//! gets a short ID (`n3fa2c1`) shown under it. Replying `snooze 2h` (or
+//! `snooze n3fa2c1 30m`, or reacting 💤 on Discord) hides the channel's
+//! latest notification and schedules a follow-up that sends it again when
+//! the snooze ends. Snoozes are kept in the preferences table so they
+//! survive a restart.
+//!
+//! Warnings and critical notifications that get no reply on their channel
+//! within the escalation interval are sent once more, marked as still
+//! waiting. Any message on that channel counts as a reply.
+
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use meepo_knowledge::KnowledgeDb;
+use serde::{Deserialize, Serialize};
+use tracing::{debug, info, warn};
+
+use crate::types::{ChannelType, IncomingMessage};
+
+/// Preference category holding snoozed notifications, keyed by
+/// `notification:<id>`
+pub const PREFERENCE_CATEGORY: &str = "snooze";
+
+/// How many sent notifications are remembered for snoozing
+const MAX_TRACKED: usize = 50;
+
+/// Longest snooze accepted
+const MAX_SNOOZE: Duration = Duration::from_secs(7 * 24 * 3600);
+
+/// How long a snooze lasts and when unanswered notifications escalate
+#[derive(Debug, Clone)]
+pub struct SnoozeConfig {
+    /// Used when a snooze gives no duration (e.g. a 💤 reaction)
+    pub default_snooze: Duration,
+    /// `None` turns escalation off
+    pub escalate_after: Option<Duration>,
+}
+
+impl Default for SnoozeConfig {
+    fn default() -> Self {
+        Self {
+            default_snooze: Duration::from_secs(3600),
+            escalate_after: Some(Duration::from_secs(1800)),
+        }
+    }
+}
+
+/// A notification that was sent to a channel
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TrackedNotification {
+    pub id: String,
+    pub channel: ChannelType,
+    pub content: String,
+    /// Warnings and critical notifications escalate when unanswered
+    pub important: bool,
+    pub sent_at: DateTime<Utc>,
+    pub snoozed_until: Option<DateTime<Utc>>,
+    pub answered: bool,
+    pub escalated: bool,
+}
+
+/// Why a notification is being sent again
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FollowUpKind {
+    Snoozed,
+    Escalated,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct FollowUp {
+    pub kind: FollowUpKind,
+    pub notification: TrackedNotification,
+}
+
+/// A parsed snooze command
+#[derive(Debug, Clone, PartialEq)]
+pub struct SnoozeRequest {
+    /// Notification to snooze; the channel's latest when `None`
+    pub id: Option<String>,
+    pub duration: Option<Duration>,
+}
+
+/// Parse `snooze [id] [duration]` or `💤 [id] [duration]`. Returns `None`
+/// for anything else, including messages that only start with "snooze".
+pub fn parse(content: &str) -> Option<SnoozeRequest> {
+    let content = content.trim().to_lowercase();
+    let rest = match content.strip_prefix('💤') {
+        Some(rest) => rest.trim_start_matches('\u{FE0F}'),
+        None => {
+            let rest = content.strip_prefix("snooze")?;
+            if !rest.is_empty() && !rest.starts_with(char::is_whitespace) {
+                return None;
+            }
+            rest
+        }
+    };
+
+    let mut request = SnoozeRequest {
+        id: None,
+        duration: None,
+    };
+    let tokens: Vec<&str> = rest
+        .split_whitespace()
+        .filter(|t| !matches!(*t, "for" | "it" | "this"))
+        .collect();
+    let mut i = 0;
+    while i < tokens.len() {
+        let token = tokens[i];
+        if request.id.is_none() && is_id(token) {
+            request.id = Some(token.to_string());
+        } else if request.duration.is_none()
+            && let Some(duration) = parse_duration(token)
+        {
+            request.duration = Some(duration);
+        } else if request.duration.is_none()
+            && let Some(unit) = tokens.get(i + 1)
+            && let Some(duration) = parse_duration(&format!("{}{}", token, unit))
+        {
+            // "2 hours"
+            request.duration = Some(duration);
+            i += 1;
+        } else {
+            return None;
+        }
+        i += 1;
+    }
+    Some(request)
+}
+
+/// Notification IDs are `n` followed by hex digits
+fn is_id(token: &str) -> bool {
+    token
+        .strip_prefix('n')
+        .is_some_and(|hex| hex.len() >= 4 && hex.chars().all(|c| c.is_ascii_hexdigit()))
+}
+
+/// `30m`, `2h`, `1d`, `90min`, `2hours`
+fn parse_duration(token: &str) -> Option<Duration> {
+    let split = token.find(|c: char| !c.is_ascii_digit())?;
+    let (amount, unit) = token.split_at(split);
+    let amount: u64 = amount.parse().ok().filter(|n| *n > 0)?;
+    let secs = match unit {
+        "m" | "min" | "mins" | "minute" | "minutes" => 60,
+        "h" | "hr" | "hrs" | "hour" | "hours" => 3600,
+        "d" | "day" | "days" => 86400,
+        _ => return None,
+    };
+    Some(Duration::from_secs(amount.saturating_mul(secs)))
+}
+
+fn preference_key(id: &str) -> String {
+    format!("notification:{}", id)
+}
+
+/// Recently sent notifications, their snoozes and escalation state
+pub struct SnoozeStore {
+    config: SnoozeConfig,
+    db: Option<Arc<KnowledgeDb>>,
+    tracked: Mutex<VecDeque<TrackedNotification>>,
+}
+
+impl SnoozeStore {
+    pub fn new(config: SnoozeConfig) -> Self {
+        Self {
+            config,
+            db: None,
+            tracked: Mutex::new(VecDeque::new()),
+        }
+    }
+
+    /// Persist snoozes in `db` so their follow-ups survive a restart
+    pub fn with_db(mut self, db: Arc<KnowledgeDb>) -> Self {
+        self.db = Some(db);
+        self
+    }
+
+    /// Restore snoozes persisted by an earlier run
+    pub async fn load(&self) -> Result<usize> {
+        let Some(db) = &self.db else {
+            return Ok(0);
+        };
+        let snoozed: Vec<TrackedNotification> = db
+            .get_preferences(Some(PREFERENCE_CATEGORY))
+            .await?
+            .into_iter()
+            .filter_map(|p| serde_json::from_value(p.value).ok())
+            .collect();
+        let count = snoozed.len();
+        self.lock().extend(snoozed);
+        if count > 0 {
+            info!("Restored {} snoozed notifications", count);
+        }
+        Ok(count)
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, VecDeque<TrackedNotification>> {
+        self.tracked.lock().unwrap_or_else(|p| p.into_inner())
+    }
+
+    /// Remember a notification sent to `channel`; returns its ID
+    pub fn track(&self, channel: ChannelType, important: bool, content: &str) -> String {
+        let id = format!("n{}", &uuid::Uuid::new_v4().simple().to_string()[..6]);
+        let mut tracked = self.lock();
+        if tracked.len() >= MAX_TRACKED
+            && let Some(oldest) = tracked.iter().position(|n| n.snoozed_until.is_none())
+        {
+            tracked.remove(oldest);
+        }
+        tracked.push_back(TrackedNotification {
+            id: id.clone(),
+            channel,
+            content: content.to_string(),
+            important,
+            sent_at: Utc::now(),
+            snoozed_until: None,
+            answered: false,
+            escalated: false,
+        });
+        id
+    }
+
+    /// Apply a snooze command, or count any other message as a reply to the
+    /// channel's notifications. Returns a reply for snooze commands.
+    pub async fn handle(&self, msg: &IncomingMessage) -> Option<String> {
+        let Some(request) = parse(&msg.content) else {
+            for notification in self.lock().iter_mut() {
+                if notification.channel == msg.channel && notification.sent_at <= msg.timestamp {
+                    notification.answered = true;
+                }
+            }
+            return None;
+        };
+        Some(self.snooze(&msg.channel, request, msg.timestamp).await)
+    }
+
+    async fn snooze(
+        &self,
+        channel: &ChannelType,
+        request: SnoozeRequest,
+        now: DateTime<Utc>,
+    ) -> String {
+        let duration = request
+            .duration
+            .unwrap_or(self.config.default_snooze)
+            .min(MAX_SNOOZE);
+        let until = now + chrono::Duration::from_std(duration).unwrap_or_default();
+        let snoozed = {
+            let mut tracked = self.lock();
+            let target = match &request.id {
+                Some(id) => tracked.iter_mut().find(|n| &n.id == id),
+                None => tracked
+                    .iter_mut()
+                    .rev()
+                    .find(|n| &n.channel == channel && n.snoozed_until.is_none()),
+            };
+            target.map(|n| {
+                n.snoozed_until = Some(until);
+                n.answered = true;
+                n.clone()
+            })
+        };
+        let Some(snoozed) = snoozed else {
+            return match request.id {
+                Some(id) => format!("There's no notification {} to snooze.", id),
+                None => "There's no notification to snooze.".to_string(),
+            };
+        };
+
+        if let Some(db) = &self.db {
+            let value = serde_json::to_value(&snoozed).unwrap_or_default();
+            if let Err(e) = db
+                .upsert_preference(
+                    PREFERENCE_CATEGORY,
+                    &preference_key(&snoozed.id),
+                    value,
+                    1.0,
+                    Some("snooze"),
+                )
+                .await
+            {
+                warn!("Failed to save snooze for {}: {}", snoozed.id, e);
+            }
+        }
+        info!("Snoozed notification {} until {}", snoozed.id, until);
+        let local = until.with_timezone(&crate::timezone::user_timezone());
+        format!(
+            "💤 Snoozed [{}] until {}.",
+            snoozed.id,
+            local.format("%a %H:%M")
+        )
+    }
+
+    /// Notifications to send again at `now`: snoozes that have ended and
+    /// important notifications left unanswered. Ended snoozes are forgotten,
+    /// since their follow-up is tracked as a new notification.
+    pub async fn due(&self, now: DateTime<Utc>) -> Vec<FollowUp> {
+        let escalate_before = self
+            .config
+            .escalate_after
+            .and_then(|d| chrono::Duration::from_std(d).ok())
+            .map(|d| now - d);
+        let mut follow_ups = Vec::new();
+        {
+            let mut tracked = self.lock();
+            tracked.retain_mut(|n| {
+                if n.snoozed_until.is_some_and(|until| until <= now) {
+                    follow_ups.push(FollowUp {
+                        kind: FollowUpKind::Snoozed,
+                        notification: n.clone(),
+                    });
+                    return false;
+                }
+                if let Some(before) = escalate_before
+                    && n.important
+                    && !n.answered
+                    && !n.escalated
+                    && n.snoozed_until.is_none()
+                    && n.sent_at <= before
+                {
+                    n.escalated = true;
+                    follow_ups.push(FollowUp {
+                        kind: FollowUpKind::Escalated,
+                        notification: n.clone(),
+                    });
+                }
+                true
+            });
+        }
+
+        if let Some(db) = &self.db {
+            for follow_up in &follow_ups {
+                if follow_up.kind == FollowUpKind::Snoozed
+                    && let Err(e) = db
+                        .delete_preference(&preference_key(&follow_up.notification.id))
+                        .await
+                {
+                    warn!(
+                        "Failed to clear snooze for {}: {}",
+                        follow_up.notification.id, e
+                    );
+                }
+            }
+        }
+        if !follow_ups.is_empty() {
+            debug!("{} notification follow-ups due", follow_ups.len());
+        }
+        follow_ups
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(
+            parse("Snooze 2h"),
+            Some(SnoozeRequest {
+                id: None,
+                duration: Some(Duration::from_secs(7200)),
+            })
+        );
+        assert_eq!(
+            parse("snooze n3fa2c1 for 30 minutes"),
+            Some(SnoozeRequest {
+                id: Some("n3fa2c1".into()),
+                duration: Some(Duration::from_secs(1800)),
+            })
+        );
+        assert_eq!(
+            parse("💤"),
+            Some(SnoozeRequest {
+                id: None,
+                duration: None,
+            })
+        );
+        assert_eq!(parse("snooze button on my alarm is broken"), None);
+        assert_eq!(parse("snoozefest"), None);
+    }
+
+    fn incoming(content: &str, at: DateTime<Utc>) -> IncomingMessage {
+        IncomingMessage {
+            id: "m1".into(),
+            sender: "user".into(),
+            content: content.into(),
+            channel: ChannelType::Discord,
+            timestamp: at,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_snooze_and_escalate() {
+        let store = SnoozeStore::new(SnoozeConfig {
+            default_snooze: Duration::from_secs(600),
+            escalate_after: Some(Duration::from_secs(1800)),
+        });
+        let task = store.track(ChannelType::Discord, false, "Task done");
+        let budget = store.track(ChannelType::Discord, true, "Budget at 90%");
+        let now = Utc::now();
+
+        // The latest notification is snoozed by default
+        let reply = store.handle(&incoming("💤", now)).await.unwrap();
+        assert!(reply.contains(&budget), "{}", reply);
+        let reply = store
+            .handle(&incoming(&format!("snooze {} 1h", task), now))
+            .await
+            .unwrap();
+        assert!(reply.contains(&task));
+
+        let later = now + chrono::Duration::minutes(11);
+        let due = store.due(later).await;
+        assert_eq!(due.len(), 1);
+        assert_eq!(due[0].kind, FollowUpKind::Snoozed);
+        assert_eq!(due[0].notification.content, "Budget at 90%");
+        assert!(store.due(later).await.is_empty());
+
+        // An unanswered important notification escalates once
+        let error = store.track(ChannelType::Discord, true, "Backup failed");
+        let due = store.due(Utc::now() + chrono::Duration::minutes(31)).await;
+        assert_eq!(due.len(), 1);
+        assert_eq!(due[0].kind, FollowUpKind::Escalated);
+        assert_eq!(due[0].notification.id, error);
+        assert!(
+            store
+                .due(Utc::now() + chrono::Duration::minutes(31))
+                .await
+                .is_empty()
+        );
+
+        // A reply on the channel counts as an answer
+        store.track(ChannelType::Discord, true, "Disk almost full");
+        assert!(store.handle(&incoming("on it", Utc::now())).await.is_none());
+        assert!(
+            store
+                .due(Utc::now() + chrono::Duration::minutes(31))
+                .await
+                .is_empty()
+        );
+    }
+}
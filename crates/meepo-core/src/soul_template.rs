@@ -0,0 +1,320 @@
+//! SOUL.md templating
+//!
+//! SOUL.md may use Tera syntax: variables (`{{ date }}`, `{{ user_name }}`,
+//! `{{ active_goals }}`), conditionals (`{% if channel == "imessage" %}`) and
+//! includes of other workspace files (`{% include "soul/tone.md" %}`). It's
+//! rendered each time a system prompt is built. Compiled templates are
+//! cached until the source or an included file changes, and rendered text
+//! is cached per set of variables. A SOUL without template tags is used as
+//! is, and one that fails to render falls back to its raw text.
+
+use std::collections::HashMap;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::path::{Component, Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::SystemTime;
+
+use anyhow::{Context, Result, anyhow};
+use serde::Serialize;
+use tracing::warn;
+
+/// Template name the SOUL itself is registered under
+const ROOT: &str = "SOUL.md";
+
+/// Most compiled templates and rendered prompts kept
+const MAX_CACHED: usize = 16;
+
+/// Most files pulled in through (nested) includes
+const MAX_INCLUDES: usize = 32;
+
+/// Values available to the template
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash, Serialize)]
+pub struct SoulVars {
+    /// "2025-03-14", in the user's timezone
+    pub date: String,
+    /// "Friday"
+    pub weekday: String,
+    /// "09:30"
+    pub time: String,
+    /// Hour of day (0–23), for greetings and quiet-hours wording
+    pub hour: u32,
+    /// From onboarding (`user_name` preference), if known
+    pub user_name: Option<String>,
+    pub active_goals: usize,
+    /// Channel the message came in on, e.g. "slack"
+    pub channel: String,
+}
+
+impl SoulVars {
+    /// Date and time variables for `now`; the rest is left empty
+    pub fn at<Tz: chrono::TimeZone>(now: &chrono::DateTime<Tz>) -> Self
+    where
+        Tz::Offset: std::fmt::Display,
+    {
+        use chrono::Timelike;
+        Self {
+            date: now.format("%Y-%m-%d").to_string(),
+            weekday: now.format("%A").to_string(),
+            time: now.format("%H:%M").to_string(),
+            hour: now.hour(),
+            ..Default::default()
+        }
+    }
+}
+
+/// Whether `source` contains template tags at all
+pub fn is_template(source: &str) -> bool {
+    source.contains("{{") || source.contains("{%")
+}
+
+/// A compiled SOUL and the included files it was built from
+struct Compiled {
+    tera: tera::Tera,
+    includes: Vec<(PathBuf, Option<SystemTime>)>,
+}
+
+impl Compiled {
+    fn is_stale(&self) -> bool {
+        self.includes
+            .iter()
+            .any(|(path, modified)| modified_time(path) != *modified)
+    }
+}
+
+/// Renders SOUL templates, resolving includes against a base directory
+#[derive(Default)]
+pub struct SoulRenderer {
+    /// Directory includes are read from; without one, includes fail
+    base_dir: Option<PathBuf>,
+    compiled: Mutex<HashMap<u64, Arc<Compiled>>>,
+    rendered: Mutex<HashMap<(u64, u64), String>>,
+}
+
+impl std::fmt::Debug for SoulRenderer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SoulRenderer")
+            .field("base_dir", &self.base_dir)
+            .finish()
+    }
+}
+
+impl SoulRenderer {
+    pub fn new(base_dir: Option<PathBuf>) -> Self {
+        Self {
+            base_dir,
+            ..Default::default()
+        }
+    }
+
+    /// Render `source`, or return it unchanged if it has no template tags
+    /// or fails to render
+    pub fn render(&self, source: &str, vars: &SoulVars) -> String {
+        if !is_template(source) {
+            return source.to_string();
+        }
+        match self.try_render(source, vars) {
+            Ok(text) => text,
+            Err(e) => {
+                warn!("Failed to render SOUL template, using it as is: {:#}", e);
+                source.to_string()
+            }
+        }
+    }
+
+    pub fn try_render(&self, source: &str, vars: &SoulVars) -> Result<String> {
+        let source_key = hash(source);
+        let compiled = self.compiled(source, source_key)?;
+        let key = (source_key, hash(vars));
+        if let Some(text) = lock(&self.rendered).get(&key) {
+            return Ok(text.clone());
+        }
+
+        let context = tera::Context::from_serialize(vars)?;
+        let text = compiled
+            .tera
+            .render(ROOT, &context)
+            .map_err(|e| anyhow!(error_chain(&e)))?;
+
+        let mut rendered = lock(&self.rendered);
+        if rendered.len() >= MAX_CACHED {
+            rendered.clear();
+        }
+        rendered.insert(key, text.clone());
+        Ok(text)
+    }
+
+    fn compiled(&self, source: &str, source_key: u64) -> Result<Arc<Compiled>> {
+        if let Some(compiled) = lock(&self.compiled).get(&source_key)
+            && !compiled.is_stale()
+        {
+            return Ok(compiled.clone());
+        }
+
+        let compiled = Arc::new(self.compile(source)?);
+        let mut cache = lock(&self.compiled);
+        if cache.len() >= MAX_CACHED {
+            cache.clear();
+        }
+        cache.insert(source_key, compiled.clone());
+        // Rendered text may come from an older version of an include
+        lock(&self.rendered).retain(|(key, _), _| *key != source_key);
+        Ok(compiled)
+    }
+
+    fn compile(&self, source: &str) -> Result<Compiled> {
+        let mut templates = vec![(ROOT.to_string(), source.to_string())];
+        let mut includes = Vec::new();
+        let mut pending = include_names(source);
+        while let Some(name) = pending.pop() {
+            if templates.iter().any(|(n, _)| *n == name) {
+                continue;
+            }
+            if templates.len() > MAX_INCLUDES {
+                return Err(anyhow!("More than {} included files", MAX_INCLUDES));
+            }
+            let Some(path) = self.include_path(&name)? else {
+                // Left to Tera: an error unless the include says "ignore missing"
+                continue;
+            };
+            let text = std::fs::read_to_string(&path)
+                .with_context(|| format!("Failed to read included file {}", path.display()))?;
+            pending.extend(include_names(&text));
+            includes.push((path.clone(), modified_time(&path)));
+            templates.push((name, text));
+        }
+
+        let mut tera = tera::Tera::default();
+        tera.add_raw_templates(templates)
+            .map_err(|e| anyhow!(error_chain(&e)))?;
+        Ok(Compiled { tera, includes })
+    }
+
+    /// Path of an included file, if it exists. Names must be relative and
+    /// stay inside the base directory.
+    fn include_path(&self, name: &str) -> Result<Option<PathBuf>> {
+        let base = self
+            .base_dir
+            .as_ref()
+            .ok_or_else(|| anyhow!("Includes need a workspace directory"))?;
+        let relative = Path::new(name);
+        if !relative
+            .components()
+            .all(|c| matches!(c, Component::Normal(_) | Component::CurDir))
+        {
+            return Err(anyhow!(
+                "Include \"{}\" must be a path inside the workspace",
+                name
+            ));
+        }
+        let path = base.join(relative);
+        Ok(path.is_file().then_some(path))
+    }
+}
+
+/// Names in `{% include "..." %}` tags
+fn include_names(source: &str) -> Vec<String> {
+    static INCLUDE: std::sync::LazyLock<regex::Regex> = std::sync::LazyLock::new(|| {
+        regex::Regex::new(r#"\{%-?\s*include\s+["']([^"']+)["']"#).expect("valid regex")
+    });
+    INCLUDE
+        .captures_iter(source)
+        .map(|c| c[1].to_string())
+        .collect()
+}
+
+fn modified_time(path: &Path) -> Option<SystemTime> {
+    std::fs::metadata(path).and_then(|m| m.modified()).ok()
+}
+
+fn hash<T: Hash + ?Sized>(value: &T) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    value.hash(&mut hasher);
+    hasher.finish()
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> std::sync::MutexGuard<'_, T> {
+    mutex
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+/// Tera's error with its causes (the top level alone is rarely useful)
+fn error_chain(error: &tera::Error) -> String {
+    let mut message = error.to_string();
+    let mut source = std::error::Error::source(error);
+    while let Some(cause) = source {
+        message.push_str(": ");
+        message.push_str(&cause.to_string());
+        source = cause.source();
+    }
+    message
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn vars() -> SoulVars {
+        let now = chrono::Utc.with_ymd_and_hms(2025, 3, 14, 9, 30, 0).unwrap();
+        SoulVars {
+            user_name: Some("Sam".to_string()),
+            active_goals: 2,
+            channel: "imessage".to_string(),
+            ..SoulVars::at(&now)
+        }
+    }
+
+    #[test]
+    fn test_variables_conditionals_and_includes() {
+        let dir = tempfile::TempDir::new().unwrap();
+        std::fs::create_dir(dir.path().join("soul")).unwrap();
+        let tone = dir.path().join("soul/tone.md");
+        std::fs::write(&tone, "Be brief, {{ user_name }}.").unwrap();
+        let renderer = SoulRenderer::new(Some(dir.path().to_path_buf()));
+
+        let source = "Today is {{ weekday }} {{ date }}. {{ active_goals }} goals.\n\
+                      {% if channel == \"imessage\" %}{% include \"soul/tone.md\" %}{% endif %}\
+                      {% include \"soul/missing.md\" ignore missing %}";
+        assert_eq!(
+            renderer.render(source, &vars()),
+            "Today is Friday 2025-03-14. 2 goals.\nBe brief, Sam."
+        );
+
+        // Edits to an included file are picked up
+        std::fs::write(&tone, "Be thorough.").unwrap();
+        let later = SystemTime::now() + std::time::Duration::from_secs(5);
+        std::fs::File::options()
+            .write(true)
+            .open(&tone)
+            .unwrap()
+            .set_modified(later)
+            .unwrap();
+        assert!(renderer.render(source, &vars()).ends_with("Be thorough."));
+
+        let slack = SoulVars {
+            channel: "slack".to_string(),
+            ..vars()
+        };
+        assert!(renderer.render(source, &slack).ends_with("goals.\n"));
+    }
+
+    #[test]
+    fn test_plain_and_broken_templates_pass_through() {
+        let renderer = SoulRenderer::new(None);
+        assert_eq!(renderer.render("Plain soul", &vars()), "Plain soul");
+        // Unclosed tag, and an include without a workspace
+        assert_eq!(
+            renderer.render("Hi {{ user_name", &vars()),
+            "Hi {{ user_name"
+        );
+        let source = "{% include \"tone.md\" %}";
+        assert_eq!(renderer.render(source, &vars()), source);
+
+        let dir = tempfile::TempDir::new().unwrap();
+        let renderer = SoulRenderer::new(Some(dir.path().to_path_buf()));
+        let escape = "{% include \"../secrets.md\" %}";
+        assert!(renderer.try_render(escape, &vars()).is_err());
+    }
+}
@@ -0,0 +1,628 @@
+//! Spam and abuse filtering for inbound channel messages
+//!
+//! Channels strangers can reach (email with the subject prefix, Discord)
+//! can deliver junk that would otherwise cost a full agent run. Messages on
+//! filtered channels are checked before they reach the loop: trusted and
+//! known senders pass, blocked senders are dropped, and everyone else is
+//! scored with simple heuristics (links, spam phrases, shouting, flooding,
+//! earlier quarantines). A borderline score can be settled by a cheap
+//! model. Messages judged spam are held in a quarantine queue, reviewed
+//! with `meepo quarantine` and counted in the evening digest; released
+//! messages are picked up by the daemon and answered as usual.
+//!
+//! Sender reputations and the queue are kept in the preferences table.
+
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use anyhow::{Result, anyhow};
+use chrono::{DateTime, Utc};
+use meepo_knowledge::KnowledgeDb;
+use serde::{Deserialize, Serialize};
+use tracing::{debug, info, warn};
+
+use crate::api::{ApiClient, ApiMessage, MessageContent};
+use crate::providers::overrides::{self, RequestOverrides};
+use crate::types::{ChannelType, IncomingMessage};
+use crate::usage::{AccumulatedUsage, UsageSource, UsageTracker};
+
+/// Preference category holding quarantined messages, keyed by
+/// `quarantine:<id>`
+pub const QUARANTINE_CATEGORY: &str = "quarantine";
+
+/// Preference category holding sender reputations, keyed by
+/// `sender_reputation:<channel>:<sender>`
+pub const REPUTATION_CATEGORY: &str = "sender_reputation";
+
+/// Messages let through before a sender counts as known and skips scoring
+const KNOWN_AFTER: u32 = 3;
+
+/// Message text sent to the classifier
+const MAX_CLASSIFY_CHARS: usize = 2000;
+
+/// Phrases common in spam and scams
+const SPAM_PHRASES: &[&str] = &[
+    "act now",
+    "bitcoin",
+    "casino",
+    "claim your",
+    "click here",
+    "congratulations you",
+    "crypto",
+    "free money",
+    "gift card",
+    "guaranteed",
+    "investment opportunity",
+    "limited time",
+    "lottery",
+    "nigerian prince",
+    "no credit check",
+    "risk-free",
+    "urgent wire",
+    "viagra",
+    "wire transfer",
+    "you have won",
+    "you've won",
+];
+
+/// Which channels are filtered and how strictly
+#[derive(Debug, Clone)]
+pub struct SpamFilterConfig {
+    pub channels: Vec<ChannelType>,
+    /// Senders never filtered (compared case-insensitively)
+    pub trusted_senders: Vec<String>,
+    /// Score (0–1) at which a message is quarantined
+    pub threshold: f64,
+    /// Ask `model` about messages scoring at least half the threshold
+    pub classify: bool,
+    pub model: String,
+    /// More messages than this from one sender in an hour counts as flooding
+    pub max_per_hour: usize,
+}
+
+impl Default for SpamFilterConfig {
+    fn default() -> Self {
+        Self {
+            channels: vec![ChannelType::Email, ChannelType::Discord],
+            trusted_senders: Vec::new(),
+            threshold: 0.6,
+            classify: false,
+            model: "haiku".to_string(),
+            max_per_hour: 20,
+        }
+    }
+}
+
+/// What to do with an inbound message
+#[derive(Debug, Clone, PartialEq)]
+pub enum Verdict {
+    Allow,
+    /// Held for review
+    Quarantine {
+        id: String,
+        reasons: Vec<String>,
+    },
+    /// From a blocked sender; dropped without a trace
+    Drop,
+}
+
+/// How a sender has behaved so far
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SenderReputation {
+    #[serde(default)]
+    pub allowed: u32,
+    #[serde(default)]
+    pub quarantined: u32,
+    #[serde(default)]
+    pub trusted: bool,
+    #[serde(default)]
+    pub blocked: bool,
+}
+
+/// A message held for review
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QuarantinedMessage {
+    pub id: String,
+    pub message: IncomingMessage,
+    pub score: f64,
+    pub reasons: Vec<String>,
+    pub quarantined_at: DateTime<Utc>,
+    /// Released by the user, waiting for the daemon to pick it up
+    #[serde(default)]
+    pub released: bool,
+}
+
+/// Heuristic spam score (0–1) of a message, with the reasons behind it
+pub fn score(content: &str) -> (f64, Vec<String>) {
+    let mut score: f64 = 0.0;
+    let mut reasons = Vec::new();
+    let lower = content.to_lowercase();
+
+    let links = lower.matches("http://").count() + lower.matches("https://").count();
+    if links >= 3 {
+        score += 0.3;
+        reasons.push(format!("{} links", links));
+    } else if links > 0 {
+        score += 0.1;
+    }
+
+    let phrases: Vec<&str> = SPAM_PHRASES
+        .iter()
+        .copied()
+        .filter(|p| lower.contains(p))
+        .collect();
+    if !phrases.is_empty() {
+        score += (0.1 + 0.2 * phrases.len() as f64).min(0.7);
+        reasons.push(format!("spam phrases: {}", phrases.join(", ")));
+    }
+
+    let letters: Vec<char> = content.chars().filter(|c| c.is_alphabetic()).collect();
+    let upper = letters.iter().filter(|c| c.is_uppercase()).count();
+    if letters.len() >= 20 && upper * 10 >= letters.len() * 6 {
+        score += 0.2;
+        reasons.push("mostly capitals".to_string());
+    }
+
+    if content.matches('!').count() >= 5 || content.contains("$$$") {
+        score += 0.1;
+        reasons.push("excessive punctuation".to_string());
+    }
+
+    if content.chars().count() > 5000 {
+        score += 0.1;
+        reasons.push("very long".to_string());
+    }
+
+    (score.min(1.0), reasons)
+}
+
+fn reputation_key(channel: &ChannelType, sender: &str) -> String {
+    format!(
+        "{}:{}:{}",
+        REPUTATION_CATEGORY,
+        channel,
+        sender.trim().to_lowercase()
+    )
+}
+
+fn quarantine_key(id: &str) -> String {
+    format!("{}:{}", QUARANTINE_CATEGORY, id)
+}
+
+/// A sender's reputation
+pub async fn reputation(
+    db: &KnowledgeDb,
+    channel: &ChannelType,
+    sender: &str,
+) -> Result<SenderReputation> {
+    let key = reputation_key(channel, sender);
+    Ok(db
+        .get_preferences(Some(REPUTATION_CATEGORY))
+        .await?
+        .into_iter()
+        .find(|p| p.key == key)
+        .and_then(|p| serde_json::from_value(p.value).ok())
+        .unwrap_or_default())
+}
+
+async fn save_reputation(
+    db: &KnowledgeDb,
+    channel: &ChannelType,
+    sender: &str,
+    reputation: &SenderReputation,
+) -> Result<()> {
+    db.upsert_preference(
+        REPUTATION_CATEGORY,
+        &reputation_key(channel, sender),
+        serde_json::to_value(reputation)?,
+        1.0,
+        Some("spam_filter"),
+    )
+    .await?;
+    Ok(())
+}
+
+async fn update_reputation(
+    db: &KnowledgeDb,
+    channel: &ChannelType,
+    sender: &str,
+    update: impl FnOnce(&mut SenderReputation),
+) -> Result<()> {
+    let mut rep = reputation(db, channel, sender).await?;
+    update(&mut rep);
+    save_reputation(db, channel, sender, &rep).await
+}
+
+/// Quarantined messages, oldest first
+pub async fn list(db: &KnowledgeDb) -> Result<Vec<QuarantinedMessage>> {
+    let mut messages: Vec<QuarantinedMessage> = db
+        .get_preferences(Some(QUARANTINE_CATEGORY))
+        .await?
+        .into_iter()
+        .filter_map(|p| serde_json::from_value(p.value).ok())
+        .collect();
+    messages.sort_by_key(|m| m.quarantined_at);
+    Ok(messages)
+}
+
+async fn find(db: &KnowledgeDb, id: &str) -> Result<QuarantinedMessage> {
+    list(db)
+        .await?
+        .into_iter()
+        .find(|m| m.id == id)
+        .ok_or_else(|| anyhow!("No quarantined message '{}'", id))
+}
+
+/// Let a message through: the daemon answers it on its next check, and the
+/// sender is trusted from now on
+pub async fn release(db: &KnowledgeDb, id: &str) -> Result<QuarantinedMessage> {
+    let mut message = find(db, id).await?;
+    message.released = true;
+    db.upsert_preference(
+        QUARANTINE_CATEGORY,
+        &quarantine_key(id),
+        serde_json::to_value(&message)?,
+        1.0,
+        Some("spam_filter"),
+    )
+    .await?;
+    update_reputation(
+        db,
+        &message.message.channel,
+        &message.message.sender,
+        |rep| {
+            rep.trusted = true;
+            rep.blocked = false;
+        },
+    )
+    .await?;
+    Ok(message)
+}
+
+/// Delete a quarantined message, optionally blocking its sender
+pub async fn discard(db: &KnowledgeDb, id: &str, block: bool) -> Result<QuarantinedMessage> {
+    let message = find(db, id).await?;
+    db.delete_preference(&quarantine_key(id)).await?;
+    if block {
+        update_reputation(
+            db,
+            &message.message.channel,
+            &message.message.sender,
+            |rep| {
+                rep.blocked = true;
+                rep.trusted = false;
+            },
+        )
+        .await?;
+    }
+    Ok(message)
+}
+
+/// Released messages, removed from the queue
+pub async fn take_released(db: &KnowledgeDb) -> Result<Vec<IncomingMessage>> {
+    let mut released = Vec::new();
+    for message in list(db).await? {
+        if message.released {
+            db.delete_preference(&quarantine_key(&message.id)).await?;
+            released.push(message.message);
+        }
+    }
+    Ok(released)
+}
+
+/// Checks inbound messages before they reach the agent
+pub struct SpamFilter {
+    config: SpamFilterConfig,
+    db: Arc<KnowledgeDb>,
+    api: Option<ApiClient>,
+    usage: Option<Arc<UsageTracker>>,
+    /// Recent message times per sender, for flood detection
+    recent: Mutex<HashMap<String, VecDeque<Instant>>>,
+}
+
+impl SpamFilter {
+    pub fn new(config: SpamFilterConfig, db: Arc<KnowledgeDb>) -> Self {
+        Self {
+            config,
+            db,
+            api: None,
+            usage: None,
+            recent: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Client for the classifier; without one, only heuristics are used
+    pub fn with_api(mut self, api: ApiClient) -> Self {
+        self.api = Some(api);
+        self
+    }
+
+    pub fn with_usage_tracker(mut self, usage: Arc<UsageTracker>) -> Self {
+        self.usage = Some(usage);
+        self
+    }
+
+    /// Decide what happens to a message, recording the outcome. Errors
+    /// reading reputations let the message through.
+    pub async fn check(&self, msg: &IncomingMessage) -> Verdict {
+        if !self.config.channels.contains(&msg.channel)
+            || self
+                .config
+                .trusted_senders
+                .iter()
+                .any(|s| s.eq_ignore_ascii_case(msg.sender.trim()))
+        {
+            return Verdict::Allow;
+        }
+        match self.judge(msg).await {
+            Ok(verdict) => verdict,
+            Err(e) => {
+                warn!("Spam filter failed, letting message through: {}", e);
+                Verdict::Allow
+            }
+        }
+    }
+
+    async fn judge(&self, msg: &IncomingMessage) -> Result<Verdict> {
+        let rep = reputation(&self.db, &msg.channel, &msg.sender).await?;
+        if rep.blocked {
+            debug!("Dropping message from blocked sender {}", msg.sender);
+            return Ok(Verdict::Drop);
+        }
+        let flooding = self.flooding(msg);
+        if (rep.trusted || rep.allowed >= KNOWN_AFTER) && !flooding {
+            self.record(msg, rep, true).await?;
+            return Ok(Verdict::Allow);
+        }
+
+        let (mut score, mut reasons) = score(&msg.content);
+        if flooding {
+            score += 0.6;
+            reasons.push(format!(
+                "more than {} messages an hour",
+                self.config.max_per_hour
+            ));
+        }
+        if rep.quarantined > 0 {
+            score += (0.2 * rep.quarantined as f64).min(0.4);
+            reasons.push(format!("{} earlier quarantines", rep.quarantined));
+        }
+
+        let mut spam = score >= self.config.threshold;
+        if !spam
+            && self.config.classify
+            && score >= self.config.threshold / 2.0
+            && let Some(api) = &self.api
+        {
+            match self.classify(api, msg).await {
+                Ok(Some(reason)) => {
+                    spam = true;
+                    reasons.push(format!("classifier: {}", reason));
+                }
+                Ok(None) => {}
+                Err(e) => warn!("Spam classification failed: {}", e),
+            }
+        }
+
+        if !spam {
+            self.record(msg, rep, true).await?;
+            return Ok(Verdict::Allow);
+        }
+        let id = self
+            .quarantine(msg, score.min(1.0), reasons.clone())
+            .await?;
+        self.record(msg, rep, false).await?;
+        info!(
+            "Quarantined message {} from {} via {}: {}",
+            id,
+            msg.sender,
+            msg.channel,
+            reasons.join("; ")
+        );
+        Ok(Verdict::Quarantine { id, reasons })
+    }
+
+    /// Note the message and report whether the sender is over the hourly limit
+    fn flooding(&self, msg: &IncomingMessage) -> bool {
+        let window = Duration::from_secs(3600);
+        let now = Instant::now();
+        let mut recent = self.recent.lock().unwrap_or_else(|e| e.into_inner());
+        let times = recent
+            .entry(format!("{}:{}", msg.channel, msg.sender.to_lowercase()))
+            .or_default();
+        while times
+            .front()
+            .is_some_and(|t| now.duration_since(*t) > window)
+        {
+            times.pop_front();
+        }
+        times.push_back(now);
+        times.len() > self.config.max_per_hour
+    }
+
+    async fn record(
+        &self,
+        msg: &IncomingMessage,
+        mut rep: SenderReputation,
+        allowed: bool,
+    ) -> Result<()> {
+        if allowed {
+            rep.allowed = rep.allowed.saturating_add(1);
+        } else {
+            rep.quarantined = rep.quarantined.saturating_add(1);
+        }
+        save_reputation(&self.db, &msg.channel, &msg.sender, &rep).await
+    }
+
+    async fn quarantine(
+        &self,
+        msg: &IncomingMessage,
+        score: f64,
+        reasons: Vec<String>,
+    ) -> Result<String> {
+        let id = format!("q{}", &uuid::Uuid::new_v4().simple().to_string()[..7]);
+        let entry = QuarantinedMessage {
+            id: id.clone(),
+            message: msg.clone(),
+            score,
+            reasons,
+            quarantined_at: Utc::now(),
+            released: false,
+        };
+        self.db
+            .upsert_preference(
+                QUARANTINE_CATEGORY,
+                &quarantine_key(&id),
+                serde_json::to_value(&entry)?,
+                1.0,
+                Some("spam_filter"),
+            )
+            .await?;
+        Ok(id)
+    }
+
+    /// Ask the cheap model; `Some(reason)` means spam
+    async fn classify(&self, api: &ApiClient, msg: &IncomingMessage) -> Result<Option<String>> {
+        let model = overrides::resolve_alias(&self.config.model);
+        let text: String = msg.content.chars().take(MAX_CLASSIFY_CHARS).collect();
+        let messages = vec![ApiMessage {
+            role: "user".to_string(),
+            content: MessageContent::Text(format!(
+                "Message from {} via {}:\n\n{}\n\nIs this spam, a scam, or abuse sent to a \
+                 personal assistant? Answer SPAM: <short reason> or OK.",
+                msg.sender, msg.channel, text
+            )),
+        }];
+        let response = overrides::scope(
+            RequestOverrides {
+                model: Some(model.clone()),
+                ..Default::default()
+            },
+            api.chat(
+                &messages,
+                &[],
+                "You screen inbound messages for spam. Answer in one line.",
+            ),
+        )
+        .await?;
+        if let Some(tracker) = &self.usage {
+            let usage = AccumulatedUsage::from_tokens(
+                response.usage.input_tokens,
+                response.usage.output_tokens,
+            );
+            let channel = msg.channel.to_string();
+            if let Err(e) = tracker
+                .record(&model, &usage, &UsageSource::Internal, Some(&channel))
+                .await
+            {
+                warn!("Failed to record spam classification usage: {}", e);
+            }
+        }
+        let answer: String = response
+            .content
+            .iter()
+            .filter_map(|block| match block {
+                crate::api::ContentBlock::Text { text } => Some(text.as_str()),
+                _ => None,
+            })
+            .collect();
+        Ok(parse_classification(&answer))
+    }
+}
+
+fn parse_classification(answer: &str) -> Option<String> {
+    let answer = answer.trim();
+    let rest = answer
+        .get(..4)
+        .filter(|head| head.eq_ignore_ascii_case("spam"))
+        .map(|_| &answer[4..])?;
+    let reason = rest.trim_start_matches(':').trim();
+    Some(if reason.is_empty() {
+        "looks like spam".to_string()
+    } else {
+        reason.to_string()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn message(sender: &str, content: &str) -> IncomingMessage {
+        IncomingMessage {
+            id: uuid::Uuid::new_v4().to_string(),
+            sender: sender.to_string(),
+            content: content.to_string(),
+            channel: ChannelType::Email,
+            timestamp: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_score_and_classification() {
+        let (low, reasons) = score("Can you move my 3pm meeting to tomorrow?");
+        assert!(low < 0.3 && reasons.is_empty());
+        let (high, reasons) = score(
+            "CONGRATULATIONS YOU HAVE WON A GIFT CARD!!!!! CLICK HERE https://a.example \
+             https://b.example https://c.example",
+        );
+        assert!(high >= 0.6, "{}", high);
+        assert!(reasons.iter().any(|r| r.contains("gift card")));
+
+        assert_eq!(
+            parse_classification("SPAM: crypto scam").as_deref(),
+            Some("crypto scam")
+        );
+        assert_eq!(parse_classification("OK"), None);
+    }
+
+    #[tokio::test]
+    async fn test_quarantine_release_and_block() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let db = Arc::new(KnowledgeDb::new(temp.path().join("test.db")).unwrap());
+        let filter = SpamFilter::new(SpamFilterConfig::default(), db.clone());
+
+        let ok = message("ana@example.com", "Lunch tomorrow?");
+        assert_eq!(filter.check(&ok).await, Verdict::Allow);
+
+        let spam = message(
+            "promo@example.net",
+            "You have won free money! Claim your gift card: https://x.example",
+        );
+        let Verdict::Quarantine { id, .. } = filter.check(&spam).await else {
+            panic!("expected quarantine");
+        };
+        assert_eq!(list(&db).await.unwrap().len(), 1);
+        assert!(take_released(&db).await.unwrap().is_empty());
+
+        // Released messages are handed over once and the sender is trusted
+        release(&db, &id).await.unwrap();
+        let released = take_released(&db).await.unwrap();
+        assert_eq!(released.len(), 1);
+        assert_eq!(released[0].id, spam.id);
+        assert!(list(&db).await.unwrap().is_empty());
+        assert_eq!(filter.check(&spam).await, Verdict::Allow);
+
+        // Blocked senders are dropped
+        let Verdict::Quarantine { id, .. } = filter
+            .check(&message(
+                "x@spam.example",
+                "Act now: bitcoin investment opportunity",
+            ))
+            .await
+        else {
+            panic!("expected quarantine");
+        };
+        discard(&db, &id, true).await.unwrap();
+        assert_eq!(
+            filter.check(&message("X@spam.example", "hello")).await,
+            Verdict::Drop
+        );
+
+        // Other channels aren't filtered
+        let mut slack = message("x@spam.example", "hello");
+        slack.channel = ChannelType::Slack;
+        assert_eq!(filter.check(&slack).await, Verdict::Allow);
+    }
+}
@@ -0,0 +1,167 @@
+//! Incremental replies while the model is still writing
+//!
+//! A turn run under [`scope`] gets a sink for text deltas. The tool loop
+//! opens it with [`live`] around each model call, so a provider that can
+//! stream (see [`live_sink`]) pushes text as it arrives. Tool calls run
+//! [`muted`], so sub-agents and delegated work never stream into the
+//! user's reply, and redacted turns don't stream at all since their text is
+//! only restored once the whole response is back. [`relay`] turns the
+//! deltas into throttled [`MessageKind::Partial`] messages, each carrying
+//! the text so far; the final response replaces them.
+
+use std::future::Future;
+use std::time::Duration;
+
+use tokio::sync::mpsc;
+use tokio::time::Instant;
+
+use crate::types::{ChannelType, MessageKind, OutgoingMessage};
+
+/// Shortest gap between two partial messages for one reply
+pub const DEFAULT_INTERVAL: Duration = Duration::from_millis(1000);
+
+/// What a streaming model call produces
+#[derive(Debug, Clone, PartialEq)]
+pub enum StreamDelta {
+    /// More text of the current response
+    Text(String),
+    /// A new model call started; what came before won't be the reply
+    Restart,
+}
+
+pub type StreamSink = mpsc::UnboundedSender<StreamDelta>;
+
+tokio::task_local! {
+    /// The turn's sink; `None` inside tool calls
+    static TURN: Option<StreamSink>;
+    /// Set only around a model call whose text is the reply
+    static LIVE: Option<StreamSink>;
+}
+
+/// Run a turn with its response text streamed to `sink`
+pub async fn scope<F: Future>(sink: StreamSink, fut: F) -> F::Output {
+    TURN.scope(Some(sink), fut).await
+}
+
+/// Run a model call that may stream into the turn's reply
+pub async fn live<F: Future>(fut: F) -> F::Output {
+    let sink = TURN.try_with(|s| s.clone()).ok().flatten();
+    match sink {
+        Some(sink) if crate::redaction::active().is_none() => {
+            let _ = sink.send(StreamDelta::Restart);
+            LIVE.scope(Some(sink), fut).await
+        }
+        _ => fut.await,
+    }
+}
+
+/// Run work whose model calls must not stream into the reply
+pub async fn muted<F: Future>(fut: F) -> F::Output {
+    if TURN.try_with(|s| s.is_some()).unwrap_or(false) {
+        TURN.scope(None, LIVE.scope(None, fut)).await
+    } else {
+        fut.await
+    }
+}
+
+/// Where a provider should send text deltas, if the current call streams
+pub fn live_sink() -> Option<StreamSink> {
+    LIVE.try_with(Clone::clone).ok().flatten()
+}
+
+/// Forward deltas as partial replies to `reply_to` on `channel`, at most
+/// one per `interval`. Returns once every sender is dropped.
+pub async fn relay(
+    mut rx: mpsc::UnboundedReceiver<StreamDelta>,
+    channel: ChannelType,
+    reply_to: Option<String>,
+    tx: mpsc::Sender<OutgoingMessage>,
+    interval: Duration,
+) {
+    let mut text = String::new();
+    let mut sent_len = 0;
+    let mut last_sent: Option<Instant> = None;
+    while let Some(delta) = rx.recv().await {
+        match delta {
+            StreamDelta::Text(more) => text.push_str(&more),
+            StreamDelta::Restart => {
+                text.clear();
+                sent_len = 0;
+                continue;
+            }
+        }
+        let due = last_sent.is_none_or(|t| t.elapsed() >= interval);
+        if !due || text.len() == sent_len || text.trim().is_empty() {
+            continue;
+        }
+        let partial = OutgoingMessage {
+            content: text.clone(),
+            channel: channel.clone(),
+            reply_to: reply_to.clone(),
+            kind: MessageKind::Partial,
+            interactive: None,
+        };
+        if tx.send(partial).await.is_err() {
+            return;
+        }
+        sent_len = text.len();
+        last_sent = Some(Instant::now());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_only_live_calls_stream() {
+        let (sink, mut rx) = mpsc::unbounded_channel();
+        scope(sink, async {
+            assert!(live_sink().is_none());
+            live(async {
+                live_sink()
+                    .unwrap()
+                    .send(StreamDelta::Text("Hi".into()))
+                    .unwrap();
+                muted(async { live(async { assert!(live_sink().is_none()) }).await }).await;
+            })
+            .await;
+        })
+        .await;
+        assert_eq!(rx.recv().await, Some(StreamDelta::Restart));
+        assert_eq!(rx.recv().await, Some(StreamDelta::Text("Hi".into())));
+        assert_eq!(rx.recv().await, None);
+        live(async { assert!(live_sink().is_none()) }).await;
+    }
+
+    #[tokio::test]
+    async fn test_relay_sends_text_so_far() {
+        let (sink, rx) = mpsc::unbounded_channel();
+        let (tx, mut out) = mpsc::channel(8);
+        for delta in [
+            StreamDelta::Restart,
+            StreamDelta::Text("Let me check".into()),
+            StreamDelta::Restart,
+            StreamDelta::Text("It's ".into()),
+            StreamDelta::Text("sunny".into()),
+        ] {
+            sink.send(delta).unwrap();
+        }
+        drop(sink);
+        relay(
+            rx,
+            ChannelType::Discord,
+            Some("m1".into()),
+            tx,
+            Duration::ZERO,
+        )
+        .await;
+
+        let mut partials = Vec::new();
+        while let Ok(msg) = out.try_recv() {
+            assert_eq!(msg.kind, MessageKind::Partial);
+            partials.push(msg.content);
+        }
+        assert_eq!(partials, vec!["Let me check", "It's ", "It's sunny"]);
+    }
+}
@@ -0,0 +1,168 @@
+//! Structured (JSON) answers
+//!
+//! For scripts that consume Meepo's answers: the model is given a single
+//! `respond` tool whose input schema is the shape the caller wants, and is
+//! told to answer by calling it. The tool input is the answer. Models that
+//! reply in text anyway get one correction round before giving up.
+
+use anyhow::{Result, anyhow};
+use serde_json::{Value, json};
+use tracing::debug;
+
+use crate::api::{ApiClient, ApiMessage, ContentBlock, MessageContent, ToolDefinition};
+
+/// Name of the tool the model answers through
+pub const RESPOND_TOOL: &str = "respond";
+
+/// Schema used when the caller doesn't give one: `{"answer": "..."}`
+pub fn default_schema() -> Value {
+    json!({
+        "type": "object",
+        "properties": {
+            "answer": {"type": "string", "description": "The complete answer"}
+        },
+        "required": ["answer"]
+    })
+}
+
+/// Check that a caller-supplied schema can be used as a tool input schema
+pub fn check_schema(schema: &Value) -> Result<()> {
+    let obj = schema
+        .as_object()
+        .ok_or_else(|| anyhow!("Schema must be a JSON object"))?;
+    match obj.get("type").and_then(|t| t.as_str()) {
+        Some("object") => {}
+        _ => return Err(anyhow!("Schema must have \"type\": \"object\"")),
+    }
+    if let Some(props) = obj.get("properties")
+        && !props.is_object()
+    {
+        return Err(anyhow!("Schema \"properties\" must be an object"));
+    }
+    Ok(())
+}
+
+/// The `respond` tool for a schema
+pub fn respond_tool(schema: &Value) -> ToolDefinition {
+    ToolDefinition {
+        name: RESPOND_TOOL.to_string(),
+        description: "Give your final answer. Always answer by calling this tool exactly once."
+            .to_string(),
+        input_schema: schema.clone(),
+    }
+}
+
+/// System prompt addition for structured answers
+pub fn instructions() -> String {
+    format!(
+        "## Output Format\n\nYour answer is read by a program. Answer only by calling the `{}` \
+         tool once, with arguments matching its schema. Don't write any other text.",
+        RESPOND_TOOL
+    )
+}
+
+/// The structured answer in a response: the `respond` tool's input, or a
+/// JSON object written as text
+pub fn extract(content: &[ContentBlock]) -> Option<Value> {
+    let from_tool = content.iter().find_map(|block| match block {
+        ContentBlock::ToolUse { name, input, .. } if name == RESPOND_TOOL => Some(input.clone()),
+        _ => None,
+    });
+    from_tool.or_else(|| {
+        let text: String = content
+            .iter()
+            .filter_map(|block| match block {
+                ContentBlock::Text { text } => Some(text.as_str()),
+                _ => None,
+            })
+            .collect();
+        parse_json_text(&text)
+    })
+}
+
+/// Parse a JSON object from text, allowing a ```json fence around it
+pub fn parse_json_text(text: &str) -> Option<Value> {
+    let text = text.trim();
+    let text = text
+        .strip_prefix("```json")
+        .or_else(|| text.strip_prefix("```"))
+        .and_then(|t| t.strip_suffix("```"))
+        .unwrap_or(text)
+        .trim();
+    serde_json::from_str::<Value>(text)
+        .ok()
+        .filter(|v| v.is_object())
+}
+
+/// Ask a one-shot question and get the answer as JSON matching `schema`
+pub async fn ask(api: &ApiClient, system: &str, message: &str, schema: &Value) -> Result<Value> {
+    check_schema(schema)?;
+    let system = format!("{}\n\n{}", system, instructions());
+    let tools = [respond_tool(schema)];
+    let mut messages = vec![ApiMessage {
+        role: "user".to_string(),
+        content: MessageContent::Text(message.to_string()),
+    }];
+
+    let response = api.chat(&messages, &tools, &system).await?;
+    if let Some(answer) = extract(&response.content) {
+        return Ok(answer);
+    }
+
+    debug!("Model answered without the respond tool; asking again");
+    messages.push(ApiMessage {
+        role: "assistant".to_string(),
+        content: MessageContent::Blocks(response.content),
+    });
+    messages.push(ApiMessage {
+        role: "user".to_string(),
+        content: MessageContent::Text(format!(
+            "Call the `{}` tool with your answer now.",
+            RESPOND_TOOL
+        )),
+    });
+    let response = api.chat(&messages, &tools, &system).await?;
+    extract(&response.content).ok_or_else(|| anyhow!("The model didn't give a structured answer"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extract_from_tool_or_text() {
+        let from_tool = extract(&[
+            ContentBlock::Text {
+                text: "Here you go".to_string(),
+            },
+            ContentBlock::ToolUse {
+                id: "t1".to_string(),
+                name: RESPOND_TOOL.to_string(),
+                input: json!({"answer": "42"}),
+            },
+        ]);
+        assert_eq!(from_tool, Some(json!({"answer": "42"})));
+
+        let from_text = extract(&[ContentBlock::Text {
+            text: "```json\n{\"answer\": \"42\"}\n```".to_string(),
+        }]);
+        assert_eq!(from_text, Some(json!({"answer": "42"})));
+
+        assert!(
+            extract(&[ContentBlock::Text {
+                text: "The answer is 42".to_string()
+            }])
+            .is_none()
+        );
+        assert!(parse_json_text("[1, 2]").is_none());
+    }
+
+    #[test]
+    fn test_check_schema() {
+        assert!(check_schema(&default_schema()).is_ok());
+        assert!(check_schema(&json!({"type": "object"})).is_ok());
+        assert!(check_schema(&json!({"type": "array"})).is_err());
+        assert!(check_schema(&json!("object")).is_err());
+        assert!(check_schema(&json!({"type": "object", "properties": []})).is_err());
+    }
+}
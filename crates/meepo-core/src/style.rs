@@ -0,0 +1,367 @@
+//! Response style preferences (verbosity, emoji, formality)
+//!
+//! Styles are stored as `style` user preferences. The `default` scope
+//! applies to everyone; `channel:sender` scopes override individual fields
+//! for one person. The merged style is injected into the agent's context so the user
+//! doesn't have to repeat how they like their answers.
+
+use anyhow::{Result, anyhow};
+use meepo_knowledge::KnowledgeDb;
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+
+/// Preference category used for response styles
+pub const CATEGORY: &str = "style";
+
+/// Preference key for the style that applies to every sender
+pub const DEFAULT_SCOPE: &str = "default";
+
+/// Style fields that can be set
+pub const FIELDS: &[&str] = &["verbosity", "emoji", "formality"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Verbosity {
+    Concise,
+    Normal,
+    Detailed,
+}
+
+impl FromStr for Verbosity {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.trim().to_lowercase().as_str() {
+            "concise" | "brief" | "short" => Ok(Self::Concise),
+            "normal" | "default" => Ok(Self::Normal),
+            "detailed" | "verbose" | "long" => Ok(Self::Detailed),
+            other => Err(anyhow!(
+                "Invalid verbosity '{}'. Use concise, normal, or detailed",
+                other
+            )),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Formality {
+    Casual,
+    Neutral,
+    Formal,
+}
+
+impl FromStr for Formality {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.trim().to_lowercase().as_str() {
+            "casual" | "informal" | "friendly" => Ok(Self::Casual),
+            "neutral" | "default" => Ok(Self::Neutral),
+            "formal" | "professional" => Ok(Self::Formal),
+            other => Err(anyhow!(
+                "Invalid formality '{}'. Use casual, neutral, or formal",
+                other
+            )),
+        }
+    }
+}
+
+/// A set of response style settings. Unset fields fall through to the
+/// default style, then to the model's normal behavior.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ResponseStyle {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub verbosity: Option<Verbosity>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub emoji: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub formality: Option<Formality>,
+}
+
+impl ResponseStyle {
+    pub fn is_empty(&self) -> bool {
+        self.verbosity.is_none() && self.emoji.is_none() && self.formality.is_none()
+    }
+
+    /// Overlay `other` on top of this style; fields set in `other` win
+    pub fn merged_with(&self, other: &ResponseStyle) -> ResponseStyle {
+        ResponseStyle {
+            verbosity: other.verbosity.or(self.verbosity),
+            emoji: other.emoji.or(self.emoji),
+            formality: other.formality.or(self.formality),
+        }
+    }
+
+    /// Set one field from its string form. "unset" or an empty value clears it.
+    pub fn set_field(&mut self, field: &str, value: &str) -> Result<()> {
+        let value = value.trim();
+        let clear = value.is_empty() || value.eq_ignore_ascii_case("unset");
+        match field.trim().to_lowercase().as_str() {
+            "verbosity" => self.verbosity = if clear { None } else { Some(value.parse()?) },
+            "formality" | "tone" => {
+                self.formality = if clear { None } else { Some(value.parse()?) }
+            }
+            "emoji" | "emojis" => {
+                self.emoji = if clear {
+                    None
+                } else {
+                    Some(match value.to_lowercase().as_str() {
+                        "on" | "true" | "yes" => true,
+                        "off" | "false" | "no" => false,
+                        other => {
+                            return Err(anyhow!(
+                                "Invalid emoji setting '{}'. Use on or off",
+                                other
+                            ));
+                        }
+                    })
+                }
+            }
+            other => {
+                return Err(anyhow!(
+                    "Unknown style field '{}'. Use one of: {}",
+                    other,
+                    FIELDS.join(", ")
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// Prompt instructions for the set fields, one per line
+    pub fn instructions(&self) -> String {
+        let mut lines = Vec::new();
+        match self.verbosity {
+            Some(Verbosity::Concise) => lines.push(
+                "- Be concise: answer in a few sentences or a short list, skip preamble and recaps.",
+            ),
+            Some(Verbosity::Detailed) => lines
+                .push("- Be detailed: explain your reasoning, include examples and caveats."),
+            Some(Verbosity::Normal) | None => {}
+        }
+        match self.emoji {
+            Some(true) => lines.push("- Emoji are welcome where they help."),
+            Some(false) => lines.push("- Do not use emoji."),
+            None => {}
+        }
+        match self.formality {
+            Some(Formality::Casual) => lines.push("- Use a casual, friendly tone."),
+            Some(Formality::Formal) => {
+                lines.push("- Use a formal, professional tone; no slang or jokes.")
+            }
+            Some(Formality::Neutral) | None => {}
+        }
+        lines.join("\n")
+    }
+
+    /// Short human-readable summary, e.g. "verbosity=concise, emoji=off"
+    pub fn describe(&self) -> String {
+        let mut parts = Vec::new();
+        if let Some(v) = self.verbosity {
+            parts.push(format!("verbosity={}", enum_name(&v)));
+        }
+        if let Some(e) = self.emoji {
+            parts.push(format!("emoji={}", if e { "on" } else { "off" }));
+        }
+        if let Some(f) = self.formality {
+            parts.push(format!("formality={}", enum_name(&f)));
+        }
+        if parts.is_empty() {
+            "(not set)".to_string()
+        } else {
+            parts.join(", ")
+        }
+    }
+}
+
+fn enum_name<T: Serialize>(value: &T) -> String {
+    serde_json::to_value(value)
+        .ok()
+        .and_then(|v| v.as_str().map(String::from))
+        .unwrap_or_default()
+}
+
+/// Scope for one sender on one channel
+pub fn sender_scope(channel: &str, sender: &str) -> String {
+    format!("{}:{}", channel, sender)
+}
+
+/// Preference key for a scope within a category. Keys are unique across
+/// categories, so each carries its category's name.
+pub fn preference_key(category: &str, scope: &str) -> String {
+    format!("{}:{}", category, scope)
+}
+
+/// Load the style stored for a scope (empty if none)
+pub async fn load(db: &KnowledgeDb, scope: &str) -> Result<ResponseStyle> {
+    let key = preference_key(CATEGORY, scope);
+    let prefs = db.get_preferences(Some(CATEGORY)).await?;
+    Ok(prefs
+        .into_iter()
+        .find(|p| p.key == key)
+        .and_then(|p| serde_json::from_value(p.value).ok())
+        .unwrap_or_default())
+}
+
+/// Store the style for a scope
+pub async fn save(
+    db: &KnowledgeDb,
+    scope: &str,
+    style: &ResponseStyle,
+    learned_from: &str,
+) -> Result<()> {
+    db.upsert_preference(
+        CATEGORY,
+        &preference_key(CATEGORY, scope),
+        serde_json::to_value(style)?,
+        1.0,
+        Some(learned_from),
+    )
+    .await?;
+    Ok(())
+}
+
+/// Set one field of a scope's style and return the updated style
+pub async fn set_field(
+    db: &KnowledgeDb,
+    scope: &str,
+    field: &str,
+    value: &str,
+    learned_from: &str,
+) -> Result<ResponseStyle> {
+    let mut style = load(db, scope).await?;
+    style.set_field(field, value)?;
+    save(db, scope, &style, learned_from).await?;
+    Ok(style)
+}
+
+/// The effective style for a sender: their overrides on top of the default
+pub async fn effective(db: &KnowledgeDb, channel: &str, sender: &str) -> Result<ResponseStyle> {
+    let prefs = db.get_preferences(Some(CATEGORY)).await?;
+    let get = |scope: &str| -> ResponseStyle {
+        let key = preference_key(CATEGORY, scope);
+        prefs
+            .iter()
+            .find(|p| p.key == key)
+            .and_then(|p| serde_json::from_value(p.value.clone()).ok())
+            .unwrap_or_default()
+    };
+    Ok(get(DEFAULT_SCOPE).merged_with(&get(&sender_scope(channel, sender))))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_set_field_parses_values() {
+        let mut style = ResponseStyle::default();
+        style.set_field("verbosity", "brief").unwrap();
+        style.set_field("emoji", "off").unwrap();
+        style.set_field("tone", "Professional").unwrap();
+        assert_eq!(style.verbosity, Some(Verbosity::Concise));
+        assert_eq!(style.emoji, Some(false));
+        assert_eq!(style.formality, Some(Formality::Formal));
+        assert_eq!(
+            style.describe(),
+            "verbosity=concise, emoji=off, formality=formal"
+        );
+
+        style.set_field("emoji", "unset").unwrap();
+        assert_eq!(style.emoji, None);
+
+        assert!(style.set_field("verbosity", "rambling").is_err());
+        assert!(style.set_field("font", "serif").is_err());
+    }
+
+    #[test]
+    fn test_merge_and_instructions() {
+        let base = ResponseStyle {
+            verbosity: Some(Verbosity::Detailed),
+            emoji: Some(false),
+            formality: None,
+        };
+        let over = ResponseStyle {
+            verbosity: Some(Verbosity::Concise),
+            ..Default::default()
+        };
+        let merged = base.merged_with(&over);
+        assert_eq!(merged.verbosity, Some(Verbosity::Concise));
+        assert_eq!(merged.emoji, Some(false));
+
+        let text = merged.instructions();
+        assert!(text.contains("Be concise"));
+        assert!(text.contains("Do not use emoji"));
+        assert!(ResponseStyle::default().instructions().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_effective_style_per_sender() {
+        let temp = TempDir::new().unwrap();
+        let db = KnowledgeDb::new(temp.path().join("test.db")).unwrap();
+
+        set_field(&db, DEFAULT_SCOPE, "emoji", "off", "config")
+            .await
+            .unwrap();
+        set_field(&db, "slack:bob", "formality", "formal", "tool")
+            .await
+            .unwrap();
+
+        let bob = effective(&db, "slack", "bob").await.unwrap();
+        assert_eq!(bob.emoji, Some(false));
+        assert_eq!(bob.formality, Some(Formality::Formal));
+
+        let alice = effective(&db, "slack", "alice").await.unwrap();
+        assert_eq!(alice.emoji, Some(false));
+        assert_eq!(alice.formality, None);
+    }
+
+    #[tokio::test]
+    async fn test_style_and_language_for_one_sender() {
+        let temp = TempDir::new().unwrap();
+        let path = temp.path().join("test.db");
+        let db = KnowledgeDb::new(&path).unwrap();
+
+        crate::i18n::pin_language(&db, "slack:anna", "de")
+            .await
+            .unwrap();
+        set_field(&db, "slack:anna", "emoji", "off", "tool")
+            .await
+            .unwrap();
+        assert_eq!(load(&db, "slack:anna").await.unwrap().emoji, Some(false));
+        let languages = db
+            .get_preferences(Some(crate::i18n::PREFERENCE_CATEGORY))
+            .await
+            .unwrap();
+        assert_eq!(languages.len(), 1);
+        assert_eq!(languages[0].value, serde_json::json!("de"));
+
+        // Unpinning the language leaves the style alone
+        crate::i18n::pin_language(&db, "slack:anna", "unset")
+            .await
+            .unwrap();
+        assert!(
+            db.get_preferences(Some(crate::i18n::PREFERENCE_CATEGORY))
+                .await
+                .unwrap()
+                .is_empty()
+        );
+        assert_eq!(load(&db, "slack:anna").await.unwrap().emoji, Some(false));
+
+        // Rows stored under the old bare keys are moved under their category
+        db.upsert_preference(
+            CATEGORY,
+            "slack:bob",
+            serde_json::json!({"emoji": true}),
+            1.0,
+            None,
+        )
+        .await
+        .unwrap();
+        drop(db);
+        let db = KnowledgeDb::new(&path).unwrap();
+        assert_eq!(load(&db, "slack:bob").await.unwrap().emoji, Some(true));
+    }
+}
@@ -0,0 +1,980 @@
+//! Two-way task sync with Apple Reminders or Todoist
+//!
+//! Pending tasks and active goals are mirrored into a designated Reminders
+//! list or Todoist project, and items added there become Meepo tasks. Each
+//! linked pair is a `task_sync` preference holding the state both sides
+//! agreed on at the last sync: a side whose completion or due date differs
+//! from it has changed, and when both have, the more recently modified side
+//! wins. Titles are owned by the remote side once an item is exported. An
+//! item that disappears remotely (deleted, or completed in Todoist, which
+//! only lists open tasks) counts as completed there.
+
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use chrono::{DateTime, NaiveDate, TimeZone, Utc};
+use meepo_knowledge::{Entity, Goal, KnowledgeDb};
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::sync::OnceCell;
+use tokio_util::sync::CancellationToken;
+use tracing::{debug, info, warn};
+
+use crate::platform::{ReminderItem, RemindersProvider};
+
+/// Preference category holding sync links
+pub const CATEGORY: &str = "task_sync";
+
+/// Prefix of link keys (preference keys are global)
+const LINK_PREFIX: &str = "task_sync:";
+
+const TODOIST_API: &str = "https://api.todoist.com/api/v1";
+
+/// Local hour given to due dates pushed to services that want a time
+const DUE_HOUR: u32 = 9;
+
+/// What both sides track for a task
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct TaskState {
+    pub title: String,
+    pub completed: bool,
+    pub due: Option<NaiveDate>,
+}
+
+impl TaskState {
+    /// Same completion and due date (titles aren't synced back)
+    fn same_progress(&self, other: &TaskState) -> bool {
+        self.completed == other.completed && self.due == other.due
+    }
+}
+
+/// A task on the remote side
+#[derive(Debug, Clone, PartialEq)]
+pub struct RemoteTask {
+    pub id: String,
+    pub state: TaskState,
+    pub modified: Option<DateTime<Utc>>,
+}
+
+/// A task list Meepo syncs with
+#[async_trait]
+pub trait TaskBackend: Send + Sync {
+    /// Name used in logs and as the `source` of imported tasks
+    fn name(&self) -> &str;
+    /// Tasks in the synced list or project
+    async fn list(&self) -> Result<Vec<RemoteTask>>;
+    /// Create a task, returning its id
+    async fn create(&self, state: &TaskState, notes: Option<&str>) -> Result<String>;
+    /// Update a task's title, due date and completion
+    async fn update(&self, id: &str, state: &TaskState) -> Result<()>;
+}
+
+/// Apple Reminders list, via the platform reminders provider
+pub struct RemindersBackend {
+    provider: Box<dyn RemindersProvider>,
+    list: String,
+}
+
+impl RemindersBackend {
+    pub fn new(provider: Box<dyn RemindersProvider>, list: impl Into<String>) -> Self {
+        Self {
+            provider,
+            list: list.into(),
+        }
+    }
+
+    fn item(id: &str, state: &TaskState, notes: Option<&str>) -> ReminderItem {
+        ReminderItem {
+            id: id.to_string(),
+            title: state.title.clone(),
+            completed: state.completed,
+            due: state.due.and_then(due_time),
+            notes: notes.map(String::from),
+            modified: None,
+        }
+    }
+}
+
+#[async_trait]
+impl TaskBackend for RemindersBackend {
+    fn name(&self) -> &str {
+        "reminders"
+    }
+
+    async fn list(&self) -> Result<Vec<RemoteTask>> {
+        let tz = crate::timezone::user_timezone();
+        Ok(self
+            .provider
+            .list_reminder_items(&self.list)
+            .await?
+            .into_iter()
+            .map(|item| RemoteTask {
+                id: item.id,
+                state: TaskState {
+                    title: item.title,
+                    completed: item.completed,
+                    due: item.due.map(|d| d.with_timezone(&tz).date_naive()),
+                },
+                modified: item.modified,
+            })
+            .collect())
+    }
+
+    async fn create(&self, state: &TaskState, notes: Option<&str>) -> Result<String> {
+        self.provider
+            .add_reminder_item(&self.list, &Self::item("", state, notes))
+            .await
+    }
+
+    async fn update(&self, id: &str, state: &TaskState) -> Result<()> {
+        self.provider
+            .update_reminder_item(&Self::item(id, state, None))
+            .await
+    }
+}
+
+/// Todoist project, via the Todoist API
+pub struct TodoistBackend {
+    client: Client,
+    token: String,
+    project: String,
+    project_id: OnceCell<String>,
+}
+
+impl std::fmt::Debug for TodoistBackend {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TodoistBackend")
+            .field("token", &"[REDACTED]")
+            .field("project", &self.project)
+            .finish()
+    }
+}
+
+impl TodoistBackend {
+    pub fn new(token: String, project: impl Into<String>) -> Self {
+        let client = Client::builder()
+            .timeout(std::time::Duration::from_secs(30))
+            .build()
+            .expect("Failed to build HTTP client");
+        Self {
+            client,
+            token,
+            project: project.into(),
+            project_id: OnceCell::new(),
+        }
+    }
+
+    async fn request(
+        &self,
+        method: reqwest::Method,
+        path: &str,
+        query: &[(&str, &str)],
+        body: Option<Value>,
+    ) -> Result<Value> {
+        crate::network::ensure_online("Todoist sync")?;
+        let mut request = self
+            .client
+            .request(method, format!("{}{}", TODOIST_API, path))
+            .query(query)
+            .bearer_auth(&self.token);
+        if let Some(body) = body {
+            request = request.json(&body);
+        }
+        let response = request
+            .send()
+            .await
+            .context("Failed to send Todoist request")?;
+
+        let status = response.status();
+        if !status.is_success() {
+            let body = response.text().await.unwrap_or_default();
+            anyhow::bail!(
+                "Todoist request {} failed with status {status}: {body}",
+                path
+            );
+        }
+        let text = response.text().await.unwrap_or_default();
+        if text.trim().is_empty() {
+            return Ok(Value::Null);
+        }
+        serde_json::from_str(&text).context("Failed to parse Todoist response")
+    }
+
+    /// Every page of a list endpoint
+    async fn list_all(&self, path: &str, query: &[(&str, &str)]) -> Result<Vec<Value>> {
+        let mut items = Vec::new();
+        let mut cursor: Option<String> = None;
+        loop {
+            let mut page_query = query.to_vec();
+            if let Some(cursor) = &cursor {
+                page_query.push(("cursor", cursor));
+            }
+            let (page, next) = todoist_page(
+                self.request(reqwest::Method::GET, path, &page_query, None)
+                    .await?,
+            );
+            items.extend(page);
+            match next {
+                Some(next) => cursor = Some(next),
+                None => return Ok(items),
+            }
+        }
+    }
+
+    /// Id of the synced project, creating it if needed
+    async fn project_id(&self) -> Result<&str> {
+        let id = self
+            .project_id
+            .get_or_try_init(|| async {
+                let projects = self.list_all("/projects", &[]).await?;
+                if let Some(id) = projects
+                    .iter()
+                    .find(|p| p.get("name").and_then(Value::as_str) == Some(&self.project))
+                    .and_then(|p| todoist_id(p.get("id")?))
+                {
+                    return Ok::<_, anyhow::Error>(id);
+                }
+                info!("Creating Todoist project '{}'", self.project);
+                let created = self
+                    .request(
+                        reqwest::Method::POST,
+                        "/projects",
+                        &[],
+                        Some(serde_json::json!({ "name": self.project })),
+                    )
+                    .await?;
+                created
+                    .get("id")
+                    .and_then(todoist_id)
+                    .context("Todoist project response has no id")
+            })
+            .await?;
+        Ok(id)
+    }
+}
+
+#[async_trait]
+impl TaskBackend for TodoistBackend {
+    fn name(&self) -> &str {
+        "todoist"
+    }
+
+    async fn list(&self) -> Result<Vec<RemoteTask>> {
+        let project_id = self.project_id().await?;
+        Ok(self
+            .list_all("/tasks", &[("project_id", project_id), ("limit", "200")])
+            .await?
+            .iter()
+            .filter_map(parse_todoist_task)
+            .collect())
+    }
+
+    async fn create(&self, state: &TaskState, notes: Option<&str>) -> Result<String> {
+        let mut body = serde_json::json!({
+            "content": state.title,
+            "project_id": self.project_id().await?,
+        });
+        if let Some(notes) = notes {
+            body["description"] = serde_json::json!(notes);
+        }
+        if let Some(due) = state.due {
+            body["due_date"] = serde_json::json!(due.to_string());
+        }
+        let created = self
+            .request(reqwest::Method::POST, "/tasks", &[], Some(body))
+            .await?;
+        let id = created
+            .get("id")
+            .and_then(todoist_id)
+            .context("Todoist task response has no id")?;
+        if state.completed {
+            self.request(
+                reqwest::Method::POST,
+                &format!("/tasks/{}/close", id),
+                &[],
+                None,
+            )
+            .await?;
+        }
+        Ok(id)
+    }
+
+    async fn update(&self, id: &str, state: &TaskState) -> Result<()> {
+        let mut body = serde_json::json!({ "content": state.title });
+        match state.due {
+            Some(due) => body["due_date"] = serde_json::json!(due.to_string()),
+            None => body["due_string"] = serde_json::json!("no date"),
+        }
+        self.request(
+            reqwest::Method::POST,
+            &format!("/tasks/{}", id),
+            &[],
+            Some(body),
+        )
+        .await?;
+        let action = if state.completed { "close" } else { "reopen" };
+        self.request(
+            reqwest::Method::POST,
+            &format!("/tasks/{}/{}", id, action),
+            &[],
+            None,
+        )
+        .await?;
+        Ok(())
+    }
+}
+
+/// Items and next cursor of a Todoist list response (a bare array for older
+/// endpoints)
+fn todoist_page(response: Value) -> (Vec<Value>, Option<String>) {
+    match response {
+        Value::Array(items) => (items, None),
+        Value::Object(mut page) => {
+            let items = match page.remove("results") {
+                Some(Value::Array(items)) => items,
+                _ => Vec::new(),
+            };
+            let next = page
+                .get("next_cursor")
+                .and_then(Value::as_str)
+                .filter(|c| !c.is_empty())
+                .map(String::from);
+            (items, next)
+        }
+        _ => (Vec::new(), None),
+    }
+}
+
+/// Todoist ids are strings, but older responses use numbers
+fn todoist_id(value: &Value) -> Option<String> {
+    match value {
+        Value::String(id) => Some(id.clone()),
+        Value::Number(id) => Some(id.to_string()),
+        _ => None,
+    }
+}
+
+fn parse_todoist_task(task: &Value) -> Option<RemoteTask> {
+    let completed = ["checked", "is_completed"]
+        .iter()
+        .any(|field| task.get(*field).and_then(Value::as_bool) == Some(true));
+    Some(RemoteTask {
+        id: todoist_id(task.get("id")?)?,
+        state: TaskState {
+            title: task.get("content")?.as_str()?.to_string(),
+            completed,
+            due: task
+                .pointer("/due/date")
+                .and_then(Value::as_str)
+                .and_then(|d| d.get(..10))
+                .and_then(|d| d.parse().ok()),
+        },
+        modified: task
+            .get("updated_at")
+            .and_then(Value::as_str)
+            .and_then(|t| t.parse().ok()),
+    })
+}
+
+/// Tasks and goals synced by the last run
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SyncReport {
+    /// Meepo changes sent to the remote side
+    pub pushed: usize,
+    /// Remote changes applied in Meepo
+    pub pulled: usize,
+    /// Meepo items newly added remotely
+    pub exported: usize,
+    /// Remote items newly added as Meepo tasks
+    pub imported: usize,
+    /// Items changed on both sides since the last sync
+    pub conflicts: usize,
+}
+
+impl SyncReport {
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+impl std::fmt::Display for SyncReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} pushed, {} pulled, {} exported, {} imported, {} conflicts",
+            self.pushed, self.pulled, self.exported, self.imported, self.conflicts
+        )
+    }
+}
+
+/// A linked task or goal as of the last sync
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Link {
+    remote_id: String,
+    state: TaskState,
+    synced_at: DateTime<Utc>,
+}
+
+/// A task (`task:<id>`) or goal (`goal:<id>`) on the Meepo side
+#[derive(Debug, Clone)]
+struct LocalItem {
+    key: String,
+    state: TaskState,
+    modified: DateTime<Utc>,
+    notes: Option<String>,
+}
+
+/// Keeps Meepo's tasks and goals in step with a remote task list
+pub struct TaskSync {
+    db: Arc<KnowledgeDb>,
+    backend: Box<dyn TaskBackend>,
+    include_goals: bool,
+}
+
+impl TaskSync {
+    pub fn new(db: Arc<KnowledgeDb>, backend: Box<dyn TaskBackend>) -> Self {
+        Self {
+            db,
+            backend,
+            include_goals: true,
+        }
+    }
+
+    /// Whether active goals are synced alongside tasks
+    pub fn with_goals(mut self, include_goals: bool) -> Self {
+        self.include_goals = include_goals;
+        self
+    }
+
+    /// Sync both ways once
+    pub async fn sync_once(&self, now: DateTime<Utc>) -> Result<SyncReport> {
+        let mut remote: HashMap<String, RemoteTask> = self
+            .backend
+            .list()
+            .await?
+            .into_iter()
+            .map(|t| (t.id.clone(), t))
+            .collect();
+        let mut local = self.local_items().await?;
+        let mut report = SyncReport::default();
+
+        for (key, link) in self.links().await? {
+            let local_item = match local.remove(&key) {
+                Some(item) => Some(item),
+                None => self.fetch_local(&key).await?,
+            };
+            let remote_item = remote.remove(&link.remote_id);
+            if let Err(e) = self
+                .sync_link(&key, link, local_item, remote_item, now, &mut report)
+                .await
+            {
+                warn!("Failed to sync {} with {}: {}", key, self.backend.name(), e);
+            }
+        }
+
+        for item in local.into_values().filter(|i| !i.state.completed) {
+            match self
+                .backend
+                .create(&item.state, item.notes.as_deref())
+                .await
+            {
+                Ok(remote_id) => {
+                    self.save_link(&item.key, remote_id, item.state, now)
+                        .await?;
+                    report.exported += 1;
+                }
+                Err(e) => warn!(
+                    "Failed to export {} to {}: {}",
+                    item.key,
+                    self.backend.name(),
+                    e
+                ),
+            }
+        }
+
+        for task in remote.into_values().filter(|t| !t.state.completed) {
+            let id = self
+                .db
+                .insert_entity(
+                    &task.state.title,
+                    "task",
+                    Some(serde_json::json!({
+                        "status": "pending",
+                        "priority": "medium",
+                        "priority_num": 3,
+                        "description": null,
+                        "due_date": task.state.due.map(|d| d.to_string()),
+                        "project": null,
+                        "context": null,
+                        "source": self.backend.name(),
+                        "created_at": now.to_rfc3339(),
+                    })),
+                )
+                .await?;
+            self.save_link(&format!("task:{}", id), task.id, task.state, now)
+                .await?;
+            report.imported += 1;
+        }
+
+        if !report.is_empty() {
+            info!("Task sync with {}: {}", self.backend.name(), report);
+        }
+        Ok(report)
+    }
+
+    /// Sync every `interval` until cancelled
+    pub async fn run(self, interval: std::time::Duration, cancel: CancellationToken) {
+        info!(
+            "Task sync with {} started (every {}s)",
+            self.backend.name(),
+            interval.as_secs()
+        );
+        loop {
+            if let Err(e) = self.sync_once(Utc::now()).await {
+                warn!("Task sync with {} failed: {}", self.backend.name(), e);
+            }
+            tokio::select! {
+                _ = cancel.cancelled() => break,
+                _ = tokio::time::sleep(interval) => {}
+            }
+        }
+        info!("Task sync shutting down");
+    }
+
+    async fn sync_link(
+        &self,
+        key: &str,
+        link: Link,
+        local: Option<LocalItem>,
+        remote: Option<RemoteTask>,
+        now: DateTime<Utc>,
+        report: &mut SyncReport,
+    ) -> Result<()> {
+        let Some(local) = local else {
+            // Deleted in Meepo: close the remote copy and stop tracking it
+            if let Some(remote) = remote.filter(|r| !r.state.completed) {
+                let state = TaskState {
+                    completed: true,
+                    ..remote.state
+                };
+                self.backend.update(&remote.id, &state).await?;
+                report.pushed += 1;
+            }
+            return self.forget(key).await;
+        };
+
+        let (remote_state, remote_modified) = match &remote {
+            Some(r) => (r.state.clone(), r.modified.unwrap_or(now)),
+            None => (
+                TaskState {
+                    completed: true,
+                    ..link.state.clone()
+                },
+                now,
+            ),
+        };
+        let local_changed = !local.state.same_progress(&link.state);
+        let remote_changed = !remote_state.same_progress(&link.state);
+        let push = match (local_changed, remote_changed) {
+            (false, false) => None,
+            (true, false) => Some(true),
+            (false, true) => Some(false),
+            (true, true) if local.state.same_progress(&remote_state) => None,
+            (true, true) => {
+                report.conflicts += 1;
+                debug!(
+                    "Sync conflict on {}: local {} vs remote {}",
+                    key, local.modified, remote_modified
+                );
+                Some(local.modified >= remote_modified)
+            }
+        };
+
+        // Remote owns the title; Meepo's progress wins when pushing
+        let mut state = TaskState {
+            title: remote_state.title.clone(),
+            ..local.state.clone()
+        };
+        let mut remote_id = link.remote_id;
+        match (push, &remote) {
+            (Some(true), Some(_)) => {
+                self.backend.update(&remote_id, &state).await?;
+                report.pushed += 1;
+            }
+            (Some(true), None) if !state.completed => {
+                remote_id = self.backend.create(&state, local.notes.as_deref()).await?;
+                report.pushed += 1;
+            }
+            (Some(false), _) => {
+                self.apply_local(key, &remote_state, now).await?;
+                state = remote_state;
+                report.pulled += 1;
+            }
+            _ => state = remote_state,
+        }
+
+        // Gone remotely and done here: nothing left to sync
+        if remote.is_none() && state.completed {
+            return self.forget(key).await;
+        }
+        self.save_link(key, remote_id, state, now).await
+    }
+
+    async fn local_items(&self) -> Result<HashMap<String, LocalItem>> {
+        let mut items = HashMap::new();
+        for entity in self.db.search_entities("", Some("task")).await? {
+            let item = task_item(&entity);
+            items.insert(item.key.clone(), item);
+        }
+        if self.include_goals {
+            for goal in self.db.get_active_goals().await? {
+                let item = goal_item(&goal);
+                items.insert(item.key.clone(), item);
+            }
+        }
+        Ok(items)
+    }
+
+    async fn fetch_local(&self, key: &str) -> Result<Option<LocalItem>> {
+        match key.split_once(':') {
+            Some(("task", id)) => Ok(self.db.get_entity(id).await?.map(|e| task_item(&e))),
+            Some(("goal", id)) => Ok(self.db.get_goal(id).await?.map(|g| goal_item(&g))),
+            _ => Ok(None),
+        }
+    }
+
+    /// Apply a remote change to the local task or goal
+    async fn apply_local(&self, key: &str, state: &TaskState, now: DateTime<Utc>) -> Result<()> {
+        match key.split_once(':') {
+            Some(("task", id)) => {
+                let Some(entity) = self.db.get_entity(id).await? else {
+                    return Ok(());
+                };
+                let mut metadata = entity.metadata.unwrap_or_else(|| serde_json::json!({}));
+                metadata["status"] = serde_json::json!(if state.completed {
+                    "completed"
+                } else {
+                    "pending"
+                });
+                if state.completed {
+                    metadata["completed_at"] = serde_json::json!(now.to_rfc3339());
+                }
+                metadata["due_date"] = serde_json::json!(state.due.map(|d| d.to_string()));
+                metadata["updated_at"] = serde_json::json!(now.to_rfc3339());
+                self.db.update_entity_metadata(id, metadata).await
+            }
+            Some(("goal", id)) => {
+                let Some(goal) = self.db.get_goal(id).await? else {
+                    return Ok(());
+                };
+                let current = goal_item(&goal).state;
+                if current.completed != state.completed {
+                    let status = if state.completed {
+                        "completed"
+                    } else {
+                        "active"
+                    };
+                    self.db.update_goal_status(id, status).await?;
+                }
+                if current.due != state.due {
+                    self.db
+                        .set_goal_deadline(id, state.due.and_then(due_time))
+                        .await?;
+                }
+                Ok(())
+            }
+            _ => Ok(()),
+        }
+    }
+
+    async fn links(&self) -> Result<Vec<(String, Link)>> {
+        Ok(self
+            .db
+            .get_preferences(Some(CATEGORY))
+            .await?
+            .into_iter()
+            .filter_map(|p| {
+                let key = p.key.strip_prefix(LINK_PREFIX)?.to_string();
+                Some((key, serde_json::from_value(p.value).ok()?))
+            })
+            .collect())
+    }
+
+    async fn save_link(
+        &self,
+        key: &str,
+        remote_id: String,
+        state: TaskState,
+        now: DateTime<Utc>,
+    ) -> Result<()> {
+        let link = Link {
+            remote_id,
+            state,
+            synced_at: now,
+        };
+        self.db
+            .upsert_preference(
+                CATEGORY,
+                &format!("{}{}", LINK_PREFIX, key),
+                serde_json::to_value(link)?,
+                1.0,
+                Some(self.backend.name()),
+            )
+            .await?;
+        Ok(())
+    }
+
+    async fn forget(&self, key: &str) -> Result<()> {
+        self.db
+            .delete_preference(&format!("{}{}", LINK_PREFIX, key))
+            .await?;
+        Ok(())
+    }
+}
+
+fn task_item(entity: &Entity) -> LocalItem {
+    let metadata = entity.metadata.as_ref();
+    let field = |name: &str| metadata.and_then(|m| m.get(name)).and_then(Value::as_str);
+    let modified = ["updated_at", "completed_at", "created_at"]
+        .iter()
+        .filter_map(|name| field(name)?.parse::<DateTime<Utc>>().ok())
+        .max()
+        .unwrap_or(entity.updated_at);
+    LocalItem {
+        key: format!("task:{}", entity.id),
+        state: TaskState {
+            title: entity.name.clone(),
+            completed: matches!(field("status"), Some("completed" | "done" | "cancelled")),
+            due: field("due_date")
+                .and_then(|d| d.get(..10))
+                .and_then(|d| d.parse().ok()),
+        },
+        modified,
+        notes: field("description").map(String::from),
+    }
+}
+
+fn goal_item(goal: &Goal) -> LocalItem {
+    let tz = crate::timezone::user_timezone();
+    LocalItem {
+        key: format!("goal:{}", goal.id),
+        state: TaskState {
+            title: goal.description.clone(),
+            completed: matches!(goal.status.as_str(), "completed" | "failed"),
+            due: goal.deadline.map(|d| d.with_timezone(&tz).date_naive()),
+        },
+        modified: goal.updated_at,
+        notes: goal.success_criteria.clone(),
+    }
+}
+
+/// A due date as a time of day in the user's timezone
+fn due_time(date: NaiveDate) -> Option<DateTime<Utc>> {
+    crate::timezone::user_timezone()
+        .from_local_datetime(&date.and_hms_opt(DUE_HOUR, 0, 0)?)
+        .earliest()
+        .map(|t| t.with_timezone(&Utc))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+    use tempfile::TempDir;
+
+    /// In-memory task list
+    #[derive(Default)]
+    struct MemoryBackend {
+        tasks: Mutex<Vec<RemoteTask>>,
+    }
+
+    #[async_trait]
+    impl TaskBackend for Arc<MemoryBackend> {
+        fn name(&self) -> &str {
+            "memory"
+        }
+
+        async fn list(&self) -> Result<Vec<RemoteTask>> {
+            Ok(self.tasks.lock().unwrap().clone())
+        }
+
+        async fn create(&self, state: &TaskState, _notes: Option<&str>) -> Result<String> {
+            let mut tasks = self.tasks.lock().unwrap();
+            let id = format!("r{}", tasks.len() + 1);
+            tasks.push(RemoteTask {
+                id: id.clone(),
+                state: state.clone(),
+                modified: Some(Utc::now()),
+            });
+            Ok(id)
+        }
+
+        async fn update(&self, id: &str, state: &TaskState) -> Result<()> {
+            let mut tasks = self.tasks.lock().unwrap();
+            let task = tasks.iter_mut().find(|t| t.id == id).context("no task")?;
+            task.state = state.clone();
+            Ok(())
+        }
+    }
+
+    impl MemoryBackend {
+        fn edit(&self, id: &str, modified: DateTime<Utc>, edit: impl FnOnce(&mut TaskState)) {
+            let mut tasks = self.tasks.lock().unwrap();
+            let task = tasks.iter_mut().find(|t| t.id == id).unwrap();
+            edit(&mut task.state);
+            task.modified = Some(modified);
+        }
+
+        fn get(&self, id: &str) -> TaskState {
+            let tasks = self.tasks.lock().unwrap();
+            tasks.iter().find(|t| t.id == id).unwrap().state.clone()
+        }
+    }
+
+    async fn task_status(db: &KnowledgeDb, id: &str) -> String {
+        let entity = db.get_entity(id).await.unwrap().unwrap();
+        entity.metadata.unwrap()["status"]
+            .as_str()
+            .unwrap()
+            .to_string()
+    }
+
+    #[test]
+    fn test_parse_todoist_task() {
+        let (items, next) = todoist_page(serde_json::json!({
+            "results": [
+                {"id": "123", "content": "Buy milk", "checked": false,
+                 "due": {"date": "2026-03-06T10:00:00"}, "updated_at": "2026-03-02T09:00:00Z"},
+                {"id": 456, "content": "Old", "is_completed": true},
+                {"content": "No id"}
+            ],
+            "next_cursor": "abc"
+        }));
+        assert_eq!(next.as_deref(), Some("abc"));
+        let tasks: Vec<RemoteTask> = items.iter().filter_map(parse_todoist_task).collect();
+        assert_eq!(tasks.len(), 2);
+        assert_eq!(tasks[0].id, "123");
+        assert_eq!(tasks[0].state.due, "2026-03-06".parse().ok());
+        assert!(tasks[0].modified.is_some());
+        assert_eq!(tasks[1].id, "456");
+        assert!(tasks[1].state.completed);
+
+        let (items, next) = todoist_page(serde_json::json!([{"id": "1", "content": "x"}]));
+        assert_eq!(items.len(), 1);
+        assert!(next.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_two_way_sync() {
+        let temp = TempDir::new().unwrap();
+        let db = Arc::new(KnowledgeDb::new(temp.path().join("test.db")).unwrap());
+        let backend = Arc::new(MemoryBackend::default());
+        let sync = TaskSync::new(db.clone(), Box::new(backend.clone()));
+        let now = Utc::now();
+
+        let task = db
+            .insert_entity(
+                "Send the doc",
+                "task",
+                Some(serde_json::json!({"status": "pending", "due_date": "2026-03-06"})),
+            )
+            .await
+            .unwrap();
+        let done = db
+            .insert_entity(
+                "Old task",
+                "task",
+                Some(serde_json::json!({"status": "completed"})),
+            )
+            .await
+            .unwrap();
+        let goal = db
+            .insert_goal("Run a 10k", 3, 3600, None, None, "user")
+            .await
+            .unwrap();
+        backend.tasks.lock().unwrap().push(RemoteTask {
+            id: "phone".to_string(),
+            state: TaskState {
+                title: "Pick up dry cleaning".to_string(),
+                completed: false,
+                due: None,
+            },
+            modified: Some(now),
+        });
+
+        // First sync exports open tasks and goals, imports the remote item
+        let report = sync.sync_once(now).await.unwrap();
+        assert_eq!((report.exported, report.imported), (2, 1));
+        let remote = backend.list().await.unwrap();
+        assert_eq!(remote.len(), 3);
+        assert!(!remote.iter().any(|t| t.state.title == "Old task"));
+        let doc_id = remote
+            .iter()
+            .find(|t| t.state.title == "Send the doc")
+            .unwrap()
+            .id
+            .clone();
+        assert_eq!(
+            backend.get(&doc_id).due,
+            "2026-03-06".parse::<NaiveDate>().ok()
+        );
+        let imported = db
+            .search_entities("Pick up dry cleaning", Some("task"))
+            .await
+            .unwrap();
+        assert_eq!(imported[0].metadata.as_ref().unwrap()["source"], "memory");
+
+        // Nothing changed, nothing to do
+        assert!(sync.sync_once(now).await.unwrap().is_empty());
+
+        // Completing remotely closes the Meepo task
+        backend.edit(&doc_id, now, |s| s.completed = true);
+        let report = sync.sync_once(now).await.unwrap();
+        assert_eq!(report.pulled, 1);
+        assert_eq!(task_status(&db, &task).await, "completed");
+
+        // Completing a goal in Meepo completes the reminder
+        db.update_goal_status(&goal, "completed").await.unwrap();
+        let report = sync.sync_once(now).await.unwrap();
+        assert_eq!(report.pushed, 1);
+        let goal_remote = backend
+            .list()
+            .await
+            .unwrap()
+            .into_iter()
+            .find(|t| t.state.title == "Run a 10k")
+            .unwrap();
+        assert!(goal_remote.state.completed);
+
+        // Both sides changed: the later edit wins
+        let phone_task = imported[0].id.clone();
+        let mut metadata = imported[0].metadata.clone().unwrap();
+        metadata["due_date"] = serde_json::json!("2026-04-01");
+        metadata["updated_at"] = serde_json::json!(now.to_rfc3339());
+        db.update_entity_metadata(&phone_task, metadata)
+            .await
+            .unwrap();
+        backend.edit("phone", now + chrono::Duration::minutes(5), |s| {
+            s.due = "2026-04-02".parse().ok()
+        });
+        let report = sync.sync_once(now).await.unwrap();
+        assert_eq!((report.conflicts, report.pulled), (1, 1));
+        let entity = db.get_entity(&phone_task).await.unwrap().unwrap();
+        assert_eq!(entity.metadata.unwrap()["due_date"], "2026-04-02");
+
+        // A remote item that disappears counts as done
+        backend.tasks.lock().unwrap().retain(|t| t.id != "phone");
+        sync.sync_once(now).await.unwrap();
+        assert_eq!(task_status(&db, &phone_task).await, "completed");
+        assert_eq!(task_status(&db, &done).await, "completed");
+        assert!(
+            sync.links()
+                .await
+                .unwrap()
+                .iter()
+                .all(|(k, _)| k != &format!("task:{}", phone_task))
+        );
+    }
+}
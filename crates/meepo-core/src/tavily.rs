@@ -105,6 +105,7 @@ impl TavilyClient {
 
     /// Search the web using the Tavily search API
     pub async fn search(&self, query: &str, max_results: usize) -> Result<SearchResponse> {
+        crate::network::ensure_online("web search")?;
         let max_results = max_results.min(10);
 
         let request = SearchRequest {
@@ -150,6 +151,7 @@ impl TavilyClient {
 
     /// Extract content from a URL using the Tavily extract API
     pub async fn extract(&self, url: &str) -> Result<String> {
+        crate::network::ensure_online("page extraction")?;
         let request = ExtractRequest {
             api_key: self.api_key.clone(),
             urls: vec![url.to_string()],
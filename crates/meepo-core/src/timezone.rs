@@ -0,0 +1,118 @@
+//! User timezone and locale handling
+//!
+//! All user-facing scheduling (daily plan, digests, quiet hours, cron
+//! watchers) runs in the user's timezone rather than UTC. The timezone is
+//! configured once at startup (`timezone = "auto"` detects it from the OS)
+//! and read from here by the agent's system prompt and datetime tool.
+
+use chrono::{DateTime, Utc};
+use chrono_tz::Tz;
+use std::sync::OnceLock;
+use tracing::{debug, warn};
+
+static USER_TIMEZONE: OnceLock<Tz> = OnceLock::new();
+
+/// Detect the system timezone from the OS, if it maps to an IANA name
+pub fn detect() -> Option<Tz> {
+    let name = iana_time_zone::get_timezone().ok()?;
+    name.parse::<Tz>().ok()
+}
+
+/// Resolve a timezone setting: an IANA name (e.g. "America/New_York"),
+/// or "auto"/empty to detect from the OS. Falls back to UTC.
+pub fn resolve(setting: &str) -> Tz {
+    let setting = setting.trim();
+    if setting.is_empty() || setting.eq_ignore_ascii_case("auto") {
+        return detect().unwrap_or_else(|| {
+            warn!("Could not detect system timezone, using UTC");
+            Tz::UTC
+        });
+    }
+    setting.parse::<Tz>().unwrap_or_else(|_| {
+        warn!("Unknown timezone '{}', using UTC", setting);
+        Tz::UTC
+    })
+}
+
+/// Set the process-wide user timezone. Only the first call takes effect.
+pub fn set_user_timezone(tz: Tz) {
+    if USER_TIMEZONE.set(tz).is_ok() {
+        debug!("User timezone set to {}", tz);
+    }
+}
+
+/// The user's timezone (auto-detected if never configured)
+pub fn user_timezone() -> Tz {
+    *USER_TIMEZONE.get_or_init(|| detect().unwrap_or(Tz::UTC))
+}
+
+/// Current time in the user's timezone
+pub fn now() -> DateTime<Tz> {
+    Utc::now().with_timezone(&user_timezone())
+}
+
+/// strftime pattern for a full date and time in the given locale
+///
+/// Covers date order and 12/24-hour clock conventions for common locales;
+/// anything else gets an unambiguous ISO-style format.
+pub fn datetime_format(locale: &str) -> &'static str {
+    let locale = locale.replace('_', "-").to_lowercase();
+    match locale.as_str() {
+        "en-us" | "en-ca" | "en-ph" => "%A, %B %-d, %Y %-I:%M %p",
+        "en-au" | "en-nz" | "en-in" => "%A, %-d %B %Y %-I:%M %p",
+        "en-gb" | "en-ie" | "en" => "%A %-d %B %Y %H:%M",
+        l if l.starts_with("de") => "%A, %-d. %B %Y %H:%M",
+        l if l.starts_with("fr") || l.starts_with("es") || l.starts_with("it") => {
+            "%A %-d %B %Y %H:%M"
+        }
+        l if l.starts_with("ja") || l.starts_with("zh") || l.starts_with("ko") => {
+            "%Y-%m-%d (%a) %H:%M"
+        }
+        _ => "%A %Y-%m-%d %H:%M",
+    }
+}
+
+/// Format a time for display in the given locale, including the zone abbreviation
+pub fn format_local(time: &DateTime<Tz>, locale: &str) -> String {
+    format!(
+        "{} {}",
+        time.format(datetime_format(locale)),
+        time.format("%Z")
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_resolve_named() {
+        assert_eq!(resolve("America/New_York"), Tz::America__New_York);
+        assert_eq!(resolve("Europe/Berlin"), Tz::Europe__Berlin);
+    }
+
+    #[test]
+    fn test_resolve_invalid_falls_back_to_utc() {
+        assert_eq!(resolve("Mars/Olympus_Mons"), Tz::UTC);
+    }
+
+    #[test]
+    fn test_resolve_auto_does_not_panic() {
+        let _ = resolve("auto");
+        let _ = resolve("");
+    }
+
+    #[test]
+    fn test_format_local() {
+        let t = Tz::America__New_York
+            .with_ymd_and_hms(2025, 3, 14, 19, 5, 0)
+            .unwrap();
+        assert_eq!(
+            format_local(&t, "en-US"),
+            "Friday, March 14, 2025 7:05 PM EDT"
+        );
+        assert_eq!(format_local(&t, "en_GB"), "Friday 14 March 2025 19:05 EDT");
+        assert_eq!(format_local(&t, "sv-SE"), "Friday 2025-03-14 19:05 EDT");
+    }
+}
@@ -0,0 +1,416 @@
+//! Tool cost model
+//!
+//! Each tool declares a rough cost and latency class (most are free and
+//! instant). The model shows non-trivial classes next to tool descriptions
+//! and keeps a running spend per conversation. Once a conversation is over
+//! its budget, medium- and high-cost tools — web search bursts, coding
+//! agents, delegation — only run when the call carries a `justification`,
+//! which breaks runaway tool spirals without blocking work that matters.
+//! A conversation's spend resets after it has been idle for a while.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use anyhow::{Result, anyhow};
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tracing::{debug, info};
+
+use crate::api::ToolDefinition;
+use crate::tools::{ToolExecutor, ToolRegistry};
+
+/// Input field a call uses to justify an expensive tool
+pub const JUSTIFICATION_FIELD: &str = "justification";
+
+/// What a call costs, in money or shared resources
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CostClass {
+    #[default]
+    Free,
+    Low,
+    Medium,
+    High,
+}
+
+impl CostClass {
+    /// Budget units one call spends
+    pub fn units(self) -> u32 {
+        match self {
+            Self::Free => 0,
+            Self::Low => 1,
+            Self::Medium => 3,
+            Self::High => 10,
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Free => "free",
+            Self::Low => "low",
+            Self::Medium => "medium",
+            Self::High => "high",
+        }
+    }
+}
+
+/// How long a call usually takes
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LatencyClass {
+    #[default]
+    Instant,
+    Seconds,
+    Minutes,
+}
+
+impl LatencyClass {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Instant => "instant",
+            Self::Seconds => "seconds",
+            Self::Minutes => "minutes",
+        }
+    }
+}
+
+/// A tool's declared cost and latency
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ToolCost {
+    #[serde(default)]
+    pub cost: CostClass,
+    #[serde(default)]
+    pub latency: LatencyClass,
+}
+
+impl ToolCost {
+    pub const fn new(cost: CostClass, latency: LatencyClass) -> Self {
+        Self { cost, latency }
+    }
+
+    /// Whether a call needs a justification once over budget
+    pub fn is_expensive(&self) -> bool {
+        self.cost >= CostClass::Medium
+    }
+
+    /// Hint appended to the tool's description; empty for free, instant tools
+    pub fn hint(&self) -> String {
+        if *self == Self::default() {
+            return String::new();
+        }
+        format!(
+            "[cost: {}, latency: {}]",
+            self.cost.as_str(),
+            self.latency.as_str()
+        )
+    }
+}
+
+/// Budget and per-tool overrides
+#[derive(Debug, Clone)]
+pub struct ToolCostConfig {
+    /// Units a conversation may spend before expensive tools need a reason
+    pub budget: u32,
+    /// A conversation's spend resets after this long without tool calls
+    pub idle_reset: Duration,
+    /// Costs for tools that don't declare one (e.g. MCP tools), or to
+    /// replace what a tool declares
+    pub overrides: HashMap<String, ToolCost>,
+}
+
+impl Default for ToolCostConfig {
+    fn default() -> Self {
+        Self {
+            budget: 30,
+            idle_reset: Duration::from_secs(30 * 60),
+            overrides: HashMap::new(),
+        }
+    }
+}
+
+/// Spend in one conversation
+struct Spend {
+    units: u32,
+    last: Instant,
+}
+
+/// Tool costs and per-conversation spend
+pub struct ToolCostModel {
+    costs: HashMap<String, ToolCost>,
+    config: ToolCostConfig,
+    spend: Mutex<HashMap<String, Spend>>,
+}
+
+impl ToolCostModel {
+    /// Costs declared by the registry's tools, with the config's overrides
+    pub fn new(registry: &ToolRegistry, config: ToolCostConfig) -> Self {
+        let mut costs = registry.costs();
+        costs.extend(config.overrides.clone());
+        Self {
+            costs,
+            config,
+            spend: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn cost(&self, tool: &str) -> ToolCost {
+        self.costs.get(tool).copied().unwrap_or_default()
+    }
+
+    /// Units the conversation has spent since it was last idle
+    pub fn spent(&self, conversation: &str) -> u32 {
+        let spend = self.spend.lock().unwrap_or_else(|e| e.into_inner());
+        spend
+            .get(conversation)
+            .filter(|s| s.last.elapsed() < self.config.idle_reset)
+            .map_or(0, |s| s.units)
+    }
+
+    fn over_budget(&self, conversation: &str, cost: ToolCost) -> bool {
+        self.spent(conversation) + cost.cost.units() > self.config.budget
+    }
+
+    /// Add cost hints to tool descriptions, and a `justification` field to
+    /// expensive tools once the conversation is over budget
+    pub fn annotate(&self, conversation: &str, tools: &mut [ToolDefinition]) {
+        let spent = self.spent(conversation);
+        for tool in tools.iter_mut() {
+            let cost = self.cost(&tool.name);
+            let hint = cost.hint();
+            if hint.is_empty() {
+                continue;
+            }
+            tool.description = format!("{} {}", tool.description, hint);
+            if cost.is_expensive() && spent + cost.cost.units() > self.config.budget {
+                tool.description.push_str(&format!(
+                    " This conversation has used its tool budget ({} of {} units): \
+                     only call this if it's needed, and say why in `{}`.",
+                    spent, self.config.budget, JUSTIFICATION_FIELD
+                ));
+                if let Some(properties) = tool
+                    .input_schema
+                    .get_mut("properties")
+                    .and_then(Value::as_object_mut)
+                {
+                    properties.insert(
+                        JUSTIFICATION_FIELD.to_string(),
+                        serde_json::json!({
+                            "type": "string",
+                            "description": "Why this call is worth its cost"
+                        }),
+                    );
+                }
+            }
+        }
+    }
+
+    /// Check a call against the budget, returning the input to run it with
+    /// (without the justification field)
+    pub fn check(&self, conversation: &str, tool: &str, mut input: Value) -> Result<Value> {
+        let cost = self.cost(tool);
+        if !cost.is_expensive() {
+            return Ok(input);
+        }
+        let justification = input
+            .as_object_mut()
+            .and_then(|o| o.remove(JUSTIFICATION_FIELD))
+            .and_then(|v| v.as_str().map(str::trim).map(str::to_string))
+            .filter(|j| !j.is_empty());
+        if !self.over_budget(conversation, cost) {
+            return Ok(input);
+        }
+        match justification {
+            Some(reason) => {
+                info!(
+                    "Over-budget call to {} in {} ({} units spent): {}",
+                    tool,
+                    conversation,
+                    self.spent(conversation),
+                    reason
+                );
+                Ok(input)
+            }
+            None => Err(anyhow!(
+                "'{}' is a {}-cost tool and this conversation has used its tool budget \
+                 ({} of {} units). Use what you already have, or call it again with a \
+                 `{}` explaining why it's needed.",
+                tool,
+                cost.cost.as_str(),
+                self.spent(conversation),
+                self.config.budget,
+                JUSTIFICATION_FIELD
+            )),
+        }
+    }
+
+    /// Charge a call to the conversation
+    pub fn record(&self, conversation: &str, tool: &str) {
+        let units = self.cost(tool).cost.units();
+        if units == 0 {
+            return;
+        }
+        let mut spend = self.spend.lock().unwrap_or_else(|e| e.into_inner());
+        spend.retain(|_, s| s.last.elapsed() < self.config.idle_reset);
+        let entry = spend.entry(conversation.to_string()).or_insert(Spend {
+            units: 0,
+            last: Instant::now(),
+        });
+        entry.units += units;
+        entry.last = Instant::now();
+        debug!(
+            "Tool {} cost {} units ({} in {})",
+            tool, units, entry.units, conversation
+        );
+    }
+}
+
+/// Tool executor that enforces the cost budget for one conversation
+pub struct CostedToolExecutor {
+    inner: Arc<dyn ToolExecutor>,
+    model: Arc<ToolCostModel>,
+    conversation: String,
+}
+
+impl CostedToolExecutor {
+    pub fn new(
+        inner: Arc<dyn ToolExecutor>,
+        model: Arc<ToolCostModel>,
+        conversation: impl Into<String>,
+    ) -> Self {
+        Self {
+            inner,
+            model,
+            conversation: conversation.into(),
+        }
+    }
+}
+
+#[async_trait]
+impl ToolExecutor for CostedToolExecutor {
+    async fn execute(&self, tool_name: &str, input: Value) -> Result<String> {
+        let input = self.model.check(&self.conversation, tool_name, input)?;
+        self.model.record(&self.conversation, tool_name);
+        self.inner.execute(tool_name, input).await
+    }
+
+    fn list_tools(&self) -> Vec<ToolDefinition> {
+        let mut tools = self.inner.list_tools();
+        self.model.annotate(&self.conversation, &mut tools);
+        tools
+    }
+
+    fn reads_personal_knowledge(&self, tool_name: &str) -> bool {
+        self.inner.reads_personal_knowledge(tool_name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tools::ToolHandler;
+
+    struct Search;
+
+    #[async_trait]
+    impl ToolHandler for Search {
+        fn name(&self) -> &str {
+            "web_search"
+        }
+
+        fn description(&self) -> &str {
+            "Search the web"
+        }
+
+        fn input_schema(&self) -> Value {
+            crate::tools::json_schema(serde_json::json!({"query": {"type": "string"}}), vec![])
+        }
+
+        fn cost(&self) -> ToolCost {
+            ToolCost::new(CostClass::Medium, LatencyClass::Seconds)
+        }
+
+        async fn execute(&self, input: Value) -> Result<String> {
+            Ok(input.to_string())
+        }
+    }
+
+    fn model(budget: u32) -> Arc<ToolCostModel> {
+        let mut registry = ToolRegistry::new();
+        registry.register(Arc::new(Search));
+        let config = ToolCostConfig {
+            budget,
+            overrides: HashMap::from([(
+                "mcp_render".to_string(),
+                ToolCost::new(CostClass::High, LatencyClass::Minutes),
+            )]),
+            ..Default::default()
+        };
+        Arc::new(ToolCostModel::new(&registry, config))
+    }
+
+    #[test]
+    fn test_costs_and_annotations() {
+        let model = model(3);
+        assert_eq!(model.cost("web_search").cost, CostClass::Medium);
+        assert_eq!(model.cost("mcp_render").latency, LatencyClass::Minutes);
+        assert_eq!(model.cost("recall"), ToolCost::default());
+
+        let definition = ToolDefinition {
+            name: "web_search".to_string(),
+            description: "Search the web".to_string(),
+            input_schema: Search.input_schema(),
+        };
+        let mut tools = vec![definition.clone()];
+        model.annotate("slack:alice", &mut tools);
+        assert_eq!(
+            tools[0].description,
+            "Search the web [cost: medium, latency: seconds]"
+        );
+        assert!(tools[0].input_schema["properties"]["justification"].is_null());
+
+        model.record("slack:alice", "web_search");
+        let mut tools = vec![definition];
+        model.annotate("slack:alice", &mut tools);
+        assert!(
+            tools[0]
+                .description
+                .contains("used its tool budget (3 of 3")
+        );
+        assert!(tools[0].input_schema["properties"]["justification"].is_object());
+    }
+
+    #[tokio::test]
+    async fn test_budget_requires_justification() {
+        let model = model(3);
+        let mut registry = ToolRegistry::new();
+        registry.register(Arc::new(Search));
+        let executor = CostedToolExecutor::new(Arc::new(registry), model.clone(), "slack:alice");
+
+        executor
+            .execute("web_search", serde_json::json!({"query": "a"}))
+            .await
+            .unwrap();
+        assert_eq!(model.spent("slack:alice"), 3);
+        assert_eq!(model.spent("slack:bob"), 0);
+
+        let err = executor
+            .execute("web_search", serde_json::json!({"query": "b"}))
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("justification"));
+        assert_eq!(model.spent("slack:alice"), 3);
+
+        // The justification is stripped before the tool sees the input
+        let output = executor
+            .execute(
+                "web_search",
+                serde_json::json!({"query": "c", "justification": "user asked for sources"}),
+            )
+            .await
+            .unwrap();
+        assert_eq!(output, r#"{"query":"c"}"#);
+        assert_eq!(model.spent("slack:alice"), 6);
+    }
+}
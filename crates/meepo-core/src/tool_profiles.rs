@@ -0,0 +1,134 @@
+//! Tool profiles for external consumers
+//!
+//! When Meepo serves tools to an MCP host or runs tasks for an A2A peer,
+//! the consumer is given a named profile instead of every tool: a
+//! description of what it's for, a list of tool names (`*` for all,
+//! `browser_*` for a prefix) and the riskiest kind of tool it may use.
+//! Exported schemas carry read-only / destructive annotations derived from
+//! the same risk classification the autonomy loop uses, so a host can tell
+//! what a tool does before calling it.
+
+use serde::{Deserialize, Serialize};
+
+use crate::autonomy::action_log::{ActionRisk, classify_tool};
+
+/// Tool list entry that allows every tool
+pub const ALL_TOOLS: &str = "*";
+
+/// A named set of tools offered to an external consumer
+#[derive(Debug, Clone, PartialEq)]
+pub struct ToolProfile {
+    pub name: String,
+    /// What the profile is for, shown to consumers
+    pub description: String,
+    /// Tool names or `prefix_*` patterns; `"*"` for all
+    pub tools: Vec<String>,
+    /// Riskiest tool class allowed
+    pub max_risk: ActionRisk,
+}
+
+impl ToolProfile {
+    /// Whether a consumer using this profile may see and call `tool`
+    pub fn allows(&self, tool: &str) -> bool {
+        classify_tool(tool) <= self.max_risk
+            && self
+                .tools
+                .iter()
+                .any(|pattern| match pattern.strip_suffix('*') {
+                    Some(prefix) => tool.starts_with(prefix),
+                    None => pattern == tool,
+                })
+    }
+
+    /// Name and description, as advertised to consumers
+    pub fn summary(&self) -> ProfileSummary {
+        ProfileSummary {
+            name: self.name.clone(),
+            description: self.description.clone(),
+            max_risk: self.max_risk,
+        }
+    }
+}
+
+/// A profile as listed in agent cards and tool listings
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProfileSummary {
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    #[serde(default = "default_max_risk")]
+    pub max_risk: ActionRisk,
+}
+
+fn default_max_risk() -> ActionRisk {
+    ActionRisk::Destructive
+}
+
+/// Behaviour hints attached to an exported tool, named as in MCP's
+/// `ToolAnnotations`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ToolAnnotations {
+    /// The tool doesn't change anything
+    #[serde(default)]
+    pub read_only_hint: bool,
+    /// The tool may do something that can't be undone
+    #[serde(default)]
+    pub destructive_hint: bool,
+    /// The tool reaches people or services outside this machine
+    #[serde(default)]
+    pub open_world_hint: bool,
+}
+
+/// Annotations for a tool, from its risk class. Unknown tools (skills,
+/// plugins, tools from MCP servers) count as destructive.
+pub fn annotations(tool: &str) -> ToolAnnotations {
+    let risk = classify_tool(tool);
+    ToolAnnotations {
+        read_only_hint: risk == ActionRisk::ReadOnly,
+        destructive_hint: risk == ActionRisk::Destructive,
+        open_world_hint: risk == ActionRisk::External,
+    }
+}
+
+/// Look up a profile by name
+pub fn find<'a>(profiles: &'a [ToolProfile], name: &str) -> Option<&'a ToolProfile> {
+    profiles.iter().find(|p| p.name == name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn profile(tools: &[&str], max_risk: ActionRisk) -> ToolProfile {
+        ToolProfile {
+            name: "research".to_string(),
+            description: "Look things up".to_string(),
+            tools: tools.iter().map(|t| t.to_string()).collect(),
+            max_risk,
+        }
+    }
+
+    #[test]
+    fn test_profile_patterns_and_risk() {
+        let read_only = profile(&[ALL_TOOLS], ActionRisk::ReadOnly);
+        assert!(read_only.allows("recall"));
+        assert!(!read_only.allows("write_file"));
+        assert!(!read_only.allows("some_plugin_tool"));
+
+        let browser = profile(&["browser_*", "send_email"], ActionRisk::External);
+        assert!(browser.allows("browser_list_tabs"));
+        assert!(!browser.allows("browser_execute_js"));
+        assert!(browser.allows("send_email"));
+        assert!(!browser.allows("recall"));
+    }
+
+    #[test]
+    fn test_annotations_serialize_as_mcp_hints() {
+        let json = serde_json::to_value(annotations("read_file")).unwrap();
+        assert_eq!(json["readOnlyHint"], true);
+        assert_eq!(json["destructiveHint"], false);
+        assert!(annotations("run_command").destructive_hint);
+        assert!(annotations("send_email").open_world_hint);
+    }
+}
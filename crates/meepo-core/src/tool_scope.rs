@@ -0,0 +1,70 @@
+//! Per-turn tool scopes
+//!
+//! Work handed to the agent on someone else's behalf (a task from an A2A
+//! peer) runs inside [`scope`] with the tools that caller may use. The agent
+//! leaves every other tool out of the turn and refuses to run it. Outside a
+//! scope every tool is available.
+
+use std::collections::HashSet;
+use std::future::Future;
+use std::sync::Arc;
+
+use anyhow::{Result, anyhow};
+use async_trait::async_trait;
+use serde_json::Value;
+use tracing::warn;
+
+use crate::api::ToolDefinition;
+use crate::tools::ToolExecutor;
+
+tokio::task_local! {
+    static ALLOWED: Arc<HashSet<String>>;
+}
+
+/// Run a future with only `tools` available to the agent
+pub async fn scope<F: Future>(tools: HashSet<String>, fut: F) -> F::Output {
+    ALLOWED.scope(Arc::new(tools), fut).await
+}
+
+/// The tools allowed in the current scope, if any
+pub fn current() -> Option<Arc<HashSet<String>>> {
+    ALLOWED.try_with(Arc::clone).ok()
+}
+
+/// Tool executor that only runs tools in a scope's allow list
+pub struct ScopedToolExecutor {
+    inner: Arc<dyn ToolExecutor>,
+    allowed: Arc<HashSet<String>>,
+}
+
+impl ScopedToolExecutor {
+    pub fn new(inner: Arc<dyn ToolExecutor>, allowed: Arc<HashSet<String>>) -> Self {
+        Self { inner, allowed }
+    }
+}
+
+#[async_trait]
+impl ToolExecutor for ScopedToolExecutor {
+    async fn execute(&self, tool_name: &str, input: Value) -> Result<String> {
+        if !self.allowed.contains(tool_name) {
+            warn!("Blocked '{}' outside the caller's tool scope", tool_name);
+            return Err(anyhow!(
+                "Tool '{}' is not available to this caller",
+                tool_name
+            ));
+        }
+        self.inner.execute(tool_name, input).await
+    }
+
+    fn list_tools(&self) -> Vec<ToolDefinition> {
+        self.inner
+            .list_tools()
+            .into_iter()
+            .filter(|t| self.allowed.contains(&t.name))
+            .collect()
+    }
+
+    fn reads_personal_knowledge(&self, tool_name: &str) -> bool {
+        self.inner.reads_personal_knowledge(tool_name)
+    }
+}
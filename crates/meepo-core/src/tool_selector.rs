@@ -100,6 +100,26 @@ pub async fn select_tools_tracked(
     }
 }
 
+/// Leave tools in `unused` out of the default list unless the query names
+/// them ("read_pdf" or "read pdf") or they're always included. Returns how
+/// many were left out.
+pub fn drop_unused(
+    query: &str,
+    tools: &mut Vec<ToolDefinition>,
+    unused: &[String],
+    config: &ToolSelectorConfig,
+) -> usize {
+    let lower = query.to_lowercase();
+    let before = tools.len();
+    tools.retain(|t| {
+        !unused.contains(&t.name)
+            || config.always_include.contains(&t.name)
+            || lower.contains(&t.name)
+            || lower.contains(&t.name.replace('_', " "))
+    });
+    before - tools.len()
+}
+
 /// Heuristic tool selection based on keyword matching
 fn select_heuristic(
     query: &str,
@@ -114,10 +134,12 @@ fn select_heuristic(
     if lower.contains("email") || lower.contains("mail") {
         relevant_prefixes.push("read_email");
         relevant_prefixes.push("send_email");
+        relevant_prefixes.push("email_");
     }
     if lower.contains("calendar") || lower.contains("schedule") || lower.contains("meeting") {
         relevant_prefixes.push("read_calendar");
         relevant_prefixes.push("create_calendar");
+        relevant_prefixes.push("meeting_prep");
     }
     if lower.contains("remind") {
         relevant_prefixes.push("list_reminder");
@@ -177,6 +199,31 @@ fn select_heuristic(
         relevant_prefixes.push("click_element");
         relevant_prefixes.push("type_text");
     }
+    if lower.contains("focus")
+        || lower.contains("do not disturb")
+        || lower.contains("deep work")
+        || lower.contains("session")
+    {
+        relevant_prefixes.push("get_focus");
+        relevant_prefixes.push("set_focus");
+    }
+    if lower.contains("screen time")
+        || lower.contains("time did i spend")
+        || lower.contains("time i spent")
+        || lower.contains("app usage")
+    {
+        relevant_prefixes.push("app_usage");
+    }
+    if lower.contains("sync") || lower.contains("backup") || lower.contains("back up") {
+        relevant_prefixes.push("sync_now");
+    }
+    if lower.contains("onboard") || lower.contains("get to know") || lower.contains("about me") {
+        relevant_prefixes.push("complete_onboarding");
+    }
+    if lower.contains("rule") || lower.contains("automat") || lower.contains("whenever") {
+        relevant_prefixes.push("create_rule");
+        relevant_prefixes.push("list_rules");
+    }
     if lower.contains("contact") {
         relevant_prefixes.push("search_contacts");
     }
@@ -195,15 +242,27 @@ fn select_heuristic(
         || lower.contains("memory")
         || lower.contains("knowledge")
         || lower.contains("know")
+        || lower.contains("everything about")
+        || lower.contains("topic")
     {
         relevant_prefixes.push("remember");
         relevant_prefixes.push("recall");
         relevant_prefixes.push("search_knowledge");
         relevant_prefixes.push("link_entities");
+        relevant_prefixes.push("list_tags");
+    }
+    if lower.contains("alias") || lower.contains("nickname") || lower.contains("abbreviat") {
+        relevant_prefixes.push("manage_aliases");
+    }
+    if lower.contains("gap") || lower.contains("research") || lower.contains("don't know") {
+        relevant_prefixes.push("list_knowledge_gaps");
     }
     if lower.contains("ingest") || lower.contains("index") || lower.contains("document") {
         relevant_prefixes.push("ingest_");
     }
+    if lower.contains("dossier") || lower.contains("profile") || lower.contains("brief me") {
+        relevant_prefixes.push("generate_dossier");
+    }
 
     // Collect matching tools + always-include tools
     let mut selected: Vec<ToolDefinition> = all_tools
@@ -325,6 +384,25 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_drop_unused_keeps_named_and_core_tools() {
+        let config = ToolSelectorConfig::default();
+        let unused: Vec<String> = ["read_pdf", "recall", "web_search", "send_email"]
+            .map(String::from)
+            .to_vec();
+        let mut tools = vec![
+            make_tool("read_pdf", "Read a PDF"),
+            make_tool("recall", "Recall knowledge"),
+            make_tool("web_search", "Search the web"),
+            make_tool("send_email", "Send an email"),
+            make_tool("read_emails", "Read recent emails"),
+        ];
+        let dropped = drop_unused("Please read pdf report.pdf", &mut tools, &unused, &config);
+        assert_eq!(dropped, 2);
+        let names: Vec<&str> = tools.iter().map(|t| t.name.as_str()).collect();
+        assert_eq!(names, ["read_pdf", "recall", "read_emails"]);
+    }
+
     fn sample_tools() -> Vec<ToolDefinition> {
         vec![
             make_tool("read_emails", "Read recent emails"),
@@ -0,0 +1,444 @@
+//! Summarizing long tool outputs
+//!
+//! Full page text or a long command log can fill the context window in one
+//! tool call. Outputs longer than a threshold are condensed by a cheap model
+//! before the agent sees them, and the full text is kept as an artifact.
+//! The agent gets a `fetch_tool_output` tool to read the artifact in pages
+//! when the summary leaves out something it needs.
+
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use anyhow::{Result, anyhow};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde_json::Value;
+use tracing::{debug, warn};
+
+use crate::api::{ApiClient, ApiMessage, ContentBlock, MessageContent, ToolDefinition, Usage};
+use crate::providers::overrides::{self, RequestOverrides};
+use crate::tools::{ToolExecutor, json_schema};
+
+pub const FETCH_TOOL: &str = "fetch_tool_output";
+
+/// Configuration for tool output summaries
+#[derive(Debug, Clone)]
+pub struct ToolSummaryConfig {
+    pub enabled: bool,
+    /// Outputs longer than this many characters are summarized
+    pub threshold_chars: usize,
+    /// Model (or alias) for summaries
+    pub model: String,
+    /// Only this much of an output is sent to the summarizer
+    pub max_input_chars: usize,
+    /// Full outputs kept for `fetch_tool_output`; the oldest go first
+    pub max_artifacts: usize,
+    /// Characters returned per `fetch_tool_output` call
+    pub page_chars: usize,
+}
+
+impl Default for ToolSummaryConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            threshold_chars: 8000,
+            model: "haiku".to_string(),
+            max_input_chars: 100_000,
+            max_artifacts: 20,
+            page_chars: 8000,
+        }
+    }
+}
+
+/// The full output of one tool call
+#[derive(Debug, Clone)]
+pub struct ToolArtifact {
+    pub id: String,
+    pub tool: String,
+    pub content: String,
+    pub stored_at: DateTime<Utc>,
+}
+
+/// Full outputs of summarized tool calls, shared across turns
+#[derive(Debug)]
+pub struct ArtifactStore {
+    artifacts: Mutex<VecDeque<ToolArtifact>>,
+    capacity: usize,
+}
+
+impl ArtifactStore {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            artifacts: Mutex::new(VecDeque::new()),
+            capacity: capacity.max(1),
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, VecDeque<ToolArtifact>> {
+        self.artifacts.lock().unwrap_or_else(|p| p.into_inner())
+    }
+
+    /// Keep `content`, dropping the oldest artifact when full; returns its ID
+    pub fn store(&self, tool: &str, content: String) -> String {
+        let id = format!("out_{}", &uuid::Uuid::new_v4().simple().to_string()[..8]);
+        let mut artifacts = self.lock();
+        while artifacts.len() >= self.capacity {
+            artifacts.pop_front();
+        }
+        artifacts.push_back(ToolArtifact {
+            id: id.clone(),
+            tool: tool.to_string(),
+            content,
+            stored_at: Utc::now(),
+        });
+        id
+    }
+
+    pub fn get(&self, id: &str) -> Option<ToolArtifact> {
+        self.lock().iter().find(|a| a.id == id).cloned()
+    }
+
+    pub fn len(&self) -> usize {
+        self.lock().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.lock().is_empty()
+    }
+}
+
+/// Tool executor that summarizes long outputs and serves the full text
+/// through `fetch_tool_output`
+pub struct SummarizingToolExecutor {
+    inner: Arc<dyn ToolExecutor>,
+    api: ApiClient,
+    store: Arc<ArtifactStore>,
+    config: ToolSummaryConfig,
+    /// Tokens and time spent on summaries since the last [`Self::take_usage`]
+    usage: Mutex<Option<(Usage, Duration)>>,
+}
+
+impl SummarizingToolExecutor {
+    pub fn new(
+        inner: Arc<dyn ToolExecutor>,
+        api: ApiClient,
+        store: Arc<ArtifactStore>,
+        config: ToolSummaryConfig,
+    ) -> Self {
+        Self {
+            inner,
+            api,
+            store,
+            config,
+            usage: Mutex::new(None),
+        }
+    }
+
+    /// Token usage and time of the summaries made so far, resetting both
+    pub fn take_usage(&self) -> Option<(Usage, Duration)> {
+        self.usage.lock().unwrap_or_else(|p| p.into_inner()).take()
+    }
+
+    fn add_usage(&self, usage: &Usage, elapsed: Duration) {
+        let mut total = self.usage.lock().unwrap_or_else(|p| p.into_inner());
+        let (tokens, time) = total.get_or_insert((
+            Usage {
+                input_tokens: 0,
+                output_tokens: 0,
+            },
+            Duration::ZERO,
+        ));
+        tokens.input_tokens += usage.input_tokens;
+        tokens.output_tokens += usage.output_tokens;
+        *time += elapsed;
+    }
+
+    fn fetch(&self, input: &Value) -> Result<String> {
+        let id = input
+            .get("id")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| anyhow!("Missing 'id' parameter"))?;
+        let artifact = self.store.get(id.trim()).ok_or_else(|| {
+            anyhow!(
+                "No stored output with ID '{}'; only the last {} are kept",
+                id,
+                self.config.max_artifacts
+            )
+        })?;
+        let offset = input.get("offset").and_then(|v| v.as_u64()).unwrap_or(0) as usize;
+        let total = artifact.content.chars().count();
+        if offset >= total {
+            return Err(anyhow!(
+                "Offset {} is past the end of the output ({} chars)",
+                offset,
+                total
+            ));
+        }
+        let page: String = artifact
+            .content
+            .chars()
+            .skip(offset)
+            .take(self.config.page_chars)
+            .collect();
+        let end = offset + page.chars().count();
+        let mut result = format!(
+            "[{} output {}, chars {}–{} of {}]\n{}",
+            artifact.tool, artifact.id, offset, end, total, page
+        );
+        if end < total {
+            result.push_str(&format!(
+                "\n[More: call {} with offset {}]",
+                FETCH_TOOL, end
+            ));
+        }
+        Ok(result)
+    }
+
+    async fn condense(&self, tool_name: &str, output: &str) -> Result<(String, Usage)> {
+        let mut text: String = output.chars().take(self.config.max_input_chars).collect();
+        if text.len() < output.len() {
+            text.push_str("\n[…cut for length]");
+        }
+        let messages = vec![ApiMessage {
+            role: "user".to_string(),
+            content: MessageContent::Text(text),
+        }];
+        let system = format!(
+            "This is the output of the '{}' tool, too long to pass on whole. Condense it for \
+             an assistant into short bullet points. Keep every distinct fact, name, number, \
+             date, error message and URL; drop boilerplate, navigation and repetition. Reply \
+             with the bullet points only.",
+            tool_name
+        );
+        let response = overrides::scope(
+            RequestOverrides {
+                model: Some(overrides::resolve_alias(&self.config.model)),
+                ..Default::default()
+            },
+            self.api.chat(&messages, &[], &system),
+        )
+        .await?;
+        let summary: String = response
+            .content
+            .iter()
+            .filter_map(|b| match b {
+                ContentBlock::Text { text } => Some(text.as_str()),
+                _ => None,
+            })
+            .collect();
+        if summary.trim().is_empty() {
+            anyhow::bail!("empty summary");
+        }
+        Ok((summary, response.usage))
+    }
+}
+
+#[async_trait]
+impl ToolExecutor for SummarizingToolExecutor {
+    async fn execute(&self, tool_name: &str, input: Value) -> Result<String> {
+        if tool_name == FETCH_TOOL {
+            return self.fetch(&input);
+        }
+        let output = self.inner.execute(tool_name, input).await?;
+        let chars = output.chars().count();
+        if !self.config.enabled || chars <= self.config.threshold_chars {
+            return Ok(output);
+        }
+
+        let started = Instant::now();
+        let summary = self.condense(tool_name, &output).await;
+        let head: Option<String> = summary
+            .is_err()
+            .then(|| output.chars().take(self.config.threshold_chars).collect());
+        let id = self.store.store(tool_name, output);
+        match summary {
+            Ok((summary, usage)) => {
+                debug!(
+                    "Summarized {} output ({} chars) as {}",
+                    tool_name, chars, id
+                );
+                self.add_usage(&usage, started.elapsed());
+                Ok(format!(
+                    "[Summary of a {}-char output, full text stored as {}; call {} to read it]\n{}",
+                    chars,
+                    id,
+                    FETCH_TOOL,
+                    summary.trim()
+                ))
+            }
+            Err(e) => {
+                // Fall back to the start of the output so the turn can go on
+                warn!("Summarizing {} output failed: {:#}", tool_name, e);
+                Ok(format!(
+                    "{}\n[Output cut at {} of {} chars; full text stored as {}, call {} \
+                     with offset {} for the rest]",
+                    head.unwrap_or_default(),
+                    self.config.threshold_chars,
+                    chars,
+                    id,
+                    FETCH_TOOL,
+                    self.config.threshold_chars
+                ))
+            }
+        }
+    }
+
+    fn list_tools(&self) -> Vec<ToolDefinition> {
+        let mut tools = self.inner.list_tools();
+        tools.push(fetch_tool_definition());
+        tools
+    }
+
+    fn reads_personal_knowledge(&self, tool_name: &str) -> bool {
+        self.inner.reads_personal_knowledge(tool_name)
+    }
+}
+
+/// Definition of the `fetch_tool_output` tool
+pub fn fetch_tool_definition() -> ToolDefinition {
+    ToolDefinition {
+        name: FETCH_TOOL.to_string(),
+        description: "Read the full text of a long tool output that was summarized, a page at a \
+                      time. Use the ID given with the summary."
+            .to_string(),
+        input_schema: json_schema(
+            serde_json::json!({
+                "id": {"type": "string", "description": "Output ID, e.g. out_1a2b3c4d"},
+                "offset": {
+                    "type": "integer",
+                    "description": "Character to start from (default 0)"
+                }
+            }),
+            vec!["id"],
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::providers::types::{ChatResponseBlock, ChatUsage, StopReason};
+    use crate::providers::{ChatMessage, ChatResponse, LlmProvider, ModelRouter};
+    use crate::tools::{ToolHandler, ToolRegistry};
+
+    struct LongTool;
+
+    #[async_trait]
+    impl ToolHandler for LongTool {
+        fn name(&self) -> &str {
+            "read_page"
+        }
+        fn description(&self) -> &str {
+            "Returns a long page"
+        }
+        fn input_schema(&self) -> Value {
+            json_schema(serde_json::json!({}), vec![])
+        }
+        async fn execute(&self, input: Value) -> Result<String> {
+            let n = input.get("n").and_then(|v| v.as_u64()).unwrap_or(0) as usize;
+            Ok("x".repeat(n))
+        }
+    }
+
+    struct ScriptedProvider;
+
+    #[async_trait]
+    impl LlmProvider for ScriptedProvider {
+        fn provider_name(&self) -> &str {
+            "scripted"
+        }
+        fn model(&self) -> &str {
+            "scripted-1"
+        }
+        async fn chat(
+            &self,
+            _messages: &[ChatMessage],
+            _tools: &[ToolDefinition],
+            _system: &str,
+        ) -> Result<ChatResponse> {
+            Ok(ChatResponse {
+                blocks: vec![ChatResponseBlock::Text {
+                    text: "- a page of x".to_string(),
+                }],
+                stop_reason: StopReason::EndTurn,
+                usage: ChatUsage {
+                    input_tokens: 500,
+                    output_tokens: 8,
+                },
+            })
+        }
+    }
+
+    #[tokio::test]
+    async fn test_long_output_is_summarized_and_fetchable() {
+        let mut registry = ToolRegistry::new();
+        registry.register(Arc::new(LongTool));
+        let api = ApiClient::from_router(ModelRouter::single(Box::new(ScriptedProvider)));
+        let store = Arc::new(ArtifactStore::new(5));
+        let executor = SummarizingToolExecutor::new(
+            Arc::new(registry),
+            api,
+            store.clone(),
+            ToolSummaryConfig {
+                threshold_chars: 100,
+                page_chars: 150,
+                ..Default::default()
+            },
+        );
+        assert!(executor.list_tools().iter().any(|t| t.name == FETCH_TOOL));
+
+        // Short outputs pass through untouched
+        let short = executor
+            .execute("read_page", serde_json::json!({"n": 50}))
+            .await
+            .unwrap();
+        assert_eq!(short, "x".repeat(50));
+        assert!(store.is_empty());
+
+        let summary = executor
+            .execute("read_page", serde_json::json!({"n": 200}))
+            .await
+            .unwrap();
+        assert!(summary.contains("- a page of x"));
+        assert_eq!(store.len(), 1);
+        let (usage, _) = executor.take_usage().unwrap();
+        assert_eq!(usage.input_tokens, 500);
+        assert!(executor.take_usage().is_none());
+
+        let id = summary
+            .split_whitespace()
+            .find(|w| w.starts_with("out_"))
+            .unwrap()
+            .trim_end_matches(';');
+        let page = executor
+            .execute(FETCH_TOOL, serde_json::json!({"id": id}))
+            .await
+            .unwrap();
+        assert!(page.contains(&"x".repeat(150)));
+        assert!(page.contains("offset 150"));
+        let rest = executor
+            .execute(FETCH_TOOL, serde_json::json!({"id": id, "offset": 150}))
+            .await
+            .unwrap();
+        assert!(rest.contains("chars 150–200 of 200"));
+        assert!(!rest.contains("[More"));
+
+        assert!(
+            executor
+                .execute(FETCH_TOOL, serde_json::json!({"id": "out_missing"}))
+                .await
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_store_drops_oldest() {
+        let store = ArtifactStore::new(2);
+        let first = store.store("a", "one".to_string());
+        store.store("b", "two".to_string());
+        store.store("c", "three".to_string());
+        assert_eq!(store.len(), 2);
+        assert!(store.get(&first).is_none());
+    }
+}
@@ -0,0 +1,141 @@
+//! App-usage tool — lets the agent answer "how much time did I spend in X?"
+
+use anyhow::Result;
+use async_trait::async_trait;
+use meepo_knowledge::KnowledgeDb;
+use serde_json::Value;
+use std::sync::Arc;
+use tracing::debug;
+
+use super::{ToolHandler, json_schema};
+use crate::app_usage::{format_duration, period_range, total_seconds, usage_between};
+
+/// Tool that reports time spent in apps from tracked samples
+pub struct AppUsageTool {
+    db: Arc<KnowledgeDb>,
+}
+
+impl AppUsageTool {
+    pub fn new(db: Arc<KnowledgeDb>) -> Self {
+        Self { db }
+    }
+}
+
+#[async_trait]
+impl ToolHandler for AppUsageTool {
+    fn name(&self) -> &str {
+        "app_usage"
+    }
+
+    fn description(&self) -> &str {
+        "Report screen time: how long the user spent in each app over a period, from \
+         tracked frontmost-app samples. Optionally filter to one app (e.g. 'Xcode')."
+    }
+
+    fn input_schema(&self) -> Value {
+        json_schema(
+            serde_json::json!({
+                "period": {
+                    "type": "string",
+                    "description": "'today', 'yesterday', 'week', 'last_week', 'month', or a range like '2025-01-01:2025-01-31' (default: today)"
+                },
+                "app": {
+                    "type": "string",
+                    "description": "Only apps whose name contains this text"
+                },
+                "limit": {
+                    "type": "integer",
+                    "description": "Maximum apps to list (default: 10)"
+                }
+            }),
+            vec![],
+        )
+    }
+
+    async fn execute(&self, input: Value) -> Result<String> {
+        let period = input
+            .get("period")
+            .and_then(|v| v.as_str())
+            .unwrap_or("today");
+        let app = input
+            .get("app")
+            .and_then(|v| v.as_str())
+            .map(str::trim)
+            .filter(|a| !a.is_empty());
+        let limit = input
+            .get("limit")
+            .and_then(|v| v.as_u64())
+            .unwrap_or(10)
+            .clamp(1, 100) as usize;
+        debug!("Getting app usage for {} (app: {:?})", period, app);
+
+        let today = crate::timezone::now().date_naive();
+        let (start, end) = period_range(period, today)?;
+        let usage = usage_between(&self.db, start, end, app).await?;
+        let range = if start == end {
+            start.to_string()
+        } else {
+            format!("{} to {}", start, end)
+        };
+        if usage.is_empty() {
+            return Ok(match app {
+                Some(app) => format!("No time recorded in apps matching '{}' ({}).", app, range),
+                None => format!(
+                    "No app usage recorded ({}). Is app-usage tracking enabled?",
+                    range
+                ),
+            });
+        }
+
+        let mut output = format!(
+            "## App Usage ({})\n\nTotal: {}\n\n",
+            range,
+            format_duration(total_seconds(&usage))
+        );
+        for entry in usage.iter().take(limit) {
+            output.push_str(&format!(
+                "- **{}:** {} ({} day{})\n",
+                entry.app,
+                format_duration(entry.seconds),
+                entry.days,
+                if entry.days == 1 { "" } else { "s" }
+            ));
+        }
+        if usage.len() > limit {
+            output.push_str(&format!("- …and {} more apps\n", usage.len() - limit));
+        }
+        Ok(output)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_app_usage_report() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let db = Arc::new(KnowledgeDb::new(temp.path().join("test.db")).unwrap());
+        let today = crate::timezone::now().date_naive().to_string();
+        db.add_app_usage(&today, "Xcode", 3 * 3600).await.unwrap();
+        db.add_app_usage(&today, "Safari", 1200).await.unwrap();
+        let tool = AppUsageTool::new(db);
+
+        let all = tool.execute(serde_json::json!({})).await.unwrap();
+        assert!(all.contains("Total: 3h 20m"));
+        assert!(all.find("Xcode").unwrap() < all.find("Safari").unwrap());
+
+        let xcode = tool
+            .execute(serde_json::json!({"period": "week", "app": "xcode"}))
+            .await
+            .unwrap();
+        assert!(xcode.contains("**Xcode:** 3h 00m"));
+        assert!(!xcode.contains("Safari"));
+
+        let none = tool
+            .execute(serde_json::json!({"app": "Figma"}))
+            .await
+            .unwrap();
+        assert!(none.starts_with("No time recorded"));
+    }
+}
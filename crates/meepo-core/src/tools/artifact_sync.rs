@@ -0,0 +1,73 @@
+//! Artifact sync tool — run a workspace sync on demand
+
+use anyhow::Result;
+use async_trait::async_trait;
+use serde_json::Value;
+use std::sync::Arc;
+use tracing::debug;
+
+use super::{ToolHandler, json_schema};
+use crate::artifact_sync::ArtifactSync;
+
+/// Files listed per section before summarizing the rest
+const MAX_LISTED: usize = 20;
+
+/// Tool that syncs workspace artifacts with the configured S3/WebDAV target now
+pub struct SyncNowTool {
+    sync: Arc<ArtifactSync>,
+}
+
+impl SyncNowTool {
+    pub fn new(sync: Arc<ArtifactSync>) -> Self {
+        Self { sync }
+    }
+}
+
+#[async_trait]
+impl ToolHandler for SyncNowTool {
+    fn name(&self) -> &str {
+        "sync_now"
+    }
+
+    fn needs_network(&self) -> bool {
+        true
+    }
+
+    fn description(&self) -> &str {
+        "Sync workspace files, ingested documents and knowledge base backups with the \
+         configured S3/WebDAV target right away, instead of waiting for the sync schedule. \
+         Use when the user wants their other machines to see something now."
+    }
+
+    fn input_schema(&self) -> Value {
+        json_schema(serde_json::json!({}), vec![])
+    }
+
+    async fn execute(&self, _input: Value) -> Result<String> {
+        debug!("Syncing artifacts with {}", self.sync.target_name());
+        let report = self.sync.sync_once().await?;
+        let mut output = format!(
+            "Synced with {}: {}\n",
+            self.sync.target_name(),
+            report.summary()
+        );
+        for (label, keys) in [
+            ("Uploaded", &report.uploaded),
+            ("Downloaded", &report.downloaded),
+            ("Conflicts (newer copy kept)", &report.conflicts),
+            ("Errors", &report.errors),
+        ] {
+            if keys.is_empty() {
+                continue;
+            }
+            output.push_str(&format!("\n{}:\n", label));
+            for key in keys.iter().take(MAX_LISTED) {
+                output.push_str(&format!("- {}\n", key));
+            }
+            if keys.len() > MAX_LISTED {
+                output.push_str(&format!("- …and {} more\n", keys.len() - MAX_LISTED));
+            }
+        }
+        Ok(output)
+    }
+}
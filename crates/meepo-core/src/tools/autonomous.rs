@@ -5,9 +5,13 @@ use async_trait::async_trait;
 use serde_json::Value;
 use std::sync::Arc;
 use tokio::sync::mpsc;
-use tracing::debug;
+use tracing::{debug, warn};
 
 use super::{ToolHandler, json_schema};
+use crate::autonomy::user_model::UserModel;
+use crate::questions::{self, PendingQuestions};
+use crate::tool_cost::{CostClass, LatencyClass, ToolCost};
+use crate::types::{Button, ChannelType, Interactive, MessageKind, OutgoingMessage};
 use meepo_knowledge::KnowledgeDb;
 
 /// Commands for background task management
@@ -17,6 +21,8 @@ pub enum BackgroundTaskCommand {
         id: String,
         description: String,
         reply_channel: String,
+        /// Heavy work waits for the host to be idle before starting
+        heavy: bool,
     },
     SpawnCodingAgent {
         id: String,
@@ -51,7 +57,9 @@ impl ToolHandler for SpawnBackgroundTaskTool {
 
     fn description(&self) -> &str {
         "Spawn an autonomous background task (sub-agent) to work on something independently. \
-         The task runs in the background and results are reported to the specified channel when done."
+         The task runs in the background and results are reported to the specified channel when done. \
+         Mark heavy work (bulk ingestion, memory consolidation, large refactors) with heavy=true \
+         so it waits until the user isn't using the machine."
     }
 
     fn input_schema(&self) -> Value {
@@ -64,12 +72,20 @@ impl ToolHandler for SpawnBackgroundTaskTool {
                 "reply_channel": {
                     "type": "string",
                     "description": "Channel to report results to (e.g., 'discord', 'slack', 'imessage'). Defaults to 'internal'."
+                },
+                "heavy": {
+                    "type": "boolean",
+                    "description": "Defer until the machine is idle (default: false)"
                 }
             }),
             vec!["description"],
         )
     }
 
+    fn cost(&self) -> ToolCost {
+        ToolCost::new(CostClass::Medium, LatencyClass::Minutes)
+    }
+
     async fn execute(&self, input: Value) -> Result<String> {
         let description = input
             .get("description")
@@ -79,6 +95,10 @@ impl ToolHandler for SpawnBackgroundTaskTool {
             .get("reply_channel")
             .and_then(|v| v.as_str())
             .unwrap_or("internal");
+        let heavy = input
+            .get("heavy")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false);
 
         if description.len() > 10_000 {
             return Err(anyhow::anyhow!(
@@ -103,6 +123,7 @@ impl ToolHandler for SpawnBackgroundTaskTool {
                 id: task_id.clone(),
                 description: description.to_string(),
                 reply_channel: reply_channel.to_string(),
+                heavy,
             })
             .await
             .context("Failed to send background task command")?;
@@ -327,6 +348,197 @@ impl ToolHandler for StopTaskTool {
     }
 }
 
+// ─── ask_user ───────────────────────────────────────────────────────
+
+/// Default time a task waits for an answer before giving up
+const DEFAULT_ANSWER_TIMEOUT_MINS: u64 = 60;
+
+/// Longest a task may wait for an answer
+const MAX_ANSWER_TIMEOUT_MINS: u64 = 24 * 60;
+
+/// Most answer buttons offered with a question
+const MAX_ANSWER_OPTIONS: usize = 5;
+
+/// Ask the user a question from a background task and wait for the reply
+pub struct AskUserTool {
+    db: Arc<KnowledgeDb>,
+    questions: Arc<PendingQuestions>,
+    outgoing: mpsc::Sender<OutgoingMessage>,
+    default_channel: ChannelType,
+}
+
+impl AskUserTool {
+    pub fn new(
+        db: Arc<KnowledgeDb>,
+        questions: Arc<PendingQuestions>,
+        outgoing: mpsc::Sender<OutgoingMessage>,
+        default_channel: ChannelType,
+    ) -> Self {
+        Self {
+            db,
+            questions,
+            outgoing,
+            default_channel,
+        }
+    }
+
+    /// The channel the user is most active on, or the configured default
+    async fn preferred_channel(&self) -> ChannelType {
+        UserModel::new(self.db.clone())
+            .build_profile()
+            .await
+            .ok()
+            .and_then(|p| p.preferred_channel().map(ChannelType::from_string))
+            .filter(|c| *c != ChannelType::Internal)
+            .unwrap_or_else(|| self.default_channel.clone())
+    }
+}
+
+#[async_trait]
+impl ToolHandler for AskUserTool {
+    fn name(&self) -> &str {
+        "ask_user"
+    }
+
+    fn description(&self) -> &str {
+        "Ask the user a clarifying question while working on a background task, and wait for          their reply. Use this instead of guessing when a decision is ambiguous or risky. The          question goes to the user's preferred channel; the task pauses until they answer, then          this tool returns the answer. Only works inside background tasks — in a live          conversation, just ask in your reply. For approvals, pass options such as          Approve/Reject; they show up as buttons on Slack and Discord."
+    }
+
+    fn input_schema(&self) -> Value {
+        json_schema(
+            serde_json::json!({
+                "question": {
+                    "type": "string",
+                    "description": "The question to ask. Include options if there are a few obvious choices."
+                },
+                "options": {
+                    "type": "array",
+                    "items": {"type": "string"},
+                    "description": "Short answers to offer as buttons where the channel supports them, e.g. [\"Approve\", \"Reject\"] for an approval (max 5)"
+                },
+                "channel": {
+                    "type": "string",
+                    "description": "Channel to ask on (default: the user's preferred channel)"
+                },
+                "timeout_minutes": {
+                    "type": "integer",
+                    "description": "How long to wait for an answer (default: 60, max: 1440)"
+                }
+            }),
+            vec!["question"],
+        )
+    }
+
+    async fn execute(&self, input: Value) -> Result<String> {
+        let question = input
+            .get("question")
+            .and_then(|v| v.as_str())
+            .filter(|q| !q.trim().is_empty())
+            .ok_or_else(|| anyhow::anyhow!("Missing 'question' parameter"))?;
+        if question.len() > 2000 {
+            return Err(anyhow::anyhow!(
+                "Question too long ({} chars, max 2,000)",
+                question.len()
+            ));
+        }
+        let Some(task_id) = questions::current_task() else {
+            return Ok(
+                "Not running in a background task. Ask the user directly in your reply instead."
+                    .to_string(),
+            );
+        };
+        let channel = match input.get("channel").and_then(|v| v.as_str()) {
+            Some(c) => ChannelType::from_string(c),
+            None => self.preferred_channel().await,
+        };
+        let options: Vec<String> = input
+            .get("options")
+            .and_then(|v| v.as_array())
+            .map(|opts| {
+                opts.iter()
+                    .filter_map(|o| o.as_str())
+                    .map(str::trim)
+                    .filter(|o| !o.is_empty() && o.len() <= 75)
+                    .take(MAX_ANSWER_OPTIONS)
+                    .map(String::from)
+                    .collect()
+            })
+            .unwrap_or_default();
+        let timeout_mins = input
+            .get("timeout_minutes")
+            .and_then(|v| v.as_u64())
+            .unwrap_or(DEFAULT_ANSWER_TIMEOUT_MINS)
+            .clamp(1, MAX_ANSWER_TIMEOUT_MINS);
+
+        let (pending, answer_rx) = self
+            .questions
+            .ask(Some(task_id.clone()), question, channel.clone())
+            .await;
+        debug!(
+            "Task {} asking on {}: {}",
+            task_id, channel, pending.question
+        );
+
+        if let Err(e) = self
+            .db
+            .update_background_task(&task_id, "waiting", None)
+            .await
+        {
+            warn!("Failed to mark task {} as waiting: {}", task_id, e);
+        }
+
+        let hint = if options.is_empty() {
+            "(Reply here to answer.)".to_string()
+        } else {
+            format!(
+                "(Reply {} or anything else to answer.)",
+                options.join(" / ")
+            )
+        };
+        let interactive = (!options.is_empty()).then(|| Interactive {
+            prompt_id: pending.id.clone(),
+            buttons: options.iter().map(|o| Button::for_choice(o)).collect(),
+        });
+        self.outgoing
+            .send(OutgoingMessage {
+                content: format!(
+                    "❓ Question from background task [{}]:\n{}\n\n{}",
+                    task_id, question, hint
+                ),
+                channel: channel.clone(),
+                reply_to: None,
+                kind: MessageKind::Response,
+                interactive,
+            })
+            .await
+            .context("Failed to send question")?;
+
+        let answer =
+            tokio::time::timeout(std::time::Duration::from_secs(timeout_mins * 60), answer_rx)
+                .await;
+
+        if let Err(e) = self
+            .db
+            .update_background_task(&task_id, "running", None)
+            .await
+        {
+            warn!("Failed to mark task {} as running: {}", task_id, e);
+        }
+
+        match answer {
+            Ok(Ok(answer)) => Ok(format!("The user answered: {}", answer)),
+            _ => {
+                self.questions.withdraw(&pending.id).await;
+                Ok(format!(
+                    "No answer after {} minutes. Proceed only if there is a safe default; \
+                     otherwise stop and report what you need.",
+                    timeout_mins
+                ))
+            }
+        }
+    }
+}
+
 // ─── Helpers ────────────────────────────────────────────────────────
 
 fn format_age(dt: chrono::DateTime<chrono::Utc>) -> String {
@@ -368,6 +580,68 @@ mod tests {
         assert!(result.contains("None"));
     }
 
+    #[tokio::test]
+    async fn test_ask_user_parks_task_until_answered() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let db = Arc::new(meepo_knowledge::KnowledgeDb::new(temp.path().join("test.db")).unwrap());
+        db.insert_background_task("t-1", "Clean up downloads", "slack", "agent")
+            .await
+            .unwrap();
+        let questions = Arc::new(PendingQuestions::new());
+        let (out_tx, mut out_rx) = mpsc::channel(4);
+        let tool = AskUserTool::new(db.clone(), questions.clone(), out_tx, ChannelType::Slack);
+
+        let ask = tokio::spawn(questions::scope_task("t-1".to_string(), async move {
+            tool.execute(serde_json::json!({
+                "question": "Delete the .dmg files too?",
+                "options": ["Yes", "No"]
+            }))
+            .await
+        }));
+
+        // The question goes out and the task shows as waiting
+        let sent = out_rx.recv().await.unwrap();
+        assert_eq!(sent.channel, ChannelType::Slack);
+        assert!(sent.content.contains("Delete the .dmg files too?"));
+        let interactive = sent.interactive.unwrap();
+        assert_eq!(interactive.buttons.len(), 2);
+        assert_eq!(interactive.prompt_id, questions.list().await[0].id);
+        let active = db.get_active_background_tasks().await.unwrap();
+        assert_eq!(active[0].status, "waiting");
+
+        let reply = crate::types::IncomingMessage {
+            id: "m1".to_string(),
+            sender: "user".to_string(),
+            content: "yes, all of them".to_string(),
+            channel: ChannelType::Slack,
+            timestamp: chrono::Utc::now(),
+        };
+        assert!(questions.try_answer(&reply).await.is_some());
+
+        let result = ask.await.unwrap().unwrap();
+        assert_eq!(result, "The user answered: yes, all of them");
+        let active = db.get_active_background_tasks().await.unwrap();
+        assert_eq!(active[0].status, "running");
+    }
+
+    #[tokio::test]
+    async fn test_ask_user_outside_task() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let db = Arc::new(meepo_knowledge::KnowledgeDb::new(temp.path().join("test.db")).unwrap());
+        let (out_tx, _out_rx) = mpsc::channel(1);
+        let tool = AskUserTool::new(
+            db,
+            Arc::new(PendingQuestions::new()),
+            out_tx,
+            ChannelType::Slack,
+        );
+        let result = tool
+            .execute(serde_json::json!({"question": "Which one?"}))
+            .await
+            .unwrap();
+        assert!(result.contains("Not running in a background task"));
+    }
+
     #[tokio::test]
     async fn test_stop_task_invalid_id() {
         let temp = tempfile::TempDir::new().unwrap();
@@ -408,10 +682,12 @@ mod tests {
                 id,
                 description,
                 reply_channel,
+                heavy,
             } => {
                 assert!(id.starts_with("t-"));
                 assert_eq!(description, "Research competitors");
                 assert_eq!(reply_channel, "slack");
+                assert!(!heavy);
             }
             _ => panic!("Expected Spawn command"),
         }
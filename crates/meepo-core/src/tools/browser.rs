@@ -2,15 +2,58 @@
 //!
 //! These tools delegate to platform-specific BrowserProvider implementations.
 //! On macOS: AppleScript-based Safari and Chrome automation.
+//! On any platform: Chrome DevTools Protocol via `CdpBrowser`.
 
 use anyhow::Result;
 use async_trait::async_trait;
 use serde_json::Value;
+use std::sync::Arc;
 use tracing::debug;
 
 use super::{ToolHandler, json_schema};
 use crate::platform::BrowserProvider;
 
+/// Build the full set of browser tools for one provider.
+///
+/// `make_provider` is called once per tool so each tool owns its provider box;
+/// pass a closure that clones a shared backend (e.g. `CdpBrowser`).
+pub fn browser_tools(
+    prefix: &str,
+    make_provider: impl Fn() -> Box<dyn BrowserProvider>,
+) -> Vec<Arc<dyn ToolHandler>> {
+    vec![
+        Arc::new(BrowserListTabsTool::with_provider(prefix, make_provider())),
+        Arc::new(BrowserOpenTabTool::with_provider(prefix, make_provider())),
+        Arc::new(BrowserCloseTabTool::with_provider(prefix, make_provider())),
+        Arc::new(BrowserSwitchTabTool::with_provider(prefix, make_provider())),
+        Arc::new(BrowserGetPageContentTool::with_provider(
+            prefix,
+            make_provider(),
+        )),
+        Arc::new(BrowserExecuteJsTool::with_provider(prefix, make_provider())),
+        Arc::new(BrowserClickElementTool::with_provider(
+            prefix,
+            make_provider(),
+        )),
+        Arc::new(BrowserFillFormTool::with_provider(prefix, make_provider())),
+        Arc::new(BrowserNavigateTool::with_provider(prefix, make_provider())),
+        Arc::new(BrowserGetUrlTool::with_provider(prefix, make_provider())),
+        Arc::new(BrowserScreenshotTool::with_provider(
+            prefix,
+            make_provider(),
+        )),
+        Arc::new(BrowserScrollTool::with_provider(prefix, make_provider())),
+        Arc::new(BrowserWaitForElementTool::with_provider(
+            prefix,
+            make_provider(),
+        )),
+        Arc::new(BrowserScreenshotTabTool::with_provider(
+            prefix,
+            make_provider(),
+        )),
+    ]
+}
+
 /// List all open browser tabs
 pub struct BrowserListTabsTool {
     provider: Box<dyn BrowserProvider>,
@@ -19,10 +62,18 @@ pub struct BrowserListTabsTool {
 
 impl BrowserListTabsTool {
     pub fn new(browser: &str) -> Self {
-        Self {
-            provider: crate::platform::create_browser_provider_for(browser)
+        Self::with_provider(
+            browser,
+            crate::platform::create_browser_provider_for(browser)
                 .expect("Browser provider not available on this platform"),
-            tool_name: format!("{}_list_tabs", browser),
+        )
+    }
+
+    /// Use an explicit provider; `prefix` becomes the tool name prefix
+    pub fn with_provider(prefix: &str, provider: Box<dyn BrowserProvider>) -> Self {
+        Self {
+            provider,
+            tool_name: format!("{}_list_tabs", prefix),
         }
     }
 }
@@ -69,10 +120,18 @@ pub struct BrowserOpenTabTool {
 
 impl BrowserOpenTabTool {
     pub fn new(browser: &str) -> Self {
-        Self {
-            provider: crate::platform::create_browser_provider_for(browser)
+        Self::with_provider(
+            browser,
+            crate::platform::create_browser_provider_for(browser)
                 .expect("Browser provider not available on this platform"),
-            tool_name: format!("{}_open_tab", browser),
+        )
+    }
+
+    /// Use an explicit provider; `prefix` becomes the tool name prefix
+    pub fn with_provider(prefix: &str, provider: Box<dyn BrowserProvider>) -> Self {
+        Self {
+            provider,
+            tool_name: format!("{}_open_tab", prefix),
         }
     }
 }
@@ -83,6 +142,10 @@ impl ToolHandler for BrowserOpenTabTool {
         &self.tool_name
     }
 
+    fn needs_network(&self) -> bool {
+        true
+    }
+
     fn description(&self) -> &str {
         "Open a new browser tab with the specified URL. The browser will be activated and the new tab will become the active tab."
     }
@@ -123,10 +186,18 @@ pub struct BrowserCloseTabTool {
 
 impl BrowserCloseTabTool {
     pub fn new(browser: &str) -> Self {
-        Self {
-            provider: crate::platform::create_browser_provider_for(browser)
+        Self::with_provider(
+            browser,
+            crate::platform::create_browser_provider_for(browser)
                 .expect("Browser provider not available on this platform"),
-            tool_name: format!("{}_close_tab", browser),
+        )
+    }
+
+    /// Use an explicit provider; `prefix` becomes the tool name prefix
+    pub fn with_provider(prefix: &str, provider: Box<dyn BrowserProvider>) -> Self {
+        Self {
+            provider,
+            tool_name: format!("{}_close_tab", prefix),
         }
     }
 }
@@ -173,10 +244,18 @@ pub struct BrowserSwitchTabTool {
 
 impl BrowserSwitchTabTool {
     pub fn new(browser: &str) -> Self {
-        Self {
-            provider: crate::platform::create_browser_provider_for(browser)
+        Self::with_provider(
+            browser,
+            crate::platform::create_browser_provider_for(browser)
                 .expect("Browser provider not available on this platform"),
-            tool_name: format!("{}_switch_tab", browser),
+        )
+    }
+
+    /// Use an explicit provider; `prefix` becomes the tool name prefix
+    pub fn with_provider(prefix: &str, provider: Box<dyn BrowserProvider>) -> Self {
+        Self {
+            provider,
+            tool_name: format!("{}_switch_tab", prefix),
         }
     }
 }
@@ -223,10 +302,18 @@ pub struct BrowserGetPageContentTool {
 
 impl BrowserGetPageContentTool {
     pub fn new(browser: &str) -> Self {
-        Self {
-            provider: crate::platform::create_browser_provider_for(browser)
+        Self::with_provider(
+            browser,
+            crate::platform::create_browser_provider_for(browser)
                 .expect("Browser provider not available on this platform"),
-            tool_name: format!("{}_get_page_content", browser),
+        )
+    }
+
+    /// Use an explicit provider; `prefix` becomes the tool name prefix
+    pub fn with_provider(prefix: &str, provider: Box<dyn BrowserProvider>) -> Self {
+        Self {
+            provider,
+            tool_name: format!("{}_get_page_content", prefix),
         }
     }
 }
@@ -273,10 +360,18 @@ pub struct BrowserExecuteJsTool {
 
 impl BrowserExecuteJsTool {
     pub fn new(browser: &str) -> Self {
-        Self {
-            provider: crate::platform::create_browser_provider_for(browser)
+        Self::with_provider(
+            browser,
+            crate::platform::create_browser_provider_for(browser)
                 .expect("Browser provider not available on this platform"),
-            tool_name: format!("{}_execute_js", browser),
+        )
+    }
+
+    /// Use an explicit provider; `prefix` becomes the tool name prefix
+    pub fn with_provider(prefix: &str, provider: Box<dyn BrowserProvider>) -> Self {
+        Self {
+            provider,
+            tool_name: format!("{}_execute_js", prefix),
         }
     }
 }
@@ -365,10 +460,18 @@ pub struct BrowserClickElementTool {
 
 impl BrowserClickElementTool {
     pub fn new(browser: &str) -> Self {
-        Self {
-            provider: crate::platform::create_browser_provider_for(browser)
+        Self::with_provider(
+            browser,
+            crate::platform::create_browser_provider_for(browser)
                 .expect("Browser provider not available on this platform"),
-            tool_name: format!("{}_click", browser),
+        )
+    }
+
+    /// Use an explicit provider; `prefix` becomes the tool name prefix
+    pub fn with_provider(prefix: &str, provider: Box<dyn BrowserProvider>) -> Self {
+        Self {
+            provider,
+            tool_name: format!("{}_click", prefix),
         }
     }
 }
@@ -424,10 +527,18 @@ pub struct BrowserFillFormTool {
 
 impl BrowserFillFormTool {
     pub fn new(browser: &str) -> Self {
-        Self {
-            provider: crate::platform::create_browser_provider_for(browser)
+        Self::with_provider(
+            browser,
+            crate::platform::create_browser_provider_for(browser)
                 .expect("Browser provider not available on this platform"),
-            tool_name: format!("{}_fill_form", browser),
+        )
+    }
+
+    /// Use an explicit provider; `prefix` becomes the tool name prefix
+    pub fn with_provider(prefix: &str, provider: Box<dyn BrowserProvider>) -> Self {
+        Self {
+            provider,
+            tool_name: format!("{}_fill_form", prefix),
         }
     }
 }
@@ -494,10 +605,18 @@ pub struct BrowserNavigateTool {
 
 impl BrowserNavigateTool {
     pub fn new(browser: &str) -> Self {
-        Self {
-            provider: crate::platform::create_browser_provider_for(browser)
+        Self::with_provider(
+            browser,
+            crate::platform::create_browser_provider_for(browser)
                 .expect("Browser provider not available on this platform"),
-            tool_name: format!("{}_navigate", browser),
+        )
+    }
+
+    /// Use an explicit provider; `prefix` becomes the tool name prefix
+    pub fn with_provider(prefix: &str, provider: Box<dyn BrowserProvider>) -> Self {
+        Self {
+            provider,
+            tool_name: format!("{}_navigate", prefix),
         }
     }
 }
@@ -508,6 +627,10 @@ impl ToolHandler for BrowserNavigateTool {
         &self.tool_name
     }
 
+    fn needs_network(&self) -> bool {
+        true
+    }
+
     fn description(&self) -> &str {
         "Navigate the browser: go back, go forward, or reload the current page."
     }
@@ -567,10 +690,18 @@ pub struct BrowserGetUrlTool {
 
 impl BrowserGetUrlTool {
     pub fn new(browser: &str) -> Self {
-        Self {
-            provider: crate::platform::create_browser_provider_for(browser)
+        Self::with_provider(
+            browser,
+            crate::platform::create_browser_provider_for(browser)
                 .expect("Browser provider not available on this platform"),
-            tool_name: format!("{}_get_url", browser),
+        )
+    }
+
+    /// Use an explicit provider; `prefix` becomes the tool name prefix
+    pub fn with_provider(prefix: &str, provider: Box<dyn BrowserProvider>) -> Self {
+        Self {
+            provider,
+            tool_name: format!("{}_get_url", prefix),
         }
     }
 }
@@ -612,10 +743,18 @@ pub struct BrowserScreenshotTool {
 
 impl BrowserScreenshotTool {
     pub fn new(browser: &str) -> Self {
-        Self {
-            provider: crate::platform::create_browser_provider_for(browser)
+        Self::with_provider(
+            browser,
+            crate::platform::create_browser_provider_for(browser)
                 .expect("Browser provider not available on this platform"),
-            tool_name: format!("{}_screenshot", browser),
+        )
+    }
+
+    /// Use an explicit provider; `prefix` becomes the tool name prefix
+    pub fn with_provider(prefix: &str, provider: Box<dyn BrowserProvider>) -> Self {
+        Self {
+            provider,
+            tool_name: format!("{}_screenshot", prefix),
         }
     }
 }
@@ -674,10 +813,18 @@ pub struct BrowserScrollTool {
 
 impl BrowserScrollTool {
     pub fn new(browser: &str) -> Self {
-        Self {
-            provider: crate::platform::create_browser_provider_for(browser)
+        Self::with_provider(
+            browser,
+            crate::platform::create_browser_provider_for(browser)
                 .expect("Browser provider not available on this platform"),
-            tool_name: format!("{}_scroll", browser),
+        )
+    }
+
+    /// Use an explicit provider; `prefix` becomes the tool name prefix
+    pub fn with_provider(prefix: &str, provider: Box<dyn BrowserProvider>) -> Self {
+        Self {
+            provider,
+            tool_name: format!("{}_scroll", prefix),
         }
     }
 }
@@ -740,10 +887,18 @@ pub struct BrowserWaitForElementTool {
 
 impl BrowserWaitForElementTool {
     pub fn new(browser: &str) -> Self {
-        Self {
-            provider: crate::platform::create_browser_provider_for(browser)
+        Self::with_provider(
+            browser,
+            crate::platform::create_browser_provider_for(browser)
                 .expect("Browser provider not available on this platform"),
-            tool_name: format!("{}_wait_for_element", browser),
+        )
+    }
+
+    /// Use an explicit provider; `prefix` becomes the tool name prefix
+    pub fn with_provider(prefix: &str, provider: Box<dyn BrowserProvider>) -> Self {
+        Self {
+            provider,
+            tool_name: format!("{}_wait_for_element", prefix),
         }
     }
 }
@@ -820,10 +975,18 @@ pub struct BrowserScreenshotTabTool {
 
 impl BrowserScreenshotTabTool {
     pub fn new(browser: &str) -> Self {
-        Self {
-            provider: crate::platform::create_browser_provider_for(browser)
+        Self::with_provider(
+            browser,
+            crate::platform::create_browser_provider_for(browser)
                 .expect("Browser provider not available on this platform"),
-            tool_name: format!("{}_screenshot_tab", browser),
+        )
+    }
+
+    /// Use an explicit provider; `prefix` becomes the tool name prefix
+    pub fn with_provider(prefix: &str, provider: Box<dyn BrowserProvider>) -> Self {
+        Self {
+            provider,
+            tool_name: format!("{}_screenshot_tab", prefix),
         }
     }
 }
@@ -1078,3 +1241,20 @@ mod tests {
         assert!(result.is_err());
     }
 }
+
+#[cfg(test)]
+mod provider_tests {
+    use super::*;
+    use crate::platform::cdp::{CdpBrowser, CdpConfig};
+
+    #[test]
+    fn test_browser_tools_with_cdp_provider() {
+        let cdp = CdpBrowser::new(CdpConfig::default());
+        let tools = browser_tools("browser", || Box::new(cdp.clone()));
+        assert_eq!(tools.len(), 14);
+        let names: Vec<&str> = tools.iter().map(|t| t.name()).collect();
+        assert!(names.contains(&"browser_list_tabs"));
+        assert!(names.contains(&"browser_wait_for_element"));
+        assert!(names.contains(&"browser_screenshot_tab"));
+    }
+}
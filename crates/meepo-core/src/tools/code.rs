@@ -11,6 +11,7 @@ use tracing::{debug, warn};
 
 use super::autonomous::BackgroundTaskCommand;
 use super::{ToolHandler, json_schema};
+use crate::tool_cost::{CostClass, LatencyClass, ToolCost};
 use meepo_knowledge::KnowledgeDb;
 
 /// Configuration for coding agent CLI tools, plumbed from [code] config section
@@ -68,6 +69,10 @@ impl ToolHandler for WriteCodeTool {
         )
     }
 
+    fn cost(&self) -> ToolCost {
+        ToolCost::new(CostClass::High, LatencyClass::Minutes)
+    }
+
     async fn execute(&self, input: Value) -> Result<String> {
         let task = input
             .get("task")
@@ -179,6 +184,10 @@ impl ToolHandler for MakePrTool {
         )
     }
 
+    fn cost(&self) -> ToolCost {
+        ToolCost::new(CostClass::High, LatencyClass::Minutes)
+    }
+
     async fn execute(&self, input: Value) -> Result<String> {
         let task = input
             .get("task")
@@ -599,6 +608,10 @@ impl ToolHandler for ReviewPrTool {
         )
     }
 
+    fn cost(&self) -> ToolCost {
+        ToolCost::new(CostClass::High, LatencyClass::Minutes)
+    }
+
     async fn execute(&self, input: Value) -> Result<String> {
         let repo = input
             .get("repo")
@@ -728,6 +741,10 @@ impl ToolHandler for SpawnCodingAgentTool {
         )
     }
 
+    fn cost(&self) -> ToolCost {
+        ToolCost::new(CostClass::High, LatencyClass::Minutes)
+    }
+
     async fn execute(&self, input: Value) -> Result<String> {
         let task = input
             .get("task")
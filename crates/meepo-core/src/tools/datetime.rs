@@ -0,0 +1,104 @@
+//! Current date/time tool — locale-aware, in the user's timezone
+
+use anyhow::{Result, anyhow};
+use async_trait::async_trait;
+use chrono::Utc;
+use chrono_tz::Tz;
+use serde_json::Value;
+
+use super::{ToolHandler, json_schema};
+use crate::timezone;
+
+/// Report the current date and time in the user's (or a requested) timezone
+pub struct CurrentDateTimeTool {
+    locale: String,
+}
+
+impl CurrentDateTimeTool {
+    pub fn new(locale: impl Into<String>) -> Self {
+        Self {
+            locale: locale.into(),
+        }
+    }
+}
+
+#[async_trait]
+impl ToolHandler for CurrentDateTimeTool {
+    fn name(&self) -> &str {
+        "current_datetime"
+    }
+
+    fn description(&self) -> &str {
+        "Get the current date and time in the user's timezone (or another IANA timezone), \
+         formatted for the user's locale, with the UTC offset and ISO 8601 timestamp. Use this \
+         before scheduling anything or answering questions about 'today' or 'now'."
+    }
+
+    fn input_schema(&self) -> Value {
+        json_schema(
+            serde_json::json!({
+                "timezone": {
+                    "type": "string",
+                    "description": "IANA timezone, e.g. 'Europe/London' (default: user's timezone)"
+                },
+                "locale": {
+                    "type": "string",
+                    "description": "Locale for formatting, e.g. 'en-US', 'en-GB', 'de-DE' (default: configured locale)"
+                }
+            }),
+            vec![],
+        )
+    }
+
+    async fn execute(&self, input: Value) -> Result<String> {
+        let tz = match input.get("timezone").and_then(|v| v.as_str()) {
+            Some(name) => name.parse::<Tz>().map_err(|_| {
+                anyhow!(
+                    "Unknown timezone '{}'. Use an IANA name like 'America/Chicago'",
+                    name
+                )
+            })?,
+            None => timezone::user_timezone(),
+        };
+        let locale = input
+            .get("locale")
+            .and_then(|v| v.as_str())
+            .unwrap_or(&self.locale);
+
+        let now = Utc::now().with_timezone(&tz);
+        Ok(format!(
+            "{}\nTimezone: {} (UTC{})\nISO 8601: {}\nUnix: {}",
+            timezone::format_local(&now, locale),
+            tz.name(),
+            now.format("%:z"),
+            now.to_rfc3339(),
+            now.timestamp()
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_current_datetime_explicit_timezone() {
+        let tool = CurrentDateTimeTool::new("en-US");
+        assert_eq!(tool.name(), "current_datetime");
+        let out = tool
+            .execute(serde_json::json!({"timezone": "Asia/Tokyo"}))
+            .await
+            .unwrap();
+        assert!(out.contains("Timezone: Asia/Tokyo (UTC+09:00)"));
+        assert!(out.contains("+09:00"));
+    }
+
+    #[tokio::test]
+    async fn test_current_datetime_invalid_timezone() {
+        let tool = CurrentDateTimeTool::new("en-US");
+        let result = tool
+            .execute(serde_json::json!({"timezone": "Not/AZone"}))
+            .await;
+        assert!(result.unwrap_err().to_string().contains("Unknown timezone"));
+    }
+}
@@ -2,6 +2,7 @@
 //!
 //! Allows the prime Meepo to spawn focused clones for parallel or background work.
 //! Each clone gets a scoped toolset and cannot recursively spawn more clones.
+//! Clones in a group share a scratchpad to hand work to one another.
 
 use std::sync::{Arc, OnceLock};
 
@@ -12,6 +13,7 @@ use serde_json::Value;
 use tracing::info;
 
 use crate::orchestrator::{ExecutionMode, SubTask, TaskGroup, TaskOrchestrator};
+use crate::tool_cost::{CostClass, LatencyClass, ToolCost};
 use crate::tools::{ToolHandler, ToolRegistry};
 use crate::types::ChannelType;
 
@@ -53,7 +55,9 @@ impl ToolHandler for DelegateTasksTool {
     fn description(&self) -> &str {
         "Spawn Meepo clones to divide and conquer. Divided We Stand. \
          Use 'parallel' mode to send clones digging simultaneously and wait for all results. \
-         Use 'background' mode to send clones off to work independently — they'll report back when done."
+         Use 'background' mode to send clones off to work independently — they'll report back when done. \
+         Use 'sequential' mode for pipelines (e.g. researcher → writer → reviewer): clones run in order, \
+         and every clone in a group shares a scratchpad for notes, so later ones build on earlier work."
     }
 
     fn input_schema(&self) -> Value {
@@ -62,8 +66,8 @@ impl ToolHandler for DelegateTasksTool {
             "properties": {
                 "mode": {
                     "type": "string",
-                    "enum": ["parallel", "background"],
-                    "description": "parallel: blocks until all complete, returns combined results. background: returns immediately, notifies user on completion."
+                    "enum": ["parallel", "background", "sequential"],
+                    "description": "parallel: blocks until all complete, returns combined results. background: returns immediately, notifies user on completion. sequential: runs the tasks one after another in the order given, then returns combined results."
                 },
                 "tasks": {
                     "type": "array",
@@ -97,6 +101,10 @@ impl ToolHandler for DelegateTasksTool {
         })
     }
 
+    fn cost(&self) -> ToolCost {
+        ToolCost::new(CostClass::High, LatencyClass::Minutes)
+    }
+
     async fn execute(&self, input: Value) -> Result<String> {
         let registry = self.registry()?;
 
@@ -108,7 +116,7 @@ impl ToolHandler for DelegateTasksTool {
         let mode: ExecutionMode = serde_json::from_value(Value::String(mode_str.to_string()))
             .map_err(|_| {
                 anyhow!(
-                    "Invalid mode '{}'. Must be 'parallel' or 'background'.",
+                    "Invalid mode '{}'. Must be 'parallel', 'background' or 'sequential'.",
                     mode_str
                 )
             })?;
@@ -179,6 +187,7 @@ impl ToolHandler for DelegateTasksTool {
         match mode {
             ExecutionMode::Parallel => self.orchestrator.run_parallel(group, registry).await,
             ExecutionMode::Background => self.orchestrator.run_background(group, registry).await,
+            ExecutionMode::Sequential => self.orchestrator.run_sequential(group, registry).await,
         }
     }
 }
@@ -0,0 +1,511 @@
+//! Entity dossier generation
+//!
+//! `generate_dossier` collects everything known about one entity — its
+//! metadata, direct relationships, related entities found by graph
+//! expansion, ingested documents and recent conversations that mention it —
+//! and writes a Markdown page to `<workspace>/dossiers/`. With `refresh`, a
+//! scheduled watcher asks the agent to regenerate the page periodically.
+
+use anyhow::{Context, Result, anyhow};
+use async_trait::async_trait;
+use chrono::Utc;
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::sync::Arc;
+use tokio::sync::mpsc;
+use tracing::{debug, info};
+
+use super::watchers::{CreateWatcherTool, WatcherCommand};
+use super::{ToolHandler, json_schema};
+use crate::tool_cost::{CostClass, LatencyClass, ToolCost};
+use meepo_knowledge::graph_rag::{EntitySource, GraphRagConfig, ScoredEntity, graph_expand};
+use meepo_knowledge::{Conversation, Entity, KnowledgeDb, Relationship};
+
+/// Conversation excerpts longer than this are cut short
+const MAX_EXCERPT_CHARS: usize = 280;
+
+/// Metadata keys not shown in the overview
+const HIDDEN_KEYS: &[&str] = &["tags", "auto_tags", "trace"];
+
+/// Generate a Markdown dossier for a person, project or other entity
+pub struct GenerateDossierTool {
+    db: Arc<KnowledgeDb>,
+    workspace: PathBuf,
+    watcher_tx: Option<mpsc::Sender<WatcherCommand>>,
+}
+
+impl GenerateDossierTool {
+    pub fn new(db: Arc<KnowledgeDb>, workspace: PathBuf) -> Self {
+        Self {
+            db,
+            workspace,
+            watcher_tx: None,
+        }
+    }
+
+    /// Allow `refresh` to schedule regeneration through the watcher runner
+    pub fn with_watcher_commands(mut self, tx: mpsc::Sender<WatcherCommand>) -> Self {
+        self.watcher_tx = Some(tx);
+        self
+    }
+
+    /// Find the entity by ID, exact name, or best name match
+    async fn resolve(&self, query: &str) -> Result<Entity> {
+        if let Some(entity) = self.db.resolve_entity(query).await? {
+            return Ok(entity);
+        }
+        let mut matches = self.db.search_entities(query, None).await?;
+        matches.retain(|e| e.entity_type != "document_chunk");
+        let exact = matches
+            .iter()
+            .position(|e| e.name.eq_ignore_ascii_case(query));
+        match exact {
+            Some(i) => Ok(matches.swap_remove(i)),
+            None => matches
+                .into_iter()
+                .next()
+                .ok_or_else(|| anyhow!("No entity found matching '{}'", query)),
+        }
+    }
+
+    /// Schedule a watcher that regenerates the dossier, unless one exists
+    async fn schedule_refresh(
+        &self,
+        entity: &Entity,
+        refresh: &str,
+        reply_channel: &str,
+    ) -> Result<String> {
+        let tx = self
+            .watcher_tx
+            .clone()
+            .ok_or_else(|| anyhow!("Scheduled refresh isn't available here"))?;
+        let cron_expr = refresh_cron(refresh)?;
+        let marker = format!("generate_dossier entity_id={}", entity.id);
+
+        let existing = self.db.get_active_watchers().await?;
+        if let Some(watcher) = existing.iter().find(|w| w.action.contains(&marker)) {
+            return Ok(format!("already refreshed by watcher {}", watcher.id));
+        }
+
+        let action = format!("Refresh the dossier for \"{}\" ({})", entity.name, marker);
+        let result = CreateWatcherTool::new(self.db.clone(), tx)
+            .execute(serde_json::json!({
+                "kind": "scheduled",
+                "config": {"cron_expr": cron_expr, "task": action},
+                "action": action,
+                "reply_channel": reply_channel,
+            }))
+            .await?;
+        Ok(result.to_lowercase())
+    }
+}
+
+#[async_trait]
+impl ToolHandler for GenerateDossierTool {
+    fn name(&self) -> &str {
+        "generate_dossier"
+    }
+
+    fn reads_personal_knowledge(&self) -> bool {
+        true
+    }
+
+    fn description(&self) -> &str {
+        "Build a Markdown dossier about a person, project or other known entity: \
+         its details, relationships, related knowledge, documents and recent \
+         conversations. Saves it to the workspace and returns it. Set 'refresh' \
+         to keep it up to date on a schedule."
+    }
+
+    fn input_schema(&self) -> Value {
+        json_schema(
+            serde_json::json!({
+                "entity": {
+                    "type": "string",
+                    "description": "Entity name or ID"
+                },
+                "max_hops": {
+                    "type": "number",
+                    "description": "How far to follow relationships (default: 2)"
+                },
+                "refresh": {
+                    "type": "string",
+                    "description": "Optional: regenerate 'daily', 'weekly', or on a cron expression"
+                },
+                "reply_channel": {
+                    "type": "string",
+                    "description": "Channel for refresh notifications (default: 'internal')"
+                }
+            }),
+            vec!["entity"],
+        )
+    }
+
+    fn cost(&self) -> ToolCost {
+        ToolCost::new(CostClass::Medium, LatencyClass::Minutes)
+    }
+
+    async fn execute(&self, input: Value) -> Result<String> {
+        let query = input
+            .get("entity")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| anyhow!("Missing 'entity' parameter"))?;
+        let max_hops = input.get("max_hops").and_then(|v| v.as_u64()).unwrap_or(2) as usize;
+        let refresh = input.get("refresh").and_then(|v| v.as_str());
+        let reply_channel = input
+            .get("reply_channel")
+            .and_then(|v| v.as_str())
+            .unwrap_or("internal");
+
+        let entity = self.resolve(query).await?;
+        debug!("Generating dossier for {} ({})", entity.name, entity.id);
+
+        let config = GraphRagConfig {
+            max_hops: max_hops.clamp(1, 4),
+            max_expanded_results: 60,
+            ..GraphRagConfig::default()
+        };
+        let related = graph_expand(&self.db, &[(entity.id.clone(), 1.0)], &config)
+            .await
+            .context("Failed to expand entity relationships")?;
+        let relationships = self.db.get_relationships_for(&entity.id).await?;
+
+        // Messages that mention the entity, plus those sharing its topic tags
+        let mut conversations = self.db.search_conversations(&entity.name, 15).await?;
+        for alias in &entity.aliases {
+            for convo in self.db.search_conversations(alias, 10).await? {
+                if !conversations.iter().any(|c| c.id == convo.id) {
+                    conversations.push(convo);
+                }
+            }
+        }
+        for tag in string_list(entity.metadata.as_ref(), "tags") {
+            for convo in self.db.get_conversations_by_tag(&tag, 10).await? {
+                if !conversations.iter().any(|c| c.id == convo.id) {
+                    conversations.push(convo);
+                }
+            }
+        }
+        conversations.retain(|c| c.sender != "meepo");
+        conversations.sort_by_key(|c| std::cmp::Reverse(c.created_at));
+        conversations.truncate(15);
+
+        let markdown = render_dossier(&entity, &relationships, &related, &conversations);
+
+        let dir = self.workspace.join("dossiers");
+        tokio::fs::create_dir_all(&dir)
+            .await
+            .with_context(|| format!("Failed to create {}", dir.display()))?;
+        let path = dir.join(format!("{}.md", slug(&entity.name)));
+        tokio::fs::write(&path, &markdown)
+            .await
+            .with_context(|| format!("Failed to write {}", path.display()))?;
+        info!("Wrote dossier for {} to {}", entity.name, path.display());
+
+        let mut output = format!("Saved dossier to {}", path.display());
+        if let Some(refresh) = refresh {
+            let note = self
+                .schedule_refresh(&entity, refresh, reply_channel)
+                .await?;
+            output.push_str(&format!(" ({})", note));
+        }
+        output.push_str("\n\n");
+        output.push_str(&markdown);
+        Ok(output)
+    }
+}
+
+/// Render the dossier page
+fn render_dossier(
+    entity: &Entity,
+    relationships: &[Relationship],
+    related: &[ScoredEntity],
+    conversations: &[Conversation],
+) -> String {
+    let names: BTreeMap<&str, &Entity> = related
+        .iter()
+        .map(|s| (s.entity.id.as_str(), &s.entity))
+        .collect();
+
+    let mut md = format!(
+        "# {}\n\n*{} · generated {}*\n\n",
+        entity.name,
+        entity.entity_type,
+        Utc::now().format("%Y-%m-%d %H:%M UTC")
+    );
+
+    let tags = string_list(entity.metadata.as_ref(), "tags");
+    if !tags.is_empty() {
+        md.push_str(&format!("Tags: {}\n\n", tags.join(", ")));
+    }
+
+    if let Some(Value::Object(map)) = &entity.metadata {
+        let details: Vec<_> = map
+            .iter()
+            .filter(|(k, _)| !HIDDEN_KEYS.contains(&k.as_str()))
+            .collect();
+        if !details.is_empty() {
+            md.push_str("## Overview\n\n");
+            for (key, value) in details {
+                let value = match value {
+                    Value::String(s) => s.clone(),
+                    other => other.to_string(),
+                };
+                md.push_str(&format!("- **{}:** {}\n", key, value));
+            }
+            md.push('\n');
+        }
+    }
+
+    if !relationships.is_empty() {
+        md.push_str("## Relationships\n\n");
+        for rel in relationships {
+            let (other, arrow) = if rel.source_id == entity.id {
+                (&rel.target_id, "→")
+            } else {
+                (&rel.source_id, "←")
+            };
+            let label = names
+                .get(other.as_str())
+                .map(|e| format!("**{}** ({})", e.name, e.entity_type))
+                .unwrap_or_else(|| format!("`{}`", other));
+            md.push_str(&format!("- {} {} {}\n", rel.relation_type, arrow, label));
+        }
+        md.push('\n');
+    }
+
+    // Related entities grouped by type; documents get their own section
+    let mut by_type: BTreeMap<&str, Vec<&ScoredEntity>> = BTreeMap::new();
+    let mut documents = Vec::new();
+    for scored in related {
+        if scored.entity.id == entity.id || scored.entity.entity_type == "document_chunk" {
+            continue;
+        }
+        if scored.entity.entity_type == "document" {
+            documents.push(&scored.entity);
+        } else {
+            by_type
+                .entry(scored.entity.entity_type.as_str())
+                .or_default()
+                .push(scored);
+        }
+    }
+
+    if !by_type.is_empty() {
+        md.push_str("## Related\n\n");
+        for (entity_type, items) in by_type {
+            md.push_str(&format!("### {}\n\n", entity_type));
+            for scored in items {
+                let via = match &scored.source {
+                    EntitySource::GraphExpansion {
+                        hops,
+                        from_entity_id,
+                    } if *hops > 1 => names
+                        .get(from_entity_id.as_str())
+                        .map(|e| format!(" — via {}", e.name))
+                        .unwrap_or_default(),
+                    _ => String::new(),
+                };
+                md.push_str(&format!("- **{}**{}\n", scored.entity.name, via));
+            }
+            md.push('\n');
+        }
+    }
+
+    if !documents.is_empty() {
+        md.push_str("## Documents\n\n");
+        for doc in documents {
+            let path = doc
+                .metadata
+                .as_ref()
+                .and_then(|m| m.get("source_path"))
+                .and_then(|v| v.as_str());
+            match path {
+                Some(path) => md.push_str(&format!("- {} — `{}`\n", doc.name, path)),
+                None => md.push_str(&format!("- {}\n", doc.name)),
+            }
+        }
+        md.push('\n');
+    }
+
+    if !conversations.is_empty() {
+        md.push_str("## Recent conversations\n\n");
+        for convo in conversations {
+            md.push_str(&format!(
+                "- {} — {} on {}: {}\n",
+                convo.created_at.format("%Y-%m-%d"),
+                convo.sender,
+                convo.channel,
+                excerpt(&convo.content)
+            ));
+        }
+        md.push('\n');
+    }
+
+    md
+}
+
+/// String values of an array field in entity metadata
+fn string_list(metadata: Option<&Value>, key: &str) -> Vec<String> {
+    metadata
+        .and_then(|m| m.get(key))
+        .and_then(|v| v.as_array())
+        .map(|a| {
+            a.iter()
+                .filter_map(|v| v.as_str().map(String::from))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Single-line excerpt of a message
+fn excerpt(content: &str) -> String {
+    let line = content.split_whitespace().collect::<Vec<_>>().join(" ");
+    if line.chars().count() > MAX_EXCERPT_CHARS {
+        let cut: String = line.chars().take(MAX_EXCERPT_CHARS).collect();
+        format!("{}…", cut)
+    } else {
+        line
+    }
+}
+
+/// File name for an entity's dossier
+fn slug(name: &str) -> String {
+    let slug = name
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .map(|w| w.to_lowercase())
+        .collect::<Vec<_>>()
+        .join("-");
+    if slug.is_empty() {
+        "entity".to_string()
+    } else {
+        slug
+    }
+}
+
+/// Cron expression (with seconds) for a refresh setting
+fn refresh_cron(refresh: &str) -> Result<String> {
+    let refresh = refresh.trim();
+    match refresh.to_lowercase().as_str() {
+        "daily" => Ok("0 0 8 * * *".to_string()),
+        "weekly" => Ok("0 0 8 * * MON".to_string()),
+        _ => {
+            // Accept standard 5-field cron by adding a seconds field
+            let expr = if refresh.split_whitespace().count() == 5 {
+                format!("0 {}", refresh)
+            } else {
+                refresh.to_string()
+            };
+            <cron::Schedule as std::str::FromStr>::from_str(&expr)
+                .map_err(|e| anyhow!("Invalid refresh schedule '{}': {}", refresh, e))?;
+            Ok(expr)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[tokio::test]
+    async fn test_generate_dossier() {
+        let temp = TempDir::new().unwrap();
+        let db = Arc::new(KnowledgeDb::new(temp.path().join("test.db")).unwrap());
+        let project = db
+            .insert_entity(
+                "Kitchen Renovation",
+                "project",
+                Some(serde_json::json!({"budget": "$40k", "tags": ["kitchen-renovation"]})),
+            )
+            .await
+            .unwrap();
+        let person = db
+            .insert_entity("Dana Contractor", "person", None)
+            .await
+            .unwrap();
+        let supplier = db
+            .insert_entity("Stone Supply Co", "company", None)
+            .await
+            .unwrap();
+        let doc = db
+            .insert_entity(
+                "Countertop quote",
+                "document",
+                Some(serde_json::json!({"source_path": "/docs/quote.pdf"})),
+            )
+            .await
+            .unwrap();
+        db.insert_relationship(&project, &person, "managed_by", None)
+            .await
+            .unwrap();
+        db.insert_relationship(&person, &supplier, "works_with", None)
+            .await
+            .unwrap();
+        db.insert_relationship(&doc, &project, "part_of", None)
+            .await
+            .unwrap();
+        db.insert_conversation(
+            "slack",
+            "alice",
+            "Is the kitchen renovation on track?",
+            None,
+        )
+        .await
+        .unwrap();
+        db.insert_conversation("slack", "bob", "lunch?", None)
+            .await
+            .unwrap();
+
+        let (tx, mut rx) = mpsc::channel(4);
+        let tool = GenerateDossierTool::new(db.clone(), temp.path().to_path_buf())
+            .with_watcher_commands(tx);
+        let output = tool
+            .execute(serde_json::json!({"entity": "kitchen renovation", "refresh": "weekly"}))
+            .await
+            .unwrap();
+
+        let path = temp.path().join("dossiers/kitchen-renovation.md");
+        let page = std::fs::read_to_string(&path).unwrap();
+        assert!(output.contains(&page));
+        assert!(page.starts_with("# Kitchen Renovation\n"));
+        assert!(page.contains("- **budget:** $40k"));
+        assert!(page.contains("Tags: kitchen-renovation"));
+        assert!(page.contains("- managed_by → **Dana Contractor** (person)"));
+        assert!(page.contains("- **Stone Supply Co** — via Dana Contractor"));
+        assert!(page.contains("- Countertop quote — `/docs/quote.pdf`"));
+        assert!(page.contains("alice on slack: Is the kitchen renovation on track?"));
+        assert!(!page.contains("lunch"));
+
+        // The refresh watcher is created once
+        match rx.try_recv().unwrap() {
+            WatcherCommand::Create { kind, config, .. } => {
+                assert_eq!(kind, "scheduled");
+                assert_eq!(config["cron_expr"], "0 0 8 * * MON");
+            }
+            other => panic!("unexpected command {:?}", other),
+        }
+        let again = tool
+            .execute(serde_json::json!({"entity": project, "refresh": "daily"}))
+            .await
+            .unwrap();
+        assert!(again.contains("already refreshed"));
+        assert!(rx.try_recv().is_err());
+
+        assert!(
+            tool.execute(serde_json::json!({"entity": "nobody"}))
+                .await
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_refresh_cron() {
+        assert_eq!(refresh_cron("daily").unwrap(), "0 0 8 * * *");
+        assert_eq!(refresh_cron("30 7 * * *").unwrap(), "0 30 7 * * *");
+        assert!(refresh_cron("whenever").is_err());
+        assert_eq!(slug("Dana O'Brien"), "dana-o-brien");
+    }
+}
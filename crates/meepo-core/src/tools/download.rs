@@ -0,0 +1,839 @@
+//! Managed downloads — HTTP and browser-initiated file downloads
+//!
+//! Downloads run in the background with progress tracking and optional
+//! SHA-256 verification. When a download has an `on_complete` instruction,
+//! finishing it spawns a background task so the agent can follow up
+//! ("when the report downloads, summarize it").
+
+use anyhow::{Context, Result, anyhow};
+use async_trait::async_trait;
+use serde::Serialize;
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tokio::io::AsyncWriteExt;
+use tokio::sync::{RwLock, mpsc};
+use tracing::{debug, info, warn};
+
+use meepo_knowledge::KnowledgeDb;
+
+use super::autonomous::BackgroundTaskCommand;
+use super::system::{ValidatedUrl, validate_file_path, validate_url};
+use super::{ToolHandler, json_schema};
+use crate::platform::BrowserProvider;
+
+/// Redirects followed before a download gives up
+const MAX_REDIRECTS: usize = 5;
+
+/// Longest to wait for a connection to each host
+const CONNECT_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(30);
+
+/// Longest a download may stall without receiving data; the whole transfer
+/// isn't capped, since large files take as long as they take
+const READ_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(60);
+
+/// Download lifecycle state
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum DownloadStatus {
+    Downloading,
+    Completed,
+    Failed,
+}
+
+/// A tracked download
+#[derive(Debug, Clone, Serialize)]
+pub struct DownloadJob {
+    pub id: String,
+    pub url: String,
+    pub path: PathBuf,
+    pub status: DownloadStatus,
+    pub bytes_downloaded: u64,
+    pub total_bytes: Option<u64>,
+    pub sha256: Option<String>,
+    pub error: Option<String>,
+    pub on_complete: Option<String>,
+    pub reply_channel: String,
+}
+
+impl DownloadJob {
+    fn summary(&self) -> String {
+        let progress = match self.total_bytes {
+            Some(total) if total > 0 => format!(
+                "{}/{} bytes ({}%)",
+                self.bytes_downloaded,
+                total,
+                self.bytes_downloaded * 100 / total
+            ),
+            _ => format!("{} bytes", self.bytes_downloaded),
+        };
+        let mut out = format!(
+            "[{}] {:?} {}\n  URL: {}\n  Path: {}",
+            self.id,
+            self.status,
+            progress,
+            self.url,
+            self.path.display()
+        );
+        if let Some(hash) = &self.sha256 {
+            out.push_str(&format!("\n  SHA-256: {}", hash));
+        }
+        if let Some(err) = &self.error {
+            out.push_str(&format!("\n  Error: {}", err));
+        }
+        out
+    }
+}
+
+/// Parameters for starting a download
+#[derive(Debug, Clone, Default)]
+pub struct DownloadRequest {
+    pub url: String,
+    pub filename: Option<String>,
+    pub expected_sha256: Option<String>,
+    pub on_complete: Option<String>,
+    pub reply_channel: String,
+    /// Extra headers (e.g. the browser's cookies for the download's host)
+    pub headers: Vec<(String, String)>,
+}
+
+/// Runs and tracks downloads into a single validated directory
+pub struct DownloadManager {
+    dir: PathBuf,
+    max_bytes: u64,
+    jobs: RwLock<HashMap<String, DownloadJob>>,
+    db: Option<Arc<KnowledgeDb>>,
+    task_tx: Option<mpsc::Sender<BackgroundTaskCommand>>,
+}
+
+impl DownloadManager {
+    /// Create a manager. The directory must be within the home, working, or
+    /// temp directory (same policy as `write_file`); it is created if missing.
+    pub fn new(dir: &str, max_bytes: u64) -> Result<Self> {
+        let validated = validate_file_path(dir, true)?;
+        std::fs::create_dir_all(&validated)
+            .with_context(|| format!("Failed to create download dir {}", validated.display()))?;
+        Ok(Self {
+            dir: validated.canonicalize().unwrap_or(validated),
+            max_bytes,
+            jobs: RwLock::new(HashMap::new()),
+            db: None,
+            task_tx: None,
+        })
+    }
+
+    /// Enable `on_complete` follow-ups via the background task runner
+    pub fn with_follow_ups(
+        mut self,
+        db: Arc<KnowledgeDb>,
+        task_tx: mpsc::Sender<BackgroundTaskCommand>,
+    ) -> Self {
+        self.db = Some(db);
+        self.task_tx = Some(task_tx);
+        self
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    pub async fn get(&self, id: &str) -> Option<DownloadJob> {
+        self.jobs.read().await.get(id).cloned()
+    }
+
+    pub async fn list(&self) -> Vec<DownloadJob> {
+        let mut jobs: Vec<DownloadJob> = self.jobs.read().await.values().cloned().collect();
+        jobs.sort_by(|a, b| a.id.cmp(&b.id));
+        jobs
+    }
+
+    /// Validate the request and start downloading in the background
+    pub async fn start(self: &Arc<Self>, request: DownloadRequest) -> Result<DownloadJob> {
+        let validated = validate_url(&request.url)?;
+        if let Some(expected) = &request.expected_sha256
+            && (expected.len() != 64 || !expected.chars().all(|c| c.is_ascii_hexdigit()))
+        {
+            return Err(anyhow!("sha256 must be 64 hex characters"));
+        }
+        let path = self.target_path(&request.url, request.filename.as_deref())?;
+
+        let id = format!("dl-{}", &uuid::Uuid::new_v4().to_string()[..8]);
+        let job = DownloadJob {
+            id: id.clone(),
+            url: request.url.clone(),
+            path: path.clone(),
+            status: DownloadStatus::Downloading,
+            bytes_downloaded: 0,
+            total_bytes: None,
+            sha256: None,
+            error: None,
+            on_complete: request.on_complete.clone(),
+            reply_channel: request.reply_channel.clone(),
+        };
+        self.jobs.write().await.insert(id.clone(), job.clone());
+
+        let manager = Arc::clone(self);
+        tokio::spawn(async move {
+            let result = manager.run(&id, validated, &request, &path).await;
+            if result.is_err() {
+                // Give back the name reserved for the file
+                let _ = tokio::fs::remove_file(&path).await;
+            }
+            manager.finish(&id, result).await;
+        });
+
+        Ok(job)
+    }
+
+    /// Resolve a safe output path inside the download directory and reserve
+    /// it by creating the file empty, so concurrent downloads can't pick the
+    /// same name
+    fn target_path(&self, url: &str, filename: Option<&str>) -> Result<PathBuf> {
+        let name = match filename {
+            Some(f) => f.to_string(),
+            None => url::Url::parse(url)
+                .ok()
+                .and_then(|u| {
+                    u.path_segments()
+                        .and_then(|mut s| s.next_back().map(String::from))
+                })
+                .filter(|s| !s.is_empty())
+                .unwrap_or_else(|| "download".to_string()),
+        };
+        let name = sanitize_filename(&name)?;
+
+        // Never overwrite: append a counter if the name is taken
+        let mut path = self.dir.join(&name);
+        let (stem, ext) = match name.rsplit_once('.') {
+            Some((s, e)) if !s.is_empty() => (s.to_string(), format!(".{}", e)),
+            _ => (name.clone(), String::new()),
+        };
+        let mut n = 1;
+        loop {
+            match std::fs::OpenOptions::new()
+                .write(true)
+                .create_new(true)
+                .open(&path)
+            {
+                Ok(_) => return Ok(path),
+                Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => {
+                    path = self.dir.join(format!("{}-{}{}", stem, n, ext));
+                    n += 1;
+                }
+                Err(e) => {
+                    return Err(e).with_context(|| format!("Failed to create {}", path.display()));
+                }
+            }
+        }
+    }
+
+    /// Request the file, following redirects one hop at a time so every
+    /// host is checked and its addresses pinned. Headers only go to the
+    /// host the user asked for.
+    async fn fetch(
+        &self,
+        mut validated: ValidatedUrl,
+        request: &DownloadRequest,
+    ) -> Result<reqwest::Response> {
+        let mut current = url::Url::parse(&request.url).context("Invalid URL")?;
+        let origin = validated.host.clone();
+        for _ in 0..=MAX_REDIRECTS {
+            // Pin resolved IPs so the request goes where we checked
+            let mut builder = reqwest::Client::builder()
+                .user_agent("meepo-agent/1.0")
+                .connect_timeout(CONNECT_TIMEOUT)
+                .read_timeout(READ_TIMEOUT)
+                .redirect(reqwest::redirect::Policy::none());
+            for addr in &validated.resolved_ips {
+                builder = builder.resolve(&validated.host, *addr);
+            }
+            let client = builder.build().context("Failed to create HTTP client")?;
+
+            let mut req = client.get(current.as_str());
+            if validated.host == origin {
+                for (k, v) in &request.headers {
+                    req = req.header(k, v);
+                }
+            }
+            let response = req.send().await.context("Download request failed")?;
+            if !response.status().is_redirection() {
+                return Ok(response.error_for_status()?);
+            }
+            let location = response
+                .headers()
+                .get(reqwest::header::LOCATION)
+                .and_then(|l| l.to_str().ok())
+                .ok_or_else(|| anyhow!("Redirect without a valid Location header"))?;
+            current = current
+                .join(location)
+                .map_err(|_| anyhow!("Invalid redirect URL: {}", location))?;
+            validated = validate_url(current.as_str())
+                .with_context(|| format!("Blocked redirect to {}", current))?;
+            debug!("Download redirected to {}", current);
+        }
+        Err(anyhow!("Too many redirects (max {})", MAX_REDIRECTS))
+    }
+
+    async fn run(
+        &self,
+        id: &str,
+        validated: ValidatedUrl,
+        request: &DownloadRequest,
+        path: &Path,
+    ) -> Result<String> {
+        let mut response = self.fetch(validated, request).await?;
+
+        let total = response.content_length();
+        if let Some(total) = total
+            && total > self.max_bytes
+        {
+            return Err(anyhow!(
+                "File is {} bytes, exceeds download limit of {} bytes",
+                total,
+                self.max_bytes
+            ));
+        }
+        if let Some(job) = self.jobs.write().await.get_mut(id) {
+            job.total_bytes = total;
+        }
+
+        // Write to a temp name so partial files never look complete
+        let partial = path.with_extension(format!(
+            "{}part",
+            path.extension()
+                .map(|e| format!("{}.", e.to_string_lossy()))
+                .unwrap_or_default()
+        ));
+        let mut file = tokio::fs::File::create(&partial)
+            .await
+            .with_context(|| format!("Failed to create {}", partial.display()))?;
+        let mut hasher = Sha256::new();
+        let mut downloaded: u64 = 0;
+
+        let outcome: Result<()> = async {
+            while let Some(chunk) = response.chunk().await.context("Download interrupted")? {
+                downloaded += chunk.len() as u64;
+                if downloaded > self.max_bytes {
+                    return Err(anyhow!(
+                        "Download exceeded limit of {} bytes",
+                        self.max_bytes
+                    ));
+                }
+                hasher.update(&chunk);
+                file.write_all(&chunk).await?;
+                if let Some(job) = self.jobs.write().await.get_mut(id) {
+                    job.bytes_downloaded = downloaded;
+                }
+            }
+            file.flush().await?;
+            Ok(())
+        }
+        .await;
+
+        if let Err(e) = outcome {
+            let _ = tokio::fs::remove_file(&partial).await;
+            return Err(e);
+        }
+
+        let digest = to_hex(&hasher.finalize());
+        if let Some(expected) = &request.expected_sha256
+            && !expected.eq_ignore_ascii_case(&digest)
+        {
+            let _ = tokio::fs::remove_file(&partial).await;
+            return Err(anyhow!(
+                "Checksum mismatch: expected {}, got {}",
+                expected,
+                digest
+            ));
+        }
+
+        tokio::fs::rename(&partial, path)
+            .await
+            .context("Failed to finalize download")?;
+        Ok(digest)
+    }
+
+    /// Record the outcome and trigger the follow-up action, if any
+    async fn finish(&self, id: &str, result: Result<String>) {
+        let job = {
+            let mut jobs = self.jobs.write().await;
+            let Some(job) = jobs.get_mut(id) else {
+                return;
+            };
+            match result {
+                Ok(digest) => {
+                    job.status = DownloadStatus::Completed;
+                    job.sha256 = Some(digest);
+                    info!("Download {} completed: {}", id, job.path.display());
+                }
+                Err(e) => {
+                    job.status = DownloadStatus::Failed;
+                    job.error = Some(e.to_string());
+                    warn!("Download {} failed: {}", id, e);
+                }
+            }
+            job.clone()
+        };
+
+        let Some(instruction) = &job.on_complete else {
+            return;
+        };
+        let (Some(db), Some(task_tx)) = (&self.db, &self.task_tx) else {
+            debug!(
+                "Download {} has on_complete but follow-ups are disabled",
+                id
+            );
+            return;
+        };
+
+        let description = match job.status {
+            DownloadStatus::Completed => format!(
+                "{}\n\nThe download of {} finished and was saved to {} ({} bytes, SHA-256 {}).",
+                instruction,
+                job.url,
+                job.path.display(),
+                job.bytes_downloaded,
+                job.sha256.as_deref().unwrap_or("")
+            ),
+            _ => format!(
+                "A download you were waiting on failed, so this follow-up could not run: {}\n\
+                 URL: {}\nError: {}\nLet the user know.",
+                instruction,
+                job.url,
+                job.error.as_deref().unwrap_or("unknown")
+            ),
+        };
+
+        let task_id = format!("t-{}", uuid::Uuid::new_v4());
+        if let Err(e) = db
+            .insert_background_task(&task_id, &description, &job.reply_channel, "download")
+            .await
+        {
+            warn!("Failed to record follow-up task for download {}: {}", id, e);
+            return;
+        }
+        let _ = task_tx
+            .send(BackgroundTaskCommand::Spawn {
+                id: task_id,
+                description,
+                reply_channel: job.reply_channel.clone(),
+                heavy: false,
+            })
+            .await;
+    }
+
+    /// Wait for a download to leave the `Downloading` state
+    async fn wait(&self, id: &str, timeout_secs: u64) -> Option<DownloadJob> {
+        let deadline = tokio::time::Instant::now() + std::time::Duration::from_secs(timeout_secs);
+        loop {
+            let job = self.get(id).await?;
+            if job.status != DownloadStatus::Downloading || tokio::time::Instant::now() >= deadline
+            {
+                return Some(job);
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(200)).await;
+        }
+    }
+}
+
+/// Reduce a filename to a single safe path component
+fn sanitize_filename(name: &str) -> Result<String> {
+    let base = name.rsplit(['/', '\\']).next().unwrap_or("").trim();
+    let cleaned: String = base
+        .chars()
+        .filter(|c| !c.is_control() && !matches!(c, ':' | '*' | '?' | '"' | '<' | '>' | '|'))
+        .collect();
+    if cleaned.is_empty() || cleaned == "." || cleaned == ".." || cleaned.len() > 255 {
+        return Err(anyhow!("Invalid filename '{}'", name));
+    }
+    Ok(cleaned)
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+fn common_schema() -> serde_json::Map<String, Value> {
+    let v = serde_json::json!({
+        "filename": {
+            "type": "string",
+            "description": "Optional file name (default: derived from the URL)"
+        },
+        "sha256": {
+            "type": "string",
+            "description": "Optional expected SHA-256 hex digest; the file is discarded on mismatch"
+        },
+        "on_complete": {
+            "type": "string",
+            "description": "Optional instruction to run when the download finishes (e.g. 'summarize it')"
+        },
+        "reply_channel": {
+            "type": "string",
+            "description": "Channel for the follow-up result (default: 'internal')"
+        },
+        "wait_secs": {
+            "type": "integer",
+            "description": "Seconds to wait for completion before returning (default: 0, max: 120)"
+        }
+    });
+    v.as_object().cloned().unwrap_or_default()
+}
+
+fn request_from_input(url: String, input: &Value) -> DownloadRequest {
+    let s = |k: &str| input.get(k).and_then(|v| v.as_str()).map(String::from);
+    DownloadRequest {
+        url,
+        filename: s("filename"),
+        expected_sha256: s("sha256"),
+        on_complete: s("on_complete"),
+        reply_channel: s("reply_channel").unwrap_or_else(|| "internal".to_string()),
+        headers: Vec::new(),
+    }
+}
+
+async fn start_and_report(
+    manager: &Arc<DownloadManager>,
+    request: DownloadRequest,
+    input: &Value,
+) -> Result<String> {
+    let wait_secs = input
+        .get("wait_secs")
+        .and_then(|v| v.as_u64())
+        .unwrap_or(0)
+        .min(120);
+    let job = manager.start(request).await?;
+    let job = if wait_secs > 0 {
+        manager.wait(&job.id, wait_secs).await.unwrap_or(job)
+    } else {
+        job
+    };
+    let mut out = job.summary();
+    if job.status == DownloadStatus::Downloading {
+        out.push_str("\nUse download_status to check progress.");
+    }
+    Ok(out)
+}
+
+/// Download a file over HTTP(S)
+pub struct DownloadFileTool {
+    manager: Arc<DownloadManager>,
+}
+
+impl DownloadFileTool {
+    pub fn new(manager: Arc<DownloadManager>) -> Self {
+        Self { manager }
+    }
+}
+
+#[async_trait]
+impl ToolHandler for DownloadFileTool {
+    fn name(&self) -> &str {
+        "download_file"
+    }
+
+    fn needs_network(&self) -> bool {
+        true
+    }
+
+    fn description(&self) -> &str {
+        "Download a file from a URL into the download directory. Runs in the background with \
+         progress tracking and optional SHA-256 verification. Set 'on_complete' to have the \
+         agent act on the file once it finishes."
+    }
+
+    fn input_schema(&self) -> Value {
+        let mut props = common_schema();
+        props.insert(
+            "url".to_string(),
+            serde_json::json!({"type": "string", "description": "URL of the file to download"}),
+        );
+        json_schema(Value::Object(props), vec!["url"])
+    }
+
+    async fn execute(&self, input: Value) -> Result<String> {
+        let url = input
+            .get("url")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| anyhow!("Missing 'url' parameter"))?;
+        let request = request_from_input(url.to_string(), &input);
+        start_and_report(&self.manager, request, &input).await
+    }
+}
+
+/// Download a file linked from the current browser page
+pub struct BrowserDownloadTool {
+    manager: Arc<DownloadManager>,
+    provider: Box<dyn BrowserProvider>,
+    tool_name: String,
+}
+
+impl BrowserDownloadTool {
+    pub fn new(
+        prefix: &str,
+        manager: Arc<DownloadManager>,
+        provider: Box<dyn BrowserProvider>,
+    ) -> Self {
+        Self {
+            manager,
+            provider,
+            tool_name: format!("{}_download", prefix),
+        }
+    }
+}
+
+#[async_trait]
+impl ToolHandler for BrowserDownloadTool {
+    fn name(&self) -> &str {
+        &self.tool_name
+    }
+
+    fn needs_network(&self) -> bool {
+        true
+    }
+
+    fn description(&self) -> &str {
+        "Download a file from the browser: pass a 'url' directly or a CSS 'selector' for a link \
+         on the current page. The browser's cookies for that site are reused so logged-in \
+         downloads work. Supports checksum verification and 'on_complete' follow-ups."
+    }
+
+    fn input_schema(&self) -> Value {
+        let mut props = common_schema();
+        props.insert(
+            "url".to_string(),
+            serde_json::json!({"type": "string", "description": "URL to download (alternative to selector)"}),
+        );
+        props.insert(
+            "selector".to_string(),
+            serde_json::json!({"type": "string", "description": "CSS selector of a link whose href to download"}),
+        );
+        props.insert(
+            "tab_id".to_string(),
+            serde_json::json!({"type": "string", "description": "Tab ID (default: active tab)"}),
+        );
+        json_schema(Value::Object(props), vec![])
+    }
+
+    async fn execute(&self, input: Value) -> Result<String> {
+        let tab_id = input.get("tab_id").and_then(|v| v.as_str());
+        let url = match (
+            input.get("url").and_then(|v| v.as_str()),
+            input.get("selector").and_then(|v| v.as_str()),
+        ) {
+            (Some(url), _) => url.to_string(),
+            (None, Some(selector)) => {
+                if selector.len() > 500 {
+                    return Err(anyhow!("Selector too long (max 500 characters)"));
+                }
+                let script = format!(
+                    "(() => {{ const el = document.querySelector({}); \
+                     return el ? (el.href || el.src || '') : ''; }})()",
+                    Value::String(selector.to_string())
+                );
+                let href = self.provider.execute_javascript(tab_id, &script).await?;
+                let href = href.trim().trim_matches('"').to_string();
+                if href.is_empty() {
+                    return Err(anyhow!("No link found for selector '{}'", selector));
+                }
+                href
+            }
+            (None, None) => return Err(anyhow!("Provide either 'url' or 'selector'")),
+        };
+
+        let mut request = request_from_input(url.clone(), &input);
+
+        // Reuse browser cookies for the same host so authenticated downloads work
+        let host = url::Url::parse(&url)
+            .ok()
+            .and_then(|u| u.host_str().map(String::from));
+        if let (Some(host), Ok(cookies)) = (host, self.provider.get_cookies(tab_id).await) {
+            let header: Vec<String> = cookies
+                .iter()
+                .filter(|c| {
+                    let domain = c.domain.trim_start_matches('.');
+                    host == domain || host.ends_with(&format!(".{}", domain))
+                })
+                .map(|c| format!("{}={}", c.name, c.value))
+                .collect();
+            if !header.is_empty() {
+                request
+                    .headers
+                    .push(("Cookie".to_string(), header.join("; ")));
+            }
+        }
+
+        start_and_report(&self.manager, request, &input).await
+    }
+}
+
+/// Check the progress of downloads
+pub struct DownloadStatusTool {
+    manager: Arc<DownloadManager>,
+}
+
+impl DownloadStatusTool {
+    pub fn new(manager: Arc<DownloadManager>) -> Self {
+        Self { manager }
+    }
+}
+
+#[async_trait]
+impl ToolHandler for DownloadStatusTool {
+    fn name(&self) -> &str {
+        "download_status"
+    }
+
+    fn description(&self) -> &str {
+        "Show progress of a download by ID, or list all downloads from this session."
+    }
+
+    fn input_schema(&self) -> Value {
+        json_schema(
+            serde_json::json!({
+                "id": {
+                    "type": "string",
+                    "description": "Download ID (default: list all)"
+                }
+            }),
+            vec![],
+        )
+    }
+
+    async fn execute(&self, input: Value) -> Result<String> {
+        if let Some(id) = input.get("id").and_then(|v| v.as_str()) {
+            return self
+                .manager
+                .get(id)
+                .await
+                .map(|j| j.summary())
+                .ok_or_else(|| anyhow!("Download not found: {}", id));
+        }
+        let jobs = self.manager.list().await;
+        if jobs.is_empty() {
+            return Ok(format!(
+                "No downloads yet. Download directory: {}",
+                self.manager.dir().display()
+            ));
+        }
+        Ok(jobs
+            .iter()
+            .map(|j| j.summary())
+            .collect::<Vec<_>>()
+            .join("\n---\n"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn manager(dir: &TempDir) -> Arc<DownloadManager> {
+        Arc::new(DownloadManager::new(dir.path().to_str().unwrap(), 1024).unwrap())
+    }
+
+    #[test]
+    fn test_sanitize_filename() {
+        assert_eq!(sanitize_filename("report.pdf").unwrap(), "report.pdf");
+        assert_eq!(sanitize_filename("../../etc/passwd").unwrap(), "passwd");
+        assert_eq!(sanitize_filename("a:b?.txt").unwrap(), "ab.txt");
+        assert!(sanitize_filename("..").is_err());
+        assert!(sanitize_filename("dir/").is_err());
+    }
+
+    #[test]
+    fn test_to_hex() {
+        let digest = Sha256::digest(b"abc");
+        assert_eq!(
+            to_hex(&digest),
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+    }
+
+    #[test]
+    fn test_manager_rejects_system_dir() {
+        assert!(DownloadManager::new("/etc/meepo-downloads", 1024).is_err());
+    }
+
+    #[test]
+    fn test_target_path_no_overwrite() {
+        let temp = TempDir::new().unwrap();
+        let mgr = manager(&temp);
+        let p = mgr
+            .target_path("https://example.com/files/report.pdf?x=1", None)
+            .unwrap();
+        assert!(p.ends_with("report.pdf"));
+        assert!(p.exists());
+        let p2 = mgr
+            .target_path("https://example.com/files/report.pdf", None)
+            .unwrap();
+        assert!(p2.ends_with("report-1.pdf"));
+        let p3 = mgr.target_path("https://example.com/", None).unwrap();
+        assert!(p3.ends_with("download"));
+    }
+
+    #[tokio::test]
+    async fn test_start_rejects_unsafe_input() {
+        let temp = TempDir::new().unwrap();
+        let mgr = manager(&temp);
+        let err = mgr
+            .start(DownloadRequest {
+                url: "http://127.0.0.1/file".to_string(),
+                ..Default::default()
+            })
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("not allowed"));
+
+        let err = mgr
+            .start(DownloadRequest {
+                url: "https://93.184.215.14/file".to_string(),
+                expected_sha256: Some("nothex".to_string()),
+                ..Default::default()
+            })
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("64 hex"));
+        assert!(mgr.list().await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_download_tools_schema_and_status() {
+        let temp = TempDir::new().unwrap();
+        let mgr = manager(&temp);
+        let tool = DownloadFileTool::new(mgr.clone());
+        assert_eq!(tool.name(), "download_file");
+        let schema = tool.input_schema();
+        assert!(schema["properties"]["on_complete"].is_object());
+        assert!(tool.execute(serde_json::json!({})).await.is_err());
+
+        let status = DownloadStatusTool::new(mgr);
+        let out = status.execute(serde_json::json!({})).await.unwrap();
+        assert!(out.contains("No downloads yet"));
+        assert!(
+            status
+                .execute(serde_json::json!({"id": "dl-missing"}))
+                .await
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_job_summary_progress() {
+        let job = DownloadJob {
+            id: "dl-1".to_string(),
+            url: "https://example.com/a.zip".to_string(),
+            path: PathBuf::from("/tmp/a.zip"),
+            status: DownloadStatus::Downloading,
+            bytes_downloaded: 50,
+            total_bytes: Some(200),
+            sha256: None,
+            error: None,
+            on_complete: None,
+            reply_channel: "internal".to_string(),
+        };
+        assert!(job.summary().contains("50/200 bytes (25%)"));
+    }
+}
@@ -0,0 +1,552 @@
+//! Generic HTTP request tool with named auth profiles
+//!
+//! Lets the agent call arbitrary REST APIs. Credentials never appear in tool
+//! input: the model picks a named profile and the secret is resolved from the
+//! secrets store at request time, only for hosts the profile is scoped to.
+
+use anyhow::{Context, Result, anyhow};
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::sync::Arc;
+use tracing::{debug, warn};
+
+use super::system::validate_url;
+use super::{ToolHandler, json_schema};
+use crate::secrets::SecretsManager;
+use crate::tool_cost::{CostClass, LatencyClass, ToolCost};
+
+/// Headers the model may not set directly (credentials go through profiles)
+const BLOCKED_HEADERS: &[&str] = &[
+    "authorization",
+    "cookie",
+    "host",
+    "x-forwarded-for",
+    "x-real-ip",
+    "proxy-authorization",
+    "set-cookie",
+];
+
+/// Maximum request body accepted from the model
+const MAX_REQUEST_BODY_BYTES: usize = 1024 * 1024;
+
+/// How an auth profile attaches its secret to a request
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum HttpAuthType {
+    /// `Authorization: Bearer <secret>`
+    #[default]
+    Bearer,
+    /// Custom header, e.g. `X-Api-Key: <secret>`
+    Header,
+    /// Query parameter, e.g. `?api_key=<secret>`
+    Query,
+    /// `Authorization: Basic base64(<secret>)` where secret is `user:password`
+    Basic,
+}
+
+/// A named credential the agent can reference by name
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HttpAuthProfile {
+    #[serde(default)]
+    pub auth_type: HttpAuthType,
+    /// Name of the secret in the secrets store (not the secret itself)
+    pub secret: String,
+    /// Header name for `header` auth or parameter name for `query` auth
+    #[serde(default)]
+    pub name: Option<String>,
+    /// Hosts this profile may be sent to (exact or subdomain match). Required.
+    #[serde(default)]
+    pub domains: Vec<String>,
+}
+
+/// Configuration for the http_request tool
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HttpToolConfig {
+    /// If non-empty, only these hosts (and their subdomains) may be called
+    #[serde(default)]
+    pub allowed_domains: Vec<String>,
+    /// Hosts (and their subdomains) that may never be called
+    #[serde(default)]
+    pub blocked_domains: Vec<String>,
+    #[serde(default = "default_max_response_bytes")]
+    pub max_response_bytes: usize,
+    #[serde(default = "default_timeout_secs")]
+    pub timeout_secs: u64,
+    #[serde(default)]
+    pub profiles: HashMap<String, HttpAuthProfile>,
+}
+
+fn default_max_response_bytes() -> usize {
+    100_000
+}
+
+fn default_timeout_secs() -> u64 {
+    30
+}
+
+impl Default for HttpToolConfig {
+    fn default() -> Self {
+        Self {
+            allowed_domains: Vec::new(),
+            blocked_domains: Vec::new(),
+            max_response_bytes: default_max_response_bytes(),
+            timeout_secs: default_timeout_secs(),
+            profiles: HashMap::new(),
+        }
+    }
+}
+
+/// Check whether `host` equals `domain` or is a subdomain of it.
+/// A leading `*.` on the pattern is accepted and ignored.
+fn host_matches(host: &str, domain: &str) -> bool {
+    let host = host.trim_end_matches('.').to_lowercase();
+    let domain = domain
+        .trim_start_matches("*.")
+        .trim_end_matches('.')
+        .to_lowercase();
+    if domain.is_empty() {
+        return false;
+    }
+    host == domain || host.ends_with(&format!(".{}", domain))
+}
+
+impl HttpToolConfig {
+    /// Enforce the domain allow/block lists for a host
+    fn check_domain(&self, host: &str) -> Result<()> {
+        if self.blocked_domains.iter().any(|d| host_matches(host, d)) {
+            return Err(anyhow!("Domain '{}' is blocked by http policy", host));
+        }
+        if !self.allowed_domains.is_empty()
+            && !self.allowed_domains.iter().any(|d| host_matches(host, d))
+        {
+            return Err(anyhow!(
+                "Domain '{}' is not in the http allowed_domains list",
+                host
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Make an HTTP request to a REST API
+pub struct HttpRequestTool {
+    config: HttpToolConfig,
+    secrets: Arc<SecretsManager>,
+}
+
+impl HttpRequestTool {
+    pub fn new(config: HttpToolConfig, secrets: Arc<SecretsManager>) -> Self {
+        Self { config, secrets }
+    }
+
+    /// Resolve a profile into (header or query) credentials for `host`
+    async fn apply_profile(
+        &self,
+        profile_name: &str,
+        host: &str,
+        mut request: reqwest::RequestBuilder,
+    ) -> Result<reqwest::RequestBuilder> {
+        let profile = self
+            .config
+            .profiles
+            .get(profile_name)
+            .ok_or_else(|| anyhow!("Unknown auth profile '{}'", profile_name))?;
+
+        if !profile.domains.iter().any(|d| host_matches(host, d)) {
+            warn!(
+                "Refusing to send auth profile '{}' to unscoped host '{}'",
+                profile_name, host
+            );
+            return Err(anyhow!(
+                "Auth profile '{}' is not permitted for host '{}'",
+                profile_name,
+                host
+            ));
+        }
+
+        let secret = self
+            .secrets
+            .resolve(&profile.secret)
+            .await?
+            .ok_or_else(|| {
+                anyhow!(
+                    "Secret '{}' for auth profile '{}' not found",
+                    profile.secret,
+                    profile_name
+                )
+            })?;
+
+        request = match profile.auth_type {
+            HttpAuthType::Bearer => request.bearer_auth(secret),
+            HttpAuthType::Header => {
+                let name = profile.name.as_deref().unwrap_or("X-Api-Key");
+                request.header(name, secret)
+            }
+            HttpAuthType::Query => {
+                let name = profile.name.as_deref().unwrap_or("api_key");
+                request.query(&[(name, secret)])
+            }
+            HttpAuthType::Basic => match secret.split_once(':') {
+                Some((user, pass)) => request.basic_auth(user, Some(pass)),
+                None => request.basic_auth(secret, None::<&str>),
+            },
+        };
+        Ok(request)
+    }
+}
+
+#[async_trait]
+impl ToolHandler for HttpRequestTool {
+    fn name(&self) -> &str {
+        "http_request"
+    }
+
+    fn needs_network(&self) -> bool {
+        true
+    }
+
+    fn description(&self) -> &str {
+        "Make an HTTP request (GET, POST, PUT, DELETE) to a REST API. Supports custom headers, \
+         query parameters, and JSON bodies. Use 'auth_profile' to authenticate with a configured \
+         credential instead of passing tokens directly. Returns the status and response body."
+    }
+
+    fn input_schema(&self) -> Value {
+        let mut profiles: Vec<&str> = self.config.profiles.keys().map(|k| k.as_str()).collect();
+        profiles.sort_unstable();
+        json_schema(
+            serde_json::json!({
+                "method": {
+                    "type": "string",
+                    "description": "HTTP method",
+                    "enum": ["GET", "POST", "PUT", "DELETE"]
+                },
+                "url": {
+                    "type": "string",
+                    "description": "Full URL to request (http or https)"
+                },
+                "headers": {
+                    "type": "object",
+                    "description": "Optional request headers (Authorization and Cookie are not allowed; use auth_profile)"
+                },
+                "query": {
+                    "type": "object",
+                    "description": "Optional query parameters"
+                },
+                "json": {
+                    "description": "Optional JSON request body"
+                },
+                "body": {
+                    "type": "string",
+                    "description": "Optional raw request body (ignored if 'json' is set)"
+                },
+                "auth_profile": {
+                    "type": "string",
+                    "description": format!(
+                        "Optional named auth profile. Available: {}",
+                        if profiles.is_empty() { "none".to_string() } else { profiles.join(", ") }
+                    )
+                }
+            }),
+            vec!["method", "url"],
+        )
+    }
+
+    fn cost(&self) -> ToolCost {
+        ToolCost::new(CostClass::Low, LatencyClass::Seconds)
+    }
+
+    async fn execute(&self, input: Value) -> Result<String> {
+        let method = input
+            .get("method")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| anyhow!("Missing 'method' parameter"))?;
+        let url = input
+            .get("url")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| anyhow!("Missing 'url' parameter"))?;
+
+        let method = match method.to_uppercase().as_str() {
+            "GET" => reqwest::Method::GET,
+            "POST" => reqwest::Method::POST,
+            "PUT" => reqwest::Method::PUT,
+            "DELETE" => reqwest::Method::DELETE,
+            other => return Err(anyhow!("Unsupported HTTP method '{}'", other)),
+        };
+
+        let validated = validate_url(url)?;
+        self.config.check_domain(&validated.host)?;
+
+        debug!("http_request: {} {}", method, url);
+
+        // Pin resolved IPs and disable redirects so credentials never follow
+        // a redirect to a host the policy hasn't checked.
+        let mut builder = reqwest::Client::builder()
+            .user_agent("meepo-agent/1.0")
+            .timeout(std::time::Duration::from_secs(self.config.timeout_secs))
+            .redirect(reqwest::redirect::Policy::none());
+        for addr in &validated.resolved_ips {
+            builder = builder.resolve(&validated.host, *addr);
+        }
+        let client = builder.build().context("Failed to create HTTP client")?;
+
+        let mut request = client.request(method.clone(), url);
+
+        if let Some(headers) = input.get("headers").and_then(|v| v.as_object()) {
+            for (key, value) in headers {
+                let Some(value_str) = value.as_str() else {
+                    continue;
+                };
+                if key.contains(['\r', '\n']) || value_str.contains(['\r', '\n']) {
+                    warn!("Skipping header '{}' due to CRLF characters", key);
+                    continue;
+                }
+                if BLOCKED_HEADERS.contains(&key.to_lowercase().as_str()) {
+                    return Err(anyhow!(
+                        "Header '{}' is not allowed; use an auth_profile instead",
+                        key
+                    ));
+                }
+                request = request.header(key, value_str);
+            }
+        }
+
+        if let Some(query) = input.get("query").and_then(|v| v.as_object()) {
+            let pairs: Vec<(String, String)> = query
+                .iter()
+                .map(|(k, v)| {
+                    let v = match v {
+                        Value::String(s) => s.clone(),
+                        other => other.to_string(),
+                    };
+                    (k.clone(), v)
+                })
+                .collect();
+            request = request.query(&pairs);
+        }
+
+        if let Some(json) = input.get("json").filter(|v| !v.is_null()) {
+            if json.to_string().len() > MAX_REQUEST_BODY_BYTES {
+                return Err(anyhow!("Request body too large (max 1MB)"));
+            }
+            request = request.json(json);
+        } else if let Some(body) = input.get("body").and_then(|v| v.as_str()) {
+            if body.len() > MAX_REQUEST_BODY_BYTES {
+                return Err(anyhow!("Request body too large (max 1MB)"));
+            }
+            request = request.body(body.to_string());
+        }
+
+        if let Some(profile) = input.get("auth_profile").and_then(|v| v.as_str()) {
+            request = self
+                .apply_profile(profile, &validated.host, request)
+                .await?;
+        }
+
+        let mut response = request.send().await.context("HTTP request failed")?;
+        let status = response.status();
+
+        let mut output = format!("HTTP {}\n", status);
+        for name in ["content-type", "location", "retry-after"] {
+            if let Some(value) = response.headers().get(name).and_then(|v| v.to_str().ok()) {
+                output.push_str(&format!("{}: {}\n", name, value));
+            }
+        }
+        output.push('\n');
+
+        // Stream the body so oversized responses are never fully buffered
+        let limit = self.config.max_response_bytes;
+        let mut body: Vec<u8> = Vec::new();
+        let mut truncated = false;
+        while let Some(chunk) = response
+            .chunk()
+            .await
+            .context("Failed to read response body")?
+        {
+            let remaining = limit.saturating_sub(body.len());
+            if chunk.len() > remaining {
+                body.extend_from_slice(&chunk[..remaining]);
+                truncated = true;
+                break;
+            }
+            body.extend_from_slice(&chunk);
+        }
+
+        output.push_str(&String::from_utf8_lossy(&body));
+        if truncated {
+            output.push_str(&format!("\n\n[Response truncated at {} bytes]", limit));
+        }
+
+        Ok(output)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::secrets::MemorySecretsProvider;
+
+    fn tool_with(config: HttpToolConfig) -> HttpRequestTool {
+        let mut provider = MemorySecretsProvider::new();
+        provider.set("GITHUB_TOKEN", "ghp_test");
+        HttpRequestTool::new(config, Arc::new(SecretsManager::new(Box::new(provider))))
+    }
+
+    #[test]
+    fn test_host_matches() {
+        assert!(host_matches("api.github.com", "github.com"));
+        assert!(host_matches("github.com", "*.github.com"));
+        assert!(host_matches("API.GitHub.com", "github.com"));
+        assert!(!host_matches("evilgithub.com", "github.com"));
+        assert!(!host_matches("github.com", ""));
+    }
+
+    #[test]
+    fn test_check_domain_policy() {
+        let config = HttpToolConfig {
+            allowed_domains: vec!["github.com".to_string()],
+            blocked_domains: vec!["gist.github.com".to_string()],
+            ..Default::default()
+        };
+        assert!(config.check_domain("api.github.com").is_ok());
+        assert!(config.check_domain("gist.github.com").is_err());
+        assert!(config.check_domain("example.com").is_err());
+        assert!(
+            HttpToolConfig::default()
+                .check_domain("example.com")
+                .is_ok()
+        );
+    }
+
+    #[test]
+    fn test_http_request_schema_lists_profiles() {
+        let mut config = HttpToolConfig::default();
+        config.profiles.insert(
+            "github".to_string(),
+            HttpAuthProfile {
+                auth_type: HttpAuthType::Bearer,
+                secret: "GITHUB_TOKEN".to_string(),
+                name: None,
+                domains: vec!["api.github.com".to_string()],
+            },
+        );
+        let tool = tool_with(config);
+        assert_eq!(tool.name(), "http_request");
+        let schema = tool.input_schema();
+        let desc = schema["properties"]["auth_profile"]["description"]
+            .as_str()
+            .unwrap();
+        assert!(desc.contains("github"));
+        let required = schema["required"].as_array().unwrap();
+        assert_eq!(required.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_http_request_rejects_bad_input() {
+        let tool = tool_with(HttpToolConfig::default());
+        assert!(
+            tool.execute(serde_json::json!({"url": "https://example.com"}))
+                .await
+                .is_err()
+        );
+        let result = tool
+            .execute(serde_json::json!({"method": "PATCH", "url": "https://example.com"}))
+            .await;
+        assert!(result.unwrap_err().to_string().contains("Unsupported"));
+        let result = tool
+            .execute(serde_json::json!({"method": "GET", "url": "http://localhost:8080/"}))
+            .await;
+        assert!(result.unwrap_err().to_string().contains("localhost"));
+        let result = tool
+            .execute(serde_json::json!({"method": "GET", "url": "file:///etc/passwd"}))
+            .await;
+        assert!(result.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_http_request_blocked_domain() {
+        let tool = tool_with(HttpToolConfig {
+            blocked_domains: vec!["93.184.215.14".to_string()],
+            ..Default::default()
+        });
+        let result = tool
+            .execute(serde_json::json!({"method": "GET", "url": "https://93.184.215.14/"}))
+            .await;
+        assert!(result.unwrap_err().to_string().contains("blocked"));
+    }
+
+    #[tokio::test]
+    async fn test_apply_profile_scoping() {
+        let mut config = HttpToolConfig::default();
+        config.profiles.insert(
+            "github".to_string(),
+            HttpAuthProfile {
+                auth_type: HttpAuthType::Header,
+                secret: "GITHUB_TOKEN".to_string(),
+                name: Some("X-Token".to_string()),
+                domains: vec!["github.com".to_string()],
+            },
+        );
+        config.profiles.insert(
+            "missing".to_string(),
+            HttpAuthProfile {
+                auth_type: HttpAuthType::Bearer,
+                secret: "NOPE".to_string(),
+                name: None,
+                domains: vec!["github.com".to_string()],
+            },
+        );
+        let tool = tool_with(config);
+        let client = reqwest::Client::new();
+
+        let req = tool
+            .apply_profile(
+                "github",
+                "api.github.com",
+                client.get("https://api.github.com"),
+            )
+            .await
+            .unwrap()
+            .build()
+            .unwrap();
+        assert_eq!(req.headers()["X-Token"], "ghp_test");
+
+        let err = tool
+            .apply_profile("github", "example.com", client.get("https://example.com"))
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("not permitted"));
+
+        let err = tool
+            .apply_profile("missing", "github.com", client.get("https://github.com"))
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("not found"));
+
+        assert!(
+            tool.apply_profile("unknown", "github.com", client.get("https://github.com"))
+                .await
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_http_tool_config_deserialize() {
+        let config: HttpToolConfig = serde_json::from_value(serde_json::json!({
+            "allowed_domains": ["api.github.com"],
+            "profiles": {
+                "github": {
+                    "auth_type": "query",
+                    "secret": "GITHUB_TOKEN",
+                    "name": "access_token",
+                    "domains": ["api.github.com"]
+                }
+            }
+        }))
+        .unwrap();
+        assert_eq!(config.max_response_bytes, 100_000);
+        assert_eq!(config.profiles["github"].auth_type, HttpAuthType::Query);
+    }
+}
@@ -9,6 +9,7 @@ use serde_json::Value;
 use std::sync::Arc;
 use tracing::debug;
 
+use crate::meeting_prep::MeetingPrep;
 use crate::platform::{CalendarProvider, ContactsProvider, EmailProvider};
 use crate::tools::{ToolHandler, json_schema};
 use meepo_knowledge::KnowledgeDb;
@@ -587,11 +588,82 @@ impl ToolHandler for WeeklyReviewTool {
     }
 }
 
+/// Build a prep brief for an upcoming meeting
+pub struct MeetingPrepTool {
+    prep: MeetingPrep,
+}
+
+impl MeetingPrepTool {
+    pub fn new(prep: MeetingPrep) -> Self {
+        Self { prep }
+    }
+}
+
+#[async_trait]
+impl ToolHandler for MeetingPrepTool {
+    fn name(&self) -> &str {
+        "meeting_prep"
+    }
+
+    fn description(&self) -> &str {
+        "Prepare for an upcoming meeting. Builds a brief for the next calendar event with \
+         attendees (or the one whose title matches 'event'): who is attending, from contacts and \
+         the knowledge graph, the last interactions with them, open action items and relevant \
+         documents."
+    }
+
+    fn input_schema(&self) -> Value {
+        json_schema(
+            serde_json::json!({
+                "event": {
+                    "type": "string",
+                    "description": "Part of the event title (default: the next meeting with attendees)"
+                },
+                "hours_ahead": {
+                    "type": "number",
+                    "description": "How far ahead to look for the meeting (default: 24, max: 168)"
+                }
+            }),
+            vec![],
+        )
+    }
+
+    async fn execute(&self, input: Value) -> Result<String> {
+        let query = input
+            .get("event")
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_lowercase());
+        let hours_ahead = input
+            .get("hours_ahead")
+            .and_then(|v| v.as_u64())
+            .unwrap_or(24)
+            .min(168);
+
+        debug!("Preparing meeting brief (event: {:?})", query);
+        let meetings = self.prep.upcoming_meetings(hours_ahead).await?;
+        let meeting = meetings.iter().find(|e| {
+            query
+                .as_deref()
+                .is_none_or(|q| e.title.to_lowercase().contains(q))
+        });
+        match meeting {
+            Some(event) => self.prep.build_brief(event, chrono::Utc::now()).await,
+            None => Ok(format!(
+                "No meetings with attendees{} in the next {} hours.",
+                query
+                    .map(|q| format!(" matching '{}'", q))
+                    .unwrap_or_default(),
+                hours_ahead
+            )),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
-    #[cfg(any(target_os = "macos", target_os = "windows"))]
+    #[cfg(any(target_os = "macos", target_os = "windows", target_os = "linux"))]
     #[test]
     fn test_find_free_time_schema() {
         let tool = FindFreeTimeTool::new();
@@ -599,7 +671,7 @@ mod tests {
         assert!(!tool.description().is_empty());
     }
 
-    #[cfg(any(target_os = "macos", target_os = "windows"))]
+    #[cfg(any(target_os = "macos", target_os = "windows", target_os = "linux"))]
     #[test]
     fn test_schedule_meeting_schema() {
         let tool = ScheduleMeetingTool::new();
@@ -615,7 +687,7 @@ mod tests {
         assert!(required.contains(&"title".to_string()));
     }
 
-    #[cfg(any(target_os = "macos", target_os = "windows"))]
+    #[cfg(any(target_os = "macos", target_os = "windows", target_os = "linux"))]
     #[test]
     fn test_reschedule_event_schema() {
         let tool = RescheduleEventTool::new();
@@ -625,17 +697,56 @@ mod tests {
     #[cfg(any(target_os = "macos", target_os = "windows"))]
     #[test]
     fn test_daily_briefing_schema() {
-        let db =
-            Arc::new(KnowledgeDb::new(&std::env::temp_dir().join("test_briefing.db")).unwrap());
+        let db = Arc::new(KnowledgeDb::new(std::env::temp_dir().join("test_briefing.db")).unwrap());
         let tool = DailyBriefingTool::new(db);
         assert_eq!(tool.name(), "daily_briefing");
     }
 
-    #[cfg(any(target_os = "macos", target_os = "windows"))]
+    #[cfg(any(target_os = "macos", target_os = "windows", target_os = "linux"))]
     #[test]
     fn test_weekly_review_schema() {
-        let db = Arc::new(KnowledgeDb::new(&std::env::temp_dir().join("test_weekly.db")).unwrap());
+        let db = Arc::new(KnowledgeDb::new(std::env::temp_dir().join("test_weekly.db")).unwrap());
         let tool = WeeklyReviewTool::new(db);
         assert_eq!(tool.name(), "weekly_review");
     }
+
+    #[tokio::test]
+    async fn test_meeting_prep_without_meetings() {
+        struct EmptyCalendar;
+
+        #[async_trait]
+        impl CalendarProvider for EmptyCalendar {
+            async fn read_events(&self, _days_ahead: u64) -> Result<String> {
+                Ok(String::new())
+            }
+            async fn create_event(
+                &self,
+                _summary: &str,
+                _start: &str,
+                _minutes: u64,
+                _location: Option<&str>,
+            ) -> Result<String> {
+                Ok(String::new())
+            }
+            async fn upcoming_events(
+                &self,
+                _hours_ahead: u64,
+            ) -> Result<Vec<crate::platform::CalendarEvent>> {
+                Ok(Vec::new())
+            }
+        }
+
+        let temp = tempfile::TempDir::new().unwrap();
+        let db = Arc::new(KnowledgeDb::new(temp.path().join("test.db")).unwrap());
+        let tool = MeetingPrepTool::new(MeetingPrep::new(db, Arc::new(EmptyCalendar)));
+        assert_eq!(tool.name(), "meeting_prep");
+        let out = tool
+            .execute(serde_json::json!({"event": "Standup", "hours_ahead": 500}))
+            .await
+            .unwrap();
+        assert_eq!(
+            out,
+            "No meetings with attendees matching 'standup' in the next 168 hours."
+        );
+    }
 }
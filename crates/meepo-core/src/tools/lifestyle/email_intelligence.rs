@@ -9,6 +9,7 @@ use serde_json::Value;
 use std::sync::Arc;
 use tracing::debug;
 
+use crate::email_triage::{self, TriageCategory};
 use crate::platform::EmailProvider;
 use crate::tools::{ToolHandler, json_schema};
 use meepo_knowledge::KnowledgeDb;
@@ -382,6 +383,112 @@ impl ToolHandler for EmailUnsubscribeTool {
     }
 }
 
+/// Manage the per-sender rules used by automatic email triage
+pub struct EmailRuleTool {
+    db: Arc<KnowledgeDb>,
+}
+
+impl EmailRuleTool {
+    pub fn new(db: Arc<KnowledgeDb>) -> Self {
+        Self { db }
+    }
+}
+
+#[async_trait]
+impl ToolHandler for EmailRuleTool {
+    fn name(&self) -> &str {
+        "email_rule"
+    }
+
+    fn description(&self) -> &str {
+        "Manage sender rules for automatic email triage. 'set' always files a sender's mail under \
+         a category (urgent, action, fyi, newsletter) — use it when the user corrects how a \
+         message was triaged. 'forget' removes a sender's rule so triage learns it again; 'list' \
+         shows every rule, including ones learned automatically."
+    }
+
+    fn input_schema(&self) -> Value {
+        json_schema(
+            serde_json::json!({
+                "action": {
+                    "type": "string",
+                    "enum": ["set", "forget", "list"],
+                    "description": "What to do"
+                },
+                "sender": {
+                    "type": "string",
+                    "description": "Sender email address (required for set and forget)"
+                },
+                "category": {
+                    "type": "string",
+                    "enum": ["urgent", "action", "fyi", "newsletter"],
+                    "description": "Category for the sender (required for set)"
+                }
+            }),
+            vec!["action"],
+        )
+    }
+
+    async fn execute(&self, input: Value) -> Result<String> {
+        let action = input
+            .get("action")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| anyhow::anyhow!("Missing 'action' parameter"))?;
+        let sender = input.get("sender").and_then(|v| v.as_str());
+
+        match action {
+            "list" => {
+                let rules = email_triage::list_rules(&self.db).await?;
+                if rules.is_empty() {
+                    return Ok("No email sender rules yet.".to_string());
+                }
+                let mut out = format!("Email sender rules ({}):\n", rules.len());
+                for (sender, rule) in rules {
+                    out.push_str(&format!(
+                        "- {} → {} ({})\n",
+                        sender,
+                        rule.category,
+                        if rule.manual {
+                            "set by user".to_string()
+                        } else {
+                            format!("learned, seen {}x", rule.count)
+                        }
+                    ));
+                }
+                Ok(out)
+            }
+            "set" => {
+                let sender = sender.ok_or_else(|| anyhow::anyhow!("Missing 'sender' parameter"))?;
+                let category = input
+                    .get("category")
+                    .and_then(|v| v.as_str())
+                    .and_then(TriageCategory::parse)
+                    .ok_or_else(|| {
+                        anyhow::anyhow!("'category' must be one of urgent, action, fyi, newsletter")
+                    })?;
+                email_triage::set_rule(&self.db, sender, category).await?;
+                Ok(format!(
+                    "Mail from {} will be triaged as {}.",
+                    email_triage::sender_address(sender),
+                    category
+                ))
+            }
+            "forget" => {
+                let sender = sender.ok_or_else(|| anyhow::anyhow!("Missing 'sender' parameter"))?;
+                if email_triage::forget_rule(&self.db, sender).await? {
+                    Ok(format!("Removed the rule for {}.", sender))
+                } else {
+                    Ok(format!("No rule for {}.", sender))
+                }
+            }
+            other => Err(anyhow::anyhow!(
+                "Unknown action '{}' (expected set, forget or list)",
+                other
+            )),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -429,4 +536,36 @@ mod tests {
         let tool = EmailUnsubscribeTool::new();
         assert_eq!(tool.name(), "email_unsubscribe");
     }
+
+    #[tokio::test]
+    async fn test_email_rule_set_list_forget() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let db = Arc::new(KnowledgeDb::new(temp.path().join("test.db")).unwrap());
+        let tool = EmailRuleTool::new(db);
+
+        let out = tool
+            .execute(serde_json::json!({
+                "action": "set", "sender": "News <digest@example.com>", "category": "newsletter"
+            }))
+            .await
+            .unwrap();
+        assert!(out.contains("digest@example.com will be triaged as newsletter"));
+        let list = tool
+            .execute(serde_json::json!({"action": "list"}))
+            .await
+            .unwrap();
+        assert!(list.contains("digest@example.com → newsletter (set by user)"));
+        assert!(
+            tool.execute(
+                serde_json::json!({"action": "set", "sender": "a@b.c", "category": "spam"})
+            )
+            .await
+            .is_err()
+        );
+        let out = tool
+            .execute(serde_json::json!({"action": "forget", "sender": "digest@example.com"}))
+            .await
+            .unwrap();
+        assert!(out.starts_with("Removed"));
+    }
 }
@@ -247,6 +247,10 @@ impl ToolHandler for SummarizeArticleTool {
         "summarize_article"
     }
 
+    fn needs_network(&self) -> bool {
+        true
+    }
+
     fn description(&self) -> &str {
         "Fetch and summarize an article from a URL. Extracts the main content, generates a \
          concise summary with key takeaways, and stores it in the knowledge graph for future \
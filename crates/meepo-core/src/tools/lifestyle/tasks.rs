@@ -399,11 +399,9 @@ impl ToolHandler for UpdateTaskTool {
         }
         metadata["updated_at"] = serde_json::json!(chrono::Utc::now().to_rfc3339());
 
-        // Update entity in knowledge graph (insert new version)
-        let _ = self
-            .db
-            .insert_entity(&entity.name, "task", Some(metadata.clone()))
-            .await;
+        self.db
+            .update_entity_metadata(&entity.id, metadata.clone())
+            .await?;
 
         Ok(format!(
             "Task updated: {} ({})\n{}",
@@ -481,10 +479,7 @@ impl ToolHandler for CompleteTaskTool {
             metadata["completion_note"] = serde_json::json!(n);
         }
 
-        let _ = self
-            .db
-            .insert_entity(&entity.name, "task", Some(metadata))
-            .await;
+        self.db.update_entity_metadata(&entity.id, metadata).await?;
 
         Ok(format!("Task completed: {} ({})", entity.name, entity.id))
     }
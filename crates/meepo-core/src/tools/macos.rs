@@ -3,13 +3,19 @@
 //! These tools delegate to platform-specific implementations through the platform module.
 //! On macOS: AppleScript-based implementations.
 //! On Windows: PowerShell/COM-based implementations.
+//! On Linux: D-Bus and desktop command-line tools (calendar, notifications,
+//! screen capture and clipboard only).
+
+use std::sync::Arc;
 
 use anyhow::Result;
 use async_trait::async_trait;
+use meepo_knowledge::KnowledgeDb;
 use serde_json::Value;
-use tracing::debug;
+use tracing::{debug, warn};
 
 use super::{ToolHandler, json_schema};
+use crate::calendar_conflicts::{self, ConflictCheck};
 use crate::platform::{
     AppLauncher, CalendarProvider, ClipboardProvider, ContactsProvider, EmailProvider,
     MusicProvider, NotesProvider, NotificationProvider, RemindersProvider, ScreenCaptureProvider,
@@ -227,6 +233,10 @@ impl ToolHandler for SendEmailTool {
 /// Create a calendar event in the default calendar application
 pub struct CreateEventTool {
     provider: Box<dyn CalendarProvider>,
+    /// Where created events are recorded for the ICS feed
+    db: Option<Arc<KnowledgeDb>>,
+    /// Clash and travel-time checks before booking, when enabled
+    conflicts: Option<ConflictCheck>,
 }
 
 impl Default for CreateEventTool {
@@ -240,8 +250,22 @@ impl CreateEventTool {
         Self {
             provider: crate::platform::create_calendar_provider()
                 .expect("Calendar provider not available on this platform"),
+            db: None,
+            conflicts: None,
         }
     }
+
+    /// Record created events so they're published in the calendar feed
+    pub fn with_db(mut self, db: Arc<KnowledgeDb>) -> Self {
+        self.db = Some(db);
+        self
+    }
+
+    /// Refuse to double-book: check for overlaps and travel time first
+    pub fn with_conflict_check(mut self, check: ConflictCheck) -> Self {
+        self.conflicts = Some(check);
+        self
+    }
 }
 
 #[async_trait]
@@ -251,7 +275,9 @@ impl ToolHandler for CreateEventTool {
     }
 
     fn description(&self) -> &str {
-        "Create a new calendar event."
+        "Create a new calendar event. Events that overlap others, or leave too little time \
+         to travel between locations, aren't created; you get the clashes and free \
+         alternatives to offer the user instead."
     }
 
     fn input_schema(&self) -> Value {
@@ -268,6 +294,14 @@ impl ToolHandler for CreateEventTool {
                 "duration_minutes": {
                     "type": "number",
                     "description": "Duration in minutes (default: 60)"
+                },
+                "location": {
+                    "type": "string",
+                    "description": "Where the event takes place (used for travel time)"
+                },
+                "allow_conflicts": {
+                    "type": "boolean",
+                    "description": "Create it even if it clashes — only after the user agreed (default: false)"
                 }
             }),
             vec!["summary", "start_time"],
@@ -287,11 +321,47 @@ impl ToolHandler for CreateEventTool {
             .get("duration_minutes")
             .and_then(|v| v.as_u64())
             .unwrap_or(60);
+        let location = input
+            .get("location")
+            .and_then(|v| v.as_str())
+            .map(str::trim)
+            .filter(|l| !l.is_empty());
+        let allow_conflicts = input
+            .get("allow_conflicts")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false);
+
+        if let Some(check) = &self.conflicts
+            && !allow_conflicts
+        {
+            match calendar_conflicts::review(
+                self.provider.as_ref(),
+                check,
+                summary,
+                start_time,
+                duration,
+                location,
+            )
+            .await
+            {
+                Ok(Some(clashes)) => return Ok(clashes),
+                Ok(None) => {}
+                Err(e) => warn!("Couldn't check '{}' for conflicts: {}", summary, e),
+            }
+        }
 
         debug!("Creating calendar event: {}", summary);
-        self.provider
-            .create_event(summary, start_time, duration)
-            .await
+        let result = self
+            .provider
+            .create_event(summary, start_time, duration, location)
+            .await?;
+        if let Some(db) = &self.db
+            && let Err(e) =
+                crate::calendar_feed::record_event(db, summary, start_time, duration).await
+        {
+            warn!("Failed to record event for the calendar feed: {}", e);
+        }
+        Ok(result)
     }
 }
 
@@ -650,7 +720,7 @@ impl ToolHandler for CreateNoteTool {
     }
 }
 
-/// Send a macOS notification
+/// Send a desktop notification
 pub struct SendNotificationTool {
     provider: Box<dyn NotificationProvider>,
 }
@@ -677,7 +747,7 @@ impl ToolHandler for SendNotificationTool {
     }
 
     fn description(&self) -> &str {
-        "Send a macOS system notification with title and message."
+        "Send a desktop notification with title and message."
     }
 
     fn input_schema(&self) -> Value {
@@ -939,6 +1009,7 @@ mod tests {
     use super::*;
     use crate::tools::ToolHandler;
 
+    #[cfg(any(target_os = "macos", target_os = "windows"))]
     #[test]
     fn test_read_emails_schema() {
         let tool = ReadEmailsTool::new();
@@ -955,6 +1026,7 @@ mod tests {
         assert!(!tool.description().is_empty());
     }
 
+    #[cfg(any(target_os = "macos", target_os = "windows"))]
     #[test]
     fn test_send_email_schema() {
         let tool = SendEmailTool::new();
@@ -1002,6 +1074,7 @@ mod tests {
         assert_eq!(tool.name(), "get_clipboard");
     }
 
+    #[cfg(any(target_os = "macos", target_os = "windows"))]
     #[tokio::test]
     async fn test_send_email_missing_params() {
         let tool = SendEmailTool::new();
@@ -1111,7 +1184,7 @@ mod tests {
     }
 
     // --- Notifications ---
-    #[cfg(target_os = "macos")]
+    #[cfg(any(target_os = "macos", target_os = "linux"))]
     #[test]
     fn test_send_notification_schema() {
         let tool = SendNotificationTool::new();
@@ -1128,7 +1201,7 @@ mod tests {
         assert!(required.contains(&"message".to_string()));
     }
 
-    #[cfg(target_os = "macos")]
+    #[cfg(any(target_os = "macos", target_os = "linux"))]
     #[tokio::test]
     async fn test_send_notification_missing_params() {
         let tool = SendNotificationTool::new();
@@ -1136,7 +1209,7 @@ mod tests {
         assert!(result.is_err());
     }
 
-    #[cfg(target_os = "macos")]
+    #[cfg(any(target_os = "macos", target_os = "linux"))]
     #[tokio::test]
     async fn test_send_notification_title_too_long() {
         let tool = SendNotificationTool::new();
@@ -1151,7 +1224,7 @@ mod tests {
     }
 
     // --- Screen Capture ---
-    #[cfg(target_os = "macos")]
+    #[cfg(any(target_os = "macos", target_os = "linux"))]
     #[test]
     fn test_screen_capture_schema() {
         let tool = ScreenCaptureTool::new();
@@ -1159,7 +1232,7 @@ mod tests {
         assert!(!tool.description().is_empty());
     }
 
-    #[cfg(target_os = "macos")]
+    #[cfg(any(target_os = "macos", target_os = "linux"))]
     #[tokio::test]
     async fn test_screen_capture_invalid_extension() {
         let tool = ScreenCaptureTool::new();
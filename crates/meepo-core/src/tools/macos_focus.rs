@@ -0,0 +1,140 @@
+//! macOS Focus mode tools — read and switch Focus modes
+
+use anyhow::Result;
+use async_trait::async_trait;
+use serde_json::Value;
+use tracing::debug;
+
+use super::{ToolHandler, json_schema};
+use crate::platform::FocusProvider;
+
+pub struct GetFocusTool {
+    provider: Box<dyn FocusProvider>,
+}
+
+impl Default for GetFocusTool {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl GetFocusTool {
+    pub fn new() -> Self {
+        Self {
+            provider: crate::platform::create_focus_provider()
+                .expect("Focus provider not available on this platform"),
+        }
+    }
+}
+
+#[async_trait]
+impl ToolHandler for GetFocusTool {
+    fn name(&self) -> &str {
+        "get_focus"
+    }
+
+    fn description(&self) -> &str {
+        "Show the active macOS Focus mode (Do Not Disturb, Work, etc.) and the Focus modes \
+         set up on this Mac."
+    }
+
+    fn input_schema(&self) -> Value {
+        json_schema(serde_json::json!({}), vec![])
+    }
+
+    async fn execute(&self, _input: Value) -> Result<String> {
+        debug!("Reading Focus mode");
+        let current = self.provider.current_focus().await?;
+        let modes = self.provider.list_focus_modes().await.unwrap_or_default();
+        let mut out = match current {
+            Some(mode) => format!("Focus: {}", mode),
+            None => "Focus: off".to_string(),
+        };
+        if !modes.is_empty() {
+            out.push_str(&format!("\nAvailable: {}", modes.join(", ")));
+        }
+        Ok(out)
+    }
+}
+
+pub struct SetFocusTool {
+    provider: Box<dyn FocusProvider>,
+}
+
+impl Default for SetFocusTool {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SetFocusTool {
+    pub fn new() -> Self {
+        Self {
+            provider: crate::platform::create_focus_provider()
+                .expect("Focus provider not available on this platform"),
+        }
+    }
+}
+
+#[async_trait]
+impl ToolHandler for SetFocusTool {
+    fn name(&self) -> &str {
+        "set_focus"
+    }
+
+    fn description(&self) -> &str {
+        "Turn a macOS Focus mode on (e.g. \"Deep Work\" when starting a writing session) or \
+         turn Focus off. Relies on a Shortcut named \"Meepo Focus <Mode>\" (or \"Meepo Focus \
+         Off\") that sets the Focus."
+    }
+
+    fn input_schema(&self) -> Value {
+        json_schema(
+            serde_json::json!({
+                "mode": {
+                    "type": "string",
+                    "description": "Focus mode to turn on, or \"off\" to turn Focus off"
+                }
+            }),
+            vec!["mode"],
+        )
+    }
+
+    async fn execute(&self, input: Value) -> Result<String> {
+        let mode = input
+            .get("mode")
+            .and_then(|v| v.as_str())
+            .map(str::trim)
+            .filter(|m| !m.is_empty())
+            .ok_or_else(|| anyhow::anyhow!("Missing 'mode' parameter"))?;
+        debug!("Setting Focus mode: {}", mode);
+        if mode.eq_ignore_ascii_case("off") {
+            self.provider.set_focus(None).await
+        } else {
+            self.provider.set_focus(Some(mode)).await
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tools::ToolHandler;
+
+    #[cfg(target_os = "macos")]
+    #[test]
+    fn test_focus_tool_schemas() {
+        assert_eq!(GetFocusTool::new().name(), "get_focus");
+        let tool = SetFocusTool::new();
+        assert_eq!(tool.name(), "set_focus");
+        let schema = tool.input_schema();
+        assert_eq!(schema["required"], serde_json::json!(["mode"]));
+    }
+
+    #[cfg(target_os = "macos")]
+    #[tokio::test]
+    async fn test_set_focus_missing_mode() {
+        let tool = SetFocusTool::new();
+        assert!(tool.execute(serde_json::json!({})).await.is_err());
+    }
+}
@@ -7,16 +7,50 @@ use std::sync::Arc;
 use tracing::debug;
 
 use super::{ToolHandler, json_schema};
-use meepo_knowledge::{KnowledgeDb, KnowledgeGraph};
+use crate::knowledge_gaps;
+use crate::memory_review::{self, StagedMemory};
+use meepo_knowledge::tagging::normalize_tag;
+use meepo_knowledge::{KnowledgeDb, KnowledgeGraph, NewEntity, NewRelationship};
+
+/// Reply to the agent when memories are held for review instead of stored
+fn staged_reply(staged: &StagedMemory) -> String {
+    format!(
+        "Held for the user's review as {} ({} item(s)); it's stored once they approve it. \
+         Don't tell them it's remembered yet.",
+        staged.id,
+        staged.entities.len() + staged.relationships.len()
+    )
+}
+
+/// Refuse names the user asked to forget, so the agent doesn't learn them
+/// again from old conversations
+async fn refuse_forgotten(db: &KnowledgeDb, names: &[&str]) -> Result<()> {
+    for name in names {
+        if db.is_forgotten(name).await? {
+            return Err(anyhow::anyhow!(
+                "The user asked to forget '{}'; it won't be remembered again",
+                name
+            ));
+        }
+    }
+    Ok(())
+}
 
 /// Remember information by adding to knowledge graph
 pub struct RememberTool {
     db: Arc<KnowledgeDb>,
+    review: bool,
 }
 
 impl RememberTool {
     pub fn new(db: Arc<KnowledgeDb>) -> Self {
-        Self { db }
+        Self { db, review: false }
+    }
+
+    /// Stage new memories in the review queue instead of storing them
+    pub fn with_review(mut self, review: bool) -> Self {
+        self.review = review;
+        self
     }
 }
 
@@ -26,6 +60,10 @@ impl ToolHandler for RememberTool {
         "remember"
     }
 
+    fn reads_personal_knowledge(&self) -> bool {
+        true
+    }
+
     fn description(&self) -> &str {
         "Remember important information by storing it in the knowledge graph. \
          Creates an entity with a name, type, and optional metadata."
@@ -44,7 +82,9 @@ impl ToolHandler for RememberTool {
                 },
                 "metadata": {
                     "type": "object",
-                    "description": "Additional structured information about this entity"
+                    "description": "Additional structured information about this entity. \
+                        Include \"aliases\": [...] for other names it goes by, so searches \
+                        for any of them find it."
                 }
             }),
             vec!["name", "entity_type"],
@@ -64,6 +104,18 @@ impl ToolHandler for RememberTool {
 
         debug!("Remembering: {} (type: {})", name, entity_type);
 
+        refuse_forgotten(&self.db, &[name]).await?;
+
+        if self.review {
+            let entity = NewEntity {
+                name: name.to_string(),
+                entity_type: entity_type.to_string(),
+                metadata,
+            };
+            let staged = memory_review::stage(&self.db, self.name(), vec![entity], vec![]).await?;
+            return Ok(staged_reply(&staged));
+        }
+
         let entity_id = self
             .db
             .insert_entity(name, entity_type, metadata)
@@ -91,9 +143,14 @@ impl ToolHandler for RecallTool {
         "recall"
     }
 
+    fn reads_personal_knowledge(&self) -> bool {
+        true
+    }
+
     fn description(&self) -> &str {
         "Search the knowledge graph for previously stored information. \
-         Returns matching entities based on name or type."
+         Returns matching entities based on name or type. Pass a tag (see list_tags) \
+         to get everything about a topic, including past messages."
     }
 
     fn input_schema(&self) -> Value {
@@ -106,28 +163,45 @@ impl ToolHandler for RecallTool {
                 "entity_type": {
                     "type": "string",
                     "description": "Optional: filter by entity type"
+                },
+                "tag": {
+                    "type": "string",
+                    "description": "Optional: only items tagged with this topic (e.g. 'kitchen-renovation')"
                 }
             }),
-            vec!["query"],
+            vec![],
         )
     }
 
     async fn execute(&self, input: Value) -> Result<String> {
-        let query = input
-            .get("query")
-            .and_then(|v| v.as_str())
-            .ok_or_else(|| anyhow::anyhow!("Missing 'query' parameter"))?;
+        let query = input.get("query").and_then(|v| v.as_str()).unwrap_or("");
         let entity_type = input.get("entity_type").and_then(|v| v.as_str());
+        let tag = input
+            .get("tag")
+            .and_then(|v| v.as_str())
+            .map(normalize_tag)
+            .filter(|t| !t.is_empty());
+        if query.is_empty() && tag.is_none() {
+            return Err(anyhow::anyhow!("Provide a 'query' or a 'tag'"));
+        }
 
-        debug!("Searching knowledge graph for: {}", query);
+        debug!("Searching knowledge graph for: {} (tag: {:?})", query, tag);
 
         let results = self
             .db
-            .search_entities(query, entity_type)
+            .search_entities_tagged(query, entity_type, tag.as_deref())
             .await
             .context("Failed to search entities")?;
+        let conversations = match &tag {
+            Some(tag) => self
+                .db
+                .get_conversations_by_tag(tag, 10)
+                .await
+                .context("Failed to search conversations")?,
+            None => Vec::new(),
+        };
 
-        if results.is_empty() {
+        if results.is_empty() && conversations.is_empty() {
             return Ok("No matching information found.".to_string());
         }
 
@@ -139,6 +213,18 @@ impl ToolHandler for RecallTool {
             }
             output.push('\n');
         }
+        if !conversations.is_empty() {
+            output.push_str(&format!("\n{} related message(s):\n", conversations.len()));
+            for convo in &conversations {
+                output.push_str(&format!(
+                    "- [{}] {} on {}: {}\n",
+                    convo.created_at.format("%Y-%m-%d"),
+                    convo.sender,
+                    convo.channel,
+                    convo.content
+                ));
+            }
+        }
 
         Ok(output)
     }
@@ -147,11 +233,18 @@ impl ToolHandler for RecallTool {
 /// Link entities together in knowledge graph
 pub struct LinkEntitiesTool {
     db: Arc<KnowledgeDb>,
+    review: bool,
 }
 
 impl LinkEntitiesTool {
     pub fn new(db: Arc<KnowledgeDb>) -> Self {
-        Self { db }
+        Self { db, review: false }
+    }
+
+    /// Stage new relationships in the review queue instead of storing them
+    pub fn with_review(mut self, review: bool) -> Self {
+        self.review = review;
+        self
     }
 }
 
@@ -161,6 +254,10 @@ impl ToolHandler for LinkEntitiesTool {
         "link_entities"
     }
 
+    fn reads_personal_knowledge(&self) -> bool {
+        true
+    }
+
     fn description(&self) -> &str {
         "Create a relationship between two entities in the knowledge graph. \
          Useful for building connections between concepts, people, facts, etc."
@@ -207,6 +304,18 @@ impl ToolHandler for LinkEntitiesTool {
 
         debug!("Linking {} -> {} ({})", source_id, target_id, relation_type);
 
+        if self.review {
+            let relationship = NewRelationship {
+                source: source_id.to_string(),
+                target: target_id.to_string(),
+                relation_type: relation_type.to_string(),
+                metadata,
+            };
+            let staged =
+                memory_review::stage(&self.db, self.name(), vec![], vec![relationship]).await?;
+            return Ok(staged_reply(&staged));
+        }
+
         let rel_id = self
             .db
             .insert_relationship(source_id, target_id, relation_type, metadata)
@@ -217,6 +326,548 @@ impl ToolHandler for LinkEntitiesTool {
     }
 }
 
+/// Most entities plus relationships accepted in one `remember_many` call
+const MAX_BATCH: usize = 100;
+
+/// Remember many entities and their relationships in one call
+pub struct RememberManyTool {
+    graph: Arc<KnowledgeGraph>,
+    review: bool,
+}
+
+impl RememberManyTool {
+    pub fn new(graph: Arc<KnowledgeGraph>) -> Self {
+        Self {
+            graph,
+            review: false,
+        }
+    }
+
+    /// Stage batches in the review queue instead of storing them
+    pub fn with_review(mut self, review: bool) -> Self {
+        self.review = review;
+        self
+    }
+}
+
+#[async_trait]
+impl ToolHandler for RememberManyTool {
+    fn name(&self) -> &str {
+        "remember_many"
+    }
+
+    fn reads_personal_knowledge(&self) -> bool {
+        true
+    }
+
+    fn description(&self) -> &str {
+        "Remember several entities and the relationships between them in one call, \
+         e.g. everything learned from a meeting. All-or-nothing: if any relationship \
+         can't be resolved, nothing is stored. Relationship endpoints can be the name \
+         of an entity in the same call or the ID of an existing entity."
+    }
+
+    fn input_schema(&self) -> Value {
+        json_schema(
+            serde_json::json!({
+                "entities": {
+                    "type": "array",
+                    "description": "Entities to create",
+                    "items": {
+                        "type": "object",
+                        "properties": {
+                            "name": { "type": "string" },
+                            "entity_type": {
+                                "type": "string",
+                                "description": "e.g. 'person', 'concept', 'fact', 'preference'"
+                            },
+                            "metadata": { "type": "object" }
+                        },
+                        "required": ["name", "entity_type"]
+                    }
+                },
+                "relationships": {
+                    "type": "array",
+                    "description": "Relationships to create",
+                    "items": {
+                        "type": "object",
+                        "properties": {
+                            "source": {
+                                "type": "string",
+                                "description": "Entity name from this call, or an existing entity ID"
+                            },
+                            "target": {
+                                "type": "string",
+                                "description": "Entity name from this call, or an existing entity ID"
+                            },
+                            "relation_type": {
+                                "type": "string",
+                                "description": "e.g. 'works_with', 'part_of', 'owns'"
+                            },
+                            "metadata": { "type": "object" }
+                        },
+                        "required": ["source", "target", "relation_type"]
+                    }
+                }
+            }),
+            vec!["entities"],
+        )
+    }
+
+    async fn execute(&self, input: Value) -> Result<String> {
+        let entities: Vec<NewEntity> = match input.get("entities") {
+            Some(v) => serde_json::from_value(v.clone()).context("Invalid 'entities' parameter")?,
+            None => return Err(anyhow::anyhow!("Missing 'entities' parameter")),
+        };
+        let relationships: Vec<NewRelationship> = match input.get("relationships") {
+            Some(v) => {
+                serde_json::from_value(v.clone()).context("Invalid 'relationships' parameter")?
+            }
+            None => Vec::new(),
+        };
+        if entities.is_empty() && relationships.is_empty() {
+            return Err(anyhow::anyhow!("Nothing to remember"));
+        }
+        if entities.len() + relationships.len() > MAX_BATCH {
+            return Err(anyhow::anyhow!(
+                "Too many items ({}); split into batches of at most {}",
+                entities.len() + relationships.len(),
+                MAX_BATCH
+            ));
+        }
+
+        debug!(
+            "Remembering {} entities and {} relationships",
+            entities.len(),
+            relationships.len()
+        );
+
+        let names: Vec<&str> = entities.iter().map(|e| e.name.as_str()).collect();
+        refuse_forgotten(&self.graph.db(), &names).await?;
+
+        if self.review {
+            let staged =
+                memory_review::stage(&self.graph.db(), self.name(), entities, relationships)
+                    .await?;
+            return Ok(staged_reply(&staged));
+        }
+
+        let batch = self
+            .graph
+            .remember_many(entities, relationships)
+            .await
+            .context("Failed to remember batch")?;
+
+        let mut output = format!(
+            "Remembered {} entities and {} relationships:\n",
+            batch.entities.len(),
+            batch.relationships.len()
+        );
+        for entity in &batch.entities {
+            output.push_str(&format!(
+                "- {} ({}): {}\n",
+                entity.name, entity.entity_type, entity.id
+            ));
+        }
+        Ok(output)
+    }
+}
+
+/// Add, remove, list or suggest other names for entities
+pub struct ManageAliasesTool {
+    graph: Arc<KnowledgeGraph>,
+}
+
+impl ManageAliasesTool {
+    pub fn new(graph: Arc<KnowledgeGraph>) -> Self {
+        Self { graph }
+    }
+}
+
+#[async_trait]
+impl ToolHandler for ManageAliasesTool {
+    fn name(&self) -> &str {
+        "manage_aliases"
+    }
+
+    fn reads_personal_knowledge(&self) -> bool {
+        true
+    }
+
+    fn description(&self) -> &str {
+        "Manage other names entities go by (nicknames, abbreviations: 'Liz' for \
+         Elizabeth Chen, 'K8s' for Kubernetes) so searches and lookups find them. \
+         Actions: add, remove, list, or suggest (aliases that keep showing up in recent \
+         messages)."
+    }
+
+    fn input_schema(&self) -> Value {
+        json_schema(
+            serde_json::json!({
+                "action": {
+                    "type": "string",
+                    "enum": ["add", "remove", "list", "suggest"],
+                    "description": "What to do"
+                },
+                "entity": {
+                    "type": "string",
+                    "description": "Entity name, alias or ID (add, remove, list)"
+                },
+                "alias": {
+                    "type": "string",
+                    "description": "The other name (add, remove)"
+                },
+                "min_mentions": {
+                    "type": "number",
+                    "description": "For suggest: mentions needed in recent messages (default: 3)"
+                }
+            }),
+            vec!["action"],
+        )
+    }
+
+    async fn execute(&self, input: Value) -> Result<String> {
+        let action = input
+            .get("action")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| anyhow::anyhow!("Missing 'action' parameter"))?;
+        let db = self.graph.db();
+
+        if action == "suggest" {
+            let min_mentions = input
+                .get("min_mentions")
+                .and_then(|v| v.as_u64())
+                .unwrap_or(3) as usize;
+            let suggestions = meepo_knowledge::aliases::suggest_aliases(&db, min_mentions)
+                .await
+                .context("Failed to suggest aliases")?;
+            if suggestions.is_empty() {
+                return Ok("No alias suggestions right now.".to_string());
+            }
+            let mut output = format!("{} suggested aliases:\n", suggestions.len());
+            for s in suggestions.iter().take(20) {
+                output.push_str(&format!(
+                    "- '{}' for {} ({}, mentioned {} times)\n",
+                    s.alias, s.entity_name, s.reason, s.mentions
+                ));
+            }
+            return Ok(output);
+        }
+
+        let name = input
+            .get("entity")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| anyhow::anyhow!("Missing 'entity' parameter"))?;
+        let entity = db
+            .resolve_entity(name)
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("No entity named '{}'", name))?;
+        let alias = || {
+            input
+                .get("alias")
+                .and_then(|v| v.as_str())
+                .ok_or_else(|| anyhow::anyhow!("Missing 'alias' parameter"))
+        };
+
+        debug!("Aliases for {}: {}", entity.id, action);
+
+        match action {
+            "add" => {
+                let alias = alias()?;
+                if self.graph.add_alias(&entity.id, alias).await? {
+                    Ok(format!("'{}' is now an alias for {}", alias, entity.name))
+                } else {
+                    Ok(format!("{} already goes by '{}'", entity.name, alias))
+                }
+            }
+            "remove" => {
+                let alias = alias()?;
+                if self.graph.remove_alias(&entity.id, alias).await? {
+                    Ok(format!("Removed alias '{}' from {}", alias, entity.name))
+                } else {
+                    Ok(format!("{} has no alias '{}'", entity.name, alias))
+                }
+            }
+            "list" if entity.aliases.is_empty() => Ok(format!("{} has no aliases", entity.name)),
+            "list" => Ok(format!(
+                "{} also goes by: {}",
+                entity.name,
+                entity.aliases.join(", ")
+            )),
+            other => Err(anyhow::anyhow!(
+                "Unknown action '{}' (use add, remove, list or suggest)",
+                other
+            )),
+        }
+    }
+}
+
+/// Forget entities, after the user has confirmed what will be removed
+pub struct ForgetTool {
+    graph: Arc<KnowledgeGraph>,
+}
+
+impl ForgetTool {
+    pub fn new(graph: Arc<KnowledgeGraph>) -> Self {
+        Self { graph }
+    }
+
+    /// What forgetting the given entities would remove, for the user to confirm
+    async fn preview(&self, entities: &[meepo_knowledge::Entity]) -> Result<String> {
+        let db = self.graph.db();
+        let mut output = format!("This would forget {} item(s):\n", entities.len());
+        for entity in entities {
+            output.push_str(&format!(
+                "- {} ({}), id: {}",
+                entity.name, entity.entity_type, entity.id
+            ));
+            if entity.entity_type == "document" {
+                let chunks = db.get_document_chunks(&entity.id).await?.len();
+                output.push_str(&format!(", with its {} chunk(s)", chunks));
+            }
+            let relationships = db.get_relationships_for(&entity.id).await?.len();
+            if relationships > 0 {
+                output.push_str(&format!(", {} relationship(s)", relationships));
+            }
+            output.push('\n');
+        }
+        output.push_str(
+            "\nNothing has been deleted. Show the user this list and ask them to confirm; \
+             then call forget again with confirm=true and these entity_ids.",
+        );
+        Ok(output)
+    }
+}
+
+#[async_trait]
+impl ToolHandler for ForgetTool {
+    fn name(&self) -> &str {
+        "forget"
+    }
+
+    fn reads_personal_knowledge(&self) -> bool {
+        true
+    }
+
+    fn description(&self) -> &str {
+        "Permanently delete memories: entities with their relationships, document chunks \
+         and search entries, so they aren't learned again from old messages. Use when the \
+         user asks you to forget something. First call it with a query (or entity_ids) to \
+         see what would be removed and confirm that with the user; only then call it with \
+         confirm=true and the entity_ids they agreed to."
+    }
+
+    fn input_schema(&self) -> Value {
+        json_schema(
+            serde_json::json!({
+                "query": {
+                    "type": "string",
+                    "description": "Entity ID, name or alias, or a search for what to forget"
+                },
+                "entity_ids": {
+                    "type": "array",
+                    "items": {"type": "string"},
+                    "description": "Exact entities to forget (from a previous preview)"
+                },
+                "confirm": {
+                    "type": "boolean",
+                    "description": "Delete now; only after the user confirmed the preview"
+                },
+                "limit": {
+                    "type": "number",
+                    "description": "Maximum search matches to consider (default: 10)"
+                }
+            }),
+            vec![],
+        )
+    }
+
+    async fn execute(&self, input: Value) -> Result<String> {
+        let query = input
+            .get("query")
+            .and_then(|v| v.as_str())
+            .map(str::trim)
+            .filter(|q| !q.is_empty());
+        let ids: Vec<String> = input
+            .get("entity_ids")
+            .and_then(|v| v.as_array())
+            .map(|ids| {
+                ids.iter()
+                    .filter_map(|id| id.as_str().map(String::from))
+                    .collect()
+            })
+            .unwrap_or_default();
+        let confirm = input
+            .get("confirm")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false);
+        let limit = input.get("limit").and_then(|v| v.as_u64()).unwrap_or(10) as usize;
+
+        if confirm {
+            // Only exact IDs are deleted, so a search can't widen the scope
+            // between the preview and the deletion
+            if ids.is_empty() {
+                return Err(anyhow::anyhow!(
+                    "Pass the entity_ids from the preview to forget them"
+                ));
+            }
+            debug!("Forgetting {} entities", ids.len());
+            let report = self
+                .graph
+                .forget_entities(&ids, query.unwrap_or("forget tool"))
+                .await
+                .context("Failed to forget")?;
+            if report.entities.is_empty() {
+                return Ok("None of those entities exist; nothing was forgotten.".to_string());
+            }
+            let names: Vec<&str> = report
+                .entities
+                .iter()
+                .filter(|e| e.entity_type != "document_chunk")
+                .map(|e| e.name.as_str())
+                .collect();
+            return Ok(format!(
+                "Forgot {} item(s): {}. Removed {} entities in total, {} relationship(s) and \
+                 {} embedding(s). They won't be learned again from past messages.",
+                names.len(),
+                names.join(", "),
+                report.entities.len(),
+                report.relationships,
+                report.embeddings
+            ));
+        }
+
+        let entities = if !ids.is_empty() {
+            let db = self.graph.db();
+            let mut entities = Vec::new();
+            for id in &ids {
+                if let Some(entity) = db.get_entity(id).await? {
+                    entities.push(entity);
+                }
+            }
+            entities
+        } else if let Some(query) = query {
+            self.graph
+                .forget_scope(query, limit)
+                .await
+                .context("Failed to find what to forget")?
+        } else {
+            return Err(anyhow::anyhow!("Provide a 'query' or 'entity_ids'"));
+        };
+        if entities.is_empty() {
+            return Ok("Nothing stored matches; there's nothing to forget.".to_string());
+        }
+        self.preview(&entities).await
+    }
+}
+
+/// List the topic tags on stored knowledge and conversations
+pub struct ListTagsTool {
+    db: Arc<KnowledgeDb>,
+}
+
+impl ListTagsTool {
+    pub fn new(db: Arc<KnowledgeDb>) -> Self {
+        Self { db }
+    }
+}
+
+#[async_trait]
+impl ToolHandler for ListTagsTool {
+    fn name(&self) -> &str {
+        "list_tags"
+    }
+
+    fn reads_personal_knowledge(&self) -> bool {
+        true
+    }
+
+    fn description(&self) -> &str {
+        "List the topic tags on stored knowledge and past messages, with how many \
+         items carry each. Tags are added automatically by grouping related items; \
+         pass one to recall to see everything about that topic."
+    }
+
+    fn input_schema(&self) -> Value {
+        json_schema(
+            serde_json::json!({
+                "limit": {
+                    "type": "number",
+                    "description": "Maximum number of tags (default: 50)"
+                }
+            }),
+            vec![],
+        )
+    }
+
+    async fn execute(&self, input: Value) -> Result<String> {
+        let limit = input.get("limit").and_then(|v| v.as_u64()).unwrap_or(50) as usize;
+        let counts = self
+            .db
+            .get_tag_counts()
+            .await
+            .context("Failed to list tags")?;
+
+        if counts.is_empty() {
+            return Ok("No tags yet.".to_string());
+        }
+
+        let mut output = format!("{} tag(s):\n", counts.len());
+        for (tag, count) in counts.iter().take(limit) {
+            output.push_str(&format!("- {} ({})\n", tag, count));
+        }
+        Ok(output)
+    }
+}
+
+/// List topics stored knowledge couldn't answer questions about
+pub struct ListKnowledgeGapsTool {
+    db: Arc<KnowledgeDb>,
+}
+
+impl ListKnowledgeGapsTool {
+    pub fn new(db: Arc<KnowledgeDb>) -> Self {
+        Self { db }
+    }
+}
+
+#[async_trait]
+impl ToolHandler for ListKnowledgeGapsTool {
+    fn name(&self) -> &str {
+        "list_knowledge_gaps"
+    }
+
+    fn reads_personal_knowledge(&self) -> bool {
+        true
+    }
+
+    fn description(&self) -> &str {
+        "List knowledge gaps: topics the user asked about that stored knowledge couldn't \
+         answer, with how often they came up and whether research is under way."
+    }
+
+    fn input_schema(&self) -> Value {
+        json_schema(
+            serde_json::json!({
+                "limit": {
+                    "type": "number",
+                    "description": "Maximum number of gaps (default: 20)"
+                }
+            }),
+            vec![],
+        )
+    }
+
+    async fn execute(&self, input: Value) -> Result<String> {
+        let limit = input.get("limit").and_then(|v| v.as_u64()).unwrap_or(20) as usize;
+        let mut gaps = knowledge_gaps::open_gaps(&self.db)
+            .await
+            .context("Failed to list knowledge gaps")?;
+        gaps.truncate(limit);
+        Ok(knowledge_gaps::format_gaps(&gaps))
+    }
+}
+
 /// Search knowledge graph using full-text search
 ///
 /// This tool can work with either KnowledgeGraph (preferred, uses Tantivy)
@@ -250,6 +901,10 @@ impl ToolHandler for SearchKnowledgeTool {
         "search_knowledge"
     }
 
+    fn reads_personal_knowledge(&self) -> bool {
+        true
+    }
+
     fn description(&self) -> &str {
         "Perform a full-text search across all stored knowledge. \
          Uses Tantivy for fast, relevance-ranked full-text search."
@@ -265,6 +920,10 @@ impl ToolHandler for SearchKnowledgeTool {
                 "limit": {
                     "type": "number",
                     "description": "Maximum number of results (default: 10)"
+                },
+                "tag": {
+                    "type": "string",
+                    "description": "Optional: only return items with this tag"
                 }
             }),
             vec!["query"],
@@ -277,15 +936,31 @@ impl ToolHandler for SearchKnowledgeTool {
             .and_then(|v| v.as_str())
             .ok_or_else(|| anyhow::anyhow!("Missing 'query' parameter"))?;
         let limit = input.get("limit").and_then(|v| v.as_u64()).unwrap_or(10) as usize;
+        let tag = input
+            .get("tag")
+            .and_then(|v| v.as_str())
+            .map(normalize_tag)
+            .filter(|t| !t.is_empty());
 
         debug!("Full-text search for: {}", query);
 
         // Use Tantivy if KnowledgeGraph is available, otherwise fall back to basic search
         if let Some(graph) = &self.graph {
             // Use Tantivy full-text search via KnowledgeGraph
-            let search_results = graph
-                .search(query, limit)
+            let mut search_results = graph
+                .search(query, if tag.is_some() { limit * 5 } else { limit })
                 .context("Failed to perform full-text search")?;
+            if let Some(tag) = &tag {
+                let tagged: std::collections::HashSet<String> = graph
+                    .db()
+                    .search_entities_tagged("", None, Some(tag))
+                    .await
+                    .context("Failed to filter by tag")?
+                    .into_iter()
+                    .map(|e| e.id)
+                    .collect();
+                search_results.retain(|r| tagged.contains(&r.id));
+            }
 
             if search_results.is_empty() {
                 return Ok("No results found.".to_string());
@@ -311,7 +986,7 @@ impl ToolHandler for SearchKnowledgeTool {
             // Fallback to basic SQL search
             debug!("Using fallback SQL search (Tantivy not available)");
             let results = db
-                .search_entities(query, None)
+                .search_entities_tagged(query, None, tag.as_deref())
                 .await
                 .context("Failed to search knowledge")?;
 
@@ -396,6 +1071,25 @@ mod tests {
         assert!(result.contains("Rust programming"));
     }
 
+    #[tokio::test]
+    async fn test_remember_with_review_stages() {
+        let (db, _temp) = setup();
+        let remember = RememberTool::new(db.clone()).with_review(true);
+
+        let result = remember
+            .execute(serde_json::json!({
+                "name": "Prefers tea",
+                "entity_type": "preference"
+            }))
+            .await
+            .unwrap();
+        assert!(result.contains("review"));
+        assert!(db.search_entities("tea", None).await.unwrap().is_empty());
+        let staged = memory_review::pending(&db).await.unwrap();
+        assert_eq!(staged.len(), 1);
+        assert_eq!(staged[0].source, "remember");
+    }
+
     #[tokio::test]
     async fn test_remember_missing_name() {
         let (db, _temp) = setup();
@@ -611,10 +1305,181 @@ mod tests {
         assert!(props.get("target_id").is_some());
     }
 
+    #[tokio::test]
+    async fn test_remember_many_tool() {
+        let (graph, _temp) = setup_graph();
+        let tool = RememberManyTool::new(graph.clone());
+
+        let result = tool
+            .execute(serde_json::json!({
+                "entities": [
+                    {"name": "Priya", "entity_type": "person"},
+                    {"name": "Billing migration", "entity_type": "project",
+                     "metadata": {"due": "2026-11-01"}}
+                ],
+                "relationships": [
+                    {"source": "Priya", "target": "Billing migration", "relation_type": "owns"}
+                ]
+            }))
+            .await
+            .unwrap();
+        assert!(result.contains("2 entities and 1 relationships"));
+        assert!(result.contains("Billing migration (project)"));
+        assert_eq!(graph.search("billing", 5).unwrap().len(), 1);
+
+        let err = tool
+            .execute(serde_json::json!({"entities": [{"name": "No type"}]}))
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("entities"));
+    }
+
+    #[tokio::test]
+    async fn test_manage_aliases_tool() {
+        let (graph, _temp) = setup_graph();
+        let tool = ManageAliasesTool::new(graph.clone());
+        let id = graph
+            .add_entity("Kubernetes", "concept", None)
+            .await
+            .unwrap();
+
+        let result = tool
+            .execute(serde_json::json!({
+                "action": "add", "entity": "kubernetes", "alias": "K8s"
+            }))
+            .await
+            .unwrap();
+        assert!(result.contains("now an alias"));
+        assert_eq!(graph.search("k8s", 5).unwrap()[0].id, id);
+
+        let result = tool
+            .execute(serde_json::json!({"action": "list", "entity": "K8s"}))
+            .await
+            .unwrap();
+        assert!(result.contains("Kubernetes also goes by: K8s"));
+
+        let result = tool
+            .execute(serde_json::json!({"action": "suggest"}))
+            .await
+            .unwrap();
+        assert!(result.contains("No alias suggestions"));
+
+        assert!(
+            tool.execute(serde_json::json!({"action": "list", "entity": "nope"}))
+                .await
+                .is_err()
+        );
+    }
+
     #[test]
     fn test_search_knowledge_tool_schema() {
         let (db, _temp) = setup();
         let tool = SearchKnowledgeTool::new(db);
         assert_eq!(tool.name(), "search_knowledge");
     }
+
+    #[tokio::test]
+    async fn test_recall_and_list_by_tag() {
+        let (db, _temp) = setup();
+        db.insert_entity(
+            "Tile quote",
+            "document",
+            Some(serde_json::json!({"tags": ["kitchen-renovation"]})),
+        )
+        .await
+        .unwrap();
+        db.insert_entity("Tax return", "document", None)
+            .await
+            .unwrap();
+        let convo = db
+            .insert_conversation("slack", "alice", "cabinets arrive monday", None)
+            .await
+            .unwrap();
+        db.update_conversation_metadata(
+            &convo,
+            serde_json::json!({"tags": ["kitchen-renovation"]}),
+        )
+        .await
+        .unwrap();
+
+        let recall = RecallTool::new(db.clone());
+        let result = recall
+            .execute(serde_json::json!({"tag": "Kitchen Renovation"}))
+            .await
+            .unwrap();
+        assert!(result.contains("Tile quote"));
+        assert!(!result.contains("Tax return"));
+        assert!(result.contains("cabinets arrive monday"));
+        assert!(recall.execute(serde_json::json!({})).await.is_err());
+
+        let tags = ListTagsTool::new(db)
+            .execute(serde_json::json!({}))
+            .await
+            .unwrap();
+        assert!(tags.contains("kitchen-renovation (2)"));
+    }
+
+    #[tokio::test]
+    async fn test_forget_previews_then_deletes() {
+        let (graph, _temp) = setup_graph();
+        let id = graph
+            .add_entity(
+                "Old address",
+                "fact",
+                Some(serde_json::json!({"city": "Reno"})),
+            )
+            .await
+            .unwrap();
+        graph.add_entity("Dentist", "person", None).await.unwrap();
+        let tool = ForgetTool::new(graph.clone());
+
+        let preview = tool
+            .execute(serde_json::json!({"query": "old address"}))
+            .await
+            .unwrap();
+        assert!(preview.contains(&format!("Old address (fact), id: {}", id)));
+        assert!(preview.contains("Nothing has been deleted"));
+        assert!(graph.get_entity(&id).await.unwrap().is_some());
+
+        // Confirming needs the exact IDs
+        assert!(
+            tool.execute(serde_json::json!({"query": "old address", "confirm": true}))
+                .await
+                .is_err()
+        );
+        let result = tool
+            .execute(serde_json::json!({"entity_ids": [id], "confirm": true}))
+            .await
+            .unwrap();
+        assert!(result.starts_with("Forgot 1 item(s): Old address."));
+        assert!(graph.get_entity(&id).await.unwrap().is_none());
+        assert!(graph.search("Reno", 10).unwrap().is_empty());
+        assert!(graph.db().is_forgotten("old address").await.unwrap());
+
+        // Not learned again, alone or in a batch
+        let err = RememberTool::new(graph.db())
+            .execute(serde_json::json!({"name": "Old Address", "entity_type": "fact"}))
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("asked to forget"));
+        assert!(
+            RememberManyTool::new(graph.clone())
+                .with_review(true)
+                .execute(serde_json::json!({"entities": [
+                    {"name": "Dentist visit", "entity_type": "fact"},
+                    {"name": "old address", "entity_type": "fact"}
+                ]}))
+                .await
+                .is_err()
+        );
+        assert!(
+            graph
+                .db()
+                .search_entities("address", None)
+                .await
+                .unwrap()
+                .is_empty()
+        );
+        assert!(memory_review::list(&graph.db()).await.unwrap().is_empty());
+    }
 }
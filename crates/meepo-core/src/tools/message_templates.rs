@@ -0,0 +1,199 @@
+//! Message template tools — send, save and list reusable messages
+
+use anyhow::{Context, Result, anyhow};
+use async_trait::async_trait;
+use serde_json::Value;
+use std::sync::Arc;
+use tokio::sync::mpsc;
+use tracing::debug;
+
+use super::{ToolHandler, json_schema};
+use crate::message_templates::TemplateStore;
+use crate::types::{ChannelType, MessageKind, OutgoingMessage};
+
+/// Tool that renders a template and sends it to a channel
+pub struct SendTemplateTool {
+    templates: Arc<TemplateStore>,
+    outgoing: mpsc::Sender<OutgoingMessage>,
+    default_channel: ChannelType,
+}
+
+impl SendTemplateTool {
+    pub fn new(
+        templates: Arc<TemplateStore>,
+        outgoing: mpsc::Sender<OutgoingMessage>,
+        default_channel: ChannelType,
+    ) -> Self {
+        Self {
+            templates,
+            outgoing,
+            default_channel,
+        }
+    }
+}
+
+#[async_trait]
+impl ToolHandler for SendTemplateTool {
+    fn name(&self) -> &str {
+        "send_template"
+    }
+
+    fn description(&self) -> &str {
+        "Send a saved message template with its {variables} filled in, exactly as written. \
+         Use this for recurring messages (reminders, check-ins, report headers) so they look \
+         the same every time. {date}, {time} and {weekday} are filled automatically."
+    }
+
+    fn input_schema(&self) -> Value {
+        json_schema(
+            serde_json::json!({
+                "name": {
+                    "type": "string",
+                    "description": "Template name, e.g. 'standup_reminder'"
+                },
+                "vars": {
+                    "type": "object",
+                    "description": "Values for the template's variables, e.g. {\"room\": \"B2\"}"
+                },
+                "channel": {
+                    "type": "string",
+                    "description": "Where to send it (default: the notification channel)"
+                }
+            }),
+            vec!["name"],
+        )
+    }
+
+    async fn execute(&self, input: Value) -> Result<String> {
+        let name = input
+            .get("name")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| anyhow!("Missing 'name' parameter"))?;
+        let vars = input.get("vars").cloned().unwrap_or(Value::Null);
+        let channel = input
+            .get("channel")
+            .and_then(|v| v.as_str())
+            .map(ChannelType::from_string)
+            .unwrap_or_else(|| self.default_channel.clone());
+        let content = self.templates.render(name, &vars).await?;
+        debug!("Sending template '{}' to {}", name, channel);
+        self.outgoing
+            .send(OutgoingMessage {
+                content: content.clone(),
+                channel: channel.clone(),
+                reply_to: None,
+                kind: MessageKind::Response,
+                interactive: None,
+            })
+            .await
+            .context("Failed to send message")?;
+        Ok(format!("Sent to {}:\n{}", channel, content))
+    }
+}
+
+/// Tool that stores a message template
+pub struct SaveTemplateTool {
+    templates: Arc<TemplateStore>,
+}
+
+impl SaveTemplateTool {
+    pub fn new(templates: Arc<TemplateStore>) -> Self {
+        Self { templates }
+    }
+}
+
+#[async_trait]
+impl ToolHandler for SaveTemplateTool {
+    fn name(&self) -> &str {
+        "save_message_template"
+    }
+
+    fn description(&self) -> &str {
+        "Save a reusable message template (replacing one with the same name). Write \
+         placeholders as {name}; they're filled when the template is sent with send_template \
+         or by a template rule."
+    }
+
+    fn input_schema(&self) -> Value {
+        json_schema(
+            serde_json::json!({
+                "name": {
+                    "type": "string",
+                    "description": "Letters, digits, '_' and '-', e.g. 'standup_reminder'"
+                },
+                "body": {
+                    "type": "string",
+                    "description": "Message text with {variables}, e.g. 'Standup in {room} at 9:30 ({weekday})'"
+                },
+                "description": {
+                    "type": "string",
+                    "description": "What the template is for"
+                }
+            }),
+            vec!["name", "body"],
+        )
+    }
+
+    async fn execute(&self, input: Value) -> Result<String> {
+        let text = |key: &str| input.get(key).and_then(|v| v.as_str());
+        let name = text("name").ok_or_else(|| anyhow!("Missing 'name' parameter"))?;
+        let body = text("body").ok_or_else(|| anyhow!("Missing 'body' parameter"))?;
+        let template = self.templates.save(name, body, text("description")).await?;
+        let variables = template.variables();
+        Ok(if variables.is_empty() {
+            format!("Saved template '{}'", template.name)
+        } else {
+            format!(
+                "Saved template '{}' with variables: {}",
+                template.name,
+                variables.join(", ")
+            )
+        })
+    }
+}
+
+/// Tool that lists message templates
+pub struct ListTemplatesTool {
+    templates: Arc<TemplateStore>,
+}
+
+impl ListTemplatesTool {
+    pub fn new(templates: Arc<TemplateStore>) -> Self {
+        Self { templates }
+    }
+}
+
+#[async_trait]
+impl ToolHandler for ListTemplatesTool {
+    fn name(&self) -> &str {
+        "list_message_templates"
+    }
+
+    fn description(&self) -> &str {
+        "List saved message templates with their variables and text."
+    }
+
+    fn input_schema(&self) -> Value {
+        json_schema(serde_json::json!({}), vec![])
+    }
+
+    async fn execute(&self, _input: Value) -> Result<String> {
+        let templates = self.templates.list().await?;
+        if templates.is_empty() {
+            return Ok("No message templates.".to_string());
+        }
+        let mut output = format!("## Message Templates ({})\n", templates.len());
+        for template in &templates {
+            output.push_str(&format!("\n### {}\n", template.name));
+            if let Some(description) = &template.description {
+                output.push_str(&format!("{}\n", description));
+            }
+            let variables = template.variables();
+            if !variables.is_empty() {
+                output.push_str(&format!("Variables: {}\n", variables.join(", ")));
+            }
+            output.push_str(&format!("```\n{}\n```\n", template.body));
+        }
+        Ok(output)
+    }
+}
@@ -8,20 +8,29 @@ use std::sync::Arc;
 use tracing::{debug, warn};
 
 use crate::api::ToolDefinition;
+use crate::tool_cost::ToolCost;
 
 pub mod accessibility;
+pub mod app_usage;
+pub mod artifact_sync;
 pub mod autonomous;
 pub mod browser;
 pub mod canvas;
 pub mod code;
+pub mod datetime;
 pub mod delegate;
+pub mod dossier;
+pub mod download;
 pub mod filesystem;
+pub mod http;
 pub mod lifestyle;
-#[cfg(any(target_os = "macos", target_os = "windows"))]
+#[cfg(any(target_os = "macos", target_os = "windows", target_os = "linux"))]
 pub mod macos;
 #[cfg(target_os = "macos")]
 pub mod macos_finder;
 #[cfg(target_os = "macos")]
+pub mod macos_focus;
+#[cfg(target_os = "macos")]
 pub mod macos_keychain;
 #[cfg(target_os = "macos")]
 pub mod macos_media;
@@ -40,9 +49,20 @@ pub mod macos_terminal;
 #[cfg(target_os = "macos")]
 pub mod macos_windows;
 pub mod memory;
+pub mod message_templates;
+pub mod modes;
+pub mod network;
+pub mod onboarding;
+pub mod pdf;
+pub mod pins;
+pub mod provenance;
 pub mod rag;
+pub mod rules;
 pub mod sandbox_exec;
+pub mod script;
 pub mod search;
+pub mod send_guard;
+pub mod style;
 pub mod system;
 pub mod usage_stats;
 pub mod watchers;
@@ -52,6 +72,10 @@ pub mod watchers;
 pub trait ToolExecutor: Send + Sync {
     async fn execute(&self, tool_name: &str, input: Value) -> Result<String>;
     fn list_tools(&self) -> Vec<ToolDefinition>;
+    /// Whether the named tool reads or changes the owner's knowledge base
+    fn reads_personal_knowledge(&self, _tool_name: &str) -> bool {
+        false
+    }
 }
 
 /// Individual tool handler
@@ -60,12 +84,28 @@ pub trait ToolHandler: Send + Sync {
     fn name(&self) -> &str;
     fn description(&self) -> &str;
     fn input_schema(&self) -> Value;
+    /// Rough cost and latency of a call; most tools are free and instant
+    fn cost(&self) -> ToolCost {
+        ToolCost::default()
+    }
+    /// Whether the tool reaches the internet; such tools refuse to run in
+    /// offline mode
+    fn needs_network(&self) -> bool {
+        false
+    }
+    /// Whether the tool reads or changes the owner's knowledge base; such
+    /// tools are hidden from restricted profiles without personal knowledge
+    fn reads_personal_knowledge(&self) -> bool {
+        false
+    }
     async fn execute(&self, input: Value) -> Result<String>;
 }
 
 /// Registry of available tools
 pub struct ToolRegistry {
     tools: HashMap<Arc<str>, Arc<dyn ToolHandler>>,
+    /// Executors whose tools come and go at runtime (e.g. remote nodes)
+    sources: Vec<Arc<dyn ToolExecutor>>,
 }
 
 impl ToolRegistry {
@@ -73,6 +113,7 @@ impl ToolRegistry {
     pub fn new() -> Self {
         Self {
             tools: HashMap::new(),
+            sources: Vec::new(),
         }
     }
 
@@ -83,6 +124,12 @@ impl ToolRegistry {
         self.tools.insert(name, handler);
     }
 
+    /// Add an executor whose tools are listed alongside the registered
+    /// ones. Registered tools win on a name clash.
+    pub fn add_source(&mut self, source: Arc<dyn ToolExecutor>) {
+        self.sources.push(source);
+    }
+
     /// Get a tool by name
     pub fn get(&self, name: &str) -> Option<Arc<dyn ToolHandler>> {
         self.tools.get(name as &str).cloned()
@@ -98,9 +145,18 @@ impl ToolRegistry {
         self.tools.is_empty()
     }
 
+    /// Declared cost of every tool that isn't free and instant
+    pub fn costs(&self) -> HashMap<String, ToolCost> {
+        self.tools
+            .iter()
+            .map(|(name, handler)| (name.to_string(), handler.cost()))
+            .filter(|(_, cost)| *cost != ToolCost::default())
+            .collect()
+    }
+
     /// Get tool definitions for only the named tools
     pub fn filter_tools(&self, names: &[String]) -> Vec<ToolDefinition> {
-        names
+        let mut tools: Vec<ToolDefinition> = names
             .iter()
             .filter_map(|name| self.tools.get(name.as_str()))
             .map(|handler| ToolDefinition {
@@ -108,6 +164,21 @@ impl ToolRegistry {
                 description: handler.description().to_string(),
                 input_schema: handler.input_schema(),
             })
+            .collect();
+        tools.extend(
+            self.source_tools()
+                .into_iter()
+                .filter(|t| names.contains(&t.name)),
+        );
+        tools
+    }
+
+    /// Tools offered by sources, minus any shadowed by a registered tool
+    fn source_tools(&self) -> Vec<ToolDefinition> {
+        self.sources
+            .iter()
+            .flat_map(|s| s.list_tools())
+            .filter(|t| !self.tools.contains_key(t.name.as_str()))
             .collect()
     }
 }
@@ -123,10 +194,18 @@ impl ToolExecutor for ToolRegistry {
     async fn execute(&self, tool_name: &str, input: Value) -> Result<String> {
         debug!("Executing tool: {} with input: {:?}", tool_name, input);
 
-        let handler = self
-            .tools
-            .get(tool_name)
-            .ok_or_else(|| anyhow!("Unknown tool: {}", tool_name))?;
+        let Some(handler) = self.tools.get(tool_name) else {
+            for source in &self.sources {
+                if source.list_tools().iter().any(|t| t.name == tool_name) {
+                    return source.execute(tool_name, input).await;
+                }
+            }
+            return Err(anyhow!("Unknown tool: {}", tool_name));
+        };
+
+        if handler.needs_network() {
+            crate::network::ensure_online(&format!("the {} tool", tool_name))?;
+        }
 
         match handler.execute(input).await {
             Ok(result) => {
@@ -141,14 +220,27 @@ impl ToolExecutor for ToolRegistry {
     }
 
     fn list_tools(&self) -> Vec<ToolDefinition> {
-        self.tools
+        let mut tools: Vec<ToolDefinition> = self
+            .tools
             .values()
             .map(|handler| ToolDefinition {
                 name: handler.name().to_string(),
                 description: handler.description().to_string(),
                 input_schema: handler.input_schema(),
             })
-            .collect()
+            .collect();
+        tools.extend(self.source_tools());
+        tools
+    }
+
+    fn reads_personal_knowledge(&self, tool_name: &str) -> bool {
+        match self.tools.get(tool_name) {
+            Some(handler) => handler.reads_personal_knowledge(),
+            None => self
+                .sources
+                .iter()
+                .any(|s| s.reads_personal_knowledge(tool_name)),
+        }
     }
 }
 
@@ -206,6 +298,10 @@ impl ToolExecutor for GuardedToolExecutor {
     fn list_tools(&self) -> Vec<ToolDefinition> {
         self.inner.list_tools()
     }
+
+    fn reads_personal_knowledge(&self, tool_name: &str) -> bool {
+        self.inner.reads_personal_knowledge(tool_name)
+    }
 }
 
 /// Helper function to create a JSON schema for tool input
@@ -0,0 +1,74 @@
+//! Mode tool — lets the user switch between named modes ("work", "weekend")
+
+use anyhow::{Result, anyhow};
+use async_trait::async_trait;
+use serde_json::Value;
+use std::sync::Arc;
+use tracing::debug;
+
+use super::{ToolHandler, json_schema};
+use crate::modes::{self, ModeSource, ModesConfig};
+use meepo_knowledge::KnowledgeDb;
+
+/// Switch the active mode
+pub struct SetModeTool {
+    db: Arc<KnowledgeDb>,
+    config: Arc<ModesConfig>,
+}
+
+impl SetModeTool {
+    pub fn new(db: Arc<KnowledgeDb>, config: Arc<ModesConfig>) -> Self {
+        Self { db, config }
+    }
+}
+
+#[async_trait]
+impl ToolHandler for SetModeTool {
+    fn name(&self) -> &str {
+        modes::SET_MODE_TOOL
+    }
+
+    fn description(&self) -> &str {
+        "Switch to a named mode (e.g. 'work', 'weekend'), which changes your personality notes, \
+         which notifications get through, how much you do on your own and which tools you can \
+         use. Use 'none' for no mode, or 'auto' to follow the mode schedule again. A mode picked \
+         here lasts until the schedule moves on to a different mode."
+    }
+
+    fn input_schema(&self) -> Value {
+        let mut names: Vec<&str> = self.config.modes.iter().map(|m| m.name.as_str()).collect();
+        names.extend([modes::NONE, modes::AUTO]);
+        json_schema(
+            serde_json::json!({
+                "mode": {
+                    "type": "string",
+                    "enum": names
+                }
+            }),
+            vec!["mode"],
+        )
+    }
+
+    async fn execute(&self, input: Value) -> Result<String> {
+        let name = input
+            .get("mode")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| anyhow!("Missing 'mode' parameter"))?;
+        debug!("Switching mode to {}", name);
+        let active = modes::set_manual(&self.db, &self.config, name, "set_mode").await?;
+        modes::set_current(active.clone());
+        Ok(match active {
+            Some(active) => format!(
+                "{} mode is on{} ({}).",
+                active.mode.name,
+                if active.source == ModeSource::Scheduled {
+                    " by schedule"
+                } else {
+                    ""
+                },
+                active.mode.describe()
+            ),
+            None => "No mode is on.".to_string(),
+        })
+    }
+}
@@ -0,0 +1,74 @@
+//! Network kill switch tool — cuts off (or restores) outbound network access
+
+use anyhow::{Result, anyhow};
+use async_trait::async_trait;
+use serde_json::Value;
+
+use super::{ToolHandler, json_schema};
+use crate::network;
+
+/// Toggle offline mode at runtime
+pub struct NetworkKillSwitchTool;
+
+impl NetworkKillSwitchTool {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Default for NetworkKillSwitchTool {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn describe() -> String {
+    match (network::is_offline(), network::is_locked()) {
+        (true, true) => "Offline mode is on and locked: only local models are used and web \
+                         tools are disabled."
+            .to_string(),
+        (true, false) => "Offline mode is on: only local models are used and web tools are \
+                          disabled."
+            .to_string(),
+        (false, _) => "Online: cloud models and web tools are available.".to_string(),
+    }
+}
+
+#[async_trait]
+impl ToolHandler for NetworkKillSwitchTool {
+    fn name(&self) -> &str {
+        "network_kill_switch"
+    }
+
+    fn description(&self) -> &str {
+        "Cut off all outbound network access ('offline'), restore it ('online'), or check it \
+         ('status'). Offline, only local models (e.g. Ollama) are used and web tools refuse to \
+         run. Use this when the user asks to go offline or stop talking to the internet."
+    }
+
+    fn input_schema(&self) -> Value {
+        json_schema(
+            serde_json::json!({
+                "action": {
+                    "type": "string",
+                    "enum": ["offline", "online", "status"]
+                }
+            }),
+            vec!["action"],
+        )
+    }
+
+    async fn execute(&self, input: Value) -> Result<String> {
+        let action = input
+            .get("action")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| anyhow!("Missing 'action' parameter"))?;
+        match action {
+            "offline" => network::set_offline(true)?,
+            "online" => network::set_offline(false)?,
+            "status" => {}
+            other => return Err(anyhow!("Unknown action '{}'", other)),
+        }
+        Ok(describe())
+    }
+}
@@ -0,0 +1,101 @@
+//! Onboarding tool — saves what the agent learned in the onboarding interview
+
+use anyhow::Result;
+use async_trait::async_trait;
+use meepo_knowledge::KnowledgeDb;
+use serde_json::Value;
+use std::path::PathBuf;
+use std::sync::Arc;
+use tracing::debug;
+
+use super::{ToolHandler, json_schema};
+use crate::onboarding::{self, OnboardingAnswers};
+
+/// Tool that writes onboarding answers to MEMORY.md, preferences and entities
+pub struct CompleteOnboardingTool {
+    db: Arc<KnowledgeDb>,
+    memory_path: PathBuf,
+}
+
+impl CompleteOnboardingTool {
+    pub fn new(db: Arc<KnowledgeDb>, memory_path: PathBuf) -> Self {
+        Self { db, memory_path }
+    }
+}
+
+#[async_trait]
+impl ToolHandler for CompleteOnboardingTool {
+    fn name(&self) -> &str {
+        "complete_onboarding"
+    }
+
+    fn description(&self) -> &str {
+        "Finish the onboarding interview: save the user's name, timezone, working hours, key \
+         people, projects and notification preferences to memory. Call once you have the \
+         answers, or with whatever you know if the user wants to skip."
+    }
+
+    fn input_schema(&self) -> Value {
+        json_schema(
+            serde_json::json!({
+                "name": {
+                    "type": "string",
+                    "description": "What the user wants to be called"
+                },
+                "timezone": {
+                    "type": "string",
+                    "description": "IANA timezone, e.g. 'Europe/Berlin'"
+                },
+                "working_hours": {
+                    "type": "string",
+                    "description": "e.g. 'Mon-Fri 9:00-17:30'"
+                },
+                "people": {
+                    "type": "array",
+                    "description": "Key people in the user's life or work",
+                    "items": {
+                        "type": "object",
+                        "properties": {
+                            "name": { "type": "string" },
+                            "relation": {
+                                "type": "string",
+                                "description": "e.g. 'manager', 'partner', 'client'"
+                            }
+                        },
+                        "required": ["name"]
+                    }
+                },
+                "projects": {
+                    "type": "array",
+                    "items": { "type": "string" },
+                    "description": "Projects the user is working on"
+                },
+                "notifications": {
+                    "type": "string",
+                    "description": "How and when the user wants to be notified"
+                }
+            }),
+            vec![],
+        )
+    }
+
+    async fn execute(&self, input: Value) -> Result<String> {
+        let mut answers: OnboardingAnswers = serde_json::from_value(input)?;
+        if let Some(tz) = &answers.timezone
+            && tz.parse::<chrono_tz::Tz>().is_err()
+        {
+            debug!("Ignoring unknown onboarding timezone '{}'", tz);
+            answers.timezone = None;
+        }
+        let mut summary = onboarding::complete(&self.db, &self.memory_path, &answers).await?;
+        if let Some(tz) = &answers.timezone
+            && *tz != crate::timezone::user_timezone().name()
+        {
+            summary.push_str(&format!(
+                " To use {} for scheduling, run `meepo config set agent.timezone {}`.",
+                tz, tz
+            ));
+        }
+        Ok(summary)
+    }
+}
@@ -0,0 +1,672 @@
+//! PDF reading — text extraction by page range, metadata, and OCR fallback
+//!
+//! Text is extracted with lopdf. Pages without a text layer (scans) are
+//! OCR'd with `pdftoppm` + `tesseract` when both are installed. Long
+//! documents can be chunked into the knowledge graph via `ingest`.
+
+use anyhow::{Context, Result, anyhow};
+use async_trait::async_trait;
+use lopdf::Document;
+use serde_json::Value;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tracing::{debug, warn};
+
+use meepo_knowledge::KnowledgeGraph;
+use meepo_knowledge::chunking::ChunkingConfig;
+
+use super::rag::index_document;
+use super::system::validate_file_path;
+use super::{ToolHandler, json_schema};
+
+/// Largest PDF we will load into memory
+const MAX_PDF_BYTES: u64 = 100 * 1024 * 1024;
+
+/// Default cap on characters returned to the model
+const DEFAULT_MAX_CHARS: usize = 50_000;
+
+/// Document-level metadata from the PDF info dictionary
+#[derive(Debug, Clone, Default)]
+pub struct PdfInfo {
+    pub title: Option<String>,
+    pub author: Option<String>,
+    pub subject: Option<String>,
+    pub page_count: u32,
+    pub encrypted: bool,
+}
+
+/// Text of a single page
+#[derive(Debug, Clone)]
+pub struct PdfPage {
+    pub number: u32,
+    pub text: String,
+    pub ocr: bool,
+}
+
+fn load_document(bytes: &[u8], password: Option<&str>) -> Result<Document> {
+    let doc = match password {
+        Some(pw) => Document::load_mem_with_password(bytes, pw),
+        None => Document::load_mem(bytes),
+    }
+    .map_err(|e| anyhow!("Failed to parse PDF: {}", e))?;
+    Ok(doc)
+}
+
+fn pdf_info(doc: &Document) -> PdfInfo {
+    let info_dict = doc
+        .trailer
+        .get(b"Info")
+        .ok()
+        .and_then(|obj| doc.dereference(obj).ok())
+        .and_then(|(_, obj)| obj.as_dict().ok());
+
+    let field = |key: &[u8]| -> Option<String> {
+        info_dict
+            .and_then(|d| d.get(key).ok())
+            .and_then(|obj| lopdf::decode_text_string(obj).ok())
+            .map(|s| s.trim().to_string())
+            .filter(|s| !s.is_empty())
+    };
+
+    PdfInfo {
+        title: field(b"Title"),
+        author: field(b"Author"),
+        subject: field(b"Subject"),
+        page_count: doc.get_pages().len() as u32,
+        encrypted: doc.trailer.get(b"Encrypt").is_ok(),
+    }
+}
+
+/// Parse a page spec like "1-3,7,10-" into sorted, de-duplicated page numbers
+fn parse_page_range(spec: &str, page_count: u32) -> Result<Vec<u32>> {
+    let mut pages = Vec::new();
+    for part in spec.split(',').map(str::trim).filter(|p| !p.is_empty()) {
+        let (start, end) = match part.split_once('-') {
+            Some((a, b)) => {
+                let start = if a.trim().is_empty() {
+                    1
+                } else {
+                    a.trim().parse::<u32>()?
+                };
+                let end = if b.trim().is_empty() {
+                    page_count
+                } else {
+                    b.trim().parse::<u32>()?
+                };
+                (start, end)
+            }
+            None => {
+                let n = part
+                    .parse::<u32>()
+                    .with_context(|| format!("Invalid page number '{}'", part))?;
+                (n, n)
+            }
+        };
+        if start == 0 || start > end {
+            return Err(anyhow!("Invalid page range '{}'", part));
+        }
+        if start > page_count {
+            return Err(anyhow!(
+                "Page {} is out of range (document has {} pages)",
+                start,
+                page_count
+            ));
+        }
+        pages.extend(start..=end.min(page_count));
+    }
+    if pages.is_empty() {
+        return Err(anyhow!("Empty page range"));
+    }
+    pages.sort_unstable();
+    pages.dedup();
+    Ok(pages)
+}
+
+/// Parse the PDF and extract metadata plus text for the requested pages
+fn extract(
+    bytes: &[u8],
+    password: Option<&str>,
+    pages: Option<&str>,
+) -> Result<(PdfInfo, Vec<PdfPage>)> {
+    let doc = load_document(bytes, password)?;
+    let info = pdf_info(&doc);
+    let numbers = match pages {
+        Some(spec) => parse_page_range(spec, info.page_count)?,
+        None => (1..=info.page_count).collect(),
+    };
+    let pages = numbers
+        .into_iter()
+        .map(|n| {
+            let text = doc.extract_text(&[n]).unwrap_or_else(|e| {
+                debug!("No text extracted from page {}: {}", n, e);
+                String::new()
+            });
+            PdfPage {
+                number: n,
+                text: text.trim().to_string(),
+                ocr: false,
+            }
+        })
+        .collect();
+    Ok((info, pages))
+}
+
+/// OCR one page by rasterizing it with pdftoppm and running tesseract.
+///
+/// Returns `Ok(None)` when the OCR tools aren't installed.
+async fn ocr_page(path: &Path, page: u32) -> Result<Option<String>> {
+    let work_dir = std::env::temp_dir().join(format!("meepo-ocr-{}", uuid::Uuid::new_v4()));
+    tokio::fs::create_dir_all(&work_dir).await?;
+    let result = ocr_page_in(path, page, &work_dir).await;
+    let _ = tokio::fs::remove_dir_all(&work_dir).await;
+    result
+}
+
+async fn ocr_page_in(path: &Path, page: u32, work_dir: &Path) -> Result<Option<String>> {
+    let prefix = work_dir.join("page");
+    let page_arg = page.to_string();
+
+    let raster = tokio::process::Command::new("pdftoppm")
+        .args([
+            "-r",
+            "300",
+            "-png",
+            "-singlefile",
+            "-f",
+            &page_arg,
+            "-l",
+            &page_arg,
+        ])
+        .arg(path)
+        .arg(&prefix)
+        .output()
+        .await;
+    match raster {
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(e.into()),
+        Ok(out) if !out.status.success() => {
+            return Err(anyhow!(
+                "pdftoppm failed: {}",
+                String::from_utf8_lossy(&out.stderr).trim()
+            ));
+        }
+        Ok(_) => {}
+    }
+
+    let image = prefix.with_extension("png");
+    let ocr = tokio::process::Command::new("tesseract")
+        .arg(&image)
+        .arg("stdout")
+        .output()
+        .await;
+    match ocr {
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(e.into()),
+        Ok(out) if !out.status.success() => Err(anyhow!(
+            "tesseract failed: {}",
+            String::from_utf8_lossy(&out.stderr).trim()
+        )),
+        Ok(out) => Ok(Some(
+            String::from_utf8_lossy(&out.stdout).trim().to_string(),
+        )),
+    }
+}
+
+/// Read a PDF from disk, OCR'ing pages that have no text layer.
+///
+/// Returns the metadata, extracted pages, and whether OCR was needed but
+/// unavailable.
+pub async fn read_pdf(
+    path: &Path,
+    pages: Option<&str>,
+    password: Option<&str>,
+) -> Result<(PdfInfo, Vec<PdfPage>, bool)> {
+    let size = tokio::fs::metadata(path)
+        .await
+        .with_context(|| format!("Failed to read file: {}", path.display()))?
+        .len();
+    if size > MAX_PDF_BYTES {
+        return Err(anyhow!(
+            "PDF is {} bytes, exceeds limit of {} bytes",
+            size,
+            MAX_PDF_BYTES
+        ));
+    }
+    let bytes = tokio::fs::read(path)
+        .await
+        .with_context(|| format!("Failed to read file: {}", path.display()))?;
+
+    let pages_spec = pages.map(String::from);
+    let password = password.map(String::from);
+    let (info, mut pages) = tokio::task::spawn_blocking(move || {
+        extract(&bytes, password.as_deref(), pages_spec.as_deref())
+    })
+    .await
+    .context("PDF extraction task failed")??;
+
+    let mut ocr_missing = false;
+    for page in pages.iter_mut().filter(|p| p.text.is_empty()) {
+        if ocr_missing {
+            break;
+        }
+        match ocr_page(path, page.number).await {
+            Ok(Some(text)) => {
+                page.text = text;
+                page.ocr = true;
+            }
+            Ok(None) => ocr_missing = true,
+            Err(e) => warn!("OCR failed for page {}: {}", page.number, e),
+        }
+    }
+
+    Ok((info, pages, ocr_missing))
+}
+
+/// Extract all text from a PDF file as one string (used for ingestion)
+pub async fn extract_file_text(path: &Path, password: Option<&str>) -> Result<String> {
+    let (_, pages, _) = read_pdf(path, None, password).await?;
+    Ok(pages
+        .into_iter()
+        .map(|p| p.text)
+        .filter(|t| !t.is_empty())
+        .collect::<Vec<_>>()
+        .join("\n\n"))
+}
+
+fn expand_home(path: &str) -> PathBuf {
+    match path
+        .strip_prefix("~/")
+        .and_then(|rest| dirs::home_dir().map(|h| h.join(rest)))
+    {
+        Some(p) => p,
+        None => PathBuf::from(path),
+    }
+}
+
+/// Read text and metadata from a PDF, optionally ingesting it for retrieval
+pub struct ReadPdfTool {
+    graph: Arc<KnowledgeGraph>,
+    chunking_config: ChunkingConfig,
+}
+
+impl ReadPdfTool {
+    pub fn new(graph: Arc<KnowledgeGraph>) -> Self {
+        Self {
+            graph,
+            chunking_config: ChunkingConfig::default(),
+        }
+    }
+
+    pub fn with_chunking_config(mut self, config: ChunkingConfig) -> Self {
+        self.chunking_config = config;
+        self
+    }
+}
+
+#[async_trait]
+impl ToolHandler for ReadPdfTool {
+    fn name(&self) -> &str {
+        "read_pdf"
+    }
+
+    /// `ingest` adds the PDF to the knowledge graph
+    fn reads_personal_knowledge(&self) -> bool {
+        true
+    }
+
+    fn description(&self) -> &str {
+        "Read a PDF file: returns metadata (title, author, page count) and text for a page \
+         range. Scanned pages are OCR'd when tesseract is installed. For long documents, \
+         read a page range at a time, or set 'ingest' to chunk and index the whole PDF in \
+         the knowledge graph for smart_recall."
+    }
+
+    fn input_schema(&self) -> Value {
+        json_schema(
+            serde_json::json!({
+                "path": {
+                    "type": "string",
+                    "description": "Path to the PDF file"
+                },
+                "pages": {
+                    "type": "string",
+                    "description": "Page range, e.g. '1-5', '2,4,6', '10-' (default: all pages)"
+                },
+                "password": {
+                    "type": "string",
+                    "description": "Password for encrypted PDFs"
+                },
+                "max_chars": {
+                    "type": "integer",
+                    "description": "Maximum characters of text to return (default: 50000)"
+                },
+                "ingest": {
+                    "type": "boolean",
+                    "description": "Also chunk and index the full document in the knowledge graph (default: false)"
+                },
+                "title": {
+                    "type": "string",
+                    "description": "Title for the ingested document (default: PDF title or filename)"
+                },
+                "tags": {
+                    "type": "array",
+                    "items": { "type": "string" },
+                    "description": "Optional tags for the ingested document"
+                }
+            }),
+            vec!["path"],
+        )
+    }
+
+    async fn execute(&self, input: Value) -> Result<String> {
+        let path = input
+            .get("path")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| anyhow!("Missing 'path' parameter"))?;
+        let pages = input.get("pages").and_then(|v| v.as_str());
+        let password = input.get("password").and_then(|v| v.as_str());
+        let max_chars = input
+            .get("max_chars")
+            .and_then(|v| v.as_u64())
+            .map(|n| n as usize)
+            .unwrap_or(DEFAULT_MAX_CHARS);
+        let ingest = input
+            .get("ingest")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false);
+
+        let expanded = expand_home(path);
+        let validated = validate_file_path(&expanded.to_string_lossy(), false)?;
+
+        let (info, pages, ocr_missing) = read_pdf(&validated, pages, password).await?;
+
+        let mut output = format!(
+            "PDF: {}\nTitle: {}\nAuthor: {}\nPages: {}",
+            validated.display(),
+            info.title.as_deref().unwrap_or("(none)"),
+            info.author.as_deref().unwrap_or("(none)"),
+            info.page_count
+        );
+        if let Some(subject) = &info.subject {
+            output.push_str(&format!("\nSubject: {}", subject));
+        }
+        if info.encrypted {
+            output.push_str("\nEncrypted: yes");
+        }
+
+        if ingest {
+            let tags: Vec<String> = input
+                .get("tags")
+                .and_then(|v| v.as_array())
+                .map(|arr| {
+                    arr.iter()
+                        .filter_map(|v| v.as_str().map(String::from))
+                        .collect()
+                })
+                .unwrap_or_default();
+            let filename = validated
+                .file_name()
+                .map(|n| n.to_string_lossy().to_string())
+                .unwrap_or_else(|| "document.pdf".to_string());
+            let title = input
+                .get("title")
+                .and_then(|v| v.as_str())
+                .map(String::from)
+                .or_else(|| info.title.clone())
+                .unwrap_or(filename);
+
+            // Index the whole document, reusing already-extracted pages when possible
+            let full_text = if pages.len() as u32 == info.page_count {
+                pages
+                    .iter()
+                    .map(|p| p.text.as_str())
+                    .filter(|t| !t.is_empty())
+                    .collect::<Vec<_>>()
+                    .join("\n\n")
+            } else {
+                extract_file_text(&validated, password).await?
+            };
+            if full_text.is_empty() {
+                output.push_str("\nIngest: skipped, no text could be extracted");
+            } else {
+                let (doc_id, metadata, _) = index_document(
+                    &self.graph,
+                    &self.chunking_config,
+                    &title,
+                    &validated.to_string_lossy(),
+                    "application/pdf",
+                    &full_text,
+                    &tags,
+                )
+                .await?;
+                output.push_str(&format!(
+                    "\nIngested '{}': {} chunks (Document ID: {}). Use smart_recall to query it.",
+                    title, metadata.chunk_count, doc_id
+                ));
+            }
+        }
+
+        let mut used = 0;
+        let mut truncated_at = None;
+        for page in &pages {
+            if used >= max_chars {
+                truncated_at = Some(page.number);
+                break;
+            }
+            let marker = if page.ocr { " (OCR)" } else { "" };
+            output.push_str(&format!("\n\n--- Page {}{} ---\n", page.number, marker));
+            if page.text.is_empty() {
+                output.push_str("(no text)");
+                continue;
+            }
+            let remaining = max_chars - used;
+            if page.text.len() > remaining {
+                let mut cut = remaining;
+                while !page.text.is_char_boundary(cut) {
+                    cut -= 1;
+                }
+                output.push_str(&page.text[..cut]);
+                output.push_str("\n[page truncated]");
+                used = max_chars;
+            } else {
+                output.push_str(&page.text);
+                used += page.text.len();
+            }
+        }
+
+        if let Some(next) = truncated_at {
+            output.push_str(&format!(
+                "\n\n[Output truncated at {} characters. Continue with pages=\"{}-\", or use \
+                 ingest=true to index the whole document.]",
+                max_chars, next
+            ));
+        }
+        if ocr_missing {
+            output.push_str(
+                "\n\nSome pages have no text layer (likely scanned). Install poppler \
+                 (pdftoppm) and tesseract to enable OCR.",
+            );
+        }
+
+        Ok(output)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use lopdf::content::{Content, Operation};
+    use lopdf::{Object, Stream, dictionary};
+
+    /// Build a small PDF with one line of text per page ("" for a blank page)
+    fn make_pdf(pages: &[&str]) -> Vec<u8> {
+        let mut doc = Document::with_version("1.5");
+        let pages_id = doc.new_object_id();
+        let font_id = doc.add_object(dictionary! {
+            "Type" => "Font",
+            "Subtype" => "Type1",
+            "BaseFont" => "Courier",
+        });
+        let resources_id = doc.add_object(dictionary! {
+            "Font" => dictionary! { "F1" => font_id },
+        });
+
+        let mut kids = Vec::new();
+        for text in pages {
+            let operations = if text.is_empty() {
+                vec![]
+            } else {
+                vec![
+                    Operation::new("BT", vec![]),
+                    Operation::new("Tf", vec!["F1".into(), 24.into()]),
+                    Operation::new("Td", vec![100.into(), 600.into()]),
+                    Operation::new("Tj", vec![Object::string_literal(*text)]),
+                    Operation::new("ET", vec![]),
+                ]
+            };
+            let content = Content { operations };
+            let content_id = doc.add_object(Stream::new(dictionary! {}, content.encode().unwrap()));
+            let page_id = doc.add_object(dictionary! {
+                "Type" => "Page",
+                "Parent" => pages_id,
+                "Contents" => content_id,
+            });
+            kids.push(page_id.into());
+        }
+
+        let count = kids.len() as i64;
+        doc.objects.insert(
+            pages_id,
+            Object::Dictionary(dictionary! {
+                "Type" => "Pages",
+                "Kids" => kids,
+                "Count" => count,
+                "Resources" => resources_id,
+                "MediaBox" => vec![0.into(), 0.into(), 595.into(), 842.into()],
+            }),
+        );
+        let catalog_id = doc.add_object(dictionary! {
+            "Type" => "Catalog",
+            "Pages" => pages_id,
+        });
+        doc.trailer.set("Root", catalog_id);
+        let info_id = doc.add_object(dictionary! {
+            "Title" => Object::string_literal("Quarterly Report"),
+            "Author" => Object::string_literal("Finance Team"),
+        });
+        doc.trailer.set("Info", info_id);
+
+        let mut buf = Vec::new();
+        doc.save_to(&mut buf).unwrap();
+        buf
+    }
+
+    #[test]
+    fn test_parse_page_range() {
+        assert_eq!(parse_page_range("1-3", 10).unwrap(), vec![1, 2, 3]);
+        assert_eq!(parse_page_range("5,2,2", 10).unwrap(), vec![2, 5]);
+        assert_eq!(parse_page_range("8-", 10).unwrap(), vec![8, 9, 10]);
+        assert_eq!(parse_page_range("-2", 10).unwrap(), vec![1, 2]);
+        assert_eq!(parse_page_range("9-20", 10).unwrap(), vec![9, 10]);
+        assert!(parse_page_range("0", 10).is_err());
+        assert!(parse_page_range("4-2", 10).is_err());
+        assert!(parse_page_range("11", 10).is_err());
+        assert!(parse_page_range("abc", 10).is_err());
+        assert!(parse_page_range("", 10).is_err());
+    }
+
+    #[test]
+    fn test_extract_text_and_metadata() {
+        let bytes = make_pdf(&["Revenue grew", "Costs fell", "Outlook"]);
+        let (info, pages) = extract(&bytes, None, Some("2-3")).unwrap();
+        assert_eq!(info.page_count, 3);
+        assert_eq!(info.title.as_deref(), Some("Quarterly Report"));
+        assert_eq!(info.author.as_deref(), Some("Finance Team"));
+        assert!(!info.encrypted);
+        assert_eq!(pages.len(), 2);
+        assert_eq!(pages[0].number, 2);
+        assert!(pages[0].text.contains("Costs fell"));
+    }
+
+    #[test]
+    fn test_extract_invalid_pdf() {
+        assert!(extract(b"not a pdf", None, None).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_read_pdf_blank_page() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let path = temp.path().join("scan.pdf");
+        std::fs::write(&path, make_pdf(&["Cover", ""])).unwrap();
+
+        let (info, pages, _) = read_pdf(&path, None, None).await.unwrap();
+        assert_eq!(info.page_count, 2);
+        assert!(pages[0].text.contains("Cover"));
+        // Blank page either stays empty or gets (empty) OCR output
+        assert!(pages[1].text.is_empty() || pages[1].ocr);
+    }
+
+    #[tokio::test]
+    async fn test_extract_file_text_joins_pages() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let path = temp.path().join("doc.pdf");
+        std::fs::write(&path, make_pdf(&["Alpha", "Beta"])).unwrap();
+
+        let text = extract_file_text(&path, None).await.unwrap();
+        assert!(text.contains("Alpha"));
+        assert!(text.contains("Beta"));
+    }
+
+    #[tokio::test]
+    async fn test_read_pdf_tool_truncates_and_ingests() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let graph = Arc::new(
+            KnowledgeGraph::new(temp.path().join("test.db"), temp.path().join("index")).unwrap(),
+        );
+        let path = temp.path().join("report.pdf");
+        std::fs::write(&path, make_pdf(&["First page text", "Second page text"])).unwrap();
+
+        let tool = ReadPdfTool::new(graph);
+        assert_eq!(tool.name(), "read_pdf");
+        let out = tool
+            .execute(serde_json::json!({
+                "path": path.to_str().unwrap(),
+                "max_chars": 10,
+                "ingest": true
+            }))
+            .await
+            .unwrap();
+        assert!(out.contains("Title: Quarterly Report"));
+        assert!(out.contains("Pages: 2"));
+        assert!(out.contains("Ingested 'Quarterly Report': 1 chunks"));
+        assert!(out.contains("[page truncated]"));
+        assert!(out.contains("pages=\"2-\""));
+    }
+
+    #[tokio::test]
+    async fn test_read_pdf_hidden_from_restricted_profiles() {
+        use crate::agents::{AgentProfile, ProfileRestrictions, RestrictedToolExecutor};
+        use crate::tools::{ToolExecutor, ToolRegistry};
+
+        let temp = tempfile::TempDir::new().unwrap();
+        let graph = Arc::new(
+            KnowledgeGraph::new(temp.path().join("test.db"), temp.path().join("index")).unwrap(),
+        );
+        let path = temp.path().join("report.pdf");
+        std::fs::write(&path, make_pdf(&["First page text"])).unwrap();
+        let mut registry = ToolRegistry::new();
+        registry.register(Arc::new(ReadPdfTool::new(graph)));
+
+        let mut profile = AgentProfile::new("kid", "Sam");
+        profile.tools = vec!["read_pdf".to_string()];
+        profile.restrictions = Some(ProfileRestrictions {
+            blocked_terms: Vec::new(),
+            personal_knowledge: false,
+            daily_message_limit: None,
+        });
+        let executor = RestrictedToolExecutor::new(Arc::new(registry), profile);
+        assert!(executor.list_tools().is_empty());
+        let ingest = serde_json::json!({"path": path.to_str().unwrap(), "ingest": true});
+        assert!(executor.execute("read_pdf", ingest).await.is_err());
+    }
+}
@@ -0,0 +1,170 @@
+//! Pin tools — keep facts in a conversation's context
+
+use anyhow::{Result, anyhow};
+use async_trait::async_trait;
+use serde_json::Value;
+use std::sync::Arc;
+use tracing::debug;
+
+use super::{ToolHandler, json_schema};
+use crate::pins;
+use meepo_knowledge::KnowledgeDb;
+
+/// The conversation a pin tool call is for: the current turn's, unless the
+/// input names one
+fn conversation(input: &Value) -> Result<(String, String)> {
+    let channel = input.get("channel").and_then(|v| v.as_str());
+    let sender = input.get("sender").and_then(|v| v.as_str());
+    match (channel, sender) {
+        (Some(channel), Some(sender)) => Ok((channel.to_string(), sender.to_string())),
+        (None, None) => pins::current_conversation()
+            .ok_or_else(|| anyhow!("No current conversation; provide 'channel' and 'sender'")),
+        _ => Err(anyhow!("Provide both 'channel' and 'sender', or neither")),
+    }
+}
+
+fn conversation_properties() -> serde_json::Map<String, Value> {
+    let Value::Object(properties) = serde_json::json!({
+        "channel": {
+            "type": "string",
+            "description": "Channel of the conversation; omit for the current one"
+        },
+        "sender": {
+            "type": "string",
+            "description": "Sender of the conversation; omit for the current one"
+        }
+    }) else {
+        unreachable!()
+    };
+    properties
+}
+
+/// Pin a fact to the current conversation
+pub struct PinContextTool {
+    db: Arc<KnowledgeDb>,
+}
+
+impl PinContextTool {
+    pub fn new(db: Arc<KnowledgeDb>) -> Self {
+        Self { db }
+    }
+}
+
+#[async_trait]
+impl ToolHandler for PinContextTool {
+    fn name(&self) -> &str {
+        "pin_context"
+    }
+
+    fn description(&self) -> &str {
+        "Pin a fact so it stays in this conversation's context for every later message, \
+         however long the thread gets. Use when the user asks you to remember something for \
+         this thread ('pin this', 'keep in mind that...'). Pin a short, self-contained \
+         statement, e.g. 'Budget for the trip is $2,000'."
+    }
+
+    fn input_schema(&self) -> Value {
+        let mut properties = conversation_properties();
+        properties.insert(
+            "text".to_string(),
+            serde_json::json!({
+                "type": "string",
+                "description": "The fact to pin (up to 500 characters)"
+            }),
+        );
+        json_schema(Value::Object(properties), vec!["text"])
+    }
+
+    async fn execute(&self, input: Value) -> Result<String> {
+        let text = input
+            .get("text")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| anyhow!("Missing 'text' parameter"))?;
+        let (channel, sender) = conversation(&input)?;
+        debug!("Pinning to {}:{}: {}", channel, sender, text);
+        pins::pin(&self.db, &channel, &sender, text).await
+    }
+}
+
+/// Remove a pinned fact from the current conversation
+pub struct UnpinContextTool {
+    db: Arc<KnowledgeDb>,
+}
+
+impl UnpinContextTool {
+    pub fn new(db: Arc<KnowledgeDb>) -> Self {
+        Self { db }
+    }
+}
+
+#[async_trait]
+impl ToolHandler for UnpinContextTool {
+    fn name(&self) -> &str {
+        "unpin_context"
+    }
+
+    fn description(&self) -> &str {
+        "Remove a fact pinned to this conversation, by its number in the Pinned section, \
+         by a unique piece of its text, or 'all'. Use when the user says a pinned fact no \
+         longer applies."
+    }
+
+    fn input_schema(&self) -> Value {
+        let mut properties = conversation_properties();
+        properties.insert(
+            "target".to_string(),
+            serde_json::json!({
+                "type": "string",
+                "description": "Pin number, text to match, or 'all'"
+            }),
+        );
+        json_schema(Value::Object(properties), vec!["target"])
+    }
+
+    async fn execute(&self, input: Value) -> Result<String> {
+        let target = input
+            .get("target")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| anyhow!("Missing 'target' parameter"))?;
+        let (channel, sender) = conversation(&input)?;
+        debug!("Unpinning '{}' from {}:{}", target, channel, sender);
+        pins::unpin(&self.db, &channel, &sender, target).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[tokio::test]
+    async fn test_pin_tools_use_current_conversation() {
+        let temp = TempDir::new().unwrap();
+        let db = Arc::new(KnowledgeDb::new(temp.path().join("test.db")).unwrap());
+        let pin = PinContextTool::new(db.clone());
+        let unpin = UnpinContextTool::new(db.clone());
+
+        // Outside a turn the conversation must be named
+        assert!(
+            pin.execute(serde_json::json!({"text": "Budget is $2k"}))
+                .await
+                .is_err()
+        );
+
+        let out = pins::scope_conversation("slack".to_string(), "alice".to_string(), async {
+            pin.execute(serde_json::json!({"text": "Budget is $2k"}))
+                .await
+                .unwrap()
+        })
+        .await;
+        assert_eq!(out, "Pinned #1: Budget is $2k");
+        assert_eq!(pins::load(&db, "slack", "alice").await.unwrap().len(), 1);
+
+        let out = unpin
+            .execute(serde_json::json!({"target": "1", "channel": "slack", "sender": "alice"}))
+            .await
+            .unwrap();
+        assert_eq!(out, "Unpinned: Budget is $2k");
+        assert!(pins::load(&db, "slack", "alice").await.unwrap().is_empty());
+    }
+}
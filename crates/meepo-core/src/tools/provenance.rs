@@ -0,0 +1,322 @@
+//! Knowledge provenance — "why do you think that?"
+//!
+//! Re-runs retrieval for a claim the agent made and lists what supports it:
+//! the entities and document chunks it matches (with where they came from
+//! and when they were stored), the relationships that connect them, and
+//! past messages that mention the same things. Gives the user something to
+//! check, and the IDs to correct or forget a wrong memory.
+
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::sync::Arc;
+use tracing::debug;
+
+use super::{ToolHandler, json_schema};
+use meepo_knowledge::embeddings::content_words;
+use meepo_knowledge::graph_rag::{
+    EntitySource, GraphRagConfig, ScoredEntity, graph_expand, source_type,
+};
+use meepo_knowledge::{Conversation, Entity, KnowledgeDb, KnowledgeGraph};
+
+/// Longest excerpt shown for one source
+const EXCERPT_CHARS: usize = 240;
+
+/// Claim words searched for in past messages
+const CONVERSATION_WORDS: usize = 4;
+
+/// Show the stored knowledge and messages behind a claim
+pub struct ExplainRecallTool {
+    graph: Arc<KnowledgeGraph>,
+    db: Arc<KnowledgeDb>,
+}
+
+impl ExplainRecallTool {
+    pub fn new(graph: Arc<KnowledgeGraph>, db: Arc<KnowledgeDb>) -> Self {
+        Self { graph, db }
+    }
+
+    /// Past messages sharing the most content words with the claim
+    async fn supporting_conversations(
+        &self,
+        claim: &str,
+        limit: usize,
+    ) -> Result<Vec<Conversation>> {
+        let mut words = content_words(claim);
+        words.sort();
+        words.dedup();
+        words.sort_by_key(|w| std::cmp::Reverse(w.chars().count()));
+        words.truncate(CONVERSATION_WORDS);
+
+        let mut found: HashMap<String, (usize, Conversation)> = HashMap::new();
+        for word in &words {
+            for convo in self.db.search_conversations(word, limit * 4).await? {
+                found.entry(convo.id.clone()).or_insert((0, convo)).0 += 1;
+            }
+        }
+        // Messages matching a single word only count for one-word claims
+        let needed = words.len().min(2);
+        let mut ranked: Vec<(usize, Conversation)> = found
+            .into_values()
+            .filter(|(hits, _)| *hits >= needed)
+            .collect();
+        ranked.sort_by(|a, b| b.0.cmp(&a.0).then(b.1.created_at.cmp(&a.1.created_at)));
+        Ok(ranked.into_iter().take(limit).map(|(_, c)| c).collect())
+    }
+
+    /// "from <document title> (<path>)" for a document chunk
+    async fn parent_document(&self, entity: &Entity) -> Option<String> {
+        let parent = entity.metadata.as_ref()?.get("parent_document")?.as_str()?;
+        let document = self.db.get_entity(parent).await.ok()??;
+        let path = document
+            .metadata
+            .as_ref()
+            .and_then(|m| m.get("source_path"))
+            .and_then(|p| p.as_str());
+        Some(match path {
+            Some(path) => format!("{} ({})", document.name, path),
+            None => document.name,
+        })
+    }
+}
+
+/// The stored text of an entity, shortened for display
+fn excerpt(entity: &Entity) -> Option<String> {
+    let metadata = entity.metadata.as_ref()?;
+    let text = match metadata.get("full_content").and_then(|c| c.as_str()) {
+        Some(content) => content.to_string(),
+        None => {
+            let mut fields = metadata.as_object()?.clone();
+            for key in ["content_hash", "tags", "source", "parent_document"] {
+                fields.remove(key);
+            }
+            if fields.is_empty() {
+                return None;
+            }
+            Value::Object(fields).to_string()
+        }
+    };
+    Some(shorten(&text))
+}
+
+fn shorten(text: &str) -> String {
+    let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    if text.chars().count() > EXCERPT_CHARS {
+        let truncated: String = text.chars().take(EXCERPT_CHARS - 3).collect();
+        format!("{}...", truncated)
+    } else {
+        text
+    }
+}
+
+/// How a source was found, e.g. "direct match (relevance 1.42)"
+fn how_found(scored: &ScoredEntity, names: &HashMap<String, String>) -> String {
+    match &scored.source {
+        EntitySource::DirectMatch { search_score } => {
+            format!("direct match (relevance {:.2})", search_score)
+        }
+        EntitySource::GraphExpansion { from_entity_id, .. } => {
+            let from = names
+                .get(from_entity_id)
+                .map_or(from_entity_id.as_str(), String::as_str);
+            let relation = scored
+                .connecting_relationships
+                .iter()
+                .find(|r| r.source_id == *from_entity_id || r.target_id == *from_entity_id)
+                .map_or("a relationship", |r| r.relation_type.as_str());
+            format!("linked to {} by {}", from, relation)
+        }
+    }
+}
+
+#[async_trait]
+impl ToolHandler for ExplainRecallTool {
+    fn name(&self) -> &str {
+        "explain_recall"
+    }
+
+    fn reads_personal_knowledge(&self) -> bool {
+        true
+    }
+
+    fn description(&self) -> &str {
+        "Show where a claim you made came from: the stored knowledge, document chunks and \
+         past messages that support it, with when and where each was recorded. Use when \
+         the user asks 'why do you think that?' or 'how do you know?', or doubts something \
+         you remembered. Pass the claim as you stated it. Share the sources with the user \
+         so they can confirm or correct them."
+    }
+
+    fn input_schema(&self) -> Value {
+        json_schema(
+            serde_json::json!({
+                "claim": {
+                    "type": "string",
+                    "description": "The statement to trace back to its sources"
+                },
+                "limit": {
+                    "type": "number",
+                    "description": "Maximum sources of each kind (default: 5)"
+                }
+            }),
+            vec!["claim"],
+        )
+    }
+
+    async fn execute(&self, input: Value) -> Result<String> {
+        let claim = input
+            .get("claim")
+            .and_then(|v| v.as_str())
+            .map(str::trim)
+            .filter(|c| !c.is_empty())
+            .ok_or_else(|| anyhow::anyhow!("Missing 'claim' parameter"))?;
+        let limit = input
+            .get("limit")
+            .and_then(|v| v.as_u64())
+            .unwrap_or(5)
+            .clamp(1, 20) as usize;
+
+        debug!("Explaining recall for: {}", claim);
+
+        let seeds: Vec<(String, f32)> = self
+            .graph
+            .search(claim, limit)
+            .context("Failed to search knowledge graph")?
+            .into_iter()
+            .map(|r| (r.id, r.score))
+            .collect();
+        let config = GraphRagConfig {
+            max_hops: 1,
+            max_expanded_results: limit * 2,
+            ..Default::default()
+        };
+        let sources = graph_expand(&self.db, &seeds, &config)
+            .await
+            .context("Failed to expand sources")?;
+        let conversations = self
+            .supporting_conversations(claim, limit)
+            .await
+            .context("Failed to search past messages")?;
+
+        if sources.is_empty() && conversations.is_empty() {
+            return Ok(format!(
+                "Nothing stored supports \"{}\". It may have come from general knowledge \
+                 or this conversation rather than memory.",
+                claim
+            ));
+        }
+
+        let names: HashMap<String, String> = sources
+            .iter()
+            .map(|s| (s.entity.id.clone(), s.entity.name.clone()))
+            .collect();
+        let mut output = format!("Sources for \"{}\":\n", claim);
+        if !sources.is_empty() {
+            output.push_str("\nStored knowledge:\n");
+            for (i, scored) in sources.iter().enumerate() {
+                let entity = &scored.entity;
+                output.push_str(&format!(
+                    "{}. {} ({}) — {}\n",
+                    i + 1,
+                    entity.name,
+                    entity.entity_type,
+                    how_found(scored, &names)
+                ));
+                output.push_str(&format!(
+                    "   Stored {}, updated {}, source: {}, id: {}\n",
+                    entity.created_at.format("%Y-%m-%d %H:%M"),
+                    entity.updated_at.format("%Y-%m-%d %H:%M"),
+                    source_type(entity),
+                    entity.id
+                ));
+                if let Some(document) = self.parent_document(entity).await {
+                    output.push_str(&format!("   From document: {}\n", document));
+                }
+                if let Some(text) = excerpt(entity) {
+                    output.push_str(&format!("   \"{}\"\n", text));
+                }
+            }
+        }
+        if !conversations.is_empty() {
+            output.push_str("\nPast messages:\n");
+            for convo in &conversations {
+                output.push_str(&format!(
+                    "- [{}] {} on {}: {}\n",
+                    convo.created_at.format("%Y-%m-%d %H:%M"),
+                    convo.sender,
+                    convo.channel,
+                    shorten(&convo.content)
+                ));
+            }
+        }
+
+        Ok(output)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[tokio::test]
+    async fn test_explain_recall_lists_sources() {
+        let temp = TempDir::new().unwrap();
+        let graph = Arc::new(
+            KnowledgeGraph::new(temp.path().join("test.db"), temp.path().join("index")).unwrap(),
+        );
+        let db = graph.db();
+        let alice = graph
+            .add_entity(
+                "Alice Chen",
+                "person",
+                Some(serde_json::json!({"role": "designer", "source": "email"})),
+            )
+            .await
+            .unwrap();
+        let acme = graph
+            .add_entity("Acme Corp", "company", None)
+            .await
+            .unwrap();
+        graph
+            .link_entities(&alice, &acme, "works_at", None)
+            .await
+            .unwrap();
+        db.insert_conversation(
+            "slack",
+            "alice",
+            "Alice here, I just started as a designer at the studio",
+            None,
+        )
+        .await
+        .unwrap();
+        db.insert_conversation("slack", "bob", "The designer lamp arrived", None)
+            .await
+            .unwrap();
+
+        let tool = ExplainRecallTool::new(graph.clone(), db);
+        let out = tool
+            .execute(serde_json::json!({"claim": "Alice is a designer"}))
+            .await
+            .unwrap();
+        assert!(out.contains("1. Alice Chen (person) — direct match"));
+        assert!(out.contains("source: email"));
+        assert!(out.contains(&format!("id: {}", alice)));
+        assert!(out.contains("\"{\"role\":\"designer\"}\""));
+        assert!(out.contains("Acme Corp (company) — linked to Alice Chen by works_at"));
+        assert!(out.contains("alice on slack: Alice here"));
+        assert!(!out.contains("lamp"));
+
+        let out = tool
+            .execute(serde_json::json!({"claim": "Zebras migrate in winter"}))
+            .await
+            .unwrap();
+        assert!(out.starts_with("Nothing stored supports"));
+        assert!(
+            tool.execute(serde_json::json!({"claim": " "}))
+                .await
+                .is_err()
+        );
+    }
+}
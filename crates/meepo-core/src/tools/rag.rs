@@ -7,15 +7,20 @@
 use anyhow::{Context, Result};
 use async_trait::async_trait;
 use serde_json::Value;
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
 use std::sync::Arc;
 use tracing::{debug, info};
 
 use super::{ToolHandler, json_schema};
+use crate::action_items::{self, ActionItemConfig};
+use crate::rerank::Reranker;
 use meepo_knowledge::chunking::{
-    ChunkingConfig, DocumentMetadata, chunk_text, detect_content_type,
+    ChunkingConfig, DocumentMetadata, chunk_document, detect_content_type,
 };
 use meepo_knowledge::graph_rag::{GraphRagConfig, format_graph_context, graph_expand};
-use meepo_knowledge::{KnowledgeDb, KnowledgeGraph};
+use meepo_knowledge::tagging::normalize_tag;
+use meepo_knowledge::{DocumentRevision, KnowledgeDb, KnowledgeGraph, NewEntity, NewRelationship};
 
 /// Smart recall tool that uses GraphRAG for relationship-aware retrieval.
 ///
@@ -25,6 +30,7 @@ pub struct SmartRecallTool {
     graph: Arc<KnowledgeGraph>,
     db: Arc<KnowledgeDb>,
     config: GraphRagConfig,
+    reranker: Option<Arc<Reranker>>,
 }
 
 impl SmartRecallTool {
@@ -33,6 +39,7 @@ impl SmartRecallTool {
             graph,
             db,
             config: GraphRagConfig::default(),
+            reranker: None,
         }
     }
 
@@ -40,6 +47,52 @@ impl SmartRecallTool {
         self.config = config;
         self
     }
+
+    /// Re-rank search hits before graph expansion
+    pub fn with_reranker(mut self, reranker: Arc<Reranker>) -> Self {
+        self.reranker = Some(reranker);
+        self
+    }
+
+    /// Reorder seeds by the reranker's relevance scores, keeping the search
+    /// order if it can't score them
+    async fn rerank_seeds(
+        &self,
+        reranker: &Reranker,
+        query: &str,
+        seeds: Vec<(String, f32)>,
+    ) -> Result<Vec<(String, f32)>> {
+        let mut texts = Vec::with_capacity(seeds.len());
+        for (id, _) in &seeds {
+            let text = match self.db.get_entity(id).await? {
+                Some(entity) => rerank_text(&entity),
+                None => String::new(),
+            };
+            texts.push(text);
+        }
+        let texts: Vec<&str> = texts.iter().map(String::as_str).collect();
+        Ok(match reranker.rerank(query, &texts).await {
+            Some(ranked) => ranked
+                .into_iter()
+                .map(|(i, score)| (seeds[i].0.clone(), score))
+                .collect(),
+            None => seeds,
+        })
+    }
+}
+
+/// What the reranker sees of an entity: its name, then a chunk's content or
+/// other entities' metadata
+fn rerank_text(entity: &meepo_knowledge::Entity) -> String {
+    let body = entity.metadata.as_ref().map(|m| {
+        m.get("full_content")
+            .and_then(|c| c.as_str())
+            .map_or_else(|| m.to_string(), str::to_string)
+    });
+    match body {
+        Some(body) => format!("{}\n{}", entity.name, body),
+        None => entity.name.clone(),
+    }
 }
 
 #[async_trait]
@@ -48,10 +101,15 @@ impl ToolHandler for SmartRecallTool {
         "smart_recall"
     }
 
+    fn reads_personal_knowledge(&self) -> bool {
+        true
+    }
+
     fn description(&self) -> &str {
         "Search the knowledge graph with relationship-aware retrieval (GraphRAG). \
          Finds directly matching entities AND related knowledge by traversing \
-         entity relationships. Returns richer context than basic recall."
+         entity relationships. Returns richer context than basic recall. \
+         Pass a tag (see list_tags) to stay within one topic."
     }
 
     fn input_schema(&self) -> Value {
@@ -67,41 +125,105 @@ impl ToolHandler for SmartRecallTool {
                 },
                 "max_hops": {
                     "type": "number",
-                    "description": "Maximum relationship hops to traverse (default: 2)"
+                    "description": "Maximum relationship hops to traverse (default: from config, normally 2)"
+                },
+                "tag": {
+                    "type": "string",
+                    "description": "Optional: only start from items with this tag"
                 }
             }),
-            vec!["query"],
+            vec![],
         )
     }
 
     async fn execute(&self, input: Value) -> Result<String> {
-        let query = input
-            .get("query")
-            .and_then(|v| v.as_str())
-            .ok_or_else(|| anyhow::anyhow!("Missing 'query' parameter"))?;
+        let query = input.get("query").and_then(|v| v.as_str()).unwrap_or("");
         let limit = input.get("limit").and_then(|v| v.as_u64()).unwrap_or(5) as usize;
-        let max_hops = input.get("max_hops").and_then(|v| v.as_u64()).unwrap_or(2) as usize;
+        let max_hops = input
+            .get("max_hops")
+            .and_then(|v| v.as_u64())
+            .map_or(self.config.max_hops, |n| n as usize);
+        let tag = input
+            .get("tag")
+            .and_then(|v| v.as_str())
+            .map(normalize_tag)
+            .filter(|t| !t.is_empty());
+        if query.is_empty() && tag.is_none() {
+            return Err(anyhow::anyhow!("Provide a 'query' or a 'tag'"));
+        }
 
         debug!(
-            "Smart recall for: {} (limit={}, hops={})",
-            query, limit, max_hops
+            "Smart recall for: {} (limit={}, hops={}, tag={:?})",
+            query, limit, max_hops, tag
         );
 
-        // Step 1: Search using Tantivy full-text search
-        let search_results = self
-            .graph
-            .search(query, limit)
-            .context("Failed to search knowledge graph")?;
+        // With a reranker, fetch its top-N candidates and keep the best `limit`
+        let candidates = match &self.reranker {
+            Some(reranker) if !query.is_empty() => reranker.top_n().max(limit),
+            _ => limit,
+        };
 
-        if search_results.is_empty() {
+        // Step 1: Full-text search (fused with embeddings when configured),
+        // limited to the tag if given
+        let mut seeds: Vec<(String, f32)> = match &tag {
+            Some(tag) => {
+                let tagged = self
+                    .db
+                    .search_entities_tagged("", None, Some(tag))
+                    .await
+                    .context("Failed to filter by tag")?;
+                if query.is_empty() {
+                    tagged
+                        .into_iter()
+                        .take(limit)
+                        .map(|e| (e.id, 1.0))
+                        .collect()
+                } else {
+                    let ids: std::collections::HashSet<String> =
+                        tagged.into_iter().map(|e| e.id).collect();
+                    self.graph
+                        .hybrid_search(query, candidates * 5)
+                        .await
+                        .context("Failed to search knowledge graph")?
+                        .into_iter()
+                        .filter(|r| ids.contains(&r.id))
+                        .take(candidates)
+                        .map(|r| (r.id, r.score))
+                        .collect()
+                }
+            }
+            None => self
+                .graph
+                .hybrid_search(query, candidates)
+                .await
+                .context("Failed to search knowledge graph")?
+                .into_iter()
+                .map(|r| (r.id, r.score))
+                .collect(),
+        };
+
+        if let Some(reranker) = &self.reranker
+            && seeds.len() > 1
+            && !query.is_empty()
+        {
+            seeds = self.rerank_seeds(reranker, query, seeds).await?;
+        }
+        seeds.truncate(limit);
+
+        // An exact alias ("K8s", "Liz") seeds its entity at the top score
+        if !query.is_empty() && tag.is_none() {
+            let top = seeds.first().map(|(_, score)| *score).unwrap_or(1.0);
+            for entity in self.db.find_entities_by_alias(query).await? {
+                seeds.retain(|(id, _)| *id != entity.id);
+                seeds.insert(0, (entity.id, top));
+            }
+        }
+
+        if seeds.is_empty() {
             return Ok("No matching knowledge found.".to_string());
         }
 
         // Step 2: Expand via GraphRAG
-        let seeds: Vec<(String, f32)> = search_results
-            .iter()
-            .map(|r| (r.id.clone(), r.score))
-            .collect();
 
         let config = GraphRagConfig {
             max_hops,
@@ -123,8 +245,8 @@ impl ToolHandler for SmartRecallTool {
         let mut output = format!(
             "Found {} result(s) ({} direct, {} via relationships):\n\n",
             expanded.len(),
-            search_results.len(),
-            expanded.len().saturating_sub(search_results.len())
+            seeds.len(),
+            expanded.len().saturating_sub(seeds.len())
         );
         output.push_str(&context);
 
@@ -132,10 +254,195 @@ impl ToolHandler for SmartRecallTool {
     }
 }
 
+/// What ingesting did to the stored copy of a document
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IngestChange {
+    New,
+    /// Same content as last time; nothing was re-chunked
+    Unchanged,
+    /// Re-chunked, reusing chunks whose content didn't change
+    Updated {
+        kept: usize,
+        added: usize,
+        removed: usize,
+    },
+}
+
+/// Chunk a document and index it in the knowledge graph.
+///
+/// Creates a `document` entity with one linked `document_chunk` entity per
+/// chunk, and links consecutive chunks with `next_chunk`. A document already
+/// ingested from the same source path is updated in place: if its content
+/// hash changed it is re-chunked, chunks with unchanged content keep their
+/// entities, and superseded chunks leave the database and search index in
+/// one step.
+pub async fn index_document(
+    graph: &KnowledgeGraph,
+    chunking_config: &ChunkingConfig,
+    doc_title: &str,
+    path: &str,
+    content_type: &str,
+    content: &str,
+    tags: &[String],
+) -> Result<(String, DocumentMetadata, IngestChange)> {
+    let db = graph.db();
+    let content_hash = sha256_hex(content);
+    let chunks = chunk_document(content, content_type, chunking_config);
+    let doc_metadata = serde_json::json!({
+        "source_path": path,
+        "content_type": content_type,
+        "content_hash": content_hash,
+        "total_chars": content.len(),
+        "chunk_count": chunks.len(),
+        "tags": tags,
+    });
+    let metadata = DocumentMetadata {
+        source_path: Some(path.to_string()),
+        title: Some(doc_title.to_string()),
+        content_type: content_type.to_string(),
+        total_chars: content.len(),
+        chunk_count: chunks.len(),
+    };
+
+    let existing = db.find_document_by_source(path).await?;
+    let (doc_id, old_chunks) = match existing {
+        Some(doc) if doc.name == doc_title && doc.metadata.as_ref() == Some(&doc_metadata) => {
+            debug!("Document {} unchanged, skipping re-chunk", path);
+            return Ok((doc.id, metadata, IngestChange::Unchanged));
+        }
+        Some(doc) => {
+            info!(
+                "Re-ingesting changed document: {} ({} chars, {})",
+                doc_title,
+                content.len(),
+                content_type
+            );
+            let old = db.get_document_chunks(&doc.id).await?;
+            (doc.id, Some(old))
+        }
+        None => {
+            info!(
+                "Ingesting document: {} ({} chars, {})",
+                doc_title,
+                content.len(),
+                content_type
+            );
+            let id = graph
+                .add_entity(doc_title, "document", Some(doc_metadata.clone()))
+                .await
+                .context("Failed to create document entity")?;
+            (id, None)
+        }
+    };
+
+    // Chunks with unchanged content keep their entity, whatever their position
+    let mut unused: HashMap<String, Vec<String>> = HashMap::new();
+    for chunk in old_chunks.iter().flatten() {
+        let hash = chunk
+            .metadata
+            .as_ref()
+            .and_then(|m| m.get("content_hash"))
+            .and_then(|h| h.as_str())
+            .map(String::from)
+            .or_else(|| {
+                chunk
+                    .metadata
+                    .as_ref()
+                    .and_then(|m| m.get("full_content"))
+                    .and_then(|c| c.as_str())
+                    .map(sha256_hex)
+            });
+        if let Some(hash) = hash {
+            unused.entry(hash).or_default().push(chunk.id.clone());
+        }
+    }
+
+    let mut revision = DocumentRevision {
+        document_id: doc_id.clone(),
+        name: doc_title.to_string(),
+        metadata: Some(doc_metadata),
+        ..Default::default()
+    };
+    let mut order = Vec::with_capacity(chunks.len());
+    for chunk in &chunks {
+        let hash = sha256_hex(&chunk.content);
+        let entity = NewEntity {
+            name: format!(
+                "{} [chunk {}/{}]",
+                doc_title,
+                chunk.chunk_index + 1,
+                chunk.total_chunks
+            ),
+            entity_type: "document_chunk".to_string(),
+            metadata: Some(serde_json::json!({
+                "full_content": chunk.content,
+                "content_hash": hash,
+                "chunk_index": chunk.chunk_index,
+                "start_offset": chunk.start_offset,
+                "end_offset": chunk.end_offset,
+                "total_chunks": chunk.total_chunks,
+                "parent_document": doc_id,
+            })),
+        };
+        match unused.get_mut(&hash).filter(|ids| !ids.is_empty()) {
+            Some(ids) => {
+                let id = ids.remove(0);
+                order.push(id.clone());
+                revision.update.push((id, entity));
+            }
+            None => {
+                // Link chunk to parent document
+                revision.relationships.push(NewRelationship {
+                    source: doc_id.clone(),
+                    target: entity.name.clone(),
+                    relation_type: "contains_chunk".to_string(),
+                    metadata: None,
+                });
+                order.push(entity.name.clone());
+                revision.add.push(entity);
+            }
+        }
+    }
+    revision.remove = unused.into_values().flatten().collect();
+
+    // Link consecutive chunks
+    for window in order.windows(2) {
+        revision.relationships.push(NewRelationship {
+            source: window[0].clone(),
+            target: window[1].clone(),
+            relation_type: "next_chunk".to_string(),
+            metadata: None,
+        });
+    }
+
+    let change = match old_chunks {
+        None => IngestChange::New,
+        Some(_) => IngestChange::Updated {
+            kept: revision.update.len(),
+            added: revision.add.len(),
+            removed: revision.remove.len(),
+        },
+    };
+    graph
+        .revise_document(revision)
+        .await
+        .context("Failed to index document chunks")?;
+
+    Ok((doc_id, metadata, change))
+}
+
+fn sha256_hex(text: &str) -> String {
+    Sha256::digest(text.as_bytes())
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
 /// Ingest a document into the knowledge graph by chunking and indexing it.
 pub struct IngestDocumentTool {
     graph: Arc<KnowledgeGraph>,
     chunking_config: ChunkingConfig,
+    action_items: Option<ActionItemConfig>,
 }
 
 impl IngestDocumentTool {
@@ -143,6 +450,7 @@ impl IngestDocumentTool {
         Self {
             graph,
             chunking_config: ChunkingConfig::default(),
+            action_items: None,
         }
     }
 
@@ -150,6 +458,12 @@ impl IngestDocumentTool {
         self.chunking_config = config;
         self
     }
+
+    /// Record action items found in ingested documents (e.g. meeting notes)
+    pub fn with_action_items(mut self, config: ActionItemConfig) -> Self {
+        self.action_items = Some(config);
+        self
+    }
 }
 
 #[async_trait]
@@ -158,10 +472,14 @@ impl ToolHandler for IngestDocumentTool {
         "ingest_document"
     }
 
+    fn reads_personal_knowledge(&self) -> bool {
+        true
+    }
+
     fn description(&self) -> &str {
         "Ingest a document into the knowledge graph. The document is split into \
          chunks and each chunk is indexed for later retrieval. Supports text files, \
-         markdown, code, and other text formats. Use this to build up the knowledge \
+         markdown, code, PDFs, and other text formats. Use this to build up the knowledge \
          base from files."
     }
 
@@ -213,15 +531,6 @@ impl ToolHandler for IngestDocumentTool {
             std::path::PathBuf::from(path)
         };
 
-        // Read the file
-        let content = tokio::fs::read_to_string(&expanded_path)
-            .await
-            .context(format!("Failed to read file: {}", expanded_path.display()))?;
-
-        if content.is_empty() {
-            return Ok("File is empty, nothing to ingest.".to_string());
-        }
-
         let filename = expanded_path
             .file_name()
             .map(|n| n.to_string_lossy().to_string())
@@ -230,89 +539,74 @@ impl ToolHandler for IngestDocumentTool {
         let doc_title = title.unwrap_or(&filename);
         let content_type = detect_content_type(path);
 
-        info!(
-            "Ingesting document: {} ({} chars, {})",
-            doc_title,
-            content.len(),
-            content_type
-        );
-
-        // Chunk the document
-        let chunks = chunk_text(&content, &self.chunking_config);
-
-        // Create a parent document entity
-        let doc_metadata = serde_json::json!({
-            "source_path": path,
-            "content_type": content_type,
-            "total_chars": content.len(),
-            "chunk_count": chunks.len(),
-            "tags": tags,
-        });
-
-        let doc_id = self
-            .graph
-            .add_entity(doc_title, "document", Some(doc_metadata))
-            .await
-            .context("Failed to create document entity")?;
-
-        // Index each chunk as a child entity linked to the document
-        let mut chunk_ids = Vec::new();
-        for chunk in &chunks {
-            let chunk_name = format!(
-                "{} [chunk {}/{}]",
-                doc_title,
-                chunk.chunk_index + 1,
-                chunk.total_chunks
-            );
-
-            let chunk_metadata = serde_json::json!({
-                "full_content": chunk.content,
-                "chunk_index": chunk.chunk_index,
-                "start_offset": chunk.start_offset,
-                "end_offset": chunk.end_offset,
-                "total_chunks": chunk.total_chunks,
-                "parent_document": doc_id,
-            });
-
-            let chunk_id = self
-                .graph
-                .add_entity(&chunk_name, "document_chunk", Some(chunk_metadata))
-                .await
-                .context("Failed to create chunk entity")?;
-
-            // Link chunk to parent document
-            self.graph
-                .link_entities(&doc_id, &chunk_id, "contains_chunk", None)
+        // Read the file (PDFs go through text extraction)
+        let content = if content_type == "application/pdf" {
+            super::pdf::extract_file_text(&expanded_path, None).await?
+        } else {
+            tokio::fs::read_to_string(&expanded_path)
                 .await
-                .context("Failed to link chunk to document")?;
-
-            chunk_ids.push(chunk_id);
-        }
+                .context(format!("Failed to read file: {}", expanded_path.display()))?
+        };
 
-        // Link consecutive chunks
-        for window in chunk_ids.windows(2) {
-            let _ = self
-                .graph
-                .link_entities(&window[0], &window[1], "next_chunk", None)
-                .await;
+        if content.is_empty() {
+            return Ok("File is empty, nothing to ingest.".to_string());
         }
 
-        let metadata = DocumentMetadata {
-            source_path: Some(path.to_string()),
-            title: Some(doc_title.to_string()),
-            content_type: content_type.to_string(),
-            total_chars: content.len(),
-            chunk_count: chunks.len(),
+        let (doc_id, metadata, change) = index_document(
+            &self.graph,
+            &self.chunking_config,
+            doc_title,
+            &expanded_path.to_string_lossy(),
+            content_type,
+            &content,
+            &tags,
+        )
+        .await?;
+
+        let title = metadata.title.as_deref().unwrap_or("unknown");
+        let mut output = match change {
+            IngestChange::New => format!(
+                "Ingested '{}': {} chunks created from {} chars ({})\nDocument ID: {}",
+                title, metadata.chunk_count, metadata.total_chars, metadata.content_type, doc_id
+            ),
+            IngestChange::Unchanged => {
+                return Ok(format!(
+                    "'{}' is unchanged since it was last ingested ({} chunks)\nDocument ID: {}",
+                    title, metadata.chunk_count, doc_id
+                ));
+            }
+            IngestChange::Updated {
+                kept,
+                added,
+                removed,
+            } => format!(
+                "Updated '{}': {} chunks ({} unchanged, {} new, {} removed) from {} chars ({})\nDocument ID: {}",
+                title,
+                metadata.chunk_count,
+                kept,
+                added,
+                removed,
+                metadata.total_chars,
+                metadata.content_type,
+                doc_id
+            ),
         };
-
-        Ok(format!(
-            "Ingested '{}': {} chunks created from {} chars ({})\nDocument ID: {}",
-            metadata.title.as_deref().unwrap_or("unknown"),
-            metadata.chunk_count,
-            metadata.total_chars,
-            metadata.content_type,
-            doc_id
-        ))
+        if let Some(config) = &self.action_items {
+            match action_items::process_document(&self.graph.db(), &content, config).await {
+                Ok(created) if !created.is_empty() => {
+                    output.push_str(&format!(
+                        "\nAction items added as tasks ({}):",
+                        created.len()
+                    ));
+                    for title in &created {
+                        output.push_str(&format!("\n  - {}", title));
+                    }
+                }
+                Ok(_) => {}
+                Err(e) => debug!("Failed to extract action items: {}", e),
+            }
+        }
+        Ok(output)
     }
 }
 
@@ -417,4 +711,75 @@ mod tests {
             .unwrap();
         assert!(result.contains("Found"));
     }
+
+    #[tokio::test]
+    async fn test_reingest_rechunks_only_changed_sections() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let graph = Arc::new(
+            KnowledgeGraph::new(temp.path().join("test.db"), temp.path().join("index")).unwrap(),
+        );
+        let db = graph.db();
+        let file = temp.path().join("notes.md");
+        let path = file.to_str().unwrap();
+        let section = |name: &str, body: &str| format!("## {name}\n\n{}\n\n", body.repeat(12));
+        let ingest = IngestDocumentTool::new(graph.clone()).with_chunking_config(ChunkingConfig {
+            chunk_size: 300,
+            ..Default::default()
+        });
+
+        let original = section("Alpha", "Alpha facts stay. ")
+            + &section("Bravo", "Bravo uses quartzite. ")
+            + &section("Charlie", "Charlie facts stay. ");
+        tokio::fs::write(&file, &original).await.unwrap();
+        ingest
+            .execute(serde_json::json!({"path": path}))
+            .await
+            .unwrap();
+        let doc = db.find_document_by_source(path).await.unwrap().unwrap();
+        let before = db.get_document_chunks(&doc.id).await.unwrap();
+        assert_eq!(before.len(), 3);
+
+        // Same content: nothing to do
+        let result = ingest
+            .execute(serde_json::json!({"path": path}))
+            .await
+            .unwrap();
+        assert!(result.contains("unchanged"));
+
+        let edited = original.replace("Bravo uses quartzite. ", "Bravo uses basalt. ");
+        tokio::fs::write(&file, &edited).await.unwrap();
+        let result = ingest
+            .execute(serde_json::json!({"path": path}))
+            .await
+            .unwrap();
+        assert!(
+            result.contains("(2 unchanged, 1 new, 1 removed)"),
+            "{}",
+            result
+        );
+
+        // Same document entity, untouched chunks kept, stale text gone
+        let after = db.get_document_chunks(&doc.id).await.unwrap();
+        assert_eq!(after.len(), 3);
+        assert_eq!(after[0].id, before[0].id);
+        assert_eq!(after[2].id, before[2].id);
+        assert!(db.get_entity(&before[1].id).await.unwrap().is_none());
+        assert!(graph.search("quartzite", 5).unwrap().is_empty());
+        assert_eq!(graph.search("basalt", 5).unwrap()[0].id, after[1].id);
+        let chain = db.get_relationships_for(&after[1].id).await.unwrap();
+        assert_eq!(
+            chain
+                .iter()
+                .filter(|r| r.relation_type == "next_chunk")
+                .count(),
+            2
+        );
+        assert_eq!(
+            db.search_entities("", Some("document"))
+                .await
+                .unwrap()
+                .len(),
+            1
+        );
+    }
 }
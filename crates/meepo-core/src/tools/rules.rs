@@ -0,0 +1,207 @@
+//! Automation rule tools — create and list "when X then Y" rules
+
+use anyhow::{Result, anyhow};
+use async_trait::async_trait;
+use meepo_knowledge::KnowledgeDb;
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use tracing::debug;
+
+use super::{ToolHandler, json_schema};
+use crate::rules::{self, RuleAction};
+
+/// Tool that stores a declarative automation rule
+pub struct CreateRuleTool {
+    db: Arc<KnowledgeDb>,
+}
+
+impl CreateRuleTool {
+    pub fn new(db: Arc<KnowledgeDb>) -> Self {
+        Self { db }
+    }
+}
+
+#[async_trait]
+impl ToolHandler for CreateRuleTool {
+    fn name(&self) -> &str {
+        "create_rule"
+    }
+
+    fn description(&self) -> &str {
+        "Create a deterministic automation rule that runs without you: when an event of a \
+         type happens (a watcher event like 'email_received', 'file_changed', 'calendar_event', \
+         'message_received' for incoming messages, or '*') and its payload matches the filter, \
+         send a notification or a saved message template, run a tool with fixed arguments, or \
+         hand you a prompt. Prefer this over watchers for simple reactions that don't need \
+         reasoning. {field} in the message, prompt, variables or arguments is replaced from the \
+         event (e.g. '{from}', '{subject}'); templates can also use event fields directly."
+    }
+
+    fn input_schema(&self) -> Value {
+        json_schema(
+            serde_json::json!({
+                "name": {
+                    "type": "string",
+                    "description": "Short name for the rule"
+                },
+                "event": {
+                    "type": "string",
+                    "description": "Event type, e.g. 'email_received', 'message_received' or '*'"
+                },
+                "filter": {
+                    "type": "object",
+                    "description": "Payload field → text it must contain (case-insensitive), e.g. {\"from\": \"boss@\"}; 'source' is the watcher ID or channel:sender",
+                    "additionalProperties": { "type": "string" }
+                },
+                "condition": {
+                    "type": "string",
+                    "description": "Optional expression that must also be true, over kind, source and payload.*, e.g. 'payload.from contains \"boss\" && hour() < 18'. Operators: && || ! == != < <= > >= contains startsWith endsWith in matches; functions: hour() minute() weekday() lower() upper() len()"
+                },
+                "action": {
+                    "type": "string",
+                    "enum": ["notify", "template", "run_tool", "prompt"]
+                },
+                "message": {
+                    "type": "string",
+                    "description": "Notification text (action 'notify')"
+                },
+                "template": {
+                    "type": "string",
+                    "description": "Message template to send (action 'template')"
+                },
+                "vars": {
+                    "type": "object",
+                    "description": "Template variables (action 'template'); missing ones come from the event"
+                },
+                "tool": {
+                    "type": "string",
+                    "description": "Tool to run (action 'run_tool'); destructive tools aren't allowed"
+                },
+                "args": {
+                    "type": "object",
+                    "description": "Fixed tool arguments (action 'run_tool')"
+                },
+                "prompt": {
+                    "type": "string",
+                    "description": "What to ask the agent (action 'prompt')"
+                },
+                "reply_channel": {
+                    "type": "string",
+                    "description": "Where results go, e.g. 'imessage', 'slack' (default: internal)"
+                },
+                "exclusive": {
+                    "type": "boolean",
+                    "description": "Matching events skip the normal agent reply (default: false)"
+                }
+            }),
+            vec!["name", "event", "action"],
+        )
+    }
+
+    async fn execute(&self, input: Value) -> Result<String> {
+        let text = |key: &str| input.get(key).and_then(|v| v.as_str()).map(String::from);
+        let name = text("name").ok_or_else(|| anyhow!("Missing 'name' parameter"))?;
+        let event = text("event").ok_or_else(|| anyhow!("Missing 'event' parameter"))?;
+        let action = match text("action").as_deref() {
+            Some("notify") => RuleAction::Notify {
+                message: text("message").ok_or_else(|| anyhow!("Missing 'message' parameter"))?,
+            },
+            Some("template") => RuleAction::Template {
+                template: text("template")
+                    .ok_or_else(|| anyhow!("Missing 'template' parameter"))?,
+                vars: input.get("vars").cloned().unwrap_or(Value::Null),
+            },
+            Some("run_tool") => RuleAction::RunTool {
+                tool: text("tool").ok_or_else(|| anyhow!("Missing 'tool' parameter"))?,
+                args: input.get("args").cloned().unwrap_or(Value::Null),
+            },
+            Some("prompt") => RuleAction::Prompt {
+                prompt: text("prompt").ok_or_else(|| anyhow!("Missing 'prompt' parameter"))?,
+            },
+            other => return Err(anyhow!("Unknown action {:?}", other)),
+        };
+        let filter: BTreeMap<String, String> = input
+            .get("filter")
+            .and_then(|f| f.as_object())
+            .map(|f| {
+                f.iter()
+                    .map(|(k, v)| {
+                        let v = v
+                            .as_str()
+                            .map(String::from)
+                            .unwrap_or_else(|| v.to_string());
+                        (k.clone(), v)
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+        let condition = text("condition");
+        let reply_channel = text("reply_channel").unwrap_or_else(|| "internal".to_string());
+        let exclusive = input
+            .get("exclusive")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false);
+        debug!("Creating rule '{}' on {}", name, event);
+
+        let id = rules::create_rule(
+            &self.db,
+            &name,
+            &event,
+            &filter,
+            condition.as_deref(),
+            &action,
+            &reply_channel,
+            exclusive,
+        )
+        .await?;
+        Ok(format!(
+            "Created rule {} '{}': when {}{}{} → {} (to {})",
+            id,
+            name,
+            event,
+            rules::describe_filter(&serde_json::to_value(&filter)?),
+            condition.map(|c| format!(" if {}", c)).unwrap_or_default(),
+            action.describe(),
+            reply_channel
+        ))
+    }
+}
+
+/// Tool that lists automation rules
+pub struct ListRulesTool {
+    db: Arc<KnowledgeDb>,
+}
+
+impl ListRulesTool {
+    pub fn new(db: Arc<KnowledgeDb>) -> Self {
+        Self { db }
+    }
+}
+
+#[async_trait]
+impl ToolHandler for ListRulesTool {
+    fn name(&self) -> &str {
+        "list_rules"
+    }
+
+    fn description(&self) -> &str {
+        "List automation rules with their triggers, actions and how often they've fired."
+    }
+
+    fn input_schema(&self) -> Value {
+        json_schema(serde_json::json!({}), vec![])
+    }
+
+    async fn execute(&self, _input: Value) -> Result<String> {
+        let stored = self.db.get_rules(false).await?;
+        if stored.is_empty() {
+            return Ok("No automation rules.".to_string());
+        }
+        let mut output = format!("## Automation Rules ({})\n\n", stored.len());
+        for rule in &stored {
+            output.push_str(&format!("- {}\n", rules::describe(rule)));
+        }
+        Ok(output)
+    }
+}
@@ -6,6 +6,7 @@ use serde_json::Value;
 use std::sync::Arc;
 
 use crate::sandbox::{DockerSandbox, SandboxConfig};
+use crate::tool_cost::{CostClass, LatencyClass, ToolCost};
 use crate::tools::{ToolHandler, json_schema};
 
 /// Tool for executing code in a sandboxed Docker container
@@ -48,6 +49,10 @@ impl ToolHandler for SandboxExecTool {
         )
     }
 
+    fn cost(&self) -> ToolCost {
+        ToolCost::new(CostClass::Low, LatencyClass::Seconds)
+    }
+
     async fn execute(&self, input: Value) -> Result<String> {
         let language = input
             .get("language")
@@ -0,0 +1,254 @@
+//! Script execution tool — quick Python/JavaScript data wrangling in the sandbox
+
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use serde_json::Value;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tracing::debug;
+
+use crate::sandbox::{DockerSandbox, SandboxConfig};
+use crate::tools::{ToolHandler, json_schema};
+
+/// Maximum number of generated files reported back as artifacts
+const MAX_ARTIFACTS: usize = 20;
+
+/// Tool for running short Python or JavaScript scripts against text input
+pub struct ExecuteScriptTool {
+    sandbox: Arc<DockerSandbox>,
+    artifacts_dir: PathBuf,
+}
+
+impl ExecuteScriptTool {
+    pub fn new(config: SandboxConfig, artifacts_dir: PathBuf) -> Self {
+        Self {
+            sandbox: Arc::new(DockerSandbox::new(config)),
+            artifacts_dir,
+        }
+    }
+}
+
+/// List regular files written to the output directory, sorted by name
+async fn collect_artifacts(dir: &Path) -> Result<Vec<(PathBuf, u64)>> {
+    let mut artifacts = Vec::new();
+    let mut entries = tokio::fs::read_dir(dir)
+        .await
+        .with_context(|| format!("Failed to read artifacts dir {}", dir.display()))?;
+    while let Some(entry) = entries.next_entry().await? {
+        let metadata = entry.metadata().await?;
+        if metadata.is_file() {
+            artifacts.push((entry.path(), metadata.len()));
+        }
+    }
+    artifacts.sort_by(|a, b| a.0.cmp(&b.0));
+    Ok(artifacts)
+}
+
+#[async_trait]
+impl ToolHandler for ExecuteScriptTool {
+    fn name(&self) -> &str {
+        "execute_script"
+    }
+
+    fn description(&self) -> &str {
+        "Run a short Python or JavaScript script for data wrangling (parse CSV/JSON, date math, \
+         calculations). Pass prior tool output via 'stdin'. Files the script writes to $OUTPUT_DIR \
+         (the working directory) are returned as artifacts. Runs in an isolated sandbox with \
+         resource limits and no network access."
+    }
+
+    fn input_schema(&self) -> Value {
+        json_schema(
+            serde_json::json!({
+                "language": {
+                    "type": "string",
+                    "description": "Script language: python or javascript",
+                    "enum": ["python", "javascript"]
+                },
+                "code": {
+                    "type": "string",
+                    "description": "The script to run. Read input from stdin; write files to $OUTPUT_DIR."
+                },
+                "stdin": {
+                    "type": "string",
+                    "description": "Optional text piped to the script's stdin (e.g. output from a previous tool)"
+                }
+            }),
+            vec!["language", "code"],
+        )
+    }
+
+    async fn execute(&self, input: Value) -> Result<String> {
+        let language = input
+            .get("language")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| anyhow::anyhow!("Missing 'language' parameter"))?;
+
+        let language = match language.to_lowercase().as_str() {
+            "python" | "python3" | "py" => "python",
+            "javascript" | "js" | "node" => "javascript",
+            other => {
+                return Err(anyhow::anyhow!(
+                    "Unsupported script language '{}'. Use python or javascript",
+                    other
+                ));
+            }
+        };
+
+        let code = input
+            .get("code")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| anyhow::anyhow!("Missing 'code' parameter"))?;
+
+        if code.trim().is_empty() {
+            return Err(anyhow::anyhow!("Code cannot be empty"));
+        }
+
+        let stdin = input.get("stdin").and_then(|v| v.as_str());
+
+        let run_dir = self
+            .artifacts_dir
+            .join(format!("script-{}", uuid::Uuid::new_v4()));
+        tokio::fs::create_dir_all(&run_dir)
+            .await
+            .with_context(|| format!("Failed to create artifacts dir {}", run_dir.display()))?;
+
+        // The container runs with all capabilities dropped, so make the
+        // directory writable regardless of the container's uid.
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let _ = std::fs::set_permissions(&run_dir, std::fs::Permissions::from_mode(0o777));
+        }
+
+        let result = self
+            .sandbox
+            .execute_with_io(language, code, stdin, Some(&run_dir), None)
+            .await;
+
+        let result = match result {
+            Ok(r) => r,
+            Err(e) => {
+                let _ = tokio::fs::remove_dir_all(&run_dir).await;
+                return Err(e);
+            }
+        };
+
+        let artifacts = collect_artifacts(&run_dir).await.unwrap_or_default();
+        if artifacts.is_empty() {
+            let _ = tokio::fs::remove_dir_all(&run_dir).await;
+        }
+        debug!(
+            "execute_script: {} finished with {} artifact(s)",
+            language,
+            artifacts.len()
+        );
+
+        let mut output = String::new();
+
+        if !result.stdout.is_empty() {
+            output.push_str("=== STDOUT ===\n");
+            output.push_str(&result.stdout);
+            output.push('\n');
+        }
+
+        if !result.stderr.is_empty() {
+            output.push_str("=== STDERR ===\n");
+            output.push_str(&result.stderr);
+            output.push('\n');
+        }
+
+        if !artifacts.is_empty() {
+            output.push_str("=== ARTIFACTS ===\n");
+            for (path, size) in artifacts.iter().take(MAX_ARTIFACTS) {
+                output.push_str(&format!("{} ({} bytes)\n", path.display(), size));
+            }
+            if artifacts.len() > MAX_ARTIFACTS {
+                output.push_str(&format!(
+                    "... and {} more in {}\n",
+                    artifacts.len() - MAX_ARTIFACTS,
+                    run_dir.display()
+                ));
+            }
+        }
+
+        if result.timed_out {
+            output.push_str("⚠ Execution timed out\n");
+        }
+
+        output.push_str(&format!(
+            "\nExit code: {} | Duration: {}ms",
+            result.exit_code, result.duration_ms
+        ));
+
+        Ok(output)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tool(dir: &Path) -> ExecuteScriptTool {
+        ExecuteScriptTool::new(SandboxConfig::default(), dir.to_path_buf())
+    }
+
+    #[test]
+    fn test_execute_script_schema() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let tool = tool(temp.path());
+        assert_eq!(tool.name(), "execute_script");
+
+        let schema = tool.input_schema();
+        let required = schema["required"].as_array().unwrap();
+        assert!(required.iter().any(|v| v.as_str() == Some("language")));
+        assert!(required.iter().any(|v| v.as_str() == Some("code")));
+        assert!(schema["properties"]["stdin"].is_object());
+    }
+
+    #[tokio::test]
+    async fn test_execute_script_unsupported_language() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let result = tool(temp.path())
+            .execute(serde_json::json!({"language": "go", "code": "package main"}))
+            .await;
+        assert!(result.unwrap_err().to_string().contains("Unsupported"));
+    }
+
+    #[tokio::test]
+    async fn test_execute_script_empty_code() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let result = tool(temp.path())
+            .execute(serde_json::json!({"language": "python", "code": " "}))
+            .await;
+        assert!(result.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_execute_script_disabled_cleans_up() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let result = tool(temp.path())
+            .execute(serde_json::json!({
+                "language": "js",
+                "code": "console.log(1)",
+                "stdin": "a,b\n1,2"
+            }))
+            .await;
+        assert!(result.unwrap_err().to_string().contains("not enabled"));
+        // No leftover run directories when execution fails
+        assert_eq!(std::fs::read_dir(temp.path()).unwrap().count(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_collect_artifacts() {
+        let temp = tempfile::TempDir::new().unwrap();
+        std::fs::write(temp.path().join("b.csv"), "1,2").unwrap();
+        std::fs::write(temp.path().join("a.json"), "{}").unwrap();
+        std::fs::create_dir(temp.path().join("sub")).unwrap();
+
+        let artifacts = collect_artifacts(temp.path()).await.unwrap();
+        assert_eq!(artifacts.len(), 2);
+        assert!(artifacts[0].0.ends_with("a.json"));
+        assert_eq!(artifacts[1].1, 3);
+    }
+}
@@ -8,6 +8,7 @@ use tracing::debug;
 
 use super::{ToolHandler, json_schema};
 use crate::tavily::TavilyClient;
+use crate::tool_cost::{CostClass, LatencyClass, ToolCost};
 
 /// Search the web using Tavily
 pub struct WebSearchTool {
@@ -26,6 +27,10 @@ impl ToolHandler for WebSearchTool {
         "web_search"
     }
 
+    fn needs_network(&self) -> bool {
+        true
+    }
+
     fn description(&self) -> &str {
         "Search the web for current information. Returns ranked results with content excerpts."
     }
@@ -46,6 +51,10 @@ impl ToolHandler for WebSearchTool {
         )
     }
 
+    fn cost(&self) -> ToolCost {
+        ToolCost::new(CostClass::Medium, LatencyClass::Seconds)
+    }
+
     async fn execute(&self, input: Value) -> Result<String> {
         let query = input
             .get("query")
@@ -0,0 +1,78 @@
+//! Cancel send tool — stops a held autonomous send before it goes out
+
+use anyhow::Result;
+use async_trait::async_trait;
+use serde_json::Value;
+use std::sync::Arc;
+
+use super::{ToolHandler, json_schema};
+use crate::send_guard::SendGuard;
+
+/// List or cancel sends waiting out their cooling-off delay
+pub struct CancelSendTool {
+    guard: Arc<SendGuard>,
+}
+
+impl CancelSendTool {
+    pub fn new(guard: Arc<SendGuard>) -> Self {
+        Self { guard }
+    }
+}
+
+#[async_trait]
+impl ToolHandler for CancelSendTool {
+    fn name(&self) -> &str {
+        "cancel_send"
+    }
+
+    fn description(&self) -> &str {
+        "Cancel an email or message that autonomous work queued and that is still waiting out \
+         its cooling-off delay. Pass the id from the notice (e.g. 'send-1a2b3c4d'), or leave it \
+         out to list what's waiting."
+    }
+
+    fn input_schema(&self) -> Value {
+        json_schema(
+            serde_json::json!({
+                "id": {
+                    "type": "string",
+                    "description": "ID of the held send to cancel"
+                }
+            }),
+            vec![],
+        )
+    }
+
+    async fn execute(&self, input: Value) -> Result<String> {
+        if let Some(id) = input.get("id").and_then(|v| v.as_str()) {
+            return Ok(match self.guard.cancel(id.trim()) {
+                Some(send) => format!(
+                    "Cancelled {} ({} to {}); nothing was sent.",
+                    send.id,
+                    send.tool,
+                    send.recipients.join(", ")
+                ),
+                None => format!("No held send '{}' (it may have gone out already).", id),
+            });
+        }
+        let pending = self.guard.pending();
+        if pending.is_empty() {
+            return Ok("No sends are waiting.".to_string());
+        }
+        Ok(pending
+            .iter()
+            .map(|p| {
+                format!(
+                    "- {}: {} to {}, goes out at {}",
+                    p.id,
+                    p.tool,
+                    p.recipients.join(", "),
+                    p.due
+                        .with_timezone(&crate::timezone::user_timezone())
+                        .format("%H:%M:%S")
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\n"))
+    }
+}
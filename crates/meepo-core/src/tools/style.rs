@@ -0,0 +1,162 @@
+//! Response style tool — lets the user change how the agent writes to them
+
+use anyhow::{Result, anyhow};
+use async_trait::async_trait;
+use serde_json::Value;
+use std::sync::Arc;
+use tracing::debug;
+
+use super::{ToolHandler, json_schema};
+use crate::style::{self, DEFAULT_SCOPE};
+use meepo_knowledge::KnowledgeDb;
+
+/// Update response style preferences (verbosity, emoji, formality)
+pub struct SetStyleTool {
+    db: Arc<KnowledgeDb>,
+}
+
+impl SetStyleTool {
+    pub fn new(db: Arc<KnowledgeDb>) -> Self {
+        Self { db }
+    }
+}
+
+#[async_trait]
+impl ToolHandler for SetStyleTool {
+    fn name(&self) -> &str {
+        "set_style"
+    }
+
+    fn description(&self) -> &str {
+        "Change how you write replies: verbosity (concise/normal/detailed), emoji (on/off), \
+         and formality (casual/neutral/formal). Use when the user states a lasting preference \
+         like 'keep it short' or 'no emoji'. Pass the channel and sender from the Response Style \
+         context to apply it to that person only; omit them to change the default for everyone. \
+         Use 'unset' to clear a setting."
+    }
+
+    fn input_schema(&self) -> Value {
+        json_schema(
+            serde_json::json!({
+                "verbosity": {
+                    "type": "string",
+                    "enum": ["concise", "normal", "detailed", "unset"]
+                },
+                "emoji": {
+                    "type": "string",
+                    "enum": ["on", "off", "unset"]
+                },
+                "formality": {
+                    "type": "string",
+                    "enum": ["casual", "neutral", "formal", "unset"]
+                },
+                "channel": {
+                    "type": "string",
+                    "description": "Channel of the person this applies to (e.g. 'slack')"
+                },
+                "sender": {
+                    "type": "string",
+                    "description": "Sender this applies to; omit with channel for the default style"
+                }
+            }),
+            vec![],
+        )
+    }
+
+    async fn execute(&self, input: Value) -> Result<String> {
+        let channel = input.get("channel").and_then(|v| v.as_str());
+        let sender = input.get("sender").and_then(|v| v.as_str());
+        let scope = match (channel, sender) {
+            (Some(channel), Some(sender)) => style::sender_scope(channel, sender),
+            (None, None) => DEFAULT_SCOPE.to_string(),
+            _ => return Err(anyhow!("Provide both 'channel' and 'sender', or neither")),
+        };
+
+        let mut current = style::load(&self.db, &scope).await?;
+        let mut changed = false;
+        for field in style::FIELDS {
+            if let Some(value) = input.get(*field).and_then(|v| v.as_str()) {
+                current.set_field(field, value)?;
+                changed = true;
+            }
+        }
+        if !changed {
+            return Err(anyhow!(
+                "Nothing to change. Set at least one of: {}",
+                style::FIELDS.join(", ")
+            ));
+        }
+
+        debug!(
+            "Setting response style for {}: {}",
+            scope,
+            current.describe()
+        );
+        style::save(&self.db, &scope, &current, "set_style").await?;
+
+        let who = if scope == DEFAULT_SCOPE {
+            "everyone (default)".to_string()
+        } else {
+            scope
+        };
+        Ok(format!(
+            "Response style for {} is now: {}",
+            who,
+            current.describe()
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[tokio::test]
+    async fn test_set_style_for_sender() {
+        let temp = TempDir::new().unwrap();
+        let db = Arc::new(KnowledgeDb::new(temp.path().join("test.db")).unwrap());
+        let tool = SetStyleTool::new(db.clone());
+        assert_eq!(tool.name(), "set_style");
+
+        let out = tool
+            .execute(serde_json::json!({
+                "verbosity": "concise",
+                "emoji": "off",
+                "channel": "discord",
+                "sender": "alice"
+            }))
+            .await
+            .unwrap();
+        assert!(out.contains("discord:alice"));
+        assert!(out.contains("verbosity=concise, emoji=off"));
+
+        let style = style::effective(&db, "discord", "alice").await.unwrap();
+        assert_eq!(style.emoji, Some(false));
+        assert!(
+            style::effective(&db, "discord", "bob")
+                .await
+                .unwrap()
+                .is_empty()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_set_style_rejects_bad_input() {
+        let temp = TempDir::new().unwrap();
+        let db = Arc::new(KnowledgeDb::new(temp.path().join("test.db")).unwrap());
+        let tool = SetStyleTool::new(db);
+
+        assert!(tool.execute(serde_json::json!({})).await.is_err());
+        assert!(
+            tool.execute(serde_json::json!({"emoji": "off", "channel": "slack"}))
+                .await
+                .is_err()
+        );
+        assert!(
+            tool.execute(serde_json::json!({"verbosity": "huge"}))
+                .await
+                .is_err()
+        );
+    }
+}
@@ -9,10 +9,11 @@ use tokio::process::Command;
 use tracing::{debug, warn};
 
 use super::{ToolHandler, json_schema};
+use crate::tool_cost::{CostClass, LatencyClass, ToolCost};
 
 /// Validate file path to prevent path traversal attacks
 /// Returns the validated PathBuf or an error if the path is unsafe
-fn validate_file_path(path: &str, for_write: bool) -> Result<PathBuf> {
+pub(crate) fn validate_file_path(path: &str, for_write: bool) -> Result<PathBuf> {
     // Check for suspicious patterns before canonicalization
     if path.contains("..") {
         return Err(anyhow::anyhow!(
@@ -459,16 +460,16 @@ fn is_private_ip(ip: &std::net::IpAddr) -> Option<&'static str> {
 /// Validated URL info returned by `validate_url`.
 /// Contains the resolved IPs so callers can pin them in reqwest,
 /// eliminating the TOCTOU gap between DNS check and HTTP request.
-struct ValidatedUrl {
-    host: String,
-    resolved_ips: Vec<std::net::SocketAddr>,
+pub(crate) struct ValidatedUrl {
+    pub(crate) host: String,
+    pub(crate) resolved_ips: Vec<std::net::SocketAddr>,
 }
 
 /// Check if a URL is safe to fetch (SSRF protection).
 ///
 /// Returns resolved socket addresses so the caller can pin them in the HTTP
 /// client, preventing DNS rebinding between validation and the actual request.
-fn validate_url(url_str: &str) -> Result<ValidatedUrl> {
+pub(crate) fn validate_url(url_str: &str) -> Result<ValidatedUrl> {
     use std::net::IpAddr;
 
     let url = url::Url::parse(url_str).context("Invalid URL format")?;
@@ -570,6 +571,10 @@ impl ToolHandler for BrowseUrlTool {
         "browse_url"
     }
 
+    fn needs_network(&self) -> bool {
+        true
+    }
+
     fn description(&self) -> &str {
         "Fetch content from a URL. Returns clean extracted text when available, otherwise raw HTML."
     }
@@ -590,6 +595,10 @@ impl ToolHandler for BrowseUrlTool {
         )
     }
 
+    fn cost(&self) -> ToolCost {
+        ToolCost::new(CostClass::Low, LatencyClass::Seconds)
+    }
+
     async fn execute(&self, input: Value) -> Result<String> {
         let url = input
             .get("url")
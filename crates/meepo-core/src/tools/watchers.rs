@@ -8,6 +8,7 @@ use tokio::sync::mpsc;
 use tracing::{debug, warn};
 
 use super::{ToolHandler, json_schema};
+use crate::expr::Expression;
 use meepo_knowledge::KnowledgeDb;
 
 /// Commands to send to the watcher scheduler
@@ -46,7 +47,8 @@ impl ToolHandler for CreateWatcherTool {
 
     fn description(&self) -> &str {
         "Create a new watcher to monitor for specific events. \
-         Watchers can monitor emails, calendar events, files, GitHub, etc."
+         Watchers can monitor emails, calendar events, files, GitHub, etc. \
+         An optional condition expression limits which events trigger it."
     }
 
     fn input_schema(&self) -> Value {
@@ -67,6 +69,10 @@ impl ToolHandler for CreateWatcherTool {
                 "reply_channel": {
                     "type": "string",
                     "description": "Channel to send notifications to (e.g., 'slack', 'discord', 'internal')"
+                },
+                "condition": {
+                    "type": "string",
+                    "description": "Optional expression an event must satisfy, over kind, source and payload.*, e.g. 'payload.from contains \"boss\" && hour() < 18'"
                 }
             }),
             vec!["kind", "config", "action", "reply_channel"],
@@ -78,7 +84,7 @@ impl ToolHandler for CreateWatcherTool {
             .get("kind")
             .and_then(|v| v.as_str())
             .ok_or_else(|| anyhow::anyhow!("Missing 'kind' parameter"))?;
-        let config = input
+        let mut config = input
             .get("config")
             .ok_or_else(|| anyhow::anyhow!("Missing 'config' parameter"))?
             .clone();
@@ -98,6 +104,17 @@ impl ToolHandler for CreateWatcherTool {
             ));
         }
 
+        if let Some(condition) = input.get("condition").and_then(|v| v.as_str()) {
+            Expression::parse(condition).context("Invalid condition")?;
+            let Some(map) = config.as_object_mut() else {
+                return Err(anyhow::anyhow!("'config' must be an object"));
+            };
+            map.insert(
+                "condition".to_string(),
+                Value::String(condition.to_string()),
+            );
+        }
+
         debug!("Creating watcher: {} -> {}", kind, action);
 
         // Store in database
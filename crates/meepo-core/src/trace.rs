@@ -0,0 +1,239 @@
+//! Structured traces of agent turns
+//!
+//! A [`TurnTrace`] records what happened while answering one message: every
+//! LLM call ("hop") with its token usage and duration, and every tool the
+//! model invoked with its (redacted) input, duration and outcome. The agent
+//! stores it in the metadata of the response's conversation row so a turn
+//! can be inspected later.
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::time::Duration;
+
+use crate::context_compression::CompressionStats;
+
+/// Tool input strings longer than this are cut short in traces
+const MAX_TRACE_STRING: usize = 200;
+
+/// Extended thinking kept per hop, in characters
+const MAX_THINKING_SUMMARY: usize = 500;
+
+/// Input keys whose values never appear in traces
+const SENSITIVE_KEYS: &[&str] = &[
+    "password",
+    "passwd",
+    "secret",
+    "token",
+    "api_key",
+    "apikey",
+    "authorization",
+    "cookie",
+    "credential",
+    "private_key",
+];
+
+/// Trace of a single agent turn
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TurnTrace {
+    pub hops: Vec<TraceHop>,
+    /// Wall-clock time for the whole turn
+    pub duration_ms: u64,
+    /// What context deduplication and compression saved
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub context: Option<CompressionStats>,
+}
+
+impl TurnTrace {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record a pre-processing LLM call (intent, routing, tool selection)
+    pub fn push_stage(
+        &mut self,
+        stage: &str,
+        input_tokens: u32,
+        output_tokens: u32,
+        elapsed: Duration,
+    ) {
+        self.hops.push(TraceHop {
+            stage: stage.to_string(),
+            input_tokens,
+            output_tokens,
+            duration_ms: elapsed.as_millis() as u64,
+            stop_reason: None,
+            tools: Vec::new(),
+            thinking: None,
+        });
+    }
+
+    /// Drop thinking summaries, e.g. before storing the trace with history
+    pub fn redact_thinking(&mut self) {
+        for hop in &mut self.hops {
+            hop.thinking = None;
+        }
+    }
+
+    /// Every tool call in the turn, in order
+    pub fn tool_calls(&self) -> impl Iterator<Item = &ToolTrace> {
+        self.hops.iter().flat_map(|h| h.tools.iter())
+    }
+
+    pub fn total_tokens(&self) -> u64 {
+        self.hops
+            .iter()
+            .map(|h| h.input_tokens as u64 + h.output_tokens as u64)
+            .sum()
+    }
+}
+
+/// One LLM call and the tools it asked for
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TraceHop {
+    /// "intent", "router", "context_compression", "tool_selector", "tool_loop"
+    /// or "loop_summary"
+    pub stage: String,
+    pub input_tokens: u32,
+    pub output_tokens: u32,
+    pub duration_ms: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stop_reason: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tools: Vec<ToolTrace>,
+    /// Start of the model's extended thinking, for debugging
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub thinking: Option<String>,
+}
+
+/// A tool invocation within a hop
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ToolTrace {
+    pub name: String,
+    /// Input with secrets masked and long strings truncated
+    pub input: Value,
+    pub duration_ms: u64,
+    pub ok: bool,
+    pub output_chars: usize,
+}
+
+impl ToolTrace {
+    pub fn new(
+        name: &str,
+        input: &Value,
+        elapsed: Duration,
+        result: &Result<String, String>,
+    ) -> Self {
+        Self {
+            name: name.to_string(),
+            input: redact_input(input),
+            duration_ms: elapsed.as_millis() as u64,
+            ok: result.is_ok(),
+            output_chars: match result {
+                Ok(out) | Err(out) => out.chars().count(),
+            },
+        }
+    }
+}
+
+/// The first few hundred characters of a hop's thinking
+pub fn thinking_summary(thinking: &str) -> Option<String> {
+    let thinking = thinking.trim();
+    if thinking.is_empty() {
+        return None;
+    }
+    let total = thinking.chars().count();
+    if total <= MAX_THINKING_SUMMARY {
+        return Some(thinking.to_string());
+    }
+    let cut: String = thinking.chars().take(MAX_THINKING_SUMMARY).collect();
+    Some(format!("{}… ({} chars)", cut.trim_end(), total))
+}
+
+/// Mask sensitive fields and truncate long strings in a tool input
+pub fn redact_input(input: &Value) -> Value {
+    match input {
+        Value::Object(map) => Value::Object(
+            map.iter()
+                .map(|(k, v)| {
+                    let value = if is_sensitive(k) {
+                        Value::String("[redacted]".to_string())
+                    } else {
+                        redact_input(v)
+                    };
+                    (k.clone(), value)
+                })
+                .collect(),
+        ),
+        Value::Array(items) => Value::Array(items.iter().map(redact_input).collect()),
+        Value::String(s) if s.chars().count() > MAX_TRACE_STRING => {
+            let cut: String = s.chars().take(MAX_TRACE_STRING).collect();
+            Value::String(format!("{}… ({} chars)", cut, s.chars().count()))
+        }
+        other => other.clone(),
+    }
+}
+
+fn is_sensitive(key: &str) -> bool {
+    let key = key.to_lowercase();
+    SENSITIVE_KEYS.iter().any(|s| key.contains(s))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_redact_input() {
+        let input = json!({
+            "url": "https://example.com",
+            "headers": {"Authorization": "Bearer abc", "Accept": "text/html"},
+            "api_key": "sk-123",
+            "body": "x".repeat(500),
+            "items": [{"password": "hunter2"}],
+        });
+        let redacted = redact_input(&input);
+        assert_eq!(redacted["url"], "https://example.com");
+        assert_eq!(redacted["headers"]["Authorization"], "[redacted]");
+        assert_eq!(redacted["headers"]["Accept"], "text/html");
+        assert_eq!(redacted["api_key"], "[redacted]");
+        assert_eq!(redacted["items"][0]["password"], "[redacted]");
+        let body = redacted["body"].as_str().unwrap();
+        assert!(body.ends_with("(500 chars)"));
+        assert!(body.len() < 300);
+    }
+
+    #[test]
+    fn test_trace_totals_and_serialization() {
+        let mut trace = TurnTrace::new();
+        trace.push_stage("intent", 100, 20, Duration::from_millis(150));
+        trace.hops.push(TraceHop {
+            stage: "tool_loop".to_string(),
+            input_tokens: 900,
+            output_tokens: 80,
+            duration_ms: 1200,
+            stop_reason: Some("tool_use".to_string()),
+            tools: vec![ToolTrace::new(
+                "read_file",
+                &json!({"path": "/tmp/a"}),
+                Duration::from_millis(5),
+                &Ok("hello".to_string()),
+            )],
+            thinking: thinking_summary(&"Look up the file. ".repeat(50)),
+        });
+        assert_eq!(trace.total_tokens(), 1100);
+        assert_eq!(trace.tool_calls().count(), 1);
+
+        let json = serde_json::to_value(&trace).unwrap();
+        assert!(json["hops"][0].get("tools").is_none());
+        assert_eq!(json["hops"][1]["tools"][0]["output_chars"], 5);
+        assert!(json["hops"][0].get("thinking").is_none());
+        let thinking = json["hops"][1]["thinking"].as_str().unwrap();
+        assert!(thinking.ends_with("… (899 chars)"));
+        let mut back: TurnTrace = serde_json::from_value(json).unwrap();
+        assert_eq!(back.hops.len(), 2);
+
+        back.redact_thinking();
+        assert!(back.hops.iter().all(|h| h.thinking.is_none()));
+    }
+}
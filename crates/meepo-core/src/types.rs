@@ -22,6 +22,11 @@ pub enum MessageKind {
     Response,
     /// Acknowledgment/typing indicator — channel decides how to display
     Acknowledgment,
+    /// Response that needs attention now — displayed like a normal response
+    Urgent,
+    /// The response so far, while the model is still writing; the final
+    /// `Response` to the same `reply_to` replaces it
+    Partial,
 }
 
 /// Outgoing message to be sent to a channel
@@ -33,6 +38,73 @@ pub struct OutgoingMessage {
     pub reply_to: Option<String>, // original message id
     #[serde(default)]
     pub kind: MessageKind,
+    /// Buttons for channels that can render them; others show `content` only
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub interactive: Option<Interactive>,
+}
+
+/// Buttons attached to an outgoing message (Slack Block Kit, Discord components)
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct Interactive {
+    /// What a press answers, e.g. a pending question ID
+    pub prompt_id: String,
+    pub buttons: Vec<Button>,
+}
+
+/// A button; pressing it answers the prompt with `value`
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct Button {
+    pub label: String,
+    pub value: String,
+    #[serde(default)]
+    pub style: ButtonStyle,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum ButtonStyle {
+    #[default]
+    Default,
+    /// Approve / go ahead
+    Primary,
+    /// Reject / destructive
+    Danger,
+}
+
+impl Button {
+    /// A button for a choice, styled by what it says
+    pub fn for_choice(choice: &str) -> Self {
+        let style = match choice.trim().to_lowercase().as_str() {
+            "approve" | "yes" | "ok" | "confirm" | "proceed" | "go ahead" | "continue" => {
+                ButtonStyle::Primary
+            }
+            "reject" | "no" | "cancel" | "deny" | "stop" | "abort" | "delete" => {
+                ButtonStyle::Danger
+            }
+            _ => ButtonStyle::Default,
+        };
+        Self {
+            label: choice.trim().to_string(),
+            value: choice.trim().to_string(),
+            style,
+        }
+    }
+}
+
+/// Marks an incoming message that is a button press: `[button <prompt id>] <value>`
+const BUTTON_PREFIX: &str = "[button ";
+
+impl Interactive {
+    /// Incoming message content for a press of `value` on prompt `prompt_id`
+    pub fn press_content(prompt_id: &str, value: &str) -> String {
+        format!("{}{}] {}", BUTTON_PREFIX, prompt_id, value)
+    }
+
+    /// The prompt ID and value of a button press message
+    pub fn parse_press(content: &str) -> Option<(&str, &str)> {
+        let (prompt_id, value) = content.strip_prefix(BUTTON_PREFIX)?.split_once("] ")?;
+        Some((prompt_id, value))
+    }
 }
 
 /// Type of communication channel
@@ -246,6 +318,7 @@ mod tests {
             channel: ChannelType::Slack,
             reply_to: Some("msg-1".to_string()),
             kind: MessageKind::Response,
+            interactive: None,
         };
         let json = serde_json::to_string(&msg).unwrap();
         let parsed: OutgoingMessage = serde_json::from_str(&json).unwrap();
@@ -262,6 +335,7 @@ mod tests {
             channel: ChannelType::Email,
             reply_to: None,
             kind: MessageKind::default(),
+            interactive: None,
         };
         let json = serde_json::to_string(&msg).unwrap();
         assert!(!json.contains("reply_to"));
@@ -273,4 +347,22 @@ mod tests {
         let parsed: OutgoingMessage = serde_json::from_str(json).unwrap();
         assert_eq!(parsed.kind, MessageKind::Response);
     }
+
+    #[test]
+    fn test_interactive_buttons_and_presses() {
+        assert_eq!(Button::for_choice("Approve").style, ButtonStyle::Primary);
+        assert_eq!(Button::for_choice(" reject ").style, ButtonStyle::Danger);
+        assert_eq!(Button::for_choice("Later").style, ButtonStyle::Default);
+
+        let content = Interactive::press_content("q-1a2b", "Approve");
+        assert_eq!(
+            Interactive::parse_press(&content),
+            Some(("q-1a2b", "Approve"))
+        );
+        assert_eq!(Interactive::parse_press("Approve"), None);
+
+        let json = r#"{"content":"hi","channel":"slack"}"#;
+        let parsed: OutgoingMessage = serde_json::from_str(json).unwrap();
+        assert!(parsed.interactive.is_none());
+    }
 }
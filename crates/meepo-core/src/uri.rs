@@ -0,0 +1,32 @@
+//! Percent-encoding for URI paths and query strings
+//!
+//! Shared by AWS request signing (S3, Bedrock), which needs RFC 3986
+//! encoding exactly, and the CDP browser backend. Unlike
+//! `application/x-www-form-urlencoded`, spaces become `%20`, never `+`.
+
+/// Percent-encode everything but RFC 3986 unreserved characters (and `/`
+/// unless `encode_slash`)
+pub fn encode(input: &str, encode_slash: bool) -> String {
+    let mut out = String::with_capacity(input.len());
+    for byte in input.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                out.push(byte as char)
+            }
+            b'/' if !encode_slash => out.push('/'),
+            _ => out.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encode() {
+        assert_eq!(encode("a b/ü.md", false), "a%20b/%C3%BC.md");
+        assert_eq!(encode("a+b/c~d", true), "a%2Bb%2Fc~d");
+    }
+}
@@ -483,6 +483,30 @@ pub fn format_usage_summary(summary: &UsageSummary) -> String {
         out.push('\n');
     }
 
+    if !summary.by_channel_model.is_empty() {
+        out.push_str("### By Channel\n\n");
+        out.push_str("| Channel | Model | Cost | Tokens | Calls |\n");
+        out.push_str("|---------|-------|------|--------|-------|\n");
+        let mut pairs: Vec<_> = summary.by_channel_model.iter().collect();
+        pairs.sort_by(|a, b| {
+            b.1.estimated_cost_usd
+                .partial_cmp(&a.1.estimated_cost_usd)
+                .unwrap_or(std::cmp::Ordering::Equal)
+        });
+        for (key, usage) in pairs {
+            let (channel, model) = key.split_once('/').unwrap_or((key.as_str(), ""));
+            out.push_str(&format!(
+                "| {} | {} | ${:.4} | {} | {} |\n",
+                channel,
+                model,
+                usage.estimated_cost_usd,
+                usage.input_tokens + usage.output_tokens,
+                usage.api_calls
+            ));
+        }
+        out.push('\n');
+    }
+
     out
 }
 
@@ -545,6 +569,7 @@ mod tests {
             estimated_cost_usd: 0.525,
             by_source: HashMap::new(),
             by_model: HashMap::new(),
+            by_channel_model: HashMap::new(),
         };
         let formatted = format_usage_summary(&summary);
         assert!(formatted.contains("$0.5250"));
@@ -852,6 +877,7 @@ mod tests {
             estimated_cost_usd: 0.5,
             by_source,
             by_model,
+            by_channel_model: HashMap::new(),
         };
 
         let formatted = format_usage_summary(&summary);
@@ -1,15 +1,18 @@
 //! Event bus — broadcast events to all connected WebSocket clients
 
-use std::sync::Arc;
+use std::sync::{Arc, RwLock};
 use tokio::sync::broadcast;
 use tracing::debug;
 
-use crate::protocol::GatewayEvent;
+use crate::protocol::{self, GatewayEvent};
+use meepo_core::types::{MessageKind, OutgoingMessage};
 
 /// Broadcast event bus for the gateway
 #[derive(Clone)]
 pub struct EventBus {
     sender: Arc<broadcast::Sender<GatewayEvent>>,
+    /// Warm-up report once the daemon is ready, for clients that connect later
+    ready: Arc<RwLock<Option<serde_json::Value>>>,
 }
 
 impl EventBus {
@@ -18,6 +21,7 @@ impl EventBus {
         let (sender, _) = broadcast::channel(capacity);
         Self {
             sender: Arc::new(sender),
+            ready: Arc::new(RwLock::new(None)),
         }
     }
 
@@ -39,6 +43,41 @@ impl EventBus {
         }
     }
 
+    /// Mirror a reply going out on another channel so clients can render it
+    /// as it's written: each partial, then the final text with `done`
+    pub fn mirror_reply(&self, msg: &OutgoingMessage) {
+        let done = match msg.kind {
+            MessageKind::Partial => false,
+            MessageKind::Response | MessageKind::Urgent => true,
+            MessageKind::Acknowledgment => return,
+        };
+        let Some(reply_to) = &msg.reply_to else {
+            return;
+        };
+        self.broadcast(GatewayEvent::new(
+            protocol::events::MESSAGE_DELTA,
+            serde_json::json!({
+                "channel": msg.channel.to_string(),
+                "reply_to": reply_to,
+                "content": msg.content,
+                "done": done,
+            }),
+        ));
+    }
+
+    /// Record that startup warm-up finished and tell connected clients
+    pub fn mark_ready(&self, report: serde_json::Value) {
+        if let Ok(mut ready) = self.ready.write() {
+            *ready = Some(report.clone());
+        }
+        self.broadcast(GatewayEvent::new(protocol::events::READY, report));
+    }
+
+    /// The warm-up report, or `None` while the daemon is still warming up
+    pub fn readiness(&self) -> Option<serde_json::Value> {
+        self.ready.read().ok().and_then(|r| r.clone())
+    }
+
     /// Number of active subscribers
     pub fn subscriber_count(&self) -> usize {
         self.sender.receiver_count()
@@ -84,6 +123,43 @@ mod tests {
         assert_eq!(bus.subscriber_count(), 1);
     }
 
+    #[tokio::test]
+    async fn test_mark_ready() {
+        let bus = EventBus::new(8);
+        let mut rx = bus.subscribe();
+        assert!(bus.readiness().is_none());
+
+        bus.mark_ready(serde_json::json!({"duration_ms": 120}));
+        let event = rx.recv().await.unwrap();
+        assert_eq!(event.event, protocol::events::READY);
+        // Clients connecting later still see it
+        assert_eq!(bus.clone().readiness().unwrap()["duration_ms"], 120);
+    }
+
+    #[tokio::test]
+    async fn test_mirror_reply() {
+        let bus = EventBus::new(8);
+        let mut rx = bus.subscribe();
+        let reply = |content: &str, kind| OutgoingMessage {
+            content: content.to_string(),
+            channel: meepo_core::types::ChannelType::Discord,
+            reply_to: Some("m1".to_string()),
+            kind,
+            interactive: None,
+        };
+        bus.mirror_reply(&reply("", MessageKind::Acknowledgment));
+        bus.mirror_reply(&reply("It's", MessageKind::Partial));
+        bus.mirror_reply(&reply("It's sunny", MessageKind::Response));
+
+        let partial = rx.recv().await.unwrap();
+        assert_eq!(partial.event, protocol::events::MESSAGE_DELTA);
+        assert_eq!(partial.data["content"], "It's");
+        assert_eq!(partial.data["done"], false);
+        let last = rx.recv().await.unwrap();
+        assert_eq!(last.data["done"], true);
+        assert_eq!(last.data["reply_to"], "m1");
+    }
+
     #[test]
     fn test_event_bus_clone() {
         let bus = EventBus::new(8);
@@ -0,0 +1,490 @@
+//! Streaming ingestion of large document corpora
+//!
+//! A client opens a job, pushes documents in batches, then finishes it. Each
+//! job has a bounded queue drained by one worker that chunks and indexes
+//! documents in order; pushing a batch waits for room in the queue, so a
+//! fast client is slowed to the indexing rate instead of buffering the whole
+//! corpus in memory. Progress is broadcast as `ingest.progress` events and
+//! can be polled per job. A job left open with nothing pushed for the idle
+//! timeout is dropped along with its queue and worker.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use anyhow::{Result, bail};
+use serde::{Deserialize, Serialize};
+use tokio::sync::mpsc;
+use tracing::{info, warn};
+
+use meepo_core::tools::rag::{IngestChange, index_document};
+use meepo_knowledge::KnowledgeGraph;
+use meepo_knowledge::chunking::{ChunkingConfig, detect_content_type};
+
+use crate::events::EventBus;
+use crate::protocol::{GatewayEvent, events};
+
+/// Minimum time between progress events for one job
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Most recent errors kept per job
+const MAX_ERRORS: usize = 20;
+
+/// Finished jobs kept for polling
+const MAX_FINISHED_JOBS: usize = 100;
+
+/// How long an unfinished job may go without documents before it's dropped
+const IDLE_TIMEOUT: Duration = Duration::from_secs(30 * 60);
+
+type Jobs = Arc<Mutex<HashMap<String, Job>>>;
+
+/// A document pushed by a client
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IngestDocument {
+    pub content: String,
+    /// Defaults to the last component of `source`
+    #[serde(default)]
+    pub title: Option<String>,
+    /// Stable identifier such as the file's path in the export. Pushing the
+    /// same source again updates that document in place.
+    #[serde(default)]
+    pub source: Option<String>,
+    /// MIME type; detected from `source` when missing
+    #[serde(default)]
+    pub content_type: Option<String>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+}
+
+/// Where an ingest job stands
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct IngestProgress {
+    pub job_id: String,
+    /// Documents accepted into the queue
+    pub received: usize,
+    pub indexed: usize,
+    /// Already ingested with the same content
+    pub unchanged: usize,
+    /// Re-chunked because their content changed
+    pub updated: usize,
+    pub failed: usize,
+    /// Chunks created for new and updated documents
+    pub chunks: usize,
+    /// The client finished pushing documents
+    pub finished: bool,
+    /// Finished and every queued document processed
+    pub done: bool,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub errors: Vec<String>,
+}
+
+impl IngestProgress {
+    pub fn queued(&self) -> usize {
+        self.received
+            .saturating_sub(self.indexed + self.unchanged + self.updated + self.failed)
+    }
+
+    /// JSON form with the `queued` count included
+    pub fn to_json(&self) -> serde_json::Value {
+        let mut value = serde_json::to_value(self).unwrap_or_default();
+        value["queued"] = serde_json::json!(self.queued());
+        value
+    }
+}
+
+struct Job {
+    /// Dropped when the client finishes, which ends the worker
+    sender: Option<mpsc::Sender<IngestDocument>>,
+    progress: Arc<Mutex<IngestProgress>>,
+}
+
+/// Queues pushed documents through chunking and indexing
+pub struct IngestService {
+    graph: Arc<KnowledgeGraph>,
+    chunking: ChunkingConfig,
+    events: EventBus,
+    queue_size: usize,
+    idle_timeout: Duration,
+    jobs: Jobs,
+    /// Finished job IDs, oldest first
+    finished: Mutex<Vec<String>>,
+}
+
+impl IngestService {
+    pub fn new(graph: Arc<KnowledgeGraph>, chunking: ChunkingConfig, events: EventBus) -> Self {
+        Self {
+            graph,
+            chunking,
+            events,
+            queue_size: 64,
+            idle_timeout: IDLE_TIMEOUT,
+            jobs: Arc::default(),
+            finished: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// Documents buffered per job before pushes wait
+    pub fn with_queue_size(mut self, queue_size: usize) -> Self {
+        self.queue_size = queue_size.max(1);
+        self
+    }
+
+    /// Drop unfinished jobs that receive no documents for this long
+    pub fn with_idle_timeout(mut self, idle_timeout: Duration) -> Self {
+        self.idle_timeout = idle_timeout;
+        self
+    }
+
+    /// Open a job and start its worker, returning the job ID
+    pub fn start(&self) -> String {
+        let job_id = uuid::Uuid::new_v4().to_string();
+        let (sender, receiver) = mpsc::channel(self.queue_size);
+        let progress = Arc::new(Mutex::new(IngestProgress {
+            job_id: job_id.clone(),
+            ..Default::default()
+        }));
+        tokio::spawn(run_worker(
+            self.graph.clone(),
+            self.chunking.clone(),
+            self.events.clone(),
+            receiver,
+            progress.clone(),
+            self.jobs.clone(),
+            self.idle_timeout,
+        ));
+        self.lock_jobs().insert(
+            job_id.clone(),
+            Job {
+                sender: Some(sender),
+                progress,
+            },
+        );
+        info!("Ingest job {} started", job_id);
+        job_id
+    }
+
+    /// Queue documents, waiting for room when the queue is full
+    pub async fn push(
+        &self,
+        job_id: &str,
+        documents: Vec<IngestDocument>,
+    ) -> Result<IngestProgress> {
+        let (sender, progress) = {
+            let jobs = self.lock_jobs();
+            let Some(job) = jobs.get(job_id) else {
+                bail!("Unknown ingest job '{}'", job_id);
+            };
+            let Some(sender) = job.sender.clone() else {
+                bail!("Ingest job '{}' is already finished", job_id);
+            };
+            (sender, job.progress.clone())
+        };
+        for document in documents {
+            if document.content.trim().is_empty() {
+                continue;
+            }
+            // Counted first so the worker never sees more processed than received
+            lock(&progress).received += 1;
+            if sender.send(document).await.is_err() {
+                lock(&progress).received -= 1;
+                bail!("Ingest job '{}' stopped", job_id);
+            }
+        }
+        Ok(lock(&progress).clone())
+    }
+
+    /// Mark a job as complete; queued documents are still processed.
+    /// Finishing a job again just returns its progress.
+    pub fn finish(&self, job_id: &str) -> Result<IngestProgress> {
+        let progress = {
+            let mut jobs = self.lock_jobs();
+            let Some(job) = jobs.get_mut(job_id) else {
+                bail!("Unknown ingest job '{}'", job_id);
+            };
+            if job.sender.take().is_none() {
+                return Ok(lock(&job.progress).clone());
+            }
+            job.progress.clone()
+        };
+        let snapshot = {
+            let mut progress = lock(&progress);
+            progress.finished = true;
+            progress.clone()
+        };
+
+        let mut finished = self.finished.lock().unwrap_or_else(|p| p.into_inner());
+        finished.push(job_id.to_string());
+        if finished.len() > MAX_FINISHED_JOBS {
+            let expired = finished.remove(0);
+            self.lock_jobs().remove(&expired);
+        }
+        Ok(snapshot)
+    }
+
+    pub fn progress(&self, job_id: &str) -> Option<IngestProgress> {
+        self.lock_jobs()
+            .get(job_id)
+            .map(|job| lock(&job.progress).clone())
+    }
+
+    fn lock_jobs(&self) -> std::sync::MutexGuard<'_, HashMap<String, Job>> {
+        lock_jobs(&self.jobs)
+    }
+}
+
+fn lock_jobs(
+    jobs: &Mutex<HashMap<String, Job>>,
+) -> std::sync::MutexGuard<'_, HashMap<String, Job>> {
+    jobs.lock().unwrap_or_else(|poisoned| {
+        warn!("Ingest jobs mutex was poisoned, recovering");
+        poisoned.into_inner()
+    })
+}
+
+fn lock(progress: &Mutex<IngestProgress>) -> std::sync::MutexGuard<'_, IngestProgress> {
+    progress.lock().unwrap_or_else(|p| p.into_inner())
+}
+
+async fn run_worker(
+    graph: Arc<KnowledgeGraph>,
+    chunking: ChunkingConfig,
+    events: EventBus,
+    mut receiver: mpsc::Receiver<IngestDocument>,
+    progress: Arc<Mutex<IngestProgress>>,
+    jobs: Jobs,
+    idle_timeout: Duration,
+) {
+    let mut last_event = Instant::now();
+    let mut count = 0usize;
+    let job_id = lock(&progress).job_id.clone();
+    loop {
+        let document = match tokio::time::timeout(idle_timeout, receiver.recv()).await {
+            Ok(Some(document)) => document,
+            Ok(None) => break,
+            Err(_) => {
+                // Dropping the entry drops the sender; the queue is empty
+                warn!(
+                    "Ingest job {} received nothing for {}s, dropping it",
+                    job_id,
+                    idle_timeout.as_secs()
+                );
+                lock_jobs(&jobs).remove(&job_id);
+                return;
+            }
+        };
+        count += 1;
+        let source = document
+            .source
+            .clone()
+            .unwrap_or_else(|| format!("gateway:{}/{}", job_id, count));
+        let title = document.title.clone().unwrap_or_else(|| {
+            source
+                .rsplit(['/', '\\'])
+                .next()
+                .unwrap_or(&source)
+                .to_string()
+        });
+        let content_type = document
+            .content_type
+            .clone()
+            .unwrap_or_else(|| detect_content_type(&source).to_string());
+
+        let result = index_document(
+            &graph,
+            &chunking,
+            &title,
+            &source,
+            &content_type,
+            &document.content,
+            &document.tags,
+        )
+        .await;
+
+        let snapshot = {
+            let mut progress = lock(&progress);
+            match result {
+                Ok((_, metadata, IngestChange::New)) => {
+                    progress.indexed += 1;
+                    progress.chunks += metadata.chunk_count;
+                }
+                Ok((_, _, IngestChange::Unchanged)) => progress.unchanged += 1,
+                Ok((_, _, IngestChange::Updated { added, .. })) => {
+                    progress.updated += 1;
+                    progress.chunks += added;
+                }
+                Err(e) => {
+                    warn!("Ingest job {}: failed to index {}: {:#}", job_id, source, e);
+                    progress.failed += 1;
+                    progress.errors.push(format!("{}: {:#}", source, e));
+                    if progress.errors.len() > MAX_ERRORS {
+                        progress.errors.remove(0);
+                    }
+                }
+            }
+            progress.done = progress.finished && progress.queued() == 0 && receiver.is_empty();
+            progress.clone()
+        };
+        if snapshot.done || last_event.elapsed() >= PROGRESS_INTERVAL {
+            broadcast(&events, &snapshot);
+            last_event = Instant::now();
+        }
+    }
+
+    // The client finished (or the job was dropped) and the queue is drained
+    let snapshot = {
+        let mut progress = lock(&progress);
+        let already_reported = progress.done;
+        progress.done = true;
+        (!already_reported).then(|| progress.clone())
+    };
+    if let Some(snapshot) = snapshot {
+        broadcast(&events, &snapshot);
+    }
+    let progress = lock(&progress);
+    info!(
+        "Ingest job {} done: {} new, {} updated, {} unchanged, {} failed",
+        progress.job_id, progress.indexed, progress.updated, progress.unchanged, progress.failed
+    );
+}
+
+fn broadcast(events: &EventBus, progress: &IngestProgress) {
+    events.broadcast(GatewayEvent::new(
+        events::INGEST_PROGRESS,
+        progress.to_json(),
+    ));
+}
+
+/// Parse a batch body: `{"documents": [...]}`, a JSON array, or one JSON
+/// document per line (NDJSON)
+pub fn parse_batch(body: &str) -> Result<Vec<IngestDocument>> {
+    let trimmed = body.trim_start();
+    if trimmed.starts_with('[') {
+        return Ok(serde_json::from_str(trimmed)?);
+    }
+    if let Ok(batch) = serde_json::from_str::<HashMap<String, Vec<IngestDocument>>>(trimmed)
+        && let Some(documents) = batch.into_values().next()
+    {
+        return Ok(documents);
+    }
+    body.lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(i, line)| {
+            serde_json::from_str(line)
+                .map_err(|e| anyhow::anyhow!("Invalid document on line {}: {}", i + 1, e))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_batch_formats() {
+        let wrapped = r#"{"documents": [{"content": "a", "source": "notes/a.md"}]}"#;
+        assert_eq!(
+            parse_batch(wrapped).unwrap()[0].source.as_deref(),
+            Some("notes/a.md")
+        );
+
+        let array = r#"[{"content": "a"}, {"content": "b", "tags": ["x"]}]"#;
+        assert_eq!(parse_batch(array).unwrap()[1].tags, ["x"]);
+
+        let ndjson = "{\"content\": \"a\"}\n\n{\"content\": \"b\", \"title\": \"B\"}\n";
+        let docs = parse_batch(ndjson).unwrap();
+        assert_eq!(docs.len(), 2);
+        assert_eq!(docs[1].title.as_deref(), Some("B"));
+
+        let err = parse_batch("{\"content\": \"a\"}\nnot json").unwrap_err();
+        assert!(err.to_string().contains("line 2"));
+    }
+
+    #[tokio::test]
+    async fn test_ingest_job_with_backpressure_and_progress() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let graph = Arc::new(
+            KnowledgeGraph::new(temp.path().join("test.db"), temp.path().join("index")).unwrap(),
+        );
+        let events = EventBus::new(64);
+        let mut rx = events.subscribe();
+        let service =
+            IngestService::new(graph.clone(), ChunkingConfig::default(), events).with_queue_size(1);
+
+        let job = service.start();
+        let doc = |source: &str, content: &str| IngestDocument {
+            content: content.to_string(),
+            title: None,
+            source: Some(source.to_string()),
+            content_type: None,
+            tags: vec!["export".to_string()],
+        };
+        let pushed = service
+            .push(
+                &job,
+                vec![
+                    doc("notes/rust.md", "# Rust\n\nOwnership and borrowing notes."),
+                    doc("notes/go.md", "# Go\n\nGoroutines and channels."),
+                    doc("notes/empty.md", "   "),
+                ],
+            )
+            .await
+            .unwrap();
+        assert_eq!(pushed.received, 2);
+        // Re-pushing a source with the same content is a no-op
+        service
+            .push(
+                &job,
+                vec![doc(
+                    "notes/rust.md",
+                    "# Rust\n\nOwnership and borrowing notes.",
+                )],
+            )
+            .await
+            .unwrap();
+        service.finish(&job).unwrap();
+        assert!(service.push(&job, vec![doc("x", "y")]).await.is_err());
+
+        let done = loop {
+            let event = rx.recv().await.unwrap();
+            assert_eq!(event.event, events::INGEST_PROGRESS);
+            if event.data["done"] == true {
+                break event;
+            }
+        };
+        assert_eq!(done.data["indexed"], 2);
+        assert_eq!(done.data["unchanged"], 1);
+        assert_eq!(done.data["queued"], 0);
+        assert_eq!(service.progress(&job).unwrap().received, 3);
+
+        let docs = graph
+            .db()
+            .search_entities("", Some("document"))
+            .await
+            .unwrap();
+        let mut titles: Vec<&str> = docs.iter().map(|d| d.name.as_str()).collect();
+        titles.sort();
+        assert_eq!(titles, ["go.md", "rust.md"]);
+    }
+
+    #[tokio::test]
+    async fn test_idle_jobs_expire_and_finish_is_idempotent() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let graph = Arc::new(
+            KnowledgeGraph::new(temp.path().join("test.db"), temp.path().join("index")).unwrap(),
+        );
+        let service = IngestService::new(graph, ChunkingConfig::default(), EventBus::new(8))
+            .with_idle_timeout(Duration::from_millis(50));
+
+        let idle = service.start();
+        let finished = service.start();
+        service.finish(&finished).unwrap();
+        assert!(service.finish(&finished).unwrap().finished);
+        assert_eq!(service.finished.lock().unwrap().len(), 1);
+
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        assert!(service.progress(&idle).is_none());
+        assert!(service.push(&idle, Vec::new()).await.is_err());
+        assert!(service.progress(&finished).unwrap().done);
+    }
+}
@@ -5,12 +5,15 @@
 
 pub mod auth;
 pub mod events;
+pub mod ingest;
+pub mod nodes;
 pub mod protocol;
 pub mod server;
 pub mod session;
 pub mod session_tools;
 pub mod webchat;
 
+pub use nodes::{NodeGrant, NodeRegistry};
 pub use server::GatewayServer;
 pub use session_tools::{
     AgentToAgentConfig, AgentsListTool, SessionsHistoryTool, SessionsListTool, SessionsSendTool,
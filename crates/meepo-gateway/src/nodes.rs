@@ -0,0 +1,372 @@
+//! Execution nodes — gateway clients that run tools for the agent
+//!
+//! A connected device (a phone offering `send_sms`, a home server offering
+//! `run_command`) sends `node.register` with its name, token and the tools
+//! it offers. It may only register under a configured grant, and only the
+//! tools that grant lists. Its tools appear to the agent as
+//! `<node>__<tool>`; a call goes to the device as a `node.invoke` event and
+//! waits up to the timeout for its `node.result`.
+
+use std::collections::HashMap;
+use std::sync::{Mutex, RwLock};
+use std::time::Duration;
+
+use anyhow::{Result, anyhow, bail};
+use async_trait::async_trait;
+use serde::Deserialize;
+use serde_json::Value;
+use tokio::sync::{mpsc, oneshot};
+use tracing::{info, warn};
+
+use crate::protocol::{self, GatewayEvent};
+use meepo_core::api::ToolDefinition;
+use meepo_core::tools::ToolExecutor;
+
+/// Between the node name and the tool name in a namespaced tool
+pub const SEPARATOR: &str = "__";
+
+/// Grant tool list entry that allows every tool
+pub const ALL_TOOLS: &str = "*";
+
+/// Longest tool name the model APIs accept
+const MAX_TOOL_NAME: usize = 64;
+
+/// A device allowed to register as an execution node
+#[derive(Clone)]
+pub struct NodeGrant {
+    pub name: String,
+    pub token: String,
+    /// Tools it may offer; `"*"` for any
+    pub tools: Vec<String>,
+}
+
+impl std::fmt::Debug for NodeGrant {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("NodeGrant")
+            .field("name", &self.name)
+            .field("token", &"***")
+            .field("tools", &self.tools)
+            .finish()
+    }
+}
+
+impl NodeGrant {
+    fn allows(&self, tool: &str) -> bool {
+        self.tools.iter().any(|t| t == ALL_TOOLS || t == tool)
+    }
+}
+
+/// A tool as a node describes it in `node.register`
+#[derive(Debug, Clone, Deserialize)]
+pub struct OfferedTool {
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    #[serde(default = "empty_schema")]
+    pub input_schema: Value,
+}
+
+fn empty_schema() -> Value {
+    serde_json::json!({"type": "object", "properties": {}})
+}
+
+struct ConnectedNode {
+    client_id: String,
+    tools: Vec<OfferedTool>,
+    sender: mpsc::UnboundedSender<GatewayEvent>,
+}
+
+struct PendingCall {
+    client_id: String,
+    reply: oneshot::Sender<Result<String, String>>,
+}
+
+/// Connected execution nodes and the calls waiting on them
+pub struct NodeRegistry {
+    grants: Vec<NodeGrant>,
+    timeout: Duration,
+    nodes: RwLock<HashMap<String, ConnectedNode>>,
+    pending: Mutex<HashMap<String, PendingCall>>,
+}
+
+impl NodeRegistry {
+    pub fn new(grants: Vec<NodeGrant>, timeout: Duration) -> Self {
+        Self {
+            grants,
+            timeout,
+            nodes: RwLock::new(HashMap::new()),
+            pending: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Register `client_id` as node `name`, replacing an earlier connection
+    /// under that name. Returns the namespaced names of the accepted tools;
+    /// tools the grant doesn't list are dropped.
+    pub fn register(
+        &self,
+        client_id: &str,
+        sender: mpsc::UnboundedSender<GatewayEvent>,
+        name: &str,
+        token: &str,
+        tools: Vec<OfferedTool>,
+    ) -> Result<Vec<String>> {
+        if name.is_empty() || !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '-') {
+            bail!("Node names may only use letters, digits and '-'");
+        }
+        let grant = self
+            .grants
+            .iter()
+            .find(|g| g.name == name && !g.token.is_empty())
+            .filter(|g| crate::auth::validate_token(&g.token, token))
+            .ok_or_else(|| anyhow!("No grant for node '{}' with that token", name))?;
+
+        let mut accepted = Vec::new();
+        for tool in tools {
+            let full = format!("{}{}{}", name, SEPARATOR, tool.name);
+            let valid = !tool.name.is_empty()
+                && full.len() <= MAX_TOOL_NAME
+                && tool
+                    .name
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-');
+            if !valid || !grant.allows(&tool.name) {
+                warn!("Node '{}' may not offer tool '{}'", name, tool.name);
+                continue;
+            }
+            accepted.push(tool);
+        }
+        let names = accepted
+            .iter()
+            .map(|t| format!("{}{}{}", name, SEPARATOR, t.name))
+            .collect();
+        info!("Node '{}' registered {} tool(s)", name, accepted.len());
+        self.nodes
+            .write()
+            .unwrap_or_else(|p| p.into_inner())
+            .insert(
+                name.to_string(),
+                ConnectedNode {
+                    client_id: client_id.to_string(),
+                    tools: accepted,
+                    sender,
+                },
+            );
+        Ok(names)
+    }
+
+    /// Deliver a node's answer to the call waiting on it
+    pub fn complete(
+        &self,
+        client_id: &str,
+        call_id: &str,
+        outcome: Result<String, String>,
+    ) -> Result<()> {
+        let mut pending = self.pending.lock().unwrap_or_else(|p| p.into_inner());
+        match pending.get(call_id) {
+            Some(call) if call.client_id == client_id => {}
+            _ => bail!("No pending call '{}' for this client", call_id),
+        }
+        if let Some(call) = pending.remove(call_id) {
+            let _ = call.reply.send(outcome);
+        }
+        Ok(())
+    }
+
+    /// Drop the nodes a disconnected client registered, failing their
+    /// pending calls. Returns the dropped node names.
+    pub fn detach(&self, client_id: &str) -> Vec<String> {
+        let mut dropped = Vec::new();
+        self.nodes
+            .write()
+            .unwrap_or_else(|p| p.into_inner())
+            .retain(|name, node| {
+                let keep = node.client_id != client_id;
+                if !keep {
+                    dropped.push(name.clone());
+                }
+                keep
+            });
+        let mut pending = self.pending.lock().unwrap_or_else(|p| p.into_inner());
+        let orphaned: Vec<String> = pending
+            .iter()
+            .filter(|(_, call)| call.client_id == client_id)
+            .map(|(id, _)| id.clone())
+            .collect();
+        for id in orphaned {
+            if let Some(call) = pending.remove(&id) {
+                let _ = call.reply.send(Err("the node disconnected".to_string()));
+            }
+        }
+        dropped
+    }
+
+    /// Connected node names with the tools each offers
+    pub fn nodes(&self) -> Vec<(String, Vec<String>)> {
+        let nodes = self.nodes.read().unwrap_or_else(|p| p.into_inner());
+        let mut list: Vec<(String, Vec<String>)> = nodes
+            .iter()
+            .map(|(name, node)| {
+                (
+                    name.clone(),
+                    node.tools.iter().map(|t| t.name.clone()).collect(),
+                )
+            })
+            .collect();
+        list.sort();
+        list
+    }
+
+    /// Run `tool` on `node` and wait for its answer
+    pub async fn invoke(&self, node: &str, tool: &str, input: Value) -> Result<String> {
+        let call_id = uuid::Uuid::new_v4().to_string();
+        let (reply_tx, reply_rx) = oneshot::channel();
+        {
+            let nodes = self.nodes.read().unwrap_or_else(|p| p.into_inner());
+            let connected = nodes
+                .get(node)
+                .filter(|n| n.tools.iter().any(|t| t.name == tool))
+                .ok_or_else(|| anyhow!("Node '{}' doesn't offer '{}' right now", node, tool))?;
+            self.pending
+                .lock()
+                .unwrap_or_else(|p| p.into_inner())
+                .insert(
+                    call_id.clone(),
+                    PendingCall {
+                        client_id: connected.client_id.clone(),
+                        reply: reply_tx,
+                    },
+                );
+            let event = GatewayEvent::new(
+                protocol::events::NODE_INVOKE,
+                serde_json::json!({
+                    "call_id": call_id,
+                    "node": node,
+                    "tool": tool,
+                    "input": input,
+                }),
+            );
+            if connected.sender.send(event).is_err() {
+                self.forget(&call_id);
+                bail!("Node '{}' is no longer connected", node);
+            }
+        }
+
+        match tokio::time::timeout(self.timeout, reply_rx).await {
+            Ok(Ok(Ok(output))) => Ok(output),
+            Ok(Ok(Err(e))) => Err(anyhow!("{} on '{}' failed: {}", tool, node, e)),
+            Ok(Err(_)) => Err(anyhow!("Node '{}' dropped the call", node)),
+            Err(_) => {
+                self.forget(&call_id);
+                Err(anyhow!(
+                    "{} on '{}' timed out after {}s",
+                    tool,
+                    node,
+                    self.timeout.as_secs()
+                ))
+            }
+        }
+    }
+
+    fn forget(&self, call_id: &str) {
+        self.pending
+            .lock()
+            .unwrap_or_else(|p| p.into_inner())
+            .remove(call_id);
+    }
+}
+
+#[async_trait]
+impl ToolExecutor for NodeRegistry {
+    async fn execute(&self, tool_name: &str, input: Value) -> Result<String> {
+        let (node, tool) = tool_name
+            .split_once(SEPARATOR)
+            .ok_or_else(|| anyhow!("Unknown tool: {}", tool_name))?;
+        self.invoke(node, tool, input).await
+    }
+
+    fn list_tools(&self) -> Vec<ToolDefinition> {
+        let nodes = self.nodes.read().unwrap_or_else(|p| p.into_inner());
+        nodes
+            .iter()
+            .flat_map(|(name, node)| {
+                node.tools.iter().map(move |t| ToolDefinition {
+                    name: format!("{}{}{}", name, SEPARATOR, t.name),
+                    description: format!("Runs on the '{}' device. {}", name, t.description),
+                    input_schema: t.input_schema.clone(),
+                })
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+
+    fn offered(names: &[&str]) -> Vec<OfferedTool> {
+        names
+            .iter()
+            .map(|n| OfferedTool {
+                name: n.to_string(),
+                description: String::new(),
+                input_schema: empty_schema(),
+            })
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_node_tools_are_granted_and_routed() {
+        let nodes = Arc::new(NodeRegistry::new(
+            vec![NodeGrant {
+                name: "phone".to_string(),
+                token: "tok".to_string(),
+                tools: vec!["send_sms".to_string()],
+            }],
+            Duration::from_millis(200),
+        ));
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        assert!(
+            nodes
+                .register("c1", tx.clone(), "phone", "wrong", offered(&["send_sms"]))
+                .is_err()
+        );
+        let names = nodes
+            .register(
+                "c1",
+                tx,
+                "phone",
+                "tok",
+                offered(&["send_sms", "run_command"]),
+            )
+            .unwrap();
+        assert_eq!(names, vec!["phone__send_sms"]);
+        assert_eq!(nodes.list_tools().len(), 1);
+
+        let device = nodes.clone();
+        let answer = tokio::spawn(async move {
+            let event = rx.recv().await.unwrap();
+            assert_eq!(event.event, protocol::events::NODE_INVOKE);
+            let call_id = event.data["call_id"].as_str().unwrap().to_string();
+            assert!(device.complete("c2", &call_id, Ok("x".into())).is_err());
+            device.complete("c1", &call_id, Ok("sent".into())).unwrap();
+            rx
+        });
+        let result = nodes
+            .execute("phone__send_sms", serde_json::json!({"to": "+1"}))
+            .await
+            .unwrap();
+        assert_eq!(result, "sent");
+
+        // A node that never answers times out
+        let _rx = answer.await.unwrap();
+        let err = nodes
+            .execute("phone__send_sms", serde_json::json!({}))
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("timed out"));
+
+        assert_eq!(nodes.detach("c1"), vec!["phone"]);
+        assert!(nodes.list_tools().is_empty());
+    }
+}
@@ -45,24 +45,43 @@ pub struct GatewayEvent {
 /// Methods the client can call
 pub mod methods {
     pub const MESSAGE_SEND: &str = "message.send";
+    pub const MESSAGE_REGENERATE: &str = "message.regenerate";
+    pub const MESSAGE_BRANCH: &str = "message.branch";
+    pub const BRANCH_SWITCH: &str = "branch.switch";
     pub const SESSION_LIST: &str = "session.list";
     pub const SESSION_NEW: &str = "session.new";
     pub const SESSION_HISTORY: &str = "session.history";
+    pub const SESSION_JOIN: &str = "session.join";
+    pub const SESSION_LEAVE: &str = "session.leave";
     pub const STATUS_GET: &str = "status.get";
+    pub const PUSH_REGISTER: &str = "push.register";
+    pub const PUSH_UNREGISTER: &str = "push.unregister";
+    pub const NODE_REGISTER: &str = "node.register";
+    pub const NODE_RESULT: &str = "node.result";
 }
 
 /// Events the server broadcasts
 pub mod events {
     pub const MESSAGE_RECEIVED: &str = "message.received";
+    /// A reply on another channel so far, while it's being written
+    pub const MESSAGE_DELTA: &str = "message.delta";
     pub const TYPING_START: &str = "typing.start";
     pub const TYPING_STOP: &str = "typing.stop";
     pub const TOOL_EXECUTING: &str = "tool.executing";
     pub const STATUS_UPDATE: &str = "status.update";
     pub const SESSION_CREATED: &str = "session.created";
+    pub const BRANCH_CHANGED: &str = "branch.changed";
+    pub const SESSION_JOINED: &str = "session.joined";
+    pub const SESSION_LEFT: &str = "session.left";
     pub const CANVAS_PUSH: &str = "canvas.push";
     pub const CANVAS_RESET: &str = "canvas.reset";
     pub const CANVAS_EVAL: &str = "canvas.eval";
     pub const CANVAS_SNAPSHOT: &str = "canvas.snapshot";
+    pub const INGEST_PROGRESS: &str = "ingest.progress";
+    /// Startup warm-up finished; carries what was warmed and how long it took
+    pub const READY: &str = "ready";
+    /// Sent only to the execution node that should run the tool
+    pub const NODE_INVOKE: &str = "node.invoke";
 }
 
 // ── Error codes ──
@@ -1,25 +1,30 @@
 //! Gateway WebSocket server — Axum-based HTTP + WS server
 
+use std::collections::HashSet;
 use std::net::SocketAddr;
 use std::sync::Arc;
 
 use axum::Router;
 use axum::extract::ws::{Message, WebSocket};
-use axum::extract::{ConnectInfo, State, WebSocketUpgrade};
+use axum::extract::{ConnectInfo, DefaultBodyLimit, Path, Query, State, WebSocketUpgrade};
 use axum::http::HeaderValue;
 use axum::http::{HeaderMap, StatusCode};
 use axum::response::IntoResponse;
-use axum::routing::get;
-use tokio::sync::broadcast;
+use axum::routing::{get, post};
+use tokio::sync::{broadcast, mpsc};
 use tower_http::cors::{AllowOrigin, CorsLayer};
 use tracing::{debug, error, info, warn};
 
 use crate::auth;
 use crate::events::EventBus;
+use crate::ingest::{IngestProgress, IngestService, parse_batch};
+use crate::nodes::{NodeRegistry, OfferedTool};
 use crate::protocol::{
-    self, ERR_INVALID_METHOD, ERR_INVALID_PARAMS, GatewayEvent, GatewayRequest, GatewayResponse,
+    self, ERR_INTERNAL, ERR_INVALID_METHOD, ERR_INVALID_PARAMS, ERR_UNAUTHORIZED, GatewayEvent,
+    GatewayRequest, GatewayResponse,
 };
-use crate::session::SessionManager;
+use crate::session::{MessageProvenance, SessionManager, SessionMessage};
+use meepo_core::push::{PushDevice, PushPlatform};
 
 /// Shared state for all WebSocket connections
 #[derive(Clone)]
@@ -28,12 +33,78 @@ pub struct GatewayState {
     pub events: EventBus,
     pub auth_token: String,
     pub start_time: std::time::Instant,
+    /// Execution nodes, when clients may offer tools
+    pub nodes: Option<Arc<NodeRegistry>>,
+}
+
+/// Characters of the latest message shown in session list previews
+const PREVIEW_CHARS: usize = 120;
+
+/// Per-connection state
+#[derive(Clone)]
+struct ClientContext {
+    id: String,
+    /// Sessions this client has joined. A client that hasn't joined any
+    /// session receives events for all of them.
+    joined: Arc<std::sync::RwLock<HashSet<String>>>,
+    /// Events for this client only (tool calls when it's a node)
+    direct: mpsc::UnboundedSender<GatewayEvent>,
+}
+
+impl ClientContext {
+    /// A new client and the receiving end of its direct events
+    fn connect() -> (Self, mpsc::UnboundedReceiver<GatewayEvent>) {
+        let (direct, direct_rx) = mpsc::unbounded_channel();
+        let client = Self {
+            id: uuid::Uuid::new_v4().to_string(),
+            joined: Arc::new(std::sync::RwLock::new(HashSet::new())),
+            direct,
+        };
+        (client, direct_rx)
+    }
+
+    #[cfg(test)]
+    fn new() -> Self {
+        Self::connect().0
+    }
+
+    fn join(&self, session_id: &str) {
+        if let Ok(mut joined) = self.joined.write() {
+            joined.insert(session_id.trim().to_lowercase());
+        }
+    }
+
+    fn leave(&self, session_id: &str) {
+        if let Ok(mut joined) = self.joined.write() {
+            joined.remove(&session_id.trim().to_lowercase());
+        }
+    }
+
+    /// Whether a broadcast event should be forwarded to this client
+    fn wants(&self, event: &GatewayEvent) -> bool {
+        let Ok(joined) = self.joined.read() else {
+            return true;
+        };
+        if joined.is_empty() {
+            return true;
+        }
+        match event.data.get("session_id").and_then(|v| v.as_str()) {
+            Some(session_id) => joined.contains(&session_id.trim().to_lowercase()),
+            None => true,
+        }
+    }
 }
 
 /// The gateway server
 pub struct GatewayServer {
     state: GatewayState,
     bind: SocketAddr,
+    /// Database behind the `/calendar.ics` feed, when published
+    calendar_feed: Option<Arc<meepo_knowledge::KnowledgeDb>>,
+    /// Database behind `/api/timeline`, when served
+    timeline: Option<Arc<meepo_knowledge::KnowledgeDb>>,
+    /// Batch ingestion behind `/api/ingest`, when enabled
+    ingest: Option<Arc<IngestService>>,
 }
 
 impl GatewayServer {
@@ -53,8 +124,50 @@ impl GatewayServer {
             events: EventBus::new(256),
             auth_token,
             start_time: std::time::Instant::now(),
+            nodes: None,
         };
-        Self { state, bind }
+        Self {
+            state,
+            bind,
+            calendar_feed: None,
+            timeline: None,
+            ingest: None,
+        }
+    }
+
+    /// Publish agent-created events, goal deadlines and scheduled tasks at
+    /// `/calendar.ics`
+    pub fn with_calendar_feed(mut self, db: Arc<meepo_knowledge::KnowledgeDb>) -> Self {
+        self.calendar_feed = Some(db);
+        self
+    }
+
+    /// Serve the activity timeline (messages, actions, tasks and watcher
+    /// fires) at `/api/timeline`
+    pub fn with_timeline(mut self, db: Arc<meepo_knowledge::KnowledgeDb>) -> Self {
+        self.timeline = Some(db);
+        self
+    }
+
+    /// Accept streamed document batches at `/api/ingest`, indexing them into
+    /// `graph` with at most `queue_size` documents buffered per job
+    pub fn with_ingest(
+        mut self,
+        graph: Arc<meepo_knowledge::KnowledgeGraph>,
+        chunking: meepo_knowledge::chunking::ChunkingConfig,
+        queue_size: usize,
+    ) -> Self {
+        self.ingest = Some(Arc::new(
+            IngestService::new(graph, chunking, self.state.events.clone())
+                .with_queue_size(queue_size),
+        ));
+        self
+    }
+
+    /// Let authenticated clients register as execution nodes offering tools
+    pub fn with_nodes(mut self, nodes: Arc<NodeRegistry>) -> Self {
+        self.state.nodes = Some(nodes);
+        self
     }
 
     /// Get a reference to the event bus (for broadcasting from outside)
@@ -87,14 +200,51 @@ impl GatewayServer {
             .allow_methods([axum::http::Method::GET, axum::http::Method::POST])
             .allow_headers(tower_http::cors::Any);
 
-        Router::new()
+        let mut router = Router::new()
             .route("/ws", get(ws_handler))
             .route("/api/status", get(status_handler))
             .route("/api/sessions", get(sessions_handler))
             .route("/", get(crate::webchat::index_handler))
             .route("/assets/{*path}", get(crate::webchat::static_handler))
-            .layer(cors)
-            .with_state(self.state.clone())
+            .with_state(self.state.clone());
+        if let Some(db) = &self.calendar_feed {
+            router = router.merge(
+                Router::new()
+                    .route("/calendar.ics", get(calendar_handler))
+                    .with_state(DbState {
+                        db: db.clone(),
+                        auth_token: self.state.auth_token.clone(),
+                    }),
+            );
+        }
+        if let Some(db) = &self.timeline {
+            router = router.merge(
+                Router::new()
+                    .route("/api/timeline", get(timeline_handler))
+                    .with_state(DbState {
+                        db: db.clone(),
+                        auth_token: self.state.auth_token.clone(),
+                    }),
+            );
+        }
+        if let Some(service) = &self.ingest {
+            router = router.merge(
+                Router::new()
+                    .route("/api/ingest", post(ingest_start_handler))
+                    .route("/api/ingest/{job_id}", get(ingest_progress_handler))
+                    .route(
+                        "/api/ingest/{job_id}/documents",
+                        post(ingest_documents_handler)
+                            .layer(DefaultBodyLimit::max(INGEST_BATCH_LIMIT)),
+                    )
+                    .route("/api/ingest/{job_id}/finish", post(ingest_finish_handler))
+                    .with_state(IngestState {
+                        service: service.clone(),
+                        auth_token: self.state.auth_token.clone(),
+                    }),
+            );
+        }
+        router.layer(cors)
     }
 
     /// Start the server (blocks until shutdown)
@@ -140,6 +290,188 @@ async fn status_handler(
     })))
 }
 
+/// State for routes that read the knowledge database
+#[derive(Clone)]
+struct DbState {
+    db: Arc<meepo_knowledge::KnowledgeDb>,
+    auth_token: String,
+}
+
+async fn calendar_handler(
+    State(state): State<DbState>,
+    Query(params): Query<std::collections::HashMap<String, String>>,
+    headers: HeaderMap,
+) -> Result<impl IntoResponse, StatusCode> {
+    // Calendar apps can't send headers, so the token may also come as ?token=
+    let query_ok = params
+        .get("token")
+        .is_some_and(|t| auth::validate_token(&state.auth_token, t));
+    if !query_ok && !check_auth(&state.auth_token, &headers) {
+        return Err(StatusCode::UNAUTHORIZED);
+    }
+    let ics = meepo_core::calendar_feed::build(&state.db)
+        .await
+        .map_err(|e| {
+            error!("Failed to build calendar feed: {}", e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+    Ok((
+        [(
+            axum::http::header::CONTENT_TYPE,
+            "text/calendar; charset=utf-8",
+        )],
+        ics,
+    ))
+}
+
+/// Timeline entries returned when the request doesn't set `limit`
+const TIMELINE_DEFAULT_LIMIT: usize = 50;
+const TIMELINE_MAX_LIMIT: usize = 500;
+
+/// `GET /api/timeline?since=&until=&sources=task,watcher&channel=&q=&limit=`,
+/// newest first; times are RFC 3339
+async fn timeline_handler(
+    State(state): State<DbState>,
+    Query(params): Query<std::collections::HashMap<String, String>>,
+    headers: HeaderMap,
+) -> Result<impl IntoResponse, (StatusCode, String)> {
+    if !check_auth(&state.auth_token, &headers) {
+        return Err((StatusCode::UNAUTHORIZED, "Unauthorized".to_string()));
+    }
+    let bad_request = |msg: String| (StatusCode::BAD_REQUEST, msg);
+    let time = |key: &str| {
+        params
+            .get(key)
+            .map(|v| {
+                chrono::DateTime::parse_from_rfc3339(v)
+                    .map(|t| t.with_timezone(&chrono::Utc))
+                    .map_err(|e| bad_request(format!("Invalid '{}': {}", key, e)))
+            })
+            .transpose()
+    };
+    let sources = params
+        .get("sources")
+        .map(|list| {
+            list.split(',')
+                .filter(|s| !s.trim().is_empty())
+                .map(|s| {
+                    meepo_knowledge::TimelineSource::from_name(s)
+                        .ok_or_else(|| bad_request(format!("Unknown timeline source '{}'", s)))
+                })
+                .collect::<Result<Vec<_>, _>>()
+        })
+        .transpose()?
+        .unwrap_or_default();
+    let limit = match params.get("limit") {
+        Some(limit) => limit
+            .parse::<usize>()
+            .map_err(|_| bad_request(format!("Invalid 'limit': {}", limit)))?,
+        None => TIMELINE_DEFAULT_LIMIT,
+    };
+    let query = meepo_knowledge::TimelineQuery {
+        since: time("since")?,
+        until: time("until")?,
+        sources,
+        channel: params.get("channel").cloned(),
+        contains: params.get("q").cloned(),
+        limit: Some(limit.clamp(1, TIMELINE_MAX_LIMIT)),
+    };
+    let entries = state.db.timeline(query).await.map_err(|e| {
+        error!("Failed to read timeline: {}", e);
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "Failed to read timeline".to_string(),
+        )
+    })?;
+    Ok(axum::Json(serde_json::json!({ "entries": entries })))
+}
+
+/// Largest accepted ingest batch body
+const INGEST_BATCH_LIMIT: usize = 32 * 1024 * 1024;
+
+/// State for the ingestion routes
+#[derive(Clone)]
+struct IngestState {
+    service: Arc<IngestService>,
+    auth_token: String,
+}
+
+type IngestResult = Result<axum::Json<serde_json::Value>, (StatusCode, String)>;
+
+fn ingest_authorized(state: &IngestState, headers: &HeaderMap) -> Result<(), (StatusCode, String)> {
+    if check_auth(&state.auth_token, headers) {
+        Ok(())
+    } else {
+        Err((StatusCode::UNAUTHORIZED, "unauthorized".to_string()))
+    }
+}
+
+fn ingest_json(progress: IngestProgress) -> IngestResult {
+    Ok(axum::Json(progress.to_json()))
+}
+
+fn unknown_job(job_id: &str) -> (StatusCode, String) {
+    (
+        StatusCode::NOT_FOUND,
+        format!("Unknown ingest job '{}'", job_id),
+    )
+}
+
+async fn ingest_start_handler(
+    State(state): State<IngestState>,
+    headers: HeaderMap,
+) -> IngestResult {
+    ingest_authorized(&state, &headers)?;
+    let job_id = state.service.start();
+    Ok(axum::Json(serde_json::json!({ "job_id": job_id })))
+}
+
+/// Queue a batch; the response is delayed while the job's queue is full
+async fn ingest_documents_handler(
+    State(state): State<IngestState>,
+    Path(job_id): Path<String>,
+    headers: HeaderMap,
+    body: String,
+) -> IngestResult {
+    ingest_authorized(&state, &headers)?;
+    if state.service.progress(&job_id).is_none() {
+        return Err(unknown_job(&job_id));
+    }
+    let documents = parse_batch(&body).map_err(|e| (StatusCode::BAD_REQUEST, e.to_string()))?;
+    let progress = state
+        .service
+        .push(&job_id, documents)
+        .await
+        .map_err(|e| (StatusCode::CONFLICT, e.to_string()))?;
+    ingest_json(progress)
+}
+
+async fn ingest_finish_handler(
+    State(state): State<IngestState>,
+    Path(job_id): Path<String>,
+    headers: HeaderMap,
+) -> IngestResult {
+    ingest_authorized(&state, &headers)?;
+    let progress = state
+        .service
+        .finish(&job_id)
+        .map_err(|_| unknown_job(&job_id))?;
+    ingest_json(progress)
+}
+
+async fn ingest_progress_handler(
+    State(state): State<IngestState>,
+    Path(job_id): Path<String>,
+    headers: HeaderMap,
+) -> IngestResult {
+    ingest_authorized(&state, &headers)?;
+    let progress = state
+        .service
+        .progress(&job_id)
+        .ok_or_else(|| unknown_job(&job_id))?;
+    ingest_json(progress)
+}
+
 async fn sessions_handler(
     State(state): State<GatewayState>,
     headers: HeaderMap,
@@ -186,14 +518,26 @@ async fn ws_handler(
 async fn handle_ws(socket: WebSocket, state: GatewayState, addr: SocketAddr) {
     let (mut ws_sender, mut ws_receiver) = socket.split();
     let mut event_rx = state.events.subscribe();
+    let (client, mut direct_rx) = ClientContext::connect();
 
     use futures_util::{SinkExt, StreamExt};
 
     // Spawn a task to forward broadcast events to this client
+    let send_client = client.clone();
     let send_task = tokio::spawn(async move {
         loop {
-            match event_rx.recv().await {
+            let received = tokio::select! {
+                direct = direct_rx.recv() => match direct {
+                    Some(event) => Ok(event),
+                    None => Err(broadcast::error::RecvError::Closed),
+                },
+                broadcast = event_rx.recv() => broadcast,
+            };
+            match received {
                 Ok(event) => {
+                    if !send_client.wants(&event) {
+                        continue;
+                    }
                     let json = match serde_json::to_string(&event) {
                         Ok(j) => j,
                         Err(e) => {
@@ -228,7 +572,7 @@ async fn handle_ws(socket: WebSocket, state: GatewayState, addr: SocketAddr) {
             }
         };
 
-        let response = handle_request(&state, &msg).await;
+        let response = handle_client_request(&state, &client, &msg).await;
         if let Err(e) = serde_json::to_string(&response) {
             error!("Failed to serialize response: {}", e);
             continue;
@@ -244,10 +588,29 @@ async fn handle_ws(socket: WebSocket, state: GatewayState, addr: SocketAddr) {
     }
 
     send_task.abort();
+    if let Some(nodes) = &state.nodes {
+        for node in nodes.detach(&client.id) {
+            info!("Execution node '{}' disconnected", node);
+        }
+    }
+    for (session_id, viewers) in state.sessions.detach_client_everywhere(&client.id).await {
+        state.events.broadcast(GatewayEvent::new(
+            protocol::events::SESSION_LEFT,
+            serde_json::json!({
+                "session_id": session_id,
+                "client_id": client.id,
+                "viewers": viewers,
+            }),
+        ));
+    }
     info!("Client {} disconnected", addr);
 }
 
-async fn handle_request(state: &GatewayState, raw: &str) -> GatewayResponse {
+async fn handle_client_request(
+    state: &GatewayState,
+    client: &ClientContext,
+    raw: &str,
+) -> GatewayResponse {
     let req: GatewayRequest = match serde_json::from_str(raw) {
         Ok(r) => r,
         Err(e) => {
@@ -262,20 +625,106 @@ async fn handle_request(state: &GatewayState, raw: &str) -> GatewayResponse {
             let sessions = state.sessions.count().await;
             let uptime = state.start_time.elapsed().as_secs();
             let clients = state.events.subscriber_count();
+            let warmup = state.events.readiness();
+            let nodes: serde_json::Map<String, serde_json::Value> = state
+                .nodes
+                .as_ref()
+                .map(|n| n.nodes())
+                .unwrap_or_default()
+                .into_iter()
+                .map(|(name, tools)| (name, serde_json::json!(tools)))
+                .collect();
             GatewayResponse::ok(
                 id,
                 serde_json::json!({
                     "status": "ok",
+                    "ready": warmup.is_some(),
+                    "warmup": warmup,
                     "sessions": sessions,
                     "connected_clients": clients,
                     "uptime_secs": uptime,
+                    "nodes": nodes,
                 }),
             )
         }
 
         protocol::methods::SESSION_LIST => {
             let sessions = state.sessions.list().await;
-            GatewayResponse::ok(id, serde_json::to_value(&sessions).unwrap_or_default())
+            let mut entries = Vec::with_capacity(sessions.len());
+            for session in &sessions {
+                let mut entry = serde_json::to_value(session).unwrap_or_default();
+                if let Some(obj) = entry.as_object_mut() {
+                    let preview = session.preview(PREVIEW_CHARS).map(
+                        |(role, content)| serde_json::json!({"role": role, "content": content}),
+                    );
+                    obj.insert("preview".to_string(), serde_json::json!(preview));
+                    obj.insert(
+                        "viewers".to_string(),
+                        serde_json::json!(state.sessions.viewer_count(&session.id).await),
+                    );
+                }
+                entries.push(entry);
+            }
+            GatewayResponse::ok(id, serde_json::Value::Array(entries))
+        }
+
+        protocol::methods::SESSION_JOIN => {
+            let Some(session_id) = req.params.get("session_id").and_then(|v| v.as_str()) else {
+                return GatewayResponse::err(id, ERR_INVALID_PARAMS, "Missing 'session_id'");
+            };
+            let viewers = match state.sessions.attach_client(session_id, &client.id).await {
+                Ok(n) => n,
+                Err(e) => return GatewayResponse::err(id, ERR_INVALID_PARAMS, e),
+            };
+            client.join(session_id);
+            state.events.broadcast(GatewayEvent::new(
+                protocol::events::SESSION_JOINED,
+                serde_json::json!({
+                    "session_id": session_id,
+                    "client_id": client.id,
+                    "viewers": viewers,
+                }),
+            ));
+
+            // Hand the conversation over: the session plus its current branch
+            let session = state.sessions.get(session_id).await;
+            let messages: Vec<serde_json::Value> = state
+                .sessions
+                .get_branch_view(session_id, 100)
+                .await
+                .unwrap_or_default()
+                .iter()
+                .map(|(m, alternatives)| message_json(m, alternatives))
+                .collect();
+            GatewayResponse::ok(
+                id,
+                serde_json::json!({
+                    "session": session,
+                    "client_id": client.id,
+                    "viewers": viewers,
+                    "messages": messages,
+                }),
+            )
+        }
+
+        protocol::methods::SESSION_LEAVE => {
+            let Some(session_id) = req.params.get("session_id").and_then(|v| v.as_str()) else {
+                return GatewayResponse::err(id, ERR_INVALID_PARAMS, "Missing 'session_id'");
+            };
+            client.leave(session_id);
+            let viewers = state.sessions.detach_client(session_id, &client.id).await;
+            state.events.broadcast(GatewayEvent::new(
+                protocol::events::SESSION_LEFT,
+                serde_json::json!({
+                    "session_id": session_id,
+                    "client_id": client.id,
+                    "viewers": viewers,
+                }),
+            ));
+            GatewayResponse::ok(
+                id,
+                serde_json::json!({"session_id": session_id, "viewers": viewers}),
+            )
         }
 
         protocol::methods::SESSION_NEW => {
@@ -297,6 +746,111 @@ async fn handle_request(state: &GatewayState, raw: &str) -> GatewayResponse {
             }
         }
 
+        protocol::methods::PUSH_REGISTER => {
+            let platform = req
+                .params
+                .get("platform")
+                .and_then(|v| v.as_str())
+                .and_then(PushPlatform::from_name);
+            let token = req.params.get("token").and_then(|v| v.as_str());
+            let (Some(platform), Some(token)) = (platform, token) else {
+                return GatewayResponse::err(
+                    id,
+                    ERR_INVALID_PARAMS,
+                    "Need 'platform' (apns or fcm) and 'token'",
+                );
+            };
+            let name = req
+                .params
+                .get("name")
+                .and_then(|v| v.as_str())
+                .unwrap_or_default();
+            let device = PushDevice {
+                platform,
+                token: token.to_string(),
+                name: name.to_string(),
+            };
+            match state.sessions.register_device(&client.id, device).await {
+                Ok(()) => GatewayResponse::ok(id, serde_json::json!({"registered": true})),
+                Err(e) => GatewayResponse::err(id, ERR_INVALID_PARAMS, e),
+            }
+        }
+
+        protocol::methods::PUSH_UNREGISTER => {
+            let Some(token) = req.params.get("token").and_then(|v| v.as_str()) else {
+                return GatewayResponse::err(id, ERR_INVALID_PARAMS, "Missing 'token'");
+            };
+            match state.sessions.unregister_device(token).await {
+                Ok(removed) => GatewayResponse::ok(id, serde_json::json!({"removed": removed})),
+                Err(e) => GatewayResponse::err(id, ERR_INVALID_PARAMS, e),
+            }
+        }
+
+        protocol::methods::NODE_REGISTER => {
+            let Some(nodes) = &state.nodes else {
+                return GatewayResponse::err(
+                    id,
+                    ERR_INVALID_METHOD,
+                    "Execution nodes are not enabled",
+                );
+            };
+            let Some(name) = req.params.get("name").and_then(|v| v.as_str()) else {
+                return GatewayResponse::err(id, ERR_INVALID_PARAMS, "Missing 'name'");
+            };
+            let token = req
+                .params
+                .get("token")
+                .and_then(|v| v.as_str())
+                .unwrap_or_default();
+            let tools: Vec<OfferedTool> = match req
+                .params
+                .get("tools")
+                .cloned()
+                .map(serde_json::from_value)
+                .transpose()
+            {
+                Ok(tools) => tools.unwrap_or_default(),
+                Err(e) => {
+                    return GatewayResponse::err(
+                        id,
+                        ERR_INVALID_PARAMS,
+                        format!("Invalid 'tools': {}", e),
+                    );
+                }
+            };
+            match nodes.register(&client.id, client.direct.clone(), name, token, tools) {
+                Ok(tools) => {
+                    GatewayResponse::ok(id, serde_json::json!({"node": name, "tools": tools}))
+                }
+                Err(e) => GatewayResponse::err(id, ERR_UNAUTHORIZED, e.to_string()),
+            }
+        }
+
+        protocol::methods::NODE_RESULT => {
+            let Some(nodes) = &state.nodes else {
+                return GatewayResponse::err(
+                    id,
+                    ERR_INVALID_METHOD,
+                    "Execution nodes are not enabled",
+                );
+            };
+            let Some(call_id) = req.params.get("call_id").and_then(|v| v.as_str()) else {
+                return GatewayResponse::err(id, ERR_INVALID_PARAMS, "Missing 'call_id'");
+            };
+            let outcome = match req.params.get("error").and_then(|v| v.as_str()) {
+                Some(error) => Err(error.to_string()),
+                None => Ok(match req.params.get("result") {
+                    Some(serde_json::Value::String(s)) => s.clone(),
+                    Some(other) => other.to_string(),
+                    None => String::new(),
+                }),
+            };
+            match nodes.complete(&client.id, call_id, outcome) {
+                Ok(()) => GatewayResponse::ok(id, serde_json::json!({"accepted": true})),
+                Err(e) => GatewayResponse::err(id, ERR_INVALID_PARAMS, e.to_string()),
+            }
+        }
+
         protocol::methods::SESSION_HISTORY => {
             let session_id = req
                 .params
@@ -304,18 +858,27 @@ async fn handle_request(state: &GatewayState, raw: &str) -> GatewayResponse {
                 .and_then(|v| v.as_str())
                 .unwrap_or("main");
 
-            match state.sessions.get(session_id).await {
-                Some(_session) => {
-                    // TODO: Load history from KnowledgeDb once integrated
+            let limit = req
+                .params
+                .get("limit")
+                .and_then(|v| v.as_u64())
+                .unwrap_or(100) as usize;
+
+            match state.sessions.get_branch_view(session_id, limit).await {
+                Ok(view) => {
+                    let messages: Vec<serde_json::Value> = view
+                        .iter()
+                        .map(|(m, alternatives)| message_json(m, alternatives))
+                        .collect();
                     GatewayResponse::ok(
                         id,
                         serde_json::json!({
                             "session_id": session_id,
-                            "messages": [],
+                            "messages": messages,
                         }),
                     )
                 }
-                None => GatewayResponse::err(
+                Err(_) => GatewayResponse::err(
                     id,
                     ERR_INVALID_PARAMS,
                     format!("Session '{}' not found", session_id),
@@ -342,40 +905,190 @@ async fn handle_request(state: &GatewayState, raw: &str) -> GatewayResponse {
                 }
             };
 
-            // Record activity
-            state.sessions.record_activity(session_id).await;
+            // Structured output: {"type": "json", "schema": {...}} (schema optional)
+            let params = match req.params.get("response_format").filter(|v| !v.is_null()) {
+                Some(format) => match response_format(format) {
+                    Ok(params) => Some(params),
+                    Err(e) => return GatewayResponse::err(id, ERR_INVALID_PARAMS, e),
+                },
+                None => None,
+            };
+            let structured = params.is_some();
 
-            // Broadcast typing indicator
-            state.events.broadcast(GatewayEvent::new(
-                protocol::events::TYPING_START,
-                serde_json::json!({"session_id": session_id}),
-            ));
+            let user_msg = match state
+                .sessions
+                .append_reply(
+                    session_id,
+                    None,
+                    "user",
+                    content,
+                    MessageProvenance::User,
+                    None,
+                )
+                .await
+            {
+                Ok(m) => m,
+                Err(e) => return GatewayResponse::err(id, ERR_INVALID_PARAMS, e),
+            };
+            mirror_user_message(state, client, session_id, &user_msg);
+
+            match respond(state, session_id, &user_msg, params).await {
+                Ok(reply) => {
+                    let mut result = serde_json::json!({
+                        "session_id": session_id,
+                        "message_id": user_msg.id,
+                        "reply_id": reply.id,
+                        "content": reply.content,
+                    });
+                    if structured {
+                        result["structured"] =
+                            meepo_core::structured::parse_json_text(&reply.content)
+                                .unwrap_or(serde_json::Value::Null);
+                    }
+                    GatewayResponse::ok(id, result)
+                }
+                Err(e) => GatewayResponse::err(id, ERR_INTERNAL, e),
+            }
+        }
+
+        protocol::methods::MESSAGE_REGENERATE => {
+            let session_id = req
+                .params
+                .get("session_id")
+                .and_then(|v| v.as_str())
+                .unwrap_or("main");
+            let message_id = req.params.get("message_id").and_then(|v| v.as_str());
+
+            let (target, prompt) = match state
+                .sessions
+                .regeneration_target(session_id, message_id)
+                .await
+            {
+                Ok(t) => t,
+                Err(e) => return GatewayResponse::err(id, ERR_INVALID_PARAMS, e),
+            };
+            let Some(prompt) = prompt else {
+                return GatewayResponse::err(id, ERR_INVALID_PARAMS, "Response has no prompt");
+            };
 
-            // TODO: Route message to Agent for processing
-            // For now, echo back a placeholder
-            let response_text = format!("[Gateway] Received: {}", content);
+            // Optional generation overrides, stored on the new alternative
+            let mut params = serde_json::Map::new();
+            for key in ["model", "temperature"] {
+                if let Some(v) = req.params.get(key).filter(|v| !v.is_null()) {
+                    params.insert(key.to_string(), v.clone());
+                }
+            }
+            let params = (!params.is_empty()).then_some(serde_json::Value::Object(params));
+
+            match respond(state, session_id, &prompt, params).await {
+                Ok(reply) => GatewayResponse::ok(
+                    id,
+                    serde_json::json!({
+                        "session_id": session_id,
+                        "replaces": target.id,
+                        "message": message_json(
+                            &reply,
+                            &alternatives(state, session_id, &reply.id).await,
+                        ),
+                    }),
+                ),
+                Err(e) => GatewayResponse::err(id, ERR_INTERNAL, e),
+            }
+        }
+
+        protocol::methods::MESSAGE_BRANCH => {
+            let session_id = req
+                .params
+                .get("session_id")
+                .and_then(|v| v.as_str())
+                .unwrap_or("main");
+            let message_id = req.params.get("message_id").and_then(|v| v.as_str());
+            let index = req
+                .params
+                .get("index")
+                .and_then(|v| v.as_u64())
+                .map(|i| i as usize);
 
+            let from = match state
+                .sessions
+                .branch_from(session_id, message_id, index)
+                .await
+            {
+                Ok(m) => m,
+                Err(e) => return GatewayResponse::err(id, ERR_INVALID_PARAMS, e),
+            };
             state.events.broadcast(GatewayEvent::new(
-                protocol::events::TYPING_STOP,
-                serde_json::json!({"session_id": session_id}),
+                protocol::events::BRANCH_CHANGED,
+                serde_json::json!({"session_id": session_id, "leaf_id": from.id}),
             ));
 
-            // Broadcast the response as a message event
+            // With content, send it as the first message of the new branch
+            let content = req
+                .params
+                .get("content")
+                .and_then(|v| v.as_str())
+                .filter(|c| !c.is_empty());
+            let Some(content) = content else {
+                return GatewayResponse::ok(
+                    id,
+                    serde_json::json!({"session_id": session_id, "branched_from": from.id}),
+                );
+            };
+            let user_msg = match state
+                .sessions
+                .append_reply(
+                    session_id,
+                    Some(&from.id),
+                    "user",
+                    content,
+                    MessageProvenance::User,
+                    None,
+                )
+                .await
+            {
+                Ok(m) => m,
+                Err(e) => return GatewayResponse::err(id, ERR_INVALID_PARAMS, e),
+            };
+            mirror_user_message(state, client, session_id, &user_msg);
+            match respond(state, session_id, &user_msg, None).await {
+                Ok(reply) => GatewayResponse::ok(
+                    id,
+                    serde_json::json!({
+                        "session_id": session_id,
+                        "branched_from": from.id,
+                        "message_id": user_msg.id,
+                        "reply_id": reply.id,
+                        "content": reply.content,
+                    }),
+                ),
+                Err(e) => GatewayResponse::err(id, ERR_INTERNAL, e),
+            }
+        }
+
+        protocol::methods::BRANCH_SWITCH => {
+            let session_id = req
+                .params
+                .get("session_id")
+                .and_then(|v| v.as_str())
+                .unwrap_or("main");
+            let Some(message_id) = req.params.get("message_id").and_then(|v| v.as_str()) else {
+                return GatewayResponse::err(id, ERR_INVALID_PARAMS, "Missing 'message_id'");
+            };
+            if let Err(e) = state.sessions.switch_branch(session_id, message_id).await {
+                return GatewayResponse::err(id, ERR_INVALID_PARAMS, e);
+            }
+            let leaf_id = state
+                .sessions
+                .get(session_id)
+                .await
+                .and_then(|s| s.active_leaf);
             state.events.broadcast(GatewayEvent::new(
-                protocol::events::MESSAGE_RECEIVED,
-                serde_json::json!({
-                    "session_id": session_id,
-                    "content": response_text,
-                    "role": "assistant",
-                }),
+                protocol::events::BRANCH_CHANGED,
+                serde_json::json!({"session_id": session_id, "leaf_id": leaf_id}),
             ));
-
             GatewayResponse::ok(
                 id,
-                serde_json::json!({
-                    "session_id": session_id,
-                    "content": response_text,
-                }),
+                serde_json::json!({"session_id": session_id, "leaf_id": leaf_id}),
             )
         }
 
@@ -387,6 +1100,120 @@ async fn handle_request(state: &GatewayState, raw: &str) -> GatewayResponse {
     }
 }
 
+/// Produce an assistant reply to `prompt` and store it as its child, making
+/// it the active leaf. Regenerations call this with a prompt that already has
+/// a reply, which adds an alternative rather than replacing it.
+async fn respond(
+    state: &GatewayState,
+    session_id: &str,
+    prompt: &SessionMessage,
+    params: Option<serde_json::Value>,
+) -> Result<SessionMessage, &'static str> {
+    // Broadcast typing indicator
+    state.events.broadcast(GatewayEvent::new(
+        protocol::events::TYPING_START,
+        serde_json::json!({"session_id": session_id}),
+    ));
+
+    // TODO: Route message to Agent for processing (with `params` overrides)
+    // For now, echo back a placeholder
+    let response_text = format!("[Gateway] Received: {}", prompt.content);
+
+    state.events.broadcast(GatewayEvent::new(
+        protocol::events::TYPING_STOP,
+        serde_json::json!({"session_id": session_id}),
+    ));
+
+    let reply = state
+        .sessions
+        .append_reply(
+            session_id,
+            Some(&prompt.id),
+            "assistant",
+            &response_text,
+            MessageProvenance::Assistant,
+            params,
+        )
+        .await?;
+
+    // Broadcast the response as a message event
+    state.events.broadcast(GatewayEvent::new(
+        protocol::events::MESSAGE_RECEIVED,
+        serde_json::json!({
+            "session_id": session_id,
+            "message_id": reply.id,
+            "parent_id": reply.parent_id,
+            "content": reply.content,
+            "role": "assistant",
+            "trace": reply.trace,
+        }),
+    ));
+
+    Ok(reply)
+}
+
+/// Generation params for a `response_format` request option, with the
+/// schema the answer must match (a `{"answer": ...}` object by default)
+fn response_format(format: &serde_json::Value) -> Result<serde_json::Value, String> {
+    match format.get("type").and_then(|t| t.as_str()) {
+        Some("json") => {}
+        _ => return Err("response_format.type must be \"json\"".to_string()),
+    }
+    let schema = match format.get("schema").filter(|s| !s.is_null()) {
+        Some(schema) => {
+            meepo_core::structured::check_schema(schema)
+                .map_err(|e| format!("Invalid response_format.schema: {}", e))?;
+            schema.clone()
+        }
+        None => meepo_core::structured::default_schema(),
+    };
+    Ok(serde_json::json!({ "response_format": { "type": "json", "schema": schema } }))
+}
+
+/// Broadcast a user's message so other devices viewing the session see it.
+/// `client_id` lets the sending client skip its own echo.
+fn mirror_user_message(
+    state: &GatewayState,
+    client: &ClientContext,
+    session_id: &str,
+    msg: &SessionMessage,
+) {
+    state.events.broadcast(GatewayEvent::new(
+        protocol::events::MESSAGE_RECEIVED,
+        serde_json::json!({
+            "session_id": session_id,
+            "message_id": msg.id,
+            "parent_id": msg.parent_id,
+            "content": msg.content,
+            "role": "user",
+            "client_id": client.id,
+        }),
+    ));
+}
+
+async fn alternatives(state: &GatewayState, session_id: &str, message_id: &str) -> Vec<String> {
+    state
+        .sessions
+        .get(session_id)
+        .await
+        .map(|s| s.alternatives(message_id))
+        .unwrap_or_default()
+}
+
+/// Wire format for a message in history and regeneration results
+fn message_json(m: &SessionMessage, alternatives: &[String]) -> serde_json::Value {
+    serde_json::json!({
+        "id": m.id,
+        "parent_id": m.parent_id,
+        "role": m.role,
+        "content": m.content,
+        "timestamp": m.timestamp.to_rfc3339(),
+        "params": m.params,
+        "alternatives": alternatives,
+        "alternative_index": alternatives.iter().position(|a| *a == m.id).unwrap_or(0),
+    })
+}
+
 fn check_auth(configured_token: &str, headers: &HeaderMap) -> bool {
     if configured_token.is_empty() {
         return true;
@@ -409,6 +1236,11 @@ fn check_auth(configured_token: &str, headers: &HeaderMap) -> bool {
 mod tests {
     use super::*;
 
+    /// Handle a request from a fresh client that hasn't joined any session
+    async fn handle_request(state: &GatewayState, raw: &str) -> GatewayResponse {
+        handle_client_request(state, &ClientContext::new(), raw).await
+    }
+
     #[test]
     fn test_check_auth_no_config() {
         let headers = HeaderMap::new();
@@ -435,6 +1267,84 @@ mod tests {
         assert!(!check_auth("secret123", &headers));
     }
 
+    #[tokio::test]
+    async fn test_calendar_feed_accepts_query_token() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let state = DbState {
+            db: Arc::new(meepo_knowledge::KnowledgeDb::new(temp.path().join("test.db")).unwrap()),
+            auth_token: "secret123".to_string(),
+        };
+        let query = |token: &str| {
+            Query(std::collections::HashMap::from([(
+                "token".to_string(),
+                token.to_string(),
+            )]))
+        };
+
+        let denied = calendar_handler(State(state.clone()), query("wrong"), HeaderMap::new()).await;
+        assert_eq!(denied.err(), Some(StatusCode::UNAUTHORIZED));
+
+        let resp = calendar_handler(State(state), query("secret123"), HeaderMap::new())
+            .await
+            .unwrap()
+            .into_response();
+        assert_eq!(resp.status(), StatusCode::OK);
+        assert_eq!(
+            resp.headers()[axum::http::header::CONTENT_TYPE],
+            "text/calendar; charset=utf-8"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_timeline_filters_by_source() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let db = meepo_knowledge::KnowledgeDb::new(temp.path().join("test.db")).unwrap();
+        db.insert_conversation("discord", "alex", "hi", None)
+            .await
+            .unwrap();
+        db.insert_background_task("t-1", "Summarize inbox", "discord", "agent")
+            .await
+            .unwrap();
+        let state = DbState {
+            db: Arc::new(db),
+            auth_token: String::new(),
+        };
+        let query = |pairs: &[(&str, &str)]| {
+            Query(
+                pairs
+                    .iter()
+                    .map(|(k, v)| (k.to_string(), v.to_string()))
+                    .collect(),
+            )
+        };
+
+        let resp = timeline_handler(
+            State(state.clone()),
+            query(&[("sources", "tasks")]),
+            HeaderMap::new(),
+        )
+        .await
+        .unwrap()
+        .into_response();
+        let body = axum::body::to_bytes(resp.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        let entries = body["entries"].as_array().unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0]["source"], "task");
+
+        let bad = timeline_handler(
+            State(state),
+            query(&[("since", "yesterday")]),
+            HeaderMap::new(),
+        )
+        .await
+        .err()
+        .unwrap();
+        assert_eq!(bad.0, StatusCode::BAD_REQUEST);
+    }
+
     #[tokio::test]
     async fn test_handle_request_status() {
         let state = GatewayState {
@@ -442,6 +1352,7 @@ mod tests {
             events: EventBus::new(16),
             auth_token: String::new(),
             start_time: std::time::Instant::now(),
+            nodes: None,
         };
         let resp = handle_request(&state, r#"{"method":"status.get","params":{}}"#).await;
         assert!(resp.result.is_some());
@@ -455,11 +1366,157 @@ mod tests {
             events: EventBus::new(16),
             auth_token: String::new(),
             start_time: std::time::Instant::now(),
+            nodes: None,
         };
         let resp = handle_request(&state, r#"{"method":"session.list","params":{}}"#).await;
         assert!(resp.result.is_some());
     }
 
+    #[tokio::test]
+    async fn test_session_handoff_between_clients() {
+        let state = GatewayState {
+            sessions: Arc::new(SessionManager::new()),
+            events: EventBus::new(16),
+            auth_token: String::new(),
+            start_time: std::time::Instant::now(),
+            nodes: None,
+        };
+        let desktop = ClientContext::new();
+        let phone = ClientContext::new();
+        let mut phone_events = state.events.subscribe();
+
+        handle_client_request(
+            &state,
+            &desktop,
+            r#"{"method":"message.send","params":{"content":"Draft the Q3 report"}}"#,
+        )
+        .await;
+
+        // The phone sees the desktop's message mirrored, tagged with its origin
+        let mirrored = phone_events.recv().await.unwrap();
+        assert_eq!(mirrored.event, protocol::events::MESSAGE_RECEIVED);
+        assert_eq!(mirrored.data["role"], "user");
+        assert_eq!(mirrored.data["client_id"], desktop.id.as_str());
+
+        // Session list carries a preview of the latest message
+        let list = handle_client_request(&state, &phone, r#"{"method":"session.list"}"#)
+            .await
+            .result
+            .unwrap();
+        assert_eq!(list[0]["preview"]["role"], "assistant");
+        assert!(
+            list[0]["preview"]["content"]
+                .as_str()
+                .unwrap()
+                .contains("Q3 report")
+        );
+
+        // Joining hands over the conversation so far
+        let joined = handle_client_request(
+            &state,
+            &phone,
+            r#"{"method":"session.join","params":{"session_id":"main"}}"#,
+        )
+        .await
+        .result
+        .unwrap();
+        assert_eq!(joined["viewers"], 1);
+        assert_eq!(joined["messages"].as_array().unwrap().len(), 2);
+        assert_eq!(state.sessions.viewer_count("main").await, 1);
+
+        let resp = handle_client_request(
+            &state,
+            &phone,
+            r#"{"method":"session.join","params":{"session_id":"nope"}}"#,
+        )
+        .await;
+        assert!(resp.error.is_some());
+
+        handle_client_request(
+            &state,
+            &phone,
+            r#"{"method":"session.leave","params":{"session_id":"main"}}"#,
+        )
+        .await;
+        assert_eq!(state.sessions.viewer_count("main").await, 0);
+    }
+
+    #[test]
+    fn test_client_only_receives_joined_sessions() {
+        let client = ClientContext::new();
+        let main_event = GatewayEvent::new(
+            "message.received",
+            serde_json::json!({"session_id": "main"}),
+        );
+        let other_event = GatewayEvent::new(
+            "message.received",
+            serde_json::json!({"session_id": "other"}),
+        );
+        let global_event = GatewayEvent::new("status.update", serde_json::json!({}));
+
+        // Not joined anywhere: sees everything
+        assert!(client.wants(&other_event));
+
+        client.join("Main");
+        assert!(client.wants(&main_event));
+        assert!(!client.wants(&other_event));
+        assert!(client.wants(&global_event));
+
+        client.leave("main");
+        assert!(client.wants(&other_event));
+    }
+
+    #[tokio::test]
+    async fn test_push_register_and_disconnect() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let db = Arc::new(meepo_knowledge::KnowledgeDb::new(temp.path().join("test.db")).unwrap());
+        let registry = meepo_core::push::PushRegistry::load(db).await.unwrap();
+        let state = GatewayState {
+            sessions: Arc::new(SessionManager::new().with_push(registry.clone())),
+            events: EventBus::new(16),
+            auth_token: String::new(),
+            start_time: std::time::Instant::now(),
+            nodes: None,
+        };
+        let phone = ClientContext::new();
+
+        let resp = handle_client_request(
+            &state,
+            &phone,
+            r#"{"method":"push.register","params":{"platform":"apns","token":"abc","name":"iPhone"}}"#,
+        )
+        .await;
+        assert!(resp.error.is_none());
+        let resp = handle_request(
+            &state,
+            r#"{"method":"push.register","params":{"platform":"pager","token":"abc"}}"#,
+        )
+        .await;
+        assert!(resp.error.is_some());
+        assert_eq!(registry.devices().await[0].name, "iPhone");
+
+        state.sessions.detach_client_everywhere(&phone.id).await;
+        let resp = handle_request(
+            &state,
+            r#"{"method":"push.unregister","params":{"token":"abc"}}"#,
+        )
+        .await;
+        assert_eq!(resp.result.unwrap()["removed"], true);
+        assert!(registry.devices().await.is_empty());
+
+        // Without a registry, registration is refused
+        let state = GatewayState {
+            sessions: Arc::new(SessionManager::new()),
+            ..state
+        };
+        let resp = handle_request(
+            &state,
+            r#"{"method":"push.register","params":{"platform":"fcm","token":"abc"}}"#,
+        )
+        .await;
+        assert!(resp.error.is_some());
+    }
+
     #[tokio::test]
     async fn test_handle_request_session_new() {
         let state = GatewayState {
@@ -467,6 +1524,7 @@ mod tests {
             events: EventBus::new(16),
             auth_token: String::new(),
             start_time: std::time::Instant::now(),
+            nodes: None,
         };
         let resp = handle_request(
             &state,
@@ -484,6 +1542,7 @@ mod tests {
             events: EventBus::new(16),
             auth_token: String::new(),
             start_time: std::time::Instant::now(),
+            nodes: None,
         };
         let resp = handle_request(&state, r#"{"method":"unknown","params":{}}"#).await;
         assert!(resp.error.is_some());
@@ -497,6 +1556,7 @@ mod tests {
             events: EventBus::new(16),
             auth_token: String::new(),
             start_time: std::time::Instant::now(),
+            nodes: None,
         };
         let resp = handle_request(&state, "not json").await;
         assert!(resp.error.is_some());
@@ -509,6 +1569,7 @@ mod tests {
             events: EventBus::new(16),
             auth_token: String::new(),
             start_time: std::time::Instant::now(),
+            nodes: None,
         };
         let resp = handle_request(
             &state,
@@ -516,8 +1577,136 @@ mod tests {
         )
         .await;
         assert!(resp.result.is_some());
+        // User message plus the reply
+        let session = state.sessions.get("main").await.unwrap();
+        assert_eq!(session.message_count, 2);
+    }
+
+    #[tokio::test]
+    async fn test_handle_request_regenerate_and_switch() {
+        let state = GatewayState {
+            sessions: Arc::new(SessionManager::new()),
+            events: EventBus::new(16),
+            auth_token: String::new(),
+            start_time: std::time::Instant::now(),
+            nodes: None,
+        };
+        let sent = handle_request(
+            &state,
+            r#"{"method":"message.send","params":{"content":"hello"}}"#,
+        )
+        .await
+        .result
+        .unwrap();
+        let first_reply = sent["reply_id"].as_str().unwrap().to_string();
+
+        let regen = handle_request(
+            &state,
+            r#"{"method":"message.regenerate","params":{"model":"gpt-4o","temperature":1.2}}"#,
+        )
+        .await
+        .result
+        .unwrap();
+        assert_eq!(regen["replaces"], first_reply.as_str());
+        assert_eq!(regen["message"]["params"]["model"], "gpt-4o");
+        assert_eq!(regen["message"]["alternative_index"], 1);
+        assert_eq!(
+            regen["message"]["alternatives"].as_array().unwrap().len(),
+            2
+        );
+
+        // History shows the newest alternative; switching brings back the first
+        let history = handle_request(&state, r#"{"method":"session.history","params":{}}"#)
+            .await
+            .result
+            .unwrap();
+        assert_eq!(history["messages"].as_array().unwrap().len(), 2);
+        assert_eq!(history["messages"][1]["id"], regen["message"]["id"]);
+
+        let switch = format!(
+            r#"{{"method":"branch.switch","params":{{"message_id":"{}"}}}}"#,
+            first_reply
+        );
+        assert!(handle_request(&state, &switch).await.error.is_none());
+        let history = handle_request(&state, r#"{"method":"session.history","params":{}}"#)
+            .await
+            .result
+            .unwrap();
+        assert_eq!(history["messages"][1]["id"], first_reply.as_str());
+    }
+
+    #[tokio::test]
+    async fn test_handle_request_branch_from_index() {
+        let state = GatewayState {
+            sessions: Arc::new(SessionManager::new()),
+            events: EventBus::new(16),
+            auth_token: String::new(),
+            start_time: std::time::Instant::now(),
+            nodes: None,
+        };
+        for text in ["one", "two"] {
+            let req = format!(
+                r#"{{"method":"message.send","params":{{"content":"{}"}}}}"#,
+                text
+            );
+            handle_request(&state, &req).await;
+        }
+
+        // Branch after the first reply (index 1) with a different follow-up
+        let resp = handle_request(
+            &state,
+            r#"{"method":"message.branch","params":{"index":1,"content":"two, but different"}}"#,
+        )
+        .await;
+        assert!(resp.error.is_none());
+
+        let history = state.sessions.get_history("main", 10, true).await.unwrap();
+        let contents: Vec<&str> = history.iter().map(|m| m.content.as_str()).collect();
+        assert_eq!(contents[0], "one");
+        assert_eq!(contents[2], "two, but different");
+        assert_eq!(history.len(), 4);
+
+        // The original continuation is still there as an alternative
+        let session = state.sessions.get("main").await.unwrap();
+        assert_eq!(session.alternatives(&history[2].id).len(), 2);
+
+        let resp = handle_request(
+            &state,
+            r#"{"method":"message.branch","params":{"index":99}}"#,
+        )
+        .await;
+        assert!(resp.error.is_some());
+    }
+
+    #[tokio::test]
+    async fn test_handle_request_message_send_response_format() {
+        let state = GatewayState {
+            sessions: Arc::new(SessionManager::new()),
+            events: EventBus::new(16),
+            auth_token: String::new(),
+            start_time: std::time::Instant::now(),
+            nodes: None,
+        };
+        let resp = handle_request(
+            &state,
+            r#"{"method":"message.send","params":{"content":"hi","response_format":{"type":"json","schema":{"type":"array"}}}}"#,
+        )
+        .await;
+        assert!(resp.error.is_some());
+
+        let resp = handle_request(
+            &state,
+            r#"{"method":"message.send","params":{"content":"hi","response_format":{"type":"json"}}}"#,
+        )
+        .await;
+        let result = resp.result.unwrap();
+        assert!(result.get("structured").is_some());
         let session = state.sessions.get("main").await.unwrap();
-        assert_eq!(session.message_count, 1);
+        let reply = session.messages.last().unwrap();
+        assert_eq!(
+            reply.params.as_ref().unwrap()["response_format"]["schema"],
+            meepo_core::structured::default_schema()
+        );
     }
 
     #[tokio::test]
@@ -527,6 +1716,7 @@ mod tests {
             events: EventBus::new(16),
             auth_token: String::new(),
             start_time: std::time::Instant::now(),
+            nodes: None,
         };
         let resp = handle_request(
             &state,
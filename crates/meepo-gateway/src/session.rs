@@ -4,10 +4,19 @@
 //! key normalization (OpenClaw #12846), credential redaction (OpenClaw #13073),
 //! and agent-to-agent session tools (sessions_list, sessions_history,
 //! sessions_send, sessions_spawn).
+//!
+//! Each session's history is a message tree: regenerating a response or
+//! branching from an earlier message adds a sibling instead of overwriting,
+//! and the session's active leaf selects which path is the "current"
+//! conversation.
+//!
+//! Mobile clients can register a push token for their connection; once it
+//! closes, severe notifications reach the phone by push instead.
 
 use chrono::{DateTime, Utc};
+use meepo_core::push::{PushDevice, PushRegistry};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 use tokio::sync::RwLock;
 use tracing::{debug, info, warn};
@@ -70,10 +79,22 @@ pub enum MessageProvenance {
 /// A message stored in session history
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SessionMessage {
+    /// Unique ID within the session's message tree
+    #[serde(default)]
+    pub id: String,
+    /// The message this one follows (None for the first message of the tree)
+    #[serde(default)]
+    pub parent_id: Option<String>,
     pub role: String,
     pub content: String,
     pub timestamp: DateTime<Utc>,
     pub provenance: MessageProvenance,
+    /// Generation parameters (model, temperature) an assistant reply was produced with
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub params: Option<serde_json::Value>,
+    /// Trace of the agent turn that produced an assistant reply (tools, tokens, timings)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub trace: Option<serde_json::Value>,
 }
 
 /// Visibility scope for session tools
@@ -104,13 +125,76 @@ pub struct Session {
     pub message_count: u64,
     #[serde(default)]
     pub parent_session: Option<String>,
+    /// Last message of the currently selected branch
+    #[serde(default)]
+    pub active_leaf: Option<String>,
     #[serde(skip_serializing)]
     pub messages: Vec<SessionMessage>,
 }
 
+impl Session {
+    /// Find a message in the tree by ID
+    pub fn message(&self, id: &str) -> Option<&SessionMessage> {
+        self.messages.iter().find(|m| m.id == id)
+    }
+
+    /// Messages on the active branch, oldest first
+    pub fn active_path(&self) -> Vec<&SessionMessage> {
+        let mut path = Vec::new();
+        let mut cursor = self.active_leaf.as_deref();
+        while let Some(id) = cursor {
+            let Some(msg) = self.message(id) else { break };
+            path.push(msg);
+            cursor = msg.parent_id.as_deref();
+        }
+        path.reverse();
+        path
+    }
+
+    /// IDs of a message and its alternatives (messages with the same parent), oldest first
+    pub fn alternatives(&self, id: &str) -> Vec<String> {
+        let Some(msg) = self.message(id) else {
+            return Vec::new();
+        };
+        self.messages
+            .iter()
+            .filter(|m| m.parent_id == msg.parent_id && m.role == msg.role)
+            .map(|m| m.id.clone())
+            .collect()
+    }
+
+    /// Short preview of the latest message on the active branch
+    pub fn preview(&self, max_chars: usize) -> Option<(String, String)> {
+        let last = self.active_path().pop()?;
+        let mut text: String = last.content.chars().take(max_chars).collect();
+        if last.content.chars().count() > max_chars {
+            text.push('…');
+        }
+        Some((last.role.clone(), redact_credentials(&text)))
+    }
+
+    /// Most recent leaf below a message (following the newest child at each step)
+    fn latest_leaf(&self, id: &str) -> String {
+        let mut current = id.to_string();
+        while let Some(child) = self
+            .messages
+            .iter()
+            .rev()
+            .find(|m| m.parent_id.as_deref() == Some(current.as_str()))
+        {
+            current = child.id.clone();
+        }
+        current
+    }
+}
+
 /// Manages all active sessions
 pub struct SessionManager {
     sessions: Arc<RwLock<HashMap<String, Session>>>,
+    /// Connected clients currently viewing each session (session ID → client IDs)
+    viewers: Arc<RwLock<HashMap<String, HashSet<String>>>>,
+    /// Device tokens mobile clients registered for push
+    push: Option<PushRegistry>,
 }
 
 /// Normalize a session key: lowercase, trim whitespace, reject path traversal
@@ -189,14 +273,51 @@ impl SessionManager {
                 last_activity: now,
                 message_count: 0,
                 parent_session: None,
+                active_leaf: None,
                 messages: Vec::new(),
             },
         );
         Self {
             sessions: Arc::new(RwLock::new(sessions)),
+            viewers: Arc::new(RwLock::new(HashMap::new())),
+            push: None,
         }
     }
 
+    /// Let clients register device tokens for push notifications
+    pub fn with_push(mut self, registry: PushRegistry) -> Self {
+        self.push = Some(registry);
+        self
+    }
+
+    /// Register a client's device token; it's pushed to once the client disconnects
+    pub async fn register_device(
+        &self,
+        client_id: &str,
+        device: PushDevice,
+    ) -> Result<(), &'static str> {
+        let push = self
+            .push
+            .as_ref()
+            .ok_or("Push notifications are not enabled")?;
+        push.register(device, client_id).await.map_err(|e| {
+            warn!("Failed to register push device: {}", e);
+            "Failed to register device"
+        })
+    }
+
+    /// Stop pushing to a device token
+    pub async fn unregister_device(&self, token: &str) -> Result<bool, &'static str> {
+        let push = self
+            .push
+            .as_ref()
+            .ok_or("Push notifications are not enabled")?;
+        push.unregister(token).await.map_err(|e| {
+            warn!("Failed to unregister push device: {}", e);
+            "Failed to unregister device"
+        })
+    }
+
     /// List all sessions (sorted by last activity, newest first)
     pub async fn list(&self) -> Vec<Session> {
         let sessions = self.sessions.read().await;
@@ -293,6 +414,7 @@ impl SessionManager {
             last_activity: now,
             message_count: 0,
             parent_session,
+            active_leaf: None,
             messages: Vec::new(),
         };
         sessions.insert(id.clone(), session.clone());
@@ -328,6 +450,7 @@ impl SessionManager {
         }
         let mut sessions = self.sessions.write().await;
         if sessions.remove(&normalized).is_some() {
+            self.viewers.write().await.remove(&normalized);
             info!("Deleted session '{}'", normalized);
             Ok(())
         } else {
@@ -352,7 +475,7 @@ impl SessionManager {
         }
     }
 
-    /// Append a message to a session's history
+    /// Append a message to the end of a session's active branch
     pub async fn append_message(
         &self,
         session_id: &str,
@@ -360,30 +483,88 @@ impl SessionManager {
         content: &str,
         provenance: MessageProvenance,
     ) -> Result<(), &'static str> {
+        self.append_reply(session_id, None, role, content, provenance, None)
+            .await
+            .map(|_| ())
+    }
+
+    /// Append a message after `parent_id` (or after the active leaf when None)
+    /// and make it the active leaf. Appending after an earlier message starts
+    /// a new branch; the existing continuation is kept as an alternative.
+    pub async fn append_reply(
+        &self,
+        session_id: &str,
+        parent_id: Option<&str>,
+        role: &str,
+        content: &str,
+        provenance: MessageProvenance,
+        params: Option<serde_json::Value>,
+    ) -> Result<SessionMessage, &'static str> {
         let normalized = normalize_session_key(session_id).map_err(|_| "Invalid session ID")?;
         let mut sessions = self.sessions.write().await;
         let session = sessions.get_mut(&normalized).ok_or("Session not found")?;
 
+        let parent_id = match parent_id {
+            Some(id) => {
+                session.message(id).ok_or("Message not found")?;
+                Some(id.to_string())
+            }
+            None => session.active_leaf.clone(),
+        };
+
         let msg = SessionMessage {
+            id: uuid::Uuid::new_v4().to_string(),
+            parent_id,
             role: role.to_string(),
             content: content.to_string(),
             timestamp: Utc::now(),
             provenance,
+            params,
+            trace: None,
         };
-        session.messages.push(msg);
+        session.messages.push(msg.clone());
+        session.active_leaf = Some(msg.id.clone());
         session.message_count += 1;
         session.last_activity = Utc::now();
 
-        // Trim old messages if over limit
+        // Trim old messages if over limit; orphans become branch roots
         if session.messages.len() > MAX_HISTORY_PER_SESSION {
             let drain_count = session.messages.len() - MAX_HISTORY_PER_SESSION;
-            session.messages.drain(..drain_count);
+            let dropped: HashSet<String> = session
+                .messages
+                .drain(..drain_count)
+                .map(|m| m.id)
+                .collect();
+            for m in session.messages.iter_mut() {
+                if m.parent_id.as_ref().is_some_and(|p| dropped.contains(p)) {
+                    m.parent_id = None;
+                }
+            }
         }
 
+        Ok(msg)
+    }
+
+    /// Attach the agent's turn trace to a reply
+    pub async fn attach_trace(
+        &self,
+        session_id: &str,
+        message_id: &str,
+        trace: serde_json::Value,
+    ) -> Result<(), &'static str> {
+        let normalized = normalize_session_key(session_id).map_err(|_| "Invalid session ID")?;
+        let mut sessions = self.sessions.write().await;
+        let session = sessions.get_mut(&normalized).ok_or("Session not found")?;
+        let msg = session
+            .messages
+            .iter_mut()
+            .find(|m| m.id == message_id)
+            .ok_or("Message not found")?;
+        msg.trace = Some(trace);
         Ok(())
     }
 
-    /// Get message history for a session
+    /// Get message history for a session (the active branch only)
     pub async fn get_history(
         &self,
         session_id: &str,
@@ -395,8 +576,8 @@ impl SessionManager {
         let session = sessions.get(&normalized).ok_or("Session not found")?;
 
         let messages: Vec<SessionMessage> = session
-            .messages
-            .iter()
+            .active_path()
+            .into_iter()
             .filter(|m| include_tool_results || m.provenance != MessageProvenance::ToolResult)
             .cloned()
             .collect();
@@ -405,6 +586,163 @@ impl SessionManager {
         Ok(messages[start..].to_vec())
     }
 
+    /// Active branch with the alternative IDs for each message, for clients
+    /// that render "1 of 3" style switchers
+    pub async fn get_branch_view(
+        &self,
+        session_id: &str,
+        limit: usize,
+    ) -> Result<Vec<(SessionMessage, Vec<String>)>, &'static str> {
+        let normalized = normalize_session_key(session_id).map_err(|_| "Invalid session ID")?;
+        let sessions = self.sessions.read().await;
+        let session = sessions.get(&normalized).ok_or("Session not found")?;
+
+        let path = session.active_path();
+        let start = path.len().saturating_sub(limit);
+        Ok(path[start..]
+            .iter()
+            .map(|m| ((*m).clone(), session.alternatives(&m.id)))
+            .collect())
+    }
+
+    /// Resolve the assistant message to regenerate (default: the last one on
+    /// the active branch). Returns it along with the message it replied to.
+    pub async fn regeneration_target(
+        &self,
+        session_id: &str,
+        message_id: Option<&str>,
+    ) -> Result<(SessionMessage, Option<SessionMessage>), &'static str> {
+        let normalized = normalize_session_key(session_id).map_err(|_| "Invalid session ID")?;
+        let sessions = self.sessions.read().await;
+        let session = sessions.get(&normalized).ok_or("Session not found")?;
+
+        let target = match message_id {
+            Some(id) => session.message(id).ok_or("Message not found")?,
+            None => session
+                .active_path()
+                .into_iter()
+                .rev()
+                .find(|m| m.role == "assistant")
+                .ok_or("No assistant response to regenerate")?,
+        };
+        if target.role != "assistant" {
+            return Err("Only assistant responses can be regenerated");
+        }
+        let prompt = target
+            .parent_id
+            .as_deref()
+            .and_then(|p| session.message(p))
+            .cloned();
+        Ok((target.clone(), prompt))
+    }
+
+    /// Make an earlier message the end of the active branch, so the next
+    /// message starts a new branch from it. `index` counts messages on the
+    /// active branch from 0.
+    pub async fn branch_from(
+        &self,
+        session_id: &str,
+        message_id: Option<&str>,
+        index: Option<usize>,
+    ) -> Result<SessionMessage, &'static str> {
+        let normalized = normalize_session_key(session_id).map_err(|_| "Invalid session ID")?;
+        let mut sessions = self.sessions.write().await;
+        let session = sessions.get_mut(&normalized).ok_or("Session not found")?;
+
+        let msg = match (message_id, index) {
+            (Some(id), _) => session.message(id).cloned(),
+            (None, Some(i)) => session.active_path().get(i).map(|m| (*m).clone()),
+            (None, None) => return Err("Provide a message ID or index"),
+        }
+        .ok_or("Message not found")?;
+
+        session.active_leaf = Some(msg.id.clone());
+        session.last_activity = Utc::now();
+        debug!("Session '{}' branched from message {}", normalized, msg.id);
+        Ok(msg)
+    }
+
+    /// Switch the active branch to the one containing a message, continuing
+    /// to its most recent leaf
+    pub async fn switch_branch(
+        &self,
+        session_id: &str,
+        message_id: &str,
+    ) -> Result<(), &'static str> {
+        let normalized = normalize_session_key(session_id).map_err(|_| "Invalid session ID")?;
+        let mut sessions = self.sessions.write().await;
+        let session = sessions.get_mut(&normalized).ok_or("Session not found")?;
+
+        session.message(message_id).ok_or("Message not found")?;
+        session.active_leaf = Some(session.latest_leaf(message_id));
+        Ok(())
+    }
+
+    /// Attach a client to a session so it receives the session's live
+    /// messages. Returns the number of clients now viewing it.
+    pub async fn attach_client(
+        &self,
+        session_id: &str,
+        client_id: &str,
+    ) -> Result<usize, &'static str> {
+        let normalized = normalize_session_key(session_id).map_err(|_| "Invalid session ID")?;
+        if !self.sessions.read().await.contains_key(&normalized) {
+            return Err("Session not found");
+        }
+        let mut viewers = self.viewers.write().await;
+        let clients = viewers.entry(normalized).or_default();
+        clients.insert(client_id.to_string());
+        Ok(clients.len())
+    }
+
+    /// Detach a client from a session. Returns the number of remaining viewers.
+    pub async fn detach_client(&self, session_id: &str, client_id: &str) -> usize {
+        let Ok(normalized) = normalize_session_key(session_id) else {
+            return 0;
+        };
+        let mut viewers = self.viewers.write().await;
+        let Some(clients) = viewers.get_mut(&normalized) else {
+            return 0;
+        };
+        clients.remove(client_id);
+        let remaining = clients.len();
+        if remaining == 0 {
+            viewers.remove(&normalized);
+        }
+        remaining
+    }
+
+    /// Detach a client from every session (on disconnect) and start pushing
+    /// to its devices. Returns the sessions it was viewing with their
+    /// remaining viewer counts.
+    pub async fn detach_client_everywhere(&self, client_id: &str) -> Vec<(String, usize)> {
+        if let Some(push) = &self.push {
+            push.disconnect(client_id).await;
+        }
+        let mut viewers = self.viewers.write().await;
+        let mut left = Vec::new();
+        viewers.retain(|session_id, clients| {
+            if clients.remove(client_id) {
+                left.push((session_id.clone(), clients.len()));
+            }
+            !clients.is_empty()
+        });
+        left
+    }
+
+    /// Number of clients viewing a session
+    pub async fn viewer_count(&self, session_id: &str) -> usize {
+        let Ok(normalized) = normalize_session_key(session_id) else {
+            return 0;
+        };
+        self.viewers
+            .read()
+            .await
+            .get(&normalized)
+            .map(|c| c.len())
+            .unwrap_or(0)
+    }
+
     /// Number of active sessions
     pub async fn count(&self) -> usize {
         self.sessions.read().await.len()
@@ -642,6 +980,31 @@ mod tests {
         assert_eq!(history_all.len(), 3);
     }
 
+    #[tokio::test]
+    async fn test_attach_trace() {
+        let mgr = SessionManager::new();
+        let reply = mgr
+            .append_reply(
+                "main",
+                None,
+                "assistant",
+                "Sunny",
+                MessageProvenance::Assistant,
+                None,
+            )
+            .await
+            .unwrap();
+        assert!(reply.trace.is_none());
+
+        let trace = serde_json::json!({"hops": [{"stage": "tool_loop"}], "duration_ms": 12});
+        mgr.attach_trace("main", &reply.id, trace.clone())
+            .await
+            .unwrap();
+        let history = mgr.get_history("main", 10, false).await.unwrap();
+        assert_eq!(history[0].trace.as_ref(), Some(&trace));
+        assert!(mgr.attach_trace("main", "missing", trace).await.is_err());
+    }
+
     #[tokio::test]
     async fn test_get_history_with_limit() {
         let mgr = SessionManager::new();
@@ -661,6 +1024,89 @@ mod tests {
         assert_eq!(history[1].content, "msg 4");
     }
 
+    #[tokio::test]
+    async fn test_message_tree_branches() {
+        let mgr = SessionManager::new();
+        let q = mgr
+            .append_reply("main", None, "user", "Q", MessageProvenance::User, None)
+            .await
+            .unwrap();
+        let a1 = mgr
+            .append_reply(
+                "main",
+                None,
+                "assistant",
+                "A1",
+                MessageProvenance::Assistant,
+                None,
+            )
+            .await
+            .unwrap();
+        assert_eq!(a1.parent_id.as_deref(), Some(q.id.as_str()));
+
+        // A second answer to the same question becomes the active alternative
+        let a2 = mgr
+            .append_reply(
+                "main",
+                Some(&q.id),
+                "assistant",
+                "A2",
+                MessageProvenance::Assistant,
+                Some(serde_json::json!({"temperature": 0.9})),
+            )
+            .await
+            .unwrap();
+        let history = mgr.get_history("main", 10, true).await.unwrap();
+        assert_eq!(history.len(), 2);
+        assert_eq!(history[1].content, "A2");
+
+        let session = mgr.get("main").await.unwrap();
+        assert_eq!(
+            session.alternatives(&a2.id),
+            vec![a1.id.clone(), a2.id.clone()]
+        );
+        assert_eq!(session.messages.len(), 3);
+
+        let (target, prompt) = mgr.regeneration_target("main", None).await.unwrap();
+        assert_eq!(target.id, a2.id);
+        assert_eq!(prompt.unwrap().id, q.id);
+        assert!(mgr.regeneration_target("main", Some(&q.id)).await.is_err());
+
+        mgr.switch_branch("main", &a1.id).await.unwrap();
+        let history = mgr.get_history("main", 10, true).await.unwrap();
+        assert_eq!(history[1].content, "A1");
+
+        let from = mgr.branch_from("main", None, Some(0)).await.unwrap();
+        assert_eq!(from.id, q.id);
+        assert_eq!(mgr.get_history("main", 10, true).await.unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_viewers_and_preview() {
+        let mgr = SessionManager::new();
+        assert_eq!(mgr.attach_client("main", "desktop").await.unwrap(), 1);
+        assert_eq!(mgr.attach_client("MAIN", "phone").await.unwrap(), 2);
+        assert!(mgr.attach_client("missing", "phone").await.is_err());
+
+        assert_eq!(mgr.detach_client("main", "desktop").await, 1);
+        let left = mgr.detach_client_everywhere("phone").await;
+        assert_eq!(left, vec![("main".to_string(), 0)]);
+        assert_eq!(mgr.viewer_count("main").await, 0);
+
+        assert!(mgr.get("main").await.unwrap().preview(10).is_none());
+        mgr.append_message(
+            "main",
+            "user",
+            "Plan a weekend trip to Lisbon",
+            MessageProvenance::User,
+        )
+        .await
+        .unwrap();
+        let (role, text) = mgr.get("main").await.unwrap().preview(10).unwrap();
+        assert_eq!(role, "user");
+        assert_eq!(text, "Plan a wee…");
+    }
+
     #[tokio::test]
     async fn test_append_message_nonexistent_session() {
         let mgr = SessionManager::new();
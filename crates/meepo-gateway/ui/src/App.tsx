@@ -1,6 +1,6 @@
 import { useCallback, useEffect, useRef, useState } from 'react'
 import ChatInput from './components/ChatInput'
-import ChatMessage from './components/ChatMessage'
+import ChatMessage, { type TurnTrace } from './components/ChatMessage'
 import SessionSidebar from './components/SessionSidebar'
 import TypingIndicator from './components/TypingIndicator'
 import { useWebSocket } from './hooks/useWebSocket'
@@ -8,6 +8,7 @@ import { useWebSocket } from './hooks/useWebSocket'
 interface Message {
   role: 'user' | 'assistant'
   content: string
+  trace?: TurnTrace
 }
 
 interface Session {
@@ -37,9 +38,12 @@ export default function App() {
     for (const evt of events) {
       switch (evt.event) {
         case 'message.received': {
-          const data = evt.data as { content: string; session_id: string; role?: string }
+          const data = evt.data as { content: string; session_id: string; role?: string; trace?: TurnTrace | null }
           if (data.session_id === activeSession) {
-            setMessages((prev) => [...prev, { role: (data.role as 'assistant') || 'assistant', content: data.content }])
+            setMessages((prev) => [
+              ...prev,
+              { role: (data.role as 'assistant') || 'assistant', content: data.content, trace: data.trace ?? undefined },
+            ])
           }
           setIsTyping(false)
           setActiveTool(undefined)
@@ -144,7 +148,7 @@ export default function App() {
               </div>
             )}
             {messages.map((msg, i) => (
-              <ChatMessage key={i} role={msg.role} content={msg.content} />
+              <ChatMessage key={i} role={msg.role} content={msg.content} trace={msg.trace} />
             ))}
             {isTyping && <TypingIndicator tool={activeTool} />}
             <div ref={messagesEndRef} />
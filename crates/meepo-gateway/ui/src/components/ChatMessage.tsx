@@ -1,11 +1,68 @@
 import { Bot, User } from 'lucide-react'
 
+export interface ToolTrace {
+  name: string
+  input: unknown
+  duration_ms: number
+  ok: boolean
+  output_chars: number
+}
+
+export interface TraceHop {
+  stage: string
+  input_tokens: number
+  output_tokens: number
+  duration_ms: number
+  stop_reason?: string
+  tools?: ToolTrace[]
+}
+
+export interface TurnTrace {
+  hops: TraceHop[]
+  duration_ms: number
+}
+
 interface ChatMessageProps {
   role: 'user' | 'assistant'
   content: string
+  trace?: TurnTrace
+}
+
+function TraceDetails({ trace }: { trace: TurnTrace }) {
+  const tools = trace.hops.flatMap((hop) => hop.tools ?? [])
+  const tokens = trace.hops.reduce((sum, hop) => sum + hop.input_tokens + hop.output_tokens, 0)
+
+  return (
+    <details className="mt-2 text-xs text-gray-400">
+      <summary className="cursor-pointer select-none text-gray-500 hover:text-gray-300">
+        {tools.length} tool call{tools.length === 1 ? '' : 's'} · {tokens.toLocaleString()} tokens ·{' '}
+        {(trace.duration_ms / 1000).toFixed(1)}s
+      </summary>
+      <ol className="mt-2 space-y-2 border-l border-gray-700 pl-3">
+        {trace.hops.map((hop, i) => (
+          <li key={i}>
+            <div className="text-gray-300">
+              {hop.stage}
+              {hop.stop_reason ? ` (${hop.stop_reason})` : ''} — {hop.input_tokens} in / {hop.output_tokens} out,{' '}
+              {hop.duration_ms}ms
+            </div>
+            {(hop.tools ?? []).map((tool, j) => (
+              <div key={j} className="mt-1 pl-3">
+                <span className={tool.ok ? 'text-green-400' : 'text-red-400'}>{tool.ok ? '✓' : '✗'}</span>{' '}
+                <span className="font-mono">{tool.name}</span> — {tool.duration_ms}ms, {tool.output_chars} chars
+                <pre className="mt-1 overflow-x-auto rounded bg-gray-950 p-2 text-gray-500">
+                  {JSON.stringify(tool.input, null, 2)}
+                </pre>
+              </div>
+            ))}
+          </li>
+        ))}
+      </ol>
+    </details>
+  )
 }
 
-export default function ChatMessage({ role, content }: ChatMessageProps) {
+export default function ChatMessage({ role, content, trace }: ChatMessageProps) {
   const isUser = role === 'user'
 
   return (
@@ -24,6 +81,7 @@ export default function ChatMessage({ role, content }: ChatMessageProps) {
         <div className="markdown-body text-sm text-gray-200 whitespace-pre-wrap break-words">
           {content}
         </div>
+        {trace && <TraceDetails trace={trace} />}
       </div>
     </div>
   )
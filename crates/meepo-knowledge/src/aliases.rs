@@ -0,0 +1,243 @@
+//! Alias suggestions from usage
+//!
+//! People rarely write full names: "Liz" for Elizabeth Chen, "K8s" for
+//! Kubernetes. For each entity this generates the short forms it plausibly
+//! goes by — nicknames, first names, numeronyms, initialisms — and suggests
+//! the ones that keep turning up in recent messages.
+
+use std::collections::{HashMap, HashSet};
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+
+use crate::sqlite::{Entity, KnowledgeDb};
+
+/// Recent messages scanned for mentions
+const SCAN_CONVERSATIONS: usize = 500;
+
+/// Common English nicknames by first name
+const NICKNAMES: &[(&str, &[&str])] = &[
+    ("alexander", &["alex"]),
+    ("alexandra", &["alex", "sasha"]),
+    ("andrew", &["andy", "drew"]),
+    ("anthony", &["tony"]),
+    ("benjamin", &["ben"]),
+    ("catherine", &["cathy", "kate"]),
+    ("charles", &["charlie", "chuck"]),
+    ("christopher", &["chris"]),
+    ("daniel", &["dan", "danny"]),
+    ("david", &["dave"]),
+    ("deborah", &["deb", "debbie"]),
+    ("edward", &["ed", "ted"]),
+    ("elizabeth", &["liz", "beth", "lizzie"]),
+    ("gregory", &["greg"]),
+    ("james", &["jim", "jimmy"]),
+    ("jennifer", &["jen", "jenny"]),
+    ("jonathan", &["jon"]),
+    ("joseph", &["joe"]),
+    ("katherine", &["kate", "katie", "kathy"]),
+    ("margaret", &["maggie", "meg", "peggy"]),
+    ("matthew", &["matt"]),
+    ("michael", &["mike"]),
+    ("nicholas", &["nick"]),
+    ("patricia", &["pat", "patty"]),
+    ("rebecca", &["becky"]),
+    ("richard", &["rick", "rich"]),
+    ("robert", &["bob", "rob", "bobby"]),
+    ("samantha", &["sam"]),
+    ("samuel", &["sam"]),
+    ("stephen", &["steve"]),
+    ("steven", &["steve"]),
+    ("susan", &["sue"]),
+    ("thomas", &["tom"]),
+    ("timothy", &["tim"]),
+    ("victoria", &["vicky", "tori"]),
+    ("william", &["bill", "will", "billy"]),
+];
+
+/// An alias that recent messages suggest an entity goes by
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AliasSuggestion {
+    pub entity_id: String,
+    pub entity_name: String,
+    pub alias: String,
+    /// "nickname", "first name", "numeronym" or "initialism"
+    pub reason: String,
+    pub mentions: usize,
+}
+
+/// Short forms an entity plausibly goes by, with why
+pub fn candidate_aliases(entity: &Entity) -> Vec<(String, &'static str)> {
+    let words: Vec<&str> = entity.name.split_whitespace().collect();
+    let mut candidates = Vec::new();
+    if words.is_empty() || entity.name.chars().count() > 60 {
+        return candidates;
+    }
+
+    if entity.entity_type == "person" {
+        let first = words[0].to_lowercase();
+        if let Some((_, nicknames)) = NICKNAMES.iter().find(|(name, _)| *name == first) {
+            for nickname in *nicknames {
+                candidates.push((capitalize(nickname), "nickname"));
+            }
+        }
+        if words.len() > 1 && words[0].chars().count() > 2 {
+            candidates.push((words[0].to_string(), "first name"));
+        }
+    } else if words.len() == 1 {
+        // k8s, i18n, a11y
+        let chars: Vec<char> = words[0].chars().collect();
+        if chars.len() >= 5 && chars.iter().all(|c| c.is_alphanumeric()) {
+            candidates.push((
+                format!(
+                    "{}{}{}",
+                    chars[0].to_ascii_uppercase(),
+                    chars.len() - 2,
+                    chars[chars.len() - 1]
+                ),
+                "numeronym",
+            ));
+        }
+    } else {
+        let initials: String = words
+            .iter()
+            .filter_map(|w| w.chars().next())
+            .filter(|c| c.is_alphanumeric())
+            .flat_map(char::to_uppercase)
+            .collect();
+        if initials.chars().count() >= 2 {
+            candidates.push((initials, "initialism"));
+        }
+    }
+    candidates
+}
+
+/// Suggest aliases mentioned at least `min_mentions` times in recent
+/// messages. Candidates that are already a name or alias, or that more than
+/// one entity could claim, are skipped.
+pub async fn suggest_aliases(
+    db: &KnowledgeDb,
+    min_mentions: usize,
+) -> Result<Vec<AliasSuggestion>> {
+    let entities: Vec<Entity> = db
+        .get_all_entities()
+        .await?
+        .into_iter()
+        .filter(|e| e.entity_type != "document_chunk")
+        .collect();
+    let taken: HashSet<String> = entities
+        .iter()
+        .flat_map(|e| std::iter::once(&e.name).chain(&e.aliases))
+        .map(|n| n.to_lowercase())
+        .collect();
+
+    let mut claims: HashMap<String, Vec<(&Entity, &'static str)>> = HashMap::new();
+    for entity in &entities {
+        for (alias, reason) in candidate_aliases(entity) {
+            if !taken.contains(&alias.to_lowercase()) {
+                claims.entry(alias).or_default().push((entity, reason));
+            }
+        }
+    }
+    if claims.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let conversations = db
+        .get_recent_conversations(None, SCAN_CONVERSATIONS)
+        .await?;
+    let mut suggestions = Vec::new();
+    for (alias, claimants) in claims {
+        let [(entity, reason)] = claimants.as_slice() else {
+            continue;
+        };
+        // Initialisms must match case so "EC" isn't found in "ec2" prose
+        let case_sensitive = *reason == "initialism";
+        let mentions = conversations
+            .iter()
+            .filter(|c| c.sender != "meepo")
+            .map(|c| count_word(&c.content, &alias, case_sensitive))
+            .sum();
+        if mentions >= min_mentions.max(1) {
+            suggestions.push(AliasSuggestion {
+                entity_id: entity.id.clone(),
+                entity_name: entity.name.clone(),
+                alias,
+                reason: reason.to_string(),
+                mentions,
+            });
+        }
+    }
+    suggestions.sort_by(|a, b| b.mentions.cmp(&a.mentions).then(a.alias.cmp(&b.alias)));
+    Ok(suggestions)
+}
+
+/// Whole-word occurrences of `word` in `text`
+fn count_word(text: &str, word: &str, case_sensitive: bool) -> usize {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|w| {
+            if case_sensitive {
+                *w == word
+            } else {
+                w.eq_ignore_ascii_case(word)
+            }
+        })
+        .count()
+}
+
+fn capitalize(word: &str) -> String {
+    let mut chars = word.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_suggest_aliases_from_messages() -> Result<()> {
+        let temp = tempfile::TempDir::new()?;
+        let db = KnowledgeDb::new(temp.path().join("test.db"))?;
+        let liz = db.insert_entity("Elizabeth Chen", "person", None).await?;
+        db.insert_entity("Kubernetes", "concept", None).await?;
+        db.insert_entity("Quarterly Business Review", "event", None)
+            .await?;
+        // Two Roberts make "Bob" ambiguous
+        db.insert_entity("Robert Smith", "person", None).await?;
+        db.insert_entity("Robert Jones", "person", None).await?;
+
+        for text in [
+            "Liz says the k8s upgrade is done",
+            "ask Liz about the QBR deck",
+            "Bob and liz are out",
+            "the K8s cluster is flaky, QBR moved",
+            "bob again",
+        ] {
+            db.insert_conversation("slack", "user", text, None).await?;
+        }
+
+        let suggestions = suggest_aliases(&db, 2).await?;
+        let found: Vec<(&str, &str, usize)> = suggestions
+            .iter()
+            .map(|s| (s.alias.as_str(), s.reason.as_str(), s.mentions))
+            .collect();
+        assert_eq!(
+            found,
+            [
+                ("Liz", "nickname", 3),
+                ("K8s", "numeronym", 2),
+                ("QBR", "initialism", 2)
+            ]
+        );
+        assert_eq!(suggestions[0].entity_id, liz);
+
+        // Accepted aliases aren't suggested again
+        db.add_alias(&liz, "Liz").await?;
+        let suggestions = suggest_aliases(&db, 2).await?;
+        assert!(suggestions.iter().all(|s| s.alias != "Liz"));
+        Ok(())
+    }
+}
@@ -1,21 +1,56 @@
 //! Document chunking and ingestion pipeline
 //!
-//! Splits documents into overlapping chunks for indexing in the knowledge
-//! graph. Supports recursive character splitting with configurable chunk
-//! size and overlap.
+//! Splits documents into chunks for indexing in the knowledge graph. Three
+//! strategies are available:
+//!
+//! - **Recursive** — split on separators by size alone, with character
+//!   overlap between consecutive chunks
+//! - **Semantic** — split prose on headings and paragraph boundaries, keep
+//!   small sections whole, and prefix each chunk with its heading path
+//! - **Code** — parse source with tree-sitter and split on top-level items
+//!   (functions, classes, impls), descending into oversized ones and
+//!   prefixing their chunks with the enclosing declaration
+//!
+//! `Auto` picks code-aware splitting for supported languages, recursive
+//! splitting for data formats and semantic splitting for everything else.
 
 use serde::{Deserialize, Serialize};
 use tracing::debug;
+use tree_sitter::{Language, Node, Parser};
+
+/// How documents are split into chunks
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ChunkingStrategy {
+    /// Pick by content type
+    #[default]
+    Auto,
+    /// Separator-based splitting by size
+    Recursive,
+    /// Heading- and paragraph-aware splitting
+    Semantic,
+    /// Function/class-aware splitting of source code
+    Code,
+}
 
 /// Configuration for document chunking
 #[derive(Debug, Clone)]
 pub struct ChunkingConfig {
     /// Target chunk size in characters
     pub chunk_size: usize,
-    /// Overlap between consecutive chunks in characters
+    /// Overlap between consecutive chunks in characters. Semantic and code
+    /// chunks only overlap where a single paragraph or function had to be
+    /// split, or when `overlap_units` is set.
     pub chunk_overlap: usize,
     /// Separators to split on, in priority order
     pub separators: Vec<String>,
+    pub strategy: ChunkingStrategy,
+    /// Prefix semantic chunks with their heading path and code chunks with
+    /// their enclosing declaration
+    pub context_headers: bool,
+    /// Trailing paragraphs or items of a semantic/code chunk to repeat at the
+    /// start of the next one (within `chunk_overlap` characters)
+    pub overlap_units: usize,
 }
 
 impl Default for ChunkingConfig {
@@ -33,6 +68,9 @@ impl Default for ChunkingConfig {
                 ", ".to_string(),
                 " ".to_string(),
             ],
+            strategy: ChunkingStrategy::Auto,
+            context_headers: true,
+            overlap_units: 0,
         }
     }
 }
@@ -67,12 +105,21 @@ pub struct DocumentMetadata {
     pub chunk_count: usize,
 }
 
-/// Split text into chunks using recursive character splitting.
-///
-/// Tries to split on the highest-priority separator that produces chunks
-/// within the target size. Falls back to lower-priority separators, and
-/// ultimately to character-level splitting.
+/// Split plain text into chunks. See [`chunk_document`].
 pub fn chunk_text(text: &str, config: &ChunkingConfig) -> Vec<DocumentChunk> {
+    chunk_document(text, "text/plain", config)
+}
+
+/// Split a document into chunks using the configured strategy.
+///
+/// `content_type` is as returned by [`detect_content_type`]; it picks the
+/// strategy under `Auto` and the grammar for code-aware splitting. Source
+/// that can't be parsed falls back to semantic splitting.
+pub fn chunk_document(
+    text: &str,
+    content_type: &str,
+    config: &ChunkingConfig,
+) -> Vec<DocumentChunk> {
     if text.is_empty() {
         return Vec::new();
     }
@@ -88,6 +135,67 @@ pub fn chunk_text(text: &str, config: &ChunkingConfig) -> Vec<DocumentChunk> {
         }];
     }
 
+    let language = code_language(content_type);
+    let strategy = match config.strategy {
+        ChunkingStrategy::Auto if language.is_some() => ChunkingStrategy::Code,
+        ChunkingStrategy::Auto
+            if matches!(
+                content_type,
+                "application/json" | "application/toml" | "application/yaml" | "text/csv"
+            ) =>
+        {
+            ChunkingStrategy::Recursive
+        }
+        ChunkingStrategy::Auto => ChunkingStrategy::Semantic,
+        strategy => strategy,
+    };
+
+    let units = match strategy {
+        ChunkingStrategy::Recursive => return recursive_chunks(text, config),
+        ChunkingStrategy::Code => language
+            .and_then(|language| code_units(text, &language, config.chunk_size))
+            .unwrap_or_else(|| semantic_units(text)),
+        _ => semantic_units(text),
+    };
+
+    let spans = pack_units(text, &units, config);
+    let mut chunks: Vec<DocumentChunk> = Vec::with_capacity(spans.len());
+    for span in spans {
+        let raw = &text[span.start..span.end];
+        let body = raw.trim();
+        if body.is_empty() {
+            continue;
+        }
+        let start = span.start + (raw.len() - raw.trim_start().len());
+        let content = if config.context_headers && !span.context.is_empty() {
+            format!("{}{}", span.context, body)
+        } else {
+            body.to_string()
+        };
+        chunks.push(DocumentChunk {
+            content,
+            chunk_index: chunks.len(),
+            start_offset: start,
+            end_offset: start + body.len(),
+            total_chunks: 0,
+        });
+    }
+    let total = chunks.len();
+    for chunk in &mut chunks {
+        chunk.total_chunks = total;
+    }
+
+    debug!(
+        "Split {} chars into {} chunks ({:?})",
+        text.len(),
+        total,
+        strategy
+    );
+    chunks
+}
+
+/// Size-only splitting with character overlap
+fn recursive_chunks(text: &str, config: &ChunkingConfig) -> Vec<DocumentChunk> {
     let raw_chunks = recursive_split(text, &config.separators, config.chunk_size);
 
     // Merge small chunks and apply overlap
@@ -104,7 +212,7 @@ pub fn chunk_text(text: &str, config: &ChunkingConfig) -> Vec<DocumentChunk> {
             0
         } else {
             text[offset..]
-                .find(chunk_text.split_at(chunk_text.len().min(50)).0)
+                .find(&chunk_text[..floor_char_boundary(chunk_text, 50)])
                 .map(|pos| offset + pos)
                 .unwrap_or(offset)
         };
@@ -119,13 +227,363 @@ pub fn chunk_text(text: &str, config: &ChunkingConfig) -> Vec<DocumentChunk> {
             total_chunks: total,
         });
 
-        offset = start + chunk_text.len().saturating_sub(config.chunk_overlap);
+        offset = floor_char_boundary(
+            text,
+            start + chunk_text.len().saturating_sub(config.chunk_overlap),
+        );
     }
 
     debug!("Split {} chars into {} chunks", text.len(), chunks.len());
     chunks
 }
 
+/// A paragraph, section or code item that chunks are packed from
+#[derive(Debug)]
+struct Unit {
+    start: usize,
+    end: usize,
+    /// End of the section this unit opens; packing starts a fresh chunk
+    /// rather than split a section that would fit in one
+    keep_with: usize,
+    /// Heading path or enclosing declarations, as prefixed to chunks
+    context: String,
+}
+
+/// A chunk as a byte range of the source plus its context prefix
+struct Span {
+    start: usize,
+    end: usize,
+    context: String,
+}
+
+/// Greedily pack consecutive units into chunks of at most `chunk_size`,
+/// splitting units that are too large on their own
+fn pack_units(text: &str, units: &[Unit], config: &ChunkingConfig) -> Vec<Span> {
+    let mut spans = Vec::new();
+    let mut current: Vec<usize> = Vec::new();
+
+    let flush = |current: &[usize], spans: &mut Vec<Span>| {
+        if let (Some(&first), Some(&last)) = (current.first(), current.last()) {
+            spans.push(Span {
+                start: units[first].start,
+                end: units[last].end,
+                context: units[first].context.clone(),
+            });
+        }
+    };
+
+    for (i, unit) in units.iter().enumerate() {
+        if unit.end - unit.start > config.chunk_size {
+            flush(&current, &mut spans);
+            current.clear();
+            split_unit(text, unit, config, &mut spans);
+            continue;
+        }
+        if let Some(&first) = current.first()
+            && unit.keep_with.max(unit.end) - units[first].start > config.chunk_size
+        {
+            flush(&current, &mut spans);
+            current = carry_units(units, &current, config);
+            if let Some(&first) = current.first()
+                && unit.end - units[first].start > config.chunk_size
+            {
+                current.clear();
+            }
+        }
+        current.push(i);
+    }
+    flush(&current, &mut spans);
+    spans
+}
+
+/// The trailing units of a chunk to repeat at the start of the next
+fn carry_units(units: &[Unit], chunk: &[usize], config: &ChunkingConfig) -> Vec<usize> {
+    let mut carried = Vec::new();
+    let mut size = 0;
+    for &i in chunk.iter().rev().take(config.overlap_units) {
+        size += units[i].end - units[i].start;
+        if size > config.chunk_overlap {
+            break;
+        }
+        carried.insert(0, i);
+    }
+    carried
+}
+
+/// Split an oversized unit by separators, overlapping consecutive pieces
+fn split_unit(text: &str, unit: &Unit, config: &ChunkingConfig, spans: &mut Vec<Span>) {
+    let mut offset = unit.start;
+    for (i, piece) in recursive_split(
+        &text[unit.start..unit.end],
+        &config.separators,
+        config.chunk_size,
+    )
+    .iter()
+    .enumerate()
+    {
+        let start = if i == 0 {
+            offset
+        } else {
+            overlap_start(text, unit.start, offset, config.chunk_overlap)
+        };
+        spans.push(Span {
+            start,
+            end: offset + piece.len(),
+            context: unit.context.clone(),
+        });
+        offset += piece.len();
+    }
+}
+
+/// Where overlap of up to `overlap` bytes before `at` begins, moved forward
+/// to a word boundary so chunks don't open mid-word
+fn overlap_start(text: &str, floor: usize, at: usize, overlap: usize) -> usize {
+    if overlap == 0 {
+        return at;
+    }
+    let mut start = at.saturating_sub(overlap).max(floor);
+    while !text.is_char_boundary(start) {
+        start += 1;
+    }
+    if start == floor {
+        return start;
+    }
+    match text[start..at]
+        .char_indices()
+        .find(|(_, c)| c.is_whitespace())
+    {
+        Some((i, c)) => start + i + c.len_utf8(),
+        None => start,
+    }
+}
+
+/// Paragraph blocks of prose, with headings kept with what follows them
+fn semantic_units(text: &str) -> Vec<Unit> {
+    // (start, heading level, context)
+    let mut blocks: Vec<(usize, Option<usize>, String)> = Vec::new();
+    let mut path: Vec<(usize, String)> = Vec::new();
+    let mut fence: Option<&str> = None;
+    let mut in_block = false;
+    let mut heading_only = false;
+    let mut pos = 0;
+
+    for line in text.split_inclusive('\n') {
+        let start = pos;
+        pos += line.len();
+        let trimmed = line.trim();
+
+        if let Some(marker) = fence {
+            if trimmed.starts_with(marker) {
+                fence = None;
+            }
+            continue;
+        }
+        if trimmed.is_empty() {
+            // A heading stays with the paragraph after it
+            if !heading_only {
+                in_block = false;
+            }
+            continue;
+        }
+        if let Some((level, title)) = parse_heading(line) {
+            while path.last().is_some_and(|(l, _)| *l >= level) {
+                path.pop();
+            }
+            blocks.push((start, Some(level), heading_context(&path)));
+            path.push((level, title.to_string()));
+            in_block = true;
+            heading_only = true;
+            continue;
+        }
+        heading_only = false;
+        if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+            fence = Some(&trimmed[..3]);
+        }
+        if !in_block {
+            blocks.push((start, None, heading_context(&path)));
+            in_block = true;
+        }
+    }
+
+    let mut units: Vec<Unit> = Vec::with_capacity(blocks.len());
+    for (i, (start, level, context)) in blocks.iter().enumerate() {
+        let start = if i == 0 { 0 } else { *start };
+        let end = blocks.get(i + 1).map_or(text.len(), |b| b.0);
+        let keep_with = match level {
+            Some(level) => blocks[i + 1..]
+                .iter()
+                .find(|b| b.1.is_some_and(|l| l <= *level))
+                .map_or(text.len(), |b| b.0),
+            None => end,
+        };
+        units.push(Unit {
+            start,
+            end,
+            keep_with,
+            context: context.clone(),
+        });
+    }
+    units
+}
+
+/// `# Title` → (1, "Title")
+fn parse_heading(line: &str) -> Option<(usize, &str)> {
+    let level = line.chars().take_while(|c| *c == '#').count();
+    let rest = &line[level..];
+    if !(1..=6).contains(&level) || !rest.starts_with([' ', '\t']) {
+        return None;
+    }
+    let title = rest.trim().trim_end_matches('#').trim();
+    (!title.is_empty()).then_some((level, title))
+}
+
+fn heading_context(path: &[(usize, String)]) -> String {
+    if path.is_empty() {
+        return String::new();
+    }
+    let titles: Vec<&str> = path.iter().map(|(_, t)| t.as_str()).collect();
+    format!("{}\n\n", titles.join(" > "))
+}
+
+/// The tree-sitter grammar for a content type, if code-aware splitting
+/// supports it. JavaScript goes through the TSX grammar, which parses plain
+/// JavaScript, TypeScript and JSX alike.
+fn code_language(content_type: &str) -> Option<Language> {
+    match content_type {
+        "text/x-rust" => Some(tree_sitter_rust::LANGUAGE.into()),
+        "text/x-python" => Some(tree_sitter_python::LANGUAGE.into()),
+        "text/javascript" => Some(tree_sitter_typescript::LANGUAGE_TSX.into()),
+        _ => None,
+    }
+}
+
+/// Top-level items of a source file, descending into items larger than a
+/// chunk (an impl, class or module) to split them by member
+fn code_units(text: &str, language: &Language, chunk_size: usize) -> Option<Vec<Unit>> {
+    let mut parser = Parser::new();
+    parser.set_language(language).ok()?;
+    let tree = parser.parse(text, None)?;
+    let root = tree.root_node();
+    if root.named_child_count() == 0 {
+        return None;
+    }
+    let mut units = Vec::new();
+    collect_code_units(text, root, 0, text.len(), "", "", chunk_size, &mut units);
+    Some(units)
+}
+
+/// Units for the items in `container` covering `start..end`. The first unit
+/// also holds whatever precedes the first item (an enclosing declaration's
+/// header), so it takes `outer_context` rather than `context`.
+#[allow(clippy::too_many_arguments)]
+fn collect_code_units(
+    text: &str,
+    container: Node,
+    start: usize,
+    end: usize,
+    outer_context: &str,
+    context: &str,
+    chunk_size: usize,
+    units: &mut Vec<Unit>,
+) {
+    // Comments and attributes belong to the item after them
+    let mut items: Vec<(usize, Node)> = Vec::new();
+    let mut leading: Option<usize> = None;
+    let mut cursor = container.walk();
+    for child in container.named_children(&mut cursor) {
+        let kind = child.kind();
+        if kind.contains("comment") || kind.ends_with("attribute_item") || kind == "decorator" {
+            leading.get_or_insert(child.start_byte());
+            continue;
+        }
+        let item_start = line_start(text, leading.take().unwrap_or(child.start_byte()));
+        if item_start >= start && items.last().is_none_or(|(s, _)| item_start > *s) {
+            items.push((item_start, child));
+        }
+    }
+    if items.is_empty() {
+        units.push(Unit {
+            start,
+            end,
+            keep_with: end,
+            context: outer_context.to_string(),
+        });
+        return;
+    }
+    items[0].0 = start;
+
+    for (i, (item_start, node)) in items.iter().enumerate() {
+        let item_end = items.get(i + 1).map_or(end, |next| next.0);
+        let item_context = if i == 0 { outer_context } else { context };
+        if item_end - item_start > chunk_size
+            && let Some(body) = body_of(*node)
+            && body.named_child_count() > 1
+        {
+            let inner = format!("{}{}\n", context, declaration_header(text, *node, body));
+            collect_code_units(
+                text,
+                body,
+                *item_start,
+                item_end,
+                item_context,
+                &inner,
+                chunk_size,
+                units,
+            );
+            continue;
+        }
+        units.push(Unit {
+            start: *item_start,
+            end: item_end,
+            keep_with: item_end,
+            context: item_context.to_string(),
+        });
+    }
+}
+
+/// The member list of a class, impl, module or function, looking through
+/// decorators (Python) and `export` (JavaScript)
+fn body_of(node: Node) -> Option<Node> {
+    if let Some(body) = node.child_by_field_name("body") {
+        return Some(body);
+    }
+    ["definition", "declaration"]
+        .iter()
+        .find_map(|field| node.child_by_field_name(field))
+        .and_then(body_of)
+}
+
+/// A declaration up to its body on one line, e.g. `impl Store for Db`
+fn declaration_header(text: &str, node: Node, body: Node) -> String {
+    let header = text[node.start_byte()..body.start_byte()]
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ");
+    match header.char_indices().nth(160) {
+        Some((i, _)) => format!("{}…", &header[..i]),
+        None => header,
+    }
+}
+
+/// `pos` moved back to the start of its line if only indentation precedes it
+fn line_start(text: &str, pos: usize) -> usize {
+    let line = text[..pos].rfind('\n').map_or(0, |i| i + 1);
+    if text[line..pos].trim().is_empty() {
+        line
+    } else {
+        pos
+    }
+}
+
+/// Largest char boundary of `text` at or before `index`
+fn floor_char_boundary(text: &str, index: usize) -> usize {
+    let mut index = index.min(text.len());
+    while !text.is_char_boundary(index) {
+        index -= 1;
+    }
+    index
+}
+
 /// Recursively split text on separators
 fn recursive_split(text: &str, separators: &[String], chunk_size: usize) -> Vec<String> {
     if text.len() <= chunk_size || separators.is_empty() {
@@ -189,19 +647,15 @@ fn merge_with_overlap(chunks: &[String], max_size: usize, overlap: usize) -> Vec
         } else {
             // Prepend overlap from previous chunk
             let prev = &chunks[i - 1];
-            let overlap_text = if prev.len() > overlap {
-                &prev[prev.len() - overlap..]
-            } else {
-                prev.as_str()
-            };
+            let overlap_text = &prev[overlap_start(prev, 0, prev.len(), overlap)..];
 
             let merged = format!("{}{}", overlap_text, chunk);
             if merged.len() <= max_size + overlap {
                 result.push(merged);
             } else {
                 // If merged is too large, just use the chunk with truncated overlap
-                let truncated_overlap =
-                    &overlap_text[overlap_text.len().saturating_sub(overlap / 2)..];
+                let truncated_overlap = &overlap_text
+                    [overlap_start(overlap_text, 0, overlap_text.len(), overlap / 2)..];
                 result.push(format!("{}{}", truncated_overlap, chunk));
             }
         }
@@ -233,6 +687,8 @@ pub fn detect_content_type(path: &str) -> &'static str {
         "text/html"
     } else if lower.ends_with(".csv") {
         "text/csv"
+    } else if lower.ends_with(".pdf") {
+        "application/pdf"
     } else {
         "text/plain"
     }
@@ -333,6 +789,7 @@ mod tests {
         assert_eq!(detect_content_type("page.html"), "text/html");
         assert_eq!(detect_content_type("page.htm"), "text/html");
         assert_eq!(detect_content_type("data.csv"), "text/csv");
+        assert_eq!(detect_content_type("report.pdf"), "application/pdf");
     }
 
     #[test]
@@ -407,4 +864,160 @@ mod tests {
         let chunks = chunk_text(text, &config);
         assert!(chunks.len() > 1);
     }
+
+    /// A Rust file whose functions contain blank lines, so size-based
+    /// splitting on paragraph breaks cuts through them
+    fn rust_source(functions: usize) -> String {
+        (0..functions)
+            .map(|i| {
+                format!(
+                    "/// Handles case {i}\nfn handle_{i}(input: &str) -> usize {{\n    let trimmed = input.trim();\n\n    let words = trimmed.split_whitespace().count();\n\n    words * {i}\n}}\n"
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    /// Fraction of functions whose doc comment, signature and closing brace
+    /// land together in one chunk
+    fn intact_ratio(chunks: &[DocumentChunk], functions: usize) -> f64 {
+        let intact = (0..functions)
+            .filter(|i| {
+                chunks.iter().any(|c| {
+                    c.content.contains(&format!("/// Handles case {i}\n"))
+                        && c.content.contains(&format!("words * {i}\n}}"))
+                })
+            })
+            .count();
+        intact as f64 / functions as f64
+    }
+
+    #[test]
+    fn test_code_chunks_keep_functions_whole() {
+        let source = rust_source(12);
+        let config = |strategy| ChunkingConfig {
+            chunk_size: 300,
+            chunk_overlap: 40,
+            strategy,
+            ..Default::default()
+        };
+
+        let code = chunk_document(&source, "text/x-rust", &config(ChunkingStrategy::Auto));
+        let recursive =
+            chunk_document(&source, "text/x-rust", &config(ChunkingStrategy::Recursive));
+        assert_eq!(intact_ratio(&code, 12), 1.0);
+        // Size-based splitting cuts through 5 of the 12
+        assert!(intact_ratio(&recursive, 12) < 0.6);
+        // Offsets point back into the source
+        for chunk in &code {
+            assert_eq!(chunk.content, source[chunk.start_offset..chunk.end_offset]);
+        }
+    }
+
+    #[test]
+    fn test_code_chunks_split_large_items_by_member() {
+        let methods = rust_source(6).replace("\nfn ", "\n    pub fn ");
+        let source = format!("use std::fmt;\n\nimpl Handler for Router {{\n{methods}}}\n");
+        let config = ChunkingConfig {
+            chunk_size: 300,
+            chunk_overlap: 0,
+            ..Default::default()
+        };
+
+        let chunks = chunk_document(&source, "text/x-rust", &config);
+        assert!(chunks.len() > 2);
+        assert!(chunks[0].content.starts_with("use std::fmt;"));
+        // Methods after the first carry the impl they belong to
+        let last = chunks.last().unwrap();
+        assert!(last.content.starts_with("impl Handler for Router\n"));
+        assert!(last.content.contains("handle_5"));
+
+        let python = (0..6)
+            .map(|i| {
+                format!(
+                    "    def method_{i}(self):\n        value = {i}\n\n        return value * 2\n"
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+        let python = format!("@dataclass\nclass Parser(Base):\n{python}");
+        let chunks = chunk_document(&python, "text/x-python", &config);
+        assert!(chunks.len() > 1);
+        assert!(
+            chunks[1..]
+                .iter()
+                .all(|c| c.content.starts_with("@dataclass class Parser(Base):\n"))
+        );
+    }
+
+    #[test]
+    fn test_semantic_chunks_follow_headings() {
+        let doc = "# Guide\n\nIntro paragraph.\n\n## Install\n\nRun the installer.\n\nThen restart.\n\n## Usage\n\n"
+            .to_string()
+            + &"Use it daily. ".repeat(20)
+            + "\n\nMore usage notes.\n";
+        let config = ChunkingConfig {
+            chunk_size: 200,
+            chunk_overlap: 20,
+            ..Default::default()
+        };
+
+        let chunks = chunk_document(&doc, "text/markdown", &config);
+        // The Install section fits, so it stays in one chunk
+        let install = chunks
+            .iter()
+            .find(|c| c.content.contains("## Install"))
+            .unwrap();
+        assert!(install.content.contains("Then restart."));
+        assert!(install.content.starts_with("# Guide\n\nIntro paragraph."));
+        // Pieces of the long Usage section carry their heading path
+        let notes = chunks
+            .iter()
+            .find(|c| c.content.contains("More usage notes."))
+            .unwrap();
+        assert!(notes.content.starts_with("Guide > Usage\n\n"));
+        assert!(chunks.iter().all(|c| c.content.len() <= 200 + 20 + 20));
+
+        let plain = ChunkingConfig {
+            context_headers: false,
+            ..config
+        };
+        let chunks = chunk_document(&doc, "text/markdown", &plain);
+        assert!(chunks.iter().all(|c| !c.content.starts_with("Guide")));
+    }
+
+    #[test]
+    fn test_overlap_units_and_boundaries() {
+        let text = (0..6)
+            .map(|i| format!("Paragraph {i} talks about ünïcödé topic number {i}."))
+            .collect::<Vec<_>>()
+            .join("\n\n");
+        let config = ChunkingConfig {
+            chunk_size: 120,
+            chunk_overlap: 60,
+            overlap_units: 1,
+            ..Default::default()
+        };
+
+        let chunks = chunk_text(&text, &config);
+        for pair in chunks.windows(2) {
+            // The last paragraph of each chunk opens the next
+            let last = pair[0].content.rsplit("\n\n").next().unwrap();
+            assert!(pair[1].content.starts_with(last));
+        }
+
+        // Character overlap never starts mid-word or mid-character
+        let config = ChunkingConfig {
+            chunk_size: 40,
+            chunk_overlap: 15,
+            strategy: ChunkingStrategy::Recursive,
+            ..Default::default()
+        };
+        let chunks = chunk_text(&"ünïcödé wörds ".repeat(20), &config);
+        assert!(
+            chunks[1..]
+                .iter()
+                .all(|c| c.content.starts_with("ünïcödé") || c.content.starts_with("wörds"))
+        );
+    }
 }
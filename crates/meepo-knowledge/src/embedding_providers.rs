@@ -0,0 +1,473 @@
+//! Built-in embedding providers
+//!
+//! [`build`] turns an [`EmbeddingConfig`] into a provider: OpenAI (or any
+//! API speaking its `/v1/embeddings`), Ollama, a local GGUF model run with
+//! llama.cpp's `llama-embedding`, or feature hashing when there's no model
+//! at all. Model-backed providers are wrapped in [`Batched`], which splits
+//! large batches and retries failed requests with exponential backoff.
+//! Remote providers refuse to send anything off this machine while the
+//! host's offline check (see [`set_offline_check`]) says it's offline.
+
+use std::path::PathBuf;
+use std::sync::{Arc, OnceLock};
+use std::time::Duration;
+
+use anyhow::{Context, Result, anyhow, bail};
+use async_trait::async_trait;
+use serde_json::Value;
+use tracing::{debug, warn};
+
+use crate::embeddings::{
+    EmbeddingBackend, EmbeddingConfig, EmbeddingProvider, HashEmbeddingProvider,
+};
+
+/// Longest a single embedding request may take
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// First retry delay; doubled on each further attempt
+const BASE_RETRY_DELAY: Duration = Duration::from_millis(500);
+
+/// Separates texts in one `llama-embedding` run
+const LOCAL_SEPARATOR: &str = "<|meepo-embed|>";
+
+/// The host's offline-mode check; this crate can't see its network settings
+static OFFLINE_CHECK: OnceLock<fn() -> bool> = OnceLock::new();
+
+/// Make remote providers ask `is_offline` before each request. Only the
+/// first call takes effect.
+pub fn set_offline_check(is_offline: fn() -> bool) {
+    let _ = OFFLINE_CHECK.set(is_offline);
+}
+
+/// Fail if `url` leaves this machine while the host is offline
+fn ensure_online(url: &str, what: &str) -> Result<()> {
+    if !OFFLINE_CHECK.get().is_some_and(|is_offline| is_offline()) {
+        return Ok(());
+    }
+    let local = url::Url::parse(url)
+        .ok()
+        .and_then(|u| {
+            u.host().map(|host| match host {
+                url::Host::Domain(d) => d.eq_ignore_ascii_case("localhost"),
+                url::Host::Ipv4(ip) => ip.is_loopback(),
+                url::Host::Ipv6(ip) => ip.is_loopback(),
+            })
+        })
+        .unwrap_or(false);
+    if !local {
+        bail!(
+            "offline mode: {} at {} needs network access, which is disabled",
+            what,
+            url
+        );
+    }
+    Ok(())
+}
+
+/// The provider `config` selects
+pub fn build(config: &EmbeddingConfig) -> Result<Arc<dyn EmbeddingProvider>> {
+    let model = config.model_name.clone();
+    let dims = config.dimensions;
+    let provider: Box<dyn EmbeddingProvider> = match &config.backend {
+        EmbeddingBackend::Hash => return Ok(Arc::new(HashEmbeddingProvider::new(dims))),
+        EmbeddingBackend::OpenAi { api_key, base_url } => {
+            if api_key.is_empty() {
+                bail!("OpenAI embeddings need an API key");
+            }
+            Box::new(OpenAiEmbeddingProvider::new(
+                api_key.clone(),
+                base_url.clone(),
+                model,
+                dims,
+            ))
+        }
+        EmbeddingBackend::Ollama { url } => {
+            Box::new(OllamaEmbeddingProvider::new(url.clone(), model, dims))
+        }
+        EmbeddingBackend::Local {
+            model_path,
+            command,
+        } => Box::new(LocalEmbeddingProvider::new(
+            model_path.clone(),
+            command.clone(),
+            dims,
+        )?),
+    };
+    Ok(Arc::new(Batched::new(
+        provider,
+        config.batch_size,
+        config.max_retries,
+    )))
+}
+
+fn http_client() -> reqwest::Client {
+    reqwest::Client::builder()
+        .timeout(REQUEST_TIMEOUT)
+        .build()
+        .unwrap_or_default()
+}
+
+/// Read a JSON array of numbers as a vector of `dims` floats
+fn parse_vector(value: &Value, dims: usize) -> Result<Vec<f32>> {
+    let vector: Vec<f32> = value
+        .as_array()
+        .ok_or_else(|| anyhow!("Embedding is not an array"))?
+        .iter()
+        .map(|v| v.as_f64().map(|f| f as f32))
+        .collect::<Option<_>>()
+        .ok_or_else(|| anyhow!("Embedding has non-numeric values"))?;
+    if vector.len() != dims {
+        bail!(
+            "Embedding has {} dimensions, expected {}; check embeddings.dimensions",
+            vector.len(),
+            dims
+        );
+    }
+    Ok(vector)
+}
+
+/// Vectors from an OpenAI-style `{"data": [{"index", "embedding"}]}` body,
+/// in input order
+fn parse_openai_data(body: &Value, count: usize, dims: usize) -> Result<Vec<Vec<f32>>> {
+    let data = body["data"]
+        .as_array()
+        .ok_or_else(|| anyhow!("Embedding response has no data"))?;
+    if data.len() != count {
+        bail!("Asked for {} embeddings, got {}", count, data.len());
+    }
+    let mut vectors = vec![Vec::new(); count];
+    for (i, item) in data.iter().enumerate() {
+        let index = item["index"].as_u64().map_or(i, |n| n as usize);
+        let slot = vectors
+            .get_mut(index)
+            .ok_or_else(|| anyhow!("Embedding index {} out of range", index))?;
+        *slot = parse_vector(&item["embedding"], dims)?;
+    }
+    Ok(vectors)
+}
+
+/// OpenAI embeddings (`text-embedding-3-small` and friends)
+pub struct OpenAiEmbeddingProvider {
+    http: reqwest::Client,
+    api_key: String,
+    base_url: String,
+    model: String,
+    dims: usize,
+}
+
+impl OpenAiEmbeddingProvider {
+    pub fn new(api_key: String, base_url: String, model: String, dims: usize) -> Self {
+        Self {
+            http: http_client(),
+            api_key,
+            base_url: base_url.trim_end_matches('/').to_string(),
+            model,
+            dims,
+        }
+    }
+}
+
+#[async_trait]
+impl EmbeddingProvider for OpenAiEmbeddingProvider {
+    async fn embed(&self, text: &str) -> Result<Vec<f32>> {
+        self.embed_batch(&[text])
+            .await?
+            .pop()
+            .ok_or_else(|| anyhow!("No embedding returned"))
+    }
+
+    async fn embed_batch(&self, texts: &[&str]) -> Result<Vec<Vec<f32>>> {
+        let mut body = serde_json::json!({"model": self.model, "input": texts});
+        // The v3 models can shorten their vectors to fit the index
+        if self.model.starts_with("text-embedding-3") {
+            body["dimensions"] = serde_json::json!(self.dims);
+        }
+        ensure_online(&self.base_url, "OpenAI embeddings")?;
+        let response = self
+            .http
+            .post(format!("{}/v1/embeddings", self.base_url))
+            .bearer_auth(&self.api_key)
+            .json(&body)
+            .send()
+            .await
+            .context("Failed to reach the OpenAI embeddings API")?;
+        let status = response.status();
+        if !status.is_success() {
+            let error = response.text().await.unwrap_or_default();
+            bail!("OpenAI embeddings failed with status {}: {}", status, error);
+        }
+        let body: Value = response
+            .json()
+            .await
+            .context("Failed to parse OpenAI embeddings response")?;
+        parse_openai_data(&body, texts.len(), self.dims)
+    }
+
+    fn dimensions(&self) -> usize {
+        self.dims
+    }
+}
+
+/// Embeddings from a local Ollama server (`nomic-embed-text`, `mxbai-embed-large`)
+pub struct OllamaEmbeddingProvider {
+    http: reqwest::Client,
+    url: String,
+    model: String,
+    dims: usize,
+}
+
+impl OllamaEmbeddingProvider {
+    pub fn new(url: String, model: String, dims: usize) -> Self {
+        Self {
+            http: http_client(),
+            url: url.trim_end_matches('/').to_string(),
+            model,
+            dims,
+        }
+    }
+}
+
+#[async_trait]
+impl EmbeddingProvider for OllamaEmbeddingProvider {
+    async fn embed(&self, text: &str) -> Result<Vec<f32>> {
+        ensure_online(&self.url, "Ollama embeddings")?;
+        let response = self
+            .http
+            .post(format!("{}/api/embeddings", self.url))
+            .json(&serde_json::json!({"model": self.model, "prompt": text}))
+            .send()
+            .await
+            .with_context(|| format!("Failed to reach Ollama at {}", self.url))?;
+        let status = response.status();
+        if !status.is_success() {
+            let error = response.text().await.unwrap_or_default();
+            bail!("Ollama embeddings failed with status {}: {}", status, error);
+        }
+        let body: Value = response
+            .json()
+            .await
+            .context("Failed to parse Ollama embeddings response")?;
+        parse_vector(&body["embedding"], self.dims)
+    }
+
+    fn dimensions(&self) -> usize {
+        self.dims
+    }
+}
+
+/// A local GGUF embedding model, run with llama.cpp's `llama-embedding`
+pub struct LocalEmbeddingProvider {
+    model_path: PathBuf,
+    command: String,
+    dims: usize,
+}
+
+impl LocalEmbeddingProvider {
+    pub fn new(model_path: PathBuf, command: String, dims: usize) -> Result<Self> {
+        if !model_path.is_file() {
+            bail!("Embedding model {:?} not found", model_path);
+        }
+        Ok(Self {
+            model_path,
+            command,
+            dims,
+        })
+    }
+}
+
+#[async_trait]
+impl EmbeddingProvider for LocalEmbeddingProvider {
+    async fn embed(&self, text: &str) -> Result<Vec<f32>> {
+        self.embed_batch(&[text])
+            .await?
+            .pop()
+            .ok_or_else(|| anyhow!("No embedding returned"))
+    }
+
+    async fn embed_batch(&self, texts: &[&str]) -> Result<Vec<Vec<f32>>> {
+        let prompt = texts
+            .iter()
+            .map(|t| t.replace(LOCAL_SEPARATOR, " "))
+            .collect::<Vec<_>>()
+            .join(LOCAL_SEPARATOR);
+        let output = tokio::process::Command::new(&self.command)
+            .arg("-m")
+            .arg(&self.model_path)
+            .args(["--embd-output-format", "json"])
+            .args(["--embd-separator", LOCAL_SEPARATOR])
+            .args(["--embd-normalize", "2"])
+            .args(["--log-disable", "-p"])
+            .arg(&prompt)
+            .kill_on_drop(true)
+            .output()
+            .await
+            .with_context(|| format!("Failed to run {}", self.command))?;
+        if !output.status.success() {
+            bail!(
+                "{} failed: {}",
+                self.command,
+                String::from_utf8_lossy(&output.stderr).trim()
+            );
+        }
+        let body: Value = serde_json::from_slice(&output.stdout)
+            .with_context(|| format!("Failed to parse {} output", self.command))?;
+        parse_openai_data(&body, texts.len(), self.dims)
+    }
+
+    fn dimensions(&self) -> usize {
+        self.dims
+    }
+}
+
+/// Splits batches to a provider's size and retries failed requests
+pub struct Batched {
+    inner: Box<dyn EmbeddingProvider>,
+    batch_size: usize,
+    max_retries: u32,
+    base_delay: Duration,
+}
+
+impl Batched {
+    pub fn new(inner: Box<dyn EmbeddingProvider>, batch_size: usize, max_retries: u32) -> Self {
+        Self {
+            inner,
+            batch_size: batch_size.max(1),
+            max_retries,
+            base_delay: BASE_RETRY_DELAY,
+        }
+    }
+
+    /// Set the first retry delay (doubled on each further attempt)
+    pub fn with_base_delay(mut self, delay: Duration) -> Self {
+        self.base_delay = delay;
+        self
+    }
+}
+
+#[async_trait]
+impl EmbeddingProvider for Batched {
+    async fn embed(&self, text: &str) -> Result<Vec<f32>> {
+        self.embed_batch(&[text])
+            .await?
+            .pop()
+            .ok_or_else(|| anyhow!("No embedding returned"))
+    }
+
+    async fn embed_batch(&self, texts: &[&str]) -> Result<Vec<Vec<f32>>> {
+        let mut vectors = Vec::with_capacity(texts.len());
+        for batch in texts.chunks(self.batch_size) {
+            let mut attempt = 0;
+            loop {
+                match self.inner.embed_batch(batch).await {
+                    Ok(batch_vectors) => {
+                        vectors.extend(batch_vectors);
+                        break;
+                    }
+                    Err(e) if attempt < self.max_retries => {
+                        let delay = self.base_delay * 2u32.pow(attempt);
+                        attempt += 1;
+                        warn!(
+                            "Embedding request failed (attempt {}), retrying in {:?}: {:#}",
+                            attempt, delay, e
+                        );
+                        tokio::time::sleep(delay).await;
+                    }
+                    Err(e) => return Err(e),
+                }
+            }
+        }
+        debug!("Embedded {} text(s)", texts.len());
+        Ok(vectors)
+    }
+
+    fn dimensions(&self) -> usize {
+        self.inner.dimensions()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    /// Fails its first `failures` requests, then echoes each text's length
+    struct Flaky {
+        failures: Mutex<u32>,
+        batches: Arc<Mutex<Vec<usize>>>,
+    }
+
+    #[async_trait]
+    impl EmbeddingProvider for Flaky {
+        async fn embed(&self, text: &str) -> Result<Vec<f32>> {
+            Ok(vec![text.len() as f32])
+        }
+
+        async fn embed_batch(&self, texts: &[&str]) -> Result<Vec<Vec<f32>>> {
+            let mut failures = self.failures.lock().unwrap();
+            if *failures > 0 {
+                *failures -= 1;
+                bail!("503 Service Unavailable");
+            }
+            self.batches.lock().unwrap().push(texts.len());
+            Ok(texts.iter().map(|t| vec![t.len() as f32]).collect())
+        }
+
+        fn dimensions(&self) -> usize {
+            1
+        }
+    }
+
+    #[tokio::test]
+    async fn test_batches_and_retries() {
+        let batches = Arc::new(Mutex::new(Vec::new()));
+        let flaky = Box::new(Flaky {
+            failures: Mutex::new(2),
+            batches: batches.clone(),
+        });
+        let batched = Batched::new(flaky, 2, 2).with_base_delay(Duration::from_millis(1));
+        let vectors = batched.embed_batch(&["a", "bb", "ccc"]).await.unwrap();
+        assert_eq!(vectors, vec![vec![1.0], vec![2.0], vec![3.0]]);
+        assert_eq!(*batches.lock().unwrap(), vec![2, 1]);
+
+        let flaky = Box::new(Flaky {
+            failures: Mutex::new(3),
+            batches: Arc::default(),
+        });
+        let batched = Batched::new(flaky, 2, 2).with_base_delay(Duration::from_millis(1));
+        assert!(batched.embed("a").await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_remote_embeddings_refused_offline() {
+        set_offline_check(|| true);
+        let remote = OpenAiEmbeddingProvider::new(
+            "sk-test".to_string(),
+            "https://api.openai.com".to_string(),
+            "text-embedding-3-small".to_string(),
+            4,
+        );
+        let err = remote.embed("hello").await.unwrap_err();
+        assert!(err.to_string().starts_with("offline mode"), "{err:#}");
+
+        // A server on this machine may still be asked
+        let local = OllamaEmbeddingProvider::new(
+            "http://127.0.0.1:9".to_string(),
+            "nomic-embed-text".to_string(),
+            4,
+        );
+        let err = local.embed("hello").await.unwrap_err();
+        assert!(!err.to_string().starts_with("offline mode"), "{err:#}");
+    }
+
+    #[test]
+    fn test_parse_openai_data() {
+        let body = serde_json::json!({"data": [
+            {"index": 1, "embedding": [0.0, 1.0]},
+            {"index": 0, "embedding": [1.0, 0.0]},
+        ]});
+        let vectors = parse_openai_data(&body, 2, 2).unwrap();
+        assert_eq!(vectors, vec![vec![1.0, 0.0], vec![0.0, 1.0]]);
+        assert!(parse_openai_data(&body, 2, 3).is_err());
+        assert!(parse_openai_data(&body, 3, 2).is_err());
+    }
+}
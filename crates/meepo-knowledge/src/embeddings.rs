@@ -1,21 +1,26 @@
 //! Vector embedding generation and similarity search
 //!
-//! Uses fastembed-rs for local ONNX-based embedding generation.
-//! Stores vectors in a simple in-memory HNSW index backed by SQLite persistence.
+//! Embeddings come from an [`EmbeddingProvider`] (OpenAI, Ollama, a local
+//! GGUF model, or feature hashing; see [`crate::embedding_providers`]).
+//! Vectors live in an in-memory index backed by SQLite persistence, or in a
+//! sqlite-vec table of the knowledge database.
 
 use anyhow::{Context, Result};
+use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::sync::{Arc, Mutex};
-use tracing::{debug, info};
+use tracing::{debug, info, warn};
 
 /// Configuration for the embedding system
 #[derive(Debug, Clone)]
 pub struct EmbeddingConfig {
     /// Whether embeddings are enabled
     pub enabled: bool,
-    /// Embedding model name (fastembed model identifier)
+    /// Where embeddings come from
+    pub backend: EmbeddingBackend,
+    /// Embedding model name, as the backend knows it
     pub model_name: String,
     /// Number of dimensions in the embedding vectors
     pub dimensions: usize,
@@ -23,16 +28,67 @@ pub struct EmbeddingConfig {
     pub vector_weight: f32,
     /// Weight for BM25/keyword score in hybrid search (0.0 to 1.0)
     pub keyword_weight: f32,
+    /// Texts sent per request
+    pub batch_size: usize,
+    /// Retries of a failed request, with exponential backoff
+    pub max_retries: u32,
+    /// sqlite-vec extension to keep vectors in the knowledge database
+    /// with; in memory when `None`
+    pub sqlite_vec: Option<PathBuf>,
+}
+
+/// Embedding backends; see [`crate::embedding_providers::build`]
+#[derive(Clone, PartialEq)]
+pub enum EmbeddingBackend {
+    /// Feature hashing over content words: lexical only, no model needed
+    Hash,
+    /// OpenAI's `/v1/embeddings`, or any API compatible with it
+    OpenAi { api_key: String, base_url: String },
+    /// Ollama's `/api/embeddings`
+    Ollama { url: String },
+    /// A local GGUF model run with llama.cpp's `llama-embedding`
+    Local {
+        model_path: PathBuf,
+        command: String,
+    },
+}
+
+impl std::fmt::Debug for EmbeddingBackend {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Hash => f.write_str("Hash"),
+            Self::OpenAi { base_url, .. } => f
+                .debug_struct("OpenAi")
+                .field("api_key", &"***")
+                .field("base_url", base_url)
+                .finish(),
+            Self::Ollama { url } => f.debug_struct("Ollama").field("url", url).finish(),
+            Self::Local {
+                model_path,
+                command,
+            } => f
+                .debug_struct("Local")
+                .field("model_path", model_path)
+                .field("command", command)
+                .finish(),
+        }
+    }
 }
 
 impl Default for EmbeddingConfig {
     fn default() -> Self {
         Self {
             enabled: false,
-            model_name: "sentence-transformers/all-MiniLM-L6-v2".to_string(),
-            dimensions: 384,
+            backend: EmbeddingBackend::Ollama {
+                url: "http://localhost:11434".to_string(),
+            },
+            model_name: "nomic-embed-text".to_string(),
+            dimensions: 768,
             vector_weight: 0.5,
             keyword_weight: 0.5,
+            batch_size: 32,
+            max_retries: 3,
+            sqlite_vec: None,
         }
     }
 }
@@ -65,19 +121,28 @@ pub struct HybridSearchResult {
 
 /// Vector index for storing and searching embeddings.
 ///
-/// Uses a simple brute-force cosine similarity search. For production
-/// scale (>100k vectors), this should be replaced with an HNSW index
-/// (e.g., `usearch` or `hnsw_rs`).
+/// By default vectors are held in memory, loaded from the `embeddings`
+/// table at startup, and searched by brute-force cosine similarity. With
+/// [`VectorIndex::open_sqlite_vec`] they live in a `vec0` table of the
+/// knowledge database instead (see <https://github.com/asg017/sqlite-vec>):
+/// writes are stored as they happen, nothing is loaded at startup, and
+/// nearest-neighbour queries run inside SQLite.
 pub struct VectorIndex {
-    embeddings: Arc<Mutex<HashMap<String, Vec<f32>>>>,
+    store: VectorStore,
     dimensions: usize,
 }
 
+enum VectorStore {
+    Memory(Arc<Mutex<HashMap<String, Vec<f32>>>>),
+    /// Connection with the sqlite-vec extension loaded
+    SqliteVec(Arc<Mutex<rusqlite::Connection>>),
+}
+
 impl VectorIndex {
     /// Create a new vector index
     pub fn new(dimensions: usize) -> Self {
         Self {
-            embeddings: Arc::new(Mutex::new(HashMap::new())),
+            store: VectorStore::Memory(Arc::new(Mutex::new(HashMap::new()))),
             dimensions,
         }
     }
@@ -113,8 +178,8 @@ impl VectorIndex {
             })
             .context("Failed to query embeddings")?;
 
-        {
-            let mut embeddings = index.embeddings.lock().unwrap();
+        if let VectorStore::Memory(embeddings) = &index.store {
+            let mut embeddings = embeddings.lock().unwrap();
             let mut count = 0;
             for (entity_id, blob) in rows.flatten() {
                 if let Some(vector) = bytes_to_f32_vec(&blob)
@@ -130,6 +195,99 @@ impl VectorIndex {
         Ok(index)
     }
 
+    /// Keep vectors in a sqlite-vec `vec0` table of the database at
+    /// `db_path`, loading the extension from `extension_path` (e.g.
+    /// `vec0.so` / `vec0.dylib`). Vectors in the `embeddings` table are
+    /// copied over the first time.
+    pub fn open_sqlite_vec(
+        db_path: &Path,
+        dimensions: usize,
+        extension_path: &Path,
+    ) -> Result<Self> {
+        let conn = rusqlite::Connection::open(db_path)
+            .context("Failed to open database for vector index")?;
+        // Safety: the extension is a file the user configured, loaded into
+        // a connection of our own; loading is switched off again afterwards
+        unsafe {
+            conn.load_extension_enable()?;
+            let loaded = conn.load_extension(extension_path, None);
+            conn.load_extension_disable()?;
+            loaded.with_context(|| {
+                format!(
+                    "Failed to load sqlite-vec extension from {}",
+                    extension_path.display()
+                )
+            })?;
+        }
+
+        // vec0 rows are keyed by integer rowid; entity IDs map onto them
+        conn.execute_batch(&format!(
+            "CREATE TABLE IF NOT EXISTS vec_embedding_ids (
+                 rowid INTEGER PRIMARY KEY,
+                 entity_id TEXT NOT NULL UNIQUE
+             );
+             CREATE VIRTUAL TABLE IF NOT EXISTS vec_embeddings
+                 USING vec0(embedding float[{dimensions}] distance_metric=cosine);"
+        ))
+        .context("Failed to create sqlite-vec tables")?;
+        let schema: String = conn.query_row(
+            "SELECT sql FROM sqlite_master WHERE name = 'vec_embeddings'",
+            [],
+            |row| row.get(0),
+        )?;
+        if !schema.contains(&format!("float[{}]", dimensions)) {
+            anyhow::bail!(
+                "vec_embeddings was created for a different embedding size than {}; \
+                 drop the vec_embeddings and vec_embedding_ids tables to rebuild it",
+                dimensions
+            );
+        }
+
+        let index = Self {
+            store: VectorStore::SqliteVec(Arc::new(Mutex::new(conn))),
+            dimensions,
+        };
+        if index.is_empty() {
+            index.import_blob_table()?;
+        }
+        info!("Vector index in sqlite-vec ({} embeddings)", index.len());
+        Ok(index)
+    }
+
+    /// Copy vectors from the `embeddings` blob table, if there is one
+    fn import_blob_table(&self) -> Result<()> {
+        let VectorStore::SqliteVec(conn) = &self.store else {
+            return Ok(());
+        };
+        let rows: Vec<(String, Vec<u8>)> = {
+            let conn = conn.lock().unwrap();
+            let exists: bool = conn.query_row(
+                "SELECT EXISTS (SELECT 1 FROM sqlite_master WHERE type = 'table' AND name = 'embeddings')",
+                [],
+                |row| row.get(0),
+            )?;
+            if !exists {
+                return Ok(());
+            }
+            let mut stmt = conn.prepare("SELECT entity_id, vector FROM embeddings")?;
+            stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+                .collect::<Result<_, _>>()?
+        };
+        let mut imported = 0;
+        for (entity_id, blob) in rows {
+            if let Some(vector) = bytes_to_f32_vec(&blob)
+                && vector.len() == self.dimensions
+            {
+                self.insert(&entity_id, vector)?;
+                imported += 1;
+            }
+        }
+        if imported > 0 {
+            info!("Copied {} embeddings into sqlite-vec", imported);
+        }
+        Ok(())
+    }
+
     /// Store an embedding for an entity
     pub fn insert(&self, entity_id: &str, vector: Vec<f32>) -> Result<()> {
         if vector.len() != self.dimensions {
@@ -140,21 +298,95 @@ impl VectorIndex {
             );
         }
 
-        let mut embeddings = self.embeddings.lock().unwrap();
-        embeddings.insert(entity_id.to_string(), vector);
+        match &self.store {
+            VectorStore::Memory(embeddings) => {
+                embeddings
+                    .lock()
+                    .unwrap()
+                    .insert(entity_id.to_string(), vector);
+            }
+            VectorStore::SqliteVec(conn) => {
+                let conn = conn.lock().unwrap();
+                let tx = conn.unchecked_transaction()?;
+                tx.execute(
+                    "INSERT INTO vec_embedding_ids (entity_id) VALUES (?1)
+                     ON CONFLICT (entity_id) DO NOTHING",
+                    [entity_id],
+                )?;
+                let rowid: i64 = tx.query_row(
+                    "SELECT rowid FROM vec_embedding_ids WHERE entity_id = ?1",
+                    [entity_id],
+                    |row| row.get(0),
+                )?;
+                // vec0 tables don't support upserts
+                tx.execute("DELETE FROM vec_embeddings WHERE rowid = ?1", [rowid])?;
+                tx.execute(
+                    "INSERT INTO vec_embeddings (rowid, embedding) VALUES (?1, ?2)",
+                    rusqlite::params![rowid, f32_vec_to_bytes(&vector)],
+                )?;
+                tx.commit()?;
+            }
+        }
         debug!("Stored embedding for entity: {}", entity_id);
         Ok(())
     }
 
+    /// Whether an entity has an embedding
+    pub fn contains(&self, entity_id: &str) -> bool {
+        match &self.store {
+            VectorStore::Memory(embeddings) => embeddings.lock().unwrap().contains_key(entity_id),
+            VectorStore::SqliteVec(conn) => conn
+                .lock()
+                .unwrap()
+                .query_row(
+                    "SELECT 1 FROM vec_embedding_ids WHERE entity_id = ?1",
+                    [entity_id],
+                    |_| Ok(()),
+                )
+                .is_ok(),
+        }
+    }
+
     /// Remove an embedding
     pub fn remove(&self, entity_id: &str) {
-        let mut embeddings = self.embeddings.lock().unwrap();
-        embeddings.remove(entity_id);
+        match &self.store {
+            VectorStore::Memory(embeddings) => {
+                embeddings.lock().unwrap().remove(entity_id);
+            }
+            VectorStore::SqliteVec(conn) => {
+                let conn = conn.lock().unwrap();
+                let removed = conn
+                    .execute(
+                        "DELETE FROM vec_embeddings WHERE rowid =
+                             (SELECT rowid FROM vec_embedding_ids WHERE entity_id = ?1)",
+                        [entity_id],
+                    )
+                    .and_then(|_| {
+                        conn.execute(
+                            "DELETE FROM vec_embedding_ids WHERE entity_id = ?1",
+                            [entity_id],
+                        )
+                    });
+                if let Err(e) = removed {
+                    warn!("Failed to remove embedding for {}: {}", entity_id, e);
+                }
+            }
+        }
     }
 
     /// Search for the most similar vectors using cosine similarity
     pub fn search(&self, query_vector: &[f32], limit: usize) -> Vec<VectorSearchResult> {
-        let embeddings = self.embeddings.lock().unwrap();
+        let embeddings = match &self.store {
+            VectorStore::Memory(embeddings) => embeddings,
+            VectorStore::SqliteVec(conn) => {
+                return search_sqlite_vec(&conn.lock().unwrap(), query_vector, limit)
+                    .unwrap_or_else(|e| {
+                        warn!("sqlite-vec search failed: {}", e);
+                        Vec::new()
+                    });
+            }
+        };
+        let embeddings = embeddings.lock().unwrap();
 
         let mut results: Vec<VectorSearchResult> = embeddings
             .iter()
@@ -175,8 +407,12 @@ impl VectorIndex {
         results
     }
 
-    /// Persist all embeddings to SQLite
+    /// Persist all embeddings to SQLite. sqlite-vec indexes store each
+    /// write as it happens, so there's nothing to do for them.
     pub fn persist_to_db(&self, db_path: &Path) -> Result<()> {
+        let VectorStore::Memory(embeddings) = &self.store else {
+            return Ok(());
+        };
         let conn = rusqlite::Connection::open(db_path)
             .context("Failed to open database for persistence")?;
 
@@ -189,7 +425,7 @@ impl VectorIndex {
             [],
         )?;
 
-        let embeddings = self.embeddings.lock().unwrap();
+        let embeddings = embeddings.lock().unwrap();
 
         let tx = conn.unchecked_transaction()?;
         for (entity_id, vector) in embeddings.iter() {
@@ -207,17 +443,60 @@ impl VectorIndex {
 
     /// Number of stored embeddings
     pub fn len(&self) -> usize {
-        self.embeddings.lock().unwrap().len()
+        match &self.store {
+            VectorStore::Memory(embeddings) => embeddings.lock().unwrap().len(),
+            VectorStore::SqliteVec(conn) => conn
+                .lock()
+                .unwrap()
+                .query_row("SELECT COUNT(*) FROM vec_embedding_ids", [], |row| {
+                    row.get::<_, i64>(0)
+                })
+                .map_or(0, |n| n as usize),
+        }
     }
 
     /// Check if index is empty
     pub fn is_empty(&self) -> bool {
-        self.embeddings.lock().unwrap().is_empty()
+        self.len() == 0
     }
 }
 
+/// k-nearest-neighbour query against the `vec0` table
+fn search_sqlite_vec(
+    conn: &rusqlite::Connection,
+    query_vector: &[f32],
+    limit: usize,
+) -> Result<Vec<VectorSearchResult>> {
+    if limit == 0 {
+        return Ok(Vec::new());
+    }
+    let mut stmt = conn.prepare_cached(
+        "WITH knn AS (
+             SELECT rowid, distance FROM vec_embeddings
+             WHERE embedding MATCH ?1 AND k = ?2
+         )
+         SELECT ids.entity_id, knn.distance FROM knn
+         JOIN vec_embedding_ids ids ON ids.rowid = knn.rowid
+         ORDER BY knn.distance",
+    )?;
+    let results = stmt
+        .query_map(
+            rusqlite::params![f32_vec_to_bytes(query_vector), limit as i64],
+            |row| {
+                let distance: f64 = row.get(1)?;
+                Ok(VectorSearchResult {
+                    entity_id: row.get(0)?,
+                    // Cosine distance is 1 - similarity
+                    similarity: (1.0 - distance) as f32,
+                })
+            },
+        )?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(results)
+}
+
 /// Compute cosine similarity between two vectors
-fn cosine_similarity(a: &[f32], b: &[f32]) -> f32 {
+pub(crate) fn cosine_similarity(a: &[f32], b: &[f32]) -> f32 {
     if a.len() != b.len() || a.is_empty() {
         return 0.0;
     }
@@ -261,6 +540,21 @@ pub fn hybrid_search_rrf(
     vector_results: &[VectorSearchResult],
     k: f32, // RRF constant (typically 60.0)
     limit: usize,
+) -> Vec<HybridSearchResult> {
+    hybrid_search_rrf_boosted(keyword_results, vector_results, k, limit, |_| 1.0)
+}
+
+/// [`hybrid_search_rrf`] with each fused score multiplied by `boost(id)`
+/// before ranking, e.g. [`GraphRagConfig::ranking_boost`] for recency and
+/// source-type weighting.
+///
+/// [`GraphRagConfig::ranking_boost`]: crate::graph_rag::GraphRagConfig::ranking_boost
+pub fn hybrid_search_rrf_boosted(
+    keyword_results: &[String],
+    vector_results: &[VectorSearchResult],
+    k: f32,
+    limit: usize,
+    boost: impl Fn(&str) -> f32,
 ) -> Vec<HybridSearchResult> {
     let mut scores: HashMap<String, (f32, Option<usize>, Option<usize>)> = HashMap::new();
 
@@ -283,8 +577,8 @@ pub fn hybrid_search_rrf(
     let mut results: Vec<HybridSearchResult> = scores
         .into_iter()
         .map(|(id, (score, kw_rank, vec_rank))| HybridSearchResult {
+            score: score * boost(&id),
             entity_id: id,
-            score,
             keyword_rank: kw_rank,
             vector_rank: vec_rank,
         })
@@ -302,16 +596,20 @@ pub fn hybrid_search_rrf(
 
 /// Trait for generating embeddings from text.
 ///
-/// This abstraction allows swapping between local (fastembed) and
-/// API-based (OpenAI, Voyage) embedding providers.
-#[allow(async_fn_in_trait)]
+/// This abstraction allows swapping between local and API-based embedding
+/// providers; see [`crate::embedding_providers`] for the built-in ones.
+#[async_trait]
 pub trait EmbeddingProvider: Send + Sync {
     /// Generate an embedding vector for a single text
-    fn embed(&self, text: &str) -> Result<Vec<f32>>;
+    async fn embed(&self, text: &str) -> Result<Vec<f32>>;
 
     /// Generate embeddings for multiple texts (batch)
-    fn embed_batch(&self, texts: &[&str]) -> Result<Vec<Vec<f32>>> {
-        texts.iter().map(|t| self.embed(t)).collect()
+    async fn embed_batch(&self, texts: &[&str]) -> Result<Vec<Vec<f32>>> {
+        let mut vectors = Vec::with_capacity(texts.len());
+        for text in texts {
+            vectors.push(self.embed(text).await?);
+        }
+        Ok(vectors)
     }
 
     /// Dimensionality of the output vectors
@@ -330,8 +628,9 @@ impl NoOpEmbeddingProvider {
     }
 }
 
+#[async_trait]
 impl EmbeddingProvider for NoOpEmbeddingProvider {
-    fn embed(&self, _text: &str) -> Result<Vec<f32>> {
+    async fn embed(&self, _text: &str) -> Result<Vec<f32>> {
         Ok(vec![0.0; self.dims])
     }
 
@@ -340,6 +639,139 @@ impl EmbeddingProvider for NoOpEmbeddingProvider {
     }
 }
 
+/// Words too common to say anything about a topic
+const STOPWORDS: &[&str] = &[
+    "the",
+    "and",
+    "for",
+    "are",
+    "but",
+    "not",
+    "you",
+    "your",
+    "all",
+    "any",
+    "can",
+    "had",
+    "her",
+    "was",
+    "one",
+    "our",
+    "out",
+    "has",
+    "have",
+    "him",
+    "his",
+    "how",
+    "its",
+    "may",
+    "new",
+    "now",
+    "see",
+    "two",
+    "who",
+    "did",
+    "get",
+    "got",
+    "let",
+    "say",
+    "she",
+    "too",
+    "use",
+    "that",
+    "with",
+    "this",
+    "they",
+    "them",
+    "then",
+    "than",
+    "there",
+    "their",
+    "what",
+    "when",
+    "where",
+    "which",
+    "will",
+    "would",
+    "could",
+    "should",
+    "about",
+    "into",
+    "from",
+    "just",
+    "like",
+    "some",
+    "been",
+    "were",
+    "also",
+    "more",
+    "very",
+    "here",
+    "want",
+    "need",
+    "please",
+    "thanks",
+    "thank",
+    "know",
+    "make",
+    "does",
+    "doing",
+    "done",
+    "going",
+    "today",
+    "tomorrow",
+    "yesterday",
+    "okay",
+];
+
+/// Lowercased content words (3+ characters, stopwords removed) in order
+pub fn content_words(text: &str) -> Vec<String> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|w| w.chars().count() >= 3 && !w.chars().all(|c| c.is_ascii_digit()))
+        .map(|w| w.to_lowercase())
+        .filter(|w| !STOPWORDS.contains(&w.as_str()))
+        .collect()
+}
+
+/// Local embedding provider using feature hashing over content words.
+///
+/// Captures lexical overlap only, but needs no model download and is fully
+/// deterministic, which is enough to group texts that talk about the same
+/// things until a neural model is integrated.
+pub struct HashEmbeddingProvider {
+    dims: usize,
+}
+
+impl HashEmbeddingProvider {
+    pub fn new(dims: usize) -> Self {
+        Self { dims: dims.max(1) }
+    }
+}
+
+#[async_trait]
+impl EmbeddingProvider for HashEmbeddingProvider {
+    async fn embed(&self, text: &str) -> Result<Vec<f32>> {
+        let mut vector = vec![0.0f32; self.dims];
+        for word in content_words(text) {
+            // FNV-1a; the top bit picks the sign to reduce collision bias
+            let hash = word.bytes().fold(0xcbf29ce484222325u64, |h, b| {
+                (h ^ b as u64).wrapping_mul(0x100000001b3)
+            });
+            let sign = if hash >> 63 == 0 { 1.0 } else { -1.0 };
+            vector[(hash % self.dims as u64) as usize] += sign;
+        }
+        let norm = vector.iter().map(|x| x * x).sum::<f32>().sqrt();
+        if norm > 0.0 {
+            vector.iter_mut().for_each(|x| *x /= norm);
+        }
+        Ok(vector)
+    }
+
+    fn dimensions(&self) -> usize {
+        self.dims
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -430,10 +862,10 @@ mod tests {
         assert!(a_score > c_score);
     }
 
-    #[test]
-    fn test_noop_provider() {
+    #[tokio::test]
+    async fn test_noop_provider() {
         let provider = NoOpEmbeddingProvider::new(384);
-        let vec = provider.embed("test").unwrap();
+        let vec = provider.embed("test").await.unwrap();
         assert_eq!(vec.len(), 384);
         assert!(vec.iter().all(|&v| v == 0.0));
     }
@@ -444,10 +876,10 @@ mod tests {
         assert_eq!(provider.dimensions(), 128);
     }
 
-    #[test]
-    fn test_noop_provider_embed_batch() {
+    #[tokio::test]
+    async fn test_noop_provider_embed_batch() {
         let provider = NoOpEmbeddingProvider::new(3);
-        let results = provider.embed_batch(&["hello", "world"]).unwrap();
+        let results = provider.embed_batch(&["hello", "world"]).await.unwrap();
         assert_eq!(results.len(), 2);
         assert_eq!(results[0].len(), 3);
         assert_eq!(results[1].len(), 3);
@@ -508,6 +940,22 @@ mod tests {
         assert!(index.is_empty());
     }
 
+    #[test]
+    fn test_sqlite_vec_missing_extension() {
+        let dir = tempfile::tempdir().unwrap();
+        let db_path = dir.path().join("vec.db");
+
+        let err = VectorIndex::open_sqlite_vec(&db_path, 3, &dir.path().join("vec0"))
+            .err()
+            .unwrap();
+        assert!(
+            err.to_string()
+                .contains("Failed to load sqlite-vec extension"),
+            "{}",
+            err
+        );
+    }
+
     #[test]
     fn test_cosine_similarity_empty() {
         assert_eq!(cosine_similarity(&[], &[]), 0.0);
@@ -577,14 +1025,25 @@ mod tests {
         assert_eq!(results.len(), 5);
     }
 
+    #[test]
+    fn test_hybrid_search_rrf_boosted() {
+        let keyword: Vec<String> = vec!["old_note".into(), "fresh_email".into()];
+        let results = hybrid_search_rrf_boosted(&keyword, &[], 60.0, 10, |id| {
+            if id == "fresh_email" { 1.5 } else { 1.0 }
+        });
+        assert_eq!(results[0].entity_id, "fresh_email");
+        assert_eq!(results[0].keyword_rank, Some(2));
+    }
+
     #[test]
     fn test_embedding_config_default() {
         let config = EmbeddingConfig::default();
         assert!(!config.enabled);
-        assert_eq!(config.dimensions, 384);
+        assert_eq!(config.dimensions, 768);
         assert_eq!(config.vector_weight, 0.5);
         assert_eq!(config.keyword_weight, 0.5);
-        assert!(config.model_name.contains("MiniLM"));
+        assert_eq!(config.model_name, "nomic-embed-text");
+        assert!(matches!(config.backend, EmbeddingBackend::Ollama { .. }));
     }
 
     #[test]
@@ -599,4 +1058,28 @@ mod tests {
         assert_eq!(results[0].entity_id, "a");
         assert!((results[0].similarity - 1.0).abs() < 1e-6);
     }
+
+    #[tokio::test]
+    async fn test_hash_embeddings_group_related_text() {
+        let provider = HashEmbeddingProvider::new(256);
+        let a = provider
+            .embed("Kitchen renovation: cabinets and countertop quotes")
+            .await
+            .unwrap();
+        let b = provider
+            .embed("The countertop for the kitchen renovation is delayed")
+            .await
+            .unwrap();
+        let c = provider
+            .embed("Quarterly tax filing deadline")
+            .await
+            .unwrap();
+        assert_eq!(a.len(), 256);
+        assert!(cosine_similarity(&a, &b) > cosine_similarity(&a, &c));
+        assert_eq!(provider.embed("the and for").await.unwrap(), vec![0.0; 256]);
+        assert_eq!(
+            content_words("The NEW kitchen, 2024!"),
+            vec!["kitchen".to_string()]
+        );
+    }
 }
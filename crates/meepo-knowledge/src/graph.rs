@@ -3,12 +3,18 @@
 use anyhow::{Context, Result};
 use serde::{Deserialize, Serialize};
 use serde_json::Value as JsonValue;
-use std::path::Path;
-use std::sync::Arc;
-use tracing::{debug, info};
-
-use crate::sqlite::{Entity, KnowledgeDb, Relationship};
-use crate::tantivy::{SearchResult, TantivyIndex};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, RwLock};
+use tracing::{debug, info, warn};
+
+use crate::embeddings::{EmbeddingProvider, VectorIndex, hybrid_search_rrf};
+use crate::sqlite::{
+    DocumentRevision, Entity, ForgetReport, KnowledgeDb, NewEntity, NewRelationship, Relationship,
+    RememberedBatch, ReplicatedChange, ReplicationReport,
+};
+use crate::synonyms::SynonymMap;
+use crate::tantivy::{IndexConfig, SearchResult, TantivyIndex};
 
 /// Context for an entity including relationships and conversations
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -19,15 +25,54 @@ pub struct EntityContext {
     pub recent_conversations: Vec<crate::sqlite::Conversation>,
 }
 
+/// Whether searches are answered by the full-text index or, when it is
+/// unavailable, by the SQLite fallback
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SearchHealth {
+    /// The full-text index is open and its last operation succeeded
+    pub full_text: bool,
+    /// Why the full-text index was last unavailable
+    pub last_error: Option<String>,
+    /// Searches answered by the SQLite fallback since startup
+    pub fallback_searches: u64,
+}
+
+/// RRF constant for fusing keyword and vector rankings
+const RRF_K: f32 = 60.0;
+
+/// Entity embeddings for semantic search, persisted beside the database
+struct SemanticIndex {
+    provider: Arc<dyn EmbeddingProvider>,
+    vectors: VectorIndex,
+    db_path: PathBuf,
+}
+
 /// Knowledge graph combining SQLite and Tantivy
 pub struct KnowledgeGraph {
     db: Arc<KnowledgeDb>,
-    index: TantivyIndex,
+    /// `None` when the index couldn't be opened; search falls back to SQLite
+    index: Option<TantivyIndex>,
+    health: Mutex<SearchHealth>,
+    /// Match query words within a small edit distance
+    fuzzy: bool,
+    /// Configured synonym groups, before entity aliases are added
+    synonym_groups: Vec<Vec<String>>,
+    synonyms: RwLock<SynonymMap>,
+    semantic: Option<SemanticIndex>,
 }
 
 impl KnowledgeGraph {
     /// Create a new knowledge graph
     pub fn new<P: AsRef<Path>, Q: AsRef<Path>>(db_path: P, index_path: Q) -> Result<Self> {
+        Self::with_index_config(db_path, index_path, IndexConfig::default())
+    }
+
+    /// Create a new knowledge graph with custom index commit and merge settings
+    pub fn with_index_config<P: AsRef<Path>, Q: AsRef<Path>>(
+        db_path: P,
+        index_path: Q,
+        index_config: IndexConfig,
+    ) -> Result<Self> {
         info!(
             "Initializing knowledge graph with db at {:?} and index at {:?}",
             db_path.as_ref(),
@@ -35,9 +80,173 @@ impl KnowledgeGraph {
         );
 
         let db = Arc::new(KnowledgeDb::new(db_path)?);
-        let index = TantivyIndex::new(index_path)?;
+        // SQLite is the source of truth; without the index (e.g. on a
+        // disconnected volume) search degrades instead of failing
+        let mut health = SearchHealth {
+            full_text: true,
+            ..Default::default()
+        };
+        let index = match TantivyIndex::with_config(index_path.as_ref(), index_config) {
+            Ok(index) => Some(index),
+            Err(e) => {
+                warn!(
+                    "Search index at {:?} is unavailable, falling back to SQLite search: {:#}",
+                    index_path.as_ref(),
+                    e
+                );
+                health.full_text = false;
+                health.last_error = Some(format!("{:#}", e));
+                None
+            }
+        };
+
+        Ok(Self {
+            db,
+            index,
+            health: Mutex::new(health),
+            fuzzy: true,
+            synonym_groups: Vec::new(),
+            synonyms: RwLock::new(SynonymMap::new()),
+            semantic: None,
+        })
+    }
+
+    /// Turn typo-tolerant matching on or off (on by default)
+    pub fn with_fuzzy(mut self, fuzzy: bool) -> Self {
+        self.fuzzy = fuzzy;
+        self
+    }
+
+    /// Groups of interchangeable search terms, e.g. `["nyc", "new york"]`.
+    /// Entity aliases are added by [`Self::refresh_synonyms`].
+    pub fn with_synonyms(mut self, groups: Vec<Vec<String>>) -> Self {
+        *self.synonyms.get_mut().unwrap_or_else(|p| p.into_inner()) =
+            SynonymMap::from_groups(&groups);
+        self.synonym_groups = groups;
+        self
+    }
+
+    /// Rank search results by vector similarity as well as keywords, with
+    /// embeddings from `provider` stored in the `embeddings` table of the
+    /// database at `db_path`. Entities are embedded by [`Self::embed_missing`].
+    pub fn with_embeddings(
+        self,
+        provider: Arc<dyn EmbeddingProvider>,
+        db_path: impl AsRef<Path>,
+    ) -> Result<Self> {
+        let vectors = VectorIndex::load_from_db(db_path.as_ref(), provider.dimensions())?;
+        Ok(self.with_vector_index(provider, vectors, db_path))
+    }
+
+    /// [`Self::with_embeddings`] with an index opened by the caller, e.g.
+    /// one kept in sqlite-vec ([`VectorIndex::open_sqlite_vec`])
+    pub fn with_vector_index(
+        mut self,
+        provider: Arc<dyn EmbeddingProvider>,
+        vectors: VectorIndex,
+        db_path: impl AsRef<Path>,
+    ) -> Self {
+        self.semantic = Some(SemanticIndex {
+            provider,
+            vectors,
+            db_path: db_path.as_ref().to_path_buf(),
+        });
+        self
+    }
 
-        Ok(Self { db, index })
+    /// Whether an embedding provider is attached
+    pub fn has_embeddings(&self) -> bool {
+        self.semantic.is_some()
+    }
+
+    /// Embed up to `limit` entities that have no embedding yet and persist
+    /// them. Returns how many were embedded.
+    pub async fn embed_missing(&self, limit: usize) -> Result<usize> {
+        let Some(semantic) = &self.semantic else {
+            return Ok(0);
+        };
+        let missing: Vec<Entity> = self
+            .db
+            .get_all_entities()
+            .await?
+            .into_iter()
+            .filter(|e| !semantic.vectors.contains(&e.id))
+            .take(limit)
+            .collect();
+        if missing.is_empty() {
+            return Ok(0);
+        }
+        let texts: Vec<String> = missing
+            .iter()
+            .map(|e| crate::tagging::entity_text(&e.name, &e.entity_type, e.metadata.as_ref()))
+            .collect();
+        let texts: Vec<&str> = texts.iter().map(String::as_str).collect();
+        let vectors = semantic.provider.embed_batch(&texts).await?;
+        for (entity, vector) in missing.iter().zip(vectors) {
+            semantic.vectors.insert(&entity.id, vector)?;
+        }
+        semantic.vectors.persist_to_db(&semantic.db_path)?;
+        info!("Embedded {} entities", missing.len());
+        Ok(missing.len())
+    }
+
+    /// Rebuild the synonym map from configured groups and the `aliases`
+    /// metadata of every stored entity
+    pub async fn refresh_synonyms(&self) -> Result<usize> {
+        let mut map = SynonymMap::from_groups(&self.synonym_groups);
+        for entity in self.db.get_all_entities().await? {
+            map.add_entity(&entity);
+        }
+        let groups = map.len();
+        *self.synonyms.write().unwrap_or_else(|p| p.into_inner()) = map;
+        debug!("Loaded {} synonym groups", groups);
+        Ok(groups)
+    }
+
+    fn learn_aliases(&self, entities: &[Entity]) {
+        let mut synonyms = self.synonyms.write().unwrap_or_else(|p| p.into_inner());
+        for entity in entities {
+            synonyms.add_entity(entity);
+        }
+    }
+
+    /// Commit buffered search index writes now
+    pub fn flush(&self) -> Result<()> {
+        match &self.index {
+            Some(index) => index.flush(),
+            None => Ok(()),
+        }
+    }
+
+    /// Whether search is using the full-text index or the SQLite fallback
+    pub fn search_health(&self) -> SearchHealth {
+        self.health
+            .lock()
+            .unwrap_or_else(|p| p.into_inner())
+            .clone()
+    }
+
+    /// Update the health flags from an index operation; true if it worked
+    fn record_index_result<T>(&self, what: &str, result: &Result<T>) -> bool {
+        let mut health = self.health.lock().unwrap_or_else(|p| p.into_inner());
+        match result {
+            Ok(_) => health.full_text = true,
+            Err(e) => {
+                warn!("Search index {} failed: {:#}", what, e);
+                health.full_text = false;
+                health.last_error = Some(format!("{} failed: {:#}", what, e));
+            }
+        }
+        health.full_text
+    }
+
+    /// Apply a write to the search index. Failures are logged and recorded
+    /// rather than returned: the write already reached SQLite, searches fall
+    /// back to it, and [`Self::reindex`] rebuilds the index later.
+    fn index_write(&self, what: &str, write: impl FnOnce(&TantivyIndex) -> Result<()>) {
+        if let Some(index) = &self.index {
+            self.record_index_result(what, &write(index));
+        }
     }
 
     /// Add an entity to the knowledge graph
@@ -63,8 +272,19 @@ impl KnowledgeGraph {
             metadata.as_ref().map(|m| m.to_string()).unwrap_or_default()
         );
 
-        self.index
-            .index_document(&id, &content, entity_type, &chrono::Utc::now().to_rfc3339())?;
+        let now = chrono::Utc::now();
+        self.index_write("write", |index| {
+            index.index_document(&id, &content, entity_type, &now.to_rfc3339())
+        });
+        self.learn_aliases(&[Entity {
+            id: id.clone(),
+            name: name.to_string(),
+            entity_type: entity_type.to_string(),
+            metadata,
+            aliases: Vec::new(),
+            created_at: now,
+            updated_at: now,
+        }]);
 
         info!("Added entity: {} with ID {}", name, id);
         Ok(id)
@@ -102,10 +322,158 @@ impl KnowledgeGraph {
         Ok(id)
     }
 
-    /// Search the knowledge graph
+    /// Add several entities in one transaction and one index commit
+    pub async fn add_entities(&self, entities: Vec<NewEntity>) -> Result<Vec<String>> {
+        let batch = self.remember_many(entities, Vec::new()).await?;
+        Ok(batch.entities.into_iter().map(|e| e.id).collect())
+    }
+
+    /// Add several relationships in one transaction; all endpoints must exist
+    pub async fn add_relationships(
+        &self,
+        relationships: Vec<NewRelationship>,
+    ) -> Result<Vec<String>> {
+        self.db.add_relationships(relationships).await
+    }
+
+    /// Add entities and the relationships between them in one transaction
+    /// and one index commit (see [`KnowledgeDb::remember_many`])
+    pub async fn remember_many(
+        &self,
+        entities: Vec<NewEntity>,
+        relationships: Vec<NewRelationship>,
+    ) -> Result<RememberedBatch> {
+        let batch = self.db.remember_many(entities, relationships).await?;
+        if !batch.entities.is_empty() {
+            self.index_write("write", |index| index.index_entities(&batch.entities));
+            self.learn_aliases(&batch.entities);
+        }
+        info!(
+            "Remembered {} entities and {} relationships",
+            batch.entities.len(),
+            batch.relationships.len()
+        );
+        Ok(batch)
+    }
+
+    /// Rewrite an ingested document's chunks, removing superseded chunks
+    /// from the database and search index together
+    pub async fn revise_document(&self, revision: DocumentRevision) -> Result<RememberedBatch> {
+        let remove = revision.remove.clone();
+        let batch = self.db.revise_document(revision).await?;
+        self.index_write("write", |index| {
+            index.replace_entities(&remove, &batch.entities)
+        });
+        Ok(batch)
+    }
+
+    /// Give an entity another name, searchable right away
+    pub async fn add_alias(&self, entity_id: &str, alias: &str) -> Result<bool> {
+        let added = self.db.add_alias(entity_id, alias).await?;
+        if added {
+            self.reindex_entity(entity_id).await?;
+        }
+        Ok(added)
+    }
+
+    /// Remove one of an entity's aliases
+    pub async fn remove_alias(&self, entity_id: &str, alias: &str) -> Result<bool> {
+        let removed = self.db.remove_alias(entity_id, alias).await?;
+        if removed {
+            self.reindex_entity(entity_id).await?;
+            self.refresh_synonyms().await?;
+        }
+        Ok(removed)
+    }
+
+    async fn reindex_entity(&self, entity_id: &str) -> Result<()> {
+        let entity = self
+            .db
+            .get_entity(entity_id)
+            .await?
+            .context("Entity not found")?;
+        self.index_write("write", |index| {
+            index.index_entities(std::slice::from_ref(&entity))
+        });
+        self.learn_aliases(&[entity]);
+        Ok(())
+    }
+
+    /// Search the knowledge graph, falling back to a SQLite LIKE search
+    /// when the full-text index is unavailable or fails
     pub fn search(&self, query: &str, limit: usize) -> Result<Vec<SearchResult>> {
         debug!("Searching knowledge graph for: {}", query);
-        self.index.search(query, limit)
+        if let Some(index) = &self.index {
+            let synonyms = self.synonyms.read().unwrap_or_else(|p| p.into_inner());
+            let results = index.search_expanded(query, limit, self.fuzzy, &synonyms);
+            if self.record_index_result("search", &results) {
+                return results;
+            }
+        }
+        self.fallback_search(query, limit)
+    }
+
+    /// [`Self::search`] fused with vector similarity by Reciprocal Rank
+    /// Fusion when embeddings are set up. Scores are scaled to 0.0–1.0. If
+    /// the query can't be embedded the keyword results stand alone.
+    pub async fn hybrid_search(&self, query: &str, limit: usize) -> Result<Vec<SearchResult>> {
+        let Some(semantic) = &self.semantic else {
+            return self.search(query, limit);
+        };
+        let candidates = limit * 2;
+        let keyword = self.search(query, candidates)?;
+        let query_vector = match semantic.provider.embed(query).await {
+            Ok(vector) => vector,
+            Err(e) => {
+                warn!(
+                    "Couldn't embed the query, using keyword search only: {:#}",
+                    e
+                );
+                return Ok(keyword.into_iter().take(limit).collect());
+            }
+        };
+        let similar: Vec<_> = semantic
+            .vectors
+            .search(&query_vector, candidates)
+            .into_iter()
+            .filter(|r| r.similarity > 0.0)
+            .collect();
+
+        let ids: Vec<String> = keyword.iter().map(|r| r.id.clone()).collect();
+        let mut by_id: HashMap<String, SearchResult> =
+            keyword.into_iter().map(|r| (r.id.clone(), r)).collect();
+        let best = 2.0 / (RRF_K + 1.0);
+        let mut results = Vec::new();
+        for hit in hybrid_search_rrf(&ids, &similar, RRF_K, limit) {
+            let result = match by_id.remove(&hit.entity_id) {
+                Some(result) => result,
+                // Found by meaning alone; stale vectors of deleted entities drop out
+                None => match self.db.get_entity(&hit.entity_id).await? {
+                    Some(entity) => entity_result(entity, 0.0),
+                    None => continue,
+                },
+            };
+            results.push(SearchResult {
+                score: hit.score / best,
+                ..result
+            });
+        }
+        Ok(results)
+    }
+
+    fn fallback_search(&self, query: &str, limit: usize) -> Result<Vec<SearchResult>> {
+        self.health
+            .lock()
+            .unwrap_or_else(|p| p.into_inner())
+            .fallback_searches += 1;
+        let results = self
+            .db
+            .search_entities_blocking(query, limit)
+            .context("Fallback search failed")?;
+        Ok(results
+            .into_iter()
+            .map(|(entity, score)| entity_result(entity, score))
+            .collect())
     }
 
     /// Get full context for an entity
@@ -275,11 +643,86 @@ impl KnowledgeGraph {
         self.db.delete_watcher(id).await
     }
 
+    /// Delete an entity (and its relationships) from the graph
+    pub async fn delete_entity(&self, id: &str) -> Result<bool> {
+        let deleted = self.db.delete_entity(id).await?;
+        self.index_write("delete", |index| index.delete_document(id));
+        Ok(deleted)
+    }
+
+    /// Entities a forget request covers: the one an ID, exact name or alias
+    /// names, else the best search matches for the query
+    pub async fn forget_scope(&self, target: &str, limit: usize) -> Result<Vec<Entity>> {
+        if let Some(entity) = self.db.resolve_entity(target).await? {
+            return Ok(vec![entity]);
+        }
+        let mut entities = Vec::new();
+        for result in self.search(target, limit)? {
+            if let Some(entity) = self.db.get_entity(&result.id).await? {
+                entities.push(entity);
+            }
+        }
+        Ok(entities)
+    }
+
+    /// Forget an entity by ID, or everything a query matches (see
+    /// [`Self::forget_scope`] to check what that is first)
+    pub async fn forget(&self, target: &str, limit: usize) -> Result<ForgetReport> {
+        let ids: Vec<String> = self
+            .forget_scope(target, limit)
+            .await?
+            .into_iter()
+            .map(|e| e.id)
+            .collect();
+        self.forget_entities(&ids, target).await
+    }
+
+    /// Forget entities with their relationships, document chunks and
+    /// embeddings (see [`KnowledgeDb::forget_entities`]), and drop them from
+    /// the search index and synonyms
+    pub async fn forget_entities(&self, ids: &[String], reason: &str) -> Result<ForgetReport> {
+        let report = self.db.forget_entities(ids, reason).await?;
+        if report.entities.is_empty() {
+            return Ok(report);
+        }
+        let removed: Vec<String> = report.entities.iter().map(|e| e.id.clone()).collect();
+        self.index_write("delete", |index| index.replace_entities(&removed, &[]));
+        if let Some(semantic) = &self.semantic {
+            removed.iter().for_each(|id| semantic.vectors.remove(id));
+        }
+        if report.entities.iter().any(|e| !e.aliases.is_empty()) {
+            self.refresh_synonyms().await?;
+        }
+        Ok(report)
+    }
+
+    /// Apply changes replicated from another instance, keeping the search
+    /// index in step so they can be recalled
+    pub async fn apply_changes(&self, changes: Vec<ReplicatedChange>) -> Result<ReplicationReport> {
+        let report = self.db.apply_changes(changes).await?;
+        if !report.entities.is_empty() {
+            self.index_write("write", |index| index.index_entities(&report.entities));
+        }
+        for id in &report.removed {
+            self.index_write("delete", |index| index.delete_document(id));
+        }
+        Ok(report)
+    }
+
     /// Reindex all entities in Tantivy
     pub async fn reindex(&self) -> Result<()> {
         info!("Reindexing all entities");
+        let Some(index) = &self.index else {
+            anyhow::bail!("Search index is unavailable; restart once its directory is reachable");
+        };
         let entities = self.db.get_all_entities().await?;
-        self.index.reindex_all_from_entities(&entities)
+        let result = index.reindex_all_from_entities(&entities);
+        self.record_index_result("reindex", &result);
+        result?;
+        if let Err(e) = self.refresh_synonyms().await {
+            warn!("Failed to reload synonyms: {}", e);
+        }
+        Ok(())
     }
 
     /// Get all entities
@@ -301,6 +744,26 @@ impl KnowledgeGraph {
     }
 }
 
+/// A search result for an entity found without the full-text index
+fn entity_result(entity: Entity, score: f32) -> SearchResult {
+    SearchResult {
+        content: format!(
+            "{} {} {}",
+            entity.name,
+            entity.entity_type,
+            entity
+                .metadata
+                .as_ref()
+                .map(|m| m.to_string())
+                .unwrap_or_default()
+        ),
+        id: entity.id,
+        entity_type: entity.entity_type,
+        score,
+        snippet: None,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -370,6 +833,217 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn test_forget_cascades_and_leaves_tombstone() -> Result<()> {
+        let temp = tempfile::TempDir::new()?;
+        let db_path = temp.path().join("test.db");
+        let graph = KnowledgeGraph::new(&db_path, temp.path().join("index"))?;
+        let batch = graph
+            .remember_many(
+                vec![
+                    NewEntity {
+                        name: "Salary review".into(),
+                        entity_type: "document".into(),
+                        metadata: None,
+                    },
+                    NewEntity {
+                        name: "Salary review [chunk 1/1]".into(),
+                        entity_type: "document_chunk".into(),
+                        metadata: Some(serde_json::json!({"full_content": "Raise to 120k"})),
+                    },
+                    NewEntity {
+                        name: "Dana".into(),
+                        entity_type: "person".into(),
+                        metadata: None,
+                    },
+                ],
+                vec![
+                    NewRelationship {
+                        source: "Salary review".into(),
+                        target: "Salary review [chunk 1/1]".into(),
+                        relation_type: "contains_chunk".into(),
+                        metadata: None,
+                    },
+                    NewRelationship {
+                        source: "Salary review".into(),
+                        target: "Dana".into(),
+                        relation_type: "mentions".into(),
+                        metadata: None,
+                    },
+                ],
+            )
+            .await?;
+        let (doc, chunk, dana) = (
+            &batch.entities[0].id,
+            &batch.entities[1].id,
+            &batch.entities[2].id,
+        );
+        let vectors = crate::embeddings::VectorIndex::new(2);
+        vectors.insert(chunk, vec![1.0, 0.0])?;
+        vectors.insert(dana, vec![0.0, 1.0])?;
+        vectors.persist_to_db(&db_path)?;
+
+        let scope = graph.forget_scope("salary review", 10).await?;
+        assert_eq!(scope.len(), 1);
+        assert_eq!(&scope[0].id, doc);
+
+        let report = graph.forget("salary review", 10).await?;
+        assert_eq!(report.entities.len(), 2);
+        assert_eq!(report.relationships, 2);
+        assert_eq!(report.embeddings, 1);
+        assert!(graph.get_entity(chunk).await?.is_none());
+        assert!(graph.search("raise", 10)?.is_empty());
+        assert!(graph.get_relationships(dana).await?.is_empty());
+        assert!(graph.db().is_forgotten("SALARY REVIEW").await?);
+        assert!(!graph.db().is_forgotten("Salary review [chunk 1/1]").await?);
+        assert!(!graph.db().is_forgotten("Dana").await?);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_search_tolerates_typos_and_synonyms() -> Result<()> {
+        let temp = tempfile::TempDir::new()?;
+        let graph = KnowledgeGraph::new(temp.path().join("test.db"), temp.path().join("index"))?
+            .with_synonyms(vec![vec!["car".into(), "automobile".into()]]);
+        let margaret = graph
+            .add_entity("Margaret Hamilton", "person", None)
+            .await?;
+        let margret = graph.add_entity("Margret Schmidt", "person", None).await?;
+        let robert = graph
+            .add_entity(
+                "Robert Smith",
+                "person",
+                Some(serde_json::json!({"aliases": ["Bobby"]})),
+            )
+            .await?;
+        let car = graph
+            .add_entity("Automobile insurance", "document", None)
+            .await?;
+
+        // A typo still finds the name
+        assert_eq!(graph.search("Hamiltn", 10)?[0].id, margaret);
+        // Exact matches outrank near ones
+        let results = graph.search("Margret", 10)?;
+        assert_eq!(results[0].id, margret);
+        assert_eq!(results[1].id, margaret);
+        assert_eq!(graph.search("Margaret", 10)?[0].id, margaret);
+        // Entity aliases and configured synonyms
+        let lunch = graph
+            .add_entity("Lunch with Robert Smith", "event", None)
+            .await?;
+        let results = graph.search("bobby", 10)?;
+        assert_eq!(results[0].id, robert);
+        assert!(results.iter().any(|r| r.id == lunch));
+        assert_eq!(graph.search("car", 10)?[0].id, car);
+
+        // Aliases are reloaded from stored entities
+        let reopened = KnowledgeGraph::new(temp.path().join("test.db"), temp.path().join("index"))?;
+        let finds_lunch = |graph: &KnowledgeGraph| -> Result<bool> {
+            Ok(graph.search("bobby", 10)?.iter().any(|r| r.id == lunch))
+        };
+        assert!(!finds_lunch(&reopened)?);
+        assert_eq!(reopened.refresh_synonyms().await?, 1);
+        assert!(finds_lunch(&reopened)?);
+        Ok(())
+    }
+
+    /// Puts anything about cars on one axis and everything else on the other
+    struct CarEmbedder;
+
+    #[async_trait::async_trait]
+    impl EmbeddingProvider for CarEmbedder {
+        async fn embed(&self, text: &str) -> Result<Vec<f32>> {
+            let text = text.to_lowercase();
+            Ok(if text.contains("toyota") || text.contains("vehicle") {
+                vec![1.0, 0.0]
+            } else {
+                vec![0.0, 1.0]
+            })
+        }
+
+        fn dimensions(&self) -> usize {
+            2
+        }
+    }
+
+    #[tokio::test]
+    async fn test_hybrid_search_finds_by_meaning() -> Result<()> {
+        let temp = tempfile::TempDir::new()?;
+        let db_path = temp.path().join("test.db");
+        let graph = KnowledgeGraph::new(&db_path, temp.path().join("index"))?
+            .with_embeddings(Arc::new(CarEmbedder), &db_path)?;
+        let toyota = graph
+            .add_entity("Toyota lease renewal", "task", None)
+            .await?;
+        graph
+            .add_entity("Dentist appointment", "event", None)
+            .await?;
+        assert!(graph.search("vehicle", 10)?.is_empty());
+
+        assert_eq!(graph.embed_missing(10).await?, 2);
+        assert_eq!(graph.embed_missing(10).await?, 0);
+        let results = graph.hybrid_search("vehicle", 1).await?;
+        assert_eq!(results[0].id, toyota);
+
+        // Embeddings persist, and forgotten entities leave the index
+        let reopened = KnowledgeGraph::new(&db_path, temp.path().join("index"))?
+            .with_embeddings(Arc::new(CarEmbedder), &db_path)?;
+        assert_eq!(reopened.embed_missing(10).await?, 0);
+        reopened.forget_entities(&[toyota], "test").await?;
+        assert!(
+            reopened
+                .hybrid_search("vehicle", 5)
+                .await?
+                .iter()
+                .all(|r| r.entity_type != "task")
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_remember_many() -> Result<()> {
+        let temp = tempfile::TempDir::new()?;
+        let graph = KnowledgeGraph::new(temp.path().join("test.db"), temp.path().join("index"))?;
+        let existing = graph.add_entity("Acme Corp", "company", None).await?;
+
+        let entity = |name: &str, entity_type: &str| NewEntity {
+            name: name.into(),
+            entity_type: entity_type.into(),
+            metadata: None,
+        };
+        let link = |source: &str, target: &str, relation_type: &str| NewRelationship {
+            source: source.into(),
+            target: target.into(),
+            relation_type: relation_type.into(),
+            metadata: None,
+        };
+
+        let batch = graph
+            .remember_many(
+                vec![entity("Dana", "person"), entity("Q3 launch", "project")],
+                vec![
+                    link("dana", "Q3 launch", "owns"),
+                    link("Dana", &existing, "works_at"),
+                ],
+            )
+            .await?;
+        assert_eq!(batch.entities.len(), 2);
+        assert_eq!(batch.relationships[1].target_id, existing);
+        assert_eq!(graph.search("launch", 10)?[0].id, batch.entities[1].id);
+
+        // An unresolved endpoint rolls back the whole batch
+        let err = graph
+            .remember_many(
+                vec![entity("Sam", "person")],
+                vec![link("Sam", "nobody", "knows")],
+            )
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("nobody"));
+        assert_eq!(graph.get_all_entities().await?.len(), 3);
+        Ok(())
+    }
+
     #[tokio::test]
     async fn test_remember_and_recall() -> Result<()> {
         let temp_dir = env::temp_dir();
@@ -630,6 +1304,7 @@ mod tests {
                 name: "Test".to_string(),
                 entity_type: "node".to_string(),
                 metadata: None,
+                aliases: Vec::new(),
                 created_at: chrono::Utc::now(),
                 updated_at: chrono::Utc::now(),
             },
@@ -691,4 +1366,55 @@ mod tests {
         assert!(results.iter().any(|r| r.content.contains("Rust")));
         Ok(())
     }
+
+    #[tokio::test]
+    async fn test_replicated_entities_are_recallable() -> Result<()> {
+        let temp = tempfile::TempDir::new()?;
+        let laptop =
+            KnowledgeGraph::new(temp.path().join("laptop.db"), temp.path().join("laptop"))?;
+        let desktop =
+            KnowledgeGraph::new(temp.path().join("desktop.db"), temp.path().join("desktop"))?;
+
+        let id = laptop
+            .remember("Parking at the office is on level 3", "fact", None)
+            .await?;
+        let batch = laptop.db().changes_since(0, 100).await?;
+        desktop.apply_changes(batch.changes).await?;
+
+        let results = desktop.search("parking", 10)?;
+        assert_eq!(results[0].id, id);
+
+        assert!(laptop.delete_entity(&id).await?);
+        let batch = laptop.db().changes_since(batch.cursor, 100).await?;
+        desktop.apply_changes(batch.changes).await?;
+        assert!(desktop.search("parking", 10)?.is_empty());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_search_falls_back_without_index() -> Result<()> {
+        let temp = tempfile::TempDir::new()?;
+        // A file where the index directory should be, like an unmounted volume
+        let index_path = temp.path().join("idx");
+        std::fs::write(&index_path, "not a directory")?;
+        let graph = KnowledgeGraph::new(temp.path().join("t.db"), &index_path)?;
+        assert!(!graph.search_health().full_text);
+
+        let parking = graph
+            .remember("Parking at the office is on level 3", "fact", None)
+            .await?;
+        graph.add_entity("Office plants", "note", None).await?;
+
+        let results = graph.search("office parking", 10)?;
+        assert_eq!(results[0].id, parking);
+        assert_eq!(results[0].score, 1.0);
+        assert_eq!(results[1].score, 0.5);
+        assert!(graph.search("zebra", 10)?.is_empty());
+
+        let health = graph.search_health();
+        assert_eq!(health.fallback_searches, 2);
+        assert!(health.last_error.is_some());
+        assert!(graph.reindex().await.is_err());
+        Ok(())
+    }
 }
@@ -5,6 +5,7 @@
 //! keyword/vector search results with graph traversal for richer context.
 
 use anyhow::Result;
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use std::collections::{HashMap, HashSet};
 use tracing::debug;
@@ -22,6 +23,15 @@ pub struct GraphRagConfig {
     pub hop_decay: f32,
     /// Whether to include relationship metadata in context
     pub include_relationship_context: bool,
+    /// How much a just-updated entity's score is raised: a multiplier of
+    /// `1 + recency_weight`, halving every `recency_half_life_days`. 0
+    /// disables recency ranking.
+    pub recency_weight: f32,
+    pub recency_half_life_days: f32,
+    /// Score multipliers by source type — a `source` in the entity's
+    /// metadata (e.g. "email", "web_clip"), else its entity type, with
+    /// document chunks counting as "document". Unlisted sources get 1.0.
+    pub source_weights: HashMap<String, f32>,
 }
 
 impl Default for GraphRagConfig {
@@ -31,10 +41,51 @@ impl Default for GraphRagConfig {
             max_expanded_results: 20,
             hop_decay: 0.5,
             include_relationship_context: true,
+            recency_weight: 0.0,
+            recency_half_life_days: 30.0,
+            source_weights: HashMap::new(),
         }
     }
 }
 
+impl GraphRagConfig {
+    /// Ranking multiplier for an entity from its recency and source type
+    pub fn ranking_boost(&self, entity: &Entity, now: DateTime<Utc>) -> f32 {
+        let mut boost = self
+            .source_weights
+            .get(source_type(entity))
+            .copied()
+            .unwrap_or(1.0)
+            .max(0.0);
+        if self.recency_weight > 0.0 && self.recency_half_life_days > 0.0 {
+            let age_days = (now - entity.updated_at).num_seconds().max(0) as f32 / 86_400.0;
+            boost *=
+                1.0 + self.recency_weight * 0.5f32.powf(age_days / self.recency_half_life_days);
+        }
+        boost
+    }
+
+    fn ranks_by_boost(&self) -> bool {
+        self.recency_weight > 0.0 || !self.source_weights.is_empty()
+    }
+}
+
+/// The source type used for weighting: metadata `source`, else entity type
+pub fn source_type(entity: &Entity) -> &str {
+    if let Some(source) = entity
+        .metadata
+        .as_ref()
+        .and_then(|m| m.get("source"))
+        .and_then(|s| s.as_str())
+    {
+        return source;
+    }
+    match entity.entity_type.as_str() {
+        "document_chunk" => "document",
+        other => other,
+    }
+}
+
 /// An entity with its graph-derived relevance score
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ScoredEntity {
@@ -153,8 +204,14 @@ pub async fn graph_expand(
         frontier = next_frontier;
     }
 
-    // Sort by score descending
+    // Sort by score descending, after recency and source weighting
     let mut results: Vec<ScoredEntity> = all_entities.into_values().collect();
+    if config.ranks_by_boost() {
+        let now = Utc::now();
+        for result in &mut results {
+            result.score *= config.ranking_boost(&result.entity, now);
+        }
+    }
     results.sort_by(|a, b| {
         b.score
             .partial_cmp(&a.score)
@@ -194,8 +251,10 @@ pub fn format_graph_context(results: &[ScoredEntity], config: &GraphRagConfig) -
         context.push_str("### Direct Matches\n\n");
         for scored in &direct {
             context.push_str(&format!(
-                "- **{}** ({})",
-                scored.entity.name, scored.entity.entity_type
+                "- **{}**{} ({})",
+                scored.entity.name,
+                aka(&scored.entity),
+                scored.entity.entity_type
             ));
             if let Some(metadata) = &scored.entity.metadata {
                 context.push_str(&format!(": {}", metadata));
@@ -213,8 +272,11 @@ pub fn format_graph_context(results: &[ScoredEntity], config: &GraphRagConfig) -
                 _ => String::new(),
             };
             context.push_str(&format!(
-                "- **{}** ({}) [{}]",
-                scored.entity.name, scored.entity.entity_type, hop_info
+                "- **{}**{} ({}) [{}]",
+                scored.entity.name,
+                aka(&scored.entity),
+                scored.entity.entity_type,
+                hop_info
             ));
             if let Some(metadata) = &scored.entity.metadata {
                 context.push_str(&format!(": {}", metadata));
@@ -242,6 +304,15 @@ pub fn format_graph_context(results: &[ScoredEntity], config: &GraphRagConfig) -
     context
 }
 
+/// " (aka K8s, Kube)" for an entity with aliases
+fn aka(entity: &Entity) -> String {
+    if entity.aliases.is_empty() {
+        String::new()
+    } else {
+        format!(" (aka {})", entity.aliases.join(", "))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -254,6 +325,70 @@ mod tests {
         assert!((config.hop_decay - 0.5).abs() < 1e-6);
     }
 
+    #[test]
+    fn test_ranking_boost() {
+        let now = chrono::Utc::now();
+        let entity = |entity_type: &str, metadata, age_days| Entity {
+            id: "e".to_string(),
+            name: "e".to_string(),
+            entity_type: entity_type.to_string(),
+            metadata,
+            aliases: Vec::new(),
+            created_at: now,
+            updated_at: now - chrono::Duration::days(age_days),
+        };
+        let config = GraphRagConfig {
+            recency_weight: 1.0,
+            recency_half_life_days: 30.0,
+            source_weights: HashMap::from([
+                ("email".to_string(), 1.2),
+                ("document".to_string(), 0.8),
+            ]),
+            ..Default::default()
+        };
+
+        let email = entity("message", Some(serde_json::json!({"source": "email"})), 0);
+        let chunk = entity("document_chunk", None, 30);
+        let old_note = entity("note", None, 365);
+        assert!((config.ranking_boost(&email, now) - 2.4).abs() < 1e-3);
+        assert!((config.ranking_boost(&chunk, now) - 1.2).abs() < 1e-3);
+        assert!((config.ranking_boost(&old_note, now) - 1.0).abs() < 1e-3);
+        // Neutral by default
+        let neutral = GraphRagConfig::default();
+        assert!((neutral.ranking_boost(&email, now) - 1.0).abs() < 1e-6);
+    }
+
+    #[tokio::test]
+    async fn test_graph_expand_prefers_fresh_sources() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let db = KnowledgeDb::new(temp.path().join("test.db")).unwrap();
+        let note = db
+            .insert_entity("Offsite note", "note", None)
+            .await
+            .unwrap();
+        let email = db
+            .insert_entity(
+                "Offsite email",
+                "message",
+                Some(serde_json::json!({"source": "email"})),
+            )
+            .await
+            .unwrap();
+        let seeds = vec![(note.clone(), 1.0), (email.clone(), 0.9)];
+
+        let results = graph_expand(&db, &seeds, &GraphRagConfig::default())
+            .await
+            .unwrap();
+        assert_eq!(results[0].entity.id, note);
+
+        let config = GraphRagConfig {
+            source_weights: HashMap::from([("email".to_string(), 1.5)]),
+            ..Default::default()
+        };
+        let results = graph_expand(&db, &seeds, &config).await.unwrap();
+        assert_eq!(results[0].entity.id, email);
+    }
+
     #[test]
     fn test_format_empty_results() {
         let config = GraphRagConfig::default();
@@ -373,6 +508,7 @@ mod tests {
                 name: "Rust".to_string(),
                 entity_type: "language".to_string(),
                 metadata: Some(serde_json::json!({"year": 2010})),
+                aliases: vec!["rustlang".to_string()],
                 created_at: chrono::Utc::now(),
                 updated_at: chrono::Utc::now(),
             },
@@ -383,8 +519,7 @@ mod tests {
 
         let context = format_graph_context(&results, &config);
         assert!(context.contains("Direct Matches"));
-        assert!(context.contains("Rust"));
-        assert!(context.contains("language"));
+        assert!(context.contains("**Rust** (aka rustlang) (language)"));
         assert!(!context.contains("Related Knowledge"));
     }
 
@@ -401,6 +536,7 @@ mod tests {
                     name: "Rust".to_string(),
                     entity_type: "language".to_string(),
                     metadata: None,
+                    aliases: Vec::new(),
                     created_at: chrono::Utc::now(),
                     updated_at: chrono::Utc::now(),
                 },
@@ -414,6 +550,7 @@ mod tests {
                     name: "Memory Safety".to_string(),
                     entity_type: "concept".to_string(),
                     metadata: None,
+                    aliases: Vec::new(),
                     created_at: chrono::Utc::now(),
                     updated_at: chrono::Utc::now(),
                 },
@@ -453,6 +590,7 @@ mod tests {
                 name: "Test".to_string(),
                 entity_type: "node".to_string(),
                 metadata: None,
+                aliases: Vec::new(),
                 created_at: chrono::Utc::now(),
                 updated_at: chrono::Utc::now(),
             },
@@ -497,6 +635,7 @@ mod tests {
                 name: "Test".to_string(),
                 entity_type: "concept".to_string(),
                 metadata: None,
+                aliases: Vec::new(),
                 created_at: chrono::Utc::now(),
                 updated_at: chrono::Utc::now(),
             },
@@ -517,6 +656,7 @@ mod tests {
             max_expanded_results: 50,
             hop_decay: 0.7,
             include_relationship_context: false,
+            ..Default::default()
         };
         assert_eq!(config.max_hops, 5);
         assert_eq!(config.max_expanded_results, 50);
@@ -0,0 +1,290 @@
+//! Event journal replay
+//!
+//! The `event_journal` table records every goal, watcher, task, memory and
+//! rule change in order. Replaying it rebuilds what the agent's state looked
+//! like at any point, which is what audits and "why did it do that at 2am"
+//! questions need.
+
+use std::collections::BTreeMap;
+
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::sqlite::{JournalEvent, JournalQuery, KnowledgeDb};
+
+pub const GOAL_CREATED: &str = "goal_created";
+pub const GOAL_STATUS_CHANGED: &str = "goal_status_changed";
+pub const GOAL_DELETED: &str = "goal_deleted";
+pub const WATCHER_CREATED: &str = "watcher_created";
+pub const WATCHER_ACTIVE_CHANGED: &str = "watcher_active_changed";
+pub const WATCHER_DELETED: &str = "watcher_deleted";
+pub const WATCHER_FIRED: &str = "watcher_fired";
+pub const TASK_CREATED: &str = "task_created";
+pub const TASK_STATE_CHANGED: &str = "task_state_changed";
+pub const MEMORY_WRITTEN: &str = "memory_written";
+pub const MEMORY_DELETED: &str = "memory_deleted";
+pub const RULE_FIRED: &str = "rule_fired";
+/// A task an A2A peer submitted (or tried to), with the outcome
+pub const A2A_DELEGATION: &str = "a2a_delegation";
+pub const A2A_DELEGATION_FINISHED: &str = "a2a_delegation_finished";
+
+/// Events read per page while replaying
+const REPLAY_PAGE: usize = 1000;
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct GoalState {
+    pub description: String,
+    pub status: String,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct WatcherState {
+    pub kind: String,
+    pub active: bool,
+    pub fired: u64,
+    pub last_fired_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct TaskState {
+    pub description: String,
+    pub status: String,
+}
+
+/// Agent state rebuilt from the journal
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct StateProjection {
+    /// Sequence number of the last event applied
+    pub seq: i64,
+    pub goals: BTreeMap<String, GoalState>,
+    pub watchers: BTreeMap<String, WatcherState>,
+    pub tasks: BTreeMap<String, TaskState>,
+    /// Entity ID → name, for memories that exist
+    pub memories: BTreeMap<String, String>,
+    pub memory_writes: u64,
+    pub rules_fired: u64,
+}
+
+impl StateProjection {
+    /// Rebuild state from events in sequence order
+    pub fn replay<'a>(events: impl IntoIterator<Item = &'a JournalEvent>) -> Self {
+        let mut state = Self::default();
+        for event in events {
+            state.apply(event);
+        }
+        state
+    }
+
+    /// Apply one event; unknown kinds only advance `seq`
+    pub fn apply(&mut self, event: &JournalEvent) {
+        self.seq = self.seq.max(event.seq);
+        let text = |key: &str| {
+            event
+                .data
+                .get(key)
+                .and_then(|v| v.as_str())
+                .unwrap_or_default()
+                .to_string()
+        };
+        let id = event.subject_id.clone();
+        match event.kind.as_str() {
+            GOAL_CREATED => {
+                self.goals.insert(
+                    id,
+                    GoalState {
+                        description: text("description"),
+                        status: text("status"),
+                    },
+                );
+            }
+            GOAL_STATUS_CHANGED => self.goals.entry(id).or_default().status = text("to"),
+            GOAL_DELETED => {
+                self.goals.remove(&id);
+            }
+            WATCHER_CREATED => {
+                self.watchers.insert(
+                    id,
+                    WatcherState {
+                        kind: text("kind"),
+                        active: true,
+                        ..Default::default()
+                    },
+                );
+            }
+            WATCHER_ACTIVE_CHANGED => {
+                self.watchers.entry(id).or_default().active = event
+                    .data
+                    .get("active")
+                    .and_then(|v| v.as_bool())
+                    .unwrap_or(false);
+            }
+            WATCHER_DELETED => {
+                self.watchers.remove(&id);
+            }
+            WATCHER_FIRED => {
+                let watcher = self.watchers.entry(id).or_default();
+                watcher.fired += 1;
+                watcher.last_fired_at = Some(event.created_at);
+            }
+            TASK_CREATED => {
+                self.tasks.insert(
+                    id,
+                    TaskState {
+                        description: text("description"),
+                        status: text("status"),
+                    },
+                );
+            }
+            TASK_STATE_CHANGED => self.tasks.entry(id).or_default().status = text("to"),
+            MEMORY_WRITTEN => {
+                self.memories.insert(id, text("name"));
+                self.memory_writes += 1;
+            }
+            MEMORY_DELETED => {
+                self.memories.remove(&id);
+            }
+            RULE_FIRED => self.rules_fired += 1,
+            _ => {}
+        }
+    }
+}
+
+/// Replay the journal up to (not including) `until`, or all of it
+pub async fn project(db: &KnowledgeDb, until: Option<DateTime<Utc>>) -> Result<StateProjection> {
+    let mut state = StateProjection::default();
+    loop {
+        let page = db
+            .get_events(JournalQuery {
+                after_seq: state.seq,
+                until,
+                limit: Some(REPLAY_PAGE),
+                ..Default::default()
+            })
+            .await?;
+        for event in &page {
+            state.apply(event);
+        }
+        if page.len() < REPLAY_PAGE {
+            return Ok(state);
+        }
+    }
+}
+
+/// One-line description of an event, e.g. for an audit log
+pub fn describe(event: &JournalEvent) -> String {
+    let field = |key: &str| {
+        event
+            .data
+            .get(key)
+            .map(|v| {
+                v.as_str()
+                    .map(String::from)
+                    .unwrap_or_else(|| v.to_string())
+            })
+            .unwrap_or_default()
+    };
+    let detail = match event.kind.as_str() {
+        GOAL_CREATED | GOAL_DELETED | TASK_CREATED => format!("\"{}\"", field("description")),
+        GOAL_STATUS_CHANGED | TASK_STATE_CHANGED => {
+            format!("{} → {}", field("from"), field("to"))
+        }
+        WATCHER_CREATED => format!("{}: {}", field("kind"), field("action")),
+        WATCHER_ACTIVE_CHANGED => format!("active = {}", field("active")),
+        WATCHER_DELETED => field("kind"),
+        WATCHER_FIRED => format!("{} {}", field("kind"), field("payload")),
+        MEMORY_WRITTEN => format!(
+            "{} {} ({})",
+            field("change"),
+            field("name"),
+            field("entity_type")
+        ),
+        MEMORY_DELETED => format!("{} ({})", field("name"), field("entity_type")),
+        RULE_FIRED => format!("'{}' on {}", field("name"), field("event")),
+        A2A_DELEGATION => format!(
+            "{} {}: \"{}\"",
+            field("outcome"),
+            field("task_id"),
+            field("prompt")
+        ),
+        A2A_DELEGATION_FINISHED => format!("{} {}", field("task_id"), field("status")),
+        _ => event.data.to_string(),
+    };
+    let mut detail = detail.trim().to_string();
+    if detail.chars().count() > 200 {
+        detail = format!("{}…", detail.chars().take(200).collect::<String>());
+    }
+    format!(
+        "#{} {} {} {}",
+        event.seq, event.kind, event.subject_id, detail
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_journal_records_and_replays_state() -> Result<()> {
+        let temp = tempfile::TempDir::new()?;
+        let db = KnowledgeDb::new(temp.path().join("test.db"))?;
+
+        let goal = db
+            .insert_goal("Ship the release", 4, 600, None, None, "user")
+            .await?;
+        let watcher = db
+            .insert_watcher("email", serde_json::json!({}), "triage", "slack")
+            .await?;
+        db.append_event(
+            WATCHER_FIRED,
+            &watcher,
+            serde_json::json!({ "kind": "email_received" }),
+        )
+        .await?;
+        let entity = db.insert_entity("Alex", "person", None).await?;
+        let before_completion = db.get_events(JournalQuery::default()).await?;
+        db.update_goal_status(&goal, "completed").await?;
+        // Unchanged status isn't journaled
+        db.update_goal_status(&goal, "completed").await?;
+        db.update_watcher_active(&watcher, false).await?;
+        db.delete_entity(&entity).await?;
+
+        let events = db.get_events(JournalQuery::default()).await?;
+        let kinds: Vec<&str> = events.iter().map(|e| e.kind.as_str()).collect();
+        assert_eq!(
+            kinds,
+            [
+                GOAL_CREATED,
+                WATCHER_CREATED,
+                WATCHER_FIRED,
+                MEMORY_WRITTEN,
+                GOAL_STATUS_CHANGED,
+                WATCHER_ACTIVE_CHANGED,
+                MEMORY_DELETED,
+            ]
+        );
+        assert!(events.windows(2).all(|w| w[0].seq < w[1].seq));
+
+        let state = project(&db, None).await?;
+        assert_eq!(state.goals[&goal].status, "completed");
+        assert!(!state.watchers[&watcher].active);
+        assert_eq!(state.watchers[&watcher].fired, 1);
+        assert!(state.memories.is_empty());
+        assert_eq!(state.memory_writes, 1);
+
+        // Replaying a prefix gives the state at that point
+        let earlier = StateProjection::replay(&before_completion);
+        assert_eq!(earlier.goals[&goal].status, "active");
+        assert_eq!(earlier.memories[&entity], "Alex");
+
+        let by_subject = db
+            .get_events(JournalQuery {
+                subject_id: Some(goal.clone()),
+                ..Default::default()
+            })
+            .await?;
+        assert_eq!(by_subject.len(), 2);
+        assert!(describe(&by_subject[1]).contains("active → completed"));
+        Ok(())
+    }
+}
@@ -2,36 +2,52 @@
 //!
 //! This crate provides:
 //! - SQLite storage for entities, relationships, conversations, and watchers
-//! - Tantivy full-text search index
+//! - Tantivy full-text search index with typo tolerance and synonyms
 //! - Knowledge graph operations combining both
+//! - Embeddings from OpenAI, Ollama or a local model for hybrid search
+//! - Embedding-based auto-tagging of entities and conversations
 //! - MEMORY.md synchronization
 
+pub mod aliases;
 pub mod chunking;
+pub mod embedding_providers;
 pub mod embeddings;
 pub mod graph;
 pub mod graph_rag;
+pub mod journal;
 pub mod memory_sync;
 pub mod sqlite;
+pub mod synonyms;
+pub mod tagging;
 pub mod tantivy;
 
 // Re-export main types
+pub use aliases::AliasSuggestion;
 pub use chunking::{
-    ChunkingConfig, DocumentChunk, DocumentMetadata, chunk_text, detect_content_type,
+    ChunkingConfig, ChunkingStrategy, DocumentChunk, DocumentMetadata, chunk_document, chunk_text,
+    detect_content_type,
 };
 pub use embeddings::{
-    EmbeddingConfig, EmbeddingProvider, HybridSearchResult, NoOpEmbeddingProvider, VectorIndex,
-    VectorSearchResult, hybrid_search_rrf,
+    EmbeddingBackend, EmbeddingConfig, EmbeddingProvider, HashEmbeddingProvider,
+    HybridSearchResult, NoOpEmbeddingProvider, VectorIndex, VectorSearchResult, hybrid_search_rrf,
+    hybrid_search_rrf_boosted,
 };
-pub use graph::KnowledgeGraph;
+pub use graph::{KnowledgeGraph, SearchHealth};
 pub use graph_rag::{
-    EntitySource, GraphRagConfig, ScoredEntity, format_graph_context, graph_expand,
+    EntitySource, GraphRagConfig, ScoredEntity, format_graph_context, graph_expand, source_type,
 };
 pub use memory_sync::{load_memory, load_soul, save_memory};
 pub use sqlite::{
-    ActionLogEntry, BackgroundTask, Conversation, Entity, Goal, KnowledgeDb, ModelUsage,
-    Relationship, SourceUsage, UsageSummary, UserPreference, Watcher,
+    ActionLogEntry, AppUsage, BackgroundTask, ChangeBatch, Conversation, DocumentRevision, Entity,
+    Feedback, FeedbackCounts, FeedbackSummary, ForgetReport, Goal, JournalEvent, JournalQuery,
+    KnowledgeDb, ModelUsage, NewEntity, NewFeedback, NewRelationship, Relationship,
+    RememberedBatch, ReplicatedChange, ReplicationReport, Rule, SourceUsage, TimelineEntry,
+    TimelineQuery, TimelineSource, ToolCallRecord, ToolStats, UsageSummary, UserPreference,
+    Watcher,
 };
-pub use tantivy::{SearchResult, TantivyIndex};
+pub use synonyms::SynonymMap;
+pub use tagging::{AutoTagConfig, AutoTagger, TaggingReport};
+pub use tantivy::{IndexConfig, SearchResult, TantivyIndex};
 
 #[cfg(test)]
 mod tests {
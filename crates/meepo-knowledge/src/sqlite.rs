@@ -2,10 +2,10 @@
 
 use anyhow::{Context, Result};
 use chrono::{DateTime, Utc};
-use rusqlite::{Connection, OptionalExtension, params};
+use rusqlite::{Connection, OpenFlags, OptionalExtension, params};
 use serde::{Deserialize, Serialize};
 use serde_json::Value as JsonValue;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::sync::{Arc, Mutex};
 use tracing::{debug, info, warn};
 use uuid::Uuid;
@@ -18,10 +18,17 @@ pub struct Entity {
     pub entity_type: String,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub metadata: Option<JsonValue>,
+    /// Other names it goes by ("K8s", "Liz")
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub aliases: Vec<String>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
 
+/// Entity columns in the order [`KnowledgeDb::row_to_entity`] reads them
+const ENTITY_COLUMNS: &str = "id, name, entity_type, metadata, created_at, updated_at,
+    (SELECT json_group_array(alias) FROM entity_aliases a WHERE a.entity_id = entities.id)";
+
 /// Relationship between entities
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Relationship {
@@ -34,6 +41,60 @@ pub struct Relationship {
     pub created_at: DateTime<Utc>,
 }
 
+/// An entity to add in a batch
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NewEntity {
+    pub name: String,
+    pub entity_type: String,
+    #[serde(default)]
+    pub metadata: Option<JsonValue>,
+}
+
+/// A relationship to add in a batch. In [`KnowledgeDb::remember_many`] an
+/// endpoint may be the name of an entity in the same batch instead of an ID.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NewRelationship {
+    pub source: String,
+    pub target: String,
+    pub relation_type: String,
+    #[serde(default)]
+    pub metadata: Option<JsonValue>,
+}
+
+/// What a batch write stored
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RememberedBatch {
+    pub entities: Vec<Entity>,
+    pub relationships: Vec<Relationship>,
+}
+
+/// What [`KnowledgeDb::forget_entities`] removed
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ForgetReport {
+    /// Removed entities, including the chunks of removed documents
+    pub entities: Vec<Entity>,
+    pub relationships: usize,
+    /// Stored embedding vectors removed with them
+    pub embeddings: usize,
+}
+
+/// A rewrite of an ingested document's chunks, applied in one transaction
+/// by [`KnowledgeDb::revise_document`]
+#[derive(Debug, Clone, Default)]
+pub struct DocumentRevision {
+    pub document_id: String,
+    pub name: String,
+    pub metadata: Option<JsonValue>,
+    /// Chunks no longer in the document
+    pub remove: Vec<String>,
+    /// Chunks kept by ID under a new name and metadata (e.g. a new position)
+    pub update: Vec<(String, NewEntity)>,
+    /// New chunks
+    pub add: Vec<NewEntity>,
+    /// Links to add; endpoints may be names of chunks in `add`
+    pub relationships: Vec<NewRelationship>,
+}
+
 /// Conversation record
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Conversation {
@@ -73,6 +134,8 @@ pub struct Goal {
     pub source: String, // "user" or "template:<name>"
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
+    #[serde(default)]
+    pub deadline: Option<DateTime<Utc>>,
 }
 
 /// Learned user preference
@@ -126,6 +189,9 @@ pub struct UsageSummary {
     pub estimated_cost_usd: f64,
     pub by_source: std::collections::HashMap<String, SourceUsage>,
     pub by_model: std::collections::HashMap<String, ModelUsage>,
+    /// Keyed by "channel/model", for turns that came from a channel
+    #[serde(default)]
+    pub by_channel_model: std::collections::HashMap<String, ModelUsage>,
 }
 
 /// Usage breakdown by source
@@ -146,6 +212,288 @@ pub struct ModelUsage {
     pub estimated_cost_usd: f64,
 }
 
+/// A thumbs up/down on one of the agent's responses
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Feedback {
+    pub id: String,
+    /// Conversation row of the response being rated
+    pub conversation_id: Option<String>,
+    pub channel: String,
+    pub sender: String,
+    pub rating: i32, // 1 (thumbs up) or -1 (thumbs down)
+    pub comment: Option<String>,
+    /// Model that produced the response
+    pub model: Option<String>,
+    /// Tools used while producing the response
+    pub tools: Vec<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Feedback to store for a response
+#[derive(Debug, Clone, Default)]
+pub struct NewFeedback<'a> {
+    pub conversation_id: Option<&'a str>,
+    pub channel: &'a str,
+    pub sender: &'a str,
+    pub rating: i32,
+    pub comment: Option<&'a str>,
+    pub model: Option<&'a str>,
+    pub tools: &'a [String],
+}
+
+/// Thumbs up/down counts
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FeedbackCounts {
+    pub positive: u64,
+    pub negative: u64,
+}
+
+impl FeedbackCounts {
+    pub fn total(&self) -> u64 {
+        self.positive + self.negative
+    }
+
+    /// Share of ratings that were positive (0.0 when there are none)
+    pub fn satisfaction(&self) -> f64 {
+        if self.total() == 0 {
+            0.0
+        } else {
+            self.positive as f64 / self.total() as f64
+        }
+    }
+
+    fn add(&mut self, rating: i32) {
+        if rating > 0 {
+            self.positive += 1;
+        } else {
+            self.negative += 1;
+        }
+    }
+}
+
+/// Feedback for a time period, broken down by channel, model and tool
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct FeedbackSummary {
+    pub period: String,
+    pub overall: FeedbackCounts,
+    pub by_channel: std::collections::HashMap<String, FeedbackCounts>,
+    pub by_model: std::collections::HashMap<String, FeedbackCounts>,
+    /// Responses that used no tools are counted under "(none)"
+    pub by_tool: std::collections::HashMap<String, FeedbackCounts>,
+}
+
+/// Time spent in one app over a period
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AppUsage {
+    pub app: String,
+    pub seconds: u64,
+    /// Days in the period the app was used
+    pub days: u32,
+}
+
+/// One tool invocation, for usage analytics
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ToolCallRecord {
+    pub tool: String,
+    pub ok: bool,
+    pub duration_ms: u64,
+    pub output_chars: usize,
+}
+
+/// How one tool has been used since it was first registered
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ToolStats {
+    pub tool: String,
+    pub calls: u64,
+    pub failures: u64,
+    pub total_duration_ms: u64,
+    /// Characters of output returned to the model across all calls
+    pub output_chars: u64,
+    /// Size of the tool's definition, sent with every request that offers it
+    pub definition_chars: u64,
+    pub first_seen_at: DateTime<Utc>,
+    pub last_used_at: Option<DateTime<Utc>>,
+}
+
+impl ToolStats {
+    pub fn success_rate(&self) -> f64 {
+        if self.calls == 0 {
+            0.0
+        } else {
+            (self.calls - self.failures) as f64 / self.calls as f64
+        }
+    }
+}
+
+/// A knowledge change exchanged with another Meepo instance
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ReplicatedChange {
+    Entity(Entity),
+    Relationship(Relationship),
+    /// An entity or relationship (`item`) was deleted
+    Tombstone {
+        item: String,
+        id: String,
+        deleted_at: DateTime<Utc>,
+    },
+}
+
+/// Changes after a replication cursor, oldest first
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ChangeBatch {
+    pub changes: Vec<ReplicatedChange>,
+    /// Pass back as `since` to get the next batch
+    pub cursor: i64,
+    /// More changes are waiting after `cursor`
+    pub more: bool,
+}
+
+/// Outcome of applying replicated changes
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ReplicationReport {
+    /// Entities inserted or updated (need reindexing)
+    pub entities: Vec<Entity>,
+    pub relationships: usize,
+    /// Entity IDs deleted
+    pub removed: Vec<String>,
+    /// Older than what we have
+    pub skipped: usize,
+    /// Relationships waiting for an endpoint entity to arrive
+    pub deferred: usize,
+}
+
+/// An entry in the append-only event journal
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JournalEvent {
+    /// Position in the journal; strictly increasing
+    pub seq: i64,
+    /// What happened, e.g. "goal_created", "watcher_fired", "memory_written"
+    pub kind: String,
+    /// ID of the goal, watcher, task, entity or rule it happened to
+    pub subject_id: String,
+    pub data: JsonValue,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Which journal events to read; the default is everything, oldest first
+#[derive(Debug, Clone, Default)]
+pub struct JournalQuery {
+    /// Only events after this sequence number
+    pub after_seq: i64,
+    pub since: Option<DateTime<Utc>>,
+    pub until: Option<DateTime<Utc>>,
+    pub subject_id: Option<String>,
+    pub kind: Option<String>,
+    pub limit: Option<usize>,
+}
+
+/// Where an activity timeline entry comes from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TimelineSource {
+    /// A message to or from the agent
+    Conversation,
+    /// An `action_log` entry
+    Action,
+    /// A background task created or changing state
+    Task,
+    /// A watcher firing
+    Watcher,
+}
+
+impl TimelineSource {
+    pub const ALL: [Self; 4] = [Self::Conversation, Self::Action, Self::Task, Self::Watcher];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Conversation => "conversation",
+            Self::Action => "action",
+            Self::Task => "task",
+            Self::Watcher => "watcher",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|s| s.as_str() == name.trim().to_lowercase().trim_end_matches('s'))
+    }
+}
+
+/// One entry of the activity timeline
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TimelineEntry {
+    pub source: TimelineSource,
+    /// Sender for conversations, action type for actions, journal event
+    /// kind for tasks and watchers
+    pub kind: String,
+    /// Conversation or action ID, task or watcher ID
+    pub subject_id: String,
+    pub summary: String,
+    /// Channel the entry happened on or replies to, when there is one
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub channel: Option<String>,
+    pub at: DateTime<Utc>,
+}
+
+/// Which timeline entries to read; the default is everything, newest first
+#[derive(Debug, Clone, Default)]
+pub struct TimelineQuery {
+    pub since: Option<DateTime<Utc>>,
+    pub until: Option<DateTime<Utc>>,
+    /// Empty means every source
+    pub sources: Vec<TimelineSource>,
+    /// Only entries on this channel (actions have none, so they're left out)
+    pub channel: Option<String>,
+    /// Only entries whose text contains this, case-insensitively
+    pub contains: Option<String>,
+    pub limit: Option<usize>,
+}
+
+impl TimelineQuery {
+    fn wants(&self, source: TimelineSource) -> bool {
+        self.sources.is_empty() || self.sources.contains(&source)
+    }
+}
+
+/// Longest summary in a timeline entry
+const TIMELINE_SUMMARY_CHARS: usize = 200;
+
+fn timeline_summary(text: &str) -> String {
+    let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    if text.chars().count() > TIMELINE_SUMMARY_CHARS {
+        format!(
+            "{}…",
+            text.chars()
+                .take(TIMELINE_SUMMARY_CHARS)
+                .collect::<String>()
+        )
+    } else {
+        text
+    }
+}
+
+/// Declarative automation rule: when `event` matches `filter`, run `action`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Rule {
+    pub id: String,
+    pub name: String,
+    /// Event kind, e.g. "email_received", "message_received" or "*"
+    pub event: String,
+    pub filter: JsonValue,
+    /// Condition expression that must also hold, e.g. `hour() < 18`
+    pub condition: Option<String>,
+    pub action: JsonValue,
+    pub reply_channel: String,
+    /// Matching events skip the agent entirely (no LLM reply)
+    pub exclusive: bool,
+    pub enabled: bool,
+    pub fire_count: i64,
+    pub last_fired_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
 /// Background task spawned by the agent
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BackgroundTask {
@@ -159,9 +507,13 @@ pub struct BackgroundTask {
     pub result: Option<String>,
 }
 
+/// How long a connection waits for another one's lock before failing
+const BUSY_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(5);
+
 /// SQLite database wrapper (thread-safe via Arc<Mutex>)
 pub struct KnowledgeDb {
     conn: Arc<Mutex<Connection>>,
+    path: PathBuf,
 }
 
 impl KnowledgeDb {
@@ -182,6 +534,13 @@ impl KnowledgeDb {
         // Enable foreign keys
         conn.execute("PRAGMA foreign_keys = ON", [])?;
 
+        // WAL lets snapshot readers keep a consistent view without blocking
+        // writers; the timeout covers other connections (vector index, snapshots)
+        conn.query_row("PRAGMA journal_mode = WAL", [], |row| {
+            row.get::<_, String>(0)
+        })?;
+        conn.busy_timeout(BUSY_TIMEOUT)?;
+
         // Create entities table
         conn.execute(
             "CREATE TABLE IF NOT EXISTS entities (
@@ -210,6 +569,39 @@ impl KnowledgeDb {
             [],
         )?;
 
+        // Other names for entities, matched case-insensitively
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS entity_aliases (
+                entity_id TEXT NOT NULL,
+                alias TEXT NOT NULL COLLATE NOCASE,
+                created_at TEXT NOT NULL,
+                PRIMARY KEY (entity_id, alias),
+                FOREIGN KEY(entity_id) REFERENCES entities(id) ON DELETE CASCADE
+            )",
+            [],
+        )?;
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_entity_aliases_alias ON entity_aliases(alias)",
+            [],
+        )?;
+
+        // Tombstones for forgotten entities, so automatic extraction doesn't
+        // learn them again from old conversations
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS forgotten (
+                entity_id TEXT PRIMARY KEY,
+                name TEXT NOT NULL COLLATE NOCASE,
+                entity_type TEXT NOT NULL,
+                reason TEXT,
+                forgotten_at TEXT NOT NULL
+            )",
+            [],
+        )?;
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_forgotten_name ON forgotten(name)",
+            [],
+        )?;
+
         // Create conversations table
         conn.execute(
             "CREATE TABLE IF NOT EXISTS conversations (
@@ -295,6 +687,8 @@ impl KnowledgeDb {
             "ALTER TABLE goals ADD COLUMN source TEXT NOT NULL DEFAULT 'user'",
             [],
         );
+        // Migration: Add deadline column to existing goals tables
+        let _ = conn.execute("ALTER TABLE goals ADD COLUMN deadline TEXT", []);
 
         // Create user_preferences table
         conn.execute(
@@ -315,6 +709,14 @@ impl KnowledgeDb {
             "CREATE INDEX IF NOT EXISTS idx_preferences_category ON user_preferences(category)",
             [],
         )?;
+        // Migration: per-sender settings were keyed by a bare `channel:sender`,
+        // shared across categories; prefix them with their category
+        conn.execute(
+            "UPDATE OR IGNORE user_preferences SET key = category || ':' || key
+             WHERE category IN ('style', 'language', 'pinned_context', 'overrides')
+               AND substr(key, 1, length(category) + 1) != category || ':'",
+            [],
+        )?;
 
         // Create action_log table
         conn.execute(
@@ -406,11 +808,297 @@ impl KnowledgeDb {
             [],
         )?;
 
+        // Create feedback table for ratings of agent responses
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS feedback (
+                id TEXT PRIMARY KEY,
+                conversation_id TEXT,
+                channel TEXT NOT NULL,
+                sender TEXT NOT NULL,
+                rating INTEGER NOT NULL,
+                comment TEXT,
+                model TEXT,
+                tools TEXT,
+                created_at TEXT NOT NULL
+            )",
+            [],
+        )?;
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_feedback_created ON feedback(created_at)",
+            [],
+        )?;
+
+        // Create app_usage table for daily time-in-app aggregates
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS app_usage (
+                date TEXT NOT NULL,
+                app TEXT NOT NULL,
+                seconds INTEGER NOT NULL DEFAULT 0,
+                PRIMARY KEY (date, app)
+            )",
+            [],
+        )?;
+
+        // Create tool_stats table for per-tool usage analytics
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS tool_stats (
+                tool TEXT PRIMARY KEY,
+                calls INTEGER NOT NULL DEFAULT 0,
+                failures INTEGER NOT NULL DEFAULT 0,
+                total_duration_ms INTEGER NOT NULL DEFAULT 0,
+                output_chars INTEGER NOT NULL DEFAULT 0,
+                definition_chars INTEGER NOT NULL DEFAULT 0,
+                first_seen_at TEXT NOT NULL,
+                last_used_at TEXT
+            )",
+            [],
+        )?;
+
+        // Replication change log: one row per entity/relationship, moved to
+        // the end whenever the row changes. Deletions keep their row as a
+        // tombstone (deleted_at set) so peers learn about them. The triggers
+        // delete then insert because the outer statement's conflict policy
+        // would override an OR REPLACE inside them.
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS replication_log (
+                seq INTEGER PRIMARY KEY AUTOINCREMENT,
+                kind TEXT NOT NULL,
+                item_id TEXT NOT NULL,
+                deleted_at TEXT,
+                UNIQUE(kind, item_id)
+            )",
+            [],
+        )?;
+        for (table, kind) in [("entities", "entity"), ("relationships", "relationship")] {
+            for (name, event) in [("insert", "INSERT"), ("update", "UPDATE")] {
+                conn.execute(
+                    &format!(
+                        "CREATE TRIGGER IF NOT EXISTS replication_{kind}_{name} AFTER {event} ON {table}
+                         BEGIN
+                             DELETE FROM replication_log WHERE kind = '{kind}' AND item_id = NEW.id;
+                             INSERT INTO replication_log (kind, item_id) VALUES ('{kind}', NEW.id);
+                         END"
+                    ),
+                    [],
+                )?;
+            }
+            conn.execute(
+                &format!(
+                    "CREATE TRIGGER IF NOT EXISTS replication_{kind}_delete AFTER DELETE ON {table}
+                     BEGIN
+                         DELETE FROM replication_log WHERE kind = '{kind}' AND item_id = OLD.id;
+                         INSERT INTO replication_log (kind, item_id, deleted_at)
+                         VALUES ('{kind}', OLD.id, strftime('%Y-%m-%dT%H:%M:%fZ', 'now'));
+                     END"
+                ),
+                [],
+            )?;
+        }
+        // Rows written before the log existed
+        conn.execute(
+            "INSERT OR IGNORE INTO replication_log (kind, item_id)
+             SELECT 'entity', id FROM entities",
+            [],
+        )?;
+        conn.execute(
+            "INSERT OR IGNORE INTO replication_log (kind, item_id)
+             SELECT 'relationship', id FROM relationships",
+            [],
+        )?;
+        // Replicated relationships whose endpoints haven't arrived yet
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS replication_pending (
+                id TEXT PRIMARY KEY,
+                payload TEXT NOT NULL,
+                received_at TEXT NOT NULL
+            )",
+            [],
+        )?;
+
+        // Declarative automation rules
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS rules (
+                id TEXT PRIMARY KEY,
+                name TEXT NOT NULL,
+                event TEXT NOT NULL,
+                filter TEXT NOT NULL,
+                condition TEXT,
+                action TEXT NOT NULL,
+                reply_channel TEXT NOT NULL,
+                exclusive INTEGER NOT NULL DEFAULT 0,
+                enabled INTEGER NOT NULL DEFAULT 1,
+                fire_count INTEGER NOT NULL DEFAULT 0,
+                last_fired_at TEXT,
+                created_at TEXT NOT NULL
+            )",
+            [],
+        )?;
+
+        // Event journal: append-only log of agent state changes for audit
+        // and replay. Changes to goals, watchers, tasks, entities (memory)
+        // and rules are recorded by triggers so no write path can skip them;
+        // occurrences that don't change a row (a watcher firing) are
+        // appended with `append_event`.
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS event_journal (
+                seq INTEGER PRIMARY KEY AUTOINCREMENT,
+                kind TEXT NOT NULL,
+                subject_id TEXT NOT NULL,
+                data TEXT NOT NULL DEFAULT '{}',
+                created_at TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%fZ', 'now'))
+            )",
+            [],
+        )?;
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_event_journal_created ON event_journal(created_at)",
+            [],
+        )?;
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_event_journal_subject ON event_journal(subject_id)",
+            [],
+        )?;
+        for statement in ["UPDATE", "DELETE"] {
+            conn.execute(
+                &format!(
+                    "CREATE TRIGGER IF NOT EXISTS event_journal_no_{lower} BEFORE {statement} ON event_journal
+                     BEGIN
+                         SELECT RAISE(ABORT, 'event_journal is append-only');
+                     END",
+                    lower = statement.to_lowercase()
+                ),
+                [],
+            )?;
+        }
+        let journal_triggers = [
+            (
+                "goal_created",
+                "AFTER INSERT ON goals",
+                "NEW.id",
+                "json_object('description', NEW.description, 'status', NEW.status,
+                             'priority', NEW.priority, 'source', NEW.source)",
+            ),
+            (
+                "goal_status_changed",
+                "AFTER UPDATE OF status ON goals WHEN OLD.status IS NOT NEW.status",
+                "NEW.id",
+                "json_object('from', OLD.status, 'to', NEW.status)",
+            ),
+            (
+                "goal_deleted",
+                "AFTER DELETE ON goals",
+                "OLD.id",
+                "json_object('description', OLD.description)",
+            ),
+            (
+                "watcher_created",
+                "AFTER INSERT ON watchers",
+                "NEW.id",
+                "json_object('kind', NEW.kind, 'action', NEW.action,
+                             'reply_channel', NEW.reply_channel)",
+            ),
+            (
+                "watcher_active_changed",
+                "AFTER UPDATE OF active ON watchers WHEN OLD.active IS NOT NEW.active",
+                "NEW.id",
+                "json_object('active', json(CASE WHEN NEW.active THEN 'true' ELSE 'false' END))",
+            ),
+            (
+                "watcher_deleted",
+                "AFTER DELETE ON watchers",
+                "OLD.id",
+                "json_object('kind', OLD.kind)",
+            ),
+            (
+                "task_created",
+                "AFTER INSERT ON background_tasks",
+                "NEW.id",
+                "json_object('description', NEW.description, 'status', NEW.status,
+                             'spawned_by', NEW.spawned_by)",
+            ),
+            (
+                "task_state_changed",
+                "AFTER UPDATE OF status ON background_tasks WHEN OLD.status IS NOT NEW.status",
+                "NEW.id",
+                "json_object('from', OLD.status, 'to', NEW.status,
+                             'result', substr(NEW.result, 1, 500))",
+            ),
+            (
+                "memory_written",
+                "AFTER INSERT ON entities",
+                "NEW.id",
+                "json_object('name', NEW.name, 'entity_type', NEW.entity_type,
+                             'change', 'created')",
+            ),
+            (
+                "memory_written",
+                "AFTER UPDATE ON entities",
+                "NEW.id",
+                "json_object('name', NEW.name, 'entity_type', NEW.entity_type,
+                             'change', 'updated')",
+            ),
+            (
+                "memory_deleted",
+                "AFTER DELETE ON entities",
+                "OLD.id",
+                "json_object('name', OLD.name, 'entity_type', OLD.entity_type)",
+            ),
+            (
+                "rule_fired",
+                "AFTER UPDATE OF fire_count ON rules WHEN NEW.fire_count > OLD.fire_count",
+                "NEW.id",
+                "json_object('name', NEW.name, 'event', NEW.event)",
+            ),
+        ];
+        for (i, (kind, when, subject, data)) in journal_triggers.iter().enumerate() {
+            conn.execute(
+                &format!(
+                    "CREATE TRIGGER IF NOT EXISTS event_journal_{kind}_{i} {when}
+                     BEGIN
+                         INSERT INTO event_journal (kind, subject_id, data)
+                         VALUES ('{kind}', {subject}, {data});
+                     END"
+                ),
+                [],
+            )?;
+        }
+
         debug!("Database schema initialized successfully");
 
         Ok(Self {
             conn: Arc::new(Mutex::new(conn)),
+            path: path.as_ref().to_path_buf(),
+        })
+    }
+
+    /// Open a read-only view of the database as it is now.
+    ///
+    /// The view is a separate connection holding one read transaction, so
+    /// every query against it sees the same committed state while writers
+    /// carry on; use it when several queries must agree with each other,
+    /// as in digests and reports. Dropping it ends the transaction. Write
+    /// methods on a snapshot fail.
+    pub async fn snapshot(&self) -> Result<KnowledgeDb> {
+        let path = self.path.clone();
+
+        tokio::task::spawn_blocking(move || {
+            let conn = Connection::open_with_flags(
+                &path,
+                OpenFlags::SQLITE_OPEN_READ_ONLY | OpenFlags::SQLITE_OPEN_NO_MUTEX,
+            )
+            .context("Failed to open database snapshot")?;
+            conn.busy_timeout(BUSY_TIMEOUT)?;
+            // A deferred transaction only pins its snapshot at the first read
+            conn.execute_batch("BEGIN")?;
+            conn.query_row("SELECT COUNT(*) FROM sqlite_master", [], |row| {
+                row.get::<_, i64>(0)
+            })?;
+            Ok(KnowledgeDb {
+                conn: Arc::new(Mutex::new(conn)),
+                path,
+            })
         })
+        .await
+        .context("spawn_blocking task panicked")?
     }
 
     /// Insert a new entity
@@ -465,37 +1153,9 @@ impl KnowledgeDb {
             });
             let result = conn
                 .query_row(
-                    "SELECT id, name, entity_type, metadata, created_at, updated_at
-                     FROM entities WHERE id = ?1",
+                    &format!("SELECT {} FROM entities WHERE id = ?1", ENTITY_COLUMNS),
                     params![&id],
-                    |row| {
-                        let metadata_str: Option<String> = row.get(3)?;
-                        let metadata = metadata_str
-                            .map(|s| serde_json::from_str(&s))
-                            .transpose()
-                            .map_err(|e| {
-                            rusqlite::Error::FromSqlConversionFailure(
-                                3,
-                                rusqlite::types::Type::Text,
-                                Box::new(e),
-                            )
-                        })?;
-
-                        Ok(Entity {
-                            id: row.get(0)?,
-                            name: row.get(1)?,
-                            entity_type: row.get(2)?,
-                            metadata,
-                            created_at: row
-                                .get::<_, String>(4)?
-                                .parse()
-                                .unwrap_or_else(|_| Utc::now()),
-                            updated_at: row
-                                .get::<_, String>(5)?
-                                .parse()
-                                .unwrap_or_else(|_| Utc::now()),
-                        })
-                    },
+                    Self::row_to_entity,
                 )
                 .optional()?;
 
@@ -510,39 +1170,51 @@ impl KnowledgeDb {
         &self,
         query: &str,
         entity_type: Option<&str>,
+    ) -> Result<Vec<Entity>> {
+        self.search_entities_tagged(query, entity_type, None).await
+    }
+
+    /// Search entities by name or type, optionally limited to those with a
+    /// tag containing `tag` (in `metadata.tags`)
+    pub async fn search_entities_tagged(
+        &self,
+        query: &str,
+        entity_type: Option<&str>,
+        tag: Option<&str>,
     ) -> Result<Vec<Entity>> {
         let conn = Arc::clone(&self.conn);
-        let query = query.to_owned();
+        let pattern = format!("%{}%", query);
         let entity_type = entity_type.map(|s| s.to_owned());
+        let tag_pattern = tag.map(|t| format!("%{}%", t));
 
         tokio::task::spawn_blocking(move || {
-            let sql = if entity_type.is_some() {
-                "SELECT id, name, entity_type, metadata, created_at, updated_at
-                 FROM entities
-                 WHERE (name LIKE ?1 OR entity_type LIKE ?1) AND entity_type = ?2
-                 ORDER BY updated_at DESC
-                 LIMIT 100"
-            } else {
-                "SELECT id, name, entity_type, metadata, created_at, updated_at
-                 FROM entities
-                 WHERE name LIKE ?1 OR entity_type LIKE ?1
-                 ORDER BY updated_at DESC
-                 LIMIT 100"
-            };
-
-            let pattern = format!("%{}%", query);
             let conn = conn.lock().unwrap_or_else(|poisoned| {
                 warn!("Database mutex was poisoned, recovering");
                 poisoned.into_inner()
             });
-            let mut stmt = conn.prepare(sql)?;
+            let mut stmt = conn.prepare(&format!(
+                "SELECT {}
+                 FROM entities
+                 WHERE (name LIKE ?1 OR entity_type LIKE ?1
+                        OR EXISTS (SELECT 1 FROM entity_aliases a
+                                   WHERE a.entity_id = entities.id AND a.alias LIKE ?1))
+                   AND (?2 IS NULL OR entity_type = ?2)
+                   AND (?3 IS NULL OR EXISTS (
+                       SELECT 1 FROM json_each(
+                           CASE WHEN json_valid(entities.metadata) THEN entities.metadata END,
+                           '$.tags'
+                       ) WHERE value LIKE ?3))
+                 ORDER BY updated_at DESC
+                 LIMIT 100",
+                ENTITY_COLUMNS
+            ))?;
 
-            let entities = if let Some(etype) = entity_type.as_deref() {
-                stmt.query_map(params![&pattern, etype], Self::row_to_entity)?
-            } else {
-                stmt.query_map(params![&pattern], Self::row_to_entity)?
-            }
-            .collect::<Result<Vec<_>, _>>()?;
+            let entities = stmt
+                .query_map(
+                    params![&pattern, entity_type, tag_pattern],
+                    Self::row_to_entity,
+                )?
+                .collect::<Result<Vec<_>, _>>()?;
 
             Ok(entities)
         })
@@ -550,21 +1222,263 @@ impl KnowledgeDb {
         .context("spawn_blocking task panicked")?
     }
 
-    /// Get all entities (capped to prevent OOM on large databases)
-    pub async fn get_all_entities(&self) -> Result<Vec<Entity>> {
+    /// Entities whose name, type, aliases or metadata contain words of
+    /// `query`, scored by the share of words matched. Blocking: this is the
+    /// fallback for the synchronous full-text search when the index is down.
+    pub fn search_entities_blocking(
+        &self,
+        query: &str,
+        limit: usize,
+    ) -> Result<Vec<(Entity, f32)>> {
+        let mut words: Vec<String> = Vec::new();
+        for word in query.split_whitespace() {
+            let word = word
+                .trim_matches(|c: char| !c.is_alphanumeric())
+                .to_lowercase();
+            if word.chars().count() >= 2 && !words.contains(&word) {
+                words.push(word);
+            }
+        }
+        words.truncate(8);
+        if words.is_empty() || limit == 0 {
+            return Ok(Vec::new());
+        }
+
+        let conn = self.conn.lock().unwrap_or_else(|poisoned| {
+            warn!("Database mutex was poisoned, recovering");
+            poisoned.into_inner()
+        });
+        let conditions: Vec<String> = (1..=words.len())
+            .map(|i| {
+                format!(
+                    "name LIKE ?{i} OR entity_type LIKE ?{i} OR metadata LIKE ?{i}
+                     OR EXISTS (SELECT 1 FROM entity_aliases a
+                                WHERE a.entity_id = entities.id AND a.alias LIKE ?{i})"
+                )
+            })
+            .collect();
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {} FROM entities WHERE {} ORDER BY updated_at DESC LIMIT {}",
+            ENTITY_COLUMNS,
+            conditions.join(" OR "),
+            limit * 5
+        ))?;
+        let patterns: Vec<String> = words.iter().map(|w| format!("%{}%", w)).collect();
+        let entities = stmt
+            .query_map(
+                rusqlite::params_from_iter(patterns.iter()),
+                Self::row_to_entity,
+            )?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let mut scored: Vec<(Entity, f32)> = entities
+            .into_iter()
+            .map(|entity| {
+                let text = format!(
+                    "{} {} {} {}",
+                    entity.name,
+                    entity.entity_type,
+                    entity.aliases.join(" "),
+                    entity
+                        .metadata
+                        .as_ref()
+                        .map(|m| m.to_string())
+                        .unwrap_or_default()
+                )
+                .to_lowercase();
+                let matched = words.iter().filter(|w| text.contains(w.as_str())).count();
+                (entity, matched as f32 / words.len() as f32)
+            })
+            .collect();
+        // Stable, so equally good matches stay most recent first
+        scored.sort_by(|a, b| b.1.total_cmp(&a.1));
+        scored.truncate(limit);
+        Ok(scored)
+    }
+
+    /// Replace an entity's metadata. `updated_at` is left alone so
+    /// background annotation doesn't reorder recent entities.
+    pub async fn update_entity_metadata(&self, id: &str, metadata: JsonValue) -> Result<()> {
         let conn = Arc::clone(&self.conn);
+        let id = id.to_owned();
 
         tokio::task::spawn_blocking(move || {
+            let metadata_json = serde_json::to_string(&metadata)?;
             let conn = conn.lock().unwrap_or_else(|poisoned| {
                 warn!("Database mutex was poisoned, recovering");
                 poisoned.into_inner()
             });
-            let mut stmt = conn.prepare(
-                "SELECT id, name, entity_type, metadata, created_at, updated_at
-                 FROM entities
-                 ORDER BY updated_at DESC
-                 LIMIT 50000",
+            conn.execute(
+                "UPDATE entities SET metadata = ?1 WHERE id = ?2",
+                params![metadata_json, &id],
             )?;
+            Ok(())
+        })
+        .await
+        .context("spawn_blocking task panicked")?
+    }
+
+    /// Delete an entity along with its relationships. Returns false if it
+    /// didn't exist.
+    pub async fn delete_entity(&self, id: &str) -> Result<bool> {
+        let conn = Arc::clone(&self.conn);
+        let id = id.to_owned();
+
+        tokio::task::spawn_blocking(move || {
+            let conn = conn.lock().unwrap_or_else(|poisoned| {
+                warn!("Database mutex was poisoned, recovering");
+                poisoned.into_inner()
+            });
+            let deleted = conn.execute("DELETE FROM entities WHERE id = ?1", params![&id])?;
+            Ok(deleted > 0)
+        })
+        .await
+        .context("spawn_blocking task panicked")?
+    }
+
+    /// Delete entities, their relationships, the chunks of any documents
+    /// among them and their stored embeddings in one transaction, leaving a
+    /// tombstone for each (chunks excepted) so [`Self::is_forgotten`] can
+    /// keep them from being learned again
+    pub async fn forget_entities(&self, ids: &[String], reason: &str) -> Result<ForgetReport> {
+        let conn = Arc::clone(&self.conn);
+        let ids = ids.to_vec();
+        let reason = reason.to_owned();
+
+        tokio::task::spawn_blocking(move || {
+            let now = Utc::now().to_rfc3339();
+            let conn = conn.lock().unwrap_or_else(|poisoned| {
+                warn!("Database mutex was poisoned, recovering");
+                poisoned.into_inner()
+            });
+            let tx = conn.unchecked_transaction()?;
+            let mut report = ForgetReport::default();
+            {
+                let mut get = tx.prepare(&format!(
+                    "SELECT {} FROM entities WHERE id = ?1",
+                    ENTITY_COLUMNS
+                ))?;
+                let mut chunks = tx.prepare(&format!(
+                    "SELECT {} FROM entities WHERE id IN
+                     (SELECT target_id FROM relationships
+                      WHERE source_id = ?1 AND relation_type = 'contains_chunk')",
+                    ENTITY_COLUMNS
+                ))?;
+                for id in &ids {
+                    let Some(entity) =
+                        get.query_row(params![id], Self::row_to_entity).optional()?
+                    else {
+                        continue;
+                    };
+                    if entity.entity_type == "document" {
+                        for chunk in chunks.query_map(params![id], Self::row_to_entity)? {
+                            report.entities.push(chunk?);
+                        }
+                    }
+                    report.entities.push(entity);
+                }
+            }
+            report.entities.sort_by(|a, b| a.id.cmp(&b.id));
+            report.entities.dedup_by(|a, b| a.id == b.id);
+
+            let has_embeddings = tx
+                .query_row(
+                    "SELECT 1 FROM sqlite_master WHERE type = 'table' AND name = 'embeddings'",
+                    [],
+                    |_| Ok(()),
+                )
+                .optional()?
+                .is_some();
+            {
+                let mut count_relationships = tx.prepare(
+                    "SELECT COUNT(*) FROM relationships WHERE source_id = ?1 OR target_id = ?1",
+                )?;
+                let mut delete_relationships =
+                    tx.prepare("DELETE FROM relationships WHERE source_id = ?1 OR target_id = ?1")?;
+                let mut delete = tx.prepare("DELETE FROM entities WHERE id = ?1")?;
+                let mut tombstone = tx.prepare(
+                    "INSERT OR REPLACE INTO forgotten
+                     (entity_id, name, entity_type, reason, forgotten_at)
+                     VALUES (?1, ?2, ?3, ?4, ?5)",
+                )?;
+                for entity in &report.entities {
+                    // Count before deleting: relationships between two
+                    // forgotten entities are only counted once
+                    report.relationships += count_relationships
+                        .query_row(params![&entity.id], |row| row.get::<_, i64>(0))?
+                        as usize;
+                    delete_relationships.execute(params![&entity.id])?;
+                    if has_embeddings {
+                        report.embeddings += tx.execute(
+                            "DELETE FROM embeddings WHERE entity_id = ?1",
+                            params![&entity.id],
+                        )?;
+                    }
+                    delete.execute(params![&entity.id])?;
+                    if entity.entity_type != "document_chunk" {
+                        tombstone.execute(params![
+                            &entity.id,
+                            entity.name.trim(),
+                            &entity.entity_type,
+                            &reason,
+                            &now
+                        ])?;
+                    }
+                }
+            }
+            tx.commit()?;
+            info!(
+                "Forgot {} entities, {} relationships and {} embeddings",
+                report.entities.len(),
+                report.relationships,
+                report.embeddings
+            );
+            Ok(report)
+        })
+        .await
+        .context("spawn_blocking task panicked")?
+    }
+
+    /// Whether an entity with this name was forgotten
+    pub async fn is_forgotten(&self, name: &str) -> Result<bool> {
+        let conn = Arc::clone(&self.conn);
+        let name = name.trim().to_owned();
+
+        tokio::task::spawn_blocking(move || {
+            let conn = conn.lock().unwrap_or_else(|poisoned| {
+                warn!("Database mutex was poisoned, recovering");
+                poisoned.into_inner()
+            });
+            Ok(Self::name_forgotten(&conn, &name)?)
+        })
+        .await
+        .context("spawn_blocking task panicked")?
+    }
+
+    fn name_forgotten(conn: &Connection, name: &str) -> rusqlite::Result<bool> {
+        let found = conn
+            .query_row(
+                "SELECT 1 FROM forgotten WHERE name = ?1 LIMIT 1",
+                params![name.trim()],
+                |_| Ok(()),
+            )
+            .optional()?;
+        Ok(found.is_some())
+    }
+
+    /// Get all entities (capped to prevent OOM on large databases)
+    pub async fn get_all_entities(&self) -> Result<Vec<Entity>> {
+        let conn = Arc::clone(&self.conn);
+
+        tokio::task::spawn_blocking(move || {
+            let conn = conn.lock().unwrap_or_else(|poisoned| {
+                warn!("Database mutex was poisoned, recovering");
+                poisoned.into_inner()
+            });
+            let mut stmt = conn.prepare(&format!(
+                "SELECT {} FROM entities ORDER BY updated_at DESC LIMIT 50000",
+                ENTITY_COLUMNS
+            ))?;
 
             let entities = stmt
                 .query_map([], Self::row_to_entity)?
@@ -576,6 +1490,187 @@ impl KnowledgeDb {
         .context("spawn_blocking task panicked")?
     }
 
+    /// Get entities created between two dates (YYYY-MM-DD, inclusive),
+    /// optionally of one type
+    pub async fn get_entities_created(
+        &self,
+        entity_type: Option<&str>,
+        start: &str,
+        end: &str,
+    ) -> Result<Vec<Entity>> {
+        let conn = Arc::clone(&self.conn);
+        let entity_type = entity_type.map(String::from);
+        let start = start.to_owned();
+        let end = end.to_owned();
+
+        tokio::task::spawn_blocking(move || {
+            let conn = conn.lock().unwrap_or_else(|poisoned| {
+                warn!("Database mutex was poisoned, recovering");
+                poisoned.into_inner()
+            });
+            let mut stmt = conn.prepare(&format!(
+                "SELECT {}
+                 FROM entities
+                 WHERE date(created_at) >= ?1 AND date(created_at) <= ?2
+                   AND (?3 IS NULL OR entity_type = ?3)
+                 ORDER BY created_at ASC",
+                ENTITY_COLUMNS
+            ))?;
+
+            let entities = stmt
+                .query_map(params![start, end, entity_type], Self::row_to_entity)?
+                .collect::<Result<Vec<_>, _>>()?;
+
+            Ok(entities)
+        })
+        .await
+        .context("spawn_blocking task panicked")?
+    }
+
+    /// Give an entity another name. Returns false if it already had it.
+    pub async fn add_alias(&self, entity_id: &str, alias: &str) -> Result<bool> {
+        let conn = Arc::clone(&self.conn);
+        let entity_id = entity_id.to_owned();
+        let alias = alias.trim().to_owned();
+        if alias.is_empty() || alias.chars().count() > 100 {
+            anyhow::bail!("Alias must be 1-100 characters");
+        }
+
+        tokio::task::spawn_blocking(move || {
+            let conn = conn.lock().unwrap_or_else(|poisoned| {
+                warn!("Database mutex was poisoned, recovering");
+                poisoned.into_inner()
+            });
+            let name: String = conn
+                .query_row(
+                    "SELECT name FROM entities WHERE id = ?1",
+                    params![&entity_id],
+                    |row| row.get(0),
+                )
+                .optional()?
+                .with_context(|| format!("Entity {} not found", entity_id))?;
+            if name.eq_ignore_ascii_case(&alias) {
+                return Ok(false);
+            }
+
+            let now = Utc::now().to_rfc3339();
+            let tx = conn.unchecked_transaction()?;
+            let added = tx.execute(
+                "INSERT OR IGNORE INTO entity_aliases (entity_id, alias, created_at)
+                 VALUES (?1, ?2, ?3)",
+                params![&entity_id, &alias, &now],
+            )? > 0;
+            if added {
+                // Bump the entity so replication carries the new alias
+                tx.execute(
+                    "UPDATE entities SET updated_at = ?1 WHERE id = ?2",
+                    params![&now, &entity_id],
+                )?;
+            }
+            tx.commit()?;
+            debug!(
+                "Alias '{}' for entity {}: added={}",
+                alias, entity_id, added
+            );
+            Ok(added)
+        })
+        .await
+        .context("spawn_blocking task panicked")?
+    }
+
+    /// Remove one of an entity's aliases. Returns false if it didn't have it.
+    pub async fn remove_alias(&self, entity_id: &str, alias: &str) -> Result<bool> {
+        let conn = Arc::clone(&self.conn);
+        let entity_id = entity_id.to_owned();
+        let alias = alias.trim().to_owned();
+
+        tokio::task::spawn_blocking(move || {
+            let conn = conn.lock().unwrap_or_else(|poisoned| {
+                warn!("Database mutex was poisoned, recovering");
+                poisoned.into_inner()
+            });
+            let tx = conn.unchecked_transaction()?;
+            let removed = tx.execute(
+                "DELETE FROM entity_aliases WHERE entity_id = ?1 AND alias = ?2",
+                params![&entity_id, &alias],
+            )? > 0;
+            if removed {
+                tx.execute(
+                    "UPDATE entities SET updated_at = ?1 WHERE id = ?2",
+                    params![Utc::now().to_rfc3339(), &entity_id],
+                )?;
+            }
+            tx.commit()?;
+            Ok(removed)
+        })
+        .await
+        .context("spawn_blocking task panicked")?
+    }
+
+    /// Entities with this alias (case-insensitive)
+    pub async fn find_entities_by_alias(&self, alias: &str) -> Result<Vec<Entity>> {
+        let conn = Arc::clone(&self.conn);
+        let alias = alias.trim().to_owned();
+
+        tokio::task::spawn_blocking(move || {
+            let conn = conn.lock().unwrap_or_else(|poisoned| {
+                warn!("Database mutex was poisoned, recovering");
+                poisoned.into_inner()
+            });
+            let mut stmt = conn.prepare(&format!(
+                "SELECT {} FROM entities
+                 WHERE id IN (SELECT entity_id FROM entity_aliases WHERE alias = ?1)
+                 ORDER BY updated_at DESC",
+                ENTITY_COLUMNS
+            ))?;
+            let entities = stmt
+                .query_map(params![&alias], Self::row_to_entity)?
+                .collect::<Result<Vec<_>, _>>()?;
+            Ok(entities)
+        })
+        .await
+        .context("spawn_blocking task panicked")?
+    }
+
+    /// Find the entity meant by an ID, exact name or alias (case-insensitive),
+    /// in that order. The most recently updated wins among equal names.
+    pub async fn resolve_entity(&self, name_or_id: &str) -> Result<Option<Entity>> {
+        if let Some(entity) = self.get_entity(name_or_id).await? {
+            return Ok(Some(entity));
+        }
+        let conn = Arc::clone(&self.conn);
+        let name = name_or_id.trim().to_owned();
+
+        let by_name = tokio::task::spawn_blocking(move || -> Result<Option<Entity>> {
+            let conn = conn.lock().unwrap_or_else(|poisoned| {
+                warn!("Database mutex was poisoned, recovering");
+                poisoned.into_inner()
+            });
+            Ok(conn
+                .query_row(
+                    &format!(
+                        "SELECT {} FROM entities
+                         WHERE name = ?1 COLLATE NOCASE AND entity_type != 'document_chunk'
+                         ORDER BY updated_at DESC LIMIT 1",
+                        ENTITY_COLUMNS
+                    ),
+                    params![&name],
+                    Self::row_to_entity,
+                )
+                .optional()?)
+        })
+        .await
+        .context("spawn_blocking task panicked")??;
+        if by_name.is_some() {
+            return Ok(by_name);
+        }
+        Ok(self
+            .find_entities_by_alias(name_or_id)
+            .await?
+            .into_iter()
+            .next())
+    }
+
     /// Helper to convert row to Entity
     fn row_to_entity(row: &rusqlite::Row) -> rusqlite::Result<Entity> {
         let metadata_str: Option<String> = row.get(3)?;
@@ -590,11 +1685,17 @@ impl KnowledgeDb {
                 )
             })?;
 
+        let aliases = row
+            .get::<_, Option<String>>(6)?
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_default();
+
         Ok(Entity {
             id: row.get(0)?,
             name: row.get(1)?,
             entity_type: row.get(2)?,
             metadata,
+            aliases,
             created_at: row
                 .get::<_, String>(4)?
                 .parse()
@@ -648,121 +1749,391 @@ impl KnowledgeDb {
         .context("spawn_blocking task panicked")?
     }
 
-    /// Get relationships for an entity
-    pub async fn get_relationships_for(&self, entity_id: &str) -> Result<Vec<Relationship>> {
+    /// Insert several entities in one transaction, returning their IDs in order
+    pub async fn add_entities(&self, entities: Vec<NewEntity>) -> Result<Vec<String>> {
+        let batch = self.remember_many(entities, Vec::new()).await?;
+        Ok(batch.entities.into_iter().map(|e| e.id).collect())
+    }
+
+    /// Insert several relationships in one transaction, returning their IDs
+    /// in order. Fails without writing anything if an endpoint is missing.
+    pub async fn add_relationships(
+        &self,
+        relationships: Vec<NewRelationship>,
+    ) -> Result<Vec<String>> {
+        let batch = self.remember_many(Vec::new(), relationships).await?;
+        Ok(batch.relationships.into_iter().map(|r| r.id).collect())
+    }
+
+    /// Insert entities and the relationships between them in one
+    /// transaction. Relationship endpoints are resolved against the batch's
+    /// entity names (case-insensitive) first, then existing entity IDs;
+    /// anything unresolved rolls back the whole batch.
+    pub async fn remember_many(
+        &self,
+        entities: Vec<NewEntity>,
+        relationships: Vec<NewRelationship>,
+    ) -> Result<RememberedBatch> {
         let conn = Arc::clone(&self.conn);
-        let entity_id = entity_id.to_owned();
 
         tokio::task::spawn_blocking(move || {
             let conn = conn.lock().unwrap_or_else(|poisoned| {
                 warn!("Database mutex was poisoned, recovering");
                 poisoned.into_inner()
             });
-            let mut stmt = conn.prepare(
-                "SELECT id, source_id, target_id, relation_type, metadata, created_at
-                 FROM relationships
-                 WHERE source_id = ?1 OR target_id = ?1
-                 ORDER BY created_at DESC",
+            let tx = conn.unchecked_transaction()?;
+            let batch = Self::insert_batch(&tx, entities, relationships)?;
+            tx.commit()?;
+            debug!(
+                "Inserted {} entities and {} relationships in one batch",
+                batch.entities.len(),
+                batch.relationships.len()
+            );
+            Ok(batch)
+        })
+        .await
+        .context("spawn_blocking task panicked")?
+    }
+
+    fn insert_batch(
+        tx: &rusqlite::Transaction,
+        entities: Vec<NewEntity>,
+        relationships: Vec<NewRelationship>,
+    ) -> Result<RememberedBatch> {
+        let now = Utc::now();
+        let mut batch = RememberedBatch::default();
+
+        {
+            let mut insert = tx.prepare(
+                "INSERT INTO entities (id, name, entity_type, metadata, created_at, updated_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
             )?;
+            for entity in entities {
+                let id = Uuid::new_v4().to_string();
+                let metadata_json = entity
+                    .metadata
+                    .as_ref()
+                    .map(serde_json::to_string)
+                    .transpose()?;
+                insert.execute(params![
+                    &id,
+                    &entity.name,
+                    &entity.entity_type,
+                    metadata_json,
+                    now.to_rfc3339(),
+                    now.to_rfc3339(),
+                ])?;
+                batch.entities.push(Entity {
+                    id,
+                    name: entity.name,
+                    entity_type: entity.entity_type,
+                    metadata: entity.metadata,
+                    aliases: Vec::new(),
+                    created_at: now,
+                    updated_at: now,
+                });
+            }
+        }
+
+        {
+            let mut exists = tx.prepare("SELECT 1 FROM entities WHERE id = ?1")?;
+            let mut resolve = |endpoint: &str| -> Result<String> {
+                if let Some(entity) = batch
+                    .entities
+                    .iter()
+                    .find(|e| e.name.eq_ignore_ascii_case(endpoint.trim()))
+                {
+                    return Ok(entity.id.clone());
+                }
+                if exists.exists(params![endpoint])? {
+                    return Ok(endpoint.to_string());
+                }
+                anyhow::bail!("No entity named or with ID '{}'", endpoint)
+            };
+            let mut resolved = Vec::with_capacity(relationships.len());
+            for rel in relationships {
+                resolved.push((resolve(&rel.source)?, resolve(&rel.target)?, rel));
+            }
+            drop(exists);
 
-            let relationships = stmt
-                .query_map(params![&entity_id], |row| {
-                    let metadata_str: Option<String> = row.get(4)?;
-                    let metadata = metadata_str
-                        .map(|s| serde_json::from_str(&s))
-                        .transpose()
-                        .map_err(|e| {
-                            rusqlite::Error::FromSqlConversionFailure(
-                                4,
-                                rusqlite::types::Type::Text,
-                                Box::new(e),
-                            )
-                        })?;
-
-                    Ok(Relationship {
-                        id: row.get(0)?,
-                        source_id: row.get(1)?,
-                        target_id: row.get(2)?,
-                        relation_type: row.get(3)?,
-                        metadata,
-                        created_at: row
-                            .get::<_, String>(5)?
-                            .parse()
-                            .unwrap_or_else(|_| Utc::now()),
-                    })
-                })?
-                .collect::<Result<Vec<_>, _>>()?;
+            let mut insert = tx.prepare(
+                "INSERT INTO relationships (id, source_id, target_id, relation_type, metadata, created_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            )?;
+            for (source_id, target_id, rel) in resolved {
+                let id = Uuid::new_v4().to_string();
+                let metadata_json = rel
+                    .metadata
+                    .as_ref()
+                    .map(serde_json::to_string)
+                    .transpose()?;
+                insert.execute(params![
+                    &id,
+                    &source_id,
+                    &target_id,
+                    &rel.relation_type,
+                    metadata_json,
+                    now.to_rfc3339(),
+                ])?;
+                batch.relationships.push(Relationship {
+                    id,
+                    source_id,
+                    target_id,
+                    relation_type: rel.relation_type,
+                    metadata: rel.metadata,
+                    created_at: now,
+                });
+            }
+        }
 
-            Ok(relationships)
+        Ok(batch)
+    }
+
+    /// The most recently ingested document from a source path
+    pub async fn find_document_by_source(&self, source_path: &str) -> Result<Option<Entity>> {
+        let conn = Arc::clone(&self.conn);
+        let source_path = source_path.to_owned();
+
+        tokio::task::spawn_blocking(move || {
+            let conn = conn.lock().unwrap_or_else(|poisoned| {
+                warn!("Database mutex was poisoned, recovering");
+                poisoned.into_inner()
+            });
+            let entity = conn
+                .query_row(
+                    &format!(
+                        "SELECT {} FROM entities
+                         WHERE entity_type = 'document'
+                           AND json_extract(metadata, '$.source_path') = ?1
+                         ORDER BY updated_at DESC LIMIT 1",
+                        ENTITY_COLUMNS
+                    ),
+                    params![&source_path],
+                    Self::row_to_entity,
+                )
+                .optional()?;
+            Ok(entity)
         })
         .await
         .context("spawn_blocking task panicked")?
     }
 
-    /// Insert a conversation
-    pub async fn insert_conversation(
-        &self,
-        channel: &str,
-        sender: &str,
-        content: &str,
-        metadata: Option<JsonValue>,
-    ) -> Result<String> {
+    /// The chunks of an ingested document, in document order
+    pub async fn get_document_chunks(&self, document_id: &str) -> Result<Vec<Entity>> {
         let conn = Arc::clone(&self.conn);
-        let channel = channel.to_owned();
-        let sender = sender.to_owned();
-        let content = content.to_owned();
+        let document_id = document_id.to_owned();
 
         tokio::task::spawn_blocking(move || {
-            let id = Uuid::new_v4().to_string();
-            let now = Utc::now();
-            let metadata_json = metadata.map(|m| serde_json::to_string(&m)).transpose()?;
             let conn = conn.lock().unwrap_or_else(|poisoned| {
                 warn!("Database mutex was poisoned, recovering");
                 poisoned.into_inner()
             });
-
-            conn.execute(
-                "INSERT INTO conversations (id, channel, sender, content, metadata, created_at)
-                 VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
-                params![
-                    &id,
-                    &channel,
-                    &sender,
-                    &content,
-                    metadata_json,
-                    now.to_rfc3339(),
-                ],
-            )?;
-
-            debug!("Inserted conversation in channel {}", channel);
-            Ok(id)
+            let mut stmt = conn.prepare(&format!(
+                "SELECT {} FROM entities
+                 WHERE id IN (SELECT target_id FROM relationships
+                              WHERE source_id = ?1 AND relation_type = 'contains_chunk')
+                 ORDER BY json_extract(metadata, '$.chunk_index')",
+                ENTITY_COLUMNS
+            ))?;
+            let chunks = stmt
+                .query_map(params![&document_id], Self::row_to_entity)?
+                .collect::<Result<Vec<_>, _>>()?;
+            Ok(chunks)
         })
         .await
         .context("spawn_blocking task panicked")?
     }
 
-    /// Get recent conversations
-    pub async fn get_recent_conversations(
-        &self,
-        channel: Option<&str>,
-        limit: usize,
-    ) -> Result<Vec<Conversation>> {
+    /// Apply a document revision in one transaction: update the document,
+    /// drop removed chunks (with their links) and the old `next_chunk`
+    /// chain, update kept chunks, then insert new chunks and links. Returns
+    /// the document, updated and new chunks, and the new links.
+    pub async fn revise_document(&self, revision: DocumentRevision) -> Result<RememberedBatch> {
         let conn = Arc::clone(&self.conn);
-        let channel = channel.map(|s| s.to_owned());
+
         tokio::task::spawn_blocking(move || {
+            let now = Utc::now().to_rfc3339();
             let conn = conn.lock().unwrap_or_else(|poisoned| {
                 warn!("Database mutex was poisoned, recovering");
                 poisoned.into_inner()
             });
-            let (sql, params_vec): (String, Vec<String>) = if let Some(ref ch) = channel {
-                (
-                    "SELECT id, channel, sender, content, metadata, created_at
-                     FROM conversations
-                     WHERE channel = ?1
-                     ORDER BY created_at DESC
-                     LIMIT ?2"
-                        .to_string(),
-                    vec![ch.to_string(), limit.to_string()],
+            let tx = conn.unchecked_transaction()?;
+            let doc_id = revision.document_id;
+            let to_json = |metadata: &Option<JsonValue>| {
+                metadata.as_ref().map(serde_json::to_string).transpose()
+            };
+
+            let updated = tx.execute(
+                "UPDATE entities SET name = ?1, metadata = ?2, updated_at = ?3 WHERE id = ?4",
+                params![&revision.name, to_json(&revision.metadata)?, &now, &doc_id],
+            )?;
+            if updated == 0 {
+                anyhow::bail!("Document {} not found", doc_id);
+            }
+            tx.execute(
+                "DELETE FROM relationships WHERE relation_type = 'next_chunk' AND source_id IN
+                 (SELECT target_id FROM relationships
+                  WHERE source_id = ?1 AND relation_type = 'contains_chunk')",
+                params![&doc_id],
+            )?;
+            {
+                let mut delete = tx.prepare("DELETE FROM entities WHERE id = ?1")?;
+                for id in &revision.remove {
+                    delete.execute(params![id])?;
+                }
+                let mut update = tx.prepare(
+                    "UPDATE entities SET name = ?1, metadata = ?2, updated_at = ?3 WHERE id = ?4",
+                )?;
+                for (id, chunk) in &revision.update {
+                    update.execute(params![&chunk.name, to_json(&chunk.metadata)?, &now, id])?;
+                }
+            }
+
+            let mut batch = Self::insert_batch(&tx, revision.add, revision.relationships)?;
+            {
+                let mut get = tx.prepare(&format!(
+                    "SELECT {} FROM entities WHERE id = ?1",
+                    ENTITY_COLUMNS
+                ))?;
+                let ids = std::iter::once(&doc_id).chain(revision.update.iter().map(|(id, _)| id));
+                for (i, id) in ids.enumerate() {
+                    batch
+                        .entities
+                        .insert(i, get.query_row(params![id], Self::row_to_entity)?);
+                }
+            }
+            tx.commit()?;
+            debug!(
+                "Revised document {}: {} chunks removed, {} kept, {} added",
+                doc_id,
+                revision.remove.len(),
+                revision.update.len(),
+                batch.entities.len() - revision.update.len() - 1
+            );
+            Ok(batch)
+        })
+        .await
+        .context("spawn_blocking task panicked")?
+    }
+
+    /// Get relationships for an entity
+    pub async fn get_relationships_for(&self, entity_id: &str) -> Result<Vec<Relationship>> {
+        let conn = Arc::clone(&self.conn);
+        let entity_id = entity_id.to_owned();
+
+        tokio::task::spawn_blocking(move || {
+            let conn = conn.lock().unwrap_or_else(|poisoned| {
+                warn!("Database mutex was poisoned, recovering");
+                poisoned.into_inner()
+            });
+            let mut stmt = conn.prepare(
+                "SELECT id, source_id, target_id, relation_type, metadata, created_at
+                 FROM relationships
+                 WHERE source_id = ?1 OR target_id = ?1
+                 ORDER BY created_at DESC",
+            )?;
+
+            let relationships = stmt
+                .query_map(params![&entity_id], Self::row_to_relationship)?
+                .collect::<Result<Vec<_>, _>>()?;
+
+            Ok(relationships)
+        })
+        .await
+        .context("spawn_blocking task panicked")?
+    }
+
+    /// Helper to convert row to Relationship
+    fn row_to_relationship(row: &rusqlite::Row) -> rusqlite::Result<Relationship> {
+        let metadata_str: Option<String> = row.get(4)?;
+        let metadata = metadata_str
+            .map(|s| serde_json::from_str(&s))
+            .transpose()
+            .map_err(|e| {
+                rusqlite::Error::FromSqlConversionFailure(
+                    4,
+                    rusqlite::types::Type::Text,
+                    Box::new(e),
+                )
+            })?;
+
+        Ok(Relationship {
+            id: row.get(0)?,
+            source_id: row.get(1)?,
+            target_id: row.get(2)?,
+            relation_type: row.get(3)?,
+            metadata,
+            created_at: row
+                .get::<_, String>(5)?
+                .parse()
+                .unwrap_or_else(|_| Utc::now()),
+        })
+    }
+
+    /// Insert a conversation
+    pub async fn insert_conversation(
+        &self,
+        channel: &str,
+        sender: &str,
+        content: &str,
+        metadata: Option<JsonValue>,
+    ) -> Result<String> {
+        let conn = Arc::clone(&self.conn);
+        let channel = channel.to_owned();
+        let sender = sender.to_owned();
+        let content = content.to_owned();
+
+        tokio::task::spawn_blocking(move || {
+            let id = Uuid::new_v4().to_string();
+            let now = Utc::now();
+            let metadata_json = metadata.map(|m| serde_json::to_string(&m)).transpose()?;
+            let conn = conn.lock().unwrap_or_else(|poisoned| {
+                warn!("Database mutex was poisoned, recovering");
+                poisoned.into_inner()
+            });
+
+            conn.execute(
+                "INSERT INTO conversations (id, channel, sender, content, metadata, created_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+                params![
+                    &id,
+                    &channel,
+                    &sender,
+                    &content,
+                    metadata_json,
+                    now.to_rfc3339(),
+                ],
+            )?;
+
+            debug!("Inserted conversation in channel {}", channel);
+            Ok(id)
+        })
+        .await
+        .context("spawn_blocking task panicked")?
+    }
+
+    /// Get recent conversations
+    pub async fn get_recent_conversations(
+        &self,
+        channel: Option<&str>,
+        limit: usize,
+    ) -> Result<Vec<Conversation>> {
+        let conn = Arc::clone(&self.conn);
+        let channel = channel.map(|s| s.to_owned());
+        tokio::task::spawn_blocking(move || {
+            let conn = conn.lock().unwrap_or_else(|poisoned| {
+                warn!("Database mutex was poisoned, recovering");
+                poisoned.into_inner()
+            });
+            let (sql, params_vec): (String, Vec<String>) = if let Some(ref ch) = channel {
+                (
+                    "SELECT id, channel, sender, content, metadata, created_at
+                     FROM conversations
+                     WHERE channel = ?1
+                     ORDER BY created_at DESC
+                     LIMIT ?2"
+                        .to_string(),
+                    vec![ch.to_string(), limit.to_string()],
                 )
             } else {
                 (
@@ -793,6 +2164,160 @@ impl KnowledgeDb {
         .context("spawn_blocking task panicked")?
     }
 
+    /// Conversations whose content mentions `query` (case-insensitive), newest first
+    pub async fn search_conversations(
+        &self,
+        query: &str,
+        limit: usize,
+    ) -> Result<Vec<Conversation>> {
+        let conn = Arc::clone(&self.conn);
+        let pattern = format!("%{}%", query);
+
+        tokio::task::spawn_blocking(move || {
+            let conn = conn.lock().unwrap_or_else(|poisoned| {
+                warn!("Database mutex was poisoned, recovering");
+                poisoned.into_inner()
+            });
+            let mut stmt = conn.prepare(
+                "SELECT id, channel, sender, content, metadata, created_at
+                 FROM conversations
+                 WHERE content LIKE ?1
+                 ORDER BY created_at DESC
+                 LIMIT ?2",
+            )?;
+
+            let conversations = stmt
+                .query_map(params![pattern, limit as i64], Self::row_to_conversation)?
+                .collect::<Result<Vec<_>, _>>()?;
+
+            Ok(conversations)
+        })
+        .await
+        .context("spawn_blocking task panicked")?
+    }
+
+    /// Conversations with a tag containing `tag` (in `metadata.tags`), newest first
+    pub async fn get_conversations_by_tag(
+        &self,
+        tag: &str,
+        limit: usize,
+    ) -> Result<Vec<Conversation>> {
+        let conn = Arc::clone(&self.conn);
+        let tag_pattern = format!("%{}%", tag);
+
+        tokio::task::spawn_blocking(move || {
+            let conn = conn.lock().unwrap_or_else(|poisoned| {
+                warn!("Database mutex was poisoned, recovering");
+                poisoned.into_inner()
+            });
+            let mut stmt = conn.prepare(
+                "SELECT id, channel, sender, content, metadata, created_at
+                 FROM conversations
+                 WHERE EXISTS (
+                     SELECT 1 FROM json_each(
+                         CASE WHEN json_valid(conversations.metadata) THEN conversations.metadata END,
+                         '$.tags'
+                     ) WHERE value LIKE ?1)
+                 ORDER BY created_at DESC
+                 LIMIT ?2",
+            )?;
+
+            let conversations = stmt
+                .query_map(params![tag_pattern, limit as i64], Self::row_to_conversation)?
+                .collect::<Result<Vec<_>, _>>()?;
+
+            Ok(conversations)
+        })
+        .await
+        .context("spawn_blocking task panicked")?
+    }
+
+    /// Replace a conversation row's metadata
+    pub async fn update_conversation_metadata(&self, id: &str, metadata: JsonValue) -> Result<()> {
+        let conn = Arc::clone(&self.conn);
+        let id = id.to_owned();
+
+        tokio::task::spawn_blocking(move || {
+            let metadata_json = serde_json::to_string(&metadata)?;
+            let conn = conn.lock().unwrap_or_else(|poisoned| {
+                warn!("Database mutex was poisoned, recovering");
+                poisoned.into_inner()
+            });
+            conn.execute(
+                "UPDATE conversations SET metadata = ?1 WHERE id = ?2",
+                params![metadata_json, &id],
+            )?;
+            Ok(())
+        })
+        .await
+        .context("spawn_blocking task panicked")?
+    }
+
+    /// Conversations whose metadata has `value` at a JSON path (e.g. `$.experiment.name`),
+    /// oldest first
+    pub async fn get_conversations_by_metadata(
+        &self,
+        path: &str,
+        value: &str,
+    ) -> Result<Vec<Conversation>> {
+        let conn = Arc::clone(&self.conn);
+        let path = path.to_owned();
+        let value = value.to_owned();
+
+        tokio::task::spawn_blocking(move || {
+            let conn = conn.lock().unwrap_or_else(|poisoned| {
+                warn!("Database mutex was poisoned, recovering");
+                poisoned.into_inner()
+            });
+            let mut stmt = conn.prepare(
+                "SELECT id, channel, sender, content, metadata, created_at
+                 FROM conversations
+                 WHERE json_valid(metadata) AND json_extract(metadata, ?1) = ?2
+                 ORDER BY created_at ASC",
+            )?;
+            let conversations = stmt
+                .query_map(params![&path, &value], Self::row_to_conversation)?
+                .collect::<Result<Vec<_>, _>>()?;
+            Ok(conversations)
+        })
+        .await
+        .context("spawn_blocking task panicked")?
+    }
+
+    /// Every tag in use on entities and conversations, with how many items carry it
+    pub async fn get_tag_counts(&self) -> Result<Vec<(String, usize)>> {
+        let conn = Arc::clone(&self.conn);
+
+        tokio::task::spawn_blocking(move || {
+            let conn = conn.lock().unwrap_or_else(|poisoned| {
+                warn!("Database mutex was poisoned, recovering");
+                poisoned.into_inner()
+            });
+            let mut stmt = conn.prepare(
+                "SELECT tag, COUNT(*) AS n FROM (
+                     SELECT j.value AS tag FROM entities e, json_each(
+                         CASE WHEN json_valid(e.metadata) THEN e.metadata END, '$.tags') j
+                     UNION ALL
+                     SELECT j.value AS tag FROM conversations c, json_each(
+                         CASE WHEN json_valid(c.metadata) THEN c.metadata END, '$.tags') j
+                 )
+                 WHERE typeof(tag) = 'text'
+                 GROUP BY tag
+                 ORDER BY n DESC, tag ASC",
+            )?;
+
+            let counts = stmt
+                .query_map([], |row| {
+                    Ok((row.get::<_, String>(0)?, row.get::<_, i64>(1)? as usize))
+                })?
+                .collect::<Result<Vec<_>, _>>()?;
+
+            Ok(counts)
+        })
+        .await
+        .context("spawn_blocking task panicked")?
+    }
+
     /// Helper to convert row to Conversation
     fn row_to_conversation(row: &rusqlite::Row) -> rusqlite::Result<Conversation> {
         let metadata_str: Option<String> = row.get(4)?;
@@ -976,43 +2501,205 @@ impl KnowledgeDb {
         .context("spawn_blocking task panicked")?
     }
 
-    /// Insert a new goal
-    pub async fn insert_goal(
+    /// Insert a new automation rule
+    #[allow(clippy::too_many_arguments)]
+    pub async fn insert_rule(
         &self,
-        description: &str,
-        priority: i32,
-        check_interval_secs: i64,
-        success_criteria: Option<&str>,
-        source_channel: Option<&str>,
-        source: &str,
+        name: &str,
+        event: &str,
+        filter: JsonValue,
+        condition: Option<&str>,
+        action: JsonValue,
+        reply_channel: &str,
+        exclusive: bool,
     ) -> Result<String> {
         let conn = Arc::clone(&self.conn);
-        let description = description.to_owned();
-        let success_criteria = success_criteria.map(|s| s.to_owned());
-        let source_channel = source_channel.map(|s| s.to_owned());
-        let source = source.to_owned();
+        let name = name.to_owned();
+        let event = event.to_owned();
+        let condition = condition.map(str::to_owned);
+        let reply_channel = reply_channel.to_owned();
 
         tokio::task::spawn_blocking(move || {
-            let id = Uuid::new_v4().to_string();
-            let now = Utc::now();
+            let id = format!("r-{}", Uuid::new_v4());
             let conn = conn.lock().unwrap_or_else(|poisoned| {
                 warn!("Database mutex was poisoned, recovering");
                 poisoned.into_inner()
             });
             conn.execute(
-                "INSERT INTO goals (id, description, status, priority, success_criteria, check_interval_secs, source_channel, source, created_at, updated_at)
-                 VALUES (?1, ?2, 'active', ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
-                params![&id, &description, priority, success_criteria, check_interval_secs, source_channel, &source, now.to_rfc3339(), now.to_rfc3339()],
+                "INSERT INTO rules (id, name, event, filter, condition, action, reply_channel,
+                                    exclusive, enabled, created_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, 1, ?9)",
+                params![
+                    &id,
+                    &name,
+                    &event,
+                    serde_json::to_string(&filter)?,
+                    &condition,
+                    serde_json::to_string(&action)?,
+                    &reply_channel,
+                    exclusive as i64,
+                    Utc::now().to_rfc3339(),
+                ],
             )?;
-            debug!("Inserted goal: {} ({})", description, id);
+            debug!("Inserted rule: {} ({})", name, id);
             Ok(id)
         })
         .await
         .context("spawn_blocking task panicked")?
     }
 
-    /// Get active goals that are due for checking
-    pub async fn get_due_goals(&self) -> Result<Vec<Goal>> {
+    /// All rules, oldest first; only enabled ones if `enabled_only`
+    pub async fn get_rules(&self, enabled_only: bool) -> Result<Vec<Rule>> {
+        let conn = Arc::clone(&self.conn);
+
+        tokio::task::spawn_blocking(move || {
+            let conn = conn.lock().unwrap_or_else(|poisoned| {
+                warn!("Database mutex was poisoned, recovering");
+                poisoned.into_inner()
+            });
+            let mut stmt = conn.prepare(
+                "SELECT id, name, event, filter, action, reply_channel, exclusive, enabled,
+                        fire_count, last_fired_at, created_at, condition
+                 FROM rules
+                 WHERE enabled = 1 OR ?1 = 0
+                 ORDER BY created_at ASC",
+            )?;
+            let rules = stmt
+                .query_map(params![enabled_only as i64], Self::row_to_rule)?
+                .collect::<Result<Vec<_>, _>>()?;
+            Ok(rules)
+        })
+        .await
+        .context("spawn_blocking task panicked")?
+    }
+
+    fn row_to_rule(row: &rusqlite::Row) -> rusqlite::Result<Rule> {
+        let json = |idx: usize| -> rusqlite::Result<JsonValue> {
+            let text: String = row.get(idx)?;
+            serde_json::from_str(&text).map_err(|e| {
+                rusqlite::Error::FromSqlConversionFailure(
+                    idx,
+                    rusqlite::types::Type::Text,
+                    Box::new(e),
+                )
+            })
+        };
+        Ok(Rule {
+            id: row.get(0)?,
+            name: row.get(1)?,
+            event: row.get(2)?,
+            filter: json(3)?,
+            condition: row.get(11)?,
+            action: json(4)?,
+            reply_channel: row.get(5)?,
+            exclusive: row.get::<_, i64>(6)? != 0,
+            enabled: row.get::<_, i64>(7)? != 0,
+            fire_count: row.get(8)?,
+            last_fired_at: row
+                .get::<_, Option<String>>(9)?
+                .and_then(|s| s.parse().ok()),
+            created_at: row
+                .get::<_, String>(10)?
+                .parse()
+                .unwrap_or_else(|_| Utc::now()),
+        })
+    }
+
+    /// Enable or disable a rule; false if it doesn't exist
+    pub async fn set_rule_enabled(&self, id: &str, enabled: bool) -> Result<bool> {
+        let conn = Arc::clone(&self.conn);
+        let id = id.to_owned();
+
+        tokio::task::spawn_blocking(move || {
+            let conn = conn.lock().unwrap_or_else(|poisoned| {
+                warn!("Database mutex was poisoned, recovering");
+                poisoned.into_inner()
+            });
+            let changed = conn.execute(
+                "UPDATE rules SET enabled = ?1 WHERE id = ?2",
+                params![enabled as i64, &id],
+            )?;
+            Ok(changed > 0)
+        })
+        .await
+        .context("spawn_blocking task panicked")?
+    }
+
+    /// Count a rule firing
+    pub async fn record_rule_fired(&self, id: &str) -> Result<()> {
+        let conn = Arc::clone(&self.conn);
+        let id = id.to_owned();
+
+        tokio::task::spawn_blocking(move || {
+            let conn = conn.lock().unwrap_or_else(|poisoned| {
+                warn!("Database mutex was poisoned, recovering");
+                poisoned.into_inner()
+            });
+            conn.execute(
+                "UPDATE rules SET fire_count = fire_count + 1, last_fired_at = ?1 WHERE id = ?2",
+                params![Utc::now().to_rfc3339(), &id],
+            )?;
+            Ok(())
+        })
+        .await
+        .context("spawn_blocking task panicked")?
+    }
+
+    /// Delete a rule; false if it doesn't exist
+    pub async fn delete_rule(&self, id: &str) -> Result<bool> {
+        let conn = Arc::clone(&self.conn);
+        let id = id.to_owned();
+
+        tokio::task::spawn_blocking(move || {
+            let conn = conn.lock().unwrap_or_else(|poisoned| {
+                warn!("Database mutex was poisoned, recovering");
+                poisoned.into_inner()
+            });
+            let deleted = conn.execute("DELETE FROM rules WHERE id = ?1", params![&id])?;
+            debug!("Deleted rule {}", id);
+            Ok(deleted > 0)
+        })
+        .await
+        .context("spawn_blocking task panicked")?
+    }
+
+    /// Insert a new goal
+    pub async fn insert_goal(
+        &self,
+        description: &str,
+        priority: i32,
+        check_interval_secs: i64,
+        success_criteria: Option<&str>,
+        source_channel: Option<&str>,
+        source: &str,
+    ) -> Result<String> {
+        let conn = Arc::clone(&self.conn);
+        let description = description.to_owned();
+        let success_criteria = success_criteria.map(|s| s.to_owned());
+        let source_channel = source_channel.map(|s| s.to_owned());
+        let source = source.to_owned();
+
+        tokio::task::spawn_blocking(move || {
+            let id = Uuid::new_v4().to_string();
+            let now = Utc::now();
+            let conn = conn.lock().unwrap_or_else(|poisoned| {
+                warn!("Database mutex was poisoned, recovering");
+                poisoned.into_inner()
+            });
+            conn.execute(
+                "INSERT INTO goals (id, description, status, priority, success_criteria, check_interval_secs, source_channel, source, created_at, updated_at)
+                 VALUES (?1, ?2, 'active', ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+                params![&id, &description, priority, success_criteria, check_interval_secs, source_channel, &source, now.to_rfc3339(), now.to_rfc3339()],
+            )?;
+            debug!("Inserted goal: {} ({})", description, id);
+            Ok(id)
+        })
+        .await
+        .context("spawn_blocking task panicked")?
+    }
+
+    /// Get active goals that are due for checking
+    pub async fn get_due_goals(&self) -> Result<Vec<Goal>> {
         let conn = Arc::clone(&self.conn);
 
         tokio::task::spawn_blocking(move || {
@@ -1022,7 +2709,7 @@ impl KnowledgeDb {
             });
             let mut stmt = conn.prepare(
                 "SELECT id, description, status, priority, success_criteria, strategy,
-                        check_interval_secs, last_checked_at, source_channel, source, created_at, updated_at
+                        check_interval_secs, last_checked_at, source_channel, source, created_at, updated_at, deadline
                  FROM goals
                  WHERE status = 'active'
                    AND (last_checked_at IS NULL
@@ -1050,7 +2737,7 @@ impl KnowledgeDb {
             });
             let mut stmt = conn.prepare(
                 "SELECT id, description, status, priority, success_criteria, strategy,
-                        check_interval_secs, last_checked_at, source_channel, source, created_at, updated_at
+                        check_interval_secs, last_checked_at, source_channel, source, created_at, updated_at, deadline
                  FROM goals WHERE status = 'active'
                  ORDER BY priority DESC, created_at ASC",
             )?;
@@ -1063,6 +2750,81 @@ impl KnowledgeDb {
         .context("spawn_blocking task panicked")?
     }
 
+    /// Get a goal by ID, whatever its status
+    pub async fn get_goal(&self, id: &str) -> Result<Option<Goal>> {
+        let conn = Arc::clone(&self.conn);
+        let id = id.to_owned();
+
+        tokio::task::spawn_blocking(move || {
+            let conn = conn.lock().unwrap_or_else(|poisoned| {
+                warn!("Database mutex was poisoned, recovering");
+                poisoned.into_inner()
+            });
+            let goal = conn
+                .query_row(
+                    "SELECT id, description, status, priority, success_criteria, strategy,
+                            check_interval_secs, last_checked_at, source_channel, source, created_at, updated_at, deadline
+                     FROM goals WHERE id = ?1",
+                    params![&id],
+                    Self::row_to_goal,
+                )
+                .optional()?;
+            Ok(goal)
+        })
+        .await
+        .context("spawn_blocking task panicked")?
+    }
+
+    /// Get goals of any status updated between two dates (YYYY-MM-DD, inclusive)
+    pub async fn get_goals_updated(&self, start: &str, end: &str) -> Result<Vec<Goal>> {
+        let conn = Arc::clone(&self.conn);
+        let start = start.to_owned();
+        let end = end.to_owned();
+
+        tokio::task::spawn_blocking(move || {
+            let conn = conn.lock().unwrap_or_else(|poisoned| {
+                warn!("Database mutex was poisoned, recovering");
+                poisoned.into_inner()
+            });
+            let mut stmt = conn.prepare(
+                "SELECT id, description, status, priority, success_criteria, strategy,
+                        check_interval_secs, last_checked_at, source_channel, source, created_at, updated_at, deadline
+                 FROM goals WHERE date(updated_at) >= ?1 AND date(updated_at) <= ?2
+                 ORDER BY priority DESC, updated_at ASC",
+            )?;
+            let goals = stmt
+                .query_map(params![start, end], Self::row_to_goal)?
+                .collect::<Result<Vec<_>, _>>()?;
+            Ok(goals)
+        })
+        .await
+        .context("spawn_blocking task panicked")?
+    }
+
+    /// Set or clear a goal's deadline
+    pub async fn set_goal_deadline(&self, id: &str, deadline: Option<DateTime<Utc>>) -> Result<()> {
+        let conn = Arc::clone(&self.conn);
+        let id = id.to_owned();
+
+        tokio::task::spawn_blocking(move || {
+            let conn = conn.lock().unwrap_or_else(|poisoned| {
+                warn!("Database mutex was poisoned, recovering");
+                poisoned.into_inner()
+            });
+            conn.execute(
+                "UPDATE goals SET deadline = ?1, updated_at = ?2 WHERE id = ?3",
+                params![
+                    deadline.map(|d| d.to_rfc3339()),
+                    Utc::now().to_rfc3339(),
+                    &id
+                ],
+            )?;
+            Ok(())
+        })
+        .await
+        .context("spawn_blocking task panicked")?
+    }
+
     /// Update goal status
     pub async fn update_goal_status(&self, id: &str, status: &str) -> Result<()> {
         let conn = Arc::clone(&self.conn);
@@ -1148,6 +2910,9 @@ impl KnowledgeDb {
                 .get::<_, String>(11)?
                 .parse()
                 .unwrap_or_else(|_| Utc::now()),
+            deadline: row
+                .get::<_, Option<String>>(12)?
+                .and_then(|s| s.parse().ok()),
         })
     }
 
@@ -1237,6 +3002,48 @@ impl KnowledgeDb {
         .context("spawn_blocking task panicked")?
     }
 
+    /// Delete a preference by key. Returns whether one was removed.
+    pub async fn delete_preference(&self, key: &str) -> Result<bool> {
+        let conn = Arc::clone(&self.conn);
+        let key = key.to_owned();
+
+        tokio::task::spawn_blocking(move || {
+            let conn = conn.lock().unwrap_or_else(|poisoned| {
+                warn!("Database mutex was poisoned, recovering");
+                poisoned.into_inner()
+            });
+            let deleted =
+                conn.execute("DELETE FROM user_preferences WHERE key = ?1", params![&key])?;
+            debug!("Deleted preference {}", key);
+            Ok(deleted > 0)
+        })
+        .await
+        .context("spawn_blocking task panicked")?
+    }
+
+    /// Delete a preference by key, only if it belongs to `category`.
+    /// Returns whether one was removed.
+    pub async fn delete_category_preference(&self, category: &str, key: &str) -> Result<bool> {
+        let conn = Arc::clone(&self.conn);
+        let category = category.to_owned();
+        let key = key.to_owned();
+
+        tokio::task::spawn_blocking(move || {
+            let conn = conn.lock().unwrap_or_else(|poisoned| {
+                warn!("Database mutex was poisoned, recovering");
+                poisoned.into_inner()
+            });
+            let deleted = conn.execute(
+                "DELETE FROM user_preferences WHERE category = ?1 AND key = ?2",
+                params![&category, &key],
+            )?;
+            debug!("Deleted {} preference {}", category, key);
+            Ok(deleted > 0)
+        })
+        .await
+        .context("spawn_blocking task panicked")?
+    }
+
     /// Helper to convert row to UserPreference
     fn row_to_preference(row: &rusqlite::Row) -> rusqlite::Result<UserPreference> {
         let value_str: String = row.get(3)?;
@@ -1363,16 +3170,577 @@ impl KnowledgeDb {
                  VALUES (?1, ?2, ?3, ?4, ?5, ?6, 'pending', ?7)",
                 params![&id, &action_type, &description, &risk_level, goal_id, &prompt, now.to_rfc3339()],
             )?;
-            debug!("Queued action for approval: {} - {}", action_type, description);
+            debug!("Queued action for approval: {} - {}", action_type, description);
+            Ok(id)
+        })
+        .await
+        .context("spawn_blocking task panicked")?
+    }
+
+    /// Get all pending approval requests
+    pub async fn get_pending_approvals(&self) -> Result<Vec<ApprovalEntry>> {
+        let conn = Arc::clone(&self.conn);
+
+        tokio::task::spawn_blocking(move || {
+            let conn = conn.lock().unwrap_or_else(|poisoned| {
+                warn!("Database mutex was poisoned, recovering");
+                poisoned.into_inner()
+            });
+            let mut stmt = conn.prepare(
+                "SELECT id, action_type, description, risk_level, goal_id, prompt, status, decided_at, created_at
+                 FROM approval_queue WHERE status = 'pending' ORDER BY created_at ASC",
+            )?;
+            let entries = stmt
+                .query_map([], |row| {
+                    Ok(ApprovalEntry {
+                        id: row.get(0)?,
+                        action_type: row.get(1)?,
+                        description: row.get(2)?,
+                        risk_level: row.get(3)?,
+                        goal_id: row.get(4)?,
+                        prompt: row.get(5)?,
+                        status: row.get(6)?,
+                        decided_at: row.get(7)?,
+                        created_at: row.get(8)?,
+                    })
+                })?
+                .collect::<Result<Vec<_>, _>>()?;
+            Ok(entries)
+        })
+        .await
+        .context("spawn_blocking task panicked")?
+    }
+
+    /// Approve or reject a queued action
+    pub async fn decide_approval(&self, id: &str, approved: bool) -> Result<()> {
+        let conn = Arc::clone(&self.conn);
+        let id = id.to_owned();
+        let status = if approved { "approved" } else { "rejected" };
+
+        tokio::task::spawn_blocking(move || {
+            let now = Utc::now();
+            let conn = conn.lock().unwrap_or_else(|poisoned| {
+                warn!("Database mutex was poisoned, recovering");
+                poisoned.into_inner()
+            });
+            conn.execute(
+                "UPDATE approval_queue SET status = ?1, decided_at = ?2 WHERE id = ?3",
+                params![status, now.to_rfc3339(), &id],
+            )?;
+            debug!("Approval {} decided: {}", id, status);
+            Ok(())
+        })
+        .await
+        .context("spawn_blocking task panicked")?
+    }
+
+    /// Clean up old conversations (keep only last N days)
+    pub async fn cleanup_old_conversations(&self, retain_days: u32) -> Result<usize> {
+        let conn = Arc::clone(&self.conn);
+
+        tokio::task::spawn_blocking(move || {
+            let conn = conn.lock().unwrap_or_else(|poisoned| {
+                warn!("Database mutex was poisoned, recovering");
+                poisoned.into_inner()
+            });
+            let deleted = conn.execute(
+                "DELETE FROM conversations WHERE created_at < datetime('now', ?)",
+                params![format!("-{} days", retain_days)],
+            )?;
+            if deleted > 0 {
+                info!("Cleaned up {} old conversations", deleted);
+            }
+            Ok(deleted)
+        })
+        .await
+        .context("spawn_blocking task panicked")?
+    }
+
+    /// Insert a new background task
+    pub async fn insert_background_task(
+        &self,
+        id: &str,
+        description: &str,
+        reply_channel: &str,
+        spawned_by: &str,
+    ) -> Result<()> {
+        let conn = Arc::clone(&self.conn);
+        let id = id.to_owned();
+        let description = description.to_owned();
+        let reply_channel = reply_channel.to_owned();
+        let spawned_by = spawned_by.to_owned();
+
+        tokio::task::spawn_blocking(move || {
+            let now = Utc::now();
+            let conn = conn.lock().unwrap_or_else(|poisoned| {
+                warn!("Database mutex was poisoned, recovering");
+                poisoned.into_inner()
+            });
+            conn.execute(
+                "INSERT INTO background_tasks (id, description, status, reply_channel, spawned_by, created_at, updated_at)
+                 VALUES (?1, ?2, 'pending', ?3, ?4, ?5, ?6)",
+                params![&id, &description, &reply_channel, &spawned_by, now.to_rfc3339(), now.to_rfc3339()],
+            )?;
+            debug!("Inserted background task: {} ({})", description, id);
+            Ok(())
+        })
+        .await
+        .context("spawn_blocking task panicked")?
+    }
+
+    /// Update background task status and optionally set result
+    pub async fn update_background_task(
+        &self,
+        id: &str,
+        status: &str,
+        result: Option<&str>,
+    ) -> Result<()> {
+        let conn = Arc::clone(&self.conn);
+        let id = id.to_owned();
+        let status = status.to_owned();
+        let result = result.map(|s| s.to_owned());
+
+        tokio::task::spawn_blocking(move || {
+            let now = Utc::now();
+            let conn = conn.lock().unwrap_or_else(|poisoned| {
+                warn!("Database mutex was poisoned, recovering");
+                poisoned.into_inner()
+            });
+            conn.execute(
+                "UPDATE background_tasks SET status = ?1, result = COALESCE(?2, result), updated_at = ?3 WHERE id = ?4",
+                params![&status, result, now.to_rfc3339(), &id],
+            )?;
+            Ok(())
+        })
+        .await
+        .context("spawn_blocking task panicked")?
+    }
+
+    /// Get active (pending, running, or waiting on the user) background tasks
+    pub async fn get_active_background_tasks(&self) -> Result<Vec<BackgroundTask>> {
+        let conn = Arc::clone(&self.conn);
+
+        tokio::task::spawn_blocking(move || {
+            let conn = conn.lock().unwrap_or_else(|poisoned| {
+                warn!("Database mutex was poisoned, recovering");
+                poisoned.into_inner()
+            });
+            let mut stmt = conn.prepare(
+                "SELECT id, description, status, reply_channel, spawned_by, created_at, updated_at, result
+                 FROM background_tasks WHERE status IN ('pending', 'running', 'waiting')
+                 ORDER BY created_at DESC",
+            )?;
+            let tasks = stmt
+                .query_map([], Self::row_to_background_task)?
+                .collect::<Result<Vec<_>, _>>()?;
+            Ok(tasks)
+        })
+        .await
+        .context("spawn_blocking task panicked")?
+    }
+
+    /// Get recently completed/failed background tasks
+    pub async fn get_recent_background_tasks(&self, limit: usize) -> Result<Vec<BackgroundTask>> {
+        let conn = Arc::clone(&self.conn);
+
+        tokio::task::spawn_blocking(move || {
+            let conn = conn.lock().unwrap_or_else(|poisoned| {
+                warn!("Database mutex was poisoned, recovering");
+                poisoned.into_inner()
+            });
+            let mut stmt = conn.prepare(
+                "SELECT id, description, status, reply_channel, spawned_by, created_at, updated_at, result
+                 FROM background_tasks WHERE status IN ('completed', 'failed')
+                 ORDER BY updated_at DESC LIMIT ?1",
+            )?;
+            let tasks = stmt
+                .query_map(params![limit as i64], Self::row_to_background_task)?
+                .collect::<Result<Vec<_>, _>>()?;
+            Ok(tasks)
+        })
+        .await
+        .context("spawn_blocking task panicked")?
+    }
+
+    /// Get completed/failed background tasks finished between two dates
+    /// (YYYY-MM-DD, inclusive)
+    pub async fn get_background_tasks_finished(
+        &self,
+        start: &str,
+        end: &str,
+    ) -> Result<Vec<BackgroundTask>> {
+        let conn = Arc::clone(&self.conn);
+        let start = start.to_owned();
+        let end = end.to_owned();
+
+        tokio::task::spawn_blocking(move || {
+            let conn = conn.lock().unwrap_or_else(|poisoned| {
+                warn!("Database mutex was poisoned, recovering");
+                poisoned.into_inner()
+            });
+            let mut stmt = conn.prepare(
+                "SELECT id, description, status, reply_channel, spawned_by, created_at, updated_at, result
+                 FROM background_tasks
+                 WHERE status IN ('completed', 'failed')
+                   AND date(updated_at) >= ?1 AND date(updated_at) <= ?2
+                 ORDER BY updated_at ASC",
+            )?;
+            let tasks = stmt
+                .query_map(params![start, end], Self::row_to_background_task)?
+                .collect::<Result<Vec<_>, _>>()?;
+            Ok(tasks)
+        })
+        .await
+        .context("spawn_blocking task panicked")?
+    }
+
+    fn row_to_background_task(row: &rusqlite::Row) -> rusqlite::Result<BackgroundTask> {
+        Ok(BackgroundTask {
+            id: row.get(0)?,
+            description: row.get(1)?,
+            status: row.get(2)?,
+            reply_channel: row.get(3)?,
+            spawned_by: row.get(4)?,
+            created_at: row
+                .get::<_, String>(5)?
+                .parse()
+                .unwrap_or_else(|_| Utc::now()),
+            updated_at: row
+                .get::<_, String>(6)?
+                .parse()
+                .unwrap_or_else(|_| Utc::now()),
+            result: row.get(7)?,
+        })
+    }
+
+    // ── Usage Tracking ─────────────────────────────────────────────
+
+    /// Insert a usage log entry
+    #[allow(clippy::too_many_arguments)]
+    pub async fn insert_usage_log(
+        &self,
+        model: &str,
+        input_tokens: u64,
+        output_tokens: u64,
+        cache_read_tokens: u64,
+        cache_write_tokens: u64,
+        estimated_cost_usd: f64,
+        source: &str,
+        channel: Option<&str>,
+        tool_calls_count: u32,
+        tool_names: &str,
+        session_id: &str,
+    ) -> Result<()> {
+        let conn = Arc::clone(&self.conn);
+        let model = model.to_owned();
+        let source = source.to_owned();
+        let channel = channel.map(|s| s.to_owned());
+        let tool_names = tool_names.to_owned();
+        let session_id = session_id.to_owned();
+
+        tokio::task::spawn_blocking(move || {
+            let now = Utc::now();
+            let conn = conn.lock().unwrap_or_else(|poisoned| {
+                warn!("Database mutex was poisoned, recovering");
+                poisoned.into_inner()
+            });
+            conn.execute(
+                "INSERT INTO usage_log (timestamp, model, input_tokens, output_tokens, cache_read_tokens, cache_write_tokens, estimated_cost_usd, source, channel, tool_calls_count, tool_names, session_id)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12)",
+                params![
+                    now.to_rfc3339(),
+                    &model,
+                    input_tokens as i64,
+                    output_tokens as i64,
+                    cache_read_tokens as i64,
+                    cache_write_tokens as i64,
+                    estimated_cost_usd,
+                    &source,
+                    channel,
+                    tool_calls_count as i64,
+                    &tool_names,
+                    &session_id,
+                ],
+            )?;
+            Ok(())
+        })
+        .await
+        .context("spawn_blocking task panicked")?
+    }
+
+    /// Get total estimated cost for a specific date (YYYY-MM-DD)
+    pub async fn get_usage_cost_for_date(&self, date: &str) -> Result<f64> {
+        let conn = Arc::clone(&self.conn);
+        let date = date.to_owned();
+
+        tokio::task::spawn_blocking(move || {
+            let conn = conn.lock().unwrap_or_else(|poisoned| {
+                warn!("Database mutex was poisoned, recovering");
+                poisoned.into_inner()
+            });
+            let cost: f64 = conn
+                .query_row(
+                    "SELECT COALESCE(SUM(estimated_cost_usd), 0.0) FROM usage_log WHERE date(timestamp) = ?1",
+                    params![&date],
+                    |row| row.get(0),
+                )?;
+            Ok(cost)
+        })
+        .await
+        .context("spawn_blocking task panicked")?
+    }
+
+    /// Get total estimated cost for a date range (inclusive)
+    pub async fn get_usage_cost_for_range(&self, start: &str, end: &str) -> Result<f64> {
+        let conn = Arc::clone(&self.conn);
+        let start = start.to_owned();
+        let end = end.to_owned();
+
+        tokio::task::spawn_blocking(move || {
+            let conn = conn.lock().unwrap_or_else(|poisoned| {
+                warn!("Database mutex was poisoned, recovering");
+                poisoned.into_inner()
+            });
+            let cost: f64 = conn
+                .query_row(
+                    "SELECT COALESCE(SUM(estimated_cost_usd), 0.0) FROM usage_log WHERE date(timestamp) >= ?1 AND date(timestamp) <= ?2",
+                    params![&start, &end],
+                    |row| row.get(0),
+                )?;
+            Ok(cost)
+        })
+        .await
+        .context("spawn_blocking task panicked")?
+    }
+
+    /// Get a usage summary for a date range
+    pub async fn get_usage_summary(&self, start: &str, end: &str) -> Result<UsageSummary> {
+        let conn = Arc::clone(&self.conn);
+        let start = start.to_owned();
+        let end = end.to_owned();
+
+        tokio::task::spawn_blocking(move || {
+            let conn = conn.lock().unwrap_or_else(|poisoned| {
+                warn!("Database mutex was poisoned, recovering");
+                poisoned.into_inner()
+            });
+
+            // Totals
+            let (total_input, total_output, total_calls, total_tools, total_cost): (i64, i64, i64, i64, f64) = conn
+                .query_row(
+                    "SELECT COALESCE(SUM(input_tokens), 0), COALESCE(SUM(output_tokens), 0), COUNT(*), COALESCE(SUM(tool_calls_count), 0), COALESCE(SUM(estimated_cost_usd), 0.0)
+                     FROM usage_log WHERE date(timestamp) >= ?1 AND date(timestamp) <= ?2",
+                    params![&start, &end],
+                    |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?, row.get(4)?)),
+                )?;
+
+            // By source
+            let mut by_source = std::collections::HashMap::new();
+            {
+                let mut stmt = conn.prepare(
+                    "SELECT source, SUM(input_tokens), SUM(output_tokens), COUNT(*), SUM(estimated_cost_usd)
+                     FROM usage_log WHERE date(timestamp) >= ?1 AND date(timestamp) <= ?2
+                     GROUP BY source",
+                )?;
+                let rows = stmt.query_map(params![&start, &end], |row| {
+                    Ok((
+                        row.get::<_, String>(0)?,
+                        row.get::<_, i64>(1)?,
+                        row.get::<_, i64>(2)?,
+                        row.get::<_, i64>(3)?,
+                        row.get::<_, f64>(4)?,
+                    ))
+                })?;
+                for row in rows {
+                    let (source, inp, out, calls, cost) = row?;
+                    by_source.insert(source, SourceUsage {
+                        input_tokens: inp as u64,
+                        output_tokens: out as u64,
+                        api_calls: calls as u64,
+                        estimated_cost_usd: cost,
+                    });
+                }
+            }
+
+            // By model
+            let mut by_model = std::collections::HashMap::new();
+            {
+                let mut stmt = conn.prepare(
+                    "SELECT model, SUM(input_tokens), SUM(output_tokens), COUNT(*), SUM(estimated_cost_usd)
+                     FROM usage_log WHERE date(timestamp) >= ?1 AND date(timestamp) <= ?2
+                     GROUP BY model",
+                )?;
+                let rows = stmt.query_map(params![&start, &end], |row| {
+                    Ok((
+                        row.get::<_, String>(0)?,
+                        row.get::<_, i64>(1)?,
+                        row.get::<_, i64>(2)?,
+                        row.get::<_, i64>(3)?,
+                        row.get::<_, f64>(4)?,
+                    ))
+                })?;
+                for row in rows {
+                    let (model, inp, out, calls, cost) = row?;
+                    by_model.insert(model, ModelUsage {
+                        input_tokens: inp as u64,
+                        output_tokens: out as u64,
+                        api_calls: calls as u64,
+                        estimated_cost_usd: cost,
+                    });
+                }
+            }
+
+            // By channel and model
+            let mut by_channel_model = std::collections::HashMap::new();
+            {
+                let mut stmt = conn.prepare(
+                    "SELECT channel, model, SUM(input_tokens), SUM(output_tokens), COUNT(*), SUM(estimated_cost_usd)
+                     FROM usage_log WHERE date(timestamp) >= ?1 AND date(timestamp) <= ?2 AND channel IS NOT NULL
+                     GROUP BY channel, model",
+                )?;
+                let rows = stmt.query_map(params![&start, &end], |row| {
+                    Ok((
+                        row.get::<_, String>(0)?,
+                        row.get::<_, String>(1)?,
+                        row.get::<_, i64>(2)?,
+                        row.get::<_, i64>(3)?,
+                        row.get::<_, i64>(4)?,
+                        row.get::<_, f64>(5)?,
+                    ))
+                })?;
+                for row in rows {
+                    let (channel, model, inp, out, calls, cost) = row?;
+                    by_channel_model.insert(format!("{}/{}", channel, model), ModelUsage {
+                        input_tokens: inp as u64,
+                        output_tokens: out as u64,
+                        api_calls: calls as u64,
+                        estimated_cost_usd: cost,
+                    });
+                }
+            }
+
+            let period = if start == end {
+                start.clone()
+            } else {
+                format!("{} to {}", start, end)
+            };
+
+            Ok(UsageSummary {
+                period,
+                total_input_tokens: total_input as u64,
+                total_output_tokens: total_output as u64,
+                total_api_calls: total_calls as u64,
+                total_tool_calls: total_tools as u64,
+                estimated_cost_usd: total_cost,
+                by_source,
+                by_model,
+                by_channel_model,
+            })
+        })
+        .await
+        .context("spawn_blocking task panicked")?
+    }
+
+    /// Export usage data as CSV for a date range
+    pub async fn export_usage_csv(&self, start: &str, end: &str) -> Result<String> {
+        let conn = Arc::clone(&self.conn);
+        let start = start.to_owned();
+        let end = end.to_owned();
+
+        tokio::task::spawn_blocking(move || {
+            let conn = conn.lock().unwrap_or_else(|poisoned| {
+                warn!("Database mutex was poisoned, recovering");
+                poisoned.into_inner()
+            });
+
+            let mut csv = String::from("timestamp,model,input_tokens,output_tokens,cache_read_tokens,cache_write_tokens,estimated_cost_usd,source,channel,tool_calls_count,tool_names,session_id\n");
+
+            let mut stmt = conn.prepare(
+                "SELECT timestamp, model, input_tokens, output_tokens, cache_read_tokens, cache_write_tokens, estimated_cost_usd, source, channel, tool_calls_count, tool_names, session_id
+                 FROM usage_log WHERE date(timestamp) >= ?1 AND date(timestamp) <= ?2
+                 ORDER BY timestamp ASC",
+            )?;
+
+            let rows = stmt.query_map(params![&start, &end], |row| {
+                Ok((
+                    row.get::<_, String>(0)?,
+                    row.get::<_, String>(1)?,
+                    row.get::<_, i64>(2)?,
+                    row.get::<_, i64>(3)?,
+                    row.get::<_, i64>(4)?,
+                    row.get::<_, i64>(5)?,
+                    row.get::<_, f64>(6)?,
+                    row.get::<_, String>(7)?,
+                    row.get::<_, Option<String>>(8)?,
+                    row.get::<_, i64>(9)?,
+                    row.get::<_, Option<String>>(10)?,
+                    row.get::<_, Option<String>>(11)?,
+                ))
+            })?;
+
+            for row in rows {
+                let (ts, model, inp, out, cr, cw, cost, src, ch, tc, tn, sid) = row?;
+                csv.push_str(&format!(
+                    "{},{},{},{},{},{},{:.6},{},{},{},{},{}\n",
+                    ts, model, inp, out, cr, cw, cost, src,
+                    ch.unwrap_or_default(), tc,
+                    tn.unwrap_or_default(),
+                    sid.unwrap_or_default(),
+                ));
+            }
+
+            Ok(csv)
+        })
+        .await
+        .context("spawn_blocking task panicked")?
+    }
+
+    // ── Feedback ───────────────────────────────────────────────────
+
+    /// Store a rating of an agent response
+    pub async fn insert_feedback(&self, feedback: NewFeedback<'_>) -> Result<String> {
+        let conn = Arc::clone(&self.conn);
+        let conversation_id = feedback.conversation_id.map(|s| s.to_owned());
+        let channel = feedback.channel.to_owned();
+        let sender = feedback.sender.to_owned();
+        let rating = feedback.rating.signum();
+        let comment = feedback.comment.map(|s| s.to_owned());
+        let model = feedback.model.map(|s| s.to_owned());
+        let tools = serde_json::to_string(feedback.tools)?;
+
+        tokio::task::spawn_blocking(move || {
+            let id = Uuid::new_v4().to_string();
+            let conn = conn.lock().unwrap_or_else(|poisoned| {
+                warn!("Database mutex was poisoned, recovering");
+                poisoned.into_inner()
+            });
+            conn.execute(
+                "INSERT INTO feedback (id, conversation_id, channel, sender, rating, comment, model, tools, created_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+                params![
+                    &id,
+                    conversation_id,
+                    &channel,
+                    &sender,
+                    rating,
+                    comment,
+                    model,
+                    &tools,
+                    Utc::now().to_rfc3339(),
+                ],
+            )?;
+            debug!("Inserted feedback {} (rating {})", id, rating);
             Ok(id)
         })
         .await
         .context("spawn_blocking task panicked")?
     }
 
-    /// Get all pending approval requests
-    pub async fn get_pending_approvals(&self) -> Result<Vec<ApprovalEntry>> {
+    /// Feedback given between two dates (YYYY-MM-DD, inclusive), oldest first
+    pub async fn get_feedback(&self, start: &str, end: &str) -> Result<Vec<Feedback>> {
         let conn = Arc::clone(&self.conn);
+        let start = start.to_owned();
+        let end = end.to_owned();
 
         tokio::task::spawn_blocking(move || {
             let conn = conn.lock().unwrap_or_else(|poisoned| {
@@ -1380,137 +3748,186 @@ impl KnowledgeDb {
                 poisoned.into_inner()
             });
             let mut stmt = conn.prepare(
-                "SELECT id, action_type, description, risk_level, goal_id, prompt, status, decided_at, created_at
-                 FROM approval_queue WHERE status = 'pending' ORDER BY created_at ASC",
+                "SELECT id, conversation_id, channel, sender, rating, comment, model, tools, created_at
+                 FROM feedback WHERE date(created_at) >= ?1 AND date(created_at) <= ?2
+                 ORDER BY created_at ASC",
             )?;
-            let entries = stmt
-                .query_map([], |row| {
-                    Ok(ApprovalEntry {
+            let feedback = stmt
+                .query_map(params![&start, &end], |row| {
+                    let tools: Option<String> = row.get(7)?;
+                    let created_at: String = row.get(8)?;
+                    Ok(Feedback {
                         id: row.get(0)?,
-                        action_type: row.get(1)?,
-                        description: row.get(2)?,
-                        risk_level: row.get(3)?,
-                        goal_id: row.get(4)?,
-                        prompt: row.get(5)?,
-                        status: row.get(6)?,
-                        decided_at: row.get(7)?,
-                        created_at: row.get(8)?,
+                        conversation_id: row.get(1)?,
+                        channel: row.get(2)?,
+                        sender: row.get(3)?,
+                        rating: row.get(4)?,
+                        comment: row.get(5)?,
+                        model: row.get(6)?,
+                        tools: tools
+                            .and_then(|t| serde_json::from_str(&t).ok())
+                            .unwrap_or_default(),
+                        created_at: DateTime::parse_from_rfc3339(&created_at)
+                            .map(|dt| dt.with_timezone(&Utc))
+                            .unwrap_or_else(|_| Utc::now()),
                     })
                 })?
                 .collect::<Result<Vec<_>, _>>()?;
-            Ok(entries)
+            Ok(feedback)
         })
         .await
         .context("spawn_blocking task panicked")?
     }
 
-    /// Approve or reject a queued action
-    pub async fn decide_approval(&self, id: &str, approved: bool) -> Result<()> {
+    /// Satisfaction between two dates, by channel, model and tool
+    pub async fn get_feedback_summary(&self, start: &str, end: &str) -> Result<FeedbackSummary> {
+        let feedback = self.get_feedback(start, end).await?;
+        let mut summary = FeedbackSummary {
+            period: format!("{} to {}", start, end),
+            ..Default::default()
+        };
+        for item in &feedback {
+            summary.overall.add(item.rating);
+            summary
+                .by_channel
+                .entry(item.channel.clone())
+                .or_default()
+                .add(item.rating);
+            summary
+                .by_model
+                .entry(item.model.clone().unwrap_or_else(|| "unknown".to_string()))
+                .or_default()
+                .add(item.rating);
+            let mut tools: Vec<&String> = item.tools.iter().collect();
+            tools.sort();
+            tools.dedup();
+            if tools.is_empty() {
+                summary
+                    .by_tool
+                    .entry("(none)".to_string())
+                    .or_default()
+                    .add(item.rating);
+            }
+            for tool in tools {
+                summary
+                    .by_tool
+                    .entry(tool.clone())
+                    .or_default()
+                    .add(item.rating);
+            }
+        }
+        Ok(summary)
+    }
+
+    // ── Backups ────────────────────────────────────────────────────
+
+    /// Write a consistent snapshot of the database to `path`, which must
+    /// not exist yet
+    pub async fn backup_to(&self, path: &Path) -> Result<()> {
         let conn = Arc::clone(&self.conn);
-        let id = id.to_owned();
-        let status = if approved { "approved" } else { "rejected" };
+        let path = path.to_string_lossy().into_owned();
 
         tokio::task::spawn_blocking(move || {
-            let now = Utc::now();
             let conn = conn.lock().unwrap_or_else(|poisoned| {
                 warn!("Database mutex was poisoned, recovering");
                 poisoned.into_inner()
             });
-            conn.execute(
-                "UPDATE approval_queue SET status = ?1, decided_at = ?2 WHERE id = ?3",
-                params![status, now.to_rfc3339(), &id],
-            )?;
-            debug!("Approval {} decided: {}", id, status);
+            conn.execute("VACUUM INTO ?1", params![&path])?;
+            debug!("Backed up database to {}", path);
             Ok(())
         })
         .await
         .context("spawn_blocking task panicked")?
     }
 
-    /// Clean up old conversations (keep only last N days)
-    pub async fn cleanup_old_conversations(&self, retain_days: u32) -> Result<usize> {
+    // ── App Usage ──────────────────────────────────────────────────
+
+    /// Add time spent in an app on a day (YYYY-MM-DD, local)
+    pub async fn add_app_usage(&self, date: &str, app: &str, seconds: u64) -> Result<()> {
         let conn = Arc::clone(&self.conn);
+        let date = date.to_owned();
+        let app = app.to_owned();
 
         tokio::task::spawn_blocking(move || {
             let conn = conn.lock().unwrap_or_else(|poisoned| {
                 warn!("Database mutex was poisoned, recovering");
                 poisoned.into_inner()
             });
-            let deleted = conn.execute(
-                "DELETE FROM conversations WHERE created_at < datetime('now', ?)",
-                params![format!("-{} days", retain_days)],
+            conn.execute(
+                "INSERT INTO app_usage (date, app, seconds) VALUES (?1, ?2, ?3)
+                 ON CONFLICT(date, app) DO UPDATE SET seconds = seconds + excluded.seconds",
+                params![&date, &app, seconds as i64],
             )?;
-            if deleted > 0 {
-                info!("Cleaned up {} old conversations", deleted);
-            }
-            Ok(deleted)
+            Ok(())
         })
         .await
         .context("spawn_blocking task panicked")?
     }
 
-    /// Insert a new background task
-    pub async fn insert_background_task(
-        &self,
-        id: &str,
-        description: &str,
-        reply_channel: &str,
-        spawned_by: &str,
-    ) -> Result<()> {
+    /// Time per app between two dates (YYYY-MM-DD, inclusive), most used first
+    pub async fn get_app_usage(&self, start: &str, end: &str) -> Result<Vec<AppUsage>> {
         let conn = Arc::clone(&self.conn);
-        let id = id.to_owned();
-        let description = description.to_owned();
-        let reply_channel = reply_channel.to_owned();
-        let spawned_by = spawned_by.to_owned();
+        let start = start.to_owned();
+        let end = end.to_owned();
 
         tokio::task::spawn_blocking(move || {
-            let now = Utc::now();
             let conn = conn.lock().unwrap_or_else(|poisoned| {
                 warn!("Database mutex was poisoned, recovering");
                 poisoned.into_inner()
             });
-            conn.execute(
-                "INSERT INTO background_tasks (id, description, status, reply_channel, spawned_by, created_at, updated_at)
-                 VALUES (?1, ?2, 'pending', ?3, ?4, ?5, ?6)",
-                params![&id, &description, &reply_channel, &spawned_by, now.to_rfc3339(), now.to_rfc3339()],
+            let mut stmt = conn.prepare(
+                "SELECT app, SUM(seconds), COUNT(*) FROM app_usage
+                 WHERE date >= ?1 AND date <= ?2
+                 GROUP BY app ORDER BY SUM(seconds) DESC, app ASC",
             )?;
-            debug!("Inserted background task: {} ({})", description, id);
-            Ok(())
+            let usage = stmt
+                .query_map(params![&start, &end], |row| {
+                    Ok(AppUsage {
+                        app: row.get(0)?,
+                        seconds: row.get::<_, i64>(1)?.max(0) as u64,
+                        days: row.get(2)?,
+                    })
+                })?
+                .collect::<Result<Vec<_>, _>>()?;
+            Ok(usage)
         })
         .await
         .context("spawn_blocking task panicked")?
     }
 
-    /// Update background task status and optionally set result
-    pub async fn update_background_task(
-        &self,
-        id: &str,
-        status: &str,
-        result: Option<&str>,
-    ) -> Result<()> {
+    // ── Tool Stats ─────────────────────────────────────────────────
+
+    /// Record the registered tools and their definition sizes. Tools seen
+    /// for the first time start their unused-days clock now.
+    pub async fn sync_tool_catalog(&self, tools: Vec<(String, usize)>) -> Result<()> {
         let conn = Arc::clone(&self.conn);
-        let id = id.to_owned();
-        let status = status.to_owned();
-        let result = result.map(|s| s.to_owned());
 
         tokio::task::spawn_blocking(move || {
-            let now = Utc::now();
             let conn = conn.lock().unwrap_or_else(|poisoned| {
                 warn!("Database mutex was poisoned, recovering");
                 poisoned.into_inner()
             });
-            conn.execute(
-                "UPDATE background_tasks SET status = ?1, result = COALESCE(?2, result), updated_at = ?3 WHERE id = ?4",
-                params![&status, result, now.to_rfc3339(), &id],
-            )?;
+            let now = Utc::now().to_rfc3339();
+            let tx = conn.unchecked_transaction()?;
+            for (tool, definition_chars) in &tools {
+                tx.execute(
+                    "INSERT INTO tool_stats (tool, definition_chars, first_seen_at) VALUES (?1, ?2, ?3)
+                     ON CONFLICT(tool) DO UPDATE SET definition_chars = excluded.definition_chars",
+                    params![tool, *definition_chars as i64, &now],
+                )?;
+            }
+            tx.commit()?;
             Ok(())
         })
         .await
         .context("spawn_blocking task panicked")?
     }
 
-    /// Get active (pending or running) background tasks
-    pub async fn get_active_background_tasks(&self) -> Result<Vec<BackgroundTask>> {
+    /// Add tool invocations to the per-tool totals
+    pub async fn record_tool_calls(&self, calls: Vec<ToolCallRecord>) -> Result<()> {
+        if calls.is_empty() {
+            return Ok(());
+        }
         let conn = Arc::clone(&self.conn);
 
         tokio::task::spawn_blocking(move || {
@@ -1518,22 +3935,36 @@ impl KnowledgeDb {
                 warn!("Database mutex was poisoned, recovering");
                 poisoned.into_inner()
             });
-            let mut stmt = conn.prepare(
-                "SELECT id, description, status, reply_channel, spawned_by, created_at, updated_at, result
-                 FROM background_tasks WHERE status IN ('pending', 'running')
-                 ORDER BY created_at DESC",
-            )?;
-            let tasks = stmt
-                .query_map([], Self::row_to_background_task)?
-                .collect::<Result<Vec<_>, _>>()?;
-            Ok(tasks)
+            let now = Utc::now().to_rfc3339();
+            let tx = conn.unchecked_transaction()?;
+            for call in &calls {
+                tx.execute(
+                    "INSERT INTO tool_stats (tool, calls, failures, total_duration_ms, output_chars, first_seen_at, last_used_at)
+                     VALUES (?1, 1, ?2, ?3, ?4, ?5, ?5)
+                     ON CONFLICT(tool) DO UPDATE SET
+                         calls = calls + 1,
+                         failures = failures + excluded.failures,
+                         total_duration_ms = total_duration_ms + excluded.total_duration_ms,
+                         output_chars = output_chars + excluded.output_chars,
+                         last_used_at = excluded.last_used_at",
+                    params![
+                        &call.tool,
+                        (!call.ok) as i64,
+                        call.duration_ms as i64,
+                        call.output_chars as i64,
+                        &now
+                    ],
+                )?;
+            }
+            tx.commit()?;
+            Ok(())
         })
         .await
         .context("spawn_blocking task panicked")?
     }
 
-    /// Get recently completed/failed background tasks
-    pub async fn get_recent_background_tasks(&self, limit: usize) -> Result<Vec<BackgroundTask>> {
+    /// Usage of every known tool, most called first
+    pub async fn get_tool_stats(&self) -> Result<Vec<ToolStats>> {
         let conn = Arc::clone(&self.conn);
 
         tokio::task::spawn_blocking(move || {
@@ -1542,289 +3973,645 @@ impl KnowledgeDb {
                 poisoned.into_inner()
             });
             let mut stmt = conn.prepare(
-                "SELECT id, description, status, reply_channel, spawned_by, created_at, updated_at, result
-                 FROM background_tasks WHERE status IN ('completed', 'failed')
-                 ORDER BY updated_at DESC LIMIT ?1",
+                "SELECT tool, calls, failures, total_duration_ms, output_chars, definition_chars,
+                        first_seen_at, last_used_at
+                 FROM tool_stats ORDER BY calls DESC, tool ASC",
             )?;
-            let tasks = stmt
-                .query_map(params![limit as i64], Self::row_to_background_task)?
+            let parse = |ts: &str| {
+                DateTime::parse_from_rfc3339(ts)
+                    .map(|dt| dt.with_timezone(&Utc))
+                    .unwrap_or_else(|_| Utc::now())
+            };
+            let stats = stmt
+                .query_map([], |row| {
+                    let first_seen_at: String = row.get(6)?;
+                    let last_used_at: Option<String> = row.get(7)?;
+                    Ok(ToolStats {
+                        tool: row.get(0)?,
+                        calls: row.get::<_, i64>(1)?.max(0) as u64,
+                        failures: row.get::<_, i64>(2)?.max(0) as u64,
+                        total_duration_ms: row.get::<_, i64>(3)?.max(0) as u64,
+                        output_chars: row.get::<_, i64>(4)?.max(0) as u64,
+                        definition_chars: row.get::<_, i64>(5)?.max(0) as u64,
+                        first_seen_at: parse(&first_seen_at),
+                        last_used_at: last_used_at.as_deref().map(parse),
+                    })
+                })?
                 .collect::<Result<Vec<_>, _>>()?;
-            Ok(tasks)
+            Ok(stats)
         })
         .await
         .context("spawn_blocking task panicked")?
     }
 
-    fn row_to_background_task(row: &rusqlite::Row) -> rusqlite::Result<BackgroundTask> {
-        Ok(BackgroundTask {
-            id: row.get(0)?,
-            description: row.get(1)?,
-            status: row.get(2)?,
-            reply_channel: row.get(3)?,
-            spawned_by: row.get(4)?,
-            created_at: row
-                .get::<_, String>(5)?
-                .parse()
-                .unwrap_or_else(|_| Utc::now()),
-            updated_at: row
-                .get::<_, String>(6)?
-                .parse()
-                .unwrap_or_else(|_| Utc::now()),
-            result: row.get(7)?,
-        })
-    }
-
-    // ── Usage Tracking ─────────────────────────────────────────────
-
-    /// Insert a usage log entry
-    #[allow(clippy::too_many_arguments)]
-    pub async fn insert_usage_log(
-        &self,
-        model: &str,
-        input_tokens: u64,
-        output_tokens: u64,
-        cache_read_tokens: u64,
-        cache_write_tokens: u64,
-        estimated_cost_usd: f64,
-        source: &str,
-        channel: Option<&str>,
-        tool_calls_count: u32,
-        tool_names: &str,
-        session_id: &str,
-    ) -> Result<()> {
+    /// Tools neither called nor newly registered in the last `days` days
+    pub async fn unused_tools(&self, days: u32) -> Result<Vec<String>> {
         let conn = Arc::clone(&self.conn);
-        let model = model.to_owned();
-        let source = source.to_owned();
-        let channel = channel.map(|s| s.to_owned());
-        let tool_names = tool_names.to_owned();
-        let session_id = session_id.to_owned();
+        let cutoff = (Utc::now() - chrono::Duration::days(days as i64)).to_rfc3339();
 
         tokio::task::spawn_blocking(move || {
-            let now = Utc::now();
             let conn = conn.lock().unwrap_or_else(|poisoned| {
                 warn!("Database mutex was poisoned, recovering");
                 poisoned.into_inner()
             });
-            conn.execute(
-                "INSERT INTO usage_log (timestamp, model, input_tokens, output_tokens, cache_read_tokens, cache_write_tokens, estimated_cost_usd, source, channel, tool_calls_count, tool_names, session_id)
-                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12)",
-                params![
-                    now.to_rfc3339(),
-                    &model,
-                    input_tokens as i64,
-                    output_tokens as i64,
-                    cache_read_tokens as i64,
-                    cache_write_tokens as i64,
-                    estimated_cost_usd,
-                    &source,
-                    channel,
-                    tool_calls_count as i64,
-                    &tool_names,
-                    &session_id,
-                ],
+            let mut stmt = conn.prepare(
+                "SELECT tool FROM tool_stats
+                 WHERE COALESCE(last_used_at, first_seen_at) < ?1
+                 ORDER BY tool",
             )?;
-            Ok(())
+            let tools = stmt
+                .query_map(params![&cutoff], |row| row.get(0))?
+                .collect::<Result<Vec<String>, _>>()?;
+            Ok(tools)
         })
         .await
         .context("spawn_blocking task panicked")?
     }
 
-    /// Get total estimated cost for a specific date (YYYY-MM-DD)
-    pub async fn get_usage_cost_for_date(&self, date: &str) -> Result<f64> {
+    // ── Event Journal ──────────────────────────────────────────────
+
+    /// Append an event to the journal, returning its sequence number
+    pub async fn append_event(&self, kind: &str, subject_id: &str, data: JsonValue) -> Result<i64> {
         let conn = Arc::clone(&self.conn);
-        let date = date.to_owned();
+        let kind = kind.to_owned();
+        let subject_id = subject_id.to_owned();
 
         tokio::task::spawn_blocking(move || {
             let conn = conn.lock().unwrap_or_else(|poisoned| {
                 warn!("Database mutex was poisoned, recovering");
                 poisoned.into_inner()
             });
-            let cost: f64 = conn
-                .query_row(
-                    "SELECT COALESCE(SUM(estimated_cost_usd), 0.0) FROM usage_log WHERE date(timestamp) = ?1",
-                    params![&date],
-                    |row| row.get(0),
-                )?;
-            Ok(cost)
+            conn.execute(
+                "INSERT INTO event_journal (kind, subject_id, data) VALUES (?1, ?2, ?3)",
+                params![&kind, &subject_id, serde_json::to_string(&data)?],
+            )?;
+            Ok(conn.last_insert_rowid())
         })
         .await
         .context("spawn_blocking task panicked")?
     }
 
-    /// Get total estimated cost for a date range (inclusive)
-    pub async fn get_usage_cost_for_range(&self, start: &str, end: &str) -> Result<f64> {
+    /// Journal events matching `query`, in sequence order
+    pub async fn get_events(&self, query: JournalQuery) -> Result<Vec<JournalEvent>> {
         let conn = Arc::clone(&self.conn);
-        let start = start.to_owned();
-        let end = end.to_owned();
+        // Same shape as the column default, so the text comparison orders by time
+        let timestamp = |t: DateTime<Utc>| t.format("%Y-%m-%dT%H:%M:%S%.3fZ").to_string();
+        let since = query.since.map(timestamp);
+        let until = query.until.map(timestamp);
 
         tokio::task::spawn_blocking(move || {
             let conn = conn.lock().unwrap_or_else(|poisoned| {
                 warn!("Database mutex was poisoned, recovering");
                 poisoned.into_inner()
             });
-            let cost: f64 = conn
-                .query_row(
-                    "SELECT COALESCE(SUM(estimated_cost_usd), 0.0) FROM usage_log WHERE date(timestamp) >= ?1 AND date(timestamp) <= ?2",
-                    params![&start, &end],
-                    |row| row.get(0),
-                )?;
-            Ok(cost)
+            let mut stmt = conn.prepare(
+                "SELECT seq, kind, subject_id, data, created_at FROM event_journal
+                 WHERE seq > ?1
+                   AND (?2 IS NULL OR created_at >= ?2)
+                   AND (?3 IS NULL OR created_at < ?3)
+                   AND (?4 IS NULL OR subject_id = ?4)
+                   AND (?5 IS NULL OR kind = ?5)
+                 ORDER BY seq ASC
+                 LIMIT ?6",
+            )?;
+            let events = stmt
+                .query_map(
+                    params![
+                        query.after_seq,
+                        &since,
+                        &until,
+                        &query.subject_id,
+                        &query.kind,
+                        query.limit.map_or(-1, |l| l as i64),
+                    ],
+                    |row| {
+                        let data: String = row.get(3)?;
+                        Ok(JournalEvent {
+                            seq: row.get(0)?,
+                            kind: row.get(1)?,
+                            subject_id: row.get(2)?,
+                            data: serde_json::from_str(&data).unwrap_or(JsonValue::Null),
+                            created_at: row
+                                .get::<_, String>(4)?
+                                .parse()
+                                .unwrap_or_else(|_| Utc::now()),
+                        })
+                    },
+                )?
+                .collect::<Result<Vec<_>, _>>()?;
+            Ok(events)
         })
         .await
         .context("spawn_blocking task panicked")?
     }
 
-    /// Get a usage summary for a date range
-    pub async fn get_usage_summary(&self, start: &str, end: &str) -> Result<UsageSummary> {
+    /// Conversations, actions, background task transitions and watcher
+    /// fires merged into one feed, newest first.
+    pub async fn timeline(&self, query: TimelineQuery) -> Result<Vec<TimelineEntry>> {
         let conn = Arc::clone(&self.conn);
-        let start = start.to_owned();
-        let end = end.to_owned();
+        // Conversations and the action log store RFC 3339 timestamps, the
+        // journal its own millisecond format; each is compared in its own
+        let rfc3339 = |t: DateTime<Utc>| t.to_rfc3339();
+        let journal = |t: DateTime<Utc>| t.format("%Y-%m-%dT%H:%M:%S%.3fZ").to_string();
+        let (since, until) = (query.since.map(rfc3339), query.until.map(rfc3339));
+        let (journal_since, journal_until) = (query.since.map(journal), query.until.map(journal));
+        let pattern = query
+            .contains
+            .as_deref()
+            .map(str::trim)
+            .filter(|c| !c.is_empty())
+            .map(|c| {
+                format!(
+                    "%{}%",
+                    c.replace('\\', "\\\\")
+                        .replace('%', "\\%")
+                        .replace('_', "\\_")
+                )
+            });
+        let limit = query.limit.map_or(-1, |l| l as i64);
 
         tokio::task::spawn_blocking(move || {
             let conn = conn.lock().unwrap_or_else(|poisoned| {
                 warn!("Database mutex was poisoned, recovering");
                 poisoned.into_inner()
             });
+            let parse_time = |t: String| t.parse().unwrap_or_else(|_| Utc::now());
+            let mut entries = Vec::new();
 
-            // Totals
-            let (total_input, total_output, total_calls, total_tools, total_cost): (i64, i64, i64, i64, f64) = conn
-                .query_row(
-                    "SELECT COALESCE(SUM(input_tokens), 0), COALESCE(SUM(output_tokens), 0), COUNT(*), COALESCE(SUM(tool_calls_count), 0), COALESCE(SUM(estimated_cost_usd), 0.0)
-                     FROM usage_log WHERE date(timestamp) >= ?1 AND date(timestamp) <= ?2",
-                    params![&start, &end],
-                    |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?, row.get(4)?)),
-                )?;
-
-            // By source
-            let mut by_source = std::collections::HashMap::new();
-            {
-                let mut stmt = conn.prepare(
-                    "SELECT source, SUM(input_tokens), SUM(output_tokens), COUNT(*), SUM(estimated_cost_usd)
-                     FROM usage_log WHERE date(timestamp) >= ?1 AND date(timestamp) <= ?2
-                     GROUP BY source",
-                )?;
-                let rows = stmt.query_map(params![&start, &end], |row| {
-                    Ok((
-                        row.get::<_, String>(0)?,
-                        row.get::<_, i64>(1)?,
-                        row.get::<_, i64>(2)?,
-                        row.get::<_, i64>(3)?,
-                        row.get::<_, f64>(4)?,
-                    ))
-                })?;
-                for row in rows {
-                    let (source, inp, out, calls, cost) = row?;
-                    by_source.insert(source, SourceUsage {
-                        input_tokens: inp as u64,
-                        output_tokens: out as u64,
-                        api_calls: calls as u64,
-                        estimated_cost_usd: cost,
-                    });
-                }
+            if query.wants(TimelineSource::Conversation) {
+                let mut stmt = conn.prepare(
+                    "SELECT id, channel, sender, content, created_at FROM conversations
+                     WHERE (?1 IS NULL OR created_at >= ?1)
+                       AND (?2 IS NULL OR created_at < ?2)
+                       AND (?3 IS NULL OR channel = ?3)
+                       AND (?4 IS NULL OR content LIKE ?4 ESCAPE '\\')
+                     ORDER BY created_at DESC LIMIT ?5",
+                )?;
+                let rows = stmt.query_map(
+                    params![&since, &until, &query.channel, &pattern, limit],
+                    |row| {
+                        let sender: String = row.get(2)?;
+                        Ok(TimelineEntry {
+                            source: TimelineSource::Conversation,
+                            summary: timeline_summary(&format!(
+                                "{}: {}",
+                                sender,
+                                row.get::<_, String>(3)?
+                            )),
+                            kind: sender,
+                            subject_id: row.get(0)?,
+                            channel: Some(row.get(1)?),
+                            at: parse_time(row.get(4)?),
+                        })
+                    },
+                )?;
+                entries.extend(rows.collect::<Result<Vec<_>, _>>()?);
             }
 
-            // By model
-            let mut by_model = std::collections::HashMap::new();
-            {
+            if query.wants(TimelineSource::Action) && query.channel.is_none() {
                 let mut stmt = conn.prepare(
-                    "SELECT model, SUM(input_tokens), SUM(output_tokens), COUNT(*), SUM(estimated_cost_usd)
-                     FROM usage_log WHERE date(timestamp) >= ?1 AND date(timestamp) <= ?2
-                     GROUP BY model",
+                    "SELECT id, action_type, description, outcome, created_at FROM action_log
+                     WHERE (?1 IS NULL OR created_at >= ?1)
+                       AND (?2 IS NULL OR created_at < ?2)
+                       AND (?3 IS NULL OR description LIKE ?3 ESCAPE '\\')
+                     ORDER BY created_at DESC LIMIT ?4",
                 )?;
-                let rows = stmt.query_map(params![&start, &end], |row| {
-                    Ok((
-                        row.get::<_, String>(0)?,
-                        row.get::<_, i64>(1)?,
-                        row.get::<_, i64>(2)?,
-                        row.get::<_, i64>(3)?,
-                        row.get::<_, f64>(4)?,
-                    ))
+                let rows = stmt.query_map(params![&since, &until, &pattern, limit], |row| {
+                    Ok(TimelineEntry {
+                        source: TimelineSource::Action,
+                        subject_id: row.get(0)?,
+                        kind: row.get(1)?,
+                        summary: timeline_summary(&format!(
+                            "{} ({})",
+                            row.get::<_, String>(2)?,
+                            row.get::<_, String>(3)?
+                        )),
+                        channel: None,
+                        at: parse_time(row.get(4)?),
+                    })
                 })?;
-                for row in rows {
-                    let (model, inp, out, calls, cost) = row?;
-                    by_model.insert(model, ModelUsage {
-                        input_tokens: inp as u64,
-                        output_tokens: out as u64,
-                        api_calls: calls as u64,
-                        estimated_cost_usd: cost,
-                    });
-                }
+                entries.extend(rows.collect::<Result<Vec<_>, _>>()?);
             }
 
-            let period = if start == end {
-                start.clone()
-            } else {
-                format!("{} to {}", start, end)
-            };
+            let tasks = query.wants(TimelineSource::Task);
+            let watchers = query.wants(TimelineSource::Watcher);
+            if tasks || watchers {
+                let mut stmt = conn.prepare(
+                    "SELECT e.kind, e.subject_id, e.data, e.created_at,
+                            COALESCE(t.description, json_extract(e.data, '$.description'), ''),
+                            COALESCE(t.reply_channel, w.reply_channel)
+                     FROM event_journal e
+                     LEFT JOIN background_tasks t
+                       ON e.kind IN ('task_created', 'task_state_changed') AND t.id = e.subject_id
+                     LEFT JOIN watchers w
+                       ON e.kind = 'watcher_fired' AND w.id = e.subject_id
+                     WHERE ((?1 AND e.kind IN ('task_created', 'task_state_changed'))
+                            OR (?2 AND e.kind = 'watcher_fired'))
+                       AND (?3 IS NULL OR e.created_at >= ?3)
+                       AND (?4 IS NULL OR e.created_at < ?4)
+                       AND (?5 IS NULL OR COALESCE(t.reply_channel, w.reply_channel) = ?5)
+                       AND (?6 IS NULL OR e.data LIKE ?6 ESCAPE '\\'
+                            OR t.description LIKE ?6 ESCAPE '\\')
+                     ORDER BY e.seq DESC LIMIT ?7",
+                )?;
+                let rows = stmt.query_map(
+                    params![
+                        tasks,
+                        watchers,
+                        &journal_since,
+                        &journal_until,
+                        &query.channel,
+                        &pattern,
+                        limit,
+                    ],
+                    |row| {
+                        let kind: String = row.get(0)?;
+                        let data: JsonValue =
+                            serde_json::from_str(&row.get::<_, String>(2)?).unwrap_or_default();
+                        let field = |key: &str| {
+                            data.get(key)
+                                .map(|v| {
+                                    v.as_str()
+                                        .map(String::from)
+                                        .unwrap_or_else(|| v.to_string())
+                                })
+                                .unwrap_or_default()
+                        };
+                        let description: String = row.get(4)?;
+                        let (source, summary) = match kind.as_str() {
+                            "task_created" => {
+                                (TimelineSource::Task, format!("Started: {}", description))
+                            }
+                            "task_state_changed" => {
+                                let mut summary =
+                                    format!("{}: {} → {}", description, field("from"), field("to"));
+                                if let Some(result) = data.get("result").and_then(|r| r.as_str()) {
+                                    summary = format!("{} — {}", summary, result);
+                                }
+                                (TimelineSource::Task, summary)
+                            }
+                            _ => (
+                                TimelineSource::Watcher,
+                                format!("{} {}", field("kind"), field("payload")),
+                            ),
+                        };
+                        Ok(TimelineEntry {
+                            source,
+                            kind,
+                            subject_id: row.get(1)?,
+                            summary: timeline_summary(&summary),
+                            channel: row.get(5)?,
+                            at: parse_time(row.get(3)?),
+                        })
+                    },
+                )?;
+                entries.extend(rows.collect::<Result<Vec<_>, _>>()?);
+            }
 
-            Ok(UsageSummary {
-                period,
-                total_input_tokens: total_input as u64,
-                total_output_tokens: total_output as u64,
-                total_api_calls: total_calls as u64,
-                total_tool_calls: total_tools as u64,
-                estimated_cost_usd: total_cost,
-                by_source,
-                by_model,
-            })
+            // Stable, so same-instant entries from one source keep their order
+            entries.sort_by_key(|e| std::cmp::Reverse(e.at));
+            if let Some(limit) = query.limit {
+                entries.truncate(limit);
+            }
+            Ok(entries)
         })
         .await
         .context("spawn_blocking task panicked")?
     }
 
-    /// Export usage data as CSV for a date range
-    pub async fn export_usage_csv(&self, start: &str, end: &str) -> Result<String> {
+    // ── Replication ────────────────────────────────────────────────
+
+    /// Entity and relationship changes after `since` (a cursor from an
+    /// earlier batch, or 0 for everything), at most `limit` of them
+    pub async fn changes_since(&self, since: i64, limit: usize) -> Result<ChangeBatch> {
         let conn = Arc::clone(&self.conn);
-        let start = start.to_owned();
-        let end = end.to_owned();
 
         tokio::task::spawn_blocking(move || {
             let conn = conn.lock().unwrap_or_else(|poisoned| {
                 warn!("Database mutex was poisoned, recovering");
                 poisoned.into_inner()
             });
-
-            let mut csv = String::from("timestamp,model,input_tokens,output_tokens,cache_read_tokens,cache_write_tokens,estimated_cost_usd,source,channel,tool_calls_count,tool_names,session_id\n");
-
             let mut stmt = conn.prepare(
-                "SELECT timestamp, model, input_tokens, output_tokens, cache_read_tokens, cache_write_tokens, estimated_cost_usd, source, channel, tool_calls_count, tool_names, session_id
-                 FROM usage_log WHERE date(timestamp) >= ?1 AND date(timestamp) <= ?2
-                 ORDER BY timestamp ASC",
+                "SELECT seq, kind, item_id, deleted_at FROM replication_log
+                 WHERE seq > ?1 ORDER BY seq ASC LIMIT ?2",
             )?;
+            let rows = stmt
+                .query_map(params![since, limit as i64 + 1], |row| {
+                    Ok((
+                        row.get::<_, i64>(0)?,
+                        row.get::<_, String>(1)?,
+                        row.get::<_, String>(2)?,
+                        row.get::<_, Option<String>>(3)?,
+                    ))
+                })?
+                .collect::<Result<Vec<_>, _>>()?;
 
-            let rows = stmt.query_map(params![&start, &end], |row| {
-                Ok((
-                    row.get::<_, String>(0)?,
-                    row.get::<_, String>(1)?,
-                    row.get::<_, i64>(2)?,
-                    row.get::<_, i64>(3)?,
-                    row.get::<_, i64>(4)?,
-                    row.get::<_, i64>(5)?,
-                    row.get::<_, f64>(6)?,
-                    row.get::<_, String>(7)?,
-                    row.get::<_, Option<String>>(8)?,
-                    row.get::<_, i64>(9)?,
-                    row.get::<_, Option<String>>(10)?,
-                    row.get::<_, Option<String>>(11)?,
-                ))
-            })?;
+            let mut batch = ChangeBatch {
+                changes: Vec::new(),
+                cursor: since,
+                more: rows.len() > limit,
+            };
+            for (seq, kind, id, deleted_at) in rows.into_iter().take(limit) {
+                batch.cursor = seq;
+                let change = match (deleted_at, kind.as_str()) {
+                    (Some(deleted_at), _) => Some(ReplicatedChange::Tombstone {
+                        item: kind,
+                        id,
+                        deleted_at: deleted_at.parse().unwrap_or_else(|_| Utc::now()),
+                    }),
+                    (None, "entity") => conn
+                        .query_row(
+                            &format!("SELECT {} FROM entities WHERE id = ?1", ENTITY_COLUMNS),
+                            params![&id],
+                            Self::row_to_entity,
+                        )
+                        .optional()?
+                        .map(ReplicatedChange::Entity),
+                    (None, "relationship") => conn
+                        .query_row(
+                            "SELECT id, source_id, target_id, relation_type, metadata, created_at
+                             FROM relationships WHERE id = ?1",
+                            params![&id],
+                            Self::row_to_relationship,
+                        )
+                        .optional()?
+                        .map(ReplicatedChange::Relationship),
+                    _ => None,
+                };
+                batch.changes.extend(change);
+            }
+            Ok(batch)
+        })
+        .await
+        .context("spawn_blocking task panicked")?
+    }
 
-            for row in rows {
-                let (ts, model, inp, out, cr, cw, cost, src, ch, tc, tn, sid) = row?;
-                csv.push_str(&format!(
-                    "{},{},{},{},{},{},{:.6},{},{},{},{},{}\n",
-                    ts, model, inp, out, cr, cw, cost, src,
-                    ch.unwrap_or_default(), tc,
-                    tn.unwrap_or_default(),
-                    sid.unwrap_or_default(),
-                ));
+    /// Apply changes from another instance. Last write wins: an entity is
+    /// only replaced by a newer copy (ties broken by content so both sides
+    /// settle on the same one), and a tombstone only deletes rows that
+    /// weren't updated after the deletion. Relationships whose endpoints
+    /// haven't arrived yet are kept and retried on later calls.
+    pub async fn apply_changes(&self, changes: Vec<ReplicatedChange>) -> Result<ReplicationReport> {
+        let conn = Arc::clone(&self.conn);
+
+        tokio::task::spawn_blocking(move || {
+            let conn = conn.lock().unwrap_or_else(|poisoned| {
+                warn!("Database mutex was poisoned, recovering");
+                poisoned.into_inner()
+            });
+            let tx = conn.unchecked_transaction()?;
+            let mut report = ReplicationReport::default();
+
+            // Entities and tombstones first, so relationships in the same
+            // batch find their endpoints
+            let mut relationships = Vec::new();
+            for change in changes {
+                match change {
+                    ReplicatedChange::Entity(entity) => {
+                        if Self::apply_entity(&tx, &entity)? {
+                            report.entities.push(entity);
+                        } else {
+                            report.skipped += 1;
+                        }
+                    }
+                    ReplicatedChange::Relationship(relationship) => {
+                        relationships.push(relationship)
+                    }
+                    ReplicatedChange::Tombstone {
+                        item,
+                        id,
+                        deleted_at,
+                    } => {
+                        if !Self::apply_tombstone(&tx, &item, &id, deleted_at)? {
+                            report.skipped += 1;
+                        } else if item == "entity" {
+                            report.removed.push(id);
+                        }
+                    }
+                }
             }
 
-            Ok(csv)
+            let pending: Vec<Relationship> = tx
+                .prepare("SELECT payload FROM replication_pending ORDER BY received_at ASC")?
+                .query_map([], |row| row.get::<_, String>(0))?
+                .filter_map(|payload| serde_json::from_str(&payload.ok()?).ok())
+                .collect();
+            let pending_count = pending.len();
+            for (i, relationship) in pending.into_iter().chain(relationships).enumerate() {
+                let outcome = Self::apply_relationship(&tx, &relationship)?;
+                if outcome == Some(false) {
+                    tx.execute(
+                        "INSERT OR IGNORE INTO replication_pending (id, payload, received_at)
+                         VALUES (?1, ?2, ?3)",
+                        params![
+                            &relationship.id,
+                            serde_json::to_string(&relationship)?,
+                            Utc::now().to_rfc3339()
+                        ],
+                    )?;
+                    report.deferred += 1;
+                    continue;
+                }
+                tx.execute(
+                    "DELETE FROM replication_pending WHERE id = ?1",
+                    params![&relationship.id],
+                )?;
+                match outcome {
+                    Some(_) => report.relationships += 1,
+                    None if i >= pending_count => report.skipped += 1,
+                    None => {}
+                }
+            }
+            // Endpoints that never showed up
+            tx.execute(
+                "DELETE FROM replication_pending WHERE received_at < ?1",
+                params![(Utc::now() - chrono::Duration::days(30)).to_rfc3339()],
+            )?;
+
+            tx.commit()?;
+            debug!(
+                "Applied replicated changes: {} entities, {} relationships, {} removed, {} skipped, {} deferred",
+                report.entities.len(),
+                report.relationships,
+                report.removed.len(),
+                report.skipped,
+                report.deferred
+            );
+            Ok(report)
         })
         .await
         .context("spawn_blocking task panicked")?
     }
+
+    /// When `kind`/`id` was deleted, if it's currently deleted
+    fn tombstone_time(
+        conn: &Connection,
+        kind: &str,
+        id: &str,
+    ) -> rusqlite::Result<Option<DateTime<Utc>>> {
+        let deleted_at: Option<String> = conn
+            .query_row(
+                "SELECT deleted_at FROM replication_log
+                 WHERE kind = ?1 AND item_id = ?2 AND deleted_at IS NOT NULL",
+                params![kind, id],
+                |row| row.get(0),
+            )
+            .optional()?;
+        Ok(deleted_at.map(|d| d.parse().unwrap_or_else(|_| Utc::now())))
+    }
+
+    /// Insert or replace an entity if the incoming copy wins
+    fn apply_entity(conn: &Connection, entity: &Entity) -> Result<bool> {
+        if Self::tombstone_time(conn, "entity", &entity.id)?
+            .is_some_and(|deleted_at| deleted_at >= entity.updated_at)
+        {
+            return Ok(false);
+        }
+        // Forgotten here; another instance doesn't bring it back
+        if Self::name_forgotten(conn, &entity.name)? {
+            return Ok(false);
+        }
+        let local = conn
+            .query_row(
+                &format!("SELECT {} FROM entities WHERE id = ?1", ENTITY_COLUMNS),
+                params![&entity.id],
+                Self::row_to_entity,
+            )
+            .optional()?;
+        if let Some(local) = local {
+            let fingerprint = |e: &Entity| {
+                serde_json::to_string(&(&e.name, &e.entity_type, &e.metadata, &e.aliases))
+            };
+            let newer = entity.updated_at > local.updated_at
+                || (entity.updated_at == local.updated_at
+                    && fingerprint(entity)? > fingerprint(&local)?);
+            if !newer {
+                return Ok(false);
+            }
+        }
+
+        let metadata_json = entity
+            .metadata
+            .as_ref()
+            .map(serde_json::to_string)
+            .transpose()?;
+        conn.execute(
+            "INSERT INTO entities (id, name, entity_type, metadata, created_at, updated_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)
+             ON CONFLICT(id) DO UPDATE SET
+                 name = excluded.name,
+                 entity_type = excluded.entity_type,
+                 metadata = excluded.metadata,
+                 updated_at = excluded.updated_at",
+            params![
+                &entity.id,
+                &entity.name,
+                &entity.entity_type,
+                metadata_json,
+                entity.created_at.to_rfc3339(),
+                entity.updated_at.to_rfc3339(),
+            ],
+        )?;
+        conn.execute(
+            "DELETE FROM entity_aliases WHERE entity_id = ?1",
+            params![&entity.id],
+        )?;
+        for alias in &entity.aliases {
+            conn.execute(
+                "INSERT OR IGNORE INTO entity_aliases (entity_id, alias, created_at)
+                 VALUES (?1, ?2, ?3)",
+                params![&entity.id, alias, entity.updated_at.to_rfc3339()],
+            )?;
+        }
+        Ok(true)
+    }
+
+    /// Delete an entity or relationship unless it changed after `deleted_at`
+    fn apply_tombstone(
+        conn: &Connection,
+        item: &str,
+        id: &str,
+        deleted_at: DateTime<Utc>,
+    ) -> Result<bool> {
+        let table = match item {
+            "entity" => "entities",
+            "relationship" => "relationships",
+            _ => return Ok(false),
+        };
+        if Self::tombstone_time(conn, item, id)?.is_some_and(|known| known >= deleted_at) {
+            return Ok(false);
+        }
+        if item == "entity" {
+            let updated_at: Option<String> = conn
+                .query_row(
+                    "SELECT updated_at FROM entities WHERE id = ?1",
+                    params![id],
+                    |row| row.get(0),
+                )
+                .optional()?;
+            if updated_at
+                .and_then(|u| u.parse::<DateTime<Utc>>().ok())
+                .is_some_and(|u| u > deleted_at)
+            {
+                return Ok(false);
+            }
+        }
+
+        conn.execute(&format!("DELETE FROM {} WHERE id = ?1", table), params![id])?;
+        // Keep the original deletion time rather than the trigger's "now"
+        conn.execute(
+            "INSERT OR REPLACE INTO replication_log (kind, item_id, deleted_at)
+             VALUES (?1, ?2, ?3)",
+            params![item, id, deleted_at.to_rfc3339()],
+        )?;
+        Ok(true)
+    }
+
+    /// Insert a relationship: Some(true) if added, Some(false) if an
+    /// endpoint is missing for now, None if it's known or was deleted
+    fn apply_relationship(conn: &Connection, relationship: &Relationship) -> Result<Option<bool>> {
+        let exists = |table: &str, id: &str| -> rusqlite::Result<bool> {
+            conn.query_row(
+                &format!("SELECT 1 FROM {} WHERE id = ?1", table),
+                params![id],
+                |_| Ok(()),
+            )
+            .optional()
+            .map(|row| row.is_some())
+        };
+        if exists("relationships", &relationship.id)?
+            || Self::tombstone_time(conn, "relationship", &relationship.id)?.is_some()
+        {
+            return Ok(None);
+        }
+        for endpoint in [&relationship.source_id, &relationship.target_id] {
+            if !exists("entities", endpoint)? {
+                return Ok(match Self::tombstone_time(conn, "entity", endpoint)? {
+                    Some(_) => None,
+                    None => Some(false),
+                });
+            }
+        }
+
+        let metadata_json = relationship
+            .metadata
+            .as_ref()
+            .map(serde_json::to_string)
+            .transpose()?;
+        conn.execute(
+            "INSERT INTO relationships (id, source_id, target_id, relation_type, metadata, created_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            params![
+                &relationship.id,
+                &relationship.source_id,
+                &relationship.target_id,
+                &relationship.relation_type,
+                metadata_json,
+                relationship.created_at.to_rfc3339(),
+            ],
+        )?;
+        Ok(Some(true))
+    }
 }
 
 #[cfg(test)]
@@ -1856,6 +4643,64 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn test_tag_filters() -> Result<()> {
+        let temp_path = env::temp_dir().join("test_tags.db");
+        let _ = std::fs::remove_file(&temp_path);
+
+        let db = KnowledgeDb::new(&temp_path)?;
+        let tiles = db
+            .insert_entity(
+                "Tile quote",
+                "document",
+                Some(serde_json::json!({"tags": ["kitchen-renovation"]})),
+            )
+            .await?;
+        db.insert_entity(
+            "Tax return",
+            "document",
+            Some(serde_json::json!({"note": "x"})),
+        )
+        .await?;
+        db.insert_entity("Plain", "document", None).await?;
+        let convo = db
+            .insert_conversation("slack", "alice", "new cabinets arrive monday", None)
+            .await?;
+        db.update_conversation_metadata(
+            &convo,
+            serde_json::json!({"tags": ["kitchen-renovation", "deliveries"]}),
+        )
+        .await?;
+
+        let tagged = db
+            .search_entities_tagged("", Some("document"), Some("kitchen"))
+            .await?;
+        assert_eq!(tagged.len(), 1);
+        assert_eq!(tagged[0].id, tiles);
+        assert_eq!(db.search_entities("", None).await?.len(), 3);
+
+        let convos = db
+            .get_conversations_by_tag("kitchen-renovation", 10)
+            .await?;
+        assert_eq!(convos.len(), 1);
+        assert!(db.get_conversations_by_tag("taxes", 10).await?.is_empty());
+
+        let counts = db.get_tag_counts().await?;
+        assert_eq!(counts[0], ("kitchen-renovation".to_string(), 2));
+        assert_eq!(counts.len(), 2);
+
+        db.update_entity_metadata(&tiles, serde_json::json!({}))
+            .await?;
+        assert!(
+            db.search_entities_tagged("", None, Some("kitchen"))
+                .await?
+                .is_empty()
+        );
+
+        let _ = std::fs::remove_file(&temp_path);
+        Ok(())
+    }
+
     #[tokio::test]
     async fn test_relationship_operations() -> Result<()> {
         let temp_path = env::temp_dir().join("test_relationships.db");
@@ -2299,6 +5144,148 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn test_entity_aliases() -> Result<()> {
+        let temp = tempfile::TempDir::new()?;
+        let db = KnowledgeDb::new(temp.path().join("test.db"))?;
+        let k8s = db.insert_entity("Kubernetes", "concept", None).await?;
+        let liz = db.insert_entity("Elizabeth Chen", "person", None).await?;
+
+        assert!(db.add_alias(&k8s, "K8s").await?);
+        assert!(!db.add_alias(&k8s, "k8s").await?);
+        assert!(!db.add_alias(&k8s, "kubernetes").await?);
+        assert!(db.add_alias(&liz, "Liz").await?);
+        assert!(db.add_alias(&k8s, " ").await.is_err());
+        assert!(db.add_alias("missing", "x").await.is_err());
+
+        assert_eq!(db.get_entity(&k8s).await?.unwrap().aliases, ["K8s"]);
+        assert_eq!(db.resolve_entity("liz").await?.unwrap().id, liz);
+        assert_eq!(db.resolve_entity("elizabeth chen").await?.unwrap().id, liz);
+        assert_eq!(db.resolve_entity(&k8s).await?.unwrap().id, k8s);
+        assert!(db.resolve_entity("Lizzy").await?.is_none());
+        // Name searches also match aliases
+        let found = db.search_entities("k8", None).await?;
+        assert_eq!(found.len(), 1);
+
+        assert!(db.remove_alias(&k8s, "K8S").await?);
+        assert!(!db.remove_alias(&k8s, "K8s").await?);
+        assert!(db.get_entity(&k8s).await?.unwrap().aliases.is_empty());
+
+        // Aliases go with the entity
+        db.delete_entity(&liz).await?;
+        assert!(db.find_entities_by_alias("Liz").await?.is_empty());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_timeline_merges_sources() -> Result<()> {
+        let temp = tempfile::TempDir::new()?;
+        let db = KnowledgeDb::new(temp.path().join("test.db"))?;
+        db.insert_conversation("discord", "alex", "What's on today?", None)
+            .await?;
+        db.insert_action_log(
+            None,
+            "reminder",
+            "Reminded Alex about the 50% deposit",
+            "success",
+        )
+        .await?;
+        db.insert_background_task("t-1", "Summarize inbox", "discord", "agent")
+            .await?;
+        db.update_background_task("t-1", "completed", Some("3 emails need replies"))
+            .await?;
+        let watcher = db
+            .insert_watcher("email", serde_json::json!({}), "triage", "slack")
+            .await?;
+        db.append_event(
+            "watcher_fired",
+            &watcher,
+            serde_json::json!({ "kind": "email_received", "payload": "Invoice" }),
+        )
+        .await?;
+
+        let all = db.timeline(TimelineQuery::default()).await?;
+        let sources: Vec<TimelineSource> = all.iter().map(|e| e.source).collect();
+        assert_eq!(sources.len(), 5);
+        assert!(all.windows(2).all(|w| w[0].at >= w[1].at));
+        let done = all.iter().find(|e| e.kind == "task_state_changed").unwrap();
+        assert_eq!(
+            done.summary,
+            "Summarize inbox: pending → completed — 3 emails need replies"
+        );
+        assert_eq!(done.channel.as_deref(), Some("discord"));
+
+        let on_discord = db
+            .timeline(TimelineQuery {
+                channel: Some("discord".into()),
+                ..Default::default()
+            })
+            .await?;
+        assert!(
+            on_discord
+                .iter()
+                .all(|e| e.channel.as_deref() == Some("discord"))
+        );
+        assert_eq!(on_discord.len(), 3);
+
+        let watchers = db
+            .timeline(TimelineQuery {
+                sources: vec![TimelineSource::Watcher],
+                ..Default::default()
+            })
+            .await?;
+        assert_eq!(watchers.len(), 1);
+        assert_eq!(watchers[0].summary, "email_received Invoice");
+
+        // `%` is matched literally
+        let deposit = db
+            .timeline(TimelineQuery {
+                contains: Some("50%".into()),
+                ..Default::default()
+            })
+            .await?;
+        assert_eq!(deposit.len(), 1);
+        assert_eq!(deposit[0].source, TimelineSource::Action);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_event_journal_is_append_only() -> Result<()> {
+        let temp = tempfile::TempDir::new()?;
+        let db = KnowledgeDb::new(temp.path().join("test.db"))?;
+        let seq = db
+            .append_event("watcher_fired", "w-1", serde_json::json!({}))
+            .await?;
+
+        {
+            let conn = db.conn.lock().unwrap();
+            assert!(
+                conn.execute("UPDATE event_journal SET kind = 'x'", [])
+                    .is_err()
+            );
+            assert!(conn.execute("DELETE FROM event_journal", []).is_err());
+        }
+
+        let since = Utc::now() - chrono::Duration::minutes(1);
+        let events = db
+            .get_events(JournalQuery {
+                since: Some(since),
+                kind: Some("watcher_fired".to_string()),
+                ..Default::default()
+            })
+            .await?;
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].seq, seq);
+        let later = db
+            .get_events(JournalQuery {
+                since: Some(Utc::now() + chrono::Duration::minutes(1)),
+                ..Default::default()
+            })
+            .await?;
+        assert!(later.is_empty());
+        Ok(())
+    }
+
     #[tokio::test]
     async fn test_entity_with_metadata() -> Result<()> {
         let temp_path = env::temp_dir().join(format!("test_entity_meta_{}.db", std::process::id()));
@@ -2594,6 +5581,9 @@ mod tests {
 
         let active = db.get_active_goals().await?;
         assert!(active.is_empty());
+        let goal = db.get_goal(&id).await?.unwrap();
+        assert_eq!(goal.status, "completed");
+        assert!(db.get_goal("missing").await?.is_none());
 
         let _ = std::fs::remove_file(&temp_path);
         Ok(())
@@ -2651,6 +5641,11 @@ mod tests {
         let all = db.get_preferences(None).await?;
         assert_eq!(all.len(), 2);
 
+        assert!(!db.delete_category_preference("lang", "theme").await?);
+        assert!(db.delete_preference("theme").await?);
+        assert!(!db.delete_preference("theme").await?);
+        assert!(db.get_preferences(Some("ui")).await?.is_empty());
+
         let _ = std::fs::remove_file(&temp_path);
         Ok(())
     }
@@ -2700,6 +5695,7 @@ mod tests {
             name: "Test".to_string(),
             entity_type: "concept".to_string(),
             metadata: Some(serde_json::json!({"key": "val"})),
+            aliases: Vec::new(),
             created_at: Utc::now(),
             updated_at: Utc::now(),
         };
@@ -2726,6 +5722,48 @@ mod tests {
         assert!(!json.contains("metadata"));
     }
 
+    #[tokio::test]
+    async fn test_feedback_summary() -> Result<()> {
+        let temp_dir = tempfile::TempDir::new()?;
+        let db = KnowledgeDb::new(temp_dir.path().join("feedback.db"))?;
+
+        let tools = ["web_search".to_string(), "web_search".to_string()];
+        for (channel, rating, model, tools) in [
+            ("slack", 1, "claude-sonnet-4-5", &tools[..]),
+            ("slack", -1, "claude-sonnet-4-5", &[][..]),
+            ("discord", 1, "gpt-4o", &tools[..]),
+        ] {
+            db.insert_feedback(NewFeedback {
+                channel,
+                sender: "alice",
+                rating,
+                model: Some(model),
+                tools,
+                ..Default::default()
+            })
+            .await?;
+        }
+
+        let today = Utc::now().format("%Y-%m-%d").to_string();
+        let feedback = db.get_feedback(&today, &today).await?;
+        assert_eq!(feedback.len(), 3);
+        assert_eq!(feedback[0].tools.len(), 2);
+
+        let summary = db.get_feedback_summary(&today, &today).await?;
+        assert_eq!(summary.overall.total(), 3);
+        assert_eq!(summary.overall.positive, 2);
+        assert_eq!(summary.by_channel["slack"].satisfaction(), 0.5);
+        assert_eq!(summary.by_model["gpt-4o"].positive, 1);
+        // Repeated tool calls in one response count once
+        assert_eq!(summary.by_tool["web_search"].positive, 2);
+        assert_eq!(summary.by_tool["(none)"].negative, 1);
+
+        let empty = db.get_feedback_summary("2000-01-01", "2000-01-01").await?;
+        assert_eq!(empty.overall.total(), 0);
+        assert_eq!(empty.overall.satisfaction(), 0.0);
+        Ok(())
+    }
+
     #[tokio::test]
     async fn test_conversation_serde_roundtrip() {
         let conv = Conversation {
@@ -2740,4 +5778,183 @@ mod tests {
         let parsed: Conversation = serde_json::from_str(&json).unwrap();
         assert_eq!(parsed.sender, "alice");
     }
+
+    #[tokio::test]
+    async fn test_app_usage() -> Result<()> {
+        let temp_dir = tempfile::TempDir::new()?;
+        let db = KnowledgeDb::new(temp_dir.path().join("app_usage.db"))?;
+
+        db.add_app_usage("2026-03-02", "Xcode", 60).await?;
+        db.add_app_usage("2026-03-02", "Xcode", 60).await?;
+        db.add_app_usage("2026-03-03", "Xcode", 300).await?;
+        db.add_app_usage("2026-03-03", "Safari", 600).await?;
+        db.add_app_usage("2026-03-09", "Safari", 60).await?;
+
+        let week = db.get_app_usage("2026-03-02", "2026-03-08").await?;
+        assert_eq!(week.len(), 2);
+        assert_eq!(week[0].app, "Safari");
+        assert_eq!(week[0].seconds, 600);
+        assert_eq!(week[1].seconds, 420);
+        assert_eq!(week[1].days, 2);
+
+        assert!(
+            db.get_app_usage("2026-04-01", "2026-04-30")
+                .await?
+                .is_empty()
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_tool_stats() -> Result<()> {
+        let temp_dir = tempfile::TempDir::new()?;
+        let db = KnowledgeDb::new(temp_dir.path().join("tool_stats.db"))?;
+
+        db.sync_tool_catalog(vec![("read_file".into(), 400), ("read_pdf".into(), 300)])
+            .await?;
+        let call = |tool: &str, ok| ToolCallRecord {
+            tool: tool.to_string(),
+            ok,
+            duration_ms: 50,
+            output_chars: 1000,
+        };
+        db.record_tool_calls(vec![call("read_file", true), call("read_file", false)])
+            .await?;
+        db.record_tool_calls(vec![call("web_search", true)]).await?;
+
+        let stats = db.get_tool_stats().await?;
+        let names: Vec<&str> = stats.iter().map(|s| s.tool.as_str()).collect();
+        assert_eq!(names, ["read_file", "web_search", "read_pdf"]);
+        assert_eq!(stats[0].calls, 2);
+        assert_eq!(stats[0].success_rate(), 0.5);
+        assert_eq!(stats[0].output_chars, 2000);
+        assert_eq!(stats[0].definition_chars, 400);
+        assert!(stats[2].last_used_at.is_none());
+
+        // Nothing is unused yet; backdate read_pdf's registration
+        assert!(db.unused_tools(30).await?.is_empty());
+        {
+            let conn = db.conn.lock().unwrap();
+            conn.execute(
+                "UPDATE tool_stats SET first_seen_at = '2020-01-01T00:00:00+00:00' WHERE tool = 'read_pdf'",
+                [],
+            )?;
+        }
+        assert_eq!(db.unused_tools(30).await?, ["read_pdf"]);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_snapshot_is_consistent() -> Result<()> {
+        let temp_dir = tempfile::TempDir::new()?;
+        let db = KnowledgeDb::new(temp_dir.path().join("snapshot.db"))?;
+        db.insert_entity("Alice", "person", None).await?;
+
+        let snapshot = db.snapshot().await?;
+        // Writers aren't blocked, and the snapshot doesn't see their changes
+        db.insert_entity("Bob", "person", None).await?;
+        assert_eq!(snapshot.search_entities("", Some("person")).await?.len(), 1);
+        assert_eq!(db.search_entities("", Some("person")).await?.len(), 2);
+        assert!(
+            snapshot
+                .insert_entity("Carol", "person", None)
+                .await
+                .is_err()
+        );
+
+        drop(snapshot);
+        let snapshot = db.snapshot().await?;
+        assert_eq!(snapshot.search_entities("", Some("person")).await?.len(), 2);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_replication_last_write_wins() -> Result<()> {
+        let temp_dir = tempfile::TempDir::new()?;
+        let laptop = KnowledgeDb::new(temp_dir.path().join("laptop.db"))?;
+        let desktop = KnowledgeDb::new(temp_dir.path().join("desktop.db"))?;
+
+        let alice = laptop.insert_entity("Alice", "person", None).await?;
+        let acme = laptop.insert_entity("Acme", "company", None).await?;
+        laptop
+            .insert_relationship(&alice, &acme, "works_at", None)
+            .await?;
+
+        // A relationship that arrives before its endpoints waits for them
+        let batch = laptop.changes_since(0, 100).await?;
+        assert_eq!(batch.changes.len(), 3);
+        assert!(!batch.more);
+        let (relationships, entities): (Vec<_>, Vec<_>) = batch
+            .changes
+            .into_iter()
+            .partition(|c| matches!(c, ReplicatedChange::Relationship(_)));
+        assert_eq!(desktop.apply_changes(relationships).await?.deferred, 1);
+        let report = desktop.apply_changes(entities).await?;
+        assert_eq!(report.entities.len(), 2);
+        assert_eq!(report.relationships, 1);
+        assert_eq!(desktop.get_relationships_for(&alice).await?.len(), 1);
+
+        // Echoing the desktop's copies back changes nothing
+        let echo = desktop.changes_since(0, 100).await?;
+        let report = laptop.apply_changes(echo.changes).await?;
+        assert!(report.entities.is_empty());
+        assert_eq!(report.skipped, 3);
+
+        // Newer copies win, older ones are ignored
+        let mut renamed = desktop.get_entity(&alice).await?.unwrap();
+        renamed.name = "Alice Smith".to_string();
+        renamed.updated_at += chrono::Duration::seconds(5);
+        let mut stale = renamed.clone();
+        stale.name = "Old Alice".to_string();
+        stale.updated_at -= chrono::Duration::seconds(60);
+        laptop
+            .apply_changes(vec![
+                ReplicatedChange::Entity(renamed),
+                ReplicatedChange::Entity(stale),
+            ])
+            .await?;
+        assert_eq!(
+            laptop.get_entity(&alice).await?.unwrap().name,
+            "Alice Smith"
+        );
+
+        // Deletions travel as tombstones and take relationships with them
+        let cursor = desktop.changes_since(0, 100).await?.cursor;
+        let before = laptop.changes_since(0, 100).await?.cursor;
+        assert!(laptop.delete_entity(&acme).await?);
+        let batch = laptop.changes_since(before, 1).await?;
+        assert_eq!(batch.changes.len(), 1);
+        assert!(batch.more);
+        let batch = laptop.changes_since(before, 100).await?;
+        assert!(
+            batch
+                .changes
+                .iter()
+                .all(|c| matches!(c, ReplicatedChange::Tombstone { .. }))
+        );
+        let report = desktop.apply_changes(batch.changes).await?;
+        assert_eq!(report.removed, vec![acme.clone()]);
+        assert!(desktop.get_entity(&acme).await?.is_none());
+        assert!(desktop.get_relationships_for(&alice).await?.is_empty());
+
+        // ...and stale copies of a deleted entity don't bring it back
+        let old_acme = desktop.changes_since(0, 100).await?;
+        assert!(old_acme.cursor > cursor);
+        let report = laptop.apply_changes(old_acme.changes).await?;
+        assert!(report.entities.iter().all(|e| e.id != acme));
+        assert!(laptop.get_entity(&acme).await?.is_none());
+
+        // Names forgotten on one side aren't learned back from the other
+        desktop
+            .forget_entities(std::slice::from_ref(&alice), "asked")
+            .await?;
+        let before = laptop.changes_since(0, 100).await?.cursor;
+        let bob = laptop.insert_entity("ALICE", "person", None).await?;
+        let report = desktop
+            .apply_changes(laptop.changes_since(before, 100).await?.changes)
+            .await?;
+        assert!(report.entities.is_empty());
+        assert!(desktop.get_entity(&bob).await?.is_none());
+        Ok(())
+    }
 }
@@ -0,0 +1,160 @@
+//! Synonym groups for search query expansion
+//!
+//! Each group is a set of interchangeable terms or phrases ("nyc", "new
+//! york"). Groups come from configuration and from entity aliases: an entity
+//! forms a group with its aliases (and any `"aliases"` list in its metadata),
+//! so searching any of those names finds it.
+
+use std::collections::HashMap;
+
+use crate::sqlite::Entity;
+
+/// Most expansions added to one query
+const MAX_EXPANSIONS: usize = 16;
+
+#[derive(Debug, Clone, Default)]
+pub struct SynonymMap {
+    groups: Vec<Vec<String>>,
+    /// Lowercased term → indices of the groups it belongs to
+    index: HashMap<String, Vec<usize>>,
+}
+
+impl SynonymMap {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn from_groups<I, G, S>(groups: I) -> Self
+    where
+        I: IntoIterator<Item = G>,
+        G: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let mut map = Self::new();
+        for group in groups {
+            map.add_group(group);
+        }
+        map
+    }
+
+    /// Add a group of interchangeable terms; groups of fewer than two
+    /// distinct terms are ignored
+    pub fn add_group<G, S>(&mut self, group: G)
+    where
+        G: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let mut terms: Vec<String> = Vec::new();
+        for term in group {
+            let term = normalize(term.as_ref());
+            if !term.is_empty() && !terms.contains(&term) {
+                terms.push(term);
+            }
+        }
+        if terms.len() < 2 {
+            return;
+        }
+        let id = self.groups.len();
+        for term in &terms {
+            self.index.entry(term.clone()).or_default().push(id);
+        }
+        self.groups.push(terms);
+    }
+
+    /// Add the group formed by an entity's name and its aliases
+    pub fn add_entity(&mut self, entity: &Entity) {
+        let aliases = entity_aliases(entity);
+        if !aliases.is_empty() {
+            self.add_group(std::iter::once(entity.name.clone()).chain(aliases));
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.groups.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.groups.is_empty()
+    }
+
+    /// Synonyms of any term or phrase in `query` that aren't already in it
+    pub fn expand(&self, query: &str) -> Vec<String> {
+        let query = normalize(query);
+        let words: Vec<&str> = query.split(' ').collect();
+        let mut expansions = Vec::new();
+        for (term, group_ids) in &self.index {
+            if !contains_phrase(&words, term) {
+                continue;
+            }
+            for &id in group_ids {
+                for synonym in &self.groups[id] {
+                    if !contains_phrase(&words, synonym) && !expansions.contains(synonym) {
+                        expansions.push(synonym.clone());
+                    }
+                }
+            }
+        }
+        expansions.sort();
+        expansions.truncate(MAX_EXPANSIONS);
+        expansions
+    }
+}
+
+/// An entity's aliases, plus any listed under `aliases` in its metadata
+pub fn entity_aliases(entity: &Entity) -> Vec<String> {
+    let mut aliases = entity.aliases.clone();
+    let from_metadata = entity
+        .metadata
+        .as_ref()
+        .and_then(|m| m.get("aliases"))
+        .and_then(|a| a.as_array())
+        .into_iter()
+        .flatten()
+        .filter_map(|a| a.as_str());
+    for alias in from_metadata {
+        if !aliases.iter().any(|a| a.eq_ignore_ascii_case(alias)) {
+            aliases.push(alias.to_string());
+        }
+    }
+    aliases
+}
+
+/// Lowercase and reduce to single-space-separated alphanumeric words
+fn normalize(text: &str) -> String {
+    text.to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Whether `phrase` appears in `words` as whole consecutive words
+fn contains_phrase(words: &[&str], phrase: &str) -> bool {
+    let phrase: Vec<&str> = phrase.split(' ').collect();
+    words.windows(phrase.len()).any(|w| w == phrase.as_slice())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_expand_terms_and_phrases() {
+        let mut map = SynonymMap::from_groups([vec!["NYC", "New York"], vec!["car", "auto"]]);
+        map.add_entity(&Entity {
+            id: "e-1".into(),
+            name: "Robert Smith".into(),
+            entity_type: "person".into(),
+            metadata: Some(serde_json::json!({"aliases": ["Bobby"]})),
+            aliases: vec!["Bob".into()],
+            created_at: chrono::Utc::now(),
+            updated_at: chrono::Utc::now(),
+        });
+
+        assert_eq!(map.expand("flights to new york"), ["nyc"]);
+        assert_eq!(map.expand("Bob's car"), ["auto", "bobby", "robert smith"]);
+        // Partial words and phrases don't match
+        assert!(map.expand("cartoon newsletter york").is_empty());
+        assert_eq!(map.len(), 3);
+    }
+}
@@ -0,0 +1,422 @@
+//! Embedding-based auto-tagging of entities and conversations
+//!
+//! [`AutoTagger`] embeds entities and recent user messages, groups similar
+//! ones into clusters, names each cluster after its most distinctive words
+//! (e.g. `kitchen-renovation`) and writes that name to the items'
+//! `metadata.tags`. Tags added this way are also listed under
+//! `metadata.auto_tags`, so later runs can replace them without touching
+//! tags that were set by hand.
+
+use anyhow::Result;
+use serde_json::{Map, Value as JsonValue};
+use std::collections::{HashMap, HashSet};
+use tracing::{debug, info};
+
+use crate::embeddings::{EmbeddingProvider, content_words, cosine_similarity};
+use crate::sqlite::KnowledgeDb;
+
+/// Conversation rows written by the agent itself are not tagged
+const AGENT_SENDER: &str = "meepo";
+
+/// Metadata keys that are never fed back into the embedding text
+const SKIPPED_KEYS: &[&str] = &["tags", "auto_tags", "trace"];
+
+/// Configuration for the auto-tagger
+#[derive(Debug, Clone)]
+pub struct AutoTagConfig {
+    /// Minimum cosine similarity for an item to join a cluster
+    pub similarity_threshold: f32,
+    /// Clusters smaller than this don't get a tag
+    pub min_cluster_size: usize,
+    /// How many recent conversation rows to consider
+    pub max_conversations: usize,
+}
+
+impl Default for AutoTagConfig {
+    fn default() -> Self {
+        Self {
+            similarity_threshold: 0.3,
+            min_cluster_size: 3,
+            max_conversations: 1000,
+        }
+    }
+}
+
+/// Outcome of one tagging run
+#[derive(Debug, Clone, Default)]
+pub struct TaggingReport {
+    /// Entities and conversations considered
+    pub items: usize,
+    /// Proposed tags with the number of items in their cluster
+    pub tags: Vec<(String, usize)>,
+    /// Items whose metadata changed
+    pub updated: usize,
+}
+
+/// Clusters entity and conversation embeddings and stores the proposed tags
+pub struct AutoTagger {
+    provider: Box<dyn EmbeddingProvider>,
+    config: AutoTagConfig,
+}
+
+enum ItemKind {
+    Entity,
+    Conversation,
+}
+
+struct Item {
+    kind: ItemKind,
+    id: String,
+    text: String,
+    metadata: Option<JsonValue>,
+}
+
+impl AutoTagger {
+    pub fn new(provider: Box<dyn EmbeddingProvider>, config: AutoTagConfig) -> Self {
+        Self { provider, config }
+    }
+
+    /// Tag everything in the database once
+    pub async fn run(&self, db: &KnowledgeDb) -> Result<TaggingReport> {
+        let mut items: Vec<Item> = db
+            .get_all_entities()
+            .await?
+            .into_iter()
+            .map(|e| Item {
+                kind: ItemKind::Entity,
+                text: entity_text(&e.name, &e.entity_type, e.metadata.as_ref()),
+                id: e.id,
+                metadata: e.metadata,
+            })
+            .collect();
+        items.extend(
+            db.get_recent_conversations(None, self.config.max_conversations)
+                .await?
+                .into_iter()
+                .filter(|c| c.sender != AGENT_SENDER)
+                .map(|c| Item {
+                    kind: ItemKind::Conversation,
+                    id: c.id,
+                    text: c.content,
+                    metadata: c.metadata,
+                }),
+        );
+
+        let texts: Vec<&str> = items.iter().map(|i| i.text.as_str()).collect();
+        let vectors = self.provider.embed_batch(&texts).await?;
+        let words: Vec<Vec<String>> = texts.iter().map(|t| content_words(t)).collect();
+
+        let mut assigned: HashMap<usize, String> = HashMap::new();
+        let mut report = TaggingReport {
+            items: items.len(),
+            ..Default::default()
+        };
+        for members in cluster(&vectors, self.config.similarity_threshold) {
+            if members.len() < self.config.min_cluster_size.max(1) {
+                continue;
+            }
+            let Some(tag) = label_cluster(&members, &words) else {
+                continue;
+            };
+            debug!("Cluster of {} item(s) tagged '{}'", members.len(), tag);
+            report.tags.push((tag.clone(), members.len()));
+            for i in members {
+                assigned.insert(i, tag.clone());
+            }
+        }
+
+        for (i, item) in items.iter().enumerate() {
+            let Some(metadata) =
+                apply_auto_tags(item.metadata.as_ref(), assigned.get(&i).map(String::as_str))
+            else {
+                continue;
+            };
+            match item.kind {
+                ItemKind::Entity => db.update_entity_metadata(&item.id, metadata).await?,
+                ItemKind::Conversation => {
+                    db.update_conversation_metadata(&item.id, metadata).await?
+                }
+            }
+            report.updated += 1;
+        }
+
+        info!(
+            "Auto-tagging: {} item(s), {} tag(s), {} updated",
+            report.items,
+            report.tags.len(),
+            report.updated
+        );
+        Ok(report)
+    }
+}
+
+/// Text used to embed an entity: its name, type and metadata string values
+pub(crate) fn entity_text(name: &str, entity_type: &str, metadata: Option<&JsonValue>) -> String {
+    fn collect(value: &JsonValue, out: &mut Vec<String>) {
+        match value {
+            JsonValue::String(s) => out.push(s.clone()),
+            JsonValue::Array(items) => items.iter().for_each(|v| collect(v, out)),
+            JsonValue::Object(map) => map
+                .iter()
+                .filter(|(k, _)| !SKIPPED_KEYS.contains(&k.as_str()))
+                .for_each(|(_, v)| collect(v, out)),
+            _ => {}
+        }
+    }
+    let mut parts = vec![name.to_string(), entity_type.to_string()];
+    if let Some(metadata) = metadata {
+        collect(metadata, &mut parts);
+    }
+    parts.join(" ")
+}
+
+/// Greedy centroid clustering: each vector joins the most similar cluster
+/// at or above `threshold`, or starts a new one. Zero vectors are skipped.
+pub fn cluster(vectors: &[Vec<f32>], threshold: f32) -> Vec<Vec<usize>> {
+    let mut centroids: Vec<Vec<f32>> = Vec::new();
+    let mut clusters: Vec<Vec<usize>> = Vec::new();
+
+    for (i, vector) in vectors.iter().enumerate() {
+        if vector.iter().all(|x| *x == 0.0) {
+            continue;
+        }
+        let best = centroids
+            .iter()
+            .enumerate()
+            .map(|(c, centroid)| (c, cosine_similarity(vector, centroid)))
+            .filter(|(_, sim)| *sim >= threshold)
+            .max_by(|a, b| a.1.partial_cmp(&b.1).unwrap_or(std::cmp::Ordering::Equal));
+        match best {
+            Some((c, _)) => {
+                // Centroid is the running sum; cosine similarity ignores scale
+                centroids[c]
+                    .iter_mut()
+                    .zip(vector)
+                    .for_each(|(a, b)| *a += b);
+                clusters[c].push(i);
+            }
+            None => {
+                centroids.push(vector.clone());
+                clusters.push(vec![i]);
+            }
+        }
+    }
+    clusters
+}
+
+/// Name a cluster after the words that are common inside it but rare
+/// elsewhere. Returns one or two words, e.g. `kitchen-renovation`.
+fn label_cluster(members: &[usize], words: &[Vec<String>]) -> Option<String> {
+    let mut doc_freq: HashMap<&str, usize> = HashMap::new();
+    for doc in words {
+        for word in doc.iter().map(String::as_str).collect::<HashSet<_>>() {
+            *doc_freq.entry(word).or_default() += 1;
+        }
+    }
+    let mut cluster_freq: HashMap<&str, usize> = HashMap::new();
+    for &m in members {
+        for word in words[m].iter().map(String::as_str).collect::<HashSet<_>>() {
+            *cluster_freq.entry(word).or_default() += 1;
+        }
+    }
+
+    // A label word must appear in at least half the cluster
+    let support = members.len().div_ceil(2).max(2);
+    let total = words.len() as f32;
+    let mut candidates: Vec<(&str, f32)> = cluster_freq
+        .into_iter()
+        .filter(|(_, n)| *n >= support)
+        .map(|(word, n)| {
+            let idf = ((total + 1.0) / doc_freq[word] as f32).ln();
+            (word, n as f32 / members.len() as f32 * idf)
+        })
+        .collect();
+    candidates.sort_by(|a, b| {
+        b.1.partial_cmp(&a.1)
+            .unwrap_or(std::cmp::Ordering::Equal)
+            .then(a.0.cmp(b.0))
+    });
+
+    let (first, first_score) = *candidates.first()?;
+    let label = match candidates.get(1) {
+        Some(&(second, score)) if score >= first_score * 0.5 => {
+            // Use the word order the texts use ("kitchen renovation")
+            let adjacent = |a: &str, b: &str| {
+                members
+                    .iter()
+                    .flat_map(|&m| words[m].windows(2))
+                    .filter(|w| w[0] == a && w[1] == b)
+                    .count()
+            };
+            if adjacent(second, first) > adjacent(first, second) {
+                format!("{} {}", second, first)
+            } else {
+                format!("{} {}", first, second)
+            }
+        }
+        _ => first.to_string(),
+    };
+    Some(normalize_tag(&label))
+}
+
+/// Canonical tag form: lowercase words joined by hyphens
+pub fn normalize_tag(tag: &str) -> String {
+    tag.split(|c: char| !c.is_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .map(|w| w.to_lowercase())
+        .collect::<Vec<_>>()
+        .join("-")
+}
+
+/// Replace an item's previous auto tags with `tag`. Returns the new metadata
+/// if anything changed. Non-object metadata is left alone.
+fn apply_auto_tags(metadata: Option<&JsonValue>, tag: Option<&str>) -> Option<JsonValue> {
+    let original = match metadata {
+        Some(JsonValue::Object(map)) => map.clone(),
+        None => Map::new(),
+        Some(_) => return None,
+    };
+    let strings = |key: &str| -> Vec<String> {
+        original
+            .get(key)
+            .and_then(|v| v.as_array())
+            .map(|a| {
+                a.iter()
+                    .filter_map(|v| v.as_str().map(String::from))
+                    .collect()
+            })
+            .unwrap_or_default()
+    };
+
+    let old_auto = strings("auto_tags");
+    let mut tags: Vec<String> = strings("tags")
+        .into_iter()
+        .filter(|t| !old_auto.contains(t))
+        .collect();
+    let mut auto = Vec::new();
+    if let Some(tag) = tag {
+        auto.push(tag.to_string());
+        if !tags.iter().any(|t| t == tag) {
+            tags.push(tag.to_string());
+        }
+    }
+
+    let mut updated = original.clone();
+    for (key, values) in [("tags", tags), ("auto_tags", auto)] {
+        if values.is_empty() {
+            updated.remove(key);
+        } else {
+            updated.insert(key.to_string(), serde_json::json!(values));
+        }
+    }
+    (updated != original).then_some(JsonValue::Object(updated))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::embeddings::HashEmbeddingProvider;
+    use tempfile::TempDir;
+
+    #[tokio::test]
+    async fn test_cluster_and_label() {
+        let texts = [
+            "kitchen renovation: cabinet quotes from two contractors",
+            "the kitchen renovation countertop is delayed",
+            "kitchen renovation budget went over by 10%",
+            "file the quarterly tax return",
+            "tax return documents from the accountant",
+        ];
+        let provider = HashEmbeddingProvider::new(256);
+        let vectors = provider.embed_batch(&texts).await.unwrap();
+        let words: Vec<Vec<String>> = texts.iter().map(|t| content_words(t)).collect();
+
+        let clusters = cluster(&vectors, 0.3);
+        let kitchen = clusters.iter().find(|c| c.contains(&0)).unwrap();
+        assert_eq!(kitchen, &vec![0, 1, 2]);
+        assert_eq!(
+            label_cluster(kitchen, &words).as_deref(),
+            Some("kitchen-renovation")
+        );
+        let taxes = clusters.iter().find(|c| c.contains(&3)).unwrap();
+        assert_eq!(label_cluster(taxes, &words).as_deref(), Some("tax-return"));
+    }
+
+    #[test]
+    fn test_apply_auto_tags_keeps_manual_tags() {
+        let metadata = serde_json::json!({
+            "tags": ["home", "old-topic"],
+            "auto_tags": ["old-topic"],
+            "note": "x",
+        });
+        let updated = apply_auto_tags(Some(&metadata), Some("kitchen-renovation")).unwrap();
+        assert_eq!(
+            updated["tags"],
+            serde_json::json!(["home", "kitchen-renovation"])
+        );
+        assert_eq!(
+            updated["auto_tags"],
+            serde_json::json!(["kitchen-renovation"])
+        );
+        assert_eq!(updated["note"], "x");
+
+        // Re-applying the same tag is a no-op; dropping it restores the original tags
+        assert!(apply_auto_tags(Some(&updated), Some("kitchen-renovation")).is_none());
+        let cleared = apply_auto_tags(Some(&updated), None).unwrap();
+        assert_eq!(cleared, serde_json::json!({"tags": ["home"], "note": "x"}));
+        assert!(apply_auto_tags(None, None).is_none());
+        assert!(apply_auto_tags(Some(&serde_json::json!("text")), Some("a")).is_none());
+        assert_eq!(normalize_tag("Kitchen Renovation!"), "kitchen-renovation");
+    }
+
+    #[tokio::test]
+    async fn test_run_tags_entities_and_conversations() {
+        let temp = TempDir::new().unwrap();
+        let db = KnowledgeDb::new(temp.path().join("test.db")).unwrap();
+        let entity = db
+            .insert_entity(
+                "Kitchen renovation contractor",
+                "contact",
+                Some(serde_json::json!({"tags": ["home"]})),
+            )
+            .await
+            .unwrap();
+        for msg in [
+            "any update on the kitchen renovation?",
+            "kitchen renovation: tiles arrive friday",
+        ] {
+            db.insert_conversation("slack", "alice", msg, None)
+                .await
+                .unwrap();
+        }
+        db.insert_conversation("slack", AGENT_SENDER, "kitchen renovation noted", None)
+            .await
+            .unwrap();
+        db.insert_conversation("slack", "alice", "what's the weather", None)
+            .await
+            .unwrap();
+
+        let tagger = AutoTagger::new(
+            Box::new(HashEmbeddingProvider::new(256)),
+            AutoTagConfig::default(),
+        );
+        let report = tagger.run(&db).await.unwrap();
+        assert_eq!(report.items, 4);
+        assert_eq!(report.tags, vec![("kitchen-renovation".to_string(), 3)]);
+        assert_eq!(report.updated, 3);
+
+        let tagged = db.get_entity(&entity).await.unwrap().unwrap();
+        assert_eq!(
+            tagged.metadata.unwrap()["tags"],
+            serde_json::json!(["home", "kitchen-renovation"])
+        );
+        let convos = db
+            .get_conversations_by_tag("kitchen-renovation", 10)
+            .await
+            .unwrap();
+        assert_eq!(convos.len(), 2);
+
+        // A second run changes nothing
+        assert_eq!(tagger.run(&db).await.unwrap().updated, 0);
+    }
+}
@@ -1,15 +1,36 @@
 //! Tantivy full-text search index
+//!
+//! Writes are buffered in a long-lived writer and committed in batches —
+//! when enough have piled up, when the oldest is `commit_interval` old, on
+//! [`TantivyIndex::flush`], or before a search so results include them. A
+//! background thread commits stragglers and, once writes go quiet, waits for
+//! merges to finish and releases the writer lock.
 
 use anyhow::{Context, Result};
 use serde::{Deserialize, Serialize};
 use std::path::Path;
+use std::sync::{Arc, Mutex, MutexGuard, Weak};
+use std::time::{Duration, Instant};
 use tantivy::{
-    Index, IndexWriter, ReloadPolicy, TantivyDocument, collector::TopDocs, query::QueryParser,
+    Index, IndexWriter, ReloadPolicy, TantivyDocument,
+    collector::TopDocs,
+    indexer::LogMergePolicy,
+    query::{BooleanQuery, BoostQuery, FuzzyTermQuery, Occur, Query, QueryParser},
     schema::*,
 };
-use tracing::{debug, info};
+use tracing::{debug, info, warn};
 
 use crate::sqlite::Entity;
+use crate::synonyms::SynonymMap;
+
+/// Score multipliers for [`TantivyIndex::search_expanded`]: exact matches
+/// outrank synonym matches, which outrank typo matches
+const EXACT_BOOST: f32 = 3.0;
+const SYNONYM_BOOST: f32 = 1.5;
+const FUZZY_BOOST: f32 = 0.5;
+/// Shorter words aren't matched fuzzily — too many near neighbours
+const FUZZY_MIN_CHARS: usize = 4;
+const MAX_FUZZY_TERMS: usize = 8;
 
 /// Search result with score and snippet
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -22,6 +43,56 @@ pub struct SearchResult {
     pub snippet: Option<String>,
 }
 
+/// Commit batching and merge tuning for the index writer
+#[derive(Debug, Clone)]
+pub struct IndexConfig {
+    /// Commit buffered writes once the oldest is this old
+    pub commit_interval: Duration,
+    /// Commit as soon as this many writes are buffered
+    pub commit_max_docs: usize,
+    /// Writer memory budget
+    pub writer_heap_bytes: usize,
+    /// Merge once this many similar-sized segments exist
+    pub merge_min_segments: usize,
+    /// Segments with more documents than this aren't merged further
+    pub merge_max_docs: usize,
+}
+
+impl Default for IndexConfig {
+    fn default() -> Self {
+        Self {
+            commit_interval: Duration::from_secs(1),
+            commit_max_docs: 1000,
+            writer_heap_bytes: 50_000_000,
+            merge_min_segments: 8,
+            merge_max_docs: 10_000_000,
+        }
+    }
+}
+
+/// The shared writer and what it has buffered
+struct WriterState {
+    writer: Option<IndexWriter>,
+    pending: usize,
+    oldest_pending: Option<Instant>,
+    last_write: Instant,
+}
+
+impl WriterState {
+    fn commit(&mut self) -> Result<()> {
+        if self.pending == 0 {
+            return Ok(());
+        }
+        if let Some(writer) = self.writer.as_mut() {
+            writer.commit().context("Failed to commit index")?;
+        }
+        debug!("Committed {} index writes", self.pending);
+        self.pending = 0;
+        self.oldest_pending = None;
+        Ok(())
+    }
+}
+
 /// Tantivy search index wrapper
 pub struct TantivyIndex {
     index: Index,
@@ -29,11 +100,18 @@ pub struct TantivyIndex {
     content_field: Field,
     entity_type_field: Field,
     created_at_field: Field,
+    config: IndexConfig,
+    state: Arc<Mutex<WriterState>>,
 }
 
 impl TantivyIndex {
     /// Create or open a Tantivy index
     pub fn new<P: AsRef<Path>>(path: P) -> Result<Self> {
+        Self::with_config(path, IndexConfig::default())
+    }
+
+    /// Create or open a Tantivy index with custom commit and merge settings
+    pub fn with_config<P: AsRef<Path>>(path: P, config: IndexConfig) -> Result<Self> {
         info!("Initializing Tantivy index at {:?}", path.as_ref());
 
         // Create directory if it doesn't exist
@@ -56,15 +134,47 @@ impl TantivyIndex {
 
         debug!("Tantivy index initialized successfully");
 
+        let state = Arc::new(Mutex::new(WriterState {
+            writer: None,
+            pending: 0,
+            oldest_pending: None,
+            last_write: Instant::now(),
+        }));
+        spawn_committer(Arc::downgrade(&state), config.commit_interval);
+
         Ok(Self {
             index,
             id_field,
             content_field,
             entity_type_field,
             created_at_field,
+            config,
+            state,
         })
     }
 
+    /// Check that the index at `path` can be opened and read, without
+    /// creating it or taking the writer lock; returns its document count
+    pub fn probe<P: AsRef<Path>>(path: P) -> Result<u64> {
+        let path = path.as_ref();
+        if !path.join("meta.json").exists() {
+            anyhow::bail!("no search index at {}", path.display());
+        }
+        let index = Index::open_in_dir(path)?;
+        let reader = index.reader().context("Failed to open index reader")?;
+        Ok(reader.searcher().num_docs())
+    }
+
+    /// Commit buffered writes now
+    pub fn flush(&self) -> Result<()> {
+        self.lock_state().commit()
+    }
+
+    /// Writes buffered but not yet committed
+    pub fn pending(&self) -> usize {
+        self.lock_state().pending
+    }
+
     /// Index a document
     pub fn index_document(
         &self,
@@ -73,24 +183,20 @@ impl TantivyIndex {
         entity_type: &str,
         created_at: &str,
     ) -> Result<()> {
-        let mut writer = self.get_writer()?;
-
-        // Delete existing document with same ID (if any)
-        let id_query = tantivy::query::TermQuery::new(
-            tantivy::Term::from_field_text(self.id_field, id),
-            tantivy::schema::IndexRecordOption::Basic,
-        );
-        let _ = writer.delete_query(Box::new(id_query));
+        self.write(1, |writer| {
+            // Delete existing document with same ID (if any)
+            writer.delete_term(tantivy::Term::from_field_text(self.id_field, id));
 
-        // Create document
-        let mut doc = TantivyDocument::default();
-        doc.add_text(self.id_field, id);
-        doc.add_text(self.content_field, content);
-        doc.add_text(self.entity_type_field, entity_type);
-        doc.add_text(self.created_at_field, created_at);
+            // Create document
+            let mut doc = TantivyDocument::default();
+            doc.add_text(self.id_field, id);
+            doc.add_text(self.content_field, content);
+            doc.add_text(self.entity_type_field, entity_type);
+            doc.add_text(self.created_at_field, created_at);
 
-        writer.add_document(doc)?;
-        writer.commit()?;
+            writer.add_document(doc)?;
+            Ok(())
+        })?;
 
         debug!("Indexed document: {} ({})", id, entity_type);
         Ok(())
@@ -98,6 +204,87 @@ impl TantivyIndex {
 
     /// Search the index
     pub fn search(&self, query_str: &str, limit: usize) -> Result<Vec<SearchResult>> {
+        self.search_expanded(query_str, limit, false, &SynonymMap::new())
+    }
+
+    /// Search with typo tolerance (`fuzzy`) and synonym expansion. Exact
+    /// matches are boosted so they still rank above fuzzy or synonym ones.
+    pub fn search_expanded(
+        &self,
+        query_str: &str,
+        limit: usize,
+        fuzzy: bool,
+        synonyms: &SynonymMap,
+    ) -> Result<Vec<SearchResult>> {
+        let query_parser = QueryParser::for_index(&self.index, vec![self.content_field]);
+        let exact = query_parser
+            .parse_query(query_str)
+            .context("Failed to parse search query")?;
+
+        let mut clauses: Vec<(Occur, Box<dyn Query>)> =
+            vec![(Occur::Should, Box::new(BoostQuery::new(exact, EXACT_BOOST)))];
+        if fuzzy {
+            for token in self
+                .query_terms(query_str)
+                .into_iter()
+                .take(MAX_FUZZY_TERMS)
+            {
+                let chars = token.chars().count();
+                if chars < FUZZY_MIN_CHARS {
+                    continue;
+                }
+                let distance = if chars >= 8 { 2 } else { 1 };
+                let term = tantivy::Term::from_field_text(self.content_field, &token);
+                clauses.push((
+                    Occur::Should,
+                    Box::new(BoostQuery::new(
+                        Box::new(FuzzyTermQuery::new(term, distance, true)),
+                        FUZZY_BOOST,
+                    )),
+                ));
+            }
+        }
+        for synonym in synonyms.expand(query_str) {
+            // Quote phrases so their words match in order
+            let (query, _) = query_parser.parse_query_lenient(&format!("\"{}\"", synonym));
+            clauses.push((
+                Occur::Should,
+                Box::new(BoostQuery::new(query, SYNONYM_BOOST)),
+            ));
+        }
+
+        let query: Box<dyn Query> = if clauses.len() == 1 {
+            clauses.remove(0).1
+        } else {
+            Box::new(BooleanQuery::new(clauses))
+        };
+        self.run_query(query_str, query.as_ref(), limit)
+    }
+
+    /// The query's words as the content field's tokenizer sees them
+    fn query_terms(&self, query_str: &str) -> Vec<String> {
+        let Ok(mut tokenizer) = self.index.tokenizer_for_field(self.content_field) else {
+            return Vec::new();
+        };
+        let mut stream = tokenizer.token_stream(query_str);
+        let mut terms = Vec::new();
+        while let Some(token) = stream.next() {
+            if !terms.contains(&token.text) {
+                terms.push(token.text.clone());
+            }
+        }
+        terms
+    }
+
+    fn run_query(
+        &self,
+        query_str: &str,
+        query: &dyn Query,
+        limit: usize,
+    ) -> Result<Vec<SearchResult>> {
+        // Read your own writes
+        self.flush()?;
+
         let reader = self
             .index
             .reader_builder()
@@ -106,14 +293,8 @@ impl TantivyIndex {
 
         let searcher = reader.searcher();
 
-        // Parse query
-        let query_parser = QueryParser::for_index(&self.index, vec![self.content_field]);
-        let query = query_parser
-            .parse_query(query_str)
-            .context("Failed to parse search query")?;
-
         // Search
-        let top_docs = searcher.search(&query, &TopDocs::with_limit(limit))?;
+        let top_docs = searcher.search(query, &TopDocs::with_limit(limit))?;
 
         let mut results = Vec::new();
         for (score, doc_address) in top_docs {
@@ -163,17 +344,50 @@ impl TantivyIndex {
 
     /// Delete a document by ID
     pub fn delete_document(&self, id: &str) -> Result<()> {
-        let mut writer = self.get_writer()?;
+        self.write(1, |writer| {
+            writer.delete_term(tantivy::Term::from_field_text(self.id_field, id));
+            Ok(())
+        })?;
 
-        let id_query = tantivy::query::TermQuery::new(
-            tantivy::Term::from_field_text(self.id_field, id),
-            tantivy::schema::IndexRecordOption::Basic,
-        );
+        debug!("Deleted document: {}", id);
+        Ok(())
+    }
+
+    /// Index (or re-index) a batch of entities with a single commit
+    pub fn index_entities(&self, entities: &[Entity]) -> Result<()> {
+        self.write(entities.len(), |writer| {
+            for entity in entities {
+                writer.delete_term(tantivy::Term::from_field_text(self.id_field, &entity.id));
+                writer.add_document(self.entity_document(entity))?;
+            }
+            Ok(())
+        })?;
+        self.flush()?;
+
+        debug!("Indexed {} entities", entities.len());
+        Ok(())
+    }
 
-        let _ = writer.delete_query(Box::new(id_query));
-        writer.commit()?;
+    /// Delete some documents and (re-)index others in a single commit, so
+    /// searches never see a half-applied change
+    pub fn replace_entities(&self, remove: &[String], entities: &[Entity]) -> Result<()> {
+        self.write(remove.len() + entities.len(), |writer| {
+            for id in remove {
+                writer.delete_term(tantivy::Term::from_field_text(self.id_field, id));
+            }
+            for entity in entities {
+                writer.delete_term(tantivy::Term::from_field_text(self.id_field, &entity.id));
+                writer.add_document(self.entity_document(entity))?;
+            }
+            Ok(())
+        })?;
+        self.flush()?;
 
-        debug!("Deleted document: {}", id);
+        debug!(
+            "Replaced {} documents with {} entities",
+            remove.len(),
+            entities.len()
+        );
         Ok(())
     }
 
@@ -181,46 +395,128 @@ impl TantivyIndex {
     pub fn reindex_all_from_entities(&self, entities: &[Entity]) -> Result<()> {
         info!("Reindexing all entities");
 
-        let mut writer = self.get_writer()?;
-
-        // Delete all documents
-        writer.delete_all_documents()?;
+        // Deleting everything only drops committed segments
+        self.flush()?;
 
         let entity_count = entities.len();
-        // Index all entities
-        for entity in entities {
-            let content = format!(
-                "{} {} {}",
-                entity.name,
-                entity.entity_type,
-                entity
-                    .metadata
-                    .as_ref()
-                    .map(|m: &serde_json::Value| m.to_string())
-                    .unwrap_or_default()
-            );
+        self.write(entity_count.max(1), |writer| {
+            // Delete all documents
+            writer.delete_all_documents()?;
+            // Index all entities
+            for entity in entities {
+                writer.add_document(self.entity_document(entity))?;
+            }
+            Ok(())
+        })?;
+        self.flush()?;
 
-            let mut doc = TantivyDocument::default();
-            doc.add_text(self.id_field, &entity.id);
-            doc.add_text(self.content_field, &content);
-            doc.add_text(self.entity_type_field, &entity.entity_type);
-            doc.add_text(self.created_at_field, entity.created_at.to_rfc3339());
+        info!("Reindexed {} entities", entity_count);
+        Ok(())
+    }
 
-            writer.add_document(doc)?;
-        }
+    fn entity_document(&self, entity: &Entity) -> TantivyDocument {
+        let content = format!(
+            "{} {} {} {}",
+            entity.name,
+            entity.aliases.join(" "),
+            entity.entity_type,
+            entity
+                .metadata
+                .as_ref()
+                .map(|m: &serde_json::Value| m.to_string())
+                .unwrap_or_default()
+        );
 
-        writer.commit()?;
+        let mut doc = TantivyDocument::default();
+        doc.add_text(self.id_field, &entity.id);
+        doc.add_text(self.content_field, &content);
+        doc.add_text(self.entity_type_field, &entity.entity_type);
+        doc.add_text(self.created_at_field, entity.created_at.to_rfc3339());
+        doc
+    }
 
-        info!("Reindexed {} entities", entity_count);
+    /// Run `ops` buffered writes against the shared writer, committing if
+    /// the batch is full or old enough
+    fn write(&self, ops: usize, f: impl FnOnce(&IndexWriter) -> Result<()>) -> Result<()> {
+        let mut state = self.lock_state();
+        let writer = match state.writer.take() {
+            Some(writer) => writer,
+            None => self.open_writer()?,
+        };
+        let result = f(&writer);
+        let state = &mut *state;
+        state.writer = Some(writer);
+        result?;
+
+        let now = Instant::now();
+        state.pending += ops;
+        state.last_write = now;
+        let oldest = *state.oldest_pending.get_or_insert(now);
+        if state.pending >= self.config.commit_max_docs
+            || now.duration_since(oldest) >= self.config.commit_interval
+        {
+            state.commit()?;
+        }
         Ok(())
     }
 
-    /// Get index writer
-    fn get_writer(&self) -> Result<IndexWriter> {
-        // 50MB heap size for writer
-        self.index
-            .writer(50_000_000)
-            .context("Failed to create index writer")
+    fn open_writer(&self) -> Result<IndexWriter> {
+        let writer: IndexWriter = self
+            .index
+            .writer(self.config.writer_heap_bytes)
+            .context("Failed to create index writer")?;
+        let mut policy = LogMergePolicy::default();
+        policy.set_min_num_segments(self.config.merge_min_segments);
+        policy.set_max_docs_before_merge(self.config.merge_max_docs);
+        writer.set_merge_policy(Box::new(policy));
+        Ok(writer)
+    }
+
+    fn lock_state(&self) -> MutexGuard<'_, WriterState> {
+        self.state.lock().unwrap_or_else(|poisoned| {
+            warn!("Index writer mutex was poisoned, recovering");
+            poisoned.into_inner()
+        })
+    }
+}
+
+impl Drop for TantivyIndex {
+    fn drop(&mut self) {
+        if let Err(e) = self.flush() {
+            warn!("Failed to commit index on close: {}", e);
+        }
+    }
+}
+
+/// Commit writes still buffered after `interval`, and release the writer
+/// (after its merges finish) once writes have been quiet that long.
+/// Exits when the index is dropped.
+fn spawn_committer(state: Weak<Mutex<WriterState>>, interval: Duration) {
+    let spawned = std::thread::Builder::new()
+        .name("tantivy-commit".into())
+        .spawn(move || {
+            loop {
+                std::thread::sleep(interval);
+                let Some(state) = state.upgrade() else {
+                    return;
+                };
+                // Hold the lock while merges finish so no new writer tries
+                // to take the directory lock in the meantime
+                let mut state = state.lock().unwrap_or_else(|p| p.into_inner());
+                if state.pending > 0 {
+                    if let Err(e) = state.commit() {
+                        warn!("Background index commit failed: {}", e);
+                    }
+                } else if state.last_write.elapsed() >= interval
+                    && let Some(writer) = state.writer.take()
+                    && let Err(e) = writer.wait_merging_threads()
+                {
+                    warn!("Index merge failed: {}", e);
+                }
+            }
+        });
+    if let Err(e) = spawned {
+        warn!("Failed to start index commit thread: {}", e);
     }
 }
 
@@ -346,6 +642,38 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_writes_are_batched() -> Result<()> {
+        let temp = tempfile::TempDir::new()?;
+        let index = TantivyIndex::with_config(
+            temp.path(),
+            IndexConfig {
+                commit_interval: Duration::from_millis(100),
+                commit_max_docs: 3,
+                ..Default::default()
+            },
+        )?;
+        let now = chrono::Utc::now().to_rfc3339();
+
+        index.index_document("doc-1", "first note", "fact", &now)?;
+        index.index_document("doc-2", "second note", "fact", &now)?;
+        assert_eq!(index.pending(), 2);
+        // A full batch commits
+        index.index_document("doc-3", "third note", "fact", &now)?;
+        assert_eq!(index.pending(), 0);
+
+        // Stragglers are committed in the background
+        index.index_document("doc-4", "fourth note", "fact", &now)?;
+        assert_eq!(index.pending(), 1);
+        std::thread::sleep(Duration::from_millis(500));
+        assert_eq!(index.pending(), 0);
+
+        // Searches see buffered writes
+        index.index_document("doc-5", "fifth note", "fact", &now)?;
+        assert_eq!(index.search("note", 10)?.len(), 5);
+        Ok(())
+    }
+
     #[test]
     fn test_index_document_overwrites() -> Result<()> {
         let temp_path =
@@ -405,6 +733,7 @@ mod tests {
                 name: "Rust Language".to_string(),
                 entity_type: "concept".to_string(),
                 metadata: Some(serde_json::json!({"category": "programming"})),
+                aliases: Vec::new(),
                 created_at: chrono::Utc::now(),
                 updated_at: chrono::Utc::now(),
             },
@@ -413,6 +742,7 @@ mod tests {
                 name: "Python Language".to_string(),
                 entity_type: "concept".to_string(),
                 metadata: None,
+                aliases: Vec::new(),
                 created_at: chrono::Utc::now(),
                 updated_at: chrono::Utc::now(),
             },
@@ -4,6 +4,7 @@ use serde_json::Value;
 use std::sync::Arc;
 use tracing::debug;
 
+use meepo_core::tool_profiles::{self, ToolProfile};
 use meepo_core::tools::{ToolExecutor, ToolRegistry};
 
 use crate::protocol::{McpTool, ToolCallResult, ToolContent};
@@ -12,6 +13,7 @@ use crate::protocol::{McpTool, ToolCallResult, ToolContent};
 pub struct McpToolAdapter {
     registry: Arc<ToolRegistry>,
     denylist: Vec<String>,
+    profile: Option<ToolProfile>,
 }
 
 impl McpToolAdapter {
@@ -20,21 +22,44 @@ impl McpToolAdapter {
         Self {
             registry,
             denylist: vec!["delegate_tasks".to_string()],
+            profile: None,
         }
     }
 
     /// Create with custom denylist
     pub fn with_denylist(registry: Arc<ToolRegistry>, denylist: Vec<String>) -> Self {
-        Self { registry, denylist }
+        Self {
+            registry,
+            denylist,
+            profile: None,
+        }
+    }
+
+    /// Only expose, and only run, the tools a profile allows
+    pub fn with_profile(mut self, profile: ToolProfile) -> Self {
+        self.profile = Some(profile);
+        self
+    }
+
+    /// The profile clients are limited to, if any
+    pub fn profile(&self) -> Option<&ToolProfile> {
+        self.profile.as_ref()
+    }
+
+    fn is_exposed(&self, name: &str) -> bool {
+        !self.denylist.iter().any(|d| d == name)
+            && self.profile.as_ref().is_none_or(|p| p.allows(name))
     }
 
-    /// List all tools as MCP tool definitions
+    /// List all exposed tools as MCP tool definitions, annotated with
+    /// read-only and destructive hints
     pub fn list_tools(&self) -> Vec<McpTool> {
         self.registry
             .list_tools()
             .into_iter()
-            .filter(|t| !self.denylist.contains(&t.name))
+            .filter(|t| self.is_exposed(&t.name))
             .map(|t| McpTool {
+                annotations: Some(tool_profiles::annotations(&t.name)),
                 name: t.name,
                 description: t.description,
                 input_schema: t.input_schema,
@@ -44,7 +69,7 @@ impl McpToolAdapter {
 
     /// Execute a tool and return MCP-formatted result
     pub async fn call_tool(&self, name: &str, arguments: Value) -> ToolCallResult {
-        if self.denylist.contains(&name.to_string()) {
+        if !self.is_exposed(name) {
             return ToolCallResult {
                 content: vec![ToolContent {
                     content_type: "text".to_string(),
@@ -114,4 +139,20 @@ mod tests {
             .await;
         assert_eq!(result.is_error, Some(true));
     }
+
+    #[tokio::test]
+    async fn test_profile_limits_calls() {
+        let registry = Arc::new(ToolRegistry::new());
+        let adapter = McpToolAdapter::new(registry).with_profile(ToolProfile {
+            name: "research".to_string(),
+            description: "Look things up".to_string(),
+            tools: vec![tool_profiles::ALL_TOOLS.to_string()],
+            max_risk: meepo_core::autonomy::action_log::ActionRisk::ReadOnly,
+        });
+        let result = adapter
+            .call_tool("run_command", serde_json::json!({"command": "ls"}))
+            .await;
+        assert_eq!(result.is_error, Some(true));
+        assert!(result.content[0].text.contains("not available"));
+    }
 }
@@ -2,6 +2,7 @@
 //!
 //! Implements the Model Context Protocol over JSON-RPC 2.0.
 
+use meepo_core::tool_profiles::ToolAnnotations;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
@@ -69,6 +70,9 @@ pub struct InitializeResult {
     pub capabilities: ServerCapabilities,
     #[serde(rename = "serverInfo")]
     pub server_info: ServerInfo,
+    /// What the tools are for, when the server runs with a tool profile
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub instructions: Option<String>,
 }
 
 /// MCP tool definition (for tools/list response)
@@ -78,6 +82,8 @@ pub struct McpTool {
     pub description: String,
     #[serde(rename = "inputSchema")]
     pub input_schema: Value,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub annotations: Option<ToolAnnotations>,
 }
 
 /// MCP tool call result
@@ -138,6 +144,7 @@ mod tests {
                 name: "meepo".to_string(),
                 version: "0.1.0".to_string(),
             },
+            instructions: None,
         };
         let json = serde_json::to_value(&result).unwrap();
         assert_eq!(json["protocolVersion"], "2024-11-05");
@@ -155,9 +162,11 @@ mod tests {
                     "path": {"type": "string"}
                 }
             }),
+            annotations: Some(meepo_core::tool_profiles::annotations("read_file")),
         };
         let json = serde_json::to_value(&tool).unwrap();
         assert_eq!(json["inputSchema"]["type"], "object");
+        assert_eq!(json["annotations"]["readOnlyHint"], true);
     }
 
     #[test]
@@ -291,6 +300,7 @@ mod tests {
                 },
                 "required": ["query"]
             }),
+            annotations: None,
         };
         let json = serde_json::to_string(&tool).unwrap();
         let parsed: McpTool = serde_json::from_str(&json).unwrap();
@@ -77,6 +77,12 @@ impl McpServer {
                         name: "meepo".to_string(),
                         version: env!("CARGO_PKG_VERSION").to_string(),
                     },
+                    instructions: self.adapter.profile().map(|p| {
+                        format!(
+                            "Tool profile '{}' (max risk: {}): {}",
+                            p.name, p.max_risk, p.description
+                        )
+                    }),
                 };
                 Some(JsonRpcResponse::success(
                     id,
@@ -6,6 +6,7 @@
 use crate::watcher::{Watcher, WatcherEvent, WatcherKind};
 use anyhow::{Context, Result};
 use chrono::{NaiveTime, Utc};
+use chrono_tz::Tz;
 #[cfg(target_os = "macos")]
 use lru::LruCache;
 use notify::{Event, RecommendedWatcher, RecursiveMode, Watcher as NotifyWatcher};
@@ -17,6 +18,7 @@ use std::num::NonZeroUsize;
 use std::path::Path;
 use std::str::FromStr;
 use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::time::Duration;
 #[cfg(target_os = "macos")]
 use tokio::process::Command;
@@ -39,6 +41,9 @@ pub struct WatcherConfig {
 
     /// Whether to enforce active hours check
     pub enforce_active_hours: bool,
+
+    /// Timezone for active hours and cron schedules
+    pub timezone: Tz,
 }
 
 impl Default for WatcherConfig {
@@ -48,6 +53,7 @@ impl Default for WatcherConfig {
             min_poll_interval_secs: 10,
             active_hours: None,
             enforce_active_hours: false,
+            timezone: Tz::UTC,
         }
     }
 }
@@ -65,6 +71,9 @@ pub struct WatcherRunner {
 
     /// Global shutdown token
     shutdown_token: CancellationToken,
+
+    /// When set, polling watchers skip their polls (e.g. low-power mode)
+    polling_paused: Arc<AtomicBool>,
 }
 
 impl WatcherRunner {
@@ -83,9 +92,26 @@ impl WatcherRunner {
             event_tx,
             active_tasks: Arc::new(RwLock::new(HashMap::new())),
             shutdown_token: CancellationToken::new(),
+            polling_paused: Arc::new(AtomicBool::new(false)),
         }
     }
 
+    /// Pause or resume polling watchers (email, calendar, GitHub).
+    /// Scheduled, one-shot and file watchers keep running.
+    pub fn set_polling_paused(&self, paused: bool) {
+        if self.polling_paused.swap(paused, Ordering::Relaxed) != paused {
+            info!(
+                "Polling watchers {}",
+                if paused { "paused" } else { "resumed" }
+            );
+        }
+    }
+
+    /// Whether polling watchers are paused
+    pub fn polling_paused(&self) -> bool {
+        self.polling_paused.load(Ordering::Relaxed)
+    }
+
     /// Start a watcher
     pub async fn start_watcher(&self, watcher: Watcher) -> Result<()> {
         // Check if we've reached max concurrent watchers
@@ -198,6 +224,7 @@ impl WatcherRunner {
         let config = self.config.clone();
         let global_shutdown = self.shutdown_token.clone();
         let active_tasks = self.active_tasks.clone();
+        let polling_paused = self.polling_paused.clone();
 
         tokio::spawn(async move {
             let interval_secs = match &watcher.kind {
@@ -234,7 +261,7 @@ impl WatcherRunner {
                         if config.enforce_active_hours
                             && let Some((start, end)) = config.active_hours
                         {
-                            let now = Utc::now().time();
+                            let now = Utc::now().with_timezone(&config.timezone).time();
                             let is_active = if start < end {
                                 now >= start && now <= end
                             } else {
@@ -247,6 +274,11 @@ impl WatcherRunner {
                             }
                         }
 
+                        if polling_paused.load(Ordering::Relaxed) {
+                            debug!("Watcher {} paused", watcher.id);
+                            continue;
+                        }
+
                         // Execute the poll
                         if let Err(e) = poll_watcher(&watcher, &event_tx, &mut poll_state).await {
                             error!("Error polling watcher {}: {}", watcher.id, e);
@@ -382,13 +414,17 @@ impl WatcherRunner {
         let task_name = task.clone();
         let global_shutdown = self.shutdown_token.clone();
         let active_tasks = self.active_tasks.clone();
+        let timezone = self.config.timezone;
 
         tokio::spawn(async move {
-            info!("Scheduled watcher {} started: {}", watcher_id, cron_expr);
+            info!(
+                "Scheduled watcher {} started: {} ({})",
+                watcher_id, cron_expr, timezone
+            );
 
             loop {
-                // Get next occurrence
-                let now = Utc::now();
+                // Get next occurrence in the user's timezone
+                let now = Utc::now().with_timezone(&timezone);
                 let next = match schedule.after(&now).next() {
                     Some(n) => n,
                     None => {
@@ -896,6 +932,17 @@ mod tests {
         assert_eq!(runner.active_count().await, 0);
     }
 
+    #[tokio::test]
+    async fn test_polling_pause_toggle() {
+        let (tx, _rx) = mpsc::unbounded_channel();
+        let runner = WatcherRunner::new(tx);
+        assert!(!runner.polling_paused());
+        runner.set_polling_paused(true);
+        assert!(runner.polling_paused());
+        runner.set_polling_paused(false);
+        assert!(!runner.polling_paused());
+    }
+
     #[tokio::test]
     async fn test_start_stop_watcher() {
         let (tx, _rx) = mpsc::unbounded_channel();
@@ -1085,6 +1132,7 @@ mod tests {
             min_poll_interval_secs: 30,
             active_hours: None,
             enforce_active_hours: false,
+            timezone: Tz::UTC,
         };
         let runner = WatcherRunner::with_config(tx, config);
         assert_eq!(runner.active_count().await, 0);